@@ -0,0 +1,4 @@
+-- Arbitrary key/value labels on events, filterable via JSONB containment
+ALTER TABLE taskcast_events ADD COLUMN IF NOT EXISTS labels JSONB;
+
+CREATE INDEX IF NOT EXISTS taskcast_events_labels_idx ON taskcast_events USING GIN (labels);
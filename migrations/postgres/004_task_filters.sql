@@ -0,0 +1,2 @@
+-- Named subscribe filter presets on tasks
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS filters JSONB;
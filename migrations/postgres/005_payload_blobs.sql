@@ -0,0 +1,6 @@
+-- Content-addressed event payloads shared by events storing {"$blobRef": hash}
+CREATE TABLE IF NOT EXISTS taskcast_blobs (
+  hash TEXT PRIMARY KEY,
+  data TEXT NOT NULL,
+  refcount INTEGER NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Retry policy on tasks
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS retry_policy JSONB;
@@ -0,0 +1,2 @@
+-- Webhook group policy on tasks
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS group_policy TEXT;
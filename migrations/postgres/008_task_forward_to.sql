@@ -0,0 +1,2 @@
+-- Event forwarding rule on tasks
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS forward_to JSONB;
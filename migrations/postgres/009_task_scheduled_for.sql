@@ -0,0 +1,2 @@
+-- Activation time of tasks created with scheduledFor
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS scheduled_for BIGINT;
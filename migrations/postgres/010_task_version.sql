@@ -0,0 +1,2 @@
+-- Optimistic concurrency counter, bumped by every task save
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 0;
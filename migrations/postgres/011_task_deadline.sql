@@ -0,0 +1,2 @@
+-- Allotted time for deadline warnings, in milliseconds
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS deadline_ms BIGINT;
@@ -0,0 +1,2 @@
+-- Which actor created the task: the HTTP API or an internal subsystem
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS origin JSONB;
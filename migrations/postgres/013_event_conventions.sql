@@ -0,0 +1,12 @@
+-- Event types each task type publishes, sampled from published events
+CREATE TABLE IF NOT EXISTS taskcast_event_conventions (
+  task_type TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  estimated_count BIGINT NOT NULL DEFAULT 0,
+  last_seen_at DOUBLE PRECISION,
+  example JSONB,
+  example_at DOUBLE PRECISION,
+  description TEXT,
+  data_schema JSONB,
+  PRIMARY KEY (task_type, event_type)
+);
@@ -0,0 +1,3 @@
+-- Parent linkage and automatic completion when children end
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS parent_id TEXT;
+ALTER TABLE taskcast_tasks ADD COLUMN IF NOT EXISTS completion_policy JSONB;
@@ -0,0 +1,2 @@
+-- Serves task listing, which pages through tasks in (created_at, id) order
+CREATE INDEX IF NOT EXISTS taskcast_tasks_created_at_idx ON taskcast_tasks(created_at, id COLLATE "C");
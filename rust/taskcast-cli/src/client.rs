@@ -57,7 +57,8 @@ impl TaskcastClient {
                     let status = res.status();
                     let message = match res.json::<serde_json::Value>().await {
                         Ok(body) => body
-                            .get("error")
+                            .get("message")
+                            .or_else(|| body.get("error"))
                             .and_then(|e| e.as_str())
                             .map(|s| s.to_string())
                             .unwrap_or_else(|| format!("HTTP {status}")),
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use clap::Args;
+use taskcast_core::config::TaskcastConfig;
+use taskcast_server::{
+    AdapterDescription, AuthMode, DebugBundle, DebugBundleOptions, DebugBundleSources, RuntimeInfo,
+};
+
+use crate::client::TaskcastClient;
+use crate::commands::start::StoreEnv;
+use crate::helpers::resolve_storage_mode;
+use crate::node_config::NodeConfigManager;
+
+#[derive(Args, Debug)]
+pub struct DebugBundleArgs {
+    /// Task to collect
+    #[arg(long)]
+    pub task: String,
+    /// Archive to write (default: taskcast-debug-<task>.tgz)
+    #[arg(long)]
+    pub out: Option<String>,
+    /// Fetch the bundle from a running node instead of reading the stores
+    #[arg(long)]
+    pub server: bool,
+    /// Named node to fetch from with --server (default: current node)
+    #[arg(long, requires = "server")]
+    pub node: Option<String>,
+    /// Config file path
+    #[arg(short, long, conflicts_with = "server")]
+    pub config: Option<String>,
+    /// Storage backend: redis or sqlite (default: detected as for `start`)
+    #[arg(short, long, default_value = "memory", conflicts_with = "server")]
+    pub storage: String,
+    /// SQLite database file path
+    #[arg(long, default_value = "./taskcast.db", conflicts_with = "server")]
+    pub db_path: String,
+    #[command(flatten)]
+    pub stores: StoreEnv,
+}
+
+/// `taskcast-debug-<task>.tgz`, with characters unfit for a file name
+/// replaced.
+pub fn default_out_path(task_id: &str) -> String {
+    let name: String = task_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("taskcast-debug-{name}.tgz")
+}
+
+/// Downloads the bundle a node builds for `task_id`.
+pub async fn fetch_bundle(client: &TaskcastClient, task_id: &str) -> Result<Vec<u8>, String> {
+    let res = client
+        .get(&format!("/admin/tasks/{task_id}/debug-bundle"))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(format!("HTTP {} — {}", status.as_u16(), body));
+    }
+    res.bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| e.to_string())
+}
+
+/// Opens the stores `config`, `storage` and `stores` name, the way `start`
+/// would, and collects the bundle from them. There is no HTTP tap or metrics
+/// sampler without a running node, so those parts are left out.
+pub async fn collect_local(
+    config: &TaskcastConfig,
+    storage: &str,
+    db_path: &str,
+    stores: &StoreEnv,
+    task_id: &str,
+) -> Result<DebugBundle, Box<dyn std::error::Error>> {
+    let redis_url = stores.redis_url.clone().or_else(|| {
+        config
+            .adapters
+            .as_ref()?
+            .broadcast
+            .as_ref()
+            .filter(|entry| entry.provider != "kafka")?
+            .url
+            .clone()
+    });
+    let postgres_url = stores.postgres_url.clone().or_else(|| {
+        config
+            .adapters
+            .as_ref()?
+            .long_term_store
+            .as_ref()?
+            .url
+            .clone()
+    });
+    let storage_mode = resolve_storage_mode(
+        storage,
+        stores.default_storage.as_deref(),
+        redis_url.is_some(),
+    );
+    let integrity = taskcast_core::IntegrityMonitor::new(
+        config
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.corrupt_records)
+            .unwrap_or_default(),
+    );
+
+    let mut runtime_info = RuntimeInfo::from_config(Some(config));
+    let (short_term_store, long_term_store): (
+        Arc<dyn taskcast_core::ShortTermStore>,
+        Option<Arc<dyn taskcast_core::LongTermStore>>,
+    ) = match storage_mode {
+        "sqlite" => {
+            let adapters = taskcast_sqlite::create_sqlite_adapters(db_path).await?;
+            let sqlite = AdapterDescription::new("sqlite").with_url(db_path);
+            runtime_info.short_term_store = sqlite.clone();
+            runtime_info.long_term_store = Some(sqlite);
+            (
+                Arc::new(adapters.short_term_store.with_integrity(integrity.clone())),
+                Some(Arc::new(adapters.long_term_store.with_integrity(integrity))),
+            )
+        }
+        "redis" => {
+            let url = redis_url
+                .as_deref()
+                .ok_or("--storage redis requires TASKCAST_REDIS_URL")?;
+            let client = redis::Client::open(url)?;
+            let store = taskcast_redis::RedisShortTermStore::new(
+                client.get_multiplexed_async_connection().await?,
+                None,
+            )
+            .with_integrity(integrity.clone());
+            runtime_info.short_term_store = AdapterDescription::new("redis")
+                .with_url(url)
+                .with_prefix(store.key_prefix());
+            let long_term_store: Option<Arc<dyn taskcast_core::LongTermStore>> = match postgres_url
+            {
+                Some(ref pg_url) => {
+                    runtime_info.long_term_store =
+                        Some(AdapterDescription::new("postgres").with_url(pg_url));
+                    let pool = sqlx::PgPool::connect(pg_url).await?;
+                    Some(Arc::new(
+                        taskcast_postgres::PostgresLongTermStore::new(pool)
+                            .with_integrity(integrity),
+                    ))
+                }
+                None => None,
+            };
+            (Arc::new(store), long_term_store)
+        }
+        _ => {
+            return Err(
+                "In-memory tasks live in the server process; use --server to fetch \
+                 the bundle from the node, or --storage redis|sqlite"
+                    .into(),
+            );
+        }
+    };
+
+    let engine = taskcast_core::TaskEngine::new(taskcast_core::TaskEngineOptions {
+        short_term_store,
+        broadcast: Arc::new(taskcast_core::MemoryBroadcastProvider::new()),
+        long_term_store,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    let options = DebugBundleOptions::from_config(
+        config
+            .debug
+            .as_ref()
+            .and_then(|debug| debug.bundle.as_ref()),
+    );
+    let sources = DebugBundleSources {
+        engine: &engine,
+        auth_mode: &AuthMode::None,
+        uptime: std::time::Duration::ZERO,
+        runtime_info: &runtime_info,
+        http_tap: None,
+        runtime_sampler: None,
+        options: &options,
+    };
+    Ok(DebugBundle::collect(&sources, task_id).await?)
+}
+
+pub async fn run(args: DebugBundleArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let archive = if args.server {
+        let config_dir = dirs::home_dir()
+            .expect("could not determine home directory")
+            .join(".taskcast");
+        let mgr = NodeConfigManager::new(config_dir);
+        let node = match args.node {
+            Some(ref name) => mgr
+                .get(name)
+                .ok_or_else(|| format!("Node \"{name}\" not found"))?,
+            None => mgr.get_current(),
+        };
+        let client = TaskcastClient::from_node(&node).await?;
+        fetch_bundle(&client, &args.task)
+            .await
+            .map_err(|e| format!("Failed to fetch debug bundle: {e}"))?
+    } else {
+        let config = taskcast_core::config::load_config_file(args.config.as_deref())
+            .map_err(|e| format!("Failed to load config file: {e}"))?;
+        collect_local(
+            &config,
+            &args.storage,
+            &args.db_path,
+            &args.stores,
+            &args.task,
+        )
+        .await?
+        .to_tar_gz()?
+    };
+
+    let out = args.out.unwrap_or_else(|| default_out_path(&args.task));
+    std::fs::write(&out, &archive)?;
+    eprintln!(
+        "[taskcast] Wrote debug bundle for {} to {out} ({} bytes)",
+        args.task,
+        archive.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_out_path_is_file_name_safe() {
+        assert_eq!(default_out_path("01JABC"), "taskcast-debug-01JABC.tgz");
+        assert_eq!(default_out_path("a/b c"), "taskcast-debug-a_b_c.tgz");
+    }
+}
@@ -1,15 +1,27 @@
 use clap::Args;
 use std::collections::HashMap;
+use taskcast_core::ConsistencyReport;
 
 use crate::node_config::NodeEntry;
+use crate::output::{self, OutputFormat, Render, Table};
 
 #[derive(Args)]
 pub struct DoctorArgs {
     /// Node name to check (default: current node)
     #[arg(long)]
     pub node: Option<String>,
+
+    /// Also scan the node's stores for inconsistencies (needs task:manage)
+    #[arg(long)]
+    pub deep: bool,
+
+    /// Repair what the deep scan finds instead of only reporting it
+    #[arg(long, requires = "deep")]
+    pub repair: bool,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ServerStatus {
     pub ok: bool,
     pub url: String,
@@ -17,24 +29,92 @@ pub struct ServerStatus {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AuthStatus {
     pub status: String, // "ok" or "warn"
     pub mode: Option<String>,
     pub message: Option<String>,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AdapterStatus {
     pub name: String,
     pub provider: String,
     pub status: String,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DoctorResult {
     pub server: ServerStatus,
     pub auth: AuthStatus,
     pub adapters: Vec<AdapterStatus>,
 }
 
+/// Result of `taskcast doctor`: the health checks, plus the store scan with
+/// `--deep`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    #[serde(flatten)]
+    pub result: DoctorResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<ConsistencyReport>,
+}
+
+impl Render for DoctorReport {
+    fn plain(&self) -> String {
+        let mut text = format_doctor_result(&self.result);
+        if let Some(ref report) = self.consistency {
+            text.push('\n');
+            text.push_str(&format_consistency_report(report));
+        }
+        text
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["CHECK", "STATUS", "DETAIL"]);
+        let server = &self.result.server;
+        if server.ok {
+            let uptime = server
+                .uptime
+                .map(|s| format!(" (uptime: {s}s)"))
+                .unwrap_or_default();
+            table.row(["server", "OK", &format!("{}{uptime}", server.url)]);
+        } else {
+            let error = server.error.as_deref().unwrap_or("unknown error");
+            table.row(["server", "FAIL", &format!("{}: {error}", server.url)]);
+        }
+        let auth = &self.result.auth;
+        let auth_detail = match auth.status.as_str() {
+            "ok" => auth.mode.as_deref(),
+            _ => auth.message.as_deref().or(auth.mode.as_deref()),
+        };
+        table.row([
+            "auth",
+            &auth.status.to_uppercase(),
+            auth_detail.unwrap_or("unknown"),
+        ]);
+        for adapter in &self.result.adapters {
+            let status = if adapter.status == "ok" { "OK" } else { "FAIL" };
+            table.row([adapter.name.as_str(), status, &adapter.provider]);
+        }
+        if let Some(ref report) = self.consistency {
+            let status = if report.is_clean() { "OK" } else { "WARN" };
+            let detail = format!(
+                "{} issues, {} short-term, {} long-term tasks scanned",
+                report.counts.total(),
+                report.short_term_tasks_scanned,
+                report.long_term_tasks_scanned
+            );
+            table.row(["stores", status, &detail]);
+        }
+        table
+    }
+}
+
 #[derive(serde::Deserialize)]
 struct HealthDetailAuth {
     mode: String,
@@ -154,6 +234,66 @@ pub async fn run_doctor(node: &NodeEntry) -> DoctorResult {
     }
 }
 
+pub async fn run_consistency(node: &NodeEntry, repair: bool) -> Result<ConsistencyReport, String> {
+    let url = node.url.trim_end_matches('/');
+    let endpoint = format!("{url}/admin/consistency");
+
+    let client = reqwest::Client::new();
+    let mut req = client
+        .post(&endpoint)
+        .json(&serde_json::json!({ "dryRun": !repair }));
+    if let Some(ref token) = node.token {
+        req = req.bearer_auth(token);
+    }
+    let res = req.send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("HTTP {}", res.status().as_u16()));
+    }
+    res.json()
+        .await
+        .map_err(|e| format!("failed to parse response: {e}"))
+}
+
+pub fn format_consistency_report(report: &ConsistencyReport) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    let scanned = format!(
+        "{} short-term, {} long-term tasks scanned",
+        report.short_term_tasks_scanned, report.long_term_tasks_scanned
+    );
+    if report.is_clean() {
+        lines.push(format!("Stores:    OK  {scanned}"));
+    } else {
+        lines.push(format!(
+            "Stores:    WARN  {} issues, {scanned}",
+            report.counts.total()
+        ));
+    }
+
+    let counts = [
+        ("orphanedEvents", report.counts.orphaned_events),
+        ("danglingSeriesLatest", report.counts.dangling_series_latest),
+        ("staleIndexCounters", report.counts.stale_index_counters),
+        ("missingFromLongTerm", report.counts.missing_from_long_term),
+    ];
+    for (kind, count) in counts {
+        if count > 0 {
+            lines.push(format!("           {kind}: {count}"));
+        }
+    }
+    if report.repair && !report.is_clean() {
+        lines.push(format!(
+            "           repaired {}, failed {}",
+            report.repaired, report.repair_failures
+        ));
+    }
+    for reason in &report.skipped {
+        lines.push(format!("           SKIP  {reason}"));
+    }
+
+    lines.join("\n")
+}
+
 pub fn format_doctor_result(result: &DoctorResult) -> String {
     let mut lines: Vec<String> = Vec::new();
 
@@ -211,7 +351,10 @@ pub fn format_doctor_result(result: &DoctorResult) -> String {
     lines.join("\n")
 }
 
-pub async fn run(args: DoctorArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(
+    args: DoctorArgs,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
     let config_dir = dirs::home_dir()
         .expect("could not determine home directory")
         .join(".taskcast");
@@ -228,12 +371,26 @@ pub async fn run(args: DoctorArgs) -> Result<(), Box<dyn std::error::Error>> {
         mgr.get_current()
     };
 
-    let result = run_doctor(&node).await;
-    println!("{}", format_doctor_result(&result));
+    let mut report = DoctorReport {
+        result: run_doctor(&node).await,
+        consistency: None,
+    };
+    if !report.result.server.ok {
+        output::print(&report, format);
+        let error = report.result.server.error.as_deref();
+        return Err(format!("Server check failed: {}", error.unwrap_or("unknown error")).into());
+    }
 
-    if !result.server.ok {
-        return Err(format!("Server check failed: {}", result.server.error.as_deref().unwrap_or("unknown error")).into());
+    if args.deep {
+        match run_consistency(&node, args.repair).await {
+            Ok(consistency) => report.consistency = Some(consistency),
+            Err(e) => {
+                output::print(&report, format);
+                return Err(format!("Consistency scan failed: {e}").into());
+            }
+        }
     }
+    output::print(&report, format);
 
     Ok(())
 }
@@ -565,4 +722,53 @@ mod tests {
             "got: {output}"
         );
     }
+
+    #[test]
+    fn format_consistency_clean() {
+        let report = ConsistencyReport {
+            short_term_tasks_scanned: 12,
+            long_term_tasks_scanned: 40,
+            ..Default::default()
+        };
+        let output = format_consistency_report(&report);
+        assert_eq!(
+            output,
+            "Stores:    OK  12 short-term, 40 long-term tasks scanned"
+        );
+    }
+
+    #[test]
+    fn format_consistency_issues_and_repairs() {
+        let report: ConsistencyReport = serde_json::from_value(serde_json::json!({
+            "dryRun": false,
+            "repair": true,
+            "shortTermTasksScanned": 3,
+            "longTermTasksScanned": 0,
+            "counts": {
+                "orphanedEvents": 0,
+                "danglingSeriesLatest": 1,
+                "staleIndexCounters": 2,
+                "missingFromLongTerm": 0
+            },
+            "repaired": 2,
+            "repairFailures": 1,
+            "findings": [],
+            "findingsTruncated": false,
+            "skipped": ["orphanedEvents, missingFromLongTerm: no long-term store"]
+        }))
+        .unwrap();
+        let output = format_consistency_report(&report);
+        assert!(
+            output.contains("Stores:    WARN  3 issues, 3 short-term, 0 long-term tasks scanned"),
+            "got: {output}"
+        );
+        assert!(output.contains("danglingSeriesLatest: 1"), "got: {output}");
+        assert!(output.contains("staleIndexCounters: 2"), "got: {output}");
+        assert!(!output.contains("orphanedEvents: 0"), "got: {output}");
+        assert!(output.contains("repaired 2, failed 1"), "got: {output}");
+        assert!(
+            output.contains("SKIP  orphanedEvents, missingFromLongTerm: no long-term store"),
+            "got: {output}"
+        );
+    }
 }
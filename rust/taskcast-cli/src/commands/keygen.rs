@@ -0,0 +1,83 @@
+use clap::Args;
+
+use crate::output::{self, OutputFormat, Render, Table};
+
+/// Prefix of generated keys, so they are recognisable in logs and secret scanners.
+const KEY_PREFIX: &str = "tck_";
+
+#[derive(Args, Debug)]
+pub struct KeygenArgs {
+    /// Name to use in the printed config snippet
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedKey {
+    pub key: String,
+    pub key_hash: String,
+}
+
+/// Generates a random 256-bit API key and its config hash.
+pub fn generate_api_key() -> Result<GeneratedKey, getrandom::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes)?;
+    let key = format!("{KEY_PREFIX}{}", hex_encode(&bytes));
+    let key_hash = taskcast_server::hash_api_key(&key);
+    Ok(GeneratedKey { key, key_hash })
+}
+
+/// Formats a generated key for the terminal: the key itself, then a config
+/// entry that only contains its hash.
+pub fn format_generated_key(generated: &GeneratedKey, name: &str) -> String {
+    format!(
+        "key:  {key}\n\
+         hash: {hash}\n\
+         \n\
+         # Add to taskcast.config.yaml (the key itself is not stored):\n\
+         auth:\n  \
+           mode: apiKeys\n  \
+           apiKeys:\n    \
+             - name: {name}\n      \
+               keyHash: \"{hash}\"\n      \
+               scope: [\"*\"]\n",
+        key = generated.key,
+        hash = generated.key_hash,
+    )
+}
+
+/// Result of `taskcast keygen`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NamedKey {
+    pub name: String,
+    #[serde(flatten)]
+    pub generated: GeneratedKey,
+}
+
+impl Render for NamedKey {
+    fn plain(&self) -> String {
+        format_generated_key(&self.generated, &self.name)
+            .trim_end()
+            .to_string()
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["NAME", "KEY", "HASH"]);
+        table.row([&self.name, &self.generated.key, &self.generated.key_hash]);
+        table
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn run(args: KeygenArgs, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let named = NamedKey {
+        name: args.name.unwrap_or_else(|| "default".to_string()),
+        generated: generate_api_key()?,
+    };
+    output::print(&named, format);
+    Ok(())
+}
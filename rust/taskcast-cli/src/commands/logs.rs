@@ -2,6 +2,7 @@ use clap::Args;
 
 use crate::client::TaskcastClient;
 use crate::node_config::NodeConfigManager;
+use crate::output::OutputFormat;
 
 // ─── Args ─────────────────────────────────────────────────────────────────────
 
@@ -68,6 +69,68 @@ pub fn format_event(
     )
 }
 
+/// One streamed event, as `logs` and `tail` print it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub level: String,
+    pub timestamp: i64,
+    pub data: serde_json::Value,
+}
+
+impl LogLine {
+    /// Reads a `taskcast.event` payload, filling in what it lacks.
+    pub fn from_event(event: &serde_json::Value) -> Self {
+        let str_field = |key: &str| event.get(key).and_then(|v| v.as_str());
+        Self {
+            task_id: str_field("taskId").map(str::to_string),
+            event_type: str_field("type").unwrap_or("unknown").to_string(),
+            level: str_field("level").unwrap_or("info").to_string(),
+            timestamp: event.get("timestamp").and_then(|v| v.as_i64()).unwrap_or(0),
+            data: event
+                .get("data")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+        }
+    }
+
+    /// The closing line for a `taskcast.done` payload.
+    pub fn done(event: &serde_json::Value) -> Self {
+        let reason = event
+            .as_object()
+            .and_then(|obj| obj.get("reason"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        Self {
+            task_id: None,
+            event_type: "taskcast.done".to_string(),
+            level: "info".to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            data: serde_json::json!({ "reason": reason }),
+        }
+    }
+
+    /// Renders the line for a stream: one compact JSON object per line for
+    /// `--output json`, and the columnar text otherwise, since a stream has
+    /// no end at which to size table columns.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Json => serde_json::to_string(self).expect("log lines serialize to JSON"),
+            OutputFormat::Plain | OutputFormat::Table => format_event(
+                &self.event_type,
+                &self.level,
+                self.timestamp,
+                &self.data,
+                self.task_id.as_deref(),
+            ),
+        }
+    }
+}
+
 // ─── SSE Consumer ─────────────────────────────────────────────────────────────
 
 pub async fn consume_sse(
@@ -130,7 +193,10 @@ pub async fn consume_sse(
 
 // ─── Commands ─────────────────────────────────────────────────────────────────
 
-pub async fn run_logs(args: LogsArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run_logs(
+    args: LogsArgs,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
     let config_dir = dirs::home_dir()
         .expect("could not determine home directory")
         .join(".taskcast");
@@ -171,37 +237,14 @@ pub async fn run_logs(args: LogsArgs) -> Result<(), Box<dyn std::error::Error>>
         client.token(),
         |event, sse_event_name| {
             if sse_event_name == "taskcast.done" {
-                let reason = event
-                    .as_object()
-                    .and_then(|obj| obj.get("reason"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-                let ts = chrono::Utc::now().timestamp_millis();
-                println!(
-                    "{}",
-                    format_event(
-                        "taskcast.done",
-                        "info",
-                        ts,
-                        &serde_json::json!({ "reason": reason }),
-                        None,
-                    )
-                );
+                println!("{}", LogLine::done(&event).render(format));
             } else if sse_event_name == "taskcast.event" {
-                let event_type = event
-                    .get("type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-                let level = event
-                    .get("level")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("info");
-                let timestamp = event
-                    .get("timestamp")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0);
-                let data = event.get("data").cloned().unwrap_or(serde_json::Value::Null);
-                println!("{}", format_event(event_type, level, timestamp, &data, None));
+                // Events on a single task's stream print without the task id.
+                let line = LogLine {
+                    task_id: None,
+                    ..LogLine::from_event(&event)
+                };
+                println!("{}", line.render(format));
             }
         },
         None,
@@ -211,7 +254,10 @@ pub async fn run_logs(args: LogsArgs) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-pub async fn run_tail(args: TailArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run_tail(
+    args: TailArgs,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
     let config_dir = dirs::home_dir()
         .expect("could not determine home directory")
         .join(".taskcast");
@@ -248,24 +294,7 @@ pub async fn run_tail(args: TailArgs) -> Result<(), Box<dyn std::error::Error>>
         client.token(),
         |event, sse_event_name| {
             if sse_event_name == "taskcast.event" {
-                let event_type = event
-                    .get("type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown");
-                let level = event
-                    .get("level")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("info");
-                let timestamp = event
-                    .get("timestamp")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0);
-                let data = event.get("data").cloned().unwrap_or(serde_json::Value::Null);
-                let task_id = event.get("taskId").and_then(|v| v.as_str());
-                println!(
-                    "{}",
-                    format_event(event_type, level, timestamp, &data, task_id)
-                );
+                println!("{}", LogLine::from_event(&event).render(format));
             }
         },
         None,
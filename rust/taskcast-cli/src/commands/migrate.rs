@@ -7,7 +7,7 @@ use crate::helpers::{format_display_url, resolve_postgres_url};
 #[derive(Args, Debug)]
 pub struct MigrateArgs {
     /// Postgres connection URL (highest priority)
-    #[arg(long)]
+    #[arg(long, env = "TASKCAST_POSTGRES_URL", hide_env_values = true)]
     pub url: Option<String>,
     /// Config file path
     #[arg(short, long)]
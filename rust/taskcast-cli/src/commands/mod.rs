@@ -1,4 +1,6 @@
+pub mod debug_bundle;
 pub mod doctor;
+pub mod keygen;
 pub mod logs;
 pub mod migrate;
 pub mod node;
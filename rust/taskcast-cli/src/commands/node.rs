@@ -1,6 +1,7 @@
 use clap::Subcommand;
 
 use crate::node_config::{NodeConfigManager, NodeEntry, NodeListEntry, TokenType};
+use crate::output::{self, OutputFormat, Render, Table};
 
 #[derive(Subcommand)]
 pub enum NodeCommands {
@@ -23,6 +24,84 @@ pub enum NodeCommands {
     List,
 }
 
+/// Result of `taskcast node list`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NodeList {
+    pub nodes: Vec<NodeListEntry>,
+}
+
+impl Render for NodeList {
+    fn plain(&self) -> String {
+        format_node_list(&self.nodes)
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["CURRENT", "NAME", "URL", "TOKEN"]);
+        for n in &self.nodes {
+            table.row([
+                if n.current { "*" } else { "" }.to_string(),
+                n.name.clone(),
+                n.entry.url.clone(),
+                token_type_label(n.entry.token_type.as_ref()).to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NodeAction {
+    Added,
+    Removed,
+    Switched,
+}
+
+/// Result of `taskcast node add|remove|use`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NodeChange {
+    pub action: NodeAction,
+    pub name: String,
+    /// The node's URL, for `add`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Render for NodeChange {
+    fn plain(&self) -> String {
+        let name = &self.name;
+        match self.action {
+            NodeAction::Added => {
+                format!(
+                    "Added node \"{name}\" -> {}",
+                    self.url.as_deref().unwrap_or("")
+                )
+            }
+            NodeAction::Removed => format!("Removed node \"{name}\""),
+            NodeAction::Switched => format!("Switched to node \"{name}\""),
+        }
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["ACTION", "NAME", "URL"]);
+        let action = match self.action {
+            NodeAction::Added => "added",
+            NodeAction::Removed => "removed",
+            NodeAction::Switched => "switched",
+        };
+        table.row([action, &self.name, self.url.as_deref().unwrap_or("")]);
+        table
+    }
+}
+
+fn token_type_label(token_type: Option<&TokenType>) -> &'static str {
+    match token_type {
+        Some(TokenType::Jwt) => "jwt",
+        Some(TokenType::Admin) => "admin",
+        None => "",
+    }
+}
+
 /// Format the node list for display. Matches TypeScript output format.
 pub fn format_node_list(nodes: &[NodeListEntry]) -> String {
     if nodes.is_empty() {
@@ -51,7 +130,7 @@ fn get_config_manager() -> NodeConfigManager {
     NodeConfigManager::new(config_dir)
 }
 
-pub fn run(command: NodeCommands) -> Result<(), Box<dyn std::error::Error>> {
+pub fn run(command: NodeCommands, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
     let mgr = get_config_manager();
 
     match command {
@@ -77,12 +156,22 @@ pub fn run(command: NodeCommands) -> Result<(), Box<dyn std::error::Error>> {
                     token_type: tt,
                 },
             );
-            println!("Added node \"{name}\" -> {url}");
+            let change = NodeChange {
+                action: NodeAction::Added,
+                name,
+                url: Some(url),
+            };
+            output::print(&change, format);
             Ok(())
         }
         NodeCommands::Remove { name } => match mgr.remove(&name) {
             Ok(()) => {
-                println!("Removed node \"{name}\"");
+                let change = NodeChange {
+                    action: NodeAction::Removed,
+                    name,
+                    url: None,
+                };
+                output::print(&change, format);
                 Ok(())
             }
             Err(e) => {
@@ -91,7 +180,12 @@ pub fn run(command: NodeCommands) -> Result<(), Box<dyn std::error::Error>> {
         },
         NodeCommands::Use { name } => match mgr.set_current(&name) {
             Ok(()) => {
-                println!("Switched to node \"{name}\"");
+                let change = NodeChange {
+                    action: NodeAction::Switched,
+                    name,
+                    url: None,
+                };
+                output::print(&change, format);
                 Ok(())
             }
             Err(e) => {
@@ -99,8 +193,8 @@ pub fn run(command: NodeCommands) -> Result<(), Box<dyn std::error::Error>> {
             }
         },
         NodeCommands::List => {
-            let nodes = mgr.list();
-            println!("{}", format_node_list(&nodes));
+            let list = NodeList { nodes: mgr.list() };
+            output::print(&list, format);
             Ok(())
         }
     }
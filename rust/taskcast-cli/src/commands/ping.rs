@@ -1,6 +1,7 @@
 use clap::Args;
 
 use crate::node_config::NodeConfigManager;
+use crate::output::{self, OutputFormat, Render, Table};
 
 #[derive(Args, Debug)]
 pub struct PingArgs {
@@ -9,12 +10,54 @@ pub struct PingArgs {
     pub node: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PingResult {
     pub ok: bool,
     pub latency_ms: Option<u64>,
     pub error: Option<String>,
 }
 
+/// Result of `taskcast ping`: the node's URL and how the ping went.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PingReport {
+    pub url: String,
+    #[serde(flatten)]
+    pub result: PingResult,
+}
+
+impl Render for PingReport {
+    fn plain(&self) -> String {
+        if self.result.ok {
+            format!(
+                "OK — taskcast at {} ({}ms)",
+                self.url,
+                self.result.latency_ms.unwrap_or_default()
+            )
+        } else {
+            format!(
+                "FAIL — cannot reach {}: {}",
+                self.url,
+                self.result.error.as_deref().unwrap_or("unknown error")
+            )
+        }
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["URL", "STATUS", "LATENCY", "ERROR"]);
+        table.row([
+            self.url.clone(),
+            if self.result.ok { "OK" } else { "FAIL" }.to_string(),
+            self.result
+                .latency_ms
+                .map(|ms| format!("{ms}ms"))
+                .unwrap_or_default(),
+            self.result.error.clone().unwrap_or_default(),
+        ]);
+        table
+    }
+}
+
 pub async fn ping_server(url: &str) -> PingResult {
     let start = std::time::Instant::now();
     let client = reqwest::Client::builder()
@@ -41,7 +84,7 @@ pub async fn ping_server(url: &str) -> PingResult {
     }
 }
 
-pub async fn run(args: PingArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(args: PingArgs, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
     let config_dir = dirs::home_dir()
         .expect("could not determine home directory")
         .join(".taskcast");
@@ -57,20 +100,20 @@ pub async fn run(args: PingArgs) -> Result<(), Box<dyn std::error::Error>> {
         None => mgr.get_current(),
     };
 
-    let result = ping_server(&node.url).await;
-    if result.ok {
-        println!(
-            "OK — taskcast at {} ({}ms)",
-            node.url,
-            result.latency_ms.unwrap()
-        );
+    let report = PingReport {
+        result: ping_server(&node.url).await,
+        url: node.url,
+    };
+    if report.result.ok {
+        output::print(&report, format);
         Ok(())
     } else {
-        Err(format!(
-            "FAIL — cannot reach {}: {}",
-            node.url,
-            result.error.unwrap()
-        ).into())
+        // A failed ping is still a result for machine-readable output; the
+        // plain message goes to stderr as the error.
+        if format != OutputFormat::Plain {
+            output::print(&report, format);
+        }
+        Err(report.plain().into())
     }
 }
 
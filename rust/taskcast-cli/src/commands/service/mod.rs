@@ -13,6 +13,7 @@ use clap::{Args, Subcommand};
 use self::manager::{create_service_manager, ServiceInstallOptions, ServiceStatus};
 use self::paths::{ServicePaths, delete_state, ensure_config, get_port, write_state};
 use self::health::{fetch_health_detail, format_uptime, poll_health};
+use crate::output::{self, OutputFormat, Render, Table};
 
 const HEALTH_TIMEOUT_MS: u64 = 5000;
 const HEALTH_INTERVAL_MS: u64 = 500;
@@ -57,7 +58,7 @@ pub enum ServiceCommands {
     Status,
 }
 
-pub async fn run(args: ServiceArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(args: ServiceArgs, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
     match args.command {
         ServiceCommands::Install { config, port, storage, db_path } => {
             run_install(config, port, storage, db_path)?;
@@ -75,7 +76,7 @@ pub async fn run(args: ServiceArgs) -> Result<(), Box<dyn std::error::Error>> {
             run_restart().await?;
         }
         ServiceCommands::Status => {
-            run_status().await?;
+            run_status(format).await?;
         }
     }
     Ok(())
@@ -197,25 +198,61 @@ async fn run_restart() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-pub async fn run_status() -> Result<(), Box<dyn std::error::Error>> {
+/// Result of `taskcast service status`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceReport {
+    /// `running (pid N)`, `stopped` or `not installed`.
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
+}
+
+impl Render for ServiceReport {
+    fn plain(&self) -> String {
+        let mut lines = vec![format!("Service:   {}", self.status)];
+        if let Some(secs) = self.uptime_secs {
+            lines.push(format!("Uptime:    {}", format_uptime(secs)));
+        }
+        if let Some(ref provider) = self.storage {
+            lines.push(format!("Storage:   {provider}"));
+        }
+        lines.join("\n")
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["STATUS", "UPTIME", "STORAGE"]);
+        table.row([
+            self.status.clone(),
+            self.uptime_secs.map(format_uptime).unwrap_or_default(),
+            self.storage.clone().unwrap_or_default(),
+        ]);
+        table
+    }
+}
+
+pub async fn run_status(format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
     let mgr = create_service_manager()?;
     let paths = ServicePaths::new()?;
 
     let status = mgr.status()?;
-    eprintln!("Service:   {status}");
+    let mut report = ServiceReport {
+        status: status.to_string(),
+        uptime_secs: None,
+        storage: None,
+    };
 
     if let ServiceStatus::Running { .. } = &status {
         let port = get_port(&paths);
         if let Some((uptime, storage)) = fetch_health_detail(port).await {
-            if let Some(secs) = uptime {
-                eprintln!("Uptime:    {}", format_uptime(secs));
-            }
-            if let Some(provider) = storage {
-                eprintln!("Storage:   {provider}");
-            }
+            report.uptime_secs = uptime;
+            report.storage = storage;
         }
     }
 
+    output::print(&report, format);
     Ok(())
 }
 
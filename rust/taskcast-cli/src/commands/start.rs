@@ -1,10 +1,10 @@
 use std::sync::Arc;
 
-use clap::Args;
+use clap::{Args, Parser};
 
 use crate::auto_migrate::run_auto_migrate;
 use crate::helpers::{
-    auth_mode_to_string, parse_jwt_algorithm, resolve_port, resolve_storage_mode,
+    auth_mode_to_string, resolve_jwt_algorithm, resolve_port, resolve_storage_mode,
 };
 
 #[derive(Args, Debug)]
@@ -27,6 +27,11 @@ pub struct StartArgs {
     /// Enable verbose output
     #[arg(short, long)]
     pub verbose: bool,
+    /// Also serve a read-only API (GET and SSE only) on this port
+    #[arg(long)]
+    pub readonly_port: Option<u16>,
+    #[command(flatten)]
+    pub env: StartEnv,
 }
 
 impl Default for StartArgs {
@@ -38,7 +43,78 @@ impl Default for StartArgs {
             db_path: "./taskcast.db".to_string(),
             playground: false,
             verbose: false,
+            readonly_port: None,
+            env: StartEnv::default(),
+        }
+    }
+}
+
+/// Deployment settings usually given as `TASKCAST_*` environment variables.
+/// Each one is also a flag, so `--help` and shell completions list them.
+/// They override the matching config file values.
+#[derive(Args, Debug, Clone)]
+#[command(next_help_heading = "Environment")]
+pub struct StartEnv {
+    /// Log level: error, warn, info, debug, or trace
+    #[arg(long, env = "TASKCAST_LOG_LEVEL")]
+    pub log_level: Option<String>,
+    #[command(flatten)]
+    pub stores: StoreEnv,
+    /// Postgres read replica URL for long-term reads
+    #[arg(long, env = "TASKCAST_POSTGRES_READ_URL", hide_env_values = true)]
+    pub postgres_read_url: Option<String>,
+    /// Run pending Postgres migrations on startup (1/true/yes/on)
+    #[arg(long, env = "TASKCAST_AUTO_MIGRATE", value_name = "BOOL")]
+    pub auto_migrate: Option<String>,
+    /// Auth mode: none, jwt, or apiKeys
+    #[arg(long, env = "TASKCAST_AUTH_MODE")]
+    pub auth_mode: Option<String>,
+    /// JWT signing algorithm (e.g. HS256, RS256)
+    #[arg(long, env = "TASKCAST_JWT_ALGORITHM")]
+    pub jwt_algorithm: Option<String>,
+    /// PEM public key for asymmetric JWT algorithms
+    #[arg(long, env = "TASKCAST_JWT_PUBLIC_KEY", hide_env_values = true)]
+    pub jwt_public_key: Option<String>,
+    /// File holding the PEM public key for asymmetric JWT algorithms
+    #[arg(long, env = "TASKCAST_JWT_PUBLIC_KEY_FILE")]
+    pub jwt_public_key_file: Option<String>,
+    /// Shared secret for HMAC JWT algorithms
+    #[arg(long, env = "TASKCAST_JWT_SECRET", hide_env_values = true)]
+    pub jwt_secret: Option<String>,
+    /// Required JWT issuer
+    #[arg(long, env = "TASKCAST_JWT_ISSUER")]
+    pub jwt_issuer: Option<String>,
+    /// Required JWT audience
+    #[arg(long, env = "TASKCAST_JWT_AUDIENCE")]
+    pub jwt_audience: Option<String>,
+}
+
+/// Where the stores are, for the commands that open them (`start` and
+/// `debug-bundle`). Overrides the config file's adapter URLs.
+#[derive(Args, Debug, Clone, Default)]
+pub struct StoreEnv {
+    /// Redis URL for broadcast and short-term storage
+    #[arg(long, env = "TASKCAST_REDIS_URL", hide_env_values = true)]
+    pub redis_url: Option<String>,
+    /// Postgres URL for long-term storage
+    #[arg(long, env = "TASKCAST_POSTGRES_URL", hide_env_values = true)]
+    pub postgres_url: Option<String>,
+    /// Use SQLite when --storage is not given (only "sqlite" is honoured)
+    #[arg(long, env = "TASKCAST_STORAGE")]
+    pub default_storage: Option<String>,
+}
+
+/// Reads the current environment, as `taskcast start` with no flags would.
+impl Default for StartEnv {
+    fn default() -> Self {
+        #[derive(Parser)]
+        struct EnvOnly {
+            #[command(flatten)]
+            env: StartEnv,
         }
+        EnvOnly::try_parse_from(["taskcast"])
+            .unwrap_or_else(|e| e.exit())
+            .env
     }
 }
 
@@ -52,27 +128,301 @@ impl Default for StartArgs {
 /// regardless of whether the URL came from an env var or the config file.
 async fn create_postgres_pool_with_auto_migrate(
     postgres_url: &str,
+    auto_migrate: Option<&str>,
 ) -> Result<sqlx::PgPool, Box<dyn std::error::Error>> {
     let pool = sqlx::PgPool::connect(postgres_url).await?;
 
-    run_auto_migrate(
-        Some(&pool),
-        Some(postgres_url),
-        std::env::var("TASKCAST_AUTO_MIGRATE").ok().as_deref(),
-    )
-    .await?;
+    run_auto_migrate(Some(&pool), Some(postgres_url), auto_migrate).await?;
 
     Ok(pool)
 }
 
-fn env_non_empty(key: &str) -> Option<String> {
-    std::env::var(key).ok().filter(|value| !value.is_empty())
+/// Read replica settings for the Postgres long-term store.
+#[derive(Debug)]
+struct PostgresReplica {
+    url: Option<String>,
+    read_after_write: Option<std::time::Duration>,
+}
+
+/// Create the Postgres long-term store, reading through a replica pool when
+/// one is configured.
+async fn create_postgres_store(
+    postgres_url: &str,
+    replica: &PostgresReplica,
+    payload_dedup: Option<taskcast_core::PayloadDedupConfig>,
+    slow_query_threshold: Option<std::time::Duration>,
+    auto_migrate: Option<&str>,
+) -> Result<taskcast_postgres::PostgresLongTermStore, Box<dyn std::error::Error>> {
+    let pool = create_postgres_pool_with_auto_migrate(postgres_url, auto_migrate).await?;
+    let mut store = match payload_dedup {
+        Some(config) => taskcast_postgres::PostgresLongTermStore::with_payload_dedup(pool, config),
+        None => taskcast_postgres::PostgresLongTermStore::new(pool),
+    };
+    if let Some(ref read_url) = replica.url {
+        store = store.with_read_pool(sqlx::PgPool::connect(read_url).await?);
+    }
+    if let Some(window) = replica.read_after_write {
+        store = store.with_read_after_write_window(window);
+    }
+    if let Some(threshold) = slow_query_threshold {
+        store = store.with_slow_query_threshold(threshold);
+    }
+    Ok(store)
+}
+
+/// Create the Kafka broadcast provider for an `adapters.broadcast` entry
+/// whose provider is `kafka`. Its `url` lists the brokers.
+fn create_kafka_broadcast(
+    entry: &taskcast_core::config::AdapterEntry,
+) -> Result<taskcast_kafka::KafkaBroadcastProvider, Box<dyn std::error::Error>> {
+    let brokers = entry
+        .url
+        .as_deref()
+        .ok_or("adapters.broadcast.url must list the Kafka brokers")?;
+    Ok(taskcast_kafka::KafkaBroadcastProvider::new(
+        brokers,
+        entry.topic.as_deref(),
+        entry.group_id.as_deref(),
+    )?)
+}
+
+const DEFAULT_MEMORY_PERSIST_INTERVAL_MS: u64 = 5000;
+#[cfg(feature = "runtime-diagnostics")]
+const DEFAULT_TOKIO_CONSOLE_ADDRESS: &str = "127.0.0.1:6669";
+
+/// Create the in-memory short-term store. An `adapters.shortTerm` entry
+/// whose provider is `memory` and that sets `persistPath` makes it snapshot
+/// to that file.
+fn create_memory_short_term_store(
+    entry: Option<&taskcast_core::config::AdapterEntry>,
+) -> Arc<taskcast_core::MemoryShortTermStore> {
+    let entry = entry.filter(|entry| entry.provider == "memory");
+    match entry.and_then(|entry| entry.persist_path.as_deref()) {
+        Some(path) => {
+            let interval = entry
+                .and_then(|entry| entry.persist_interval_ms)
+                .unwrap_or(DEFAULT_MEMORY_PERSIST_INTERVAL_MS);
+            eprintln!("[taskcast] Snapshotting in-memory tasks to {path} every {interval} ms");
+            taskcast_core::MemoryShortTermStore::with_persistence(
+                path,
+                std::time::Duration::from_millis(interval.max(1)),
+            )
+        }
+        None => Arc::new(taskcast_core::MemoryShortTermStore::new()),
+    }
+}
+
+#[cfg(test)]
+mod memory_store_tests {
+    use std::sync::Arc;
+
+    use taskcast_core::config::AdapterEntry;
+    use taskcast_core::{CreateTaskInput, ShortTermStore};
+
+    use super::create_memory_short_term_store;
+
+    fn entry(provider: &str, persist_path: Option<String>) -> AdapterEntry {
+        AdapterEntry {
+            provider: provider.to_string(),
+            url: None,
+            read_url: None,
+            read_after_write_ms: None,
+            topic: None,
+            group_id: None,
+            type_channels: None,
+            legacy_channels: None,
+            persist_path,
+            persist_interval_ms: None,
+            slow_query_ms: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn persist_path_enables_snapshots() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("memory.json");
+        let entry = entry("memory", Some(path.to_string_lossy().into_owned()));
+
+        let store = create_memory_short_term_store(Some(&entry));
+        let engine = taskcast_core::TaskEngine::new(taskcast_core::TaskEngineOptions {
+            short_term_store: store.clone(),
+            broadcast: Arc::new(taskcast_core::MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+        let task = engine
+            .create_task(CreateTaskInput::default())
+            .await
+            .unwrap();
+        store.flush().unwrap();
+
+        let reloaded = create_memory_short_term_store(Some(&entry));
+        assert_eq!(reloaded.get_task(&task.id).await.unwrap(), Some(task));
+    }
+
+    #[tokio::test]
+    async fn other_providers_do_not_persist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("memory.json");
+        let entry = entry("redis", Some(path.to_string_lossy().into_owned()));
+
+        create_memory_short_term_store(Some(&entry))
+            .flush()
+            .unwrap();
+        create_memory_short_term_store(None).flush().unwrap();
+        assert!(!path.exists());
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
 }
 
 fn resolve_log_level(value: Option<&str>) -> Result<taskcast_server::LogLevel, String> {
     taskcast_server::LogLevel::parse(value)
 }
 
+/// The value format the Redis store writes in: `storage.redisValueFormat`,
+/// or the latest when unset.
+fn resolve_redis_value_format(configured: Option<u64>) -> Result<u64, String> {
+    use taskcast_redis::codec::{MIN_VALUE_FORMAT_VERSION, VALUE_FORMAT_VERSION};
+
+    match configured {
+        None => Ok(VALUE_FORMAT_VERSION),
+        Some(version) if (MIN_VALUE_FORMAT_VERSION..=VALUE_FORMAT_VERSION).contains(&version) => {
+            Ok(version)
+        }
+        Some(version) => Err(format!(
+            "storage.redisValueFormat must be between {MIN_VALUE_FORMAT_VERSION} and {VALUE_FORMAT_VERSION}, got {version}"
+        )),
+    }
+}
+
+/// Lease settings from an enabled `server.leaderElection`.
+#[derive(Debug, PartialEq)]
+struct LeaderElectionSettings {
+    key: String,
+    ttl: std::time::Duration,
+    renew_interval: std::time::Duration,
+}
+
+/// The lease settings if `server.leaderElection` is enabled, which needs the
+/// Redis storage mode.
+fn resolve_leader_election(
+    config: Option<&taskcast_core::config::LeaderElectionConfig>,
+    storage_mode: &str,
+) -> Result<Option<LeaderElectionSettings>, String> {
+    let Some(config) = config.filter(|c| c.enabled == Some(true)) else {
+        return Ok(None);
+    };
+    if storage_mode != "redis" {
+        return Err(format!(
+            "server.leaderElection needs Redis storage, but storage is {storage_mode}"
+        ));
+    }
+    let ttl = config
+        .ttl_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(taskcast_core::DEFAULT_LEADER_TTL);
+    Ok(Some(LeaderElectionSettings {
+        key: config
+            .key
+            .clone()
+            .unwrap_or_else(|| taskcast_core::DEFAULT_LEADER_KEY.to_string()),
+        ttl,
+        renew_interval: config
+            .renew_interval_ms
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(ttl / 3),
+    }))
+}
+
+/// A name for this process in the leader lease: its pid and a random suffix.
+fn leader_holder_id() -> String {
+    let mut bytes = [0u8; 8];
+    let suffix = match getrandom::getrandom(&mut bytes) {
+        Ok(()) => u64::from_le_bytes(bytes),
+        Err(_) => chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64,
+    };
+    format!("{}-{suffix:016x}", std::process::id())
+}
+
+#[cfg(test)]
+mod leader_election_tests {
+    use std::time::Duration;
+
+    use taskcast_core::config::LeaderElectionConfig;
+
+    use super::{resolve_leader_election, LeaderElectionSettings};
+
+    #[test]
+    fn disabled_unless_enabled() {
+        assert_eq!(resolve_leader_election(None, "redis").unwrap(), None);
+        let config = LeaderElectionConfig::default();
+        assert_eq!(
+            resolve_leader_election(Some(&config), "memory").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn defaults_to_a_third_of_the_ttl() {
+        let config = LeaderElectionConfig {
+            enabled: Some(true),
+            ttl_ms: Some(6_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_leader_election(Some(&config), "redis").unwrap(),
+            Some(LeaderElectionSettings {
+                key: "leader".to_string(),
+                ttl: Duration::from_secs(6),
+                renew_interval: Duration::from_secs(2),
+            })
+        );
+    }
+
+    #[test]
+    fn needs_redis_storage() {
+        let config = LeaderElectionConfig {
+            enabled: Some(true),
+            ..Default::default()
+        };
+        assert!(resolve_leader_election(Some(&config), "sqlite").is_err());
+    }
+}
+
+#[cfg(test)]
+mod redis_value_format_tests {
+    use taskcast_redis::codec::{MIN_VALUE_FORMAT_VERSION, VALUE_FORMAT_VERSION};
+
+    use super::resolve_redis_value_format;
+
+    #[test]
+    fn defaults_to_latest() {
+        assert_eq!(
+            resolve_redis_value_format(None).unwrap(),
+            VALUE_FORMAT_VERSION
+        );
+    }
+
+    #[test]
+    fn accepts_supported_versions() {
+        for version in MIN_VALUE_FORMAT_VERSION..=VALUE_FORMAT_VERSION {
+            assert_eq!(resolve_redis_value_format(Some(version)).unwrap(), version);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        assert!(resolve_redis_value_format(Some(0)).is_err());
+        assert!(resolve_redis_value_format(Some(VALUE_FORMAT_VERSION + 1)).is_err());
+    }
+}
+
 #[cfg(test)]
 mod log_level_tests {
     use taskcast_server::LogLevel;
@@ -128,6 +478,111 @@ fn trusted_services_from_config(
         .collect()
 }
 
+fn api_keys_from_config(
+    keys: Option<&[taskcast_core::config::ApiKeyConfig]>,
+) -> Vec<taskcast_server::ApiKeyEntry> {
+    keys.unwrap_or_default()
+        .iter()
+        .map(|key| taskcast_server::ApiKeyEntry {
+            name: key.name.clone(),
+            key_hash: key.key_hash.clone(),
+            task_ids: trusted_service_task_ids(key.task_ids.as_ref()),
+            scope: key.scope.clone(),
+        })
+        .collect()
+}
+
+/// Builds a [`taskcast_core::StorageManager`] with the `spool`, `blobs` and
+/// `exports` directories under `storage.dataDir`, capped by `storage.limits`.
+fn storage_manager_from_config(
+    cfg: &taskcast_core::config::StorageConfig,
+) -> std::io::Result<taskcast_core::StorageManager> {
+    let data_dir = std::path::PathBuf::from(cfg.data_dir.as_deref().unwrap_or("."));
+    let mut opts = taskcast_core::StorageManagerOptions::default();
+    if let Some(ms) = cfg.sweep_interval_ms {
+        opts.sweep_interval = std::time::Duration::from_millis(ms.max(1));
+    }
+    let storage = taskcast_core::StorageManager::new(opts);
+    let limits = cfg.limits.clone().unwrap_or_default();
+    for (name, limit) in [
+        ("spool", limits.spool),
+        ("blobs", limits.blobs),
+        ("exports", limits.exports),
+    ] {
+        let limit = limit.unwrap_or_default();
+        storage.register(
+            name,
+            data_dir.join(name),
+            taskcast_core::DirectoryLimits {
+                max_bytes: limit.max_bytes,
+                max_age: limit.max_age_ms.map(std::time::Duration::from_millis),
+            },
+        )?;
+    }
+    Ok(storage)
+}
+
+#[cfg(test)]
+mod storage_tests {
+    use taskcast_core::config::{DirectoryLimitConfig, StorageConfig, StorageLimitsConfig};
+
+    use super::storage_manager_from_config;
+
+    #[test]
+    fn registers_directories_under_data_dir_with_limits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cfg = StorageConfig {
+            data_dir: Some(tmp.path().to_string_lossy().into_owned()),
+            limits: Some(StorageLimitsConfig {
+                spool: Some(DirectoryLimitConfig {
+                    max_bytes: Some(1024),
+                    max_age_ms: Some(60_000),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let storage = storage_manager_from_config(&cfg).unwrap();
+        assert_eq!(storage.directory_names(), ["blobs", "exports", "spool"]);
+        assert!(tmp.path().join("exports").is_dir());
+
+        let spool = storage.directory_usage("spool").unwrap();
+        assert_eq!(spool.max_bytes, Some(1024));
+        assert_eq!(spool.max_age_ms, Some(60_000));
+        assert_eq!(storage.directory_usage("blobs").unwrap().max_bytes, None);
+    }
+}
+
+/// Serves the tokio-console stream. Task-level data also needs the binary
+/// built with `RUSTFLAGS="--cfg tokio_unstable"`.
+#[cfg(feature = "runtime-diagnostics")]
+fn start_tokio_console(
+    config: &taskcast_core::config::TokioConsoleConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let addr: std::net::SocketAddr = config
+        .bind_address
+        .as_deref()
+        .unwrap_or(DEFAULT_TOKIO_CONSOLE_ADDRESS)
+        .parse()
+        .map_err(|e| format!("Invalid debug.tokioConsole.bindAddress: {e}"))?;
+    console_subscriber::ConsoleLayer::builder()
+        .server_addr(addr)
+        .init();
+    eprintln!("[taskcast] tokio-console listening on {addr}");
+    Ok(())
+}
+
+#[cfg(not(feature = "runtime-diagnostics"))]
+fn start_tokio_console(
+    _config: &taskcast_core::config::TokioConsoleConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!(
+        "[taskcast] debug.tokioConsole is enabled but this binary was built without the runtime-diagnostics feature"
+    );
+    Ok(())
+}
+
 pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
     let StartArgs {
         config,
@@ -136,9 +591,11 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
         db_path,
         playground,
         verbose,
+        readonly_port,
+        env,
     } = args;
 
-    let log_level = resolve_log_level(env_non_empty("TASKCAST_LOG_LEVEL").as_deref())?;
+    let log_level = resolve_log_level(non_empty(env.log_level).as_deref())?;
 
     // 1. Load config file
     let file_config =
@@ -148,16 +605,18 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
     let port = resolve_port(port, file_config.port);
 
     // 3. Resolve adapter URLs
-    let redis_url = std::env::var("TASKCAST_REDIS_URL").ok().or_else(|| {
-        file_config
-            .adapters
-            .as_ref()?
-            .broadcast
-            .as_ref()?
+    let broadcast_entry = file_config
+        .adapters
+        .as_ref()
+        .and_then(|adapters| adapters.broadcast.as_ref());
+    let kafka_broadcast = broadcast_entry.filter(|entry| entry.provider == "kafka");
+    let redis_url = env.stores.redis_url.or_else(|| {
+        broadcast_entry
+            .filter(|entry| entry.provider != "kafka")?
             .url
             .clone()
     });
-    let postgres_url = std::env::var("TASKCAST_POSTGRES_URL").ok().or_else(|| {
+    let postgres_url = env.stores.postgres_url.or_else(|| {
         file_config
             .adapters
             .as_ref()?
@@ -166,10 +625,65 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
             .url
             .clone()
     });
+    let long_term_entry = file_config
+        .adapters
+        .as_ref()
+        .and_then(|adapters| adapters.long_term_store.as_ref());
+    let postgres_replica = PostgresReplica {
+        url: non_empty(env.postgres_read_url).or_else(|| long_term_entry?.read_url.clone()),
+        read_after_write: long_term_entry
+            .and_then(|entry| entry.read_after_write_ms)
+            .map(std::time::Duration::from_millis),
+    };
+    let slow_query_threshold = long_term_entry
+        .and_then(|entry| entry.slow_query_ms)
+        .map(std::time::Duration::from_millis);
 
     // 4. Resolve storage mode: CLI flag > env var > auto-detect
-    let env_storage = std::env::var("TASKCAST_STORAGE").ok();
-    let storage_mode = resolve_storage_mode(&storage, env_storage.as_deref(), redis_url.is_some());
+    let storage_mode = resolve_storage_mode(
+        &storage,
+        env.stores.default_storage.as_deref(),
+        redis_url.is_some(),
+    );
+    let leader_election = resolve_leader_election(
+        file_config
+            .server
+            .as_ref()
+            .and_then(|server| server.leader_election.as_ref()),
+        storage_mode,
+    )?;
+
+    let payload_dedup = file_config
+        .storage
+        .as_ref()
+        .and_then(|storage| storage.dedup.as_ref()?.min_bytes)
+        .map(|min_bytes| taskcast_core::PayloadDedupConfig { min_bytes });
+    let integrity = taskcast_core::IntegrityMonitor::new(
+        file_config
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.corrupt_records)
+            .unwrap_or_default(),
+    );
+
+    let redis_value_format = resolve_redis_value_format(
+        file_config
+            .storage
+            .as_ref()
+            .and_then(|storage| storage.redis_value_format),
+    )?;
+
+    let redis_compression = file_config
+        .storage
+        .as_ref()
+        .and_then(|storage| storage.redis_compression.as_ref())
+        .map(|config| taskcast_core::ValueCompressionConfig {
+            algorithm: config.algorithm.unwrap_or_default(),
+            min_bytes: config
+                .min_bytes
+                .unwrap_or(taskcast_core::DEFAULT_COMPRESSION_MIN_BYTES),
+        })
+        .unwrap_or_default();
 
     // 5. Build adapters
     type StorageAdapters = (
@@ -177,14 +691,24 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
         Arc<dyn taskcast_core::ShortTermStore>,
         Option<Arc<dyn taskcast_core::LongTermStore>>,
     );
+    let mut runtime_info = taskcast_server::RuntimeInfo::default();
+    // Started with the engine and closed on shutdown for a final snapshot.
+    let mut memory_store = None;
+    let postgres_description = postgres_url
+        .as_deref()
+        .map(|url| taskcast_server::AdapterDescription::new("postgres").with_url(url));
     let (broadcast, short_term_store, long_term_store): StorageAdapters = match storage_mode {
         "sqlite" => {
             let adapters = taskcast_sqlite::create_sqlite_adapters(&db_path).await?;
             eprintln!("[taskcast] Using SQLite storage at {db_path}");
+            let sqlite = taskcast_server::AdapterDescription::new("sqlite").with_url(&db_path);
+            runtime_info.short_term_store = sqlite.clone();
+            runtime_info.long_term_store = Some(sqlite);
             (
                 Arc::new(taskcast_core::MemoryBroadcastProvider::new()),
-                Arc::new(adapters.short_term_store),
-                Some(Arc::new(adapters.long_term_store) as Arc<dyn taskcast_core::LongTermStore>),
+                Arc::new(adapters.short_term_store.with_integrity(integrity.clone())),
+                Some(Arc::new(adapters.long_term_store.with_integrity(integrity))
+                    as Arc<dyn taskcast_core::LongTermStore>),
             )
         }
         "redis" => {
@@ -196,21 +720,41 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
             let sub_conn = client.get_async_pubsub().await?;
             let store_conn = client.get_multiplexed_async_connection().await?;
 
-            let adapters =
-                taskcast_redis::create_redis_adapters(pub_conn, sub_conn, store_conn, None);
+            let broadcast = taskcast_redis::RedisBroadcastProvider::new(pub_conn, sub_conn, None);
+            let short_term_store = match payload_dedup {
+                Some(config) => taskcast_redis::RedisShortTermStore::with_payload_dedup(
+                    store_conn, None, config,
+                ),
+                None => taskcast_redis::RedisShortTermStore::new(store_conn, None),
+            }
+            .with_integrity(integrity.clone())
+            .with_value_format_version(redis_value_format)
+            .with_compression(redis_compression);
+            let redis = taskcast_server::AdapterDescription::new("redis")
+                .with_url(url)
+                .with_prefix(short_term_store.key_prefix());
+            runtime_info.broadcast = redis.clone();
+            runtime_info.short_term_store = redis;
+            runtime_info.long_term_store = postgres_description;
 
             let long_term_store: Option<Arc<dyn taskcast_core::LongTermStore>> =
                 if let Some(ref pg_url) = postgres_url {
-                    let pool = create_postgres_pool_with_auto_migrate(pg_url).await?;
-                    let store = taskcast_postgres::PostgresLongTermStore::new(pool);
-                    Some(Arc::new(store))
+                    let store = create_postgres_store(
+                        pg_url,
+                        &postgres_replica,
+                        payload_dedup,
+                        slow_query_threshold,
+                        env.auto_migrate.as_deref(),
+                    )
+                    .await?;
+                    Some(Arc::new(store.with_integrity(integrity)))
                 } else {
                     None
                 };
 
             (
-                Arc::new(adapters.broadcast),
-                Arc::new(adapters.short_term_store),
+                Arc::new(broadcast),
+                Arc::new(short_term_store),
                 long_term_store,
             )
         }
@@ -218,40 +762,219 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
             eprintln!(
                 "[taskcast] No TASKCAST_REDIS_URL configured \u{2014} using in-memory adapters"
             );
+            runtime_info.long_term_store = postgres_description;
 
             let long_term_store: Option<Arc<dyn taskcast_core::LongTermStore>> =
                 if let Some(ref pg_url) = postgres_url {
-                    let pool = create_postgres_pool_with_auto_migrate(pg_url).await?;
-                    let store = taskcast_postgres::PostgresLongTermStore::new(pool);
-                    Some(Arc::new(store))
+                    let store = create_postgres_store(
+                        pg_url,
+                        &postgres_replica,
+                        payload_dedup,
+                        slow_query_threshold,
+                        env.auto_migrate.as_deref(),
+                    )
+                    .await?;
+                    Some(Arc::new(store.with_integrity(integrity)))
                 } else {
                     None
                 };
 
+            let short_term_store = create_memory_short_term_store(
+                file_config
+                    .adapters
+                    .as_ref()
+                    .and_then(|adapters| adapters.short_term_store.as_ref()),
+            );
+            memory_store = Some(Arc::clone(&short_term_store));
+
             (
                 Arc::new(taskcast_core::MemoryBroadcastProvider::new()),
-                Arc::new(taskcast_core::MemoryShortTermStore::new()),
+                short_term_store,
                 long_term_store,
             )
         }
     };
 
+    // Kafka replaces whichever broadcast provider the storage mode picked.
+    let broadcast: Arc<dyn taskcast_core::BroadcastProvider> = match kafka_broadcast {
+        Some(entry) => {
+            let kafka = create_kafka_broadcast(entry)?;
+            eprintln!(
+                "[taskcast] Using Kafka broadcast on topic {} (consumer group {})",
+                kafka.topic(),
+                kafka.group_id()
+            );
+            runtime_info.broadcast = taskcast_server::AdapterDescription::new("kafka")
+                .with_url(entry.url.as_deref().unwrap_or_default());
+            Arc::new(kafka)
+        }
+        None => broadcast,
+    };
+
+    let mut sinks: Vec<Arc<dyn taskcast_core::EventSink>> = Vec::new();
+    // Started once the engine exists; closed on shutdown to flush its queue.
+    let mut replication_sink = None;
+    if let Some(kafka) = file_config.sinks.as_ref().and_then(|s| s.kafka.as_ref()) {
+        let sink = taskcast_kafka::KafkaEventSink::new(&kafka.brokers, kafka.topic.as_deref())?;
+        eprintln!(
+            "[taskcast] Streaming events to Kafka topic {}",
+            sink.topic()
+        );
+        sinks.push(Arc::new(sink));
+    }
+    if let Some(replication) = file_config
+        .sinks
+        .as_ref()
+        .and_then(|s| s.replication.as_ref())
+    {
+        let sink = Arc::new(taskcast_server::ReplicationSink::open(
+            taskcast_server::ReplicationSinkOptions::from_config(replication),
+        )?);
+        eprintln!(
+            "[taskcast] Replicating to {} ({} writes queued)",
+            replication.url,
+            sink.queued()
+        );
+        sinks.push(Arc::clone(&sink) as Arc<dyn taskcast_core::EventSink>);
+        replication_sink = Some(sink);
+    }
+
     // 6. Build engine (clone adapters for WorkerManager before moving into engine)
     let short_term_for_wm = Arc::clone(&short_term_store);
     let broadcast_for_wm = Arc::clone(&broadcast);
     let long_term_for_wm = long_term_store.clone();
 
-    let engine = Arc::new(taskcast_core::TaskEngine::new(
-        taskcast_core::TaskEngineOptions {
-            short_term_store,
-            broadcast,
-            long_term_store,
-            hooks: None,
-        },
+    let label_limits = file_config.event_labels.as_ref().map(|cfg| {
+        let defaults = taskcast_core::LabelLimits::default();
+        taskcast_core::LabelLimits {
+            max_labels: cfg.max_labels.unwrap_or(defaults.max_labels),
+            max_key_length: cfg.max_key_length.unwrap_or(defaults.max_key_length),
+            max_value_length: cfg.max_value_length.unwrap_or(defaults.max_value_length),
+        }
+    });
+
+    let read_routing = file_config.short_term.as_ref().map(|cfg| {
+        let defaults = taskcast_core::ReadRoutingConfig::default();
+        taskcast_core::ReadRoutingConfig {
+            preference: cfg.read_preference.unwrap_or(defaults.preference),
+            latency_threshold: cfg
+                .latency_threshold_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(defaults.latency_threshold),
+            ..defaults
+        }
+    });
+    let negative_cache_ttl = file_config
+        .short_term
+        .as_ref()
+        .and_then(|cfg| cfg.negative_cache.as_ref())
+        .filter(|cache| cache.enabled == Some(true))
+        .map(|cache| {
+            cache
+                .ttl_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(taskcast_core::DEFAULT_NEGATIVE_CACHE_TTL)
+        });
+
+    let mut engine = taskcast_core::TaskEngine::new(taskcast_core::TaskEngineOptions {
+        short_term_store,
+        broadcast,
+        long_term_store,
+        hooks: None,
+        label_limits,
+        sinks,
+        coalesce_reads: None,
+    });
+    if let Some(read_routing) = read_routing {
+        engine = engine.with_read_routing(read_routing);
+    }
+    if let Some(ttl) = negative_cache_ttl {
+        engine = engine.with_negative_cache(ttl);
+    }
+    if let Some(rate) = file_config
+        .long_term
+        .as_ref()
+        .and_then(|cfg| cfg.write_rate.as_ref())
+    {
+        let defaults = taskcast_core::WriteShapingConfig::default();
+        engine = engine.with_write_shaping(taskcast_core::WriteShapingConfig {
+            rows_per_sec: rate.rows_per_sec.unwrap_or(defaults.rows_per_sec),
+            burst: rate.burst.unwrap_or(defaults.burst),
+            queue_capacity: rate.queue_capacity.unwrap_or(defaults.queue_capacity),
+            overflow: rate.overflow.unwrap_or(defaults.overflow),
+        });
+    }
+    if let Some(ref events) = file_config.events {
+        let defaults = taskcast_core::EventDiffs::default();
+        engine = engine.with_event_diffs(taskcast_core::EventDiffs {
+            enabled: events.include_diffs.unwrap_or(defaults.enabled),
+            max_bytes: events.max_diff_bytes.unwrap_or(defaults.max_bytes),
+        });
+        engine = engine.with_event_types(taskcast_core::EventTypeRules {
+            max_length: events
+                .max_type_length
+                .unwrap_or(taskcast_core::DEFAULT_MAX_EVENT_TYPE_LENGTH),
+            normalization: events.normalize_types.unwrap_or_default(),
+        });
+    }
+    if let Some(ref limits) = file_config.limits {
+        let defaults = taskcast_core::SeriesLimits::default();
+        engine = engine.with_series_limits(taskcast_core::SeriesLimits {
+            max_per_task: limits.max_series_per_task.unwrap_or(defaults.max_per_task),
+            over_limit: limits.series_over_limit.unwrap_or(defaults.over_limit),
+        });
+    }
+    let discovery = file_config
+        .discovery
+        .as_ref()
+        .filter(|discovery| discovery.enabled == Some(true));
+    if let Some(discovery) = discovery {
+        engine = engine.with_discovery(taskcast_core::DiscoveryOptions {
+            sample_rate: discovery
+                .sample_rate
+                .unwrap_or(taskcast_core::DEFAULT_DISCOVERY_SAMPLE_RATE),
+            max_example_bytes: discovery
+                .max_example_bytes
+                .unwrap_or(taskcast_core::DEFAULT_MAX_EXAMPLE_BYTES),
+            redact_keys: discovery.redact_keys.clone().unwrap_or_default(),
+        });
+    }
+    if let Some(entry) = broadcast_entry {
+        engine = engine.with_broadcast_channels(taskcast_core::BroadcastChannels {
+            type_channels: entry.type_channels.unwrap_or(false),
+            legacy_channels: entry.legacy_channels.unwrap_or(false),
+        });
+    }
+    let flags = Arc::new(taskcast_core::FlagEvaluator::from_config(
+        file_config.flags.as_ref(),
     ));
+    engine = engine.with_flags(Arc::clone(&flags));
+    let engine = Arc::new(engine);
+    if let Some(sink) = replication_sink.as_ref() {
+        sink.start(engine.background());
+    }
+    if let Some(store) = memory_store.as_ref() {
+        store.start_flushing(engine.background());
+    }
+    reload_flags_on_hangup(config, flags);
+
+    // A standby holds its runners from the start, so none of them gets a
+    // first pass in. Under leader election every instance starts as standby.
+    let role = taskcast_core::RoleSwitch::new(
+        if leader_election.is_some() {
+            taskcast_core::ServerRole::Standby
+        } else {
+            file_config
+                .server
+                .as_ref()
+                .and_then(|server| server.role)
+                .unwrap_or_default()
+        },
+        engine.runners().clone(),
+    );
 
     // 7. Auth mode
-    let auth_mode_str = std::env::var("TASKCAST_AUTH_MODE").ok().or_else(|| {
+    let auth_mode_str = env.auth_mode.or_else(|| {
         file_config
             .auth
             .as_ref()
@@ -262,16 +985,16 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
         Some("jwt") => {
             let jwt_config = file_config.auth.as_ref().and_then(|a| a.jwt.as_ref());
 
-            let env_algorithm = env_non_empty("TASKCAST_JWT_ALGORITHM");
-            let algorithm = parse_jwt_algorithm(
-                env_algorithm
-                    .as_deref()
-                    .or_else(|| jwt_config.and_then(|j| j.algorithm.as_deref())),
-            );
+            let env_algorithm = non_empty(env.jwt_algorithm);
+            let algorithm = resolve_jwt_algorithm(
+                env_algorithm.as_deref(),
+                jwt_config.and_then(|j| j.algorithm),
+            )
+            .map_err(|e| format!("TASKCAST_JWT_ALGORITHM: {e}"))?;
 
-            let public_key = if let Some(key) = env_non_empty("TASKCAST_JWT_PUBLIC_KEY") {
+            let public_key = if let Some(key) = non_empty(env.jwt_public_key) {
                 Some(key)
-            } else if let Some(path) = env_non_empty("TASKCAST_JWT_PUBLIC_KEY_FILE") {
+            } else if let Some(path) = non_empty(env.jwt_public_key_file) {
                 Some(std::fs::read_to_string(path)?)
             } else if let Some(key) = jwt_config.and_then(|j| j.public_key.clone()) {
                 Some(key)
@@ -281,13 +1004,16 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
                 None
             };
 
+            let secret = non_empty(env.jwt_secret).or_else(|| jwt_config?.secret.clone());
+            algorithm.check_key_material(secret.is_some(), public_key.is_some())?;
+
             let jwt = taskcast_server::JwtConfig {
                 algorithm,
-                secret: env_non_empty("TASKCAST_JWT_SECRET").or_else(|| jwt_config?.secret.clone()),
+                secret,
                 public_key,
-                issuer: env_non_empty("TASKCAST_JWT_ISSUER")
+                issuer: non_empty(env.jwt_issuer)
                     .or_else(|| jwt_config.and_then(|j| j.issuer.clone())),
-                audience: env_non_empty("TASKCAST_JWT_AUDIENCE")
+                audience: non_empty(env.jwt_audience)
                     .or_else(|| jwt_config.and_then(|j| j.audience.clone())),
             };
             let trusted_services =
@@ -302,6 +1028,18 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
+        Some("apiKeys") => {
+            let keys = api_keys_from_config(
+                file_config
+                    .auth
+                    .as_ref()
+                    .and_then(|a| a.api_keys.as_deref()),
+            );
+            if keys.is_empty() {
+                return Err("auth.mode is apiKeys but auth.apiKeys is empty".into());
+            }
+            taskcast_server::AuthMode::ApiKeys(keys)
+        }
         _ => taskcast_server::AuthMode::None,
     };
 
@@ -376,14 +1114,147 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
     };
     let failure_logger: Arc<dyn taskcast_server::HttpFailureLogger> =
         Arc::new(taskcast_server::StderrHttpFailureLogger::new(log_level));
-    let (app, _ws_registry) = taskcast_server::create_app_with_failure_logger_and_routes(
+    let background = engine.background().clone();
+    let runners = engine.runners().clone();
+
+    // 10. Disk storage directories and their sweep
+    let storage = match file_config.storage.as_ref() {
+        Some(cfg) if cfg.data_dir.is_some() => {
+            let storage = Arc::new(storage_manager_from_config(cfg)?);
+            storage.start(&runners);
+            Some(storage)
+        }
+        _ => None,
+    };
+
+    // Retention for the GET /outcomes index
+    let mut outcome_trimmer =
+        taskcast_core::OutcomeTrimmer::new(taskcast_core::OutcomeTrimmerOptions {
+            engine: Arc::clone(&engine),
+            short_term_store: Arc::clone(engine.short_term_store()),
+            check_interval_ms: 60_000,
+            retention_ms: file_config
+                .outcomes
+                .as_ref()
+                .and_then(|cfg| cfg.retention_ms)
+                .unwrap_or(taskcast_core::DEFAULT_OUTCOME_RETENTION_MS),
+        });
+    outcome_trimmer.start();
+
+    // Event type conventions sampled for GET /conventions
+    let mut convention_flusher = discovery.map(|discovery| {
+        taskcast_core::ConventionFlusher::new(
+            Arc::clone(&engine),
+            discovery
+                .flush_interval_ms
+                .unwrap_or(taskcast_core::DEFAULT_CONVENTION_FLUSH_INTERVAL_MS),
+        )
+    });
+    if let Some(flusher) = convention_flusher.as_mut() {
+        flusher.start();
+    }
+
+    // Tasks created but never started
+    let mut abandoned_pending_sweeper = taskcast_core::AbandonedPendingSweeper::new(
+        taskcast_core::AbandonedPendingSweeperOptions {
+            engine: Arc::clone(&engine),
+            short_term_store: Arc::clone(engine.short_term_store()),
+            check_interval_ms: 60_000,
+            config: file_config
+                .cleanup
+                .as_ref()
+                .and_then(|cfg| cfg.abandoned_pending.clone()),
+        },
+    );
+    abandoned_pending_sweeper.start();
+
+    // Warnings ahead of task deadlines
+    let mut deadline_monitor = file_config
+        .timeouts
+        .as_ref()
+        .and_then(|cfg| Some((cfg.warnings.clone()?, cfg.check_interval_ms)))
+        .filter(|(warnings, _)| !warnings.is_empty())
+        .map(|(warnings, check_interval_ms)| {
+            let mut monitor =
+                taskcast_core::DeadlineMonitor::new(taskcast_core::DeadlineMonitorOptions {
+                    engine: Arc::clone(&engine),
+                    short_term_store: Arc::clone(engine.short_term_store()),
+                    check_interval_ms: check_interval_ms.unwrap_or(5_000),
+                    warnings,
+                });
+            monitor.start();
+            monitor
+        });
+
+    // 11. HTTP debug tap
+    let http_tap = file_config
+        .debug
+        .as_ref()
+        .and_then(|debug| debug.http_tap.as_ref())
+        .filter(|tap| tap.enabled == Some(true))
+        .map(|tap| {
+            eprintln!(
+                "[taskcast] HTTP tap enabled; request and response bodies are recorded in memory"
+            );
+            Arc::new(taskcast_server::HttpTap::new(tap))
+        });
+
+    // 12. Runtime diagnostics: tokio-console and the GET /admin/runtime sampler
+    let debug_config = file_config.debug.clone().unwrap_or_default();
+    if let Some(console) = debug_config
+        .tokio_console
+        .as_ref()
+        .filter(|console| console.enabled == Some(true))
+    {
+        start_tokio_console(console)?;
+    }
+    let runtime_sampler = Arc::new(taskcast_server::RuntimeSampler::new(Arc::clone(&engine)));
+    if let Some(sampler) = debug_config
+        .runtime_sampler
+        .as_ref()
+        .filter(|sampler| sampler.enabled == Some(true))
+    {
+        runtime_sampler.start(
+            sampler
+                .interval_ms
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(taskcast_server::DEFAULT_SAMPLE_INTERVAL),
+        );
+    }
+
+    // 13. Runtime info for GET /admin/info and the startup banner
+    runtime_info.config = Some(taskcast_core::config::TaskcastConfig {
+        port: Some(port),
+        ..file_config.clone()
+    });
+    let banner = runtime_info.banner(&auth_mode);
+
+    let templates = taskcast_server::TemplateRegistry::from_config(Some(&file_config));
+
+    let readonly_app = readonly_port.map(|_| {
+        taskcast_server::create_readonly_app(
+            Arc::clone(&engine),
+            auth_mode.clone(),
+            Some(&file_config),
+            taskcast_server::CorsConfig::default(),
+        )
+    });
+
+    let (app, _ws_registry) = taskcast_server::create_app_with_options(
         engine,
         auth_mode,
-        worker_manager,
-        None,
-        taskcast_server::CorsConfig::default(),
-        Arc::clone(&failure_logger),
-        additional_routes,
+        taskcast_server::AppOptions {
+            worker_manager,
+            failure_logger: Some(Arc::clone(&failure_logger)),
+            additional_routes,
+            storage: storage.clone(),
+            http_tap,
+            runtime_info: Some(Arc::new(runtime_info)),
+            templates: Some(Arc::new(templates)),
+            runtime_sampler: Some(Arc::clone(&runtime_sampler)),
+            role: Some(role.clone()),
+            ..Default::default()
+        },
     );
 
     // Apply verbose request logging middleware if --verbose
@@ -400,14 +1271,152 @@ pub async fn run(args: StartArgs) -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{port}")).await?;
+    // Bound before either server starts, so a taken port fails startup.
+    let readonly = match (readonly_port, readonly_app) {
+        (Some(readonly_port), Some(readonly_app)) => {
+            let listener =
+                tokio::net::TcpListener::bind(format!("0.0.0.0:{readonly_port}")).await?;
+            Some((readonly_port, listener, readonly_app))
+        }
+        _ => None,
+    };
     println!("[taskcast] Server started on http://localhost:{port}");
+    for line in banner {
+        println!("[taskcast] {line}");
+    }
+
+    // 14. Leader election, once the server can answer
+    let election = match (leader_election, redis_url.as_deref()) {
+        (Some(settings), Some(url)) => {
+            let conn = redis::Client::open(url)?
+                .get_multiplexed_async_connection()
+                .await?;
+            let lease =
+                taskcast_redis::RedisLeaderLease::new(conn, None, Some(settings.key.as_str()));
+            println!(
+                "[taskcast] Standby until this instance holds the leader lease {}",
+                lease.key()
+            );
+            Some(taskcast_core::LeaderElection::start(
+                role.clone(),
+                taskcast_core::LeaderElectionOptions {
+                    lease: Arc::new(lease),
+                    holder: leader_holder_id(),
+                    ttl: settings.ttl,
+                    renew_interval: settings.renew_interval,
+                },
+            ))
+        }
+        _ => {
+            if !role.is_active() {
+                println!("[taskcast] Standby: writes are refused until POST /admin/promote");
+            }
+            None
+        }
+    };
+
+    // The read-only server stops when the main one does.
+    let (readonly_stop, readonly_stopped) = tokio::sync::oneshot::channel::<()>();
+    let readonly_server = readonly.map(|(readonly_port, listener, readonly_app)| {
+        println!("[taskcast] Read-only API on http://localhost:{readonly_port}");
+        tokio::spawn(async move {
+            axum::serve(listener, readonly_app)
+                .with_graceful_shutdown(async {
+                    readonly_stopped.await.ok();
+                })
+                .await
+        })
+    });
     axum::serve(listener, app)
         .with_graceful_shutdown(shutdown_signal())
         .await?;
+    let _ = readonly_stop.send(());
+    // Hand the lease over now rather than when it lapses.
+    if let Some(election) = election {
+        election.stop().await;
+    }
+    if let Some(server) = readonly_server {
+        if let Ok(Err(err)) = server.await {
+            eprintln!("[taskcast] Read-only server failed: {err}");
+        }
+    }
+
+    // Let runner passes in progress finish before their loops stop.
+    if !runners.shutdown(SHUTDOWN_DRAIN_TIMEOUT).await {
+        eprintln!("[taskcast] Runner shutdown timed out; passes in progress were cancelled");
+    }
+    if let Some(storage) = storage {
+        storage.stop();
+    }
+    outcome_trimmer.stop();
+    if let Some(flusher) = convention_flusher.as_mut() {
+        flusher.stop();
+        if let Err(err) = flusher.tick().await {
+            eprintln!("[taskcast] Final event convention flush failed: {err}");
+        }
+    }
+    abandoned_pending_sweeper.stop();
+    if let Some(monitor) = deadline_monitor.as_mut() {
+        monitor.stop();
+    }
+    runtime_sampler.stop();
+    if let Some(sink) = replication_sink.as_ref() {
+        sink.close();
+    }
+    if let Some(store) = memory_store.as_ref() {
+        store.close();
+    }
+
+    // Let in-flight persistence, dispatch and replication finish, and the
+    // memory store write its final snapshot, before the stores go away.
+    if !background.drain(SHUTDOWN_DRAIN_TIMEOUT).await {
+        eprintln!(
+            "[taskcast] Shutdown drain timed out with {} background task(s) still running",
+            background.in_flight()
+        );
+    }
 
     Ok(())
 }
 
+/// Re-reads the config file on SIGHUP and swaps in its `flags`. A file that
+/// no longer loads leaves the current flags in place.
+#[cfg(unix)]
+fn reload_flags_on_hangup(
+    config_path: Option<String>,
+    flags: Arc<taskcast_core::FlagEvaluator>,
+) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(err) => {
+            eprintln!("[taskcast] Flags will not reload on SIGHUP: {err}");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match taskcast_core::config::load_config_file(config_path.as_deref()) {
+                Ok(config) => {
+                    flags.reload(config.flags.as_ref());
+                    println!("[taskcast] Reloaded feature flags");
+                }
+                Err(err) => eprintln!("[taskcast] Feature flags not reloaded: {err}"),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn reload_flags_on_hangup(
+    _config_path: Option<String>,
+    _flags: Arc<taskcast_core::FlagEvaluator>,
+) {
+}
+
+const SHUTDOWN_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
 async fn shutdown_signal() {
     let ctrl_c = tokio::signal::ctrl_c();
 
@@ -2,6 +2,7 @@ use clap::{Args, Subcommand};
 
 use crate::client::TaskcastClient;
 use crate::node_config::NodeConfigManager;
+use crate::output::{self, OutputFormat, Render, Table};
 
 #[derive(Args, Debug)]
 pub struct TasksArgs {
@@ -36,7 +37,7 @@ pub enum TasksCommands {
     },
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct TaskListItem {
     pub id: String,
     #[serde(rename = "type")]
@@ -46,12 +47,7 @@ pub struct TaskListItem {
     pub created_at: Option<f64>,
 }
 
-#[derive(serde::Deserialize, Debug)]
-struct ListTasksResponse {
-    tasks: Vec<TaskListItem>,
-}
-
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct TaskDetail {
     pub id: String,
     #[serde(rename = "type")]
@@ -62,7 +58,7 @@ pub struct TaskDetail {
     pub created_at: Option<f64>,
 }
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct EventItem {
     #[serde(rename = "type")]
     pub event_type: Option<String>,
@@ -72,6 +68,62 @@ pub struct EventItem {
     pub timestamp: Option<f64>,
 }
 
+/// Result of `taskcast tasks list`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskList {
+    pub tasks: Vec<TaskListItem>,
+}
+
+/// Result of `taskcast tasks inspect`: the task and its recent events.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskInspection {
+    pub task: TaskDetail,
+    /// The last [`INSPECT_EVENT_COUNT`] events, oldest first.
+    pub events: Vec<EventItem>,
+}
+
+/// How many of a task's most recent events `tasks inspect` shows.
+pub const INSPECT_EVENT_COUNT: usize = 5;
+
+impl Render for TaskList {
+    fn plain(&self) -> String {
+        format_task_list(&self.tasks)
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["ID", "TYPE", "STATUS", "CREATED"]);
+        for t in &self.tasks {
+            table.row([
+                t.id.clone(),
+                t.task_type.clone().unwrap_or_default(),
+                t.status.clone(),
+                format_timestamp(t.created_at),
+            ]);
+        }
+        table
+    }
+}
+
+impl Render for TaskInspection {
+    fn plain(&self) -> String {
+        format_task_inspect(&self.task, &self.events)
+    }
+
+    fn table(&self) -> Table {
+        let mut table = Table::new(["#", "TYPE", "LEVEL", "SERIES", "TIMESTAMP"]);
+        for (i, e) in self.events.iter().enumerate() {
+            table.row([
+                i.to_string(),
+                e.event_type.clone().unwrap_or_default(),
+                e.level.clone().unwrap_or_default(),
+                e.series_id.clone().unwrap_or_default(),
+                format_timestamp(e.timestamp),
+            ]);
+        }
+        table
+    }
+}
+
 pub fn format_task_list(tasks: &[TaskListItem]) -> String {
     if tasks.is_empty() {
         return "No tasks found.".to_string();
@@ -120,7 +172,7 @@ pub fn format_task_inspect(task: &TaskDetail, events: &[EventItem]) -> String {
         lines.push(String::new());
         lines.push("No events.".to_string());
     } else {
-        let last5: Vec<&EventItem> = events.iter().rev().take(5).collect::<Vec<_>>().into_iter().rev().collect();
+        let last5 = last_events(events);
         lines.push(String::new());
         lines.push(format!("Recent Events (last {}):", last5.len()));
         for (i, e) in last5.iter().enumerate() {
@@ -141,6 +193,11 @@ pub fn format_task_inspect(task: &TaskDetail, events: &[EventItem]) -> String {
     lines.join("\n")
 }
 
+/// The last [`INSPECT_EVENT_COUNT`] of `events`, oldest first.
+fn last_events(events: &[EventItem]) -> &[EventItem] {
+    &events[events.len().saturating_sub(INSPECT_EVENT_COUNT)..]
+}
+
 fn format_timestamp(ts: Option<f64>) -> String {
     match ts {
         Some(ms) if ms > 0.0 => {
@@ -155,24 +212,30 @@ fn format_timestamp(ts: Option<f64>) -> String {
     }
 }
 
-pub async fn run(args: TasksArgs) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(args: TasksArgs, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
     match args.command {
         TasksCommands::List {
             status,
             task_type,
             limit,
             node,
-        } => run_list(status, task_type, limit, node).await,
-        TasksCommands::Inspect { task_id, node } => run_inspect(task_id, node).await,
+        } => {
+            let client = client_for(node).await?;
+            let list = list_tasks(&client, status, task_type, limit).await?;
+            output::print(&list, format);
+        }
+        TasksCommands::Inspect { task_id, node } => {
+            let client = client_for(node).await?;
+            let inspection = inspect_task(&client, &task_id).await?;
+            output::print(&inspection, format);
+        }
     }
+    Ok(())
 }
 
-async fn run_list(
-    status: Option<String>,
-    task_type: Option<String>,
-    limit: u32,
+async fn client_for(
     node_name: Option<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<TaskcastClient, Box<dyn std::error::Error>> {
     let config_dir = dirs::home_dir()
         .expect("could not determine home directory")
         .join(".taskcast");
@@ -189,8 +252,16 @@ async fn run_list(
         mgr.get_current()
     };
 
-    let client = TaskcastClient::from_node(&node).await?;
+    TaskcastClient::from_node(&node).await
+}
 
+/// Fetches up to `limit` tasks, optionally filtered by status and type.
+pub async fn list_tasks(
+    client: &TaskcastClient,
+    status: Option<String>,
+    task_type: Option<String>,
+    limit: u32,
+) -> Result<TaskList, Box<dyn std::error::Error>> {
     let mut params = Vec::new();
     if let Some(ref s) = status {
         params.push(format!("status={}", s));
@@ -213,44 +284,16 @@ async fn run_list(
         return Err(format!("HTTP {} — {}", status_code.as_u16(), body).into());
     }
 
-    let body: ListTasksResponse = res.json().await?;
-    let tasks: Vec<&TaskListItem> = body.tasks.iter().take(limit as usize).collect();
-    let owned: Vec<TaskListItem> = tasks
-        .into_iter()
-        .map(|t| TaskListItem {
-            id: t.id.clone(),
-            task_type: t.task_type.clone(),
-            status: t.status.clone(),
-            created_at: t.created_at,
-        })
-        .collect();
-    println!("{}", format_task_list(&owned));
-
-    Ok(())
+    let mut body: TaskList = res.json().await?;
+    body.tasks.truncate(limit as usize);
+    Ok(body)
 }
 
-async fn run_inspect(
-    task_id: String,
-    node_name: Option<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let config_dir = dirs::home_dir()
-        .expect("could not determine home directory")
-        .join(".taskcast");
-    let mgr = NodeConfigManager::new(config_dir);
-
-    let node = if let Some(ref name) = node_name {
-        match mgr.get(name) {
-            Some(n) => n,
-            None => {
-                return Err(format!("Node \"{name}\" not found").into());
-            }
-        }
-    } else {
-        mgr.get_current()
-    };
-
-    let client = TaskcastClient::from_node(&node).await?;
-
+/// Fetches a task and its most recent events.
+pub async fn inspect_task(
+    client: &TaskcastClient,
+    task_id: &str,
+) -> Result<TaskInspection, Box<dyn std::error::Error>> {
     // Get task details
     let task_res = client.get(&format!("/tasks/{}", task_id)).await?;
     if !task_res.status().is_success() {
@@ -270,9 +313,10 @@ async fn run_inspect(
         vec![]
     };
 
-    println!("{}", format_task_inspect(&task, &events));
-
-    Ok(())
+    Ok(TaskInspection {
+        task,
+        events: last_events(&events).to_vec(),
+    })
 }
 
 #[cfg(test)]
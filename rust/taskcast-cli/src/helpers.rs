@@ -1,4 +1,4 @@
-use taskcast_core::config::AuthMode;
+use taskcast_core::config::{AuthMode, JwtAlgorithm, UnknownJwtAlgorithm};
 
 pub const DEFAULT_PORT: u16 = 3721;
 
@@ -28,18 +28,15 @@ pub fn resolve_storage_mode<'a>(
     }
 }
 
-/// Map a JWT algorithm string to jsonwebtoken::Algorithm. Defaults to HS256.
-pub fn parse_jwt_algorithm(alg: Option<&str>) -> jsonwebtoken::Algorithm {
-    match alg {
-        Some("RS256") => jsonwebtoken::Algorithm::RS256,
-        Some("RS384") => jsonwebtoken::Algorithm::RS384,
-        Some("RS512") => jsonwebtoken::Algorithm::RS512,
-        Some("ES256") => jsonwebtoken::Algorithm::ES256,
-        Some("ES384") => jsonwebtoken::Algorithm::ES384,
-        Some("PS256") => jsonwebtoken::Algorithm::PS256,
-        Some("PS384") => jsonwebtoken::Algorithm::PS384,
-        Some("PS512") => jsonwebtoken::Algorithm::PS512,
-        _ => jsonwebtoken::Algorithm::HS256,
+/// Resolve the JWT algorithm: env var > config file > HS256. An unknown env
+/// value is an error rather than a fallback to HS256.
+pub fn resolve_jwt_algorithm(
+    env_algorithm: Option<&str>,
+    config_algorithm: Option<JwtAlgorithm>,
+) -> Result<JwtAlgorithm, UnknownJwtAlgorithm> {
+    match env_algorithm {
+        Some(value) => value.parse(),
+        None => Ok(config_algorithm.unwrap_or_default()),
     }
 }
 
@@ -49,6 +46,7 @@ pub fn auth_mode_to_string(mode: &AuthMode) -> String {
         AuthMode::None => "none".to_string(),
         AuthMode::Jwt => "jwt".to_string(),
         AuthMode::Custom => "custom".to_string(),
+        AuthMode::ApiKeys => "apiKeys".to_string(),
     }
 }
 
@@ -107,7 +105,6 @@ pub fn parse_boolean_env(value: Option<&str>) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use jsonwebtoken::Algorithm;
     use taskcast_core::config::AuthMode;
 
     // ─── resolve_port ────────────────────────────────────────────────────────
@@ -181,90 +178,42 @@ mod tests {
         assert_eq!(resolve_storage_mode("memory", Some("other"), false), "memory");
     }
 
-    // ─── parse_jwt_algorithm ─────────────────────────────────────────────────
+    // ─── resolve_jwt_algorithm ───────────────────────────────────────────────
 
     #[test]
-    fn algorithm_hs256_is_default_for_none() {
-        assert_eq!(parse_jwt_algorithm(None), Algorithm::HS256);
+    fn algorithm_defaults_to_hs256() {
+        assert_eq!(resolve_jwt_algorithm(None, None), Ok(JwtAlgorithm::HS256));
     }
 
     #[test]
-    fn algorithm_hs256_is_default_for_unknown() {
-        assert_eq!(parse_jwt_algorithm(Some("UNKNOWN")), Algorithm::HS256);
-    }
-
-    #[test]
-    fn algorithm_hs256_not_explicitly_mapped() {
-        // HS256 is the catch-all default, not an explicit match arm
-        assert_eq!(parse_jwt_algorithm(Some("HS256")), Algorithm::HS256);
-    }
-
-    #[test]
-    fn algorithm_hs384_falls_to_default() {
-        // HS384 is not explicitly mapped, falls through to HS256 default
-        assert_eq!(parse_jwt_algorithm(Some("HS384")), Algorithm::HS256);
-    }
-
-    #[test]
-    fn algorithm_hs512_falls_to_default() {
-        // HS512 is not explicitly mapped, falls through to HS256 default
-        assert_eq!(parse_jwt_algorithm(Some("HS512")), Algorithm::HS256);
-    }
-
-    #[test]
-    fn algorithm_rs256() {
-        assert_eq!(parse_jwt_algorithm(Some("RS256")), Algorithm::RS256);
-    }
-
-    #[test]
-    fn algorithm_rs384() {
-        assert_eq!(parse_jwt_algorithm(Some("RS384")), Algorithm::RS384);
-    }
-
-    #[test]
-    fn algorithm_rs512() {
-        assert_eq!(parse_jwt_algorithm(Some("RS512")), Algorithm::RS512);
-    }
-
-    #[test]
-    fn algorithm_es256() {
-        assert_eq!(parse_jwt_algorithm(Some("ES256")), Algorithm::ES256);
-    }
-
-    #[test]
-    fn algorithm_es384() {
-        assert_eq!(parse_jwt_algorithm(Some("ES384")), Algorithm::ES384);
-    }
-
-    #[test]
-    fn algorithm_ps256() {
-        assert_eq!(parse_jwt_algorithm(Some("PS256")), Algorithm::PS256);
-    }
-
-    #[test]
-    fn algorithm_ps384() {
-        assert_eq!(parse_jwt_algorithm(Some("PS384")), Algorithm::PS384);
-    }
-
-    #[test]
-    fn algorithm_ps512() {
-        assert_eq!(parse_jwt_algorithm(Some("PS512")), Algorithm::PS512);
-    }
-
-    #[test]
-    fn algorithm_case_sensitive_lowercase_rejected() {
-        assert_eq!(parse_jwt_algorithm(Some("rs256")), Algorithm::HS256);
-        assert_eq!(parse_jwt_algorithm(Some("es256")), Algorithm::HS256);
+    fn algorithm_env_overrides_config() {
+        assert_eq!(
+            resolve_jwt_algorithm(Some("HS512"), Some(JwtAlgorithm::RS256)),
+            Ok(JwtAlgorithm::HS512)
+        );
+        assert_eq!(
+            resolve_jwt_algorithm(None, Some(JwtAlgorithm::ES384)),
+            Ok(JwtAlgorithm::ES384)
+        );
     }
 
     #[test]
-    fn algorithm_case_sensitive_mixed_case_rejected() {
-        assert_eq!(parse_jwt_algorithm(Some("Rs256")), Algorithm::HS256);
+    fn algorithm_env_parses_every_supported_name() {
+        for algorithm in JwtAlgorithm::ALL {
+            assert_eq!(
+                resolve_jwt_algorithm(Some(algorithm.as_str()), None),
+                Ok(algorithm)
+            );
+        }
     }
 
     #[test]
-    fn algorithm_empty_string_falls_to_default() {
-        assert_eq!(parse_jwt_algorithm(Some("")), Algorithm::HS256);
+    fn algorithm_unknown_env_value_is_an_error_not_hs256() {
+        for value in ["RS265", "rs256", "Rs256", ""] {
+            let err = resolve_jwt_algorithm(Some(value), Some(JwtAlgorithm::HS256)).unwrap_err();
+            assert_eq!(err, UnknownJwtAlgorithm(value.to_string()));
+            assert!(err.to_string().contains("expected one of HS256"), "{err}");
+        }
     }
 
     // ─── auth_mode_to_string ─────────────────────────────────────────────────
@@ -284,6 +233,11 @@ mod tests {
         assert_eq!(auth_mode_to_string(&AuthMode::Custom), "custom");
     }
 
+    #[test]
+    fn auth_mode_api_keys() {
+        assert_eq!(auth_mode_to_string(&AuthMode::ApiKeys), "apiKeys");
+    }
+
     // ─── resolve_postgres_url ────────────────────────────────────────────────
 
     #[test]
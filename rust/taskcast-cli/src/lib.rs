@@ -3,6 +3,7 @@ pub mod client;
 pub mod commands;
 pub mod helpers;
 pub mod node_config;
+pub mod output;
 pub mod tty;
 
 pub use auto_migrate::run_auto_migrate;
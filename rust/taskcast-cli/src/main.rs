@@ -3,9 +3,12 @@ mod client;
 mod commands;
 mod helpers;
 mod node_config;
+mod output;
 mod tty;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::output::OutputFormat;
 
 #[derive(Parser)]
 #[command(
@@ -14,6 +17,9 @@ use clap::{Parser, Subcommand};
     about = "Taskcast \u{2014} unified task tracking and streaming service"
 )]
 struct Cli {
+    /// Output format for commands that print data
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Plain)]
+    output: OutputFormat,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -41,6 +47,10 @@ enum Commands {
     Tail(commands::logs::TailArgs),
     /// Manage tasks on a Taskcast server
     Tasks(commands::tasks::TasksArgs),
+    /// Generate an API key and the hash to put in config
+    Keygen(commands::keygen::KeygenArgs),
+    /// Collect a redacted tar.gz of everything a support ticket about a task needs
+    DebugBundle(commands::debug_bundle::DebugBundleArgs),
     /// Manage Taskcast as a background system service
     Service(commands::service::ServiceArgs),
     /// Alias for `taskcast service start`
@@ -49,11 +59,17 @@ enum Commands {
     Stop,
     /// Alias for `taskcast service status`
     Status,
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate the script for
+        shell: clap_complete::Shell,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let format = cli.output;
 
     match cli.command {
         None => {
@@ -75,31 +91,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             commands::playground::run(args).await?;
         }
         Some(Commands::Node { command }) => {
-            if let Err(e) = commands::node::run(command) {
+            if let Err(e) = commands::node::run(command, format) {
                 eprintln!("{e}");
                 std::process::exit(1);
             }
         }
         Some(Commands::Doctor(args)) => {
-            commands::doctor::run(args).await?;
+            commands::doctor::run(args, format).await?;
         }
         Some(Commands::Ping(args)) => {
-            if let Err(e) = commands::ping::run(args).await {
+            if let Err(e) = commands::ping::run(args, format).await {
                 eprintln!("{e}");
                 std::process::exit(1);
             }
         }
         Some(Commands::Logs(args)) => {
-            commands::logs::run_logs(args).await?;
+            commands::logs::run_logs(args, format).await?;
         }
         Some(Commands::Tail(args)) => {
-            commands::logs::run_tail(args).await?;
+            commands::logs::run_tail(args, format).await?;
         }
         Some(Commands::Tasks(args)) => {
-            commands::tasks::run(args).await?;
+            commands::tasks::run(args, format).await?;
+        }
+        Some(Commands::Keygen(args)) => {
+            commands::keygen::run(args, format)?;
+        }
+        Some(Commands::DebugBundle(args)) => {
+            commands::debug_bundle::run(args).await?;
         }
         Some(Commands::Service(args)) => {
-            commands::service::run(args).await?;
+            commands::service::run(args, format).await?;
         }
         Some(Commands::Daemon) => {
             commands::service::run_start().await?;
@@ -108,13 +130,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             commands::service::run_stop()?;
         }
         Some(Commands::Status) => {
-            commands::service::run_status().await?;
+            commands::service::run_status(format).await?;
+        }
+        Some(Commands::Completions { shell }) => {
+            print_completions(shell, &mut std::io::stdout());
         }
     }
 
     Ok(())
 }
 
+fn print_completions(shell: clap_complete::Shell, out: &mut dyn std::io::Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "taskcast", out);
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -175,31 +204,6 @@ mod tests {
         assert_eq!(resolve_storage_mode("memory", Some("other"), false), "memory");
     }
 
-    // ─── parse_jwt_algorithm ─────────────────────────────────────────────────
-
-    #[test]
-    fn algorithm_maps_all_known_values() {
-        assert_eq!(parse_jwt_algorithm(Some("RS256")), jsonwebtoken::Algorithm::RS256);
-        assert_eq!(parse_jwt_algorithm(Some("RS384")), jsonwebtoken::Algorithm::RS384);
-        assert_eq!(parse_jwt_algorithm(Some("RS512")), jsonwebtoken::Algorithm::RS512);
-        assert_eq!(parse_jwt_algorithm(Some("ES256")), jsonwebtoken::Algorithm::ES256);
-        assert_eq!(parse_jwt_algorithm(Some("ES384")), jsonwebtoken::Algorithm::ES384);
-        assert_eq!(parse_jwt_algorithm(Some("PS256")), jsonwebtoken::Algorithm::PS256);
-        assert_eq!(parse_jwt_algorithm(Some("PS384")), jsonwebtoken::Algorithm::PS384);
-        assert_eq!(parse_jwt_algorithm(Some("PS512")), jsonwebtoken::Algorithm::PS512);
-    }
-
-    #[test]
-    fn algorithm_defaults_to_hs256_for_unknown() {
-        assert_eq!(parse_jwt_algorithm(Some("UNKNOWN")), jsonwebtoken::Algorithm::HS256);
-        assert_eq!(parse_jwt_algorithm(Some("HS384")), jsonwebtoken::Algorithm::HS256);
-    }
-
-    #[test]
-    fn algorithm_defaults_to_hs256_when_none() {
-        assert_eq!(parse_jwt_algorithm(None), jsonwebtoken::Algorithm::HS256);
-    }
-
     // ─── auth_mode_to_string ─────────────────────────────────────────────────
 
     #[test]
@@ -228,6 +232,15 @@ mod tests {
 
     // ─── CLI struct parsing ──────────────────────────────────────────────────
 
+    #[test]
+    fn cli_parses_keygen_with_name() {
+        let cli = Cli::parse_from(["taskcast", "keygen", "--name", "ci"]);
+        match cli.command.unwrap() {
+            Commands::Keygen(args) => assert_eq!(args.name.as_deref(), Some("ci")),
+            _ => panic!("expected Keygen command"),
+        }
+    }
+
     #[test]
     fn cli_default_command_is_start() {
         let cli = Cli::parse_from(["taskcast"]);
@@ -256,6 +269,18 @@ mod tests {
                 assert_eq!(args.storage, "memory");
                 assert_eq!(args.db_path, "./taskcast.db");
                 assert!(!args.playground);
+                assert!(args.readonly_port.is_none());
+            }
+            _ => panic!("expected Start command"),
+        }
+    }
+
+    #[test]
+    fn cli_start_with_readonly_port() {
+        let cli = Cli::parse_from(["taskcast", "start", "--readonly-port", "3722"]);
+        match cli.command.unwrap() {
+            Commands::Start(args) => {
+                assert_eq!(args.readonly_port, Some(3722));
             }
             _ => panic!("expected Start command"),
         }
@@ -444,6 +469,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_doctor_deep_and_repair_flags() {
+        let cli = Cli::parse_from(["taskcast", "doctor", "--deep", "--repair"]);
+        match cli.command.unwrap() {
+            Commands::Doctor(args) => {
+                assert!(args.deep);
+                assert!(args.repair);
+            }
+            _ => panic!("expected Doctor command"),
+        }
+        assert!(Cli::try_parse_from(["taskcast", "doctor", "--repair"]).is_err());
+    }
+
+    #[test]
+    fn cli_debug_bundle_flags() {
+        let cli = Cli::parse_from([
+            "taskcast", "debug-bundle", "--task", "t1", "--out", "t1.tgz", "--server", "--node",
+            "prod",
+        ]);
+        match cli.command.unwrap() {
+            Commands::DebugBundle(args) => {
+                assert_eq!(args.task, "t1");
+                assert_eq!(args.out.as_deref(), Some("t1.tgz"));
+                assert!(args.server);
+                assert_eq!(args.node.as_deref(), Some("prod"));
+            }
+            _ => panic!("expected DebugBundle command"),
+        }
+        assert!(Cli::try_parse_from(["taskcast", "debug-bundle"]).is_err());
+        assert!(
+            Cli::try_parse_from(["taskcast", "debug-bundle", "--task", "t1", "--node", "prod"])
+                .is_err()
+        );
+        assert!(Cli::try_parse_from([
+            "taskcast", "debug-bundle", "--task", "t1", "--server", "--storage", "sqlite",
+        ])
+        .is_err());
+    }
+
     // ─── Ping subcommand parsing ──────────────────────────────────────
 
     #[test]
@@ -674,6 +738,63 @@ mod tests {
         }
     }
 
+    // ─── Output format and completions ─────────────────────────────────
+
+    #[test]
+    fn cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn cli_output_defaults_to_plain() {
+        let cli = Cli::parse_from(["taskcast", "tasks", "list"]);
+        assert_eq!(cli.output, OutputFormat::Plain);
+    }
+
+    #[test]
+    fn cli_output_is_global() {
+        let cli = Cli::parse_from(["taskcast", "tasks", "list", "--output", "json"]);
+        assert_eq!(cli.output, OutputFormat::Json);
+        let cli = Cli::parse_from(["taskcast", "--output", "table", "node", "list"]);
+        assert_eq!(cli.output, OutputFormat::Table);
+        assert!(Cli::try_parse_from(["taskcast", "ping", "--output", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn completions_generate_for_every_shell() {
+        use clap::ValueEnum;
+
+        for shell in clap_complete::Shell::value_variants() {
+            let mut script = Vec::new();
+            print_completions(*shell, &mut script);
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains("taskcast"), "{shell}: {script}");
+            assert!(script.contains("completions"), "{shell}: {script}");
+        }
+    }
+
+    #[test]
+    fn completions_list_env_backed_flags() {
+        let mut script = Vec::new();
+        print_completions(clap_complete::Shell::Bash, &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("--redis-url"));
+        assert!(script.contains("--auth-mode"));
+        assert!(script.contains("--output"));
+    }
+
+    #[test]
+    fn cli_completions_parse() {
+        let cli = Cli::parse_from(["taskcast", "completions", "zsh"]);
+        match cli.command {
+            Some(Commands::Completions { shell }) => {
+                assert_eq!(shell, clap_complete::Shell::Zsh);
+            }
+            _ => panic!("expected Completions command"),
+        }
+        assert!(Cli::try_parse_from(["taskcast", "completions", "tcsh"]).is_err());
+    }
+
     // ─── resolve_postgres_url ────────────────────────────────────────────
 
     #[test]
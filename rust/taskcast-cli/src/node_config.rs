@@ -27,6 +27,21 @@ pub struct NodeListEntry {
     pub current: bool,
 }
 
+/// Serializes as `{ name, url, tokenType, current }`. The token itself is
+/// left out, so listings are safe to paste.
+impl Serialize for NodeListEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("NodeListEntry", 4)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("url", &self.entry.url)?;
+        state.serialize_field("tokenType", &self.entry.token_type)?;
+        state.serialize_field("current", &self.current)?;
+        state.end()
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct NodeConfigData {
     current: Option<String>,
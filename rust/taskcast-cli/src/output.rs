@@ -0,0 +1,141 @@
+//! Rendering of command results for `--output plain|json|table`.
+//!
+//! Commands that print data build a typed result implementing [`Render`] and
+//! hand it to [`print`]; they never format for the terminal themselves.
+
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Value of the global `--output` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-oriented text
+    #[default]
+    Plain,
+    /// The result as JSON
+    Json,
+    /// Aligned columns with a header row
+    Table,
+}
+
+/// A command result that can be printed in every [`OutputFormat`].
+pub trait Render: Serialize {
+    /// The human-oriented text for `--output plain`.
+    fn plain(&self) -> String;
+
+    /// The rows for `--output table`.
+    fn table(&self) -> Table;
+}
+
+/// Renders `value` in `format`, without a trailing newline.
+pub fn render<T: Render>(value: &T, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Plain => value.plain(),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(value).expect("command results serialize to JSON")
+        }
+        OutputFormat::Table => value.table().to_string(),
+    }
+}
+
+/// Prints `value` in `format` to stdout.
+pub fn print<T: Render>(value: &T, format: OutputFormat) {
+    println!("{}", render(value, format));
+}
+
+// ─── Table ───────────────────────────────────────────────────────────────────
+
+/// Column separator in rendered tables.
+const COLUMN_GAP: &str = "  ";
+
+/// A minimal table: a header row and data rows, left-aligned and padded to
+/// the widest cell of each column.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row. Missing cells render empty; extra cells are dropped.
+    pub fn row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut cells: Vec<String> = cells.into_iter().map(Into::into).collect();
+        cells.resize(self.headers.len(), String::new());
+        self.rows.push(cells);
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let lines = std::iter::once(&self.headers).chain(&self.rows);
+        for (i, cells) in lines.enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let mut line = String::new();
+            for (j, (cell, width)) in cells.iter().zip(&widths).enumerate() {
+                if j > 0 {
+                    line.push_str(COLUMN_GAP);
+                }
+                line.push_str(cell);
+                let pad = width - cell.chars().count();
+                line.extend(std::iter::repeat_n(' ', pad));
+            }
+            f.write_str(line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_pads_columns_to_the_widest_cell() {
+        let mut table = Table::new(["ID", "STATUS"]);
+        table.row(["t-1", "running"]);
+        table.row(["task-22", "ok"]);
+        assert_eq!(
+            table.to_string(),
+            "ID       STATUS\nt-1      running\ntask-22  ok"
+        );
+    }
+
+    #[test]
+    fn table_fills_missing_cells() {
+        let mut table = Table::new(["A", "B", "C"]);
+        table.row(["1"]);
+        table.row(["", "", "3"]);
+        assert_eq!(table.to_string(), "A  B  C\n1\n      3");
+    }
+
+    #[test]
+    fn table_without_rows_is_the_header() {
+        assert_eq!(Table::new(["NAME", "URL"]).to_string(), "NAME  URL");
+    }
+}
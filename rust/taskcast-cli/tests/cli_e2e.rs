@@ -24,6 +24,9 @@ fn make_server() -> (Arc<TaskEngine>, TestServer) {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (app, _) = create_app(
         Arc::clone(&engine),
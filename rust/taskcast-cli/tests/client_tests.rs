@@ -88,6 +88,23 @@ fn mock_app_admin_token_error() -> Router {
     )
 }
 
+/// Build a mock server whose `/admin/token` returns the structured `{code, message}` error body.
+fn mock_app_admin_token_structured_error() -> Router {
+    Router::new().route(
+        "/admin/token",
+        post(|| async {
+            (
+                axum::http::StatusCode::UNAUTHORIZED,
+                Json(json!({
+                    "code": "INVALID_ADMIN_TOKEN",
+                    "message": "Invalid admin token",
+                    "requestId": "req-1"
+                })),
+            )
+        }),
+    )
+}
+
 /// Build a mock server whose `/admin/token` returns a non-200 status with a non-JSON body.
 fn mock_app_admin_token_error_no_json() -> Router {
     Router::new().route(
@@ -133,6 +150,21 @@ async fn from_node_admin_token_error_returns_error_message() {
     );
 }
 
+#[tokio::test]
+async fn from_node_admin_token_error_reads_structured_message() {
+    let base_url = start_mock_server(mock_app_admin_token_structured_error()).await;
+
+    let node = NodeEntry {
+        url: base_url,
+        token: Some("bad-admin-token".to_string()),
+        token_type: Some(TokenType::Admin),
+    };
+
+    let result = TaskcastClient::from_node(&node).await;
+    let msg = result.err().unwrap().to_string();
+    assert_eq!(msg, "Invalid admin token");
+}
+
 #[tokio::test]
 async fn from_node_admin_token_error_without_json_body() {
     let base_url = start_mock_server(mock_app_admin_token_error_no_json()).await;
@@ -0,0 +1,124 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::net::TcpListener;
+
+use taskcast_core::config::TaskcastConfig;
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+use taskcast_cli::client::TaskcastClient;
+use taskcast_cli::commands::debug_bundle::{collect_local, fetch_bundle};
+use taskcast_cli::commands::start::StoreEnv;
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+async fn start_server(engine: Arc<TaskEngine>) -> String {
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr: SocketAddr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    format!("http://127.0.0.1:{}", addr.port())
+}
+
+// ─── --server ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn fetch_bundle_downloads_gzip_from_node() {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("remote".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let client = TaskcastClient::new(start_server(engine).await, None);
+
+    let archive = fetch_bundle(&client, "remote").await.unwrap();
+    assert_eq!(&archive[..2], &[0x1f, 0x8b]);
+
+    let err = fetch_bundle(&client, "missing").await.unwrap_err();
+    assert!(err.starts_with("HTTP 404"), "{err}");
+}
+
+// ─── Direct store access ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn collect_local_reads_sqlite_stores() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("taskcast.db");
+    let db_path = db_path.to_str().unwrap();
+    {
+        let adapters = taskcast_sqlite::create_sqlite_adapters(db_path)
+            .await
+            .unwrap();
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(adapters.short_term_store),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: Some(Arc::new(adapters.long_term_store)),
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("local".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    let stores = StoreEnv::default();
+    let bundle = collect_local(
+        &TaskcastConfig::default(),
+        "sqlite",
+        db_path,
+        &stores,
+        "local",
+    )
+    .await
+    .unwrap();
+    let names: Vec<&str> = bundle.file_names().collect();
+    assert!(names.contains(&"task/short-term.json"), "{names:?}");
+    assert!(names.contains(&"task/long-term.json"), "{names:?}");
+    let manifest = bundle.manifest();
+    let absent: Vec<&str> = manifest["absent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["name"].as_str().unwrap())
+        .collect();
+    assert!(absent.contains(&"http-tap.json"));
+    assert!(absent.contains(&"metrics.json"));
+}
+
+#[tokio::test]
+async fn collect_local_refuses_in_memory_stores() {
+    let err = collect_local(
+        &TaskcastConfig::default(),
+        "memory",
+        "unused.db",
+        &StoreEnv::default(),
+        "t1",
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("--server"), "{err}");
+}
@@ -1,16 +1,19 @@
 use std::net::SocketAddr;
-use std::sync::Mutex;
 
-use axum::{routing::get, Json, Router};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
 use serde_json::json;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
+use tokio::sync::Mutex;
 
 use taskcast_cli::commands::doctor::{run, DoctorArgs};
 use taskcast_cli::node_config::{NodeConfigManager, NodeEntry};
+use taskcast_cli::output::OutputFormat;
 
 /// Global lock to serialize tests that modify the HOME env var.
-static HOME_LOCK: Mutex<()> = Mutex::new(());
+static HOME_LOCK: Mutex<()> = Mutex::const_new(());
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
@@ -53,7 +56,7 @@ fn healthy_detail_app() -> Router {
 
 #[tokio::test]
 async fn run_success_default_node() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     let base_url = start_mock_server(healthy_detail_app()).await;
 
@@ -70,7 +73,15 @@ async fn run_success_default_node() {
     mgr.set_current("mock").unwrap();
 
     // run() should succeed without calling process::exit
-    let result = run(DoctorArgs { node: None }).await;
+    let result = run(
+        DoctorArgs {
+            node: None,
+            deep: false,
+            repair: false,
+        },
+        OutputFormat::Plain,
+    )
+    .await;
     assert!(result.is_ok());
 }
 
@@ -78,7 +89,7 @@ async fn run_success_default_node() {
 
 #[tokio::test]
 async fn run_success_named_node() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     let base_url = start_mock_server(healthy_detail_app()).await;
 
@@ -94,9 +105,14 @@ async fn run_success_named_node() {
     );
 
     // Explicitly name the node
-    let result = run(DoctorArgs {
-        node: Some("my-server".to_string()),
-    })
+    let result = run(
+        DoctorArgs {
+            node: Some("my-server".to_string()),
+            deep: false,
+            repair: false,
+        },
+        OutputFormat::Plain,
+    )
     .await;
     assert!(result.is_ok());
 }
@@ -105,12 +121,17 @@ async fn run_success_named_node() {
 
 #[tokio::test]
 async fn run_node_not_found_returns_error() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
     let _dir = setup_home();
 
-    let result = run(DoctorArgs {
-        node: Some("nonexistent".to_string()),
-    })
+    let result = run(
+        DoctorArgs {
+            node: Some("nonexistent".to_string()),
+            deep: false,
+            repair: false,
+        },
+        OutputFormat::Plain,
+    )
     .await;
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
@@ -124,7 +145,7 @@ async fn run_node_not_found_returns_error() {
 
 #[tokio::test]
 async fn run_server_unreachable_returns_error() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     let dir = setup_home();
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
@@ -138,7 +159,15 @@ async fn run_server_unreachable_returns_error() {
     );
     mgr.set_current("dead-server").unwrap();
 
-    let result = run(DoctorArgs { node: None }).await;
+    let result = run(
+        DoctorArgs {
+            node: None,
+            deep: false,
+            repair: false,
+        },
+        OutputFormat::Plain,
+    )
+    .await;
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -146,3 +175,80 @@ async fn run_server_unreachable_returns_error() {
         "error should indicate server failure, got: {err}"
     );
 }
+
+// ─── run() with --deep ──────────────────────────────────────────────────────
+
+fn consistency_app(expected_dry_run: bool) -> Router {
+    healthy_detail_app().route(
+        "/admin/consistency",
+        post(
+            move |headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer tok")
+                {
+                    return Err(StatusCode::UNAUTHORIZED);
+                }
+                assert_eq!(body["dryRun"], expected_dry_run);
+                Ok(Json(json!({
+                    "dryRun": expected_dry_run,
+                    "repair": !expected_dry_run,
+                    "shortTermTasksScanned": 2,
+                    "longTermTasksScanned": 5,
+                    "counts": {
+                        "orphanedEvents": 1,
+                        "danglingSeriesLatest": 0,
+                        "staleIndexCounters": 0,
+                        "missingFromLongTerm": 0
+                    },
+                    "repaired": 0,
+                    "repairFailures": 0,
+                    "findings": [{ "kind": "orphanedEvents", "taskId": "gone" }],
+                    "findingsTruncated": false,
+                    "skipped": []
+                })))
+            },
+        ),
+    )
+}
+
+#[tokio::test]
+async fn run_deep_scans_and_repairs_with_the_node_token() {
+    let _lock = HOME_LOCK.lock().await;
+
+    for (repair, token) in [(false, Some("tok")), (true, Some("tok")), (false, None)] {
+        let base_url = start_mock_server(consistency_app(!repair)).await;
+        let dir = setup_home();
+        let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
+        mgr.add(
+            "mock",
+            NodeEntry {
+                url: base_url,
+                token: token.map(str::to_string),
+                token_type: None,
+            },
+        );
+        mgr.set_current("mock").unwrap();
+
+        let result = run(
+            DoctorArgs {
+                node: None,
+                deep: true,
+                repair,
+            },
+            OutputFormat::Plain,
+        )
+        .await;
+        if token.is_some() {
+            assert!(
+                result.is_ok(),
+                "repair={repair}: {:?}",
+                result.err().map(|e| e.to_string())
+            );
+        } else {
+            let err = result.unwrap_err().to_string();
+            assert!(
+                err.contains("Consistency scan failed: HTTP 401"),
+                "got: {err}"
+            );
+        }
+    }
+}
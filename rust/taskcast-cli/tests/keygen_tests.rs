@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use axum_test::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use taskcast_cli::commands::keygen::{format_generated_key, generate_api_key};
+use taskcast_core::config::{parse_config, ConfigFormat};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, hash_api_key, ApiKeyEntry, AuthMode, CorsConfig, TaskIdAccess};
+
+#[test]
+fn generated_key_hashes_to_printed_hash() {
+    let generated = generate_api_key().unwrap();
+    assert!(generated.key.starts_with("tck_"));
+    assert_eq!(generated.key.len(), 4 + 64);
+    assert_eq!(hash_api_key(&generated.key), generated.key_hash);
+    let output = format_generated_key(&generated, "ci");
+    assert_eq!(output.matches(&generated.key).count(), 1);
+}
+
+#[test]
+fn generated_keys_are_unique() {
+    let first = generate_api_key().unwrap();
+    let second = generate_api_key().unwrap();
+    assert_ne!(first.key, second.key);
+    assert_ne!(first.key_hash, second.key_hash);
+}
+
+#[tokio::test]
+async fn printed_config_accepts_the_printed_key() {
+    let generated = generate_api_key().unwrap();
+    let output = format_generated_key(&generated, "ci");
+
+    // The output is "key", "hash", a blank line, then a pasteable YAML snippet.
+    let yaml: String = output
+        .lines()
+        .skip_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let config = parse_config(&yaml, ConfigFormat::Yaml).unwrap();
+    let keys = config.auth.unwrap().api_keys.unwrap();
+    assert_eq!(keys[0].name, "ci");
+    assert_eq!(keys[0].key_hash, generated.key_hash);
+
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let auth_mode = AuthMode::ApiKeys(vec![ApiKeyEntry {
+        name: keys[0].name.clone(),
+        key_hash: keys[0].key_hash.clone(),
+        task_ids: TaskIdAccess::All,
+        scope: keys[0].scope.clone(),
+    }]);
+    let (app, _) = create_app(engine, auth_mode, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+
+    let x_api_key = HeaderName::from_static("x-api-key");
+    server
+        .get("/tasks")
+        .add_header(
+            x_api_key.clone(),
+            HeaderValue::from_str(&generated.key).unwrap(),
+        )
+        .await
+        .assert_status_ok();
+    server
+        .get("/tasks")
+        .add_header(
+            x_api_key,
+            HeaderValue::from_str(&generated.key_hash).unwrap(),
+        )
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
@@ -17,6 +17,7 @@ use taskcast_server::{create_app, AuthMode, CorsConfig};
 
 use taskcast_cli::client::TaskcastClient;
 use taskcast_cli::commands::logs::{consume_sse, format_event};
+use taskcast_cli::output::OutputFormat;
 
 // ─── Helpers ──────────────────────────────────────────────────────────────────
 
@@ -26,6 +27,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -511,6 +515,9 @@ async fn format_event_with_real_server_data() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -572,6 +579,9 @@ async fn format_event_with_task_id_from_real_server() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -687,6 +697,9 @@ async fn consume_sse_from_real_server_task_events() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -759,6 +772,9 @@ async fn consume_sse_from_real_server_formats_events_correctly() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -774,6 +790,9 @@ async fn consume_sse_from_real_server_formats_events_correctly() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -871,6 +890,9 @@ async fn consume_sse_from_real_server_tail_format() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -951,6 +973,9 @@ async fn consume_sse_query_string_construction() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -966,6 +991,9 @@ async fn consume_sse_query_string_construction() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1283,6 +1311,9 @@ async fn run_logs_node_lookup_client_creation_and_sse() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1389,6 +1420,9 @@ async fn run_logs_with_types_and_levels_query_params() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1403,6 +1437,9 @@ async fn run_logs_with_types_and_levels_query_params() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1584,6 +1621,9 @@ async fn run_tail_node_lookup_and_url_construction() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1647,6 +1687,9 @@ async fn run_tail_callback_formats_with_task_id() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1754,6 +1797,9 @@ async fn run_logs_direct_call_completed_task() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1763,12 +1809,15 @@ async fn run_logs_direct_call_completed_task() {
         .await
         .unwrap();
 
-    let result = run_logs(LogsArgs {
-        task_id: task.id.clone(),
-        types: None,
-        levels: None,
-        node: None,
-    })
+    let result = run_logs(
+        LogsArgs {
+            task_id: task.id.clone(),
+            types: None,
+            levels: None,
+            node: None,
+        },
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_logs should succeed for completed task: {:?}", result.err());
@@ -1786,12 +1835,15 @@ async fn run_logs_node_not_found_returns_error() {
     let _mgr = NodeConfigManager::new(config_dir);
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run_logs(LogsArgs {
-        task_id: "some-task".to_string(),
-        types: None,
-        levels: None,
-        node: Some("nonexistent".to_string()),
-    })
+    let result = run_logs(
+        LogsArgs {
+            task_id: "some-task".to_string(),
+            types: None,
+            levels: None,
+            node: Some("nonexistent".to_string()),
+        },
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -1834,6 +1886,9 @@ async fn run_logs_with_filters() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1843,12 +1898,15 @@ async fn run_logs_with_filters() {
         .await
         .unwrap();
 
-    let result = run_logs(LogsArgs {
-        task_id: task.id.clone(),
-        types: Some("llm.*".to_string()),
-        levels: Some("info".to_string()),
-        node: None,
-    })
+    let result = run_logs(
+        LogsArgs {
+            task_id: task.id.clone(),
+            types: Some("llm.*".to_string()),
+            levels: Some("info".to_string()),
+            node: None,
+        },
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_logs with filters should succeed: {:?}", result.err());
@@ -1866,11 +1924,14 @@ async fn run_tail_node_not_found_returns_error() {
     let _mgr = NodeConfigManager::new(config_dir);
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run_tail(TailArgs {
-        types: None,
-        levels: None,
-        node: Some("nonexistent".to_string()),
-    })
+    let result = run_tail(
+        TailArgs {
+            types: None,
+            levels: None,
+            node: Some("nonexistent".to_string()),
+        },
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -1900,11 +1961,14 @@ async fn run_tail_direct_call_receives_events() {
     local.run_until(async move {
         // spawn_local for run_tail (its future is !Send due to FnMut callback)
         let handle = tokio::task::spawn_local(async move {
-            let _ = run_tail(TailArgs {
-                types: None,
-                levels: None,
-                node: None,
-            })
+            let _ = run_tail(
+                TailArgs {
+                    types: None,
+                    levels: None,
+                    node: None,
+                },
+                OutputFormat::Plain,
+            )
             .await;
         });
 
@@ -1931,6 +1995,9 @@ async fn run_tail_direct_call_receives_events() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1957,11 +2024,14 @@ async fn run_tail_direct_call_with_filters() {
     let engine_for_local = engine.clone();
     local.run_until(async move {
         let handle = tokio::task::spawn_local(async move {
-            let _ = run_tail(TailArgs {
-                types: Some("llm.*".to_string()),
-                levels: Some("info,warn".to_string()),
-                node: None,
-            })
+            let _ = run_tail(
+                TailArgs {
+                    types: Some("llm.*".to_string()),
+                    levels: Some("info,warn".to_string()),
+                    node: None,
+                },
+                OutputFormat::Plain,
+            )
             .await;
         });
 
@@ -1988,6 +2058,9 @@ async fn run_tail_direct_call_with_filters() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
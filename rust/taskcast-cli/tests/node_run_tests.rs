@@ -2,6 +2,7 @@ use std::sync::Mutex;
 
 use taskcast_cli::commands::node::{run, NodeCommands};
 use taskcast_cli::node_config::{NodeConfigManager, TokenType};
+use taskcast_cli::output::OutputFormat;
 use tempfile::TempDir;
 
 /// Global lock to serialize tests that modify the HOME env var.
@@ -19,12 +20,15 @@ fn setup_home() -> TempDir {
 fn run_add_without_token() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "local".to_string(),
-        url: "http://localhost:3721".to_string(),
-        token: None,
-        token_type: "jwt".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "local".to_string(),
+            url: "http://localhost:3721".to_string(),
+            token: None,
+            token_type: "jwt".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     // Verify via NodeConfigManager
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
@@ -40,12 +44,15 @@ fn run_add_without_token() {
 fn run_add_with_jwt_token() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "prod".to_string(),
-        url: "https://prod.example.com".to_string(),
-        token: Some("eyJ...".to_string()),
-        token_type: "jwt".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "prod".to_string(),
+            url: "https://prod.example.com".to_string(),
+            token: Some("eyJ...".to_string()),
+            token_type: "jwt".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
     let node = mgr.get("prod").unwrap();
@@ -60,12 +67,15 @@ fn run_add_with_jwt_token() {
 fn run_add_with_admin_token() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "staging".to_string(),
-        url: "https://staging.example.com".to_string(),
-        token: Some("admin_xxx".to_string()),
-        token_type: "admin".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "staging".to_string(),
+            url: "https://staging.example.com".to_string(),
+            token: Some("admin_xxx".to_string()),
+            token_type: "admin".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
     let node = mgr.get("staging").unwrap();
@@ -80,12 +90,15 @@ fn run_add_with_admin_token() {
 fn run_add_with_unknown_token_type_defaults_to_jwt() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "test".to_string(),
-        url: "http://localhost:3721".to_string(),
-        token: Some("tok".to_string()),
-        token_type: "bearer".to_string(), // unknown type
-    })
+    run(
+        NodeCommands::Add {
+            name: "test".to_string(),
+            url: "http://localhost:3721".to_string(),
+            token: Some("tok".to_string()),
+            token_type: "bearer".to_string(), // unknown type
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
     let node = mgr.get("test").unwrap();
@@ -99,7 +112,7 @@ fn run_list_empty() {
     let _lock = HOME_LOCK.lock().unwrap();
     let _dir = setup_home();
     // Should not panic, just prints "No nodes configured..."
-    run(NodeCommands::List).unwrap();
+    run(NodeCommands::List, OutputFormat::Plain).unwrap();
 }
 
 // ─── run(List) with nodes ──────────────────────────────────────────────────
@@ -108,15 +121,18 @@ fn run_list_empty() {
 fn run_list_with_nodes() {
     let _lock = HOME_LOCK.lock().unwrap();
     let _dir = setup_home();
-    run(NodeCommands::Add {
-        name: "test".to_string(),
-        url: "http://localhost:3721".to_string(),
-        token: None,
-        token_type: "jwt".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "test".to_string(),
+            url: "http://localhost:3721".to_string(),
+            token: None,
+            token_type: "jwt".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     // Should not panic, prints the node list
-    run(NodeCommands::List).unwrap();
+    run(NodeCommands::List, OutputFormat::Plain).unwrap();
 }
 
 // ─── run(Use) existing node ────────────────────────────────────────────────
@@ -125,16 +141,22 @@ fn run_list_with_nodes() {
 fn run_use_existing_node() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "test".to_string(),
-        url: "http://localhost:3721".to_string(),
-        token: None,
-        token_type: "jwt".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "test".to_string(),
+            url: "http://localhost:3721".to_string(),
+            token: None,
+            token_type: "jwt".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
-    run(NodeCommands::Use {
-        name: "test".to_string(),
-    })
+    run(
+        NodeCommands::Use {
+            name: "test".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     // Verify current node was set
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
@@ -148,16 +170,22 @@ fn run_use_existing_node() {
 fn run_remove_existing_node() {
     let _lock = HOME_LOCK.lock().unwrap();
     let dir = setup_home();
-    run(NodeCommands::Add {
-        name: "test".to_string(),
-        url: "http://localhost:3721".to_string(),
-        token: None,
-        token_type: "jwt".to_string(),
-    })
+    run(
+        NodeCommands::Add {
+            name: "test".to_string(),
+            url: "http://localhost:3721".to_string(),
+            token: None,
+            token_type: "jwt".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
-    run(NodeCommands::Remove {
-        name: "test".to_string(),
-    })
+    run(
+        NodeCommands::Remove {
+            name: "test".to_string(),
+        },
+        OutputFormat::Plain,
+    )
     .unwrap();
     // Verify node was removed
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
@@ -170,9 +198,12 @@ fn run_remove_existing_node() {
 fn run_remove_nonexistent_returns_error() {
     let _lock = HOME_LOCK.lock().unwrap();
     let _dir = setup_home();
-    let result = run(NodeCommands::Remove {
-        name: "ghost".to_string(),
-    });
+    let result = run(
+        NodeCommands::Remove {
+            name: "ghost".to_string(),
+        },
+        OutputFormat::Plain,
+    );
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -187,9 +218,12 @@ fn run_remove_nonexistent_returns_error() {
 fn run_use_nonexistent_returns_error() {
     let _lock = HOME_LOCK.lock().unwrap();
     let _dir = setup_home();
-    let result = run(NodeCommands::Use {
-        name: "ghost".to_string(),
-    });
+    let result = run(
+        NodeCommands::Use {
+            name: "ghost".to_string(),
+        },
+        OutputFormat::Plain,
+    );
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
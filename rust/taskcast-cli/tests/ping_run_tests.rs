@@ -1,16 +1,17 @@
 use std::net::SocketAddr;
-use std::sync::Mutex;
 
 use axum::{routing::get, Json, Router};
 use serde_json::json;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
+use tokio::sync::Mutex;
 
 use taskcast_cli::commands::ping::{run, PingArgs};
 use taskcast_cli::node_config::{NodeConfigManager, NodeEntry};
+use taskcast_cli::output::OutputFormat;
 
 /// Global lock to serialize tests that modify the HOME env var.
-static HOME_LOCK: Mutex<()> = Mutex::new(());
+static HOME_LOCK: Mutex<()> = Mutex::const_new(());
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
 
@@ -36,7 +37,7 @@ fn setup_home() -> TempDir {
 
 #[tokio::test]
 async fn run_success_default_node() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     // Start a mock server that returns 200 OK on /health
     let app = Router::new().route(
@@ -59,7 +60,7 @@ async fn run_success_default_node() {
     mgr.set_current("mock").unwrap();
 
     // run() should succeed
-    let result = run(PingArgs { node: None }).await;
+    let result = run(PingArgs { node: None }, OutputFormat::Plain).await;
     assert!(result.is_ok(), "run should succeed: {:?}", result.err());
 }
 
@@ -67,7 +68,7 @@ async fn run_success_default_node() {
 
 #[tokio::test]
 async fn run_success_named_node() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     let app = Router::new().route(
         "/health",
@@ -87,9 +88,12 @@ async fn run_success_named_node() {
     );
 
     // Explicitly name the node via the --node flag
-    let result = run(PingArgs {
-        node: Some("my-server".to_string()),
-    })
+    let result = run(
+        PingArgs {
+            node: Some("my-server".to_string()),
+        },
+        OutputFormat::Plain,
+    )
     .await;
     assert!(result.is_ok(), "run should succeed: {:?}", result.err());
 }
@@ -98,12 +102,15 @@ async fn run_success_named_node() {
 
 #[tokio::test]
 async fn run_node_not_found_returns_error() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
     let _dir = setup_home();
 
-    let result = run(PingArgs {
-        node: Some("nonexistent".to_string()),
-    })
+    let result = run(
+        PingArgs {
+            node: Some("nonexistent".to_string()),
+        },
+        OutputFormat::Plain,
+    )
     .await;
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
@@ -117,7 +124,7 @@ async fn run_node_not_found_returns_error() {
 
 #[tokio::test]
 async fn run_ping_failure_returns_error() {
-    let _lock = HOME_LOCK.lock().unwrap();
+    let _lock = HOME_LOCK.lock().await;
 
     let dir = setup_home();
     let mgr = NodeConfigManager::new(dir.path().join(".taskcast"));
@@ -131,7 +138,7 @@ async fn run_ping_failure_returns_error() {
     );
     mgr.set_current("bad-server").unwrap();
 
-    let result = run(PingArgs { node: None }).await;
+    let result = run(PingArgs { node: None }, OutputFormat::Plain).await;
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -445,6 +445,49 @@ async fn run_jwt_auth_accepts_rs256_public_key_file_from_env() {
     handle.abort();
 }
 
+#[tokio::test]
+async fn run_jwt_unknown_algorithm_fails_instead_of_falling_back_to_hs256() {
+    let _env = EnvGuard::with_removed(
+        &[
+            ("TASKCAST_AUTH_MODE", "jwt"),
+            ("TASKCAST_JWT_ALGORITHM", "RS265"),
+            ("TASKCAST_JWT_SECRET", "test-secret-key-for-testing"),
+        ],
+        &["TASKCAST_JWT_PUBLIC_KEY", "TASKCAST_JWT_PUBLIC_KEY_FILE"],
+    );
+
+    let err = taskcast_cli::commands::start::run(StartArgs {
+        port: find_available_port().await,
+        ..Default::default()
+    })
+    .await
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("\"RS265\""), "{message}");
+    assert!(message.contains("expected one of"), "{message}");
+}
+
+#[tokio::test]
+async fn run_jwt_rejects_hmac_algorithm_with_public_key() {
+    let _env = EnvGuard::with_removed(
+        &[
+            ("TASKCAST_AUTH_MODE", "jwt"),
+            ("TASKCAST_JWT_ALGORITHM", "HS256"),
+            ("TASKCAST_JWT_SECRET", "test-secret-key-for-testing"),
+            ("TASKCAST_JWT_PUBLIC_KEY", TEST_RSA_PUBLIC_KEY),
+        ],
+        &["TASKCAST_JWT_PUBLIC_KEY_FILE"],
+    );
+
+    let err = taskcast_cli::commands::start::run(StartArgs {
+        port: find_available_port().await,
+        ..Default::default()
+    })
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("HS256"), "{err}");
+}
+
 // ─── Env var resolution for TASKCAST_STORAGE ────────────────────────────────
 
 #[tokio::test]
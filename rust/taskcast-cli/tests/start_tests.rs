@@ -18,6 +18,7 @@ fn start_args_default_values() {
     assert!(args.config.is_none());
     assert!(!args.playground);
     assert!(!args.verbose);
+    assert!(args.readonly_port.is_none());
 }
 
 // ─── run() with memory backend ──────────────────────────────────────────────
@@ -182,6 +183,54 @@ async fn run_memory_backend_crud_operations() {
     handle.abort();
 }
 
+// ─── run() with a read-only port ────────────────────────────────────────────
+
+#[tokio::test]
+async fn run_with_readonly_port_serves_reads_on_both_ports() {
+    let port = find_available_port().await;
+    let readonly_port = find_available_port().await;
+    let handle = tokio::spawn(async move {
+        let _ = taskcast_cli::commands::start::run(StartArgs {
+            port,
+            readonly_port: Some(readonly_port),
+            ..Default::default()
+        })
+        .await;
+    });
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let client = reqwest::Client::new();
+
+    // Writes go through the main port...
+    let res = client
+        .post(format!("http://127.0.0.1:{port}/tasks"))
+        .json(&serde_json::json!({ "id": "mirrored" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 201);
+
+    // ...and are refused on the read-only one, which still serves reads.
+    let res = client
+        .post(format!("http://127.0.0.1:{readonly_port}/tasks"))
+        .json(&serde_json::json!({ "id": "refused" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 405);
+    let res = client
+        .get(format!("http://127.0.0.1:{readonly_port}/tasks/mirrored"))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+    let fetched: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(fetched["id"], "mirrored");
+
+    handle.abort();
+}
+
 // ─── run() with verbose + playground combined ───────────────────────────────
 
 #[tokio::test]
@@ -12,8 +12,10 @@ use taskcast_server::{create_app, AuthMode, CorsConfig};
 
 use taskcast_cli::client::TaskcastClient;
 use taskcast_cli::commands::tasks::{
-    format_task_inspect, format_task_list, EventItem, TaskDetail, TaskListItem,
+    format_task_inspect, format_task_list, inspect_task, list_tasks, EventItem, TaskDetail,
+    TaskInspection, TaskList, TaskListItem,
 };
+use taskcast_cli::output::{render, OutputFormat};
 
 // ─── Helpers ──────────────────────────────────────────────────────────────────
 
@@ -23,6 +25,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -398,6 +403,9 @@ async fn run_inspect_with_events() {
                 series_id: Some("response".to_string()),
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -489,6 +497,9 @@ async fn run_inspect_with_many_events_shows_last_5() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -566,6 +577,131 @@ async fn run_inspect_completed_task_with_result() {
     assert_eq!(task_body["result"]["tokens"], 42);
 }
 
+// ─── Output formats ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn list_tasks_json_output_parses_back() {
+    let engine = make_engine();
+    let base_url = start_server(engine.clone()).await;
+    let client = make_client(&base_url);
+    for task_type in ["llm.chat", "agent.step", "batch"] {
+        engine
+            .create_task(CreateTaskInput {
+                r#type: Some(task_type.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    let list = list_tasks(&client, None, None, 2).await.unwrap();
+    assert_eq!(list.tasks.len(), 2);
+
+    let json = render(&list, OutputFormat::Json);
+    let parsed: TaskList = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, list);
+    let raw: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(raw["tasks"][0]["createdAt"].is_number(), "{json}");
+}
+
+#[tokio::test]
+async fn inspect_task_json_output_parses_back() {
+    let engine = make_engine();
+    let base_url = start_server(engine.clone()).await;
+    let client = make_client(&base_url);
+    let task = engine
+        .create_task(CreateTaskInput {
+            r#type: Some("llm.chat".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..7 {
+        engine
+            .publish_event(
+                &task.id,
+                PublishEventInput {
+                    r#type: format!("step.{i}"),
+                    level: Level::Info,
+                    data: json!({}),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let inspection = inspect_task(&client, &task.id).await.unwrap();
+    assert_eq!(inspection.task.status, "running");
+    let types: Vec<_> = inspection
+        .events
+        .iter()
+        .map(|e| e.event_type.as_deref().unwrap())
+        .collect();
+    assert_eq!(types, ["step.2", "step.3", "step.4", "step.5", "step.6"]);
+
+    let json = render(&inspection, OutputFormat::Json);
+    let parsed: TaskInspection = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, inspection);
+}
+
+#[test]
+fn task_list_table_output_has_headers() {
+    let list = TaskList {
+        tasks: vec![TaskListItem {
+            id: "01JABCDEF".to_string(),
+            task_type: None,
+            status: "pending".to_string(),
+            created_at: Some(1741355401000.0),
+        }],
+    };
+    let table = render(&list, OutputFormat::Table);
+    let lines: Vec<&str> = table.lines().collect();
+    let headers: Vec<&str> = lines[0].split_whitespace().collect();
+    assert_eq!(headers, ["ID", "TYPE", "STATUS", "CREATED"]);
+    assert!(lines[1].starts_with("01JABCDEF  "), "{table}");
+    assert!(lines[1].contains("pending"), "{table}");
+
+    assert_eq!(
+        render(&list, OutputFormat::Plain),
+        format_task_list(&list.tasks)
+    );
+}
+
+#[test]
+fn task_inspection_table_output_has_headers() {
+    let inspection = TaskInspection {
+        task: TaskDetail {
+            id: "01JABCDEF".to_string(),
+            task_type: Some("llm.chat".to_string()),
+            status: "running".to_string(),
+            params: None,
+            created_at: None,
+        },
+        events: vec![EventItem {
+            event_type: Some("llm.delta".to_string()),
+            level: Some("info".to_string()),
+            series_id: Some("response".to_string()),
+            timestamp: None,
+        }],
+    };
+    let table = render(&inspection, OutputFormat::Table);
+    assert!(
+        table.starts_with("#  TYPE       LEVEL  SERIES    TIMESTAMP"),
+        "{table}"
+    );
+    assert!(table.contains("llm.delta"), "{table}");
+}
+
 // ─── run_list with combined filters ───────────────────────────────────────────
 
 #[tokio::test]
@@ -974,14 +1110,17 @@ async fn run_list_via_node_config_happy_path() {
         .unwrap();
 
     // Call the actual run function (dispatches to run_list)
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 20,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 20,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list should succeed: {:?}", result.err());
@@ -1010,14 +1149,17 @@ async fn run_list_with_status_filter_via_run() {
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: Some("running".to_string()),
-            task_type: None,
-            limit: 20,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: Some("running".to_string()),
+                task_type: None,
+                limit: 20,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list with status filter should succeed: {:?}", result.err());
@@ -1042,14 +1184,17 @@ async fn run_list_with_type_filter_via_run() {
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: Some("llm.chat".to_string()),
-            limit: 20,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: Some("llm.chat".to_string()),
+                limit: 20,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list with type filter should succeed: {:?}", result.err());
@@ -1076,14 +1221,17 @@ async fn run_list_with_limit_via_run() {
             .unwrap();
     }
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 2,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 2,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list with limit should succeed: {:?}", result.err());
@@ -1100,14 +1248,17 @@ async fn run_list_empty_server_via_run() {
     let temp_dir = setup_temp_home_with_node(&base_url, "default");
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 20,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 20,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list on empty server should succeed: {:?}", result.err());
@@ -1133,12 +1284,15 @@ async fn run_inspect_via_run_happy_path() {
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::Inspect {
-            task_id: task.id.clone(),
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::Inspect {
+                task_id: task.id.clone(),
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_inspect should succeed: {:?}", result.err());
@@ -1176,17 +1330,23 @@ async fn run_inspect_with_events_via_run() {
                 series_id: Some("response".to_string()),
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::Inspect {
-            task_id: task.id.clone(),
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::Inspect {
+                task_id: task.id.clone(),
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_inspect with events should succeed: {:?}", result.err());
@@ -1223,14 +1383,17 @@ async fn run_list_with_named_node_via_run() {
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 20,
-            node: Some("my-node".to_string()),
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 20,
+                node: Some("my-node".to_string()),
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_list with named node should succeed: {:?}", result.err());
@@ -1266,12 +1429,15 @@ async fn run_inspect_with_named_node_via_run() {
         .await
         .unwrap();
 
-    let result = run(TasksArgs {
-        command: TasksCommands::Inspect {
-            task_id: task.id.clone(),
-            node: Some("my-node".to_string()),
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::Inspect {
+                task_id: task.id.clone(),
+                node: Some("my-node".to_string()),
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_ok(), "run_inspect with named node should succeed: {:?}", result.err());
@@ -1352,6 +1518,9 @@ async fn run_inspect_node_config_lookup_path() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1491,14 +1660,17 @@ async fn run_list_node_not_found_returns_error() {
     let _mgr = NodeConfigManager::new(config_dir);
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 20,
-            node: Some("ghost".to_string()),
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 20,
+                node: Some("ghost".to_string()),
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -1521,12 +1693,15 @@ async fn run_inspect_node_not_found_returns_error() {
     let _mgr = NodeConfigManager::new(config_dir);
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run(TasksArgs {
-        command: TasksCommands::Inspect {
-            task_id: "some-task".to_string(),
-            node: Some("ghost".to_string()),
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::Inspect {
+                task_id: "some-task".to_string(),
+                node: Some("ghost".to_string()),
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -1560,14 +1735,17 @@ async fn run_list_http_error_returns_error() {
     let temp_dir = setup_temp_home_with_node(&mock_url, "mock-500");
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
-    let result = run(TasksArgs {
-        command: TasksCommands::List {
-            status: None,
-            task_type: None,
-            limit: 20,
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::List {
+                status: None,
+                task_type: None,
+                limit: 20,
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -1591,12 +1769,15 @@ async fn run_inspect_http_error_returns_error() {
     unsafe { std::env::set_var("HOME", temp_dir.path()); }
 
     // Inspect a nonexistent task — server returns 404
-    let result = run(TasksArgs {
-        command: TasksCommands::Inspect {
-            task_id: "nonexistent-task-id".to_string(),
-            node: None,
+    let result = run(
+        TasksArgs {
+            command: TasksCommands::Inspect {
+                task_id: "nonexistent-task-id".to_string(),
+                node: None,
+            },
         },
-    })
+        OutputFormat::Plain,
+    )
     .await;
 
     assert!(result.is_err());
@@ -0,0 +1,96 @@
+//! Per-event cost of fanning a status change out to subscribers that
+//! exclude status events, compared with subscribers that drop it themselves.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::json;
+use taskcast_core::{
+    matches_filter, BroadcastProvider, Level, MemoryBroadcastProvider, SubscribeFilter, TaskEvent,
+};
+use tokio::sync::mpsc::unbounded_channel;
+
+const CHANNEL: &str = "task.t1";
+
+fn status_event() -> TaskEvent {
+    TaskEvent {
+        id: "e1".to_string(),
+        task_id: "t1".to_string(),
+        index: 0,
+        timestamp: 0.0,
+        r#type: "taskcast:status".to_string(),
+        level: Level::Info,
+        data: json!({ "status": "paused" }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn status_fanout(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let filter = Arc::new(SubscribeFilter {
+        include_status: Some(false),
+        ..Default::default()
+    });
+    let mut group = c.benchmark_group("status_fanout");
+    for subscribers in [100, 1_000, 10_000] {
+        // Every subscriber receives a copy and filters it out on its side.
+        let provider = MemoryBroadcastProvider::new();
+        let mut receivers = Vec::new();
+        runtime.block_on(async {
+            for _ in 0..subscribers {
+                let (tx, rx) = unbounded_channel();
+                let filter = Arc::clone(&filter);
+                let _subscribed = provider
+                    .subscribe(
+                        CHANNEL,
+                        Box::new(move |event| {
+                            if matches_filter(&event, &filter) {
+                                let _ = tx.send(event);
+                            }
+                        }),
+                    )
+                    .await;
+                receivers.push(rx);
+            }
+        });
+        group.bench_with_input(
+            BenchmarkId::new("filtered_by_subscriber", subscribers),
+            &provider,
+            |b, provider| b.iter(|| runtime.block_on(provider.publish(CHANNEL, status_event()))),
+        );
+
+        // Subscribers registered as excluding status events are skipped.
+        let provider = MemoryBroadcastProvider::new();
+        runtime.block_on(async {
+            for _ in 0..subscribers {
+                let (tx, rx) = unbounded_channel();
+                let _subscribed = provider
+                    .subscribe_without_status(
+                        CHANNEL,
+                        Box::new(move |event| {
+                            let _ = tx.send(event);
+                        }),
+                    )
+                    .await;
+                receivers.push(rx);
+            }
+        });
+        group.bench_with_input(
+            BenchmarkId::new("status_excluding", subscribers),
+            &provider,
+            |b, provider| b.iter(|| runtime.block_on(provider.publish(CHANNEL, status_event()))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, status_fanout);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+//! Activation of tasks created with `scheduledFor`.
+//!
+//! Such a task starts out `scheduled` and the engine records its activation
+//! time in the short-term store. A [`SchedulerRunner`] on any instance later
+//! claims the due activation and moves the task to `pending`, emitting the
+//! usual status event. A task is activated no earlier than its
+//! `scheduledFor` and, while a runner is up, at most one check interval
+//! later.
+
+use std::sync::Arc;
+
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::engine::TaskEngine;
+use crate::runner::{runner_fn, RunnerTickReport};
+use crate::types::ShortTermStore;
+
+pub struct SchedulerRunnerOptions {
+    pub engine: Arc<TaskEngine>,
+    pub short_term_store: Arc<dyn ShortTermStore>,
+    /// How often to look for due activations, in milliseconds. This bounds
+    /// how late a scheduled task becomes pending. Default: 1_000.
+    pub check_interval_ms: u64,
+    /// How long a claimed activation stays reserved for this instance. A
+    /// claim that is not completed in time (e.g. the instance crashed) can be
+    /// taken by another instance. Default: 30_000.
+    pub claim_lease_ms: u64,
+}
+
+/// Moves `scheduled` tasks to `pending` once their time arrives.
+pub struct SchedulerRunner {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    check_interval_ms: u64,
+    claim_lease_ms: u64,
+    handle: Option<AbortHandle>,
+}
+
+impl SchedulerRunner {
+    pub fn new(opts: SchedulerRunnerOptions) -> Self {
+        Self {
+            engine: opts.engine,
+            short_term_store: opts.short_term_store,
+            check_interval_ms: opts.check_interval_ms.max(100),
+            claim_lease_ms: opts.claim_lease_ms,
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let engine = self.engine.clone();
+        let store = self.short_term_store.clone();
+        let interval_ms = self.check_interval_ms;
+        let lease_ms = self.claim_lease_ms;
+
+        let runner = runner_fn(
+            "activation.loop",
+            Duration::from_millis(interval_ms),
+            move || {
+                let engine = engine.clone();
+                let store = store.clone();
+                async move {
+                    Self::tick_inner(&engine, &store, lease_ms)
+                        .await
+                        .map(|activated| RunnerTickReport::processed(activated as u64))
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs one pass immediately. Returns the number of tasks activated.
+    pub async fn tick(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        Self::tick_inner(&self.engine, &self.short_term_store, self.claim_lease_ms).await
+    }
+
+    async fn tick_inner(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        lease_ms: u64,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let now = engine.clock().now_ms();
+        let mut activated = 0;
+        for task_id in store.list_due_activations(now).await? {
+            if !store.claim_activation(&task_id, now, lease_ms).await? {
+                continue;
+            }
+            // The transition removes the activation; a task that is gone or
+            // already left `scheduled` only needs its stale entry dropped.
+            match engine.activate_scheduled_task(&task_id).await? {
+                Some(_) => activated += 1,
+                None => store.delete_activation(&task_id).await?,
+            }
+        }
+        Ok(activated)
+    }
+}
@@ -1,5 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
+use serde_json::json;
+
+use crate::series::apply_json_patch;
 use crate::types::{
     SeriesLatestEntry, SeriesMode, Task, TaskArchive, TaskArchiveRestoreData, TaskEvent,
 };
@@ -202,6 +205,14 @@ fn build_series_latest(events: &[TaskEvent]) -> Vec<SeriesLatestEntry> {
                 ),
                 None => sanitize_task_archive_event(event.clone()),
             }
+        } else if series_mode == &SeriesMode::JsonPatch {
+            let document = index_by_key.get(&key).map_or_else(
+                || json!({}),
+                |&existing| latest[existing].event.data.clone(),
+            );
+            let mut patched = sanitize_task_archive_event(event.clone());
+            patched.data = apply_json_patch(&document, &event.data).unwrap_or(document);
+            patched
         } else {
             sanitize_task_archive_event(event.clone())
         };
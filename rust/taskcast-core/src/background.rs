@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::{AbortHandle, JoinHandle};
+
+use crate::types::{ErrorContext, TaskcastHooks};
+
+/// A spawned background operation handed to the supervision loop.
+struct Watched {
+    name: &'static str,
+    task_id: Option<String>,
+    handle: JoinHandle<()>,
+}
+
+struct Inner {
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    in_flight: Mutex<HashMap<&'static str, usize>>,
+    runs: Mutex<HashMap<&'static str, RunnerRun>>,
+    idle: Notify,
+    supervisor: OnceLock<mpsc::UnboundedSender<Watched>>,
+}
+
+/// Supervised spawner for fire-and-forget work (long-term persistence,
+/// dispatch, grace timers, stream forwarding).
+///
+/// Every operation is spawned as its own tokio task and its `JoinHandle` is
+/// watched by a single supervision loop, so a panic is reported through
+/// `TaskcastHooks::on_unhandled_error` (with `operation` set to the
+/// operation's name) instead of vanishing with a detached task. The loop is
+/// started lazily on the first `spawn`, so constructing an engine does not
+/// require a runtime.
+#[derive(Clone)]
+pub struct BackgroundTasks {
+    inner: Arc<Inner>,
+}
+
+impl BackgroundTasks {
+    pub fn new(hooks: Option<Arc<dyn TaskcastHooks>>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                hooks,
+                in_flight: Mutex::new(HashMap::new()),
+                runs: Mutex::new(HashMap::new()),
+                idle: Notify::new(),
+                supervisor: OnceLock::new(),
+            }),
+        }
+    }
+
+    /// Spawns `future` as the background operation `name`. `task_id` is
+    /// passed through to the error context if it panics. The returned handle
+    /// cancels the operation; a cancelled operation is not reported.
+    pub fn spawn<F>(&self, name: &'static str, task_id: Option<String>, future: F) -> AbortHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        *self
+            .inner
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert(0) += 1;
+        let handle = tokio::spawn(future);
+        let abort = handle.abort_handle();
+        // The receiver lives as long as any sender, so this cannot fail.
+        let _ = self.supervisor().send(Watched {
+            name,
+            task_id,
+            handle,
+        });
+        abort
+    }
+
+    /// Number of background operations that have been spawned and not yet
+    /// finished.
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.lock().unwrap().values().sum()
+    }
+
+    /// In-flight background operations, keyed by operation name.
+    pub fn in_flight_by_name(&self) -> HashMap<String, usize> {
+        self.inner
+            .in_flight
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, count)| (name.to_string(), *count))
+            .collect()
+    }
+
+    /// Records that one pass of the periodic runner `name` just finished.
+    pub fn record_run(&self, name: &'static str, ok: bool) {
+        let last_run_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time before UNIX epoch")
+            .as_millis() as f64;
+        self.inner
+            .runs
+            .lock()
+            .unwrap()
+            .insert(name, RunnerRun { last_run_at, ok });
+    }
+
+    /// The most recent pass recorded with [`record_run`](Self::record_run).
+    pub fn last_run(&self, name: &str) -> Option<RunnerRun> {
+        self.inner.runs.lock().unwrap().get(name).cloned()
+    }
+
+    /// Waits until no background operations are in flight, or `timeout`
+    /// elapses. Returns `true` if everything finished.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                let notified = self.inner.idle.notified();
+                tokio::pin!(notified);
+                // Register before checking so a completion in between wakes us.
+                notified.as_mut().enable();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+
+    fn supervisor(&self) -> &mpsc::UnboundedSender<Watched> {
+        self.inner.supervisor.get_or_init(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            tokio::spawn(supervise(Arc::downgrade(&self.inner), rx));
+            tx
+        })
+    }
+}
+
+/// When a periodic runner last completed a pass, and whether it succeeded.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerRun {
+    /// Unix epoch milliseconds.
+    pub last_run_at: f64,
+    pub ok: bool,
+}
+
+impl std::fmt::Debug for BackgroundTasks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackgroundTasks")
+            .field("in_flight", &self.in_flight())
+            .finish()
+    }
+}
+
+/// Awaits every watched handle, reporting panics and keeping the in-flight
+/// counts. Exits once all senders are gone and the last handle has finished.
+async fn supervise(inner: Weak<Inner>, mut rx: mpsc::UnboundedReceiver<Watched>) {
+    let mut running = FuturesUnordered::new();
+    let mut open = true;
+    loop {
+        tokio::select! {
+            watched = rx.recv(), if open => match watched {
+                Some(Watched { name, task_id, handle }) => {
+                    running.push(async move { (name, task_id, handle.await) });
+                }
+                None => open = false,
+            },
+            Some((name, task_id, result)) = running.next(), if !running.is_empty() => {
+                if let Some(inner) = inner.upgrade() {
+                    finish(&inner, name, task_id, result);
+                }
+            }
+            else => break,
+        }
+    }
+}
+
+fn finish(
+    inner: &Inner,
+    name: &'static str,
+    task_id: Option<String>,
+    result: Result<(), tokio::task::JoinError>,
+) {
+    if let Err(err) = result {
+        if err.is_panic() {
+            if let Some(ref hooks) = inner.hooks {
+                let context = ErrorContext {
+                    operation: name.to_string(),
+                    task_id,
+                };
+                hooks.on_unhandled_error(&err, &context);
+            }
+        }
+    }
+
+    let mut in_flight = inner.in_flight.lock().unwrap();
+    if let Some(count) = in_flight.get_mut(name) {
+        *count -= 1;
+        if *count == 0 {
+            in_flight.remove(name);
+        }
+    }
+    if in_flight.is_empty() {
+        inner.idle.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Default)]
+    struct RecordingHooks {
+        errors: Mutex<Vec<(String, ErrorContext)>>,
+    }
+
+    impl TaskcastHooks for RecordingHooks {
+        fn on_unhandled_error(
+            &self,
+            err: &(dyn std::error::Error + Send + Sync),
+            context: &ErrorContext,
+        ) {
+            self.errors
+                .lock()
+                .unwrap()
+                .push((err.to_string(), context.clone()));
+        }
+    }
+
+    #[tokio::test]
+    async fn counts_in_flight_until_finished() {
+        let background = BackgroundTasks::new(None);
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+
+        for _ in 0..3 {
+            let release = Arc::clone(&release);
+            background.spawn("test.wait", None, async move {
+                let _permit = release.acquire().await.unwrap();
+            });
+        }
+        background.spawn("test.noop", None, async {});
+
+        assert!(background.in_flight() >= 3);
+        assert_eq!(background.in_flight_by_name()["test.wait"], 3);
+
+        // Not drained while the waiters are blocked.
+        assert!(!background.drain(Duration::from_millis(20)).await);
+
+        release.add_permits(3);
+        assert!(background.drain(Duration::from_secs(5)).await);
+        assert_eq!(background.in_flight(), 0);
+        assert!(background.in_flight_by_name().is_empty());
+    }
+
+    #[tokio::test]
+    async fn drain_returns_immediately_when_idle() {
+        let background = BackgroundTasks::new(None);
+        assert!(background.drain(Duration::from_millis(1)).await);
+    }
+
+    #[tokio::test]
+    async fn panic_is_reported_with_operation_and_task_id() {
+        let hooks = Arc::new(RecordingHooks::default());
+        let background = BackgroundTasks::new(Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+
+        background.spawn("test.explode", Some("task-1".to_string()), async {
+            panic!("boom");
+        });
+        assert!(background.drain(Duration::from_secs(5)).await);
+
+        let errors = hooks.errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.contains("boom"), "got {}", errors[0].0);
+        assert_eq!(
+            errors[0].1,
+            ErrorContext {
+                operation: "test.explode".to_string(),
+                task_id: Some("task-1".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn completed_operations_are_not_reported() {
+        let hooks = Arc::new(RecordingHooks::default());
+        let background = BackgroundTasks::new(Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+        let ran = Arc::new(AtomicBool::new(false));
+
+        let flag = Arc::clone(&ran);
+        background.spawn("test.ok", None, async move {
+            flag.store(true, Ordering::SeqCst);
+        });
+        assert!(background.drain(Duration::from_secs(5)).await);
+
+        assert!(ran.load(Ordering::SeqCst));
+        assert!(hooks.errors.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn record_run_keeps_the_latest_pass() {
+        let background = BackgroundTasks::new(None);
+        assert_eq!(background.last_run("test.loop"), None);
+
+        background.record_run("test.loop", false);
+        let first = background.last_run("test.loop").unwrap();
+        assert!(!first.ok);
+
+        background.record_run("test.loop", true);
+        let second = background.last_run("test.loop").unwrap();
+        assert!(second.ok);
+        assert!(second.last_run_at >= first.last_run_at);
+    }
+
+    #[tokio::test]
+    async fn aborted_operations_are_not_reported() {
+        let hooks = Arc::new(RecordingHooks::default());
+        let background = BackgroundTasks::new(Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+
+        let handle = background.spawn("test.loop", None, std::future::pending());
+        assert_eq!(background.in_flight(), 1);
+        handle.abort();
+
+        assert!(background.drain(Duration::from_secs(5)).await);
+        assert!(hooks.errors.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+//! Retry delays, shared by webhook delivery, replication and task retry
+//! policies.
+//!
+//! [`compute_backoff`] is pure apart from the random source it is handed,
+//! so a seeded [`fastrand::Rng`] gives a repeatable delay sequence.
+
+use crate::types::{BackoffStrategy, RetryConfig, RetryJitter};
+
+/// The delay before retry `attempt` (1-based) under `retry`, in
+/// milliseconds.
+///
+/// Exponential delays are capped at `max_delay_ms`; fixed and linear ones
+/// are not. The delay is then jittered: `full` picks uniformly from
+/// `[0, delay]`, `equal` from `[delay / 2, delay]`.
+pub fn compute_backoff(retry: &RetryConfig, attempt: u32, rng: &mut fastrand::Rng) -> u64 {
+    let delay = strategy_delay_ms(&retry.backoff, retry.initial_delay_ms, attempt);
+    let delay = match retry.backoff {
+        BackoffStrategy::Exponential => delay.min(retry.max_delay_ms),
+        BackoffStrategy::Fixed | BackoffStrategy::Linear => delay,
+    };
+    match retry.jitter {
+        RetryJitter::None => delay,
+        RetryJitter::Full => rng.u64(0..=delay),
+        RetryJitter::Equal => {
+            let half = delay / 2;
+            half + rng.u64(0..=delay - half)
+        }
+    }
+}
+
+/// The uncapped, un-jittered delay before retry `attempt` (1-based).
+pub(crate) fn strategy_delay_ms(
+    backoff: &BackoffStrategy,
+    initial_delay_ms: u64,
+    attempt: u32,
+) -> u64 {
+    match backoff {
+        BackoffStrategy::Fixed => initial_delay_ms,
+        BackoffStrategy::Linear => initial_delay_ms.saturating_mul(attempt as u64),
+        BackoffStrategy::Exponential => initial_delay_ms
+            .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry(backoff: BackoffStrategy, jitter: RetryJitter) -> RetryConfig {
+        RetryConfig {
+            retries: 5,
+            backoff,
+            initial_delay_ms: 1000,
+            max_delay_ms: 10_000,
+            timeout_ms: 5000,
+            jitter,
+            max_elapsed_ms: None,
+        }
+    }
+
+    fn delays(retry: &RetryConfig, seed: u64) -> Vec<u64> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (1..=6).map(|attempt| compute_backoff(retry, attempt, &mut rng)).collect()
+    }
+
+    #[test]
+    fn strategies_without_jitter() {
+        let fixed = retry(BackoffStrategy::Fixed, RetryJitter::None);
+        let mut linear = retry(BackoffStrategy::Linear, RetryJitter::None);
+        linear.max_delay_ms = 3000;
+        let exponential = retry(BackoffStrategy::Exponential, RetryJitter::None);
+
+        assert_eq!(delays(&fixed, 1), [1000; 6]);
+        // Only exponential delays are capped.
+        assert_eq!(delays(&linear, 1), [1000, 2000, 3000, 4000, 5000, 6000]);
+        assert_eq!(
+            delays(&exponential, 1),
+            [1000, 2000, 4000, 8000, 10_000, 10_000]
+        );
+    }
+
+    #[test]
+    fn exponential_saturates_instead_of_overflowing() {
+        let exponential = retry(BackoffStrategy::Exponential, RetryJitter::None);
+        let mut rng = fastrand::Rng::with_seed(1);
+        assert_eq!(compute_backoff(&exponential, 200, &mut rng), 10_000);
+    }
+
+    #[test]
+    fn full_jitter_stays_within_zero_and_the_delay() {
+        for backoff in [
+            BackoffStrategy::Fixed,
+            BackoffStrategy::Linear,
+            BackoffStrategy::Exponential,
+        ] {
+            let plain = delays(&retry(backoff.clone(), RetryJitter::None), 7);
+            let jittered = delays(&retry(backoff, RetryJitter::Full), 7);
+            for (delay, bound) in jittered.iter().zip(&plain) {
+                assert!(delay <= bound, "{delay} > {bound}");
+            }
+            assert_ne!(jittered, plain);
+        }
+    }
+
+    #[test]
+    fn equal_jitter_keeps_at_least_half_the_delay() {
+        for backoff in [
+            BackoffStrategy::Fixed,
+            BackoffStrategy::Linear,
+            BackoffStrategy::Exponential,
+        ] {
+            let plain = delays(&retry(backoff.clone(), RetryJitter::None), 7);
+            let jittered = delays(&retry(backoff, RetryJitter::Equal), 7);
+            for (delay, bound) in jittered.iter().zip(&plain) {
+                assert!(*delay >= bound / 2 && delay <= bound, "{delay} vs {bound}");
+            }
+            assert_ne!(jittered, plain);
+        }
+    }
+
+    #[test]
+    fn seeded_jitter_repeats() {
+        let full = retry(BackoffStrategy::Exponential, RetryJitter::Full);
+        assert_eq!(delays(&full, 42), delays(&full, 42));
+        assert_ne!(delays(&full, 42), delays(&full, 43));
+    }
+
+    #[test]
+    fn zero_delay_jitters_to_zero() {
+        let mut zero = retry(BackoffStrategy::Fixed, RetryJitter::Equal);
+        zero.initial_delay_ms = 0;
+        assert_eq!(delays(&zero, 3), [0; 6]);
+        zero.jitter = RetryJitter::Full;
+        assert_eq!(delays(&zero, 3), [0; 6]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Broadcast channel names and the glob patterns subscribers match them with.
+//!
+//! The engine publishes each event to `task.{taskId}` and, when enabled,
+//! `type.{segment}`, where the segment is the event type up to its first
+//! `.` (`llm.delta` goes to `type.llm`). Pattern subscriptions use Redis
+//! `PSUBSCRIBE` glob syntax, which [`channel_matches`] implements for the
+//! providers that match locally, so a pattern selects the same channels
+//! whichever provider carries it.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::types::{BroadcastProvider, TaskEvent};
+
+/// How many recent event ids a [`deliver_once`] handler remembers. An event
+/// published to several channels reaches a subscriber on each of them
+/// within a few deliveries, far inside this window.
+const RECENT_EVENT_IDS: usize = 1024;
+
+/// Which channels the engine publishes to besides `task.{taskId}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastChannels {
+    /// Also publish to `type.{segment}`, for [`subscribe_by_type`]. Off by
+    /// default since it doubles publish volume.
+    ///
+    /// [`subscribe_by_type`]: crate::TaskEngine::subscribe_by_type
+    pub type_channels: bool,
+    /// Also publish to, and subscribe on, the bare task id that releases
+    /// before hierarchical channels used. Turn it on while a deployment runs
+    /// old and new instances side by side, and off once all are upgraded.
+    pub legacy_channels: bool,
+}
+
+impl BroadcastChannels {
+    /// Publishes `event` to its task's channel and the enabled extra ones.
+    pub(crate) async fn publish(
+        self,
+        broadcast: &dyn BroadcastProvider,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        broadcast
+            .publish(&task_channel(&event.task_id), event.clone())
+            .await?;
+        if self.legacy_channels {
+            broadcast.publish(&event.task_id, event.clone()).await?;
+        }
+        if self.type_channels {
+            broadcast
+                .publish(&type_channel(&event.r#type), event)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// The channel every event of `task_id` is published to.
+pub fn task_channel(task_id: &str) -> String {
+    format!("task.{task_id}")
+}
+
+/// The channel events of `event_type` are published to when type channels
+/// are enabled: `type.` followed by the type up to its first `.`.
+pub fn type_channel(event_type: &str) -> String {
+    let segment = event_type.split('.').next().unwrap_or_default();
+    format!("type.{segment}")
+}
+
+/// Whether `channel` matches the glob `pattern`, with the semantics of Redis
+/// `PSUBSCRIBE`: `*` matches any run of bytes, `?` any one byte, `[abc]`,
+/// `[a-z]` and `[^abc]` one byte from (or not from) a set, and `\` escapes
+/// the byte after it. Matching is byte-wise and case-sensitive.
+pub fn channel_matches(pattern: &str, channel: &str) -> bool {
+    let (pattern, channel) = (pattern.as_bytes(), channel.as_bytes());
+    let (mut p, mut c) = (0, 0);
+    // Where to resume after the last `*`: the pattern just past it, and the
+    // channel position it has absorbed up to.
+    let mut star: Option<(usize, usize)> = None;
+    while c < channel.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            star = Some((p, c));
+            continue;
+        }
+        if let Some(next) = match_one(pattern, p, channel[c]) {
+            p = next;
+            c += 1;
+            continue;
+        }
+        match star {
+            Some((resume, absorbed)) => {
+                p = resume;
+                c = absorbed + 1;
+                star = Some((resume, c));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|&b| b == b'*')
+}
+
+/// Matches the pattern element starting at `p` against `byte`, returning
+/// where the next element starts. `None` at the end of the pattern.
+fn match_one(pattern: &[u8], p: usize, byte: u8) -> Option<usize> {
+    match *pattern.get(p)? {
+        b'?' => Some(p + 1),
+        b'[' => match_set(pattern, p + 1, byte),
+        b'\\' if p + 1 < pattern.len() => (pattern[p + 1] == byte).then_some(p + 2),
+        literal => (literal == byte).then_some(p + 1),
+    }
+}
+
+/// Matches a `[...]` set whose contents start at `p`. Like Redis, an
+/// unterminated set runs to the end of the pattern.
+fn match_set(pattern: &[u8], mut p: usize, byte: u8) -> Option<usize> {
+    let negated = pattern.get(p) == Some(&b'^');
+    if negated {
+        p += 1;
+    }
+    let mut matched = false;
+    while p < pattern.len() {
+        match pattern[p] {
+            b'\\' if p + 1 < pattern.len() => {
+                matched |= pattern[p + 1] == byte;
+                p += 2;
+            }
+            b']' => {
+                p += 1;
+                break;
+            }
+            start if p + 2 < pattern.len() && pattern[p + 1] == b'-' => {
+                let end = pattern[p + 2];
+                matched |= (start.min(end)..=start.max(end)).contains(&byte);
+                p += 3;
+            }
+            member => {
+                matched |= member == byte;
+                p += 1;
+            }
+        }
+    }
+    (matched != negated).then_some(p)
+}
+
+/// Wraps `handler` so that an event delivered on several channels, such as a
+/// pattern matching both `task.*` and `type.*` names, reaches it once.
+pub(crate) fn deliver_once(
+    handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+) -> Box<dyn Fn(TaskEvent) + Send + Sync> {
+    let seen = Arc::new(Mutex::new(RecentIds::default()));
+    Box::new(move |event: TaskEvent| {
+        if seen.lock().unwrap().insert(&event.id) {
+            handler(event);
+        }
+    })
+}
+
+/// A bounded set of the most recently delivered event ids.
+#[derive(Default)]
+struct RecentIds {
+    order: VecDeque<String>,
+    ids: HashSet<String>,
+}
+
+impl RecentIds {
+    /// Records `id`, returning whether it was new.
+    fn insert(&mut self, id: &str) -> bool {
+        if !self.ids.insert(id.to_string()) {
+            return false;
+        }
+        self.order.push_back(id.to_string());
+        if self.order.len() > RECENT_EVENT_IDS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_task_and_type_channels() {
+        assert_eq!(task_channel("t1"), "task.t1");
+        assert_eq!(type_channel("llm.delta.token"), "type.llm");
+        assert_eq!(type_channel("log"), "type.log");
+        assert_eq!(type_channel("taskcast:status"), "type.taskcast:status");
+    }
+
+    #[test]
+    fn star_and_question_mark() {
+        assert!(channel_matches("task.*", "task.t1"));
+        assert!(channel_matches("task.*", "task."));
+        assert!(channel_matches("*", ""));
+        assert!(channel_matches("t*", "type.llm"));
+        assert!(channel_matches("*.llm", "type.llm"));
+        assert!(channel_matches("task.*.x*", "task.a.b.xyz"));
+        assert!(!channel_matches("task.*", "type.llm"));
+        assert!(channel_matches("task.t?", "task.t1"));
+        assert!(!channel_matches("task.t?", "task.t12"));
+        assert!(!channel_matches("task.t?", "task.t"));
+        assert!(!channel_matches("task.t1", "task.t1x"));
+    }
+
+    #[test]
+    fn sets_and_ranges() {
+        assert!(channel_matches("task.[ab]1", "task.b1"));
+        assert!(!channel_matches("task.[ab]1", "task.c1"));
+        assert!(channel_matches("task.[^ab]1", "task.c1"));
+        assert!(!channel_matches("task.[^ab]1", "task.a1"));
+        assert!(channel_matches("task.[0-9]", "task.7"));
+        assert!(channel_matches("task.[9-0]", "task.7"));
+        assert!(!channel_matches("task.[0-9]", "task.x"));
+        assert!(channel_matches("task.[-a]", "task.-"));
+        assert!(!channel_matches("task.[]", "task.x"));
+        // An unterminated set runs to the end of the pattern.
+        assert!(channel_matches("task.[xy", "task.y"));
+    }
+
+    #[test]
+    fn escapes() {
+        assert!(channel_matches(r"task.\*", "task.*"));
+        assert!(!channel_matches(r"task.\*", "task.t1"));
+        assert!(channel_matches(r"task.[\]]", "task.]"));
+        assert!(!channel_matches(r"task.\*", "task."));
+        // A trailing backslash matches itself.
+        assert!(channel_matches("task.\\", "task.\\"));
+    }
+
+    #[test]
+    fn deliver_once_drops_repeated_ids() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let handler = deliver_once(Box::new(move |event: TaskEvent| {
+            sink.lock().unwrap().push(event.id);
+        }));
+        let event = |id: &str| TaskEvent {
+            id: id.to_string(),
+            task_id: "t1".to_string(),
+            index: 0,
+            timestamp: 0.0,
+            r#type: "log".to_string(),
+            level: crate::types::Level::Info,
+            data: serde_json::Value::Null,
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            _accumulated_data: None,
+            replaces_event_id: None,
+        };
+        handler(event("a"));
+        handler(event("a"));
+        handler(event("b"));
+        handler(event("a"));
+        assert_eq!(*received.lock().unwrap(), ["a", "b"]);
+    }
+}
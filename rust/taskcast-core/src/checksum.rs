@@ -0,0 +1,208 @@
+//! Integrity checksums over event histories.
+//!
+//! The digest is SHA-256 over the events in order, each written as its
+//! canonical JSON followed by a single `\n`. Canonical JSON is the event's
+//! wire serialization with object keys sorted by their UTF-8 bytes at every
+//! depth, no insignificant whitespace, and strings and numbers formatted as
+//! `serde_json` formats them. [`HISTORY_CHECKSUM_VERSION`] names this scheme;
+//! any change to it gets a new version.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::types::TaskEvent;
+
+pub const HISTORY_CHECKSUM_VERSION: &str = "taskcast.history.v1";
+
+/// Summary of a sequence of returned events and their digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryChecksum {
+    pub version: String,
+    pub count: u64,
+    pub first_index: Option<u64>,
+    pub last_index: Option<u64>,
+    /// Lowercase hex.
+    pub sha256: String,
+}
+
+/// Incremental [`HistoryChecksum`] computation, for streams that emit events
+/// one at a time.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryChecksumBuilder {
+    hasher: Sha256,
+    count: u64,
+    first_index: Option<u64>,
+    last_index: Option<u64>,
+}
+
+impl HistoryChecksumBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_event(&mut self, event: &TaskEvent) {
+        let value = serde_json::to_value(event).expect("TaskEvent serializes to JSON");
+        self.push_value(event.index, &value);
+    }
+
+    /// Adds an already-serialized item, e.g. an SSE envelope, at `index`.
+    pub fn push_value(&mut self, index: u64, value: &Value) {
+        let mut canonical = String::new();
+        write_canonical(value, &mut canonical);
+        canonical.push('\n');
+        self.hasher.update(canonical.as_bytes());
+        self.count += 1;
+        self.first_index.get_or_insert(index);
+        self.last_index = Some(index);
+    }
+
+    pub fn finish(self) -> HistoryChecksum {
+        HistoryChecksum {
+            version: HISTORY_CHECKSUM_VERSION.to_string(),
+            count: self.count,
+            first_index: self.first_index,
+            last_index: self.last_index,
+            sha256: hex::encode(self.hasher.finalize()),
+        }
+    }
+}
+
+pub fn history_checksum(events: &[TaskEvent]) -> HistoryChecksum {
+    let mut builder = HistoryChecksumBuilder::new();
+    for event in events {
+        builder.push_event(event);
+    }
+    builder.finish()
+}
+
+/// Whether `events` are exactly the events `checksum` was computed over.
+/// Checksums with an unknown version never verify.
+pub fn verify_history_checksum(events: &[TaskEvent], checksum: &HistoryChecksum) -> bool {
+    checksum.version == HISTORY_CHECKSUM_VERSION && history_checksum(events) == *checksum
+}
+
+/// Serializes `value` as canonical JSON (sorted keys, no whitespace).
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Level;
+    use serde_json::json;
+
+    fn make_event(index: u64, data: Value) -> TaskEvent {
+        TaskEvent {
+            id: format!("evt-{index}"),
+            task_id: "t1".to_string(),
+            index,
+            timestamp: 1000.5 + index as f64,
+            r#type: "log".to_string(),
+            level: Level::Info,
+            data,
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    #[test]
+    fn canonical_json_sorts_keys_at_every_depth_without_whitespace() {
+        let value = json!({ "b": [1, { "z": null, "a": "x" }], "a": { "d": 1.5, "c": true } });
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":{"c":true,"d":1.5},"b":[1,{"a":"x","z":null}]}"#
+        );
+    }
+
+    #[test]
+    fn checksum_is_pinned_for_the_v1_scheme() {
+        // Changing this digest breaks every checksum clients have stored.
+        let checksum = history_checksum(&[make_event(0, json!({ "k": "v" }))]);
+        let line = concat!(
+            r#"{"data":{"k":"v"},"id":"evt-0","index":0,"level":"info","#,
+            r#""taskId":"t1","timestamp":1000.5,"type":"log"}"#,
+            "\n"
+        );
+        assert_eq!(
+            checksum.sha256,
+            hex::encode(Sha256::digest(line.as_bytes()))
+        );
+        assert_eq!(checksum.version, HISTORY_CHECKSUM_VERSION);
+    }
+
+    #[test]
+    fn checksum_reports_count_and_index_range() {
+        let events = vec![make_event(2, json!(1)), make_event(5, json!(2))];
+        let checksum = history_checksum(&events);
+        assert_eq!(checksum.count, 2);
+        assert_eq!(checksum.first_index, Some(2));
+        assert_eq!(checksum.last_index, Some(5));
+
+        let empty = history_checksum(&[]);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.first_index, None);
+        assert!(verify_history_checksum(&[], &empty));
+    }
+
+    #[test]
+    fn verify_rejects_changed_dropped_or_reordered_events() {
+        let events = vec![make_event(0, json!("a")), make_event(1, json!("b"))];
+        let checksum = history_checksum(&events);
+        assert!(verify_history_checksum(&events, &checksum));
+
+        let mut changed = events.clone();
+        changed[1].data = json!("c");
+        assert!(!verify_history_checksum(&changed, &checksum));
+        assert!(!verify_history_checksum(&events[..1], &checksum));
+        let reordered = vec![events[1].clone(), events[0].clone()];
+        assert!(!verify_history_checksum(&reordered, &checksum));
+    }
+
+    #[test]
+    fn verify_rejects_unknown_versions() {
+        let events = vec![make_event(0, json!(null))];
+        let mut checksum = history_checksum(&events);
+        checksum.version = "taskcast.history.v0".to_string();
+        assert!(!verify_history_checksum(&events, &checksum));
+    }
+}
@@ -1,6 +1,17 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::engine::{EngineError, TaskEngine, TransitionPayload};
 use crate::filter::matches_type;
+use crate::runner::{Runner, RunnerTickReport};
 use crate::state_machine::is_terminal;
-use crate::types::{CleanupRule, Task, TaskEvent};
+use crate::types::{
+    AbandonAction, AbandonedPendingConfig, CleanupRule, ShortTermStore, Task, TaskError, TaskEvent,
+    TaskFilter, TaskStatus,
+};
 
 /// Returns `true` if the given task matches the cleanup rule at time `now` (ms).
 ///
@@ -100,6 +111,223 @@ pub fn filter_events_for_cleanup(
         .collect()
 }
 
+// ─── Abandoned Pending Tasks ─────────────────────────────────────────────────
+
+/// Error code of tasks cancelled for staying `pending` too long.
+pub const ABANDONED_ERROR_CODE: &str = "ABANDONED";
+
+/// The abandonment settings that apply to `task`: its own
+/// `cleanup.abandonedPending`, else `global`.
+pub fn abandoned_pending_config<'a>(
+    task: &'a Task,
+    global: Option<&'a AbandonedPendingConfig>,
+) -> Option<&'a AbandonedPendingConfig> {
+    task.cleanup
+        .as_ref()
+        .and_then(|cleanup| cleanup.abandoned_pending.as_ref())
+        .or(global)
+}
+
+/// Returns `true` if `task` is still `pending` more than `config.after_ms`
+/// after it became pending at `now` (ms): after creation, or after its
+/// scheduled activation.
+pub fn is_abandoned(task: &Task, config: &AbandonedPendingConfig, now: f64) -> bool {
+    if task.status != TaskStatus::Pending {
+        return false;
+    }
+    let pending_since = task.scheduled_for.unwrap_or(task.created_at);
+    now - pending_since >= config.after_ms as f64
+}
+
+pub struct AbandonedPendingSweeperOptions {
+    pub engine: Arc<TaskEngine>,
+    pub short_term_store: Arc<dyn ShortTermStore>,
+    /// How often to sweep, in milliseconds. Default: 60_000.
+    pub check_interval_ms: u64,
+    /// Applies to tasks without their own `cleanup.abandonedPending`.
+    pub config: Option<AbandonedPendingConfig>,
+}
+
+/// What one sweep did with abandoned tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AbandonedPendingSweep {
+    pub cancelled: u64,
+    pub deleted: u64,
+}
+
+/// Cancels or deletes tasks that were created but never started.
+///
+/// Each instance may run one. A cancel is saved against the version the
+/// sweep read, so it loses to any concurrent change, including another
+/// instance's cancel; a task deleted twice is simply gone.
+pub struct AbandonedPendingSweeper {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    check_interval_ms: u64,
+    config: Option<AbandonedPendingConfig>,
+    handle: Option<AbortHandle>,
+}
+
+impl AbandonedPendingSweeper {
+    pub fn new(opts: AbandonedPendingSweeperOptions) -> Self {
+        Self {
+            engine: opts.engine,
+            short_term_store: opts.short_term_store,
+            check_interval_ms: opts.check_interval_ms.max(100),
+            config: opts.config,
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let runner = AbandonedPendingRunner {
+            engine: self.engine.clone(),
+            short_term_store: self.short_term_store.clone(),
+            interval: Duration::from_millis(self.check_interval_ms),
+            config: self.config.clone(),
+        };
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs one sweep immediately.
+    pub async fn tick(
+        &self,
+    ) -> Result<AbandonedPendingSweep, Box<dyn std::error::Error + Send + Sync>> {
+        Self::tick_inner(&self.engine, &self.short_term_store, self.config.as_ref()).await
+    }
+
+    async fn tick_inner(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        global: Option<&AbandonedPendingConfig>,
+    ) -> Result<AbandonedPendingSweep, Box<dyn std::error::Error + Send + Sync>> {
+        let now = engine.clock().now_ms();
+        // With a global window only tasks older than it are listed, so a
+        // shorter per-task window takes effect once the task passes the
+        // global one.
+        let pending = store
+            .list_tasks(TaskFilter {
+                status: Some(vec![TaskStatus::Pending]),
+                created_before: global.map(|config| now - config.after_ms as f64),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut sweep = AbandonedPendingSweep::default();
+        for task in pending {
+            let Some(config) = abandoned_pending_config(&task, global) else {
+                continue;
+            };
+            if !is_abandoned(&task, config, now) {
+                continue;
+            }
+            if config.include_with_events != Some(true) && store.event_count(&task.id).await? > 0 {
+                continue;
+            }
+            match config.action {
+                AbandonAction::Cancel => {
+                    if Self::cancel(engine, &task, config).await? {
+                        sweep.cancelled += 1;
+                    }
+                }
+                AbandonAction::Delete => {
+                    if Self::delete(engine, store, &task).await? {
+                        sweep.deleted += 1;
+                    }
+                }
+            }
+        }
+        Ok(sweep)
+    }
+
+    async fn cancel(
+        engine: &TaskEngine,
+        task: &Task,
+        config: &AbandonedPendingConfig,
+    ) -> Result<bool, EngineError> {
+        let payload = TransitionPayload {
+            error: Some(TaskError {
+                code: Some(ABANDONED_ERROR_CODE.to_string()),
+                message: format!("Task stayed pending for over {} ms", config.after_ms),
+                details: None,
+            }),
+            ..Default::default()
+        };
+        let result = engine
+            .transition_task_versioned(
+                &task.id,
+                TaskStatus::Cancelled,
+                Some(payload),
+                Some(task.version),
+            )
+            .await;
+        match result {
+            Ok(_) => Ok(true),
+            // Started, cancelled by another instance, or gone since the
+            // listing.
+            Err(
+                EngineError::ConcurrentModification(_)
+                | EngineError::InvalidTransition { .. }
+                | EngineError::TaskNotFound(_),
+            ) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn delete(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        task: &Task,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Leave the task alone if it changed since the listing.
+        let current = store.get_task(&task.id).await?;
+        if current.is_none_or(|current| current.version != task.version) {
+            return Ok(false);
+        }
+        let deleted = engine.delete_task(&task.id).await?;
+        if let Some(long_term) = engine.long_term_store() {
+            long_term.delete_task(&task.id).await?;
+        }
+        Ok(deleted)
+    }
+}
+
+/// The sweeper's pass as a [`Runner`], so the supervisor can report,
+/// pause and trigger it like any other loop.
+struct AbandonedPendingRunner {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    interval: Duration,
+    config: Option<AbandonedPendingConfig>,
+}
+
+#[async_trait]
+impl Runner for AbandonedPendingRunner {
+    fn name(&self) -> &'static str {
+        "cleanup.abandoned"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn tick(&self) -> Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>> {
+        let sweep = AbandonedPendingSweeper::tick_inner(
+            &self.engine,
+            &self.short_term_store,
+            self.config.as_ref(),
+        )
+        .await?;
+        Ok(RunnerTickReport::processed(sweep.cancelled + sweep.deleted))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +363,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         }
     }
 
@@ -161,6 +399,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }
     }
@@ -566,4 +806,56 @@ mod tests {
         let result = filter_events_for_cleanup(&events, &rule, 999.0, None);
         assert!(result.is_empty());
     }
+
+    // ─── Abandoned pending tasks ────────────────────────────────────────────
+
+    fn abandon_after(after_ms: u64) -> AbandonedPendingConfig {
+        AbandonedPendingConfig {
+            after_ms,
+            action: AbandonAction::Cancel,
+            include_with_events: None,
+        }
+    }
+
+    #[test]
+    fn pending_task_is_abandoned_once_past_the_window() {
+        let task = make_task(TaskStatus::Pending);
+        let config = abandon_after(500);
+        // created_at=1_000_000
+        assert!(!is_abandoned(&task, &config, 1_000_499.0));
+        assert!(is_abandoned(&task, &config, 1_000_500.0));
+        let running = make_task(TaskStatus::Running);
+        assert!(!is_abandoned(&running, &config, 9_999_999.0));
+    }
+
+    #[test]
+    fn scheduled_task_is_pending_from_its_activation() {
+        let task = Task {
+            scheduled_for: Some(1_500_000.0),
+            ..make_task(TaskStatus::Pending)
+        };
+        let config = abandon_after(500);
+        assert!(!is_abandoned(&task, &config, 1_500_499.0));
+        assert!(is_abandoned(&task, &config, 1_500_500.0));
+    }
+
+    #[test]
+    fn task_setting_overrides_the_global_one() {
+        let global = abandon_after(500);
+        let mut task = make_task(TaskStatus::Pending);
+        assert_eq!(
+            abandoned_pending_config(&task, Some(&global)),
+            Some(&global)
+        );
+        assert_eq!(abandoned_pending_config(&task, None), None);
+
+        task.cleanup = Some(crate::types::CleanupConfig {
+            rules: Vec::new(),
+            abandoned_pending: Some(abandon_after(5_000)),
+        });
+        assert_eq!(
+            abandoned_pending_config(&task, Some(&global)).map(|c| c.after_ms),
+            Some(5_000)
+        );
+    }
 }
@@ -0,0 +1,135 @@
+//! Single-flight coalescing of identical concurrent reads.
+//!
+//! When many subscribers open a stream on the same task at once, each one
+//! reads the task and its history. A [`ReadCoalescer`] lets the first caller
+//! for a key run the read while later callers wait for its result instead
+//! of issuing their own. Nothing is cached: the key is released as soon as
+//! the read completes, successfully or not.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use futures::future::{FutureExt, Shared};
+use tokio::sync::oneshot;
+
+use crate::engine::EngineError;
+
+type SharedRead<T> = Shared<oneshot::Receiver<Result<T, Arc<EngineError>>>>;
+
+pub(crate) struct ReadCoalescer<T: Clone> {
+    in_flight: Mutex<HashMap<String, SharedRead<T>>>,
+}
+
+impl<T: Clone> Default for ReadCoalescer<T> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> ReadCoalescer<T> {
+    /// Runs `read` for `key`, or waits for the result of a `read` already
+    /// in flight for it. Waiters see a failed read as an
+    /// [`EngineError::Store`] with the same message.
+    pub(crate) async fn run<F>(&self, key: &str, read: F) -> Result<T, EngineError>
+    where
+        F: Future<Output = Result<T, EngineError>>,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let joined = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(shared) => Some(shared.clone()),
+                None => {
+                    in_flight.insert(key.to_string(), receiver.shared());
+                    None
+                }
+            }
+        };
+        if let Some(shared) = joined {
+            return match shared.await {
+                Ok(result) => result.map_err(shared_error),
+                // The reading caller was dropped before it finished.
+                Err(_) => read.await,
+            };
+        }
+
+        let release = Release {
+            coalescer: self,
+            key,
+        };
+        let result = read.await;
+        drop(release);
+        match result {
+            Ok(value) => {
+                let _ = sender.send(Ok(value.clone()));
+                Ok(value)
+            }
+            Err(err) => {
+                let err = Arc::new(err);
+                let _ = sender.send(Err(Arc::clone(&err)));
+                // Unwraps when nobody was waiting.
+                Err(Arc::try_unwrap(err).unwrap_or_else(shared_error))
+            }
+        }
+    }
+}
+
+/// Releases the key when the read completes or its caller is dropped.
+struct Release<'a, T: Clone> {
+    coalescer: &'a ReadCoalescer<T>,
+    key: &'a str,
+}
+
+impl<T: Clone> Drop for Release<'_, T> {
+    fn drop(&mut self) {
+        self.coalescer.in_flight.lock().unwrap().remove(self.key);
+    }
+}
+
+/// An error from a read several callers shared.
+#[derive(Debug)]
+struct SharedReadError(Arc<EngineError>);
+
+impl std::fmt::Display for SharedReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for SharedReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+fn shared_error(err: Arc<EngineError>) -> EngineError {
+    EngineError::Store(Box::new(SharedReadError(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::poll;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn waiters_read_themselves_when_the_reader_is_dropped() {
+        let coalescer = ReadCoalescer::<u32>::default();
+        let mut stalled = Box::pin(coalescer.run("k", async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(1)
+        }));
+        assert!(poll!(stalled.as_mut()).is_pending());
+        let mut waiter = Box::pin(coalescer.run("k", async { Ok(2) }));
+        assert!(poll!(waiter.as_mut()).is_pending());
+
+        drop(stalled);
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+        assert_eq!(waiter.await.unwrap(), 2);
+    }
+}
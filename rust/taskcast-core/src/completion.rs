@@ -0,0 +1,196 @@
+//! Umbrella tasks: tasks that end on their own when their children end.
+//!
+//! A task created with a [`CompletionPolicy`] other than `manual` awaits the
+//! children in its `childIds`. Tasks created with it as their `parentId` are
+//! appended to that list. Each time one of its children ends, the engine
+//! reads the parent and every child afresh and, once [`completion_verdict`]
+//! has one, transitions the parent: `completed` when every child completed,
+//! `failed` with a [`CHILD_FAILED`] error listing the others.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+
+use crate::state_machine::is_terminal;
+use crate::types::{CompletionMode, CompletionPolicy, TaskError, TaskStatus};
+
+/// Error code of a parent failed by its children.
+pub const CHILD_FAILED: &str = "CHILD_FAILED";
+/// Most children a completion policy can await.
+pub const MAX_COMPLETION_CHILDREN: usize = 1000;
+
+/// Why a completion policy or parent link cannot be used for the task
+/// `task_id`, or `None` when they can.
+pub fn completion_policy_violation(
+    task_id: &str,
+    parent_id: Option<&str>,
+    policy: Option<&CompletionPolicy>,
+) -> Option<String> {
+    if let Some(parent_id) = parent_id {
+        if parent_id.is_empty() {
+            return Some("Invalid parentId: it must not be empty.".to_string());
+        }
+        if parent_id == task_id {
+            return Some(format!(
+                "Invalid parentId: task {task_id} cannot be its own parent."
+            ));
+        }
+    }
+    let policy = policy?;
+    if policy.child_ids.len() > MAX_COMPLETION_CHILDREN {
+        return Some(format!(
+            "Invalid completionPolicy: at most {MAX_COMPLETION_CHILDREN} childIds are allowed."
+        ));
+    }
+    let mut seen = HashSet::new();
+    for child_id in &policy.child_ids {
+        if child_id.is_empty() {
+            return Some("Invalid completionPolicy: childIds must not be empty.".to_string());
+        }
+        if child_id == task_id || Some(child_id.as_str()) == parent_id {
+            return Some(format!(
+                "Invalid completionPolicy: {child_id} cannot be a child of {task_id}."
+            ));
+        }
+        if !seen.insert(child_id) {
+            return Some(format!(
+                "Invalid completionPolicy: {child_id} is listed twice in childIds."
+            ));
+        }
+    }
+    None
+}
+
+/// How the parent awaiting `children` under `policy` ends now: its status
+/// and, when it fails, its error. `None` while it must keep waiting.
+///
+/// Each child comes with its current status, `None` if it does not exist
+/// (yet), which counts as not finished. A child ending in any status but
+/// `completed` counts as failed.
+pub fn completion_verdict(
+    policy: &CompletionPolicy,
+    children: &[(&str, Option<TaskStatus>)],
+) -> Option<(TaskStatus, Option<TaskError>)> {
+    if policy.mode == CompletionMode::Manual || children.is_empty() {
+        return None;
+    }
+    let all_ended = children
+        .iter()
+        .all(|(_, status)| status.as_ref().is_some_and(is_terminal));
+    let failed: Vec<&str> = children
+        .iter()
+        .filter(|(_, status)| {
+            status
+                .as_ref()
+                .is_some_and(|s| is_terminal(s) && *s != TaskStatus::Completed)
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    let decided = match policy.mode {
+        CompletionMode::AnyChildFailure => all_ended || !failed.is_empty(),
+        _ => all_ended,
+    };
+    if !decided {
+        return None;
+    }
+    if failed.is_empty() {
+        return Some((TaskStatus::Completed, None));
+    }
+    Some((
+        TaskStatus::Failed,
+        Some(TaskError {
+            code: Some(CHILD_FAILED.to_string()),
+            message: format!(
+                "{} of {} child tasks did not complete",
+                failed.len(),
+                children.len()
+            ),
+            details: Some(HashMap::from([(
+                "failedChildIds".to_string(),
+                json!(failed),
+            )])),
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: CompletionMode) -> CompletionPolicy {
+        CompletionPolicy {
+            mode,
+            child_ids: vec!["a".to_string(), "b".to_string()],
+        }
+    }
+
+    #[test]
+    fn all_children_waits_for_every_child() {
+        let policy = policy(CompletionMode::AllChildren);
+        let verdict = completion_verdict(
+            &policy,
+            &[
+                ("a", Some(TaskStatus::Failed)),
+                ("b", Some(TaskStatus::Running)),
+            ],
+        );
+        assert_eq!(verdict, None);
+        let verdict =
+            completion_verdict(&policy, &[("a", Some(TaskStatus::Completed)), ("b", None)]);
+        assert_eq!(verdict, None);
+        let verdict = completion_verdict(
+            &policy,
+            &[
+                ("a", Some(TaskStatus::Completed)),
+                ("b", Some(TaskStatus::Completed)),
+            ],
+        );
+        assert_eq!(verdict, Some((TaskStatus::Completed, None)));
+    }
+
+    #[test]
+    fn any_child_failure_fails_at_the_first_failure() {
+        let policy = policy(CompletionMode::AnyChildFailure);
+        let (status, error) = completion_verdict(
+            &policy,
+            &[
+                ("a", Some(TaskStatus::Cancelled)),
+                ("b", Some(TaskStatus::Running)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(status, TaskStatus::Failed);
+        let error = error.unwrap();
+        assert_eq!(error.code.as_deref(), Some(CHILD_FAILED));
+        assert_eq!(error.details.unwrap()["failedChildIds"], json!(["a"]));
+    }
+
+    #[test]
+    fn manual_and_childless_policies_never_decide() {
+        let done = [("a", Some(TaskStatus::Completed))];
+        assert_eq!(
+            completion_verdict(&policy(CompletionMode::Manual), &done),
+            None
+        );
+        assert_eq!(
+            completion_verdict(&policy(CompletionMode::AllChildren), &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn violations_name_the_offending_field() {
+        let mut policy = policy(CompletionMode::AllChildren);
+        assert_eq!(completion_policy_violation("p", None, Some(&policy)), None);
+        assert!(completion_policy_violation("a", None, Some(&policy))
+            .unwrap()
+            .contains("cannot be a child"));
+        assert!(completion_policy_violation("p", Some("p"), None)
+            .unwrap()
+            .starts_with("Invalid parentId"));
+        policy.child_ids.push("a".to_string());
+        assert!(completion_policy_violation("p", None, Some(&policy))
+            .unwrap()
+            .contains("listed twice"));
+    }
+}
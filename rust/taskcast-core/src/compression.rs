@@ -0,0 +1,135 @@
+//! Transparent compression of the values a store keeps, and how much it
+//! saves.
+//!
+//! Stores that support it compress a serialized value of at least
+//! [`ValueCompressionConfig::min_bytes`] with the configured algorithm, keep
+//! it as is when that does not make it smaller, and decompress on read.
+//! [`CompressionCounters`] tracks the bytes written either way; stores serve
+//! them through `ShortTermStore::compression_stats`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Default [`ValueCompressionConfig::min_bytes`].
+pub const DEFAULT_COMPRESSION_MIN_BYTES: usize = 256;
+
+/// Algorithm stored values are compressed with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Values are written uncompressed. Compressed values already stored are
+    /// still read.
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+/// Storage-adapter option for compressing stored values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueCompressionConfig {
+    pub algorithm: CompressionAlgorithm,
+    /// Values shorter than this many bytes are written uncompressed.
+    pub min_bytes: usize,
+}
+
+impl Default for ValueCompressionConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CompressionAlgorithm::None,
+            min_bytes: DEFAULT_COMPRESSION_MIN_BYTES,
+        }
+    }
+}
+
+impl ValueCompressionConfig {
+    /// Whether a value of `len` bytes is worth compressing.
+    pub fn applies_to(&self, len: usize) -> bool {
+        self.algorithm != CompressionAlgorithm::None && len >= self.min_bytes
+    }
+}
+
+/// Bytes a store wrote with compression configured, for the stats surface.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressionStats {
+    pub algorithm: CompressionAlgorithm,
+    pub values_written: u64,
+    /// Values written compressed; the rest were below the threshold or did
+    /// not shrink.
+    pub values_compressed: u64,
+    /// Serialized size of the values written.
+    pub raw_bytes: u64,
+    /// Size of the values as stored.
+    pub stored_bytes: u64,
+    /// `raw_bytes / stored_bytes`, 1 before anything is written.
+    pub ratio: f64,
+}
+
+/// Counts what a store's compression saves.
+#[derive(Debug, Default)]
+pub struct CompressionCounters {
+    values_written: AtomicU64,
+    values_compressed: AtomicU64,
+    raw_bytes: AtomicU64,
+    stored_bytes: AtomicU64,
+}
+
+impl CompressionCounters {
+    /// Records a value of `raw` bytes written as `stored` bytes.
+    pub fn record(&self, raw: usize, stored: usize, compressed: bool) {
+        self.values_written.fetch_add(1, Ordering::Relaxed);
+        if compressed {
+            self.values_compressed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.raw_bytes.fetch_add(raw as u64, Ordering::Relaxed);
+        self.stored_bytes
+            .fetch_add(stored as u64, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self, algorithm: CompressionAlgorithm) -> CompressionStats {
+        let raw_bytes = self.raw_bytes.load(Ordering::Relaxed);
+        let stored_bytes = self.stored_bytes.load(Ordering::Relaxed);
+        CompressionStats {
+            algorithm,
+            values_written: self.values_written.load(Ordering::Relaxed),
+            values_compressed: self.values_compressed.load(Ordering::Relaxed),
+            raw_bytes,
+            stored_bytes,
+            ratio: if stored_bytes == 0 {
+                1.0
+            } else {
+                raw_bytes as f64 / stored_bytes as f64
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_values_at_the_threshold_are_compressed() {
+        let config = ValueCompressionConfig {
+            algorithm: CompressionAlgorithm::Zstd,
+            min_bytes: 100,
+        };
+        assert!(!config.applies_to(99));
+        assert!(config.applies_to(100));
+        assert!(!ValueCompressionConfig::default().applies_to(usize::MAX));
+    }
+
+    #[test]
+    fn ratio_covers_every_value_written() {
+        let counters = CompressionCounters::default();
+        assert_eq!(counters.stats(CompressionAlgorithm::Lz4).ratio, 1.0);
+        counters.record(1000, 250, true);
+        counters.record(50, 50, false);
+        let stats = counters.stats(CompressionAlgorithm::Lz4);
+        assert_eq!(stats.values_written, 2);
+        assert_eq!(stats.values_compressed, 1);
+        assert_eq!(stats.ratio, 1050.0 / 300.0);
+    }
+}
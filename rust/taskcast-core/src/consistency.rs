@@ -0,0 +1,606 @@
+//! Consistency scan across the short-term and long-term stores.
+//!
+//! [`scan_consistency`] looks for four kinds of damage that accumulate when
+//! records are removed by hand or a write is lost:
+//!
+//! - events in the long-term store whose task exists in neither store,
+//! - series-latest entries pointing at an event the task's history no
+//!   longer holds,
+//! - index counters behind the highest stored event index, which would hand
+//!   out an index already taken,
+//! - terminal tasks still missing from the long-term store once the
+//!   persistence lag has passed.
+//!
+//! [`scan_task_consistency`] runs the same checks for a single task.
+//!
+//! With [`ScanOptions::repair`] each finding is also fixed. Long-term events
+//! are paged through `batch_size` task ids at a time; short-term tasks come
+//! from `list_tasks`, which TTLs keep bounded, and are read one at a time.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::persist_long_term_event;
+use crate::retry::{Clock, SystemClock};
+use crate::series::{attach_accumulated_data, fold_json_patch_series};
+use crate::state_machine::is_terminal;
+use crate::types::{
+    LongTermStore, ScanToken, SeriesMode, ShortTermStore, Task, TaskEvent, TaskFilter, TaskOrigin,
+    TaskOriginKind, TaskStatus,
+};
+
+/// Default [`ScanOptions::persistence_lag_ms`]: 5 minutes.
+pub const DEFAULT_PERSISTENCE_LAG_MS: u64 = 5 * 60 * 1000;
+
+/// Default [`ScanOptions::batch_size`].
+pub const DEFAULT_SCAN_BATCH_SIZE: u64 = 500;
+
+/// Default [`ScanOptions::max_findings`].
+pub const DEFAULT_MAX_FINDINGS: usize = 1000;
+
+/// Metadata key set on a task record recreated for orphaned events.
+pub const PLACEHOLDER_METADATA_KEY: &str = "taskcast:placeholder";
+
+/// What a repair does with long-term events whose task is gone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrphanPolicy {
+    /// Delete the events.
+    #[default]
+    Delete,
+    /// Keep the events and recreate a task record for them, with the status
+    /// of their last status event and [`PLACEHOLDER_METADATA_KEY`] set.
+    Placeholder,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Fix what the scan finds instead of only reporting it.
+    pub repair: bool,
+    pub orphan_policy: OrphanPolicy,
+    /// How long a terminal task may be missing from the long-term store
+    /// before it is reported, in milliseconds. Writes to the long-term store
+    /// happen in the background, so a task that just ended may not be there
+    /// yet.
+    pub persistence_lag_ms: u64,
+    /// Task ids read from the long-term store per page.
+    pub batch_size: u64,
+    /// Findings listed in the report; the counts cover all of them.
+    pub max_findings: usize,
+    /// The time the persistence lag is measured from, in epoch
+    /// milliseconds. Defaults to the system clock.
+    pub now: Option<f64>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            repair: false,
+            orphan_policy: OrphanPolicy::default(),
+            persistence_lag_ms: DEFAULT_PERSISTENCE_LAG_MS,
+            batch_size: DEFAULT_SCAN_BATCH_SIZE,
+            max_findings: DEFAULT_MAX_FINDINGS,
+            now: None,
+        }
+    }
+}
+
+/// One inconsistency found by [`scan_consistency`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ConsistencyIssue {
+    /// The long-term store holds events of a task found in neither store.
+    OrphanedEvents { task_id: String },
+    /// A series-latest entry refers to an event missing from the task's
+    /// history.
+    DanglingSeriesLatest {
+        task_id: String,
+        series_id: String,
+        event_id: String,
+    },
+    /// The task's index counter would hand out `next_index`, at or below
+    /// the highest stored index.
+    StaleIndexCounter {
+        task_id: String,
+        next_index: u64,
+        max_index: u64,
+    },
+    /// A terminal task past the persistence lag has no long-term record.
+    MissingFromLongTerm { task_id: String, status: TaskStatus },
+}
+
+/// An issue and, when repairing, how its repair went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyFinding {
+    #[serde(flatten)]
+    pub issue: ConsistencyIssue,
+    /// Whether the repair succeeded; absent when not repairing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repaired: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Number of findings of each kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyCounts {
+    pub orphaned_events: u64,
+    pub dangling_series_latest: u64,
+    pub stale_index_counters: u64,
+    pub missing_from_long_term: u64,
+}
+
+impl ConsistencyCounts {
+    pub fn total(&self) -> u64 {
+        self.orphaned_events
+            + self.dangling_series_latest
+            + self.stale_index_counters
+            + self.missing_from_long_term
+    }
+
+    fn count(&mut self, issue: &ConsistencyIssue) {
+        match issue {
+            ConsistencyIssue::OrphanedEvents { .. } => self.orphaned_events += 1,
+            ConsistencyIssue::DanglingSeriesLatest { .. } => self.dangling_series_latest += 1,
+            ConsistencyIssue::StaleIndexCounter { .. } => self.stale_index_counters += 1,
+            ConsistencyIssue::MissingFromLongTerm { .. } => self.missing_from_long_term += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    /// Whether findings were repaired.
+    pub repair: bool,
+    pub short_term_tasks_scanned: u64,
+    pub long_term_tasks_scanned: u64,
+    pub counts: ConsistencyCounts,
+    pub repaired: u64,
+    pub repair_failures: u64,
+    /// The first `max_findings` findings.
+    pub findings: Vec<ConsistencyFinding>,
+    /// Whether findings were left out of `findings`.
+    pub findings_truncated: bool,
+    /// Checks that could not run, and why.
+    pub skipped: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// Whether the scan found nothing.
+    pub fn is_clean(&self) -> bool {
+        self.counts.total() == 0
+    }
+
+    fn record(&mut self, issue: ConsistencyIssue, repair: Option<Result<(), String>>, max: usize) {
+        self.counts.count(&issue);
+        match repair {
+            Some(Ok(())) => self.repaired += 1,
+            Some(Err(_)) => self.repair_failures += 1,
+            None => {}
+        }
+        if self.findings.len() >= max {
+            self.findings_truncated = true;
+            return;
+        }
+        self.findings.push(ConsistencyFinding {
+            issue,
+            repaired: repair.as_ref().map(Result::is_ok),
+            error: repair.and_then(Result::err),
+        });
+    }
+}
+
+type StoreResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Scans the stores for inconsistencies, repairing them when
+/// `options.repair` is set. See the [module docs](self) for what is
+/// checked. Checks a store does not support are listed in
+/// [`ConsistencyReport::skipped`]; read failures end the scan with an
+/// error, while repair failures are reported per finding.
+pub async fn scan_consistency(
+    short_term: &Arc<dyn ShortTermStore>,
+    long_term: Option<&Arc<dyn LongTermStore>>,
+    options: ScanOptions,
+) -> StoreResult<ConsistencyReport> {
+    let now = options.now.unwrap_or_else(|| SystemClock.now_ms());
+    let mut report = ConsistencyReport {
+        repair: options.repair,
+        ..Default::default()
+    };
+
+    let tasks = short_term.list_tasks(TaskFilter::default()).await?;
+    for task in &tasks {
+        report.short_term_tasks_scanned += 1;
+        scan_short_term_task(
+            short_term.as_ref(),
+            long_term,
+            task,
+            now,
+            &options,
+            &mut report,
+        )
+        .await?;
+    }
+
+    match long_term {
+        Some(long_term) => {
+            scan_orphaned_events(
+                short_term.as_ref(),
+                long_term.as_ref(),
+                &options,
+                &mut report,
+            )
+            .await?
+        }
+        None => report
+            .skipped
+            .push("orphanedEvents, missingFromLongTerm: no long-term store".to_string()),
+    }
+    Ok(report)
+}
+
+/// Runs the checks of [`scan_consistency`] for `task_id` alone. Orphaned
+/// events are only looked for when the task is in neither store.
+pub async fn scan_task_consistency(
+    short_term: &Arc<dyn ShortTermStore>,
+    long_term: Option<&Arc<dyn LongTermStore>>,
+    task_id: &str,
+    options: ScanOptions,
+) -> StoreResult<ConsistencyReport> {
+    let now = options.now.unwrap_or_else(|| SystemClock.now_ms());
+    let mut report = ConsistencyReport {
+        repair: options.repair,
+        ..Default::default()
+    };
+
+    if let Some(task) = short_term.get_task(task_id).await? {
+        report.short_term_tasks_scanned += 1;
+        scan_short_term_task(
+            short_term.as_ref(),
+            long_term,
+            &task,
+            now,
+            &options,
+            &mut report,
+        )
+        .await?;
+        return Ok(report);
+    }
+
+    let Some(long_term) = long_term else {
+        report
+            .skipped
+            .push("orphanedEvents, missingFromLongTerm: no long-term store".to_string());
+        return Ok(report);
+    };
+    report.long_term_tasks_scanned += 1;
+    if long_term.get_task(task_id).await?.is_none()
+        && !long_term.get_events(task_id, None).await?.is_empty()
+    {
+        let repair = if options.repair {
+            Some(repair_orphan(long_term.as_ref(), task_id, options.orphan_policy).await)
+        } else {
+            None
+        };
+        report.record(
+            ConsistencyIssue::OrphanedEvents {
+                task_id: task_id.to_string(),
+            },
+            repair,
+            options.max_findings,
+        );
+    }
+    Ok(report)
+}
+
+async fn scan_short_term_task(
+    short_term: &dyn ShortTermStore,
+    long_term: Option<&Arc<dyn LongTermStore>>,
+    task: &Task,
+    now: f64,
+    options: &ScanOptions,
+    report: &mut ConsistencyReport,
+) -> StoreResult<()> {
+    // Taken first, so a series latest published after the history was read
+    // is not reported as dangling. The read itself stays unbounded: a stale
+    // index counter would hide the events it is checked against.
+    let scan = short_term.begin_scan(&task.id).await?;
+    let events = short_term.get_events(&task.id, None).await?;
+    let max = options.max_findings;
+
+    for (series_id, event_id) in dangling_series(short_term, &scan, &events).await? {
+        let repair = if options.repair {
+            Some(rebuild_series_latest(short_term, &task.id, &series_id, &events).await)
+        } else {
+            None
+        };
+        let issue = ConsistencyIssue::DanglingSeriesLatest {
+            task_id: task.id.clone(),
+            series_id,
+            event_id,
+        };
+        report.record(issue, repair, max);
+    }
+
+    if let Some(max_index) = events.iter().map(|event| event.index).max() {
+        let next_index = short_term.event_count(&task.id).await?;
+        if next_index <= max_index {
+            let repair = if options.repair {
+                Some(raise_index_counter(short_term, &task.id, max_index).await)
+            } else {
+                None
+            };
+            let issue = ConsistencyIssue::StaleIndexCounter {
+                task_id: task.id.clone(),
+                next_index,
+                max_index,
+            };
+            report.record(issue, repair, max);
+        }
+    }
+
+    if let Some(long_term) = long_term {
+        let ended_at = task.completed_at.unwrap_or(task.updated_at);
+        let overdue =
+            is_terminal(&task.status) && now - ended_at > options.persistence_lag_ms as f64;
+        if overdue && long_term.get_task(&task.id).await?.is_none() {
+            let repair = if options.repair {
+                Some(backfill_long_term(long_term, task, events).await)
+            } else {
+                None
+            };
+            let issue = ConsistencyIssue::MissingFromLongTerm {
+                task_id: task.id.clone(),
+                status: task.status.clone(),
+            };
+            report.record(issue, repair, max);
+        }
+    }
+    Ok(())
+}
+
+/// Series of `events` whose latest entry names an event not among them, with
+/// that event's id.
+async fn dangling_series(
+    short_term: &dyn ShortTermStore,
+    scan: &ScanToken,
+    events: &[TaskEvent],
+) -> StoreResult<Vec<(String, String)>> {
+    let ids: HashSet<&str> = events.iter().map(|event| event.id.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut dangling = Vec::new();
+    for event in events {
+        let Some(ref series_id) = event.series_id else {
+            continue;
+        };
+        if matches!(event.series_mode, None | Some(SeriesMode::KeepAll))
+            || !seen.insert(series_id.as_str())
+        {
+            continue;
+        }
+        if let Some(latest) = short_term
+            .get_series_latest(&scan.task_id, series_id)
+            .await?
+        {
+            if scan.covers(&latest) && !ids.contains(latest.id.as_str()) {
+                dangling.push((series_id.clone(), latest.id));
+            }
+        }
+    }
+    Ok(dangling)
+}
+
+/// Sets the series latest to what the series' events in `events` build.
+async fn rebuild_series_latest(
+    short_term: &dyn ShortTermStore,
+    task_id: &str,
+    series_id: &str,
+    events: &[TaskEvent],
+) -> Result<(), String> {
+    let mut series: Vec<TaskEvent> = events
+        .iter()
+        .filter(|event| event.series_id.as_deref() == Some(series_id))
+        .cloned()
+        .collect();
+    let latest = match series.last().and_then(|event| event.series_mode.clone()) {
+        Some(SeriesMode::Accumulate) => {
+            attach_accumulated_data(&mut series);
+            series.pop().map(|mut event| {
+                if let Some(accumulated) = event._accumulated_data.take() {
+                    event.data = accumulated;
+                }
+                event
+            })
+        }
+        Some(SeriesMode::JsonPatch) => fold_json_patch_series(&series, series_id),
+        _ => series.pop(),
+    };
+    let Some(latest) = latest else {
+        return Err(format!(
+            "Series {series_id} could not be rebuilt from its events"
+        ));
+    };
+    short_term
+        .set_series_latest(task_id, series_id, latest)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Allocates indices until the counter is past `max_index`.
+async fn raise_index_counter(
+    short_term: &dyn ShortTermStore,
+    task_id: &str,
+    max_index: u64,
+) -> Result<(), String> {
+    while short_term
+        .next_index(task_id)
+        .await
+        .map_err(|e| e.to_string())?
+        < max_index
+    {}
+    Ok(())
+}
+
+/// Writes `task` and the events of `history` the long-term store lacks, as
+/// the engine would have persisted them.
+async fn backfill_long_term(
+    long_term: &Arc<dyn LongTermStore>,
+    task: &Task,
+    mut history: Vec<TaskEvent>,
+) -> Result<(), String> {
+    let stored: HashSet<String> = long_term
+        .get_events(&task.id, None)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|event| event.id)
+        .collect();
+    attach_accumulated_data(&mut history);
+    for mut event in history {
+        if stored.contains(&event.id) {
+            continue;
+        }
+        let accumulated = event._accumulated_data.take().map(|data| TaskEvent {
+            data,
+            ..event.clone()
+        });
+        persist_long_term_event(Arc::clone(long_term), event, accumulated)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    long_term
+        .save_task(task.clone())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn scan_orphaned_events(
+    short_term: &dyn ShortTermStore,
+    long_term: &dyn LongTermStore,
+    options: &ScanOptions,
+    report: &mut ConsistencyReport,
+) -> StoreResult<()> {
+    let mut after: Option<String> = None;
+    loop {
+        let page = match long_term
+            .list_event_task_ids(after.as_deref(), options.batch_size.max(1))
+            .await
+        {
+            Ok(page) => page,
+            Err(err) if is_unsupported(err.as_ref()) => {
+                report.skipped.push(format!("orphanedEvents: {err}"));
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+        let Some(last) = page.last().cloned() else {
+            return Ok(());
+        };
+        for task_id in page {
+            report.long_term_tasks_scanned += 1;
+            if long_term.get_task(&task_id).await?.is_some()
+                || short_term.get_task(&task_id).await?.is_some()
+            {
+                continue;
+            }
+            let repair = if options.repair {
+                Some(repair_orphan(long_term, &task_id, options.orphan_policy).await)
+            } else {
+                None
+            };
+            report.record(
+                ConsistencyIssue::OrphanedEvents { task_id },
+                repair,
+                options.max_findings,
+            );
+        }
+        after = Some(last);
+    }
+}
+
+async fn repair_orphan(
+    long_term: &dyn LongTermStore,
+    task_id: &str,
+    policy: OrphanPolicy,
+) -> Result<(), String> {
+    let result = match policy {
+        OrphanPolicy::Delete => long_term.delete_task(task_id).await,
+        OrphanPolicy::Placeholder => match long_term.get_events(task_id, None).await {
+            Ok(events) => {
+                long_term
+                    .save_task(placeholder_task(task_id, &events))
+                    .await
+            }
+            Err(err) => Err(err),
+        },
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// A task record for orphaned `events`, spanning their timestamps, with the
+/// status of the last status event among them, or `cancelled` without one.
+fn placeholder_task(task_id: &str, events: &[TaskEvent]) -> Task {
+    let status = events
+        .iter()
+        .rev()
+        .filter(|event| event.r#type == "taskcast:status")
+        .find_map(|event| serde_json::from_value(event.data.get("status")?.clone()).ok())
+        .unwrap_or(TaskStatus::Cancelled);
+    let created_at = events.first().map_or(0.0, |event| event.timestamp);
+    let updated_at = events.last().map_or(created_at, |event| event.timestamp);
+    Task {
+        id: task_id.to_string(),
+        r#type: None,
+        completed_at: is_terminal(&status).then_some(updated_at),
+        status,
+        params: None,
+        result: None,
+        error: None,
+        metadata: Some(
+            [(
+                PLACEHOLDER_METADATA_KEY.to_string(),
+                serde_json::Value::Bool(true),
+            )]
+            .into_iter()
+            .collect(),
+        ),
+        created_at,
+        updated_at,
+        ttl: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: Some("Recreated by a consistency repair for orphaned events".to_string()),
+        resume_at: None,
+        blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: Some(TaskOrigin::new(TaskOriginKind::System).with_detail("consistency-repair")),
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn is_unsupported(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<std::io::Error>()
+        .is_some_and(|err| err.kind() == std::io::ErrorKind::Unsupported)
+}
@@ -0,0 +1,601 @@
+//! Event type conventions discovered per deployment: for each task type,
+//! the event types its tasks publish, how often, and a recent example
+//! payload.
+//!
+//! A [`ConventionRegistry`] samples published events, so the catalog costs
+//! one random draw per event. Sampled payloads are redacted before they are
+//! kept and examples above a size cap are not kept at all. Samples gather in
+//! memory until [`TaskEngine::flush_conventions`] adds them to the
+//! [`EventConventionStore`] of the long-term store, or else of the
+//! short-term store, so instances sharing storage build one catalog.
+//! Descriptions and JSON Schemas are curated by hand next to the samples.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::config::{glob_matches, REDACTED};
+use crate::engine::TaskEngine;
+use crate::runner::{runner_fn, RunnerTickReport};
+use crate::types::TaskEvent;
+
+/// Fraction of published events sampled by default.
+pub const DEFAULT_DISCOVERY_SAMPLE_RATE: f64 = 0.05;
+
+/// Largest example payload kept by default, in bytes of JSON.
+pub const DEFAULT_MAX_EXAMPLE_BYTES: usize = 2048;
+
+/// How often samples are written to storage by default: 30 seconds.
+pub const DEFAULT_CONVENTION_FLUSH_INTERVAL_MS: u64 = 30_000;
+
+/// Key name patterns whose values are always redacted in examples.
+pub const DEFAULT_REDACT_KEYS: [&str; 7] = [
+    "*password*",
+    "*secret*",
+    "*token*",
+    "*apikey*",
+    "*api_key*",
+    "*credential*",
+    "authorization",
+];
+
+/// What the deployment knows about one event type of one task type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventConvention {
+    pub task_type: String,
+    pub event_type: String,
+    /// Published events of this type, estimated from the samples.
+    pub estimated_count: u64,
+    /// When the type was last sampled, in epoch milliseconds.
+    pub last_seen_at: Option<f64>,
+    /// The `data` of the most recent sample small enough to keep, redacted.
+    pub example: Option<Value>,
+    /// When `example` was published, in epoch milliseconds.
+    pub example_at: Option<f64>,
+    pub description: Option<String>,
+    /// JSON Schema of the event's `data`, as curated.
+    pub schema: Option<Value>,
+}
+
+impl EventConvention {
+    fn new(task_type: &str, event_type: &str) -> Self {
+        Self {
+            task_type: task_type.to_string(),
+            event_type: event_type.to_string(),
+            estimated_count: 0,
+            last_seen_at: None,
+            example: None,
+            example_at: None,
+            description: None,
+            schema: None,
+        }
+    }
+
+    /// Adds the samples of `observed` to these: counts add up, and the
+    /// newer last sighting and example win. Notes are left alone.
+    pub fn absorb(&mut self, observed: &EventConvention) {
+        self.estimated_count += observed.estimated_count;
+        if later(observed.last_seen_at, self.last_seen_at) {
+            self.last_seen_at = observed.last_seen_at;
+        }
+        if later(observed.example_at, self.example_at) {
+            self.example = observed.example.clone();
+            self.example_at = observed.example_at;
+        }
+    }
+}
+
+fn later(candidate: Option<f64>, current: Option<f64>) -> bool {
+    candidate.is_some_and(|at| current.is_none_or(|current| at >= current))
+}
+
+/// Hand-written notes on an event type, set by
+/// `PUT /conventions/{taskType}/{eventType}`. Each call replaces both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConventionNotes {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// JSON Schema of the event's `data`: an object or a boolean.
+    #[serde(default)]
+    pub schema: Option<Value>,
+}
+
+/// Where discovered conventions are kept, shared by the instances using the
+/// store.
+#[async_trait]
+pub trait EventConventionStore: Send + Sync {
+    /// Adds `observed` to the stored conventions with
+    /// [`EventConvention::absorb`], creating the missing ones.
+    async fn record_event_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Sets the notes on one convention, creating it without samples if
+    /// needed.
+    async fn annotate_event_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// The conventions of `task_type`, or of every task type, ordered by
+    /// task type and then event type.
+    async fn list_event_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Conventions kept in this process, for stores that keep none.
+#[derive(Default)]
+pub struct MemoryEventConventionStore {
+    conventions: Mutex<BTreeMap<(String, String), EventConvention>>,
+}
+
+impl MemoryEventConventionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl EventConventionStore for MemoryEventConventionStore {
+    async fn record_event_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conventions = self.conventions.lock().unwrap();
+        for sample in observed {
+            conventions
+                .entry((sample.task_type.clone(), sample.event_type.clone()))
+                .or_insert_with(|| EventConvention::new(&sample.task_type, &sample.event_type))
+                .absorb(sample);
+        }
+        Ok(())
+    }
+
+    async fn annotate_event_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conventions = self.conventions.lock().unwrap();
+        let convention = conventions
+            .entry((task_type.to_string(), event_type.to_string()))
+            .or_insert_with(|| EventConvention::new(task_type, event_type));
+        convention.description = notes.description.clone();
+        convention.schema = notes.schema.clone();
+        Ok(())
+    }
+
+    async fn list_event_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        let conventions = self.conventions.lock().unwrap();
+        Ok(conventions
+            .values()
+            .filter(|c| task_type.is_none_or(|task_type| c.task_type == task_type))
+            .cloned()
+            .collect())
+    }
+}
+
+// ─── Registry ────────────────────────────────────────────────────────────────
+
+/// How published events are sampled for the catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryOptions {
+    /// Fraction of published events sampled, above 0 and at most 1.
+    pub sample_rate: f64,
+    /// Examples whose redacted JSON is larger are not kept.
+    pub max_example_bytes: usize,
+    /// Key name patterns redacted at any depth of an example, besides
+    /// [`DEFAULT_REDACT_KEYS`]. `*` matches any run of characters.
+    pub redact_keys: Vec<String>,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: DEFAULT_DISCOVERY_SAMPLE_RATE,
+            max_example_bytes: DEFAULT_MAX_EXAMPLE_BYTES,
+            redact_keys: Vec::new(),
+        }
+    }
+}
+
+/// Samples published events and holds the samples not yet flushed to
+/// storage.
+pub struct ConventionRegistry {
+    sample_rate: f64,
+    /// Events each sample stands for.
+    weight: u64,
+    max_example_bytes: usize,
+    redact_keys: Vec<String>,
+    pending: Mutex<HashMap<(String, String), EventConvention>>,
+    /// Used when neither store keeps conventions.
+    fallback: MemoryEventConventionStore,
+}
+
+impl ConventionRegistry {
+    pub fn new(options: DiscoveryOptions) -> Self {
+        let sample_rate = options.sample_rate.clamp(f64::MIN_POSITIVE, 1.0);
+        Self {
+            sample_rate,
+            weight: (1.0 / sample_rate).round().max(1.0) as u64,
+            max_example_bytes: options.max_example_bytes,
+            redact_keys: DEFAULT_REDACT_KEYS
+                .iter()
+                .map(|key| key.to_string())
+                .chain(options.redact_keys)
+                .collect(),
+            pending: Mutex::new(HashMap::new()),
+            fallback: MemoryEventConventionStore::new(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Samples `event`, published on a task of `task_type`, with the
+    /// configured probability.
+    pub fn observe(&self, task_type: &str, event: &TaskEvent) {
+        if fastrand::f64() >= self.sample_rate {
+            return;
+        }
+        self.record(task_type, event);
+    }
+
+    /// Keeps `event` as a sample.
+    pub fn record(&self, task_type: &str, event: &TaskEvent) {
+        let example = self.example_of(&event.data);
+        let sample = EventConvention {
+            estimated_count: self.weight,
+            last_seen_at: Some(event.timestamp),
+            example_at: example.is_some().then_some(event.timestamp),
+            example,
+            ..EventConvention::new(task_type, &event.r#type)
+        };
+        self.pending
+            .lock()
+            .unwrap()
+            .entry((sample.task_type.clone(), sample.event_type.clone()))
+            .or_insert_with(|| EventConvention::new(task_type, &event.r#type))
+            .absorb(&sample);
+    }
+
+    /// `data` redacted, or `None` if it is too large to keep.
+    fn example_of(&self, data: &Value) -> Option<Value> {
+        let mut example = data.clone();
+        redact_keys(&mut example, &self.redact_keys);
+        let size = serde_json::to_vec(&example).map_or(usize::MAX, |bytes| bytes.len());
+        (size <= self.max_example_bytes).then_some(example)
+    }
+
+    /// Samples not yet flushed, of `task_type` or of every task type.
+    pub fn pending(&self, task_type: Option<&str>) -> Vec<EventConvention> {
+        self.pending
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|c| task_type.is_none_or(|task_type| c.task_type == task_type))
+            .cloned()
+            .collect()
+    }
+
+    fn take_pending(&self) -> Vec<EventConvention> {
+        self.pending
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    /// Puts back samples whose flush failed, merged with newer ones.
+    fn restore_pending(&self, samples: Vec<EventConvention>) {
+        let mut pending = self.pending.lock().unwrap();
+        for sample in samples {
+            pending
+                .entry((sample.task_type.clone(), sample.event_type.clone()))
+                .or_insert_with(|| EventConvention::new(&sample.task_type, &sample.event_type))
+                .absorb(&sample);
+        }
+    }
+
+    pub(crate) fn fallback_store(&self) -> &MemoryEventConventionStore {
+        &self.fallback
+    }
+}
+
+/// Replaces the value of every object key matching one of `patterns`, at
+/// any depth.
+fn redact_keys(value: &mut Value, patterns: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if patterns.iter().any(|pattern| glob_matches(pattern, key)) {
+                    *child = Value::String(REDACTED.to_string());
+                } else {
+                    redact_keys(child, patterns);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_keys(item, patterns);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl TaskEngine {
+    /// Writes the samples gathered since the last flush to the convention
+    /// store. Returns how many conventions were written. Samples that
+    /// could not be written are kept for the next flush.
+    pub async fn flush_conventions(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(registry) = self.conventions() else {
+            return Ok(0);
+        };
+        let samples = registry.take_pending();
+        if samples.is_empty() {
+            return Ok(0);
+        }
+        match self
+            .convention_store(registry)
+            .record_event_conventions(&samples)
+            .await
+        {
+            Ok(()) => Ok(samples.len() as u64),
+            Err(err) => {
+                registry.restore_pending(samples);
+                Err(err)
+            }
+        }
+    }
+
+    /// The catalog of `task_type`, or of every task type: stored
+    /// conventions with the samples not yet flushed added in. `None` while
+    /// discovery is off.
+    pub async fn list_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Option<Vec<EventConvention>>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(registry) = self.conventions() else {
+            return Ok(None);
+        };
+        let stored = self
+            .convention_store(registry)
+            .list_event_conventions(task_type)
+            .await?;
+        let mut catalog: BTreeMap<(String, String), EventConvention> = stored
+            .into_iter()
+            .map(|c| ((c.task_type.clone(), c.event_type.clone()), c))
+            .collect();
+        for sample in registry.pending(task_type) {
+            catalog
+                .entry((sample.task_type.clone(), sample.event_type.clone()))
+                .or_insert_with(|| EventConvention::new(&sample.task_type, &sample.event_type))
+                .absorb(&sample);
+        }
+        Ok(Some(catalog.into_values().collect()))
+    }
+
+    /// Sets the notes on an event type of `task_type` and returns its
+    /// convention. `None` while discovery is off.
+    pub async fn annotate_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<Option<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(registry) = self.conventions() else {
+            return Ok(None);
+        };
+        self.convention_store(registry)
+            .annotate_event_convention(task_type, event_type, notes)
+            .await?;
+        Ok(self
+            .list_conventions(Some(task_type))
+            .await?
+            .into_iter()
+            .flatten()
+            .find(|c| c.event_type == event_type))
+    }
+
+    fn convention_store<'a>(
+        &'a self,
+        registry: &'a ConventionRegistry,
+    ) -> &'a dyn EventConventionStore {
+        self.long_term_store()
+            .and_then(|store| store.event_conventions())
+            .or_else(|| self.short_term_store().event_conventions())
+            .unwrap_or(registry.fallback_store())
+    }
+}
+
+// ─── Flusher ─────────────────────────────────────────────────────────────────
+
+/// Writes discovered conventions to storage periodically.
+pub struct ConventionFlusher {
+    engine: Arc<TaskEngine>,
+    interval_ms: u64,
+    handle: Option<AbortHandle>,
+}
+
+impl ConventionFlusher {
+    pub fn new(engine: Arc<TaskEngine>, interval_ms: u64) -> Self {
+        Self {
+            engine,
+            interval_ms: interval_ms.max(100),
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let engine = self.engine.clone();
+        let runner = runner_fn(
+            "conventions.flush",
+            Duration::from_millis(self.interval_ms),
+            move || {
+                let engine = engine.clone();
+                async move {
+                    engine
+                        .flush_conventions()
+                        .await
+                        .map(RunnerTickReport::processed)
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Flushes immediately. Returns the number of conventions written.
+    pub async fn tick(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.engine.flush_conventions().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::Level;
+
+    fn event(r#type: &str, timestamp: f64, data: Value) -> TaskEvent {
+        TaskEvent {
+            id: ulid::Ulid::new().to_string(),
+            task_id: "t1".to_string(),
+            index: 0,
+            timestamp,
+            r#type: r#type.to_string(),
+            level: Level::Info,
+            data,
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    #[test]
+    fn samples_weigh_by_the_sample_rate_and_keep_the_latest_example() {
+        let registry = ConventionRegistry::new(DiscoveryOptions {
+            sample_rate: 0.25,
+            ..Default::default()
+        });
+        registry.record("llm", &event("llm.delta", 1.0, json!({ "text": "a" })));
+        registry.record("llm", &event("llm.delta", 2.0, json!({ "text": "b" })));
+
+        let pending = registry.pending(Some("llm"));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].estimated_count, 8);
+        assert_eq!(pending[0].example, Some(json!({ "text": "b" })));
+        assert_eq!(pending[0].last_seen_at, Some(2.0));
+        assert!(registry.pending(Some("other")).is_empty());
+    }
+
+    #[test]
+    fn examples_are_redacted_and_capped() {
+        let registry = ConventionRegistry::new(DiscoveryOptions {
+            max_example_bytes: 160,
+            redact_keys: vec!["ssn".to_string()],
+            ..Default::default()
+        });
+        let data = json!({
+            "user": { "ssn": "123-45-6789", "name": "Ada" },
+            "headers": [{ "Authorization": "Bearer abc" }],
+            "apiKey": "sk-1"
+        });
+        registry.record("t", &event("call", 1.0, data));
+        assert_eq!(
+            registry.pending(None)[0].example,
+            Some(json!({
+                "user": { "ssn": REDACTED, "name": "Ada" },
+                "headers": [{ "Authorization": REDACTED }],
+                "apiKey": REDACTED
+            }))
+        );
+
+        // Too large: the earlier example stays.
+        registry.record("t", &event("call", 2.0, json!({ "blob": "x".repeat(200) })));
+        let convention = &registry.pending(None)[0];
+        assert_eq!(convention.example_at, Some(1.0));
+        assert_eq!(convention.last_seen_at, Some(2.0));
+    }
+
+    #[test]
+    fn full_rate_samples_every_event_and_tiny_rates_weigh_more() {
+        let registry = ConventionRegistry::new(DiscoveryOptions {
+            sample_rate: 1.0,
+            ..Default::default()
+        });
+        for i in 0..10 {
+            registry.observe("t", &event("tick", i as f64, json!({})));
+        }
+        assert_eq!(registry.pending(None)[0].estimated_count, 10);
+
+        let sparse = ConventionRegistry::new(DiscoveryOptions {
+            sample_rate: 0.001,
+            ..Default::default()
+        });
+        sparse.record("t", &event("tick", 0.0, json!({})));
+        assert_eq!(sparse.pending(None)[0].estimated_count, 1000);
+    }
+
+    #[tokio::test]
+    async fn memory_store_merges_samples_and_keeps_notes() {
+        let store = MemoryEventConventionStore::new();
+        let notes = ConventionNotes {
+            description: Some("Token deltas".to_string()),
+            schema: Some(json!({ "type": "object" })),
+        };
+        store
+            .annotate_event_convention("llm", "llm.delta", &notes)
+            .await
+            .unwrap();
+        let mut sample = EventConvention::new("llm", "llm.delta");
+        sample.estimated_count = 20;
+        sample.example = Some(json!({ "text": "hi" }));
+        sample.example_at = Some(5.0);
+        sample.last_seen_at = Some(5.0);
+        store
+            .record_event_conventions(&[sample.clone(), sample])
+            .await
+            .unwrap();
+
+        let listed = store.list_event_conventions(Some("llm")).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].estimated_count, 40);
+        assert_eq!(listed[0].description.as_deref(), Some("Token deltas"));
+        assert_eq!(listed[0].example, Some(json!({ "text": "hi" })));
+        assert!(store
+            .list_event_conventions(Some("other"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
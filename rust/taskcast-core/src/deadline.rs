@@ -0,0 +1,262 @@
+//! Deadline warnings: advisory `taskcast:deadline-warning` events published
+//! while a running task still has time left, so subscribers can react before
+//! it times out.
+//!
+//! A task's deadline is its `deadlineMs`, else its `ttl`, counted from its
+//! scheduled activation or its creation. A [`DeadlineMonitor`] scans running
+//! tasks and publishes each configured [`DeadlineWarning`] once it is due.
+//! Every instance may run one: a warning is claimed in the short-term store
+//! before it is published, so only one instance publishes it.
+
+use std::sync::Arc;
+
+use serde_json::json;
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::engine::{EngineError, PublishEventInput, TaskEngine};
+use crate::runner::{runner_fn, RunnerTickReport};
+use crate::types::{DeadlineWarning, Level, ShortTermStore, Task, TaskFilter, TaskStatus};
+
+/// Type of the events a [`DeadlineMonitor`] publishes.
+pub const DEADLINE_WARNING_EVENT_TYPE: &str = "taskcast:deadline-warning";
+
+/// The epoch milliseconds at which `task` runs out of time, or `None` when
+/// it has neither `deadlineMs` nor `ttl`.
+pub fn task_deadline(task: &Task) -> Option<f64> {
+    let allotted = allotted_ms(task)?;
+    Some(task.scheduled_for.unwrap_or(task.created_at) + allotted)
+}
+
+fn allotted_ms(task: &Task) -> Option<f64> {
+    task.deadline_ms
+        .map(|ms| ms as f64)
+        .or_else(|| task.ttl.map(|secs| secs as f64 * 1000.0))
+}
+
+impl DeadlineWarning {
+    /// The epoch milliseconds at which this warning is due for `task`, or
+    /// `None` when the task has no deadline.
+    pub fn due_at(&self, task: &Task) -> Option<f64> {
+        let allotted = allotted_ms(task)?;
+        let start = task.scheduled_for.unwrap_or(task.created_at);
+        Some(match *self {
+            Self::AtFraction { at_fraction } => start + allotted * at_fraction.clamp(0.0, 1.0),
+            Self::BeforeMs { before_ms } => start + allotted - before_ms as f64,
+        })
+    }
+
+    /// Identifies this warning in claims, e.g. `atFraction:0.8`.
+    pub fn key(&self) -> String {
+        match self {
+            Self::AtFraction { at_fraction } => format!("atFraction:{at_fraction}"),
+            Self::BeforeMs { before_ms } => format!("beforeMs:{before_ms}"),
+        }
+    }
+}
+
+pub struct DeadlineMonitorOptions {
+    pub engine: Arc<TaskEngine>,
+    pub short_term_store: Arc<dyn ShortTermStore>,
+    /// How often to scan running tasks, in milliseconds. Default: 5_000.
+    pub check_interval_ms: u64,
+    pub warnings: Vec<DeadlineWarning>,
+}
+
+/// Publishes deadline warnings for running tasks.
+///
+/// A warning is only published between its due time and the deadline, and
+/// never for a task that is no longer running. It is claimed before it is
+/// published, so a warning whose publish fails is not retried.
+pub struct DeadlineMonitor {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    check_interval_ms: u64,
+    warnings: Arc<Vec<DeadlineWarning>>,
+    handle: Option<AbortHandle>,
+}
+
+impl DeadlineMonitor {
+    pub fn new(opts: DeadlineMonitorOptions) -> Self {
+        Self {
+            engine: opts.engine,
+            short_term_store: opts.short_term_store,
+            check_interval_ms: opts.check_interval_ms.max(100),
+            warnings: Arc::new(opts.warnings),
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let engine = self.engine.clone();
+        let store = self.short_term_store.clone();
+        let interval_ms = self.check_interval_ms;
+        let warnings = self.warnings.clone();
+
+        let runner = runner_fn(
+            "deadline.warnings",
+            Duration::from_millis(interval_ms),
+            move || {
+                let engine = engine.clone();
+                let store = store.clone();
+                let warnings = warnings.clone();
+                async move {
+                    Self::tick_inner(&engine, &store, &warnings)
+                        .await
+                        .map(RunnerTickReport::processed)
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs one scan immediately and returns how many warnings it published.
+    pub async fn tick(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Self::tick_inner(&self.engine, &self.short_term_store, &self.warnings).await
+    }
+
+    async fn tick_inner(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        warnings: &[DeadlineWarning],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        if warnings.is_empty() {
+            return Ok(0);
+        }
+        let now = engine.clock().now_ms();
+        let running = store
+            .list_tasks(TaskFilter {
+                status: Some(vec![TaskStatus::Running]),
+                ..Default::default()
+            })
+            .await?;
+
+        let mut published = 0;
+        for task in running {
+            let Some(deadline) = task_deadline(&task) else {
+                continue;
+            };
+            if now >= deadline {
+                continue;
+            }
+            for warning in warnings {
+                if warning.due_at(&task).is_none_or(|due| now < due) {
+                    continue;
+                }
+                // The claim only has to outlive the deadline: no warning is
+                // published after it.
+                let claim_ttl_ms = (deadline - now).ceil().max(1.0) as u64;
+                if !store
+                    .claim_deadline_warning(&task.id, &warning.key(), claim_ttl_ms)
+                    .await?
+                {
+                    continue;
+                }
+                if Self::publish(engine, &task, warning, deadline, now).await? {
+                    published += 1;
+                }
+            }
+        }
+        Ok(published)
+    }
+
+    async fn publish(
+        engine: &TaskEngine,
+        task: &Task,
+        warning: &DeadlineWarning,
+        deadline: f64,
+        now: f64,
+    ) -> Result<bool, EngineError> {
+        let input = PublishEventInput {
+            r#type: DEADLINE_WARNING_EVENT_TYPE.to_string(),
+            level: Level::Warn,
+            data: json!({
+                "remainingMs": (deadline - now).max(0.0) as u64,
+                "deadline": deadline,
+                "trigger": warning,
+            }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        };
+        match engine.publish_event(&task.id, input).await {
+            Ok(_) => Ok(true),
+            // Ended or gone since the listing.
+            Err(EngineError::TaskTerminal(_) | EngineError::TaskNotFound(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_task(ttl: Option<u64>, deadline_ms: Option<u64>) -> Task {
+        let mut task: Task = serde_json::from_value(json!({
+            "id": "task_01",
+            "status": "running",
+            "createdAt": 1_000_000.0,
+            "updatedAt": 1_000_000.0,
+        }))
+        .unwrap();
+        task.ttl = ttl;
+        task.deadline_ms = deadline_ms;
+        task
+    }
+
+    #[test]
+    fn deadline_prefers_deadline_ms_over_ttl() {
+        assert_eq!(task_deadline(&make_task(None, None)), None);
+        assert_eq!(task_deadline(&make_task(Some(60), None)), Some(1_060_000.0));
+        assert_eq!(
+            task_deadline(&make_task(Some(60), Some(10_000))),
+            Some(1_010_000.0)
+        );
+    }
+
+    #[test]
+    fn deadline_counts_from_scheduled_activation() {
+        let mut task = make_task(None, Some(10_000));
+        task.scheduled_for = Some(5_000_000.0);
+        assert_eq!(task_deadline(&task), Some(5_010_000.0));
+    }
+
+    #[test]
+    fn due_at_for_fraction_and_before_ms() {
+        let task = make_task(None, Some(100_000));
+        let fraction = DeadlineWarning::AtFraction { at_fraction: 0.8 };
+        let before = DeadlineWarning::BeforeMs { before_ms: 60_000 };
+        assert_eq!(fraction.due_at(&task), Some(1_080_000.0));
+        assert_eq!(before.due_at(&task), Some(1_040_000.0));
+        assert_eq!(fraction.due_at(&make_task(None, None)), None);
+    }
+
+    #[test]
+    fn warning_keys_and_config_shape() {
+        let warnings: Vec<DeadlineWarning> =
+            serde_json::from_value(json!([{ "atFraction": 0.8 }, { "beforeMs": 60000 }])).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                DeadlineWarning::AtFraction { at_fraction: 0.8 },
+                DeadlineWarning::BeforeMs { before_ms: 60_000 },
+            ]
+        );
+        assert_eq!(warnings[0].key(), "atFraction:0.8");
+        assert_eq!(warnings[1].key(), "beforeMs:60000");
+        assert_eq!(
+            serde_json::to_value(warnings[1]).unwrap(),
+            json!({ "beforeMs": 60000 })
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Trailing-edge debouncing of series broadcasts.
+//!
+//! A producer streaming tokens into an `accumulate` series publishes far
+//! more events than subscribers can render. When an event carries
+//! `broadcast_debounce_ms`, the engine still stores it at once but holds its
+//! broadcast: the first event of a series opens a window, later events in
+//! the window replace the held one, and the window's last event is broadcast
+//! when it closes. A held `accumulate` event is broadcast as a snapshot of
+//! the series total, so subscribers lose nothing to the skipped deltas.
+//!
+//! Only `accumulate` and `latest` series are debounced: their latest event
+//! stands for everything before it. A `series_end` event, and a task's
+//! terminal transition, broadcast whatever is held at once.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::types::{SeriesMode, TaskEvent};
+
+/// A window held open for one series of one task.
+#[derive(Debug)]
+struct Window {
+    /// When the window closes, in the engine clock's epoch milliseconds.
+    ends_at: f64,
+    /// Tells a window's timer apart from a later window's on the same series.
+    generation: u64,
+    /// The broadcast the window will send.
+    held: TaskEvent,
+}
+
+/// What to do with a series event's broadcast.
+#[derive(Debug)]
+pub(crate) enum Debounced {
+    /// Broadcast this event now.
+    Now(Box<TaskEvent>),
+    /// Held in a window. `Some` when the event opened it, with the
+    /// generation the window's timer must flush.
+    Held(Option<u64>),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct BroadcastDebouncer {
+    /// (task id, series id) → open window.
+    windows: Mutex<HashMap<(String, String), Window>>,
+    next_generation: AtomicU64,
+}
+
+impl BroadcastDebouncer {
+    /// Decides when `event`, just stored, is broadcast. `event` must belong
+    /// to a series [`debounces`] accepts.
+    pub(crate) fn offer(
+        &self,
+        event: TaskEvent,
+        debounce_ms: Option<u64>,
+        series_end: bool,
+        now: f64,
+    ) -> Debounced {
+        let key = window_key(&event);
+        let mut windows = self.windows.lock().unwrap();
+        if let Some(window) = windows.get_mut(&key) {
+            let held = folded(&window.held, event);
+            if series_end || now >= window.ends_at {
+                windows.remove(&key);
+                return Debounced::Now(Box::new(held));
+            }
+            window.held = held;
+            return Debounced::Held(None);
+        }
+        let window_ms = debounce_ms.unwrap_or(0);
+        if series_end || window_ms == 0 {
+            return Debounced::Now(Box::new(event));
+        }
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        windows.insert(
+            key,
+            Window {
+                ends_at: now + window_ms as f64,
+                generation,
+                held: snapshot(event),
+            },
+        );
+        Debounced::Held(Some(generation))
+    }
+
+    /// Closes the window `generation` opened, if it is still open, returning
+    /// its broadcast.
+    pub(crate) fn take(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        generation: u64,
+    ) -> Option<TaskEvent> {
+        let key = (task_id.to_string(), series_id.to_string());
+        let mut windows = self.windows.lock().unwrap();
+        if windows.get(&key)?.generation != generation {
+            return None;
+        }
+        windows.remove(&key).map(|window| window.held)
+    }
+
+    /// Closes every window of `task_id`, returning their broadcasts in index
+    /// order.
+    pub(crate) fn drain(&self, task_id: &str) -> Vec<TaskEvent> {
+        let mut windows = self.windows.lock().unwrap();
+        let keys: Vec<_> = windows
+            .keys()
+            .filter(|(task, _)| task == task_id)
+            .cloned()
+            .collect();
+        let mut held: Vec<TaskEvent> = keys
+            .iter()
+            .filter_map(|key| windows.remove(key))
+            .map(|window| window.held)
+            .collect();
+        held.sort_by_key(|event| event.index);
+        held
+    }
+
+    /// Number of open windows, for leak checks.
+    pub(crate) fn len(&self) -> usize {
+        self.windows.lock().unwrap().len()
+    }
+}
+
+/// Whether `event`'s broadcast may be debounced.
+pub(crate) fn debounces(event: &TaskEvent) -> bool {
+    event.series_id.is_some()
+        && matches!(
+            event.series_mode,
+            Some(SeriesMode::Accumulate | SeriesMode::Latest)
+        )
+}
+
+fn window_key(event: &TaskEvent) -> (String, String) {
+    (
+        event.task_id.clone(),
+        event.series_id.clone().unwrap_or_default(),
+    )
+}
+
+/// `event` as the broadcast of a window it closes after `held`. A `latest`
+/// rewrite keeps pointing at the event subscribers last saw.
+fn folded(held: &TaskEvent, event: TaskEvent) -> TaskEvent {
+    TaskEvent {
+        replaces_event_id: held.replaces_event_id.clone(),
+        ..snapshot(event)
+    }
+}
+
+/// `event` standing for its whole series: an `accumulate` delta is replaced
+/// by the series total.
+fn snapshot(event: TaskEvent) -> TaskEvent {
+    match (&event.series_mode, &event._accumulated_data) {
+        (Some(SeriesMode::Accumulate), Some(total)) => TaskEvent {
+            data: total.clone(),
+            series_snapshot: Some(true),
+            ..event
+        },
+        _ => event,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Level;
+    use serde_json::json;
+
+    fn delta(index: u64, total: &str) -> TaskEvent {
+        TaskEvent {
+            id: format!("e{index}"),
+            task_id: "t1".to_string(),
+            index,
+            timestamp: 0.0,
+            r#type: "llm.delta".to_string(),
+            level: Level::Info,
+            data: json!({ "delta": "x" }),
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::Accumulate),
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            _accumulated_data: Some(json!({ "delta": total })),
+        }
+    }
+
+    fn now_event(debounced: Debounced) -> TaskEvent {
+        match debounced {
+            Debounced::Now(event) => *event,
+            Debounced::Held(_) => panic!("expected an immediate broadcast"),
+        }
+    }
+
+    #[test]
+    fn without_a_window_events_broadcast_as_is() {
+        let debouncer = BroadcastDebouncer::default();
+        let event = now_event(debouncer.offer(delta(0, "x"), None, false, 0.0));
+        assert_eq!(event.data, json!({ "delta": "x" }));
+        assert_eq!(event.series_snapshot, None);
+        assert_eq!(debouncer.len(), 0);
+    }
+
+    #[test]
+    fn a_window_holds_the_latest_total_until_it_closes() {
+        let debouncer = BroadcastDebouncer::default();
+        let Debounced::Held(Some(generation)) =
+            debouncer.offer(delta(0, "x"), Some(50), false, 0.0)
+        else {
+            panic!("first event should open a window");
+        };
+        assert!(matches!(
+            debouncer.offer(delta(1, "xx"), Some(50), false, 10.0),
+            Debounced::Held(None)
+        ));
+
+        let held = debouncer.take("t1", "s1", generation).unwrap();
+        assert_eq!(held.index, 1);
+        assert_eq!(held.data, json!({ "delta": "xx" }));
+        assert_eq!(held.series_snapshot, Some(true));
+        assert!(debouncer.take("t1", "s1", generation).is_none());
+    }
+
+    #[test]
+    fn an_event_after_the_window_ends_closes_it() {
+        let debouncer = BroadcastDebouncer::default();
+        debouncer.offer(delta(0, "x"), Some(50), false, 0.0);
+        let event = now_event(debouncer.offer(delta(1, "xx"), Some(50), false, 50.0));
+        assert_eq!(event.index, 1);
+        assert_eq!(debouncer.len(), 0);
+    }
+
+    #[test]
+    fn series_end_closes_the_window() {
+        let debouncer = BroadcastDebouncer::default();
+        debouncer.offer(delta(0, "x"), Some(50), false, 0.0);
+        let event = now_event(debouncer.offer(delta(1, "xx"), Some(50), true, 1.0));
+        assert_eq!(event.data, json!({ "delta": "xx" }));
+        assert_eq!(debouncer.len(), 0);
+    }
+
+    #[test]
+    fn a_stale_timer_does_not_close_a_newer_window() {
+        let debouncer = BroadcastDebouncer::default();
+        let Debounced::Held(Some(first)) = debouncer.offer(delta(0, "x"), Some(50), false, 0.0)
+        else {
+            panic!("first event should open a window");
+        };
+        now_event(debouncer.offer(delta(1, "xx"), Some(50), true, 1.0));
+        debouncer.offer(delta(2, "xxx"), Some(50), false, 2.0);
+        assert!(debouncer.take("t1", "s1", first).is_none());
+        assert_eq!(debouncer.len(), 1);
+    }
+
+    #[test]
+    fn latest_rewrites_keep_the_first_replaced_id() {
+        let debouncer = BroadcastDebouncer::default();
+        let latest = |index: u64, replaces: &str| TaskEvent {
+            series_mode: Some(SeriesMode::Latest),
+            replaces_event_id: Some(replaces.to_string()),
+            _accumulated_data: None,
+            ..delta(index, "")
+        };
+        debouncer.offer(latest(1, "e0"), Some(50), false, 0.0);
+        let event = now_event(debouncer.offer(latest(2, "e1"), Some(50), true, 1.0));
+        assert_eq!(event.id, "e2");
+        assert_eq!(event.replaces_event_id.as_deref(), Some("e0"));
+        assert_eq!(event.series_snapshot, None);
+    }
+
+    #[test]
+    fn drain_returns_every_window_of_the_task_in_index_order() {
+        let debouncer = BroadcastDebouncer::default();
+        let other_series = TaskEvent {
+            series_id: Some("s2".to_string()),
+            ..delta(0, "y")
+        };
+        debouncer.offer(other_series, Some(50), false, 0.0);
+        debouncer.offer(delta(1, "x"), Some(50), false, 0.0);
+        debouncer.offer(
+            TaskEvent {
+                task_id: "t2".to_string(),
+                ..delta(0, "z")
+            },
+            Some(50),
+            false,
+            0.0,
+        );
+
+        let drained = debouncer.drain("t1");
+        assert_eq!(
+            drained.iter().map(|e| e.index).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(debouncer.len(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+//! RFC 6902 diffs of a task's `result` and `metadata`, attached as `diff` to
+//! the events that change them so subscribers can apply the change instead
+//! of re-reading the whole value.
+//!
+//! A diff is a JSON Patch against the task itself: paths start at
+//! `/result` or `/metadata`. One whose serialized size exceeds
+//! [`EventDiffs::max_bytes`] is replaced by
+//! `{"diffOmitted": true, "reason": "too-large"}`.
+
+use serde_json::{json, Value};
+
+use crate::types::Task;
+
+/// Published by [`TaskEngine::update_task`](crate::TaskEngine::update_task)
+/// when the update changed the task's result or metadata.
+pub const TASK_UPDATED_EVENT: &str = "taskcast:updated";
+
+/// Feature flag that, when configured, decides [`EventDiffs::enabled`] per
+/// task.
+pub const INCLUDE_DIFFS_FLAG: &str = "includeDiffs";
+
+/// Largest diff attached by default, in serialized bytes.
+pub const DEFAULT_MAX_DIFF_BYTES: usize = 64 * 1024;
+
+/// Whether and up to what size events carry diffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventDiffs {
+    pub enabled: bool,
+    pub max_bytes: usize,
+}
+
+impl Default for EventDiffs {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: DEFAULT_MAX_DIFF_BYTES,
+        }
+    }
+}
+
+impl EventDiffs {
+    /// The `diff` field for a change from `before` to `after`, both built
+    /// with [`diff_view`]. `None` when diffs are off or nothing changed.
+    pub fn diff(&self, before: &Value, after: &Value) -> Option<Value> {
+        if !self.enabled || before == after {
+            return None;
+        }
+        let patch = serde_json::to_value(json_patch::diff(before, after))
+            .expect("a JSON patch always serializes");
+        let size = serde_json::to_vec(&patch).map_or(usize::MAX, |bytes| bytes.len());
+        if size > self.max_bytes {
+            return Some(json!({ "diffOmitted": true, "reason": "too-large" }));
+        }
+        Some(patch)
+    }
+}
+
+/// The part of `task` a diff covers: its result, and its metadata too when
+/// `with_metadata` is set.
+pub fn diff_view(task: &Task, with_metadata: bool) -> Value {
+    if with_metadata {
+        json!({ "result": task.result, "metadata": task.metadata })
+    } else {
+        json!({ "result": task.result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_added_changed_and_removed_keys() {
+        let diffs = EventDiffs::default();
+        let before = json!({ "result": { "rows": 10, "file": "a.csv" } });
+        let after = json!({ "result": { "rows": 12, "errors": 1 } });
+        let Some(Value::Array(ops)) = diffs.diff(&before, &after) else {
+            panic!("expected a patch");
+        };
+        let mut ops: Vec<String> = ops
+            .iter()
+            .map(|op| {
+                format!(
+                    "{} {}",
+                    op["op"].as_str().unwrap(),
+                    op["path"].as_str().unwrap()
+                )
+            })
+            .collect();
+        ops.sort();
+        assert_eq!(
+            ops,
+            [
+                "add /result/errors",
+                "remove /result/file",
+                "replace /result/rows"
+            ]
+        );
+
+        let mut patched = before.clone();
+        let patch: json_patch::Patch =
+            serde_json::from_value(diffs.diff(&before, &after).unwrap()).unwrap();
+        json_patch::patch(&mut patched, &patch).unwrap();
+        assert_eq!(patched, after);
+    }
+
+    #[test]
+    fn no_diff_without_a_change_or_when_disabled() {
+        let value = json!({ "result": { "rows": 1 } });
+        assert_eq!(EventDiffs::default().diff(&value, &value), None);
+        let disabled = EventDiffs {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(disabled.diff(&value, &json!({ "result": null })), None);
+    }
+
+    #[test]
+    fn large_diffs_are_omitted() {
+        let diffs = EventDiffs {
+            enabled: true,
+            max_bytes: 64,
+        };
+        let after = json!({ "result": { "text": "x".repeat(100) } });
+        assert_eq!(
+            diffs.diff(&json!({ "result": null }), &after),
+            Some(json!({ "diffOmitted": true, "reason": "too-large" }))
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tokio::sync::Mutex as TokioMutex;
 
@@ -8,16 +10,55 @@ use crate::archive::{
     build_task_archive_restore_data, sanitize_task_archive_event, validate_task_archive,
     ArchiveError, TASK_ARCHIVE_SCHEMA, TASK_ARCHIVE_VERSION,
 };
-use crate::series::process_series;
+use crate::background::BackgroundTasks;
+use crate::runner::RunnerSupervisor;
+use crate::channels::{deliver_once, task_channel, type_channel, BroadcastChannels};
+use crate::coalesce::ReadCoalescer;
+use crate::completion::{completion_policy_violation, completion_verdict, MAX_COMPLETION_CHILDREN};
+use crate::compression::CompressionStats;
+use crate::conventions::{ConventionRegistry, DiscoveryOptions};
+use crate::debounce::{debounces, BroadcastDebouncer, Debounced};
+use crate::diff::{diff_view, EventDiffs, INCLUDE_DIFFS_FLAG, TASK_UPDATED_EVENT};
+use crate::event_stream::{terminal_status_of, Subscription, TaskEventStream};
+use crate::filter::{apply_event_query, resolve_filter, EventTypeRules, FilterPresetError};
+use crate::flags::{FlagContext, FlagEvaluator};
+use crate::forward::forwarded_event_input;
+use crate::integrity::IntegrityMonitor;
+use crate::lifecycle::{TaskLifecycle, TaskLifecycleEvent};
+use crate::negative_cache::{NegativeCache, NegativeCacheStats};
+use crate::point_in_time::reconstruct_task_at;
+use crate::query_stats::QueryStatsSnapshot;
+use crate::read_routing::{LatencyEwma, ReadRouter, ReadRoutingConfig};
+use crate::reader::TaskcastReader;
+use crate::redaction::{tombstone, DeleteEventsInput, DeletedEvents, REDACTED_EVENT_TYPE};
+use crate::replay::{replay_stream, EventChunks, ReplayOptions, ReplaySource};
+use crate::request_deadline::RequestCounters;
+use crate::retry::{
+    retry_attempt, retry_of, successor_input, Clock, SystemClock, TaskAttempt, TaskAttempts,
+    RETRY_SCHEDULED_EVENT,
+};
+use crate::series::{
+    apply_json_patch, attach_accumulated_data, collapse_accumulate_series,
+    collapse_json_patch_series, process_series, series_document, store_series_document,
+    SeriesLimits, SeriesOverLimit,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::state_machine::{can_transition, is_suspended, is_terminal};
+use crate::store_error::PartialTaskGroup;
+use crate::state_machine::{allowed_transitions, can_transition, is_suspended, is_terminal};
 use crate::types::{
-    AssignMode, BlockedRequest, BroadcastProvider, CleanupConfig, DisconnectPolicy,
-    EventQueryOptions, Level, LongTermStore, SeriesMode, ShortTermStore, Task, TaskArchive,
-    TaskArchiveImportOptions, TaskArchiveImportResult, TaskAuthConfig, TaskError, TaskEvent,
-    TaskFilter, TaskStatus, TaskcastHooks, WebhookConfig,
+    AssignMode, BlockedRequest, BroadcastProvider, CleanupConfig, CompletionMode, CompletionPolicy,
+    DisconnectPolicy, ErrorContext, EventQueryOptions, EventSink, EventTypeCounts, ForwardRule,
+    Level, ListTasksQuery, LongTermStore, NewTaskGroupOutcome, NewTaskOutcome, OutcomeQuery,
+    PoolHealth,
+    RetryPolicy, RetrySchedule, ScanToken, SeriesFormat, SeriesMode, SeriesSummary, ShortTermStore,
+    SinceCursor,
+    SubscribeFilter, Task, TaskArchive, TaskArchiveImportOptions, TaskArchiveImportResult,
+    TaskAuthConfig, TaskCursor, TaskError, TaskEvent, TaskFilter, TaskOrigin, TaskOriginKind,
+    TaskOutcome, TaskPage, TaskStatus, TaskTransitions, TaskcastHooks, WebhookConfig,
+    WebhookGroupPolicy,
 };
+use crate::write_shaping::{WriteShaper, WriteShapingConfig, WriteShapingStats};
 
 // ─── Error ───────────────────────────────────────────────────────────────────
 
@@ -29,18 +70,47 @@ pub enum EngineError {
     #[error("Task already exists: {0}")]
     TaskConflict(String),
 
+    /// The task's version was not the one the caller expected, or saves
+    /// kept losing races with other writers until the retries ran out.
+    #[error("Task was modified concurrently: {0}")]
+    ConcurrentModification(String),
+
+    /// Lost a creation race for a caller-supplied id. `existing` is the
+    /// winner's task, or `None` if its write has not landed yet.
+    #[error("Task already exists: {task_id}")]
+    TaskAlreadyExists {
+        task_id: String,
+        existing: Option<Box<Task>>,
+    },
+
     #[error("{0}")]
     InvalidInput(String),
 
+    /// A task group could not be created, nor fully removed again.
+    #[error("{0}")]
+    PartialTaskGroup(PartialTaskGroup),
+
     #[error("Invalid transition: {from:?} \u{2192} {to:?}")]
     InvalidTransition { from: TaskStatus, to: TaskStatus },
 
     #[error("Cannot publish to task in terminal status: {0:?}")]
     TaskTerminal(TaskStatus),
 
+    /// A `json-patch` series event whose patch does not apply to the series
+    /// document.
+    #[error("{0}")]
+    InvalidSeriesPatch(String),
+
+    /// An event would start a series past the task's series cap.
+    #[error("Task {task_id} already has {limit} series")]
+    SeriesLimitExceeded { task_id: String, limit: usize },
+
     #[error("{0}")]
     Archive(#[from] ArchiveError),
 
+    #[error("{0}")]
+    FilterPreset(#[from] FilterPresetError),
+
     #[error("{0}")]
     Store(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -61,6 +131,41 @@ pub struct CreateTaskInput {
     pub assign_mode: Option<AssignMode>,
     pub cost: Option<u32>,
     pub disconnect_policy: Option<DisconnectPolicy>,
+    pub filters: Option<HashMap<String, SubscribeFilter>>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub group_policy: Option<WebhookGroupPolicy>,
+    pub forward_to: Option<ForwardRule>,
+    /// Create the task `scheduled` and make it `pending` at this time, in
+    /// epoch milliseconds.
+    pub scheduled_for: Option<f64>,
+    /// Milliseconds the task is allotted, for deadline warnings.
+    pub deadline_ms: Option<u64>,
+    /// Who is creating the task; `user` when unset.
+    pub origin: Option<TaskOrigin>,
+    /// Creates the task as a child of this one, which must exist and not
+    /// have ended, appending it to the parent's completion policy.
+    pub parent_id: Option<String>,
+    /// Ends the task automatically when its children end.
+    pub completion_policy: Option<CompletionPolicy>,
+}
+
+/// A parent task and its children, created all or none by
+/// [`TaskEngine::create_task_group`].
+#[derive(Default)]
+pub struct CreateTaskGroupInput {
+    pub parent: CreateTaskInput,
+    pub children: Vec<CreateTaskInput>,
+    /// Makes every child a child of the parent, as if created with its
+    /// `parent_id`. Children the parent's completion policy lists are
+    /// linked either way.
+    pub link_children: bool,
+}
+
+/// The tasks [`TaskEngine::create_task_group`] created.
+#[derive(Debug, Clone)]
+pub struct TaskGroup {
+    pub parent: Task,
+    pub children: Vec<Task>,
 }
 
 pub struct PublishEventInput {
@@ -70,6 +175,61 @@ pub struct PublishEventInput {
     pub series_id: Option<String>,
     pub series_mode: Option<crate::types::SeriesMode>,
     pub series_acc_field: Option<String>,
+    pub labels: Option<HashMap<String, String>>,
+    /// Hold this event's broadcast for up to this many milliseconds so later
+    /// events of its `accumulate` or `latest` series can take its place. The
+    /// event is stored at once either way.
+    pub broadcast_debounce_ms: Option<u64>,
+    /// Marks the last event of its series: a held broadcast goes out now.
+    pub series_end: bool,
+}
+
+/// Cardinality limits enforced on `PublishEventInput::labels`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LabelLimits {
+    pub max_labels: usize,
+    pub max_key_length: usize,
+    pub max_value_length: usize,
+}
+
+impl Default for LabelLimits {
+    fn default() -> Self {
+        Self {
+            max_labels: 16,
+            max_key_length: 64,
+            max_value_length: 256,
+        }
+    }
+}
+
+impl LabelLimits {
+    /// Validate a label set against these limits.
+    pub fn validate(&self, labels: &HashMap<String, String>) -> Result<(), EngineError> {
+        if labels.len() > self.max_labels {
+            return Err(EngineError::InvalidInput(format!(
+                "Too many labels: {} (max {})",
+                labels.len(),
+                self.max_labels
+            )));
+        }
+        for (key, value) in labels {
+            if key.is_empty() || key.len() > self.max_key_length {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid label key length: {} (must be 1-{})",
+                    key.len(),
+                    self.max_key_length
+                )));
+            }
+            if value.len() > self.max_value_length {
+                return Err(EngineError::InvalidInput(format!(
+                    "Label value for \"{key}\" too long: {} (max {})",
+                    value.len(),
+                    self.max_value_length
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -81,6 +241,48 @@ pub struct TransitionPayload {
     pub resume_after_ms: Option<f64>,
     pub blocked_request: Option<BlockedRequest>,
     pub ttl: Option<u64>,
+    /// Replaces the task's filter presets; an empty map removes them.
+    pub filters: Option<HashMap<String, SubscribeFilter>>,
+}
+
+/// What [`TaskEngine::apply_replicated_task`] did with a replicated task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicatedTaskOutcome {
+    /// The task was not here and has been created.
+    Created,
+    /// The copy replaced the task here.
+    Updated,
+    /// The copy matched the task here or was older, and was ignored.
+    Unchanged,
+}
+
+/// Most indices a replicated event may skip past the task's next index.
+pub const MAX_REPLICATED_INDEX_GAP: u64 = 10_000;
+
+/// Times a task save is attempted before it gives up on other writers with
+/// [`EngineError::ConcurrentModification`].
+pub const MAX_SAVE_ATTEMPTS: u32 = 5;
+
+/// Error code [`TaskEngine::cancel_task`] records on the tasks it cancels.
+pub const CANCELLED_ERROR_CODE: &str = "CANCELLED";
+
+/// Page size [`TaskEngine::query_tasks`] reads the short-term store in when
+/// the query has no limit.
+const LIST_TASKS_CHUNK: usize = 500;
+
+/// The id, index and timestamp a replicated event was given at its origin.
+struct ReplicatedOrigin {
+    id: String,
+    index: u64,
+    timestamp: f64,
+}
+
+/// What [`TaskEngine::connect_live`] found once subscribed.
+enum LiveConnection {
+    /// The task is still running; its live events arrive on the receiver.
+    Tail(tokio::sync::mpsc::UnboundedReceiver<TaskEvent>, Subscription),
+    /// The task was terminal, with this status.
+    Ended(TaskStatus),
 }
 
 // ─── TaskEngineOptions ───────────────────────────────────────────────────────
@@ -90,6 +292,14 @@ pub struct TaskEngineOptions {
     pub broadcast: Arc<dyn BroadcastProvider>,
     pub long_term_store: Option<Arc<dyn LongTermStore>>,
     pub hooks: Option<Arc<dyn TaskcastHooks>>,
+    /// Limits applied to published event labels. `None` uses `LabelLimits::default()`.
+    pub label_limits: Option<LabelLimits>,
+    /// Receive every published event after it is broadcast.
+    pub sinks: Vec<Arc<dyn EventSink>>,
+    /// Share one store read between concurrent `get_task` calls for a task,
+    /// and likewise between concurrent `get_events` calls without options.
+    /// `None` enables it; embedders with their own caching may turn it off.
+    pub coalesce_reads: Option<bool>,
 }
 
 // ─── TaskEngine ──────────────────────────────────────────────────────────────
@@ -107,26 +317,253 @@ pub struct TaskEngine {
     broadcast: Arc<dyn BroadcastProvider>,
     long_term_store: Option<Arc<dyn LongTermStore>>,
     hooks: Option<Arc<dyn TaskcastHooks>>,
+    label_limits: LabelLimits,
+    sinks: Vec<Arc<dyn EventSink>>,
+    task_reads: Option<ReadCoalescer<Option<Task>>>,
+    history_reads: Option<ReadCoalescer<Vec<TaskEvent>>>,
     transition_listeners: Mutex<Vec<TransitionListener>>,
     creation_listeners: Mutex<Vec<CreationListener>>,
     /// Per-task mutex to serialize `emit` calls, ensuring events are stored
     /// in the same order as their atomically-assigned indices.
-    emit_locks: Mutex<HashMap<String, Arc<TokioMutex<()>>>>,
+    emit_locks: Arc<Mutex<HashMap<String, Arc<TokioMutex<()>>>>>,
+    lifecycle: TaskLifecycle,
+    background: BackgroundTasks,
+    runners: RunnerSupervisor,
+    read_router: Arc<ReadRouter>,
+    request_counters: Arc<RequestCounters>,
+    negative_cache: Option<NegativeCache>,
+    diffs: EventDiffs,
+    flags: Arc<FlagEvaluator>,
+    event_types: EventTypeRules,
+    clock: Arc<dyn Clock>,
+    channels: BroadcastChannels,
+    debouncer: Arc<BroadcastDebouncer>,
+    write_shaper: Option<Arc<WriteShaper>>,
+    series_limits: SeriesLimits,
+    conventions: Option<Arc<ConventionRegistry>>,
 }
 
 impl TaskEngine {
     pub fn new(opts: TaskEngineOptions) -> Self {
+        let coalesce_reads = opts.coalesce_reads.unwrap_or(true);
+        let emit_locks: Arc<Mutex<HashMap<String, Arc<TokioMutex<()>>>>> = Arc::default();
+        let lifecycle = TaskLifecycle::new();
+        // No more events can be published to a terminal task (publish_event
+        // rejects), so its lock is unused. A reopened task lazily recreates
+        // the entry on its next emit.
+        let locks = Arc::clone(&emit_locks);
+        lifecycle.subscribe(Arc::new(move |task_id, _| {
+            locks.lock().unwrap().remove(task_id);
+        }));
+        // A terminal transition has already broadcast held events; a deleted
+        // or expired task has no one left to receive them.
+        let debouncer = Arc::new(BroadcastDebouncer::default());
+        let held = Arc::clone(&debouncer);
+        lifecycle.subscribe(Arc::new(move |task_id, _| {
+            held.drain(task_id);
+        }));
+        let background = BackgroundTasks::new(opts.hooks.clone());
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         Self {
             short_term_store: opts.short_term_store,
             broadcast: opts.broadcast,
             long_term_store: opts.long_term_store,
+            runners: RunnerSupervisor::new(
+                background.clone(),
+                opts.hooks.clone(),
+                Arc::clone(&clock),
+            ),
+            background,
             hooks: opts.hooks,
+            label_limits: opts.label_limits.unwrap_or_default(),
+            sinks: opts.sinks,
+            task_reads: coalesce_reads.then(ReadCoalescer::default),
+            history_reads: coalesce_reads.then(ReadCoalescer::default),
             transition_listeners: Mutex::new(Vec::new()),
             creation_listeners: Mutex::new(Vec::new()),
-            emit_locks: Mutex::new(HashMap::new()),
+            emit_locks,
+            lifecycle,
+            read_router: Arc::new(ReadRouter::default()),
+            request_counters: Arc::default(),
+            negative_cache: None,
+            diffs: EventDiffs::default(),
+            flags: Arc::default(),
+            event_types: EventTypeRules::default(),
+            clock,
+            channels: BroadcastChannels::default(),
+            debouncer,
+            write_shaper: None,
+            series_limits: SeriesLimits::default(),
+            conventions: None,
+        }
+    }
+
+    /// Sets where terminal tasks and their history are read from. Without
+    /// it the short-term store is always read first.
+    pub fn with_read_routing(mut self, config: ReadRoutingConfig) -> Self {
+        self.read_router = Arc::new(ReadRouter::new(config));
+        self
+    }
+
+    /// Remembers task ids found in no store for `ttl`, so repeated lookups
+    /// of a missing task skip the stores. Creating or importing the task on
+    /// this instance forgets it at once; other instances may keep reporting
+    /// it missing for up to `ttl`. Off by default.
+    pub fn with_negative_cache(mut self, ttl: Duration) -> Self {
+        self.negative_cache = Some(NegativeCache::new(ttl));
+        self
+    }
+
+    /// Sets whether status and update events carry a `diff` of the task's
+    /// result and metadata, and its size cap. On by default.
+    pub fn with_event_diffs(mut self, diffs: EventDiffs) -> Self {
+        self.diffs = diffs;
+        self
+    }
+
+    /// Sets the feature flags behavior switches consult, shared with
+    /// whatever else evaluates them. Defaults to none configured.
+    pub fn with_flags(mut self, flags: Arc<FlagEvaluator>) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// The feature flags this engine's switches consult.
+    pub fn flags(&self) -> &Arc<FlagEvaluator> {
+        &self.flags
+    }
+
+    /// The diff settings for events of `task`, with `includeDiffs` decided
+    /// by its flag when one is configured.
+    fn diffs_for(&self, task: &Task) -> EventDiffs {
+        let ctx = FlagContext {
+            task_id: &task.id,
+            task_type: task.r#type.as_deref(),
+            sub: None,
+        };
+        EventDiffs {
+            enabled: self
+                .flags
+                .is_enabled(INCLUDE_DIFFS_FLAG, &ctx, self.diffs.enabled),
+            ..self.diffs
         }
     }
 
+    /// Sets what published event types may look like and how types are
+    /// normalized. Defaults to 256 bytes, matched exactly.
+    pub fn with_event_types(mut self, rules: EventTypeRules) -> Self {
+        self.event_types = rules;
+        self
+    }
+
+    /// A read-only view of this engine, for code that should see tasks but
+    /// never change them.
+    pub fn reader(self: &Arc<Self>) -> TaskcastReader {
+        TaskcastReader::from_engine(Arc::clone(self))
+    }
+
+    /// The rules published event types are checked and normalized with.
+    pub fn event_types(&self) -> EventTypeRules {
+        self.event_types
+    }
+
+    /// Sets the time source retry due times are computed from. Defaults to
+    /// the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.runners = RunnerSupervisor::new(
+            self.background.clone(),
+            self.hooks.clone(),
+            Arc::clone(&clock),
+        );
+        self.clock = clock;
+        self
+    }
+
+    /// Sets which broadcast channels events are published to besides
+    /// `task.{taskId}`. Defaults to none.
+    pub fn with_broadcast_channels(mut self, channels: BroadcastChannels) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Queues long-term event writes and starts them at a token-bucket
+    /// rate, so a burst of events does not flood the long-term store. Task
+    /// records are still saved inline. Off by default.
+    pub fn with_write_shaping(mut self, config: WriteShapingConfig) -> Self {
+        self.write_shaper = Some(Arc::new(WriteShaper::new(config)));
+        self
+    }
+
+    /// Sets how many series a task may keep state for, and what happens to
+    /// an event that would start one more. Defaults to 1000, rejected.
+    pub fn with_series_limits(mut self, limits: SeriesLimits) -> Self {
+        self.series_limits = limits;
+        self
+    }
+
+    /// Samples published events into a catalog of the event types each
+    /// task type publishes. Off by default.
+    pub fn with_discovery(mut self, options: DiscoveryOptions) -> Self {
+        self.conventions = Some(Arc::new(ConventionRegistry::new(options)));
+        self
+    }
+
+    /// The event type catalog's sampler, or `None` while discovery is off.
+    pub fn conventions(&self) -> Option<&Arc<ConventionRegistry>> {
+        self.conventions.as_ref()
+    }
+
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub fn hooks(&self) -> Option<&Arc<dyn TaskcastHooks>> {
+        self.hooks.as_ref()
+    }
+
+    pub fn short_term_store(&self) -> &Arc<dyn ShortTermStore> {
+        &self.short_term_store
+    }
+
+    pub fn long_term_store(&self) -> Option<&Arc<dyn LongTermStore>> {
+        self.long_term_store.as_ref()
+    }
+
+    /// Store latency estimates and the read preference they feed.
+    pub fn read_router(&self) -> &ReadRouter {
+        &self.read_router
+    }
+
+    /// How deadline-aware requests ended early.
+    pub fn request_counters(&self) -> &Arc<RequestCounters> {
+        &self.request_counters
+    }
+
+    /// Negative cache counters, or `None` when the cache is off.
+    pub fn negative_cache_stats(&self) -> Option<NegativeCacheStats> {
+        self.negative_cache.as_ref().map(NegativeCache::stats)
+    }
+
+    /// Queue depth and shed count of long-term write shaping. `None` while
+    /// shaping is off.
+    pub fn write_shaping_stats(&self) -> Option<WriteShapingStats> {
+        self.write_shaper.as_deref().map(WriteShaper::stats)
+    }
+
+    /// Supervised spawner for this engine's fire-and-forget work. Callers
+    /// outside the engine (workers, schedulers, transports) should spawn
+    /// through it too so panics are reported and shutdown can drain.
+    pub fn background(&self) -> &BackgroundTasks {
+        &self.background
+    }
+
+    /// Supervisor of this engine's periodic runners (retries, activations,
+    /// cleanup, ...), which reports their status and lets them be paused,
+    /// resumed or run on demand.
+    pub fn runners(&self) -> &RunnerSupervisor {
+        &self.runners
+    }
+
     /// Register a callback that fires whenever a task transitions status.
     /// Also fires when a task is created (with from = to = Pending).
     pub fn add_transition_listener(&self, listener: TransitionListener) {
@@ -145,7 +582,206 @@ impl TaskEngine {
         listeners.retain(|l| !Arc::ptr_eq(l, listener));
     }
 
+    /// Notifications for per-task bookkeeping: subscribe to drop state kept
+    /// by task id when a task ends, is deleted or expires.
+    pub fn lifecycle(&self) -> &TaskLifecycle {
+        &self.lifecycle
+    }
+
+    /// Number of tasks with a per-task emit lock, for leak checks.
+    pub fn emit_lock_count(&self) -> usize {
+        self.emit_locks.lock().unwrap().len()
+    }
+
+    /// Number of series with a debounced broadcast held, for leak checks.
+    pub fn held_broadcast_count(&self) -> usize {
+        self.debouncer.len()
+    }
+
     pub async fn create_task(&self, input: CreateTaskInput) -> Result<Task, EngineError> {
+        let explicit_id = input.id.is_some();
+        let task = self.prepare_task(input).await?;
+        let adopted = self.check_adoptable_children(&task).await?;
+        let linked = match task.parent_id {
+            Some(ref parent_id) => self.link_to_parent(parent_id, &task.id).await?,
+            None => false,
+        };
+
+        // Caller-supplied ids can collide across instances, so they go through
+        // the store's exclusive write; generated ULIDs cannot.
+        let saved = if explicit_id {
+            self.short_term_store.save_new_task(task.clone()).await
+        } else {
+            self.short_term_store
+                .save_task(task.clone())
+                .await
+                .map(|()| NewTaskOutcome::Created)
+        };
+        if !matches!(saved, Ok(NewTaskOutcome::Created)) && linked {
+            if let Some(ref parent_id) = task.parent_id {
+                self.unlink_from_parent(parent_id, &task.id).await;
+            }
+        }
+        if let NewTaskOutcome::AlreadyExists(existing) = saved? {
+            return Err(EngineError::TaskAlreadyExists {
+                task_id: task.id,
+                existing,
+            });
+        }
+        if let Some(ref cache) = self.negative_cache {
+            cache.invalidate(&task.id);
+        }
+
+        if let Some(ref long_term_store) = self.long_term_store {
+            long_term_store.save_task(task.clone()).await?;
+        }
+        self.finish_created(&task).await?;
+        if !adopted.is_empty() {
+            self.adopt_children(&task, &adopted).await;
+        }
+
+        Ok(task)
+    }
+
+    /// Creates a parent task and its children all or none: if any id is
+    /// taken or the short-term write fails, none of them is created. The
+    /// long-term copies follow the short-term write, and a failure there is
+    /// reported through `on_unhandled_error` instead of failing the group.
+    ///
+    /// Group members cannot have a `parent_id` of their own, and the
+    /// parent's completion policy may only list the group's children, so
+    /// creating the group changes no existing task. When the short-term
+    /// write fails part way and cannot be undone, the error is
+    /// [`EngineError::PartialTaskGroup`], naming the tasks that may exist.
+    pub async fn create_task_group(
+        &self,
+        input: CreateTaskGroupInput,
+    ) -> Result<TaskGroup, EngineError> {
+        let CreateTaskGroupInput {
+            parent,
+            children,
+            link_children,
+        } = input;
+        if parent.parent_id.is_some() || children.iter().any(|child| child.parent_id.is_some()) {
+            return Err(EngineError::InvalidInput(
+                "Invalid task group: parentId is not supported, use linkChildren.".to_string(),
+            ));
+        }
+        let mut parent = self.prepare_task(parent).await?;
+        let mut prepared = Vec::with_capacity(children.len());
+        for child in children {
+            prepared.push(self.prepare_task(child).await?);
+        }
+        let mut children = prepared;
+
+        let mut ids = HashSet::new();
+        for task in std::iter::once(&parent).chain(&children) {
+            if !ids.insert(task.id.as_str()) {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid task group: task id {} is used twice.",
+                    task.id
+                )));
+            }
+        }
+        for task in &children {
+            if task
+                .completion_policy
+                .as_ref()
+                .is_some_and(|policy| !policy.child_ids.is_empty())
+            {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid task group: child {} cannot list childIds.",
+                    task.id
+                )));
+            }
+        }
+        if let Some(ref mut policy) = parent.completion_policy {
+            if let Some(child_id) = policy
+                .child_ids
+                .iter()
+                .find(|child_id| !children.iter().any(|child| child.id == **child_id))
+            {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid completionPolicy: {child_id} is not a child in the task group."
+                )));
+            }
+            if link_children && policy.mode != CompletionMode::Manual {
+                for child in &children {
+                    if !policy.child_ids.contains(&child.id) {
+                        policy.child_ids.push(child.id.clone());
+                    }
+                }
+            }
+        }
+        if let Some(violation) =
+            completion_policy_violation(&parent.id, None, parent.completion_policy.as_ref())
+        {
+            return Err(EngineError::InvalidInput(violation));
+        }
+        let listed = parent
+            .completion_policy
+            .as_ref()
+            .map(|policy| policy.child_ids.clone())
+            .unwrap_or_default();
+        for child in &mut children {
+            if link_children || listed.contains(&child.id) {
+                child.parent_id = Some(parent.id.clone());
+            }
+        }
+
+        let mut group = Vec::with_capacity(children.len() + 1);
+        group.push(parent.clone());
+        group.extend(children.iter().cloned());
+        match self.short_term_store.save_new_task_group(group).await {
+            Ok(NewTaskGroupOutcome::Created) => {}
+            Ok(NewTaskGroupOutcome::AlreadyExists { task_id, existing }) => {
+                return Err(EngineError::TaskAlreadyExists { task_id, existing });
+            }
+            Err(err) => {
+                let partial = match err.downcast::<PartialTaskGroup>() {
+                    Ok(partial) => *partial,
+                    Err(err) => return Err(EngineError::Store(err)),
+                };
+                if let Some(ref hooks) = self.hooks {
+                    hooks.on_unhandled_error(
+                        &partial,
+                        &ErrorContext {
+                            operation: "task_group.compensate".to_string(),
+                            task_id: Some(parent.id.clone()),
+                        },
+                    );
+                }
+                return Err(EngineError::PartialTaskGroup(partial));
+            }
+        }
+
+        for task in std::iter::once(&parent).chain(&children) {
+            if let Some(ref cache) = self.negative_cache {
+                cache.invalidate(&task.id);
+            }
+            let Some(ref long_term_store) = self.long_term_store else {
+                continue;
+            };
+            if let Err(err) = long_term_store.save_task(task.clone()).await {
+                if let Some(ref hooks) = self.hooks {
+                    hooks.on_unhandled_error(
+                        err.as_ref(),
+                        &ErrorContext {
+                            operation: "task_group.long_term".to_string(),
+                            task_id: Some(task.id.clone()),
+                        },
+                    );
+                }
+            }
+        }
+        for task in std::iter::once(&parent).chain(&children) {
+            self.finish_created(task).await?;
+        }
+        Ok(TaskGroup { parent, children })
+    }
+
+    /// Validates `input` and builds the task it creates, unsaved.
+    async fn prepare_task(&self, input: CreateTaskInput) -> Result<Task, EngineError> {
         if let Some(ttl) = input.ttl {
             if ttl == 0 {
                 return Err(EngineError::InvalidInput(
@@ -153,17 +789,25 @@ impl TaskEngine {
                 ));
             }
         }
-
-        let id = input
-            .id
-            .clone()
-            .unwrap_or_else(|| ulid::Ulid::new().to_string());
-
-        // Check for duplicate user-supplied IDs
-        if input.id.is_some() {
-            let existing = self.short_term_store.get_task(&id).await?;
-            if existing.is_some() {
-                return Err(EngineError::TaskConflict(id));
+        if input.deadline_ms == Some(0) {
+            return Err(EngineError::InvalidInput(
+                "Invalid deadlineMs: 0. It must be a positive number.".to_string(),
+            ));
+        }
+        if input
+            .retry_policy
+            .as_ref()
+            .is_some_and(|policy| policy.max_attempts == 0)
+        {
+            return Err(EngineError::InvalidInput(
+                "Invalid retryPolicy: maxAttempts must be at least 1.".to_string(),
+            ));
+        }
+        if let Some(at) = input.scheduled_for {
+            if !at.is_finite() || at < 0.0 {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid scheduledFor: {at}. It must be epoch milliseconds."
+                )));
             }
         }
 
@@ -173,14 +817,16 @@ impl TaskEngine {
             .clone()
             .unwrap_or_else(|| ulid::Ulid::new().to_string());
 
-        // Check for duplicate explicit ID
-        if input.id.is_some() && self.short_term_store.get_task(&id).await?.is_some() {
-            return Err(EngineError::TaskConflict(id));
-        }
-
-        let task = Task {
+        // A scheduled task waits, without a TTL running, for the scheduler
+        // runner to make it pending.
+        let status = if input.scheduled_for.is_some() {
+            TaskStatus::Scheduled
+        } else {
+            TaskStatus::Pending
+        };
+        let mut task = Task {
             id,
-            status: TaskStatus::Pending,
+            status,
             created_at: now,
             updated_at: now,
             r#type: input.r#type,
@@ -201,27 +847,55 @@ impl TaskEngine {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: input.filters,
+            retry_policy: input.retry_policy,
+            group_policy: input.group_policy,
+            forward_to: input.forward_to,
+            scheduled_for: input.scheduled_for,
+            deadline_ms: input.deadline_ms,
+            origin: Some(input.origin.unwrap_or_default()),
+            parent_id: input.parent_id,
+            completion_policy: input.completion_policy,
+            version: 1,
         };
-
-        self.short_term_store.save_task(task.clone()).await?;
-
-        if let Some(ref long_term_store) = self.long_term_store {
-            long_term_store.save_task(task.clone()).await?;
+        normalize_task_types(&self.event_types, &mut task);
+        validate_webhook_presets(&task)?;
+        if let Some(ref rule) = task.forward_to {
+            self.check_forward_cycle(&task.id, rule).await?;
+        }
+        if let Some(violation) = completion_policy_violation(
+            &task.id,
+            task.parent_id.as_deref(),
+            task.completion_policy.as_ref(),
+        ) {
+            return Err(EngineError::InvalidInput(violation));
         }
+        Ok(task)
+    }
 
-        if let Some(ttl) = task.ttl {
-            self.short_term_store.set_ttl(&task.id, ttl).await?;
+    /// What follows the saves of a created task: its activation or TTL,
+    /// sinks, hooks and listeners.
+    async fn finish_created(&self, task: &Task) -> Result<(), EngineError> {
+        match task.scheduled_for {
+            Some(at) => self.short_term_store.save_activation(&task.id, at).await?,
+            None => {
+                if let Some(ttl) = task.ttl {
+                    self.short_term_store.set_ttl(&task.id, ttl).await?;
+                }
+            }
         }
+        self.sink_task(task).await;
 
         if let Some(ref hooks) = self.hooks {
-            hooks.on_task_created(&task);
+            hooks.on_task_created(task);
         }
 
-        // Fire transition listeners for task creation (pending → pending)
+        // Fire transition listeners for task creation (pending → pending, or
+        // scheduled → scheduled)
         {
             let listeners = self.transition_listeners.lock().unwrap();
             for listener in listeners.iter() {
-                listener(&task, &TaskStatus::Pending, &TaskStatus::Pending);
+                listener(task, &task.status, &task.status);
             }
         }
 
@@ -231,106 +905,396 @@ impl TaskEngine {
         {
             let listeners: Vec<CreationListener> = self.creation_listeners.lock().unwrap().clone();
             for listener in &listeners {
-                listener(&task);
+                listener(task);
             }
         }
 
-        Ok(task)
+        Ok(())
     }
 
     pub async fn get_task(&self, task_id: &str) -> Result<Option<Task>, EngineError> {
-        let from_short = self.short_term_store.get_task(task_id).await?;
+        let Some(ref cache) = self.negative_cache else {
+            return self.read_task_coalesced(task_id).await;
+        };
+        if cache.contains(task_id, self.clock.now_ms()) {
+            return Ok(None);
+        }
+        let generation = cache.generation();
+        let task = self.read_task_coalesced(task_id).await?;
+        if task.is_none() {
+            cache.insert(task_id, generation, self.clock.now_ms());
+        }
+        Ok(task)
+    }
+
+    async fn read_task_coalesced(&self, task_id: &str) -> Result<Option<Task>, EngineError> {
+        match self.task_reads {
+            Some(ref reads) => reads.run(task_id, self.read_task(task_id)).await,
+            None => self.read_task(task_id).await,
+        }
+    }
+
+    async fn read_task(&self, task_id: &str) -> Result<Option<Task>, EngineError> {
+        if let Some(task) = self.terminal_task_from_long_term(task_id).await? {
+            self.read_router.note_long_term_read();
+            return Ok(Some(task));
+        }
+        let from_short = timed(
+            self.read_router.short_term_latency(),
+            self.short_term_store.get_task(task_id),
+        )
+        .await?;
         if from_short.is_some() {
             return Ok(from_short);
         }
         if let Some(ref long_term_store) = self.long_term_store {
-            return Ok(long_term_store.get_task(task_id).await?);
+            return Ok(timed(
+                self.read_router.long_term_latency(),
+                long_term_store.get_task(task_id),
+            )
+            .await?);
         }
         Ok(None)
     }
 
-    pub async fn transition_task(
+    /// The task from the long-term store, if the read preference currently
+    /// favours it and the task is terminal there. Non-terminal tasks are
+    /// always read from the short-term store for freshness.
+    async fn terminal_task_from_long_term(
         &self,
         task_id: &str,
-        to: TaskStatus,
-        payload: Option<TransitionPayload>,
-    ) -> Result<Task, EngineError> {
-        let task = self
-            .get_task(task_id)
-            .await?
-            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+    ) -> Result<Option<Task>, EngineError> {
+        let Some(ref long_term_store) = self.long_term_store else {
+            return Ok(None);
+        };
+        if !self.read_router.prefer_long_term() {
+            return Ok(None);
+        }
+        let task = timed(
+            self.read_router.long_term_latency(),
+            long_term_store.get_task(task_id),
+        )
+        .await?;
+        Ok(task.filter(|task| is_terminal(&task.status)))
+    }
 
-        let from = task.status.clone();
+    /// Reads the task, derives its next state with `change` and saves that
+    /// unless another writer saved the task since the read, in which case it
+    /// reads again and retries, up to [`MAX_SAVE_ATTEMPTS`] times. The saved
+    /// task gets the next `version`. Returns the task as read and as saved.
+    async fn save_with_retry<F>(
+        &self,
+        task_id: &str,
+        expected_version: Option<u64>,
+        mut change: F,
+    ) -> Result<(Task, Task), EngineError>
+    where
+        F: FnMut(&Task) -> Result<Task, EngineError>,
+    {
+        for attempt in 0..MAX_SAVE_ATTEMPTS {
+            // Retries skip coalescing, which could hand back the stale read.
+            let task = if attempt == 0 {
+                self.get_task(task_id).await?
+            } else {
+                self.short_term_store.get_task(task_id).await?
+            }
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+            check_version(&task, expected_version)?;
+            let mut updated = change(&task)?;
+            updated.version = task.version + 1;
+            if self
+                .short_term_store
+                .save_task_versioned(updated.clone(), task.version)
+                .await?
+            {
+                return Ok((task, updated));
+            }
+        }
+        Err(EngineError::ConcurrentModification(task_id.to_string()))
+    }
 
-        if !can_transition(&from, &to) {
-            return Err(EngineError::InvalidTransition { from, to });
+    /// Copies a task saved by [`save_with_retry`](Self::save_with_retry) to
+    /// the long-term store. The short-term store has already ruled on the
+    /// write, so a rejection here only means a newer copy got there first.
+    async fn save_long_term(&self, task: &Task, read_version: u64) -> Result<(), EngineError> {
+        if let Some(ref long_term_store) = self.long_term_store {
+            long_term_store
+                .save_task_versioned(task.clone(), read_version)
+                .await?;
         }
+        Ok(())
+    }
 
-        let now = now_millis();
-        let new_result = payload
-            .as_ref()
-            .and_then(|p| p.result.clone())
-            .or_else(|| task.result.clone());
-        let new_error = payload
-            .as_ref()
-            .and_then(|p| p.error.clone())
-            .or_else(|| task.error.clone());
-        let new_completed_at = if is_terminal(&to) {
-            Some(now)
-        } else {
-            task.completed_at
-        };
+    /// Removes a task and everything the short-term store keeps for it, then
+    /// notifies lifecycle listeners. History already persisted to a separate
+    /// long-term store is kept. Returns `false` when the short-term store had
+    /// no such task.
+    pub async fn delete_task(&self, task_id: &str) -> Result<bool, EngineError> {
+        if self.short_term_store.get_task(task_id).await?.is_none() {
+            return Ok(false);
+        }
+        self.short_term_store.delete_task(task_id).await?;
+        self.lifecycle.notify(task_id, &TaskLifecycleEvent::Deleted);
+        Ok(true)
+    }
 
-        let mut updated = Task {
-            status: to.clone(),
-            updated_at: now,
-            completed_at: new_completed_at,
-            result: new_result,
-            error: new_error,
-            ..task.clone()
-        };
+    /// Erases the task's events selected by `input` from the short-term and
+    /// long-term stores, replacing each with a tombstone at the same index,
+    /// then publishes a `taskcast:redacted` event listing the erased ids.
+    /// Terminal tasks are covered too. Nothing is published when no event
+    /// matched.
+    pub async fn delete_events(
+        &self,
+        task_id: &str,
+        input: DeleteEventsInput,
+    ) -> Result<DeletedEvents, EngineError> {
+        input.validate().map_err(EngineError::InvalidInput)?;
+        self.get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        // Held broadcasts may carry events about to be erased.
+        self.flush_held_broadcasts(task_id).await?;
 
-        // ─── Suspended-state field management ────────────────────────────────
-        // Set reason when entering suspended state
-        if is_suspended(&to) {
-            if let Some(ref payload) = payload {
-                if payload.reason.is_some() {
-                    updated.reason = payload.reason.clone();
-                }
-            }
-        } else {
-            // Clear suspended fields when leaving suspended state
-            updated.reason = None;
-            updated.blocked_request = None;
-            updated.resume_at = None;
+        let emit_lock = self.emit_lock(task_id);
+        let _guard = emit_lock.lock().await;
+        // Long-term writes still in flight would land after the tombstones.
+        while self.read_router.has_pending_writes(task_id) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
 
-        // Blocked-specific: set blockedRequest and resumeAt
-        if to == TaskStatus::Blocked {
-            if let Some(ref payload) = payload {
-                if payload.blocked_request.is_some() {
-                    updated.blocked_request = payload.blocked_request.clone();
-                }
-                if let Some(resume_after_ms) = payload.resume_after_ms {
-                    updated.resume_at = Some(now + resume_after_ms);
-                }
-            }
+        let reason = input.reason.as_deref();
+        let tombstones: Vec<TaskEvent> = self
+            .read_events(task_id, None)
+            .await?
+            .iter()
+            .filter(|event| input.matches(event))
+            .map(|event| tombstone(event, reason))
+            .collect();
+        let event_ids: Vec<String> = tombstones.iter().map(|t| t.id.clone()).collect();
+        if tombstones.is_empty() {
+            return Ok(DeletedEvents {
+                task_id: task_id.to_string(),
+                event_ids,
+                short_term: 0,
+                long_term: self.long_term_store.as_ref().map(|_| 0),
+                notification_index: None,
+            });
         }
 
-        // ─── TTL manipulation for suspended states ───────────────────────────
-        // → paused: stop TTL clock
+        let short_term = self
+            .short_term_store
+            .redact_events(task_id, &tombstones)
+            .await?;
+        let long_term = match self.long_term_store {
+            Some(ref long_term_store) => {
+                Some(long_term_store.redact_events(task_id, &tombstones).await?)
+            }
+            None => None,
+        };
+        let notification = self
+            .emit_locked(
+                task_id,
+                PublishEventInput {
+                    r#type: REDACTED_EVENT_TYPE.to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!({ "eventIds": event_ids, "reason": reason }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+                None,
+            )
+            .await?;
+        Ok(DeletedEvents {
+            task_id: task_id.to_string(),
+            event_ids,
+            short_term,
+            long_term,
+            notification_index: Some(notification.index),
+        })
+    }
+
+    /// Reports that a task's TTL ran out: drops whatever the short-term
+    /// store still holds for it and notifies lifecycle listeners. Redis
+    /// expires keys without telling the engine and the memory store ignores
+    /// TTLs, so whoever tracks expiry calls this.
+    pub async fn expire_task(&self, task_id: &str) -> Result<(), EngineError> {
+        self.short_term_store.delete_task(task_id).await?;
+        self.lifecycle.notify(task_id, &TaskLifecycleEvent::Expired);
+        Ok(())
+    }
+
+    /// Checks that `task_id` exists and may move to `to` now, without
+    /// changing anything.
+    pub async fn validate_transition(
+        &self,
+        task_id: &str,
+        to: &TaskStatus,
+    ) -> Result<(), EngineError> {
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        check_transition(&task, to)
+    }
+
+    /// The statuses `task_id` may move to from its current one, and whether
+    /// it still accepts events. Returns `None` if the task does not exist.
+    pub async fn task_transitions(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<TaskTransitions>, EngineError> {
+        let Some(task) = self.get_task(task_id).await? else {
+            return Ok(None);
+        };
+        let transitions = allowed_transitions(&task.status)
+            .iter()
+            .filter(|to| can_transition(&task.status, to))
+            .cloned()
+            .collect();
+        Ok(Some(TaskTransitions {
+            can_publish: !is_terminal(&task.status),
+            task_id: task.id,
+            status: task.status,
+            transitions,
+        }))
+    }
+
+    /// The task [`transition_task_versioned`](Self::transition_task_versioned)
+    /// would save for the same arguments, or the error it would fail with.
+    /// Nothing is saved or published.
+    pub async fn preview_transition(
+        &self,
+        task_id: &str,
+        to: TaskStatus,
+        payload: Option<TransitionPayload>,
+        expected_version: Option<u64>,
+    ) -> Result<Task, EngineError> {
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        check_version(&task, expected_version)?;
+        check_transition(&task, &to)?;
+        let payload = self.normalized_payload(payload);
+        let mut updated = transitioned(&task, to, payload.as_ref(), now_millis())?;
+        updated.version = task.version + 1;
+        Ok(updated)
+    }
+
+    pub async fn transition_task(
+        &self,
+        task_id: &str,
+        to: TaskStatus,
+        payload: Option<TransitionPayload>,
+    ) -> Result<Task, EngineError> {
+        self.transition_task_versioned(task_id, to, payload, None).await
+    }
+
+    /// Like [`transition_task`](Self::transition_task), but fails with
+    /// [`EngineError::ConcurrentModification`] unless the task is at
+    /// `expected_version`.
+    pub async fn transition_task_versioned(
+        &self,
+        task_id: &str,
+        to: TaskStatus,
+        payload: Option<TransitionPayload>,
+        expected_version: Option<u64>,
+    ) -> Result<Task, EngineError> {
+        self.transition_task_checked(task_id, to, payload, expected_version, |_| Ok(()))
+            .await
+    }
+
+    /// Cancels a pending or running task, recording `reason` as its error
+    /// with code `CANCELLED` and on its `taskcast:status` event. Fails with
+    /// [`EngineError::TaskTerminal`] once the task has finished, and with
+    /// [`EngineError::InvalidTransition`] from any other status.
+    pub async fn cancel_task(
+        &self,
+        task_id: &str,
+        reason: Option<String>,
+    ) -> Result<Task, EngineError> {
+        self.cancel_task_versioned(task_id, reason, None).await
+    }
+
+    /// Like [`cancel_task`](Self::cancel_task), but fails with
+    /// [`EngineError::ConcurrentModification`] unless the task is at
+    /// `expected_version`.
+    pub async fn cancel_task_versioned(
+        &self,
+        task_id: &str,
+        reason: Option<String>,
+        expected_version: Option<u64>,
+    ) -> Result<Task, EngineError> {
+        let payload = TransitionPayload {
+            error: Some(TaskError {
+                code: Some(CANCELLED_ERROR_CODE.to_string()),
+                message: reason
+                    .clone()
+                    .unwrap_or_else(|| "Task cancelled".to_string()),
+                details: None,
+            }),
+            reason,
+            ..Default::default()
+        };
+        self.transition_task_checked(
+            task_id,
+            TaskStatus::Cancelled,
+            Some(payload),
+            expected_version,
+            |task| match task.status {
+                TaskStatus::Pending | TaskStatus::Running => Ok(()),
+                ref status if is_terminal(status) => Err(EngineError::TaskTerminal(status.clone())),
+                ref status => Err(EngineError::InvalidTransition {
+                    from: status.clone(),
+                    to: TaskStatus::Cancelled,
+                }),
+            },
+        )
+        .await
+    }
+
+    /// [`transition_task_versioned`](Self::transition_task_versioned), also
+    /// failing unless `precondition` holds for the task as read by the same
+    /// save.
+    async fn transition_task_checked(
+        &self,
+        task_id: &str,
+        to: TaskStatus,
+        payload: Option<TransitionPayload>,
+        expected_version: Option<u64>,
+        precondition: fn(&Task) -> Result<(), EngineError>,
+    ) -> Result<Task, EngineError> {
+        let payload = self.normalized_payload(payload);
+        let (task, updated) = self
+            .save_with_retry(task_id, expected_version, |task| {
+                precondition(task)?;
+                check_transition(task, &to)?;
+                transitioned(task, to.clone(), payload.as_ref(), now_millis())
+            })
+            .await?;
+        let from = task.status.clone();
+        if from == TaskStatus::Scheduled {
+            self.short_term_store.delete_activation(task_id).await?;
+        }
+
+        // ─── TTL manipulation for suspended states ───────────────────────────
+        // → paused: stop TTL clock
         if to == TaskStatus::Paused {
             self.short_term_store.clear_ttl(task_id).await?;
         }
         // paused → blocked: restart TTL
         if from == TaskStatus::Paused && to == TaskStatus::Blocked {
-            if let Some(ttl) = updated.ttl {
+            if let Some(ttl) = task.ttl {
                 self.short_term_store.set_ttl(task_id, ttl).await?;
             }
         }
         // paused → running: reset full TTL
         if from == TaskStatus::Paused && to == TaskStatus::Running {
-            if let Some(ttl) = updated.ttl {
+            if let Some(ttl) = task.ttl {
                 self.short_term_store.set_ttl(task_id, ttl).await?;
             }
         }
@@ -338,39 +1302,73 @@ impl TaskEngine {
         if from == TaskStatus::Blocked && to == TaskStatus::Paused {
             self.short_term_store.clear_ttl(task_id).await?;
         }
+        // scheduled → pending: start TTL clock
+        if from == TaskStatus::Scheduled && to == TaskStatus::Pending {
+            if let Some(ttl) = task.ttl {
+                self.short_term_store.set_ttl(task_id, ttl).await?;
+            }
+        }
 
         // TTL override from payload
         if let Some(ref payload) = payload {
             if let Some(ttl) = payload.ttl {
-                updated.ttl = Some(ttl);
                 if to != TaskStatus::Paused {
                     self.short_term_store.set_ttl(task_id, ttl).await?;
                 }
             }
         }
 
-        self.short_term_store.save_task(updated.clone()).await?;
-
-        if let Some(ref long_term_store) = self.long_term_store {
-            long_term_store.save_task(updated.clone()).await?;
+        // Held until the status event below is queued for persistence, so a
+        // routed history read never sees the terminal task without it.
+        let _pending_write = self
+            .long_term_store
+            .as_ref()
+            .map(|_| self.read_router.begin_write(task_id));
+        self.save_long_term(&updated, task.version).await?;
+        self.sink_task(&updated).await;
+
+        let mut data = serde_json::json!({
+            "status": to,
+            "result": updated.result,
+            "error": updated.error,
+        });
+        // A cancellation's reason is not kept on the task, only here and in
+        // its error.
+        let cancel_reason = payload
+            .as_ref()
+            .and_then(|p| p.reason.as_ref())
+            .filter(|_| to == TaskStatus::Cancelled);
+        if let Some(reason) = cancel_reason {
+            data["reason"] = serde_json::json!(reason);
         }
-
-        self.emit(
-            task_id,
-            PublishEventInput {
-                r#type: "taskcast:status".to_string(),
-                level: Level::Info,
-                data: serde_json::json!({
-                    "status": to,
-                    "result": updated.result,
-                    "error": updated.error,
-                }),
-                series_id: None,
-                series_mode: None,
-                series_acc_field: None,
-            },
-        )
-        .await?;
+        if let Some(diff) = self
+            .diffs_for(&updated)
+            .diff(&diff_view(&task, false), &diff_view(&updated, false))
+        {
+            data["diff"] = diff;
+        }
+        // Subscribers stop at the terminal status event, so held series
+        // broadcasts go out ahead of it.
+        if is_terminal(&to) {
+            self.flush_held_broadcasts(task_id).await?;
+        }
+        let status_event = self
+            .emit(
+                task_id,
+                PublishEventInput {
+                    r#type: "taskcast:status".to_string(),
+                    level: Level::Info,
+                    data,
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await?;
+        self.forward_event(&updated, &status_event).await;
 
         // Emit taskcast:blocked event when entering blocked with blockedRequest
         if to == TaskStatus::Blocked {
@@ -386,47 +1384,59 @@ impl TaskEngine {
                     "request".to_string(),
                     serde_json::to_value(blocked_request).unwrap(),
                 );
-                self.emit(
+                let blocked_event = self
+                    .emit(
+                        task_id,
+                        PublishEventInput {
+                            r#type: "taskcast:blocked".to_string(),
+                            level: Level::Info,
+                            data: serde_json::Value::Object(data),
+                            series_id: None,
+                            series_mode: None,
+                            series_acc_field: None,
+                            labels: None,
+                            broadcast_debounce_ms: None,
+                            series_end: false,
+                        },
+                    )
+                    .await?;
+                self.forward_event(&updated, &blocked_event).await;
+            }
+        }
+
+        // Emit taskcast:resolved event when going from blocked → running
+        if from == TaskStatus::Blocked
+            && to == TaskStatus::Running
+            && task.blocked_request.is_some()
+        {
+            let resolution = payload.as_ref().and_then(|p| p.result.clone());
+            let resolved_event = self
+                .emit(
                     task_id,
                     PublishEventInput {
-                        r#type: "taskcast:blocked".to_string(),
+                        r#type: "taskcast:resolved".to_string(),
                         level: Level::Info,
-                        data: serde_json::Value::Object(data),
+                        data: serde_json::json!({ "resolution": resolution }),
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await?;
-            }
+            self.forward_event(&updated, &resolved_event).await;
         }
 
-        // Emit taskcast:resolved event when going from blocked → running
-        if from == TaskStatus::Blocked
-            && to == TaskStatus::Running
-            && task.blocked_request.is_some()
-        {
-            let resolution = payload.as_ref().and_then(|p| p.result.clone());
-            self.emit(
-                task_id,
-                PublishEventInput {
-                    r#type: "taskcast:resolved".to_string(),
-                    level: Level::Info,
-                    data: serde_json::json!({ "resolution": resolution }),
-                    series_id: None,
-                    series_mode: None,
-                    series_acc_field: None,
-                },
-            )
-            .await?;
+        if is_terminal(&to) {
+            self.record_outcome(&updated).await;
+            self.schedule_retry(&updated).await;
         }
 
-        // Clean up per-task emit lock — no more events can be published
-        // to a terminal task (publish_event rejects), so the lock is unused.
-        // A reopened task will lazily recreate the entry on next emit.
         if is_terminal(&to) {
-            let mut locks = self.emit_locks.lock().unwrap();
-            locks.remove(task_id);
+            self.lifecycle
+                .notify(task_id, &TaskLifecycleEvent::Terminal(to.clone()));
         }
 
         if let Some(ref hooks) = self.hooks {
@@ -441,9 +1451,221 @@ impl TaskEngine {
             }
         }
 
+        if is_terminal(&to) {
+            if let Some(ref parent_id) = updated.parent_id {
+                self.settle_parent(parent_id).await;
+            }
+        }
+
         Ok(updated)
     }
 
+    /// Passes a saved task to the sinks. The save has already succeeded, so
+    /// a sink error is reported through `on_unhandled_error` rather than
+    /// returned.
+    async fn sink_task(&self, task: &Task) {
+        for sink in &self.sinks {
+            let result = sink.on_task_saved(task).await;
+            if let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) {
+                hooks.on_unhandled_error(
+                    err.as_ref(),
+                    &ErrorContext {
+                        operation: "sink.task".to_string(),
+                        task_id: Some(task.id.clone()),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Adds a task that just ended to the outcomes index. The transition has
+    /// already succeeded, so a failed write is reported through
+    /// `on_unhandled_error` rather than returned.
+    async fn record_outcome(&self, task: &Task) {
+        let Some(outcome) = TaskOutcome::of(task) else {
+            return;
+        };
+        let result = self.short_term_store.record_outcome(outcome).await;
+        if let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) {
+            hooks.on_unhandled_error(
+                err.as_ref(),
+                &ErrorContext {
+                    operation: "outcomes.record".to_string(),
+                    task_id: Some(task.id.clone()),
+                },
+            );
+        }
+    }
+
+    /// Saves the retry of a task that just ended, if its policy covers the
+    /// status and attempts remain, and announces it on the ended task. The
+    /// transition has already succeeded, so failures here are reported
+    /// through `on_unhandled_error` rather than returned.
+    async fn schedule_retry(&self, task: &Task) {
+        let Some(policy) = task.retry_policy.as_ref() else {
+            return;
+        };
+        let attempt = retry_attempt(task);
+        if !policy.retries_on(&task.status) || attempt >= policy.max_attempts {
+            return;
+        }
+
+        let delay_ms = policy.delay_ms(attempt);
+        let schedule = RetrySchedule {
+            task_id: task.id.clone(),
+            successor_id: ulid::Ulid::new().to_string(),
+            attempt: attempt + 1,
+            due_at: self.clock.now_ms() + delay_ms as f64,
+        };
+        let result = async {
+            self.short_term_store
+                .save_retry_schedule(schedule.clone())
+                .await?;
+            let event = self
+                .emit(
+                    &task.id,
+                    PublishEventInput {
+                        r#type: RETRY_SCHEDULED_EVENT.to_string(),
+                        level: Level::Info,
+                        data: serde_json::json!({
+                            "attempt": schedule.attempt,
+                            "maxAttempts": policy.max_attempts,
+                            "successorId": schedule.successor_id,
+                            "dueAt": schedule.due_at,
+                            "delayMs": delay_ms,
+                        }),
+                        series_id: None,
+                        series_mode: None,
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await?;
+            self.forward_event(task, &event).await;
+            Ok::<(), EngineError>(())
+        }
+        .await;
+
+        if let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) {
+            hooks.on_unhandled_error(
+                &err,
+                &ErrorContext {
+                    operation: "retry.schedule".to_string(),
+                    task_id: Some(task.id.clone()),
+                },
+            );
+        }
+    }
+
+    /// Makes a `scheduled` task `pending`, as the scheduler runner does when
+    /// its time arrives. Returns `None`, changing nothing, if the task no
+    /// longer exists or has already left `scheduled`.
+    pub async fn activate_scheduled_task(&self, task_id: &str) -> Result<Option<Task>, EngineError> {
+        let Some(task) = self.get_task(task_id).await? else {
+            return Ok(None);
+        };
+        if task.status != TaskStatus::Scheduled {
+            return Ok(None);
+        }
+        match self.transition_task(task_id, TaskStatus::Pending, None).await {
+            Ok(task) => Ok(Some(task)),
+            // Cancelled since it was read.
+            Err(EngineError::InvalidTransition { .. } | EngineError::TaskTerminal(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Creates the successor `schedule` describes, copying the retried
+    /// task's definition. If the successor already exists (a runner stopped
+    /// between creating it and clearing the schedule) it is returned as is.
+    /// Returns `None` if the retried task no longer exists.
+    pub async fn create_retry_successor(
+        &self,
+        schedule: &RetrySchedule,
+    ) -> Result<Option<Task>, EngineError> {
+        let Some(task) = self.get_task(&schedule.task_id).await? else {
+            return Ok(None);
+        };
+        match self.create_task(successor_input(&task, schedule)).await {
+            Ok(successor) => Ok(Some(successor)),
+            Err(EngineError::TaskAlreadyExists {
+                existing: Some(existing),
+                ..
+            }) => Ok(Some(*existing)),
+            Err(EngineError::TaskAlreadyExists {
+                task_id,
+                existing: None,
+            }) => self.get_task(&task_id).await,
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The retry chain `task_id` belongs to, from the original task to its
+    /// latest successor. Returns `None` if the task does not exist.
+    pub async fn task_attempts(&self, task_id: &str) -> Result<Option<TaskAttempts>, EngineError> {
+        let Some(task) = self.get_task(task_id).await? else {
+            return Ok(None);
+        };
+        let mut seen = HashSet::from([task.id.clone()]);
+        let mut chain = vec![task];
+
+        while let Some(previous_id) = retry_of(&chain[0]).map(str::to_string) {
+            if !seen.insert(previous_id.clone()) {
+                break;
+            }
+            match self.get_task(&previous_id).await? {
+                Some(previous) => chain.insert(0, previous),
+                None => break,
+            }
+        }
+
+        let mut scheduled = None;
+        loop {
+            let last_id = chain.last().expect("chain is never empty").id.clone();
+            let Some(successor_id) = self.retry_successor_id(&last_id).await? else {
+                break;
+            };
+            if !seen.insert(successor_id.clone()) {
+                break;
+            }
+            match self.get_task(&successor_id).await? {
+                Some(successor) => chain.push(successor),
+                None => {
+                    scheduled = self.short_term_store.get_retry_schedule(&last_id).await?;
+                    break;
+                }
+            }
+        }
+
+        Ok(Some(TaskAttempts {
+            attempts: chain.iter().map(TaskAttempt::from).collect(),
+            scheduled,
+        }))
+    }
+
+    /// Successor id announced by the task's `taskcast:retry-scheduled`
+    /// event, falling back to its pending schedule.
+    async fn retry_successor_id(&self, task_id: &str) -> Result<Option<String>, EngineError> {
+        let events = self.get_events(task_id, None).await?;
+        let announced = events
+            .iter()
+            .rev()
+            .find(|event| event.r#type == RETRY_SCHEDULED_EVENT)
+            .and_then(|event| event.data.get("successorId"))
+            .and_then(|id| id.as_str())
+            .map(str::to_string);
+        if announced.is_some() {
+            return Ok(announced);
+        }
+        Ok(self
+            .short_term_store
+            .get_retry_schedule(task_id)
+            .await?
+            .map(|schedule| schedule.successor_id))
+    }
+
     pub async fn publish_event(
         &self,
         task_id: &str,
@@ -458,30 +1680,443 @@ impl TaskEngine {
             return Err(EngineError::TaskTerminal(task.status));
         }
 
-        self.emit(task_id, input).await
-    }
-
-    pub async fn export_task_archive(&self, task_id: &str) -> Result<TaskArchive, EngineError> {
-        let task = self
-            .get_task(task_id)
-            .await?
-            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        if let Some(ref labels) = input.labels {
+            self.label_limits.validate(labels)?;
+        }
+        self.event_types
+            .check(&input.r#type, "/type")
+            .map_err(|violation| EngineError::InvalidInput(violation.message))?;
+        let input = PublishEventInput {
+            r#type: self.event_types.normalize(&input.r#type),
+            ..input
+        };
 
-        self.build_export_archive(&task).await
+        let event = self.emit(task_id, input).await?;
+        if let (Some(registry), Some(task_type)) = (&self.conventions, &task.r#type) {
+            registry.observe(task_type, &event);
+        }
+        self.forward_event(&task, &event).await;
+        Ok(event)
     }
 
-    pub async fn import_task_archive(
-        &self,
-        archive: TaskArchive,
-        options: Option<TaskArchiveImportOptions>,
-    ) -> Result<TaskArchiveImportResult, EngineError> {
-        let import_options = options.unwrap_or_default();
-        let normalized = validate_task_archive(&archive)?;
-        let task_id = normalized.task.id.clone();
-        let existing = self.get_task(&task_id).await?;
+    /// `payload` with the type patterns of its filter presets normalized.
+    fn normalized_payload(&self, payload: Option<TransitionPayload>) -> Option<TransitionPayload> {
+        let mut payload = payload?;
+        for filter in payload.filters.iter_mut().flat_map(|f| f.values_mut()) {
+            self.event_types.normalize_filter(filter);
+        }
+        Some(payload)
+    }
 
-        if existing.is_some() && !import_options.overwrite {
-            return Err(EngineError::TaskConflict(task_id));
+    /// Publishes `event`, just emitted on `source`, on the task `source`
+    /// forwards to if its rule passes it. The source event is already
+    /// stored, so a forward that cannot be published is reported through
+    /// hooks: a missing or finished target drops it, anything else is an
+    /// unhandled error.
+    async fn forward_event(&self, source: &Task, event: &TaskEvent) {
+        let Some(ref rule) = source.forward_to else {
+            return;
+        };
+        let Some(input) = forwarded_event_input(&source.id, event, rule) else {
+            return;
+        };
+        let target_id = &rule.target_task_id;
+        let result = match self.get_task(target_id).await {
+            Ok(Some(target)) if is_terminal(&target.status) => {
+                Err(EngineError::TaskTerminal(target.status))
+            }
+            Ok(Some(_)) => self.emit(target_id, input).await.map(drop),
+            Ok(None) => Err(EngineError::TaskNotFound(target_id.clone())),
+            Err(err) => Err(err),
+        };
+        let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) else {
+            return;
+        };
+        match err {
+            EngineError::TaskTerminal(_) | EngineError::TaskNotFound(_) => {
+                hooks.on_event_dropped(event, &format!("forward to {target_id}: {err}"));
+            }
+            err => hooks.on_unhandled_error(
+                &err,
+                &ErrorContext {
+                    operation: "forward".to_string(),
+                    task_id: Some(source.id.clone()),
+                },
+            ),
+        }
+    }
+
+    /// Rejects a rule that would forward `task_id`'s events back to it,
+    /// directly or through the rules of the tasks it forwards to.
+    async fn check_forward_cycle(
+        &self,
+        task_id: &str,
+        rule: &ForwardRule,
+    ) -> Result<(), EngineError> {
+        if rule.target_task_id.is_empty() {
+            return Err(EngineError::InvalidInput(
+                "Invalid forwardTo: targetTaskId must not be empty.".to_string(),
+            ));
+        }
+        let mut visited = HashSet::new();
+        let mut target_id = rule.target_task_id.clone();
+        loop {
+            if target_id == task_id {
+                return Err(EngineError::InvalidInput(format!(
+                    "Invalid forwardTo: forwarding to {} would form a cycle back to {task_id}.",
+                    rule.target_task_id
+                )));
+            }
+            if !visited.insert(target_id.clone()) {
+                return Ok(());
+            }
+            match self.get_task(&target_id).await?.and_then(|t| t.forward_to) {
+                Some(next) => target_id = next.target_task_id,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// The children `task`'s completion policy lists that already exist and
+    /// have no parent yet, to be linked to it once it is saved. Rejects the
+    /// policy if one of them is another task's child.
+    async fn check_adoptable_children(&self, task: &Task) -> Result<Vec<String>, EngineError> {
+        let Some(ref policy) = task.completion_policy else {
+            return Ok(Vec::new());
+        };
+        if policy.mode == CompletionMode::Manual {
+            return Ok(Vec::new());
+        }
+        let mut adoptable = Vec::new();
+        for child_id in &policy.child_ids {
+            let Some(child) = self.get_task(child_id).await? else {
+                continue;
+            };
+            match child.parent_id {
+                Some(ref parent_id) if *parent_id != task.id => {
+                    return Err(EngineError::InvalidInput(format!(
+                        "Invalid completionPolicy: {child_id} is already a child of {parent_id}."
+                    )));
+                }
+                Some(_) => {}
+                None => adoptable.push(child_id.clone()),
+            }
+        }
+        Ok(adoptable)
+    }
+
+    /// Appends `child_id` to the completion policy of `parent_id` ahead of
+    /// the child's creation. Returns whether the parent was changed. A
+    /// missing or ended parent is rejected, as is a full policy.
+    async fn link_to_parent(&self, parent_id: &str, child_id: &str) -> Result<bool, EngineError> {
+        let ended = || {
+            EngineError::InvalidInput(format!(
+                "Invalid parentId: task {parent_id} has already ended."
+            ))
+        };
+        let Some(parent) = self.get_task(parent_id).await? else {
+            return Err(EngineError::InvalidInput(format!(
+                "Invalid parentId: task {parent_id} does not exist."
+            )));
+        };
+        if is_terminal(&parent.status) {
+            return Err(ended());
+        }
+        let awaits_children = parent.completion_policy.as_ref().is_some_and(|policy| {
+            policy.mode != CompletionMode::Manual
+                && !policy.child_ids.iter().any(|id| id == child_id)
+        });
+        if !awaits_children {
+            return Ok(false);
+        }
+        let (read, updated) = self
+            .save_with_retry(parent_id, None, |parent| {
+                // Checked again under the save, so a child is never added
+                // to a parent its last sibling has just ended.
+                if is_terminal(&parent.status) {
+                    return Err(ended());
+                }
+                let mut updated = parent.clone();
+                if let Some(ref mut policy) = updated.completion_policy {
+                    if policy.child_ids.len() >= MAX_COMPLETION_CHILDREN {
+                        return Err(EngineError::InvalidInput(format!(
+                            "Invalid parentId: task {parent_id} already awaits {MAX_COMPLETION_CHILDREN} children."
+                        )));
+                    }
+                    if !policy.child_ids.iter().any(|id| id == child_id) {
+                        policy.child_ids.push(child_id.to_string());
+                    }
+                }
+                updated.updated_at = now_millis();
+                Ok(updated)
+            })
+            .await?;
+        self.save_long_term(&updated, read.version).await?;
+        self.sink_task(&updated).await;
+        Ok(true)
+    }
+
+    /// Takes back a [`link_to_parent`](Self::link_to_parent) whose child
+    /// could not be created. The creation error is what the caller sees, so
+    /// a failure here is reported through `on_unhandled_error`.
+    async fn unlink_from_parent(&self, parent_id: &str, child_id: &str) {
+        let result = async {
+            let (read, updated) = self
+                .save_with_retry(parent_id, None, |parent| {
+                    let mut updated = parent.clone();
+                    if let Some(ref mut policy) = updated.completion_policy {
+                        policy.child_ids.retain(|id| id != child_id);
+                    }
+                    updated.updated_at = now_millis();
+                    Ok(updated)
+                })
+                .await?;
+            self.save_long_term(&updated, read.version).await
+        }
+        .await;
+        if let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) {
+            hooks.on_unhandled_error(
+                &err,
+                &ErrorContext {
+                    operation: "completion.unlink".to_string(),
+                    task_id: Some(parent_id.to_string()),
+                },
+            );
+        }
+    }
+
+    /// Links the existing `children` to `parent`, just created, then settles
+    /// it in case they have all ended already. The parent is saved, so
+    /// failures are reported through `on_unhandled_error`.
+    async fn adopt_children(&self, parent: &Task, children: &[String]) {
+        for child_id in children {
+            let result = async {
+                let (read, updated) = self
+                    .save_with_retry(child_id, None, |child| {
+                        let mut updated = child.clone();
+                        updated.parent_id.get_or_insert_with(|| parent.id.clone());
+                        Ok(updated)
+                    })
+                    .await?;
+                self.save_long_term(&updated, read.version).await
+            }
+            .await;
+            match (result, self.hooks.as_ref()) {
+                (Err(EngineError::TaskNotFound(_)), _) | (Ok(()), _) | (_, None) => {}
+                (Err(err), Some(hooks)) => hooks.on_unhandled_error(
+                    &err,
+                    &ErrorContext {
+                        operation: "completion.adopt".to_string(),
+                        task_id: Some(child_id.clone()),
+                    },
+                ),
+            }
+        }
+        self.settle_parent(&parent.id).await;
+    }
+
+    /// Ends the task `parent_id` if its completion policy is now satisfied,
+    /// after one of its children ended. The child's transition has already
+    /// succeeded, so failures are reported through `on_unhandled_error`.
+    ///
+    /// Children that end at the same time, on any instance, each settle the
+    /// parent after their own terminal save. Every read here is fresh, so
+    /// the last of them to read sees all the others ended; when several do,
+    /// the parent's terminal save lets one through and the rest find it
+    /// already ended, which is not an error.
+    ///
+    /// Boxed, as ending the parent settles the parent's own parent in turn.
+    fn settle_parent<'a>(
+        &'a self,
+        parent_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            let result = async {
+                let Some(parent) = self.read_task(parent_id).await? else {
+                    return Ok(());
+                };
+                let Some(ref policy) = parent.completion_policy else {
+                    return Ok(());
+                };
+                if is_terminal(&parent.status) || policy.mode == CompletionMode::Manual {
+                    return Ok(());
+                }
+                let mut children = Vec::with_capacity(policy.child_ids.len());
+                for child_id in &policy.child_ids {
+                    let status = self.read_task(child_id).await?.map(|child| child.status);
+                    children.push((child_id.as_str(), status));
+                }
+                let Some((status, error)) = completion_verdict(policy, &children) else {
+                    return Ok(());
+                };
+
+                // `completed` is only reachable from `running`, so a parent
+                // nobody started is started first.
+                if !can_transition(&parent.status, &status)
+                    && can_transition(&parent.status, &TaskStatus::Running)
+                {
+                    match self
+                        .transition_task(parent_id, TaskStatus::Running, None)
+                        .await
+                    {
+                        Ok(_)
+                        | Err(
+                            EngineError::InvalidTransition { .. } | EngineError::TaskTerminal(_),
+                        ) => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+                let payload = TransitionPayload {
+                    error,
+                    reason: Some("child tasks ended".to_string()),
+                    ..Default::default()
+                };
+                match self.transition_task(parent_id, status, Some(payload)).await {
+                    Ok(_)
+                    | Err(EngineError::InvalidTransition { .. } | EngineError::TaskTerminal(_)) => {
+                        Ok(())
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            .await;
+            if let (Err(err), Some(hooks)) = (result, self.hooks.as_ref()) {
+                hooks.on_unhandled_error(
+                    &err,
+                    &ErrorContext {
+                        operation: "completion.settle".to_string(),
+                        task_id: Some(parent_id.to_string()),
+                    },
+                );
+            }
+        })
+    }
+
+    /// Saves a task replicated from another deployment as is, but for its
+    /// origin. A task not yet here is created with a `replication` origin
+    /// whose detail is the kind it had at the source; one that is is
+    /// replaced unless the copy has an earlier `updated_at`, so stale copies
+    /// are ignored, and keeps the origin it was created with.
+    ///
+    /// No hooks, listeners or sinks run: the write is never replicated
+    /// onwards, and workers here are not offered the task.
+    pub async fn apply_replicated_task(
+        &self,
+        mut task: Task,
+    ) -> Result<ReplicatedTaskOutcome, EngineError> {
+        let Some(existing) = self.get_task(&task.id).await? else {
+            task.origin = Some(
+                TaskOrigin::new(TaskOriginKind::Replication).with_detail(task.origin_kind().as_str()),
+            );
+            if let NewTaskOutcome::AlreadyExists(_) =
+                self.short_term_store.save_new_task(task.clone()).await?
+            {
+                return Ok(ReplicatedTaskOutcome::Unchanged);
+            }
+            if let Some(ref cache) = self.negative_cache {
+                cache.invalidate(&task.id);
+            }
+            if let Some(ref long_term_store) = self.long_term_store {
+                long_term_store.save_task(task.clone()).await?;
+            }
+            if let Some(ttl) = task.ttl {
+                self.short_term_store.set_ttl(&task.id, ttl).await?;
+            }
+            return Ok(ReplicatedTaskOutcome::Created);
+        };
+        task.origin = existing.origin.clone();
+        // Copies from one origin arrive in order, and several changes can
+        // share a millisecond, so only a strictly older copy is stale.
+        if task == existing || task.updated_at < existing.updated_at {
+            return Ok(ReplicatedTaskOutcome::Unchanged);
+        }
+
+        self.short_term_store.save_task(task.clone()).await?;
+        let _pending_write = self
+            .long_term_store
+            .as_ref()
+            .map(|_| self.read_router.begin_write(&task.id));
+        if let Some(ref long_term_store) = self.long_term_store {
+            long_term_store.save_task(task.clone()).await?;
+        }
+        if is_terminal(&task.status) && !is_terminal(&existing.status) {
+            self.record_outcome(&task).await;
+            self.lifecycle
+                .notify(&task.id, &TaskLifecycleEvent::Terminal(task.status.clone()));
+        }
+        Ok(ReplicatedTaskOutcome::Updated)
+    }
+
+    /// Stores and broadcasts an event replicated from another deployment,
+    /// keeping its id, index and timestamp. Returns `None` if the task
+    /// already holds an event at that index, so a redelivered event is
+    /// applied once.
+    ///
+    /// The task's terminal status does not stop the event, and sinks do not
+    /// see it, so it is never replicated onwards.
+    pub async fn apply_replicated_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<Option<TaskEvent>, EngineError> {
+        let task_id = event.task_id.clone();
+        if self.get_task(&task_id).await?.is_none() {
+            return Err(EngineError::TaskNotFound(task_id));
+        }
+
+        let emit_lock = self.emit_lock(&task_id);
+        let _guard = emit_lock.lock().await;
+        let next_index = self.short_term_store.event_count(&task_id).await?;
+        if event.index < next_index {
+            return Ok(None);
+        }
+        if event.index - next_index > MAX_REPLICATED_INDEX_GAP {
+            return Err(EngineError::InvalidInput(format!(
+                "Invalid replicated event index: {} is more than {MAX_REPLICATED_INDEX_GAP} past the next index {next_index}.",
+                event.index
+            )));
+        }
+        let origin = ReplicatedOrigin {
+            id: event.id,
+            index: event.index,
+            timestamp: event.timestamp,
+        };
+        let input = PublishEventInput {
+            r#type: event.r#type,
+            level: event.level,
+            data: event.data,
+            series_id: event.series_id,
+            series_mode: event.series_mode,
+            series_acc_field: event.series_acc_field,
+            labels: event.labels,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        };
+        self.emit_locked(&task_id, input, Some(origin))
+            .await
+            .map(Some)
+    }
+
+    pub async fn export_task_archive(&self, task_id: &str) -> Result<TaskArchive, EngineError> {
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+
+        self.build_export_archive(&task).await
+    }
+
+    pub async fn import_task_archive(
+        &self,
+        archive: TaskArchive,
+        options: Option<TaskArchiveImportOptions>,
+    ) -> Result<TaskArchiveImportResult, EngineError> {
+        let import_options = options.unwrap_or_default();
+        let normalized = validate_task_archive(&archive)?;
+        let task_id = normalized.task.id.clone();
+        let existing = self.get_task(&task_id).await?;
+
+        if existing.is_some() && !import_options.overwrite {
+            return Err(EngineError::TaskConflict(task_id));
         }
 
         if !self.short_term_store.supports_task_archive_restore() {
@@ -495,90 +2130,766 @@ impl TaskEngine {
             .map(|store| store.shares_task_archive_restore_storage())
             .unwrap_or(false);
 
-        if let Some(ref long_term_store) = self.long_term_store {
-            if !long_term_shares_archive_restore_storage
-                && !long_term_store.supports_task_archive_restore()
-            {
-                return Err(unsupported_archive_restore(
-                    "longTermStore does not support restore_task_archive",
-                ));
-            }
-        }
+        if let Some(ref long_term_store) = self.long_term_store {
+            if !long_term_shares_archive_restore_storage
+                && !long_term_store.supports_task_archive_restore()
+            {
+                return Err(unsupported_archive_restore(
+                    "longTermStore does not support restore_task_archive",
+                ));
+            }
+        }
+
+        let event_count = normalized.events.len();
+        let restore_data = build_task_archive_restore_data(&normalized)?;
+        self.short_term_store
+            .validate_task_archive_restore(&restore_data, Some(import_options))
+            .await?;
+        if let Some(ref long_term_store) = self.long_term_store {
+            long_term_store
+                .validate_task_archive_restore(&restore_data, Some(import_options))
+                .await?;
+        }
+
+        let restore_options = Some(import_options);
+        // Durable history is restored before the live short-term cache so a final
+        // long-term failure cannot expose an imported task that was never persisted.
+        if let Some(ref long_term_store) = self.long_term_store {
+            if !long_term_shares_archive_restore_storage {
+                long_term_store
+                    .restore_task_archive(restore_data.clone(), restore_options)
+                    .await?;
+            }
+        }
+        self.short_term_store
+            .restore_task_archive(restore_data, restore_options)
+            .await?;
+        if let Some(ref cache) = self.negative_cache {
+            cache.invalidate(&task_id);
+        }
+
+        self.emit_locks.lock().unwrap().remove(&task_id);
+
+        Ok(TaskArchiveImportResult {
+            task_id,
+            event_count,
+            overwritten: existing.is_some(),
+        })
+    }
+
+    /// The task as it was at `as_of` (epoch milliseconds), rebuilt from the
+    /// status events up to then by [`reconstruct_task_at`]. `as_of` must fall
+    /// between the task's creation and now.
+    pub async fn get_task_as_of(
+        &self,
+        task_id: &str,
+        as_of: f64,
+    ) -> Result<Option<Task>, EngineError> {
+        let Some(task) = self.get_task(task_id).await? else {
+            return Ok(None);
+        };
+        if as_of < task.created_at {
+            return Err(EngineError::InvalidInput(format!(
+                "asOf {as_of} is before the task was created at {}",
+                task.created_at
+            )));
+        }
+        if as_of > now_millis() {
+            return Err(EngineError::InvalidInput(format!(
+                "asOf {as_of} is in the future"
+            )));
+        }
+        let events = self
+            .get_events(
+                task_id,
+                Some(EventQueryOptions {
+                    until: Some(as_of),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        Ok(Some(reconstruct_task_at(&task, &events, as_of)))
+    }
+
+    pub async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, EngineError> {
+        // Only full-history reads are shared; cursors and filters vary too
+        // much between callers to be worth it.
+        match (&self.history_reads, opts) {
+            (Some(reads), None) => reads.run(task_id, self.read_events(task_id, None)).await,
+            (_, opts) => self.read_events(task_id, opts).await,
+        }
+    }
+
+    async fn read_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, EngineError> {
+        if let Some(ref long_term_store) = self.long_term_store {
+            // Only a terminal task with every write landed has a complete
+            // long-term history.
+            if self.terminal_task_from_long_term(task_id).await?.is_some()
+                && !self.read_router.has_pending_writes(task_id)
+            {
+                let from_long = timed(
+                    self.read_router.long_term_latency(),
+                    long_term_store.get_events(task_id, opts.clone()),
+                )
+                .await?;
+                if !from_long.is_empty() {
+                    self.read_router.note_long_term_read();
+                    return Ok(from_long);
+                }
+            }
+        }
+        let from_short = timed(
+            self.read_router.short_term_latency(),
+            self.short_term_store.get_events(task_id, opts.clone()),
+        )
+        .await?;
+        if !from_short.is_empty() {
+            return Ok(from_short);
+        }
+        if let Some(ref long_term_store) = self.long_term_store {
+            return Ok(timed(
+                self.read_router.long_term_latency(),
+                long_term_store.get_events(task_id, opts),
+            )
+            .await?);
+        }
+        Ok(vec![])
+    }
+
+    /// Recently ended tasks from the outcomes index, as selected by `query`.
+    pub async fn list_outcomes(
+        &self,
+        query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, EngineError> {
+        Ok(self.short_term_store.list_outcomes(query).await?)
+    }
+
+    /// The task's event with id `event_id`, looked up in its full history.
+    pub async fn get_event(
+        &self,
+        task_id: &str,
+        event_id: &str,
+    ) -> Result<Option<TaskEvent>, EngineError> {
+        let events = self.get_events(task_id, None).await?;
+        Ok(events.into_iter().find(|event| event.id == event_id))
+    }
+
+    /// Number of events published to a task so far, read from the short-term
+    /// store's index counter.
+    pub async fn event_count(&self, task_id: &str) -> Result<u64, EngineError> {
+        Ok(self.short_term_store.event_count(task_id).await?)
+    }
+
+    /// The series a task keeps state for, ordered by series id, as of each
+    /// one's latest event.
+    pub async fn list_series(&self, task_id: &str) -> Result<Vec<SeriesSummary>, EngineError> {
+        Ok(self.short_term_store.list_series(task_id).await?)
+    }
+
+    /// Type and level counts of a task's events: the short-term store's
+    /// running counts while it holds the task's history, otherwise counted
+    /// by the long-term store.
+    pub async fn event_type_counts(&self, task_id: &str) -> Result<EventTypeCounts, EngineError> {
+        let from_short = self.short_term_store.get_event_type_counts(task_id).await?;
+        if !from_short.is_empty() {
+            return Ok(from_short);
+        }
+        if let Some(ref long_term_store) = self.long_term_store {
+            return Ok(long_term_store.get_event_type_counts(task_id).await?);
+        }
+        Ok(from_short)
+    }
+
+    /// [`event_type_counts`](Self::event_type_counts) together with the scan
+    /// they describe: reading the task's events bounded by the token gives
+    /// the counted events.
+    pub async fn scanned_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<(ScanToken, EventTypeCounts), EngineError> {
+        let emit_lock = self.scan_lock(task_id);
+        let _guard = match emit_lock {
+            Some(ref lock) => Some(lock.lock().await),
+            None => None,
+        };
+        let scan = self.short_term_store.begin_scan(task_id).await?;
+        let counts = self.event_type_counts(task_id).await?;
+        Ok((scan, counts))
+    }
+
+    /// Starts a scan of a task's history. Reads bounded by the token, through
+    /// [`get_events_in_scan`](Self::get_events_in_scan), see the events
+    /// published before this call and none after it, so a history read in
+    /// pages, or from both stores, stays consistent while publishers append.
+    ///
+    /// Publishes on this instance are waited out; another instance's
+    /// publishes are bounded only by the short-term store's index counter.
+    pub async fn begin_scan(&self, task_id: &str) -> Result<ScanToken, EngineError> {
+        let emit_lock = self.scan_lock(task_id);
+        let _guard = match emit_lock {
+            Some(ref lock) => Some(lock.lock().await),
+            None => None,
+        };
+        Ok(self.short_term_store.begin_scan(task_id).await?)
+    }
+
+    /// The task's events inside `scan`, read as [`get_events`](Self::get_events)
+    /// reads them.
+    pub async fn get_events_in_scan(
+        &self,
+        scan: &ScanToken,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, EngineError> {
+        self.read_events(&scan.task_id, scan.bound(opts)).await
+    }
+
+    /// The emit lock of a task with one, so a scan starts between publishes.
+    /// Unlike [`emit_lock`](Self::emit_lock) it does not create one: a task
+    /// without a lock has no publish in flight on this instance.
+    fn scan_lock(&self, task_id: &str) -> Option<Arc<TokioMutex<()>>> {
+        self.emit_locks.lock().unwrap().get(task_id).cloned()
+    }
+
+    /// Health of the long-term store's connection pools, empty without a
+    /// long-term store.
+    pub async fn long_term_pool_health(&self) -> Vec<PoolHealth> {
+        match self.long_term_store {
+            Some(ref store) => store.pool_health().await,
+            None => Vec::new(),
+        }
+    }
+
+    /// Bytes saved by the short-term store's value compression, when it
+    /// compresses.
+    pub fn short_term_compression_stats(&self) -> Option<CompressionStats> {
+        self.short_term_store.compression_stats()
+    }
+
+    /// Query latency of the long-term store, when it records it.
+    pub fn long_term_query_stats(&self) -> Option<QueryStatsSnapshot> {
+        self.long_term_store.as_ref()?.query_stats()
+    }
+
+    /// Distinct corrupt records the stores have read for a task.
+    pub fn corrupt_records(&self, task_id: &str) -> u64 {
+        self.integrity_monitors()
+            .iter()
+            .map(|monitor| monitor.corrupt_records(task_id))
+            .sum()
+    }
+
+    /// Distinct corrupt records the stores have read across all tasks.
+    pub fn total_corrupt_records(&self) -> u64 {
+        self.integrity_monitors()
+            .into_iter()
+            .map(IntegrityMonitor::total_corrupt_records)
+            .sum()
+    }
+
+    /// The stores' integrity monitors, with a monitor shared between the
+    /// short-term and long-term store listed once.
+    fn integrity_monitors(&self) -> Vec<&IntegrityMonitor> {
+        let mut monitors: Vec<&IntegrityMonitor> =
+            self.short_term_store.integrity().into_iter().collect();
+        if let Some(long_term) = self.long_term_store.as_ref().and_then(|s| s.integrity()) {
+            if !monitors.iter().any(|monitor| monitor.ptr_eq(long_term)) {
+                monitors.push(long_term);
+            }
+        }
+        monitors
+    }
+
+    pub async fn list_tasks(&self, filter: TaskFilter) -> Result<Vec<Task>, EngineError> {
+        Ok(self.short_term_store.list_tasks(filter).await?)
+    }
+
+    /// One page of tasks in `(createdAt, id)` order, starting after `after`.
+    /// See [`ShortTermStore::list_tasks_page`].
+    pub async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, EngineError> {
+        Ok(self
+            .short_term_store
+            .list_tasks_page(filter, after, limit)
+            .await?)
+    }
+
+    /// One page of the tasks matching `query`, in `(createdAt, id)` order.
+    ///
+    /// The short-term store is always read. A query for terminal statuses
+    /// only is also sent to the long-term store when it can list tasks, as
+    /// it keeps them after the short-term store lets them go; the two pages
+    /// are merged, preferring the short-term copy of a task, so tasks whose
+    /// long-term write is still queued or not yet on a replica are listed
+    /// too.
+    pub async fn query_tasks(&self, query: &ListTasksQuery) -> Result<TaskPage, EngineError> {
+        let page = self.query_short_term_tasks(query).await?;
+        let terminal_only = query
+            .status
+            .as_ref()
+            .is_some_and(|statuses| !statuses.is_empty() && statuses.iter().all(is_terminal));
+        match self.long_term_store {
+            Some(ref long_term_store)
+                if terminal_only && long_term_store.supports_task_listing() =>
+            {
+                let archived = long_term_store.list_tasks(query).await?;
+                Ok(page.merge(archived, query.limit.unwrap_or(usize::MAX)))
+            }
+            _ => Ok(page),
+        }
+    }
+
+    /// [`query_tasks`](Self::query_tasks) against the short-term store,
+    /// read a chunk at a time until the page is full, since it cannot
+    /// filter on a wildcard type.
+    async fn query_short_term_tasks(
+        &self,
+        query: &ListTasksQuery,
+    ) -> Result<TaskPage, EngineError> {
+        let filter = query.filter();
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let chunk = query.limit.unwrap_or(LIST_TASKS_CHUNK);
+        let mut after = query.start();
+        let mut tasks = Vec::new();
+        loop {
+            let page = self
+                .short_term_store
+                .list_tasks_page(filter.clone(), after, chunk)
+                .await?;
+            tasks.extend(
+                page.tasks
+                    .into_iter()
+                    .filter(|task| query.matches_type(task)),
+            );
+            after = page.next;
+            if after.is_none() {
+                return Ok(TaskPage::from_sorted(tasks, limit));
+            }
+            if tasks.len() >= limit {
+                tasks.truncate(limit);
+                return Ok(TaskPage {
+                    next: tasks.last().map(TaskCursor::of),
+                    tasks,
+                });
+            }
+        }
+    }
+
+    /// Applies `update` to the task and saves it, leaving its status alone.
+    /// If the update changed the task's result or metadata, a
+    /// [`TASK_UPDATED_EVENT`] carrying both (and their `diff`) is published.
+    ///
+    /// `update` runs again on a fresh read whenever another writer saved the
+    /// task first.
+    pub async fn update_task<F>(&self, task_id: &str, update: F) -> Result<Task, EngineError>
+    where
+        F: FnMut(&mut Task),
+    {
+        self.update_task_versioned(task_id, None, update).await
+    }
+
+    /// Like [`update_task`](Self::update_task), but fails with
+    /// [`EngineError::ConcurrentModification`] unless the task is at
+    /// `expected_version`.
+    pub async fn update_task_versioned<F>(
+        &self,
+        task_id: &str,
+        expected_version: Option<u64>,
+        mut update: F,
+    ) -> Result<Task, EngineError>
+    where
+        F: FnMut(&mut Task),
+    {
+        let (read, task) = self
+            .save_with_retry(task_id, expected_version, |task| {
+                let mut updated = task.clone();
+                update(&mut updated);
+                updated.origin = task.origin.clone();
+                updated.updated_at = now_millis();
+                Ok(updated)
+            })
+            .await?;
+        let _pending_write = self
+            .long_term_store
+            .as_ref()
+            .map(|_| self.read_router.begin_write(task_id));
+        self.save_long_term(&task, read.version).await?;
+        self.sink_task(&task).await;
+
+        let before = diff_view(&read, true);
+        let after = diff_view(&task, true);
+        if after != before {
+            let mut data = after.clone();
+            if let Some(diff) = self.diffs_for(&task).diff(&before, &after) {
+                data["diff"] = diff;
+            }
+            let updated_event = self
+                .emit(
+                    task_id,
+                    PublishEventInput {
+                        r#type: TASK_UPDATED_EVENT.to_string(),
+                        level: Level::Info,
+                        data,
+                        series_id: None,
+                        series_mode: None,
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await?;
+            self.forward_event(&task, &updated_event).await;
+        }
+        Ok(task)
+    }
+
+    /// Subscribe to the live events of a task, as published on its
+    /// `task.{taskId}` channel. With legacy channels enabled, events that
+    /// older instances publish on the bare task id are received too.
+    pub async fn subscribe(
+        &self,
+        task_id: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        if !self.channels.legacy_channels {
+            return self
+                .broadcast
+                .subscribe(&task_channel(task_id), handler)
+                .await;
+        }
+        let handler: Arc<dyn Fn(TaskEvent) + Send + Sync> = Arc::from(deliver_once(handler));
+        let current = Arc::clone(&handler);
+        let unsubscribe_current = self
+            .broadcast
+            .subscribe(
+                &task_channel(task_id),
+                Box::new(move |event| current(event)),
+            )
+            .await;
+        let unsubscribe_legacy = self
+            .broadcast
+            .subscribe(task_id, Box::new(move |event| handler(event)))
+            .await;
+        Box::new(move || {
+            unsubscribe_current();
+            unsubscribe_legacy();
+        })
+    }
+
+    /// Like [`subscribe`](Self::subscribe), through
+    /// [`BroadcastProvider::subscribe_without_status`] unless
+    /// `include_status`.
+    async fn subscribe_live(
+        &self,
+        task_id: &str,
+        include_status: bool,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        if include_status {
+            return self.subscribe(task_id, handler).await;
+        }
+        if !self.channels.legacy_channels {
+            return self
+                .broadcast
+                .subscribe_without_status(&task_channel(task_id), handler)
+                .await;
+        }
+        let handler: Arc<dyn Fn(TaskEvent) + Send + Sync> = Arc::from(deliver_once(handler));
+        let current = Arc::clone(&handler);
+        let unsubscribe_current = self
+            .broadcast
+            .subscribe_without_status(
+                &task_channel(task_id),
+                Box::new(move |event| current(event)),
+            )
+            .await;
+        let unsubscribe_legacy = self
+            .broadcast
+            .subscribe_without_status(task_id, Box::new(move |event| handler(event)))
+            .await;
+        Box::new(move || {
+            unsubscribe_current();
+            unsubscribe_legacy();
+        })
+    }
+
+    /// Synchronous version of `subscribe` for use in contexts where async
+    /// is not available (e.g., inside creation listener callbacks).
+    ///
+    /// Not all broadcast providers support this — see `BroadcastProvider::subscribe_sync`.
+    pub fn subscribe_sync(
+        &self,
+        task_id: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.channels.legacy_channels {
+            return self
+                .broadcast
+                .subscribe_sync(&task_channel(task_id), handler);
+        }
+        let handler: Arc<dyn Fn(TaskEvent) + Send + Sync> = Arc::from(deliver_once(handler));
+        let current = Arc::clone(&handler);
+        let unsubscribe_current = self.broadcast.subscribe_sync(
+            &task_channel(task_id),
+            Box::new(move |event| current(event)),
+        )?;
+        let unsubscribe_legacy = match self
+            .broadcast
+            .subscribe_sync(task_id, Box::new(move |event| handler(event)))
+        {
+            Ok(unsubscribe) => unsubscribe,
+            Err(err) => {
+                unsubscribe_current();
+                return Err(err);
+            }
+        };
+        Ok(Box::new(move || {
+            unsubscribe_current();
+            unsubscribe_legacy();
+        }))
+    }
+
+    /// Subscribe to every broadcast channel matching the glob `pattern`,
+    /// such as `task.*` for the events of all tasks. See
+    /// [`channel_matches`](crate::channels::channel_matches) for the syntax.
+    /// An event published to several matching channels, as `t*` matches
+    /// both its `task.` and `type.` channels, is delivered once. Other
+    /// broadcast traffic, such as the worker manager's
+    /// `taskcast:worker:new-task` notices, matches `*` as well.
+    ///
+    /// Fails when the broadcast provider does not support pattern
+    /// subscriptions.
+    pub async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        self.broadcast
+            .subscribe_pattern(pattern, deliver_once(handler))
+            .await
+    }
+
+    /// Subscribe to the events of every task whose type's first segment
+    /// matches the glob `pattern`: `llm` receives `llm.delta` and
+    /// `llm.done`, `*` every type. Only events published with type channels
+    /// enabled are received, so enable them on every instance that
+    /// publishes.
+    pub async fn subscribe_by_type(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        self.subscribe_pattern(&type_channel(pattern), handler)
+            .await
+    }
+
+    /// Subscribe to a task as a [`TaskEventStream`]: stored history matching
+    /// `filter` is replayed first, then live events are tailed until the task
+    /// reaches a terminal status, at which point [`StreamItem::Done`](crate::StreamItem::Done)
+    /// is yielded.
+    ///
+    /// Returns `TaskNotFound` if the task does not exist. A failure while
+    /// loading history is reported in-band as
+    /// [`StreamItem::Error`](crate::StreamItem::Error).
+    pub async fn subscribe_stream(
+        &self,
+        task_id: &str,
+        filter: SubscribeFilter,
+    ) -> Result<TaskEventStream, EngineError> {
+        self.subscribe_stream_with_limit(task_id, filter, None)
+            .await
+    }
+
+    /// Like [`subscribe_stream`](Self::subscribe_stream), but replays at most
+    /// `history_limit` stored events before tailing.
+    pub async fn subscribe_stream_with_limit(
+        &self,
+        task_id: &str,
+        filter: SubscribeFilter,
+        history_limit: Option<u64>,
+    ) -> Result<TaskEventStream, EngineError> {
+        let include_status = filter.include_status.unwrap_or(true);
+        let live = self.connect_live(task_id, include_status).await?;
+
+        let replay_events = match self
+            .load_replay_events(task_id, &filter, history_limit)
+            .await
+        {
+            Ok(events) => events,
+            Err(e) => return Ok(TaskEventStream::failed(e)),
+        };
 
-        let event_count = normalized.events.len();
-        let restore_data = build_task_archive_restore_data(&normalized)?;
-        self.short_term_store
-            .validate_task_archive_restore(&restore_data, Some(import_options))
-            .await?;
-        if let Some(ref long_term_store) = self.long_term_store {
-            long_term_store
-                .validate_task_archive_restore(&restore_data, Some(import_options))
-                .await?;
-        }
+        let stream = TaskEventStream::replay(&replay_events, filter);
+        Ok(match live {
+            LiveConnection::Tail(rx, subscription) => stream.tail(rx, subscription),
+            LiveConnection::Ended(status) => stream.finish(status),
+        })
+    }
 
-        let restore_options = Some(import_options);
-        // Durable history is restored before the live short-term cache so a final
-        // long-term failure cannot expose an imported task that was never persisted.
-        if let Some(ref long_term_store) = self.long_term_store {
-            if !long_term_shares_archive_restore_storage {
-                long_term_store
-                    .restore_task_archive(restore_data.clone(), restore_options)
-                    .await?;
-            }
-        }
-        self.short_term_store
-            .restore_task_archive(restore_data, restore_options)
+    /// Like [`subscribe_stream_with_limit`](Self::subscribe_stream_with_limit),
+    /// but history is read a chunk of `replay.chunk_size` events at a time as
+    /// the stream is polled, and only the most recent events within
+    /// `replay.budget` are replayed. A replay the budget cut short starts
+    /// with [`StreamItem::Truncated`](crate::StreamItem::Truncated).
+    ///
+    /// Replays that materialize json-patch series, or fold running totals
+    /// from a store cursor, still read the history at once.
+    pub async fn subscribe_stream_with_replay(
+        self: &Arc<Self>,
+        task_id: &str,
+        filter: SubscribeFilter,
+        replay: ReplayOptions,
+    ) -> Result<TaskEventStream, EngineError> {
+        let live = self
+            .connect_live(task_id, filter.include_status.unwrap_or(true))
             .await?;
 
-        self.emit_locks.lock().unwrap().remove(&task_id);
+        let since = store_cursor(&filter);
+        let folds_series = folds_series(&filter);
+        let source = if filter.materialize_series == Some(true) || (folds_series && since.is_some())
+        {
+            match self
+                .load_replay_events(task_id, &filter, replay.history_limit)
+                .await
+            {
+                Ok(events) => ReplaySource::loaded(events),
+                Err(e) => return Ok(TaskEventStream::failed(e)),
+            }
+        } else {
+            let chunks = self.event_chunks(
+                task_id,
+                Some(EventQueryOptions {
+                    since,
+                    limit: replay.history_limit,
+                    label_selector: None,
+                    until: None,
+                    before_index: None,
+                }),
+                replay.chunk_size,
+            );
+            ReplaySource::chunked(chunks, filter.since.is_none(), folds_series)
+        };
 
-        Ok(TaskArchiveImportResult {
-            task_id,
-            event_count,
-            overwritten: existing.is_some(),
+        let stream = TaskEventStream::replaying(
+            replay_stream(source, filter.clone(), replay.budget),
+            filter,
+        );
+        Ok(match live {
+            LiveConnection::Tail(rx, subscription) => stream.tail(rx, subscription),
+            LiveConnection::Ended(status) => stream.finish(status),
         })
     }
 
-    pub async fn get_events(
+    /// Opens the live tail of a stream, before its history is read so that
+    /// nothing published while the replay runs is missed; the stream skips
+    /// live events the replay covered. The task is re-read after
+    /// subscribing, so one that turns terminal in between ends the stream
+    /// instead of leaving it tailing a task that publishes nothing more.
+    async fn connect_live(
         &self,
         task_id: &str,
-        opts: Option<EventQueryOptions>,
-    ) -> Result<Vec<TaskEvent>, EngineError> {
-        let from_short = self
-            .short_term_store
-            .get_events(task_id, opts.clone())
-            .await?;
-        if !from_short.is_empty() {
-            return Ok(from_short);
-        }
-        if let Some(ref long_term_store) = self.long_term_store {
-            return Ok(long_term_store.get_events(task_id, opts).await?);
+        include_status: bool,
+    ) -> Result<LiveConnection, EngineError> {
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        if is_terminal(&task.status) {
+            return Ok(LiveConnection::Ended(task.status));
         }
-        Ok(vec![])
-    }
 
-    pub async fn list_tasks(&self, filter: TaskFilter) -> Result<Vec<Task>, EngineError> {
-        Ok(self.short_term_store.list_tasks(filter).await?)
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let subscription = Subscription(
+            self.subscribe_live(
+                task_id,
+                include_status,
+                Box::new(move |event| {
+                    let _ = tx.send(event);
+                }),
+            )
+            .await,
+        );
+
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        Ok(if is_terminal(&task.status) {
+            LiveConnection::Ended(task.status)
+        } else {
+            LiveConnection::Tail(rx, subscription)
+        })
     }
 
-    pub async fn subscribe(
-        &self,
+    /// Reads a task's history `chunk_size` events at a time, one
+    /// `get_events` call per chunk. `opts` applies to the read as a whole:
+    /// its cursor to the first chunk and its `limit` to the total.
+    pub fn event_chunks(
+        self: &Arc<Self>,
         task_id: &str,
-        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
-    ) -> Box<dyn Fn() + Send + Sync> {
-        self.broadcast.subscribe(task_id, handler).await
+        opts: Option<EventQueryOptions>,
+        chunk_size: u64,
+    ) -> EventChunks {
+        EventChunks::new(Arc::clone(self), task_id, opts, chunk_size)
     }
 
-    /// Synchronous version of `subscribe` for use in contexts where async
-    /// is not available (e.g., inside creation listener callbacks).
+    /// Wait until a task reaches a terminal status, or until `timeout` elapses.
     ///
-    /// Not all broadcast providers support this — see `BroadcastProvider::subscribe_sync`.
-    pub fn subscribe_sync(
+    /// Returns the task as stored once it is terminal, or its current state on
+    /// timeout; callers tell the two apart with `is_terminal`. Waits on the
+    /// broadcast channel instead of polling the store, and re-reads the task
+    /// after subscribing so a transition that lands between the first check
+    /// and the subscription is not missed.
+    pub async fn wait_for_terminal(
         &self,
         task_id: &str,
-        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
-    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
-        self.broadcast.subscribe_sync(task_id, handler)
+        timeout: Duration,
+    ) -> Result<Task, EngineError> {
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        if is_terminal(&task.status) {
+            return Ok(task);
+        }
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let _subscription = Subscription(
+            self.subscribe(
+                task_id,
+                Box::new(move |event| {
+                    if terminal_status_of(&event).is_some() {
+                        let _ = tx.send(());
+                    }
+                }),
+            )
+            .await,
+        );
+
+        let task = self
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        if is_terminal(&task.status) {
+            return Ok(task);
+        }
+
+        // Either way, re-read: the task is saved before its status event is
+        // broadcast. If it has since been cleaned up, report the last state seen.
+        let _ = tokio::time::timeout(timeout, rx.recv()).await;
+        Ok(self.get_task(task_id).await?.unwrap_or(task))
     }
 
     /// Get the latest accumulated event for a series.
@@ -595,10 +2906,88 @@ impl TaskEngine {
 
     // ─── Private ─────────────────────────────────────────────────────────
 
+    /// The stored history replayed to a subscriber under `filter`, shaped
+    /// for it: accumulate series collapsed for late joiners, running totals
+    /// attached for series views that carry them, and json-patch series
+    /// materialized on request.
+    async fn load_replay_events(
+        &self,
+        task_id: &str,
+        filter: &SubscribeFilter,
+        history_limit: Option<u64>,
+    ) -> Result<Vec<TaskEvent>, EngineError> {
+        let since = store_cursor(filter);
+        let store_cursor = since.is_some();
+        let history_opts =
+            (since.is_some() || history_limit.is_some()).then_some(EventQueryOptions {
+                since,
+                limit: history_limit,
+                label_selector: None,
+                until: None,
+                before_index: None,
+            });
+        // Running totals are folded from the start of each series, so for
+        // views that carry them the cursor is applied after folding.
+        let folds_series = folds_series(filter);
+        let history = if folds_series && store_cursor {
+            let mut events = self.get_events(task_id, None).await?;
+            attach_accumulated_data(&mut events);
+            apply_event_query(events, history_opts.as_ref())
+        } else {
+            self.get_events(task_id, history_opts).await?
+        };
+
+        // Late joiners without a cursor get accumulate series collapsed to a snapshot.
+        let mut replay_events = if filter.since.is_none() {
+            collapse_accumulate_series(&history, |tid: &str, sid: &str| {
+                let tid = tid.to_string();
+                let sid = sid.to_string();
+                async move {
+                    self.get_series_latest(&tid, &sid)
+                        .await
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                }
+            })
+            .await
+            .unwrap_or(history)
+        } else {
+            history
+        };
+        if folds_series && !store_cursor {
+            attach_accumulated_data(&mut replay_events);
+        }
+        if filter.materialize_series == Some(true) {
+            replay_events = collapse_json_patch_series(
+                &replay_events,
+                filter.since.is_none(),
+                |tid: &str, sid: &str| {
+                    let tid = tid.to_string();
+                    let sid = sid.to_string();
+                    async move {
+                        self.get_series_latest(&tid, &sid)
+                            .await
+                            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                    }
+                },
+            )
+            .await
+            .unwrap_or(replay_events);
+        }
+        Ok(replay_events)
+    }
+
     async fn build_export_archive(&self, task: &Task) -> Result<TaskArchive, EngineError> {
-        let short_term_events = self.short_term_store.get_events(&task.id, None).await?;
+        // Both stores are read up to the same point, so events published
+        // in between cannot land in one read and not the other.
+        let scan = self.begin_scan(&task.id).await?;
+        let short_term_events = self
+            .short_term_store
+            .get_events(&task.id, scan.bound(None))
+            .await?;
         if let Some(ref long_term_store) = self.long_term_store {
-            let long_term_events = long_term_store.get_events(&task.id, None).await?;
+            let long_term_events = long_term_store
+                .get_events(&task.id, scan.bound(None))
+                .await?;
             if !long_term_events.is_empty() {
                 let merged = self.merge_export_histories(&long_term_events, &short_term_events)?;
                 return self.normalize_export_archive(task, merged).await;
@@ -762,21 +3151,98 @@ impl TaskEngine {
         // Acquire per-task lock to serialize event storage + broadcast,
         // preventing race conditions where concurrent publishes could
         // store events in a different order than their assigned indices.
-        let emit_lock = {
-            let mut locks = self.emit_locks.lock().unwrap();
-            locks
-                .entry(task_id.to_string())
-                .or_insert_with(|| Arc::new(TokioMutex::new(())))
-                .clone()
-        };
+        let emit_lock = self.emit_lock(task_id);
         let _guard = emit_lock.lock().await;
+        self.emit_locked(task_id, input, None).await
+    }
+
+    /// Stores and broadcasts an event while holding the task's emit lock.
+    /// A `replicated` event keeps its origin's id, index and timestamp and
+    /// is not passed to sinks.
+    /// Holds an event that would start a series past the task's cap to the
+    /// configured [`SeriesOverLimit`].
+    async fn apply_series_limit(
+        &self,
+        task_id: &str,
+        input: &mut PublishEventInput,
+    ) -> Result<(), EngineError> {
+        let Some(ref series_id) = input.series_id else {
+            return Ok(());
+        };
+        if matches!(input.series_mode, None | Some(SeriesMode::KeepAll)) {
+            return Ok(());
+        }
+        let store = self.short_term_store.as_ref();
+        if store.get_series_latest(task_id, series_id).await?.is_some() {
+            return Ok(());
+        }
+        let limit = self.series_limits.max_per_task;
+        if store.series_count(task_id).await? < limit as u64 {
+            return Ok(());
+        }
+        match self.series_limits.over_limit {
+            SeriesOverLimit::Reject => Err(EngineError::SeriesLimitExceeded {
+                task_id: task_id.to_string(),
+                limit,
+            }),
+            SeriesOverLimit::SkipSeries => {
+                input.series_mode = None;
+                input.series_acc_field = None;
+                Ok(())
+            }
+        }
+    }
+
+    async fn emit_locked(
+        &self,
+        task_id: &str,
+        mut input: PublishEventInput,
+        replicated: Option<ReplicatedOrigin>,
+    ) -> Result<TaskEvent, EngineError> {
+        let debounce_ms = input.broadcast_debounce_ms;
+        let series_end = input.series_end;
+
+        // Replicated events were already admitted by their origin.
+        if replicated.is_none() {
+            self.apply_series_limit(task_id, &mut input).await?;
+        }
+
+        // Patches are applied before an index is assigned, so a rejected
+        // patch leaves no gap in the task's indices.
+        let patched_document = match (&input.series_id, &input.series_mode) {
+            (Some(series_id), Some(SeriesMode::JsonPatch)) => {
+                let document =
+                    series_document(self.short_term_store.as_ref(), task_id, series_id).await?;
+                Some(
+                    apply_json_patch(&document, &input.data)
+                        .map_err(EngineError::InvalidSeriesPatch)?,
+                )
+            }
+            _ => None,
+        };
 
-        let index = self.short_term_store.next_index(task_id).await?;
+        let (id, index, timestamp) = match replicated {
+            Some(ref origin) => {
+                // Indices the origin gave to events never replicated here
+                // are skipped, so later events keep their indices.
+                while self.short_term_store.next_index(task_id).await? < origin.index {}
+                (origin.id.clone(), origin.index, origin.timestamp)
+            }
+            None => (
+                ulid::Ulid::new().to_string(),
+                self.short_term_store.next_index(task_id).await?,
+                now_millis(),
+            ),
+        };
+        let sinks: &[Arc<dyn EventSink>] = match replicated {
+            Some(_) => &[],
+            None => &self.sinks,
+        };
         let raw = TaskEvent {
-            id: ulid::Ulid::new().to_string(),
+            id,
             task_id: task_id.to_string(),
             index,
-            timestamp: now_millis(),
+            timestamp,
             r#type: input.r#type,
             level: input.level,
             data: input.data,
@@ -784,29 +3250,71 @@ impl TaskEngine {
             series_mode: input.series_mode,
             series_acc_field: input.series_acc_field,
             series_snapshot: None,
+            labels: input.labels.filter(|l| !l.is_empty()),
+            replaces_event_id: None,
             _accumulated_data: None,
         };
 
-        let series_result = process_series(raw, self.short_term_store.as_ref()).await?;
+        let series_result = match patched_document {
+            Some(document) => {
+                store_series_document(raw, document, self.short_term_store.as_ref()).await?
+            }
+            None => process_series(raw, self.short_term_store.as_ref()).await?,
+        };
         let event = series_result.event;
 
         // Store delta event in short-term store (skip if process_series already stored it)
         if !series_result.stored {
-            self.short_term_store
-                .append_event(task_id, event.clone())
-                .await?;
+            timed(
+                self.read_router.short_term_latency(),
+                self.short_term_store.append_event(task_id, event.clone()),
+            )
+            .await?;
         }
 
-        // Attach accumulated data to broadcast event for SSE accumulated subscribers
-        let broadcast_event = if let Some(ref accumulated) = series_result.accumulated_event {
-            TaskEvent {
-                _accumulated_data: Some(accumulated.data.clone()),
-                ..event.clone()
+        // Attach accumulated data to broadcast event for SSE accumulated
+        // subscribers, and mark a live rewrite of a `latest` series so
+        // subscribers do not take it for a new event.
+        let broadcast_event = TaskEvent {
+            _accumulated_data: series_result
+                .accumulated_event
+                .as_ref()
+                .map(|accumulated| accumulated.data.clone()),
+            replaces_event_id: series_result.replaced_event_id.clone(),
+            ..event.clone()
+        };
+        let live_event = if debounces(&broadcast_event) {
+            let offered = self.debouncer.offer(
+                broadcast_event.clone(),
+                debounce_ms,
+                series_end,
+                self.clock.now_ms(),
+            );
+            match offered {
+                Debounced::Now(live_event) => Some(*live_event),
+                Debounced::Held(opened) => {
+                    if let (Some(generation), Some(window_ms)) = (opened, debounce_ms) {
+                        self.schedule_held_broadcast(&event, generation, window_ms);
+                    }
+                    None
+                }
             }
         } else {
-            event.clone()
+            Some(broadcast_event.clone())
         };
-        self.broadcast.publish(task_id, broadcast_event).await?;
+        if let Some(live_event) = live_event {
+            self.channels
+                .publish(self.broadcast.as_ref(), live_event)
+                .await?;
+        }
+
+        for sink in sinks {
+            if let Err(err) = sink.on_event(&broadcast_event).await {
+                if let Some(ref hooks) = self.hooks {
+                    hooks.on_event_dropped(&broadcast_event, &format!("event sink: {err}"));
+                }
+            }
+        }
 
         if let Some(ref long_term_store) = self.long_term_store {
             let long_term_store = Arc::clone(long_term_store);
@@ -816,7 +3324,9 @@ impl TaskEngine {
                 .clone()
                 .unwrap_or_else(|| raw_event.clone());
             let hooks = self.hooks.clone();
-            tokio::spawn(async move {
+            let pending_write = self.read_router.begin_write(task_id);
+            let write = async move {
+                let _pending_write = pending_write;
                 if let Err(err) =
                     persist_long_term_event(long_term_store, raw_event, accumulated_event).await
                 {
@@ -824,14 +3334,115 @@ impl TaskEngine {
                         hooks.on_event_dropped(&store_event, &err.to_string());
                     }
                 }
-            });
+            };
+            match self.write_shaper {
+                Some(ref shaper) => {
+                    if !shaper
+                        .submit(&self.background, task_id, Box::pin(write))
+                        .await
+                    {
+                        if let Some(ref hooks) = self.hooks {
+                            let event = series_result.accumulated_event.as_ref().unwrap_or(&event);
+                            hooks.on_event_dropped(event, "long-term write queue full");
+                        }
+                    }
+                }
+                None => {
+                    self.background
+                        .spawn("long_term.save_event", Some(task_id.to_string()), write);
+                }
+            }
         }
 
         Ok(event)
     }
+
+    fn emit_lock(&self, task_id: &str) -> Arc<TokioMutex<()>> {
+        let mut locks = self.emit_locks.lock().unwrap();
+        locks
+            .entry(task_id.to_string())
+            .or_insert_with(|| Arc::new(TokioMutex::new(())))
+            .clone()
+    }
+
+    /// Broadcasts the window `event` opened when it closes, `window_ms`
+    /// from now, unless a later event of its series closes it first.
+    fn schedule_held_broadcast(&self, event: &TaskEvent, generation: u64, window_ms: u64) {
+        let task_id = event.task_id.clone();
+        let series_id = event.series_id.clone().unwrap_or_default();
+        let debouncer = Arc::clone(&self.debouncer);
+        let broadcast = Arc::clone(&self.broadcast);
+        let channels = self.channels;
+        let emit_locks = Arc::clone(&self.emit_locks);
+        let hooks = self.hooks.clone();
+        self.background
+            .spawn("broadcast.debounce", Some(task_id.clone()), async move {
+                tokio::time::sleep(Duration::from_millis(window_ms)).await;
+                // Taken under the emit lock so the window cannot close after
+                // an event published behind it. A task whose lock is gone
+                // has ended and its windows with it.
+                let Some(emit_lock) = emit_locks.lock().unwrap().get(&task_id).cloned() else {
+                    return;
+                };
+                let _guard = emit_lock.lock().await;
+                let Some(held) = debouncer.take(&task_id, &series_id, generation) else {
+                    return;
+                };
+                if let Err(err) = channels.publish(broadcast.as_ref(), held).await {
+                    if let Some(hooks) = hooks {
+                        hooks.on_unhandled_error(
+                            err.as_ref(),
+                            &ErrorContext {
+                                operation: "broadcast.debounce".to_string(),
+                                task_id: Some(task_id),
+                            },
+                        );
+                    }
+                }
+            });
+    }
+
+    /// Broadcasts every series event of `task_id` held by a debounce window.
+    async fn flush_held_broadcasts(&self, task_id: &str) -> Result<(), EngineError> {
+        let emit_lock = self.emit_lock(task_id);
+        let _guard = emit_lock.lock().await;
+        for held in self.debouncer.drain(task_id) {
+            self.channels.publish(self.broadcast.as_ref(), held).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The part of `filter`'s cursor the store resolves: id and timestamp
+/// cursors. An index cursor counts filtered events, so it is applied to the
+/// filtered stream instead.
+fn store_cursor(filter: &SubscribeFilter) -> Option<SinceCursor> {
+    filter.since.as_ref().and_then(|s| {
+        (s.id.is_some() || s.timestamp.is_some()).then(|| SinceCursor {
+            id: s.id.clone(),
+            index: None,
+            timestamp: s.timestamp,
+        })
+    })
 }
 
-async fn persist_long_term_event(
+/// Whether `filter`'s series view carries running totals.
+fn folds_series(filter: &SubscribeFilter) -> bool {
+    matches!(
+        filter.series_format,
+        Some(SeriesFormat::Accumulated | SeriesFormat::Both)
+    )
+}
+
+/// Awaits `future`, recording how long it took in `latency`.
+async fn timed<T>(latency: &LatencyEwma, future: impl Future<Output = T>) -> T {
+    let started = Instant::now();
+    let output = future.await;
+    latency.record(started.elapsed());
+    output
+}
+
+pub(crate) async fn persist_long_term_event(
     long_term_store: Arc<dyn LongTermStore>,
     event: TaskEvent,
     accumulated_event: Option<TaskEvent>,
@@ -858,7 +3469,8 @@ async fn persist_long_term_event(
                         .await?;
                     return Ok(());
                 }
-                SeriesMode::KeepAll => {}
+                // Every patch is needed to rebuild the document.
+                SeriesMode::KeepAll | SeriesMode::JsonPatch => {}
             }
         }
     }
@@ -899,13 +3511,135 @@ fn unsupported_archive_restore(message: &'static str) -> EngineError {
     )))
 }
 
+/// Normalizes the event type patterns `task` is created with: its filter
+/// presets, webhook filters and cleanup rule event filters.
+fn normalize_task_types(rules: &EventTypeRules, task: &mut Task) {
+    for filter in task.filters.iter_mut().flat_map(|f| f.values_mut()) {
+        rules.normalize_filter(filter);
+    }
+    for webhook in task.webhooks.iter_mut().flatten() {
+        if let Some(ref mut filter) = webhook.filter {
+            rules.normalize_filter(filter);
+        }
+    }
+    let cleanup_rules = task.cleanup.iter_mut().flat_map(|c| c.rules.iter_mut());
+    for rule in cleanup_rules {
+        if let Some(types) = rule.event_filter.as_mut().and_then(|f| f.types.as_mut()) {
+            rules.normalize_patterns(types);
+        }
+    }
+}
+
+/// Rejects a task whose webhooks reference a filter preset it doesn't define,
+/// so a bad name fails the write instead of every later delivery.
+fn validate_webhook_presets(task: &Task) -> Result<(), EngineError> {
+    for webhook in task.webhooks.iter().flatten() {
+        resolve_filter(
+            task.filters.as_ref(),
+            webhook.filter_preset.as_deref(),
+            webhook.filter.clone().unwrap_or_default(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Fails unless `task` is at `expected_version`, when one is given.
+fn check_version(task: &Task, expected_version: Option<u64>) -> Result<(), EngineError> {
+    match expected_version {
+        Some(expected) if expected != task.version => {
+            Err(EngineError::ConcurrentModification(task.id.clone()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_transition(task: &Task, to: &TaskStatus) -> Result<(), EngineError> {
+    if !can_transition(&task.status, to) {
+        return Err(EngineError::InvalidTransition {
+            from: task.status.clone(),
+            to: to.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// `task` as it is after moving to `to` at `now` with `payload`. The TTL is
+/// recorded on the task but the store's TTL clock is left to the caller.
+fn transitioned(
+    task: &Task,
+    to: TaskStatus,
+    payload: Option<&TransitionPayload>,
+    now: f64,
+) -> Result<Task, EngineError> {
+    let new_result = payload
+        .and_then(|p| p.result.clone())
+        .or_else(|| task.result.clone());
+    let new_error = payload
+        .and_then(|p| p.error.clone())
+        .or_else(|| task.error.clone());
+    let new_completed_at = if is_terminal(&to) {
+        Some(now)
+    } else {
+        task.completed_at
+    };
+
+    let mut updated = Task {
+        status: to.clone(),
+        updated_at: now,
+        completed_at: new_completed_at,
+        result: new_result,
+        error: new_error,
+        ..task.clone()
+    };
+
+    if let Some(filters) = payload.and_then(|p| p.filters.clone()) {
+        updated.filters = (!filters.is_empty()).then_some(filters);
+        validate_webhook_presets(&updated)?;
+    }
+
+    // ─── Suspended-state field management ────────────────────────────────
+    // Set reason when entering suspended state
+    if is_suspended(&to) {
+        if let Some(payload) = payload {
+            if payload.reason.is_some() {
+                updated.reason = payload.reason.clone();
+            }
+        }
+    } else {
+        // Clear suspended fields when leaving suspended state
+        updated.reason = None;
+        updated.blocked_request = None;
+        updated.resume_at = None;
+    }
+
+    // Blocked-specific: set blockedRequest and resumeAt
+    if to == TaskStatus::Blocked {
+        if let Some(payload) = payload {
+            if payload.blocked_request.is_some() {
+                updated.blocked_request = payload.blocked_request.clone();
+            }
+            if let Some(resume_after_ms) = payload.resume_after_ms {
+                updated.resume_at = Some(now + resume_after_ms);
+            }
+        }
+    }
+
+    if let Some(ttl) = payload.and_then(|p| p.ttl) {
+        updated.ttl = Some(ttl);
+    }
+    Ok(updated)
+}
+
 // ─── Tests ───────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::memory_adapters::{MemoryBroadcastProvider, MemoryShortTermStore};
-    use crate::types::{LongTermStore, SeriesMode, WorkerAuditEvent};
+    use crate::types::{
+        CleanupEventFilter, CleanupRule, CleanupTarget, CleanupTrigger, ErrorContext, LongTermStore,
+        SeriesMode, WorkerAuditEvent,
+    };
     use std::sync::atomic::{AtomicU64, Ordering};
     use tokio::sync::RwLock as TokioRwLock;
 
@@ -917,6 +3651,7 @@ mod tests {
         replace_latest_calls: AtomicU64,
         accumulate_calls: AtomicU64,
         fail_save_event: bool,
+        panic_save_event: bool,
     }
 
     impl MockLongTermStore {
@@ -927,6 +3662,7 @@ mod tests {
                 replace_latest_calls: AtomicU64::new(0),
                 accumulate_calls: AtomicU64::new(0),
                 fail_save_event: false,
+                panic_save_event: false,
             }
         }
 
@@ -937,6 +3673,14 @@ mod tests {
                 replace_latest_calls: AtomicU64::new(0),
                 accumulate_calls: AtomicU64::new(0),
                 fail_save_event: true,
+                panic_save_event: false,
+            }
+        }
+
+        fn panicking_save_event() -> Self {
+            Self {
+                panic_save_event: true,
+                ..Self::new()
             }
         }
     }
@@ -962,6 +3706,9 @@ mod tests {
             &self,
             event: TaskEvent,
         ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self.panic_save_event {
+                panic!("mock save_event panic");
+            }
             if self.fail_save_event {
                 return Err("mock save_event failure".into());
             }
@@ -1067,6 +3814,25 @@ mod tests {
             true
         }
 
+        async fn redact_events(
+            &self,
+            task_id: &str,
+            tombstones: &[TaskEvent],
+        ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+            let mut events = self.events.write().await;
+            let mut replaced = 0;
+            for tombstone in tombstones {
+                if let Some(event) = events
+                    .iter_mut()
+                    .find(|e| e.task_id == task_id && e.id == tombstone.id)
+                {
+                    *event = tombstone.clone();
+                    replaced += 1;
+                }
+            }
+            Ok(replaced)
+        }
+
         async fn save_worker_event(
             &self,
             _event: WorkerAuditEvent,
@@ -1087,12 +3853,14 @@ mod tests {
 
     struct MockHooks {
         dropped_count: AtomicU64,
+        unhandled: Mutex<Vec<(String, ErrorContext)>>,
     }
 
     impl MockHooks {
         fn new() -> Self {
             Self {
                 dropped_count: AtomicU64::new(0),
+                unhandled: Mutex::new(Vec::new()),
             }
         }
     }
@@ -1101,6 +3869,17 @@ mod tests {
         fn on_event_dropped(&self, _event: &TaskEvent, _reason: &str) {
             self.dropped_count.fetch_add(1, Ordering::SeqCst);
         }
+
+        fn on_unhandled_error(
+            &self,
+            err: &(dyn std::error::Error + Send + Sync),
+            context: &ErrorContext,
+        ) {
+            self.unhandled
+                .lock()
+                .unwrap()
+                .push((err.to_string(), context.clone()));
+        }
     }
 
     fn make_engine() -> TaskEngine {
@@ -1109,6 +3888,9 @@ mod tests {
             broadcast: Arc::new(MemoryBroadcastProvider::new()),
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         })
     }
 
@@ -1118,6 +3900,9 @@ mod tests {
             broadcast,
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         })
     }
 
@@ -1173,17 +3958,37 @@ mod tests {
                 ttl: Some(3600),
                 webhooks: Some(vec![WebhookConfig {
                     url: "https://hook.example.com".to_string(),
+                    group: None,
+                    suppression_window_ms: None,
+                    mode: None,
                     filter: None,
                     secret: None,
                     wrap: None,
                     retry: None,
+                    filter_preset: None,
+                    backfill: None,
+                    initial_delivery: None,
+                    max_payload_bytes: None,
+                    task_match: None,
                 }]),
-                cleanup: Some(CleanupConfig { rules: vec![] }),
+                cleanup: Some(CleanupConfig {
+                    rules: vec![],
+                    abandoned_pending: None,
+                }),
                 auth_config: Some(TaskAuthConfig { rules: vec![] }),
                 tags: Some(vec!["gpu".to_string()]),
                 assign_mode: Some(AssignMode::Pull),
                 cost: Some(2),
                 disconnect_policy: Some(DisconnectPolicy::Reassign),
+                filters: None,
+                retry_policy: None,
+                group_policy: None,
+                forward_to: None,
+                scheduled_for: None,
+                deadline_ms: None,
+                origin: None,
+                parent_id: None,
+                completion_policy: None,
             })
             .await
             .unwrap();
@@ -1244,11 +4049,142 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
-            matches!(err, EngineError::TaskConflict(_)),
-            "Expected TaskConflict error, got: {err}"
+            matches!(err, EngineError::TaskAlreadyExists { .. }),
+            "Expected TaskAlreadyExists error, got: {err}"
         );
     }
 
+    #[tokio::test]
+    async fn create_task_conflict_carries_winning_task() {
+        let engine = make_engine();
+        let mut params = HashMap::new();
+        params.insert("owner".to_string(), serde_json::json!("first"));
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("dup-id".to_string()),
+                params: Some(params.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let err = engine
+            .create_task(CreateTaskInput {
+                id: Some("dup-id".to_string()),
+                params: Some(HashMap::from([(
+                    "owner".to_string(),
+                    serde_json::json!("second"),
+                )])),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        match err {
+            EngineError::TaskAlreadyExists { task_id, existing } => {
+                assert_eq!(task_id, "dup-id");
+                assert_eq!(existing.unwrap().params, Some(params));
+            }
+            other => panic!("Expected TaskAlreadyExists error, got: {other}"),
+        }
+        let stored = engine.get_task("dup-id").await.unwrap().unwrap();
+        assert_eq!(stored.params.unwrap()["owner"], "first");
+    }
+
+    #[tokio::test]
+    async fn concurrent_creates_with_same_id_have_one_winner() {
+        let engine = Arc::new(make_engine());
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let engine = Arc::clone(&engine);
+            handles.push(tokio::spawn(async move {
+                engine
+                    .create_task(CreateTaskInput {
+                        id: Some("race".to_string()),
+                        params: Some(HashMap::from([("n".to_string(), serde_json::json!(i))])),
+                        ..Default::default()
+                    })
+                    .await
+            }));
+        }
+
+        let mut winners = Vec::new();
+        for handle in handles {
+            match handle.await.unwrap() {
+                Ok(task) => winners.push(task),
+                Err(EngineError::TaskAlreadyExists { .. }) => {}
+                Err(other) => panic!("unexpected error: {other}"),
+            }
+        }
+
+        assert_eq!(winners.len(), 1);
+        let stored = engine.get_task("race").await.unwrap().unwrap();
+        assert_eq!(stored.params, winners[0].params);
+    }
+
+    fn errors_only_presets() -> HashMap<String, SubscribeFilter> {
+        HashMap::from([(
+            "errorsOnly".to_string(),
+            SubscribeFilter {
+                levels: Some(vec![Level::Error]),
+                ..Default::default()
+            },
+        )])
+    }
+
+    fn webhook_with_preset(preset: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: "https://example.com/hook".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter: None,
+            filter_preset: Some(preset.to_string()),
+            secret: None,
+            wrap: None,
+            retry: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_task_accepts_webhook_referencing_defined_preset() {
+        let engine = make_engine();
+        let task = engine
+            .create_task(CreateTaskInput {
+                filters: Some(errors_only_presets()),
+                webhooks: Some(vec![webhook_with_preset("errorsOnly")]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let stored = engine.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(stored.filters, Some(errors_only_presets()));
+    }
+
+    #[tokio::test]
+    async fn create_task_rejects_webhook_with_unknown_preset() {
+        let engine = make_engine();
+        let result = engine
+            .create_task(CreateTaskInput {
+                id: Some("bad-preset".to_string()),
+                filters: Some(errors_only_presets()),
+                webhooks: Some(vec![webhook_with_preset("warnings")]),
+                ..Default::default()
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(EngineError::FilterPreset(FilterPresetError::UnknownPreset(ref name))) if name == "warnings"
+        ));
+        assert!(engine.get_task("bad-preset").await.unwrap().is_none());
+    }
+
     // ─── get_task ────────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -1276,6 +4212,44 @@ mod tests {
 
     // ─── transition_task ─────────────────────────────────────────────────
 
+    #[tokio::test]
+    async fn validate_and_preview_transition_change_nothing() {
+        let engine = make_engine();
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        engine
+            .validate_transition("t1", &TaskStatus::Running)
+            .await
+            .unwrap();
+        assert!(matches!(
+            engine
+                .validate_transition("t1", &TaskStatus::Completed)
+                .await,
+            Err(EngineError::InvalidTransition { .. })
+        ));
+        assert!(matches!(
+            engine
+                .validate_transition("ghost", &TaskStatus::Running)
+                .await,
+            Err(EngineError::TaskNotFound(_))
+        ));
+
+        let preview = engine
+            .preview_transition("t1", TaskStatus::Running, None, None)
+            .await
+            .unwrap();
+        assert_eq!(preview.status, TaskStatus::Running);
+        let task = engine.get_task("t1").await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert!(engine.get_events("t1", None).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn transition_task_pending_to_running() {
         let engine = make_engine();
@@ -1477,6 +4451,80 @@ mod tests {
         assert_eq!(data["status"], "running");
     }
 
+    #[tokio::test]
+    async fn transition_task_replaces_filter_presets() {
+        let engine = make_engine();
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let task = engine
+            .transition_task(
+                "t1",
+                TaskStatus::Running,
+                Some(TransitionPayload {
+                    filters: Some(errors_only_presets()),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(task.filters, Some(errors_only_presets()));
+
+        // An empty map removes the presets.
+        let task = engine
+            .transition_task(
+                "t1",
+                TaskStatus::Paused,
+                Some(TransitionPayload {
+                    filters: Some(HashMap::new()),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(task.filters, None);
+    }
+
+    #[tokio::test]
+    async fn transition_task_rejects_removing_preset_used_by_webhook() {
+        let engine = make_engine();
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                filters: Some(errors_only_presets()),
+                webhooks: Some(vec![webhook_with_preset("errorsOnly")]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let result = engine
+            .transition_task(
+                "t1",
+                TaskStatus::Running,
+                Some(TransitionPayload {
+                    filters: Some(HashMap::new()),
+                    ..Default::default()
+                }),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(EngineError::FilterPreset(FilterPresetError::UnknownPreset(
+                _
+            )))
+        ));
+        let stored = engine.get_task("t1").await.unwrap().unwrap();
+        assert_eq!(stored.status, TaskStatus::Pending);
+        assert_eq!(stored.filters, Some(errors_only_presets()));
+    }
+
     // ─── publish_event ───────────────────────────────────────────────────
 
     #[tokio::test]
@@ -1500,7 +4548,7 @@ mod tests {
         let count_clone = Arc::clone(&broadcast_count);
         let _unsub = broadcast
             .subscribe(
-                "t1",
+                &task_channel("t1"),
                 Box::new(move |_| {
                     count_clone.fetch_add(1, Ordering::SeqCst);
                 }),
@@ -1518,6 +4566,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1565,6 +4616,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await;
@@ -1591,6 +4645,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await;
@@ -1630,47 +4687,259 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        let e2 = engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "b".to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!(null),
+                    series_id: None,
+                    series_mode: None,
+
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        let e3 = engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "c".to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!(null),
+                    series_id: None,
+                    series_mode: None,
+
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
             .unwrap();
 
-        let e2 = engine
-            .publish_event(
-                "t1",
-                PublishEventInput {
-                    r#type: "b".to_string(),
-                    level: Level::Info,
-                    data: serde_json::json!(null),
-                    series_id: None,
-                    series_mode: None,
+        // Index 0 was used by the transition_task status event
+        assert_eq!(e1.index, 1);
+        assert_eq!(e2.index, 2);
+        assert_eq!(e3.index, 3);
+    }
+
+    fn labeled_input(labels: HashMap<String, String>) -> PublishEventInput {
+        PublishEventInput {
+            r#type: "log".to_string(),
+            level: Level::Info,
+            data: serde_json::json!(null),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            labels: Some(labels),
+            broadcast_debounce_ms: None,
+            series_end: false,
+        }
+    }
+
+    async fn make_running_task(engine: &TaskEngine, id: &str) {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task(id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn publish_event_stores_labels_and_drops_empty_sets() {
+        let engine = make_engine();
+        make_running_task(&engine, "t1").await;
+
+        let labels = HashMap::from([("region".to_string(), "eu".to_string())]);
+        let labeled = engine
+            .publish_event("t1", labeled_input(labels.clone()))
+            .await
+            .unwrap();
+        assert_eq!(labeled.labels, Some(labels));
+
+        let unlabeled = engine
+            .publish_event("t1", labeled_input(HashMap::new()))
+            .await
+            .unwrap();
+        assert_eq!(unlabeled.labels, None);
+    }
+
+    #[tokio::test]
+    async fn publish_event_rejects_too_many_labels() {
+        let engine = make_engine();
+        make_running_task(&engine, "t1").await;
+
+        let labels: HashMap<String, String> = (0..17)
+            .map(|i| (format!("k{i}"), "v".to_string()))
+            .collect();
+        let err = engine
+            .publish_event("t1", labeled_input(labels))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EngineError::InvalidInput(_)),
+            "Expected InvalidInput error, got: {err}"
+        );
+
+        // Nothing was stored for the rejected event
+        let events = engine.get_events("t1", None).await.unwrap();
+        assert_eq!(events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn publish_event_rejects_overlong_label_keys_and_values() {
+        let engine = make_engine();
+        make_running_task(&engine, "t1").await;
+
+        let long_key = HashMap::from([("k".repeat(65), "v".to_string())]);
+        let err = engine
+            .publish_event("t1", labeled_input(long_key))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InvalidInput(_)));
+
+        let long_value = HashMap::from([("k".to_string(), "v".repeat(257))]);
+        let err = engine
+            .publish_event("t1", labeled_input(long_value))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn publish_event_honours_custom_label_limits() {
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: Some(LabelLimits {
+                max_labels: 1,
+                ..Default::default()
+            }),
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+        make_running_task(&engine, "t1").await;
+
+        let one = HashMap::from([("a".to_string(), "1".to_string())]);
+        assert!(engine.publish_event("t1", labeled_input(one)).await.is_ok());
+
+        let two = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]);
+        let err = engine
+            .publish_event("t1", labeled_input(two))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn publish_event_rejects_malformed_types() {
+        let engine = make_engine();
+        make_running_task(&engine, "t1").await;
+
+        for bad in ["", "a,b", "has space", "tab\t", "caf\u{e9}", &"x".repeat(257)] {
+            let err = engine.publish_event("t1", typed_input(bad)).await.unwrap_err();
+            assert!(matches!(err, EngineError::InvalidInput(_)), "{bad:?}");
+        }
+        let event = engine
+            .publish_event("t1", typed_input(&"x".repeat(256)))
+            .await
+            .unwrap();
+        assert_eq!(event.r#type.len(), 256);
+        assert_eq!(engine.event_count("t1").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn publish_event_keeps_type_case_by_default() {
+        let engine = make_engine();
+        make_running_task(&engine, "t1").await;
 
-                    series_acc_field: None,
-                },
-            )
+        let event = engine.publish_event("t1", typed_input("Progress")).await.unwrap();
+        assert_eq!(event.r#type, "Progress");
+    }
+
+    #[tokio::test]
+    async fn lowercase_normalization_applies_to_types_and_patterns() {
+        let engine = make_engine().with_event_types(EventTypeRules {
+            normalization: crate::filter::TypeNormalization::Lowercase,
+            ..Default::default()
+        });
+        let filter = SubscribeFilter {
+            types: Some(vec!["Progress".to_string(), "LLM.*".to_string()]),
+            ..Default::default()
+        };
+        let task = engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                filters: Some(HashMap::from([("main".to_string(), filter.clone())])),
+                webhooks: Some(vec![WebhookConfig {
+                    filter: Some(filter),
+                    filter_preset: None,
+                    ..webhook_with_preset("main")
+                }]),
+                cleanup: Some(CleanupConfig {
+                    rules: vec![CleanupRule {
+                        name: None,
+                        r#match: None,
+                        trigger: CleanupTrigger { after_ms: None },
+                        target: CleanupTarget::Events,
+                        event_filter: Some(CleanupEventFilter {
+                            types: Some(vec!["Progress".to_string()]),
+                            levels: None,
+                            older_than_ms: None,
+                            series_mode: None,
+                        }),
+                    }],
+                    abandoned_pending: None,
+                }),
+                ..Default::default()
+            })
             .await
             .unwrap();
 
-        let e3 = engine
-            .publish_event(
-                "t1",
-                PublishEventInput {
-                    r#type: "c".to_string(),
-                    level: Level::Info,
-                    data: serde_json::json!(null),
-                    series_id: None,
-                    series_mode: None,
+        let lowered = Some(vec!["progress".to_string(), "llm.*".to_string()]);
+        assert_eq!(task.filters.unwrap()["main"].types, lowered);
+        let webhooks = task.webhooks.unwrap();
+        assert_eq!(webhooks[0].filter.as_ref().unwrap().types, lowered);
+        let rule = &task.cleanup.unwrap().rules[0];
+        assert_eq!(
+            rule.event_filter.as_ref().unwrap().types,
+            Some(vec!["progress".to_string()])
+        );
 
-                    series_acc_field: None,
-                },
-            )
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
             .await
             .unwrap();
-
-        // Index 0 was used by the transition_task status event
-        assert_eq!(e1.index, 1);
-        assert_eq!(e2.index, 2);
-        assert_eq!(e3.index, 3);
+        let event = engine.publish_event("t1", typed_input("PROGRESS")).await.unwrap();
+        assert_eq!(event.r#type, "progress");
+        assert!(crate::filter::matches_type(&event.r#type, lowered.as_deref()));
     }
 
     // ─── get_events ──────────────────────────────────────────────────────
@@ -1701,6 +4970,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1710,6 +4982,8 @@ mod tests {
         assert_eq!(events.len(), 2); // 1 status + 1 progress
         assert_eq!(events[0].r#type, "taskcast:status");
         assert_eq!(events[1].r#type, "progress");
+        assert_eq!(engine.event_count("t1").await.unwrap(), 2);
+        assert_eq!(engine.event_count("unknown").await.unwrap(), 0);
     }
 
     // ─── list_tasks ──────────────────────────────────────────────────────
@@ -1755,13 +5029,346 @@ mod tests {
         assert!(tasks.is_empty());
     }
 
-    // ─── subscribe ───────────────────────────────────────────────────────
+    // ─── subscribe ───────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn subscribe_receives_events_via_broadcast() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
+
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let received_types = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let types_clone = Arc::clone(&received_types);
+
+        let _unsub = engine
+            .subscribe(
+                "t1",
+                Box::new(move |event| {
+                    types_clone.lock().unwrap().push(event.r#type.clone());
+                }),
+            )
+            .await;
+
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+
+        engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "progress".to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!({ "percent": 75 }),
+                    series_id: None,
+                    series_mode: None,
+
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        let types = received_types.lock().unwrap();
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0], "taskcast:status");
+        assert_eq!(types[1], "progress");
+    }
+
+    // ─── broadcast channels ─────────────────────────────────────────────
+
+    fn typed_input(event_type: &str) -> PublishEventInput {
+        PublishEventInput {
+            r#type: event_type.to_string(),
+            level: Level::Info,
+            data: serde_json::json!(null),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        }
+    }
+
+    async fn make_running(engine: &TaskEngine, ids: &[&str]) {
+        for id in ids {
+            engine
+                .create_task(CreateTaskInput {
+                    id: Some(id.to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            engine
+                .transition_task(id, TaskStatus::Running, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    type Received = Arc<std::sync::Mutex<Vec<(String, String)>>>;
+
+    /// Collects `(task id, type)` of the events a handler receives.
+    fn recorder() -> (Received, Box<dyn Fn(TaskEvent) + Send + Sync>) {
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let handler = Box::new(move |event: TaskEvent| {
+            sink.lock().unwrap().push((event.task_id, event.r#type));
+        });
+        (received, handler)
+    }
+
+    #[tokio::test]
+    async fn pattern_subscription_receives_events_from_multiple_tasks() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
+        make_running(&engine, &["a1", "a2", "b1"]).await;
+
+        let (received, handler) = recorder();
+        let unsub = engine.subscribe_pattern("task.a*", handler).await.unwrap();
+        for id in ["a1", "b1", "a2"] {
+            engine.publish_event(id, typed_input("log")).await.unwrap();
+        }
+        unsub();
+        engine
+            .publish_event("a1", typed_input("log"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                ("a1".to_string(), "log".to_string()),
+                ("a2".to_string(), "log".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribe_by_type_matches_the_first_type_segment() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast)).with_broadcast_channels(
+            BroadcastChannels {
+                type_channels: true,
+                ..Default::default()
+            },
+        );
+        make_running(&engine, &["t1", "t2"]).await;
+
+        let (received, handler) = recorder();
+        let _unsub = engine.subscribe_by_type("llm", handler).await.unwrap();
+        engine
+            .publish_event("t1", typed_input("llm.delta"))
+            .await
+            .unwrap();
+        engine
+            .publish_event("t1", typed_input("log"))
+            .await
+            .unwrap();
+        engine
+            .publish_event("t2", typed_input("llm.done"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                ("t1".to_string(), "llm.delta".to_string()),
+                ("t2".to_string(), "llm.done".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn type_channels_are_off_by_default() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
+        make_running(&engine, &["t1"]).await;
+
+        let (received, handler) = recorder();
+        let _unsub = engine.subscribe_by_type("*", handler).await.unwrap();
+        engine
+            .publish_event("t1", typed_input("log"))
+            .await
+            .unwrap();
+
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pattern_matching_task_and_type_channels_delivers_once() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast)).with_broadcast_channels(
+            BroadcastChannels {
+                type_channels: true,
+                legacy_channels: true,
+            },
+        );
+        make_running(&engine, &["t1"]).await;
+
+        // `*` matches `task.t1`, `type.log` and the legacy `t1` channel.
+        let (received, handler) = recorder();
+        let _unsub = engine.subscribe_pattern("*", handler).await.unwrap();
+        engine
+            .publish_event("t1", typed_input("log"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [("t1".to_string(), "log".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn legacy_channels_publish_to_both_and_deliver_once() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast)).with_broadcast_channels(
+            BroadcastChannels {
+                legacy_channels: true,
+                ..Default::default()
+            },
+        );
+        make_running(&engine, &["t1"]).await;
+
+        // An old instance listens on the bare task id.
+        let (legacy, legacy_handler) = recorder();
+        let _legacy_unsub = broadcast.subscribe("t1", legacy_handler).await;
+        let (received, handler) = recorder();
+        let unsub = engine.subscribe("t1", handler).await;
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 1);
+        assert_eq!(broadcast.listener_count("t1"), 2);
+
+        engine
+            .publish_event("t1", typed_input("log"))
+            .await
+            .unwrap();
+        // An old instance publishes on the bare task id only.
+        let mut old_event = engine.get_events("t1", None).await.unwrap().remove(0);
+        old_event.id = "from-old-instance".to_string();
+        old_event.r#type = "old".to_string();
+        broadcast.publish("t1", old_event).await.unwrap();
+
+        let log = ("t1".to_string(), "log".to_string());
+        let old = ("t1".to_string(), "old".to_string());
+        assert_eq!(*received.lock().unwrap(), [log.clone(), old.clone()]);
+        assert_eq!(*legacy.lock().unwrap(), [log, old]);
+
+        unsub();
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 0);
+        assert_eq!(broadcast.listener_count("t1"), 1);
+    }
+
+    #[tokio::test]
+    async fn without_legacy_channels_the_bare_task_id_is_unused() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
+        make_running(&engine, &["t1"]).await;
+
+        let (legacy, legacy_handler) = recorder();
+        let _legacy_unsub = broadcast.subscribe("t1", legacy_handler).await;
+        let _unsub = engine.subscribe("t1", Box::new(|_| {})).await;
+        assert_eq!(broadcast.listener_count("t1"), 1);
+
+        engine
+            .publish_event("t1", typed_input("log"))
+            .await
+            .unwrap();
+        assert!(legacy.lock().unwrap().is_empty());
+    }
+
+    // ─── wait_for_terminal ──────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn wait_for_terminal_returns_immediately_for_terminal_task() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Cancelled, None)
+            .await
+            .unwrap();
+
+        let task = engine
+            .wait_for_terminal("t1", Duration::from_secs(30))
+            .await
+            .unwrap();
+
+        assert_eq!(task.status, TaskStatus::Cancelled);
+        assert_eq!(
+            broadcast.listener_count(&task_channel("t1")),
+            0,
+            "must not subscribe"
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_for_terminal_wakes_on_completion() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = Arc::new(make_engine_with_broadcast(Arc::clone(&broadcast)));
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+
+        let waiter = {
+            let engine = Arc::clone(&engine);
+            tokio::spawn(async move {
+                engine
+                    .wait_for_terminal("t1", Duration::from_secs(30))
+                    .await
+            })
+        };
+        while broadcast.listener_count(&task_channel("t1")) == 0 {
+            tokio::task::yield_now().await;
+        }
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let task = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake promptly")
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(
+            broadcast.listener_count(&task_channel("t1")),
+            0,
+            "must unsubscribe"
+        );
+    }
 
     #[tokio::test]
-    async fn subscribe_receives_events_via_broadcast() {
+    async fn wait_for_terminal_returns_current_task_on_timeout() {
         let broadcast = Arc::new(MemoryBroadcastProvider::new());
         let engine = make_engine_with_broadcast(Arc::clone(&broadcast));
-
         engine
             .create_task(CreateTaskInput {
                 id: Some("t1".to_string()),
@@ -1770,43 +5377,93 @@ mod tests {
             .await
             .unwrap();
 
-        let received_types = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
-        let types_clone = Arc::clone(&received_types);
+        let task = engine
+            .wait_for_terminal("t1", Duration::from_millis(20))
+            .await
+            .unwrap();
 
-        let _unsub = engine
-            .subscribe(
-                "t1",
-                Box::new(move |event| {
-                    types_clone.lock().unwrap().push(event.r#type.clone());
-                }),
-            )
-            .await;
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert_eq!(
+            broadcast.listener_count(&task_channel("t1")),
+            0,
+            "must unsubscribe"
+        );
+    }
 
-        engine
-            .transition_task("t1", TaskStatus::Running, None)
+    #[tokio::test]
+    async fn wait_for_terminal_missing_task_is_not_found() {
+        let engine = make_engine();
+        let err = engine
+            .wait_for_terminal("missing", Duration::from_millis(20))
             .await
-            .unwrap();
+            .unwrap_err();
+        assert!(matches!(err, EngineError::TaskNotFound(_)));
+    }
 
-        engine
-            .publish_event(
-                "t1",
-                PublishEventInput {
-                    r#type: "progress".to_string(),
-                    level: Level::Info,
-                    data: serde_json::json!({ "percent": 75 }),
-                    series_id: None,
-                    series_mode: None,
+    /// Completes the task in the store (without broadcasting) right before
+    /// registering the subscription, reproducing a transition that lands
+    /// between the waiter's first check and its subscribe.
+    struct CompleteOnSubscribe {
+        inner: MemoryBroadcastProvider,
+        store: Arc<MemoryShortTermStore>,
+    }
 
-                    series_acc_field: None,
-                },
-            )
+    #[async_trait::async_trait]
+    impl BroadcastProvider for CompleteOnSubscribe {
+        async fn publish(
+            &self,
+            channel: &str,
+            event: TaskEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.publish(channel, event).await
+        }
+
+        async fn subscribe(
+            &self,
+            channel: &str,
+            handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+        ) -> Box<dyn Fn() + Send + Sync> {
+            let task_id = channel.strip_prefix("task.").unwrap();
+            let mut task = self.store.get_task(task_id).await.unwrap().unwrap();
+            task.status = TaskStatus::Completed;
+            self.store.save_task(task).await.unwrap();
+            self.inner.subscribe(channel, handler).await
+        }
+    }
+
+    #[tokio::test]
+    async fn wait_for_terminal_rechecks_after_subscribing() {
+        let store = Arc::new(MemoryShortTermStore::new());
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+            broadcast: Arc::new(CompleteOnSubscribe {
+                inner: MemoryBroadcastProvider::new(),
+                store: Arc::clone(&store),
+            }),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
             .await
             .unwrap();
 
-        let types = received_types.lock().unwrap();
-        assert_eq!(types.len(), 2);
-        assert_eq!(types[0], "taskcast:status");
-        assert_eq!(types[1], "progress");
+        // The status event was never broadcast, so without the re-check this
+        // would sit out the full timeout.
+        let task = tokio::time::timeout(
+            Duration::from_secs(1),
+            engine.wait_for_terminal("t1", Duration::from_secs(30)),
+        )
+        .await
+        .expect("re-check after subscribing should catch the transition")
+        .unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
     }
 
     // ─── Concurrency ────────────────────────────────────────────────────
@@ -1844,6 +5501,9 @@ mod tests {
                             series_mode: None,
 
                             series_acc_field: None,
+                            labels: None,
+                            broadcast_debounce_ms: None,
+                            series_end: false,
                         },
                     )
                     .await
@@ -1962,7 +5622,7 @@ mod tests {
             let arr = Arc::clone(arr);
             let unsub = broadcast
                 .subscribe(
-                    &task.id,
+                    &task_channel(&task.id),
                     Box::new(move |event| {
                         if event.r#type != "taskcast:status" {
                             arr.lock().unwrap().push(event.id.clone());
@@ -1987,6 +5647,9 @@ mod tests {
                         series_mode: None,
 
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -2022,6 +5685,9 @@ mod tests {
             broadcast: Arc::new(MemoryBroadcastProvider::new()),
             long_term_store: Some(long_term_store),
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         })
     }
 
@@ -2071,6 +5737,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         long_term_store.save_task(task).await.unwrap();
 
@@ -2132,6 +5808,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2155,6 +5834,9 @@ mod tests {
             broadcast: Arc::new(MemoryBroadcastProvider::new()),
             long_term_store: Some(long_term_store),
             hooks: Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         });
 
         engine
@@ -2180,6 +5862,9 @@ mod tests {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2191,6 +5876,168 @@ mod tests {
         assert!(hooks.dropped_count.load(Ordering::SeqCst) >= 1);
     }
 
+    /// Records the events it receives, failing every call when `fail` is set.
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<TaskEvent>>,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for RecordingSink {
+        async fn on_event(
+            &self,
+            event: &TaskEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self.fail {
+                return Err("sink unavailable".into());
+            }
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn sinks_receive_every_event_in_order() {
+        let sink = Arc::new(RecordingSink::default());
+        let failing = Arc::new(RecordingSink {
+            fail: true,
+            ..Default::default()
+        });
+        let hooks = Arc::new(MockHooks::new());
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+            label_limits: None,
+            sinks: vec![
+                Arc::clone(&failing) as Arc<dyn EventSink>,
+                Arc::clone(&sink) as Arc<dyn EventSink>,
+            ],
+            coalesce_reads: None,
+        });
+        make_running_task(&engine, "t1").await;
+        for i in 0..3 {
+            engine
+                .publish_event(
+                    "t1",
+                    PublishEventInput {
+                        r#type: "log".to_string(),
+                        level: Level::Info,
+                        data: serde_json::json!({ "i": i }),
+                        series_id: None,
+                        series_mode: None,
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let events = sink.events.lock().unwrap().clone();
+        let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert_eq!(events[0].r#type, "taskcast:status");
+        assert_eq!(events[3].data, serde_json::json!({ "i": 2 }));
+        // The failing sink neither blocks the others nor fails the publish.
+        assert_eq!(hooks.dropped_count.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn emit_reports_long_term_panic_through_on_unhandled_error() {
+        let hooks = Arc::new(MockHooks::new());
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: Some(Arc::new(MockLongTermStore::panicking_save_event())),
+            hooks: Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("lt-panic".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("lt-panic", TaskStatus::Running, None)
+            .await
+            .unwrap();
+
+        assert!(engine.background().drain(Duration::from_secs(5)).await);
+
+        let unhandled = hooks.unhandled.lock().unwrap();
+        assert_eq!(unhandled.len(), 1);
+        assert!(unhandled[0].0.contains("mock save_event panic"));
+        assert_eq!(
+            unhandled[0].1,
+            ErrorContext {
+                operation: "long_term.save_event".to_string(),
+                task_id: Some("lt-panic".to_string()),
+            }
+        );
+        // A panic is not a failed save, so on_event_dropped is not called.
+        assert_eq!(hooks.dropped_count.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn background_in_flight_returns_to_zero_after_emits() {
+        let long_term_store = Arc::new(MockLongTermStore::new());
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: Some(Arc::clone(&long_term_store) as Arc<dyn LongTermStore>),
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        });
+
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("lt-drain".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("lt-drain", TaskStatus::Running, None)
+            .await
+            .unwrap();
+        for _ in 0..5 {
+            engine
+                .publish_event(
+                    "lt-drain",
+                    PublishEventInput {
+                        r#type: "test".to_string(),
+                        level: Level::Info,
+                        data: serde_json::json!(null),
+                        series_id: None,
+                        series_mode: None,
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        assert!(engine.background().drain(Duration::from_secs(5)).await);
+        assert_eq!(engine.background().in_flight(), 0);
+        assert!(engine.background().in_flight_by_name().is_empty());
+        assert_eq!(long_term_store.events.read().await.len(), 6);
+    }
+
     // ─── get_series_latest ──────────────────────────────────────────────
 
     #[tokio::test]
@@ -2233,6 +6080,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2247,6 +6097,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2258,6 +6111,65 @@ mod tests {
         assert_eq!(latest.data["delta"], "Hello world");
     }
 
+    #[tokio::test]
+    async fn emit_json_patch_rejects_bad_patch_without_using_an_index() {
+        let engine = make_engine();
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+        let patch = |data: serde_json::Value| PublishEventInput {
+            r#type: "crawl.frontier".to_string(),
+            level: Level::Info,
+            data,
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::JsonPatch),
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        };
+
+        let first = engine
+            .publish_event(
+                "t1",
+                patch(serde_json::json!([{ "op": "add", "path": "/n", "value": 1 }])),
+            )
+            .await
+            .unwrap();
+        let err = engine
+            .publish_event(
+                "t1",
+                patch(serde_json::json!([{ "op": "remove", "path": "/missing" }])),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InvalidSeriesPatch(_)));
+        let second = engine
+            .publish_event(
+                "t1",
+                patch(serde_json::json!([{ "op": "replace", "path": "/n", "value": 2 }])),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(second.index, first.index + 1);
+        assert_eq!(
+            second.data,
+            serde_json::json!([{ "op": "replace", "path": "/n", "value": 2 }])
+        );
+        let latest = engine.get_series_latest("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(latest.id, second.id);
+        assert_eq!(latest.data, serde_json::json!({ "n": 2 }));
+    }
+
     #[tokio::test]
     async fn emit_accumulate_broadcasts_with_accumulated_data() {
         let broadcast = Arc::new(MemoryBroadcastProvider::new());
@@ -2296,6 +6208,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2310,6 +6225,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2361,6 +6279,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2375,6 +6296,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2396,6 +6320,9 @@ mod tests {
             broadcast: Arc::new(MemoryBroadcastProvider::new()),
             long_term_store: Some(Arc::clone(&long_term) as Arc<dyn LongTermStore>),
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         });
         engine
             .create_task(CreateTaskInput {
@@ -2419,6 +6346,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2433,6 +6363,9 @@ mod tests {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2460,6 +6393,9 @@ mod tests {
             broadcast: Arc::new(MemoryBroadcastProvider::new()),
             long_term_store: Some(Arc::clone(&long_term) as Arc<dyn LongTermStore>),
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         });
         engine
             .create_task(CreateTaskInput {
@@ -2484,6 +6420,9 @@ mod tests {
                         series_id: Some("status".to_string()),
                         series_mode: Some(SeriesMode::Latest),
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -2517,6 +6456,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         long_term_store.events.write().await.push(event.clone());
@@ -2556,6 +6497,9 @@ mod tests {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -2565,6 +6509,115 @@ mod tests {
         assert!(!events.is_empty());
     }
 
+    #[tokio::test]
+    async fn delete_events_replaces_events_in_both_stores_and_announces_them() {
+        let long_term_store = Arc::new(MockLongTermStore::new());
+        let engine =
+            make_engine_with_long_term(Arc::clone(&long_term_store) as Arc<dyn LongTermStore>);
+        let task = engine
+            .create_task(CreateTaskInput::default())
+            .await
+            .unwrap();
+        engine
+            .transition_task(&task.id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+        let mut published = Vec::new();
+        for r#type in ["user.profile", "log", "user.profile"] {
+            let event = engine
+                .publish_event(
+                    &task.id,
+                    PublishEventInput {
+                        r#type: r#type.to_string(),
+                        level: Level::Info,
+                        data: serde_json::json!({"email": "someone@example.com"}),
+                        series_id: None,
+                        series_mode: None,
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await
+                .unwrap();
+            published.push(event);
+        }
+
+        let deleted = engine
+            .delete_events(
+                &task.id,
+                DeleteEventsInput {
+                    filter: Some(crate::redaction::DeleteEventsFilter {
+                        types: Some(vec!["user.*".to_string()]),
+                        ..Default::default()
+                    }),
+                    reason: Some("erasure request".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            deleted.event_ids,
+            vec![published[0].id.clone(), published[2].id.clone()]
+        );
+        assert_eq!(deleted.short_term, 2);
+        assert_eq!(deleted.long_term, Some(2));
+        assert_eq!(deleted.notification_index, Some(4));
+
+        let short_term = engine.get_events(&task.id, None).await.unwrap();
+        // The announcing event reaches the long-term store in the background.
+        let mut long_term = Vec::new();
+        for _ in 0..100 {
+            long_term = long_term_store.get_events(&task.id, None).await.unwrap();
+            if long_term.len() == 5 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        for events in [&short_term, &long_term] {
+            let types: Vec<&str> = events.iter().map(|e| e.r#type.as_str()).collect();
+            assert_eq!(
+                types,
+                vec![
+                    "taskcast:status",
+                    REDACTED_EVENT_TYPE,
+                    "log",
+                    REDACTED_EVENT_TYPE,
+                    REDACTED_EVENT_TYPE
+                ]
+            );
+            let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+            assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+            assert_eq!(events[1].id, published[0].id);
+            assert_eq!(events[1].data, serde_json::json!({"reason": "erasure request"}));
+        }
+        assert_eq!(
+            short_term[4].data["eventIds"],
+            serde_json::json!(deleted.event_ids)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_events_rejects_missing_tasks_and_ambiguous_input() {
+        let engine = make_engine();
+        let missing = engine
+            .delete_events(
+                "missing",
+                DeleteEventsInput {
+                    event_ids: Some(vec!["e1".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(missing, Err(EngineError::TaskNotFound(_))));
+        let ambiguous = engine
+            .delete_events("missing", DeleteEventsInput::default())
+            .await;
+        assert!(matches!(ambiguous, Err(EngineError::InvalidInput(_))));
+    }
+
     #[tokio::test]
     async fn emit_non_series_has_no_accumulated_data() {
         let broadcast = Arc::new(MemoryBroadcastProvider::new());
@@ -2602,6 +6655,9 @@ mod tests {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
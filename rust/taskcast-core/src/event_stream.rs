@@ -0,0 +1,1105 @@
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::engine::EngineError;
+use crate::filter::{apply_filtered_index, matches_filter};
+use crate::replay::{ReplayBatch, ReplayTruncation};
+use crate::state_machine::is_terminal;
+use crate::types::{SSEEnvelope, SeriesFormat, SeriesMode, SubscribeFilter, TaskEvent, TaskStatus};
+
+/// An item yielded by [`TaskEventStream`].
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum StreamItem {
+    /// The replay budget left older events out. Precedes the replayed events.
+    Truncated(ReplayTruncation),
+    /// An event that passed the subscription filter, with its filtered index.
+    Event(SSEEnvelope),
+    /// The task is in a terminal status. Always the last item of a stream.
+    Done(TaskStatus),
+    /// Replaying history failed. Always the last item of a stream.
+    Error(EngineError),
+}
+
+/// Wrap an event in an [`SSEEnvelope`] carrying its position in the filtered stream.
+pub fn to_envelope(event: &TaskEvent, filtered_index: u64) -> SSEEnvelope {
+    SSEEnvelope {
+        filtered_index,
+        raw_index: event.index,
+        event_id: event.id.clone(),
+        task_id: event.task_id.clone(),
+        r#type: event.r#type.clone(),
+        timestamp: event.timestamp,
+        level: event.level.clone(),
+        data: event.data.clone(),
+        series_id: event.series_id.clone(),
+        series_mode: event.series_mode.clone(),
+        series_acc_field: event.series_acc_field.clone(),
+        series_snapshot: event.series_snapshot,
+        labels: event.labels.clone(),
+        replaces_event_id: event.replaces_event_id.clone(),
+        filter_epoch: None,
+    }
+}
+
+impl From<SSEEnvelope> for TaskEvent {
+    fn from(envelope: SSEEnvelope) -> Self {
+        TaskEvent {
+            id: envelope.event_id,
+            task_id: envelope.task_id,
+            index: envelope.raw_index,
+            timestamp: envelope.timestamp,
+            r#type: envelope.r#type,
+            level: envelope.level,
+            data: envelope.data,
+            series_id: envelope.series_id,
+            series_mode: envelope.series_mode,
+            series_acc_field: envelope.series_acc_field,
+            series_snapshot: envelope.series_snapshot,
+            labels: envelope.labels,
+            replaces_event_id: envelope.replaces_event_id,
+            _accumulated_data: None,
+        }
+    }
+}
+
+/// Whether `event` is a control event, which a live tail delivers ahead of
+/// data events still queued for the subscriber. Status changes are the only
+/// control events; the done frame that follows a terminal one is synthetic
+/// and always comes last.
+pub fn is_control_event(event: &TaskEvent) -> bool {
+    event.r#type == "taskcast:status"
+}
+
+/// Whether `event` is a status change that leaves its task running: what a
+/// subscriber excluding status events can be spared. The terminal status
+/// event still reaches it, as it ends the stream.
+pub fn is_intermediate_status(event: &TaskEvent) -> bool {
+    is_control_event(event) && terminal_status_of(event).is_none()
+}
+
+/// Returns the terminal status carried by a `taskcast:status` event, if any.
+pub(crate) fn terminal_status_of(event: &TaskEvent) -> Option<TaskStatus> {
+    if event.r#type != "taskcast:status" {
+        return None;
+    }
+    let status = TaskStatus::deserialize(event.data.get("status")?).ok()?;
+    is_terminal(&status).then_some(status)
+}
+
+/// Unsubscribes from the broadcast provider when dropped.
+pub(crate) struct Subscription(pub(crate) Box<dyn Fn() + Send + Sync>);
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
+struct LiveTail {
+    events: UnboundedReceiver<TaskEvent>,
+    _subscription: Subscription,
+}
+
+/// History replay followed by a live tail, as returned by
+/// [`TaskEngine::subscribe_stream`](crate::TaskEngine::subscribe_stream).
+///
+/// Ends after yielding [`StreamItem::Done`] or [`StreamItem::Error`].
+/// Dropping the stream unsubscribes from the broadcast provider.
+///
+/// Live events are taken off the broadcast channel as they arrive and queued
+/// by class: [control events](is_control_event) are yielded before any data
+/// event still queued, so a status change never waits behind a flood of data
+/// the subscriber has not caught up with. Each class keeps store order, and a
+/// control event is never yielded after a data event stored later than it.
+/// Filtered indices follow store order, not delivery order. History replay
+/// is not reordered; [`in_store_order`](Self::in_store_order) turns the
+/// priority off for the live tail too.
+pub struct TaskEventStream {
+    pending: VecDeque<StreamItem>,
+    /// History still being read from the stores, ahead of the live tail.
+    replay: Option<BoxStream<'static, ReplayBatch>>,
+    /// Yielded once `replay` ends, for a task that was terminal on connect.
+    done_after_replay: Option<TaskStatus>,
+    live: Option<LiveTail>,
+    /// Live control events, yielded ahead of `queued`.
+    priority: VecDeque<StreamItem>,
+    /// Live data events with their filtered indices.
+    queued: VecDeque<(TaskEvent, u64)>,
+    /// Terminal status seen on the live tail, yielded once both queues drain.
+    done: Option<TaskStatus>,
+    prioritize: bool,
+    filter: SubscribeFilter,
+    next_filtered_index: u64,
+    /// Index of the last stored event replayed. Live events up to it were
+    /// part of the replay.
+    replayed_through: Option<u64>,
+    /// Whether the item last yielded came off the live tail.
+    last_live: bool,
+}
+
+impl TaskEventStream {
+    pub(crate) fn failed(error: EngineError) -> Self {
+        Self {
+            pending: VecDeque::from([StreamItem::Error(error)]),
+            replay: None,
+            done_after_replay: None,
+            live: None,
+            priority: VecDeque::new(),
+            queued: VecDeque::new(),
+            done: None,
+            prioritize: true,
+            filter: SubscribeFilter::default(),
+            next_filtered_index: 0,
+            replayed_through: None,
+            last_live: false,
+        }
+    }
+
+    /// Build the replay part of the stream from already-loaded history.
+    pub(crate) fn replay(history: &[TaskEvent], filter: SubscribeFilter) -> Self {
+        let filtered = apply_filtered_index(history, &filter);
+        let next_filtered_index = filtered.last().map_or(0, |fe| fe.filtered_index + 1);
+        let replayed_through = history.iter().map(|e| e.index).max();
+        let pending = filtered
+            .iter()
+            .map(|fe| StreamItem::Event(envelope_for(&fe.event, fe.filtered_index, &filter)))
+            .collect();
+        Self {
+            pending,
+            replay: None,
+            done_after_replay: None,
+            live: None,
+            priority: VecDeque::new(),
+            queued: VecDeque::new(),
+            done: None,
+            prioritize: true,
+            filter,
+            next_filtered_index,
+            replayed_through,
+            last_live: false,
+        }
+    }
+
+    /// Build the replay part of the stream from batches read as it is polled.
+    pub(crate) fn replaying(
+        replay: BoxStream<'static, ReplayBatch>,
+        filter: SubscribeFilter,
+    ) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            replay: Some(replay),
+            done_after_replay: None,
+            live: None,
+            priority: VecDeque::new(),
+            queued: VecDeque::new(),
+            done: None,
+            prioritize: true,
+            filter,
+            next_filtered_index: 0,
+            replayed_through: None,
+            last_live: false,
+        }
+    }
+
+    pub(crate) fn finish(mut self, status: TaskStatus) -> Self {
+        if self.replay.is_some() {
+            self.done_after_replay = Some(status);
+        } else {
+            self.pending.push_back(StreamItem::Done(status));
+        }
+        self
+    }
+
+    pub(crate) fn tail(
+        mut self,
+        events: UnboundedReceiver<TaskEvent>,
+        subscription: Subscription,
+    ) -> Self {
+        self.live = Some(LiveTail {
+            events,
+            _subscription: subscription,
+        });
+        self
+    }
+
+    /// Yield live events strictly in store order, without moving control
+    /// events ahead of queued data.
+    pub fn in_store_order(mut self) -> Self {
+        self.prioritize = false;
+        self
+    }
+
+    /// Whether the item last yielded came off the live tail rather than the
+    /// replayed history. A live event reaches every subscriber as broadcast,
+    /// so its envelope differs between subscribers with the same series
+    /// format only in `filtered_index`.
+    pub fn last_item_live(&self) -> bool {
+        self.last_live
+    }
+
+    fn push_replayed(&mut self, batch: ReplayBatch) {
+        self.next_filtered_index = batch.next_filtered_index;
+        self.replayed_through = batch.replayed_through;
+        let failed = matches!(batch.items.last(), Some(StreamItem::Error(_)));
+        self.pending.extend(batch.items);
+        if failed {
+            self.replay = None;
+            self.done_after_replay = None;
+            self.live = None;
+        }
+    }
+
+    fn push_live(&mut self, event: TaskEvent) {
+        // The subscription is opened before history is read, so the replay
+        // may already have covered this event.
+        let replayed = self
+            .replayed_through
+            .is_some_and(|through| event.index <= through);
+        let status = terminal_status_of(&event);
+        if !replayed && matches_filter(&event, &self.filter) {
+            let filtered_index = self.next_filtered_index;
+            self.next_filtered_index += 1;
+            if self.prioritize && is_control_event(&event) {
+                let envelope = envelope_for(&event, filtered_index, &self.filter);
+                self.priority.push_back(StreamItem::Event(envelope));
+            } else {
+                self.queued.push_back((event, filtered_index));
+            }
+        }
+        if let Some(status) = status {
+            self.done = Some(status);
+            self.live = None;
+        }
+    }
+
+    /// Moves every event already on the live channel into the queues.
+    /// `try_recv` rather than `poll_recv`, whose cooperative budget would
+    /// stop short of a status event deep in the channel.
+    fn take_live(&mut self) {
+        while let Some(live) = self.live.as_mut() {
+            match live.events.try_recv() {
+                Ok(event) => self.push_live(event),
+                Err(TryRecvError::Disconnected) => self.live = None,
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+    }
+
+    fn pop_live(&mut self) -> Option<StreamItem> {
+        if let Some(item) = self.priority.pop_front() {
+            return Some(item);
+        }
+        if let Some((event, filtered_index)) = self.queued.pop_front() {
+            let envelope = envelope_for(&event, filtered_index, &self.filter);
+            return Some(StreamItem::Event(envelope));
+        }
+        self.done.take().map(StreamItem::Done)
+    }
+}
+
+/// Envelopes for `events` as a cursor-based replay of `history` under `filter`
+/// yields them, which is also what a subscriber tailing the task receives.
+/// `events` must be in ascending index order; an event the filter excludes
+/// maps to `None`.
+pub fn envelopes_in_history(
+    history: &[TaskEvent],
+    events: &[TaskEvent],
+    filter: &SubscribeFilter,
+) -> Vec<Option<SSEEnvelope>> {
+    let mut position = 0;
+    let mut filtered_index = 0;
+    events
+        .iter()
+        .map(|event| {
+            while position < history.len() && history[position].index < event.index {
+                if matches_filter(&history[position], filter) {
+                    filtered_index += 1;
+                }
+                position += 1;
+            }
+            matches_filter(event, filter).then(|| envelope_for(event, filtered_index, filter))
+        })
+        .collect()
+}
+
+pub(crate) fn envelope_for(
+    event: &TaskEvent,
+    filtered_index: u64,
+    filter: &SubscribeFilter,
+) -> SSEEnvelope {
+    let mut envelope = to_envelope(event, filtered_index);
+    envelope.data = series_view_data(event, filter.series_format.as_ref());
+    envelope
+}
+
+/// `event`'s data as delivered under `format`. Only `accumulate` series
+/// events are affected, and their running total comes from
+/// `_accumulated_data`; without it the event's own data stands in.
+pub fn series_view_data(event: &TaskEvent, format: Option<&SeriesFormat>) -> serde_json::Value {
+    if event.series_mode != Some(SeriesMode::Accumulate) {
+        return event.data.clone();
+    }
+    let accumulated = event._accumulated_data.as_ref().unwrap_or(&event.data);
+    match format {
+        None | Some(SeriesFormat::Delta) => event.data.clone(),
+        Some(SeriesFormat::Accumulated) => accumulated.clone(),
+        Some(SeriesFormat::Both) => serde_json::json!({
+            "delta": event.data,
+            "accumulated": accumulated,
+        }),
+    }
+}
+
+impl Stream for TaskEventStream {
+    type Item = StreamItem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamItem>> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                self.last_live = false;
+                return Poll::Ready(Some(item));
+            }
+            if let Some(replay) = self.replay.as_mut() {
+                match replay.poll_next_unpin(cx) {
+                    Poll::Ready(Some(batch)) => self.push_replayed(batch),
+                    Poll::Ready(None) => {
+                        self.replay = None;
+                        if let Some(status) = self.done_after_replay.take() {
+                            self.pending.push_back(StreamItem::Done(status));
+                        }
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+                continue;
+            }
+            self.take_live();
+            if let Some(item) = self.pop_live() {
+                self.last_live = true;
+                return Poll::Ready(Some(item));
+            }
+            let Some(live) = self.live.as_mut() else {
+                return Poll::Ready(None);
+            };
+            match live.events.poll_recv(cx) {
+                Poll::Ready(Some(event)) => self.push_live(event),
+                Poll::Ready(None) => self.live = None,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+    use crate::channels::task_channel;
+    use crate::engine::{CreateTaskInput, PublishEventInput, TaskEngine, TaskEngineOptions};
+    use crate::memory_adapters::{MemoryBroadcastProvider, MemoryShortTermStore};
+    use crate::types::{BroadcastProvider, Level, SeriesMode, ShortTermStore, SinceCursor};
+
+    fn make_engine(broadcast: Arc<MemoryBroadcastProvider>) -> TaskEngine {
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast,
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+    }
+
+    fn log_event(message: &str) -> PublishEventInput {
+        PublishEventInput {
+            r#type: "log".to_string(),
+            level: Level::Info,
+            data: json!({ "message": message }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        }
+    }
+
+    async fn running_task(engine: &TaskEngine, id: &str) {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task(id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+    }
+
+    fn chunk(delta: &str) -> PublishEventInput {
+        PublishEventInput {
+            r#type: "llm.delta".to_string(),
+            level: Level::Info,
+            data: json!({ "delta": delta }),
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::Accumulate),
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        }
+    }
+
+    fn series_filter(format: SeriesFormat, since_index: Option<u64>) -> SubscribeFilter {
+        SubscribeFilter {
+            types: Some(vec!["llm.delta".to_string()]),
+            series_format: Some(format),
+            since: since_index.map(|index| SinceCursor {
+                id: None,
+                index: Some(index),
+                timestamp: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn event_data(items: &[StreamItem]) -> Vec<serde_json::Value> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Event(env) => Some(env.data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn event_types(items: &[StreamItem]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                StreamItem::Event(env) => env.r#type.clone(),
+                StreamItem::Done(status) => format!("done:{status:?}"),
+                StreamItem::Truncated(t) => format!("truncated:{}", t.omitted_count),
+                StreamItem::Error(e) => format!("error:{e}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn terminal_task_yields_history_then_done() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        engine.publish_event("t1", log_event("a")).await.unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        let items: Vec<StreamItem> = stream.collect().await;
+
+        assert_eq!(
+            event_types(&items),
+            vec![
+                "taskcast:status",
+                "log",
+                "taskcast:status",
+                "done:Completed"
+            ]
+        );
+        let indices: Vec<u64> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Event(env) => Some(env.filtered_index),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn status_excluding_stream_is_spared_intermediate_status_events() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine(Arc::clone(&broadcast));
+        running_task(&engine, "t1").await;
+
+        let filter = SubscribeFilter {
+            include_status: Some(false),
+            ..Default::default()
+        };
+        let stream = engine.subscribe_stream("t1", filter).await.unwrap();
+        assert_eq!(broadcast.status_excluding_count(&task_channel("t1")), 1);
+
+        engine.publish_event("t1", log_event("a")).await.unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Paused, None)
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let items: Vec<StreamItem> = stream.collect().await;
+        assert_eq!(event_types(&items), vec!["log", "done:Completed"]);
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 0);
+    }
+
+    #[tokio::test]
+    async fn live_task_yields_replay_then_live_events_until_terminal() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        engine
+            .publish_event("t1", log_event("before"))
+            .await
+            .unwrap();
+
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            ..Default::default()
+        };
+        let mut stream = engine.subscribe_stream("t1", filter).await.unwrap();
+
+        let Some(StreamItem::Event(replayed)) = stream.next().await else {
+            panic!("expected replayed event");
+        };
+        assert_eq!(replayed.data["message"], "before");
+        assert_eq!(replayed.filtered_index, 0);
+        assert!(!stream.last_item_live());
+
+        engine
+            .publish_event("t1", log_event("after"))
+            .await
+            .unwrap();
+        let Some(StreamItem::Event(live)) = stream.next().await else {
+            panic!("expected live event");
+        };
+        assert_eq!(live.data["message"], "after");
+        assert_eq!(live.filtered_index, 1);
+        assert!(stream.last_item_live());
+
+        // The status event is filtered out by `types`, but still ends the stream.
+        engine
+            .transition_task("t1", TaskStatus::Failed, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            stream.next().await,
+            Some(StreamItem::Done(TaskStatus::Failed))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    /// Acts on the store right after the subscription is registered, as a
+    /// writer racing the subscriber would: either publishes a log event,
+    /// which the history read then sees as well, or completes the task
+    /// without broadcasting it.
+    struct RaceOnSubscribe {
+        inner: MemoryBroadcastProvider,
+        store: Arc<MemoryShortTermStore>,
+        complete: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl BroadcastProvider for RaceOnSubscribe {
+        async fn publish(
+            &self,
+            channel: &str,
+            event: TaskEvent,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.inner.publish(channel, event).await
+        }
+
+        async fn subscribe(
+            &self,
+            channel: &str,
+            handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+        ) -> Box<dyn Fn() + Send + Sync> {
+            let unsubscribe = self.inner.subscribe(channel, handler).await;
+            let task_id = channel.strip_prefix("task.").unwrap();
+            if self.complete {
+                let mut task = self.store.get_task(task_id).await.unwrap().unwrap();
+                task.status = TaskStatus::Completed;
+                self.store.save_task(task).await.unwrap();
+            } else {
+                let event = TaskEvent {
+                    id: "raced".to_string(),
+                    task_id: task_id.to_string(),
+                    index: self.store.next_index(task_id).await.unwrap(),
+                    timestamp: 0.0,
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: json!({ "message": "raced" }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    series_snapshot: None,
+                    labels: None,
+                    replaces_event_id: None,
+                    _accumulated_data: None,
+                };
+                self.store
+                    .append_event(task_id, event.clone())
+                    .await
+                    .unwrap();
+                self.inner.publish(channel, event).await.unwrap();
+            }
+            unsubscribe
+        }
+    }
+
+    fn racing_engine(complete: bool) -> TaskEngine {
+        let store = Arc::new(MemoryShortTermStore::new());
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+            broadcast: Arc::new(RaceOnSubscribe {
+                inner: MemoryBroadcastProvider::new(),
+                store,
+                complete,
+            }),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn event_published_while_connecting_is_delivered_once() {
+        let engine = racing_engine(false);
+        running_task(&engine, "t1").await;
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            ..Default::default()
+        };
+        let mut stream = engine.subscribe_stream("t1", filter).await.unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let mut items = Vec::new();
+        while let Some(item) = stream.next().await {
+            items.push(item);
+        }
+        assert_eq!(event_types(&items), vec!["log", "done:Completed"]);
+    }
+
+    #[tokio::test]
+    async fn task_completed_while_connecting_ends_the_stream() {
+        let engine = racing_engine(true);
+        running_task(&engine, "t1").await;
+        let stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+
+        // The status was never broadcast, so without the re-check the stream
+        // would tail forever.
+        let items = tokio::time::timeout(Duration::from_secs(1), stream.collect::<Vec<_>>())
+            .await
+            .expect("re-check after subscribing should end the stream");
+        assert!(matches!(
+            items.last(),
+            Some(StreamItem::Done(TaskStatus::Completed))
+        ));
+    }
+
+    #[tokio::test]
+    async fn dropping_the_stream_unsubscribes() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine(Arc::clone(&broadcast));
+        running_task(&engine, "t1").await;
+
+        let stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 1);
+
+        drop(stream);
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 0);
+    }
+
+    #[tokio::test]
+    async fn reaching_terminal_status_unsubscribes() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine(Arc::clone(&broadcast));
+        running_task(&engine, "t1").await;
+
+        let mut stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+        while stream.next().await.is_some() {}
+
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 0);
+    }
+
+    #[tokio::test]
+    async fn terminal_task_does_not_subscribe() {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = make_engine(Arc::clone(&broadcast));
+        running_task(&engine, "t1").await;
+        engine
+            .transition_task("t1", TaskStatus::Cancelled, None)
+            .await
+            .unwrap();
+
+        let _stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(broadcast.listener_count(&task_channel("t1")), 0);
+    }
+
+    #[tokio::test]
+    async fn missing_task_is_an_error() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        let result = engine
+            .subscribe_stream("missing", SubscribeFilter::default())
+            .await;
+        assert!(matches!(result, Err(EngineError::TaskNotFound(id)) if id == "missing"));
+    }
+
+    #[tokio::test]
+    async fn since_index_and_history_limit_are_applied() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        for i in 0..4 {
+            engine
+                .publish_event("t1", log_event(&i.to_string()))
+                .await
+                .unwrap();
+        }
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            since: Some(SinceCursor {
+                id: None,
+                index: Some(1),
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        let items: Vec<StreamItem> = engine
+            .subscribe_stream("t1", filter.clone())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        let messages: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Event(env) => Some(env.data["message"].clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, vec![json!("2"), json!("3")]);
+
+        let limited: Vec<StreamItem> = engine
+            .subscribe_stream_with_limit("t1", SubscribeFilter::default(), Some(2))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(
+            event_types(&limited),
+            vec!["taskcast:status", "log", "done:Completed"]
+        );
+    }
+
+    #[tokio::test]
+    async fn accumulated_series_format_swaps_in_accumulated_data() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+
+        let filter = SubscribeFilter {
+            types: Some(vec!["llm.delta".to_string()]),
+            series_format: Some(SeriesFormat::Accumulated),
+            ..Default::default()
+        };
+        let mut stream = engine.subscribe_stream("t1", filter).await.unwrap();
+
+        for delta in ["Hel", "lo"] {
+            engine
+                .publish_event(
+                    "t1",
+                    PublishEventInput {
+                        r#type: "llm.delta".to_string(),
+                        level: Level::Info,
+                        data: json!({ "delta": delta }),
+                        series_id: Some("s1".to_string()),
+                        series_mode: Some(SeriesMode::Accumulate),
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        for _ in 0..2 {
+            let Some(StreamItem::Event(env)) = stream.next().await else {
+                panic!("expected event");
+            };
+            seen.push(env.data["delta"].clone());
+        }
+        assert_eq!(seen, vec![json!("Hel"), json!("Hello")]);
+    }
+
+    #[tokio::test]
+    async fn live_series_views() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+
+        let mut streams = Vec::new();
+        for format in [
+            SeriesFormat::Delta,
+            SeriesFormat::Accumulated,
+            SeriesFormat::Both,
+        ] {
+            let filter = series_filter(format, None);
+            streams.push(engine.subscribe_stream("t1", filter).await.unwrap());
+        }
+        for delta in ["a", "b", "c"] {
+            engine.publish_event("t1", chunk(delta)).await.unwrap();
+        }
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let mut seen = Vec::new();
+        for stream in streams {
+            seen.push(event_data(&stream.collect::<Vec<_>>().await));
+        }
+        assert_eq!(
+            seen[0],
+            vec![
+                json!({ "delta": "a" }),
+                json!({ "delta": "b" }),
+                json!({ "delta": "c" }),
+            ]
+        );
+        assert_eq!(
+            seen[1],
+            vec![
+                json!({ "delta": "a" }),
+                json!({ "delta": "ab" }),
+                json!({ "delta": "abc" }),
+            ]
+        );
+        assert_eq!(
+            seen[2][1],
+            json!({ "delta": { "delta": "b" }, "accumulated": { "delta": "ab" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnect_replays_series_views_after_the_cursor() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        for delta in ["a", "b", "c"] {
+            engine.publish_event("t1", chunk(delta)).await.unwrap();
+        }
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+
+        let replay = |format| {
+            let engine = &engine;
+            async move {
+                let filter = series_filter(format, Some(0));
+                let stream = engine.subscribe_stream("t1", filter).await.unwrap();
+                event_data(&stream.collect::<Vec<_>>().await)
+            }
+        };
+        assert_eq!(
+            replay(SeriesFormat::Delta).await,
+            vec![json!({ "delta": "b" }), json!({ "delta": "c" })]
+        );
+        assert_eq!(
+            replay(SeriesFormat::Accumulated).await,
+            vec![json!({ "delta": "ab" }), json!({ "delta": "abc" })]
+        );
+        assert_eq!(
+            replay(SeriesFormat::Both).await[1],
+            json!({ "delta": { "delta": "c" }, "accumulated": { "delta": "abc" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn envelopes_in_history_match_live_delivery() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            ..Default::default()
+        };
+        let mut stream = engine.subscribe_stream("t1", filter.clone()).await.unwrap();
+
+        let mut published = Vec::new();
+        for message in ["a", "b"] {
+            published.push(
+                engine
+                    .publish_event("t1", log_event(message))
+                    .await
+                    .unwrap(),
+            );
+        }
+        let mut other = log_event("c");
+        other.r#type = "metric".to_string();
+        published.push(engine.publish_event("t1", other).await.unwrap());
+
+        let history = engine.get_events("t1", None).await.unwrap();
+        let envelopes = envelopes_in_history(&history, &published, &filter);
+        assert_eq!(envelopes.len(), 3);
+        assert!(envelopes[2].is_none());
+        for expected in envelopes.into_iter().take(2) {
+            let Some(StreamItem::Event(live)) = stream.next().await else {
+                panic!("expected event");
+            };
+            assert_eq!(Some(live), expected);
+        }
+    }
+
+    /// Subscribes to a running task, then floods it with `logs` events and
+    /// completes it before the stream is polled.
+    async fn flooded_stream(logs: usize, store_order: bool) -> Vec<StreamItem> {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        let stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        let stream = if store_order {
+            stream.in_store_order()
+        } else {
+            stream
+        };
+        for i in 0..logs {
+            engine
+                .publish_event("t1", log_event(&i.to_string()))
+                .await
+                .unwrap();
+        }
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+        stream.collect().await
+    }
+
+    #[tokio::test]
+    async fn live_status_events_overtake_queued_data() {
+        let items = flooded_stream(10_000, false).await;
+
+        // The replayed `running` status, then the completion ahead of the
+        // flood, which keeps its order behind it.
+        let types = event_types(&items);
+        assert_eq!(types[..2], ["taskcast:status", "taskcast:status"]);
+        assert!(types[2..10_002].iter().all(|t| t == "log"));
+        assert_eq!(types[10_002..], ["done:Completed"]);
+
+        let envelopes: Vec<&SSEEnvelope> = items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Event(env) => Some(env),
+                _ => None,
+            })
+            .collect();
+        let completed = envelopes[1];
+        assert_eq!(completed.data["status"], "completed");
+        assert_eq!(completed.raw_index, 10_001);
+        assert_eq!(completed.filtered_index, 10_001);
+        let logs: Vec<u64> = envelopes[2..]
+            .iter()
+            .map(|env| env.filtered_index)
+            .collect();
+        assert_eq!(logs, (1..=10_000).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn in_store_order_keeps_status_events_behind_data() {
+        let items = flooded_stream(3, true).await;
+        assert_eq!(
+            event_types(&items),
+            vec![
+                "taskcast:status",
+                "log",
+                "log",
+                "log",
+                "taskcast:status",
+                "done:Completed"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn status_events_never_overtake_delivered_data() {
+        let engine = make_engine(Arc::new(MemoryBroadcastProvider::new()));
+        running_task(&engine, "t1").await;
+        let mut stream = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        assert!(matches!(stream.next().await, Some(StreamItem::Event(_))));
+
+        engine.publish_event("t1", log_event("a")).await.unwrap();
+        let Some(StreamItem::Event(first)) = stream.next().await else {
+            panic!("expected event");
+        };
+        assert_eq!(first.data["message"], "a");
+
+        engine.publish_event("t1", log_event("b")).await.unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Failed, None)
+            .await
+            .unwrap();
+        engine
+            .publish_event("t1", log_event("late"))
+            .await
+            .unwrap_err();
+        let rest: Vec<StreamItem> = stream.collect().await;
+        assert_eq!(
+            event_types(&rest),
+            vec!["taskcast:status", "log", "done:Failed"]
+        );
+    }
+}
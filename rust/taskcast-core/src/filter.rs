@@ -1,4 +1,11 @@
-use crate::types::{SubscribeFilter, TaskEvent};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::payload_dedup::content_hash;
+use crate::types::{EventQueryOptions, SubscribeFilter, Task, TaskEvent, TaskMatch};
+use crate::validation::Violation;
 
 /// A task event annotated with its filtered (post-filter) index and raw (original) index.
 #[derive(Debug, Clone)]
@@ -35,6 +42,342 @@ pub fn matches_type(event_type: &str, patterns: Option<&[String]>) -> bool {
     })
 }
 
+/// Returns `true` if every `key: value` pair in `selector` is present in `labels`.
+///
+/// An empty selector matches every event, including unlabeled ones.
+pub fn matches_labels(
+    labels: Option<&HashMap<String, String>>,
+    selector: &HashMap<String, String>,
+) -> bool {
+    selector
+        .iter()
+        .all(|(key, value)| labels.and_then(|l| l.get(key)) == Some(value))
+}
+
+/// Parses a label selector of the form `"region:eu,worker:w-42"` into a map.
+///
+/// Empty segments are ignored. A segment without a `:` or with an empty key is an error.
+pub fn parse_label_selector(raw: &str) -> Result<HashMap<String, String>, String> {
+    let mut selector = HashMap::new();
+    for segment in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match segment.split_once(':') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                selector.insert(key.trim().to_string(), value.trim().to_string());
+            }
+            _ => return Err(format!("Invalid label selector segment: \"{segment}\"")),
+        }
+    }
+    Ok(selector)
+}
+
+// ─── Event Type Rules ───────────────────────────────────────────────────────
+
+/// Longest event type accepted at publish time, in bytes.
+pub const DEFAULT_MAX_EVENT_TYPE_LENGTH: usize = 256;
+
+/// How event types and type patterns are rewritten before they are stored
+/// or matched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TypeNormalization {
+    /// Types are matched exactly as published.
+    #[default]
+    None,
+    /// Published types and every type pattern are lowercased, so
+    /// `"Progress"` and `"progress"` are the same type.
+    Lowercase,
+}
+
+/// What a published event type may look like, and how types are normalized.
+///
+/// Types are checked only when published: events already stored with a type
+/// these rules would refuse stay readable. Normalization applies to both
+/// sides of [`matches_type`], published types and every filter's patterns,
+/// so the two always agree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventTypeRules {
+    pub max_length: usize,
+    pub normalization: TypeNormalization,
+}
+
+impl Default for EventTypeRules {
+    fn default() -> Self {
+        Self {
+            max_length: DEFAULT_MAX_EVENT_TYPE_LENGTH,
+            normalization: TypeNormalization::None,
+        }
+    }
+}
+
+impl EventTypeRules {
+    /// `event_type`, or a type pattern, as it is stored and matched.
+    pub fn normalize(&self, event_type: &str) -> String {
+        match self.normalization {
+            TypeNormalization::None => event_type.to_string(),
+            TypeNormalization::Lowercase => event_type.to_ascii_lowercase(),
+        }
+    }
+
+    /// Normalizes a list of type patterns in place.
+    pub fn normalize_patterns(&self, patterns: &mut [String]) {
+        if self.normalization == TypeNormalization::Lowercase {
+            patterns.iter_mut().for_each(|p| p.make_ascii_lowercase());
+        }
+    }
+
+    /// Normalizes the type patterns of `filter` in place.
+    pub fn normalize_filter(&self, filter: &mut SubscribeFilter) {
+        if let Some(ref mut types) = filter.types {
+            self.normalize_patterns(types);
+        }
+    }
+
+    /// Checks a type about to be published: 1 to `max_length` bytes of
+    /// visible ASCII, without commas. The violation points at `pointer`.
+    pub fn check(&self, event_type: &str, pointer: &str) -> Result<(), Violation> {
+        if event_type.is_empty() || event_type.len() > self.max_length {
+            return Err(Violation::new(
+                pointer,
+                format!(
+                    "Invalid event type length: {} (must be 1-{})",
+                    event_type.len(),
+                    self.max_length
+                ),
+            ));
+        }
+        if event_type.contains(',') {
+            return Err(Violation::new(
+                pointer,
+                "Invalid event type: commas are not allowed, since `types` filters are comma-separated",
+            ));
+        }
+        if let Some(c) = event_type.chars().find(|c| !c.is_ascii_graphic()) {
+            return Err(Violation::new(
+                pointer,
+                format!(
+                    "Invalid event type: {c:?} is not allowed; types are visible ASCII without spaces"
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ─── Filter Presets ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FilterPresetError {
+    #[error("Unknown filter preset: {0}")]
+    UnknownPreset(String),
+
+    #[error("Label selector conflicts with filter preset \"{preset}\" on key \"{key}\"")]
+    LabelConflict { preset: String, key: String },
+
+    #[error("Task match conflicts with filter preset \"{preset}\" on metadata key \"{key}\"")]
+    TaskMatchConflict { preset: String, key: String },
+}
+
+/// Returns `true` if every event type matched by `narrow` is also matched by `wide`.
+fn type_pattern_covers(wide: &str, narrow: &str) -> bool {
+    if wide == "*" {
+        return true;
+    }
+    if narrow == "*" {
+        return false;
+    }
+    match wide.strip_suffix(".*") {
+        // "llm.*" covers "llm.delta" and "llm.tool.*" but NOT "llm"
+        Some(prefix) => narrow.starts_with(&format!("{}.", prefix)),
+        None => wide == narrow,
+    }
+}
+
+/// Intersects two type pattern lists. Two patterns that share a matching type
+/// always nest, so keeping each pattern covered by the other side is exact.
+fn intersect_types(preset: &[String], inline: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = inline
+        .iter()
+        .filter(|p| preset.iter().any(|w| type_pattern_covers(w, p)))
+        .cloned()
+        .collect();
+    for pattern in preset {
+        if inline.iter().any(|w| type_pattern_covers(w, pattern)) && !result.contains(pattern) {
+            result.push(pattern.clone());
+        }
+    }
+    result
+}
+
+/// `base` narrowed by `inline`: type patterns and statuses are intersected
+/// and metadata entries merged. Fails with the first metadata key the two
+/// give different values.
+fn narrow_task_match(base: &TaskMatch, inline: TaskMatch) -> Result<TaskMatch, String> {
+    let types = match (&base.types, inline.types) {
+        (Some(base_types), Some(inline_types)) => Some(intersect_types(base_types, &inline_types)),
+        (base_types, inline_types) => inline_types.or_else(|| base_types.clone()),
+    };
+    let status = match (&base.status, inline.status) {
+        (Some(base_status), Some(inline_status)) => Some(
+            inline_status
+                .into_iter()
+                .filter(|s| base_status.contains(s))
+                .collect(),
+        ),
+        (base_status, inline_status) => inline_status.or_else(|| base_status.clone()),
+    };
+    let metadata = match (&base.metadata, inline.metadata) {
+        (Some(base_metadata), Some(inline_metadata)) => {
+            let mut merged = base_metadata.clone();
+            for (key, value) in inline_metadata {
+                if merged.get(&key).is_some_and(|v| v != &value) {
+                    return Err(key);
+                }
+                merged.insert(key, value);
+            }
+            Some(merged)
+        }
+        (base_metadata, inline_metadata) => inline_metadata.or_else(|| base_metadata.clone()),
+    };
+    Ok(TaskMatch {
+        types,
+        metadata,
+        status,
+    })
+}
+
+/// Resolves the effective filter for a subscription, history query or webhook:
+/// the task's preset named `preset`, with the `inline` filter layered on top.
+///
+/// Inline parameters only ever narrow the preset: `types` and `levels` are
+/// intersected, `includeStatus` is AND-ed, and label selectors and task
+/// matches are merged.
+/// `since`, `wrap` and `seriesFormat` don't affect which events match, so an
+/// inline value simply replaces the preset's. Without a preset, `inline` is
+/// returned unchanged.
+pub fn resolve_filter(
+    presets: Option<&HashMap<String, SubscribeFilter>>,
+    preset: Option<&str>,
+    inline: SubscribeFilter,
+) -> Result<SubscribeFilter, FilterPresetError> {
+    let Some(name) = preset else {
+        return Ok(inline);
+    };
+    let base = presets
+        .and_then(|p| p.get(name))
+        .ok_or_else(|| FilterPresetError::UnknownPreset(name.to_string()))?;
+
+    let types = match (&base.types, inline.types) {
+        (Some(base_types), Some(inline_types)) => Some(intersect_types(base_types, &inline_types)),
+        (base_types, inline_types) => inline_types.or_else(|| base_types.clone()),
+    };
+
+    let levels = match (&base.levels, inline.levels) {
+        (Some(base_levels), Some(inline_levels)) => Some(
+            inline_levels
+                .into_iter()
+                .filter(|l| base_levels.contains(l))
+                .collect(),
+        ),
+        (base_levels, inline_levels) => inline_levels.or_else(|| base_levels.clone()),
+    };
+
+    let include_status = match (base.include_status, inline.include_status) {
+        (None, None) => None,
+        (b, i) => Some(b.unwrap_or(true) && i.unwrap_or(true)),
+    };
+
+    let label_selector = match (&base.label_selector, inline.label_selector) {
+        (Some(base_selector), Some(inline_selector)) => {
+            let mut merged = base_selector.clone();
+            for (key, value) in inline_selector {
+                if merged.get(&key).is_some_and(|v| v != &value) {
+                    return Err(FilterPresetError::LabelConflict {
+                        preset: name.to_string(),
+                        key,
+                    });
+                }
+                merged.insert(key, value);
+            }
+            Some(merged)
+        }
+        (base_selector, inline_selector) => inline_selector.or_else(|| base_selector.clone()),
+    };
+
+    let task_match = match (&base.task_match, inline.task_match) {
+        (Some(base_match), Some(inline_match)) => {
+            Some(narrow_task_match(base_match, inline_match).map_err(|key| {
+                FilterPresetError::TaskMatchConflict {
+                    preset: name.to_string(),
+                    key,
+                }
+            })?)
+        }
+        (base_match, inline_match) => inline_match.or_else(|| base_match.clone()),
+    };
+
+    Ok(SubscribeFilter {
+        since: inline.since.or_else(|| base.since.clone()),
+        types,
+        levels,
+        include_status,
+        wrap: inline.wrap.or(base.wrap),
+        series_format: inline.series_format.or_else(|| base.series_format.clone()),
+        materialize_series: inline.materialize_series.or(base.materialize_series),
+        label_selector,
+        task_match,
+    })
+}
+
+/// Returns `true` if `task` meets every condition of `task_match`.
+pub fn matches_task(task: &Task, task_match: &TaskMatch) -> bool {
+    if let Some(ref types) = task_match.types {
+        if !task
+            .r#type
+            .as_deref()
+            .is_some_and(|t| matches_type(t, Some(types)))
+        {
+            return false;
+        }
+    }
+
+    if let Some(ref status) = task_match.status {
+        if !status.contains(&task.status) {
+            return false;
+        }
+    }
+
+    if let Some(ref metadata) = task_match.metadata {
+        let present = task.metadata.as_ref();
+        if !metadata
+            .iter()
+            .all(|(key, value)| present.and_then(|m| m.get(key)) == Some(value))
+        {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Like [`matches_filter`], also checking the filter's `task_match` against
+/// `task`, the task `event` belongs to. Without a task, a filter with a
+/// `task_match` matches nothing.
+pub fn matches_filter_for_task(
+    event: &TaskEvent,
+    filter: &SubscribeFilter,
+    task: Option<&Task>,
+) -> bool {
+    filter_admits_task(filter, task) && matches_filter(event, filter)
+}
+
+/// Whether `filter`'s `task_match`, if any, admits `task`.
+fn filter_admits_task(filter: &SubscribeFilter, task: Option<&Task>) -> bool {
+    filter
+        .task_match
+        .as_ref()
+        .is_none_or(|task_match| task.is_some_and(|task| matches_task(task, task_match)))
+}
+
 /// Returns `true` if the given event passes the subscribe filter.
 pub fn matches_filter(event: &TaskEvent, filter: &SubscribeFilter) -> bool {
     let include_status = filter.include_status.unwrap_or(true);
@@ -55,6 +398,13 @@ pub fn matches_filter(event: &TaskEvent, filter: &SubscribeFilter) -> bool {
         }
     }
 
+    // Status events never carry labels; they are governed by `include_status` alone.
+    if let Some(ref selector) = filter.label_selector {
+        if event.r#type != "taskcast:status" && !matches_labels(event.labels.as_ref(), selector) {
+            return false;
+        }
+    }
+
     true
 }
 
@@ -99,10 +449,140 @@ pub fn apply_filtered_index(
     result
 }
 
+/// Like [`apply_filtered_index`], for the events of `task`. Nothing is kept
+/// when the filter's `task_match` does not admit the task, as for
+/// [`matches_filter_for_task`].
+pub fn apply_filtered_index_for_task(
+    events: &[TaskEvent],
+    filter: &SubscribeFilter,
+    task: Option<&Task>,
+) -> Vec<FilteredEvent> {
+    if !filter_admits_task(filter, task) {
+        return Vec::new();
+    }
+    apply_filtered_index(events, filter)
+}
+
+/// Identifies which events `filter` selects: its types, levels, status
+/// inclusion, label selector and task match, regardless of their order. Cursors and
+/// presentation options such as `wrap` or `seriesFormat` are left out, as
+/// they do not change what matches. A persisted filtered numbering is kept
+/// only while its consumer's filter hash stays the same.
+pub fn filter_hash(filter: &SubscribeFilter) -> String {
+    let mut types = filter.types.clone();
+    if let Some(ref mut types) = types {
+        types.sort();
+        types.dedup();
+    }
+    let mut levels = filter.levels.clone();
+    if let Some(ref mut levels) = levels {
+        levels.sort();
+        levels.dedup();
+    }
+    let labels: Option<BTreeMap<_, _>> = filter
+        .label_selector
+        .as_ref()
+        .map(|selector| selector.iter().collect());
+    let mut canonical = serde_json::json!({
+        "types": types,
+        "levels": levels,
+        "includeStatus": filter.include_status.unwrap_or(true),
+        "labelSelector": labels,
+    });
+    // Left out when unset, so hashes of filters without one are unchanged.
+    if let Some(ref task_match) = filter.task_match {
+        let mut types = task_match.types.clone();
+        if let Some(ref mut types) = types {
+            types.sort();
+            types.dedup();
+        }
+        let mut status: Option<Vec<String>> = task_match.status.as_ref().map(|status| {
+            status
+                .iter()
+                .map(|s| serde_json::to_string(s).unwrap())
+                .collect()
+        });
+        if let Some(ref mut status) = status {
+            status.sort();
+            status.dedup();
+        }
+        let metadata: Option<BTreeMap<_, _>> = task_match
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.iter().collect());
+        canonical["taskMatch"] = serde_json::json!({
+            "types": types,
+            "metadata": metadata,
+            "status": status,
+        });
+    }
+    content_hash(&canonical.to_string())
+}
+
+/// The canonical order of a task's events: `index`, then `timestamp`, then
+/// `id` (bytewise), all ascending. Every store returns `get_events` in this
+/// order, so a page is the same whichever store served it, even when a series
+/// replacement or an old index collision left two events sharing an index.
+pub fn cmp_events(a: &TaskEvent, b: &TaskEvent) -> Ordering {
+    a.index
+        .cmp(&b.index)
+        .then_with(|| a.timestamp.total_cmp(&b.timestamp))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Sorts events into the canonical order of [`cmp_events`].
+pub fn sort_events(events: &mut [TaskEvent]) {
+    events.sort_by(cmp_events);
+}
+
+/// Applies an `EventQueryOptions` cursor, `until` bound, label selector and
+/// limit to a task's full event list, after sorting it into the canonical
+/// order. This is the reference implementation of the `get_events` cursor
+/// contract documented on `ShortTermStore::get_events`; stores that cannot
+/// push the query down (memory, Redis) use it directly.
+pub fn apply_event_query(events: Vec<TaskEvent>, opts: Option<&EventQueryOptions>) -> Vec<TaskEvent> {
+    let mut result = events;
+    sort_events(&mut result);
+    let Some(opts) = opts else {
+        return result;
+    };
+
+    if let Some(ref since) = opts.since {
+        if let Some(ref id) = since.id {
+            // since.id takes priority; an unknown anchor falls back to the full list
+            if let Some(i) = result.iter().position(|e| &e.id == id) {
+                result.drain(..=i);
+            }
+        } else if let Some(index) = since.index {
+            result.retain(|e| e.index > index);
+        } else if let Some(timestamp) = since.timestamp {
+            result.retain(|e| e.timestamp > timestamp);
+        }
+    }
+
+    if let Some(until) = opts.until {
+        result.retain(|e| e.timestamp <= until);
+    }
+
+    if let Some(before_index) = opts.before_index {
+        result.retain(|e| e.index < before_index);
+    }
+
+    if let Some(ref selector) = opts.label_selector {
+        result.retain(|e| matches_labels(e.labels.as_ref(), selector));
+    }
+
+    if let Some(limit) = opts.limit {
+        result.truncate(limit as usize);
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Level, SinceCursor, SubscribeFilter};
+    use crate::types::{Level, SinceCursor, SubscribeFilter, TaskStatus};
     use serde_json::json;
 
     // ─── Helper ──────────────────────────────────────────────────────────
@@ -120,6 +600,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }
     }
@@ -132,6 +614,9 @@ mod tests {
             include_status: None,
             wrap: None,
             series_format: None,
+            materialize_series: None,
+            label_selector: None,
+            task_match: None,
         }
     }
 
@@ -181,6 +666,72 @@ mod tests {
         assert!(!matches_type("log", Some(&patterns)));
     }
 
+    // ─── event type rules ────────────────────────────────────────────────
+
+    #[test]
+    fn event_type_check_points_at_the_field() {
+        let rules = EventTypeRules::default();
+        assert!(rules.check("llm.delta:v2", "/type").is_ok());
+        assert!(rules.check(&"x".repeat(256), "/type").is_ok());
+
+        let violation = rules.check(&"x".repeat(257), "/3/type").unwrap_err();
+        assert_eq!(violation.pointer, "/3/type");
+        assert!(violation.message.contains("must be 1-256"));
+        assert!(rules.check("", "/type").is_err());
+        assert!(rules.check("two words", "/type").is_err());
+        assert!(rules.check("line\n", "/type").is_err());
+        assert!(rules.check("r\u{e9}sum\u{e9}", "/type").is_err());
+    }
+
+    #[test]
+    fn event_type_check_explains_commas() {
+        let violation = EventTypeRules::default()
+            .check("progress,log", "/type")
+            .unwrap_err();
+        assert!(violation.message.contains("commas are not allowed"));
+    }
+
+    #[test]
+    fn event_type_check_honours_max_length() {
+        let rules = EventTypeRules {
+            max_length: 4,
+            ..Default::default()
+        };
+        assert!(rules.check("abcd", "/type").is_ok());
+        assert!(rules.check("abcde", "/type").is_err());
+    }
+
+    #[test]
+    fn normalization_none_leaves_types_alone() {
+        let rules = EventTypeRules::default();
+        let mut filter = SubscribeFilter {
+            types: Some(vec!["Progress".to_string()]),
+            ..Default::default()
+        };
+        rules.normalize_filter(&mut filter);
+        assert_eq!(rules.normalize("Progress"), "Progress");
+        assert!(!matches_type(&rules.normalize("progress"), filter.types.as_deref()));
+    }
+
+    #[test]
+    fn lowercase_normalization_is_symmetric() {
+        let rules = EventTypeRules {
+            normalization: TypeNormalization::Lowercase,
+            ..Default::default()
+        };
+        let mut filter = SubscribeFilter {
+            types: Some(vec!["Progress".to_string(), "LLM.*".to_string()]),
+            ..Default::default()
+        };
+        rules.normalize_filter(&mut filter);
+        for published in ["progress", "Progress", "PROGRESS", "llm.Delta"] {
+            assert!(
+                matches_type(&rules.normalize(published), filter.types.as_deref()),
+                "{published}"
+            );
+        }
+    }
+
     // ─── matches_filter ──────────────────────────────────────────────────
 
     #[test]
@@ -271,6 +822,91 @@ mod tests {
         assert!(!matches_filter(&event, &filter_type_mismatch));
     }
 
+    // ─── labels ──────────────────────────────────────────────────────────
+
+    fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn make_labeled_event(index: u64, pairs: &[(&str, &str)]) -> TaskEvent {
+        TaskEvent {
+            labels: Some(labels(pairs)),
+            ..make_event(index, "log", Level::Info)
+        }
+    }
+
+    #[test]
+    fn matches_labels_requires_every_selector_pair() {
+        let event_labels = labels(&[("region", "eu"), ("worker", "w-42"), ("attempt", "2")]);
+        assert!(matches_labels(
+            Some(&event_labels),
+            &labels(&[("region", "eu"), ("worker", "w-42")])
+        ));
+        assert!(!matches_labels(
+            Some(&event_labels),
+            &labels(&[("region", "eu"), ("worker", "w-7")])
+        ));
+        assert!(!matches_labels(Some(&event_labels), &labels(&[("zone", "a")])));
+    }
+
+    #[test]
+    fn matches_labels_empty_selector_matches_unlabeled_events() {
+        assert!(matches_labels(None, &HashMap::new()));
+        assert!(!matches_labels(None, &labels(&[("region", "eu")])));
+    }
+
+    #[test]
+    fn matches_filter_with_label_selector() {
+        let eu = make_labeled_event(0, &[("region", "eu"), ("worker", "w-42")]);
+        let us = make_labeled_event(1, &[("region", "us"), ("worker", "w-42")]);
+        let unlabeled = make_event(2, "log", Level::Info);
+
+        let filter = SubscribeFilter {
+            label_selector: Some(labels(&[("region", "eu")])),
+            ..empty_filter()
+        };
+        assert!(matches_filter(&eu, &filter));
+        assert!(!matches_filter(&us, &filter));
+        assert!(!matches_filter(&unlabeled, &filter));
+    }
+
+    #[test]
+    fn matches_filter_label_selector_does_not_hide_status_events() {
+        let status = make_event(0, "taskcast:status", Level::Info);
+        let filter = SubscribeFilter {
+            label_selector: Some(labels(&[("region", "eu")])),
+            ..empty_filter()
+        };
+        assert!(matches_filter(&status, &filter));
+
+        let no_status = SubscribeFilter {
+            include_status: Some(false),
+            ..filter
+        };
+        assert!(!matches_filter(&status, &no_status));
+    }
+
+    #[test]
+    fn parse_label_selector_splits_pairs() {
+        let selector = parse_label_selector("region:eu, worker:w-42,").unwrap();
+        assert_eq!(selector, labels(&[("region", "eu"), ("worker", "w-42")]));
+    }
+
+    #[test]
+    fn parse_label_selector_keeps_colons_in_values() {
+        let selector = parse_label_selector("endpoint:http://x").unwrap();
+        assert_eq!(selector, labels(&[("endpoint", "http://x")]));
+    }
+
+    #[test]
+    fn parse_label_selector_rejects_malformed_segments() {
+        assert!(parse_label_selector("region").is_err());
+        assert!(parse_label_selector(":eu").is_err());
+    }
+
     // ─── apply_filtered_index ────────────────────────────────────────────
 
     #[test]
@@ -395,4 +1031,573 @@ mod tests {
         let result = apply_filtered_index(&events, &filter);
         assert!(result.is_empty());
     }
+
+    // ─── filter_hash ─────────────────────────────────────────────────────
+
+    #[test]
+    fn filter_hash_ignores_order_cursors_and_presentation() {
+        let filter = SubscribeFilter {
+            types: Some(vec!["llm.*".to_string(), "log".to_string()]),
+            levels: Some(vec![Level::Warn, Level::Info]),
+            ..empty_filter()
+        };
+        let same = SubscribeFilter {
+            types: Some(vec!["log".to_string(), "llm.*".to_string()]),
+            levels: Some(vec![Level::Info, Level::Warn]),
+            include_status: Some(true),
+            wrap: Some(false),
+            since: Some(SinceCursor {
+                id: None,
+                index: Some(4),
+                timestamp: None,
+            }),
+            ..empty_filter()
+        };
+        assert_eq!(filter_hash(&filter), filter_hash(&same));
+    }
+
+    #[test]
+    fn filter_hash_changes_with_what_matches() {
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            ..empty_filter()
+        };
+        let hash = filter_hash(&filter);
+        assert_ne!(hash, filter_hash(&empty_filter()));
+        assert_ne!(
+            hash,
+            filter_hash(&SubscribeFilter {
+                include_status: Some(false),
+                ..filter.clone()
+            })
+        );
+        assert_ne!(
+            hash,
+            filter_hash(&SubscribeFilter {
+                label_selector: Some(HashMap::from([("env".to_string(), "prod".to_string())])),
+                ..filter
+            })
+        );
+    }
+
+    #[test]
+    fn filter_hash_covers_task_match_only_when_set() {
+        let filter = SubscribeFilter {
+            types: Some(vec!["log".to_string()]),
+            ..empty_filter()
+        };
+        let with_match = |status: Vec<TaskStatus>| SubscribeFilter {
+            task_match: Some(TaskMatch {
+                status: Some(status),
+                ..Default::default()
+            }),
+            ..filter.clone()
+        };
+        assert_ne!(
+            filter_hash(&filter),
+            filter_hash(&with_match(vec![TaskStatus::Running]))
+        );
+        assert_eq!(
+            filter_hash(&with_match(vec![TaskStatus::Running, TaskStatus::Paused])),
+            filter_hash(&with_match(vec![TaskStatus::Paused, TaskStatus::Running]))
+        );
+    }
+
+    // ─── matches_task ────────────────────────────────────────────────────
+
+    fn eu_task(status: TaskStatus) -> Task {
+        serde_json::from_value(json!({
+            "id": "task_01",
+            "type": "report.render",
+            "status": status,
+            "metadata": { "region": "eu", "tier": 2 },
+            "createdAt": 0.0,
+            "updatedAt": 0.0,
+        }))
+        .unwrap()
+    }
+
+    fn region(value: &str) -> Option<HashMap<String, serde_json::Value>> {
+        Some(HashMap::from([("region".to_string(), json!(value))]))
+    }
+
+    #[test]
+    fn matches_task_checks_every_given_field() {
+        let task = eu_task(TaskStatus::Running);
+        assert!(matches_task(&task, &TaskMatch::default()));
+        assert!(matches_task(
+            &task,
+            &TaskMatch {
+                types: Some(vec!["report.*".to_string()]),
+                metadata: Some(HashMap::from([
+                    ("region".to_string(), json!("eu")),
+                    ("tier".to_string(), json!(2)),
+                ])),
+                status: Some(vec![TaskStatus::Running, TaskStatus::Paused]),
+            }
+        ));
+        assert!(!matches_task(
+            &task,
+            &TaskMatch {
+                metadata: region("us"),
+                ..Default::default()
+            }
+        ));
+        assert!(!matches_task(
+            &task,
+            &TaskMatch {
+                metadata: Some(HashMap::from([("owner".to_string(), json!("a"))])),
+                ..Default::default()
+            }
+        ));
+        assert!(!matches_task(
+            &task,
+            &TaskMatch {
+                status: Some(vec![TaskStatus::Completed]),
+                ..Default::default()
+            }
+        ));
+    }
+
+    #[test]
+    fn matches_task_untyped_task_matches_no_type_pattern() {
+        let mut task = eu_task(TaskStatus::Running);
+        task.r#type = None;
+        let task_match = TaskMatch {
+            types: Some(vec!["*".to_string()]),
+            ..Default::default()
+        };
+        assert!(!matches_task(&task, &task_match));
+    }
+
+    #[test]
+    fn matches_filter_for_task_combines_event_and_task_conditions() {
+        let filter = SubscribeFilter {
+            types: Some(vec!["llm.*".to_string()]),
+            task_match: Some(TaskMatch {
+                metadata: region("eu"),
+                ..Default::default()
+            }),
+            ..empty_filter()
+        };
+        let eu = eu_task(TaskStatus::Running);
+        let mut us = eu.clone();
+        us.metadata = Some(region("us").unwrap());
+        let delta = make_event(0, "llm.delta", Level::Info);
+        let log = make_event(1, "log", Level::Info);
+
+        assert!(matches_filter_for_task(&delta, &filter, Some(&eu)));
+        assert!(!matches_filter_for_task(&log, &filter, Some(&eu)));
+        assert!(!matches_filter_for_task(&delta, &filter, Some(&us)));
+        assert!(!matches_filter_for_task(&delta, &filter, None));
+        // Without a task match the task is not consulted.
+        let event_only = SubscribeFilter {
+            task_match: None,
+            ..filter
+        };
+        assert!(matches_filter_for_task(&delta, &event_only, None));
+    }
+
+    #[test]
+    fn apply_filtered_index_for_task_keeps_nothing_for_other_tasks() {
+        let events = vec![
+            make_event(0, "llm.delta", Level::Info),
+            make_event(1, "log", Level::Info),
+            make_event(2, "llm.delta", Level::Info),
+        ];
+        let filter = SubscribeFilter {
+            types: Some(vec!["llm.*".to_string()]),
+            task_match: Some(TaskMatch {
+                status: Some(vec![TaskStatus::Running]),
+                ..Default::default()
+            }),
+            ..empty_filter()
+        };
+        let running = eu_task(TaskStatus::Running);
+        let indexed = apply_filtered_index_for_task(&events, &filter, Some(&running));
+        let raw: Vec<u64> = indexed.iter().map(|fe| fe.raw_index).collect();
+        assert_eq!(raw, [0, 2]);
+        assert_eq!(indexed[1].filtered_index, 1);
+
+        let completed = eu_task(TaskStatus::Completed);
+        assert!(apply_filtered_index_for_task(&events, &filter, Some(&completed)).is_empty());
+    }
+
+    // ─── apply_event_query ───────────────────────────────────────────────
+
+    fn events_0_to_4() -> Vec<TaskEvent> {
+        (0..5).map(|i| make_event(i, "a", Level::Info)).collect()
+    }
+
+    fn query(id: Option<&str>, index: Option<u64>, limit: Option<u64>) -> EventQueryOptions {
+        EventQueryOptions {
+            since: Some(SinceCursor {
+                id: id.map(str::to_string),
+                index,
+                timestamp: None,
+            }),
+            limit,
+            label_selector: None,
+            until: None,
+            before_index: None,
+        }
+    }
+
+    #[test]
+    fn apply_event_query_none_returns_all() {
+        let result = apply_event_query(events_0_to_4(), None);
+        assert_eq!(result.len(), 5);
+    }
+
+    #[test]
+    fn apply_event_query_since_id_takes_priority_over_index() {
+        let opts = query(Some("evt_3"), Some(0), None);
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![4]);
+    }
+
+    #[test]
+    fn apply_event_query_unknown_since_id_returns_full_list() {
+        let opts = query(Some("missing"), Some(3), None);
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        assert_eq!(result.len(), 5);
+    }
+
+    #[test]
+    fn apply_event_query_limit_applies_after_since() {
+        let opts = query(None, Some(1), Some(2));
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![2, 3]);
+    }
+
+    #[test]
+    fn apply_event_query_since_timestamp_is_exclusive() {
+        let opts = EventQueryOptions {
+            since: Some(SinceCursor {
+                id: None,
+                index: None,
+                timestamp: Some(1_700_000_000_002.0),
+            }),
+            limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
+        };
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![3, 4]);
+    }
+
+    #[test]
+    fn apply_event_query_label_selector_applies_before_limit() {
+        let events = vec![
+            make_labeled_event(0, &[("region", "us")]),
+            make_labeled_event(1, &[("region", "eu")]),
+            make_labeled_event(2, &[("region", "us")]),
+            make_labeled_event(3, &[("region", "eu")]),
+            make_labeled_event(4, &[("region", "eu")]),
+        ];
+        let opts = EventQueryOptions {
+            since: None,
+            limit: Some(2),
+            label_selector: Some(labels(&[("region", "eu")])),
+            until: None,
+            before_index: None,
+        };
+        let result = apply_event_query(events, Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![1, 3]);
+    }
+
+    #[test]
+    fn apply_event_query_until_is_inclusive_and_applies_before_limit() {
+        let opts = EventQueryOptions {
+            since: Some(SinceCursor {
+                id: None,
+                index: Some(0),
+                timestamp: None,
+            }),
+            until: Some(1_700_000_000_003.0),
+            ..Default::default()
+        };
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![1, 2, 3]);
+
+        let opts = EventQueryOptions {
+            limit: Some(2),
+            until: Some(1_700_000_000_000.5),
+            ..Default::default()
+        };
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![0]);
+    }
+
+    #[test]
+    fn apply_event_query_before_index_is_exclusive_and_applies_before_limit() {
+        let opts = EventQueryOptions {
+            since: Some(SinceCursor {
+                id: None,
+                index: Some(0),
+                timestamp: None,
+            }),
+            before_index: Some(3),
+            ..Default::default()
+        };
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![1, 2]);
+
+        let opts = EventQueryOptions {
+            limit: Some(4),
+            before_index: Some(2),
+            ..Default::default()
+        };
+        let result = apply_event_query(events_0_to_4(), Some(&opts));
+        let indexes: Vec<u64> = result.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![0, 1]);
+    }
+
+    /// Events sharing indexes, appended out of order.
+    fn tied_events() -> Vec<TaskEvent> {
+        let at = |id: &str, index: u64, offset: f64| TaskEvent {
+            id: id.to_string(),
+            timestamp: 1_700_000_000_000.0 + offset,
+            ..make_event(index, "a", Level::Info)
+        };
+        vec![
+            at("evt_d", 2, 0.0),
+            at("evt_b", 1, 5.0),
+            at("evt_c", 1, 5.0),
+            at("evt_a", 1, 9.0),
+            at("evt_z", 0, 7.0),
+            at("evt_y", 1, 1.0),
+        ]
+    }
+
+    fn ids(events: &[TaskEvent]) -> Vec<&str> {
+        events.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    #[test]
+    fn apply_event_query_sorts_by_index_then_timestamp_then_id() {
+        let expected = ["evt_z", "evt_y", "evt_b", "evt_c", "evt_a", "evt_d"];
+        let result = apply_event_query(tied_events(), None);
+        assert_eq!(ids(&result), expected);
+
+        let mut reversed = tied_events();
+        reversed.reverse();
+        let result = apply_event_query(reversed, Some(&EventQueryOptions::default()));
+        assert_eq!(ids(&result), expected);
+    }
+
+    #[test]
+    fn apply_event_query_since_index_excludes_every_tie() {
+        let opts = query(None, Some(1), None);
+        let result = apply_event_query(tied_events(), Some(&opts));
+        assert_eq!(ids(&result), ["evt_d"]);
+    }
+
+    #[test]
+    fn apply_event_query_since_id_resumes_after_the_anchor_within_a_tie() {
+        let opts = query(Some("evt_b"), None, Some(2));
+        let result = apply_event_query(tied_events(), Some(&opts));
+        assert_eq!(ids(&result), ["evt_c", "evt_a"]);
+    }
+
+    // ─── resolve_filter ──────────────────────────────────────────────────
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn presets() -> HashMap<String, SubscribeFilter> {
+        let mut presets = HashMap::new();
+        presets.insert(
+            "llm".to_string(),
+            SubscribeFilter {
+                types: Some(strings(&["llm.*", "tool.call"])),
+                levels: Some(vec![Level::Info, Level::Warn, Level::Error]),
+                include_status: Some(false),
+                label_selector: Some(labels(&[("region", "eu")])),
+                wrap: Some(false),
+                ..empty_filter()
+            },
+        );
+        presets
+    }
+
+    #[test]
+    fn resolve_filter_without_preset_returns_inline() {
+        let inline = SubscribeFilter {
+            types: Some(strings(&["a"])),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(None, None, inline.clone()).unwrap();
+        assert_eq!(resolved, inline);
+    }
+
+    #[test]
+    fn resolve_filter_preset_alone_is_used_verbatim() {
+        let presets = presets();
+        let resolved = resolve_filter(Some(&presets), Some("llm"), empty_filter()).unwrap();
+        assert_eq!(resolved, presets["llm"]);
+    }
+
+    #[test]
+    fn resolve_filter_unknown_preset_is_an_error() {
+        let presets = presets();
+        let err = resolve_filter(Some(&presets), Some("nope"), empty_filter()).unwrap_err();
+        assert_eq!(err, FilterPresetError::UnknownPreset("nope".to_string()));
+
+        let err = resolve_filter(None, Some("llm"), empty_filter()).unwrap_err();
+        assert_eq!(err, FilterPresetError::UnknownPreset("llm".to_string()));
+    }
+
+    #[test]
+    fn resolve_filter_inline_types_narrow_but_never_widen() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            types: Some(strings(&["llm.delta", "tool.*", "other"])),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        // "llm.delta" narrows "llm.*"; "tool.*" only admits the preset's "tool.call";
+        // "other" is outside the preset entirely.
+        assert_eq!(resolved.types, Some(strings(&["llm.delta", "tool.call"])));
+
+        let inline = SubscribeFilter {
+            types: Some(strings(&["*"])),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        assert_eq!(resolved.types, Some(strings(&["llm.*", "tool.call"])));
+    }
+
+    #[test]
+    fn resolve_filter_disjoint_types_match_nothing() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            types: Some(strings(&["build.*"])),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        assert_eq!(resolved.types, Some(vec![]));
+        assert!(!matches_filter(&make_event(0, "llm.delta", Level::Info), &resolved));
+    }
+
+    #[test]
+    fn resolve_filter_levels_intersect_and_include_status_is_anded() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            levels: Some(vec![Level::Debug, Level::Error]),
+            include_status: Some(true),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        assert_eq!(resolved.levels, Some(vec![Level::Error]));
+        assert_eq!(resolved.include_status, Some(false));
+    }
+
+    #[test]
+    fn resolve_filter_merges_label_selectors() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            label_selector: Some(labels(&[("worker", "w-1")])),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        assert_eq!(
+            resolved.label_selector,
+            Some(labels(&[("region", "eu"), ("worker", "w-1")]))
+        );
+    }
+
+    #[test]
+    fn resolve_filter_conflicting_label_is_an_error() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            label_selector: Some(labels(&[("region", "us")])),
+            ..empty_filter()
+        };
+        let err = resolve_filter(Some(&presets), Some("llm"), inline).unwrap_err();
+        assert_eq!(
+            err,
+            FilterPresetError::LabelConflict {
+                preset: "llm".to_string(),
+                key: "region".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_filter_narrows_the_preset_task_match() {
+        let mut presets = presets();
+        presets.insert(
+            "eu".to_string(),
+            SubscribeFilter {
+                task_match: Some(TaskMatch {
+                    types: Some(strings(&["report.*"])),
+                    metadata: region("eu"),
+                    status: Some(vec![TaskStatus::Running, TaskStatus::Paused]),
+                }),
+                ..empty_filter()
+            },
+        );
+        let inline = SubscribeFilter {
+            task_match: Some(TaskMatch {
+                types: Some(strings(&["*"])),
+                metadata: Some(HashMap::from([("tier".to_string(), json!(2))])),
+                status: Some(vec![TaskStatus::Paused, TaskStatus::Completed]),
+            }),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("eu"), inline).unwrap();
+        assert_eq!(
+            resolved.task_match,
+            Some(TaskMatch {
+                types: Some(strings(&["report.*"])),
+                metadata: Some(HashMap::from([
+                    ("region".to_string(), json!("eu")),
+                    ("tier".to_string(), json!(2)),
+                ])),
+                status: Some(vec![TaskStatus::Paused]),
+            })
+        );
+
+        let conflicting = SubscribeFilter {
+            task_match: Some(TaskMatch {
+                metadata: region("us"),
+                ..Default::default()
+            }),
+            ..empty_filter()
+        };
+        assert_eq!(
+            resolve_filter(Some(&presets), Some("eu"), conflicting).unwrap_err(),
+            FilterPresetError::TaskMatchConflict {
+                preset: "eu".to_string(),
+                key: "region".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_filter_inline_presentation_overrides_preset() {
+        let presets = presets();
+        let inline = SubscribeFilter {
+            wrap: Some(true),
+            since: Some(SinceCursor {
+                id: None,
+                index: Some(3),
+                timestamp: None,
+            }),
+            ..empty_filter()
+        };
+        let resolved = resolve_filter(Some(&presets), Some("llm"), inline).unwrap();
+        assert_eq!(resolved.wrap, Some(true));
+        assert_eq!(resolved.since.unwrap().index, Some(3));
+    }
 }
@@ -0,0 +1,350 @@
+//! Feature flags for rolling a behavior change out to some tasks first.
+//!
+//! A flag is a named [`FlagRule`] from the `flags` config section. Code
+//! behind a switch asks the [`FlagEvaluator`] whether the flag is on for
+//! the task at hand, passing the switch's configured value as the answer
+//! for when no rule of that name exists. Rules are compiled once, when
+//! loaded, so an evaluation is a map lookup, a pattern match and a hash.
+//!
+//! [`FlagEvaluator::reload`] swaps every rule at once; evaluations already
+//! under way finish against the rules they started with.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+
+use crate::config::FlagRule;
+use crate::filter::matches_type;
+
+/// Percentage rollouts are decided in hundredths of a percent.
+const BUCKETS: u64 = 10_000;
+
+/// What a flag is evaluated against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlagContext<'a> {
+    pub task_id: &'a str,
+    pub task_type: Option<&'a str>,
+    /// Subject of the token behind the request, if any.
+    pub sub: Option<&'a str>,
+}
+
+/// One flag as reported by `GET /admin/flags`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagStatus {
+    pub name: String,
+    pub rule: FlagRule,
+    /// Times the flag was evaluated since its rule was loaded.
+    pub evaluations: u64,
+    /// Of those, times it was on.
+    pub enabled: u64,
+}
+
+#[derive(Debug)]
+struct CompiledFlag {
+    rule: FlagRule,
+    /// Seeds the bucket hash, so two flags at 10% pick different tasks.
+    seed: u64,
+    /// Buckets below this are in the rollout.
+    threshold: Option<u64>,
+    subs: Option<HashSet<String>>,
+    evaluations: AtomicU64,
+    enabled: AtomicU64,
+}
+
+impl CompiledFlag {
+    fn new(name: &str, rule: FlagRule) -> Self {
+        Self {
+            seed: fnv1a(FNV_OFFSET, name.as_bytes()),
+            threshold: rule
+                .percentage
+                .map(|p| (p.clamp(0.0, 100.0) * (BUCKETS / 100) as f64).round() as u64),
+            subs: rule
+                .subs
+                .as_ref()
+                .map(|subs| subs.iter().cloned().collect()),
+            rule,
+            evaluations: AtomicU64::new(0),
+            enabled: AtomicU64::new(0),
+        }
+    }
+
+    fn evaluate(&self, ctx: &FlagContext<'_>) -> bool {
+        let on = self.rule.enabled
+            && self.rule.task_types.as_deref().is_none_or(|patterns| {
+                ctx.task_type
+                    .is_some_and(|task_type| matches_type(task_type, Some(patterns)))
+            })
+            && self
+                .subs
+                .as_ref()
+                .is_none_or(|subs| ctx.sub.is_some_and(|sub| subs.contains(sub)))
+            && self
+                .threshold
+                .is_none_or(|threshold| self.bucket(ctx.task_id) < threshold);
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        if on {
+            self.enabled.fetch_add(1, Ordering::Relaxed);
+        }
+        on
+    }
+
+    fn bucket(&self, task_id: &str) -> u64 {
+        fnv1a(self.seed, task_id.as_bytes()) % BUCKETS
+    }
+}
+
+/// Evaluates the configured flags. Shared by the engine and the server.
+#[derive(Debug, Default)]
+pub struct FlagEvaluator {
+    flags: RwLock<Arc<HashMap<String, Arc<CompiledFlag>>>>,
+}
+
+impl FlagEvaluator {
+    pub fn from_config(flags: Option<&HashMap<String, FlagRule>>) -> Self {
+        let evaluator = Self::default();
+        evaluator.reload(flags);
+        evaluator
+    }
+
+    /// Replaces every rule. A flag whose rule is unchanged keeps its
+    /// counters.
+    pub fn reload(&self, flags: Option<&HashMap<String, FlagRule>>) {
+        let mut current = self.flags.write().unwrap();
+        let compiled = flags
+            .into_iter()
+            .flatten()
+            .map(|(name, rule)| {
+                let flag = match current.get(name) {
+                    Some(flag) if flag.rule == *rule => Arc::clone(flag),
+                    _ => Arc::new(CompiledFlag::new(name, rule.clone())),
+                };
+                (name.clone(), flag)
+            })
+            .collect();
+        *current = Arc::new(compiled);
+    }
+
+    fn get(&self, name: &str) -> Option<Arc<CompiledFlag>> {
+        self.flags.read().unwrap().get(name).cloned()
+    }
+
+    /// Whether flag `name` is on for `ctx`, or `None` when no rule of that
+    /// name is configured.
+    pub fn evaluate(&self, name: &str, ctx: &FlagContext<'_>) -> Option<bool> {
+        self.get(name).map(|flag| flag.evaluate(ctx))
+    }
+
+    /// [`evaluate`](Self::evaluate), falling back to `default`, the
+    /// switch's own setting, when the flag is not configured.
+    pub fn is_enabled(&self, name: &str, ctx: &FlagContext<'_>, default: bool) -> bool {
+        self.evaluate(name, ctx).unwrap_or(default)
+    }
+
+    pub fn is_configured(&self, name: &str) -> bool {
+        self.flags.read().unwrap().contains_key(name)
+    }
+
+    /// Whether flag `name` needs the task's type, so callers that would
+    /// have to read the task for it can skip the read otherwise.
+    pub fn targets_task_types(&self, name: &str) -> bool {
+        self.get(name)
+            .is_some_and(|flag| flag.rule.enabled && flag.rule.task_types.is_some())
+    }
+
+    /// Every flag with its rule and counters, by name.
+    pub fn statuses(&self) -> Vec<FlagStatus> {
+        let flags = Arc::clone(&self.flags.read().unwrap());
+        let mut statuses: Vec<FlagStatus> = flags
+            .iter()
+            .map(|(name, flag)| FlagStatus {
+                name: name.clone(),
+                rule: flag.rule.clone(),
+                evaluations: flag.evaluations.load(Ordering::Relaxed),
+                enabled: flag.enabled.load(Ordering::Relaxed),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a, stable across processes and releases unlike `std`'s
+/// hasher, so every instance puts a task in the same bucket.
+fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(seed, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(enabled: bool) -> FlagRule {
+        FlagRule {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    fn evaluator(name: &str, rule: FlagRule) -> FlagEvaluator {
+        FlagEvaluator::from_config(Some(&HashMap::from([(name.to_string(), rule)])))
+    }
+
+    fn task(task_id: &str) -> FlagContext<'_> {
+        FlagContext {
+            task_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unconfigured_flags_fall_back_to_the_switch() {
+        let flags = FlagEvaluator::default();
+        assert_eq!(flags.evaluate("strictBodies", &task("t1")), None);
+        assert!(flags.is_enabled("strictBodies", &task("t1"), true));
+        assert!(!flags.is_enabled("strictBodies", &task("t1"), false));
+    }
+
+    #[test]
+    fn configured_flags_override_the_switch() {
+        let off = evaluator("strictBodies", rule(false));
+        assert!(!off.is_enabled("strictBodies", &task("t1"), true));
+        let on = evaluator("strictBodies", rule(true));
+        assert!(on.is_enabled("strictBodies", &task("t1"), false));
+    }
+
+    #[test]
+    fn percentage_buckets_are_deterministic_per_task_id() {
+        let ten_percent = FlagRule {
+            percentage: Some(10.0),
+            ..rule(true)
+        };
+        let first = evaluator("includeDiffs", ten_percent.clone());
+        let second = evaluator("includeDiffs", ten_percent);
+        let ids: Vec<String> = (0..2000).map(|i| format!("task-{i}")).collect();
+        let picked: Vec<&String> = ids
+            .iter()
+            .filter(|id| first.is_enabled("includeDiffs", &task(id), false))
+            .collect();
+
+        for id in &ids {
+            let on = first.is_enabled("includeDiffs", &task(id), false);
+            assert_eq!(second.is_enabled("includeDiffs", &task(id), false), on);
+        }
+        assert!((150..250).contains(&picked.len()), "{}", picked.len());
+
+        // Another flag at the same percentage picks other tasks.
+        let other = evaluator(
+            "strictBodies",
+            FlagRule {
+                percentage: Some(10.0),
+                ..rule(true)
+            },
+        );
+        let overlap = picked
+            .iter()
+            .filter(|id| other.is_enabled("strictBodies", &task(id), false))
+            .count();
+        assert!(overlap < picked.len() / 2, "{overlap} of {}", picked.len());
+    }
+
+    #[test]
+    fn zero_and_full_percentages() {
+        let none = evaluator(
+            "f",
+            FlagRule {
+                percentage: Some(0.0),
+                ..rule(true)
+            },
+        );
+        let all = evaluator(
+            "f",
+            FlagRule {
+                percentage: Some(100.0),
+                ..rule(true)
+            },
+        );
+        for i in 0..500 {
+            let id = format!("task-{i}");
+            assert!(!none.is_enabled("f", &task(&id), true));
+            assert!(all.is_enabled("f", &task(&id), false));
+        }
+    }
+
+    #[test]
+    fn task_type_patterns_target_tasks() {
+        let flags = evaluator(
+            "f",
+            FlagRule {
+                task_types: Some(vec!["llm.*".to_string(), "export".to_string()]),
+                ..rule(true)
+            },
+        );
+        let typed = |task_type| FlagContext {
+            task_id: "t1",
+            task_type: Some(task_type),
+            sub: None,
+        };
+        assert!(flags.is_enabled("f", &typed("llm.chat"), false));
+        assert!(flags.is_enabled("f", &typed("export"), false));
+        assert!(!flags.is_enabled("f", &typed("llm"), true));
+        assert!(!flags.is_enabled("f", &typed("import"), true));
+        assert!(!flags.is_enabled("f", &task("t1"), true));
+        assert!(flags.targets_task_types("f"));
+        assert!(!evaluator("f", rule(true)).targets_task_types("f"));
+    }
+
+    #[test]
+    fn subs_target_callers() {
+        let flags = evaluator(
+            "f",
+            FlagRule {
+                subs: Some(vec!["svc-a".to_string()]),
+                ..rule(true)
+            },
+        );
+        let from = |sub| FlagContext {
+            task_id: "t1",
+            task_type: None,
+            sub,
+        };
+        assert!(flags.is_enabled("f", &from(Some("svc-a")), false));
+        assert!(!flags.is_enabled("f", &from(Some("svc-b")), true));
+        assert!(!flags.is_enabled("f", &from(None), true));
+    }
+
+    #[test]
+    fn reload_swaps_rules_and_keeps_unchanged_counters() {
+        let flags = FlagEvaluator::from_config(Some(&HashMap::from([
+            ("a".to_string(), rule(true)),
+            ("b".to_string(), rule(true)),
+        ])));
+        flags.is_enabled("a", &task("t1"), false);
+        flags.is_enabled("b", &task("t1"), false);
+
+        flags.reload(Some(&HashMap::from([
+            ("a".to_string(), rule(true)),
+            ("b".to_string(), rule(false)),
+        ])));
+        flags.is_enabled("b", &task("t1"), true);
+
+        let statuses = flags.statuses();
+        assert_eq!(
+            statuses
+                .iter()
+                .map(|s| (s.name.as_str(), s.evaluations, s.enabled))
+                .collect::<Vec<_>>(),
+            [("a", 1, 1), ("b", 1, 0)]
+        );
+        flags.reload(None);
+        assert!(flags.statuses().is_empty());
+        assert_eq!(flags.evaluate("a", &task("t1")), None);
+    }
+}
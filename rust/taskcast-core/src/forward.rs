@@ -0,0 +1,147 @@
+//! Observer tasks: events a task emits, also published on another task.
+//!
+//! A task created with a [`ForwardRule`] has each of its events that pass
+//! the rule's filter copied onto the target task, after the original is
+//! stored. The copy gets the target's next index like any other event and
+//! carries the original's data wrapped with where it came from. Forwarded
+//! events are never forwarded again, whatever rule the target has.
+
+use serde_json::json;
+
+use crate::engine::PublishEventInput;
+use crate::filter::matches_filter;
+use crate::types::{ForwardRule, TaskEvent};
+
+/// Event types in this namespace describe the task they are emitted on, so
+/// they are only forwarded under a `prefixType`.
+const RESERVED_PREFIX: &str = "taskcast:";
+
+/// The event to publish on `rule`'s target for `event`, emitted on the task
+/// `source_task_id`, or `None` when the rule does not forward it.
+///
+/// The data becomes `{ sourceTaskId, sourceEventId, data }`. Labels are kept;
+/// series fields are not, as the series belongs to the source task.
+pub fn forwarded_event_input(
+    source_task_id: &str,
+    event: &TaskEvent,
+    rule: &ForwardRule,
+) -> Option<PublishEventInput> {
+    if !matches_filter(event, &rule.filter) {
+        return None;
+    }
+    let transform = rule.transform.clone().unwrap_or_default();
+    let r#type = match transform.prefix_type {
+        Some(prefix) => format!("{prefix}:{}", event.r#type),
+        None if event.r#type.starts_with(RESERVED_PREFIX) => return None,
+        None => event.r#type.clone(),
+    };
+    Some(PublishEventInput {
+        r#type,
+        level: transform.level.unwrap_or_else(|| event.level.clone()),
+        data: json!({
+            "sourceTaskId": source_task_id,
+            "sourceEventId": event.id,
+            "data": event.data,
+        }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: event.labels.clone(),
+        broadcast_debounce_ms: None,
+        series_end: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::types::{ForwardTransform, Level, SeriesMode, SubscribeFilter};
+
+    fn event(r#type: &str, level: Level) -> TaskEvent {
+        TaskEvent {
+            id: "e1".to_string(),
+            task_id: "child".to_string(),
+            index: 3,
+            timestamp: 1.0,
+            r#type: r#type.to_string(),
+            level,
+            data: json!({ "percent": 50 }),
+            series_id: Some("progress".to_string()),
+            series_mode: Some(SeriesMode::Latest),
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: Some(HashMap::from([("stage".to_string(), "fetch".to_string())])),
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    fn rule(filter: SubscribeFilter, transform: Option<ForwardTransform>) -> ForwardRule {
+        ForwardRule {
+            target_task_id: "parent".to_string(),
+            filter,
+            transform,
+        }
+    }
+
+    #[test]
+    fn wraps_the_data_and_applies_the_transform() {
+        let transform = ForwardTransform {
+            prefix_type: Some("child".to_string()),
+            level: Some(Level::Debug),
+        };
+        let input = forwarded_event_input(
+            "child",
+            &event("progress", Level::Info),
+            &rule(SubscribeFilter::default(), Some(transform)),
+        )
+        .unwrap();
+        assert_eq!(input.r#type, "child:progress");
+        assert_eq!(input.level, Level::Debug);
+        assert_eq!(
+            input.data,
+            json!({
+                "sourceTaskId": "child",
+                "sourceEventId": "e1",
+                "data": { "percent": 50 },
+            })
+        );
+        assert_eq!(input.labels.unwrap()["stage"], "fetch");
+        assert!(input.series_id.is_none());
+        assert!(input.series_mode.is_none());
+    }
+
+    #[test]
+    fn respects_the_filter() {
+        let filter = SubscribeFilter {
+            levels: Some(vec![Level::Warn, Level::Error]),
+            ..Default::default()
+        };
+        let rule = rule(filter, None);
+        assert!(forwarded_event_input("child", &event("log", Level::Info), &rule).is_none());
+        let input = forwarded_event_input("child", &event("log", Level::Warn), &rule).unwrap();
+        assert_eq!(input.r#type, "log");
+        assert_eq!(input.level, Level::Warn);
+    }
+
+    #[test]
+    fn reserved_types_need_a_prefix() {
+        let status = event("taskcast:status", Level::Info);
+        assert!(forwarded_event_input("child", &status, &rule(Default::default(), None)).is_none());
+        let prefixed = rule(
+            Default::default(),
+            Some(ForwardTransform {
+                prefix_type: Some("child".to_string()),
+                level: None,
+            }),
+        );
+        assert_eq!(
+            forwarded_event_input("child", &status, &prefixed)
+                .unwrap()
+                .r#type,
+            "child:taskcast:status"
+        );
+    }
+}
@@ -3,10 +3,11 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use tokio::sync::RwLock;
-use tokio::task::JoinHandle;
-use tokio::time::{sleep, interval, Duration};
+use tokio::task::AbortHandle;
+use tokio::time::{sleep, Duration};
 
 use crate::engine::{TaskEngine, TransitionPayload};
+use crate::runner::{runner_fn, RunnerTickReport};
 use crate::types::{
     DisconnectPolicy, ShortTermStore, TaskError, TaskStatus, WorkerFilter, WorkerStatus,
 };
@@ -38,7 +39,7 @@ pub struct HeartbeatMonitor {
     heartbeat_timeout_ms: u64,
     default_disconnect_policy: DisconnectPolicy,
     disconnect_grace_ms: u64,
-    handle: Option<JoinHandle<()>>,
+    handle: Option<AbortHandle>,
     /// Set of worker IDs currently in a grace period (pending reassignment).
     grace_workers: Arc<RwLock<HashSet<String>>>,
 }
@@ -68,22 +69,31 @@ impl HeartbeatMonitor {
         let grace_ms = self.disconnect_grace_ms;
         let grace_workers = self.grace_workers.clone();
 
-        self.handle = Some(tokio::spawn(async move {
-            let mut ticker = interval(Duration::from_millis(interval_ms));
-            loop {
-                ticker.tick().await;
-                let _ = Self::tick_inner(
-                    &worker_manager,
-                    &engine,
-                    &store,
-                    timeout_ms,
-                    &default_policy,
-                    grace_ms,
-                    &grace_workers,
-                )
-                .await;
-            }
-        }));
+        let runner = runner_fn(
+            "heartbeat.loop",
+            Duration::from_millis(interval_ms),
+            move || {
+                let worker_manager = worker_manager.clone();
+                let engine = engine.clone();
+                let store = store.clone();
+                let default_policy = default_policy.clone();
+                let grace_workers = grace_workers.clone();
+                async move {
+                    Self::tick_inner(
+                        &worker_manager,
+                        &engine,
+                        &store,
+                        timeout_ms,
+                        &default_policy,
+                        grace_ms,
+                        &grace_workers,
+                    )
+                    .await
+                    .map(|()| RunnerTickReport::default())
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
     }
 
     pub fn stop(&mut self) {
@@ -215,23 +225,27 @@ impl HeartbeatMonitor {
                     let tid = assignment.task_id.clone();
                     let grace_set = grace_workers.clone();
 
-                    tokio::spawn(async move {
-                        sleep(Duration::from_millis(grace_ms)).await;
-                        grace_set.write().await.remove(&wid);
+                    engine.background().spawn(
+                        "heartbeat.grace_reassign",
+                        Some(tid.clone()),
+                        async move {
+                            sleep(Duration::from_millis(grace_ms)).await;
+                            grace_set.write().await.remove(&wid);
 
-                        // Check if worker came back during grace period
-                        if let Ok(Some(w)) = store_clone.get_worker(&wid).await {
-                            if w.status != WorkerStatus::Offline {
-                                return;
+                            // Check if worker came back during grace period
+                            if let Ok(Some(w)) = store_clone.get_worker(&wid).await {
+                                if w.status != WorkerStatus::Offline {
+                                    return;
+                                }
                             }
-                        }
-
-                        // Worker is still offline — reassign the task
-                        let _ = eng
-                            .transition_task(&tid, TaskStatus::Pending, None)
-                            .await;
-                        let _ = wm.release_task(&tid).await;
-                    });
+
+                            // Worker is still offline — reassign the task
+                            let _ = eng
+                                .transition_task(&tid, TaskStatus::Pending, None)
+                                .await;
+                            let _ = wm.release_task(&tid).await;
+                        },
+                    );
                 }
             }
         }
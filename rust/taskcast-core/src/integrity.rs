@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::{ErrorContext, Level, Task, TaskEvent, TaskcastHooks};
+
+/// Type of the synthetic event that stands in for an undecodable stored event
+/// under [`CorruptRecordPolicy::Surface`].
+pub const CORRUPT_EVENT_TYPE: &str = "taskcast:corrupt";
+
+/// What a store read does with a record it cannot decode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorruptRecordPolicy {
+    /// Corrupt events are returned as `taskcast:corrupt` events carrying the
+    /// raw payload and the parse error; reading a corrupt task fails with a
+    /// [`CorruptRecord`] error.
+    #[default]
+    Surface,
+    /// Corrupt records are left out of the result and reported through
+    /// `TaskcastHooks::on_unhandled_error`.
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorruptRecordKind {
+    Task,
+    Event,
+}
+
+impl std::fmt::Display for CorruptRecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorruptRecordKind::Task => f.write_str("task"),
+            CorruptRecordKind::Event => f.write_str("event"),
+        }
+    }
+}
+
+/// A stored task or event that could not be decoded, with whatever could be
+/// recovered from it.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Corrupt {kind} record for task {task_id} ({}): {reason}", self.location())]
+pub struct CorruptRecord {
+    pub kind: CorruptRecordKind,
+    pub task_id: String,
+    /// Event id, if it could be read.
+    pub id: Option<String>,
+    /// Event index, or the record's offset in storage when the index is
+    /// unreadable.
+    pub position: Option<u64>,
+    pub timestamp: Option<f64>,
+    /// The stored payload as read: the raw text for JSON-encoded records, or
+    /// the row's columns for relational stores.
+    pub raw: Box<Value>,
+    pub reason: String,
+}
+
+impl CorruptRecord {
+    pub fn task(task_id: &str, raw: Value, reason: impl Into<String>) -> Self {
+        Self {
+            kind: CorruptRecordKind::Task,
+            task_id: task_id.to_string(),
+            id: Some(task_id.to_string()),
+            position: None,
+            timestamp: None,
+            raw: Box::new(raw),
+            reason: reason.into(),
+        }
+    }
+
+    /// The synthetic `taskcast:corrupt` event surfaced in place of this record.
+    pub fn to_event(&self) -> TaskEvent {
+        let position = self.position.unwrap_or(0);
+        TaskEvent {
+            id: self
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("corrupt:{}:{position}", self.task_id)),
+            task_id: self.task_id.clone(),
+            index: position,
+            timestamp: self.timestamp.unwrap_or(0.0),
+            r#type: CORRUPT_EVENT_TYPE.to_string(),
+            level: Level::Error,
+            data: serde_json::json!({ "raw": self.raw, "error": self.reason }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    fn location(&self) -> String {
+        match (&self.id, self.position) {
+            (Some(id), _) => format!("id {id}"),
+            (None, Some(position)) => format!("position {position}"),
+            (None, None) => "unknown position".to_string(),
+        }
+    }
+
+    /// Identifies the record so re-reading it is not counted twice.
+    fn key(&self) -> String {
+        format!("{}:{}", self.kind, self.location())
+    }
+}
+
+/// Decodes a JSON-encoded stored event. On failure, the id, index and
+/// timestamp are recovered from the text where possible, falling back to
+/// `position` (the record's offset in storage) for the index.
+pub fn decode_stored_event(
+    task_id: &str,
+    position: u64,
+    raw: &str,
+) -> Result<TaskEvent, CorruptRecord> {
+    serde_json::from_str(raw).map_err(|err| {
+        let partial: Option<Value> = serde_json::from_str(raw).ok();
+        let field = |name: &str| partial.as_ref().and_then(|v| v.get(name));
+        CorruptRecord {
+            kind: CorruptRecordKind::Event,
+            task_id: task_id.to_string(),
+            id: field("id").and_then(Value::as_str).map(str::to_string),
+            position: Some(field("index").and_then(Value::as_u64).unwrap_or(position)),
+            timestamp: field("timestamp").and_then(Value::as_f64),
+            raw: Box::new(Value::String(raw.to_string())),
+            reason: err.to_string(),
+        }
+    })
+}
+
+/// Decodes a JSON-encoded stored task.
+pub fn decode_stored_task(task_id: &str, raw: &str) -> Result<Task, CorruptRecord> {
+    serde_json::from_str(raw).map_err(|err| {
+        CorruptRecord::task(task_id, Value::String(raw.to_string()), err.to_string())
+    })
+}
+
+struct Inner {
+    policy: CorruptRecordPolicy,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    seen: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+/// Applies a [`CorruptRecordPolicy`] on store read paths and counts the
+/// distinct corrupt records seen per task. Clones share state, so one monitor
+/// can be handed to several stores.
+#[derive(Clone)]
+pub struct IntegrityMonitor {
+    inner: Arc<Inner>,
+}
+
+impl IntegrityMonitor {
+    pub fn new(policy: CorruptRecordPolicy) -> Self {
+        Self::with_hooks(policy, None)
+    }
+
+    /// Like [`new`](Self::new), reporting skipped records to `hooks`.
+    pub fn with_hooks(policy: CorruptRecordPolicy, hooks: Option<Arc<dyn TaskcastHooks>>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                policy,
+                hooks,
+                seen: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    pub fn policy(&self) -> CorruptRecordPolicy {
+        self.inner.policy
+    }
+
+    /// Resolves a decoded event: a corrupt one is counted, then surfaced as a
+    /// `taskcast:corrupt` event or reported and dropped.
+    pub fn event(&self, decoded: Result<TaskEvent, CorruptRecord>) -> Option<TaskEvent> {
+        let record = match decoded {
+            Ok(event) => return Some(event),
+            Err(record) => record,
+        };
+        self.record(&record);
+        match self.inner.policy {
+            CorruptRecordPolicy::Surface => Some(record.to_event()),
+            CorruptRecordPolicy::Skip => {
+                self.report(&record, "storage.read_event");
+                None
+            }
+        }
+    }
+
+    /// Resolves a decoded task: a corrupt one is counted, then returned as an
+    /// error or reported and dropped.
+    pub fn task(
+        &self,
+        decoded: Result<Task, CorruptRecord>,
+    ) -> Result<Option<Task>, CorruptRecord> {
+        let record = match decoded {
+            Ok(task) => return Ok(Some(task)),
+            Err(record) => record,
+        };
+        self.record(&record);
+        match self.inner.policy {
+            CorruptRecordPolicy::Surface => Err(record),
+            CorruptRecordPolicy::Skip => {
+                self.report(&record, "storage.read_task");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Distinct corrupt records seen for a task.
+    pub fn corrupt_records(&self, task_id: &str) -> u64 {
+        let seen = self.inner.seen.lock().unwrap();
+        seen.get(task_id).map_or(0, |keys| keys.len() as u64)
+    }
+
+    /// Distinct corrupt records seen across all tasks.
+    pub fn total_corrupt_records(&self) -> u64 {
+        let seen = self.inner.seen.lock().unwrap();
+        seen.values().map(|keys| keys.len() as u64).sum()
+    }
+
+    /// Whether two monitors share state.
+    pub fn ptr_eq(&self, other: &IntegrityMonitor) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn record(&self, record: &CorruptRecord) {
+        self.inner
+            .seen
+            .lock()
+            .unwrap()
+            .entry(record.task_id.clone())
+            .or_default()
+            .insert(record.key());
+    }
+
+    fn report(&self, record: &CorruptRecord, operation: &str) {
+        if let Some(ref hooks) = self.inner.hooks {
+            let context = ErrorContext {
+                operation: operation.to_string(),
+                task_id: Some(record.task_id.clone()),
+            };
+            hooks.on_unhandled_error(record, &context);
+        }
+    }
+}
+
+impl Default for IntegrityMonitor {
+    fn default() -> Self {
+        Self::new(CorruptRecordPolicy::default())
+    }
+}
+
+impl std::fmt::Debug for IntegrityMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntegrityMonitor")
+            .field("policy", &self.inner.policy)
+            .field("corrupt_records", &self.total_corrupt_records())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct RecordingHooks {
+        errors: Mutex<Vec<(String, ErrorContext)>>,
+    }
+
+    impl TaskcastHooks for RecordingHooks {
+        fn on_unhandled_error(
+            &self,
+            err: &(dyn std::error::Error + Send + Sync),
+            context: &ErrorContext,
+        ) {
+            self.errors
+                .lock()
+                .unwrap()
+                .push((err.to_string(), context.clone()));
+        }
+    }
+
+    const BAD_LEVEL: &str = r#"{"id":"e1","taskId":"t1","index":3,"timestamp":5.0,"type":"log","level":"critical","data":null}"#;
+
+    #[test]
+    fn decode_recovers_id_index_and_timestamp() {
+        let record = decode_stored_event("t1", 9, BAD_LEVEL).unwrap_err();
+        assert_eq!(record.kind, CorruptRecordKind::Event);
+        assert_eq!(record.id.as_deref(), Some("e1"));
+        assert_eq!(record.position, Some(3));
+        assert_eq!(record.timestamp, Some(5.0));
+        assert_eq!(*record.raw, json!(BAD_LEVEL));
+        assert!(record.reason.contains("critical"), "{}", record.reason);
+    }
+
+    #[test]
+    fn decode_falls_back_to_storage_position() {
+        let record = decode_stored_event("t1", 9, "{not json").unwrap_err();
+        assert_eq!(record.id, None);
+        assert_eq!(record.position, Some(9));
+        assert_eq!(record.to_event().id, "corrupt:t1:9");
+    }
+
+    #[test]
+    fn surface_returns_synthetic_event() {
+        let monitor = IntegrityMonitor::new(CorruptRecordPolicy::Surface);
+        let event = monitor
+            .event(decode_stored_event("t1", 0, BAD_LEVEL))
+            .unwrap();
+        assert_eq!(event.r#type, CORRUPT_EVENT_TYPE);
+        assert_eq!(event.id, "e1");
+        assert_eq!(event.index, 3);
+        assert_eq!(event.level, Level::Error);
+        assert_eq!(event.data["raw"], json!(BAD_LEVEL));
+        assert!(event.data["error"].as_str().unwrap().contains("critical"));
+    }
+
+    #[test]
+    fn skip_drops_and_reports() {
+        let hooks = Arc::new(RecordingHooks::default());
+        let monitor = IntegrityMonitor::with_hooks(
+            CorruptRecordPolicy::Skip,
+            Some(hooks.clone() as Arc<dyn TaskcastHooks>),
+        );
+        assert!(monitor
+            .event(decode_stored_event("t1", 0, BAD_LEVEL))
+            .is_none());
+
+        let errors = hooks.errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.contains("id e1"), "{}", errors[0].0);
+        assert_eq!(errors[0].1.operation, "storage.read_event");
+        assert_eq!(errors[0].1.task_id.as_deref(), Some("t1"));
+    }
+
+    #[test]
+    fn corrupt_task_is_an_error_or_skipped() {
+        let surface = IntegrityMonitor::new(CorruptRecordPolicy::Surface);
+        let err = surface
+            .task(decode_stored_task("t1", r#"{"id":"t1","status":"zombie"}"#))
+            .unwrap_err();
+        assert_eq!(err.kind, CorruptRecordKind::Task);
+
+        let skip = IntegrityMonitor::new(CorruptRecordPolicy::Skip);
+        assert_eq!(skip.task(decode_stored_task("t1", "{")).unwrap(), None);
+    }
+
+    #[test]
+    fn counts_distinct_records_per_task() {
+        let monitor = IntegrityMonitor::default();
+        for _ in 0..3 {
+            monitor.event(decode_stored_event("t1", 0, BAD_LEVEL));
+        }
+        monitor.event(decode_stored_event("t1", 7, "{"));
+        monitor.event(decode_stored_event("t2", 0, "{"));
+
+        assert_eq!(monitor.corrupt_records("t1"), 2);
+        assert_eq!(monitor.corrupt_records("t2"), 1);
+        assert_eq!(monitor.corrupt_records("t3"), 0);
+        assert_eq!(monitor.total_corrupt_records(), 3);
+        assert!(monitor.ptr_eq(&monitor.clone()));
+    }
+
+    #[test]
+    fn policy_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_value(CorruptRecordPolicy::Skip).unwrap(),
+            json!("skip")
+        );
+        assert_eq!(
+            serde_json::from_value::<CorruptRecordPolicy>(json!("surface")).unwrap(),
+            CorruptRecordPolicy::Surface
+        );
+    }
+}
@@ -1,26 +1,98 @@
+pub mod activation;
 pub mod archive;
+pub mod backoff;
+pub mod background;
+pub mod channels;
+pub mod checksum;
 pub mod cleanup;
+mod coalesce;
+pub mod completion;
+pub mod compression;
 pub mod config;
+pub mod consistency;
+pub mod conventions;
+pub mod deadline;
+mod debounce;
+pub mod diff;
 pub mod engine;
+pub mod event_stream;
 pub mod filter;
+pub mod flags;
+pub mod forward;
 pub mod heartbeat_monitor;
+pub mod integrity;
+pub mod lifecycle;
 pub mod memory_adapters;
+pub mod migration;
+pub mod negative_cache;
+pub mod outcomes;
+pub mod payload_dedup;
+pub mod point_in_time;
+pub mod query_stats;
+pub mod read_routing;
+pub mod reader;
+pub mod redaction;
+pub mod replay;
+pub mod request_deadline;
+pub mod retry;
+pub mod runner;
 pub mod scheduler;
 pub mod series;
+pub mod standby;
 pub mod state_machine;
+pub mod storage;
+pub mod store_error;
+pub mod typed;
 pub mod types;
+pub mod validation;
 pub mod worker_manager;
 pub mod worker_matching;
+pub mod write_shaping;
 
+pub use activation::*;
 pub use archive::*;
+pub use backoff::compute_backoff;
+pub use background::*;
+pub use channels::*;
+pub use checksum::*;
 pub use cleanup::*;
+pub use completion::*;
+pub use compression::*;
+pub use consistency::*;
+pub use conventions::*;
+pub use deadline::*;
+pub use diff::*;
 pub use engine::*;
+pub use event_stream::*;
 pub use filter::*;
+pub use flags::*;
+pub use forward::*;
 pub use heartbeat_monitor::*;
+pub use integrity::*;
+pub use lifecycle::*;
 pub use memory_adapters::*;
+pub use migration::*;
+pub use negative_cache::{NegativeCacheStats, DEFAULT_NEGATIVE_CACHE_TTL};
+pub use outcomes::*;
+pub use payload_dedup::*;
+pub use point_in_time::*;
+pub use query_stats::*;
+pub use read_routing::*;
+pub use reader::TaskcastReader;
+pub use redaction::*;
+pub use replay::*;
+pub use request_deadline::*;
+pub use retry::*;
+pub use runner::*;
 pub use scheduler::*;
 pub use series::*;
+pub use standby::*;
 pub use state_machine::*;
+pub use storage::*;
+pub use store_error::*;
+pub use typed::*;
 pub use types::*;
+pub use validation::*;
 pub use worker_manager::*;
 pub use worker_matching::*;
+pub use write_shaping::{WriteOverflow, WriteShapingConfig, WriteShapingStats};
@@ -0,0 +1,116 @@
+//! Task lifecycle notifications for in-process bookkeeping.
+//!
+//! Maps keyed by task id (locks, caches, queues, subscriber registries)
+//! subscribe to the engine's [`TaskLifecycle`] and drop their entries when a
+//! task ends, is deleted or expires, rather than each growing its own
+//! garbage collection.
+
+use std::sync::{Arc, Mutex};
+
+use crate::types::TaskStatus;
+
+/// What happened to a task.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskLifecycleEvent {
+    /// The task moved to a terminal status. It may still be reopened by a
+    /// retry or re-claim, so state needed to finish delivering its last
+    /// events should be kept until it is deleted or expires.
+    Terminal(TaskStatus),
+    /// The task was deleted from the short-term store.
+    Deleted,
+    /// The task's TTL ran out.
+    Expired,
+}
+
+impl TaskLifecycleEvent {
+    /// Whether the task is gone from the short-term store.
+    pub fn is_removal(&self) -> bool {
+        matches!(self, Self::Deleted | Self::Expired)
+    }
+}
+
+/// Callback signature for lifecycle listeners.
+/// Receives the task id and what happened to it.
+pub type LifecycleListener = Arc<dyn Fn(&str, &TaskLifecycleEvent) + Send + Sync>;
+
+/// Fans lifecycle events out to the listeners cleaning up per-task state.
+/// Listeners run synchronously on the notifying call, outside the lock, so
+/// they may subscribe or unsubscribe.
+#[derive(Default)]
+pub struct TaskLifecycle {
+    listeners: Mutex<Vec<LifecycleListener>>,
+}
+
+impl TaskLifecycle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, listener: LifecycleListener) {
+        self.listeners.lock().unwrap().push(listener);
+    }
+
+    /// Remove a previously subscribed listener by Arc identity.
+    pub fn unsubscribe(&self, listener: &LifecycleListener) {
+        self.listeners
+            .lock()
+            .unwrap()
+            .retain(|l| !Arc::ptr_eq(l, listener));
+    }
+
+    pub fn listener_count(&self) -> usize {
+        self.listeners.lock().unwrap().len()
+    }
+
+    pub fn notify(&self, task_id: &str, event: &TaskLifecycleEvent) {
+        let listeners = self.listeners.lock().unwrap().clone();
+        for listener in &listeners {
+            listener(task_id, event);
+        }
+    }
+}
+
+// ─── Unit Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_reaches_subscribers_until_they_unsubscribe() {
+        let lifecycle = TaskLifecycle::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let listener: LifecycleListener = Arc::new(move |task_id, event| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((task_id.to_string(), event.clone()));
+        });
+        lifecycle.subscribe(Arc::clone(&listener));
+
+        lifecycle.notify("t1", &TaskLifecycleEvent::Terminal(TaskStatus::Completed));
+        lifecycle.notify("t1", &TaskLifecycleEvent::Deleted);
+        lifecycle.unsubscribe(&listener);
+        lifecycle.notify("t2", &TaskLifecycleEvent::Expired);
+
+        assert_eq!(lifecycle.listener_count(), 0);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (
+                    "t1".to_string(),
+                    TaskLifecycleEvent::Terminal(TaskStatus::Completed)
+                ),
+                ("t1".to_string(), TaskLifecycleEvent::Deleted),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_deletion_and_expiry_are_removals() {
+        assert!(!TaskLifecycleEvent::Terminal(TaskStatus::Failed).is_removal());
+        assert!(TaskLifecycleEvent::Deleted.is_removal());
+        assert!(TaskLifecycleEvent::Expired.is_removal());
+    }
+}
@@ -1,27 +1,144 @@
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
+use crate::background::BackgroundTasks;
+use crate::channels::channel_matches;
+use crate::event_stream::is_intermediate_status;
+use crate::filter::apply_event_query;
 use crate::types::{
-    BroadcastProvider, EventQueryOptions, ShortTermStore, Task, TaskEvent, TaskFilter, TaskStatus,
-    TaskArchiveImportOptions, TaskArchiveRestoreData, Worker, WorkerAssignment, WorkerFilter,
+    task_order, BroadcastProvider, ErrorContext, EventQueryOptions, EventTypeCounts, FilteredIndexClaim, FilteredIndexMark, NewTaskGroupOutcome, NewTaskOutcome, OutcomeQuery, RetrySchedule, SeriesSummary, ShortTermStore, Task, TaskCursor, TaskEvent, TaskFilter, TaskOutcome, TaskPage, TaskStatus,
+    TaskArchiveImportOptions, TaskArchiveRestoreData, TaskcastHooks, Worker, WorkerAssignment, WorkerFilter,
 };
 
 // ─── MemoryBroadcastProvider ────────────────────────────────────────────────
 
 type Handler = Arc<dyn Fn(TaskEvent) + Send + Sync>;
+type Listeners = Arc<RwLock<HashMap<String, Vec<Handler>>>>;
+
+/// Handlers that take longer than this are reported through
+/// [`TaskcastHooks::on_slow_broadcast_handler`] unless the provider is
+/// configured otherwise.
+pub const DEFAULT_SLOW_HANDLER_THRESHOLD: Duration = Duration::from_millis(50);
+
+/// A subscriber handler panicked while an event was delivered to it.
+/// Reported through [`TaskcastHooks::on_unhandled_error`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Broadcast handler on channel {channel} panicked: {message}")]
+pub struct BroadcastHandlerPanic {
+    pub channel: String,
+    pub message: String,
+}
 
+/// In-process broadcast.
+///
+/// Handlers run inline on the publisher's task, one after another in
+/// subscription order, so each subscriber sees events in publish order and
+/// `publish` returns once every handler has run. A handler that panics is
+/// reported through [`TaskcastHooks::on_unhandled_error`] as a
+/// [`BroadcastHandlerPanic`] and delivery continues with the next handler;
+/// the panic never reaches the publisher. Each handler is timed, and one
+/// that runs longer than the slow-handler threshold is reported through
+/// [`TaskcastHooks::on_slow_broadcast_handler`]. Handlers should hand
+/// anything slow off to their own task, since the publisher waits for them.
 pub struct MemoryBroadcastProvider {
-    listeners: Arc<RwLock<HashMap<String, Vec<Handler>>>>,
+    listeners: Listeners,
+    /// Subscriptions made with `subscribe_without_status`, kept apart so an
+    /// intermediate status event skips them without a look.
+    status_excluding_listeners: Listeners,
+    /// Pattern subscriptions, keyed by glob pattern.
+    pattern_listeners: Listeners,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    slow_handler_threshold: Duration,
 }
 
 impl MemoryBroadcastProvider {
     pub fn new() -> Self {
+        Self::with_hooks(None)
+    }
+
+    /// Like [`new`](Self::new), reporting panicking and slow handlers to
+    /// `hooks`.
+    pub fn with_hooks(hooks: Option<Arc<dyn TaskcastHooks>>) -> Self {
         Self {
             listeners: Arc::new(RwLock::new(HashMap::new())),
+            status_excluding_listeners: Arc::new(RwLock::new(HashMap::new())),
+            pattern_listeners: Arc::new(RwLock::new(HashMap::new())),
+            hooks,
+            slow_handler_threshold: DEFAULT_SLOW_HANDLER_THRESHOLD,
+        }
+    }
+
+    /// Reports handlers that run longer than `threshold` instead of
+    /// [`DEFAULT_SLOW_HANDLER_THRESHOLD`].
+    pub fn with_slow_handler_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_handler_threshold = threshold;
+        self
+    }
+
+    /// Runs `handler`, containing a panic and reporting it or a slow run.
+    fn deliver(&self, channel: &str, handler: &Handler, event: &TaskEvent) {
+        let started = Instant::now();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            handler(event.clone())
+        }));
+        let elapsed = started.elapsed();
+        let Some(ref hooks) = self.hooks else {
+            return;
+        };
+        if let Err(payload) = result {
+            let err = BroadcastHandlerPanic {
+                channel: channel.to_string(),
+                message: panic_message(payload.as_ref()),
+            };
+            let context = ErrorContext {
+                operation: "broadcast.handler".to_string(),
+                task_id: Some(event.task_id.clone()),
+            };
+            hooks.on_unhandled_error(&err, &context);
         }
+        if elapsed > self.slow_handler_threshold {
+            hooks.on_slow_broadcast_handler(channel, event, elapsed);
+        }
+    }
+
+    /// Number of handlers currently subscribed to `channel`.
+    pub fn listener_count(&self, channel: &str) -> usize {
+        let count =
+            |listeners: &Listeners| listeners.read().unwrap().get(channel).map_or(0, Vec::len);
+        count(&self.listeners) + count(&self.status_excluding_listeners)
+    }
+
+    /// Number of the handlers subscribed to `channel` that exclude status
+    /// events.
+    pub fn status_excluding_count(&self, channel: &str) -> usize {
+        self.status_excluding_listeners
+            .read()
+            .unwrap()
+            .get(channel)
+            .map_or(0, Vec::len)
+    }
+
+    /// Number of channels with at least one handler.
+    pub fn channel_count(&self) -> usize {
+        let listeners = self.listeners.read().unwrap();
+        let excluding = self.status_excluding_listeners.read().unwrap();
+        listeners.len()
+            + excluding
+                .keys()
+                .filter(|channel| !listeners.contains_key(*channel))
+                .count()
+    }
+
+    /// Number of patterns with at least one handler.
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_listeners.read().unwrap().len()
     }
 }
 
@@ -31,6 +148,50 @@ impl Default for MemoryBroadcastProvider {
     }
 }
 
+/// The message a panic was raised with, for the two payload types `panic!`
+/// produces.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+/// Adds `handler` under `key`, returning the closure that removes it again.
+fn add_listener(
+    listeners: &Listeners,
+    key: &str,
+    handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+) -> Box<dyn Fn() + Send + Sync> {
+    let handler: Handler = Arc::from(handler);
+    {
+        let mut listeners = listeners.write().unwrap();
+        listeners
+            .entry(key.to_string())
+            .or_default()
+            .push(Arc::clone(&handler));
+    }
+
+    let listeners = Arc::clone(listeners);
+    let key = key.to_string();
+    // Store the pointer address as usize for Send + Sync compatibility.
+    // This is only used for identity comparison, never dereferenced.
+    let handler_addr = Arc::as_ptr(&handler) as *const () as usize;
+
+    Box::new(move || {
+        let mut listeners = listeners.write().unwrap();
+        if let Some(handlers) = listeners.get_mut(&key) {
+            handlers.retain(|h| (Arc::as_ptr(h) as *const () as usize) != handler_addr);
+            if handlers.is_empty() {
+                listeners.remove(&key);
+            }
+        }
+    })
+}
+
 #[async_trait]
 impl BroadcastProvider for MemoryBroadcastProvider {
     async fn publish(
@@ -38,15 +199,26 @@ impl BroadcastProvider for MemoryBroadcastProvider {
         channel: &str,
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let handlers = {
+        // Handlers run on the publisher's task, outside the lock.
+        let mut handlers = {
             let listeners = self.listeners.read().unwrap();
-            listeners.get(channel).cloned()
+            listeners.get(channel).cloned().unwrap_or_default()
         };
-        if let Some(handlers) = handlers {
-            for handler in &handlers {
-                handler(event.clone());
+        if !is_intermediate_status(&event) {
+            let excluding = self.status_excluding_listeners.read().unwrap();
+            handlers.extend(excluding.get(channel).into_iter().flatten().cloned());
+        }
+        {
+            let patterns = self.pattern_listeners.read().unwrap();
+            for (pattern, pattern_handlers) in patterns.iter() {
+                if channel_matches(pattern, channel) {
+                    handlers.extend(pattern_handlers.iter().cloned());
+                }
             }
         }
+        for handler in &handlers {
+            self.deliver(channel, handler, &event);
+        }
         Ok(())
     }
 
@@ -63,27 +235,23 @@ impl BroadcastProvider for MemoryBroadcastProvider {
         channel: &str,
         handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
     ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
-        let handler: Handler = Arc::from(handler);
-        {
-            let mut listeners = self.listeners.write().unwrap();
-            listeners
-                .entry(channel.to_string())
-                .or_default()
-                .push(Arc::clone(&handler));
-        }
+        Ok(add_listener(&self.listeners, channel, handler))
+    }
 
-        let listeners = Arc::clone(&self.listeners);
-        let channel = channel.to_string();
-        // Store the pointer address as usize for Send + Sync compatibility.
-        // This is only used for identity comparison, never dereferenced.
-        let handler_addr = Arc::as_ptr(&handler) as *const () as usize;
+    async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(add_listener(&self.pattern_listeners, pattern, handler))
+    }
 
-        Ok(Box::new(move || {
-            let mut listeners = listeners.write().unwrap();
-            if let Some(handlers) = listeners.get_mut(&channel) {
-                handlers.retain(|h| (Arc::as_ptr(h) as *const () as usize) != handler_addr);
-            }
-        }))
+    async fn subscribe_without_status(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        add_listener(&self.status_excluding_listeners, channel, handler)
     }
 }
 
@@ -92,10 +260,26 @@ impl BroadcastProvider for MemoryBroadcastProvider {
 pub struct MemoryShortTermStore {
     tasks: RwLock<HashMap<String, Task>>,
     events: RwLock<HashMap<String, Vec<TaskEvent>>>,
+    /// Running type and level counts of each task's events.
+    event_type_counts: RwLock<HashMap<String, EventTypeCounts>>,
     series_latest: RwLock<HashMap<String, TaskEvent>>,
     index_counters: RwLock<HashMap<String, Arc<AtomicU64>>>,
     workers: RwLock<HashMap<String, Worker>>,
     assignments: RwLock<Vec<WorkerAssignment>>,
+    /// Pending retries by task id, with the time their current claim expires.
+    retries: RwLock<HashMap<String, (RetrySchedule, Option<f64>)>>,
+    /// Activation times of scheduled tasks by task id, with the time their
+    /// current claim expires.
+    activations: RwLock<HashMap<String, (f64, Option<f64>)>>,
+    /// Last delivery time by (task id, webhook index, event fingerprint).
+    webhook_deliveries: RwLock<HashMap<(String, usize, String), f64>>,
+    /// Persisted filtered numbering by (task id, consumer).
+    filtered_indices: RwLock<HashMap<(String, String), FilteredIndexMark>>,
+    /// Claimed deadline warnings by (task id, warning).
+    deadline_warnings: RwLock<HashSet<(String, String)>>,
+    /// The outcomes index, in recording order.
+    outcomes: RwLock<Vec<TaskOutcome>>,
+    persistence: Option<Persistence>,
 }
 
 impl MemoryShortTermStore {
@@ -103,20 +287,324 @@ impl MemoryShortTermStore {
         Self {
             tasks: RwLock::new(HashMap::new()),
             events: RwLock::new(HashMap::new()),
+            event_type_counts: RwLock::new(HashMap::new()),
             series_latest: RwLock::new(HashMap::new()),
             index_counters: RwLock::new(HashMap::new()),
             workers: RwLock::new(HashMap::new()),
             assignments: RwLock::new(Vec::new()),
+            retries: RwLock::new(HashMap::new()),
+            activations: RwLock::new(HashMap::new()),
+            webhook_deliveries: RwLock::new(HashMap::new()),
+            filtered_indices: RwLock::new(HashMap::new()),
+            deadline_warnings: RwLock::new(HashSet::new()),
+            outcomes: RwLock::new(Vec::new()),
+            persistence: None,
+        }
+    }
+
+    /// A store that snapshots its tasks, events, series state and index
+    /// counters to `path` every `flush_interval` once
+    /// [started](Self::start_flushing), starting from the snapshot already
+    /// at `path` if there is one.
+    ///
+    /// This is a development convenience, not durability: anything written
+    /// since the last flush is lost if the process dies, so
+    /// [`close`](Self::close) the store on graceful shutdown. Workers,
+    /// assignments, pending retries and webhook suppression windows are not
+    /// persisted; activations of scheduled tasks are rebuilt from the
+    /// restored tasks. A snapshot that cannot be read, or was written by an
+    /// incompatible version, is ignored with a warning.
+    pub fn with_persistence(path: impl Into<PathBuf>, flush_interval: Duration) -> Arc<Self> {
+        let path = path.into();
+        let mut store = Self::new();
+        match load_snapshot(&path) {
+            Ok(Some(snapshot)) => store.restore(snapshot),
+            Ok(None) => {}
+            Err(reason) => eprintln!(
+                "[taskcast] Ignoring memory snapshot at {}: {reason}",
+                path.display()
+            ),
+        }
+        store.persistence = Some(Persistence {
+            path,
+            flush_interval,
+            writing: Mutex::new(()),
+            started: AtomicBool::new(false),
+            closing: Arc::new(tokio::sync::Notify::new()),
+        });
+        Arc::new(store)
+    }
+
+    /// Starts the snapshot loop on `background` as `memory.snapshot`. Does
+    /// nothing for a store without persistence, or if already started. Must
+    /// be called within a Tokio runtime.
+    pub fn start_flushing(self: &Arc<Self>, background: &BackgroundTasks) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        if persistence.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let flush_interval = persistence.flush_interval;
+        let closing = Arc::clone(&persistence.closing);
+        let weak = Arc::downgrade(self);
+        background.spawn("memory.snapshot", None, async move {
+            let mut ticker = tokio::time::interval(flush_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately.
+            ticker.tick().await;
+            loop {
+                let closed = tokio::select! {
+                    _ = ticker.tick() => false,
+                    _ = closing.notified() => true,
+                };
+                let Some(store) = weak.upgrade() else {
+                    return;
+                };
+                let flushed = tokio::task::spawn_blocking(move || store.flush()).await;
+                if let Ok(Err(err)) = flushed {
+                    eprintln!("[taskcast] Memory snapshot failed: {err}");
+                }
+                if closed {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Stops the snapshot loop after one final flush, so that draining the
+    /// background tasks waits for it.
+    pub fn close(&self) {
+        if let Some(persistence) = &self.persistence {
+            persistence.closing.notify_one();
+        }
+    }
+
+    /// Writes a snapshot now. Does nothing for a store without persistence.
+    ///
+    /// The store is copied under its read locks and written outside them,
+    /// to a temporary file that then replaces the snapshot.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let Some(persistence) = &self.persistence else {
+            return Ok(());
+        };
+        let bytes = serde_json::to_vec(&self.snapshot())?;
+
+        let _writing = persistence.writing.lock().unwrap();
+        let mut tmp = persistence.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, &persistence.path)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            tasks: self.tasks.read().unwrap().clone(),
+            events: self.events.read().unwrap().clone(),
+            series_latest: self.series_latest.read().unwrap().clone(),
+            index_counters: self
+                .index_counters
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(task_id, counter)| (task_id.clone(), counter.load(Ordering::SeqCst)))
+                .collect(),
+            filtered_indices: self.filtered_indices.read().unwrap().iter().fold(
+                HashMap::new(),
+                |mut by_task: HashMap<String, HashMap<String, FilteredIndexMark>>,
+                 ((task_id, consumer), mark)| {
+                    by_task
+                        .entry(task_id.clone())
+                        .or_default()
+                        .insert(consumer.clone(), mark.clone());
+                    by_task
+                },
+            ),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        // The maps are copied one at a time, so a counter may lag behind
+        // events appended while the snapshot was taken.
+        let mut counters = snapshot.index_counters;
+        for (task_id, events) in &snapshot.events {
+            if let Some(last) = events.last() {
+                let counter = counters.entry(task_id.clone()).or_default();
+                *counter = (*counter).max(last.index + 1);
+            }
+        }
+        *self.activations.get_mut().unwrap() = snapshot
+            .tasks
+            .values()
+            .filter(|task| task.status == TaskStatus::Scheduled)
+            .filter_map(|task| Some((task.id.clone(), (task.scheduled_for?, None))))
+            .collect();
+        *self.tasks.get_mut().unwrap() = snapshot.tasks;
+        *self.event_type_counts.get_mut().unwrap() = snapshot
+            .events
+            .iter()
+            .map(|(task_id, events)| (task_id.clone(), EventTypeCounts::from_events(events)))
+            .collect();
+        *self.events.get_mut().unwrap() = snapshot.events;
+        *self.series_latest.get_mut().unwrap() = snapshot.series_latest;
+        *self.filtered_indices.get_mut().unwrap() = snapshot
+            .filtered_indices
+            .into_iter()
+            .flat_map(|(task_id, marks)| {
+                marks
+                    .into_iter()
+                    .map(move |(consumer, mark)| ((task_id.clone(), consumer), mark))
+            })
+            .collect();
+        *self.index_counters.get_mut().unwrap() = counters
+            .into_iter()
+            .map(|(task_id, next)| (task_id, Arc::new(AtomicU64::new(next))))
+            .collect();
+    }
+
+    /// Entry counts of the per-task maps, for checking that deleted tasks
+    /// leave nothing behind.
+    pub fn sizes(&self) -> MemoryStoreSizes {
+        MemoryStoreSizes {
+            tasks: self.tasks.read().unwrap().len(),
+            events: self.events.read().unwrap().len(),
+            event_type_counts: self.event_type_counts.read().unwrap().len(),
+            series_latest: self.series_latest.read().unwrap().len(),
+            index_counters: self.index_counters.read().unwrap().len(),
+            retries: self.retries.read().unwrap().len(),
+            activations: self.activations.read().unwrap().len(),
+            webhook_deliveries: self.webhook_deliveries.read().unwrap().len(),
+            filtered_indices: self.filtered_indices.read().unwrap().len(),
+            deadline_warnings: self.deadline_warnings.read().unwrap().len(),
         }
     }
 }
 
+/// Entry counts of a [`MemoryShortTermStore`]'s per-task maps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStoreSizes {
+    pub tasks: usize,
+    pub events: usize,
+    pub event_type_counts: usize,
+    pub series_latest: usize,
+    pub index_counters: usize,
+    pub retries: usize,
+    pub activations: usize,
+    pub webhook_deliveries: usize,
+    pub filtered_indices: usize,
+    pub deadline_warnings: usize,
+}
+
 impl Default for MemoryShortTermStore {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Snapshot format version. Snapshots with any other version are ignored.
+const SNAPSHOT_VERSION: u32 = 1;
+
+struct Persistence {
+    path: PathBuf,
+    flush_interval: Duration,
+    /// Keeps concurrent flushes off the same temporary file.
+    writing: Mutex<()>,
+    started: AtomicBool,
+    /// Wakes the snapshot loop for its final flush.
+    closing: Arc<tokio::sync::Notify>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snapshot {
+    version: u32,
+    tasks: HashMap<String, Task>,
+    events: HashMap<String, Vec<TaskEvent>>,
+    series_latest: HashMap<String, TaskEvent>,
+    index_counters: HashMap<String, u64>,
+    /// Persisted filtered numbering by task id, then consumer. Missing from
+    /// snapshots written before it was kept.
+    #[serde(default)]
+    filtered_indices: HashMap<String, HashMap<String, FilteredIndexMark>>,
+}
+
+/// Reads the snapshot at `path`. `Ok(None)` means there is none yet; an
+/// error describes why an existing one was unusable.
+fn load_snapshot(path: &Path) -> Result<Option<Snapshot>, String> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let header: SnapshotHeader =
+        serde_json::from_slice(&bytes).map_err(|err| format!("unreadable snapshot ({err})"))?;
+    if header.version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "snapshot version {} is not supported (expected {SNAPSHOT_VERSION})",
+            header.version
+        ));
+    }
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|err| format!("unreadable snapshot ({err})"))
+}
+
+/// Whether `t` passes every condition of `filter` but its `limit`.
+fn task_matches(t: &Task, filter: &TaskFilter) -> bool {
+    if let Some(ref statuses) = filter.status {
+        if !statuses.contains(&t.status) {
+            return false;
+        }
+    }
+    if let Some(ref types) = filter.types {
+        if let Some(ref task_type) = t.r#type {
+            if !types.iter().any(|ty| ty == task_type) {
+                return false;
+            }
+        } else {
+            return false;
+        }
+    }
+    if let Some(ref modes) = filter.assign_mode {
+        if let Some(ref am) = t.assign_mode {
+            if !modes.contains(am) {
+                return false;
+            }
+        } else {
+            return false;
+        }
+    }
+    if let Some(ref tag_matcher) = filter.tags {
+        if !crate::worker_matching::matches_tag(t.tags.as_deref(), tag_matcher) {
+            return false;
+        }
+    }
+    if let Some(ref exclude) = filter.exclude_task_ids {
+        if exclude.contains(&t.id) {
+            return false;
+        }
+    }
+    if let Some(created_before) = filter.created_before {
+        if t.created_at >= created_before {
+            return false;
+        }
+    }
+    if let Some(ref origins) = filter.origin {
+        if !origins.contains(&t.origin_kind()) {
+            return false;
+        }
+    }
+    true
+}
+
 #[async_trait]
 impl ShortTermStore for MemoryShortTermStore {
     async fn save_task(
@@ -128,6 +616,52 @@ impl ShortTermStore for MemoryShortTermStore {
         Ok(())
     }
 
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tasks = self.tasks.write().unwrap();
+        if tasks
+            .get(&task.id)
+            .is_some_and(|existing| existing.version != expected_version)
+        {
+            return Ok(false);
+        }
+        tasks.insert(task.id.clone(), task);
+        Ok(true)
+    }
+
+    async fn save_new_task(
+        &self,
+        task: Task,
+    ) -> Result<NewTaskOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tasks = self.tasks.write().unwrap();
+        if let Some(existing) = tasks.get(&task.id) {
+            return Ok(NewTaskOutcome::AlreadyExists(Some(Box::new(existing.clone()))));
+        }
+        tasks.insert(task.id.clone(), task);
+        Ok(NewTaskOutcome::Created)
+    }
+
+    /// Checks and writes the whole group under one lock.
+    async fn save_new_task_group(
+        &self,
+        group: Vec<Task>,
+    ) -> Result<NewTaskGroupOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tasks = self.tasks.write().unwrap();
+        if let Some(existing) = group.iter().find_map(|task| tasks.get(&task.id)) {
+            return Ok(NewTaskGroupOutcome::AlreadyExists {
+                task_id: existing.id.clone(),
+                existing: Some(Box::new(existing.clone())),
+            });
+        }
+        for task in group {
+            tasks.insert(task.id.clone(), task);
+        }
+        Ok(NewTaskGroupOutcome::Created)
+    }
+
     async fn get_task(
         &self,
         task_id: &str,
@@ -142,6 +676,12 @@ impl ShortTermStore for MemoryShortTermStore {
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mut events = self.events.write().unwrap();
+        self.event_type_counts
+            .write()
+            .unwrap()
+            .entry(task_id.to_string())
+            .or_default()
+            .add(&event);
         events
             .entry(task_id.to_string())
             .or_default()
@@ -160,32 +700,7 @@ impl ShortTermStore for MemoryShortTermStore {
             None => return Ok(vec![]),
         };
 
-        let mut result = all;
-
-        if let Some(ref opts) = opts {
-            if let Some(ref since) = opts.since {
-                if let Some(ref id) = since.id {
-                    // since.id takes priority
-                    let idx = result.iter().position(|e| &e.id == id);
-                    result = match idx {
-                        Some(i) => result[i + 1..].to_vec(),
-                        None => result,
-                    };
-                } else if let Some(index) = since.index {
-                    // since.index is second priority
-                    result.retain(|e| e.index > index);
-                } else if let Some(timestamp) = since.timestamp {
-                    // since.timestamp is third priority
-                    result.retain(|e| e.timestamp > timestamp);
-                }
-            }
-
-            if let Some(limit) = opts.limit {
-                result.truncate(limit as usize);
-            }
-        }
-
-        Ok(result)
+        Ok(apply_event_query(all, opts.as_ref()))
     }
 
     async fn set_ttl(
@@ -224,7 +739,7 @@ impl ShortTermStore for MemoryShortTermStore {
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         let key = format!("{task_id}:{series_id}");
 
         let prev = {
@@ -232,20 +747,27 @@ impl ShortTermStore for MemoryShortTermStore {
             series.get(&key).cloned()
         };
 
-        if let Some(prev) = prev {
+        let replaced = if let Some(prev) = prev {
             let mut events = self.events.write().unwrap();
             if let Some(task_events) = events.get_mut(task_id) {
                 if let Some(idx) = task_events.iter().rposition(|e| e.id == prev.id) {
-                    task_events[idx] = event.clone();
+                    let previous = std::mem::replace(&mut task_events[idx], event.clone());
+                    let mut counts = self.event_type_counts.write().unwrap();
+                    if let Some(counts) = counts.get_mut(task_id) {
+                        counts.remove(&previous);
+                        counts.add(&event);
+                    }
                 }
             }
+            Some(prev.id)
         } else {
             self.append_event(task_id, event.clone()).await?;
-        }
+            None
+        };
 
         let mut series = self.series_latest.write().unwrap();
         series.insert(key, event);
-        Ok(())
+        Ok(replaced)
     }
 
     async fn accumulate_series(
@@ -292,6 +814,25 @@ impl ShortTermStore for MemoryShortTermStore {
         Ok(accumulated)
     }
 
+    async fn list_series(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<SeriesSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let prefix = format!("{task_id}:");
+        let mut series: Vec<SeriesSummary> = self
+            .series_latest
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|(key, latest)| {
+                let series_id = key.strip_prefix(&prefix)?;
+                Some(SeriesSummary::of(series_id, latest))
+            })
+            .collect();
+        series.sort_by(|a, b| a.series_id.cmp(&b.series_id));
+        Ok(series)
+    }
+
     async fn next_index(
         &self,
         task_id: &str,
@@ -306,6 +847,24 @@ impl ShortTermStore for MemoryShortTermStore {
         Ok(counter.fetch_add(1, Ordering::SeqCst))
     }
 
+    async fn event_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let counters = self.index_counters.read().unwrap();
+        Ok(counters
+            .get(task_id)
+            .map_or(0, |counter| counter.load(Ordering::SeqCst)))
+    }
+
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let counts = self.event_type_counts.read().unwrap();
+        Ok(counts.get(task_id).cloned().unwrap_or_default())
+    }
+
     fn supports_task_archive_restore(&self) -> bool {
         true
     }
@@ -352,6 +911,10 @@ impl ShortTermStore for MemoryShortTermStore {
         }
 
         self.tasks.write().unwrap().insert(task_id.clone(), data.task);
+        self.event_type_counts
+            .write()
+            .unwrap()
+            .insert(task_id.clone(), EventTypeCounts::from_events(&data.events));
         self.events.write().unwrap().insert(task_id.clone(), data.events);
         self.index_counters.write().unwrap().insert(
             task_id,
@@ -368,47 +931,38 @@ impl ShortTermStore for MemoryShortTermStore {
         let tasks = self.tasks.read().unwrap();
         Ok(tasks
             .values()
-            .filter(|t| {
-                if let Some(ref statuses) = filter.status {
-                    if !statuses.contains(&t.status) {
-                        return false;
-                    }
-                }
-                if let Some(ref types) = filter.types {
-                    if let Some(ref task_type) = t.r#type {
-                        if !types.iter().any(|ty| ty == task_type) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-                if let Some(ref modes) = filter.assign_mode {
-                    if let Some(ref am) = t.assign_mode {
-                        if !modes.contains(am) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                }
-                if let Some(ref tag_matcher) = filter.tags {
-                    if !crate::worker_matching::matches_tag(t.tags.as_deref(), tag_matcher) {
-                        return false;
-                    }
-                }
-                if let Some(ref exclude) = filter.exclude_task_ids {
-                    if exclude.contains(&t.id) {
-                        return false;
-                    }
-                }
-                true
-            })
+            .filter(|t| task_matches(t, &filter))
             .take(filter.limit.unwrap_or(u64::MAX) as usize)
             .cloned()
             .collect())
     }
 
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        let tasks = self.tasks.read().unwrap();
+        let mut matching: Vec<&Task> = tasks
+            .values()
+            .filter(|t| after.as_ref().is_none_or(|cursor| cursor.precedes(t)))
+            .filter(|t| task_matches(t, &filter))
+            .collect();
+        // Only the page and the task after it need to be in order.
+        if matching.len() > limit + 1 {
+            matching.select_nth_unstable_by(limit, |a, b| {
+                task_order(a.created_at, &a.id, b.created_at, &b.id)
+            });
+            matching.truncate(limit + 1);
+        }
+        matching.sort_by(|a, b| task_order(a.created_at, &a.id, b.created_at, &b.id));
+        Ok(TaskPage::from_sorted(
+            matching.into_iter().cloned().collect(),
+            limit,
+        ))
+    }
+
     async fn save_worker(
         &self,
         worker: Worker,
@@ -498,6 +1052,7 @@ impl ShortTermStore for MemoryShortTermStore {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as f64;
+        task.version += 1;
 
         Ok(true)
     }
@@ -559,6 +1114,277 @@ impl ShortTermStore for MemoryShortTermStore {
             .cloned()
             .collect())
     }
+
+    async fn save_retry_schedule(
+        &self,
+        schedule: RetrySchedule,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut retries = self.retries.write().unwrap();
+        retries.insert(schedule.task_id.clone(), (schedule, None));
+        Ok(())
+    }
+
+    async fn list_due_retries(
+        &self,
+        now: f64,
+    ) -> Result<Vec<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let retries = self.retries.read().unwrap();
+        let mut due: Vec<RetrySchedule> = retries
+            .values()
+            .filter(|(schedule, _)| schedule.due_at <= now)
+            .map(|(schedule, _)| schedule.clone())
+            .collect();
+        due.sort_by(|a, b| a.due_at.total_cmp(&b.due_at));
+        Ok(due)
+    }
+
+    async fn get_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let retries = self.retries.read().unwrap();
+        Ok(retries.get(task_id).map(|(schedule, _)| schedule.clone()))
+    }
+
+    async fn claim_retry(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut retries = self.retries.write().unwrap();
+        let Some((_, claimed_until)) = retries.get_mut(task_id) else {
+            return Ok(false);
+        };
+        if claimed_until.is_some_and(|until| until > now) {
+            return Ok(false);
+        }
+        *claimed_until = Some(now + lease_ms as f64);
+        Ok(true)
+    }
+
+    async fn delete_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.retries.write().unwrap().remove(task_id);
+        Ok(())
+    }
+
+    async fn save_activation(
+        &self,
+        task_id: &str,
+        at: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut activations = self.activations.write().unwrap();
+        activations.insert(task_id.to_string(), (at, None));
+        Ok(())
+    }
+
+    async fn list_due_activations(
+        &self,
+        now: f64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let activations = self.activations.read().unwrap();
+        let mut due: Vec<(&String, f64)> = activations
+            .iter()
+            .filter(|(_, (at, _))| *at <= now)
+            .map(|(task_id, (at, _))| (task_id, *at))
+            .collect();
+        due.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Ok(due.into_iter().map(|(task_id, _)| task_id.clone()).collect())
+    }
+
+    async fn claim_activation(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut activations = self.activations.write().unwrap();
+        let Some((_, claimed_until)) = activations.get_mut(task_id) else {
+            return Ok(false);
+        };
+        if claimed_until.is_some_and(|until| until > now) {
+            return Ok(false);
+        }
+        *claimed_until = Some(now + lease_ms as f64);
+        Ok(true)
+    }
+
+    async fn delete_activation(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.activations.write().unwrap().remove(task_id);
+        Ok(())
+    }
+
+    async fn claim_filtered_index(
+        &self,
+        task_id: &str,
+        consumer: &str,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> Result<Option<FilteredIndexClaim>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut marks = self.filtered_indices.write().unwrap();
+        let key = (task_id.to_string(), consumer.to_string());
+        let (mark, claim) = FilteredIndexMark::claim(marks.remove(&key), filter_hash, raw_index);
+        marks.insert(key, mark);
+        Ok(Some(claim))
+    }
+
+    async fn get_filtered_index_mark(
+        &self,
+        task_id: &str,
+        consumer: &str,
+    ) -> Result<Option<FilteredIndexMark>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .filtered_indices
+            .read()
+            .unwrap()
+            .get(&(task_id.to_string(), consumer.to_string()))
+            .cloned())
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        fingerprint: &str,
+        now: f64,
+        window_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut deliveries = self.webhook_deliveries.write().unwrap();
+        let key = (task_id.to_string(), webhook, fingerprint.to_string());
+        if deliveries
+            .get(&key)
+            .is_some_and(|last| now - last < window_ms as f64)
+        {
+            return Ok(false);
+        }
+        deliveries.insert(key, now);
+        Ok(true)
+    }
+
+    async fn claim_deadline_warning(
+        &self,
+        task_id: &str,
+        warning: &str,
+        _ttl_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .deadline_warnings
+            .write()
+            .unwrap()
+            .insert((task_id.to_string(), warning.to_string())))
+    }
+
+    async fn record_outcome(
+        &self,
+        outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.outcomes.write().unwrap().push(outcome);
+        Ok(())
+    }
+
+    async fn list_outcomes(
+        &self,
+        query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut matched: Vec<TaskOutcome> = self
+            .outcomes
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|outcome| query.matches(outcome))
+            .cloned()
+            .collect();
+        matched.sort_by(|a, b| {
+            a.completed_at
+                .total_cmp(&b.completed_at)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        matched.truncate(query.limit);
+        Ok(matched)
+    }
+
+    async fn trim_outcomes(
+        &self,
+        before: f64,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut outcomes = self.outcomes.write().unwrap();
+        let kept = outcomes.len();
+        outcomes.retain(|outcome| outcome.completed_at >= before);
+        Ok((kept - outcomes.len()) as u64)
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.write().unwrap().remove(task_id);
+        self.events.write().unwrap().remove(task_id);
+        self.event_type_counts.write().unwrap().remove(task_id);
+        self.index_counters.write().unwrap().remove(task_id);
+        let series_prefix = format!("{task_id}:");
+        self.series_latest
+            .write()
+            .unwrap()
+            .retain(|key, _| !key.starts_with(&series_prefix));
+        self.assignments
+            .write()
+            .unwrap()
+            .retain(|assignment| assignment.task_id != task_id);
+        self.retries.write().unwrap().remove(task_id);
+        self.activations.write().unwrap().remove(task_id);
+        self.webhook_deliveries
+            .write()
+            .unwrap()
+            .retain(|(id, _, _), _| id != task_id);
+        self.filtered_indices
+            .write()
+            .unwrap()
+            .retain(|(id, _), _| id != task_id);
+        self.deadline_warnings
+            .write()
+            .unwrap()
+            .retain(|(id, _)| id != task_id);
+        Ok(())
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut replaced = 0;
+        let mut series_ids = Vec::new();
+        {
+            let mut events = self.events.write().unwrap();
+            let Some(task_events) = events.get_mut(task_id) else {
+                return Ok(0);
+            };
+            let mut counts = self.event_type_counts.write().unwrap();
+            for tombstone in tombstones {
+                let Some(slot) = task_events.iter_mut().find(|e| e.id == tombstone.id) else {
+                    continue;
+                };
+                let previous = std::mem::replace(slot, tombstone.clone());
+                if let Some(counts) = counts.get_mut(task_id) {
+                    counts.remove(&previous);
+                    counts.add(tombstone);
+                }
+                series_ids.extend(previous.series_id);
+                replaced += 1;
+            }
+        }
+        let mut series = self.series_latest.write().unwrap();
+        for series_id in series_ids {
+            series.remove(&format!("{task_id}:{series_id}"));
+        }
+        Ok(replaced)
+    }
 }
 
 // ─── Tests ──────────────────────────────────────────────────────────────────
@@ -571,6 +1397,7 @@ mod tests {
         WorkerAssignmentStatus, WorkerFilter, WorkerMatchRule, WorkerStatus,
     };
     use serde_json::json;
+    use std::collections::BTreeMap;
     use std::sync::atomic::{AtomicU64, Ordering};
 
     fn make_task(id: &str) -> Task {
@@ -597,6 +1424,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         }
     }
 
@@ -613,6 +1450,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }
     }
@@ -653,6 +1492,52 @@ mod tests {
         assert_eq!(retrieved.status, TaskStatus::Completed);
     }
 
+    #[tokio::test]
+    async fn short_term_store_save_task_versioned_compares_versions() {
+        let store = MemoryShortTermStore::new();
+        let mut task = make_task("t1");
+        task.version = 1;
+        // A task not stored yet is written whatever the expected version.
+        assert!(store.save_task_versioned(task.clone(), 0).await.unwrap());
+
+        task.version = 2;
+        assert!(!store.save_task_versioned(task.clone(), 0).await.unwrap());
+        assert_eq!(store.get_task("t1").await.unwrap().unwrap().version, 1);
+        assert!(store.save_task_versioned(task, 1).await.unwrap());
+        assert_eq!(store.get_task("t1").await.unwrap().unwrap().version, 2);
+    }
+
+    #[tokio::test]
+    async fn claim_task_bumps_version() {
+        let store = MemoryShortTermStore::new();
+        let mut task = make_task("t1");
+        task.status = TaskStatus::Pending;
+        task.version = 3;
+        store.save_task(task).await.unwrap();
+        store.save_worker(make_worker("w1")).await.unwrap();
+
+        assert!(store.claim_task("t1", "w1", 1).await.unwrap());
+        assert_eq!(store.get_task("t1").await.unwrap().unwrap().version, 4);
+    }
+
+    #[tokio::test]
+    async fn short_term_store_save_new_task_keeps_first_writer() {
+        let store = MemoryShortTermStore::new();
+        let task1 = make_task("t1");
+        assert_eq!(
+            store.save_new_task(task1.clone()).await.unwrap(),
+            NewTaskOutcome::Created
+        );
+
+        let mut task2 = make_task("t1");
+        task2.status = TaskStatus::Completed;
+        assert_eq!(
+            store.save_new_task(task2).await.unwrap(),
+            NewTaskOutcome::AlreadyExists(Some(Box::new(task1.clone())))
+        );
+        assert_eq!(store.get_task("t1").await.unwrap(), Some(task1));
+    }
+
     // ─── MemoryShortTermStore: append/get events ────────────────────────
 
     #[tokio::test]
@@ -710,6 +1595,9 @@ mod tests {
                 timestamp: None,
             }),
             limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -736,6 +1624,9 @@ mod tests {
                 timestamp: None,
             }),
             limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -766,6 +1657,9 @@ mod tests {
                 timestamp: None,
             }),
             limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -798,6 +1692,9 @@ mod tests {
                 timestamp: Some(1000.0),
             }),
             limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -831,12 +1728,47 @@ mod tests {
                 timestamp: None,
             }),
             limit: None,
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].id, "e3");
     }
 
+    // ─── MemoryShortTermStore: ordering ─────────────────────────────────
+
+    #[tokio::test]
+    async fn short_term_store_get_events_sorts_ties_and_out_of_order_appends() {
+        let store = MemoryShortTermStore::new();
+        for event in [
+            make_event("e4", "t1", 2, 1000.0),
+            make_event("e3", "t1", 1, 3000.0),
+            make_event("e2b", "t1", 1, 2000.0),
+            make_event("e2a", "t1", 1, 2000.0),
+            make_event("e1", "t1", 0, 4000.0),
+        ] {
+            store.append_event("t1", event).await.unwrap();
+        }
+
+        let events = store.get_events("t1", None).await.unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e1", "e2a", "e2b", "e3", "e4"]);
+
+        let opts = EventQueryOptions {
+            since: Some(crate::types::SinceCursor {
+                id: None,
+                index: Some(1),
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        let events = store.get_events("t1", Some(opts)).await.unwrap();
+        let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e4"]);
+    }
+
     // ─── MemoryShortTermStore: limit ────────────────────────────────────
 
     #[tokio::test]
@@ -858,6 +1790,9 @@ mod tests {
         let opts = EventQueryOptions {
             since: None,
             limit: Some(2),
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -892,6 +1827,9 @@ mod tests {
                 timestamp: None,
             }),
             limit: Some(2),
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let events = store.get_events("t1", Some(opts)).await.unwrap();
         assert_eq!(events.len(), 2);
@@ -899,13 +1837,115 @@ mod tests {
         assert_eq!(events[1].id, "e3");
     }
 
-    // ─── MemoryShortTermStore: setTTL no-op ─────────────────────────────
-
+    // ─── MemoryShortTermStore: setTTL no-op ─────────────────────────────
+
+    #[tokio::test]
+    async fn short_term_store_set_ttl_is_noop() {
+        let store = MemoryShortTermStore::new();
+        let result = store.set_ttl("t1", 3600).await;
+        assert!(result.is_ok());
+    }
+
+    // ─── MemoryShortTermStore: webhook suppression ──────────────────────
+
+    #[tokio::test]
+    async fn short_term_store_claim_webhook_delivery_suppresses_within_window() {
+        let store = MemoryShortTermStore::new();
+        let claim = |webhook, now| store.claim_webhook_delivery("t1", webhook, "e:info", now, 100);
+        assert!(claim(0, 0.0).await.unwrap());
+        assert!(!claim(0, 99.0).await.unwrap());
+        assert!(claim(1, 99.0).await.unwrap());
+        assert!(claim(0, 100.0).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn short_term_store_claim_filtered_index_numbers_per_consumer_and_filter() {
+        let store = MemoryShortTermStore::new();
+        let claim = |consumer, hash, raw| store.claim_filtered_index("t1", consumer, hash, raw);
+        let at = |filtered_index, epoch| Some(FilteredIndexClaim { filtered_index, epoch });
+        assert_eq!(claim("a", "h1", 3).await.unwrap(), at(0, 0));
+        assert_eq!(claim("a", "h1", 5).await.unwrap(), at(1, 0));
+        assert_eq!(claim("b", "h1", 5).await.unwrap(), at(0, 0));
+        // A new filter restarts the numbering in the next epoch.
+        assert_eq!(claim("a", "h2", 6).await.unwrap(), at(0, 1));
+        assert_eq!(
+            store.get_filtered_index_mark("t1", "a").await.unwrap(),
+            Some(FilteredIndexMark {
+                filter_hash: "h2".to_string(),
+                epoch: 1,
+                next: 1,
+                through: Some(6),
+            })
+        );
+        assert_eq!(store.get_filtered_index_mark("t2", "a").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn short_term_store_claim_deadline_warning_once_per_task_and_warning() {
+        let store = MemoryShortTermStore::new();
+        assert!(store
+            .claim_deadline_warning("t1", "beforeMs:1000", 1000)
+            .await
+            .unwrap());
+        assert!(!store
+            .claim_deadline_warning("t1", "beforeMs:1000", 1000)
+            .await
+            .unwrap());
+        assert!(store
+            .claim_deadline_warning("t1", "atFraction:0.8", 1000)
+            .await
+            .unwrap());
+        assert!(store
+            .claim_deadline_warning("t2", "beforeMs:1000", 1000)
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
-    async fn short_term_store_set_ttl_is_noop() {
+    async fn short_term_store_delete_task_drops_all_task_state() {
         let store = MemoryShortTermStore::new();
-        let result = store.set_ttl("t1", 3600).await;
-        assert!(result.is_ok());
+        for task_id in ["t1", "t2"] {
+            store.save_task(make_task(task_id)).await.unwrap();
+            let index = store.next_index(task_id).await.unwrap();
+            let event = make_event(&format!("{task_id}-e"), task_id, index, 1000.0);
+            store.append_event(task_id, event.clone()).await.unwrap();
+            store.set_series_latest(task_id, "s", event).await.unwrap();
+            store
+                .claim_webhook_delivery(task_id, 0, "e:info", 0.0, 100)
+                .await
+                .unwrap();
+            store
+                .claim_deadline_warning(task_id, "atFraction:0.8", 1000)
+                .await
+                .unwrap();
+            store
+                .claim_filtered_index(task_id, "webhook:https://hooks.test", "h", index)
+                .await
+                .unwrap();
+        }
+        store.add_assignment(make_assignment("t1", "w1")).await.unwrap();
+
+        store.delete_task("t1").await.unwrap();
+        store.delete_task("missing").await.unwrap();
+
+        assert!(store.get_task("t1").await.unwrap().is_none());
+        assert!(store.get_task_assignment("t1").await.unwrap().is_none());
+        assert_eq!(store.event_count("t1").await.unwrap(), 0);
+        assert_eq!(
+            store.sizes(),
+            MemoryStoreSizes {
+                tasks: 1,
+                events: 1,
+                event_type_counts: 1,
+                series_latest: 1,
+                index_counters: 1,
+                retries: 0,
+                activations: 0,
+                webhook_deliveries: 1,
+                filtered_indices: 1,
+                deadline_warnings: 1,
+            }
+        );
     }
 
     // ─── MemoryShortTermStore: series operations ────────────────────────
@@ -969,10 +2009,11 @@ mod tests {
 
         // Replace with e3
         let replacement = make_event("e3", "t1", 1, 2500.0);
-        store
+        let replaced = store
             .replace_last_series_event("t1", "s1", replacement)
             .await
             .unwrap();
+        assert_eq!(replaced.as_deref(), Some("e2"));
 
         // The events list should have e3 in place of e2
         let events = store.get_events("t1", None).await.unwrap();
@@ -991,10 +2032,11 @@ mod tests {
 
         // No prior series latest, should append
         let event = make_event("e1", "t1", 0, 1000.0);
-        store
+        let replaced = store
             .replace_last_series_event("t1", "s1", event)
             .await
             .unwrap();
+        assert_eq!(replaced, None);
 
         let events = store.get_events("t1", None).await.unwrap();
         assert_eq!(events.len(), 1);
@@ -1040,6 +2082,70 @@ mod tests {
         assert_eq!(events[2].id, "e3");
     }
 
+    // ─── MemoryShortTermStore: event type counts ────────────────────────
+
+    #[tokio::test]
+    async fn short_term_store_event_type_counts_track_appends_and_deletion() {
+        let store = MemoryShortTermStore::new();
+        store
+            .append_event("t1", make_event("e1", "t1", 0, 1000.0))
+            .await
+            .unwrap();
+        store
+            .append_event("t1", make_event("e2", "t1", 1, 2000.0))
+            .await
+            .unwrap();
+        let mut warning = make_event("e3", "t1", 2, 3000.0);
+        warning.r#type = "log".to_string();
+        warning.level = Level::Warn;
+        store.append_event("t1", warning).await.unwrap();
+
+        let counts = store.get_event_type_counts("t1").await.unwrap();
+        assert_eq!(counts.types.get("progress"), Some(&2));
+        assert_eq!(counts.types.get("log"), Some(&1));
+        assert_eq!(counts.levels.get(&Level::Info), Some(&2));
+        assert_eq!(counts.levels.get(&Level::Warn), Some(&1));
+        assert_eq!(counts.total(), 3);
+        assert!(store.get_event_type_counts("t2").await.unwrap().is_empty());
+
+        store.delete_task("t1").await.unwrap();
+        assert!(store.get_event_type_counts("t1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn short_term_store_event_type_counts_follow_series_replacement() {
+        let store = MemoryShortTermStore::new();
+        let first = make_event("e1", "t1", 0, 1000.0);
+        store
+            .replace_last_series_event("t1", "s1", first)
+            .await
+            .unwrap();
+
+        // Same type and level: neutral.
+        store
+            .replace_last_series_event("t1", "s1", make_event("e2", "t1", 1, 2000.0))
+            .await
+            .unwrap();
+        let counts = store.get_event_type_counts("t1").await.unwrap();
+        assert_eq!(counts.types, BTreeMap::from([("progress".to_string(), 1)]));
+
+        // A different type and level moves the count over.
+        let mut status = make_event("e3", "t1", 2, 3000.0);
+        status.r#type = "status.line".to_string();
+        status.level = Level::Debug;
+        store
+            .replace_last_series_event("t1", "s1", status)
+            .await
+            .unwrap();
+        let counts = store.get_event_type_counts("t1").await.unwrap();
+        assert_eq!(counts.types, BTreeMap::from([("status.line".to_string(), 1)]));
+        assert_eq!(counts.levels, BTreeMap::from([(Level::Debug, 1)]));
+        assert_eq!(
+            counts,
+            EventTypeCounts::from_events(&store.get_events("t1", None).await.unwrap())
+        );
+    }
+
     // ─── MemoryBroadcastProvider: publish with no subscribers ────────────
 
     #[tokio::test]
@@ -1108,6 +2214,93 @@ mod tests {
         assert_eq!(received.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn broadcast_last_unsubscribe_drops_the_channel() {
+        let provider = MemoryBroadcastProvider::new();
+        let first = provider.subscribe("channel1", Box::new(|_| {})).await;
+        let second = provider.subscribe("channel1", Box::new(|_| {})).await;
+        assert_eq!(provider.channel_count(), 1);
+
+        first();
+        assert_eq!(provider.channel_count(), 1);
+        second();
+        second();
+        assert_eq!(provider.channel_count(), 0);
+        assert_eq!(provider.listener_count("channel1"), 0);
+    }
+
+    // ─── MemoryBroadcastProvider: status-excluding subscribers ───────────
+
+    fn status_event(id: &str, status: &str) -> TaskEvent {
+        TaskEvent {
+            r#type: "taskcast:status".to_string(),
+            data: json!({ "status": status }),
+            ..make_event(id, "t1", 0, 1000.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcast_without_status_skips_intermediate_status_events() {
+        let provider = MemoryBroadcastProvider::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = Arc::clone(&received);
+        let unsub = provider
+            .subscribe_without_status(
+                "channel1",
+                Box::new(move |event| received_clone.lock().unwrap().push(event.id)),
+            )
+            .await;
+        let _other = provider.subscribe("channel1", Box::new(|_| {})).await;
+        assert_eq!(provider.listener_count("channel1"), 2);
+        assert_eq!(provider.status_excluding_count("channel1"), 1);
+
+        for event in [
+            status_event("running", "running"),
+            make_event("log", "t1", 1, 1001.0),
+            status_event("paused", "paused"),
+            status_event("completed", "completed"),
+        ] {
+            provider.publish("channel1", event).await.unwrap();
+        }
+        assert_eq!(*received.lock().unwrap(), ["log", "completed"]);
+
+        unsub();
+        assert_eq!(provider.status_excluding_count("channel1"), 0);
+        assert_eq!(provider.channel_count(), 1);
+    }
+
+    // ─── MemoryBroadcastProvider: pattern subscriptions ──────────────────
+
+    #[tokio::test]
+    async fn broadcast_pattern_subscriber_receives_matching_channels() {
+        let provider = MemoryBroadcastProvider::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = Arc::clone(&received);
+        let unsub = provider
+            .subscribe_pattern(
+                "task.*",
+                Box::new(move |event| received_clone.lock().unwrap().push(event.id)),
+            )
+            .await
+            .unwrap();
+
+        for (id, channel) in [("e1", "task.t1"), ("e2", "type.log"), ("e3", "task.t2")] {
+            provider
+                .publish(channel, make_event(id, "t1", 0, 1000.0))
+                .await
+                .unwrap();
+        }
+        assert_eq!(*received.lock().unwrap(), ["e1", "e3"]);
+
+        unsub();
+        assert_eq!(provider.pattern_count(), 0);
+        provider
+            .publish("task.t1", make_event("e4", "t1", 1, 2000.0))
+            .await
+            .unwrap();
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
     // ─── MemoryBroadcastProvider: multiple subscribers ───────────────────
 
     #[tokio::test]
@@ -1211,6 +2404,174 @@ mod tests {
         assert_eq!(count2.load(Ordering::SeqCst), 1);
     }
 
+    // ─── MemoryBroadcastProvider: handlers run outside the lock ─────────
+
+    #[tokio::test]
+    async fn broadcast_handler_can_unsubscribe_itself() {
+        let provider = MemoryBroadcastProvider::new();
+        let count = Arc::new(AtomicU64::new(0));
+        type Unsubscribe = Box<dyn Fn() + Send + Sync>;
+        let unsub: Arc<std::sync::Mutex<Option<Unsubscribe>>> =
+            Arc::new(std::sync::Mutex::new(None));
+        let count_clone = Arc::clone(&count);
+        let unsub_clone = Arc::clone(&unsub);
+
+        let handle = provider
+            .subscribe(
+                "channel1",
+                Box::new(move |_event| {
+                    count_clone.fetch_add(1, Ordering::SeqCst);
+                    if let Some(unsub) = unsub_clone.lock().unwrap().take() {
+                        unsub();
+                    }
+                }),
+            )
+            .await;
+        *unsub.lock().unwrap() = Some(handle);
+
+        for i in 0..2 {
+            provider
+                .publish("channel1", make_event(&format!("e{i}"), "t1", i, 1000.0))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    // ─── MemoryBroadcastProvider: panicking and slow handlers ───────────
+
+    #[derive(Default)]
+    struct HandlerHooks {
+        errors: std::sync::Mutex<Vec<(String, ErrorContext)>>,
+        slow: std::sync::Mutex<Vec<(String, String, Duration)>>,
+    }
+
+    impl TaskcastHooks for HandlerHooks {
+        fn on_unhandled_error(
+            &self,
+            err: &(dyn std::error::Error + Send + Sync),
+            context: &ErrorContext,
+        ) {
+            self.errors
+                .lock()
+                .unwrap()
+                .push((err.to_string(), context.clone()));
+        }
+
+        fn on_slow_broadcast_handler(&self, channel: &str, event: &TaskEvent, elapsed: Duration) {
+            self.slow
+                .lock()
+                .unwrap()
+                .push((channel.to_string(), event.id.clone(), elapsed));
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcast_panicking_handler_is_reported_and_others_still_run() {
+        let hooks = Arc::new(HandlerHooks::default());
+        let provider =
+            MemoryBroadcastProvider::with_hooks(Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+        let received = Arc::new(AtomicU64::new(0));
+        let received_clone = Arc::clone(&received);
+
+        let _panicking = provider
+            .subscribe("task:t1", Box::new(|_event| panic!("handler exploded")))
+            .await;
+        let _healthy = provider
+            .subscribe(
+                "task:t1",
+                Box::new(move |_event| {
+                    received_clone.fetch_add(1, Ordering::SeqCst);
+                }),
+            )
+            .await;
+
+        for i in 0..2 {
+            provider
+                .publish("task:t1", make_event(&format!("e{i}"), "t1", i, 1000.0))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(received.load(Ordering::SeqCst), 2);
+        let errors = hooks.errors.lock().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].0,
+            "Broadcast handler on channel task:t1 panicked: handler exploded"
+        );
+        assert_eq!(
+            errors[0].1,
+            ErrorContext {
+                operation: "broadcast.handler".to_string(),
+                task_id: Some("t1".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn broadcast_panicking_handler_without_hooks_does_not_fail_publish() {
+        let provider = MemoryBroadcastProvider::new();
+        let received = Arc::new(AtomicU64::new(0));
+        let received_clone = Arc::clone(&received);
+
+        let _healthy = provider
+            .subscribe(
+                "channel1",
+                Box::new(move |_event| {
+                    received_clone.fetch_add(1, Ordering::SeqCst);
+                }),
+            )
+            .await;
+        let _panicking = provider
+            .subscribe(
+                "channel1",
+                Box::new(|event| panic!("bad event {}", event.id)),
+            )
+            .await;
+
+        let result = provider
+            .publish("channel1", make_event("e1", "t1", 0, 1000.0))
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn broadcast_slow_handler_is_reported() {
+        let hooks = Arc::new(HandlerHooks::default());
+        let provider =
+            MemoryBroadcastProvider::with_hooks(Some(hooks.clone() as Arc<dyn TaskcastHooks>))
+                .with_slow_handler_threshold(Duration::from_millis(10));
+
+        let _fast = provider.subscribe("channel1", Box::new(|_event| {})).await;
+        let _slow = provider
+            .subscribe(
+                "channel1",
+                Box::new(|_event| std::thread::sleep(Duration::from_millis(30))),
+            )
+            .await;
+
+        provider
+            .publish("channel1", make_event("e1", "t1", 0, 1000.0))
+            .await
+            .unwrap();
+
+        let slow = hooks.slow.lock().unwrap();
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].0, "channel1");
+        assert_eq!(slow[0].1, "e1");
+        assert!(slow[0].2 >= Duration::from_millis(30));
+        assert!(hooks.errors.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn broadcast_default_slow_handler_threshold() {
+        let provider = MemoryBroadcastProvider::new();
+        assert_eq!(provider.slow_handler_threshold, Duration::from_millis(50));
+    }
+
     // ─── Default impls ───────────────────────────────────────────────
 
     #[test]
@@ -1738,6 +3099,25 @@ mod tests {
         assert_eq!(tasks[0].id, "t2");
     }
 
+    #[tokio::test]
+    async fn list_tasks_filter_by_created_before() {
+        let store = MemoryShortTermStore::new();
+
+        for (id, created_at) in [("t1", 1000.0), ("t2", 2000.0), ("t3", 3000.0)] {
+            let mut task = make_task(id);
+            task.created_at = created_at;
+            store.save_task(task).await.unwrap();
+        }
+
+        let filter = TaskFilter {
+            created_before: Some(2000.0),
+            ..Default::default()
+        };
+        let tasks = store.list_tasks(filter).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "t1");
+    }
+
     #[tokio::test]
     async fn list_tasks_with_limit() {
         let store = MemoryShortTermStore::new();
@@ -1765,4 +3145,242 @@ mod tests {
         let tasks = store.list_tasks(filter).await.unwrap();
         assert_eq!(tasks.len(), 2);
     }
+
+    #[tokio::test]
+    async fn list_tasks_page_walks_tasks_in_creation_order() {
+        let store = MemoryShortTermStore::new();
+
+        // Two tasks share a creation time and are ordered by id.
+        for (id, created_at, status) in [
+            ("t4", 4000.0, TaskStatus::Failed),
+            ("t1", 1000.0, TaskStatus::Failed),
+            ("t3", 2000.0, TaskStatus::Failed),
+            ("t2", 2000.0, TaskStatus::Failed),
+            ("t5", 5000.0, TaskStatus::Completed),
+            ("t6", 6000.0, TaskStatus::Failed),
+        ] {
+            let mut task = make_task(id);
+            task.created_at = created_at;
+            task.status = status;
+            store.save_task(task).await.unwrap();
+        }
+        let filter = TaskFilter {
+            status: Some(vec![TaskStatus::Failed]),
+            ..Default::default()
+        };
+
+        let mut ids = Vec::new();
+        let mut after = None;
+        loop {
+            let page = store
+                .list_tasks_page(filter.clone(), after, 2)
+                .await
+                .unwrap();
+            assert!(page.tasks.len() <= 2);
+            ids.extend(page.tasks.into_iter().map(|t| t.id));
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(ids, vec!["t1", "t2", "t3", "t4", "t6"]);
+    }
+
+    // ─── MemoryShortTermStore: persistence ──────────────────────────────
+
+    const NO_FLUSH: Duration = Duration::from_secs(3600);
+
+    async fn publish(store: &MemoryShortTermStore, task_id: &str) -> u64 {
+        let index = store.next_index(task_id).await.unwrap();
+        let event = make_event(&format!("{task_id}-{index}"), task_id, index, 1000.0);
+        store.append_event(task_id, event).await.unwrap();
+        index
+    }
+
+    #[tokio::test]
+    async fn persisted_store_round_trips_through_a_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let store = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        store.save_task(make_task("t1")).await.unwrap();
+        store.save_task(make_task("t2")).await.unwrap();
+        publish(&store, "t1").await;
+        publish(&store, "t1").await;
+        let latest = TaskEvent {
+            series_id: Some("s1".to_string()),
+            ..make_event("t1-s1", "t1", 1, 1000.0)
+        };
+        store
+            .set_series_latest("t1", "s1", latest.clone())
+            .await
+            .unwrap();
+        store
+            .claim_filtered_index("t1", "sse:reader", "h", 1)
+            .await
+            .unwrap();
+        store.flush().unwrap();
+
+        let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        assert_eq!(
+            reloaded.get_task("t1").await.unwrap(),
+            Some(make_task("t1"))
+        );
+        assert_eq!(
+            reloaded.get_task("t2").await.unwrap(),
+            Some(make_task("t2"))
+        );
+        assert_eq!(
+            reloaded.get_events("t1", None).await.unwrap(),
+            store.get_events("t1", None).await.unwrap()
+        );
+        assert_eq!(
+            reloaded.get_series_latest("t1", "s1").await.unwrap(),
+            Some(latest)
+        );
+        assert_eq!(reloaded.event_count("t1").await.unwrap(), 2);
+        assert_eq!(
+            reloaded
+                .claim_filtered_index("t1", "sse:reader", "h", 2)
+                .await
+                .unwrap(),
+            Some(FilteredIndexClaim {
+                filtered_index: 1,
+                epoch: 0
+            })
+        );
+        assert!(!Path::new(&format!("{}.tmp", path.display())).exists());
+    }
+
+    #[tokio::test]
+    async fn index_counters_continue_after_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let store = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        for _ in 0..3 {
+            publish(&store, "t1").await;
+        }
+        store.flush().unwrap();
+
+        let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        assert_eq!(publish(&reloaded, "t1").await, 3);
+        assert_eq!(publish(&reloaded, "t2").await, 0);
+    }
+
+    #[tokio::test]
+    async fn reload_never_reuses_an_index_a_lagging_counter_missed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let mut snapshot = MemoryShortTermStore::new().snapshot();
+        snapshot.events.insert(
+            "t1".to_string(),
+            vec![
+                make_event("e0", "t1", 0, 1000.0),
+                make_event("e1", "t1", 1, 1000.0),
+            ],
+        );
+        snapshot.index_counters.insert("t1".to_string(), 1);
+        std::fs::write(&path, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+        let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        assert_eq!(reloaded.next_index("t1").await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn unusable_snapshots_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let old = json!({ "version": 0, "tasks": { "t1": make_task("t1") } });
+        for contents in [b"{ not json".to_vec(), serde_json::to_vec(&old).unwrap()] {
+            std::fs::write(&path, contents).unwrap();
+            let store = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+            assert_eq!(store.get_task("t1").await.unwrap(), None);
+
+            // The next flush replaces the unusable snapshot.
+            store.save_task(make_task("t2")).await.unwrap();
+            store.flush().unwrap();
+            let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+            assert!(reloaded.get_task("t2").await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn store_flushes_on_its_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let store = MemoryShortTermStore::with_persistence(&path, Duration::from_millis(20));
+        store.start_flushing(&BackgroundTasks::new(None));
+        store.save_task(make_task("t1")).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("snapshot was never written");
+    }
+
+    #[tokio::test]
+    async fn closing_flushes_once_more_and_drains() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let background = BackgroundTasks::new(None);
+        let store = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        store.start_flushing(&background);
+        assert_eq!(background.in_flight_by_name()["memory.snapshot"], 1);
+        store.save_task(make_task("t1")).await.unwrap();
+        assert!(!path.exists());
+
+        store.close();
+        assert!(background.drain(Duration::from_secs(5)).await);
+        let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        assert!(reloaded.get_task("t1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn flush_without_persistence_does_nothing() {
+        MemoryShortTermStore::new().flush().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn publishes_during_snapshots_do_not_deadlock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.json");
+        let store = MemoryShortTermStore::with_persistence(&path, Duration::from_millis(1));
+
+        let flusher = {
+            let store = Arc::clone(&store);
+            tokio::task::spawn_blocking(move || {
+                for _ in 0..50 {
+                    store.flush().unwrap();
+                }
+            })
+        };
+        let publishers: Vec<_> = (0..8)
+            .map(|i| {
+                let store = Arc::clone(&store);
+                tokio::spawn(async move {
+                    let task_id = format!("t{i}");
+                    store.save_task(make_task(&task_id)).await.unwrap();
+                    for _ in 0..200 {
+                        publish(&store, &task_id).await;
+                    }
+                })
+            })
+            .collect();
+
+        tokio::time::timeout(Duration::from_secs(30), async {
+            flusher.await.unwrap();
+            for publisher in publishers {
+                publisher.await.unwrap();
+            }
+        })
+        .await
+        .expect("publishing and snapshotting deadlocked");
+
+        store.flush().unwrap();
+        let reloaded = MemoryShortTermStore::with_persistence(&path, NO_FLUSH);
+        for i in 0..8 {
+            assert_eq!(reloaded.event_count(&format!("t{i}")).await.unwrap(), 200);
+        }
+    }
 }
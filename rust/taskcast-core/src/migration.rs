@@ -0,0 +1,926 @@
+//! Moving a live deployment from one short-term store to another.
+//!
+//! [`MigratingShortTermStore`] wraps the store being left (`old`) and the
+//! one being moved to (`new`) and runs in one of two [`MigrationMode`]s:
+//!
+//! - `DualWrite`: every write goes to `new` and is mirrored to `old`, where
+//!   a failure is logged rather than returned. Before its first write a task
+//!   is copied from `old` (see [`MigratingShortTermStore::migrate_task`]), so
+//!   `new` always holds its whole history and hands out its indices. Reads
+//!   prefer `new`; a task only `old` holds is read from `old`, or copied
+//!   over first when lazy backfill is on.
+//! - `Cutover`: only `new` is used. Tasks not copied by then are left
+//!   behind, so run [`MigratingShortTermStore::backfill_all`] first.
+//!
+//! Claims (retries, activations, filtered indices, webhook suppression,
+//! deadline warnings) are decided by `new` alone and not mirrored.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::compression::CompressionStats;
+use crate::conventions::EventConventionStore;
+use crate::integrity::IntegrityMonitor;
+use crate::types::{
+    task_order, EventQueryOptions, EventTypeCounts, FilteredIndexClaim, FilteredIndexMark,
+    NewTaskGroupOutcome, NewTaskOutcome, OutcomeQuery, RetrySchedule, SeriesSummary,
+    ShortTermStore, Task, TaskArchiveImportOptions, TaskArchiveRestoreData, TaskCursor, TaskEvent,
+    TaskFilter, TaskOutcome, TaskPage, TaskStatus, Worker, WorkerAssignment, WorkerFilter,
+};
+
+type StoreResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Tasks [`MigratingShortTermStore::backfill_all`] reads from the old store
+/// at a time.
+pub const BACKFILL_PAGE_SIZE: usize = 100;
+
+/// How a [`MigratingShortTermStore`] uses the store being left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationMode {
+    /// Write to both stores, read the new one first.
+    #[default]
+    DualWrite,
+    /// Use the new store only.
+    Cutover,
+}
+
+/// Where a [`MigratingShortTermStore::backfill_all`] run stands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillProgress {
+    /// Tasks read from the old store so far.
+    pub scanned: u64,
+    /// Tasks the run wrote anything to the new store for.
+    pub copied: u64,
+    /// Tasks the new store already held in full.
+    pub skipped: u64,
+    /// Tasks whose copy failed; they are retried by the next run.
+    pub failed: u64,
+    /// Whether every task has been scanned.
+    pub done: bool,
+}
+
+/// Short-term store that moves tasks from `old` to `new` while serving
+/// traffic. See the [module docs](self).
+pub struct MigratingShortTermStore {
+    old: Arc<dyn ShortTermStore>,
+    new: Arc<dyn ShortTermStore>,
+    mode: RwLock<MigrationMode>,
+    lazy_backfill: bool,
+    /// Tasks known to be complete in `new`.
+    migrated: Mutex<HashSet<String>>,
+    /// Held while a task is copied, so two copies never interleave.
+    copying: tokio::sync::Mutex<()>,
+    progress: Mutex<Option<BackfillProgress>>,
+}
+
+impl MigratingShortTermStore {
+    /// Starts in `DualWrite` mode with lazy backfill off.
+    pub fn new(old: Arc<dyn ShortTermStore>, new: Arc<dyn ShortTermStore>) -> Self {
+        Self {
+            old,
+            new,
+            mode: RwLock::new(MigrationMode::DualWrite),
+            lazy_backfill: false,
+            migrated: Mutex::new(HashSet::new()),
+            copying: tokio::sync::Mutex::new(()),
+            progress: Mutex::new(None),
+        }
+    }
+
+    /// Copies a task the new store lacks on its first read instead of
+    /// reading it from the old store.
+    pub fn with_lazy_backfill(mut self, enabled: bool) -> Self {
+        self.lazy_backfill = enabled;
+        self
+    }
+
+    pub fn with_mode(self, mode: MigrationMode) -> Self {
+        *self.mode.write().unwrap() = mode;
+        self
+    }
+
+    pub fn mode(&self) -> MigrationMode {
+        *self.mode.read().unwrap()
+    }
+
+    /// Switches mode. Takes effect for the next call into the store.
+    pub fn set_mode(&self, mode: MigrationMode) {
+        *self.mode.write().unwrap() = mode;
+    }
+
+    pub fn lazy_backfill(&self) -> bool {
+        self.lazy_backfill
+    }
+
+    /// Number of tasks known to be complete in the new store.
+    pub fn migrated_tasks(&self) -> usize {
+        self.migrated.lock().unwrap().len()
+    }
+
+    /// The latest or current [`backfill_all`](Self::backfill_all) run, if
+    /// any.
+    pub fn backfill_progress(&self) -> Option<BackfillProgress> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Copies what the new store lacks of a task from the old one: missing
+    /// events, series-latest entries, its retry schedule, activation and
+    /// assignment, then the task record itself, last, so a reader that finds
+    /// the task in the new store finds its history too. The new index
+    /// counter is raised to the old one's, so indices continue where the old
+    /// store left off. Returns whether anything was written; copying a task
+    /// twice writes nothing the second time.
+    pub async fn migrate_task(&self, task_id: &str) -> StoreResult<bool> {
+        let _copying = self.copying.lock().await;
+        let copied = self.copy_task(task_id).await?;
+        self.migrated.lock().unwrap().insert(task_id.to_string());
+        Ok(copied)
+    }
+
+    async fn copy_task(&self, task_id: &str) -> StoreResult<bool> {
+        let Some(task) = self.old.get_task(task_id).await? else {
+            return Ok(false);
+        };
+        let mut copied = false;
+
+        let present: HashSet<String> = self
+            .new
+            .get_events(task_id, None)
+            .await?
+            .into_iter()
+            .map(|event| event.id)
+            .collect();
+        let mut series_ids = HashSet::new();
+        for event in self.old.get_events(task_id, None).await? {
+            if let Some(series_id) = &event.series_id {
+                series_ids.insert(series_id.clone());
+            }
+            if !present.contains(&event.id) {
+                self.new.append_event(task_id, event).await?;
+                copied = true;
+            }
+        }
+
+        for series_id in series_ids {
+            if self
+                .new
+                .get_series_latest(task_id, &series_id)
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+            if let Some(latest) = self.old.get_series_latest(task_id, &series_id).await? {
+                self.new
+                    .set_series_latest(task_id, &series_id, latest)
+                    .await?;
+                copied = true;
+            }
+        }
+
+        let old_count = self.old.event_count(task_id).await?;
+        if self.new.event_count(task_id).await? < old_count {
+            // A history-derived count does not move with the counter, so
+            // go by the indices handed out instead.
+            while self.new.next_index(task_id).await? + 1 < old_count {}
+            copied = true;
+        }
+
+        if self.new.get_retry_schedule(task_id).await?.is_none() {
+            if let Some(schedule) = self.old.get_retry_schedule(task_id).await? {
+                self.new.save_retry_schedule(schedule).await?;
+                copied = true;
+            }
+        }
+        if self.new.get_task_assignment(task_id).await?.is_none() {
+            if let Some(assignment) = self.old.get_task_assignment(task_id).await? {
+                self.new.add_assignment(assignment).await?;
+                copied = true;
+            }
+        }
+
+        if self.new.get_task(task_id).await?.is_none() {
+            match (&task.status, task.scheduled_for) {
+                (TaskStatus::Scheduled, Some(at)) => self.new.save_activation(task_id, at).await?,
+                _ => {
+                    if let Some(ttl) = task.ttl {
+                        self.new.set_ttl(task_id, ttl).await?;
+                    }
+                }
+            }
+            self.new.save_task(task).await?;
+            copied = true;
+        }
+        Ok(copied)
+    }
+
+    /// Copies every task of the old store with
+    /// [`migrate_task`](Self::migrate_task), then the workers the new store
+    /// lacks. `on_progress` is called after each page of
+    /// [`BACKFILL_PAGE_SIZE`] tasks and once more when done. A task whose
+    /// copy fails is counted and logged and the run goes on; running again
+    /// retries it and skips the rest.
+    pub async fn backfill_all(
+        &self,
+        mut on_progress: impl FnMut(&BackfillProgress) + Send,
+    ) -> StoreResult<BackfillProgress> {
+        let mut progress = BackfillProgress::default();
+        self.report(&progress, &mut on_progress);
+        let mut after = None;
+        loop {
+            let page = self
+                .old
+                .list_tasks_page(TaskFilter::default(), after, BACKFILL_PAGE_SIZE)
+                .await?;
+            for task in &page.tasks {
+                progress.scanned += 1;
+                match self.migrate_task(&task.id).await {
+                    Ok(true) => progress.copied += 1,
+                    Ok(false) => progress.skipped += 1,
+                    Err(err) => {
+                        progress.failed += 1;
+                        eprintln!("[taskcast] Backfill of task {} failed: {err}", task.id);
+                    }
+                }
+            }
+            self.report(&progress, &mut on_progress);
+            after = page.next;
+            if after.is_none() {
+                break;
+            }
+        }
+
+        for worker in self.old.list_workers(None).await? {
+            if self.new.get_worker(&worker.id).await?.is_none() {
+                self.new.save_worker(worker).await?;
+            }
+        }
+        progress.done = true;
+        self.report(&progress, &mut on_progress);
+        Ok(progress)
+    }
+
+    fn report(&self, progress: &BackfillProgress, on_progress: &mut impl FnMut(&BackfillProgress)) {
+        *self.progress.lock().unwrap() = Some(progress.clone());
+        on_progress(progress);
+    }
+
+    /// The old store when writes are mirrored to it.
+    fn mirror(&self) -> Option<&dyn ShortTermStore> {
+        match self.mode() {
+            MigrationMode::DualWrite => Some(&*self.old),
+            MigrationMode::Cutover => None,
+        }
+    }
+
+    /// Gets a task ready for a write: in `DualWrite` mode copies it over if
+    /// that has not happened yet, and returns the store to mirror to.
+    async fn prepare_write(&self, task_id: &str) -> StoreResult<Option<&dyn ShortTermStore>> {
+        let Some(old) = self.mirror() else {
+            return Ok(None);
+        };
+        if !self.is_migrated(task_id) {
+            self.migrate_task(task_id).await?;
+        }
+        Ok(Some(old))
+    }
+
+    fn is_migrated(&self, task_id: &str) -> bool {
+        self.migrated.lock().unwrap().contains(task_id)
+    }
+
+    /// The store holding a task's current state.
+    async fn read_from(&self, task_id: &str) -> StoreResult<&dyn ShortTermStore> {
+        if self.mode() == MigrationMode::Cutover || self.is_migrated(task_id) {
+            return Ok(&*self.new);
+        }
+        if self.lazy_backfill {
+            self.migrate_task(task_id).await?;
+            return Ok(&*self.new);
+        }
+        if self.new.get_task(task_id).await?.is_some() {
+            return Ok(&*self.new);
+        }
+        Ok(&*self.old)
+    }
+}
+
+/// Logs a write the old store failed to mirror.
+fn mirrored<T>(operation: &str, task_id: &str, result: StoreResult<T>) {
+    if let Err(err) = result {
+        eprintln!("[taskcast] Mirroring {operation} for {task_id} to the old store failed: {err}");
+    }
+}
+
+/// `new` followed by the entries of `old` whose key `new` lacks.
+fn merge_by<T>(new: Vec<T>, old: Vec<T>, key: impl Fn(&T) -> &str) -> Vec<T> {
+    let seen: HashSet<String> = new.iter().map(|item| key(item).to_string()).collect();
+    let mut merged = new;
+    merged.extend(old.into_iter().filter(|item| !seen.contains(key(item))));
+    merged
+}
+
+#[async_trait]
+impl ShortTermStore for MigratingShortTermStore {
+    async fn save_task(&self, task: Task) -> StoreResult<()> {
+        let old = self.prepare_write(&task.id).await?;
+        self.new.save_task(task.clone()).await?;
+        if let Some(old) = old {
+            let task_id = task.id.clone();
+            mirrored("save_task", &task_id, old.save_task(task).await);
+        }
+        Ok(())
+    }
+
+    async fn save_new_task(&self, task: Task) -> StoreResult<NewTaskOutcome> {
+        let old = self.prepare_write(&task.id).await?;
+        let outcome = self.new.save_new_task(task.clone()).await?;
+        if let (Some(old), NewTaskOutcome::Created) = (old, &outcome) {
+            let task_id = task.id.clone();
+            mirrored("save_new_task", &task_id, old.save_task(task).await);
+        }
+        Ok(outcome)
+    }
+
+    async fn save_new_task_group(&self, tasks: Vec<Task>) -> StoreResult<NewTaskGroupOutcome> {
+        for task in &tasks {
+            self.prepare_write(&task.id).await?;
+        }
+        let old = self.mirror();
+        let outcome = self.new.save_new_task_group(tasks.clone()).await?;
+        if let (Some(old), NewTaskGroupOutcome::Created) = (old, &outcome) {
+            for task in tasks {
+                let task_id = task.id.clone();
+                mirrored("save_new_task_group", &task_id, old.save_task(task).await);
+            }
+        }
+        Ok(outcome)
+    }
+
+    async fn save_task_versioned(&self, task: Task, expected_version: u64) -> StoreResult<bool> {
+        let old = self.prepare_write(&task.id).await?;
+        let written = self
+            .new
+            .save_task_versioned(task.clone(), expected_version)
+            .await?;
+        if let (Some(old), true) = (old, written) {
+            let task_id = task.id.clone();
+            mirrored("save_task_versioned", &task_id, old.save_task(task).await);
+        }
+        Ok(written)
+    }
+
+    async fn get_task(&self, task_id: &str) -> StoreResult<Option<Task>> {
+        if self.mode() == MigrationMode::Cutover || self.is_migrated(task_id) {
+            return self.new.get_task(task_id).await;
+        }
+        if let Some(task) = self.new.get_task(task_id).await? {
+            return Ok(Some(task));
+        }
+        if self.lazy_backfill {
+            self.migrate_task(task_id).await?;
+            return self.new.get_task(task_id).await;
+        }
+        self.old.get_task(task_id).await
+    }
+
+    async fn append_event(&self, task_id: &str, event: TaskEvent) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.append_event(task_id, event.clone()).await?;
+        if let Some(old) = old {
+            mirrored(
+                "append_event",
+                task_id,
+                old.append_event(task_id, event).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> StoreResult<Vec<TaskEvent>> {
+        self.read_from(task_id)
+            .await?
+            .get_events(task_id, opts)
+            .await
+    }
+
+    async fn set_ttl(&self, task_id: &str, ttl_seconds: u64) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.set_ttl(task_id, ttl_seconds).await?;
+        if let Some(old) = old {
+            mirrored("set_ttl", task_id, old.set_ttl(task_id, ttl_seconds).await);
+        }
+        Ok(())
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> StoreResult<Option<TaskEvent>> {
+        self.read_from(task_id)
+            .await?
+            .get_series_latest(task_id, series_id)
+            .await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new
+            .set_series_latest(task_id, series_id, event.clone())
+            .await?;
+        if let Some(old) = old {
+            let result = old.set_series_latest(task_id, series_id, event).await;
+            mirrored("set_series_latest", task_id, result);
+        }
+        Ok(())
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> StoreResult<Option<String>> {
+        let old = self.prepare_write(task_id).await?;
+        let replaced = self
+            .new
+            .replace_last_series_event(task_id, series_id, event.clone())
+            .await?;
+        if let Some(old) = old {
+            let result = old
+                .replace_last_series_event(task_id, series_id, event)
+                .await;
+            mirrored("replace_last_series_event", task_id, result);
+        }
+        Ok(replaced)
+    }
+
+    /// Accumulates in the new store and mirrors the result as the old
+    /// store's series latest, so both agree even where they had drifted.
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> StoreResult<TaskEvent> {
+        let old = self.prepare_write(task_id).await?;
+        let accumulated = self
+            .new
+            .accumulate_series(task_id, series_id, event, field)
+            .await?;
+        if let Some(old) = old {
+            let result = old
+                .set_series_latest(task_id, series_id, accumulated.clone())
+                .await;
+            mirrored("accumulate_series", task_id, result);
+        }
+        Ok(accumulated)
+    }
+
+    async fn list_series(&self, task_id: &str) -> StoreResult<Vec<SeriesSummary>> {
+        self.read_from(task_id).await?.list_series(task_id).await
+    }
+
+    async fn series_count(&self, task_id: &str) -> StoreResult<u64> {
+        self.read_from(task_id).await?.series_count(task_id).await
+    }
+
+    /// Allocated by the new store. The old counter is advanced alongside it
+    /// so it stays usable if the migration is abandoned.
+    async fn next_index(&self, task_id: &str) -> StoreResult<u64> {
+        let old = self.prepare_write(task_id).await?;
+        let index = self.new.next_index(task_id).await?;
+        if let Some(old) = old {
+            mirrored("next_index", task_id, old.next_index(task_id).await);
+        }
+        Ok(index)
+    }
+
+    async fn event_count(&self, task_id: &str) -> StoreResult<u64> {
+        self.read_from(task_id).await?.event_count(task_id).await
+    }
+
+    async fn get_event_type_counts(&self, task_id: &str) -> StoreResult<EventTypeCounts> {
+        self.read_from(task_id)
+            .await?
+            .get_event_type_counts(task_id)
+            .await
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        self.new.integrity()
+    }
+
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        self.new.event_conventions()
+    }
+
+    fn compression_stats(&self) -> Option<CompressionStats> {
+        self.new.compression_stats()
+    }
+
+    fn migration(&self) -> Option<&MigratingShortTermStore> {
+        Some(self)
+    }
+
+    fn supports_task_archive_restore(&self) -> bool {
+        self.new.supports_task_archive_restore()
+    }
+
+    async fn validate_task_archive_restore(
+        &self,
+        data: &TaskArchiveRestoreData,
+        options: Option<TaskArchiveImportOptions>,
+    ) -> StoreResult<()> {
+        self.new.validate_task_archive_restore(data, options).await
+    }
+
+    /// Restored into the new store only.
+    async fn restore_task_archive(
+        &self,
+        data: TaskArchiveRestoreData,
+        options: Option<TaskArchiveImportOptions>,
+    ) -> StoreResult<bool> {
+        self.prepare_write(&data.task.id).await?;
+        self.new.restore_task_archive(data, options).await
+    }
+
+    async fn list_tasks(&self, filter: TaskFilter) -> StoreResult<Vec<Task>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_tasks(filter).await;
+        };
+        let limit = filter.limit;
+        let new_tasks = self.new.list_tasks(filter.clone()).await?;
+        let old_tasks = old.list_tasks(filter).await?;
+        let mut tasks = merge_by(new_tasks, old_tasks, |task| &task.id);
+        if let Some(limit) = limit {
+            tasks.truncate(limit as usize);
+        }
+        Ok(tasks)
+    }
+
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> StoreResult<TaskPage> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_tasks_page(filter, after, limit).await;
+        };
+        let new_page = self
+            .new
+            .list_tasks_page(filter.clone(), after.clone(), limit)
+            .await?;
+        let old_page = old.list_tasks_page(filter, after, limit).await?;
+        let more = new_page.next.is_some() || old_page.next.is_some();
+        let mut tasks = merge_by(new_page.tasks, old_page.tasks, |task| &task.id);
+        tasks.sort_by(|a, b| task_order(a.created_at, &a.id, b.created_at, &b.id));
+        let mut page = TaskPage::from_sorted(tasks, limit);
+        // Either store may have more past its own page even when the merged
+        // page is not full.
+        if page.next.is_none() && more {
+            page.next = page.tasks.last().map(TaskCursor::of);
+        }
+        Ok(page)
+    }
+
+    async fn save_worker(&self, worker: Worker) -> StoreResult<()> {
+        self.new.save_worker(worker.clone()).await?;
+        if let Some(old) = self.mirror() {
+            let worker_id = worker.id.clone();
+            mirrored("save_worker", &worker_id, old.save_worker(worker).await);
+        }
+        Ok(())
+    }
+
+    async fn get_worker(&self, worker_id: &str) -> StoreResult<Option<Worker>> {
+        let worker = self.new.get_worker(worker_id).await?;
+        match self.mirror() {
+            Some(old) if worker.is_none() => old.get_worker(worker_id).await,
+            _ => Ok(worker),
+        }
+    }
+
+    async fn list_workers(&self, filter: Option<WorkerFilter>) -> StoreResult<Vec<Worker>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_workers(filter).await;
+        };
+        let new_workers = self.new.list_workers(filter.clone()).await?;
+        let old_workers = old.list_workers(filter).await?;
+        Ok(merge_by(new_workers, old_workers, |worker| &worker.id))
+    }
+
+    async fn delete_worker(&self, worker_id: &str) -> StoreResult<()> {
+        self.new.delete_worker(worker_id).await?;
+        if let Some(old) = self.mirror() {
+            mirrored(
+                "delete_worker",
+                worker_id,
+                old.delete_worker(worker_id).await,
+            );
+        }
+        Ok(())
+    }
+
+    /// Decided by the new store; the claimed task and worker are then
+    /// written to the old one.
+    async fn claim_task(&self, task_id: &str, worker_id: &str, cost: u32) -> StoreResult<bool> {
+        let old = self.prepare_write(task_id).await?;
+        if let Some(old) = old {
+            if self.new.get_worker(worker_id).await?.is_none() {
+                if let Some(worker) = old.get_worker(worker_id).await? {
+                    self.new.save_worker(worker).await?;
+                }
+            }
+        }
+        let claimed = self.new.claim_task(task_id, worker_id, cost).await?;
+        if let (Some(old), true) = (old, claimed) {
+            if let Some(task) = self.new.get_task(task_id).await? {
+                mirrored("claim_task", task_id, old.save_task(task).await);
+            }
+            if let Some(worker) = self.new.get_worker(worker_id).await? {
+                mirrored("claim_task", task_id, old.save_worker(worker).await);
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn add_assignment(&self, assignment: WorkerAssignment) -> StoreResult<()> {
+        let old = self.prepare_write(&assignment.task_id).await?;
+        self.new.add_assignment(assignment.clone()).await?;
+        if let Some(old) = old {
+            let task_id = assignment.task_id.clone();
+            mirrored(
+                "add_assignment",
+                &task_id,
+                old.add_assignment(assignment).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn remove_assignment(&self, task_id: &str) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.remove_assignment(task_id).await?;
+        if let Some(old) = old {
+            mirrored(
+                "remove_assignment",
+                task_id,
+                old.remove_assignment(task_id).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn get_worker_assignments(&self, worker_id: &str) -> StoreResult<Vec<WorkerAssignment>> {
+        let Some(old) = self.mirror() else {
+            return self.new.get_worker_assignments(worker_id).await;
+        };
+        let new_assignments = self.new.get_worker_assignments(worker_id).await?;
+        let old_assignments = old.get_worker_assignments(worker_id).await?;
+        Ok(merge_by(new_assignments, old_assignments, |assignment| {
+            &assignment.task_id
+        }))
+    }
+
+    async fn get_task_assignment(&self, task_id: &str) -> StoreResult<Option<WorkerAssignment>> {
+        self.read_from(task_id)
+            .await?
+            .get_task_assignment(task_id)
+            .await
+    }
+
+    async fn clear_ttl(&self, task_id: &str) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.clear_ttl(task_id).await?;
+        if let Some(old) = old {
+            mirrored("clear_ttl", task_id, old.clear_ttl(task_id).await);
+        }
+        Ok(())
+    }
+
+    async fn list_by_status(&self, statuses: &[TaskStatus]) -> StoreResult<Vec<Task>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_by_status(statuses).await;
+        };
+        let new_tasks = self.new.list_by_status(statuses).await?;
+        let old_tasks = old.list_by_status(statuses).await?;
+        Ok(merge_by(new_tasks, old_tasks, |task| &task.id))
+    }
+
+    async fn save_retry_schedule(&self, schedule: RetrySchedule) -> StoreResult<()> {
+        let old = self.prepare_write(&schedule.task_id).await?;
+        self.new.save_retry_schedule(schedule.clone()).await?;
+        if let Some(old) = old {
+            let task_id = schedule.task_id.clone();
+            mirrored(
+                "save_retry_schedule",
+                &task_id,
+                old.save_retry_schedule(schedule).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn list_due_retries(&self, now: f64) -> StoreResult<Vec<RetrySchedule>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_due_retries(now).await;
+        };
+        let new_due = self.new.list_due_retries(now).await?;
+        let old_due = old.list_due_retries(now).await?;
+        let mut due = merge_by(new_due, old_due, |schedule| &schedule.task_id);
+        due.sort_by(|a, b| a.due_at.total_cmp(&b.due_at));
+        Ok(due)
+    }
+
+    async fn get_retry_schedule(&self, task_id: &str) -> StoreResult<Option<RetrySchedule>> {
+        self.read_from(task_id)
+            .await?
+            .get_retry_schedule(task_id)
+            .await
+    }
+
+    async fn claim_retry(&self, task_id: &str, now: f64, lease_ms: u64) -> StoreResult<bool> {
+        self.prepare_write(task_id).await?;
+        self.new.claim_retry(task_id, now, lease_ms).await
+    }
+
+    async fn delete_retry_schedule(&self, task_id: &str) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.delete_retry_schedule(task_id).await?;
+        if let Some(old) = old {
+            mirrored(
+                "delete_retry_schedule",
+                task_id,
+                old.delete_retry_schedule(task_id).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn save_activation(&self, task_id: &str, at: f64) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.save_activation(task_id, at).await?;
+        if let Some(old) = old {
+            mirrored(
+                "save_activation",
+                task_id,
+                old.save_activation(task_id, at).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn list_due_activations(&self, now: f64) -> StoreResult<Vec<String>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_due_activations(now).await;
+        };
+        let new_due = self.new.list_due_activations(now).await?;
+        let old_due = old.list_due_activations(now).await?;
+        Ok(merge_by(new_due, old_due, String::as_str))
+    }
+
+    async fn claim_activation(&self, task_id: &str, now: f64, lease_ms: u64) -> StoreResult<bool> {
+        self.prepare_write(task_id).await?;
+        self.new.claim_activation(task_id, now, lease_ms).await
+    }
+
+    async fn delete_activation(&self, task_id: &str) -> StoreResult<()> {
+        let old = self.prepare_write(task_id).await?;
+        self.new.delete_activation(task_id).await?;
+        if let Some(old) = old {
+            mirrored(
+                "delete_activation",
+                task_id,
+                old.delete_activation(task_id).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn claim_filtered_index(
+        &self,
+        task_id: &str,
+        consumer: &str,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> StoreResult<Option<FilteredIndexClaim>> {
+        self.prepare_write(task_id).await?;
+        self.new
+            .claim_filtered_index(task_id, consumer, filter_hash, raw_index)
+            .await
+    }
+
+    async fn get_filtered_index_mark(
+        &self,
+        task_id: &str,
+        consumer: &str,
+    ) -> StoreResult<Option<FilteredIndexMark>> {
+        self.read_from(task_id)
+            .await?
+            .get_filtered_index_mark(task_id, consumer)
+            .await
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        fingerprint: &str,
+        now: f64,
+        window_ms: u64,
+    ) -> StoreResult<bool> {
+        self.prepare_write(task_id).await?;
+        self.new
+            .claim_webhook_delivery(task_id, webhook, fingerprint, now, window_ms)
+            .await
+    }
+
+    async fn claim_deadline_warning(
+        &self,
+        task_id: &str,
+        warning: &str,
+        ttl_ms: u64,
+    ) -> StoreResult<bool> {
+        self.prepare_write(task_id).await?;
+        self.new
+            .claim_deadline_warning(task_id, warning, ttl_ms)
+            .await
+    }
+
+    async fn record_outcome(&self, outcome: TaskOutcome) -> StoreResult<()> {
+        self.new.record_outcome(outcome.clone()).await?;
+        if let Some(old) = self.mirror() {
+            let task_id = outcome.task_id.clone();
+            mirrored(
+                "record_outcome",
+                &task_id,
+                old.record_outcome(outcome).await,
+            );
+        }
+        Ok(())
+    }
+
+    async fn list_outcomes(&self, query: &OutcomeQuery) -> StoreResult<Vec<TaskOutcome>> {
+        let Some(old) = self.mirror() else {
+            return self.new.list_outcomes(query).await;
+        };
+        let new_outcomes = self.new.list_outcomes(query).await?;
+        let old_outcomes = old.list_outcomes(query).await?;
+        let mut outcomes = merge_by(new_outcomes, old_outcomes, |outcome| &outcome.task_id);
+        outcomes.sort_by(|a, b| {
+            a.completed_at
+                .total_cmp(&b.completed_at)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        outcomes.truncate(query.limit);
+        Ok(outcomes)
+    }
+
+    async fn trim_outcomes(&self, before: f64) -> StoreResult<u64> {
+        let trimmed = self.new.trim_outcomes(before).await?;
+        if let Some(old) = self.mirror() {
+            mirrored("trim_outcomes", "outcomes", old.trim_outcomes(before).await);
+        }
+        Ok(trimmed)
+    }
+
+    async fn delete_task(&self, task_id: &str) -> StoreResult<()> {
+        self.new.delete_task(task_id).await?;
+        if let Some(old) = self.mirror() {
+            mirrored("delete_task", task_id, old.delete_task(task_id).await);
+        }
+        self.migrated.lock().unwrap().remove(task_id);
+        Ok(())
+    }
+
+    async fn redact_events(&self, task_id: &str, tombstones: &[TaskEvent]) -> StoreResult<u64> {
+        let old = self.prepare_write(task_id).await?;
+        let replaced = self.new.redact_events(task_id, tombstones).await?;
+        if let Some(old) = old {
+            mirrored(
+                "redact_events",
+                task_id,
+                old.redact_events(task_id, tombstones).await,
+            );
+        }
+        Ok(replaced)
+    }
+}
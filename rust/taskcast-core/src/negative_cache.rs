@@ -0,0 +1,135 @@
+//! Short-lived memory of task ids confirmed missing from every store.
+//!
+//! A client polling a task id that does not exist makes the engine consult
+//! the short-term store and then the long-term store on every request. With
+//! a [`NegativeCache`], a confirmed miss is remembered for a few seconds and
+//! repeat lookups return `None` without touching either store.
+//!
+//! Only this instance's creations invalidate an entry. A task created on
+//! another instance may keep reading as missing here for up to the TTL,
+//! which is why the cache is off unless
+//! [`TaskEngine::with_negative_cache`](crate::TaskEngine::with_negative_cache)
+//! is called.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// TTL used when none is configured.
+pub const DEFAULT_NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(3);
+
+/// Most ids remembered at once. When full, expired entries are dropped
+/// first; if none have expired, new misses are not remembered.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Snapshot of the negative cache, for the stats surface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NegativeCacheStats {
+    pub ttl_ms: f64,
+    /// Ids currently remembered, including expired ones not yet dropped.
+    pub entries: usize,
+    /// Lookups answered from the cache without reading a store.
+    pub hits: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct NegativeCache {
+    ttl_ms: f64,
+    /// Task id → time its entry expires, in epoch milliseconds.
+    entries: Mutex<HashMap<String, f64>>,
+    /// Bumped by every invalidation, so a lookup that raced a creation does
+    /// not remember its now-stale miss.
+    generation: AtomicU64,
+    hits: AtomicU64,
+}
+
+impl NegativeCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl_ms: ttl.as_secs_f64() * 1000.0,
+            entries: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether `task_id` is known to be missing at `now`. Counts a hit.
+    pub(crate) fn contains(&self, task_id: &str, now: f64) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(task_id) {
+            Some(&expires_at) if expires_at > now => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Some(_) => {
+                entries.remove(task_id);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Taken before a store lookup and handed back to [`insert`](Self::insert).
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Remembers that a lookup started at `generation` found no `task_id`,
+    /// unless an invalidation happened since.
+    pub(crate) fn insert(&self, task_id: &str, generation: u64, now: f64) {
+        let mut entries = self.entries.lock().unwrap();
+        if self.generation.load(Ordering::Acquire) != generation {
+            return;
+        }
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, expires_at| *expires_at > now);
+            if entries.len() >= MAX_ENTRIES {
+                return;
+            }
+        }
+        entries.insert(task_id.to_string(), now + self.ttl_ms);
+    }
+
+    /// Forgets `task_id`, which now exists.
+    pub(crate) fn invalidate(&self, task_id: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.remove(task_id);
+    }
+
+    pub(crate) fn stats(&self) -> NegativeCacheStats {
+        NegativeCacheStats {
+            ttl_ms: self.ttl_ms,
+            entries: self.entries.lock().unwrap().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let cache = NegativeCache::new(Duration::from_secs(2));
+        cache.insert("ghost", cache.generation(), 1000.0);
+        assert!(cache.contains("ghost", 2999.0));
+        assert!(!cache.contains("ghost", 3000.0));
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().hits, 1);
+    }
+
+    #[test]
+    fn a_miss_that_raced_an_invalidation_is_not_remembered() {
+        let cache = NegativeCache::new(Duration::from_secs(2));
+        let generation = cache.generation();
+        cache.invalidate("t1");
+        cache.insert("t1", generation, 1000.0);
+        assert!(!cache.contains("t1", 1000.0));
+    }
+}
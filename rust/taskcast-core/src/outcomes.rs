@@ -0,0 +1,283 @@
+//! Outcomes index: a compact [`TaskOutcome`] per task that reached a
+//! terminal status, for integrations that poll for results.
+//!
+//! The engine records an outcome on every terminal transition, best-effort:
+//! a failed write is reported through `on_unhandled_error` and never fails
+//! the transition. An [`OutcomeTrimmer`] drops entries older than the
+//! retention window.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::engine::TaskEngine;
+use crate::filter::matches_type;
+use crate::runner::{runner_fn, RunnerTickReport};
+use crate::state_machine::is_terminal;
+use crate::types::{OutcomeCursor, OutcomeQuery, ShortTermStore, Task, TaskOutcome};
+
+/// How long outcomes are kept by default: 48 hours.
+pub const DEFAULT_OUTCOME_RETENTION_MS: u64 = 48 * 60 * 60 * 1000;
+
+impl TaskOutcome {
+    /// The outcome of `task`, or `None` while it has not ended.
+    pub fn of(task: &Task) -> Option<Self> {
+        if !is_terminal(&task.status) {
+            return None;
+        }
+        Some(Self {
+            task_id: task.id.clone(),
+            r#type: task.r#type.clone(),
+            status: task.status.clone(),
+            completed_at: task.completed_at.unwrap_or(task.updated_at),
+            error_code: task.error.as_ref().and_then(|error| error.code.clone()),
+            origin: Some(task.origin_kind()),
+        })
+    }
+
+    /// Whether `self` sorts after `cursor`.
+    fn is_after(&self, cursor: &OutcomeCursor) -> bool {
+        self.completed_at
+            .total_cmp(&cursor.completed_at)
+            .then_with(|| self.task_id.as_str().cmp(&cursor.task_id))
+            .is_gt()
+    }
+}
+
+impl OutcomeCursor {
+    /// The position of `outcome`, from which the next page starts.
+    pub fn of(outcome: &TaskOutcome) -> Self {
+        Self {
+            completed_at: outcome.completed_at,
+            task_id: outcome.task_id.clone(),
+        }
+    }
+}
+
+/// `{completedAt}:{taskId}`, as handed to clients for the next page.
+impl fmt::Display for OutcomeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.completed_at, self.task_id)
+    }
+}
+
+impl FromStr for OutcomeCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (completed_at, task_id) = s
+            .split_once(':')
+            .filter(|(_, task_id)| !task_id.is_empty())
+            .ok_or_else(|| "must be a cursor from a previous response".to_string())?;
+        let completed_at = completed_at
+            .parse::<f64>()
+            .ok()
+            .filter(|at| at.is_finite())
+            .ok_or_else(|| "must be a cursor from a previous response".to_string())?;
+        Ok(Self {
+            completed_at,
+            task_id: task_id.to_string(),
+        })
+    }
+}
+
+impl OutcomeQuery {
+    /// Whether `outcome` passes every filter. `limit` is left to the caller.
+    pub fn matches(&self, outcome: &TaskOutcome) -> bool {
+        if self
+            .statuses
+            .as_ref()
+            .is_some_and(|statuses| !statuses.contains(&outcome.status))
+        {
+            return false;
+        }
+        if self.types.is_some()
+            && !outcome
+                .r#type
+                .as_deref()
+                .is_some_and(|t| matches_type(t, self.types.as_deref()))
+        {
+            return false;
+        }
+        if self.since.is_some_and(|since| outcome.completed_at < since) {
+            return false;
+        }
+        self.after
+            .as_ref()
+            .is_none_or(|after| outcome.is_after(after))
+    }
+}
+
+// ─── OutcomeTrimmer ──────────────────────────────────────────────────────────
+
+pub struct OutcomeTrimmerOptions {
+    pub engine: Arc<TaskEngine>,
+    pub short_term_store: Arc<dyn ShortTermStore>,
+    /// How often to trim, in milliseconds. Default: 60_000.
+    pub check_interval_ms: u64,
+    /// How long an outcome is kept after its task completed, in
+    /// milliseconds. Default: [`DEFAULT_OUTCOME_RETENTION_MS`].
+    pub retention_ms: u64,
+}
+
+/// Drops outcomes that have outlived the retention window.
+pub struct OutcomeTrimmer {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    check_interval_ms: u64,
+    retention_ms: u64,
+    handle: Option<AbortHandle>,
+}
+
+impl OutcomeTrimmer {
+    pub fn new(opts: OutcomeTrimmerOptions) -> Self {
+        Self {
+            engine: opts.engine,
+            short_term_store: opts.short_term_store,
+            check_interval_ms: opts.check_interval_ms.max(100),
+            retention_ms: opts.retention_ms,
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let engine = self.engine.clone();
+        let store = self.short_term_store.clone();
+        let interval_ms = self.check_interval_ms;
+        let retention_ms = self.retention_ms;
+
+        let runner = runner_fn(
+            "outcomes.trim",
+            Duration::from_millis(interval_ms),
+            move || {
+                let engine = engine.clone();
+                let store = store.clone();
+                async move {
+                    Self::tick_inner(&engine, &store, retention_ms)
+                        .await
+                        .map(RunnerTickReport::processed)
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs one pass immediately. Returns the number of outcomes dropped.
+    pub async fn tick(&self) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Self::tick_inner(&self.engine, &self.short_term_store, self.retention_ms).await
+    }
+
+    async fn tick_inner(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        retention_ms: u64,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let before = engine.clock().now_ms() - retention_ms as f64;
+        store.trim_outcomes(before).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TaskError, TaskStatus};
+
+    fn outcome(task_id: &str, r#type: Option<&str>, status: TaskStatus, at: f64) -> TaskOutcome {
+        TaskOutcome {
+            task_id: task_id.to_string(),
+            r#type: r#type.map(str::to_string),
+            status,
+            completed_at: at,
+            error_code: None,
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn only_ended_tasks_have_an_outcome() {
+        let mut task: Task = serde_json::from_value(serde_json::json!({
+            "id": "t1",
+            "type": "import.csv",
+            "status": "running",
+            "createdAt": 1000.0,
+            "updatedAt": 2000.0,
+        }))
+        .unwrap();
+        assert_eq!(TaskOutcome::of(&task), None);
+
+        task.status = TaskStatus::Failed;
+        task.completed_at = Some(3000.0);
+        task.error = Some(TaskError {
+            code: Some("BAD_ROW".to_string()),
+            message: "row 7".to_string(),
+            details: None,
+        });
+        let recorded = TaskOutcome::of(&task).unwrap();
+        assert_eq!(recorded.completed_at, 3000.0);
+        assert_eq!(recorded.error_code.as_deref(), Some("BAD_ROW"));
+        assert_eq!(recorded.r#type.as_deref(), Some("import.csv"));
+    }
+
+    #[test]
+    fn query_filters_by_status_type_since_and_cursor() {
+        let failed = outcome("b", Some("import.csv"), TaskStatus::Failed, 2000.0);
+        let untyped = outcome("c", None, TaskStatus::Failed, 2000.0);
+
+        assert!(OutcomeQuery::default().matches(&untyped));
+        let query = OutcomeQuery {
+            statuses: Some(vec![TaskStatus::Failed, TaskStatus::Timeout]),
+            types: Some(vec!["import.*".to_string()]),
+            since: Some(2000.0),
+            ..Default::default()
+        };
+        assert!(query.matches(&failed));
+        assert!(!query.matches(&untyped));
+        assert!(!query.matches(&outcome(
+            "b",
+            Some("import.csv"),
+            TaskStatus::Completed,
+            2000.0
+        )));
+        assert!(!query.matches(&outcome(
+            "b",
+            Some("import.csv"),
+            TaskStatus::Failed,
+            1999.0
+        )));
+
+        // Ties on completedAt are broken by task id.
+        let after = |task_id: &str, at: f64| OutcomeQuery {
+            after: Some(OutcomeCursor {
+                completed_at: at,
+                task_id: task_id.to_string(),
+            }),
+            ..Default::default()
+        };
+        assert!(after("a", 2000.0).matches(&failed));
+        assert!(!after("b", 2000.0).matches(&failed));
+        assert!(after("z", 1999.0).matches(&failed));
+        assert!(!after("a", 2001.0).matches(&failed));
+    }
+
+    #[test]
+    fn cursors_round_trip_through_strings() {
+        let cursor = OutcomeCursor {
+            completed_at: 1_700_000_000_123.0,
+            task_id: "ns:01JB".to_string(),
+        };
+        assert_eq!(cursor.to_string(), "1700000000123:ns:01JB");
+        assert_eq!(cursor.to_string().parse::<OutcomeCursor>(), Ok(cursor));
+        for bad in ["", "abc", "12:", "x:id", "inf:id"] {
+            assert!(bad.parse::<OutcomeCursor>().is_err(), "{bad}");
+        }
+    }
+}
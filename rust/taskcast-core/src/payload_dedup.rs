@@ -0,0 +1,120 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Key of the single-field object that stands in for a deduplicated payload
+/// in stored events: `{"$blobRef": "<sha256 hex>"}`.
+pub const BLOB_REF_KEY: &str = "$blobRef";
+
+/// Storage-adapter option for content-addressed deduplication of event
+/// payloads. Payloads whose serialized JSON is at least `min_bytes` long are
+/// stored once per distinct content and referenced from each event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadDedupConfig {
+    pub min_bytes: usize,
+}
+
+/// A payload selected for deduplication: its content hash and the exact JSON
+/// text to store in the blob area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DedupedPayload {
+    pub hash: String,
+    pub json: String,
+}
+
+impl PayloadDedupConfig {
+    /// Returns the blob to store for `data`, or `None` if it should be stored
+    /// inline. A payload that is itself shaped like a blob reference is always
+    /// deduplicated so it cannot be mistaken for one on read.
+    pub fn dedupe(&self, data: &Value) -> Option<DedupedPayload> {
+        if data.is_null() {
+            return None;
+        }
+        let json = serde_json::to_string(data).ok()?;
+        if json.len() < self.min_bytes && blob_ref_hash(data).is_none() {
+            return None;
+        }
+        Some(DedupedPayload {
+            hash: content_hash(&json),
+            json,
+        })
+    }
+}
+
+/// Hex-encoded SHA-256 of a serialized payload.
+pub fn content_hash(json: &str) -> String {
+    hex::encode(Sha256::digest(json.as_bytes()))
+}
+
+/// The stored stand-in for a deduplicated payload.
+pub fn blob_ref(hash: &str) -> Value {
+    serde_json::json!({ BLOB_REF_KEY: hash })
+}
+
+/// Returns the hash if `data` is a blob reference.
+pub fn blob_ref_hash(data: &Value) -> Option<&str> {
+    let object = data.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.get(BLOB_REF_KEY)?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(min_bytes: usize) -> PayloadDedupConfig {
+        PayloadDedupConfig { min_bytes }
+    }
+
+    #[test]
+    fn small_payload_is_stored_inline() {
+        assert_eq!(config(64).dedupe(&json!({ "ok": true })), None);
+    }
+
+    #[test]
+    fn null_payload_is_never_deduplicated() {
+        assert_eq!(config(0).dedupe(&Value::Null), None);
+    }
+
+    #[test]
+    fn large_payload_is_hashed_by_content() {
+        let data = json!({ "blob": "x".repeat(100) });
+        let a = config(64).dedupe(&data).unwrap();
+        let b = config(64).dedupe(&data.clone()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.hash.len(), 64);
+        assert_eq!(a.json, serde_json::to_string(&data).unwrap());
+        assert_eq!(serde_json::from_str::<Value>(&a.json).unwrap(), data);
+
+        let other = config(64)
+            .dedupe(&json!({ "blob": "y".repeat(100) }))
+            .unwrap();
+        assert_ne!(a.hash, other.hash);
+    }
+
+    #[test]
+    fn threshold_is_inclusive() {
+        let data = json!("abcd");
+        let len = serde_json::to_string(&data).unwrap().len();
+        assert!(config(len).dedupe(&data).is_some());
+        assert!(config(len + 1).dedupe(&data).is_none());
+    }
+
+    #[test]
+    fn ref_shaped_payload_is_always_deduplicated() {
+        let data = json!({ "$blobRef": "abc" });
+        assert!(config(1024).dedupe(&data).is_some());
+    }
+
+    #[test]
+    fn blob_ref_round_trip() {
+        let reference = blob_ref("abc123");
+        assert_eq!(reference, json!({ "$blobRef": "abc123" }));
+        assert_eq!(blob_ref_hash(&reference), Some("abc123"));
+        assert_eq!(blob_ref_hash(&json!({ "$blobRef": "a", "x": 1 })), None);
+        assert_eq!(blob_ref_hash(&json!({ "$blobRef": 1 })), None);
+        assert_eq!(blob_ref_hash(&json!("abc123")), None);
+    }
+}
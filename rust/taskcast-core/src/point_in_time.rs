@@ -0,0 +1,323 @@
+//! Point-in-time reads: a task as it was at an earlier moment.
+//!
+//! Status, result and error are event-sourced: every transition emits a
+//! `taskcast:status` event carrying all three, so replaying those events
+//! over the task's creation-time fields gives their value at any moment.
+//! Fields that change without an event keep their current values.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::state_machine::is_terminal;
+use crate::types::{Task, TaskEvent, TaskStatus};
+
+const STATUS_EVENT_TYPE: &str = "taskcast:status";
+
+/// `task` as it was at `as_of` (epoch milliseconds), rebuilt from its
+/// `taskcast:status` events.
+///
+/// Replay starts from the task as created, `pending` (or `scheduled` when it
+/// has `scheduledFor`) with no result or error, and applies each status event
+/// timestamped at or before `as_of` in index order. An event sets the status; its `result` and `error` replace the
+/// previous ones unless they are null, which is how `transition_task` merges
+/// a payload into the task. `updatedAt` is the last applied event's
+/// timestamp, and `completedAt` is set by a terminal status.
+///
+/// `reason`, `blockedRequest` and `resumeAt` are not on status events: they
+/// are the current values when the task has not been updated since `as_of`,
+/// and unset otherwise. Every other field, such as `metadata`, `ttl` or
+/// `assignedWorker`, is the current value. Events of other types, events
+/// after `as_of` and status events whose data does not parse are skipped.
+pub fn reconstruct_task_at(task: &Task, status_events: &[TaskEvent], as_of: f64) -> Task {
+    let mut events: Vec<&TaskEvent> = status_events
+        .iter()
+        .filter(|event| event.r#type == STATUS_EVENT_TYPE)
+        .collect();
+    events.sort_by_key(|event| event.index);
+
+    let mut past = Task {
+        status: initial_status(task),
+        result: None,
+        error: None,
+        updated_at: task.created_at,
+        completed_at: None,
+        reason: None,
+        blocked_request: None,
+        resume_at: None,
+        ..task.clone()
+    };
+    for event in events {
+        if event.timestamp > as_of {
+            continue;
+        }
+        let Some(status) = field::<TaskStatus>(&event.data, "status") else {
+            continue;
+        };
+        if let Some(result) = field(&event.data, "result") {
+            past.result = Some(result);
+        }
+        if let Some(error) = field(&event.data, "error") {
+            past.error = Some(error);
+        }
+        if is_terminal(&status) {
+            past.completed_at = Some(event.timestamp);
+        }
+        past.status = status;
+        past.updated_at = event.timestamp;
+    }
+    if task.updated_at <= as_of {
+        past.reason = task.reason.clone();
+        past.blocked_request = task.blocked_request.clone();
+        past.resume_at = task.resume_at;
+    }
+    past
+}
+
+/// The status `task` was created in.
+fn initial_status(task: &Task) -> TaskStatus {
+    if task.scheduled_for.is_some() {
+        TaskStatus::Scheduled
+    } else {
+        TaskStatus::Pending
+    }
+}
+
+/// `data[key]`, or `None` when it is missing, null or of the wrong shape.
+fn field<T: DeserializeOwned>(data: &Value, key: &str) -> Option<T> {
+    match data.get(key) {
+        None | Some(Value::Null) => None,
+        Some(value) => serde_json::from_value(value.clone()).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{BlockedRequest, Level, TaskError};
+
+    const CREATED: f64 = 1_000.0;
+
+    fn task(status: TaskStatus) -> Task {
+        Task {
+            id: "t1".to_string(),
+            r#type: Some("report".to_string()),
+            status,
+            params: None,
+            result: None,
+            error: None,
+            metadata: Some(HashMap::from([("owner".to_string(), json!("ops"))])),
+            created_at: CREATED,
+            updated_at: CREATED,
+            completed_at: None,
+            ttl: Some(60),
+            auth_config: None,
+            webhooks: None,
+            cleanup: None,
+            tags: None,
+            assign_mode: None,
+            cost: None,
+            assigned_worker: None,
+            disconnect_policy: None,
+            reason: None,
+            resume_at: None,
+            blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
+        }
+    }
+
+    fn status_event(index: u64, timestamp: f64, data: Value) -> TaskEvent {
+        TaskEvent {
+            id: format!("e{index}"),
+            task_id: "t1".to_string(),
+            index,
+            timestamp,
+            r#type: STATUS_EVENT_TYPE.to_string(),
+            level: Level::Info,
+            data,
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    fn result(value: Value) -> Option<HashMap<String, Value>> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// created at 1000, running at 2000 with a partial result, failed at 3000.
+    fn failed_run() -> (Task, Vec<TaskEvent>) {
+        let mut current = task(TaskStatus::Failed);
+        current.result = result(json!({ "rows": 10 }));
+        current.error = Some(TaskError {
+            code: Some("TIMEOUT".to_string()),
+            message: "upstream timed out".to_string(),
+            details: None,
+        });
+        current.completed_at = Some(3_000.0);
+        current.updated_at = 3_000.0;
+        let events = vec![
+            status_event(
+                0,
+                2_000.0,
+                json!({ "status": "running", "result": { "rows": 10 }, "error": null }),
+            ),
+            status_event(
+                2,
+                3_000.0,
+                json!({
+                    "status": "failed",
+                    "result": { "rows": 10 },
+                    "error": { "code": "TIMEOUT", "message": "upstream timed out" },
+                }),
+            ),
+        ];
+        (current, events)
+    }
+
+    #[test]
+    fn before_the_first_transition_the_task_is_pending() {
+        let (current, events) = failed_run();
+        let past = reconstruct_task_at(&current, &events, 1_500.0);
+        assert_eq!(past.status, TaskStatus::Pending);
+        assert_eq!(past.result, None);
+        assert_eq!(past.error, None);
+        assert_eq!(past.updated_at, CREATED);
+        assert_eq!(past.completed_at, None);
+        // Fields that are not event-sourced are the current values.
+        assert_eq!(past.metadata, current.metadata);
+        assert_eq!(past.ttl, Some(60));
+    }
+
+    #[test]
+    fn cut_points_across_a_failed_run() {
+        let (current, events) = failed_run();
+
+        let running = reconstruct_task_at(&current, &events, 2_000.0);
+        assert_eq!(running.status, TaskStatus::Running);
+        assert_eq!(running.result, result(json!({ "rows": 10 })));
+        assert_eq!(running.error, None);
+        assert_eq!(running.updated_at, 2_000.0);
+        assert_eq!(running.completed_at, None);
+
+        assert_eq!(
+            reconstruct_task_at(&current, &events, 2_999.0).status,
+            TaskStatus::Running
+        );
+
+        let failed = reconstruct_task_at(&current, &events, 3_000.0);
+        assert_eq!(failed.status, TaskStatus::Failed);
+        assert_eq!(failed.error.unwrap().code.as_deref(), Some("TIMEOUT"));
+        assert_eq!(failed.completed_at, Some(3_000.0));
+        assert_eq!(failed.result, current.result);
+    }
+
+    #[test]
+    fn a_null_result_or_error_keeps_the_previous_one() {
+        let current = task(TaskStatus::Completed);
+        let events = vec![
+            status_event(
+                0,
+                2_000.0,
+                json!({ "status": "running", "result": { "step": 1 } }),
+            ),
+            status_event(
+                1,
+                2_500.0,
+                json!({ "status": "paused", "result": null, "error": { "message": "slow" } }),
+            ),
+            status_event(2, 2_600.0, json!({ "status": "running", "error": null })),
+            status_event(
+                3,
+                3_000.0,
+                json!({ "status": "completed", "result": { "step": 2 }, "error": null }),
+            ),
+        ];
+
+        let resumed = reconstruct_task_at(&current, &events, 2_700.0);
+        assert_eq!(resumed.status, TaskStatus::Running);
+        assert_eq!(resumed.result, result(json!({ "step": 1 })));
+        assert_eq!(resumed.error.unwrap().message, "slow");
+
+        let done = reconstruct_task_at(&current, &events, 3_000.0);
+        assert_eq!(done.result, result(json!({ "step": 2 })));
+        assert_eq!(done.error.unwrap().message, "slow");
+    }
+
+    #[test]
+    fn events_are_replayed_in_index_order() {
+        let (current, mut events) = failed_run();
+        events.reverse();
+        let past = reconstruct_task_at(&current, &events, 5_000.0);
+        assert_eq!(past.status, TaskStatus::Failed);
+
+        // A later result wins even when its event comes first in the slice.
+        let events = vec![
+            status_event(
+                1,
+                2_100.0,
+                json!({ "status": "running", "result": { "v": 2 } }),
+            ),
+            status_event(
+                0,
+                2_000.0,
+                json!({ "status": "assigned", "result": { "v": 1 } }),
+            ),
+        ];
+        let past = reconstruct_task_at(&task(TaskStatus::Running), &events, 5_000.0);
+        assert_eq!(past.status, TaskStatus::Running);
+        assert_eq!(past.result, result(json!({ "v": 2 })));
+    }
+
+    #[test]
+    fn other_events_and_unparseable_status_events_are_skipped() {
+        let (current, mut events) = failed_run();
+        let mut log = status_event(1, 2_500.0, json!({ "status": "completed" }));
+        log.r#type = "log".to_string();
+        events.push(log);
+        events.push(status_event(3, 2_600.0, json!({ "status": "exploded" })));
+        let past = reconstruct_task_at(&current, &events, 2_700.0);
+        assert_eq!(past.status, TaskStatus::Running);
+        assert_eq!(past.updated_at, 2_000.0);
+    }
+
+    #[test]
+    fn suspension_fields_are_only_kept_while_still_current() {
+        let mut current = task(TaskStatus::Blocked);
+        current.updated_at = 3_000.0;
+        current.reason = Some("needs approval".to_string());
+        current.blocked_request = Some(BlockedRequest {
+            request_type: "approval".to_string(),
+            data: json!({}),
+        });
+        let events = vec![
+            status_event(0, 2_000.0, json!({ "status": "running" })),
+            status_event(1, 3_000.0, json!({ "status": "blocked" })),
+        ];
+
+        let now = reconstruct_task_at(&current, &events, 3_500.0);
+        assert_eq!(now.reason.as_deref(), Some("needs approval"));
+        assert!(now.blocked_request.is_some());
+
+        let earlier = reconstruct_task_at(&current, &events, 2_500.0);
+        assert_eq!(earlier.status, TaskStatus::Running);
+        assert_eq!(earlier.reason, None);
+        assert_eq!(earlier.blocked_request, None);
+    }
+}
@@ -0,0 +1,289 @@
+//! Query latency for stores that talk to a database: a histogram per store
+//! method and a log of the calls slower than a threshold.
+//!
+//! A store owns one [`QueryStats`] and records every call through it. The
+//! snapshot is served by [`LongTermStore::query_stats`](crate::LongTermStore::query_stats).
+//! Slow calls are logged with their method, task id, row count and duration,
+//! never with their SQL or bound values, which can carry payload data.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Default threshold above which a call is logged as slow.
+pub const DEFAULT_SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// Upper bounds of the histogram buckets, in milliseconds. A last, unbounded
+/// bucket holds everything slower.
+pub const LATENCY_BUCKETS_MS: [f64; 12] = [
+    1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0,
+];
+
+// ─── Histogram ──────────────────────────────────────────────────────────────
+
+/// Latency distribution of one method.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS_MS.len() + 1],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&self, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let us = elapsed.as_micros() as u64;
+        self.sum_us.fetch_add(us, Ordering::Relaxed);
+        self.max_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| BucketCount {
+                le_ms: LATENCY_BUCKETS_MS.get(i).copied(),
+                count: count.load(Ordering::Relaxed),
+            })
+            .collect();
+        HistogramSnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            sum_ms: self.sum_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            max_ms: self.max_us.load(Ordering::Relaxed) as f64 / 1000.0,
+            buckets,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum_ms: f64,
+    pub max_ms: f64,
+    /// Calls per bucket (not cumulative), fastest first.
+    pub buckets: Vec<BucketCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketCount {
+    /// Upper bound in milliseconds; `None` for the last, unbounded bucket.
+    pub le_ms: Option<f64>,
+    pub count: u64,
+}
+
+// ─── Slow Query Log ─────────────────────────────────────────────────────────
+
+/// One call slower than the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQuery {
+    /// Unix epoch milliseconds.
+    pub timestamp: f64,
+    pub level: &'static str,
+    pub event: &'static str,
+    /// Store the call went to, e.g. `postgres`.
+    pub store: &'static str,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// Rows returned, when the call reads rows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    pub duration_ms: f64,
+}
+
+pub trait SlowQueryLogger: Send + Sync + 'static {
+    fn log(&self, query: &SlowQuery);
+}
+
+/// Writes each slow query to stderr as one JSON line.
+pub struct StderrSlowQueryLogger;
+
+impl SlowQueryLogger for StderrSlowQueryLogger {
+    fn log(&self, query: &SlowQuery) {
+        eprintln!(
+            "{}",
+            serde_json::to_string(query).expect("SlowQuery contains only serializable fields")
+        );
+    }
+}
+
+/// Keeps slow queries in memory, for tests.
+#[derive(Clone, Default)]
+pub struct CollectingSlowQueryLogger {
+    queries: Arc<Mutex<Vec<SlowQuery>>>,
+}
+
+impl CollectingSlowQueryLogger {
+    pub fn queries(&self) -> Vec<SlowQuery> {
+        self.queries.lock().unwrap().clone()
+    }
+}
+
+impl SlowQueryLogger for CollectingSlowQueryLogger {
+    fn log(&self, query: &SlowQuery) {
+        self.queries.lock().unwrap().push(query.clone());
+    }
+}
+
+// ─── Stats ──────────────────────────────────────────────────────────────────
+
+/// Per-method latency histograms and the slow query log of one store.
+pub struct QueryStats {
+    store: &'static str,
+    methods: Mutex<BTreeMap<&'static str, Arc<LatencyHistogram>>>,
+    slow_threshold: Duration,
+    slow_queries: AtomicU64,
+    logger: Arc<dyn SlowQueryLogger>,
+}
+
+impl QueryStats {
+    /// Stats for `store`, logging calls slower than
+    /// [`DEFAULT_SLOW_QUERY_THRESHOLD`] to stderr.
+    pub fn new(store: &'static str) -> Self {
+        Self {
+            store,
+            methods: Mutex::new(BTreeMap::new()),
+            slow_threshold: DEFAULT_SLOW_QUERY_THRESHOLD,
+            slow_queries: AtomicU64::new(0),
+            logger: Arc::new(StderrSlowQueryLogger),
+        }
+    }
+
+    pub fn with_slow_threshold(self, threshold: Duration) -> Self {
+        Self {
+            slow_threshold: threshold,
+            ..self
+        }
+    }
+
+    pub fn with_logger(self, logger: Arc<dyn SlowQueryLogger>) -> Self {
+        Self { logger, ..self }
+    }
+
+    pub fn slow_threshold(&self) -> Duration {
+        self.slow_threshold
+    }
+
+    /// Records one call of `method`, logging it when it reached the slow
+    /// threshold.
+    pub fn record(
+        &self,
+        method: &'static str,
+        task_id: Option<&str>,
+        rows: Option<u64>,
+        elapsed: Duration,
+    ) {
+        let histogram = Arc::clone(self.methods.lock().unwrap().entry(method).or_default());
+        histogram.record(elapsed);
+        if elapsed < self.slow_threshold {
+            return;
+        }
+        self.slow_queries.fetch_add(1, Ordering::Relaxed);
+        self.logger.log(&SlowQuery {
+            timestamp: now_ms(),
+            level: "warn",
+            event: "store.slow_query",
+            store: self.store,
+            method: method.to_string(),
+            task_id: task_id.map(str::to_string),
+            rows,
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+        });
+    }
+
+    pub fn snapshot(&self) -> QueryStatsSnapshot {
+        let methods = self
+            .methods
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(method, histogram)| (method.to_string(), histogram.snapshot()))
+            .collect();
+        QueryStatsSnapshot {
+            store: self.store,
+            slow_threshold_ms: self.slow_threshold.as_secs_f64() * 1000.0,
+            slow_queries: self.slow_queries.load(Ordering::Relaxed),
+            methods,
+        }
+    }
+}
+
+/// Snapshot of a store's [`QueryStats`], for the stats surface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStatsSnapshot {
+    pub store: &'static str,
+    pub slow_threshold_ms: f64,
+    /// Calls logged as slow since startup.
+    pub slow_queries: u64,
+    pub methods: BTreeMap<String, HistogramSnapshot>,
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_by_upper_bound() {
+        let histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_micros(500));
+        histogram.record(Duration::from_millis(1));
+        histogram.record(Duration::from_millis(30));
+        histogram.record(Duration::from_secs(10));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 4);
+        assert_eq!(snapshot.max_ms, 10_000.0);
+        let counts: Vec<u64> = snapshot.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [2, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(snapshot.buckets.last().unwrap().le_ms, None);
+    }
+
+    #[test]
+    fn slow_calls_are_logged_without_sql() {
+        let logger = CollectingSlowQueryLogger::default();
+        let stats = QueryStats::new("postgres")
+            .with_slow_threshold(Duration::from_millis(100))
+            .with_logger(Arc::new(logger.clone()));
+        stats.record("get_task", Some("t1"), Some(1), Duration::from_millis(5));
+        stats.record(
+            "get_events.full",
+            Some("t1"),
+            Some(40),
+            Duration::from_millis(150),
+        );
+
+        let queries = logger.queries();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].method, "get_events.full");
+        assert_eq!(queries[0].task_id.as_deref(), Some("t1"));
+        assert_eq!(queries[0].rows, Some(40));
+        assert_eq!(queries[0].level, "warn");
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.slow_queries, 1);
+        assert_eq!(snapshot.methods["get_task"].count, 1);
+        assert_eq!(snapshot.methods["get_events.full"].count, 1);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Which store the engine reads terminal tasks and their history from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadPreference {
+    /// Always read the short-term store first.
+    #[default]
+    Short,
+    /// Read terminal tasks from the long-term store.
+    Long,
+    /// Read terminal tasks from the long-term store while the short-term
+    /// store is slow or marked degraded.
+    Adaptive,
+}
+
+/// Read routing options for [`TaskEngine`](crate::TaskEngine).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadRoutingConfig {
+    pub preference: ReadPreference,
+    /// Short-term latency estimate above which `Adaptive` prefers the
+    /// long-term store.
+    pub latency_threshold: Duration,
+    /// While `Adaptive` is routing to the long-term store, every Nth eligible
+    /// read still goes to the short-term store so recovery is noticed.
+    pub probe_every: u64,
+}
+
+impl Default for ReadRoutingConfig {
+    fn default() -> Self {
+        Self {
+            preference: ReadPreference::Short,
+            latency_threshold: Duration::from_millis(50),
+            probe_every: 8,
+        }
+    }
+}
+
+/// Weight of the newest sample in the latency estimate.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Exponentially weighted moving average of call latency, updated lock-free.
+#[derive(Debug, Default)]
+pub struct LatencyEwma {
+    /// `f64` milliseconds, stored as bits.
+    estimate: AtomicU64,
+    samples: AtomicU64,
+}
+
+impl LatencyEwma {
+    pub fn record(&self, elapsed: Duration) {
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        let first = self.samples.fetch_add(1, Ordering::Relaxed) == 0;
+        let _ = self
+            .estimate
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                let next = if first {
+                    sample
+                } else {
+                    let current = f64::from_bits(bits);
+                    current + EWMA_ALPHA * (sample - current)
+                };
+                Some(next.to_bits())
+            });
+    }
+
+    /// Current estimate in milliseconds, or `None` before the first sample.
+    pub fn estimate_ms(&self) -> Option<f64> {
+        if self.samples.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        Some(f64::from_bits(self.estimate.load(Ordering::Relaxed)))
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples.load(Ordering::Relaxed)
+    }
+}
+
+/// Snapshot of read routing state, for the stats surface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadRoutingStats {
+    pub preference: ReadPreference,
+    pub latency_threshold_ms: f64,
+    pub short_term_latency_ms: Option<f64>,
+    pub long_term_latency_ms: Option<f64>,
+    pub short_term_degraded: bool,
+    /// Reads served from the long-term store because of the preference.
+    pub long_term_reads: u64,
+}
+
+/// Tracks store latency and decides where the engine reads terminal tasks
+/// from. Also tracks which tasks still have long-term writes in flight, so a
+/// history read is never routed to a long-term copy that is still catching up.
+#[derive(Debug)]
+pub struct ReadRouter {
+    config: ReadRoutingConfig,
+    short_term: LatencyEwma,
+    long_term: LatencyEwma,
+    short_term_degraded: AtomicBool,
+    eligible_reads: AtomicU64,
+    long_term_reads: AtomicU64,
+    pending_writes: Mutex<HashMap<String, usize>>,
+}
+
+impl ReadRouter {
+    pub fn new(config: ReadRoutingConfig) -> Self {
+        Self {
+            config,
+            short_term: LatencyEwma::default(),
+            long_term: LatencyEwma::default(),
+            short_term_degraded: AtomicBool::new(false),
+            eligible_reads: AtomicU64::new(0),
+            long_term_reads: AtomicU64::new(0),
+            pending_writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ReadRoutingConfig {
+        &self.config
+    }
+
+    pub fn short_term_latency(&self) -> &LatencyEwma {
+        &self.short_term
+    }
+
+    pub fn long_term_latency(&self) -> &LatencyEwma {
+        &self.long_term
+    }
+
+    /// Marks the short-term store degraded (e.g. from an external health
+    /// check). `Adaptive` routes terminal reads away from it until cleared.
+    pub fn set_short_term_degraded(&self, degraded: bool) {
+        self.short_term_degraded.store(degraded, Ordering::Relaxed);
+    }
+
+    pub fn short_term_degraded(&self) -> bool {
+        self.short_term_degraded.load(Ordering::Relaxed)
+    }
+
+    /// Whether the next read of a possibly-terminal task should try the
+    /// long-term store first.
+    pub(crate) fn prefer_long_term(&self) -> bool {
+        match self.config.preference {
+            ReadPreference::Short => false,
+            ReadPreference::Long => true,
+            ReadPreference::Adaptive => {
+                if self.short_term_degraded() {
+                    return true;
+                }
+                let slow = self.short_term.estimate_ms().is_some_and(|latency| {
+                    latency > self.config.latency_threshold.as_secs_f64() * 1000.0
+                });
+                if !slow {
+                    return false;
+                }
+                let n = self.eligible_reads.fetch_add(1, Ordering::Relaxed) + 1;
+                !n.is_multiple_of(self.config.probe_every.max(1))
+            }
+        }
+    }
+
+    pub(crate) fn note_long_term_read(&self) {
+        self.long_term_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Marks a long-term write for `task_id` as in flight until the returned
+    /// guard is dropped.
+    pub(crate) fn begin_write(self: &Arc<Self>, task_id: &str) -> PendingWrite {
+        *self
+            .pending_writes
+            .lock()
+            .unwrap()
+            .entry(task_id.to_string())
+            .or_insert(0) += 1;
+        PendingWrite {
+            router: Arc::clone(self),
+            task_id: task_id.to_string(),
+        }
+    }
+
+    pub fn has_pending_writes(&self, task_id: &str) -> bool {
+        self.pending_writes.lock().unwrap().contains_key(task_id)
+    }
+
+    pub fn stats(&self) -> ReadRoutingStats {
+        ReadRoutingStats {
+            preference: self.config.preference,
+            latency_threshold_ms: self.config.latency_threshold.as_secs_f64() * 1000.0,
+            short_term_latency_ms: self.short_term.estimate_ms(),
+            long_term_latency_ms: self.long_term.estimate_ms(),
+            short_term_degraded: self.short_term_degraded(),
+            long_term_reads: self.long_term_reads.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for ReadRouter {
+    fn default() -> Self {
+        Self::new(ReadRoutingConfig::default())
+    }
+}
+
+/// Guard for an in-flight long-term write; see [`ReadRouter::begin_write`].
+pub(crate) struct PendingWrite {
+    router: Arc<ReadRouter>,
+    task_id: String,
+}
+
+impl Drop for PendingWrite {
+    fn drop(&mut self) {
+        let mut pending = self.router.pending_writes.lock().unwrap();
+        if let Some(count) = pending.get_mut(&self.task_id) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&self.task_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive(threshold_ms: u64, probe_every: u64) -> ReadRouter {
+        ReadRouter::new(ReadRoutingConfig {
+            preference: ReadPreference::Adaptive,
+            latency_threshold: Duration::from_millis(threshold_ms),
+            probe_every,
+        })
+    }
+
+    #[test]
+    fn ewma_starts_at_first_sample_and_moves_toward_new_ones() {
+        let ewma = LatencyEwma::default();
+        assert_eq!(ewma.estimate_ms(), None);
+        ewma.record(Duration::from_millis(10));
+        assert_eq!(ewma.estimate_ms(), Some(10.0));
+        ewma.record(Duration::from_millis(60));
+        assert!((ewma.estimate_ms().unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(ewma.samples(), 2);
+    }
+
+    #[test]
+    fn fixed_preferences_ignore_latency() {
+        let short = ReadRouter::default();
+        short.short_term_latency().record(Duration::from_secs(1));
+        assert!(!short.prefer_long_term());
+
+        let long = ReadRouter::new(ReadRoutingConfig {
+            preference: ReadPreference::Long,
+            ..Default::default()
+        });
+        assert!(long.prefer_long_term());
+    }
+
+    #[test]
+    fn adaptive_routes_long_when_slow_with_periodic_probes() {
+        let router = adaptive(20, 4);
+        assert!(!router.prefer_long_term(), "no samples yet");
+
+        router
+            .short_term_latency()
+            .record(Duration::from_millis(100));
+        let picks: Vec<bool> = (0..8).map(|_| router.prefer_long_term()).collect();
+        assert_eq!(picks, [true, true, true, false, true, true, true, false]);
+
+        for _ in 0..30 {
+            router.short_term_latency().record(Duration::from_millis(1));
+        }
+        assert!(!router.prefer_long_term());
+    }
+
+    #[test]
+    fn adaptive_routes_long_while_degraded() {
+        let router = adaptive(20, 4);
+        router.set_short_term_degraded(true);
+        assert!((0..8).all(|_| router.prefer_long_term()));
+        router.set_short_term_degraded(false);
+        assert!(!router.prefer_long_term());
+    }
+
+    #[test]
+    fn pending_writes_are_tracked_until_guards_drop() {
+        let router = Arc::new(ReadRouter::default());
+        let first = router.begin_write("t1");
+        let second = router.begin_write("t1");
+        drop(first);
+        assert!(router.has_pending_writes("t1"));
+        drop(second);
+        assert!(!router.has_pending_writes("t1"));
+    }
+}
@@ -0,0 +1,194 @@
+//! A read-only view of task state.
+//!
+//! [`TaskcastReader`] is for embedders that should see tasks but never change
+//! them, such as a status widget in an internal tool. It exposes reads,
+//! stats, live streams and point-in-time snapshots, and nothing that creates,
+//! transitions or publishes:
+//!
+//! ```compile_fail
+//! # async fn f(reader: taskcast_core::TaskcastReader) {
+//! reader.create_task(Default::default()).await;
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use crate::engine::{EngineError, TaskEngine, TaskEngineOptions};
+use crate::event_stream::TaskEventStream;
+use crate::types::{EventQueryOptions, EventTypeCounts, SubscribeFilter, Task, TaskEvent};
+
+/// Reads tasks and their events through a [`TaskEngine`] it never hands out.
+#[derive(Clone)]
+pub struct TaskcastReader {
+    engine: Arc<TaskEngine>,
+}
+
+impl TaskcastReader {
+    /// A reader over the given stores and broadcast provider, with none of
+    /// the engine's background work.
+    pub fn new(opts: TaskEngineOptions) -> Self {
+        Self {
+            engine: Arc::new(TaskEngine::new(opts)),
+        }
+    }
+
+    pub(crate) fn from_engine(engine: Arc<TaskEngine>) -> Self {
+        Self { engine }
+    }
+
+    pub async fn get_task(&self, task_id: &str) -> Result<Option<Task>, EngineError> {
+        self.engine.get_task(task_id).await
+    }
+
+    pub async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, EngineError> {
+        self.engine.get_events(task_id, opts).await
+    }
+
+    /// Type and level counts of the task's events.
+    pub async fn get_task_stats(&self, task_id: &str) -> Result<EventTypeCounts, EngineError> {
+        self.engine
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        self.engine.event_type_counts(task_id).await
+    }
+
+    /// The task's events matching `filter`: its history, then live events
+    /// until it ends.
+    pub async fn subscribe_stream(
+        &self,
+        task_id: &str,
+        filter: SubscribeFilter,
+    ) -> Result<TaskEventStream, EngineError> {
+        self.engine.subscribe_stream(task_id, filter).await
+    }
+
+    /// The task as it was at `as_of` (epoch milliseconds). See
+    /// [`TaskEngine::get_task_as_of`].
+    pub async fn get_task_as_of(
+        &self,
+        task_id: &str,
+        as_of: f64,
+    ) -> Result<Option<Task>, EngineError> {
+        self.engine.get_task_as_of(task_id, as_of).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{CreateTaskInput, PublishEventInput};
+    use crate::event_stream::StreamItem;
+    use crate::memory_adapters::{MemoryBroadcastProvider, MemoryShortTermStore};
+    use crate::types::{Level, TaskStatus};
+    use futures::StreamExt;
+
+    fn options(store: Arc<MemoryShortTermStore>) -> TaskEngineOptions {
+        TaskEngineOptions {
+            short_term_store: store,
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        }
+    }
+
+    fn progress() -> PublishEventInput {
+        PublishEventInput {
+            r#type: "progress".to_string(),
+            level: Level::Info,
+            data: serde_json::json!({ "percent": 50 }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
+        }
+    }
+
+    async fn running_task(engine: &TaskEngine) {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+        engine.publish_event("t1", progress()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_what_the_engine_wrote() {
+        let engine = Arc::new(TaskEngine::new(options(Arc::new(
+            MemoryShortTermStore::new(),
+        ))));
+        running_task(&engine).await;
+        let reader = engine.reader();
+
+        let task = reader.get_task("t1").await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Running);
+        let events = reader.get_events("t1", None).await.unwrap();
+        assert_eq!(events.last().unwrap().r#type, "progress");
+        let stats = reader.get_task_stats("t1").await.unwrap();
+        assert_eq!(stats.types.get("progress"), Some(&1));
+        let snapshot = reader
+            .get_task_as_of("t1", task.updated_at)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.status, TaskStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn missing_tasks_have_no_stats() {
+        let reader = TaskcastReader::new(options(Arc::new(MemoryShortTermStore::new())));
+        assert!(reader.get_task("nope").await.unwrap().is_none());
+        assert!(matches!(
+            reader.get_task_stats("nope").await,
+            Err(EngineError::TaskNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_reader_over_shared_adapters_streams_live_events() {
+        let store = Arc::new(MemoryShortTermStore::new());
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let engine = TaskEngine::new(TaskEngineOptions {
+            broadcast: broadcast.clone(),
+            ..options(Arc::clone(&store))
+        });
+        running_task(&engine).await;
+        let reader = TaskcastReader::new(TaskEngineOptions {
+            broadcast,
+            ..options(Arc::clone(&store))
+        });
+
+        let mut stream = reader
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+        let mut types = Vec::new();
+        while let Some(item) = stream.next().await {
+            if let StreamItem::Event(event) = item {
+                types.push(event.r#type);
+            }
+        }
+        assert!(types.contains(&"progress".to_string()));
+        assert_eq!(types.last().map(String::as_str), Some("taskcast:status"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Erasing events from a task's history, e.g. for a data erasure request.
+//!
+//! Each selected event is overwritten in every store by a tombstone: a stub
+//! of type [`REDACTED_EVENT_TYPE`] that keeps the event's id, index and
+//! timestamp and carries only the deletion's reason. Indices stay
+//! contiguous, so cursors and history reads across the erased range keep
+//! working. Once the stores are rewritten, an event of the same type
+//! announces the deleted ids so live subscribers can purge their copies.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::filter::{matches_labels, matches_type};
+use crate::types::{Level, TaskEvent};
+
+/// Type of tombstones and of the event announcing a deletion.
+pub const REDACTED_EVENT_TYPE: &str = "taskcast:redacted";
+
+/// Selects the events `TaskEngine::delete_events` erases: either by id or by
+/// filter, never both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteEventsInput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<DeleteEventsFilter>,
+    /// Recorded in each tombstone and in the announcing event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Events matching every given field are erased.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteEventsFilter {
+    /// Type patterns, as in subscription filters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub levels: Option<Vec<Level>>,
+    /// Epoch milliseconds; events at or after it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<f64>,
+    /// Epoch milliseconds; events at or before it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<f64>,
+    /// Equality selector on event labels; every entry must match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+}
+
+/// What `TaskEngine::delete_events` erased.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedEvents {
+    pub task_id: String,
+    /// Ids of the erased events, in index order.
+    pub event_ids: Vec<String>,
+    /// Events replaced by a tombstone in the short-term store.
+    pub short_term: u64,
+    /// Events replaced in the long-term store, `None` without one.
+    pub long_term: Option<u64>,
+    /// Index of the event announcing the deletion, `None` when nothing
+    /// matched.
+    pub notification_index: Option<u64>,
+}
+
+impl DeleteEventsInput {
+    /// Rejects an input selecting by both ids and filter, or by neither.
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.event_ids, &self.filter) {
+            (Some(_), Some(_)) => Err("Give either eventIds or filter, not both".to_string()),
+            (None, None) => Err("Give eventIds or filter".to_string()),
+            (Some(ids), None) if ids.is_empty() => Err("eventIds must not be empty".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `event` is erased. Events the engine publishes itself, whose
+    /// types start with `taskcast:`, never are.
+    pub fn matches(&self, event: &TaskEvent) -> bool {
+        if event.r#type.starts_with("taskcast:") {
+            return false;
+        }
+        if let Some(ref ids) = self.event_ids {
+            return ids.contains(&event.id);
+        }
+        let Some(ref filter) = self.filter else {
+            return false;
+        };
+        matches_type(&event.r#type, filter.types.as_deref())
+            && filter
+                .levels
+                .as_ref()
+                .is_none_or(|levels| levels.contains(&event.level))
+            && filter.since.is_none_or(|since| event.timestamp >= since)
+            && filter.until.is_none_or(|until| event.timestamp <= until)
+            && filter
+                .labels
+                .as_ref()
+                .is_none_or(|selector| matches_labels(event.labels.as_ref(), selector))
+    }
+}
+
+/// The stub that replaces `event`: same id, task, index and timestamp, with
+/// `data` holding only `reason`.
+pub fn tombstone(event: &TaskEvent, reason: Option<&str>) -> TaskEvent {
+    TaskEvent {
+        id: event.id.clone(),
+        task_id: event.task_id.clone(),
+        index: event.index,
+        timestamp: event.timestamp,
+        r#type: REDACTED_EVENT_TYPE.to_string(),
+        level: Level::Info,
+        data: json!({ "reason": reason }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SeriesMode;
+
+    fn make_event(id: &str, r#type: &str, timestamp: f64) -> TaskEvent {
+        TaskEvent {
+            id: id.to_string(),
+            task_id: "task_01".to_string(),
+            index: 3,
+            timestamp,
+            r#type: r#type.to_string(),
+            level: Level::Warn,
+            data: json!({ "email": "someone@example.com" }),
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::Accumulate),
+            series_acc_field: Some("text".to_string()),
+            series_snapshot: None,
+            labels: Some(HashMap::from([("user".to_string(), "u-1".to_string())])),
+            replaces_event_id: None,
+            _accumulated_data: None,
+        }
+    }
+
+    #[test]
+    fn validate_requires_exactly_one_selector() {
+        assert!(DeleteEventsInput::default().validate().is_err());
+        assert!(DeleteEventsInput {
+            event_ids: Some(vec![]),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(DeleteEventsInput {
+            event_ids: Some(vec!["e1".to_string()]),
+            filter: Some(DeleteEventsFilter::default()),
+            reason: None,
+        }
+        .validate()
+        .is_err());
+        assert!(DeleteEventsInput {
+            filter: Some(DeleteEventsFilter::default()),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn filter_fields_must_all_match() {
+        let event = make_event("e1", "llm.chunk", 1_000.0);
+        let by = |filter: DeleteEventsFilter| {
+            DeleteEventsInput {
+                filter: Some(filter),
+                ..Default::default()
+            }
+            .matches(&event)
+        };
+        assert!(by(DeleteEventsFilter {
+            types: Some(vec!["llm.*".to_string()]),
+            levels: Some(vec![Level::Warn]),
+            since: Some(1_000.0),
+            until: Some(1_000.0),
+            labels: Some(HashMap::from([("user".to_string(), "u-1".to_string())])),
+        }));
+        assert!(!by(DeleteEventsFilter {
+            types: Some(vec!["log".to_string()]),
+            ..Default::default()
+        }));
+        assert!(!by(DeleteEventsFilter {
+            since: Some(1_001.0),
+            ..Default::default()
+        }));
+        assert!(!by(DeleteEventsFilter {
+            labels: Some(HashMap::from([("user".to_string(), "u-2".to_string())])),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn engine_events_are_never_selected() {
+        let input = DeleteEventsInput {
+            event_ids: Some(vec!["e1".to_string()]),
+            ..Default::default()
+        };
+        assert!(input.matches(&make_event("e1", "log", 0.0)));
+        assert!(!input.matches(&make_event("e1", "taskcast:status", 0.0)));
+        assert!(!input.matches(&make_event("e1", REDACTED_EVENT_TYPE, 0.0)));
+    }
+
+    #[test]
+    fn tombstone_keeps_position_and_drops_content() {
+        let event = make_event("e1", "llm.chunk", 1_000.0);
+        let stub = tombstone(&event, Some("erasure request"));
+        assert_eq!(
+            serde_json::to_value(&stub).unwrap(),
+            json!({
+                "id": "e1",
+                "taskId": "task_01",
+                "index": 3,
+                "timestamp": 1_000.0,
+                "type": REDACTED_EVENT_TYPE,
+                "level": "info",
+                "data": { "reason": "erasure request" },
+            })
+        );
+    }
+}
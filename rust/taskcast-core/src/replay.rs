@@ -0,0 +1,634 @@
+//! Chunked, budgeted history replay for new subscribers.
+//!
+//! History is read from the stores a chunk at a time through
+//! [`EventChunks`] rather than in one `get_events` call. Under a
+//! [`ReplayBudget`] only the most recent events that fit are replayed, led by
+//! a [`StreamItem::Truncated`] item saying how many were left out; without
+//! one, every chunk is yielded as soon as it is read.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::Serialize;
+
+use crate::config::SseConfig;
+use crate::engine::{EngineError, TaskEngine};
+use crate::event_stream::{envelope_for, StreamItem};
+use crate::filter::matches_filter;
+use crate::series::SeriesTotals;
+use crate::types::{
+    EventQueryOptions, SSEEnvelope, SeriesMode, SinceCursor, SubscribeFilter, TaskEvent,
+};
+
+/// Events replayed on connect when `sse.maxReplayEvents` is unset.
+pub const DEFAULT_MAX_REPLAY_EVENTS: u64 = 10_000;
+
+/// Events read per `get_events` call while replaying.
+pub const DEFAULT_REPLAY_CHUNK_SIZE: u64 = 1_000;
+
+// ─── Options ────────────────────────────────────────────────────────────────
+
+/// How much of a task's filtered history is replayed to a new subscriber.
+/// An unset limit is unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayBudget {
+    pub max_events: Option<u64>,
+    /// Total size of the replayed events, measured as serialized envelopes.
+    pub max_bytes: Option<u64>,
+}
+
+impl ReplayBudget {
+    /// A budget that replays the whole history.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// The budget set by the `sse` config section. `maxReplayEvents`
+    /// defaults to [`DEFAULT_MAX_REPLAY_EVENTS`].
+    pub fn from_config(config: Option<&SseConfig>) -> Self {
+        Self {
+            max_events: Some(
+                config
+                    .and_then(|c| c.max_replay_events)
+                    .unwrap_or(DEFAULT_MAX_REPLAY_EVENTS),
+            ),
+            max_bytes: config.and_then(|c| c.max_replay_bytes),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_events.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// Options for [`TaskEngine::subscribe_stream_with_replay`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    /// Read at most this many stored events, counted from the start of the
+    /// history or the filter's cursor.
+    pub history_limit: Option<u64>,
+    pub budget: ReplayBudget,
+    /// Events read per `get_events` call.
+    pub chunk_size: u64,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            history_limit: None,
+            budget: ReplayBudget::unlimited(),
+            chunk_size: DEFAULT_REPLAY_CHUNK_SIZE,
+        }
+    }
+}
+
+/// Reported ahead of a replay the budget cut short.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayTruncation {
+    /// Events matching the filter that were left out of the replay.
+    pub omitted_count: u64,
+    /// Raw index of the first replayed event, or `None` if no event fit.
+    pub earliest_sent_index: Option<u64>,
+}
+
+// ─── Chunked Reads ──────────────────────────────────────────────────────────
+
+/// A task's history read a chunk at a time, as returned by
+/// [`TaskEngine::event_chunks`].
+#[derive(Clone)]
+pub struct EventChunks {
+    engine: Arc<TaskEngine>,
+    task_id: String,
+    /// The query for the next chunk; its `limit` is what is left of the
+    /// caller's.
+    query: EventQueryOptions,
+    chunk_size: u64,
+    last_index: Option<u64>,
+    exhausted: bool,
+}
+
+impl EventChunks {
+    pub(crate) fn new(
+        engine: Arc<TaskEngine>,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+        chunk_size: u64,
+    ) -> Self {
+        Self {
+            engine,
+            task_id: task_id.to_string(),
+            query: opts.unwrap_or_default(),
+            chunk_size: chunk_size.max(1),
+            last_index: None,
+            exhausted: false,
+        }
+    }
+
+    /// The next chunk of events in index order, or `None` once the history
+    /// or the query's `limit` is used up.
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<TaskEvent>>, EngineError> {
+        if self.exhausted {
+            return Ok(None);
+        }
+        let limit = self
+            .query
+            .limit
+            .map_or(self.chunk_size, |remaining| remaining.min(self.chunk_size));
+        if limit == 0 {
+            self.exhausted = true;
+            return Ok(None);
+        }
+        let chunk = self
+            .engine
+            .get_events(
+                &self.task_id,
+                Some(EventQueryOptions {
+                    limit: Some(limit),
+                    ..self.query.clone()
+                }),
+            )
+            .await?;
+        let Some(last) = chunk.last() else {
+            self.exhausted = true;
+            return Ok(None);
+        };
+
+        // Whatever cursor the first read used, later ones resume after the
+        // last event read.
+        self.query.since = Some(SinceCursor {
+            id: None,
+            index: Some(last.index),
+            timestamp: None,
+        });
+        self.last_index = Some(last.index);
+        if let Some(remaining) = self.query.limit.as_mut() {
+            *remaining -= chunk.len() as u64;
+        }
+        self.exhausted = (chunk.len() as u64) < limit;
+        Ok(Some(chunk))
+    }
+
+    /// Index of the last event read so far.
+    pub fn last_index(&self) -> Option<u64> {
+        self.last_index
+    }
+}
+
+// ─── Replay Sources ─────────────────────────────────────────────────────────
+
+/// Where replayed events come from: a history shaped up front, or chunks
+/// shaped as they are read.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum ReplaySource {
+    Loaded(Option<Vec<TaskEvent>>),
+    Chunked {
+        chunks: EventChunks,
+        /// Set for late joiners, who get accumulate series as snapshots.
+        collapse: Option<AccumulateCollapse>,
+        /// Set for series views that carry running totals.
+        totals: Option<SeriesTotals>,
+    },
+}
+
+impl ReplaySource {
+    pub(crate) fn loaded(events: Vec<TaskEvent>) -> Self {
+        Self::Loaded(Some(events))
+    }
+
+    pub(crate) fn chunked(chunks: EventChunks, collapse: bool, totals: bool) -> Self {
+        Self::Chunked {
+            chunks,
+            collapse: collapse.then(AccumulateCollapse::default),
+            totals: totals.then(SeriesTotals::default),
+        }
+    }
+
+    /// The next events to replay, with the index of the last stored event
+    /// read so far.
+    async fn next(&mut self) -> Result<Option<(Vec<TaskEvent>, Option<u64>)>, EngineError> {
+        match self {
+            Self::Loaded(events) => Ok(events.take().map(|events| {
+                let through = events.iter().map(|e| e.index).max();
+                (events, through)
+            })),
+            Self::Chunked {
+                chunks,
+                collapse,
+                totals,
+            } => {
+                let Some(chunk) = chunks.next_chunk().await? else {
+                    return Ok(None);
+                };
+                let mut events = match collapse {
+                    Some(collapse) => collapse.apply(chunk, chunks).await,
+                    None => chunk,
+                };
+                if let Some(totals) = totals {
+                    totals.attach(&mut events);
+                }
+                Ok(Some((events, chunks.last_index())))
+            }
+        }
+    }
+}
+
+/// Collapses accumulate series into snapshots across chunks, the way
+/// `collapse_accumulate_series` does for a whole history: each series is
+/// replaced by its latest value at the position of its first event.
+#[derive(Default)]
+pub(crate) struct AccumulateCollapse {
+    /// The snapshot of each series seen so far, `None` if it is replayed as is.
+    snapshots: HashMap<String, Option<TaskEvent>>,
+    emitted: HashSet<String>,
+    /// The last event of each series from where the first series without a
+    /// stored latest turned up to the end of the history.
+    tails: Option<HashMap<String, TaskEvent>>,
+}
+
+impl AccumulateCollapse {
+    async fn apply(&mut self, chunk: Vec<TaskEvent>, rest: &EventChunks) -> Vec<TaskEvent> {
+        let mut result = Vec::with_capacity(chunk.len());
+        for (position, event) in chunk.iter().enumerate() {
+            let series_id = match (&event.series_mode, &event.series_id) {
+                (Some(SeriesMode::Accumulate), Some(series_id)) => series_id,
+                _ => {
+                    result.push(event.clone());
+                    continue;
+                }
+            };
+            if !self.snapshots.contains_key(series_id) {
+                let snapshot = self
+                    .snapshot(&event.task_id, series_id, &chunk[position..], rest)
+                    .await;
+                self.snapshots.insert(series_id.clone(), snapshot);
+            }
+            match &self.snapshots[series_id] {
+                Some(snapshot) => {
+                    if self.emitted.insert(series_id.clone()) {
+                        result.push(snapshot.clone());
+                    }
+                }
+                None => result.push(event.clone()),
+            }
+        }
+        result
+    }
+
+    /// The series latest, or for a cold task the series' last event, found
+    /// by reading ahead from `ahead`, the unreplayed part of the chunk.
+    async fn snapshot(
+        &mut self,
+        task_id: &str,
+        series_id: &str,
+        ahead: &[TaskEvent],
+        rest: &EventChunks,
+    ) -> Option<TaskEvent> {
+        let snapshot = match rest.engine.get_series_latest(task_id, series_id).await {
+            Ok(Some(latest)) => Some(latest),
+            Ok(None) => self.tail(series_id, ahead, rest).await,
+            Err(_) => None,
+        };
+        snapshot.map(|mut snapshot| {
+            snapshot.series_snapshot = Some(true);
+            snapshot
+        })
+    }
+
+    async fn tail(
+        &mut self,
+        series_id: &str,
+        ahead: &[TaskEvent],
+        rest: &EventChunks,
+    ) -> Option<TaskEvent> {
+        if self.tails.is_none() {
+            let mut tails = HashMap::new();
+            let mut record = |events: &[TaskEvent]| {
+                for event in events {
+                    if let Some(ref sid) = event.series_id {
+                        tails.insert(sid.clone(), event.clone());
+                    }
+                }
+            };
+            record(ahead);
+            let mut rest = rest.clone();
+            loop {
+                match rest.next_chunk().await {
+                    Ok(Some(chunk)) => record(&chunk),
+                    Ok(None) => break,
+                    // Series left without a snapshot are replayed as is.
+                    Err(_) => {
+                        tails.clear();
+                        break;
+                    }
+                }
+            }
+            self.tails = Some(tails);
+        }
+        self.tails.as_ref()?.get(series_id).cloned()
+    }
+}
+
+// ─── Replay Stream ──────────────────────────────────────────────────────────
+
+/// Replayed items, with where the replay has got to.
+pub(crate) struct ReplayBatch {
+    pub(crate) items: Vec<StreamItem>,
+    pub(crate) next_filtered_index: u64,
+    pub(crate) replayed_through: Option<u64>,
+}
+
+/// The replay of `source` under `filter`, cut to `budget`. Ends after an
+/// [`StreamItem::Error`] item if reading fails.
+pub(crate) fn replay_stream(
+    source: ReplaySource,
+    filter: SubscribeFilter,
+    budget: ReplayBudget,
+) -> BoxStream<'static, ReplayBatch> {
+    let since_index = filter.since.as_ref().and_then(|s| s.index);
+    let state = ReplayState {
+        source,
+        indexer: FilteredIndexer {
+            filter,
+            since_index,
+            next: 0,
+        },
+        budget,
+        replayed_through: None,
+        finished: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        let batch = state.step().await?;
+        Some((batch, state))
+    })
+    .boxed()
+}
+
+struct ReplayState {
+    source: ReplaySource,
+    indexer: FilteredIndexer,
+    budget: ReplayBudget,
+    replayed_through: Option<u64>,
+    finished: bool,
+}
+
+impl ReplayState {
+    async fn step(&mut self) -> Option<ReplayBatch> {
+        if self.finished {
+            return None;
+        }
+        if self.budget.is_unlimited() {
+            return match self.source.next().await {
+                Ok(Some((events, through))) => {
+                    self.replayed_through = through;
+                    let items = events
+                        .iter()
+                        .filter_map(|event| self.indexer.envelope(event))
+                        .map(StreamItem::Event)
+                        .collect();
+                    Some(self.batch(items))
+                }
+                Ok(None) => None,
+                Err(e) => Some(self.fail(e)),
+            };
+        }
+
+        // Only the tail is replayed, so the whole history is read first.
+        let mut window = ReplayWindow::new(self.budget);
+        loop {
+            match self.source.next().await {
+                Ok(Some((events, through))) => {
+                    self.replayed_through = through;
+                    for event in &events {
+                        if let Some(envelope) = self.indexer.envelope(event) {
+                            window.push(envelope);
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => return Some(self.fail(e)),
+            }
+        }
+        self.finished = true;
+        Some(self.batch(window.into_items()))
+    }
+
+    fn fail(&mut self, error: EngineError) -> ReplayBatch {
+        self.finished = true;
+        self.batch(vec![StreamItem::Error(error)])
+    }
+
+    fn batch(&self, items: Vec<StreamItem>) -> ReplayBatch {
+        ReplayBatch {
+            items,
+            next_filtered_index: self.indexer.next,
+            replayed_through: self.replayed_through,
+        }
+    }
+}
+
+/// Assigns filtered indices across chunks, as `apply_filtered_index` does
+/// for a whole history.
+struct FilteredIndexer {
+    filter: SubscribeFilter,
+    since_index: Option<u64>,
+    next: u64,
+}
+
+impl FilteredIndexer {
+    fn envelope(&mut self, event: &TaskEvent) -> Option<SSEEnvelope> {
+        if !matches_filter(event, &self.filter) {
+            return None;
+        }
+        let filtered_index = self.next;
+        self.next += 1;
+        if self
+            .since_index
+            .is_some_and(|since| filtered_index <= since)
+        {
+            return None;
+        }
+        Some(envelope_for(event, filtered_index, &self.filter))
+    }
+}
+
+/// The most recent envelopes that fit a budget.
+struct ReplayWindow {
+    budget: ReplayBudget,
+    envelopes: VecDeque<(SSEEnvelope, u64)>,
+    bytes: u64,
+    omitted: u64,
+}
+
+impl ReplayWindow {
+    fn new(budget: ReplayBudget) -> Self {
+        Self {
+            budget,
+            envelopes: VecDeque::new(),
+            bytes: 0,
+            omitted: 0,
+        }
+    }
+
+    fn push(&mut self, envelope: SSEEnvelope) {
+        let size = match self.budget.max_bytes {
+            Some(_) => serde_json::to_vec(&envelope).map_or(0, |json| json.len() as u64),
+            None => 0,
+        };
+        self.envelopes.push_back((envelope, size));
+        self.bytes += size;
+        while self.over_budget() {
+            let Some((_, size)) = self.envelopes.pop_front() else {
+                break;
+            };
+            self.bytes -= size;
+            self.omitted += 1;
+        }
+    }
+
+    fn over_budget(&self) -> bool {
+        self.budget
+            .max_events
+            .is_some_and(|max| self.envelopes.len() as u64 > max)
+            || self.budget.max_bytes.is_some_and(|max| self.bytes > max)
+    }
+
+    fn into_items(self) -> Vec<StreamItem> {
+        let truncation = (self.omitted > 0).then(|| ReplayTruncation {
+            omitted_count: self.omitted,
+            earliest_sent_index: self.envelopes.front().map(|(e, _)| e.raw_index),
+        });
+        truncation
+            .map(StreamItem::Truncated)
+            .into_iter()
+            .chain(
+                self.envelopes
+                    .into_iter()
+                    .map(|(envelope, _)| StreamItem::Event(envelope)),
+            )
+            .collect()
+    }
+}
+
+// ─── Unit Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Level;
+
+    fn envelope(index: u64, text: &str) -> SSEEnvelope {
+        SSEEnvelope {
+            filtered_index: index,
+            raw_index: index,
+            event_id: format!("e{index}"),
+            task_id: "t1".to_string(),
+            r#type: "log".to_string(),
+            timestamp: 1000.0,
+            level: Level::Info,
+            data: serde_json::json!({ "text": text }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            filter_epoch: None,
+        }
+    }
+
+    fn raw_indices(items: &[StreamItem]) -> Vec<u64> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                StreamItem::Event(envelope) => Some(envelope.raw_index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn budget_from_config_defaults_the_event_limit_only() {
+        assert_eq!(
+            ReplayBudget::from_config(None),
+            ReplayBudget {
+                max_events: Some(DEFAULT_MAX_REPLAY_EVENTS),
+                max_bytes: None,
+            }
+        );
+        let config = SseConfig {
+            max_replay_events: Some(5),
+            max_replay_bytes: Some(100),
+            heartbeat_interval_ms: None,
+        };
+        assert_eq!(
+            ReplayBudget::from_config(Some(&config)),
+            ReplayBudget {
+                max_events: Some(5),
+                max_bytes: Some(100),
+            }
+        );
+        assert!(ReplayBudget::unlimited().is_unlimited());
+    }
+
+    #[test]
+    fn window_keeps_the_most_recent_events_within_the_count() {
+        let mut window = ReplayWindow::new(ReplayBudget {
+            max_events: Some(3),
+            max_bytes: None,
+        });
+        for index in 0..10 {
+            window.push(envelope(index, "x"));
+        }
+        let items = window.into_items();
+        assert_eq!(
+            items.first().and_then(|item| match item {
+                StreamItem::Truncated(t) => Some(t.clone()),
+                _ => None,
+            }),
+            Some(ReplayTruncation {
+                omitted_count: 7,
+                earliest_sent_index: Some(7),
+            })
+        );
+        assert_eq!(raw_indices(&items), vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn window_keeps_the_most_recent_events_within_the_bytes() {
+        let size = serde_json::to_vec(&envelope(0, "x")).unwrap().len() as u64;
+        let mut window = ReplayWindow::new(ReplayBudget {
+            max_events: None,
+            max_bytes: Some(size * 2),
+        });
+        for index in 0..5 {
+            window.push(envelope(index, "x"));
+        }
+        assert_eq!(raw_indices(&window.into_items()), vec![3, 4]);
+
+        // An event larger than the whole budget is left out too.
+        let mut window = ReplayWindow::new(ReplayBudget {
+            max_events: None,
+            max_bytes: Some(size),
+        });
+        window.push(envelope(0, &"x".repeat(100)));
+        let items = window.into_items();
+        assert!(matches!(
+            items.as_slice(),
+            [StreamItem::Truncated(ReplayTruncation {
+                omitted_count: 1,
+                earliest_sent_index: None,
+            })]
+        ));
+    }
+
+    #[test]
+    fn window_within_budget_is_not_truncated() {
+        let mut window = ReplayWindow::new(ReplayBudget::from_config(None));
+        window.push(envelope(0, "x"));
+        let items = window.into_items();
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0], StreamItem::Event(_)));
+    }
+}
@@ -0,0 +1,203 @@
+//! Deadlines of the HTTP request a read serves, so expensive reads stop once
+//! the client can no longer use their answer.
+//!
+//! The server runs a deadline-aware handler inside [`RequestDeadline::scope`].
+//! Stores wrap their queries in [`limit`] and paged loops call
+//! [`RequestDeadline::check`] between pages; both fail with
+//! [`DeadlineExceeded`] once the deadline has passed. [`RequestCounters`]
+//! tells reads cut short by their deadline apart from reads abandoned by
+//! their client.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::time::{Duration, Instant};
+
+tokio::task_local! {
+    static CURRENT: RequestDeadline;
+}
+
+/// When the request being served must have its answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestDeadline {
+    at: Instant,
+    budget: Duration,
+}
+
+impl RequestDeadline {
+    /// A deadline `budget` from now.
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            at: Instant::now() + budget,
+            budget,
+        }
+    }
+
+    /// The deadline of the request being served on this task, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(|deadline| *deadline).ok()
+    }
+
+    pub fn at(&self) -> Instant {
+        self.at
+    }
+
+    /// The time the request was given.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Fails once the deadline has passed.
+    pub fn check(&self) -> Result<(), DeadlineExceeded> {
+        if Instant::now() >= self.at {
+            return Err(self.exceeded());
+        }
+        Ok(())
+    }
+
+    /// Runs `fut` with this as the [`current`](Self::current) deadline.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+
+    /// Runs `fut`, dropping it when the deadline passes first.
+    pub async fn run<F: Future>(&self, fut: F) -> Result<F::Output, DeadlineExceeded> {
+        tokio::time::timeout_at(self.at, fut)
+            .await
+            .map_err(|_| self.exceeded())
+    }
+
+    fn exceeded(&self) -> DeadlineExceeded {
+        DeadlineExceeded {
+            budget_ms: self.budget.as_millis() as u64,
+        }
+    }
+}
+
+/// Runs a store query under the deadline of the request being served, if
+/// any, dropping it when the deadline passes first.
+pub async fn limit<T, F>(query: F) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
+where
+    F: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    match RequestDeadline::current() {
+        Some(deadline) => deadline.run(query).await?,
+        None => query.await,
+    }
+}
+
+/// A read stopped because its request's deadline passed. Stores return it
+/// boxed, like any other store error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Request deadline of {budget_ms} ms exceeded")]
+pub struct DeadlineExceeded {
+    pub budget_ms: u64,
+}
+
+// ─── Counters ───────────────────────────────────────────────────────────────
+
+/// Deadline-aware requests that did not run to the end, for the stats
+/// surface.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestCancellationStats {
+    /// Requests dropped before they were answered, or exports whose body was
+    /// dropped before it ended, because the client went away.
+    pub cancelled_by_client: u64,
+    /// Requests answered with a deadline error.
+    pub deadline_exceeded: u64,
+}
+
+/// Counts how deadline-aware requests ended early.
+#[derive(Debug, Default)]
+pub struct RequestCounters {
+    cancelled_by_client: AtomicU64,
+    deadline_exceeded: AtomicU64,
+}
+
+impl RequestCounters {
+    /// Starts tracking a request. Dropping the guard before
+    /// [`InFlightRequest::finish`] counts it as cancelled by its client.
+    pub fn begin(self: &Arc<Self>) -> InFlightRequest {
+        InFlightRequest {
+            counters: Arc::clone(self),
+            finished: false,
+        }
+    }
+
+    pub fn record_deadline_exceeded(&self) {
+        self.deadline_exceeded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> RequestCancellationStats {
+        RequestCancellationStats {
+            cancelled_by_client: self.cancelled_by_client.load(Ordering::Relaxed),
+            deadline_exceeded: self.deadline_exceeded.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A request being served; see [`RequestCounters::begin`].
+#[derive(Debug)]
+pub struct InFlightRequest {
+    counters: Arc<RequestCounters>,
+    finished: bool,
+}
+
+impl InFlightRequest {
+    /// The request ran to the end, whatever its outcome.
+    pub fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.counters
+                .cancelled_by_client
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn store_queries_stop_at_the_current_deadline() {
+        let deadline = RequestDeadline::after(Duration::from_millis(100));
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(1)
+        };
+        let err = deadline.scope(limit(slow)).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DeadlineExceeded>(),
+            Some(&DeadlineExceeded { budget_ms: 100 })
+        );
+        assert!(deadline.check().is_err());
+
+        // Outside a scope, queries run unbounded.
+        let fast = async { Ok::<_, Box<dyn std::error::Error + Send + Sync>>(2) };
+        assert_eq!(limit(fast).await.unwrap(), 2);
+    }
+
+    #[test]
+    fn dropped_requests_count_as_cancelled() {
+        let counters = Arc::new(RequestCounters::default());
+        counters.begin().finish();
+        drop(counters.begin());
+        counters.record_deadline_exceeded();
+        assert_eq!(
+            counters.stats(),
+            RequestCancellationStats {
+                cancelled_by_client: 1,
+                deadline_exceeded: 1,
+            }
+        );
+    }
+}
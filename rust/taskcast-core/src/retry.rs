@@ -0,0 +1,213 @@
+//! Automatic re-runs of tasks that declare a [`RetryPolicy`].
+//!
+//! When such a task ends in a status its policy covers, the engine saves a
+//! [`RetrySchedule`] in the short-term store and emits
+//! `taskcast:retry-scheduled` on the ended task. A [`RetryRunner`] on any
+//! instance later claims the due schedule and creates the successor task.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
+
+use crate::engine::{CreateTaskInput, TaskEngine};
+use crate::runner::{runner_fn, RunnerTickReport};
+use crate::types::{
+    RetrySchedule, ShortTermStore, Task, TaskOrigin, TaskOriginKind, TaskStatus,
+};
+
+pub const RETRY_SCHEDULED_EVENT: &str = "taskcast:retry-scheduled";
+/// Metadata key on a successor holding the id of the task it retries.
+pub const RETRY_OF_KEY: &str = "retryOf";
+/// Metadata key on a successor holding its attempt number.
+pub const RETRY_ATTEMPT_KEY: &str = "retryAttempt";
+
+// ─── Clock ───────────────────────────────────────────────────────────────────
+
+/// Source of the current time for retry due times.
+pub trait Clock: Send + Sync {
+    /// Milliseconds since the Unix epoch.
+    fn now_ms(&self) -> f64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time before UNIX epoch")
+            .as_millis() as f64
+    }
+}
+
+// ─── Lineage ─────────────────────────────────────────────────────────────────
+
+/// Attempt number of `task`: 1 for an original task, otherwise the number
+/// recorded in its metadata when it was created as a successor.
+pub fn retry_attempt(task: &Task) -> u32 {
+    task.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(RETRY_ATTEMPT_KEY))
+        .and_then(|attempt| attempt.as_u64())
+        .map_or(1, |attempt| attempt as u32)
+}
+
+/// Id of the task `task` retries, if it is a successor.
+pub fn retry_of(task: &Task) -> Option<&str> {
+    task.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(RETRY_OF_KEY))
+        .and_then(|id| id.as_str())
+}
+
+/// Input for the successor of `task`: its definition copied, linked back to
+/// it through `retryOf` and `retryAttempt` metadata.
+pub(crate) fn successor_input(task: &Task, schedule: &RetrySchedule) -> CreateTaskInput {
+    let mut metadata = task.metadata.clone().unwrap_or_default();
+    metadata.insert(RETRY_OF_KEY.to_string(), task.id.clone().into());
+    metadata.insert(RETRY_ATTEMPT_KEY.to_string(), schedule.attempt.into());
+    CreateTaskInput {
+        id: Some(schedule.successor_id.clone()),
+        r#type: task.r#type.clone(),
+        params: task.params.clone(),
+        metadata: Some(metadata),
+        ttl: task.ttl,
+        webhooks: task.webhooks.clone(),
+        cleanup: task.cleanup.clone(),
+        auth_config: task.auth_config.clone(),
+        tags: task.tags.clone(),
+        assign_mode: task.assign_mode.clone(),
+        cost: task.cost,
+        disconnect_policy: task.disconnect_policy.clone(),
+        filters: task.filters.clone(),
+        retry_policy: task.retry_policy.clone(),
+        group_policy: task.group_policy,
+        forward_to: task.forward_to.clone(),
+        scheduled_for: None,
+        deadline_ms: task.deadline_ms,
+        origin: Some(TaskOrigin::new(TaskOriginKind::Retry).with_source_task_id(&task.id)),
+        parent_id: None,
+        completion_policy: None,
+    }
+}
+
+/// One run in a retry chain.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttempt {
+    pub attempt: u32,
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub created_at: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<f64>,
+}
+
+impl From<&Task> for TaskAttempt {
+    fn from(task: &Task) -> Self {
+        Self {
+            attempt: retry_attempt(task),
+            task_id: task.id.clone(),
+            status: task.status.clone(),
+            created_at: task.created_at,
+            completed_at: task.completed_at,
+        }
+    }
+}
+
+/// A task's retry chain, oldest first, plus the successor still waiting to
+/// be created, if any.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttempts {
+    pub attempts: Vec<TaskAttempt>,
+    pub scheduled: Option<RetrySchedule>,
+}
+
+// ─── RetryRunner ─────────────────────────────────────────────────────────────
+
+pub struct RetryRunnerOptions {
+    pub engine: Arc<TaskEngine>,
+    pub short_term_store: Arc<dyn ShortTermStore>,
+    /// How often to look for due retries, in milliseconds. Default: 1_000.
+    pub check_interval_ms: u64,
+    /// How long a claimed retry stays reserved for this instance. A claim
+    /// that is not completed in time (e.g. the instance crashed) can be taken
+    /// by another instance. Default: 30_000.
+    pub claim_lease_ms: u64,
+}
+
+/// Creates successor tasks for due [`RetrySchedule`]s.
+pub struct RetryRunner {
+    engine: Arc<TaskEngine>,
+    short_term_store: Arc<dyn ShortTermStore>,
+    check_interval_ms: u64,
+    claim_lease_ms: u64,
+    handle: Option<AbortHandle>,
+}
+
+impl RetryRunner {
+    pub fn new(opts: RetryRunnerOptions) -> Self {
+        Self {
+            engine: opts.engine,
+            short_term_store: opts.short_term_store,
+            check_interval_ms: opts.check_interval_ms.max(100),
+            claim_lease_ms: opts.claim_lease_ms,
+            handle: None,
+        }
+    }
+
+    pub fn start(&mut self) {
+        let engine = self.engine.clone();
+        let store = self.short_term_store.clone();
+        let interval_ms = self.check_interval_ms;
+        let lease_ms = self.claim_lease_ms;
+
+        let runner = runner_fn(
+            "retry.loop",
+            Duration::from_millis(interval_ms),
+            move || {
+                let engine = engine.clone();
+                let store = store.clone();
+                async move {
+                    Self::tick_inner(&engine, &store, lease_ms)
+                        .await
+                        .map(|created| RunnerTickReport::processed(created as u64))
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Runs one pass immediately. Returns the number of retries completed.
+    pub async fn tick(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        Self::tick_inner(&self.engine, &self.short_term_store, self.claim_lease_ms).await
+    }
+
+    async fn tick_inner(
+        engine: &TaskEngine,
+        store: &Arc<dyn ShortTermStore>,
+        lease_ms: u64,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let now = engine.clock().now_ms();
+        let mut completed = 0;
+        for schedule in store.list_due_retries(now).await? {
+            if !store.claim_retry(&schedule.task_id, now, lease_ms).await? {
+                continue;
+            }
+            engine.create_retry_successor(&schedule).await?;
+            store.delete_retry_schedule(&schedule.task_id).await?;
+            completed += 1;
+        }
+        Ok(completed)
+    }
+}
@@ -0,0 +1,813 @@
+//! Periodic background runners and the supervisor that runs them.
+//!
+//! Every periodic loop (retries, activations, cleanup, deadline warnings,
+//! storage sweeps, ...) is a [`Runner`]: a name, an interval and one pass of
+//! work. [`RunnerSupervisor`] owns the loops. It ticks each runner on a
+//! jittered interval, records how every pass went, restarts a runner whose
+//! pass panicked after a backoff, and lets operators pause, resume or run a
+//! runner right away. A standby instance holds all of its runners at once.
+//!
+//! The supervisor needs no runtime until the first runner is started, so an
+//! engine can be built outside one.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::{oneshot, watch, Notify};
+use tokio::task::AbortHandle;
+
+use crate::background::BackgroundTasks;
+use crate::backoff::strategy_delay_ms;
+use crate::retry::Clock;
+use crate::types::{BackoffStrategy, ErrorContext, TaskcastHooks};
+
+/// Fraction of the interval each wait is randomly lengthened or shortened
+/// by, so instances started together do not tick in lockstep.
+pub const DEFAULT_RUNNER_JITTER: f64 = 0.1;
+
+/// Delay before the first pass after a panic; it doubles with each further
+/// consecutive failure, up to the runner's interval.
+const RESTART_INITIAL_DELAY_MS: u64 = 1_000;
+
+// ─── Runner ──────────────────────────────────────────────────────────────────
+
+/// What one pass of a runner did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerTickReport {
+    /// Items the pass acted on: tasks activated, outcomes trimmed, files
+    /// swept and so on.
+    pub processed: u64,
+}
+
+impl RunnerTickReport {
+    pub fn processed(processed: u64) -> Self {
+        Self { processed }
+    }
+}
+
+/// A periodic background job.
+#[async_trait]
+pub trait Runner: Send + Sync + 'static {
+    /// Unique name, used in status reports, control routes and error
+    /// contexts.
+    fn name(&self) -> &'static str;
+
+    /// Time between passes, before jitter.
+    fn interval(&self) -> Duration;
+
+    /// Runs one pass.
+    async fn tick(&self) -> Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A [`Runner`] whose pass is a closure, for loops whose state is a handful
+/// of cloned handles.
+pub struct FnRunner<F> {
+    name: &'static str,
+    interval: Duration,
+    tick: F,
+}
+
+/// Builds a [`FnRunner`] calling `tick` for every pass.
+pub fn runner_fn<F, Fut>(name: &'static str, interval: Duration, tick: F) -> FnRunner<F>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>>>
+        + Send
+        + 'static,
+{
+    FnRunner {
+        name,
+        interval,
+        tick,
+    }
+}
+
+#[async_trait]
+impl<F, Fut> Runner for FnRunner<F>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>>>
+        + Send
+        + 'static,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn tick(&self) -> Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>> {
+        (self.tick)().await
+    }
+}
+
+// ─── Status ──────────────────────────────────────────────────────────────────
+
+/// How a runner's pass ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RunnerOutcome {
+    Ok,
+    Failed,
+    Panicked,
+}
+
+/// A runner's schedule and the record of its recent passes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerStatus {
+    pub name: String,
+    pub interval_ms: u64,
+    /// Whether its loop is up. A stopped runner keeps its last status.
+    pub running: bool,
+    /// Paused runners skip their scheduled passes but still run on demand.
+    pub paused: bool,
+    pub runs: u64,
+    /// Epoch milliseconds the last pass started at.
+    pub last_run_at: Option<f64>,
+    pub last_duration_ms: Option<u64>,
+    pub last_outcome: Option<RunnerOutcome>,
+    pub last_report: Option<RunnerTickReport>,
+    pub last_error: Option<String>,
+    /// Failed or panicked passes since the last successful one.
+    pub consecutive_failures: u32,
+    /// Times the runner was restarted after a panicking pass.
+    pub restarts: u32,
+}
+
+impl RunnerStatus {
+    fn new(name: &str, interval: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            interval_ms: interval.as_millis() as u64,
+            running: true,
+            paused: false,
+            runs: 0,
+            last_run_at: None,
+            last_duration_ms: None,
+            last_outcome: None,
+            last_report: None,
+            last_error: None,
+            consecutive_failures: 0,
+            restarts: 0,
+        }
+    }
+}
+
+/// Why a runner could not be controlled.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RunnerError {
+    #[error("Runner not found: {0}")]
+    NotFound(String),
+    #[error("Runner is not running: {0}")]
+    NotRunning(String),
+}
+
+// ─── Supervisor ──────────────────────────────────────────────────────────────
+
+/// One registered runner: its status and the channels into its loop.
+struct Slot {
+    status: Mutex<RunnerStatus>,
+    /// Woken for run-now requests and shutdown.
+    wake: Notify,
+    run_now: Mutex<Vec<oneshot::Sender<RunnerStatus>>>,
+    handle: Mutex<Option<AbortHandle>>,
+}
+
+impl Slot {
+    fn status(&self) -> RunnerStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+struct Inner {
+    background: BackgroundTasks,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    clock: Arc<dyn Clock>,
+    jitter: f64,
+    slots: Mutex<BTreeMap<&'static str, Arc<Slot>>>,
+    shutting_down: AtomicBool,
+    /// Set while every runner is held; see [`RunnerSupervisor::hold`].
+    held: watch::Sender<bool>,
+}
+
+/// Runs [`Runner`]s and keeps their status.
+///
+/// Each runner's loop is spawned on [`BackgroundTasks`] under the runner's
+/// name and every pass on a task of its own, so a panicking pass is
+/// reported through `TaskcastHooks::on_unhandled_error` and the loop starts
+/// the next pass after a backoff instead of dying with it.
+#[derive(Clone)]
+pub struct RunnerSupervisor {
+    inner: Arc<Inner>,
+}
+
+impl RunnerSupervisor {
+    pub fn new(
+        background: BackgroundTasks,
+        hooks: Option<Arc<dyn TaskcastHooks>>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                background,
+                hooks,
+                clock,
+                jitter: DEFAULT_RUNNER_JITTER,
+                slots: Mutex::new(BTreeMap::new()),
+                shutting_down: AtomicBool::new(false),
+                held: watch::Sender::new(false),
+            }),
+        }
+    }
+
+    /// Sets the jitter fraction (0 for exact intervals). Only takes effect
+    /// before any runner is started.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            inner.jitter = jitter.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// Starts `runner`: a first pass right away, then one every jittered
+    /// interval. A runner already started under the same name is stopped
+    /// and replaced, keeping its pause. The returned handle stops the loop.
+    pub fn start(&self, runner: Arc<dyn Runner>) -> AbortHandle {
+        let name = runner.name();
+        let mut status = RunnerStatus::new(name, runner.interval());
+        let previous = self.inner.slots.lock().unwrap().remove(name);
+        if let Some(previous) = previous {
+            status.paused = previous.status().paused;
+            if let Some(handle) = previous.handle.lock().unwrap().take() {
+                handle.abort();
+            }
+        }
+        let slot = Arc::new(Slot {
+            status: Mutex::new(status),
+            wake: Notify::new(),
+            run_now: Mutex::new(Vec::new()),
+            handle: Mutex::new(None),
+        });
+        self.inner
+            .slots
+            .lock()
+            .unwrap()
+            .insert(name, Arc::clone(&slot));
+
+        let handle = self.inner.background.spawn(
+            name,
+            None,
+            run_loop(Arc::clone(&self.inner), Stopped(Arc::clone(&slot)), runner),
+        );
+        *slot.handle.lock().unwrap() = Some(handle.clone());
+        handle
+    }
+
+    /// The spawner the runner loops are supervised on.
+    pub(crate) fn background(&self) -> &BackgroundTasks {
+        &self.inner.background
+    }
+
+    /// Every started runner's status, by name.
+    pub fn statuses(&self) -> Vec<RunnerStatus> {
+        self.inner
+            .slots
+            .lock()
+            .unwrap()
+            .values()
+            .map(|slot| slot.status())
+            .collect()
+    }
+
+    pub fn status(&self, name: &str) -> Option<RunnerStatus> {
+        self.slot(name).ok().map(|slot| slot.status())
+    }
+
+    /// Skips the runner's scheduled passes until it is resumed.
+    pub fn pause(&self, name: &str) -> Result<RunnerStatus, RunnerError> {
+        self.set_paused(name, true)
+    }
+
+    pub fn resume(&self, name: &str) -> Result<RunnerStatus, RunnerError> {
+        self.set_paused(name, false)
+    }
+
+    /// Holds every runner, started or not, until [`release`](Self::release):
+    /// scheduled passes are skipped and scheduled passes in progress are
+    /// cancelled. Unlike a pause, it applies to all runners and leaves their
+    /// own pause alone. Run-now requests are still served.
+    pub fn hold(&self) {
+        self.inner.held.send_replace(true);
+    }
+
+    /// Lifts [`hold`](Self::hold), running every runner right away.
+    pub fn release(&self) {
+        if !self.inner.held.send_replace(false) {
+            return;
+        }
+        for slot in self.inner.slots.lock().unwrap().values() {
+            slot.wake.notify_one();
+        }
+    }
+
+    pub fn is_held(&self) -> bool {
+        *self.inner.held.borrow()
+    }
+
+    /// Runs a pass now, even if the runner is paused, and returns its status
+    /// once the pass is done. A request made during a pass is served right
+    /// after it.
+    pub async fn run_now(&self, name: &str) -> Result<RunnerStatus, RunnerError> {
+        let slot = self.slot(name)?;
+        let (tx, rx) = oneshot::channel();
+        {
+            // Checked under the queue lock, which the loop clears when it
+            // exits, so a request is never left unanswered.
+            let mut queue = slot.run_now.lock().unwrap();
+            if !slot.status().running {
+                return Err(RunnerError::NotRunning(name.to_string()));
+            }
+            queue.push(tx);
+        }
+        slot.wake.notify_one();
+        rx.await
+            .map_err(|_| RunnerError::NotRunning(name.to_string()))
+    }
+
+    /// Stops every runner, letting passes in progress finish for up to
+    /// `timeout` before they are cancelled. Returns `true` if all finished.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.inner.shutting_down.store(true, Ordering::SeqCst);
+        let slots: Vec<Arc<Slot>> = self.inner.slots.lock().unwrap().values().cloned().collect();
+        for slot in &slots {
+            slot.wake.notify_one();
+        }
+        let stopped = tokio::time::timeout(timeout, async {
+            while slots.iter().any(|slot| slot.status().running) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .is_ok();
+        for slot in &slots {
+            if let Some(handle) = slot.handle.lock().unwrap().take() {
+                handle.abort();
+            }
+        }
+        stopped
+    }
+
+    fn slot(&self, name: &str) -> Result<Arc<Slot>, RunnerError> {
+        self.inner
+            .slots
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RunnerError::NotFound(name.to_string()))
+    }
+
+    fn set_paused(&self, name: &str, paused: bool) -> Result<RunnerStatus, RunnerError> {
+        let slot = self.slot(name)?;
+        let mut status = slot.status.lock().unwrap();
+        status.paused = paused;
+        Ok(status.clone())
+    }
+}
+
+impl std::fmt::Debug for RunnerSupervisor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunnerSupervisor")
+            .field("runners", &self.inner.slots.lock().unwrap().len())
+            .finish()
+    }
+}
+
+/// Marks the slot stopped and drops queued run-now requests however the
+/// loop ends, including by abort.
+struct Stopped(Arc<Slot>);
+
+impl Drop for Stopped {
+    fn drop(&mut self) {
+        let mut queue = self.0.run_now.lock().unwrap();
+        self.0.status.lock().unwrap().running = false;
+        queue.clear();
+    }
+}
+
+/// Aborts a spawned pass when the loop awaiting it is cancelled.
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+// The guard is an argument rather than a local so that it is also dropped
+// when the loop is aborted before its first poll.
+async fn run_loop(inner: Arc<Inner>, _stopped: Stopped, runner: Arc<dyn Runner>) {
+    let slot = Arc::clone(&_stopped.0);
+    let name = runner.name();
+    let interval = runner.interval();
+    let mut rng = fastrand::Rng::new();
+    let mut delay = Duration::ZERO;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = slot.wake.notified() => {}
+        }
+        if inner.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+        let waiters = std::mem::take(&mut *slot.run_now.lock().unwrap());
+        delay = jittered(interval, inner.jitter, &mut rng);
+        if waiters.is_empty() && (slot.status().paused || *inner.held.borrow()) {
+            continue;
+        }
+
+        let started_at = inner.clock.now_ms();
+        let started = Instant::now();
+        let pass = {
+            let runner = Arc::clone(&runner);
+            tokio::spawn(async move { runner.tick().await })
+        };
+        let _guard = AbortOnDrop(pass.abort_handle());
+        let mut held = inner.held.subscribe();
+        let result = tokio::select! {
+            result = pass => result,
+            // A hold cancels a scheduled pass; the guard aborts it.
+            _ = held.wait_for(|held| *held), if waiters.is_empty() => continue,
+        };
+        let elapsed = started.elapsed();
+
+        let status = {
+            let mut status = slot.status.lock().unwrap();
+            status.runs += 1;
+            status.last_run_at = Some(started_at);
+            status.last_duration_ms = Some(elapsed.as_millis() as u64);
+            match result {
+                Ok(Ok(report)) => {
+                    status.last_outcome = Some(RunnerOutcome::Ok);
+                    status.last_report = Some(report);
+                    status.last_error = None;
+                    status.consecutive_failures = 0;
+                }
+                Ok(Err(err)) => {
+                    status.last_outcome = Some(RunnerOutcome::Failed);
+                    status.last_error = Some(err.to_string());
+                    status.consecutive_failures += 1;
+                }
+                Err(err) => {
+                    status.last_outcome = Some(RunnerOutcome::Panicked);
+                    status.last_error = Some(err.to_string());
+                    status.consecutive_failures += 1;
+                    status.restarts += 1;
+                    if err.is_panic() {
+                        if let Some(ref hooks) = inner.hooks {
+                            let context = ErrorContext {
+                                operation: name.to_string(),
+                                task_id: None,
+                            };
+                            hooks.on_unhandled_error(&err, &context);
+                        }
+                    }
+                    delay = restart_delay(interval, status.consecutive_failures);
+                }
+            }
+            status.clone()
+        };
+        inner
+            .background
+            .record_run(name, status.last_outcome == Some(RunnerOutcome::Ok));
+        for waiter in waiters {
+            let _ = waiter.send(status.clone());
+        }
+    }
+}
+
+/// `interval` lengthened or shortened by up to `jitter` of itself.
+fn jittered(interval: Duration, jitter: f64, rng: &mut fastrand::Rng) -> Duration {
+    if jitter <= 0.0 {
+        return interval;
+    }
+    interval.mul_f64(1.0 + jitter * (rng.f64() * 2.0 - 1.0))
+}
+
+/// Wait before the pass after `failures` consecutive failures ending in a
+/// panic: exponential from one second, capped at the interval.
+fn restart_delay(interval: Duration, failures: u32) -> Duration {
+    let delay_ms = strategy_delay_ms(
+        &BackoffStrategy::Exponential,
+        RESTART_INITIAL_DELAY_MS,
+        failures,
+    );
+    Duration::from_millis(delay_ms).min(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    struct FixedClock;
+
+    impl Clock for FixedClock {
+        fn now_ms(&self) -> f64 {
+            1_700_000_000_000.0
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingHooks {
+        errors: Mutex<Vec<String>>,
+    }
+
+    impl TaskcastHooks for RecordingHooks {
+        fn on_unhandled_error(
+            &self,
+            _err: &(dyn std::error::Error + Send + Sync),
+            context: &ErrorContext,
+        ) {
+            self.errors.lock().unwrap().push(context.operation.clone());
+        }
+    }
+
+    fn supervisor(hooks: Option<Arc<dyn TaskcastHooks>>) -> RunnerSupervisor {
+        RunnerSupervisor::new(BackgroundTasks::new(None), hooks, Arc::new(FixedClock))
+            .with_jitter(0.0)
+    }
+
+    /// Counts its passes; fails or panics on the passes listed.
+    fn counting(
+        name: &'static str,
+        interval_ms: u64,
+        fail_on: &'static [u64],
+        panic_on: &'static [u64],
+    ) -> (Arc<dyn Runner>, Arc<AtomicU64>) {
+        let passes = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&passes);
+        let runner = runner_fn(name, Duration::from_millis(interval_ms), move || {
+            let pass = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if panic_on.contains(&pass) {
+                    panic!("pass {pass} exploded");
+                }
+                if fail_on.contains(&pass) {
+                    return Err(format!("pass {pass} failed").into());
+                }
+                Ok(RunnerTickReport::processed(pass))
+            }
+        });
+        (Arc::new(runner), passes)
+    }
+
+    /// Lets spawned passes run to completion on the paused clock.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ticks_at_once_then_every_interval() {
+        let runners = supervisor(None);
+        let (runner, passes) = counting("test.every", 1_000, &[], &[]);
+        runners.start(runner);
+
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_millis(999)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_millis(1)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 2);
+
+        // Each wait starts when the previous pass ends.
+        for _ in 0..3 {
+            tokio::time::advance(Duration::from_millis(1_000)).await;
+            settle().await;
+        }
+        let status = runners.status("test.every").unwrap();
+        assert_eq!(status.runs, 5);
+        assert_eq!(status.last_run_at, Some(1_700_000_000_000.0));
+        assert_eq!(status.last_outcome, Some(RunnerOutcome::Ok));
+        assert_eq!(status.last_report, Some(RunnerTickReport::processed(5)));
+        assert_eq!(status.interval_ms, 1_000);
+    }
+
+    #[test]
+    fn jitter_stays_within_its_fraction() {
+        let mut rng = fastrand::Rng::with_seed(7);
+        let interval = Duration::from_millis(1_000);
+        for _ in 0..100 {
+            let delay = jittered(interval, 0.1, &mut rng);
+            assert!(delay >= Duration::from_millis(900) && delay <= Duration::from_millis(1_100));
+        }
+        assert_eq!(jittered(interval, 0.0, &mut rng), interval);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_panicking_pass_is_reported_and_retried_after_a_backoff() {
+        let hooks = Arc::new(RecordingHooks::default());
+        let runners = supervisor(Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+        let (runner, passes) = counting("test.panics", 60_000, &[], &[1, 2]);
+        runners.start(runner);
+
+        settle().await;
+        let status = runners.status("test.panics").unwrap();
+        assert_eq!(status.last_outcome, Some(RunnerOutcome::Panicked));
+        assert_eq!(status.restarts, 1);
+        assert!(status.running);
+        assert!(status.last_error.unwrap().contains("pass 1 exploded"));
+        assert_eq!(*hooks.errors.lock().unwrap(), ["test.panics"]);
+
+        // Restarted after 1s, then 2s, well before the 60s interval.
+        tokio::time::advance(Duration::from_millis(1_000)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            runners.status("test.panics").unwrap().consecutive_failures,
+            2
+        );
+
+        tokio::time::advance(Duration::from_millis(2_000)).await;
+        settle().await;
+        let status = runners.status("test.panics").unwrap();
+        assert_eq!(passes.load(Ordering::SeqCst), 3);
+        assert_eq!(status.last_outcome, Some(RunnerOutcome::Ok));
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.restarts, 2);
+    }
+
+    #[test]
+    fn restart_delay_doubles_up_to_the_interval() {
+        let interval = Duration::from_secs(5);
+        assert_eq!(restart_delay(interval, 1), Duration::from_secs(1));
+        assert_eq!(restart_delay(interval, 2), Duration::from_secs(2));
+        assert_eq!(restart_delay(interval, 3), Duration::from_secs(4));
+        assert_eq!(restart_delay(interval, 4), interval);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_now_ticks_immediately_and_returns_the_pass() {
+        let runners = supervisor(None);
+        let (runner, passes) = counting("test.now", 60_000, &[2], &[]);
+        runners.start(runner);
+        settle().await;
+
+        let status = runners.run_now("test.now").await.unwrap();
+        assert_eq!(passes.load(Ordering::SeqCst), 2);
+        assert_eq!(status.runs, 2);
+        assert_eq!(status.last_outcome, Some(RunnerOutcome::Failed));
+        assert_eq!(status.last_error.as_deref(), Some("pass 2 failed"));
+        assert_eq!(status.consecutive_failures, 1);
+
+        assert_eq!(
+            runners.run_now("missing").await,
+            Err(RunnerError::NotFound("missing".to_string()))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paused_runners_skip_scheduled_passes_until_resumed() {
+        let runners = supervisor(None);
+        let (runner, passes) = counting("test.pause", 1_000, &[], &[]);
+        runners.start(runner);
+        settle().await;
+
+        assert!(runners.pause("test.pause").unwrap().paused);
+        tokio::time::advance(Duration::from_millis(3_000)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 1);
+
+        // Still runs on demand.
+        runners.run_now("test.pause").await.unwrap();
+        assert_eq!(passes.load(Ordering::SeqCst), 2);
+
+        assert!(!runners.resume("test.pause").unwrap().paused);
+        tokio::time::advance(Duration::from_millis(1_000)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn held_runners_skip_passes_and_run_at_once_when_released() {
+        let runners = supervisor(None);
+        runners.hold();
+        let (runner, passes) = counting("test.hold", 60_000, &[], &[]);
+        runners.start(runner);
+        runners.pause("test.hold").unwrap();
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 0);
+
+        // A release leaves the runner's own pause in place.
+        runners.release();
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 0);
+
+        runners.resume("test.hold").unwrap();
+        runners.hold();
+        tokio::time::advance(Duration::from_millis(120_000)).await;
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 0);
+        assert!(runners.is_held());
+
+        // No need to wait out the interval.
+        runners.release();
+        settle().await;
+        assert_eq!(passes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_hold_cancels_the_pass_in_progress() {
+        let runners = supervisor(None);
+        let finished = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&finished);
+        let runner = runner_fn("test.cancel", Duration::from_secs(60), move || {
+            let flag = Arc::clone(&flag);
+            async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                flag.store(true, Ordering::SeqCst);
+                Ok(RunnerTickReport::default())
+            }
+        });
+        runners.start(Arc::new(runner));
+        settle().await;
+
+        runners.hold();
+        tokio::time::advance(Duration::from_millis(100)).await;
+        settle().await;
+        assert!(!finished.load(Ordering::SeqCst));
+        let status = runners.status("test.cancel").unwrap();
+        assert!(status.running);
+        assert_eq!(status.runs, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stopped_runners_keep_their_status_and_refuse_run_now() {
+        let runners = supervisor(None);
+        let (runner, _) = counting("test.stop", 1_000, &[], &[]);
+        let handle = runners.start(runner);
+        settle().await;
+
+        handle.abort();
+        settle().await;
+        let status = runners.status("test.stop").unwrap();
+        assert!(!status.running);
+        assert_eq!(status.runs, 1);
+        assert_eq!(
+            runners.run_now("test.stop").await,
+            Err(RunnerError::NotRunning("test.stop".to_string()))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn runners_aborted_before_their_first_pass_are_stopped() {
+        let runners = supervisor(None);
+        let (runner, _) = counting("test.early", 1_000, &[], &[]);
+        runners.start(runner).abort();
+        settle().await;
+
+        let status = runners.status("test.early").unwrap();
+        assert!(!status.running);
+        assert_eq!(status.runs, 0);
+        assert_eq!(
+            runners.run_now("test.early").await,
+            Err(RunnerError::NotRunning("test.early".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_lets_the_current_pass_finish() {
+        let runners = supervisor(None);
+        let finished = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&finished);
+        let runner = runner_fn("test.slow", Duration::from_secs(60), move || {
+            let flag = Arc::clone(&flag);
+            async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                flag.store(true, Ordering::SeqCst);
+                Ok(RunnerTickReport::default())
+            }
+        });
+        runners.start(Arc::new(runner));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(runners.shutdown(Duration::from_secs(5)).await);
+        assert!(finished.load(Ordering::SeqCst));
+        assert!(!runners.status("test.slow").unwrap().running);
+    }
+}
@@ -1,10 +1,11 @@
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use tokio::task::JoinHandle;
-use tokio::time::{interval, Duration};
+use tokio::task::AbortHandle;
+use tokio::time::Duration;
 
 use crate::engine::{PublishEventInput, TaskEngine};
+use crate::runner::{runner_fn, RunnerTickReport};
 use crate::types::{Level, ShortTermStore, TaskStatus};
 
 // ─── Options ─────────────────────────────────────────────────────────────────
@@ -28,7 +29,7 @@ pub struct TaskScheduler {
     check_interval_ms: u64,
     paused_cold_after_ms: Option<u64>,
     blocked_cold_after_ms: Option<u64>,
-    handle: Option<JoinHandle<()>>,
+    handle: Option<AbortHandle>,
 }
 
 impl TaskScheduler {
@@ -50,13 +51,20 @@ impl TaskScheduler {
         let paused_cold = self.paused_cold_after_ms;
         let blocked_cold = self.blocked_cold_after_ms;
 
-        self.handle = Some(tokio::spawn(async move {
-            let mut ticker = interval(Duration::from_millis(interval_ms));
-            loop {
-                ticker.tick().await;
-                let _ = Self::tick_inner(&engine, &store, paused_cold, blocked_cold).await;
-            }
-        }));
+        let runner = runner_fn(
+            "scheduler.loop",
+            Duration::from_millis(interval_ms),
+            move || {
+                let engine = engine.clone();
+                let store = store.clone();
+                async move {
+                    Self::tick_inner(&engine, &store, paused_cold, blocked_cold)
+                        .await
+                        .map(|()| RunnerTickReport::default())
+                }
+            },
+        );
+        self.handle = Some(self.engine.runners().start(Arc::new(runner)));
     }
 
     pub fn stop(&mut self) {
@@ -144,6 +152,9 @@ impl TaskScheduler {
                                 series_id: None,
                                 series_mode: None,
                                 series_acc_field: None,
+                                labels: None,
+                                broadcast_debounce_ms: None,
+                                series_end: false,
                             },
                         )
                         .await;
@@ -1,25 +1,61 @@
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::{SeriesMode, SeriesResult, ShortTermStore, TaskEvent};
 
+/// Default cap on the series a task keeps state for.
+pub const DEFAULT_MAX_SERIES_PER_TASK: usize = 1000;
+
+/// What a publish does when it would start a series past the task's cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SeriesOverLimit {
+    /// Reject the event.
+    #[default]
+    Reject,
+    /// Append the event as a plain event: it keeps its `series_id`, but its
+    /// series mode is dropped and no series state is kept for it.
+    SkipSeries,
+}
+
+/// Cap on the distinct series a task keeps state for. Series already
+/// started are unaffected, as are `keep-all` series, which keep no state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesLimits {
+    pub max_per_task: usize,
+    pub over_limit: SeriesOverLimit,
+}
+
+impl Default for SeriesLimits {
+    fn default() -> Self {
+        Self {
+            max_per_task: DEFAULT_MAX_SERIES_PER_TASK,
+            over_limit: SeriesOverLimit::Reject,
+        }
+    }
+}
+
 /// Process a task event through its series mode logic.
 ///
 /// - If the event has no `series_id` or `series_mode`, it is returned unchanged.
 /// - `keep-all`: returned unchanged with no store interaction.
 /// - `accumulate`: delegates to `store.accumulate_series()`, returns both delta and accumulated.
 /// - `latest`: replaces the last series event in the store and returns the event.
+/// - `json-patch`: applies the event's patch to the series document and stores the
+///   result as the series latest; the event itself keeps the patch.
 pub async fn process_series(
     event: TaskEvent,
     store: &dyn ShortTermStore,
 ) -> Result<SeriesResult, Box<dyn std::error::Error + Send + Sync>> {
     let (series_id, series_mode) = match (&event.series_id, &event.series_mode) {
         (Some(sid), Some(mode)) => (sid.clone(), mode.clone()),
-        _ => return Ok(SeriesResult { event, accumulated_event: None, stored: false }),
+        _ => return Ok(unchanged(event)),
     };
 
     match series_mode {
-        SeriesMode::KeepAll => Ok(SeriesResult { event, accumulated_event: None, stored: false }),
+        SeriesMode::KeepAll => Ok(unchanged(event)),
 
         SeriesMode::Accumulate => {
             let field = event
@@ -29,18 +65,85 @@ pub async fn process_series(
             let accumulated = store
                 .accumulate_series(&event.task_id, &series_id, event.clone(), field)
                 .await?;
-            Ok(SeriesResult { event, accumulated_event: Some(accumulated), stored: false })
+            Ok(SeriesResult {
+                accumulated_event: Some(accumulated),
+                ..unchanged(event)
+            })
         }
 
         SeriesMode::Latest => {
-            store
+            let replaced_event_id = store
                 .replace_last_series_event(&event.task_id, &series_id, event.clone())
                 .await?;
-            Ok(SeriesResult { event, accumulated_event: None, stored: true })
+            Ok(SeriesResult {
+                stored: true,
+                replaced_event_id,
+                ..unchanged(event)
+            })
+        }
+
+        SeriesMode::JsonPatch => {
+            let document = series_document(store, &event.task_id, &series_id).await?;
+            let document = apply_json_patch(&document, &event.data)?;
+            store_series_document(event, document, store).await
         }
     }
 }
 
+/// `event` to be appended as is.
+fn unchanged(event: TaskEvent) -> SeriesResult {
+    SeriesResult {
+        event,
+        accumulated_event: None,
+        stored: false,
+        replaced_event_id: None,
+    }
+}
+
+/// The current document of a `json-patch` series: the materialized data of
+/// its latest event, or an empty object before the first patch.
+pub async fn series_document(
+    store: &dyn ShortTermStore,
+    task_id: &str,
+    series_id: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(store
+        .get_series_latest(task_id, series_id)
+        .await?
+        .map_or_else(|| serde_json::json!({}), |latest| latest.data))
+}
+
+/// `document` with the RFC 6902 `patch` applied.
+pub fn apply_json_patch(
+    document: &serde_json::Value,
+    patch: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let patch: json_patch::Patch = serde_json::from_value(patch.clone())
+        .map_err(|e| format!("Series data is not a JSON Patch: {e}"))?;
+    let mut patched = document.clone();
+    json_patch::patch(&mut patched, &patch)
+        .map_err(|e| format!("JSON Patch could not be applied: {e}"))?;
+    Ok(patched)
+}
+
+/// Records `document`, already patched with `event`, as the series latest.
+/// The event is returned unchanged and still has to be appended.
+pub async fn store_series_document(
+    event: TaskEvent,
+    document: serde_json::Value,
+    store: &dyn ShortTermStore,
+) -> Result<SeriesResult, Box<dyn std::error::Error + Send + Sync>> {
+    let series_id = event.series_id.clone().unwrap_or_default();
+    let latest = TaskEvent {
+        data: document,
+        ..event.clone()
+    };
+    store
+        .set_series_latest(&event.task_id, &series_id, latest)
+        .await?;
+    Ok(unchanged(event))
+}
+
 /// Collapse accumulate-mode series events into single snapshot events.
 ///
 /// For each accumulate series found in `events`:
@@ -116,6 +219,180 @@ where
     Ok(result)
 }
 
+/// Replace the patches of each json-patch series in `events` with a single
+/// snapshot event carrying the materialized document.
+///
+/// The snapshot is the series latest from `get_series_latest`, marked with
+/// `series_snapshot = true`, and takes the place of the series' first event.
+/// Patches newer than the snapshot are kept after it. For a cold task (no
+/// series latest) the patches in `events` are folded instead when
+/// `from_start` says `events` begins at the start of the task's history;
+/// otherwise, or if its patches do not apply, a series is left as is.
+pub async fn collapse_json_patch_series<F, Fut>(
+    events: &[TaskEvent],
+    from_start: bool,
+    get_series_latest: F,
+) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>>
+where
+    F: Fn(&str, &str) -> Fut,
+    Fut: Future<Output = Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    let mut series_ids = Vec::new();
+    for e in events {
+        if e.series_mode.as_ref() == Some(&SeriesMode::JsonPatch) {
+            if let Some(ref sid) = e.series_id {
+                if !series_ids.contains(sid) {
+                    series_ids.push(sid.clone());
+                }
+            }
+        }
+    }
+
+    if series_ids.is_empty() {
+        return Ok(events.to_vec());
+    }
+
+    let task_id = &events[0].task_id;
+    let mut snapshots = HashMap::new();
+    for sid in &series_ids {
+        let snapshot = match get_series_latest(task_id, sid).await? {
+            Some(latest) => Some(latest),
+            None if from_start => fold_json_patch_series(events, sid),
+            None => None,
+        };
+        if let Some(mut snapshot) = snapshot {
+            snapshot.series_snapshot = Some(true);
+            snapshots.insert(sid.clone(), snapshot);
+        }
+    }
+
+    let mut emitted = HashSet::new();
+    let mut result = Vec::new();
+    for event in events {
+        if event.series_mode.as_ref() == Some(&SeriesMode::JsonPatch) {
+            if let Some(ref sid) = event.series_id {
+                if let Some(snapshot) = snapshots.get(sid) {
+                    if event.index <= snapshot.index {
+                        if emitted.insert(sid.clone()) {
+                            result.push(snapshot.clone());
+                        }
+                        continue;
+                    }
+                }
+            }
+        }
+        result.push(event.clone());
+    }
+
+    Ok(result)
+}
+
+/// The last patch of `series_id` in `events` with the document all of its
+/// patches build, or `None` if one does not apply.
+pub(crate) fn fold_json_patch_series(events: &[TaskEvent], series_id: &str) -> Option<TaskEvent> {
+    let mut document = serde_json::json!({});
+    let mut last = None;
+    for event in events {
+        if event.series_id.as_deref() == Some(series_id)
+            && event.series_mode.as_ref() == Some(&SeriesMode::JsonPatch)
+        {
+            document = apply_json_patch(&document, &event.data).ok()?;
+            last = Some(event);
+        }
+    }
+    last.map(|event| TaskEvent {
+        data: document,
+        ..event.clone()
+    })
+}
+
+/// Attach to each accumulate-series event the series' running total as of
+/// that event, in `_accumulated_data`.
+///
+/// Totals are folded over `events` in order the same way
+/// `ShortTermStore::accumulate_series` merges them, so `events` must start
+/// at the beginning of each series (a collapsed snapshot counts as one).
+pub fn attach_accumulated_data(events: &mut [TaskEvent]) {
+    SeriesTotals::default().attach(events);
+}
+
+/// Running totals of accumulate series, for attaching them to a history
+/// read in several parts. See [`attach_accumulated_data`].
+#[derive(Debug, Default)]
+pub(crate) struct SeriesTotals {
+    totals: HashMap<String, serde_json::Value>,
+}
+
+impl SeriesTotals {
+    /// Folds `events`, which continue the events attached so far, into the
+    /// totals and attaches them.
+    pub(crate) fn attach(&mut self, events: &mut [TaskEvent]) {
+        for event in events.iter_mut() {
+            if event.series_mode.as_ref() != Some(&SeriesMode::Accumulate) {
+                continue;
+            }
+            let Some(ref series_id) = event.series_id else {
+                continue;
+            };
+            let field = event.series_acc_field.as_deref().unwrap_or("delta");
+            let accumulated = match self.totals.get(series_id) {
+                Some(previous) => merge_accumulated(previous, &event.data, field),
+                None => event.data.clone(),
+            };
+            self.totals.insert(series_id.clone(), accumulated.clone());
+            event._accumulated_data = Some(accumulated);
+        }
+    }
+}
+
+/// `data` with its `field` appended to `previous`'s, when both are strings.
+fn merge_accumulated(
+    previous: &serde_json::Value,
+    data: &serde_json::Value,
+    field: &str,
+) -> serde_json::Value {
+    let previous_text = previous.get(field).and_then(|v| v.as_str());
+    let text = data.get(field).and_then(|v| v.as_str());
+    match (previous_text, text, data.as_object()) {
+        (Some(previous_text), Some(text), Some(object)) => {
+            let mut merged = object.clone();
+            merged.insert(
+                field.to_string(),
+                serde_json::Value::String(format!("{previous_text}{text}")),
+            );
+            serde_json::Value::Object(merged)
+        }
+        _ => data.clone(),
+    }
+}
+
+/// Payload field carrying `_accumulated_data`, which the event's own
+/// serialization skips.
+const ACCUMULATED_DATA_FIELD: &str = "_accumulatedData";
+
+/// Serializes `event` for a broadcast transport, keeping its accumulated
+/// series data so subscribers on other instances can still deliver
+/// accumulated views.
+pub fn encode_broadcast_payload(event: &TaskEvent) -> Result<String, serde_json::Error> {
+    let mut payload = serde_json::to_value(event)?;
+    if let (Some(accumulated), Some(object)) =
+        (&event._accumulated_data, payload.as_object_mut())
+    {
+        object.insert(ACCUMULATED_DATA_FIELD.to_string(), accumulated.clone());
+    }
+    serde_json::to_string(&payload)
+}
+
+/// Reads a payload written by [`encode_broadcast_payload`]. `None` if it is
+/// not an event.
+pub fn decode_broadcast_payload(payload: &[u8]) -> Option<TaskEvent> {
+    let mut value: serde_json::Value = serde_json::from_slice(payload).ok()?;
+    let accumulated = value.as_object_mut()?.remove(ACCUMULATED_DATA_FIELD);
+    let mut event: TaskEvent = serde_json::from_value(value).ok()?;
+    event._accumulated_data = accumulated;
+    Some(event)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +418,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }
     }
@@ -713,4 +992,166 @@ mod tests {
         let acc = result.accumulated_event.unwrap();
         assert_eq!(acc.data, json!([4, 5, 6]));
     }
+
+    // ─── attach_accumulated_data ─────────────────────────────────────────
+
+    // ─── json-patch mode → materializes the series document ──────────────
+
+    fn patch_event(id: &str, index: u64, patch: serde_json::Value) -> TaskEvent {
+        make_series_event(id, "t1", index, patch, "s1", SeriesMode::JsonPatch)
+    }
+
+    #[tokio::test]
+    async fn json_patch_chain_materializes_the_document() {
+        let store = MemoryShortTermStore::new();
+        let patches = [
+            json!([{ "op": "add", "path": "/frontier", "value": ["a.com"] }]),
+            json!([{ "op": "add", "path": "/frontier/-", "value": "b.com" }]),
+            json!([
+                { "op": "remove", "path": "/frontier/0" },
+                { "op": "add", "path": "/visited", "value": 1 },
+            ]),
+        ];
+        for (index, patch) in patches.into_iter().enumerate() {
+            let event = patch_event(&format!("e{index}"), index as u64, patch);
+            let result = process_series(event.clone(), &store).await.unwrap();
+
+            // The event keeps its patch and is left for the caller to append.
+            assert_eq!(result.event, event);
+            assert!(result.accumulated_event.is_none());
+            assert!(!result.stored);
+        }
+
+        let latest = store.get_series_latest("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(latest.id, "e2");
+        assert_eq!(latest.data, json!({ "frontier": ["b.com"], "visited": 1 }));
+        assert!(store.get_events("t1", None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn json_patch_that_does_not_apply_leaves_the_document() {
+        let store = MemoryShortTermStore::new();
+        process_series(
+            patch_event("e0", 0, json!([{ "op": "add", "path": "/a", "value": 1 }])),
+            &store,
+        )
+        .await
+        .unwrap();
+
+        let bad_path = patch_event("e1", 1, json!([{ "op": "remove", "path": "/missing" }]));
+        assert!(process_series(bad_path, &store).await.is_err());
+        let not_a_patch = patch_event("e2", 2, json!({ "a": 2 }));
+        assert!(process_series(not_a_patch, &store).await.is_err());
+
+        let latest = store.get_series_latest("t1", "s1").await.unwrap().unwrap();
+        assert_eq!(latest.data, json!({ "a": 1 }));
+    }
+
+    #[tokio::test]
+    async fn collapse_json_patch_replaces_patches_up_to_the_snapshot() {
+        let events = vec![
+            make_event("e0", "t1", 0, json!({ "text": "start" })),
+            patch_event("e1", 1, json!([{ "op": "add", "path": "/n", "value": 1 }])),
+            patch_event(
+                "e2",
+                2,
+                json!([{ "op": "replace", "path": "/n", "value": 2 }]),
+            ),
+            patch_event(
+                "e3",
+                3,
+                json!([{ "op": "replace", "path": "/n", "value": 3 }]),
+            ),
+        ];
+        let latest = TaskEvent {
+            data: json!({ "n": 2 }),
+            ..events[2].clone()
+        };
+
+        let collapsed = collapse_json_patch_series(&events, true, |_, _| {
+            let latest = latest.clone();
+            async move { Ok(Some(latest)) }
+        })
+        .await
+        .unwrap();
+        let ids: Vec<&str> = collapsed.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e0", "e2", "e3"]);
+        assert_eq!(collapsed[1].data, json!({ "n": 2 }));
+        assert_eq!(collapsed[1].series_snapshot, Some(true));
+        assert_eq!(collapsed[2], events[3]);
+    }
+
+    #[tokio::test]
+    async fn collapse_json_patch_folds_a_cold_series_from_the_start() {
+        let events = vec![
+            patch_event("e0", 0, json!([{ "op": "add", "path": "/n", "value": 1 }])),
+            patch_event(
+                "e1",
+                1,
+                json!([{ "op": "replace", "path": "/n", "value": 2 }]),
+            ),
+        ];
+        let no_latest = |_: &str, _: &str| async { Ok(None) };
+
+        let folded = collapse_json_patch_series(&events, true, no_latest)
+            .await
+            .unwrap();
+        assert_eq!(folded.len(), 1);
+        assert_eq!(folded[0].id, "e1");
+        assert_eq!(folded[0].data, json!({ "n": 2 }));
+        assert_eq!(folded[0].series_snapshot, Some(true));
+
+        // Without the start of the history the patches cannot be folded.
+        let partial = collapse_json_patch_series(&events[1..], false, no_latest)
+            .await
+            .unwrap();
+        assert_eq!(partial, events[1..]);
+    }
+
+    #[tokio::test]
+    async fn attached_totals_match_the_store() {
+        let store = MemoryShortTermStore::new();
+        let chunk = |id: &str, index, data, series_id| {
+            make_series_event(id, "t1", index, data, series_id, SeriesMode::Accumulate)
+        };
+        let mut events = vec![
+            chunk("e0", 0, json!({ "delta": "Hel" }), "s1"),
+            chunk("e1", 1, json!({ "delta": "x" }), "s2"),
+            make_event("e2", "t1", 2, json!({ "delta": "other" })),
+            chunk("e3", 3, json!({ "delta": "lo" }), "s1"),
+            chunk("e4", 4, json!({ "delta": 1 }), "s2"),
+        ];
+        let mut stored = Vec::new();
+        for event in &events {
+            let result = process_series(event.clone(), &store).await.unwrap();
+            stored.push(result.accumulated_event.map(|e| e.data));
+        }
+
+        attach_accumulated_data(&mut events);
+        for (event, stored) in events.iter().zip(stored) {
+            assert_eq!(event._accumulated_data, stored, "{}", event.id);
+        }
+        assert_eq!(events[3]._accumulated_data, Some(json!({ "delta": "Hello" })));
+    }
+
+    #[test]
+    fn broadcast_payload_keeps_accumulated_data() {
+        let event = TaskEvent {
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::Accumulate),
+            _accumulated_data: Some(json!({ "delta": "Hello" })),
+            ..make_event("evt_1", "task_01", 1, json!({ "delta": "lo" }))
+        };
+        let payload = encode_broadcast_payload(&event).unwrap();
+        assert_eq!(decode_broadcast_payload(payload.as_bytes()), Some(event.clone()));
+
+        let plain = TaskEvent {
+            _accumulated_data: None,
+            ..event
+        };
+        let payload = encode_broadcast_payload(&plain).unwrap();
+        assert!(!payload.contains("_accumulatedData"));
+        assert_eq!(decode_broadcast_payload(payload.as_bytes()), Some(plain));
+        assert_eq!(decode_broadcast_payload(b"not json"), None);
+    }
 }
@@ -0,0 +1,489 @@
+//! Active and standby roles, and the leader election that moves an instance
+//! between them.
+//!
+//! A standby instance shares its stores and broadcast provider with the
+//! active one and serves reads and SSE streams from them, but takes no
+//! writes and holds its background runners. [`RoleSwitch`] keeps the role
+//! and flips both at once. [`LeaderElection`] drives it from a
+//! [`LeaderLease`], so that at most one instance of a group is active.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+
+use crate::runner::RunnerSupervisor;
+
+/// Default lifetime of the leader lease. A standby takes over at most this
+/// long after the active instance stops renewing it.
+pub const DEFAULT_LEADER_TTL: Duration = Duration::from_secs(10);
+
+/// Default name of the leader lease key, under the store's key prefix.
+pub const DEFAULT_LEADER_KEY: &str = "leader";
+
+// ─── Role ────────────────────────────────────────────────────────────────────
+
+/// Whether an instance takes writes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerRole {
+    /// Takes writes and runs background runners.
+    #[default]
+    Active,
+    /// Serves reads and streams only, ready to be promoted.
+    Standby,
+}
+
+impl ServerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerRole::Active => "active",
+            ServerRole::Standby => "standby",
+        }
+    }
+}
+
+struct RoleInner {
+    role: watch::Sender<ServerRole>,
+    runners: RunnerSupervisor,
+    /// Serializes promotions and demotions, so the role and the runner hold
+    /// always agree.
+    switching: Mutex<()>,
+    elected: AtomicBool,
+}
+
+/// An instance's current role, switchable at runtime.
+///
+/// Promotion enables writes, then releases the runners. Demotion disables
+/// writes, then holds the runners; writes in progress are cancelled by
+/// whoever [subscribes](RoleSwitch::subscribe) to the change.
+#[derive(Clone)]
+pub struct RoleSwitch {
+    inner: Arc<RoleInner>,
+}
+
+impl RoleSwitch {
+    /// Starts in `role`, holding `runners` if it is standby.
+    pub fn new(role: ServerRole, runners: RunnerSupervisor) -> Self {
+        if role == ServerRole::Standby {
+            runners.hold();
+        }
+        Self {
+            inner: Arc::new(RoleInner {
+                role: watch::Sender::new(role),
+                runners,
+                switching: Mutex::new(()),
+                elected: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn role(&self) -> ServerRole {
+        *self.inner.role.borrow()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.role() == ServerRole::Active
+    }
+
+    /// Makes the instance active. Returns whether it was standby.
+    pub fn promote(&self) -> bool {
+        let _switching = self.inner.switching.lock().unwrap();
+        if !self.set(ServerRole::Active) {
+            return false;
+        }
+        self.inner.runners.release();
+        true
+    }
+
+    /// Makes the instance standby. Returns whether it was active.
+    pub fn demote(&self) -> bool {
+        let _switching = self.inner.switching.lock().unwrap();
+        if !self.set(ServerRole::Standby) {
+            return false;
+        }
+        self.inner.runners.hold();
+        true
+    }
+
+    /// Role changes as they happen.
+    pub fn subscribe(&self) -> watch::Receiver<ServerRole> {
+        self.inner.role.subscribe()
+    }
+
+    /// Whether a [`LeaderElection`] decides the role, in which case it must
+    /// not be switched by hand.
+    pub fn is_elected(&self) -> bool {
+        self.inner.elected.load(Ordering::SeqCst)
+    }
+
+    fn set(&self, role: ServerRole) -> bool {
+        self.inner
+            .role
+            .send_if_modified(|current| std::mem::replace(current, role) != role)
+    }
+}
+
+impl std::fmt::Debug for RoleSwitch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoleSwitch")
+            .field("role", &self.role())
+            .field("elected", &self.is_elected())
+            .finish()
+    }
+}
+
+// ─── Lease ───────────────────────────────────────────────────────────────────
+
+/// A lock held by one named holder at a time, which lapses unless renewed.
+#[async_trait]
+pub trait LeaderLease: Send + Sync {
+    /// Takes the lease for `holder` if it is free, or extends it if `holder`
+    /// already has it, to lapse `ttl` from now. Returns whether `holder`
+    /// has it.
+    async fn acquire(
+        &self,
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Gives the lease up if `holder` has it.
+    async fn release(&self, holder: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A [`LeaderLease`] for instances in one process.
+#[derive(Debug, Default)]
+pub struct MemoryLeaderLease {
+    holder: Mutex<Option<(String, Instant)>>,
+}
+
+impl MemoryLeaderLease {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current holder, if the lease has not lapsed.
+    pub fn holder(&self) -> Option<String> {
+        let holder = self.holder.lock().unwrap();
+        holder
+            .as_ref()
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(name, _)| name.clone())
+    }
+}
+
+#[async_trait]
+impl LeaderLease for MemoryLeaderLease {
+    async fn acquire(
+        &self,
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let now = Instant::now();
+        let mut current = self.holder.lock().unwrap();
+        let free = match current.as_ref() {
+            Some((name, expires)) => name == holder || *expires <= now,
+            None => true,
+        };
+        if free {
+            *current = Some((holder.to_string(), now + ttl));
+        }
+        Ok(free)
+    }
+
+    async fn release(&self, holder: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut current = self.holder.lock().unwrap();
+        if current.as_ref().is_some_and(|(name, _)| name == holder) {
+            *current = None;
+        }
+        Ok(())
+    }
+}
+
+// ─── Election ────────────────────────────────────────────────────────────────
+
+pub struct LeaderElectionOptions {
+    pub lease: Arc<dyn LeaderLease>,
+    /// This instance's name in the lease, unique within the group.
+    pub holder: String,
+    pub ttl: Duration,
+    /// Time between renewals; must be well under `ttl`.
+    pub renew_interval: Duration,
+}
+
+/// Keeps a [`RoleSwitch`] active exactly while this instance holds the
+/// leader lease.
+///
+/// The lease is renewed every `renew_interval`. A renewal counts from when
+/// it was sent, so the instance demotes itself no later than the lease can
+/// lapse in the store, even if the store stops answering, and before any
+/// other instance can take it over.
+pub struct LeaderElection {
+    role: RoleSwitch,
+    lease: Arc<dyn LeaderLease>,
+    holder: String,
+    handle: Option<AbortHandle>,
+}
+
+impl LeaderElection {
+    /// Demotes `role` until the lease is won and starts campaigning for it.
+    ///
+    /// The campaign runs on the [`BackgroundTasks`](crate::BackgroundTasks)
+    /// behind the role's runners, not as a runner itself, since it has to
+    /// keep going while they are held.
+    pub fn start(role: RoleSwitch, options: LeaderElectionOptions) -> Self {
+        role.inner.elected.store(true, Ordering::SeqCst);
+        role.demote();
+        let handle = role.inner.runners.background().spawn(
+            "leader.campaign",
+            None,
+            campaign(
+                role.clone(),
+                Arc::clone(&options.lease),
+                options.holder.clone(),
+                options.ttl,
+                options.renew_interval,
+            ),
+        );
+        Self {
+            role,
+            lease: options.lease,
+            holder: options.holder,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Stops campaigning, demotes and gives the lease up, so a standby can
+    /// take over without waiting for it to lapse.
+    pub async fn stop(mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+        self.role.demote();
+        if let Err(err) = self.lease.release(&self.holder).await {
+            eprintln!("[taskcast] Releasing the leader lease failed: {err}");
+        }
+    }
+}
+
+impl Drop for LeaderElection {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+async fn campaign(
+    role: RoleSwitch,
+    lease: Arc<dyn LeaderLease>,
+    holder: String,
+    ttl: Duration,
+    renew_interval: Duration,
+) {
+    // When the lease lapses unless renewed; set only while it is held.
+    let mut expires: Option<Instant> = None;
+    loop {
+        if expires.is_some_and(|deadline| Instant::now() >= deadline) {
+            expires = None;
+            if role.demote() {
+                eprintln!("[taskcast] Leader lease lapsed; now standby");
+            }
+        }
+        let sent = Instant::now();
+        // An attempt may not outlast the lease it would renew.
+        let limit = expires.unwrap_or(sent + ttl);
+        match tokio::time::timeout_at(limit, lease.acquire(&holder, ttl)).await {
+            // Counted from before the call, which is no later than the
+            // store started the lease.
+            Ok(Ok(true)) if Instant::now() < sent + ttl => {
+                expires = Some(sent + ttl);
+                if role.promote() {
+                    eprintln!("[taskcast] Won the leader lease; now active");
+                }
+            }
+            Ok(Ok(_)) => {
+                expires = None;
+                if role.demote() {
+                    eprintln!("[taskcast] Lost the leader lease; now standby");
+                }
+            }
+            Ok(Err(err)) => eprintln!("[taskcast] Leader lease renewal failed: {err}"),
+            Err(_) => eprintln!("[taskcast] Leader lease renewal timed out"),
+        }
+        let next = sent + renew_interval;
+        tokio::time::sleep_until(expires.map_or(next, |deadline| deadline.min(next))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::BackgroundTasks;
+    use crate::retry::SystemClock;
+
+    fn runners() -> RunnerSupervisor {
+        RunnerSupervisor::new(BackgroundTasks::new(None), None, Arc::new(SystemClock))
+    }
+
+    /// Reaches a shared lease through a link that hangs while `cut` is set.
+    struct Partitioned {
+        lease: Arc<MemoryLeaderLease>,
+        cut: AtomicBool,
+    }
+
+    #[async_trait]
+    impl LeaderLease for Partitioned {
+        async fn acquire(
+            &self,
+            holder: &str,
+            ttl: Duration,
+        ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+            if self.cut.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+            self.lease.acquire(holder, ttl).await
+        }
+
+        async fn release(
+            &self,
+            holder: &str,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.lease.release(holder).await
+        }
+    }
+
+    /// Lets spawned tasks run on the paused clock.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn switching_roles_holds_and_releases_the_runners() {
+        let runners = runners();
+        let role = RoleSwitch::new(ServerRole::Standby, runners.clone());
+        assert!(runners.is_held());
+        let mut changes = role.subscribe();
+
+        assert!(role.promote());
+        assert!(!role.promote());
+        assert!(role.is_active());
+        assert!(!runners.is_held());
+        assert!(changes.has_changed().unwrap());
+        assert_eq!(*changes.borrow_and_update(), ServerRole::Active);
+
+        assert!(role.demote());
+        assert!(runners.is_held());
+        assert_eq!(*changes.borrow_and_update(), ServerRole::Standby);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn memory_lease_lapses_after_its_ttl() {
+        let lease = MemoryLeaderLease::new();
+        let ttl = Duration::from_secs(10);
+        assert!(lease.acquire("a", ttl).await.unwrap());
+        assert!(!lease.acquire("b", ttl).await.unwrap());
+        assert!(lease.acquire("a", ttl).await.unwrap());
+
+        tokio::time::advance(ttl).await;
+        assert_eq!(lease.holder(), None);
+        assert!(lease.acquire("b", ttl).await.unwrap());
+
+        lease.release("a").await.unwrap();
+        assert_eq!(lease.holder().as_deref(), Some("b"));
+        lease.release("b").await.unwrap();
+        assert_eq!(lease.holder(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn the_standby_takes_over_once_the_leader_stops_renewing() {
+        let ttl = Duration::from_secs(3);
+        let shared = Arc::new(MemoryLeaderLease::new());
+        let partitioned = Arc::new(Partitioned {
+            lease: Arc::clone(&shared),
+            cut: AtomicBool::new(false),
+        });
+        let options = |lease: Arc<dyn LeaderLease>, holder: &str| LeaderElectionOptions {
+            lease,
+            holder: holder.to_string(),
+            ttl,
+            renew_interval: Duration::from_secs(1),
+        };
+
+        let a = RoleSwitch::new(ServerRole::Active, runners());
+        let _a = LeaderElection::start(a.clone(), options(partitioned.clone(), "a"));
+        settle().await;
+        assert!(a.is_active());
+        assert!(a.is_elected());
+
+        let b = RoleSwitch::new(ServerRole::Active, runners());
+        let b_election = LeaderElection::start(b.clone(), options(shared.clone(), "b"));
+        settle().await;
+        assert!(!b.is_active());
+
+        partitioned.cut.store(true, Ordering::SeqCst);
+        let cut_at = Instant::now();
+        let mut a_demoted = None;
+        let mut b_promoted = None;
+        while b_promoted.is_none() {
+            tokio::time::advance(Duration::from_millis(100)).await;
+            settle().await;
+            if a_demoted.is_none() && !a.is_active() {
+                a_demoted = Some(cut_at.elapsed());
+            }
+            if b.is_active() {
+                b_promoted = Some(cut_at.elapsed());
+            }
+            assert!(!(a.is_active() && b.is_active()), "both active");
+        }
+        assert!(a_demoted.unwrap() <= ttl);
+        assert!(b_promoted.unwrap() <= ttl + Duration::from_secs(1));
+
+        // Back online, `a` finds the lease taken and stays standby.
+        partitioned.cut.store(false, Ordering::SeqCst);
+        tokio::time::advance(Duration::from_secs(5)).await;
+        settle().await;
+        assert!(!a.is_active());
+        assert!(b.is_active());
+
+        // A clean stop hands the lease straight back.
+        b_election.stop().await;
+        assert!(!b.is_active());
+        tokio::time::advance(Duration::from_secs(1)).await;
+        settle().await;
+        assert!(a.is_active());
+    }
+
+    #[tokio::test]
+    async fn the_campaign_is_tracked_until_stopped() {
+        let background = BackgroundTasks::new(None);
+        let role = RoleSwitch::new(
+            ServerRole::Active,
+            RunnerSupervisor::new(background.clone(), None, Arc::new(SystemClock)),
+        );
+        let election = LeaderElection::start(
+            role,
+            LeaderElectionOptions {
+                lease: Arc::new(MemoryLeaderLease::new()),
+                holder: "a".to_string(),
+                ttl: Duration::from_secs(3),
+                renew_interval: Duration::from_secs(1),
+            },
+        );
+        assert_eq!(background.in_flight_by_name()["leader.campaign"], 1);
+
+        election.stop().await;
+        assert!(background.drain(Duration::from_secs(5)).await);
+    }
+}
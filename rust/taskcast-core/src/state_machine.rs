@@ -14,6 +14,7 @@ pub const SUSPENDED_STATUSES: &[TaskStatus] = &[
 
 pub fn allowed_transitions(from: &TaskStatus) -> &'static [TaskStatus] {
     match from {
+        TaskStatus::Scheduled => &[TaskStatus::Pending, TaskStatus::Cancelled],
         TaskStatus::Pending => &[TaskStatus::Assigned, TaskStatus::Running, TaskStatus::Paused, TaskStatus::Cancelled],
         TaskStatus::Assigned => &[TaskStatus::Running, TaskStatus::Pending, TaskStatus::Paused, TaskStatus::Cancelled],
         TaskStatus::Running => &[
@@ -59,6 +60,30 @@ pub fn is_suspended(status: &TaskStatus) -> bool {
 mod tests {
     use super::*;
 
+    // ─── can_transition: Scheduled ───────────────────────────────────────
+
+    #[test]
+    fn scheduled_activates_to_pending_or_is_cancelled() {
+        assert!(can_transition(&TaskStatus::Scheduled, &TaskStatus::Pending));
+        assert!(can_transition(&TaskStatus::Scheduled, &TaskStatus::Cancelled));
+    }
+
+    #[test]
+    fn scheduled_cannot_skip_activation() {
+        for to in [
+            TaskStatus::Assigned,
+            TaskStatus::Running,
+            TaskStatus::Paused,
+            TaskStatus::Completed,
+            TaskStatus::Failed,
+            TaskStatus::Timeout,
+        ] {
+            assert!(!can_transition(&TaskStatus::Scheduled, &to), "{to:?}");
+        }
+        assert!(!can_transition(&TaskStatus::Pending, &TaskStatus::Scheduled));
+        assert!(!is_terminal(&TaskStatus::Scheduled));
+    }
+
     // ─── can_transition: valid transitions from Pending ──────────────────
 
     #[test]
@@ -0,0 +1,459 @@
+//! Local disk usage for the directories a node writes to (spool, blobs,
+//! exports), with per-directory caps.
+//!
+//! Writes through [`StorageManager::write`] are refused once a directory is
+//! at its byte cap. A periodic sweep deletes files older than the
+//! directory's age limit, then the oldest files until it is back under its
+//! byte cap. The sweep never touches a file that was modified within
+//! [`StorageManagerOptions::min_file_age`], that has a `<name>.lock` sibling,
+//! or that is still being written by [`StorageManager::write`], so it is
+//! safe to run next to concurrent writers.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::task::AbortHandle;
+
+use crate::runner::{Runner, RunnerSupervisor, RunnerTickReport};
+use crate::types::{ErrorContext, TaskcastHooks};
+
+/// Suffix of the lock file a writer holds next to a file it is still using.
+pub const STORAGE_LOCK_SUFFIX: &str = ".lock";
+
+/// Caps for one registered directory. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirectoryLimits {
+    pub max_bytes: Option<u64>,
+    pub max_age: Option<Duration>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Unknown storage directory: {0}")]
+    UnknownDirectory(String),
+
+    #[error("Storage directory {directory} is full ({used_bytes} of {max_bytes} bytes used)")]
+    Full {
+        directory: String,
+        used_bytes: u64,
+        max_bytes: u64,
+    },
+
+    #[error("Invalid file name: {0}")]
+    InvalidFileName(String),
+
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// Current usage of one registered directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryUsage {
+    pub name: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub files: u64,
+    /// Modification time of the oldest file, in epoch milliseconds.
+    pub oldest_modified_at: Option<f64>,
+    pub newest_modified_at: Option<f64>,
+    pub max_bytes: Option<u64>,
+    pub max_age_ms: Option<u64>,
+    /// `bytes / maxBytes`, when the directory has a byte cap.
+    pub utilization: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RemovalReason {
+    /// Older than the directory's `max_age`.
+    Age,
+    /// Among the oldest files while the directory was over `max_bytes`.
+    Cap,
+}
+
+/// A file deleted by a sweep.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedFile {
+    pub directory: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub reason: RemovalReason,
+}
+
+pub struct StorageManagerOptions {
+    pub hooks: Option<Arc<dyn TaskcastHooks>>,
+    /// How often the background sweep runs. Default: 60s.
+    pub sweep_interval: Duration,
+    /// Files modified more recently than this are never swept, since a
+    /// writer may still be using them. Default: 60s.
+    pub min_file_age: Duration,
+}
+
+impl Default for StorageManagerOptions {
+    fn default() -> Self {
+        Self {
+            hooks: None,
+            sweep_interval: Duration::from_secs(60),
+            min_file_age: Duration::from_secs(60),
+        }
+    }
+}
+
+struct Directory {
+    path: PathBuf,
+    limits: DirectoryLimits,
+    /// Serializes writes so two concurrent writers cannot both pass the cap
+    /// check.
+    write_lock: Arc<Mutex<()>>,
+}
+
+struct FileEntry {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Registered storage directories, their caps, and the sweep that enforces
+/// them.
+pub struct StorageManager {
+    directories: RwLock<BTreeMap<String, Directory>>,
+    /// Final paths of files `write` has not finished yet.
+    in_flight: Mutex<HashSet<PathBuf>>,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    sweep_interval: Duration,
+    min_file_age: Duration,
+    handle: Mutex<Option<AbortHandle>>,
+}
+
+impl StorageManager {
+    pub fn new(opts: StorageManagerOptions) -> Self {
+        Self {
+            directories: RwLock::new(BTreeMap::new()),
+            in_flight: Mutex::new(HashSet::new()),
+            hooks: opts.hooks,
+            sweep_interval: opts.sweep_interval,
+            min_file_age: opts.min_file_age,
+            handle: Mutex::new(None),
+        }
+    }
+
+    /// Registers `path` under `name`, creating it if needed. Registering a
+    /// name again replaces its path and limits.
+    pub fn register(
+        &self,
+        name: &str,
+        path: impl Into<PathBuf>,
+        limits: DirectoryLimits,
+    ) -> io::Result<()> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        self.directories.write().unwrap().insert(
+            name.to_string(),
+            Directory {
+                path,
+                limits,
+                write_lock: Arc::new(Mutex::new(())),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn directory_names(&self) -> Vec<String> {
+        self.directories.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Usage of every registered directory, ordered by name.
+    pub fn usage(&self) -> Result<Vec<DirectoryUsage>, StorageError> {
+        self.directory_names()
+            .iter()
+            .map(|name| self.directory_usage(name))
+            .collect()
+    }
+
+    pub fn directory_usage(&self, name: &str) -> Result<DirectoryUsage, StorageError> {
+        let (path, limits) = self.lookup(name)?;
+        let files = list_files(&path)?;
+        let bytes: u64 = files.iter().map(|f| f.bytes).sum();
+        let oldest = files.iter().map(|f| f.modified).min();
+        let newest = files.iter().map(|f| f.modified).max();
+        Ok(DirectoryUsage {
+            name: name.to_string(),
+            path,
+            bytes,
+            files: files.len() as u64,
+            oldest_modified_at: oldest.map(epoch_ms),
+            newest_modified_at: newest.map(epoch_ms),
+            max_bytes: limits.max_bytes,
+            max_age_ms: limits.max_age.map(|age| age.as_millis() as u64),
+            utilization: limits.max_bytes.map(|max| {
+                if max == 0 {
+                    1.0
+                } else {
+                    bytes as f64 / max as f64
+                }
+            }),
+        })
+    }
+
+    /// Fails with [`StorageError::Full`] if writing `incoming_bytes` more to
+    /// `name` would exceed its byte cap.
+    pub fn check_capacity(&self, name: &str, incoming_bytes: u64) -> Result<(), StorageError> {
+        let (path, limits) = self.lookup(name)?;
+        let Some(max_bytes) = limits.max_bytes else {
+            return Ok(());
+        };
+        let used_bytes: u64 = list_files(&path)?.iter().map(|f| f.bytes).sum();
+        if used_bytes.saturating_add(incoming_bytes) > max_bytes {
+            return Err(StorageError::Full {
+                directory: name.to_string(),
+                used_bytes,
+                max_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes `contents` to `file_name` in directory `name`, refusing the
+    /// write if it would take the directory over its byte cap. The file is
+    /// written under a temporary name and renamed into place, so readers and
+    /// the sweep never see it half-written.
+    pub fn write(
+        &self,
+        name: &str,
+        file_name: &str,
+        contents: &[u8],
+    ) -> Result<PathBuf, StorageError> {
+        if file_name.is_empty()
+            || file_name.starts_with('.')
+            || file_name.contains(['/', '\\'])
+            || file_name.ends_with(STORAGE_LOCK_SUFFIX)
+        {
+            return Err(StorageError::InvalidFileName(file_name.to_string()));
+        }
+        let (dir, write_lock) = {
+            let directories = self.directories.read().unwrap();
+            let directory = directories
+                .get(name)
+                .ok_or_else(|| StorageError::UnknownDirectory(name.to_string()))?;
+            (directory.path.clone(), Arc::clone(&directory.write_lock))
+        };
+
+        let _guard = write_lock.lock().unwrap();
+        self.check_capacity(name, contents.len() as u64)?;
+
+        let path = dir.join(file_name);
+        let temp = dir.join(format!(".{file_name}.{}.tmp", ulid::Ulid::new()));
+        self.in_flight.lock().unwrap().insert(path.clone());
+        let result = fs::write(&temp, contents).and_then(|()| fs::rename(&temp, &path));
+        self.in_flight.lock().unwrap().remove(&path);
+        if let Err(err) = result {
+            let _ = fs::remove_file(&temp);
+            return Err(err.into());
+        }
+        Ok(path)
+    }
+
+    /// Deletes expired files, then the oldest files of any directory over
+    /// its byte cap, and reports them through
+    /// [`TaskcastHooks::on_storage_swept`]. Returns what was removed.
+    pub fn sweep(&self) -> Vec<RemovedFile> {
+        let mut removed = Vec::new();
+        for name in self.directory_names() {
+            match self.sweep_directory(&name) {
+                Ok(mut files) => removed.append(&mut files),
+                Err(err) => self.report_error(&err),
+            }
+        }
+        if !removed.is_empty() {
+            if let Some(ref hooks) = self.hooks {
+                hooks.on_storage_swept(&removed);
+            }
+        }
+        removed
+    }
+
+    fn sweep_directory(&self, name: &str) -> Result<Vec<RemovedFile>, StorageError> {
+        let (path, limits) = self.lookup(name)?;
+        let mut files = list_files(&path)?;
+        files.sort_by_key(|f| f.modified);
+        let mut total: u64 = files.iter().map(|f| f.bytes).sum();
+
+        let now = SystemTime::now();
+        let locked = locked_files(&files);
+        let in_flight = self.in_flight.lock().unwrap().clone();
+        let sweepable = |file: &FileEntry| {
+            let age = now.duration_since(file.modified).unwrap_or_default();
+            age >= self.min_file_age
+                && !is_lock_file(&file.path)
+                && !locked.contains(&file.path)
+                && !in_flight.contains(&file.path)
+        };
+
+        let mut removed = Vec::new();
+        let mut remove = |file: &FileEntry, reason: RemovalReason| -> Result<(), StorageError> {
+            match fs::remove_file(&file.path) {
+                Ok(()) => {}
+                // Already gone, e.g. removed by its writer.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+            removed.push(RemovedFile {
+                directory: name.to_string(),
+                path: file.path.clone(),
+                bytes: file.bytes,
+                reason,
+            });
+            Ok(())
+        };
+
+        let mut kept = Vec::with_capacity(files.len());
+        for file in files {
+            let expired = limits.max_age.is_some_and(|max_age| {
+                now.duration_since(file.modified).unwrap_or_default() > max_age
+            });
+            if expired && sweepable(&file) {
+                remove(&file, RemovalReason::Age)?;
+                total -= file.bytes;
+            } else {
+                kept.push(file);
+            }
+        }
+
+        if let Some(max_bytes) = limits.max_bytes {
+            for file in &kept {
+                if total <= max_bytes {
+                    break;
+                }
+                if sweepable(file) {
+                    remove(file, RemovalReason::Cap)?;
+                    total -= file.bytes;
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Runs [`sweep`](Self::sweep) every `sweep_interval` under `runners`.
+    pub fn start(self: &Arc<Self>, runners: &RunnerSupervisor) {
+        let handle = runners.start(Arc::new(StorageSweepRunner(Arc::clone(self))));
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    pub fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<(PathBuf, DirectoryLimits), StorageError> {
+        let directories = self.directories.read().unwrap();
+        let directory = directories
+            .get(name)
+            .ok_or_else(|| StorageError::UnknownDirectory(name.to_string()))?;
+        Ok((directory.path.clone(), directory.limits))
+    }
+
+    fn report_error(&self, err: &StorageError) {
+        if let Some(ref hooks) = self.hooks {
+            hooks.on_unhandled_error(
+                err,
+                &ErrorContext {
+                    operation: "storage.sweep".to_string(),
+                    task_id: None,
+                },
+            );
+        }
+    }
+}
+
+/// The periodic sweep. The file walk blocks, so it runs off the runtime.
+struct StorageSweepRunner(Arc<StorageManager>);
+
+#[async_trait]
+impl Runner for StorageSweepRunner {
+    fn name(&self) -> &'static str {
+        "storage.sweep"
+    }
+
+    fn interval(&self) -> Duration {
+        self.0.sweep_interval
+    }
+
+    async fn tick(&self) -> Result<RunnerTickReport, Box<dyn std::error::Error + Send + Sync>> {
+        let manager = Arc::clone(&self.0);
+        let removed = tokio::task::spawn_blocking(move || manager.sweep()).await?;
+        Ok(RunnerTickReport::processed(removed.len() as u64))
+    }
+}
+
+/// Regular files under `dir`, recursively, excluding in-progress temporary
+/// files.
+fn list_files(dir: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                // Deleted between listing and stat.
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() && !is_temp_file(&entry.path()) {
+                files.push(FileEntry {
+                    path: entry.path(),
+                    bytes: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(".tmp"))
+}
+
+fn is_lock_file(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|path| path.ends_with(STORAGE_LOCK_SUFFIX))
+}
+
+/// Files that have a `<name>.lock` sibling.
+fn locked_files(files: &[FileEntry]) -> HashSet<PathBuf> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let path = file.path.to_str()?;
+            path.strip_suffix(STORAGE_LOCK_SUFFIX).map(PathBuf::from)
+        })
+        .collect()
+}
+
+fn epoch_ms(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
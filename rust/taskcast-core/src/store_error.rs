@@ -0,0 +1,135 @@
+//! Bounded errors for store adapters to return in place of raw driver errors.
+//!
+//! Driver messages can run to kilobytes, span lines, quote SQL or key names
+//! and come localized from the server. Adapters wrap them in a
+//! [`StoreError`], whose kind and short detail are what gets displayed. The
+//! original stays reachable through `source()`, for
+//! `TaskcastHooks::on_unhandled_error` and debug logging.
+
+use std::error::Error;
+
+/// Longest [`StoreError::detail`], in characters.
+pub const MAX_STORE_ERROR_DETAIL: usize = 200;
+
+/// What went wrong in a store, as far as a caller can act on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreErrorKind {
+    /// The backend is down, unreachable or out of resources.
+    Unavailable,
+    /// The backend did not answer in time.
+    Timeout,
+    /// A concurrent write won; retrying may succeed.
+    Conflict,
+    /// A value exceeded a backend limit.
+    TooLarge,
+    /// Stored data could not be read back.
+    Corrupt,
+    Other,
+}
+
+impl std::fmt::Display for StoreErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StoreErrorKind::Unavailable => "unavailable",
+            StoreErrorKind::Timeout => "timeout",
+            StoreErrorKind::Conflict => "conflict",
+            StoreErrorKind::TooLarge => "too large",
+            StoreErrorKind::Corrupt => "corrupt",
+            StoreErrorKind::Other => "other",
+        })
+    }
+}
+
+/// A classified store failure. Displays as its kind and [`detail`], never
+/// the full driver message.
+///
+/// [`detail`]: StoreError::detail
+#[derive(Debug, thiserror::Error)]
+#[error("Store error ({kind}): {detail}")]
+pub struct StoreError {
+    pub kind: StoreErrorKind,
+    /// The driver message on one line, without control characters and at
+    /// most [`MAX_STORE_ERROR_DETAIL`] characters long.
+    pub detail: String,
+    #[source]
+    source: Box<dyn Error + Send + Sync>,
+}
+
+impl StoreError {
+    pub fn new(kind: StoreErrorKind, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        let source = source.into();
+        Self {
+            kind,
+            detail: store_error_detail(&source.to_string()),
+            source,
+        }
+    }
+}
+
+/// Shortens `message` to a single line of at most
+/// [`MAX_STORE_ERROR_DETAIL`] characters, replacing control characters and
+/// runs of whitespace with one space.
+pub fn store_error_detail(message: &str) -> String {
+    let line = message
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if line.chars().count() <= MAX_STORE_ERROR_DETAIL {
+        return line;
+    }
+    let mut detail: String = line.chars().take(MAX_STORE_ERROR_DETAIL - 1).collect();
+    detail.push('…');
+    detail
+}
+
+/// A task group write that failed part way and could not be fully undone.
+/// The tasks in `task_ids` may have been left in the store; no other task
+/// of the group was.
+#[derive(Debug, thiserror::Error)]
+#[error("Task group write failed and tasks {} may remain: {source}", task_ids.join(", "))]
+pub struct PartialTaskGroup {
+    pub task_ids: Vec<String>,
+    /// What failed the write.
+    #[source]
+    pub source: Box<dyn Error + Send + Sync>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detail_is_one_bounded_line() {
+        let message = format!(
+            "ERROR:  value too long\n\tDETAIL:  row \u{1b}[31m{}\r\nCONTEXT: SQL",
+            "x".repeat(500)
+        );
+        let detail = store_error_detail(&message);
+        assert!(detail.starts_with("ERROR: value too long DETAIL: row [31mxxx"));
+        assert_eq!(detail.chars().count(), MAX_STORE_ERROR_DETAIL);
+        assert!(detail.ends_with('…'));
+        assert!(!detail.contains(|c: char| c.is_control()));
+    }
+
+    #[test]
+    fn short_detail_is_kept_whole() {
+        assert_eq!(
+            store_error_detail("  connection refused \n"),
+            "connection refused"
+        );
+        assert_eq!(store_error_detail(""), "");
+    }
+
+    #[test]
+    fn display_is_bounded_and_source_is_full() {
+        let raw = format!("relation \"tasks\" {}", "y".repeat(1000));
+        let err = StoreError::new(StoreErrorKind::TooLarge, raw.clone());
+        assert_eq!(err.kind, StoreErrorKind::TooLarge);
+        assert!(err
+            .to_string()
+            .starts_with("Store error (too large): relation"));
+        assert!(err.to_string().chars().count() < 250);
+        assert_eq!(err.source().unwrap().to_string(), raw);
+    }
+}
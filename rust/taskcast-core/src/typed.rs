@@ -0,0 +1,169 @@
+//! Typed event payloads.
+//!
+//! Events carry a `type` string and a JSON `data` payload. A [`TypedEvent`]
+//! ties the two to a Rust type, so an embedding application can publish and
+//! read events without building `serde_json::Value`s by hand:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use taskcast_core::TypedEvent;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct TokenDelta {
+//!     text: String,
+//! }
+//!
+//! impl TypedEvent for TokenDelta {
+//!     const TYPE: &'static str = "llm.delta";
+//!     type Data = Self;
+//! }
+//! ```
+//!
+//! Reads decode each event's `data` separately, so an event stored with a
+//! different shape surfaces as a [`DecodeError`] for that event alone. The
+//! SSE envelopes and the untyped engine methods are unaffected.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{EngineError, PublishEventInput, TaskEngine};
+use crate::types::{EventQueryOptions, Level, TaskEvent};
+
+/// An event type with a statically known payload.
+pub trait TypedEvent {
+    /// The event `type` the payload is published under. Matched exactly, not
+    /// as a wildcard pattern.
+    const TYPE: &'static str;
+    /// The payload stored in the event's `data`.
+    type Data: Serialize + DeserializeOwned;
+}
+
+/// A stored event that could not be read as a [`TypedEvent`].
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error("event {event_id} has type {actual}, expected {expected}")]
+    WrongType {
+        event_id: String,
+        expected: &'static str,
+        actual: String,
+    },
+
+    #[error("event {event_id} does not match the {event_type} payload: {source}")]
+    Data {
+        event_id: String,
+        event_type: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Decodes `event`'s `data` as `E::Data`.
+pub fn decode_event<E: TypedEvent>(event: &TaskEvent) -> Result<E::Data, DecodeError> {
+    if event.r#type != E::TYPE {
+        return Err(DecodeError::WrongType {
+            event_id: event.id.clone(),
+            expected: E::TYPE,
+            actual: event.r#type.clone(),
+        });
+    }
+    E::Data::deserialize(&event.data).map_err(|source| DecodeError::Data {
+        event_id: event.id.clone(),
+        event_type: E::TYPE,
+        source,
+    })
+}
+
+// ─── Built-in Payloads ──────────────────────────────────────────────────────
+
+/// `progress`: how far along a task is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    /// 0 to 100.
+    pub percent: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl TypedEvent for ProgressEvent {
+    const TYPE: &'static str = "progress";
+    type Data = Self;
+}
+
+/// `log`: a line of task output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEvent {
+    pub text: String,
+    /// The part of the task that wrote the line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+}
+
+impl TypedEvent for LogEvent {
+    const TYPE: &'static str = "log";
+    type Data = Self;
+}
+
+// ─── Engine Extensions ──────────────────────────────────────────────────────
+
+impl TaskEngine {
+    /// Publishes `data` as an `E::TYPE` event. See [`publish_event`](Self::publish_event).
+    pub async fn publish_typed<E: TypedEvent>(
+        &self,
+        task_id: &str,
+        data: E::Data,
+        level: Level,
+    ) -> Result<TaskEvent, EngineError> {
+        let data = serde_json::to_value(&data).map_err(|e| {
+            EngineError::InvalidInput(format!("{} payload does not serialize: {e}", E::TYPE))
+        })?;
+        self.publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: E::TYPE.to_string(),
+                level,
+                data,
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+    }
+
+    /// The task's `E::TYPE` events, decoded, in index order. Other event
+    /// types are skipped; `opts.limit` applies before they are.
+    pub async fn get_typed_events<E: TypedEvent>(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<Result<E::Data, DecodeError>>, EngineError> {
+        Ok(self
+            .get_events(task_id, opts)
+            .await?
+            .iter()
+            .filter(|event| event.r#type == E::TYPE)
+            .map(decode_event::<E>)
+            .collect())
+    }
+
+    /// Like [`subscribe`](Self::subscribe), calling `handler` with the
+    /// decoded payload of each live `E::TYPE` event.
+    pub async fn subscribe_typed<E: TypedEvent + 'static>(
+        &self,
+        task_id: &str,
+        handler: Box<dyn Fn(Result<E::Data, DecodeError>) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.subscribe(
+            task_id,
+            Box::new(move |event| {
+                if event.r#type == E::TYPE {
+                    handler(decode_event::<E>(&event));
+                }
+            }),
+        )
+        .await
+    }
+}
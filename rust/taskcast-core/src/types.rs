@@ -1,12 +1,20 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::compression::CompressionStats;
+use crate::conventions::EventConventionStore;
+use crate::integrity::IntegrityMonitor;
+use crate::migration::MigratingShortTermStore;
+use crate::query_stats::QueryStatsSnapshot;
 
 // ─── Task ───────────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TaskStatus {
+    /// Created with `scheduledFor`; becomes `pending` when that time arrives.
+    Scheduled,
     Pending,
     Assigned,
     Running,
@@ -40,6 +48,8 @@ pub enum PermissionScope {
     EventSubscribe,
     #[serde(rename = "event:history")]
     EventHistory,
+    #[serde(rename = "event:delete")]
+    EventDelete,
     #[serde(rename = "webhook:create")]
     WebhookCreate,
     #[serde(rename = "worker:connect")]
@@ -50,10 +60,33 @@ pub enum PermissionScope {
     TaskResolve,
     #[serde(rename = "task:signal")]
     TaskSignal,
+    #[serde(rename = "task:replicate")]
+    TaskReplicate,
+    /// Change a task's status, including cancelling it.
+    #[serde(rename = "task:transition")]
+    TaskTransition,
+    /// Read tasks: get, list and export them.
+    #[serde(rename = "task:read")]
+    TaskRead,
     #[serde(rename = "*")]
     All,
 }
 
+impl PermissionScope {
+    /// The narrower scopes a token holding this one is also granted, unless
+    /// `auth.strictScopes` is set. `task:manage` covers the task scopes split
+    /// out of it, and `event:subscribe` the task reads it used to guard.
+    pub fn implied(&self) -> &'static [PermissionScope] {
+        match self {
+            PermissionScope::TaskManage => {
+                &[PermissionScope::TaskTransition, PermissionScope::TaskRead]
+            }
+            PermissionScope::EventSubscribe => &[PermissionScope::TaskRead],
+            _ => &[],
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskAuthRule {
@@ -88,12 +121,125 @@ pub struct WebhookConfig {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub filter: Option<SubscribeFilter>,
+    /// Name of one of the task's `filters`; `filter` narrows it further.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_preset: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secret: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wrap: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+    /// Webhooks sharing a group are delivered according to the task's
+    /// `group_policy`; ungrouped webhooks always receive matching events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Drops an event whose type and level match one delivered to this
+    /// webhook within the window.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression_window_ms: Option<u64>,
+    /// Defaults to [`WebhookMode::Async`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<WebhookMode>,
+    /// History delivered before live events when the webhook is added to an
+    /// existing task. Ignored at task creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backfill: Option<WebhookBackfill>,
+    /// Posts a `task.snapshot` of the task when the webhook becomes active,
+    /// ahead of any backfilled or live event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_delivery: Option<bool>,
+    /// Largest event delivery body to post, in bytes. A larger one is sent
+    /// with its `data` replaced by a pointer to fetch the event from.
+    /// Defaults to `webhook.maxPayloadBytes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_payload_bytes: Option<usize>,
+    /// Conditions on the task, checked against its current state on every
+    /// delivery: a task that stops matching stops receiving deliveries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_match: Option<TaskMatch>,
+}
+
+/// Which of a task's earlier events a newly added webhook receives:
+/// `"all"`, or a cursor object as in `since`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookBackfill {
+    All,
+    Since(SinceCursor),
+}
+
+impl Serialize for WebhookBackfill {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::All => serializer.serialize_str("all"),
+            Self::Since(cursor) => cursor.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookBackfill {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawBackfill {
+            Keyword(String),
+            Since(SinceCursor),
+        }
+
+        match RawBackfill::deserialize(deserializer)? {
+            RawBackfill::Keyword(keyword) if keyword == "all" => Ok(Self::All),
+            RawBackfill::Keyword(keyword) => Err(serde::de::Error::custom(format!(
+                "unknown backfill \"{keyword}\", expected \"all\" or a cursor"
+            ))),
+            RawBackfill::Since(cursor) => Ok(Self::Since(cursor)),
+        }
+    }
+}
+
+impl utoipa::PartialSchema for WebhookBackfill {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{ObjectBuilder, OneOfBuilder, Type};
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["all"])),
+            )
+            .item(SinceCursor::schema())
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for WebhookBackfill {}
+
+/// When a webhook is delivered relative to the call that produced the event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookMode {
+    /// Delivered inline, before the publish or transition request returns,
+    /// with a single attempt bounded by the server's sync timeout.
+    Sync,
+    /// Delivered in the background with the webhook's retry policy.
+    #[default]
+    Async,
+}
+
+/// How an event is delivered to webhooks sharing a `group`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookGroupPolicy {
+    /// Only the first webhook of the group, in declaration order, whose
+    /// filter matches.
+    FirstMatch,
+    /// Every webhook of the group whose filter matches.
+    #[default]
+    All,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -104,6 +250,26 @@ pub enum BackoffStrategy {
     Linear,
 }
 
+/// Randomness applied to a computed retry delay, so retries that failed
+/// together do not all come back at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RetryJitter {
+    /// The delay as computed.
+    #[default]
+    None,
+    /// Anywhere from zero to the delay.
+    Full,
+    /// Half the delay plus up to the other half.
+    Equal,
+}
+
+fn jitter_is_none(jitter: &RetryJitter) -> bool {
+    *jitter == RetryJitter::None
+}
+
+/// How a webhook delivery is retried. Delays follow `backoff`; see
+/// [`compute_backoff`](crate::backoff::compute_backoff).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RetryConfig {
@@ -112,6 +278,109 @@ pub struct RetryConfig {
     pub initial_delay_ms: u64,
     pub max_delay_ms: u64,
     pub timeout_ms: u64,
+    #[serde(default, skip_serializing_if = "jitter_is_none")]
+    pub jitter: RetryJitter,
+    /// Stops retrying once the next retry would start this long after the
+    /// first attempt, whatever `retries` allows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_elapsed_ms: Option<u64>,
+}
+
+/// Terminal statuses a [`RetryPolicy`] re-runs a task on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RetryOn {
+    Failed,
+    Timeout,
+}
+
+fn default_retry_on() -> Vec<RetryOn> {
+    vec![RetryOn::Failed, RetryOn::Timeout]
+}
+
+/// Re-runs a task that ends in one of `retry_on` by creating a successor
+/// task after a backoff delay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Total runs, counting the original task.
+    pub max_attempts: u32,
+    pub backoff: BackoffStrategy,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl RetryPolicy {
+    /// Whether a task ending in `status` is retried by this policy.
+    pub fn retries_on(&self, status: &TaskStatus) -> bool {
+        let on = match status {
+            TaskStatus::Failed => RetryOn::Failed,
+            TaskStatus::Timeout => RetryOn::Timeout,
+            _ => return false,
+        };
+        self.retry_on.contains(&on)
+    }
+
+    /// Delay before the run after `attempt` (1-based) ended.
+    pub fn delay_ms(&self, attempt: u32) -> u64 {
+        crate::backoff::strategy_delay_ms(&self.backoff, self.initial_delay_ms, attempt)
+            .min(self.max_delay_ms)
+    }
+}
+
+/// Copies events a task emits onto another task, so a parent can observe a
+/// child through its own stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardRule {
+    pub target_task_id: String,
+    /// Which of the task's events are forwarded; all of them when empty.
+    #[serde(default)]
+    pub filter: SubscribeFilter,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<ForwardTransform>,
+}
+
+/// How a forwarded event differs from the one it copies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardTransform {
+    /// Prepended to the event type with a `:`, e.g. `child` turns
+    /// `progress` into `child:progress`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix_type: Option<String>,
+    /// Level of the forwarded event in place of the original's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<Level>,
+}
+
+/// When a task ends on its own once its children have ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CompletionMode {
+    /// The task ends only by explicit transitions.
+    #[default]
+    Manual,
+    /// Once every child has ended: `completed` if all of them completed,
+    /// `failed` otherwise.
+    AllChildren,
+    /// `failed` as soon as a child ends other than `completed`, `completed`
+    /// once all of them have completed.
+    AnyChildFailure,
+}
+
+/// Ends a task automatically when its child tasks end.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionPolicy {
+    #[serde(default)]
+    pub mode: CompletionMode,
+    /// The children awaited. Tasks created with this task as their
+    /// `parentId` are appended.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub child_ids: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -120,9 +389,13 @@ pub enum SeriesMode {
     KeepAll,
     Accumulate,
     Latest,
+    /// Each event's data is an RFC 6902 patch to the series document.
+    JsonPatch,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, utoipa::ToSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub enum Level {
     Debug,
@@ -184,7 +457,48 @@ pub struct CleanupRule {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CleanupConfig {
+    #[serde(default)]
     pub rules: Vec<CleanupRule>,
+    /// Overrides the global `cleanup.abandonedPending` for this task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abandoned_pending: Option<AbandonedPendingConfig>,
+}
+
+/// What happens to a task left `pending` past its abandonment window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AbandonAction {
+    /// Cancel it with error code `ABANDONED`, emitting the status event.
+    Cancel,
+    /// Remove it from every store.
+    Delete,
+}
+
+/// Handling of tasks that were created but never started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AbandonedPendingConfig {
+    /// How long after creation a `pending` task counts as abandoned.
+    pub after_ms: u64,
+    pub action: AbandonAction,
+    /// Also treat pending tasks that have published events as abandoned.
+    /// Defaults to false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_with_events: Option<bool>,
+}
+
+// ─── Deadline Warnings ──────────────────────────────────────────────────────
+
+/// When to warn that a task is approaching its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(untagged)]
+pub enum DeadlineWarning {
+    /// Once this fraction of the allotted time has passed, e.g. `0.8`.
+    #[serde(rename_all = "camelCase")]
+    AtFraction { at_fraction: f64 },
+    /// This many milliseconds before the deadline.
+    #[serde(rename_all = "camelCase")]
+    BeforeMs { before_ms: u64 },
 }
 
 // ─── Worker Assignment ──────────────────────────────────────────────────────
@@ -334,10 +648,198 @@ pub struct TaskFilter {
     pub assign_mode: Option<Vec<AssignMode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_task_ids: Option<Vec<String>>,
+    /// Only tasks created before this time (epoch ms).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<f64>,
+    /// Only tasks whose origin is one of these kinds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Vec<TaskOriginKind>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
 }
 
+/// A position in `(createdAt, id)` order, for paging through tasks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskCursor {
+    pub created_at: f64,
+    pub id: String,
+}
+
+impl TaskCursor {
+    /// The position of `task`.
+    pub fn of(task: &Task) -> Self {
+        Self {
+            created_at: task.created_at,
+            id: task.id.clone(),
+        }
+    }
+
+    /// Whether `task` comes after this position.
+    pub fn precedes(&self, task: &Task) -> bool {
+        task_order(self.created_at, &self.id, task.created_at, &task.id).is_lt()
+    }
+}
+
+impl std::fmt::Display for TaskCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.created_at, self.id)
+    }
+}
+
+impl std::str::FromStr for TaskCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (created_at, id) = s
+            .split_once(':')
+            .filter(|(_, id)| !id.is_empty())
+            .ok_or_else(|| "must be a cursor from a previous response".to_string())?;
+        let created_at = created_at
+            .parse::<f64>()
+            .ok()
+            .filter(|at| at.is_finite())
+            .ok_or_else(|| "must be a cursor from a previous response".to_string())?;
+        Ok(Self {
+            created_at,
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Orders tasks by creation time, then id.
+pub fn task_order(
+    a_created_at: f64,
+    a_id: &str,
+    b_created_at: f64,
+    b_id: &str,
+) -> std::cmp::Ordering {
+    a_created_at
+        .total_cmp(&b_created_at)
+        .then_with(|| a_id.cmp(b_id))
+}
+
+/// One page of [`ShortTermStore::list_tasks_page`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskPage {
+    /// Matching tasks in `(createdAt, id)` order.
+    pub tasks: Vec<Task>,
+    /// Where the next page starts, or `None` on the last page.
+    pub next: Option<TaskCursor>,
+}
+
+impl TaskPage {
+    /// The page of at most `limit` tasks from `tasks`, which are already
+    /// sorted and follow the requested position.
+    pub fn from_sorted(mut tasks: Vec<Task>, limit: usize) -> Self {
+        let next = if tasks.len() > limit {
+            tasks.truncate(limit);
+            tasks.last().map(TaskCursor::of)
+        } else {
+            None
+        };
+        Self { tasks, next }
+    }
+
+    /// The first `limit` tasks of this page and `other`, both starting at
+    /// the same position and read with the same `limit`. A task on both
+    /// pages is kept once, as this page has it.
+    pub fn merge(self, other: TaskPage, limit: usize) -> Self {
+        let more = self.next.is_some() || other.next.is_some();
+        let mut tasks = Vec::with_capacity(self.tasks.len() + other.tasks.len());
+        let mut ours = self.tasks.into_iter().peekable();
+        let mut theirs = other.tasks.into_iter().peekable();
+        loop {
+            let order = match (ours.peek(), theirs.peek()) {
+                (Some(a), Some(b)) => task_order(a.created_at, &a.id, b.created_at, &b.id),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => break,
+            };
+            match order {
+                std::cmp::Ordering::Less => tasks.extend(ours.next()),
+                std::cmp::Ordering::Greater => tasks.extend(theirs.next()),
+                std::cmp::Ordering::Equal => {
+                    tasks.extend(ours.next());
+                    theirs.next();
+                }
+            }
+        }
+        // Each page holds `limit` tasks when its store has more, so the
+        // first `limit` merged tasks come before anything left unread.
+        let mut page = Self::from_sorted(tasks, limit);
+        if more && page.next.is_none() {
+            page.next = page.tasks.last().map(TaskCursor::of);
+        }
+        page
+    }
+}
+
+/// Selects one page of tasks for `TaskEngine::query_tasks`. Unset filters
+/// match everything.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ListTasksQuery {
+    pub status: Option<Vec<TaskStatus>>,
+    /// A type pattern, matched as event types are in filters (`crawl.*`).
+    pub r#type: Option<String>,
+    /// Only tasks whose origin is one of these kinds.
+    pub origin: Option<Vec<TaskOriginKind>>,
+    /// Only tasks created at or after this time (epoch ms).
+    pub created_after: Option<f64>,
+    /// Only tasks created before this time (epoch ms).
+    pub created_before: Option<f64>,
+    /// Keep tasks after this position.
+    pub cursor: Option<TaskCursor>,
+    /// Most tasks to return; `None` for every match.
+    pub limit: Option<usize>,
+}
+
+impl ListTasksQuery {
+    /// Where the page starts: after `cursor`, and not before
+    /// `created_after`.
+    pub fn start(&self) -> Option<TaskCursor> {
+        // Every id sorts after the empty one, so this starts at `created_after`.
+        let created_after = self.created_after.map(|created_at| TaskCursor {
+            created_at,
+            id: String::new(),
+        });
+        [self.cursor.clone(), created_after]
+            .into_iter()
+            .flatten()
+            .max_by(|a, b| task_order(a.created_at, &a.id, b.created_at, &b.id))
+    }
+
+    /// The short-term store filter for everything but the position. A
+    /// wildcard type is left to [`matches_type`](Self::matches_type).
+    pub fn filter(&self) -> TaskFilter {
+        TaskFilter {
+            status: self.status.clone(),
+            types: self
+                .r#type
+                .as_ref()
+                .filter(|pattern| !is_type_pattern(pattern))
+                .map(|task_type| vec![task_type.clone()]),
+            origin: self.origin.clone(),
+            created_before: self.created_before,
+            ..Default::default()
+        }
+    }
+
+    /// Whether `task` has the queried type.
+    pub fn matches_type(&self, task: &Task) -> bool {
+        self.r#type.as_ref().is_none_or(|pattern| {
+            task.r#type.as_deref().is_some_and(|task_type| {
+                crate::filter::matches_type(task_type, Some(std::slice::from_ref(pattern)))
+            })
+        })
+    }
+}
+
+/// Whether `pattern` is a wildcard rather than a single type.
+pub fn is_type_pattern(pattern: &str) -> bool {
+    pattern == "*" || pattern.ends_with(".*")
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Task {
@@ -381,10 +883,199 @@ pub struct Task {
     pub resume_at: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blocked_request: Option<BlockedRequest>,
+    /// Named filter presets that subscribers, history queries and webhooks
+    /// can reference instead of repeating the filter inline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<HashMap<String, SubscribeFilter>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_policy: Option<RetryPolicy>,
+    /// Delivery policy for webhook groups; `all` when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_policy: Option<WebhookGroupPolicy>,
+    /// Where the task's events are also published.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forward_to: Option<ForwardRule>,
+    /// When a `scheduled` task becomes `pending`, in epoch milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_for: Option<f64>,
+    /// Milliseconds the task is allotted, counted like `ttl` from creation
+    /// or activation. Deadline warnings count down to it; unlike `ttl` it
+    /// never times the task out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_ms: Option<u64>,
+    /// Who created the task. Set once at creation and never changed by
+    /// updates. Absent on tasks saved before origins existed, which count as
+    /// `user`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<TaskOrigin>,
+    /// The task this one was created as a child of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Ends the task when its children end; `manual` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_policy: Option<CompletionPolicy>,
+    /// Bumped by every save, starting at 1, so a writer can tell whether the
+    /// task changed since it read it. Absent means 0, the version of a task
+    /// saved before versions existed.
+    #[serde(default, skip_serializing_if = "version_is_unset")]
+    pub version: u64,
+}
+
+fn version_is_unset(version: &u64) -> bool {
+    *version == 0
+}
+
+impl Task {
+    /// Kind of actor that created the task; `user` when no origin is
+    /// recorded.
+    pub fn origin_kind(&self) -> TaskOriginKind {
+        self.origin
+            .as_ref()
+            .map_or(TaskOriginKind::User, |origin| origin.kind)
+    }
+}
+
+/// Kind of actor behind a task's creation.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskOriginKind {
+    /// A client of the API or an embedder calling the engine.
+    #[default]
+    User,
+    /// The successor a retry policy created for an ended task.
+    Retry,
+    /// A task created on a schedule.
+    Schedule,
+    /// A task recreated from recorded history.
+    Replay,
+    /// A copy received from another deployment.
+    Replication,
+    /// Taskcast itself, such as a placeholder from a consistency repair.
+    System,
+}
+
+impl TaskOriginKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Retry => "retry",
+            Self::Schedule => "schedule",
+            Self::Replay => "replay",
+            Self::Replication => "replication",
+            Self::System => "system",
+        }
+    }
+}
+
+/// Provenance of a task: which actor created it, and from what.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskOrigin {
+    pub kind: TaskOriginKind,
+    /// The task this one was created from, such as the task a retry
+    /// succeeds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_task_id: Option<String>,
+    /// Free-form detail, such as the subject of the token a user task was
+    /// created with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl TaskOrigin {
+    pub fn new(kind: TaskOriginKind) -> Self {
+        Self {
+            kind,
+            source_task_id: None,
+            detail: None,
+        }
+    }
+
+    pub fn with_source_task_id(mut self, task_id: impl Into<String>) -> Self {
+        self.source_task_id = Some(task_id.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// A successor task waiting to be created for a task its [`RetryPolicy`]
+/// retries. Persisted in the short-term store so it survives restarts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetrySchedule {
+    /// The task that ended and is being retried.
+    pub task_id: String,
+    /// Id the successor is created with.
+    pub successor_id: String,
+    /// The successor's attempt number; the original task is attempt 1.
+    pub attempt: u32,
+    /// When the successor is due, in epoch milliseconds.
+    pub due_at: f64,
+}
+
+/// Compact record of a task reaching a terminal status, kept in the
+/// short-term store's outcomes index for cheap polling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskOutcome {
+    pub task_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    pub status: TaskStatus,
+    /// In epoch milliseconds.
+    pub completed_at: f64,
+    /// `error.code` of a task that ended with an error carrying one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// Kind of actor that created the task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<TaskOriginKind>,
+}
+
+/// What a task can do next: the statuses it may move to from its current
+/// one, and whether events may still be published to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskTransitions {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub transitions: Vec<TaskStatus>,
+    pub can_publish: bool,
+}
+
+/// Position in the outcomes index, ordered by `completed_at` then task id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutcomeCursor {
+    pub completed_at: f64,
+    pub task_id: String,
+}
+
+/// Selects entries of the outcomes index. Unset filters match everything.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutcomeQuery {
+    pub statuses: Option<Vec<TaskStatus>>,
+    /// Type patterns, matched as event types are in filters (`import.*`).
+    pub types: Option<Vec<String>>,
+    /// Keep entries completed at or after this time, in epoch milliseconds.
+    pub since: Option<f64>,
+    /// Keep entries after this position.
+    pub after: Option<OutcomeCursor>,
+    /// Most entries to return.
+    pub limit: usize,
 }
 
 // ─── Events ─────────────────────────────────────────────────────────────────
 
+fn labels_is_empty(labels: &Option<HashMap<String, String>>) -> bool {
+    labels.as_ref().is_none_or(|l| l.is_empty())
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskEvent {
@@ -403,16 +1094,28 @@ pub struct TaskEvent {
     pub series_acc_field: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub series_snapshot: Option<bool>,
+    #[serde(default, skip_serializing_if = "labels_is_empty")]
+    pub labels: Option<HashMap<String, String>>,
+    /// Set on a live `latest` series event that took the place of an
+    /// earlier one in history: the id of the event it replaced. Only present
+    /// on broadcast; stored history holds just the new event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces_event_id: Option<String>,
     /// Transient: accumulated data attached during broadcast, not persisted.
     #[serde(skip)]
     pub _accumulated_data: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+/// How `accumulate` series events are delivered to a subscriber.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum SeriesFormat {
+    /// The event's own chunk.
     Delta,
+    /// The series' running total as of the event.
     Accumulated,
+    /// `{"delta": ..., "accumulated": ...}`.
+    Both,
 }
 
 /// Result of series processing: original delta event + optional accumulated event.
@@ -422,6 +1125,31 @@ pub struct SeriesResult {
     pub accumulated_event: Option<TaskEvent>,
     /// Whether process_series already stored the event (e.g. latest mode uses replace_last_series_event).
     pub stored: bool,
+    /// The event a `latest` series event replaced in history.
+    pub replaced_event_id: Option<String>,
+}
+
+/// A series the short-term store keeps state for, as of its latest event.
+/// `keep-all` series keep no state and are not listed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesSummary {
+    pub series_id: String,
+    pub mode: Option<SeriesMode>,
+    pub latest_index: u64,
+    pub latest_timestamp: f64,
+}
+
+impl SeriesSummary {
+    /// Summary of the series `latest` is the latest event of.
+    pub fn of(series_id: &str, latest: &TaskEvent) -> Self {
+        Self {
+            series_id: series_id.to_string(),
+            mode: latest.series_mode.clone(),
+            latest_index: latest.index,
+            latest_timestamp: latest.timestamp,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
@@ -443,10 +1171,124 @@ pub struct SSEEnvelope {
     pub series_acc_field: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub series_snapshot: Option<bool>,
+    #[serde(default, skip_serializing_if = "labels_is_empty")]
+    pub labels: Option<HashMap<String, String>>,
+    /// See [`TaskEvent::replaces_event_id`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces_event_id: Option<String>,
+    /// Generation of a named consumer's persisted numbering, bumped when its
+    /// filter changes and `filtered_index` restarts at 0. Absent for
+    /// per-connection numbering.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_epoch: Option<u64>,
+}
+
+/// How many of a task's stored events have each type and each level.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTypeCounts {
+    pub types: BTreeMap<String, u64>,
+    pub levels: BTreeMap<Level, u64>,
+}
+
+impl EventTypeCounts {
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a TaskEvent>) -> Self {
+        let mut counts = Self::default();
+        for event in events {
+            counts.add(event);
+        }
+        counts
+    }
+
+    pub fn add(&mut self, event: &TaskEvent) {
+        *self.types.entry(event.r#type.clone()).or_default() += 1;
+        *self.levels.entry(event.level.clone()).or_default() += 1;
+    }
+
+    /// Takes `event` back out, dropping entries that reach zero.
+    pub fn remove(&mut self, event: &TaskEvent) {
+        fn decrement<K: Ord>(counts: &mut BTreeMap<K, u64>, key: &K) {
+            if let Some(count) = counts.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(key);
+                }
+            }
+        }
+        decrement(&mut self.types, &event.r#type);
+        decrement(&mut self.levels, &event.level);
+    }
+
+    /// Number of events counted.
+    pub fn total(&self) -> u64 {
+        self.types.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
 }
 
 // ─── Subscription ────────────────────────────────────────────────────────────
 
+/// Where a named consumer's persisted filtered numbering of a task stands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilteredIndexMark {
+    /// [`filter_hash`](crate::filter::filter_hash) of the filter the
+    /// numbering follows.
+    pub filter_hash: String,
+    /// Bumped each time the filter changes and the numbering restarts.
+    pub epoch: u64,
+    /// Filtered index of the next event numbered.
+    pub next: u64,
+    /// Highest raw index numbered so far.
+    pub through: Option<u64>,
+}
+
+/// A position handed out by [`ShortTermStore::claim_filtered_index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilteredIndexClaim {
+    pub filtered_index: u64,
+    pub epoch: u64,
+}
+
+impl FilteredIndexMark {
+    /// Numbers the event at `raw_index` after `mark` under `filter_hash`. A
+    /// missing mark starts epoch 0; a mark kept under another filter hash
+    /// restarts at 0 in the next epoch.
+    pub fn claim(
+        mark: Option<Self>,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> (Self, FilteredIndexClaim) {
+        let mark = match mark {
+            Some(mark) if mark.filter_hash == filter_hash => mark,
+            previous => Self {
+                filter_hash: filter_hash.to_string(),
+                epoch: previous.map_or(0, |mark| mark.epoch + 1),
+                next: 0,
+                through: None,
+            },
+        };
+        let claim = FilteredIndexClaim {
+            filtered_index: mark.next,
+            epoch: mark.epoch,
+        };
+        let through = mark
+            .through
+            .map_or(raw_index, |through| through.max(raw_index));
+        (
+            Self {
+                next: mark.next + 1,
+                through: Some(through),
+                ..mark
+            },
+            claim,
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SinceCursor {
@@ -458,7 +1300,7 @@ pub struct SinceCursor {
     pub timestamp: Option<f64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubscribeFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -471,17 +1313,88 @@ pub struct SubscribeFilter {
     pub include_status: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wrap: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "seriesView", skip_serializing_if = "Option::is_none")]
     pub series_format: Option<SeriesFormat>,
+    /// Replay each `json-patch` series as one snapshot event carrying its
+    /// current document, followed by any newer patches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub materialize_series: Option<bool>,
+    /// Equality selector on event labels; every entry must match (AND).
+    /// `taskcast:status` events are not subject to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<HashMap<String, String>>,
+    /// Conditions on the task itself rather than its events. SSE checks them
+    /// once when a client connects, webhooks on every delivery.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_match: Option<TaskMatch>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+/// Conditions on the task events belong to. Every given field must match.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskMatch {
+    /// Task type patterns, with the syntax of event type patterns. A task
+    /// without a type matches none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+    /// Metadata entries the task must have, with equal values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<TaskStatus>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EventQueryOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub since: Option<SinceCursor>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
+    /// Equality selector on event labels, applied before `limit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<HashMap<String, String>>,
+    /// Keep only events with a `timestamp` at or before this one, in epoch
+    /// milliseconds. Applied before `limit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<f64>,
+    /// Keep only events with an index below this one. Applied before
+    /// `limit`; set from a [`ScanToken`] by [`ScanToken::bound`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_index: Option<u64>,
+}
+
+/// A stable prefix of a task's history, taken by
+/// [`ShortTermStore::begin_scan`]. Reads bounded by it see the same events
+/// however many store calls they take, while publishers keep appending.
+///
+/// A latest-mode series event replaced during the scan is the one exception:
+/// the replacement is read if it falls inside the prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanToken {
+    pub task_id: String,
+    /// Index of the first event after the prefix. `None` leaves reads
+    /// unbounded, for a history that no longer grows.
+    pub end_index: Option<u64>,
+}
+
+impl ScanToken {
+    /// `opts` limited to the prefix, keeping a tighter bound it already has.
+    pub fn bound(&self, opts: Option<EventQueryOptions>) -> Option<EventQueryOptions> {
+        let Some(end_index) = self.end_index else {
+            return opts;
+        };
+        let mut opts = opts.unwrap_or_default();
+        opts.before_index = Some(opts.before_index.map_or(end_index, |i| i.min(end_index)));
+        Some(opts)
+    }
+
+    /// Whether `event` is inside the prefix.
+    pub fn covers(&self, event: &TaskEvent) -> bool {
+        self.end_index
+            .is_none_or(|end_index| event.index < end_index)
+    }
 }
 
 // ─── Archive ────────────────────────────────────────────────────────────────
@@ -513,6 +1426,8 @@ pub struct TaskArchiveEvent {
     pub series_mode: Option<SeriesMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub series_acc_field: Option<String>,
+    #[serde(default, skip_serializing_if = "labels_is_empty")]
+    pub labels: Option<HashMap<String, String>>,
 }
 
 impl<'de> Deserialize<'de> for TaskArchive {
@@ -595,6 +1510,25 @@ pub struct TaskArchiveRestoreData {
 
 // ─── Storage Interfaces ──────────────────────────────────────────────────────
 
+/// Result of probing one of a store's connection pools.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolHealth {
+    /// `write`, `read`, ...
+    pub name: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Fans task events out to subscribed handlers.
+///
+/// Handlers run inline, on whichever task delivers the event (the publisher
+/// for in-process providers, the listener for networked ones), and after the
+/// provider has released its handler registry lock, so a handler may
+/// subscribe or unsubscribe. Handlers must not block: hand slow work off to
+/// a channel or a spawned task.
 #[async_trait]
 pub trait BroadcastProvider: Send + Sync {
     async fn publish(
@@ -624,11 +1558,158 @@ pub trait BroadcastProvider: Send + Sync {
             "subscribe_sync is not supported by this broadcast provider",
         )))
     }
+
+    /// Subscribes `handler` to every channel whose name matches the glob
+    /// `pattern`, with Redis `PSUBSCRIBE` semantics (see
+    /// [`channel_matches`](crate::channels::channel_matches)). An event is
+    /// delivered once per matching channel it was published to.
+    ///
+    /// The default implementation returns an error. Providers that support
+    /// pattern subscriptions should override this.
+    async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        let _ = (pattern, handler);
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "subscribe_pattern is not supported by this broadcast provider",
+        )))
+    }
+
+    /// Like [`subscribe`](Self::subscribe), for a subscriber that does not
+    /// want status changes: `handler` is not called for
+    /// [intermediate status events](crate::event_stream::is_intermediate_status).
+    ///
+    /// The default implementation subscribes a handler that drops them on
+    /// arrival. Providers that fan out in process should override it to skip
+    /// such subscribers before copying the event for them.
+    async fn subscribe_without_status(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.subscribe(
+            channel,
+            Box::new(move |event| {
+                if !crate::event_stream::is_intermediate_status(&event) {
+                    handler(event);
+                }
+            }),
+        )
+        .await
+    }
+}
+
+/// Result of [`ShortTermStore::save_new_task`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum NewTaskOutcome {
+    /// The task was written; this caller won the creation race.
+    Created,
+    /// Another caller already created (or is still creating) a task with the
+    /// same id. Carries the winner's task once it is readable.
+    AlreadyExists(Option<Box<Task>>),
+}
+
+/// Result of [`ShortTermStore::save_new_task_group`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum NewTaskGroupOutcome {
+    /// Every task of the group was written.
+    Created,
+    /// `task_id` was already taken, so none of the group was written.
+    AlreadyExists {
+        task_id: String,
+        existing: Option<Box<Task>>,
+    },
 }
 
 #[async_trait]
 pub trait ShortTermStore: Send + Sync {
     async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// Writes a freshly created task unless its id is already taken.
+    ///
+    /// Unlike [`save_task`](Self::save_task) this never overwrites: when
+    /// several callers race on the same id, exactly one gets
+    /// [`NewTaskOutcome::Created`]. The default is a plain read-then-write and
+    /// is only safe within a single process; stores shared between instances
+    /// must override it.
+    async fn save_new_task(
+        &self,
+        task: Task,
+    ) -> Result<NewTaskOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(existing) = self.get_task(&task.id).await? {
+            return Ok(NewTaskOutcome::AlreadyExists(Some(Box::new(existing))));
+        }
+        self.save_task(task).await?;
+        Ok(NewTaskOutcome::Created)
+    }
+    /// Writes freshly created tasks all or none: if any id is taken, or the
+    /// write fails, no task of the group is left in the store.
+    ///
+    /// The default stages the tasks one [`save_new_task`](Self::save_new_task)
+    /// at a time and deletes the ones written when a later one fails. Readers
+    /// may see part of the group meanwhile. When a compensating delete fails
+    /// too, the error is a [`PartialTaskGroup`](crate::PartialTaskGroup)
+    /// naming the tasks that may remain. Stores whose
+    /// [`delete_task`](Self::delete_task) does nothing must override this.
+    async fn save_new_task_group(
+        &self,
+        tasks: Vec<Task>,
+    ) -> Result<NewTaskGroupOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let mut written: Vec<String> = Vec::new();
+        for task in tasks {
+            let task_id = task.id.clone();
+            let failed = match self.save_new_task(task).await {
+                Ok(NewTaskOutcome::Created) => {
+                    written.push(task_id);
+                    continue;
+                }
+                Ok(NewTaskOutcome::AlreadyExists(existing)) => {
+                    Ok(NewTaskGroupOutcome::AlreadyExists { task_id, existing })
+                }
+                Err(err) => Err(err),
+            };
+            let mut remaining = Vec::new();
+            for task_id in written.into_iter().rev() {
+                if self.delete_task(&task_id).await.is_err() {
+                    remaining.push(task_id);
+                }
+            }
+            if remaining.is_empty() {
+                return failed;
+            }
+            remaining.reverse();
+            let source = match failed {
+                Ok(_) => "a task id of the group was already taken".into(),
+                Err(err) => err,
+            };
+            return Err(Box::new(crate::PartialTaskGroup {
+                task_ids: remaining,
+                source,
+            }));
+        }
+        Ok(NewTaskGroupOutcome::Created)
+    }
+    /// Writes `task` unless the stored copy's `version` is not
+    /// `expected_version`, returning whether it was written. A task missing
+    /// from the store is written.
+    ///
+    /// The default is a plain read-then-write and is only safe within a
+    /// single process; stores shared between instances must override it.
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(existing) = self.get_task(&task.id).await? {
+            if existing.version != expected_version {
+                return Ok(false);
+            }
+        }
+        self.save_task(task).await?;
+        Ok(true)
+    }
     async fn get_task(
         &self,
         task_id: &str,
@@ -638,6 +1719,32 @@ pub trait ShortTermStore: Send + Sync {
         task_id: &str,
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// Returns a task's events in the canonical order: `index`, then
+    /// `timestamp`, then `id`, all ascending (see `filter::cmp_events`).
+    /// Stores sort explicitly rather than trusting insertion order, since
+    /// series replacements and older index collisions can leave events
+    /// sharing an index or stored out of index order.
+    ///
+    /// Cursor semantics shared by every store (see `apply_event_query` for the
+    /// reference implementation):
+    ///
+    /// - Only one `since` field is honoured, in priority order `id`, then
+    ///   `index`, then `timestamp`. All cursors are exclusive.
+    /// - `since.index` keeps events whose index is strictly greater, so every
+    ///   event tied at the cursor's index is excluded.
+    /// - `since.id` resumes right after the anchor in the canonical order,
+    ///   including after it among events sharing its index.
+    /// - `since.id` is resolved within `task_id` only. An id that does not
+    ///   belong to the task is treated as "no cursor" and the full history is
+    ///   returned. Replaying too much is preferred over silently skipping events
+    ///   for a reconnecting client; clients that care should resume by `index`.
+    /// - `label_selector`, when present, keeps only events whose labels contain
+    ///   every selector entry.
+    /// - `until`, when present, keeps only events whose `timestamp` is at or
+    ///   before it.
+    /// - `limit` is applied after the `since` slice, `until` and the label
+    ///   selector.
+    /// - An unknown task yields an empty list, not an error.
     async fn get_events(
         &self,
         task_id: &str,
@@ -659,12 +1766,15 @@ pub trait ShortTermStore: Send + Sync {
         series_id: &str,
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// Puts `event` in place of the series' latest event in history, or
+    /// appends it when the series has none yet. Returns the id of the event
+    /// replaced.
     async fn replace_last_series_event(
         &self,
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>>;
     async fn accumulate_series(
         &self,
         task_id: &str,
@@ -672,10 +1782,92 @@ pub trait ShortTermStore: Send + Sync {
         event: TaskEvent,
         field: &str,
     ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>>;
+    /// The series a task keeps state for, ordered by series id. The default
+    /// derives them from stored history; stores that keep series state
+    /// override it to read that state.
+    async fn list_series(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<SeriesSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let events = self.get_events(task_id, None).await?;
+        let mut series = BTreeMap::new();
+        for event in &events {
+            if let (Some(series_id), Some(mode)) = (&event.series_id, &event.series_mode) {
+                if *mode != SeriesMode::KeepAll {
+                    series.insert(series_id.as_str(), event);
+                }
+            }
+        }
+        Ok(series
+            .into_iter()
+            .map(|(series_id, latest)| SeriesSummary::of(series_id, latest))
+            .collect())
+    }
+    /// Number of series [`list_series`](Self::list_series) returns. Stores
+    /// that index their series override it with a single read.
+    async fn series_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.list_series(task_id).await?.len() as u64)
+    }
     async fn next_index(
         &self,
         task_id: &str,
-    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>;
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>>;
+    /// Number of indices `next_index` has handed out for a task, i.e. the
+    /// index the next event will receive, without allocating one. The default
+    /// derives it from stored history; stores that keep a counter override it
+    /// with a single read.
+    async fn event_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let events = self.get_events(task_id, None).await?;
+        Ok(events.last().map_or(0, |event| event.index + 1))
+    }
+    /// Starts a scan of a task's history: reads bounded by the token see
+    /// only events indexed below [`event_count`](Self::event_count) at this
+    /// point. A count of zero bounds nothing, since whatever history there
+    /// is was written without the counter, as for one moved to the
+    /// long-term store.
+    async fn begin_scan(
+        &self,
+        task_id: &str,
+    ) -> Result<ScanToken, Box<dyn std::error::Error + Send + Sync>> {
+        let count = self.event_count(task_id).await?;
+        Ok(ScanToken {
+            task_id: task_id.to_string(),
+            end_index: (count > 0).then_some(count),
+        })
+    }
+    /// Type and level counts of a task's stored events. The default derives
+    /// them from stored history; stores that keep running counts override it
+    /// with a single read.
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let events = self.get_events(task_id, None).await?;
+        Ok(EventTypeCounts::from_events(&events))
+    }
+
+    /// The monitor applying this store's corrupt-record policy, for stores
+    /// that decode serialized records.
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        None
+    }
+
+    /// Bytes saved by compressing stored values, for stores configured to.
+    fn compression_stats(&self) -> Option<CompressionStats> {
+        None
+    }
+
+    /// The migration wrapper itself, when this store is a
+    /// [`MigratingShortTermStore`].
+    fn migration(&self) -> Option<&MigratingShortTermStore> {
+        None
+    }
 
     fn supports_task_archive_restore(&self) -> bool {
         false
@@ -709,6 +1901,27 @@ pub trait ShortTermStore: Send + Sync {
         filter: TaskFilter,
     ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>>;
 
+    /// Up to `limit` tasks matching `filter` that come after `after`, in
+    /// `(createdAt, id)` order. `filter.limit` is ignored. The default lists
+    /// every match and sorts it; stores override it to read a page at a
+    /// time.
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tasks = self
+            .list_tasks(TaskFilter {
+                limit: None,
+                ..filter
+            })
+            .await?;
+        tasks.retain(|task| after.as_ref().is_none_or(|cursor| cursor.precedes(task)));
+        tasks.sort_by(|a, b| task_order(a.created_at, &a.id, b.created_at, &b.id));
+        Ok(TaskPage::from_sorted(tasks, limit))
+    }
+
     // Worker state
     async fn save_worker(
         &self,
@@ -768,11 +1981,248 @@ pub trait ShortTermStore: Send + Sync {
     ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
         Ok(vec![])
     }
+
+    // Retry schedules
+    /// Persists a pending retry, replacing any for the same task.
+    async fn save_retry_schedule(
+        &self,
+        _schedule: RetrySchedule,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "retry schedules are not supported by this short-term store",
+        )))
+    }
+    /// Pending retries due at or before `now` (epoch ms), earliest first.
+    async fn list_due_retries(
+        &self,
+        _now: f64,
+    ) -> Result<Vec<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(vec![])
+    }
+    async fn get_retry_schedule(
+        &self,
+        _task_id: &str,
+    ) -> Result<Option<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+    /// Claims a pending retry for `lease_ms`. While the lease holds, every
+    /// other caller gets `false`, so one instance creates the successor. An
+    /// unfinished claim expires and the retry can be claimed again.
+    async fn claim_retry(
+        &self,
+        _task_id: &str,
+        _now: f64,
+        _lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(false)
+    }
+    /// Removes a retry once its successor exists.
+    async fn delete_retry_schedule(
+        &self,
+        _task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    // Scheduled activations
+    /// Records that the `scheduled` task `task_id` becomes pending at `at`
+    /// (epoch ms), replacing any earlier time.
+    async fn save_activation(
+        &self,
+        _task_id: &str,
+        _at: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "scheduled tasks are not supported by this short-term store",
+        )))
+    }
+    /// Ids of scheduled tasks due at or before `now` (epoch ms), earliest
+    /// first.
+    async fn list_due_activations(
+        &self,
+        _now: f64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(vec![])
+    }
+    /// Claims an activation for `lease_ms`, as [`claim_retry`](Self::claim_retry)
+    /// claims a retry.
+    async fn claim_activation(
+        &self,
+        _task_id: &str,
+        _now: f64,
+        _lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(false)
+    }
+    /// Removes an activation once the task has left `scheduled`.
+    async fn delete_activation(
+        &self,
+        _task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    // Persisted filtered indices
+    /// Gives the event at `raw_index` the next filtered index of
+    /// `consumer`'s numbering for the task, in one atomic step (see
+    /// [`FilteredIndexMark::claim`]). The state expires with the task.
+    /// Stores without it return `None`, and callers number per connection.
+    async fn claim_filtered_index(
+        &self,
+        _task_id: &str,
+        _consumer: &str,
+        _filter_hash: &str,
+        _raw_index: u64,
+    ) -> Result<Option<FilteredIndexClaim>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+    /// Where `consumer`'s numbering for the task stands, if one is kept.
+    async fn get_filtered_index_mark(
+        &self,
+        _task_id: &str,
+        _consumer: &str,
+    ) -> Result<Option<FilteredIndexMark>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    // Webhook suppression
+    /// Records a delivery of `fingerprint` to the task's `webhook`-th
+    /// webhook at `now` (epoch ms), unless one was recorded within the last
+    /// `window_ms`. Returns whether the delivery should go ahead. The state
+    /// expires with the task. Stores without suppression state never
+    /// suppress.
+    async fn claim_webhook_delivery(
+        &self,
+        _task_id: &str,
+        _webhook: usize,
+        _fingerprint: &str,
+        _now: f64,
+        _window_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(true)
+    }
+
+    // Deadline warnings
+    /// Claims `warning` for the task, so of several monitors only one emits
+    /// it. Returns `true` the first time only; the claim is kept for at
+    /// least `ttl_ms`. Stores without claim state always return `true`.
+    async fn claim_deadline_warning(
+        &self,
+        _task_id: &str,
+        _warning: &str,
+        _ttl_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(true)
+    }
+
+    // Outcomes index
+    /// Adds `outcome` to the index of recently completed tasks. Stores
+    /// without an index keep nothing.
+    async fn record_outcome(
+        &self,
+        _outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+    /// Indexed outcomes matching `query`, ordered by `completed_at` then
+    /// task id, at most `query.limit` of them.
+    async fn list_outcomes(
+        &self,
+        _query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(vec![])
+    }
+    /// Drops indexed outcomes completed before `before` (epoch ms) and
+    /// returns how many were dropped.
+    async fn trim_outcomes(
+        &self,
+        _before: f64,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(0)
+    }
+
+    // Deletion
+    /// Removes a task with its events, series state, index counter,
+    /// assignment, retry and suppression state. Deleting a missing task is
+    /// not an error. Stores that only drop tasks when their TTL expires keep
+    /// the default, which does nothing.
+    async fn delete_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Overwrites each stored event with the tombstone of the same id, in
+    /// place so indices are unchanged, and drops the series state of any
+    /// series a replaced event belonged to. Returns how many stored events
+    /// were replaced; ids the store no longer holds are skipped.
+    async fn redact_events(
+        &self,
+        _task_id: &str,
+        _tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "redact_events is not supported by this short-term store",
+        )))
+    }
+
+    /// Where discovered event conventions are kept, used when the long-term
+    /// store keeps none. `None` from stores that keep none.
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        None
+    }
+}
+
+/// Receives every published event, e.g. to stream it into another system,
+/// independently of the broadcast provider and long-term store.
+///
+/// `on_event` is awaited inline, in publish order, while the task's events
+/// are serialized, so it should hand the event off (say, to a producer
+/// queue) rather than wait for delivery. An error is reported through
+/// [`TaskcastHooks::on_event_dropped`] and does not fail the publish.
+///
+/// Writes applied through `TaskEngine::apply_replicated_task` and
+/// `TaskEngine::apply_replicated_event` are not passed to sinks.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    async fn on_event(
+        &self,
+        event: &TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Receives a task after it is created, transitioned or updated, ahead
+    /// of the events the change publishes. An error is reported through
+    /// [`TaskcastHooks::on_unhandled_error`].
+    async fn on_task_saved(
+        &self,
+        _task: &Task,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
 }
 
 #[async_trait]
 pub trait LongTermStore: Send + Sync {
     async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    /// Like [`ShortTermStore::save_task_versioned`]: writes `task` unless the
+    /// stored copy's `version` is not `expected_version`.
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(existing) = self.get_task(&task.id).await? {
+            if existing.version != expected_version {
+                return Ok(false);
+            }
+        }
+        self.save_task(task).await?;
+        Ok(true)
+    }
     async fn get_task(
         &self,
         task_id: &str,
@@ -799,16 +2249,61 @@ pub trait LongTermStore: Send + Sync {
         self.save_event(event.clone()).await?;
         Ok(event)
     }
+    /// Returns a task's archived events. Follows the same cursor contract as
+    /// `ShortTermStore::get_events`.
     async fn get_events(
         &self,
         task_id: &str,
         opts: Option<EventQueryOptions>,
     ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>>;
 
+    /// Type and level counts of a task's archived events, computed on
+    /// demand. The default derives them from [`get_events`](Self::get_events).
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let events = self.get_events(task_id, None).await?;
+        Ok(EventTypeCounts::from_events(&events))
+    }
+
     fn supports_series_compaction(&self) -> bool {
         false
     }
 
+    /// The monitor applying this store's corrupt-record policy.
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        None
+    }
+
+    /// Where discovered event conventions are kept. `None` from stores that
+    /// keep none.
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        None
+    }
+
+    /// Probes each connection pool the store reads or writes through. Stores
+    /// without pools report none.
+    async fn pool_health(&self) -> Vec<PoolHealth> {
+        Vec::new()
+    }
+
+    /// Per-method query latency, for stores that record it.
+    fn query_stats(&self) -> Option<QueryStatsSnapshot> {
+        None
+    }
+
+    /// The database's plan for the query [`get_events`](Self::get_events)
+    /// would run with `opts`, without running it. `None` from stores that
+    /// cannot explain their queries.
+    async fn explain_get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+
     fn supports_task_archive_restore(&self) -> bool {
         false
     }
@@ -839,6 +2334,64 @@ pub trait LongTermStore: Send + Sync {
         )))
     }
 
+    // Task query
+    /// Whether [`list_tasks`](Self::list_tasks) is implemented.
+    fn supports_task_listing(&self) -> bool {
+        false
+    }
+
+    /// One page of the archived tasks matching `query`, in `(createdAt, id)`
+    /// order.
+    async fn list_tasks(
+        &self,
+        _query: &ListTasksQuery,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "list_tasks is not supported by this long-term store",
+        )))
+    }
+
+    // Consistency scans
+    /// Ids of the tasks with stored events, in ascending order, starting
+    /// after `after` and at most `limit` of them. Tasks whose record is gone
+    /// are included. Used to page through the store for orphaned events.
+    async fn list_event_task_ids(
+        &self,
+        _after: Option<&str>,
+        _limit: u64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "list_event_task_ids is not supported by this long-term store",
+        )))
+    }
+
+    /// Removes a task's record and all of its events. Deleting a missing
+    /// task is not an error.
+    async fn delete_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "delete_task is not supported by this long-term store",
+        )))
+    }
+
+    /// Overwrites each stored event with the tombstone of the same id.
+    /// Returns how many stored events were replaced.
+    async fn redact_events(
+        &self,
+        _task_id: &str,
+        _tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "redact_events is not supported by this long-term store",
+        )))
+    }
+
     // Worker audit
     async fn save_worker_event(
         &self,
@@ -881,6 +2434,29 @@ pub trait TaskcastHooks: Send + Sync {
         _err: &(dyn std::error::Error + Send + Sync),
     ) {
     }
+    /// A delivery of `kind` (the event type, or `task.snapshot`) to
+    /// `config` is about to make `attempt` (1-based). Retries report the
+    /// `delay` they are about to sleep and the `error` that ended the
+    /// previous attempt; the first attempt has neither.
+    fn on_webhook_attempt(
+        &self,
+        _config: &WebhookConfig,
+        _kind: &str,
+        _attempt: u32,
+        _delay: std::time::Duration,
+        _error: Option<&str>,
+    ) {
+    }
+    /// Every background webhook delivery permit has been taken for
+    /// `saturated_for`, longer than the delivery's saturation threshold,
+    /// with `queued` deliveries waiting. Fires once per saturated stretch.
+    fn on_webhook_saturated(
+        &self,
+        _in_use: usize,
+        _queued: usize,
+        _saturated_for: std::time::Duration,
+    ) {
+    }
     fn on_sse_connect(&self, _task_id: &str, _client_id: &str) {}
     fn on_sse_disconnect(&self, _task_id: &str, _client_id: &str, _duration: f64) {}
     fn on_task_created(&self, _task: &Task) {}
@@ -889,6 +2465,16 @@ pub trait TaskcastHooks: Send + Sync {
     fn on_worker_disconnected(&self, _worker: &Worker, _reason: &str) {}
     fn on_task_assigned(&self, _task: &Task, _worker: &Worker) {}
     fn on_task_declined(&self, _task: &Task, _worker: &Worker, _blacklisted: bool) {}
+    fn on_storage_swept(&self, _removed: &[crate::storage::RemovedFile]) {}
+    /// A broadcast handler on `channel` took `elapsed` to handle `event`,
+    /// longer than the provider's slow-handler threshold.
+    fn on_slow_broadcast_handler(
+        &self,
+        _channel: &str,
+        _event: &TaskEvent,
+        _elapsed: std::time::Duration,
+    ) {
+    }
 }
 
 // ─── Tests ──────────────────────────────────────────────────────────────────
@@ -938,6 +2524,10 @@ mod tests {
             serde_json::to_string(&TaskStatus::Cancelled).unwrap(),
             "\"cancelled\""
         );
+        assert_eq!(
+            serde_json::to_string(&TaskStatus::Scheduled).unwrap(),
+            "\"scheduled\""
+        );
     }
 
     #[test]
@@ -962,6 +2552,10 @@ mod tests {
             serde_json::from_str::<TaskStatus>("\"cancelled\"").unwrap(),
             TaskStatus::Cancelled
         );
+        assert_eq!(
+            serde_json::from_str::<TaskStatus>("\"scheduled\"").unwrap(),
+            TaskStatus::Scheduled
+        );
     }
 
     // ─── Level ──────────────────────────────────────────────────────────
@@ -990,6 +2584,10 @@ mod tests {
             serde_json::to_string(&SeriesMode::Latest).unwrap(),
             "\"latest\""
         );
+        assert_eq!(
+            serde_json::to_string(&SeriesMode::JsonPatch).unwrap(),
+            "\"json-patch\""
+        );
     }
 
     #[test]
@@ -1024,6 +2622,10 @@ mod tests {
             serde_json::to_string(&PermissionScope::EventHistory).unwrap(),
             "\"event:history\""
         );
+        assert_eq!(
+            serde_json::to_string(&PermissionScope::EventDelete).unwrap(),
+            "\"event:delete\""
+        );
         assert_eq!(
             serde_json::to_string(&PermissionScope::WebhookCreate).unwrap(),
             "\"webhook:create\""
@@ -1044,6 +2646,18 @@ mod tests {
             serde_json::to_string(&PermissionScope::TaskSignal).unwrap(),
             "\"task:signal\""
         );
+        assert_eq!(
+            serde_json::to_string(&PermissionScope::TaskReplicate).unwrap(),
+            "\"task:replicate\""
+        );
+        assert_eq!(
+            serde_json::to_string(&PermissionScope::TaskTransition).unwrap(),
+            "\"task:transition\""
+        );
+        assert_eq!(
+            serde_json::to_string(&PermissionScope::TaskRead).unwrap(),
+            "\"task:read\""
+        );
         assert_eq!(
             serde_json::to_string(&PermissionScope::All).unwrap(),
             "\"*\""
@@ -1052,10 +2666,28 @@ mod tests {
 
     #[test]
     fn permission_scope_roundtrip() {
-        let scope = PermissionScope::All;
-        let json = serde_json::to_string(&scope).unwrap();
-        let back: PermissionScope = serde_json::from_str(&json).unwrap();
-        assert_eq!(back, scope);
+        for scope in [
+            PermissionScope::All,
+            PermissionScope::TaskTransition,
+            PermissionScope::TaskRead,
+        ] {
+            let json = serde_json::to_string(&scope).unwrap();
+            let back: PermissionScope = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, scope);
+        }
+    }
+
+    #[test]
+    fn task_manage_implies_the_narrow_task_scopes() {
+        assert_eq!(
+            PermissionScope::TaskManage.implied(),
+            &[PermissionScope::TaskTransition, PermissionScope::TaskRead]
+        );
+        assert_eq!(
+            PermissionScope::EventSubscribe.implied(),
+            &[PermissionScope::TaskRead]
+        );
+        assert!(PermissionScope::TaskTransition.implied().is_empty());
     }
 
     // ─── BackoffStrategy ────────────────────────────────────────────────
@@ -1154,6 +2786,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let json = serde_json::to_value(&task).unwrap();
         // Check camelCase field names
@@ -1172,6 +2814,7 @@ mod tests {
         assert!(json.get("authConfig").is_none());
         assert!(json.get("webhooks").is_none());
         assert!(json.get("cleanup").is_none());
+        assert!(json.get("filters").is_none());
     }
 
     // ─── Task (full) ────────────────────────────────────────────────────
@@ -1210,6 +2853,9 @@ mod tests {
             }),
             webhooks: Some(vec![WebhookConfig {
                 url: "https://hook.example.com".to_string(),
+                group: None,
+                suppression_window_ms: None,
+                mode: None,
                 filter: None,
                 secret: Some("s3cret".to_string()),
                 wrap: Some(true),
@@ -1219,7 +2865,14 @@ mod tests {
                     initial_delay_ms: 1000,
                     max_delay_ms: 30000,
                     timeout_ms: 5000,
+                    jitter: RetryJitter::None,
+                    max_elapsed_ms: None,
                 }),
+                filter_preset: None,
+                backfill: None,
+                initial_delivery: None,
+                max_payload_bytes: None,
+                task_match: None,
             }]),
             cleanup: Some(CleanupConfig {
                 rules: vec![CleanupRule {
@@ -1234,6 +2887,7 @@ mod tests {
                     target: CleanupTarget::All,
                     event_filter: None,
                 }],
+                abandoned_pending: None,
             }),
             tags: None,
             assign_mode: None,
@@ -1242,7 +2896,17 @@ mod tests {
             disconnect_policy: None,
             reason: None,
             resume_at: None,
+            filters: None,
             blocked_request: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
 
         let json = serde_json::to_value(&task).unwrap();
@@ -1315,6 +2979,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let json_str = serde_json::to_string(&task).unwrap();
         let back: Task = serde_json::from_str(&json_str).unwrap();
@@ -1324,6 +2998,51 @@ mod tests {
         assert_eq!(back.created_at, task.created_at);
     }
 
+    #[test]
+    fn task_filters_roundtrip_and_webhook_preset_reference() {
+        let json = serde_json::json!({
+            "id": "task_presets",
+            "status": "running",
+            "createdAt": 1700000000000.0,
+            "updatedAt": 1700000000000.0,
+            "filters": {
+                "errorsOnly": { "levels": ["error"], "includeStatus": false }
+            },
+            "webhooks": [{ "url": "https://example.com/hook", "filterPreset": "errorsOnly" }]
+        });
+        let task: Task = serde_json::from_value(json.clone()).unwrap();
+        let preset = &task.filters.as_ref().unwrap()["errorsOnly"];
+        assert_eq!(preset.levels, Some(vec![Level::Error]));
+        assert_eq!(preset.include_status, Some(false));
+        assert_eq!(
+            task.webhooks.as_ref().unwrap()[0].filter_preset.as_deref(),
+            Some("errorsOnly")
+        );
+        assert_eq!(serde_json::to_value(&task).unwrap(), json);
+    }
+
+    #[test]
+    fn task_webhook_groups_roundtrip() {
+        let json = serde_json::json!({
+            "id": "task_groups",
+            "status": "running",
+            "createdAt": 1700000000000.0,
+            "updatedAt": 1700000000000.0,
+            "webhooks": [
+                { "url": "https://pager.example.com", "group": "severity" },
+                { "url": "https://lake.example.com", "suppressionWindowMs": 5000 }
+            ],
+            "groupPolicy": "firstMatch"
+        });
+        let task: Task = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(task.group_policy, Some(WebhookGroupPolicy::FirstMatch));
+        let webhooks = task.webhooks.as_ref().unwrap();
+        assert_eq!(webhooks[0].group.as_deref(), Some("severity"));
+        assert_eq!(webhooks[1].suppression_window_ms, Some(5000));
+        assert_eq!(serde_json::to_value(&task).unwrap(), json);
+        assert_eq!(WebhookGroupPolicy::default(), WebhookGroupPolicy::All);
+    }
+
     // ─── TaskEvent ──────────────────────────────────────────────────────
 
     #[test]
@@ -1340,6 +3059,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let json = serde_json::to_value(&event).unwrap();
@@ -1368,6 +3089,8 @@ mod tests {
             series_mode: Some(SeriesMode::Accumulate),
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let json = serde_json::to_value(&event).unwrap();
@@ -1389,6 +3112,8 @@ mod tests {
             series_mode: Some(SeriesMode::Latest),
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let json_str = serde_json::to_string(&event).unwrap();
@@ -1413,6 +3138,9 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            filter_epoch: None,
         };
         let json = serde_json::to_value(&envelope).unwrap();
         assert_eq!(json["filteredIndex"], 3);
@@ -1442,6 +3170,9 @@ mod tests {
             series_mode: Some(SeriesMode::KeepAll),
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            filter_epoch: None,
         };
         let json = serde_json::to_value(&envelope).unwrap();
         assert_eq!(json["seriesId"], "s1");
@@ -1489,6 +3220,9 @@ mod tests {
             include_status: Some(true),
             wrap: Some(false),
             series_format: None,
+            materialize_series: None,
+            label_selector: None,
+            task_match: None,
         };
         let json = serde_json::to_value(&filter).unwrap();
         assert_eq!(json["since"]["index"], 10);
@@ -1528,6 +3262,9 @@ mod tests {
             include_status: None,
             wrap: None,
             series_format: Some(SeriesFormat::Accumulated),
+            materialize_series: None,
+            label_selector: None,
+            task_match: None,
         };
         let json = serde_json::to_value(&filter).unwrap();
         assert_eq!(json["seriesFormat"], "accumulated");
@@ -1536,6 +3273,17 @@ mod tests {
         assert_eq!(back.series_format, Some(SeriesFormat::Accumulated));
     }
 
+    #[test]
+    fn series_view_is_an_alias_for_series_format() {
+        let filter: SubscribeFilter =
+            serde_json::from_value(serde_json::json!({ "seriesView": "both" })).unwrap();
+        assert_eq!(filter.series_format, Some(SeriesFormat::Both));
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap()["seriesFormat"],
+            "both"
+        );
+    }
+
     #[test]
     fn series_result_fields() {
         let event = TaskEvent {
@@ -1550,12 +3298,15 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let result = SeriesResult {
             event: event.clone(),
             accumulated_event: Some(event.clone()),
             stored: false,
+            replaced_event_id: None,
         };
         assert_eq!(result.event.id, "e");
         assert!(result.accumulated_event.is_some());
@@ -1575,6 +3326,9 @@ mod tests {
         let opts = EventQueryOptions {
             since: None,
             limit: Some(100),
+            label_selector: None,
+            until: None,
+            before_index: None,
         };
         let json = serde_json::to_value(&opts).unwrap();
         assert!(json.get("since").is_none());
@@ -1591,6 +3345,8 @@ mod tests {
             initial_delay_ms: 500,
             max_delay_ms: 10000,
             timeout_ms: 30000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
         let json = serde_json::to_value(&cfg).unwrap();
         assert_eq!(json["retries"], 5);
@@ -1600,16 +3356,65 @@ mod tests {
         assert_eq!(json["timeoutMs"], 30000);
     }
 
+    // ─── RetryPolicy ────────────────────────────────────────────────────
+
+    fn retry_policy(backoff: BackoffStrategy) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            backoff,
+            initial_delay_ms: 1000,
+            max_delay_ms: 5000,
+            retry_on: default_retry_on(),
+        }
+    }
+
+    #[test]
+    fn retry_policy_delay_follows_backoff_and_caps_at_max() {
+        let fixed = retry_policy(BackoffStrategy::Fixed);
+        assert_eq!([1, 2, 3].map(|a| fixed.delay_ms(a)), [1000, 1000, 1000]);
+        let linear = retry_policy(BackoffStrategy::Linear);
+        assert_eq!([1, 2, 6].map(|a| linear.delay_ms(a)), [1000, 2000, 5000]);
+        let exponential = retry_policy(BackoffStrategy::Exponential);
+        assert_eq!(
+            [1, 2, 3, 4, 100].map(|a| exponential.delay_ms(a)),
+            [1000, 2000, 4000, 5000, 5000]
+        );
+    }
+
+    #[test]
+    fn retry_policy_defaults_retry_on_to_failed_and_timeout() {
+        let policy: RetryPolicy = serde_json::from_value(serde_json::json!({
+            "maxAttempts": 3,
+            "backoff": "fixed",
+            "initialDelayMs": 100,
+            "maxDelayMs": 100
+        }))
+        .unwrap();
+        assert_eq!(policy.retry_on, vec![RetryOn::Failed, RetryOn::Timeout]);
+        assert!(policy.retries_on(&TaskStatus::Failed));
+        assert!(policy.retries_on(&TaskStatus::Timeout));
+        assert!(!policy.retries_on(&TaskStatus::Cancelled));
+        assert!(!policy.retries_on(&TaskStatus::Completed));
+    }
+
     // ─── WebhookConfig ──────────────────────────────────────────────────
 
     #[test]
     fn webhook_config_minimal_serializes_correctly() {
         let cfg = WebhookConfig {
             url: "https://example.com/hook".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
             retry: None,
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
         let json = serde_json::to_value(&cfg).unwrap();
         assert_eq!(json, json!({ "url": "https://example.com/hook" }));
@@ -1723,6 +3528,21 @@ mod tests {
         assert_eq!(params["width"], json!(1920));
     }
 
+    #[test]
+    fn scheduled_task_roundtrips_typescript_json() {
+        let ts_json = json!({
+            "id": "nightly-export",
+            "status": "scheduled",
+            "createdAt": 1700000000000.0,
+            "updatedAt": 1700000000000.0,
+            "scheduledFor": 1700007200000.0
+        });
+        let task: Task = serde_json::from_value(ts_json.clone()).unwrap();
+        assert_eq!(task.status, TaskStatus::Scheduled);
+        assert_eq!(task.scheduled_for, Some(1700007200000.0));
+        assert_eq!(serde_json::to_value(&task).unwrap(), ts_json);
+    }
+
     #[test]
     fn task_event_deserializes_from_typescript_json() {
         let ts_json = json!({
@@ -1797,6 +3617,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let json_str = serde_json::to_string(&task).unwrap();
         // These keys must NOT appear at all
@@ -1834,6 +3664,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let json_str = serde_json::to_string(&event).unwrap();
@@ -1856,6 +3688,9 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            filter_epoch: None,
         };
         let json_str = serde_json::to_string(&envelope).unwrap();
         assert!(!json_str.contains("\"seriesId\""));
@@ -1890,6 +3725,7 @@ mod tests {
                     target: CleanupTarget::Task,
                     event_filter: None,
                 }],
+                abandoned_pending: None,
             }),
             tags: None,
             assign_mode: None,
@@ -1899,6 +3735,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let json = serde_json::to_value(&task).unwrap();
         assert_eq!(json["cleanup"]["rules"][0]["trigger"]["afterMs"], 1000);
@@ -1911,6 +3757,9 @@ mod tests {
     fn webhook_config_with_filter_serializes_correctly() {
         let cfg = WebhookConfig {
             url: "https://example.com".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: Some(SubscribeFilter {
                 since: None,
                 types: Some(vec!["status".to_string()]),
@@ -1918,10 +3767,18 @@ mod tests {
                 include_status: Some(true),
                 wrap: None,
                 series_format: None,
+                materialize_series: None,
+                label_selector: None,
+                task_match: None,
             }),
             secret: None,
             wrap: None,
             retry: None,
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
         let json = serde_json::to_value(&cfg).unwrap();
         assert_eq!(json["url"], "https://example.com");
@@ -1929,6 +3786,27 @@ mod tests {
         assert_eq!(json["filter"]["includeStatus"], true);
     }
 
+    #[test]
+    fn webhook_backfill_roundtrips_keyword_and_cursor() {
+        let all: WebhookBackfill = serde_json::from_value(json!("all")).unwrap();
+        assert_eq!(all, WebhookBackfill::All);
+        assert_eq!(serde_json::to_value(&all).unwrap(), json!("all"));
+
+        let since: WebhookBackfill = serde_json::from_value(json!({ "index": 3 })).unwrap();
+        assert_eq!(
+            since,
+            WebhookBackfill::Since(SinceCursor {
+                id: None,
+                index: Some(3),
+                timestamp: None,
+            })
+        );
+        assert_eq!(serde_json::to_value(&since).unwrap(), json!({ "index": 3 }));
+
+        let err = serde_json::from_value::<WebhookBackfill>(json!("latest")).unwrap_err();
+        assert!(err.to_string().contains("unknown backfill \"latest\""));
+    }
+
     // ─── TaskcastHooks default no-op impls ──────────────────────────
 
     struct NoopHooks;
@@ -1960,6 +3838,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let err = TaskError {
             code: None,
@@ -1978,14 +3866,24 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let webhook = WebhookConfig {
             url: "https://example.com".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
             retry: None,
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
         let io_err: Box<dyn std::error::Error + Send + Sync> = "io error".into();
         let ctx = ErrorContext {
@@ -2059,8 +3957,8 @@ mod tests {
             _: &str,
             _: &str,
             _: TaskEvent,
-        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-            Ok(())
+        ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(None)
         }
         async fn accumulate_series(
             &self,
@@ -2181,6 +4079,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         };
         let event = TaskEvent {
             id: "e".to_string(),
@@ -2194,6 +4102,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
 
@@ -2271,7 +4181,7 @@ mod tests {
         ) -> Box<dyn Fn() + Send + Sync> {
             Box::new(|| {})
         }
-        // subscribe_sync intentionally NOT overridden — uses the default
+        // subscribe_sync and subscribe_pattern intentionally NOT overridden — use the defaults
     }
 
     #[test]
@@ -2286,6 +4196,17 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn subscribe_pattern_default_returns_unsupported_error() {
+        let provider = StubBroadcast;
+        let result = provider.subscribe_pattern("task.*", Box::new(|_| {})).await;
+        let msg = result.err().unwrap().to_string();
+        assert!(
+            msg.contains("subscribe_pattern is not supported"),
+            "expected 'not supported' message, got: {msg}"
+        );
+    }
+
     #[test]
     fn series_result_stored_field_true() {
         let event = TaskEvent {
@@ -2300,12 +4221,15 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let result = SeriesResult {
             event,
             accumulated_event: None,
             stored: true,
+            replaced_event_id: None,
         };
         assert!(result.stored);
         assert!(result.accumulated_event.is_none());
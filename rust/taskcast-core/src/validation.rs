@@ -0,0 +1,533 @@
+//! Up-front checks on task creation input.
+//!
+//! The engine only rejects input it cannot store. [`validate_create_task_input`]
+//! goes further and rejects values that would store fine but misbehave later:
+//! ids that break routes and store keys, webhook URLs that can never be
+//! delivered, retry schedules that cannot be followed, cleanup rules that
+//! delete a task the moment it ends, auth rules that match nothing and
+//! forwarding rules that point back at the task. Every violation is
+//! reported, each with a JSON pointer into the request body.
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::engine::CreateTaskInput;
+use crate::types::{ForwardRule, RetryConfig, RetryPolicy, WebhookConfig};
+
+/// Caller-supplied task ids must match this unless configured otherwise:
+/// 1 to 128 ASCII letters, digits, `.`, `_`, `:` or `-`.
+pub const DEFAULT_TASK_ID_PATTERN: &str = r"^[A-Za-z0-9._:-]{1,128}$";
+
+/// Longest TTL a task may be given, in seconds (one year).
+pub const MAX_TTL_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+/// Most retries a webhook may ask for.
+pub const MAX_WEBHOOK_RETRIES: u32 = 10;
+
+/// Most attempts a task retry policy may allow.
+pub const MAX_RETRY_ATTEMPTS: u32 = 100;
+
+/// Shortest `trigger.afterMs` a cleanup rule may wait after a task ends.
+pub const MIN_CLEANUP_AFTER_MS: u64 = 1_000;
+
+/// One rejected field: an RFC 6901 pointer into the camelCase request body,
+/// and what is wrong with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct Violation {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(pointer: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            pointer: pointer.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// The configurable parts of [`validate_create_task_input`].
+#[derive(Debug, Clone)]
+pub struct TaskValidationOptions {
+    /// Caller-supplied ids must match this.
+    pub id_pattern: Regex,
+    /// Accept `http://` webhook URLs as well as `https://` ones.
+    pub allow_insecure_webhooks: bool,
+}
+
+impl Default for TaskValidationOptions {
+    fn default() -> Self {
+        Self {
+            id_pattern: Regex::new(DEFAULT_TASK_ID_PATTERN).expect("default id pattern"),
+            allow_insecure_webhooks: false,
+        }
+    }
+}
+
+impl TaskValidationOptions {
+    /// Options with `pattern` in place of [`DEFAULT_TASK_ID_PATTERN`].
+    pub fn with_id_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.id_pattern = Regex::new(pattern)?;
+        Ok(self)
+    }
+}
+
+/// Checks `input` field by field, returning every violation found.
+pub fn validate_create_task_input(
+    input: &CreateTaskInput,
+    options: &TaskValidationOptions,
+) -> Result<(), Vec<Violation>> {
+    let mut violations = Vec::new();
+
+    if let Some(id) = &input.id {
+        if !options.id_pattern.is_match(id) {
+            violations.push(Violation::new(
+                "/id",
+                format!(
+                    "Invalid task id: must match {}",
+                    options.id_pattern.as_str()
+                ),
+            ));
+        }
+    }
+    if let Some(ttl) = input.ttl {
+        violations.extend(validate_ttl(ttl, "/ttl").err());
+    }
+    for (i, webhook) in input.webhooks.iter().flatten().enumerate() {
+        check_webhook(webhook, &format!("/webhooks/{i}"), options, &mut violations);
+    }
+    if let Some(policy) = &input.retry_policy {
+        check_retry_policy(policy, &mut violations);
+    }
+    for (i, rule) in input.cleanup.iter().flat_map(|c| &c.rules).enumerate() {
+        if let Some(after_ms) = rule.trigger.after_ms {
+            if after_ms < MIN_CLEANUP_AFTER_MS {
+                violations.push(Violation::new(
+                    format!("/cleanup/rules/{i}/trigger/afterMs"),
+                    format!(
+                        "Invalid cleanup trigger: afterMs must be at least {MIN_CLEANUP_AFTER_MS}"
+                    ),
+                ));
+            }
+        }
+    }
+    if let Some(rule) = &input.forward_to {
+        check_forward_rule(rule, input.id.as_deref(), options, &mut violations);
+    }
+    for (i, rule) in input.auth_config.iter().flat_map(|c| &c.rules).enumerate() {
+        if rule.r#match.scope.is_empty() {
+            violations.push(Violation::new(
+                format!("/authConfig/rules/{i}/match/scope"),
+                "Invalid auth rule: scope must list at least one permission",
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+/// Checks a TTL in seconds against `1..=`[`MAX_TTL_SECONDS`], reporting a
+/// violation at `pointer`. Shared with routes that change a task's TTL.
+pub fn validate_ttl(ttl: u64, pointer: &str) -> Result<(), Violation> {
+    if (1..=MAX_TTL_SECONDS).contains(&ttl) {
+        return Ok(());
+    }
+    Err(Violation::new(
+        pointer,
+        format!("Invalid TTL: {ttl}. TTL must be between 1 and {MAX_TTL_SECONDS} seconds."),
+    ))
+}
+
+fn check_webhook(
+    webhook: &WebhookConfig,
+    pointer: &str,
+    options: &TaskValidationOptions,
+    violations: &mut Vec<Violation>,
+) {
+    let url_error = match url::Url::parse(&webhook.url) {
+        Err(e) => Some(format!("Invalid webhook url: {e}")),
+        Ok(url) if !url.has_host() => Some("Invalid webhook url: missing host".to_string()),
+        Ok(url) => match url.scheme() {
+            "https" => None,
+            "http" if options.allow_insecure_webhooks => None,
+            "http" => Some("Invalid webhook url: https is required".to_string()),
+            scheme => Some(format!("Invalid webhook url: unsupported scheme {scheme}")),
+        },
+    };
+    if let Some(message) = url_error {
+        violations.push(Violation::new(format!("{pointer}/url"), message));
+    }
+    if let Some(retry) = &webhook.retry {
+        check_webhook_retry(retry, &format!("{pointer}/retry"), violations);
+    }
+}
+
+fn check_webhook_retry(retry: &RetryConfig, pointer: &str, violations: &mut Vec<Violation>) {
+    if retry.retries > MAX_WEBHOOK_RETRIES {
+        violations.push(Violation::new(
+            format!("{pointer}/retries"),
+            format!("Invalid webhook retry: retries must be at most {MAX_WEBHOOK_RETRIES}"),
+        ));
+    }
+    if retry.initial_delay_ms > retry.max_delay_ms {
+        violations.push(Violation::new(
+            format!("{pointer}/initialDelayMs"),
+            "Invalid webhook retry: initialDelayMs must not exceed maxDelayMs",
+        ));
+    }
+}
+
+fn check_retry_policy(policy: &RetryPolicy, violations: &mut Vec<Violation>) {
+    if !(1..=MAX_RETRY_ATTEMPTS).contains(&policy.max_attempts) {
+        violations.push(Violation::new(
+            "/retryPolicy/maxAttempts",
+            format!("Invalid retryPolicy: maxAttempts must be between 1 and {MAX_RETRY_ATTEMPTS}."),
+        ));
+    }
+    if policy.initial_delay_ms > policy.max_delay_ms {
+        violations.push(Violation::new(
+            "/retryPolicy/initialDelayMs",
+            "Invalid retryPolicy: initialDelayMs must not exceed maxDelayMs.",
+        ));
+    }
+}
+
+fn check_forward_rule(
+    rule: &ForwardRule,
+    id: Option<&str>,
+    options: &TaskValidationOptions,
+    violations: &mut Vec<Violation>,
+) {
+    if !options.id_pattern.is_match(&rule.target_task_id) {
+        violations.push(Violation::new(
+            "/forwardTo/targetTaskId",
+            format!(
+                "Invalid forwardTo: targetTaskId must match {}",
+                options.id_pattern.as_str()
+            ),
+        ));
+    } else if id == Some(rule.target_task_id.as_str()) {
+        violations.push(Violation::new(
+            "/forwardTo/targetTaskId",
+            "Invalid forwardTo: a task cannot forward to itself",
+        ));
+    }
+    if let Some(prefix) = rule.transform.as_ref().and_then(|t| t.prefix_type.as_ref()) {
+        if prefix.is_empty() {
+            violations.push(Violation::new(
+                "/forwardTo/transform/prefixType",
+                "Invalid forwardTo: prefixType must not be empty",
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        BackoffStrategy, CleanupConfig, CleanupRule, CleanupTarget, CleanupTrigger,
+        ForwardTransform, PermissionScope, RetryJitter, TaskAuthConfig, TaskAuthRule, TaskAuthRuleMatch,
+        TaskAuthRuleRequire,
+    };
+
+    fn webhook(url: &str) -> WebhookConfig {
+        serde_json::from_value(serde_json::json!({ "url": url })).unwrap()
+    }
+
+    fn retry(retries: u32, initial_delay_ms: u64, max_delay_ms: u64) -> RetryConfig {
+        RetryConfig {
+            retries,
+            backoff: BackoffStrategy::Exponential,
+            initial_delay_ms,
+            max_delay_ms,
+            timeout_ms: 5_000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
+        }
+    }
+
+    fn cleanup_after(after_ms: u64) -> CleanupConfig {
+        CleanupConfig {
+            rules: vec![CleanupRule {
+                name: None,
+                r#match: None,
+                trigger: CleanupTrigger {
+                    after_ms: Some(after_ms),
+                },
+                target: CleanupTarget::All,
+                event_filter: None,
+            }],
+            abandoned_pending: None,
+        }
+    }
+
+    fn auth_rule(scope: Vec<PermissionScope>) -> TaskAuthRule {
+        TaskAuthRule {
+            r#match: TaskAuthRuleMatch { scope },
+            require: TaskAuthRuleRequire {
+                claims: None,
+                sub: Some(vec!["user-1".to_string()]),
+            },
+        }
+    }
+
+    fn pointers(input: &CreateTaskInput, options: &TaskValidationOptions) -> Vec<String> {
+        validate_create_task_input(input, options)
+            .unwrap_err()
+            .into_iter()
+            .map(|v| v.pointer)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_an_empty_input() {
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            validate_create_task_input(&CreateTaskInput::default(), &options),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn accepts_a_maximal_valid_input() {
+        let mut hook = webhook("https://hooks.example/taskcast");
+        hook.retry = Some(retry(MAX_WEBHOOK_RETRIES, 1_000, 60_000));
+        let input = CreateTaskInput {
+            id: Some("a".repeat(128)),
+            ttl: Some(MAX_TTL_SECONDS),
+            webhooks: Some(vec![hook]),
+            cleanup: Some(cleanup_after(MIN_CLEANUP_AFTER_MS)),
+            auth_config: Some(TaskAuthConfig {
+                rules: vec![auth_rule(vec![PermissionScope::EventSubscribe])],
+            }),
+            retry_policy: Some(RetryPolicy {
+                max_attempts: MAX_RETRY_ATTEMPTS,
+                backoff: BackoffStrategy::Fixed,
+                initial_delay_ms: 5_000,
+                max_delay_ms: 5_000,
+                retry_on: Vec::new(),
+            }),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(validate_create_task_input(&input, &options), Ok(()));
+    }
+
+    #[test]
+    fn rejects_ids_outside_the_pattern() {
+        let options = TaskValidationOptions::default();
+        for id in ["", "a/b", "line\nbreak", "has space", &"a".repeat(129)] {
+            let input = CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(pointers(&input, &options), ["/id"], "{id:?}");
+        }
+    }
+
+    #[test]
+    fn id_pattern_is_configurable() {
+        let options = TaskValidationOptions::default()
+            .with_id_pattern("^job-[0-9]+$")
+            .unwrap();
+        let input = |id: &str| CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_create_task_input(&input("job-42"), &options),
+            Ok(())
+        );
+        assert_eq!(pointers(&input("t1"), &options), ["/id"]);
+        assert!(TaskValidationOptions::default()
+            .with_id_pattern("[")
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_ttl_out_of_bounds() {
+        let options = TaskValidationOptions::default();
+        for ttl in [0, MAX_TTL_SECONDS + 1] {
+            let input = CreateTaskInput {
+                ttl: Some(ttl),
+                ..Default::default()
+            };
+            assert_eq!(pointers(&input, &options), ["/ttl"]);
+        }
+        assert!(validate_ttl(1, "/ttl").is_ok());
+    }
+
+    #[test]
+    fn rejects_webhook_urls_that_are_not_https() {
+        let input = CreateTaskInput {
+            webhooks: Some(vec![
+                webhook("https://hooks.example/ok"),
+                webhook("notaurl"),
+                webhook("http://hooks.example/plain"),
+                webhook("ftp://hooks.example/file"),
+                webhook("mailto:ops@hooks.example"),
+            ]),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            pointers(&input, &options),
+            [
+                "/webhooks/1/url",
+                "/webhooks/2/url",
+                "/webhooks/3/url",
+                "/webhooks/4/url"
+            ]
+        );
+    }
+
+    #[test]
+    fn insecure_webhooks_can_be_allowed() {
+        let input = CreateTaskInput {
+            webhooks: Some(vec![webhook("http://localhost:8080/hook")]),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions {
+            allow_insecure_webhooks: true,
+            ..Default::default()
+        };
+        assert_eq!(validate_create_task_input(&input, &options), Ok(()));
+    }
+
+    #[test]
+    fn rejects_unfollowable_webhook_retries() {
+        let mut too_many = webhook("https://hooks.example/a");
+        too_many.retry = Some(retry(MAX_WEBHOOK_RETRIES + 1, 0, 0));
+        let mut inverted = webhook("https://hooks.example/b");
+        inverted.retry = Some(retry(3, 10_000, 1_000));
+        let input = CreateTaskInput {
+            webhooks: Some(vec![too_many, inverted]),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            pointers(&input, &options),
+            [
+                "/webhooks/0/retry/retries",
+                "/webhooks/1/retry/initialDelayMs"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unfollowable_retry_policies() {
+        let policy = |max_attempts, initial_delay_ms| RetryPolicy {
+            max_attempts,
+            backoff: BackoffStrategy::Linear,
+            initial_delay_ms,
+            max_delay_ms: 1_000,
+            retry_on: Vec::new(),
+        };
+        let options = TaskValidationOptions::default();
+        for (policy, expected) in [
+            (policy(0, 0), "/retryPolicy/maxAttempts"),
+            (
+                policy(MAX_RETRY_ATTEMPTS + 1, 0),
+                "/retryPolicy/maxAttempts",
+            ),
+            (policy(3, 2_000), "/retryPolicy/initialDelayMs"),
+        ] {
+            let input = CreateTaskInput {
+                retry_policy: Some(policy),
+                ..Default::default()
+            };
+            assert_eq!(pointers(&input, &options), [expected]);
+        }
+    }
+
+    #[test]
+    fn rejects_cleanup_triggers_below_the_minimum() {
+        let input = CreateTaskInput {
+            cleanup: Some(cleanup_after(0)),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            pointers(&input, &options),
+            ["/cleanup/rules/0/trigger/afterMs"]
+        );
+    }
+
+    #[test]
+    fn rejects_auth_rules_without_scope() {
+        let input = CreateTaskInput {
+            auth_config: Some(TaskAuthConfig {
+                rules: vec![
+                    auth_rule(vec![PermissionScope::TaskManage]),
+                    auth_rule(Vec::new()),
+                ],
+            }),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            pointers(&input, &options),
+            ["/authConfig/rules/1/match/scope"]
+        );
+    }
+
+    #[test]
+    fn rejects_forward_rules_to_bad_or_own_ids() {
+        let forward = |id: &str, target: &str, prefix: Option<&str>| CreateTaskInput {
+            id: Some(id.to_string()),
+            forward_to: Some(ForwardRule {
+                target_task_id: target.to_string(),
+                filter: Default::default(),
+                transform: prefix.map(|p| ForwardTransform {
+                    prefix_type: Some(p.to_string()),
+                    level: None,
+                }),
+            }),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            validate_create_task_input(&forward("child", "parent", Some("child")), &options),
+            Ok(())
+        );
+        assert_eq!(
+            pointers(&forward("child", "a/b", None), &options),
+            ["/forwardTo/targetTaskId"]
+        );
+        assert_eq!(
+            pointers(&forward("child", "child", Some("")), &options),
+            ["/forwardTo/targetTaskId", "/forwardTo/transform/prefixType"]
+        );
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let input = CreateTaskInput {
+            id: Some("a/b".to_string()),
+            ttl: Some(0),
+            webhooks: Some(vec![webhook("notaurl")]),
+            cleanup: Some(cleanup_after(0)),
+            auth_config: Some(TaskAuthConfig {
+                rules: vec![auth_rule(Vec::new())],
+            }),
+            ..Default::default()
+        };
+        let options = TaskValidationOptions::default();
+        assert_eq!(
+            pointers(&input, &options),
+            [
+                "/id",
+                "/ttl",
+                "/webhooks/0/url",
+                "/cleanup/rules/0/trigger/afterMs",
+                "/authConfig/rules/0/match/scope"
+            ]
+        );
+    }
+}
@@ -167,6 +167,9 @@ impl WorkerManager {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await;
@@ -189,9 +192,11 @@ impl WorkerManager {
             data,
         };
         let lt = Arc::clone(long_term_store);
-        tokio::spawn(async move {
-            let _ = lt.save_worker_event(event).await;
-        });
+        self.engine
+            .background()
+            .spawn("worker.save_audit_event", None, async move {
+                let _ = lt.save_worker_event(event).await;
+            });
     }
 
     // ─── Worker Registration & Lifecycle ────────────────────────────────
@@ -663,8 +668,9 @@ impl WorkerManager {
                     let wid = worker_id_owned.clone();
                     let rule = worker_match_rule.clone();
                     let tx = Arc::clone(&tx_clone);
+                    let background = engine.background().clone();
 
-                    tokio::spawn(async move {
+                    background.spawn("worker.pull_claim", Some(task_id.clone()), async move {
                         let Ok(Some(task)) = engine.get_task(&task_id).await else {
                             return;
                         };
@@ -747,6 +753,8 @@ impl WorkerManager {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         self.broadcast
@@ -800,6 +808,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
         let manager = WorkerManager::new(WorkerManagerOptions {
             engine: Arc::clone(&engine),
@@ -1650,6 +1661,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
 
         let received = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
@@ -1805,6 +1819,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
         let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
             engine: Arc::clone(&engine),
@@ -1939,6 +1956,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: None,
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
 
         engine
@@ -2134,6 +2154,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: Some(Arc::clone(&long_term_store) as Arc<dyn LongTermStore>),
             hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
         let manager = WorkerManager::new(WorkerManagerOptions {
             engine: Arc::clone(&engine),
@@ -2154,6 +2177,9 @@ mod tests {
             broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
             long_term_store: None,
             hooks: Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
         }));
         let manager = WorkerManager::new(WorkerManagerOptions {
             engine: Arc::clone(&engine),
@@ -96,6 +96,16 @@ mod tests {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         }
     }
 
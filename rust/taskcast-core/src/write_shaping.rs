@@ -0,0 +1,254 @@
+//! Rate shaping of long-term event writes.
+//!
+//! Without shaping, every published event spawns its own long-term write, so
+//! a task bursting tens of thousands of events opens as many writes at once
+//! and can saturate a database pool shared with every other query. With a
+//! [`WriteShaper`], event writes wait in a bounded queue and are started at
+//! a token-bucket rate: up to `burst` at once, then `rows_per_sec`.
+//!
+//! Task records are saved inline by the transition or update that changed
+//! them and never wait behind queued events.
+//!
+//! When the queue is full, [`WriteOverflow`] decides between dropping the
+//! event's long-term write (reported through
+//! `TaskcastHooks::on_event_dropped`) and making the publisher wait for room.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::background::BackgroundTasks;
+
+/// What a publish does when the long-term write queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteOverflow {
+    /// Skip the event's long-term write and report it as dropped.
+    #[default]
+    Drop,
+    /// Make the publisher wait until the queue has room.
+    Backpressure,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteShapingConfig {
+    /// Long-term event writes started per second once the burst is spent.
+    pub rows_per_sec: f64,
+    /// Writes that may start at once after a quiet period.
+    pub burst: u32,
+    /// Event writes held while waiting for the rate.
+    pub queue_capacity: usize,
+    pub overflow: WriteOverflow,
+}
+
+impl Default for WriteShapingConfig {
+    fn default() -> Self {
+        Self {
+            rows_per_sec: 500.0,
+            burst: 100,
+            queue_capacity: 10_000,
+            overflow: WriteOverflow::Drop,
+        }
+    }
+}
+
+/// Snapshot of the write shaper, for the stats surface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteShapingStats {
+    pub rows_per_sec: f64,
+    pub burst: u32,
+    /// Event writes waiting for the rate.
+    pub queue_depth: usize,
+    pub queue_capacity: usize,
+    pub overflow: WriteOverflow,
+    /// Event writes started since startup.
+    pub started: u64,
+    /// Event writes dropped because the queue was full, since startup.
+    pub shed: u64,
+}
+
+// ─── Token bucket ────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: u32, now: Instant) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate,
+            burst,
+            tokens: burst,
+            refilled_at: now,
+        }
+    }
+
+    /// Takes a token, returning how long to wait before using it.
+    fn take(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        self.refilled_at = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+// ─── Shaper ──────────────────────────────────────────────────────────────────
+
+struct QueuedWrite {
+    task_id: String,
+    write: BoxFuture<'static, ()>,
+    /// Holds the write's place in the queue until it is started.
+    _room: OwnedSemaphorePermit,
+}
+
+#[derive(Default)]
+struct Queue {
+    writes: VecDeque<QueuedWrite>,
+    /// Whether a drain loop is running. It exits once the queue is empty,
+    /// so an idle shaper leaves nothing in flight for shutdown to wait on.
+    draining: bool,
+}
+
+pub(crate) struct WriteShaper {
+    config: WriteShapingConfig,
+    queue: Mutex<Queue>,
+    room: Arc<Semaphore>,
+    bucket: Mutex<TokenBucket>,
+    started: AtomicU64,
+    shed: AtomicU64,
+}
+
+impl WriteShaper {
+    pub(crate) fn new(config: WriteShapingConfig) -> Self {
+        let capacity = config.queue_capacity.max(1);
+        Self {
+            bucket: Mutex::new(TokenBucket::new(
+                config.rows_per_sec,
+                config.burst,
+                Instant::now(),
+            )),
+            queue: Mutex::new(Queue::default()),
+            room: Arc::new(Semaphore::new(capacity)),
+            started: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+            config,
+        }
+    }
+
+    /// Queues `write` to be started through `background` at the shaped rate.
+    /// Returns `false` when the queue was full and the write was dropped.
+    pub(crate) async fn submit(
+        self: &Arc<Self>,
+        background: &BackgroundTasks,
+        task_id: &str,
+        write: BoxFuture<'static, ()>,
+    ) -> bool {
+        let room = match self.config.overflow {
+            WriteOverflow::Drop => Arc::clone(&self.room).try_acquire_owned().ok(),
+            WriteOverflow::Backpressure => Arc::clone(&self.room).acquire_owned().await.ok(),
+        };
+        let Some(room) = room else {
+            self.shed.fetch_add(1, Ordering::Relaxed);
+            return false;
+        };
+
+        let mut queue = self.queue.lock().unwrap();
+        queue.writes.push_back(QueuedWrite {
+            task_id: task_id.to_string(),
+            write,
+            _room: room,
+        });
+        if !queue.draining {
+            queue.draining = true;
+            let shaper = Arc::clone(self);
+            let spawner = background.clone();
+            background.spawn("long_term.shaper", None, async move {
+                shaper.drain(spawner).await;
+            });
+        }
+        true
+    }
+
+    /// Starts queued writes one token at a time until the queue is empty.
+    async fn drain(&self, background: BackgroundTasks) {
+        loop {
+            {
+                let mut queue = self.queue.lock().unwrap();
+                if queue.writes.is_empty() {
+                    queue.draining = false;
+                    return;
+                }
+            }
+            let wait = self.bucket.lock().unwrap().take(Instant::now());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+            // Only this loop takes writes out, so the one seen above is
+            // still there.
+            let Some(next) = self.queue.lock().unwrap().writes.pop_front() else {
+                continue;
+            };
+            self.started.fetch_add(1, Ordering::Relaxed);
+            background.spawn("long_term.save_event", Some(next.task_id), next.write);
+        }
+    }
+
+    pub(crate) fn stats(&self) -> WriteShapingStats {
+        WriteShapingStats {
+            rows_per_sec: self.config.rows_per_sec,
+            burst: self.config.burst,
+            queue_depth: self.queue.lock().unwrap().writes.len(),
+            queue_capacity: self.config.queue_capacity,
+            overflow: self.config.overflow,
+            started: self.started.load(Ordering::Relaxed),
+            shed: self.shed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_spends_its_burst_then_spaces_tokens_at_the_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 3, start);
+
+        for _ in 0..3 {
+            assert_eq!(bucket.take(start), Duration::ZERO);
+        }
+        assert_eq!(bucket.take(start), Duration::from_millis(100));
+        assert_eq!(bucket.take(start), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn bucket_refills_up_to_its_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 2, start);
+        bucket.take(start);
+        bucket.take(start);
+
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later), Duration::from_millis(100));
+    }
+}
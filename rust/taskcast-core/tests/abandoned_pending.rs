@@ -0,0 +1,394 @@
+//! Sweeping up tasks that were created but never started.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde_json::json;
+use taskcast_core::{
+    AbandonAction, AbandonedPendingConfig, AbandonedPendingSweep, AbandonedPendingSweeper,
+    AbandonedPendingSweeperOptions, BroadcastProvider, CleanupConfig, Clock, CreateTaskInput,
+    EventQueryOptions, Level, LongTermStore, MemoryBroadcastProvider, MemoryShortTermStore,
+    PublishEventInput, ShortTermStore, Task, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
+    WorkerAuditEvent, ABANDONED_ERROR_CODE,
+};
+
+const AFTER_MS: u64 = 60_000;
+
+// ─── Long-term store ─────────────────────────────────────────────────────────
+
+/// Long-term store keeping tasks and events in maps.
+#[derive(Default)]
+struct MapLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    events: Mutex<HashMap<String, Vec<TaskEvent>>>,
+}
+
+#[async_trait]
+impl LongTermStore for MapLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.events
+            .lock()
+            .unwrap()
+            .entry(event.task_id.clone())
+            .or_default()
+            .push(event);
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.events.lock().unwrap().remove(task_id);
+        self.tasks.lock().unwrap().remove(task_id);
+        Ok(())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    /// Starts at the wall clock, which task creation times come from.
+    fn new() -> Arc<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as f64;
+        Arc::new(Self(Mutex::new(now)))
+    }
+
+    fn advance(&self, ms: u64) {
+        *self.0.lock().unwrap() += ms as f64;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+struct Harness {
+    engine: Arc<TaskEngine>,
+    store: Arc<MemoryShortTermStore>,
+    long_term: Arc<MapLongTermStore>,
+    clock: Arc<ManualClock>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let store = Arc::new(MemoryShortTermStore::new());
+        let long_term = Arc::new(MapLongTermStore::default());
+        let clock = ManualClock::new();
+        let engine = Arc::new(
+            TaskEngine::new(TaskEngineOptions {
+                short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+                broadcast: Arc::new(MemoryBroadcastProvider::new()) as Arc<dyn BroadcastProvider>,
+                long_term_store: Some(Arc::clone(&long_term) as Arc<dyn LongTermStore>),
+                hooks: None,
+                label_limits: None,
+                sinks: Vec::new(),
+                coalesce_reads: None,
+            })
+            .with_clock(Arc::clone(&clock) as Arc<dyn Clock>),
+        );
+        Self {
+            engine,
+            store,
+            long_term,
+            clock,
+        }
+    }
+
+    fn sweeper(&self, config: Option<AbandonedPendingConfig>) -> AbandonedPendingSweeper {
+        AbandonedPendingSweeper::new(AbandonedPendingSweeperOptions {
+            engine: Arc::clone(&self.engine),
+            short_term_store: Arc::clone(&self.store) as Arc<dyn ShortTermStore>,
+            check_interval_ms: 60_000,
+            config,
+        })
+    }
+
+    async fn create(&self, task_id: &str, cleanup: Option<CleanupConfig>) {
+        self.engine
+            .create_task(CreateTaskInput {
+                id: Some(task_id.to_string()),
+                cleanup,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn publish(&self, task_id: &str) {
+        self.engine
+            .publish_event(
+                task_id,
+                PublishEventInput {
+                    r#type: "progress".to_string(),
+                    level: Level::Info,
+                    data: json!({ "percent": 0 }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    async fn task(&self, task_id: &str) -> Option<Task> {
+        self.engine.get_task(task_id).await.unwrap()
+    }
+
+    /// Waits for background long-term writes to land.
+    async fn settle(&self) {
+        assert!(self.engine.background().drain(Duration::from_secs(5)).await);
+    }
+}
+
+fn abandoned(action: AbandonAction) -> AbandonedPendingConfig {
+    AbandonedPendingConfig {
+        after_ms: AFTER_MS,
+        action,
+        include_with_events: None,
+    }
+}
+
+// ─── Cancel ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn stale_pending_task_is_cancelled_as_abandoned() {
+    let h = Harness::new();
+    h.create("stale", None).await;
+    let sweeper = h.sweeper(Some(abandoned(AbandonAction::Cancel)));
+
+    // Still fresh: not yet past the window.
+    h.clock.advance(AFTER_MS - 1_000);
+    assert_eq!(
+        sweeper.tick().await.unwrap(),
+        AbandonedPendingSweep::default()
+    );
+    assert_eq!(h.task("stale").await.unwrap().status, TaskStatus::Pending);
+
+    h.clock.advance(2_000);
+    let sweep = sweeper.tick().await.unwrap();
+    assert_eq!(sweep.cancelled, 1);
+
+    let task = h.task("stale").await.unwrap();
+    assert_eq!(task.status, TaskStatus::Cancelled);
+    let error = task.error.unwrap();
+    assert_eq!(error.code.as_deref(), Some(ABANDONED_ERROR_CODE));
+
+    let events = h.engine.get_events("stale", None).await.unwrap();
+    let status = events
+        .iter()
+        .find(|e| e.r#type == "taskcast:status")
+        .expect("status event emitted");
+    assert_eq!(status.data["status"], "cancelled");
+    assert_eq!(status.data["error"]["code"], ABANDONED_ERROR_CODE);
+
+    // A second sweep finds nothing left to do.
+    assert_eq!(
+        sweeper.tick().await.unwrap(),
+        AbandonedPendingSweep::default()
+    );
+}
+
+#[tokio::test]
+async fn tasks_that_left_pending_are_untouched() {
+    let h = Harness::new();
+    h.create("started", None).await;
+    h.engine
+        .transition_task("started", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    h.create("paused", None).await;
+    h.engine
+        .transition_task("paused", TaskStatus::Paused, None)
+        .await
+        .unwrap();
+    h.clock.advance(AFTER_MS + 1_000);
+
+    let sweep = h
+        .sweeper(Some(abandoned(AbandonAction::Cancel)))
+        .tick()
+        .await
+        .unwrap();
+
+    assert_eq!(sweep, AbandonedPendingSweep::default());
+    assert_eq!(h.task("started").await.unwrap().status, TaskStatus::Running);
+    assert_eq!(h.task("paused").await.unwrap().status, TaskStatus::Paused);
+}
+
+#[tokio::test]
+async fn pending_task_with_events_is_spared_unless_included() {
+    let h = Harness::new();
+    h.create("busy", None).await;
+    h.publish("busy").await;
+    h.clock.advance(AFTER_MS + 1_000);
+
+    let sweep = h
+        .sweeper(Some(abandoned(AbandonAction::Cancel)))
+        .tick()
+        .await
+        .unwrap();
+    assert_eq!(sweep.cancelled, 0);
+    assert_eq!(h.task("busy").await.unwrap().status, TaskStatus::Pending);
+
+    let sweep = h
+        .sweeper(Some(AbandonedPendingConfig {
+            include_with_events: Some(true),
+            ..abandoned(AbandonAction::Cancel)
+        }))
+        .tick()
+        .await
+        .unwrap();
+    assert_eq!(sweep.cancelled, 1);
+    assert_eq!(h.task("busy").await.unwrap().status, TaskStatus::Cancelled);
+}
+
+// ─── Delete ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn delete_mode_removes_the_task_from_every_store() {
+    let h = Harness::new();
+    h.create("stale", None).await;
+    h.settle().await;
+    assert!(h.long_term.get_task("stale").await.unwrap().is_some());
+
+    h.clock.advance(AFTER_MS + 1_000);
+    let sweeper = h.sweeper(Some(abandoned(AbandonAction::Delete)));
+    let sweep = sweeper.tick().await.unwrap();
+
+    assert_eq!(sweep.deleted, 1);
+    assert!(h.store.get_task("stale").await.unwrap().is_none());
+    assert!(h.long_term.get_task("stale").await.unwrap().is_none());
+    assert!(h.task("stale").await.is_none());
+    assert_eq!(
+        sweeper.tick().await.unwrap(),
+        AbandonedPendingSweep::default()
+    );
+}
+
+// ─── Per-task override ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn task_override_replaces_the_global_setting() {
+    let h = Harness::new();
+    let keep_longer = CleanupConfig {
+        rules: Vec::new(),
+        abandoned_pending: Some(AbandonedPendingConfig {
+            after_ms: AFTER_MS * 10,
+            ..abandoned(AbandonAction::Cancel)
+        }),
+    };
+    h.create("patient", Some(keep_longer)).await;
+    let delete = CleanupConfig {
+        rules: Vec::new(),
+        abandoned_pending: Some(abandoned(AbandonAction::Delete)),
+    };
+    h.create("opted_in", Some(delete)).await;
+    h.create("default", None).await;
+    h.clock.advance(AFTER_MS + 1_000);
+
+    // No global setting: only the task that set its own is swept.
+    let sweep = h.sweeper(None).tick().await.unwrap();
+    assert_eq!(
+        sweep,
+        AbandonedPendingSweep {
+            cancelled: 0,
+            deleted: 1
+        }
+    );
+    assert!(h.task("opted_in").await.is_none());
+    assert_eq!(h.task("default").await.unwrap().status, TaskStatus::Pending);
+
+    let sweep = h
+        .sweeper(Some(abandoned(AbandonAction::Cancel)))
+        .tick()
+        .await
+        .unwrap();
+    assert_eq!(sweep.cancelled, 1);
+    assert_eq!(
+        h.task("default").await.unwrap().status,
+        TaskStatus::Cancelled
+    );
+    assert_eq!(h.task("patient").await.unwrap().status, TaskStatus::Pending);
+}
+
+// ─── Multiple instances ─────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn concurrent_sweeps_cancel_each_task_once() {
+    let h = Harness::new();
+    for i in 0..10 {
+        h.create(&format!("t{i}"), None).await;
+    }
+    h.clock.advance(AFTER_MS + 1_000);
+
+    let first = h.sweeper(Some(abandoned(AbandonAction::Cancel)));
+    let second = h.sweeper(Some(abandoned(AbandonAction::Cancel)));
+    let (a, b) = tokio::join!(first.tick(), second.tick());
+
+    assert_eq!(a.unwrap().cancelled + b.unwrap().cancelled, 10);
+    for i in 0..10 {
+        let events = h.engine.get_events(&format!("t{i}"), None).await.unwrap();
+        let cancels = events
+            .iter()
+            .filter(|e| e.r#type == "taskcast:status" && e.data["status"] == "cancelled")
+            .count();
+        assert_eq!(cancels, 1);
+    }
+}
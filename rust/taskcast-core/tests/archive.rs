@@ -4,11 +4,11 @@ use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use serde_json::json;
 use taskcast_core::{
-    build_task_archive_restore_data, validate_task_archive, BroadcastProvider, CreateTaskInput,
-    EngineError, EventQueryOptions, Level, LongTermStore, MemoryBroadcastProvider,
-    MemoryShortTermStore, PublishEventInput, SeriesMode, ShortTermStore, Task, TaskArchive,
-    TaskArchiveImportOptions, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
-    WorkerAuditEvent,
+    build_task_archive_restore_data, task_channel, validate_task_archive, BroadcastProvider,
+    CreateTaskInput, EngineError, EventQueryOptions, Level, LongTermStore,
+    MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput, SeriesMode, ShortTermStore,
+    Task, TaskArchive, TaskArchiveImportOptions, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskStatus, WorkerAuditEvent,
 };
 
 fn make_task(id: &str) -> Task {
@@ -35,6 +35,16 @@ fn make_task(id: &str) -> Task {
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     }
 }
 
@@ -51,6 +61,8 @@ fn make_event(id: &str, task_id: &str, index: u64, data: serde_json::Value) -> T
         series_mode: None,
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
@@ -115,6 +127,9 @@ fn make_engine(
         broadcast,
         long_term_store,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     })
 }
 
@@ -437,7 +452,7 @@ async fn engine_import_preserves_history_is_silent_and_next_index_continues() {
     let observed_for_handler = Arc::clone(&observed);
     let _unsubscribe = broadcast
         .subscribe_sync(
-            "task-1",
+            &task_channel("task-1"),
             Box::new(move |event| {
                 observed_for_handler.lock().unwrap().push(event);
             }),
@@ -457,6 +472,9 @@ async fn engine_import_preserves_history_is_silent_and_next_index_continues() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -485,6 +503,9 @@ async fn engine_import_rejects_short_term_store_without_archive_restore_support(
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     });
 
     let result = engine.import_task_archive(make_archive(vec![]), None).await;
@@ -555,6 +576,9 @@ async fn engine_import_rejects_conflict_without_overwrite_and_allows_overwrite()
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -802,8 +826,8 @@ impl ShortTermStore for UnsupportedShortTermStore {
         _task_id: &str,
         _series_id: &str,
         _event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        Ok(())
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
     }
 
     async fn accumulate_series(
@@ -0,0 +1,238 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::json;
+use taskcast_core::{
+    BroadcastProvider, Clock, CreateTaskInput, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, SeriesMode, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskStatus,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+/// Long enough that a window's timer never fires during a test, so only the
+/// manual clock closes windows.
+const LONG_WINDOW_MS: u64 = 60_000;
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    fn new(now: f64) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(now)))
+    }
+
+    fn advance(&self, ms: f64) {
+        *self.0.lock().unwrap() += ms;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+struct Harness {
+    engine: TaskEngine,
+    clock: Arc<ManualClock>,
+    live: Arc<Mutex<Vec<TaskEvent>>>,
+}
+
+impl Harness {
+    /// A running task `t1` whose broadcasts are collected in `live`.
+    async fn new() -> Self {
+        let broadcast = Arc::new(MemoryBroadcastProvider::new());
+        let clock = ManualClock::new(0.0);
+        let engine = TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_clock(Arc::clone(&clock) as Arc<dyn Clock>);
+        engine
+            .create_task(CreateTaskInput {
+                id: Some("t1".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        engine
+            .transition_task("t1", TaskStatus::Running, None)
+            .await
+            .unwrap();
+
+        let live = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&live);
+        let _unsubscribe = broadcast
+            .subscribe(
+                "task.t1",
+                Box::new(move |event: TaskEvent| sink.lock().unwrap().push(event)),
+            )
+            .await;
+        Self {
+            engine,
+            clock,
+            live,
+        }
+    }
+
+    async fn publish(&self, input: PublishEventInput) -> TaskEvent {
+        self.engine.publish_event("t1", input).await.unwrap()
+    }
+
+    /// Broadcasts of the `s1` series seen so far.
+    fn series_broadcasts(&self) -> Vec<TaskEvent> {
+        self.live
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.series_id.as_deref() == Some("s1"))
+            .cloned()
+            .collect()
+    }
+}
+
+fn token(text: &str, window_ms: Option<u64>, series_end: bool) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "llm.delta".to_string(),
+        level: Level::Info,
+        data: json!({ "delta": text }),
+        series_id: Some("s1".to_string()),
+        series_mode: Some(SeriesMode::Accumulate),
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: window_ms,
+        series_end,
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn rapid_events_broadcast_once_per_window_and_all_are_stored() {
+    let harness = Harness::new().await;
+
+    // 20 events 10s apart under a 60s window: each window spans six events.
+    let mut last = None;
+    for i in 0..20 {
+        last = Some(
+            harness
+                .publish(token(&i.to_string(), Some(LONG_WINDOW_MS), false))
+                .await,
+        );
+        harness.clock.advance(10_000.0);
+    }
+    let broadcasts = harness.series_broadcasts();
+    assert_eq!(broadcasts.len(), 2);
+    assert!(broadcasts.iter().all(|e| e.series_snapshot == Some(true)));
+    assert_eq!(broadcasts[0].data, json!({ "delta": "0123456" }));
+    assert_eq!(harness.engine.held_broadcast_count(), 1);
+
+    let history = harness.engine.get_events("t1", None).await.unwrap();
+    let stored: Vec<_> = history
+        .iter()
+        .filter(|e| e.series_id.as_deref() == Some("s1"))
+        .collect();
+    assert_eq!(stored.len(), 20);
+    assert!(stored.iter().all(|e| e.series_snapshot.is_none()));
+
+    // The terminal transition sends the held window ahead of the status.
+    harness
+        .engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    let broadcasts = harness.series_broadcasts();
+    assert_eq!(broadcasts.len(), 3);
+    let trailing = broadcasts.last().unwrap();
+    let last = last.unwrap();
+    assert_eq!(trailing.id, last.id);
+    assert_eq!(trailing.index, last.index);
+    assert_eq!(
+        trailing.data,
+        json!({ "delta": (0..20).map(|i| i.to_string()).collect::<String>() })
+    );
+    let live = harness.live.lock().unwrap();
+    let position = |id: &str| live.iter().position(|e| e.id == id).unwrap();
+    let status = live.last().unwrap();
+    assert_eq!(status.r#type, "taskcast:status");
+    assert!(position(&trailing.id) < position(&status.id));
+    drop(live);
+    assert_eq!(harness.engine.held_broadcast_count(), 0);
+}
+
+#[tokio::test]
+async fn series_end_broadcasts_the_total_at_once() {
+    let harness = Harness::new().await;
+
+    for text in ["a", "b", "c"] {
+        harness
+            .publish(token(text, Some(LONG_WINDOW_MS), false))
+            .await;
+    }
+    assert!(harness.series_broadcasts().is_empty());
+
+    let end = harness
+        .publish(token("d", Some(LONG_WINDOW_MS), true))
+        .await;
+    let broadcasts = harness.series_broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].id, end.id);
+    assert_eq!(broadcasts[0].data, json!({ "delta": "abcd" }));
+    assert_eq!(broadcasts[0].series_snapshot, Some(true));
+    assert_eq!(harness.engine.held_broadcast_count(), 0);
+}
+
+#[tokio::test]
+async fn a_window_broadcasts_when_its_timer_fires() {
+    let harness = Harness::new().await;
+
+    for text in ["a", "b", "c"] {
+        harness.publish(token(text, Some(20), false)).await;
+    }
+    assert!(harness.series_broadcasts().is_empty());
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let broadcasts = harness.series_broadcasts();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].data, json!({ "delta": "abc" }));
+    assert_eq!(harness.engine.held_broadcast_count(), 0);
+}
+
+#[tokio::test]
+async fn events_without_a_window_broadcast_as_published() {
+    let harness = Harness::new().await;
+
+    harness.publish(token("a", None, false)).await;
+    harness
+        .publish(PublishEventInput {
+            series_mode: Some(SeriesMode::KeepAll),
+            ..token("b", Some(LONG_WINDOW_MS), false)
+        })
+        .await;
+
+    let broadcasts = harness.series_broadcasts();
+    assert_eq!(broadcasts.len(), 2);
+    assert_eq!(broadcasts[0].data, json!({ "delta": "a" }));
+    assert_eq!(broadcasts[0].series_snapshot, None);
+    assert_eq!(broadcasts[1].data, json!({ "delta": "b" }));
+    assert_eq!(harness.engine.held_broadcast_count(), 0);
+}
+
+#[tokio::test]
+async fn deleting_a_task_drops_its_windows() {
+    let harness = Harness::new().await;
+
+    harness
+        .publish(token("a", Some(LONG_WINDOW_MS), false))
+        .await;
+    assert_eq!(harness.engine.held_broadcast_count(), 1);
+
+    assert!(harness.engine.delete_task("t1").await.unwrap());
+    assert_eq!(harness.engine.held_broadcast_count(), 0);
+    assert!(harness.series_broadcasts().is_empty());
+}
@@ -0,0 +1,134 @@
+//! `TaskEngine::cancel_task`: which statuses it cancels from, and what it
+//! records on the task and its status event.
+
+use std::sync::Arc;
+
+use taskcast_core::{
+    CreateTaskInput, EngineError, EventQueryOptions, MemoryBroadcastProvider, MemoryShortTermStore,
+    TaskEngine, TaskEngineOptions, TaskStatus, CANCELLED_ERROR_CODE,
+};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+fn make_engine() -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+/// Creates `id` and moves it through `path`.
+async fn task_at(engine: &TaskEngine, id: &str, path: &[TaskStatus]) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    for status in path {
+        engine
+            .transition_task(id, status.clone(), None)
+            .await
+            .unwrap();
+    }
+}
+
+async fn last_status_data(engine: &TaskEngine, id: &str) -> serde_json::Value {
+    let events = engine
+        .get_events(id, None::<EventQueryOptions>)
+        .await
+        .unwrap();
+    events
+        .into_iter()
+        .rev()
+        .find(|e| e.r#type == "taskcast:status")
+        .unwrap()
+        .data
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn cancels_a_running_task_with_its_reason() {
+    let engine = make_engine();
+    task_at(&engine, "t1", &[TaskStatus::Running]).await;
+
+    let task = engine
+        .cancel_task("t1", Some("user aborted".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(task.status, TaskStatus::Cancelled);
+    assert!(task.completed_at.is_some());
+    let error = task.error.unwrap();
+    assert_eq!(error.code.as_deref(), Some(CANCELLED_ERROR_CODE));
+    assert_eq!(error.message, "user aborted");
+
+    let data = last_status_data(&engine, "t1").await;
+    assert_eq!(data["status"], "cancelled");
+    assert_eq!(data["reason"], "user aborted");
+    assert_eq!(data["error"]["code"], "CANCELLED");
+}
+
+#[tokio::test]
+async fn cancels_a_pending_task_without_a_reason() {
+    let engine = make_engine();
+    task_at(&engine, "t1", &[]).await;
+
+    let task = engine.cancel_task("t1", None).await.unwrap();
+
+    assert_eq!(task.status, TaskStatus::Cancelled);
+    assert_eq!(task.error.unwrap().message, "Task cancelled");
+    let data = last_status_data(&engine, "t1").await;
+    assert!(data.get("reason").is_none(), "{data}");
+}
+
+#[tokio::test]
+async fn finished_tasks_are_terminal() {
+    let engine = make_engine();
+    task_at(&engine, "t1", &[TaskStatus::Running, TaskStatus::Completed]).await;
+
+    let err = engine.cancel_task("t1", None).await.unwrap_err();
+
+    assert!(
+        matches!(err, EngineError::TaskTerminal(TaskStatus::Completed)),
+        "{err}"
+    );
+    let task = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert!(task.error.is_none());
+}
+
+#[tokio::test]
+async fn other_statuses_are_invalid_transitions() {
+    let engine = make_engine();
+    task_at(&engine, "t1", &[TaskStatus::Running, TaskStatus::Paused]).await;
+
+    let err = engine.cancel_task("t1", None).await.unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            EngineError::InvalidTransition {
+                from: TaskStatus::Paused,
+                to: TaskStatus::Cancelled,
+            }
+        ),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn missing_tasks_are_not_found() {
+    let engine = make_engine();
+
+    let err = engine.cancel_task("nope", None).await.unwrap_err();
+
+    assert!(matches!(err, EngineError::TaskNotFound(_)), "{err}");
+}
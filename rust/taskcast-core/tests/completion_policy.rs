@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::{
+    CompletionMode, CompletionPolicy, CreateTaskInput, EngineError, MemoryBroadcastProvider,
+    MemoryShortTermStore, TaskEngine, TaskEngineOptions, TaskError, TaskStatus, TransitionPayload,
+    CHILD_FAILED,
+};
+
+fn make_engine() -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+async fn create_parent(engine: &TaskEngine, id: &str, mode: CompletionMode, child_ids: &[&str]) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            completion_policy: Some(CompletionPolicy {
+                mode,
+                child_ids: child_ids.iter().map(|id| id.to_string()).collect(),
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+async fn create_child(engine: &TaskEngine, id: &str, parent_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            parent_id: Some(parent_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+async fn finish(engine: &TaskEngine, task_id: &str, status: TaskStatus) {
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let payload = (status == TaskStatus::Failed).then(|| TransitionPayload {
+        error: Some(TaskError {
+            code: None,
+            message: "boom".to_string(),
+            details: None,
+        }),
+        ..Default::default()
+    });
+    engine
+        .transition_task(task_id, status, payload)
+        .await
+        .unwrap();
+}
+
+async fn status_events(engine: &TaskEngine, task_id: &str) -> Vec<String> {
+    engine
+        .get_events(task_id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.r#type == "taskcast:status")
+        .map(|event| event.data["status"].as_str().unwrap().to_string())
+        .collect()
+}
+
+async fn status_of(engine: &TaskEngine, task_id: &str) -> TaskStatus {
+    engine.get_task(task_id).await.unwrap().unwrap().status
+}
+
+#[tokio::test]
+async fn parent_completes_once_every_child_completes() {
+    let engine = make_engine();
+    create_parent(&engine, "deploy", CompletionMode::AllChildren, &[]).await;
+    for id in ["build", "test", "ship"] {
+        create_child(&engine, id, "deploy").await;
+    }
+    let parent = engine.get_task("deploy").await.unwrap().unwrap();
+    assert_eq!(
+        parent.completion_policy.unwrap().child_ids,
+        vec!["build", "test", "ship"]
+    );
+
+    finish(&engine, "build", TaskStatus::Completed).await;
+    finish(&engine, "test", TaskStatus::Completed).await;
+    assert_eq!(status_of(&engine, "deploy").await, TaskStatus::Pending);
+    finish(&engine, "ship", TaskStatus::Completed).await;
+
+    let parent = engine.get_task("deploy").await.unwrap().unwrap();
+    assert_eq!(parent.status, TaskStatus::Completed);
+    assert_eq!(parent.error, None);
+    assert_eq!(
+        status_events(&engine, "deploy").await,
+        vec!["running", "completed"]
+    );
+}
+
+#[tokio::test]
+async fn failed_children_are_listed_on_the_failed_parent() {
+    let engine = make_engine();
+    create_parent(&engine, "deploy", CompletionMode::AllChildren, &[]).await;
+    for id in ["a", "b", "c"] {
+        create_child(&engine, id, "deploy").await;
+    }
+    finish(&engine, "a", TaskStatus::Failed).await;
+    // allChildren waits for the rest even after a failure.
+    assert_eq!(status_of(&engine, "deploy").await, TaskStatus::Pending);
+    finish(&engine, "b", TaskStatus::Completed).await;
+    engine
+        .transition_task("c", TaskStatus::Cancelled, None)
+        .await
+        .unwrap();
+
+    let parent = engine.get_task("deploy").await.unwrap().unwrap();
+    assert_eq!(parent.status, TaskStatus::Failed);
+    let error = parent.error.unwrap();
+    assert_eq!(error.code.as_deref(), Some(CHILD_FAILED));
+    assert_eq!(error.details.unwrap()["failedChildIds"], json!(["a", "c"]));
+}
+
+#[tokio::test]
+async fn any_child_failure_fails_the_parent_at_once() {
+    let engine = make_engine();
+    create_parent(&engine, "deploy", CompletionMode::AnyChildFailure, &[]).await;
+    create_child(&engine, "a", "deploy").await;
+    create_child(&engine, "b", "deploy").await;
+    finish(&engine, "a", TaskStatus::Failed).await;
+
+    assert_eq!(status_of(&engine, "deploy").await, TaskStatus::Failed);
+    assert_eq!(status_of(&engine, "b").await, TaskStatus::Pending);
+    // The parent has ended, so no more children can join it.
+    let err = engine
+        .create_task(CreateTaskInput {
+            id: Some("late".to_string()),
+            parent_id: Some("deploy".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, EngineError::InvalidInput(ref m) if m.contains("already ended")));
+    assert!(engine.get_task("late").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn explicitly_listed_children_settle_the_parent() {
+    let engine = make_engine();
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("early".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    finish(&engine, "early", TaskStatus::Completed).await;
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("late".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    create_parent(
+        &engine,
+        "deploy",
+        CompletionMode::AllChildren,
+        &["early", "late"],
+    )
+    .await;
+    assert_eq!(
+        engine.get_task("late").await.unwrap().unwrap().parent_id,
+        Some("deploy".to_string())
+    );
+
+    finish(&engine, "late", TaskStatus::Completed).await;
+    assert_eq!(status_of(&engine, "deploy").await, TaskStatus::Completed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn last_two_children_ending_together_complete_the_parent_once() {
+    for _ in 0..20 {
+        let engine = Arc::new(make_engine());
+        create_parent(&engine, "deploy", CompletionMode::AllChildren, &[]).await;
+        for id in ["a", "b"] {
+            create_child(&engine, id, "deploy").await;
+            engine
+                .transition_task(id, TaskStatus::Running, None)
+                .await
+                .unwrap();
+        }
+
+        let handles: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|id| {
+                let engine = Arc::clone(&engine);
+                tokio::spawn(async move {
+                    engine
+                        .transition_task(id, TaskStatus::Completed, None)
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(status_of(&engine, "deploy").await, TaskStatus::Completed);
+        assert_eq!(
+            status_events(&engine, "deploy").await,
+            vec!["running", "completed"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn manual_parents_are_left_alone() {
+    let engine = make_engine();
+    create_parent(&engine, "deploy", CompletionMode::Manual, &["a"]).await;
+    create_child(&engine, "a", "deploy").await;
+    create_child(&engine, "b", "deploy").await;
+    finish(&engine, "a", TaskStatus::Completed).await;
+    finish(&engine, "b", TaskStatus::Completed).await;
+
+    let parent = engine.get_task("deploy").await.unwrap().unwrap();
+    assert_eq!(parent.status, TaskStatus::Pending);
+    assert_eq!(parent.completion_policy.unwrap().child_ids, vec!["a"]);
+    assert_eq!(
+        engine.get_task("b").await.unwrap().unwrap().parent_id,
+        Some("deploy".to_string())
+    );
+}
+
+#[tokio::test]
+async fn invalid_policies_are_rejected_at_creation() {
+    let engine = make_engine();
+    let create = |id: &str, parent_id: Option<&str>, child_ids: &[&str]| CreateTaskInput {
+        id: Some(id.to_string()),
+        parent_id: parent_id.map(str::to_string),
+        completion_policy: Some(CompletionPolicy {
+            mode: CompletionMode::AllChildren,
+            child_ids: child_ids.iter().map(|id| id.to_string()).collect(),
+        }),
+        ..Default::default()
+    };
+
+    for input in [
+        create("p", None, &["p"]),
+        create("p", None, &["a", "a"]),
+        create("p", None, &[""]),
+        create("p", Some("p"), &[]),
+        create("p", Some("missing"), &[]),
+    ] {
+        let err = engine.create_task(input).await.unwrap_err();
+        assert!(matches!(err, EngineError::InvalidInput(_)), "{err}");
+    }
+    assert!(engine.get_task("p").await.unwrap().is_none());
+
+    create_parent(&engine, "other", CompletionMode::AllChildren, &[]).await;
+    create_child(&engine, "taken", "other").await;
+    let err = engine
+        .create_task(create("p", None, &["taken"]))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, EngineError::InvalidInput(ref m) if m.contains("already a child")));
+}
+
+#[tokio::test]
+async fn a_child_that_cannot_be_created_is_unlinked() {
+    let engine = make_engine();
+    create_parent(&engine, "deploy", CompletionMode::AllChildren, &[]).await;
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("dup".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let err = engine
+        .create_task(CreateTaskInput {
+            id: Some("dup".to_string()),
+            parent_id: Some("deploy".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, EngineError::TaskAlreadyExists { .. }));
+    let parent = engine.get_task("deploy").await.unwrap().unwrap();
+    assert!(parent.completion_policy.unwrap().child_ids.is_empty());
+}
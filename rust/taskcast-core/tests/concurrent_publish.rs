@@ -20,6 +20,9 @@ fn make_engine() -> TaskEngine {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     })
 }
 
@@ -69,6 +72,9 @@ async fn concurrent_publishes_preserve_index_order() {
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -124,6 +130,9 @@ async fn concurrent_latest_and_plain_events_preserve_relative_order() {
                         series_id: Some("msg_content".to_string()),
                         series_mode: Some(taskcast_core::SeriesMode::Latest),
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -142,6 +151,9 @@ async fn concurrent_latest_and_plain_events_preserve_relative_order() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -159,6 +171,9 @@ async fn concurrent_latest_and_plain_events_preserve_relative_order() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -209,6 +224,9 @@ async fn concurrent_publishes_to_different_tasks_are_independent() {
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
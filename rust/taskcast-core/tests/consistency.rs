@@ -0,0 +1,451 @@
+//! Consistency scans and repairs across a memory short-term store and an
+//! in-memory long-term store.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde_json::json;
+use taskcast_core::{
+    scan_consistency, ConsistencyIssue, EventQueryOptions, Level, LongTermStore,
+    MemoryShortTermStore, OrphanPolicy, ScanOptions, SeriesMode, ShortTermStore, Task, TaskEvent,
+    TaskOriginKind, TaskStatus, WorkerAuditEvent, PLACEHOLDER_METADATA_KEY,
+};
+
+// ─── Long-term store ─────────────────────────────────────────────────────────
+
+/// Long-term store keeping tasks and events in maps.
+#[derive(Default)]
+struct MapLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    events: Mutex<BTreeMap<String, Vec<TaskEvent>>>,
+    /// Whether `list_event_task_ids` is left to the unsupported default.
+    unlisted: bool,
+}
+
+#[async_trait]
+impl LongTermStore for MapLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut events = self.events.lock().unwrap();
+        let task_events = events.entry(event.task_id.clone()).or_default();
+        if !task_events.iter().any(|e| e.id == event.id) {
+            task_events.push(event);
+        }
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_event_task_ids(
+        &self,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.unlisted {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "list_event_task_ids is not supported by this long-term store",
+            )));
+        }
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|id| after.is_none_or(|after| id.as_str() > after))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.events.lock().unwrap().remove(task_id);
+        self.tasks.lock().unwrap().remove(task_id);
+        Ok(())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+fn task(id: &str, status: TaskStatus, completed_at: Option<f64>) -> Task {
+    serde_json::from_value(json!({
+        "id": id,
+        "status": status,
+        "createdAt": 1000.0,
+        "updatedAt": completed_at.unwrap_or(1000.0),
+        "completedAt": completed_at,
+    }))
+    .unwrap()
+}
+
+fn event(task_id: &str, index: u64, r#type: &str, data: serde_json::Value) -> TaskEvent {
+    TaskEvent {
+        id: format!("{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: r#type.to_string(),
+        level: Level::Info,
+        data,
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn chunk(task_id: &str, index: u64, delta: &str) -> TaskEvent {
+    TaskEvent {
+        series_id: Some("s1".to_string()),
+        series_mode: Some(SeriesMode::Accumulate),
+        ..event(task_id, index, "llm.delta", json!({ "delta": delta }))
+    }
+}
+
+struct Stores {
+    short_term: Arc<dyn ShortTermStore>,
+    long_term: Arc<MapLongTermStore>,
+}
+
+impl Stores {
+    fn new(long_term: MapLongTermStore) -> Self {
+        Self {
+            short_term: Arc::new(MemoryShortTermStore::new()),
+            long_term: Arc::new(long_term),
+        }
+    }
+
+    async fn scan(&self, options: ScanOptions) -> taskcast_core::ConsistencyReport {
+        let long_term: Arc<dyn LongTermStore> = self.long_term.clone();
+        scan_consistency(&self.short_term, Some(&long_term), options)
+            .await
+            .unwrap()
+    }
+
+    /// A running task whose history holds `events`, appended without
+    /// allocating indices.
+    async fn running_task(&self, id: &str, events: Vec<TaskEvent>) {
+        self.short_term
+            .save_task(task(id, TaskStatus::Running, None))
+            .await
+            .unwrap();
+        for event in events {
+            self.short_term.append_event(id, event).await.unwrap();
+        }
+    }
+}
+
+fn repair() -> ScanOptions {
+    ScanOptions {
+        repair: true,
+        ..Default::default()
+    }
+}
+
+// ─── Detection and repair ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn finds_each_class_and_a_repair_leaves_a_clean_scan() {
+    let stores = Stores::new(MapLongTermStore::default());
+
+    // (a) Long-term events whose task is gone from both stores.
+    for index in 0..2 {
+        stores
+            .long_term
+            .save_event(event("orphan", index, "log", json!({})))
+            .await
+            .unwrap();
+    }
+
+    // (b) A series latest naming an event history no longer holds, and
+    // (c) an index counter that never moved past the stored events.
+    stores
+        .running_task(
+            "series",
+            vec![chunk("series", 0, "a"), chunk("series", 1, "b")],
+        )
+        .await;
+    stores
+        .short_term
+        .set_series_latest("series", "s1", chunk("series", 9, "ab-lost"))
+        .await
+        .unwrap();
+
+    // (d) A task that ended long ago and never reached the long-term store.
+    stores
+        .short_term
+        .save_task(task("ended", TaskStatus::Completed, Some(2000.0)))
+        .await
+        .unwrap();
+    let index = stores.short_term.next_index("ended").await.unwrap();
+    stores
+        .short_term
+        .append_event("ended", event("ended", index, "log", json!({ "n": 1 })))
+        .await
+        .unwrap();
+
+    let report = stores.scan(ScanOptions::default()).await;
+    assert!(!report.repair);
+    assert_eq!(report.counts.orphaned_events, 1);
+    assert_eq!(report.counts.dangling_series_latest, 1);
+    assert_eq!(report.counts.stale_index_counters, 1);
+    assert_eq!(report.counts.missing_from_long_term, 1);
+    assert_eq!(report.short_term_tasks_scanned, 2);
+    assert_eq!(report.long_term_tasks_scanned, 1);
+    let issues: Vec<&ConsistencyIssue> = report.findings.iter().map(|f| &f.issue).collect();
+    assert!(issues.contains(&&ConsistencyIssue::DanglingSeriesLatest {
+        task_id: "series".to_string(),
+        series_id: "s1".to_string(),
+        event_id: "series-9".to_string(),
+    }));
+    assert!(issues.contains(&&ConsistencyIssue::StaleIndexCounter {
+        task_id: "series".to_string(),
+        next_index: 0,
+        max_index: 1,
+    }));
+    assert!(report.findings.iter().all(|f| f.repaired.is_none()));
+
+    // A dry run changes nothing.
+    assert_eq!(stores.scan(ScanOptions::default()).await.counts.total(), 4);
+
+    let repaired = stores.scan(repair()).await;
+    assert_eq!(repaired.counts.total(), 4);
+    assert_eq!(repaired.repaired, 4);
+    assert_eq!(repaired.repair_failures, 0);
+    assert!(repaired.findings.iter().all(|f| f.repaired == Some(true)));
+
+    let rescan = stores.scan(ScanOptions::default()).await;
+    assert!(rescan.is_clean(), "{rescan:?}");
+
+    // The orphans are gone, the series total is rebuilt from history, the
+    // counter is past the stored indices and the ended task is persisted.
+    assert!(stores
+        .long_term
+        .get_events("orphan", None)
+        .await
+        .unwrap()
+        .is_empty());
+    let latest = stores
+        .short_term
+        .get_series_latest("series", "s1")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.id, "series-1");
+    assert_eq!(latest.data, json!({ "delta": "ab" }));
+    assert_eq!(stores.short_term.next_index("series").await.unwrap(), 2);
+    assert!(stores.long_term.get_task("ended").await.unwrap().is_some());
+    let persisted = stores.long_term.get_events("ended", None).await.unwrap();
+    assert_eq!(persisted.len(), 1);
+}
+
+#[tokio::test]
+async fn placeholder_policy_recreates_the_task_record() {
+    let stores = Stores::new(MapLongTermStore::default());
+    stores
+        .long_term
+        .save_event(event(
+            "orphan",
+            0,
+            "taskcast:status",
+            json!({ "status": "running" }),
+        ))
+        .await
+        .unwrap();
+    stores
+        .long_term
+        .save_event(event(
+            "orphan",
+            1,
+            "taskcast:status",
+            json!({ "status": "failed" }),
+        ))
+        .await
+        .unwrap();
+    stores
+        .long_term
+        .save_event(event("orphan", 2, "log", json!({})))
+        .await
+        .unwrap();
+
+    let report = stores
+        .scan(ScanOptions {
+            orphan_policy: OrphanPolicy::Placeholder,
+            ..repair()
+        })
+        .await;
+    assert_eq!(report.repaired, 1);
+
+    let placeholder = stores.long_term.get_task("orphan").await.unwrap().unwrap();
+    assert_eq!(placeholder.status, TaskStatus::Failed);
+    assert_eq!(placeholder.created_at, 1000.0);
+    assert_eq!(placeholder.completed_at, Some(1002.0));
+    assert_eq!(placeholder.origin_kind(), TaskOriginKind::System);
+    assert_eq!(
+        placeholder.metadata.unwrap()[PLACEHOLDER_METADATA_KEY],
+        json!(true)
+    );
+    assert_eq!(
+        stores
+            .long_term
+            .get_events("orphan", None)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+    assert!(stores.scan(ScanOptions::default()).await.is_clean());
+}
+
+#[tokio::test]
+async fn events_of_a_task_still_in_the_short_term_store_are_not_orphans() {
+    let stores = Stores::new(MapLongTermStore::default());
+    stores.running_task("live", Vec::new()).await;
+    stores
+        .long_term
+        .save_event(event("live", 0, "log", json!({})))
+        .await
+        .unwrap();
+
+    let report = stores.scan(ScanOptions::default()).await;
+    assert!(report.is_clean(), "{report:?}");
+    assert_eq!(report.long_term_tasks_scanned, 1);
+}
+
+#[tokio::test]
+async fn tasks_within_the_persistence_lag_are_not_reported() {
+    let stores = Stores::new(MapLongTermStore::default());
+    stores
+        .short_term
+        .save_task(task("recent", TaskStatus::Completed, Some(10_000.0)))
+        .await
+        .unwrap();
+
+    let within = ScanOptions {
+        persistence_lag_ms: 5_000,
+        now: Some(14_000.0),
+        ..Default::default()
+    };
+    assert!(stores.scan(within).await.is_clean());
+
+    let past = ScanOptions {
+        persistence_lag_ms: 5_000,
+        now: Some(16_000.0),
+        ..Default::default()
+    };
+    assert_eq!(stores.scan(past).await.counts.missing_from_long_term, 1);
+}
+
+// ─── Paging and reporting ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn long_term_task_ids_are_paged_and_findings_capped() {
+    let stores = Stores::new(MapLongTermStore::default());
+    for id in ["o1", "o2", "o3", "o4", "o5"] {
+        stores
+            .long_term
+            .save_event(event(id, 0, "log", json!({})))
+            .await
+            .unwrap();
+    }
+
+    let report = stores
+        .scan(ScanOptions {
+            batch_size: 2,
+            max_findings: 3,
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(report.long_term_tasks_scanned, 5);
+    assert_eq!(report.counts.orphaned_events, 5);
+    assert_eq!(report.findings.len(), 3);
+    assert!(report.findings_truncated);
+
+    let report = stores
+        .scan(ScanOptions {
+            batch_size: 2,
+            ..repair()
+        })
+        .await;
+    assert_eq!(report.repaired, 5);
+    assert!(stores.scan(ScanOptions::default()).await.is_clean());
+}
+
+#[tokio::test]
+async fn unsupported_checks_are_reported_as_skipped() {
+    let stores = Stores::new(MapLongTermStore {
+        unlisted: true,
+        ..Default::default()
+    });
+    let report = stores.scan(ScanOptions::default()).await;
+    assert!(report.is_clean());
+    assert_eq!(report.skipped.len(), 1);
+    assert!(report.skipped[0].starts_with("orphanedEvents:"));
+
+    let report = scan_consistency(&stores.short_term, None, ScanOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(report.skipped.len(), 1);
+    assert!(report.skipped[0].contains("no long-term store"));
+}
+
+#[test]
+fn findings_serialize_with_their_kind() {
+    let issue = ConsistencyIssue::StaleIndexCounter {
+        task_id: "t1".to_string(),
+        next_index: 2,
+        max_index: 4,
+    };
+    assert_eq!(
+        serde_json::to_value(issue).unwrap(),
+        json!({ "kind": "staleIndexCounter", "taskId": "t1", "nextIndex": 2, "maxIndex": 4 })
+    );
+}
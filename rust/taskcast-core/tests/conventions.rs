@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::config::REDACTED;
+use taskcast_core::{
+    ConventionNotes, CreateTaskInput, DiscoveryOptions, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+
+fn make_engine(discovery: Option<DiscoveryOptions>) -> TaskEngine {
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    match discovery {
+        Some(options) => engine.with_discovery(options),
+        None => engine,
+    }
+}
+
+fn every_event() -> Option<DiscoveryOptions> {
+    Some(DiscoveryOptions {
+        sample_rate: 1.0,
+        max_example_bytes: 128,
+        redact_keys: Vec::new(),
+    })
+}
+
+async fn create_running_task(engine: &TaskEngine, task_id: &str, task_type: Option<&str>) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            r#type: task_type.map(str::to_string),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, r#type: &str, data: serde_json::Value) {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: r#type.to_string(),
+                level: Level::Info,
+                data,
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn published_events_populate_the_catalog() {
+    let engine = make_engine(every_event());
+    create_running_task(&engine, "chat-1", Some("llm.chat")).await;
+    create_running_task(&engine, "import-1", Some("import.csv")).await;
+    create_running_task(&engine, "untyped", None).await;
+
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "He" })).await;
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "llo" })).await;
+    publish(
+        &engine,
+        "chat-1",
+        "llm.usage",
+        json!({ "token": "t-1", "in": 3 }),
+    )
+    .await;
+    publish(
+        &engine,
+        "import-1",
+        "rows",
+        json!({ "blob": "x".repeat(200) }),
+    )
+    .await;
+    publish(&engine, "untyped", "log", json!({})).await;
+
+    let catalog = engine.list_conventions(None).await.unwrap().unwrap();
+    let keys: Vec<(&str, &str, u64)> = catalog
+        .iter()
+        .map(|c| {
+            (
+                c.task_type.as_str(),
+                c.event_type.as_str(),
+                c.estimated_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            ("import.csv", "rows", 1),
+            ("llm.chat", "llm.delta", 2),
+            ("llm.chat", "llm.usage", 1),
+        ]
+    );
+    assert_eq!(catalog[1].example, Some(json!({ "text": "llo" })));
+    assert_eq!(
+        catalog[2].example,
+        Some(json!({ "token": REDACTED, "in": 3 }))
+    );
+    // Over the 128-byte cap.
+    assert_eq!(catalog[0].example, None);
+    assert!(catalog[0].last_seen_at.is_some());
+}
+
+#[tokio::test]
+async fn flushed_samples_add_up_with_later_ones() {
+    let engine = make_engine(every_event());
+    create_running_task(&engine, "chat-1", Some("llm.chat")).await;
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "a" })).await;
+    assert_eq!(engine.flush_conventions().await.unwrap(), 1);
+    assert_eq!(engine.flush_conventions().await.unwrap(), 0);
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "b" })).await;
+
+    let catalog = engine
+        .list_conventions(Some("llm.chat"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(catalog.len(), 1);
+    assert_eq!(catalog[0].estimated_count, 2);
+    assert_eq!(catalog[0].example, Some(json!({ "text": "b" })));
+}
+
+#[tokio::test]
+async fn notes_round_trip_alongside_samples() {
+    let engine = make_engine(every_event());
+    let notes = ConventionNotes {
+        description: Some("Incremental completion text".to_string()),
+        schema: Some(json!({ "type": "object", "required": ["text"] })),
+    };
+    let convention = engine
+        .annotate_convention("llm.chat", "llm.delta", &notes)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(convention.estimated_count, 0);
+    assert_eq!(convention.description, notes.description);
+
+    create_running_task(&engine, "chat-1", Some("llm.chat")).await;
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "a" })).await;
+    engine.flush_conventions().await.unwrap();
+
+    let catalog = engine
+        .list_conventions(Some("llm.chat"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(catalog[0].estimated_count, 1);
+    assert_eq!(catalog[0].description, notes.description);
+    assert_eq!(catalog[0].schema, notes.schema);
+}
+
+#[tokio::test]
+async fn discovery_is_off_by_default() {
+    let engine = make_engine(None);
+    assert!(engine.conventions().is_none());
+    create_running_task(&engine, "chat-1", Some("llm.chat")).await;
+    publish(&engine, "chat-1", "llm.delta", json!({ "text": "a" })).await;
+
+    assert_eq!(engine.list_conventions(None).await.unwrap(), None);
+    assert_eq!(engine.flush_conventions().await.unwrap(), 0);
+    let annotated = engine
+        .annotate_convention("llm.chat", "llm.delta", &ConventionNotes::default())
+        .await
+        .unwrap();
+    assert_eq!(annotated, None);
+}
@@ -0,0 +1,278 @@
+//! Deadline warnings published ahead of running tasks' deadlines.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+use taskcast_core::{
+    BroadcastProvider, Clock, CreateTaskInput, DeadlineMonitor, DeadlineMonitorOptions,
+    DeadlineWarning, Level, MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore,
+    TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus, TransitionPayload,
+    DEADLINE_WARNING_EVENT_TYPE,
+};
+
+/// Time allotted to every task below.
+const DEADLINE_MS: u64 = 100_000;
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    /// Starts at the wall clock, which task creation times come from.
+    fn new() -> Arc<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as f64;
+        Arc::new(Self(Mutex::new(now)))
+    }
+
+    fn advance(&self, ms: u64) {
+        *self.0.lock().unwrap() += ms as f64;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+fn warnings() -> Vec<DeadlineWarning> {
+    vec![
+        DeadlineWarning::AtFraction { at_fraction: 0.8 },
+        DeadlineWarning::BeforeMs { before_ms: 60_000 },
+    ]
+}
+
+/// An engine on `store`, standing in for one instance of a deployment.
+fn make_engine(store: &Arc<MemoryShortTermStore>, clock: &Arc<ManualClock>) -> Arc<TaskEngine> {
+    Arc::new(
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+            broadcast: Arc::new(MemoryBroadcastProvider::new()) as Arc<dyn BroadcastProvider>,
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_clock(Arc::clone(clock) as Arc<dyn Clock>),
+    )
+}
+
+fn make_monitor(engine: &Arc<TaskEngine>, store: &Arc<MemoryShortTermStore>) -> DeadlineMonitor {
+    DeadlineMonitor::new(DeadlineMonitorOptions {
+        engine: Arc::clone(engine),
+        short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+        check_interval_ms: 5_000,
+        warnings: warnings(),
+    })
+}
+
+async fn create_running(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            deadline_ms: Some(DEADLINE_MS),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+async fn warnings_of(engine: &TaskEngine, task_id: &str) -> Vec<TaskEvent> {
+    engine
+        .get_events(task_id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.r#type == DEADLINE_WARNING_EVENT_TYPE)
+        .collect()
+}
+
+// ─── Timing ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn each_warning_fires_once_when_due() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engine = make_engine(&store, &clock);
+    let monitor = make_monitor(&engine, &store);
+    create_running(&engine, "t1").await;
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let _unsubscribe = engine
+        .subscribe(
+            "t1",
+            Box::new(move |event| sink.lock().unwrap().push(event)),
+        )
+        .await;
+
+    // Neither is due 30s in.
+    clock.advance(30_000);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+
+    // 60s before the deadline passes at 40s.
+    clock.advance(15_000);
+    assert_eq!(monitor.tick().await.unwrap(), 1);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+
+    // 80% of the allotted time passes at 80s.
+    clock.advance(40_000);
+    assert_eq!(monitor.tick().await.unwrap(), 1);
+    clock.advance(5_000);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+
+    let events = warnings_of(&engine, "t1").await;
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.level == Level::Warn));
+    assert_eq!(events[0].data["trigger"], json!({ "beforeMs": 60000 }));
+    assert_eq!(events[1].data["trigger"], json!({ "atFraction": 0.8 }));
+    // Creation reads the wall clock a little after the manual clock started.
+    let remaining = events[0].data["remainingMs"].as_u64().unwrap();
+    assert!((55_000..56_000).contains(&remaining), "{remaining}");
+    let remaining = events[1].data["remainingMs"].as_u64().unwrap();
+    assert!((15_000..16_000).contains(&remaining), "{remaining}");
+
+    let broadcast: Vec<String> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| event.r#type == DEADLINE_WARNING_EVENT_TYPE)
+        .map(|event| event.id.clone())
+        .collect();
+    assert_eq!(
+        broadcast,
+        events
+            .iter()
+            .map(|event| event.id.clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn nothing_fires_after_the_deadline() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engine = make_engine(&store, &clock);
+    let monitor = make_monitor(&engine, &store);
+    create_running(&engine, "t1").await;
+
+    // A monitor that first looks after the deadline stays quiet.
+    clock.advance(DEADLINE_MS + 1_000);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+    assert!(warnings_of(&engine, "t1").await.is_empty());
+}
+
+#[tokio::test]
+async fn tasks_without_a_deadline_are_ignored() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engine = make_engine(&store, &clock);
+    let monitor = make_monitor(&engine, &store);
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("open-ended".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("open-ended", TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    clock.advance(10 * DEADLINE_MS);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn ttl_counts_as_the_deadline() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engine = make_engine(&store, &clock);
+    let monitor = make_monitor(&engine, &store);
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ttl: Some(100),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    clock.advance(45_000);
+    assert_eq!(monitor.tick().await.unwrap(), 1);
+    assert_eq!(
+        warnings_of(&engine, "t1").await[0].data["trigger"],
+        json!({ "beforeMs": 60000 })
+    );
+}
+
+// ─── Completion ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn completion_suppresses_pending_warnings() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engine = make_engine(&store, &clock);
+    let monitor = make_monitor(&engine, &store);
+    create_running(&engine, "t1").await;
+
+    clock.advance(45_000);
+    assert_eq!(monitor.tick().await.unwrap(), 1);
+    engine
+        .transition_task(
+            "t1",
+            TaskStatus::Completed,
+            Some(TransitionPayload {
+                result: Some(HashMap::from([("ok".to_string(), json!(true))])),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+    clock.advance(40_000);
+    assert_eq!(monitor.tick().await.unwrap(), 0);
+    assert_eq!(warnings_of(&engine, "t1").await.len(), 1);
+}
+
+// ─── Multiple instances ─────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn racing_monitors_publish_each_warning_once() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new();
+    let engines = [make_engine(&store, &clock), make_engine(&store, &clock)];
+    let monitors = [
+        make_monitor(&engines[0], &store),
+        make_monitor(&engines[1], &store),
+    ];
+    for i in 0..5 {
+        create_running(&engines[0], &format!("t{i}")).await;
+    }
+
+    let mut published = 0;
+    for advance in [45_000, 40_000] {
+        clock.advance(advance);
+        let (a, b) = tokio::join!(monitors[0].tick(), monitors[1].tick());
+        published += a.unwrap() + b.unwrap();
+    }
+
+    assert_eq!(published, 10);
+    for i in 0..5 {
+        assert_eq!(warnings_of(&engines[0], &format!("t{i}")).await.len(), 2);
+    }
+}
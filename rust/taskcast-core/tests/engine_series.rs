@@ -12,6 +12,9 @@ fn make_engine() -> TaskEngine {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     })
 }
 
@@ -54,6 +57,9 @@ async fn latest_keeps_only_last_event_after_multiple_publishes() {
                     series_id: Some("msg".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -85,6 +91,9 @@ async fn latest_first_event_stored_exactly_once() {
                 series_id: Some("s1".to_string()),
                 series_mode: Some(SeriesMode::Latest),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -116,6 +125,9 @@ async fn latest_multiple_independent_series_each_deduplicated() {
                     series_id: Some("seriesA".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -130,6 +142,9 @@ async fn latest_multiple_independent_series_each_deduplicated() {
                     series_id: Some("seriesB".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -169,6 +184,9 @@ async fn latest_get_series_latest_returns_the_latest_value() {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -196,6 +214,9 @@ async fn latest_indices_are_unique_after_replacements() {
                     series_id: Some("s1".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -212,6 +233,9 @@ async fn latest_indices_are_unique_after_replacements() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -235,6 +259,9 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
             r#type: "log".to_string(), level: Level::Info,
             data: json!({ "n": 1 }),
             series_id: None, series_mode: None, series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
         })
         .await.unwrap();
     engine
@@ -243,6 +270,9 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
             data: json!({ "v": 1 }),
             series_id: Some("s1".to_string()),
             series_mode: Some(SeriesMode::Latest), series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
         })
         .await.unwrap();
     engine
@@ -250,6 +280,9 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
             r#type: "log".to_string(), level: Level::Info,
             data: json!({ "n": 2 }),
             series_id: None, series_mode: None, series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
         })
         .await.unwrap();
     engine
@@ -258,6 +291,9 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
             data: json!({ "v": 2 }),
             series_id: Some("s1".to_string()),
             series_mode: Some(SeriesMode::Latest), series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
         })
         .await.unwrap();
     engine
@@ -265,6 +301,9 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
             r#type: "log".to_string(), level: Level::Info,
             data: json!({ "n": 3 }),
             series_id: None, series_mode: None, series_acc_field: None,
+            labels: None,
+            broadcast_debounce_ms: None,
+            series_end: false,
         })
         .await.unwrap();
 
@@ -281,6 +320,58 @@ async fn latest_interleaved_with_non_series_preserves_all_non_series() {
     assert_eq!(latest[0].data, json!({ "v": 2 }));
 }
 
+#[tokio::test]
+async fn latest_rewrite_is_broadcast_with_the_replaced_event_id() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let _unsubscribe = engine
+        .subscribe(
+            "t1",
+            Box::new(move |event| sink.lock().unwrap().push(event)),
+        )
+        .await;
+
+    let mut published = Vec::new();
+    for v in 1..=2 {
+        published.push(
+            engine
+                .publish_event(
+                    "t1",
+                    PublishEventInput {
+                        r#type: "update".to_string(),
+                        level: Level::Info,
+                        data: json!({ "v": v }),
+                        series_id: Some("s1".to_string()),
+                        series_mode: Some(SeriesMode::Latest),
+                        series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
+                    },
+                )
+                .await
+                .unwrap(),
+        );
+    }
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].replaces_event_id, None);
+    assert_eq!(
+        received[1].replaces_event_id.as_deref(),
+        Some(published[0].id.as_str())
+    );
+    // Only the live broadcast carries it: history and the publish result
+    // hold the new event alone.
+    assert_eq!(published[1].replaces_event_id, None);
+    let history = engine.get_events("t1", None).await.unwrap();
+    assert!(history.iter().all(|e| e.replaces_event_id.is_none()));
+    let raw = serde_json::to_value(&published[1]).unwrap();
+    assert!(raw.get("replacesEventId").is_none());
+}
+
 // ─── seriesMode: keep-all ─────────────────────────────────────────────────
 
 #[tokio::test]
@@ -299,6 +390,9 @@ async fn keep_all_retains_every_event_in_history() {
                     series_id: Some("logs".to_string()),
                     series_mode: Some(SeriesMode::KeepAll),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -331,6 +425,9 @@ async fn accumulate_stores_all_deltas_in_history() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -345,6 +442,9 @@ async fn accumulate_stores_all_deltas_in_history() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -376,6 +476,9 @@ async fn accumulate_get_series_latest_returns_accumulated_value() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -390,6 +493,9 @@ async fn accumulate_get_series_latest_returns_accumulated_value() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -419,6 +525,9 @@ async fn mixed_series_modes_coexist_correctly() {
                     series_id: Some("status".to_string()),
                     series_mode: Some(SeriesMode::Latest),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -437,6 +546,9 @@ async fn mixed_series_modes_coexist_correctly() {
                     series_id: Some("logs".to_string()),
                     series_mode: Some(SeriesMode::KeepAll),
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -454,6 +566,9 @@ async fn mixed_series_modes_coexist_correctly() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -468,6 +583,9 @@ async fn mixed_series_modes_coexist_correctly() {
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -484,6 +602,9 @@ async fn mixed_series_modes_coexist_correctly() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -524,6 +645,9 @@ async fn interleaved_series_modes_produce_correct_counts_and_unique_indices() {
                 data: json!({ "v": i }),
                 series_id: Some("status".to_string()),
                 series_mode: Some(SeriesMode::Latest), series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             })
             .await.unwrap();
         engine
@@ -532,6 +656,9 @@ async fn interleaved_series_modes_produce_correct_counts_and_unique_indices() {
                 data: json!({ "line": i }),
                 series_id: Some("logs".to_string()),
                 series_mode: Some(SeriesMode::KeepAll), series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             })
             .await.unwrap();
         engine
@@ -540,6 +667,9 @@ async fn interleaved_series_modes_produce_correct_counts_and_unique_indices() {
                 data: json!({ "delta": format!("{}", (b'a' + i as u8 - 1) as char) }),
                 series_id: Some("output".to_string()),
                 series_mode: Some(SeriesMode::Accumulate), series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             })
             .await.unwrap();
         if i <= 2 {
@@ -548,6 +678,9 @@ async fn interleaved_series_modes_produce_correct_counts_and_unique_indices() {
                     r#type: "plain".to_string(), level: Level::Info,
                     data: json!({ "n": i }),
                     series_id: None, series_mode: None, series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 })
                 .await.unwrap();
         }
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EventTypeCounts, Level, MemoryBroadcastProvider, MemoryShortTermStore,
+    PublishEventInput, SeriesMode, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+async fn running_task() -> TaskEngine {
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine
+}
+
+fn input(r#type: &str, level: Level, series_id: Option<&str>) -> PublishEventInput {
+    PublishEventInput {
+        r#type: r#type.to_string(),
+        level,
+        data: json!({}),
+        series_id: series_id.map(str::to_string),
+        series_mode: series_id.map(|_| SeriesMode::Latest),
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+async fn counts_from_history(engine: &TaskEngine) -> EventTypeCounts {
+    EventTypeCounts::from_events(&engine.get_events("t1", None).await.unwrap())
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn counts_track_published_events() {
+    let engine = running_task().await;
+    for _ in 0..3 {
+        engine
+            .publish_event("t1", input("llm.delta", Level::Info, None))
+            .await
+            .unwrap();
+    }
+    engine
+        .publish_event("t1", input("tool.error", Level::Error, None))
+        .await
+        .unwrap();
+
+    let counts = engine.event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types["llm.delta"], 3);
+    assert_eq!(counts.types["tool.error"], 1);
+    assert_eq!(counts.levels[&Level::Error], 1);
+    assert_eq!(counts, counts_from_history(&engine).await);
+}
+
+#[tokio::test]
+async fn latest_series_replacements_move_counts_between_types() {
+    let engine = running_task().await;
+    engine
+        .publish_event("t1", input("phase.plan", Level::Info, Some("phase")))
+        .await
+        .unwrap();
+    engine
+        .publish_event("t1", input("phase.plan", Level::Info, Some("phase")))
+        .await
+        .unwrap();
+    let counts = engine.event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types["phase.plan"], 1);
+
+    engine
+        .publish_event("t1", input("phase.execute", Level::Warn, Some("phase")))
+        .await
+        .unwrap();
+    let counts = engine.event_type_counts("t1").await.unwrap();
+    assert!(!counts.types.contains_key("phase.plan"));
+    assert_eq!(counts.types["phase.execute"], 1);
+    assert_eq!(counts.levels[&Level::Warn], 1);
+    assert_eq!(counts, counts_from_history(&engine).await);
+}
+
+#[tokio::test]
+async fn deleting_the_task_drops_its_counts() {
+    let engine = running_task().await;
+    engine
+        .publish_event("t1", input("llm.delta", Level::Info, None))
+        .await
+        .unwrap();
+    assert!(!engine.event_type_counts("t1").await.unwrap().is_empty());
+
+    assert!(engine.delete_task("t1").await.unwrap());
+    assert!(engine.event_type_counts("t1").await.unwrap().is_empty());
+}
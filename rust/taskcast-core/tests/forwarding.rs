@@ -0,0 +1,257 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EngineError, ForwardRule, ForwardTransform, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, SubscribeFilter, TaskEngine, TaskEngineOptions,
+    TaskEvent, TaskStatus, TaskcastHooks,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct DroppedHooks {
+    dropped: Mutex<Vec<(String, String)>>,
+}
+
+impl TaskcastHooks for DroppedHooks {
+    fn on_event_dropped(&self, event: &TaskEvent, reason: &str) {
+        self.dropped
+            .lock()
+            .unwrap()
+            .push((event.r#type.clone(), reason.to_string()));
+    }
+}
+
+fn make_engine(hooks: Option<Arc<DroppedHooks>>) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: hooks.map(|hooks| hooks as Arc<dyn TaskcastHooks>),
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+fn forward_to(target: &str, filter: SubscribeFilter, prefix: Option<&str>) -> ForwardRule {
+    ForwardRule {
+        target_task_id: target.to_string(),
+        filter,
+        transform: prefix.map(|prefix| ForwardTransform {
+            prefix_type: Some(prefix.to_string()),
+            level: None,
+        }),
+    }
+}
+
+async fn create(
+    engine: &TaskEngine,
+    id: &str,
+    rule: Option<ForwardRule>,
+) -> Result<(), EngineError> {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            forward_to: rule,
+            ..Default::default()
+        })
+        .await?;
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await?;
+    Ok(())
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, r#type: &str, level: Level) -> TaskEvent {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: r#type.to_string(),
+                level,
+                data: json!({ "percent": 40 }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+async fn events_of(engine: &TaskEngine, task_id: &str) -> Vec<TaskEvent> {
+    engine
+        .get_events(task_id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.r#type != "taskcast:status")
+        .collect()
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn forwarded_events_wrap_the_source_event() {
+    let engine = make_engine(None);
+    create(&engine, "parent", None).await.unwrap();
+    let filter = SubscribeFilter {
+        include_status: Some(false),
+        ..Default::default()
+    };
+    create(
+        &engine,
+        "child",
+        Some(forward_to("parent", filter, Some("child"))),
+    )
+    .await
+    .unwrap();
+
+    let source = publish(&engine, "child", "progress", Level::Info).await;
+
+    let forwarded = events_of(&engine, "parent").await;
+    assert_eq!(forwarded.len(), 1);
+    let event = &forwarded[0];
+    assert_eq!(event.task_id, "parent");
+    assert_eq!(event.r#type, "child:progress");
+    assert_eq!(event.level, Level::Info);
+    assert_eq!(
+        event.data,
+        json!({
+            "sourceTaskId": "child",
+            "sourceEventId": source.id,
+            "data": { "percent": 40 },
+        })
+    );
+    // The index is the target's next: its running status event took 0.
+    assert_eq!(event.index, 1);
+}
+
+#[tokio::test]
+async fn only_events_passing_the_filter_are_forwarded() {
+    let engine = make_engine(None);
+    create(&engine, "parent", None).await.unwrap();
+    let filter = SubscribeFilter {
+        types: Some(vec!["progress".to_string()]),
+        levels: Some(vec![Level::Warn]),
+        ..Default::default()
+    };
+    create(&engine, "child", Some(forward_to("parent", filter, None)))
+        .await
+        .unwrap();
+
+    publish(&engine, "child", "progress", Level::Info).await;
+    publish(&engine, "child", "log", Level::Warn).await;
+    publish(&engine, "child", "progress", Level::Warn).await;
+
+    let forwarded = events_of(&engine, "parent").await;
+    assert_eq!(forwarded.len(), 1);
+    assert_eq!(forwarded[0].r#type, "progress");
+    assert_eq!(forwarded[0].level, Level::Warn);
+}
+
+#[tokio::test]
+async fn forwarding_cycles_are_rejected_at_creation() {
+    let engine = make_engine(None);
+    create(
+        &engine,
+        "a",
+        Some(forward_to("b", Default::default(), None)),
+    )
+    .await
+    .unwrap();
+
+    let err = create(
+        &engine,
+        "b",
+        Some(forward_to("a", Default::default(), None)),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        matches!(&err, EngineError::InvalidInput(message) if message.contains("cycle")),
+        "{err}"
+    );
+    assert!(engine.get_task("b").await.unwrap().is_none());
+
+    let err = create(
+        &engine,
+        "self",
+        Some(forward_to("self", Default::default(), None)),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, EngineError::InvalidInput(_)));
+}
+
+#[tokio::test]
+async fn forwarded_events_are_not_forwarded_again() {
+    let engine = make_engine(None);
+    create(&engine, "root", None).await.unwrap();
+    create(
+        &engine,
+        "parent",
+        Some(forward_to("root", Default::default(), Some("parent"))),
+    )
+    .await
+    .unwrap();
+    create(
+        &engine,
+        "child",
+        Some(forward_to("parent", Default::default(), Some("child"))),
+    )
+    .await
+    .unwrap();
+
+    publish(&engine, "child", "progress", Level::Info).await;
+    publish(&engine, "parent", "progress", Level::Info).await;
+
+    let on_parent: Vec<_> = events_of(&engine, "parent")
+        .await
+        .into_iter()
+        .map(|event| event.r#type)
+        .collect();
+    assert_eq!(
+        on_parent,
+        ["child:taskcast:status", "child:progress", "progress"]
+    );
+    let on_root: Vec<_> = events_of(&engine, "root")
+        .await
+        .into_iter()
+        .map(|event| event.r#type)
+        .collect();
+    assert_eq!(on_root, ["parent:taskcast:status", "parent:progress"]);
+}
+
+#[tokio::test]
+async fn forwards_to_a_finished_target_are_dropped() {
+    let hooks = Arc::new(DroppedHooks::default());
+    let engine = make_engine(Some(Arc::clone(&hooks)));
+    create(&engine, "parent", None).await.unwrap();
+    create(
+        &engine,
+        "child",
+        Some(forward_to("parent", Default::default(), None)),
+    )
+    .await
+    .unwrap();
+    engine
+        .transition_task("parent", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    // The source event is still published.
+    publish(&engine, "child", "progress", Level::Info).await;
+    assert_eq!(events_of(&engine, "child").await.len(), 1);
+
+    assert!(events_of(&engine, "parent").await.is_empty());
+    let dropped = hooks.dropped.lock().unwrap();
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(dropped[0].0, "progress");
+    assert!(dropped[0].1.contains("parent"), "{}", dropped[0].1);
+}
@@ -28,6 +28,9 @@ fn make_monitor(
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let worker_manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -829,6 +832,9 @@ async fn start_runs_tick_cycles_and_processes_workers() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let worker_manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
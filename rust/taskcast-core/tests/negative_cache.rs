@@ -0,0 +1,384 @@
+//! Negative caching of missing tasks in `TaskEngine::get_task`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use taskcast_core::{
+    Clock, CreateTaskInput, EventQueryOptions, LongTermStore, MemoryBroadcastProvider,
+    MemoryShortTermStore, ShortTermStore, Task, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskFilter, Worker, WorkerAssignment, WorkerAuditEvent, WorkerFilter,
+};
+
+// ─── Counting stores ─────────────────────────────────────────────────────────
+
+/// Memory short-term store that counts task reads.
+#[derive(Default)]
+struct CountingShortTermStore {
+    inner: MemoryShortTermStore,
+    task_reads: AtomicUsize,
+}
+
+#[async_trait]
+impl ShortTermStore for CountingShortTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.task_reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+/// Long-term store that never has the task and counts how often it is asked.
+#[derive(Default)]
+struct EmptyLongTermStore {
+    task_reads: AtomicUsize,
+}
+
+#[async_trait]
+impl LongTermStore for EmptyLongTermStore {
+    async fn save_task(&self, _task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.task_reads.fetch_add(1, Ordering::SeqCst);
+        Ok(None)
+    }
+
+    async fn save_event(
+        &self,
+        _event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+const TTL: Duration = Duration::from_secs(3);
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    fn advance(&self, ms: f64) {
+        *self.0.lock().unwrap() += ms;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+struct Setup {
+    engine: TaskEngine,
+    short: Arc<CountingShortTermStore>,
+    long: Arc<EmptyLongTermStore>,
+    clock: Arc<ManualClock>,
+}
+
+fn setup(ttl: Option<Duration>) -> Setup {
+    let short = Arc::new(CountingShortTermStore::default());
+    let long = Arc::new(EmptyLongTermStore::default());
+    let clock = Arc::new(ManualClock(Mutex::new(1_000_000.0)));
+    let mut engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: short.clone(),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: Some(long.clone()),
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_clock(clock.clone());
+    if let Some(ttl) = ttl {
+        engine = engine.with_negative_cache(ttl);
+    }
+    Setup {
+        engine,
+        short,
+        long,
+        clock,
+    }
+}
+
+fn engine_on(store: Arc<dyn ShortTermStore>, clock: Arc<ManualClock>) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_clock(clock)
+    .with_negative_cache(TTL)
+}
+
+async fn create(engine: &TaskEngine, id: &str) -> Task {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn repeated_misses_read_the_stores_once_per_ttl() {
+    let s = setup(Some(TTL));
+    for _ in 0..10 {
+        assert!(s.engine.get_task("ghost").await.unwrap().is_none());
+    }
+    assert_eq!(s.short.task_reads.load(Ordering::SeqCst), 1);
+    assert_eq!(s.long.task_reads.load(Ordering::SeqCst), 1);
+    let stats = s.engine.negative_cache_stats().unwrap();
+    assert_eq!(stats.hits, 9);
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.ttl_ms, 3000.0);
+
+    s.clock.advance(2999.0);
+    assert!(s.engine.get_task("ghost").await.unwrap().is_none());
+    assert_eq!(s.short.task_reads.load(Ordering::SeqCst), 1);
+
+    // Expired: the stores are asked again, and the miss is cached anew.
+    s.clock.advance(1.0);
+    assert!(s.engine.get_task("ghost").await.unwrap().is_none());
+    assert!(s.engine.get_task("ghost").await.unwrap().is_none());
+    assert_eq!(s.short.task_reads.load(Ordering::SeqCst), 2);
+    assert_eq!(s.long.task_reads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn creating_a_cached_missing_task_is_seen_at_once() {
+    let s = setup(Some(TTL));
+    assert!(s.engine.get_task("t1").await.unwrap().is_none());
+
+    create(&s.engine, "t1").await;
+    let task = s.engine.get_task("t1").await.unwrap();
+    assert_eq!(task.map(|t| t.id).as_deref(), Some("t1"));
+    assert_eq!(s.engine.negative_cache_stats().unwrap().hits, 0);
+}
+
+#[tokio::test]
+async fn importing_a_cached_missing_task_is_seen_at_once() {
+    let clock = Arc::new(ManualClock(Mutex::new(1_000_000.0)));
+    let source = engine_on(Arc::new(MemoryShortTermStore::new()), clock.clone());
+    create(&source, "t1").await;
+    let archive = source.export_task_archive("t1").await.unwrap();
+
+    let engine = engine_on(Arc::new(MemoryShortTermStore::new()), clock);
+    assert!(engine.get_task("t1").await.unwrap().is_none());
+    engine.import_task_archive(archive, None).await.unwrap();
+    assert!(engine.get_task("t1").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn other_instances_rediscover_the_task_after_the_ttl() {
+    let clock = Arc::new(ManualClock(Mutex::new(1_000_000.0)));
+    let store: Arc<dyn ShortTermStore> = Arc::new(MemoryShortTermStore::new());
+    let creator = engine_on(Arc::clone(&store), clock.clone());
+    let poller = engine_on(store, clock.clone());
+
+    assert!(poller.get_task("t1").await.unwrap().is_none());
+    create(&creator, "t1").await;
+    // The creation happened elsewhere, so this instance's entry stands.
+    assert!(poller.get_task("t1").await.unwrap().is_none());
+
+    clock.advance(TTL.as_millis() as f64);
+    assert!(poller.get_task("t1").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn without_the_cache_every_miss_reads_the_stores() {
+    let s = setup(None);
+    for _ in 0..3 {
+        assert!(s.engine.get_task("ghost").await.unwrap().is_none());
+    }
+    assert_eq!(s.short.task_reads.load(Ordering::SeqCst), 3);
+    assert_eq!(s.long.task_reads.load(Ordering::SeqCst), 3);
+    assert!(s.engine.negative_cache_stats().is_none());
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use serde_json::json;
+use taskcast_core::{
+    BroadcastProvider, CreateTaskInput, EngineError, MemoryBroadcastProvider, MemoryShortTermStore,
+    ShortTermStore, TaskEngine, TaskEngineOptions, TaskStatus, TransitionPayload,
+    MAX_SAVE_ATTEMPTS,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+/// An engine on `store`, standing in for one instance of a deployment.
+fn make_engine(store: &Arc<MemoryShortTermStore>) -> Arc<TaskEngine> {
+    let broadcast = Arc::new(MemoryBroadcastProvider::new());
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+        broadcast: broadcast as Arc<dyn BroadcastProvider>,
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+async fn create(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+/// Saves a change to the task behind the engine's back, as another instance
+/// would between the engine's read and its save. The memory store never
+/// suspends, so this completes inside a synchronous update closure.
+fn write_behind(store: &MemoryShortTermStore, task_id: &str, key: &str) {
+    let mut task = store
+        .get_task(task_id)
+        .now_or_never()
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    task.metadata
+        .get_or_insert_with(HashMap::new)
+        .insert(key.to_string(), json!(true));
+    task.version += 1;
+    store.save_task(task).now_or_never().unwrap().unwrap();
+}
+
+// ─── Versions ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn version_rises_by_one_with_every_save_across_a_lifecycle() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(&store);
+    create(&engine, "t1").await;
+    let mut versions = vec![engine.get_task("t1").await.unwrap().unwrap().version];
+
+    for to in [TaskStatus::Running, TaskStatus::Paused, TaskStatus::Running] {
+        versions.push(
+            engine
+                .transition_task("t1", to, None)
+                .await
+                .unwrap()
+                .version,
+        );
+    }
+    let updated = engine
+        .update_task("t1", |task| {
+            task.metadata = Some(HashMap::from([("step".to_string(), json!(1))]));
+        })
+        .await
+        .unwrap();
+    versions.push(updated.version);
+    let completed = engine
+        .transition_task(
+            "t1",
+            TaskStatus::Completed,
+            Some(TransitionPayload {
+                result: Some(HashMap::from([("ok".to_string(), json!(true))])),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    versions.push(completed.version);
+
+    assert_eq!(versions, vec![1, 2, 3, 4, 5, 6]);
+    let stored = store.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(stored.version, 6);
+}
+
+#[tokio::test]
+async fn expected_version_must_match_the_stored_task() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(&store);
+    create(&engine, "t1").await;
+
+    let stale = engine
+        .transition_task_versioned("t1", TaskStatus::Running, None, Some(7))
+        .await;
+    assert!(matches!(stale, Err(EngineError::ConcurrentModification(id)) if id == "t1"));
+    assert_eq!(
+        store.get_task("t1").await.unwrap().unwrap().status,
+        TaskStatus::Pending
+    );
+
+    let task = engine
+        .transition_task_versioned("t1", TaskStatus::Running, None, Some(1))
+        .await
+        .unwrap();
+    assert_eq!(task.version, 2);
+    let stale = engine.update_task_versioned("t1", Some(1), |_| {}).await;
+    assert!(matches!(stale, Err(EngineError::ConcurrentModification(_))));
+}
+
+// ─── Retries ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn update_retries_on_a_fresh_read_until_it_wins() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(&store);
+    create(&engine, "t1").await;
+
+    let mut attempts = 0;
+    let task = engine
+        .update_task("t1", |task| {
+            attempts += 1;
+            if attempts < 3 {
+                write_behind(&store, "t1", &format!("other{attempts}"));
+            }
+            task.metadata
+                .get_or_insert_with(HashMap::new)
+                .insert("mine".to_string(), json!(true));
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(attempts, 3);
+    let metadata = task.metadata.unwrap();
+    for key in ["other1", "other2", "mine"] {
+        assert_eq!(metadata.get(key), Some(&json!(true)), "{key} was lost");
+    }
+    // Created at 1, two writes behind, then this update.
+    assert_eq!(task.version, 4);
+}
+
+#[tokio::test]
+async fn update_gives_up_after_max_attempts() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(&store);
+    create(&engine, "t1").await;
+
+    let mut attempts = 0;
+    let result = engine
+        .update_task("t1", |_| {
+            attempts += 1;
+            write_behind(&store, "t1", &format!("other{attempts}"));
+        })
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(EngineError::ConcurrentModification(_))
+    ));
+    assert_eq!(attempts, MAX_SAVE_ATTEMPTS);
+}
+
+// ─── Interleaving ───────────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn interleaved_updates_from_two_engines_keep_every_field() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engines = [make_engine(&store), make_engine(&store)];
+    create(&engines[0], "t1").await;
+
+    let mut handles = Vec::new();
+    for i in 0..40 {
+        let engine = Arc::clone(&engines[i % 2]);
+        handles.push(tokio::spawn(async move {
+            let key = format!("field{i}");
+            // Under this much contention a single call may run out of
+            // attempts; the caller tries again, and no write may be lost.
+            loop {
+                let result = engine
+                    .update_task("t1", |task| {
+                        task.metadata
+                            .get_or_insert_with(HashMap::new)
+                            .insert(key.clone(), json!(i));
+                    })
+                    .await;
+                match result {
+                    Ok(_) => break,
+                    Err(EngineError::ConcurrentModification(_)) => continue,
+                    Err(err) => panic!("update failed: {err}"),
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let task = store.get_task("t1").await.unwrap().unwrap();
+    let metadata = task.metadata.unwrap();
+    for i in 0..40 {
+        assert_eq!(metadata.get(&format!("field{i}")), Some(&json!(i)));
+    }
+    assert_eq!(task.version, 41);
+}
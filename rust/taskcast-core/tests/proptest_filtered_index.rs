@@ -0,0 +1,421 @@
+//! Properties of `apply_filtered_index` over arbitrary histories and filters:
+//! ordering, resumability from a `since.index` cursor, soundness and
+//! completeness against `matches_filter`, and how cursors commute with
+//! filtering. The same numbering is checked against what the memory store
+//! returns for the history once series processing has rewritten it.
+
+use std::collections::HashMap;
+
+use proptest::prelude::*;
+use serde_json::json;
+use taskcast_core::filter::{
+    apply_event_query, apply_filtered_index, matches_filter, FilteredEvent,
+};
+use taskcast_core::memory_adapters::MemoryShortTermStore;
+use taskcast_core::series::process_series;
+use taskcast_core::types::{
+    EventQueryOptions, Level, SeriesMode, ShortTermStore, SinceCursor, SubscribeFilter, TaskEvent,
+};
+
+const TASK_ID: &str = "task-1";
+
+const TYPES: &[&str] = &[
+    "llm.delta",
+    "llm.done",
+    "tool.call",
+    "log",
+    "taskcast:status",
+];
+
+const TYPE_PATTERNS: &[&str] = &[
+    "llm.*",
+    "llm.delta",
+    "tool.call",
+    "log",
+    "*",
+    "taskcast:status",
+];
+
+// ─── Generators ─────────────────────────────────────────────────────────────
+
+/// One event as published. Each series id always uses the same mode, as a
+/// task's producer would.
+#[derive(Debug, Clone)]
+struct EventSpec {
+    r#type: &'static str,
+    level: Level,
+    series: Option<SeriesMode>,
+    region: Option<&'static str>,
+    /// Indices skipped before this event, as cleanup or retention leaves them.
+    gap: u64,
+}
+
+fn arb_level() -> impl Strategy<Value = Level> {
+    prop_oneof![
+        Just(Level::Debug),
+        Just(Level::Info),
+        Just(Level::Warn),
+        Just(Level::Error),
+    ]
+}
+
+fn arb_series_mode() -> impl Strategy<Value = SeriesMode> {
+    prop_oneof![
+        Just(SeriesMode::KeepAll),
+        Just(SeriesMode::Accumulate),
+        Just(SeriesMode::Latest),
+        Just(SeriesMode::JsonPatch),
+    ]
+}
+
+fn arb_event_spec() -> impl Strategy<Value = EventSpec> {
+    (
+        prop::sample::select(TYPES),
+        arb_level(),
+        prop::option::weighted(0.4, arb_series_mode()),
+        prop::option::of(prop::sample::select(&["eu", "us"][..])),
+        prop_oneof![4 => Just(0u64), 1 => 1u64..4],
+    )
+        .prop_map(|(r#type, level, series, region, gap)| EventSpec {
+            r#type,
+            level,
+            series,
+            region,
+            gap,
+        })
+}
+
+fn arb_specs() -> impl Strategy<Value = Vec<EventSpec>> {
+    prop::collection::vec(arb_event_spec(), 0..40)
+}
+
+fn arb_filter() -> impl Strategy<Value = SubscribeFilter> {
+    (
+        prop::option::of(prop::collection::vec(
+            prop::sample::select(TYPE_PATTERNS),
+            0..3,
+        )),
+        prop::option::of(prop::collection::vec(arb_level(), 0..3)),
+        prop::option::of(any::<bool>()),
+        prop::option::of(prop::sample::select(&["eu", "us"][..])),
+        prop::option::of(0u64..30),
+    )
+        .prop_map(
+            |(types, levels, include_status, region, since)| SubscribeFilter {
+                types: types.map(|t| t.into_iter().map(String::from).collect()),
+                levels,
+                include_status,
+                label_selector: region
+                    .map(|r| HashMap::from([("region".to_string(), r.to_string())])),
+                since: since.map(|index| SinceCursor {
+                    id: None,
+                    index: Some(index),
+                    timestamp: None,
+                }),
+                ..Default::default()
+            },
+        )
+}
+
+// ─── Building histories ─────────────────────────────────────────────────────
+
+fn series_id(mode: &SeriesMode) -> &'static str {
+    match mode {
+        SeriesMode::KeepAll => "s-keep",
+        SeriesMode::Accumulate => "s-acc",
+        SeriesMode::Latest => "s-latest",
+        SeriesMode::JsonPatch => "s-patch",
+    }
+}
+
+/// The events as published, with raw indices that have gaps.
+fn build_events(specs: &[EventSpec]) -> Vec<TaskEvent> {
+    let mut next_index = 0;
+    specs
+        .iter()
+        .map(|spec| {
+            let index = next_index + spec.gap;
+            next_index = index + 1;
+            let data = match spec.series {
+                Some(SeriesMode::JsonPatch) => json!([]),
+                _ => json!({ "delta": format!("#{index}") }),
+            };
+            TaskEvent {
+                id: format!("evt-{index:03}"),
+                task_id: TASK_ID.to_string(),
+                index,
+                timestamp: 1000.0 + index as f64,
+                r#type: spec.r#type.to_string(),
+                level: spec.level.clone(),
+                data,
+                series_id: spec.series.as_ref().map(|mode| series_id(mode).to_string()),
+                series_mode: spec.series.clone(),
+                series_acc_field: None,
+                series_snapshot: None,
+                labels: spec
+                    .region
+                    .map(|r| HashMap::from([("region".to_string(), r.to_string())])),
+                replaces_event_id: None,
+                _accumulated_data: None,
+            }
+        })
+        .collect()
+}
+
+/// The history a store holds once `events` are published: each `latest`
+/// series event takes the place of the previous one.
+fn stored_history(events: &[TaskEvent]) -> Vec<TaskEvent> {
+    let mut history: Vec<TaskEvent> = Vec::new();
+    for event in events {
+        if event.series_mode == Some(SeriesMode::Latest) {
+            history.retain(|e| {
+                e.series_mode != Some(SeriesMode::Latest) || e.series_id != event.series_id
+            });
+        }
+        history.push(event.clone());
+    }
+    history
+}
+
+/// Publishes `events` into a memory store the way the engine does: through
+/// series processing, appending whatever it did not store itself.
+async fn publish(store: &MemoryShortTermStore, events: &[TaskEvent]) {
+    for event in events {
+        let result = process_series(event.clone(), store).await.unwrap();
+        if !result.stored {
+            store.append_event(TASK_ID, result.event).await.unwrap();
+        }
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+fn with_since(filter: &SubscribeFilter, index: Option<u64>) -> SubscribeFilter {
+    SubscribeFilter {
+        since: index.map(|index| SinceCursor {
+            id: None,
+            index: Some(index),
+            timestamp: None,
+        }),
+        ..filter.clone()
+    }
+}
+
+fn since_index(filter: &SubscribeFilter) -> Option<u64> {
+    filter.since.as_ref().and_then(|s| s.index)
+}
+
+/// `(filtered_index, raw_index, id)` of each result, for comparisons.
+fn keys(result: &[FilteredEvent]) -> Vec<(u64, u64, String)> {
+    result
+        .iter()
+        .map(|fe| (fe.filtered_index, fe.raw_index, fe.event.id.clone()))
+        .collect()
+}
+
+fn ids(events: &[TaskEvent]) -> Vec<String> {
+    events.iter().map(|e| e.id.clone()).collect()
+}
+
+// ─── Pure-function properties ───────────────────────────────────────────────
+
+proptest! {
+    /// (a) Filtered and raw indices both strictly increase.
+    #[test]
+    fn indices_strictly_increase(specs in arb_specs(), filter in arb_filter()) {
+        let history = stored_history(&build_events(&specs));
+        let result = apply_filtered_index(&history, &filter);
+        for pair in result.windows(2) {
+            prop_assert!(pair[0].filtered_index < pair[1].filtered_index);
+            prop_assert!(pair[0].raw_index < pair[1].raw_index);
+        }
+    }
+
+    /// (b) Reading a prefix of the history, then resuming from the last
+    /// filtered index it returned over the whole history, yields exactly one
+    /// read over the whole history.
+    #[test]
+    fn resuming_from_a_cursor_equals_one_read(
+        specs in arb_specs(),
+        filter in arb_filter(),
+        split in any::<prop::sample::Index>(),
+    ) {
+        let history = stored_history(&build_events(&specs));
+        let split = split.index(history.len() + 1);
+
+        let first = apply_filtered_index(&history[..split], &filter);
+        let cursor = first.last().map(|fe| fe.filtered_index).or(since_index(&filter));
+        let second = apply_filtered_index(&history, &with_since(&filter, cursor));
+
+        let mut resumed = keys(&first);
+        resumed.extend(keys(&second));
+        prop_assert_eq!(resumed, keys(&apply_filtered_index(&history, &filter)));
+    }
+
+    /// (c) Every returned event matches the filter, and is returned unchanged.
+    #[test]
+    fn every_result_matches_the_filter(specs in arb_specs(), filter in arb_filter()) {
+        let history = stored_history(&build_events(&specs));
+        for fe in apply_filtered_index(&history, &filter) {
+            prop_assert!(matches_filter(&fe.event, &filter));
+            prop_assert_eq!(fe.raw_index, fe.event.index);
+            prop_assert!(history.iter().any(|e| e.id == fe.event.id));
+        }
+    }
+
+    /// (d) Every matching event past the cursor is returned, numbered by its
+    /// position among the matching events.
+    #[test]
+    fn no_matching_event_after_the_cursor_is_omitted(specs in arb_specs(), filter in arb_filter()) {
+        let history = stored_history(&build_events(&specs));
+        let expected: Vec<(u64, u64, String)> = history
+            .iter()
+            .filter(|e| matches_filter(e, &filter))
+            .enumerate()
+            .map(|(position, e)| (position as u64, e.index, e.id.clone()))
+            .filter(|(position, _, _)| since_index(&filter).is_none_or(|since| *position > since))
+            .collect();
+        prop_assert_eq!(keys(&apply_filtered_index(&history, &filter)), expected);
+    }
+
+    /// (e) A `since.index` cursor on the filtered numbering equals filtering
+    /// first and dropping the positions up to the cursor afterwards.
+    #[test]
+    fn filtered_cursor_is_a_suffix_of_the_uncursored_result(
+        specs in arb_specs(),
+        filter in arb_filter(),
+    ) {
+        let history = stored_history(&build_events(&specs));
+        let all = apply_filtered_index(&history, &with_since(&filter, None));
+        let expected: Vec<_> = all
+            .into_iter()
+            .filter(|fe| since_index(&filter).is_none_or(|since| fe.filtered_index > since))
+            .collect();
+        prop_assert_eq!(keys(&apply_filtered_index(&history, &filter)), keys(&expected));
+    }
+
+    /// (e) A raw-index cursor commutes with filtering: the same events come
+    /// back whether the store applies it before the filter or it is applied
+    /// to the filtered result. Only the filtered numbering differs.
+    #[test]
+    fn raw_cursor_commutes_with_filtering(
+        specs in arb_specs(),
+        filter in arb_filter(),
+        raw_since in 0u64..60,
+    ) {
+        let history = stored_history(&build_events(&specs));
+        let filter = with_since(&filter, None);
+        let opts = EventQueryOptions {
+            since: Some(SinceCursor { id: None, index: Some(raw_since), timestamp: None }),
+            ..Default::default()
+        };
+
+        let cursored_first = apply_filtered_index(&apply_event_query(history.clone(), Some(&opts)), &filter);
+        let filtered_first: Vec<_> = apply_filtered_index(&history, &filter)
+            .into_iter()
+            .filter(|fe| fe.raw_index > raw_since)
+            .collect();
+
+        let events = |result: &[FilteredEvent]| -> Vec<String> {
+            result.iter().map(|fe| fe.event.id.clone()).collect()
+        };
+        prop_assert_eq!(events(&cursored_first), events(&filtered_first));
+    }
+}
+
+// ─── Against the memory store ───────────────────────────────────────────────
+
+proptest! {
+    /// The memory store holds the modelled history after series processing,
+    /// so numbering what `get_events` returns gives the pure result.
+    #[test]
+    fn store_history_matches_the_model(specs in arb_specs(), filter in arb_filter()) {
+        let events = build_events(&specs);
+        let history = stored_history(&events);
+        let stored = runtime().block_on(async {
+            let store = MemoryShortTermStore::new();
+            publish(&store, &events).await;
+            store.get_events(TASK_ID, None).await.unwrap()
+        });
+
+        prop_assert_eq!(ids(&stored), ids(&history));
+        prop_assert_eq!(
+            keys(&apply_filtered_index(&stored, &filter)),
+            keys(&apply_filtered_index(&history, &filter))
+        );
+    }
+
+    /// Resuming a subscription against the store, with a filtered cursor
+    /// taken from a read made before the rest of the history was published,
+    /// continues exactly where that read ended.
+    #[test]
+    fn store_resumes_from_a_filtered_cursor(
+        specs in arb_specs(),
+        filter in arb_filter(),
+        split in any::<prop::sample::Index>(),
+    ) {
+        let events = build_events(&specs);
+        // A `latest` rewrite after the split would renumber the first read;
+        // resuming is only promised over an append-only history.
+        let events: Vec<_> = events
+            .into_iter()
+            .filter(|e| e.series_mode != Some(SeriesMode::Latest))
+            .collect();
+        let split = split.index(events.len() + 1);
+
+        let (first, second) = runtime().block_on(async {
+            let store = MemoryShortTermStore::new();
+            publish(&store, &events[..split]).await;
+            let first = apply_filtered_index(&store.get_events(TASK_ID, None).await.unwrap(), &filter);
+            publish(&store, &events[split..]).await;
+            let cursor = first.last().map(|fe| fe.filtered_index).or(since_index(&filter));
+            let second = apply_filtered_index(
+                &store.get_events(TASK_ID, None).await.unwrap(),
+                &with_since(&filter, cursor),
+            );
+            (first, second)
+        });
+
+        let mut resumed = keys(&first);
+        resumed.extend(keys(&second));
+        prop_assert_eq!(resumed, keys(&apply_filtered_index(&events, &filter)));
+    }
+
+    /// A raw `since.index` pushed down to `get_events` returns the same
+    /// events as applying it to the filtered full history.
+    #[test]
+    fn store_raw_cursor_commutes_with_filtering(
+        specs in arb_specs(),
+        filter in arb_filter(),
+        raw_since in 0u64..60,
+    ) {
+        let events = build_events(&specs);
+        let filter = with_since(&filter, None);
+        let opts = EventQueryOptions {
+            since: Some(SinceCursor { id: None, index: Some(raw_since), timestamp: None }),
+            ..Default::default()
+        };
+        let page = runtime().block_on(async {
+            let store = MemoryShortTermStore::new();
+            publish(&store, &events).await;
+            store.get_events(TASK_ID, Some(opts)).await.unwrap()
+        });
+
+        let from_store: Vec<String> = apply_filtered_index(&page, &filter)
+            .into_iter()
+            .map(|fe| fe.event.id)
+            .collect();
+        let expected: Vec<String> = apply_filtered_index(&stored_history(&events), &filter)
+            .into_iter()
+            .filter(|fe| fe.raw_index > raw_since)
+            .map(|fe| fe.event.id)
+            .collect();
+        prop_assert_eq!(from_store, expected);
+    }
+}
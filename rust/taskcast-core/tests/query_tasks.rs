@@ -0,0 +1,321 @@
+//! `TaskEngine::query_tasks`: paging through tasks with filters, and which
+//! store answers the query.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde_json::json;
+use taskcast_core::{
+    EventQueryOptions, ListTasksQuery, LongTermStore, MemoryBroadcastProvider,
+    MemoryShortTermStore, ShortTermStore, Task, TaskCursor, TaskEngine, TaskEngineOptions,
+    TaskEvent, TaskPage, TaskStatus, WorkerAuditEvent,
+};
+
+// ─── Long-term store ─────────────────────────────────────────────────────────
+
+/// Long-term store keeping tasks in a map and recording the queries it
+/// lists tasks for. Listing honours the status filter and the cursor only.
+#[derive(Default)]
+struct MapLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    queries: Mutex<Vec<ListTasksQuery>>,
+}
+
+#[async_trait]
+impl LongTermStore for MapLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        _event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    fn supports_task_listing(&self) -> bool {
+        true
+    }
+
+    async fn list_tasks(
+        &self,
+        query: &ListTasksQuery,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        self.queries.lock().unwrap().push(query.clone());
+        let start = query.start();
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|t| query.status.as_ref().is_none_or(|s| s.contains(&t.status)))
+            .filter(|t| start.as_ref().is_none_or(|start| start.precedes(t)))
+            .cloned()
+            .collect();
+        tasks.sort_by(|a, b| a.created_at.total_cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(TaskPage::from_sorted(
+            tasks,
+            query.limit.unwrap_or(usize::MAX),
+        ))
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+fn task(id: &str, task_type: Option<&str>, status: TaskStatus, created_at: f64) -> Task {
+    serde_json::from_value(json!({
+        "id": id,
+        "type": task_type,
+        "status": status,
+        "createdAt": created_at,
+        "updatedAt": created_at,
+    }))
+    .unwrap()
+}
+
+/// An engine over a memory store holding `tasks`.
+async fn engine_with(
+    tasks: Vec<Task>,
+    long_term_store: Option<Arc<dyn LongTermStore>>,
+) -> TaskEngine {
+    let store = Arc::new(MemoryShortTermStore::new());
+    for task in tasks {
+        store.save_task(task).await.unwrap();
+    }
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+fn ids(page: &TaskPage) -> Vec<&str> {
+    page.tasks.iter().map(|t| t.id.as_str()).collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn pages_follow_creation_order_then_id() {
+    let engine = engine_with(
+        vec![
+            task("c", None, TaskStatus::Pending, 1000.0),
+            task("a", None, TaskStatus::Pending, 2000.0),
+            task("b", None, TaskStatus::Running, 1000.0),
+            task("d", None, TaskStatus::Pending, 3000.0),
+        ],
+        None,
+    )
+    .await;
+
+    let mut query = ListTasksQuery {
+        limit: Some(2),
+        ..Default::default()
+    };
+    let mut pages = Vec::new();
+    loop {
+        let page = engine.query_tasks(&query).await.unwrap();
+        pages.push(ids(&page).join(","));
+        match page.next {
+            Some(next) => query.cursor = Some(next),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, ["b,c", "a,d"]);
+}
+
+#[tokio::test]
+async fn wildcard_types_fill_the_page() {
+    // Nine untyped tasks between every crawl, so a page of two takes
+    // several reads of the store.
+    let tasks = (0..40)
+        .map(|i| {
+            let task_type = (i % 10 == 0).then_some("crawl.page");
+            task(
+                &format!("t{i:02}"),
+                task_type,
+                TaskStatus::Running,
+                i as f64,
+            )
+        })
+        .collect();
+    let engine = engine_with(tasks, None).await;
+
+    let first = engine
+        .query_tasks(&ListTasksQuery {
+            r#type: Some("crawl.*".to_string()),
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&first), ["t00", "t10"]);
+
+    let second = engine
+        .query_tasks(&ListTasksQuery {
+            r#type: Some("crawl.*".to_string()),
+            limit: Some(2),
+            cursor: first.next,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&second), ["t20", "t30"]);
+}
+
+#[tokio::test]
+async fn filters_status_type_and_creation_window() {
+    let engine = engine_with(
+        vec![
+            task("t1", Some("crawl.page"), TaskStatus::Running, 1000.0),
+            task("t2", Some("crawl.page"), TaskStatus::Running, 2000.0),
+            task("t3", Some("crawl"), TaskStatus::Running, 2000.0),
+            task("t4", Some("crawl.site"), TaskStatus::Pending, 2500.0),
+            task("t5", Some("crawl.site"), TaskStatus::Running, 3000.0),
+        ],
+        None,
+    )
+    .await;
+
+    let page = engine
+        .query_tasks(&ListTasksQuery {
+            status: Some(vec![TaskStatus::Running]),
+            r#type: Some("crawl.*".to_string()),
+            created_after: Some(2000.0),
+            created_before: Some(3000.0),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&page), ["t2"]);
+    assert_eq!(page.next, None);
+}
+
+#[tokio::test]
+async fn terminal_statuses_merge_both_stores() {
+    let long_term = Arc::new(MapLongTermStore::default());
+    for task in [
+        task("archived", None, TaskStatus::Completed, 1000.0),
+        task("both", None, TaskStatus::Completed, 3000.0),
+    ] {
+        long_term.save_task(task).await.unwrap();
+    }
+    // `fresh` finished but has not reached the long-term store yet.
+    let engine = engine_with(
+        vec![
+            task("fresh", None, TaskStatus::Failed, 2000.0),
+            task("both", None, TaskStatus::Completed, 3000.0),
+            task("live", None, TaskStatus::Running, 4000.0),
+        ],
+        Some(long_term.clone()),
+    )
+    .await;
+
+    let done = engine
+        .query_tasks(&ListTasksQuery {
+            status: Some(vec![TaskStatus::Completed, TaskStatus::Failed]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&done), ["archived", "fresh", "both"]);
+    assert_eq!(done.next, None);
+
+    let mixed = engine
+        .query_tasks(&ListTasksQuery {
+            status: Some(vec![TaskStatus::Completed, TaskStatus::Running]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(ids(&mixed), ["both", "live"]);
+    assert_eq!(long_term.queries.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn merged_pages_follow_one_cursor() {
+    let long_term = Arc::new(MapLongTermStore::default());
+    for (id, created_at) in [("a", 1000.0), ("c", 3000.0), ("d", 4000.0)] {
+        long_term
+            .save_task(task(id, None, TaskStatus::Completed, created_at))
+            .await
+            .unwrap();
+    }
+    let engine = engine_with(
+        vec![
+            task("b", None, TaskStatus::Completed, 2000.0),
+            task("c", None, TaskStatus::Completed, 3000.0),
+            task("e", None, TaskStatus::Completed, 5000.0),
+        ],
+        Some(long_term),
+    )
+    .await;
+
+    let mut query = ListTasksQuery {
+        status: Some(vec![TaskStatus::Completed]),
+        limit: Some(2),
+        ..Default::default()
+    };
+    let mut pages = Vec::new();
+    loop {
+        let page = engine.query_tasks(&query).await.unwrap();
+        pages.push(ids(&page).join(","));
+        match page.next {
+            Some(next) => query.cursor = Some(next),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, ["a,b", "c,d", "e"]);
+}
+
+#[test]
+fn cursors_round_trip_through_strings() {
+    let cursor = TaskCursor {
+        created_at: 1700000000000.0,
+        id: "job:42".to_string(),
+    };
+    assert_eq!(cursor.to_string().parse::<TaskCursor>(), Ok(cursor));
+    for bad in ["", "1700000000000", "1700000000000:", "abc:t1", "inf:t1"] {
+        assert!(bad.parse::<TaskCursor>().is_err(), "{bad}");
+    }
+}
@@ -0,0 +1,299 @@
+//! Coalescing of concurrent identical reads in `TaskEngine`.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use taskcast_core::{
+    CreateTaskInput, EngineError, EventQueryOptions, MemoryBroadcastProvider, MemoryShortTermStore,
+    ShortTermStore, Task, TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter, Worker,
+    WorkerAssignment, WorkerFilter,
+};
+
+// ─── Counting store ──────────────────────────────────────────────────────────
+
+/// Memory short-term store that counts task and event reads. Reads sleep
+/// briefly so concurrent callers overlap, and fail while `fail` is set.
+#[derive(Default)]
+struct CountingShortTermStore {
+    inner: MemoryShortTermStore,
+    task_reads: AtomicUsize,
+    event_reads: AtomicUsize,
+    fail: AtomicBool,
+}
+
+impl CountingShortTermStore {
+    async fn read(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if self.fail.load(Ordering::SeqCst) {
+            return Err("store unavailable".into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+#[async_trait]
+impl ShortTermStore for CountingShortTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.task_reads.fetch_add(1, Ordering::SeqCst);
+        self.read().await?;
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.event_reads.fetch_add(1, Ordering::SeqCst);
+        self.read().await?;
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+async fn setup(coalesce_reads: Option<bool>) -> (TaskEngine, Arc<CountingShortTermStore>) {
+    let store = Arc::new(CountingShortTermStore::default());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads,
+    });
+    for id in ["t1", "t2"] {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    store.task_reads.store(0, Ordering::SeqCst);
+    (engine, store)
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn concurrent_get_task_calls_share_one_read() {
+    let (engine, store) = setup(None).await;
+
+    let results = join_all((0..100).map(|_| engine.get_task("t1"))).await;
+    assert_eq!(store.task_reads.load(Ordering::SeqCst), 1);
+    for result in results {
+        assert_eq!(result.unwrap().unwrap().id, "t1");
+    }
+
+    // Finished reads are not cached.
+    engine.get_task("t1").await.unwrap();
+    assert_eq!(store.task_reads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn different_tasks_do_not_coalesce() {
+    let (engine, store) = setup(None).await;
+
+    let (t1, t2) = tokio::join!(engine.get_task("t1"), engine.get_task("t2"));
+    assert_eq!(t1.unwrap().unwrap().id, "t1");
+    assert_eq!(t2.unwrap().unwrap().id, "t2");
+    assert_eq!(store.task_reads.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn a_failed_read_reaches_every_caller() {
+    let (engine, store) = setup(None).await;
+    store.fail.store(true, Ordering::SeqCst);
+
+    let results = join_all((0..10).map(|_| engine.get_task("t1"))).await;
+    assert_eq!(store.task_reads.load(Ordering::SeqCst), 1);
+    for result in results {
+        match result {
+            Err(EngineError::Store(err)) => assert_eq!(err.to_string(), "store unavailable"),
+            other => panic!("expected a store error, got {other:?}"),
+        }
+    }
+
+    // The error is not cached either.
+    store.fail.store(false, Ordering::SeqCst);
+    assert!(engine.get_task("t1").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn only_unfiltered_history_reads_coalesce() {
+    let (engine, store) = setup(None).await;
+
+    join_all((0..10).map(|_| engine.get_events("t1", None))).await;
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 1);
+
+    let limited = || {
+        Some(EventQueryOptions {
+            since: None,
+            limit: Some(5),
+            label_selector: None,
+            until: None,
+            before_index: None,
+        })
+    };
+    join_all((0..10).map(|_| engine.get_events("t1", limited()))).await;
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 11);
+}
+
+#[tokio::test]
+async fn coalescing_can_be_disabled() {
+    let (engine, store) = setup(Some(false)).await;
+
+    join_all((0..10).map(|_| engine.get_task("t1"))).await;
+    assert_eq!(store.task_reads.load(Ordering::SeqCst), 10);
+}
@@ -0,0 +1,495 @@
+//! Read routing between the short-term and long-term stores.
+//!
+//! The stores here wrap in-memory implementations and sleep for a
+//! configurable delay on every read, so the engine's latency estimate can be
+//! driven up and down.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EventQueryOptions, Level, LongTermStore, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, ReadPreference, ReadRoutingConfig, ShortTermStore,
+    Task, TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter, TaskStatus, Worker,
+    WorkerAssignment, WorkerAuditEvent, WorkerFilter,
+};
+
+// ─── Delayed stores ──────────────────────────────────────────────────────────
+
+/// Memory short-term store whose reads sleep for `delay_ms` first.
+#[derive(Default)]
+struct DelayedShortTermStore {
+    inner: MemoryShortTermStore,
+    delay_ms: AtomicU64,
+    event_reads: AtomicUsize,
+}
+
+impl DelayedShortTermStore {
+    fn set_delay(&self, ms: u64) {
+        self.delay_ms.store(ms, Ordering::SeqCst);
+    }
+
+    async fn delay(&self) {
+        let ms = self.delay_ms.load(Ordering::SeqCst);
+        if ms > 0 {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for DelayedShortTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.delay().await;
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.delay().await;
+        self.event_reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+/// In-memory long-term store that can hold back event writes, either for a
+/// while (`save_delay_ms`) or for good (`drop_events`).
+#[derive(Default)]
+struct SlowLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    events: Mutex<Vec<TaskEvent>>,
+    save_delay_ms: AtomicU64,
+    drop_events: AtomicBool,
+    event_reads: AtomicUsize,
+}
+
+#[async_trait]
+impl LongTermStore for SlowLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let delay = self.save_delay_ms.load(Ordering::SeqCst);
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+        if !self.drop_events.load(Ordering::SeqCst) {
+            self.events.lock().unwrap().push(event);
+        }
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.event_reads.fetch_add(1, Ordering::SeqCst);
+        let mut events: Vec<TaskEvent> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.task_id == task_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| event.index);
+        Ok(events)
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+const THRESHOLD_MS: u64 = 20;
+const SLOW_MS: u64 = 60;
+
+struct Setup {
+    engine: TaskEngine,
+    short: Arc<DelayedShortTermStore>,
+    long: Arc<SlowLongTermStore>,
+}
+
+fn setup(preference: ReadPreference) -> Setup {
+    let short = Arc::new(DelayedShortTermStore::default());
+    let long = Arc::new(SlowLongTermStore::default());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: short.clone(),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: Some(long.clone()),
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_read_routing(ReadRoutingConfig {
+        preference,
+        latency_threshold: Duration::from_millis(THRESHOLD_MS),
+        probe_every: 4,
+    });
+    Setup {
+        engine,
+        short,
+        long,
+    }
+}
+
+async fn create_task_with_events(engine: &TaskEngine, task_id: &str, terminal: bool) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..3 {
+        engine
+            .publish_event(
+                task_id,
+                PublishEventInput {
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: json!({ "i": i }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    if terminal {
+        engine
+            .transition_task(task_id, TaskStatus::Completed, None)
+            .await
+            .unwrap();
+    }
+    assert!(engine.background().drain(Duration::from_secs(5)).await);
+}
+
+/// Reads the task's history `n` times and returns how many reads the
+/// long-term store served.
+async fn long_term_share(setup: &Setup, task_id: &str, n: usize) -> usize {
+    let before = setup.long.event_reads.load(Ordering::SeqCst);
+    for _ in 0..n {
+        let events = setup.engine.get_events(task_id, None).await.unwrap();
+        assert_eq!(events.len(), 5, "full history on every read");
+    }
+    setup.long.event_reads.load(Ordering::SeqCst) - before
+}
+
+fn short_latency_ms(setup: &Setup) -> f64 {
+    setup
+        .engine
+        .read_router()
+        .short_term_latency()
+        .estimate_ms()
+        .unwrap()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn adaptive_reads_short_term_while_it_is_fast() {
+    let setup = setup(ReadPreference::Adaptive);
+    create_task_with_events(&setup.engine, "t1", true).await;
+
+    assert_eq!(long_term_share(&setup, "t1", 8).await, 0);
+    assert_eq!(setup.engine.read_router().stats().long_term_reads, 0);
+}
+
+#[tokio::test]
+async fn adaptive_flips_to_long_term_when_short_term_slows_and_back_on_recovery() {
+    let setup = setup(ReadPreference::Adaptive);
+    create_task_with_events(&setup.engine, "t1", true).await;
+
+    setup.short.set_delay(SLOW_MS);
+    // Let the estimate climb past the threshold.
+    while short_latency_ms(&setup) <= THRESHOLD_MS as f64 {
+        setup.engine.get_events("t1", None).await.unwrap();
+    }
+
+    // Three of every four reads go long-term; the fourth probes short-term.
+    assert_eq!(long_term_share(&setup, "t1", 8).await, 6);
+    let stats = setup.engine.read_router().stats();
+    assert_eq!(stats.long_term_reads, 6);
+    assert!(stats.short_term_latency_ms.unwrap() > THRESHOLD_MS as f64);
+
+    setup.short.set_delay(0);
+    // Probes bring the estimate back down.
+    for _ in 0..64 {
+        if short_latency_ms(&setup) < THRESHOLD_MS as f64 {
+            break;
+        }
+        setup.engine.get_events("t1", None).await.unwrap();
+    }
+    assert!(short_latency_ms(&setup) < THRESHOLD_MS as f64);
+    assert_eq!(long_term_share(&setup, "t1", 8).await, 0);
+}
+
+#[tokio::test]
+async fn degraded_short_term_routes_terminal_reads_long_term() {
+    let setup = setup(ReadPreference::Adaptive);
+    create_task_with_events(&setup.engine, "t1", true).await;
+
+    setup.engine.read_router().set_short_term_degraded(true);
+    assert_eq!(long_term_share(&setup, "t1", 4).await, 4);
+    let task = setup.engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+
+    setup.engine.read_router().set_short_term_degraded(false);
+    assert_eq!(long_term_share(&setup, "t1", 4).await, 0);
+}
+
+#[tokio::test]
+async fn running_task_is_never_read_from_long_term() {
+    for preference in [ReadPreference::Adaptive, ReadPreference::Long] {
+        let setup = setup(preference);
+        // Long-term history is missing every event.
+        setup.long.drop_events.store(true, Ordering::SeqCst);
+        create_task_with_events(&setup.engine, "t1", false).await;
+        setup.engine.read_router().set_short_term_degraded(true);
+        setup.short.set_delay(SLOW_MS);
+
+        let events = setup.engine.get_events("t1", None).await.unwrap();
+        assert_eq!(events.len(), 4, "{preference:?}");
+        let task = setup.engine.get_task("t1").await.unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Running);
+        assert_eq!(setup.long.event_reads.load(Ordering::SeqCst), 0);
+        assert_eq!(setup.engine.read_router().stats().long_term_reads, 0);
+    }
+}
+
+#[tokio::test]
+async fn terminal_task_with_writes_in_flight_reads_short_term() {
+    let setup = setup(ReadPreference::Long);
+    setup.long.save_delay_ms.store(200, Ordering::SeqCst);
+    setup
+        .engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    setup
+        .engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    setup
+        .engine
+        .transition_task("t1", TaskStatus::Failed, None)
+        .await
+        .unwrap();
+
+    let events = setup.engine.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(setup.long.event_reads.load(Ordering::SeqCst), 0);
+
+    assert!(
+        setup
+            .engine
+            .background()
+            .drain(Duration::from_secs(5))
+            .await
+    );
+    let events = setup.engine.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(setup.long.event_reads.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn short_preference_keeps_reading_short_term() {
+    let setup = setup(ReadPreference::Short);
+    create_task_with_events(&setup.engine, "t1", true).await;
+    setup.engine.read_router().set_short_term_degraded(true);
+    setup.short.set_delay(SLOW_MS);
+
+    assert_eq!(long_term_share(&setup, "t1", 3).await, 0);
+    assert!(setup.short.event_reads.load(Ordering::SeqCst) >= 3);
+}
@@ -0,0 +1,480 @@
+//! Chunked, budgeted history replay through
+//! `TaskEngine::subscribe_stream_with_replay` and `TaskEngine::event_chunks`.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EventQueryOptions, Level, MemoryBroadcastProvider, MemoryShortTermStore,
+    PublishEventInput, ReplayBudget, ReplayOptions, ReplayTruncation, SeriesMode, ShortTermStore,
+    StreamItem, SubscribeFilter, Task, TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter,
+    TaskStatus, Worker, WorkerAssignment, WorkerFilter,
+};
+
+// ─── Counting store ──────────────────────────────────────────────────────────
+
+/// Memory short-term store that counts event reads. While `cold` is set it
+/// has no series latest, like a store whose series state has expired.
+#[derive(Default)]
+struct CountingStore {
+    inner: MemoryShortTermStore,
+    event_reads: AtomicUsize,
+    cold: AtomicBool,
+}
+
+#[async_trait]
+impl ShortTermStore for CountingStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.event_reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.cold.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+fn setup() -> (Arc<TaskEngine>, Arc<CountingStore>) {
+    let store = Arc::new(CountingStore::default());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    (Arc::new(engine), store)
+}
+
+fn log(message: &str) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!({ "message": message }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+fn delta(text: &str) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "llm.delta".to_string(),
+        level: Level::Info,
+        data: json!({ "delta": text }),
+        series_id: Some("output".to_string()),
+        series_mode: Some(SeriesMode::Accumulate),
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+/// A running task with `logs` log events after its status event.
+async fn running_task(engine: &TaskEngine, task_id: &str, logs: usize) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..logs {
+        engine
+            .publish_event(task_id, log(&format!("m{i}")))
+            .await
+            .unwrap();
+    }
+}
+
+async fn complete(engine: &TaskEngine, task_id: &str) {
+    engine
+        .transition_task(task_id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+fn options(budget: ReplayBudget, chunk_size: u64) -> ReplayOptions {
+    ReplayOptions {
+        budget,
+        chunk_size,
+        ..Default::default()
+    }
+}
+
+fn max_events(max: u64) -> ReplayBudget {
+    ReplayBudget {
+        max_events: Some(max),
+        max_bytes: None,
+    }
+}
+
+/// (raw index, filtered index, data) of each event item.
+fn events(items: &[StreamItem]) -> Vec<(u64, u64, serde_json::Value)> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            StreamItem::Event(env) => Some((env.raw_index, env.filtered_index, env.data.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn event_chunks_read_the_history_one_limited_call_at_a_time() {
+    let (engine, store) = setup();
+    running_task(&engine, "t1", 24).await;
+    let history = engine.get_events("t1", None).await.unwrap();
+    assert_eq!(history.len(), 25);
+    store.event_reads.store(0, Ordering::SeqCst);
+
+    let mut chunks = engine.event_chunks("t1", None, 10);
+    let mut read = Vec::new();
+    let mut sizes = Vec::new();
+    while let Some(chunk) = chunks.next_chunk().await.unwrap() {
+        sizes.push(chunk.len());
+        read.extend(chunk);
+    }
+    assert_eq!(sizes, vec![10, 10, 5]);
+    assert_eq!(read, history);
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 3);
+    assert_eq!(chunks.last_index(), Some(24));
+
+    // The query's limit caps the read as a whole.
+    let mut chunks = engine.event_chunks(
+        "t1",
+        Some(EventQueryOptions {
+            limit: Some(15),
+            ..Default::default()
+        }),
+        10,
+    );
+    let mut sizes = Vec::new();
+    while let Some(chunk) = chunks.next_chunk().await.unwrap() {
+        sizes.push(chunk.len());
+    }
+    assert_eq!(sizes, vec![10, 5]);
+}
+
+#[tokio::test]
+async fn over_budget_replay_sends_the_truncation_then_the_tail() {
+    let (engine, store) = setup();
+    running_task(&engine, "t1", 50).await;
+    complete(&engine, "t1").await;
+    store.event_reads.store(0, Ordering::SeqCst);
+
+    let filter = SubscribeFilter {
+        types: Some(vec!["log".to_string()]),
+        ..Default::default()
+    };
+    let stream = engine
+        .subscribe_stream_with_replay("t1", filter, options(max_events(5), 8))
+        .await
+        .unwrap();
+    let items: Vec<StreamItem> = stream.collect().await;
+
+    assert!(matches!(
+        &items[0],
+        StreamItem::Truncated(ReplayTruncation {
+            omitted_count: 45,
+            earliest_sent_index: Some(46),
+        })
+    ));
+    let replayed = events(&items);
+    assert_eq!(
+        replayed
+            .iter()
+            .map(|(raw, filtered, _)| (*raw, *filtered))
+            .collect::<Vec<_>>(),
+        vec![(46, 45), (47, 46), (48, 47), (49, 48), (50, 49)]
+    );
+    assert!(matches!(
+        items.last(),
+        Some(StreamItem::Done(TaskStatus::Completed))
+    ));
+    // 52 events in chunks of 8.
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 7);
+}
+
+#[tokio::test]
+async fn within_budget_replay_is_not_truncated() {
+    let (engine, _store) = setup();
+    running_task(&engine, "t1", 3).await;
+    complete(&engine, "t1").await;
+
+    let stream = engine
+        .subscribe_stream_with_replay("t1", SubscribeFilter::default(), options(max_events(10), 2))
+        .await
+        .unwrap();
+    let items: Vec<StreamItem> = stream.collect().await;
+
+    assert!(!items
+        .iter()
+        .any(|item| matches!(item, StreamItem::Truncated(_))));
+    assert_eq!(events(&items).len(), 5);
+}
+
+#[tokio::test]
+async fn unlimited_replay_reads_chunks_as_it_is_polled_then_tails_live() {
+    let (engine, store) = setup();
+    running_task(&engine, "t1", 29).await;
+    store.event_reads.store(0, Ordering::SeqCst);
+
+    let mut stream = engine
+        .subscribe_stream_with_replay(
+            "t1",
+            SubscribeFilter::default(),
+            options(ReplayBudget::unlimited(), 10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 0);
+
+    let first = stream.next().await.unwrap();
+    assert!(matches!(first, StreamItem::Event(ref env) if env.raw_index == 0));
+    assert_eq!(store.event_reads.load(Ordering::SeqCst), 1);
+
+    // Published after subscribing but before the replay reads it: replayed
+    // once, not again from the live tail.
+    engine.publish_event("t1", log("late")).await.unwrap();
+
+    let mut items = vec![first];
+    for _ in 0..30 {
+        items.push(stream.next().await.unwrap());
+    }
+    let replayed = events(&items);
+    assert_eq!(
+        replayed.iter().map(|(raw, _, _)| *raw).collect::<Vec<_>>(),
+        (0..31).collect::<Vec<_>>()
+    );
+    assert_eq!(replayed[30].2["message"], "late");
+
+    engine.publish_event("t1", log("live")).await.unwrap();
+    let Some(StreamItem::Event(live)) = stream.next().await else {
+        panic!("expected the live event");
+    };
+    assert_eq!((live.raw_index, live.filtered_index), (31, 31));
+    assert_eq!(live.data["message"], "live");
+    assert!(store.event_reads.load(Ordering::SeqCst) >= 4);
+
+    complete(&engine, "t1").await;
+    let rest: Vec<StreamItem> = stream.collect().await;
+    assert!(matches!(
+        rest.last(),
+        Some(StreamItem::Done(TaskStatus::Completed))
+    ));
+}
+
+#[tokio::test]
+async fn chunked_replay_collapses_accumulate_series_like_a_whole_history_replay() {
+    for cold in [false, true] {
+        let (engine, store) = setup();
+        running_task(&engine, "t1", 1).await;
+        for i in 0..10 {
+            engine
+                .publish_event("t1", delta(&format!("d{i}")))
+                .await
+                .unwrap();
+            engine
+                .publish_event("t1", log(&format!("after {i}")))
+                .await
+                .unwrap();
+        }
+        complete(&engine, "t1").await;
+        store.cold.store(cold, Ordering::SeqCst);
+
+        let whole: Vec<StreamItem> = engine
+            .subscribe_stream("t1", SubscribeFilter::default())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        let chunked: Vec<StreamItem> = engine
+            .subscribe_stream_with_replay(
+                "t1",
+                SubscribeFilter::default(),
+                options(ReplayBudget::unlimited(), 3),
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(events(&chunked), events(&whole), "cold: {cold}");
+        let snapshots: Vec<&StreamItem> = chunked
+            .iter()
+            .filter(
+                |item| matches!(item, StreamItem::Event(env) if env.series_snapshot == Some(true)),
+            )
+            .collect();
+        assert_eq!(snapshots.len(), 1, "cold: {cold}");
+    }
+}
@@ -0,0 +1,360 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use taskcast_core::{
+    BackoffStrategy, BroadcastProvider, Clock, CreateTaskInput, MemoryBroadcastProvider,
+    MemoryShortTermStore, RetryOn, RetryPolicy, RetryRunner, RetryRunnerOptions, ShortTermStore,
+    TaskEngine, TaskEngineOptions, TaskOrigin, TaskOriginKind, TaskStatus, RETRY_SCHEDULED_EVENT,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    fn new(now: f64) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(now)))
+    }
+
+    fn advance(&self, ms: f64) {
+        *self.0.lock().unwrap() += ms;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+fn make_engine(store: &Arc<MemoryShortTermStore>, clock: &Arc<ManualClock>) -> Arc<TaskEngine> {
+    let broadcast = Arc::new(MemoryBroadcastProvider::new());
+    Arc::new(
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+            broadcast: broadcast as Arc<dyn BroadcastProvider>,
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_clock(Arc::clone(clock) as Arc<dyn Clock>),
+    )
+}
+
+fn make_runner(engine: &Arc<TaskEngine>, store: &Arc<MemoryShortTermStore>) -> RetryRunner {
+    RetryRunner::new(RetryRunnerOptions {
+        engine: Arc::clone(engine),
+        short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+        check_interval_ms: 60_000,
+        claim_lease_ms: 30_000,
+    })
+}
+
+fn policy(max_attempts: u32, retry_on: Vec<RetryOn>) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        backoff: BackoffStrategy::Exponential,
+        initial_delay_ms: 1_000,
+        max_delay_ms: 10_000,
+        retry_on,
+    }
+}
+
+/// Create a task with `retry_policy` and run it to `end`.
+async fn run_to(engine: &TaskEngine, task_id: &str, retry_policy: RetryPolicy, end: TaskStatus) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            params: Some([("prompt".to_string(), json!("hi"))].into()),
+            retry_policy: Some(retry_policy),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    finish(engine, task_id, end).await;
+}
+
+async fn finish(engine: &TaskEngine, task_id: &str, end: TaskStatus) {
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine.transition_task(task_id, end, None).await.unwrap();
+}
+
+async fn retry_events(engine: &TaskEngine, task_id: &str) -> Vec<serde_json::Value> {
+    engine
+        .get_events(task_id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.r#type == RETRY_SCHEDULED_EVENT)
+        .map(|e| e.data)
+        .collect()
+}
+
+// ─── Scheduling ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn failed_task_gets_successor_after_backoff() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(10_000.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(3, vec![RetryOn::Failed]),
+        TaskStatus::Failed,
+    )
+    .await;
+
+    let events = retry_events(&engine, "t1").await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["attempt"], 2);
+    assert_eq!(events[0]["maxAttempts"], 3);
+    assert_eq!(events[0]["delayMs"], 1_000);
+    assert_eq!(events[0]["dueAt"], 11_000.0);
+    let successor_id = events[0]["successorId"].as_str().unwrap().to_string();
+
+    clock.advance(999.0);
+    assert_eq!(runner.tick().await.unwrap(), 0);
+    assert!(engine.get_task(&successor_id).await.unwrap().is_none());
+
+    clock.advance(1.0);
+    assert_eq!(runner.tick().await.unwrap(), 1);
+    let successor = engine.get_task(&successor_id).await.unwrap().unwrap();
+    assert_eq!(successor.status, TaskStatus::Pending);
+    assert_eq!(successor.params.as_ref().unwrap()["prompt"], json!("hi"));
+    let metadata = successor.metadata.as_ref().unwrap();
+    assert_eq!(metadata["retryOf"], json!("t1"));
+    assert_eq!(metadata["retryAttempt"], json!(2));
+    assert_eq!(
+        successor.origin,
+        Some(TaskOrigin::new(TaskOriginKind::Retry).with_source_task_id("t1"))
+    );
+
+    // The failed task stays terminal and the schedule is cleared.
+    let original = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(original.status, TaskStatus::Failed);
+    assert!(store.get_retry_schedule("t1").await.unwrap().is_none());
+    assert_eq!(runner.tick().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn backoff_grows_with_each_attempt() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(3, vec![RetryOn::Failed]),
+        TaskStatus::Failed,
+    )
+    .await;
+    let successor_id = retry_events(&engine, "t1").await[0]["successorId"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    clock.advance(1_000.0);
+    runner.tick().await.unwrap();
+
+    finish(&engine, &successor_id, TaskStatus::Failed).await;
+    let events = retry_events(&engine, &successor_id).await;
+    assert_eq!(events[0]["attempt"], 3);
+    assert_eq!(events[0]["delayMs"], 2_000);
+}
+
+#[tokio::test]
+async fn max_attempts_caps_the_chain() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(2, vec![RetryOn::Failed]),
+        TaskStatus::Failed,
+    )
+    .await;
+    clock.advance(1_000.0);
+    assert_eq!(runner.tick().await.unwrap(), 1);
+    let successor_id = retry_events(&engine, "t1").await[0]["successorId"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    finish(&engine, &successor_id, TaskStatus::Failed).await;
+    assert!(retry_events(&engine, &successor_id).await.is_empty());
+    assert!(store
+        .get_retry_schedule(&successor_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    clock.advance(60_000.0);
+    assert_eq!(runner.tick().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn completed_task_never_retries() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(3, vec![RetryOn::Failed, RetryOn::Timeout]),
+        TaskStatus::Completed,
+    )
+    .await;
+
+    assert!(retry_events(&engine, "t1").await.is_empty());
+    assert!(store.get_retry_schedule("t1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn statuses_outside_retry_on_are_not_retried() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(3, vec![RetryOn::Failed]),
+        TaskStatus::Timeout,
+    )
+    .await;
+    assert!(retry_events(&engine, "t1").await.is_empty());
+
+    run_to(
+        &engine,
+        "t2",
+        policy(3, vec![RetryOn::Timeout]),
+        TaskStatus::Timeout,
+    )
+    .await;
+    assert_eq!(retry_events(&engine, "t2").await.len(), 1);
+}
+
+#[tokio::test]
+async fn zero_max_attempts_is_rejected() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(&store, &ManualClock::new(0.0));
+
+    let err = engine
+        .create_task(CreateTaskInput {
+            retry_policy: Some(policy(0, vec![RetryOn::Failed])),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("maxAttempts"), "{err}");
+}
+
+// ─── Runner ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn pending_retry_survives_restart() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    {
+        let engine = make_engine(&store, &clock);
+        run_to(
+            &engine,
+            "t1",
+            policy(3, vec![RetryOn::Failed]),
+            TaskStatus::Failed,
+        )
+        .await;
+    }
+
+    // A fresh engine and runner over the same store pick the retry up.
+    clock.advance(5_000.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+    assert_eq!(runner.tick().await.unwrap(), 1);
+
+    let attempts = engine.task_attempts("t1").await.unwrap().unwrap();
+    assert_eq!(attempts.attempts.len(), 2);
+    assert_eq!(attempts.attempts[1].attempt, 2);
+    assert_eq!(attempts.attempts[1].status, TaskStatus::Pending);
+}
+
+#[tokio::test]
+async fn concurrent_runners_create_one_successor() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine_a = make_engine(&store, &clock);
+    let engine_b = make_engine(&store, &clock);
+    let runner_a = make_runner(&engine_a, &store);
+    let runner_b = make_runner(&engine_b, &store);
+
+    run_to(
+        &engine_a,
+        "t1",
+        policy(3, vec![RetryOn::Failed]),
+        TaskStatus::Failed,
+    )
+    .await;
+    clock.advance(1_000.0);
+
+    let (a, b) = tokio::join!(runner_a.tick(), runner_b.tick());
+    assert_eq!(a.unwrap() + b.unwrap(), 1);
+    let tasks = engine_a.list_tasks(Default::default()).await.unwrap();
+    assert_eq!(tasks.len(), 2);
+}
+
+// ─── Attempts ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn attempts_list_the_chain_from_any_member() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    run_to(
+        &engine,
+        "t1",
+        policy(3, vec![RetryOn::Failed]),
+        TaskStatus::Failed,
+    )
+    .await;
+
+    let pending = engine.task_attempts("t1").await.unwrap().unwrap();
+    assert_eq!(pending.attempts.len(), 1);
+    let scheduled = pending.scheduled.unwrap();
+    assert_eq!(scheduled.attempt, 2);
+    assert_eq!(scheduled.due_at, 1_000.0);
+
+    clock.advance(1_000.0);
+    runner.tick().await.unwrap();
+    let successor_id = scheduled.successor_id;
+    finish(&engine, &successor_id, TaskStatus::Completed).await;
+
+    for id in ["t1", successor_id.as_str()] {
+        let attempts = engine.task_attempts(id).await.unwrap().unwrap();
+        let ids: Vec<&str> = attempts
+            .attempts
+            .iter()
+            .map(|a| a.task_id.as_str())
+            .collect();
+        assert_eq!(ids, ["t1", successor_id.as_str()]);
+        assert_eq!(attempts.attempts[0].status, TaskStatus::Failed);
+        assert_eq!(attempts.attempts[1].status, TaskStatus::Completed);
+        assert!(attempts.scheduled.is_none());
+    }
+
+    assert!(engine.task_attempts("missing").await.unwrap().is_none());
+}
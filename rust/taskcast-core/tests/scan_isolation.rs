@@ -0,0 +1,186 @@
+//! Scan tokens: multi-call reads of a task's history that stay on one prefix
+//! while events keep being published.
+
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EventQueryOptions, EventTypeCounts, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, SinceCursor, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskStatus,
+};
+use tokio::task::JoinHandle;
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+async fn create_running_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+fn event(i: usize) -> PublishEventInput {
+    PublishEventInput {
+        r#type: if i.is_multiple_of(3) { "tool.call" } else { "llm.delta" }.to_string(),
+        level: if i.is_multiple_of(5) { Level::Warn } else { Level::Info },
+        data: json!({ "i": i }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+/// Publishes `count` events to `task_id`, yielding between them so reads
+/// interleave with the publishes.
+fn spawn_publisher(engine: &Arc<TaskEngine>, task_id: &str, count: usize) -> JoinHandle<()> {
+    let engine = Arc::clone(engine);
+    let task_id = task_id.to_string();
+    tokio::spawn(async move {
+        for i in 0..count {
+            engine.publish_event(&task_id, event(i)).await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    })
+}
+
+fn below(events: Vec<TaskEvent>, end_index: u64) -> Vec<TaskEvent> {
+    events
+        .into_iter()
+        .filter(|event| event.index < end_index)
+        .collect()
+}
+
+// ─── Paged Reads ─────────────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn paged_reads_in_a_scan_match_a_later_read_of_the_prefix() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+    let publisher = spawn_publisher(&engine, "t1", 300);
+    while engine.event_count("t1").await.unwrap() < 50 {
+        tokio::task::yield_now().await;
+    }
+
+    let scan = engine.begin_scan("t1").await.unwrap();
+    let end_index = scan.end_index.unwrap();
+    let mut paged: Vec<TaskEvent> = Vec::new();
+    loop {
+        let page = engine
+            .get_events_in_scan(
+                &scan,
+                Some(EventQueryOptions {
+                    since: paged.last().map(|last| SinceCursor {
+                        id: None,
+                        index: Some(last.index),
+                        timestamp: None,
+                    }),
+                    limit: Some(7),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        paged.extend(page);
+        tokio::task::yield_now().await;
+    }
+    publisher.await.unwrap();
+
+    let history = engine.get_events("t1", None).await.unwrap();
+    assert!(history.len() as u64 > end_index);
+    assert_eq!(paged, below(history, end_index));
+}
+
+#[tokio::test]
+async fn scans_keep_a_tighter_bound_from_the_caller() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+    for i in 0..5 {
+        engine.publish_event("t1", event(i)).await.unwrap();
+    }
+
+    let scan = engine.begin_scan("t1").await.unwrap();
+    assert_eq!(scan.end_index, Some(6));
+    engine.publish_event("t1", event(5)).await.unwrap();
+
+    let indices = |events: Vec<TaskEvent>| events.iter().map(|e| e.index).collect::<Vec<_>>();
+    let all = engine.get_events_in_scan(&scan, None).await.unwrap();
+    assert_eq!(indices(all), [0, 1, 2, 3, 4, 5]);
+    let tighter = engine
+        .get_events_in_scan(
+            &scan,
+            Some(EventQueryOptions {
+                before_index: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(indices(tighter), [0, 1]);
+}
+
+// ─── Stats And Export ────────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn stats_match_a_later_read_truncated_at_the_scan() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+    let publisher = spawn_publisher(&engine, "t1", 300);
+    while engine.event_count("t1").await.unwrap() < 50 {
+        tokio::task::yield_now().await;
+    }
+
+    let (scan, counts) = engine.scanned_event_type_counts("t1").await.unwrap();
+    publisher.await.unwrap();
+
+    let history = engine.get_events("t1", None).await.unwrap();
+    let end_index = scan.end_index.unwrap();
+    assert!(history.len() as u64 > end_index);
+    assert_eq!(
+        counts,
+        EventTypeCounts::from_events(&below(history, end_index))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn export_during_publishes_is_a_prefix_of_the_history() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+    let publisher = spawn_publisher(&engine, "t1", 300);
+    while engine.event_count("t1").await.unwrap() < 50 {
+        tokio::task::yield_now().await;
+    }
+
+    let archive = engine.export_task_archive("t1").await.unwrap();
+    publisher.await.unwrap();
+
+    let history = engine.get_events("t1", None).await.unwrap();
+    let end_index = archive.events.len() as u64;
+    assert!(history.len() as u64 > end_index);
+    assert_eq!(archive.events, below(history, end_index));
+}
@@ -0,0 +1,231 @@
+use std::sync::{Arc, Mutex};
+
+use taskcast_core::{
+    BroadcastProvider, Clock, CreateTaskInput, EngineError, MemoryBroadcastProvider,
+    MemoryShortTermStore, SchedulerRunner, SchedulerRunnerOptions, ShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskFilter, TaskStatus,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+struct ManualClock(Mutex<f64>);
+
+impl ManualClock {
+    fn new(now: f64) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(now)))
+    }
+
+    fn advance(&self, ms: f64) {
+        *self.0.lock().unwrap() += ms;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        *self.0.lock().unwrap()
+    }
+}
+
+fn make_engine(store: &Arc<MemoryShortTermStore>, clock: &Arc<ManualClock>) -> Arc<TaskEngine> {
+    let broadcast = Arc::new(MemoryBroadcastProvider::new());
+    Arc::new(
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+            broadcast: broadcast as Arc<dyn BroadcastProvider>,
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_clock(Arc::clone(clock) as Arc<dyn Clock>),
+    )
+}
+
+fn make_runner(engine: &Arc<TaskEngine>, store: &Arc<MemoryShortTermStore>) -> SchedulerRunner {
+    SchedulerRunner::new(SchedulerRunnerOptions {
+        engine: Arc::clone(engine),
+        short_term_store: Arc::clone(store) as Arc<dyn ShortTermStore>,
+        check_interval_ms: 60_000,
+        claim_lease_ms: 30_000,
+    })
+}
+
+async fn schedule(engine: &TaskEngine, task_id: &str, at: f64) {
+    let task = engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            scheduled_for: Some(at),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(task.status, TaskStatus::Scheduled);
+    assert_eq!(task.scheduled_for, Some(at));
+}
+
+async fn status(engine: &TaskEngine, task_id: &str) -> TaskStatus {
+    engine.get_task(task_id).await.unwrap().unwrap().status
+}
+
+async fn status_events(engine: &TaskEngine, task_id: &str) -> Vec<serde_json::Value> {
+    engine
+        .get_events(task_id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.r#type == "taskcast:status")
+        .map(|e| e.data)
+        .collect()
+}
+
+// ─── Activation ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn scheduled_task_activates_when_its_time_arrives() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(1_000.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    schedule(&engine, "t1", 5_000.0).await;
+    assert!(status_events(&engine, "t1").await.is_empty());
+
+    clock.advance(3_999.0);
+    assert_eq!(runner.tick().await.unwrap(), 0);
+    assert_eq!(status(&engine, "t1").await, TaskStatus::Scheduled);
+
+    clock.advance(1.0);
+    assert_eq!(runner.tick().await.unwrap(), 1);
+    assert_eq!(status(&engine, "t1").await, TaskStatus::Pending);
+    let events = status_events(&engine, "t1").await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["status"], "pending");
+
+    assert_eq!(runner.tick().await.unwrap(), 0);
+    assert_eq!(store.sizes().activations, 0);
+}
+
+#[tokio::test]
+async fn due_tasks_activate_earliest_first() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    schedule(&engine, "late", 2_000.0).await;
+    schedule(&engine, "early", 1_000.0).await;
+    schedule(&engine, "future", 9_000.0).await;
+    clock.advance(5_000.0);
+
+    let due = store.list_due_activations(clock.now_ms()).await.unwrap();
+    assert_eq!(due, vec!["early".to_string(), "late".to_string()]);
+}
+
+#[tokio::test]
+async fn concurrent_runners_activate_once() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine_a = make_engine(&store, &clock);
+    let engine_b = make_engine(&store, &clock);
+    let runner_a = make_runner(&engine_a, &store);
+    let runner_b = make_runner(&engine_b, &store);
+
+    schedule(&engine_a, "t1", 1_000.0).await;
+    clock.advance(1_000.0);
+
+    let (a, b) = tokio::join!(runner_a.tick(), runner_b.tick());
+    assert_eq!(a.unwrap() + b.unwrap(), 1);
+    assert_eq!(status_events(&engine_a, "t1").await.len(), 1);
+}
+
+#[tokio::test]
+async fn cancelled_before_activation_stays_cancelled() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    schedule(&engine, "t1", 1_000.0).await;
+    engine
+        .transition_task("t1", TaskStatus::Cancelled, None)
+        .await
+        .unwrap();
+    assert_eq!(store.sizes().activations, 0);
+
+    clock.advance(1_000.0);
+    assert_eq!(runner.tick().await.unwrap(), 0);
+    assert_eq!(status(&engine, "t1").await, TaskStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn scheduled_task_cannot_start_early() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    schedule(&engine, "t1", 1_000.0).await;
+    let err = engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, EngineError::InvalidTransition { .. }), "{err}");
+}
+
+#[tokio::test]
+async fn stale_activation_of_an_activated_task_is_dropped() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+    let runner = make_runner(&engine, &store);
+
+    schedule(&engine, "t1", 1_000.0).await;
+    engine
+        .transition_task("t1", TaskStatus::Pending, None)
+        .await
+        .unwrap();
+    // As if another instance saved the activation again concurrently.
+    store.save_activation("t1", 1_000.0).await.unwrap();
+
+    clock.advance(1_000.0);
+    assert_eq!(runner.tick().await.unwrap(), 0);
+    assert_eq!(store.sizes().activations, 0);
+    assert_eq!(status_events(&engine, "t1").await.len(), 1);
+}
+
+#[tokio::test]
+async fn scheduled_tasks_can_be_listed_by_status() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    schedule(&engine, "later", 1_000.0).await;
+    engine.create_task(CreateTaskInput::default()).await.unwrap();
+
+    let scheduled = engine
+        .list_tasks(TaskFilter {
+            status: Some(vec![TaskStatus::Scheduled]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].id, "later");
+}
+
+#[tokio::test]
+async fn invalid_scheduled_for_is_rejected() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let clock = ManualClock::new(0.0);
+    let engine = make_engine(&store, &clock);
+
+    for at in [f64::NAN, -1.0] {
+        let err = engine
+            .create_task(CreateTaskInput {
+                scheduled_for: Some(at),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::InvalidInput(_)), "{err}");
+    }
+}
@@ -26,6 +26,9 @@ fn make_scheduler(
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let scheduler = TaskScheduler::new(TaskSchedulerOptions {
         engine: Arc::clone(&engine),
@@ -333,6 +336,9 @@ async fn stop_aborts_running_scheduler() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let mut scheduler = TaskScheduler::new(TaskSchedulerOptions {
         engine: Arc::clone(&engine),
@@ -366,6 +372,9 @@ async fn stop_is_noop_when_never_started() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let mut scheduler = TaskScheduler::new(TaskSchedulerOptions {
         engine,
@@ -390,6 +399,9 @@ async fn start_runs_tick_automatically() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
 
     // Create a blocked task with resume_after_ms = 0 (expires immediately)
@@ -705,6 +717,9 @@ async fn tick_works_after_start_and_stop() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let mut scheduler = TaskScheduler::new(TaskSchedulerOptions {
         engine: Arc::clone(&engine),
@@ -18,6 +18,8 @@ fn make_event(id: &str, task_id: &str, index: u64, data: serde_json::Value) -> T
         series_mode: None,
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, DeleteEventsFilter, DeleteEventsInput, EngineError, Level,
+    MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput, SeriesLimits, SeriesMode,
+    SeriesOverLimit, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+
+fn make_engine(limits: Option<SeriesLimits>) -> TaskEngine {
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    match limits {
+        Some(limits) => engine.with_series_limits(limits),
+        None => engine,
+    }
+}
+
+fn capped(max_per_task: usize, over_limit: SeriesOverLimit) -> Option<SeriesLimits> {
+    Some(SeriesLimits {
+        max_per_task,
+        over_limit,
+    })
+}
+
+async fn create_running_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+fn series_event(series_id: &str, mode: SeriesMode, data: serde_json::Value) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "llm.delta".to_string(),
+        level: Level::Info,
+        data,
+        series_id: Some(series_id.to_string()),
+        series_mode: Some(mode),
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+fn series_ids(series: &[taskcast_core::SeriesSummary]) -> Vec<&str> {
+    series.iter().map(|s| s.series_id.as_str()).collect()
+}
+
+// ─── Cap ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn reject_mode_refuses_a_series_past_the_cap() {
+    let engine = make_engine(capped(2, SeriesOverLimit::Reject));
+    create_running_task(&engine, "t1").await;
+    for sid in ["a", "b"] {
+        engine
+            .publish_event("t1", series_event(sid, SeriesMode::Latest, json!({})))
+            .await
+            .unwrap();
+    }
+
+    let err = engine
+        .publish_event("t1", series_event("c", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::SeriesLimitExceeded { ref task_id, limit: 2 } if task_id == "t1"
+    ));
+
+    // Existing series and keep-all series are not capped.
+    engine
+        .publish_event(
+            "t1",
+            series_event("a", SeriesMode::Latest, json!({ "v": 2 })),
+        )
+        .await
+        .unwrap();
+    engine
+        .publish_event("t1", series_event("log", SeriesMode::KeepAll, json!({})))
+        .await
+        .unwrap();
+    let series = engine.list_series("t1").await.unwrap();
+    assert_eq!(series_ids(&series), ["a", "b"]);
+}
+
+#[tokio::test]
+async fn a_rejected_series_event_leaves_no_index_gap() {
+    let engine = make_engine(capped(1, SeriesOverLimit::Reject));
+    create_running_task(&engine, "t1").await;
+    let first = engine
+        .publish_event("t1", series_event("a", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap();
+    engine
+        .publish_event("t1", series_event("b", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap_err();
+    let next = engine
+        .publish_event("t1", series_event("a", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap();
+    assert_eq!(next.index, first.index + 1);
+}
+
+#[tokio::test]
+async fn skip_mode_appends_the_event_without_series_state() {
+    let engine = make_engine(capped(1, SeriesOverLimit::SkipSeries));
+    create_running_task(&engine, "t1").await;
+    engine
+        .publish_event(
+            "t1",
+            series_event("a", SeriesMode::Accumulate, json!({ "delta": "x" })),
+        )
+        .await
+        .unwrap();
+
+    for text in ["1", "2"] {
+        let event = engine
+            .publish_event(
+                "t1",
+                series_event("b", SeriesMode::Accumulate, json!({ "delta": text })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(event.series_id.as_deref(), Some("b"));
+        assert_eq!(event.series_mode, None);
+    }
+
+    let events = engine.get_events("t1", None).await.unwrap();
+    let skipped: Vec<_> = events
+        .iter()
+        .filter(|e| e.series_id.as_deref() == Some("b"))
+        .map(|e| e.data.clone())
+        .collect();
+    assert_eq!(skipped, [json!({ "delta": "1" }), json!({ "delta": "2" })]);
+    let series = engine.list_series("t1").await.unwrap();
+    assert_eq!(series_ids(&series), ["a"]);
+}
+
+#[tokio::test]
+async fn under_the_cap_series_behave_as_before() {
+    let engine = make_engine(None);
+    create_running_task(&engine, "t1").await;
+    for text in ["he", "llo"] {
+        engine
+            .publish_event(
+                "t1",
+                series_event("acc", SeriesMode::Accumulate, json!({ "delta": text })),
+            )
+            .await
+            .unwrap();
+    }
+    let latest = engine
+        .short_term_store()
+        .get_series_latest("t1", "acc")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest.data, json!({ "delta": "hello" }));
+    assert_eq!(latest.series_mode, Some(SeriesMode::Accumulate));
+}
+
+// ─── Listing ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn list_series_covers_every_stateful_mode() {
+    let engine = make_engine(None);
+    create_running_task(&engine, "t1").await;
+    engine
+        .publish_event(
+            "t1",
+            series_event(
+                "patch",
+                SeriesMode::JsonPatch,
+                json!([
+                    { "op": "add", "path": "/n", "value": 1 }
+                ]),
+            ),
+        )
+        .await
+        .unwrap();
+    engine
+        .publish_event(
+            "t1",
+            series_event("acc", SeriesMode::Accumulate, json!({ "delta": "a" })),
+        )
+        .await
+        .unwrap();
+    engine
+        .publish_event("t1", series_event("log", SeriesMode::KeepAll, json!({})))
+        .await
+        .unwrap();
+    let last = engine
+        .publish_event("t1", series_event("status", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap();
+
+    let series = engine.list_series("t1").await.unwrap();
+    assert_eq!(series_ids(&series), ["acc", "patch", "status"]);
+    assert_eq!(series[0].mode, Some(SeriesMode::Accumulate));
+    assert_eq!(series[1].mode, Some(SeriesMode::JsonPatch));
+    assert_eq!(series[2].mode, Some(SeriesMode::Latest));
+    assert_eq!(series[2].latest_index, last.index);
+    assert_eq!(series[2].latest_timestamp, last.timestamp);
+    assert!(engine.list_series("missing").await.unwrap().is_empty());
+}
+
+// ─── Cleanup ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn deleting_a_series_events_frees_its_slot() {
+    let engine = make_engine(capped(1, SeriesOverLimit::Reject));
+    create_running_task(&engine, "t1").await;
+    engine
+        .publish_event("t1", series_event("a", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap();
+
+    engine
+        .delete_events(
+            "t1",
+            DeleteEventsInput {
+                filter: Some(DeleteEventsFilter {
+                    types: Some(vec!["llm.delta".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(engine.list_series("t1").await.unwrap().is_empty());
+
+    engine
+        .publish_event("t1", series_event("b", SeriesMode::Latest, json!({})))
+        .await
+        .unwrap();
+    let series = engine.list_series("t1").await.unwrap();
+    assert_eq!(series_ids(&series), ["b"]);
+}
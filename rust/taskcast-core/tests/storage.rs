@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use taskcast_core::{
+    DirectoryLimits, RemovalReason, RemovedFile, StorageError, StorageManager,
+    StorageManagerOptions, TaskcastHooks,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct RecordingHooks(Mutex<Vec<RemovedFile>>);
+
+impl TaskcastHooks for RecordingHooks {
+    fn on_storage_swept(&self, removed: &[RemovedFile]) {
+        self.0.lock().unwrap().extend_from_slice(removed);
+    }
+}
+
+fn make_manager(hooks: Option<Arc<RecordingHooks>>) -> StorageManager {
+    StorageManager::new(StorageManagerOptions {
+        hooks: hooks.map(|h| h as Arc<dyn TaskcastHooks>),
+        sweep_interval: Duration::from_secs(60),
+        min_file_age: Duration::ZERO,
+    })
+}
+
+/// Writes `bytes` bytes to `dir/name` with a modification time `age_secs`
+/// in the past.
+fn write_aged(dir: &Path, name: &str, bytes: usize, age_secs: u64) {
+    let path = dir.join(name);
+    fs::write(&path, vec![b'x'; bytes]).unwrap();
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+        .unwrap();
+}
+
+fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+// ─── Sweep ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn sweep_deletes_oldest_files_until_under_cap() {
+    let tmp = tempfile::tempdir().unwrap();
+    let hooks = Arc::new(RecordingHooks::default());
+    let manager = make_manager(Some(Arc::clone(&hooks)));
+    let limits = DirectoryLimits {
+        max_bytes: Some(250),
+        max_age: None,
+    };
+    manager.register("spool", tmp.path(), limits).unwrap();
+
+    write_aged(tmp.path(), "a", 100, 400);
+    write_aged(tmp.path(), "b", 100, 300);
+    write_aged(tmp.path(), "c", 100, 200);
+    write_aged(tmp.path(), "d", 100, 100);
+
+    let removed = manager.sweep();
+    assert_eq!(removed.len(), 2);
+    assert!(removed.iter().all(|r| r.reason == RemovalReason::Cap));
+    assert_eq!(names(tmp.path()), ["c", "d"]);
+    assert_eq!(*hooks.0.lock().unwrap(), removed);
+
+    // Under the cap now, so a second sweep is a no-op and fires no hook.
+    assert!(manager.sweep().is_empty());
+    assert_eq!(hooks.0.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn sweep_expires_files_older_than_max_age() {
+    let tmp = tempfile::tempdir().unwrap();
+    let manager = make_manager(None);
+    let limits = DirectoryLimits {
+        max_bytes: None,
+        max_age: Some(Duration::from_secs(3600)),
+    };
+    manager.register("exports", tmp.path(), limits).unwrap();
+
+    write_aged(tmp.path(), "old", 10, 7200);
+    write_aged(tmp.path(), "new", 10, 60);
+
+    let removed = manager.sweep();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].directory, "exports");
+    assert_eq!(removed[0].reason, RemovalReason::Age);
+    assert_eq!(removed[0].bytes, 10);
+    assert_eq!(names(tmp.path()), ["new"]);
+}
+
+#[test]
+fn sweep_skips_recent_and_locked_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let manager = StorageManager::new(StorageManagerOptions {
+        min_file_age: Duration::from_secs(120),
+        ..Default::default()
+    });
+    let limits = DirectoryLimits {
+        max_bytes: Some(0),
+        max_age: Some(Duration::from_secs(1)),
+    };
+    manager.register("blobs", tmp.path(), limits).unwrap();
+
+    write_aged(tmp.path(), "recent", 10, 30);
+    write_aged(tmp.path(), "held", 10, 600);
+    write_aged(tmp.path(), "held.lock", 0, 600);
+    write_aged(tmp.path(), "free", 10, 600);
+
+    let removed = manager.sweep();
+    assert_eq!(removed.len(), 1);
+    assert!(removed[0].path.ends_with("free"));
+    assert_eq!(names(tmp.path()), ["held", "held.lock", "recent"]);
+}
+
+// ─── Writes ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn write_is_refused_when_directory_is_at_cap() {
+    let tmp = tempfile::tempdir().unwrap();
+    let manager = make_manager(None);
+    let limits = DirectoryLimits {
+        max_bytes: Some(100),
+        max_age: None,
+    };
+    manager.register("spool", tmp.path(), limits).unwrap();
+
+    let path = manager.write("spool", "first", &[0; 60]).unwrap();
+    assert_eq!(fs::read(&path).unwrap().len(), 60);
+
+    let err = manager.write("spool", "second", &[0; 60]).unwrap_err();
+    match err {
+        StorageError::Full {
+            directory,
+            used_bytes,
+            max_bytes,
+        } => {
+            assert_eq!(directory, "spool");
+            assert_eq!(used_bytes, 60);
+            assert_eq!(max_bytes, 100);
+        }
+        other => panic!("expected Full, got {other:?}"),
+    }
+    assert_eq!(names(tmp.path()), ["first"]);
+
+    manager.write("spool", "small", &[0; 40]).unwrap();
+}
+
+#[test]
+fn write_rejects_unknown_directories_and_path_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let manager = make_manager(None);
+    manager
+        .register("spool", tmp.path(), DirectoryLimits::default())
+        .unwrap();
+
+    assert!(matches!(
+        manager.write("missing", "f", b"x"),
+        Err(StorageError::UnknownDirectory(_))
+    ));
+    for name in ["", "../escape", "a/b", ".hidden", "f.lock"] {
+        assert!(matches!(
+            manager.write("spool", name, b"x"),
+            Err(StorageError::InvalidFileName(_))
+        ));
+    }
+}
+
+// ─── Usage ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn usage_reports_bytes_files_and_utilization() {
+    let tmp = tempfile::tempdir().unwrap();
+    let manager = make_manager(None);
+    manager
+        .register(
+            "spool",
+            tmp.path().join("spool"),
+            DirectoryLimits {
+                max_bytes: Some(400),
+                max_age: Some(Duration::from_secs(60)),
+            },
+        )
+        .unwrap();
+    manager
+        .register(
+            "blobs",
+            tmp.path().join("blobs"),
+            DirectoryLimits::default(),
+        )
+        .unwrap();
+
+    let spool = tmp.path().join("spool");
+    write_aged(&spool, "a", 100, 500);
+    fs::create_dir(spool.join("nested")).unwrap();
+    write_aged(&spool.join("nested"), "b", 200, 100);
+
+    let usage = manager.usage().unwrap();
+    let names: Vec<&str> = usage.iter().map(|u| u.name.as_str()).collect();
+    assert_eq!(names, ["blobs", "spool"]);
+
+    let blobs = &usage[0];
+    assert_eq!((blobs.bytes, blobs.files), (0, 0));
+    assert_eq!(blobs.oldest_modified_at, None);
+    assert_eq!(blobs.utilization, None);
+
+    let spool = &usage[1];
+    assert_eq!((spool.bytes, spool.files), (300, 2));
+    assert_eq!(spool.max_bytes, Some(400));
+    assert_eq!(spool.max_age_ms, Some(60_000));
+    assert_eq!(spool.utilization, Some(0.75));
+    let oldest = spool.oldest_modified_at.unwrap();
+    let newest = spool.newest_modified_at.unwrap();
+    assert!(
+        (newest - oldest - 400_000.0).abs() < 2_000.0,
+        "{oldest} {newest}"
+    );
+}
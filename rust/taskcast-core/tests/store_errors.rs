@@ -0,0 +1,121 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use taskcast_core::{
+    BackoffStrategy, BroadcastProvider, CreateTaskInput, EngineError, ErrorContext,
+    MemoryBroadcastProvider, MemoryShortTermStore, RetryOn, RetryPolicy, StoreError,
+    StoreErrorKind, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus, TaskcastHooks,
+    RETRY_SCHEDULED_EVENT,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+const DRIVER_MESSAGE: &str = "OOM command not allowed when used memory > 'maxmemory'.\n\
+    key: taskcast:events:t1 script: redis.call('RPUSH', KEYS[1], ARGV[1])";
+
+/// Fails to publish retry announcements the way an adapter reports an
+/// unreachable backend.
+struct FailingRetryBroadcast(MemoryBroadcastProvider);
+
+#[async_trait::async_trait]
+impl BroadcastProvider for FailingRetryBroadcast {
+    async fn publish(
+        &self,
+        channel: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if event.r#type == RETRY_SCHEDULED_EVENT {
+            return Err(Box::new(StoreError::new(
+                StoreErrorKind::Unavailable,
+                DRIVER_MESSAGE,
+            )));
+        }
+        self.0.publish(channel, event).await
+    }
+
+    async fn subscribe(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.0.subscribe(channel, handler).await
+    }
+}
+
+/// Records each unhandled error with its whole `source()` chain.
+#[derive(Default)]
+struct ChainHooks {
+    errors: Mutex<Vec<Vec<String>>>,
+}
+
+impl TaskcastHooks for ChainHooks {
+    fn on_unhandled_error(&self, err: &(dyn Error + Send + Sync), _context: &ErrorContext) {
+        let mut chain = vec![err.to_string()];
+        let mut next = err.source();
+        while let Some(source) = next {
+            chain.push(source.to_string());
+            next = source.source();
+        }
+        self.errors.lock().unwrap().push(chain);
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn unhandled_error_hook_receives_the_full_driver_error() {
+    let hooks = Arc::new(ChainHooks::default());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(FailingRetryBroadcast(MemoryBroadcastProvider::new())),
+        long_term_store: None,
+        hooks: Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            retry_policy: Some(RetryPolicy {
+                max_attempts: 2,
+                backoff: BackoffStrategy::Fixed,
+                initial_delay_ms: 1_000,
+                max_delay_ms: 1_000,
+                retry_on: vec![RetryOn::Failed],
+            }),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Failed, None)
+        .await
+        .unwrap();
+
+    let errors = hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    let chain = &errors[0];
+    // The displayed error is the bounded summary...
+    assert!(!chain[0].contains('\n'), "{chain:?}");
+    assert!(chain[0].starts_with("Store error (unavailable): OOM"));
+    // ...and the driver's message is one `source()` away.
+    assert_eq!(chain.last().unwrap(), DRIVER_MESSAGE);
+}
+
+#[test]
+fn engine_store_errors_display_the_bounded_detail() {
+    let message = format!("ERROR: relation \"taskcast_tasks\"\n{}", "x".repeat(1_000));
+    let err = EngineError::Store(Box::new(StoreError::new(
+        StoreErrorKind::Other,
+        message.as_str(),
+    )));
+    let shown = err.to_string();
+    assert!(!shown.contains('\n'));
+    assert!(shown.chars().count() < 250, "{shown}");
+}
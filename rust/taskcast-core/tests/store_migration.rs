@@ -0,0 +1,233 @@
+//! Moving tasks between two memory short-term stores with
+//! `MigratingShortTermStore`, across dual-write and cutover.
+
+use std::sync::Arc;
+
+use serde_json::json;
+use taskcast_core::{
+    BackfillProgress, CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore,
+    MigratingShortTermStore, MigrationMode, PublishEventInput, SeriesMode, ShortTermStore,
+    TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
+};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(store: Arc<dyn ShortTermStore>) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+async fn create_running_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, text: &str) {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: "llm.delta".to_string(),
+                level: Level::Info,
+                data: json!({ "text": text }),
+                series_id: Some("reply".to_string()),
+                series_mode: Some(SeriesMode::Accumulate),
+                series_acc_field: Some("text".to_string()),
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+fn indices(events: &[TaskEvent]) -> Vec<u64> {
+    events.iter().map(|event| event.index).collect()
+}
+
+/// An old store holding `task_ids`, each running with two events.
+async fn seeded_old_store(task_ids: &[&str]) -> Arc<MemoryShortTermStore> {
+    let old = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(old.clone());
+    for task_id in task_ids {
+        create_running_task(&engine, task_id).await;
+        publish(&engine, task_id, "Hel").await;
+        publish(&engine, task_id, "lo").await;
+    }
+    old
+}
+
+// ─── Dual Write ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn lifecycle_across_a_mode_switch_keeps_indices_and_history() {
+    let old = seeded_old_store(&["t1"]).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = Arc::new(MigratingShortTermStore::new(old.clone(), new.clone()));
+    let engine = make_engine(store.clone());
+    let before = engine.get_events("t1", None).await.unwrap();
+
+    publish(&engine, "t1", ", wor").await;
+    store.set_mode(MigrationMode::Cutover);
+    publish(&engine, "t1", "ld").await;
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    let events = engine.get_events("t1", None).await.unwrap();
+    let expected: Vec<u64> = (0..events.len() as u64).collect();
+    assert_eq!(indices(&events), expected);
+    assert_eq!(events[..before.len()], before[..]);
+    assert_eq!(new.get_events("t1", None).await.unwrap(), events);
+
+    let latest = new.get_series_latest("t1", "reply").await.unwrap().unwrap();
+    assert_eq!(latest.data, json!({ "text": "Hello, world" }));
+    // Mirrored while dual-writing, left alone after the cutover.
+    let old_latest = old.get_series_latest("t1", "reply").await.unwrap().unwrap();
+    assert_eq!(old_latest.data, json!({ "text": "Hello, wor" }));
+    assert_eq!(
+        old.get_task("t1").await.unwrap().unwrap().status,
+        TaskStatus::Running
+    );
+}
+
+#[tokio::test]
+async fn first_write_raises_the_new_counter_to_the_old_one() {
+    let old = seeded_old_store(&["t1"]).await;
+    // Indices handed out without an event stored for them.
+    old.next_index("t1").await.unwrap();
+    old.next_index("t1").await.unwrap();
+    let old_count = old.event_count("t1").await.unwrap();
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = MigratingShortTermStore::new(old.clone(), new.clone());
+
+    assert_eq!(store.next_index("t1").await.unwrap(), old_count);
+    assert_eq!(new.event_count("t1").await.unwrap(), old_count + 1);
+    assert_eq!(old.event_count("t1").await.unwrap(), old_count + 1);
+}
+
+// ─── Reads ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn lazy_backfill_copies_a_task_on_first_read() {
+    let old = seeded_old_store(&["t1"]).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = MigratingShortTermStore::new(old.clone(), new.clone()).with_lazy_backfill(true);
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events, old.get_events("t1", None).await.unwrap());
+    assert_eq!(new.get_events("t1", None).await.unwrap(), events);
+    assert_eq!(
+        new.get_task("t1").await.unwrap(),
+        old.get_task("t1").await.unwrap()
+    );
+    assert_eq!(store.migrated_tasks(), 1);
+}
+
+#[tokio::test]
+async fn reads_fall_back_to_the_old_store_without_lazy_backfill() {
+    let old = seeded_old_store(&["t1"]).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = MigratingShortTermStore::new(old.clone(), new.clone());
+
+    assert!(store.get_task("t1").await.unwrap().is_some());
+    assert_eq!(
+        store.get_events("t1", None).await.unwrap(),
+        old.get_events("t1", None).await.unwrap()
+    );
+    assert!(new.get_task("t1").await.unwrap().is_none());
+    assert!(new.get_events("t1", None).await.unwrap().is_empty());
+}
+
+// ─── Backfill ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn backfill_all_is_idempotent() {
+    let task_ids = ["t1", "t2", "t3"];
+    let old = seeded_old_store(&task_ids).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = MigratingShortTermStore::new(old.clone(), new.clone());
+
+    let mut reports = Vec::new();
+    let first = store
+        .backfill_all(|progress| reports.push(progress.clone()))
+        .await
+        .unwrap();
+    assert_eq!(
+        first,
+        BackfillProgress {
+            scanned: 3,
+            copied: 3,
+            skipped: 0,
+            failed: 0,
+            done: true,
+        }
+    );
+    assert_eq!(reports.last(), Some(&first));
+    assert_eq!(store.backfill_progress(), Some(first));
+
+    let second = store.backfill_all(|_| {}).await.unwrap();
+    assert_eq!((second.copied, second.skipped), (0, 3));
+
+    for task_id in task_ids {
+        assert_eq!(
+            new.get_events(task_id, None).await.unwrap(),
+            old.get_events(task_id, None).await.unwrap()
+        );
+        assert_eq!(
+            new.event_count(task_id).await.unwrap(),
+            old.event_count(task_id).await.unwrap()
+        );
+        assert_eq!(
+            new.get_series_latest(task_id, "reply").await.unwrap(),
+            old.get_series_latest(task_id, "reply").await.unwrap()
+        );
+    }
+}
+
+// ─── Cutover ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn cutover_leaves_the_old_store_untouched() {
+    let old = seeded_old_store(&["t1"]).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let store = Arc::new(MigratingShortTermStore::new(old.clone(), new.clone()));
+    store.backfill_all(|_| {}).await.unwrap();
+    store.set_mode(MigrationMode::Cutover);
+    let old_events = old.get_events("t1", None).await.unwrap();
+    let old_task = old.get_task("t1").await.unwrap();
+
+    let engine = make_engine(store.clone());
+    publish(&engine, "t1", ", world").await;
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    create_running_task(&engine, "t2").await;
+
+    assert_eq!(old.get_events("t1", None).await.unwrap(), old_events);
+    assert_eq!(old.get_task("t1").await.unwrap(), old_task);
+    assert!(old.get_task("t2").await.unwrap().is_none());
+    assert_eq!(
+        new.get_task("t1").await.unwrap().unwrap().status,
+        TaskStatus::Completed
+    );
+}
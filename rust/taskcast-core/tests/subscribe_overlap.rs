@@ -0,0 +1,147 @@
+//! Subscribers connecting while events are published, through
+//! `TaskEngine::subscribe_stream` and `TaskEngine::subscribe_stream_with_replay`:
+//! each must receive every event exactly once, whichever side of its history
+//! read the event was published on.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    ReplayBudget, ReplayOptions, StreamItem, SubscribeFilter, TaskEngine, TaskEngineOptions,
+    TaskEventStream, TaskStatus,
+};
+
+const EVENTS: u64 = 1500;
+const SUBSCRIBERS: usize = 40;
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn log(i: u64) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!({ "i": i }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+async fn connect(engine: &Arc<TaskEngine>, task_id: &str, n: usize) -> TaskEventStream {
+    let filter = SubscribeFilter::default();
+    match n % 3 {
+        0 => engine.subscribe_stream(task_id, filter).await.unwrap(),
+        1 => engine
+            .subscribe_stream(task_id, filter)
+            .await
+            .unwrap()
+            .in_store_order(),
+        _ => engine
+            .subscribe_stream_with_replay(
+                task_id,
+                filter,
+                ReplayOptions {
+                    budget: ReplayBudget::unlimited(),
+                    chunk_size: 16,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap(),
+    }
+}
+
+/// Raw indices of the events `stream` yields until it ends, which it must
+/// do with a done item.
+async fn drain(mut stream: TaskEventStream) -> Vec<u64> {
+    let mut indices = Vec::new();
+    loop {
+        match stream.next().await {
+            Some(StreamItem::Event(envelope)) => indices.push(envelope.raw_index),
+            Some(StreamItem::Done(_)) => return indices,
+            Some(other) => panic!("unexpected item: {other:?}"),
+            None => panic!("stream ended without a done item"),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn subscribers_connecting_under_load_miss_and_repeat_nothing() {
+    let engine = make_engine();
+    let task = engine
+        .create_task(CreateTaskInput::default())
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    let publisher = {
+        let engine = Arc::clone(&engine);
+        let task_id = task.id.clone();
+        tokio::spawn(async move {
+            for i in 0..EVENTS {
+                engine.publish_event(&task_id, log(i)).await.unwrap();
+            }
+            engine
+                .transition_task(&task_id, TaskStatus::Completed, None)
+                .await
+                .unwrap();
+        })
+    };
+
+    // Connections are spread over the whole run, including the completion.
+    let mut subscribers = Vec::new();
+    for n in 0..SUBSCRIBERS {
+        let stream = connect(&engine, &task.id, n).await;
+        subscribers.push(tokio::spawn(drain(stream)));
+        tokio::time::sleep(Duration::from_micros(500)).await;
+    }
+    publisher.await.unwrap();
+    for n in 0..3 {
+        let stream = connect(&engine, &task.id, n).await;
+        subscribers.push(tokio::spawn(drain(stream)));
+    }
+
+    // Status events for running and completed surround the published ones.
+    let last = EVENTS + 1;
+    for (n, subscriber) in subscribers.into_iter().enumerate() {
+        let indices = tokio::time::timeout(Duration::from_secs(10), subscriber)
+            .await
+            .unwrap_or_else(|_| panic!("subscriber {n} never finished"))
+            .unwrap();
+        let unique: BTreeSet<u64> = indices.iter().copied().collect();
+        assert_eq!(unique.len(), indices.len(), "subscriber {n} got duplicates");
+        let first = *unique.first().unwrap();
+        assert_eq!(first, 0, "subscriber {n} replayed from {first}");
+        assert_eq!(
+            unique,
+            (first..=last).collect::<BTreeSet<u64>>(),
+            "subscriber {n} has gaps"
+        );
+        if n % 3 == 1 {
+            assert!(
+                indices.windows(2).all(|pair| pair[0] < pair[1]),
+                "subscriber {n} out of store order"
+            );
+        }
+    }
+}
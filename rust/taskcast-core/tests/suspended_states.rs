@@ -2,9 +2,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use taskcast_core::{
-    BlockedRequest, BroadcastProvider, CreateTaskInput, MemoryBroadcastProvider,
-    MemoryShortTermStore, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
-    TransitionPayload,
+    task_channel, BlockedRequest, BroadcastProvider, CreateTaskInput, MemoryBroadcastProvider,
+    MemoryShortTermStore, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus, TransitionPayload,
 };
 use tokio::sync::Mutex;
 
@@ -19,6 +18,9 @@ fn make_engine_with_capture() -> (TaskEngine, Arc<MemoryBroadcastProvider>, Arc<
         broadcast: broadcast.clone(),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     });
     (engine, broadcast, events)
 }
@@ -29,6 +31,9 @@ fn make_engine() -> TaskEngine {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     })
 }
 
@@ -185,7 +190,7 @@ async fn blocked_with_blocked_request_emits_blocked_event() {
     let events_clone = events.clone();
     let _unsub = broadcast
         .subscribe(
-            "t5",
+            &task_channel("t5"),
             Box::new(move |event| {
                 let events_clone = events_clone.clone();
                 tokio::spawn(async move {
@@ -262,7 +267,7 @@ async fn blocked_to_running_emits_resolved_event() {
     let events_clone = events.clone();
     let _unsub = broadcast
         .subscribe(
-            "t6",
+            &task_channel("t6"),
             Box::new(move |event| {
                 let events_clone = events_clone.clone();
                 tokio::spawn(async move {
@@ -408,7 +413,7 @@ async fn blocked_without_blocked_request_does_not_emit_blocked_event() {
     let events_clone = events.clone();
     let _unsub = broadcast
         .subscribe(
-            "t9",
+            &task_channel("t9"),
             Box::new(move |event| {
                 let events_clone = events_clone.clone();
                 tokio::spawn(async move {
@@ -464,7 +469,7 @@ async fn blocked_to_running_without_blocked_request_does_not_emit_resolved() {
     let events_clone = events.clone();
     let _unsub = broadcast
         .subscribe(
-            "t10",
+            &task_channel("t10"),
             Box::new(move |event| {
                 let events_clone = events_clone.clone();
                 tokio::spawn(async move {
@@ -527,6 +532,9 @@ async fn paused_to_blocked_restarts_ttl() {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     });
 
     // Create task with TTL, move to running, then to paused
@@ -570,6 +578,9 @@ async fn blocked_to_paused_clears_ttl() {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     });
 
     engine
@@ -0,0 +1,397 @@
+//! All-or-nothing task groups via `TaskEngine::create_task_group`.
+
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use taskcast_core::{
+    CompletionMode, CompletionPolicy, CreateTaskGroupInput, CreateTaskInput, EngineError,
+    ErrorContext, EventQueryOptions, MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore,
+    StoreError, StoreErrorKind, Task, TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter,
+    TaskcastHooks, Worker, WorkerAssignment, WorkerFilter,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+/// Memory short-term store without its own group write, so groups are
+/// staged one task at a time, that fails the `fail_on`-th task write and,
+/// with `fail_deletes`, every delete.
+struct FlakyStore {
+    inner: MemoryShortTermStore,
+    fail_on: usize,
+    fail_deletes: bool,
+    writes: AtomicUsize,
+}
+
+impl FlakyStore {
+    fn new(fail_on: usize, fail_deletes: bool) -> Self {
+        Self {
+            inner: MemoryShortTermStore::new(),
+            fail_on,
+            fail_deletes,
+            writes: AtomicUsize::new(0),
+        }
+    }
+}
+
+fn unavailable(message: &str) -> Box<dyn Error + Send + Sync> {
+    Box::new(StoreError::new(StoreErrorKind::Unavailable, message))
+}
+
+#[async_trait]
+impl ShortTermStore for FlakyStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.writes.fetch_add(1, Ordering::SeqCst) + 1 == self.fail_on {
+            return Err(unavailable("connection reset"));
+        }
+        self.inner.save_task(task).await
+    }
+
+    async fn delete_task(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.fail_deletes {
+            return Err(unavailable("connection refused"));
+        }
+        self.inner.delete_task(task_id).await
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<Option<Task>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(&self, task_id: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(&self, worker: Worker) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(&self, worker_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+/// Records the operation of each unhandled error.
+#[derive(Default)]
+struct RecordingHooks {
+    errors: Mutex<Vec<(String, String)>>,
+}
+
+impl TaskcastHooks for RecordingHooks {
+    fn on_unhandled_error(&self, err: &(dyn Error + Send + Sync), context: &ErrorContext) {
+        self.errors
+            .lock()
+            .unwrap()
+            .push((context.operation.clone(), err.to_string()));
+    }
+}
+
+fn make_engine(
+    store: Arc<dyn ShortTermStore>,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+fn task(id: &str) -> CreateTaskInput {
+    CreateTaskInput {
+        id: Some(id.to_string()),
+        ..Default::default()
+    }
+}
+
+/// `deploy` with children `build`, `test` and `ship`.
+fn deploy_group() -> CreateTaskGroupInput {
+    CreateTaskGroupInput {
+        parent: CreateTaskInput {
+            completion_policy: Some(CompletionPolicy {
+                mode: CompletionMode::AllChildren,
+                child_ids: Vec::new(),
+            }),
+            ..task("deploy")
+        },
+        children: vec![task("build"), task("test"), task("ship")],
+        link_children: true,
+    }
+}
+
+async fn stored_ids(store: &dyn ShortTermStore) -> Vec<String> {
+    let mut ids: Vec<String> = store
+        .list_tasks(TaskFilter::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn linked_children_are_awaited_by_the_parent() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+
+    let group = engine.create_task_group(deploy_group()).await.unwrap();
+
+    assert_eq!(group.parent.id, "deploy");
+    assert_eq!(
+        group.parent.completion_policy.unwrap().child_ids,
+        vec!["build", "test", "ship"]
+    );
+    let child_ids: Vec<&str> = group.children.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(child_ids, vec!["build", "test", "ship"]);
+    for id in child_ids {
+        let child = engine.get_task(id).await.unwrap().unwrap();
+        assert_eq!(child.parent_id.as_deref(), Some("deploy"));
+    }
+}
+
+#[tokio::test]
+async fn unlinked_children_have_no_parent() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+
+    let group = engine
+        .create_task_group(CreateTaskGroupInput {
+            parent: task("batch"),
+            children: vec![task("a"), task("b")],
+            link_children: false,
+        })
+        .await
+        .unwrap();
+
+    assert!(group.parent.completion_policy.is_none());
+    assert!(group.children.iter().all(|child| child.parent_id.is_none()));
+}
+
+#[tokio::test]
+async fn failed_child_write_leaves_no_task_behind() {
+    // The parent, then `build`, then `test`: the third write fails.
+    let store = Arc::new(FlakyStore::new(3, false));
+    let engine = make_engine(Arc::clone(&store) as Arc<dyn ShortTermStore>, None);
+
+    let err = engine.create_task_group(deploy_group()).await.unwrap_err();
+
+    assert!(matches!(err, EngineError::Store(_)), "{err}");
+    assert!(stored_ids(store.as_ref()).await.is_empty());
+}
+
+#[tokio::test]
+async fn failed_compensation_names_the_tasks_that_may_remain() {
+    let hooks = Arc::new(RecordingHooks::default());
+    let store = Arc::new(FlakyStore::new(3, true));
+    let engine = make_engine(
+        Arc::clone(&store) as Arc<dyn ShortTermStore>,
+        Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+    );
+
+    let err = engine.create_task_group(deploy_group()).await.unwrap_err();
+
+    let EngineError::PartialTaskGroup(partial) = err else {
+        panic!("expected a partial task group, got {err}");
+    };
+    assert_eq!(partial.task_ids, vec!["deploy", "build"]);
+    assert_eq!(stored_ids(store.as_ref()).await, vec!["build", "deploy"]);
+    let errors = hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, "task_group.compensate");
+    assert!(errors[0].1.contains("deploy, build"), "{}", errors[0].1);
+}
+
+#[tokio::test]
+async fn taken_child_id_creates_nothing() {
+    for store in [
+        Arc::new(MemoryShortTermStore::new()) as Arc<dyn ShortTermStore>,
+        Arc::new(FlakyStore::new(0, false)),
+    ] {
+        let engine = make_engine(Arc::clone(&store), None);
+        engine.create_task(task("test")).await.unwrap();
+
+        let err = engine.create_task_group(deploy_group()).await.unwrap_err();
+
+        assert!(
+            matches!(err, EngineError::TaskAlreadyExists { ref task_id, .. } if task_id == "test"),
+            "{err}"
+        );
+        assert_eq!(stored_ids(store.as_ref()).await, vec!["test"]);
+    }
+}
+
+#[tokio::test]
+async fn concurrent_identical_groups_do_not_interleave() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = Arc::new(make_engine(
+        Arc::clone(&store) as Arc<dyn ShortTermStore>,
+        None,
+    ));
+
+    let attempts = (0..8).map(|attempt| {
+        let engine = Arc::clone(&engine);
+        tokio::spawn(async move {
+            let mut input = deploy_group();
+            for member in std::iter::once(&mut input.parent).chain(&mut input.children) {
+                member.metadata = Some([("attempt".to_string(), attempt.into())].into());
+            }
+            engine.create_task_group(input).await
+        })
+    });
+    let results = futures::future::join_all(attempts).await;
+
+    let created: Vec<_> = results
+        .into_iter()
+        .filter_map(|result| result.unwrap().ok())
+        .collect();
+    assert_eq!(created.len(), 1);
+    let winner = &created[0].parent.metadata.as_ref().unwrap()["attempt"];
+    for task in store.list_tasks(TaskFilter::default()).await.unwrap() {
+        assert_eq!(&task.metadata.unwrap()["attempt"], winner, "{}", task.id);
+    }
+    assert_eq!(
+        stored_ids(store.as_ref()).await,
+        vec!["build", "deploy", "ship", "test"]
+    );
+}
+
+#[tokio::test]
+async fn members_may_not_name_a_parent() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+    engine.create_task(task("root")).await.unwrap();
+
+    let mut input = deploy_group();
+    input.children[1].parent_id = Some("root".to_string());
+    let err = engine.create_task_group(input).await.unwrap_err();
+
+    assert!(matches!(err, EngineError::InvalidInput(_)), "{err}");
+    assert!(engine.get_task("deploy").await.unwrap().is_none());
+}
@@ -0,0 +1,148 @@
+//! Per-task bookkeeping is released over a task's lifecycle: a few thousand
+//! short-lived tasks driven through create → complete → delete leave the
+//! engine's and the memory adapters' maps as they found them.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, MemoryStoreSizes,
+    PublishEventInput, SeriesMode, TaskEngine, TaskEngineOptions, TaskLifecycleEvent, TaskStatus,
+};
+
+struct Harness {
+    engine: TaskEngine,
+    store: Arc<MemoryShortTermStore>,
+    broadcast: Arc<MemoryBroadcastProvider>,
+}
+
+fn make_harness() -> Harness {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let broadcast = Arc::new(MemoryBroadcastProvider::new());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: store.clone(),
+        broadcast: broadcast.clone(),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    Harness {
+        engine,
+        store,
+        broadcast,
+    }
+}
+
+fn event(r#type: &str, series_id: Option<&str>) -> PublishEventInput {
+    PublishEventInput {
+        r#type: r#type.to_string(),
+        level: Level::Info,
+        data: json!({ "text": "a" }),
+        series_id: series_id.map(str::to_string),
+        series_mode: series_id.map(|_| SeriesMode::Accumulate),
+        series_acc_field: series_id.map(|_| "text".to_string()),
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+async fn run_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let unsubscribe = engine.subscribe(task_id, Box::new(|_| {})).await;
+    engine
+        .publish_event(task_id, event("progress", None))
+        .await
+        .unwrap();
+    engine
+        .publish_event(task_id, event("llm.delta", Some("output")))
+        .await
+        .unwrap();
+    unsubscribe();
+    engine
+        .transition_task(task_id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn short_lived_tasks_leave_no_bookkeeping_behind() {
+    let harness = make_harness();
+
+    for i in 0..2_000 {
+        let task_id = format!("task-{i}");
+        run_task(&harness.engine, &task_id).await;
+        assert!(harness.engine.delete_task(&task_id).await.unwrap());
+    }
+
+    assert_eq!(harness.store.sizes(), MemoryStoreSizes::default());
+    assert_eq!(harness.broadcast.channel_count(), 0);
+    assert_eq!(harness.engine.emit_lock_count(), 0);
+}
+
+#[tokio::test]
+async fn completed_tasks_keep_their_store_state_until_deleted() {
+    let harness = make_harness();
+
+    for i in 0..100 {
+        run_task(&harness.engine, &format!("task-{i}")).await;
+    }
+    // The emit locks go with the terminal transition; stored state stays.
+    assert_eq!(harness.engine.emit_lock_count(), 0);
+    assert_eq!(harness.broadcast.channel_count(), 0);
+    let sizes = harness.store.sizes();
+    assert_eq!(sizes.tasks, 100);
+    assert_eq!(sizes.index_counters, 100);
+    assert_eq!(sizes.series_latest, 100);
+
+    for i in 0..100 {
+        harness.engine.expire_task(&format!("task-{i}")).await.unwrap();
+    }
+    assert_eq!(harness.store.sizes(), MemoryStoreSizes::default());
+}
+
+#[tokio::test]
+async fn lifecycle_listeners_see_terminal_deletion_and_expiry() {
+    let harness = make_harness();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&seen);
+    harness
+        .engine
+        .lifecycle()
+        .subscribe(Arc::new(move |task_id, event| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((task_id.to_string(), event.clone()));
+        }));
+
+    run_task(&harness.engine, "a").await;
+    assert!(harness.engine.delete_task("a").await.unwrap());
+    assert!(!harness.engine.delete_task("a").await.unwrap());
+    harness.engine.expire_task("b").await.unwrap();
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            (
+                "a".to_string(),
+                TaskLifecycleEvent::Terminal(TaskStatus::Completed)
+            ),
+            ("a".to_string(), TaskLifecycleEvent::Deleted),
+            ("b".to_string(), TaskLifecycleEvent::Expired),
+        ]
+    );
+    assert!(harness.engine.get_task("a").await.unwrap().is_none());
+}
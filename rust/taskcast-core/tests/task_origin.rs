@@ -0,0 +1,164 @@
+//! Task origins: how they are stamped at creation, kept across updates, and
+//! used to filter task lists and the outcomes index.
+
+use std::sync::Arc;
+
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, OutcomeQuery, ShortTermStore,
+    Task, TaskEngine, TaskEngineOptions, TaskFilter, TaskOrigin, TaskOriginKind, TaskStatus,
+};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(store: Arc<MemoryShortTermStore>) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+async fn create(engine: &TaskEngine, task_id: &str, origin: Option<TaskOrigin>) -> Task {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            origin,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+fn ids(tasks: &[Task]) -> Vec<&str> {
+    let mut ids: Vec<&str> = tasks.iter().map(|task| task.id.as_str()).collect();
+    ids.sort();
+    ids
+}
+
+// ─── Creation ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn tasks_are_created_with_a_user_origin_unless_given_one() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+
+    let task = create(&engine, "t1", None).await;
+    assert_eq!(task.origin, Some(TaskOrigin::new(TaskOriginKind::User)));
+
+    let scheduled = TaskOrigin::new(TaskOriginKind::Schedule).with_detail("nightly");
+    let task = create(&engine, "t2", Some(scheduled.clone())).await;
+    assert_eq!(task.origin, Some(scheduled.clone()));
+    assert_eq!(
+        engine.get_task("t2").await.unwrap().unwrap().origin,
+        Some(scheduled)
+    );
+}
+
+#[tokio::test]
+async fn origin_survives_updates() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    let origin = TaskOrigin::new(TaskOriginKind::Replay).with_source_task_id("t0");
+    create(&engine, "t1", Some(origin.clone())).await;
+
+    let updated = engine
+        .update_task("t1", |task| {
+            task.origin = Some(TaskOrigin::new(TaskOriginKind::User));
+        })
+        .await
+        .unwrap();
+    assert_eq!(updated.origin, Some(origin.clone()));
+
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        engine.get_task("t1").await.unwrap().unwrap().origin,
+        Some(origin)
+    );
+}
+
+// ─── Filtering ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn list_filters_by_origin_kind() {
+    let store = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(store.clone());
+    create(&engine, "user", None).await;
+    create(
+        &engine,
+        "retry",
+        Some(TaskOrigin::new(TaskOriginKind::Retry).with_source_task_id("user")),
+    )
+    .await;
+    create(
+        &engine,
+        "system",
+        Some(TaskOrigin::new(TaskOriginKind::System)),
+    )
+    .await;
+    // Saved before origins were recorded.
+    let mut legacy = engine.get_task("user").await.unwrap().unwrap();
+    legacy.id = "legacy".to_string();
+    legacy.origin = None;
+    store.save_task(legacy).await.unwrap();
+
+    let list = |kinds: Vec<TaskOriginKind>| {
+        engine.list_tasks(TaskFilter {
+            origin: Some(kinds),
+            ..Default::default()
+        })
+    };
+    assert_eq!(
+        ids(&list(vec![TaskOriginKind::Retry]).await.unwrap()),
+        ["retry"]
+    );
+    assert_eq!(
+        ids(&list(vec![TaskOriginKind::User]).await.unwrap()),
+        ["legacy", "user"]
+    );
+    assert_eq!(
+        ids(&list(vec![TaskOriginKind::Retry, TaskOriginKind::System])
+            .await
+            .unwrap()),
+        ["retry", "system"]
+    );
+}
+
+// ─── Outcomes ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn outcomes_record_the_origin_kind() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    for (task_id, origin) in [
+        ("t1", None),
+        ("t2", Some(TaskOrigin::new(TaskOriginKind::Retry))),
+    ] {
+        create(&engine, task_id, origin).await;
+        engine
+            .transition_task(task_id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+        engine
+            .transition_task(task_id, TaskStatus::Completed, None)
+            .await
+            .unwrap();
+    }
+
+    let mut outcomes = engine
+        .list_outcomes(&OutcomeQuery {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    outcomes.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+    let origins: Vec<_> = outcomes.iter().map(|outcome| outcome.origin).collect();
+    assert_eq!(
+        origins,
+        [Some(TaskOriginKind::User), Some(TaskOriginKind::Retry)]
+    );
+}
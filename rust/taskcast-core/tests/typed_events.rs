@@ -0,0 +1,173 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, DecodeError, Level, LogEvent, MemoryBroadcastProvider, MemoryShortTermStore,
+    ProgressEvent, PublishEventInput, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+
+fn make_engine() -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+}
+
+async fn create_running_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+fn progress(percent: f64, message: &str) -> ProgressEvent {
+    ProgressEvent {
+        percent,
+        message: Some(message.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn publish_typed_round_trips_through_history() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+
+    let event = engine
+        .publish_typed::<ProgressEvent>("t1", progress(40.0, "halfway-ish"), Level::Info)
+        .await
+        .unwrap();
+    assert_eq!(event.r#type, "progress");
+    assert_eq!(
+        event.data,
+        json!({ "percent": 40.0, "message": "halfway-ish" })
+    );
+    engine
+        .publish_typed::<ProgressEvent>(
+            "t1",
+            ProgressEvent {
+                percent: 100.0,
+                message: None,
+            },
+            Level::Info,
+        )
+        .await
+        .unwrap();
+
+    let read: Vec<ProgressEvent> = engine
+        .get_typed_events::<ProgressEvent>("t1", None)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        read,
+        [
+            progress(40.0, "halfway-ish"),
+            ProgressEvent {
+                percent: 100.0,
+                message: None,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn wrong_shape_surfaces_decode_error() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+
+    engine
+        .publish_event(
+            "t1",
+            PublishEventInput {
+                r#type: "progress".to_string(),
+                level: Level::Info,
+                data: json!({ "percent": "half" }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+    engine
+        .publish_typed::<ProgressEvent>("t1", progress(60.0, "ok"), Level::Info)
+        .await
+        .unwrap();
+
+    let read = engine
+        .get_typed_events::<ProgressEvent>("t1", None)
+        .await
+        .unwrap();
+    assert_eq!(read.len(), 2);
+    match &read[0] {
+        Err(DecodeError::Data { event_type, .. }) => assert_eq!(*event_type, "progress"),
+        other => panic!("expected a data decode error, got {other:?}"),
+    }
+    assert_eq!(read[1].as_ref().unwrap(), &progress(60.0, "ok"));
+}
+
+#[tokio::test]
+async fn type_filter_only_yields_matching_events() {
+    let engine = make_engine();
+    create_running_task(&engine, "t1").await;
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    let unsubscribe = engine
+        .subscribe_typed::<LogEvent>(
+            "t1",
+            Box::new(move |log| sink.lock().unwrap().push(log.unwrap())),
+        )
+        .await;
+
+    let log = LogEvent {
+        text: "compiling".to_string(),
+        component: Some("build".to_string()),
+    };
+    engine
+        .publish_typed::<ProgressEvent>("t1", progress(10.0, "start"), Level::Info)
+        .await
+        .unwrap();
+    engine
+        .publish_typed::<LogEvent>("t1", log.clone(), Level::Debug)
+        .await
+        .unwrap();
+    unsubscribe();
+
+    assert_eq!(*received.lock().unwrap(), std::slice::from_ref(&log));
+
+    let logs = engine
+        .get_typed_events::<LogEvent>("t1", None)
+        .await
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].as_ref().unwrap(), &log);
+
+    // Decoding directly checks the type as well.
+    let history = engine.get_events("t1", None).await.unwrap();
+    let progress_event = history.iter().find(|e| e.r#type == "progress").unwrap();
+    assert!(matches!(
+        taskcast_core::decode_event::<LogEvent>(progress_event),
+        Err(DecodeError::WrongType {
+            expected: "log",
+            ..
+        })
+    ));
+}
@@ -31,6 +31,9 @@ fn make_context() -> TestContext {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -455,6 +458,9 @@ async fn publish_event_while_task_is_assigned_succeeds() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -508,6 +514,9 @@ async fn multiple_events_during_assigned_maintain_order() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -579,6 +588,9 @@ async fn event_ordering_across_claim_decline_reclaim_cycle() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -594,6 +606,9 @@ async fn event_ordering_across_claim_decline_reclaim_cycle() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -615,6 +630,9 @@ async fn event_ordering_across_claim_decline_reclaim_cycle() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -630,6 +648,9 @@ async fn event_ordering_across_claim_decline_reclaim_cycle() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -28,6 +28,9 @@ fn make_context() -> TestContext {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -66,6 +69,9 @@ async fn concurrent_wait_for_task_exactly_one_wins() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
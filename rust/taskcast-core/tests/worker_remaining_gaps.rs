@@ -38,6 +38,9 @@ fn make_context() -> TestContext {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -222,6 +225,9 @@ fn make_context_with_long_term(
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: Some(Arc::clone(&long_term_store) as Arc<dyn LongTermStore>),
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -242,6 +248,9 @@ fn make_context_with_hooks(hooks: Arc<dyn TaskcastHooks>) -> TestContext {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: Some(Arc::clone(&hooks)),
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -1207,6 +1216,9 @@ async fn panicking_hook_on_claim_propagates() {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: Some(Arc::clone(&hooks)),
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
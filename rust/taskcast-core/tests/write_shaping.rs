@@ -0,0 +1,252 @@
+//! Token-bucket shaping of long-term event writes.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, EventQueryOptions, Level, LongTermStore, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, Task, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskStatus, TaskcastHooks, WorkerAuditEvent, WriteOverflow, WriteShapingConfig,
+};
+
+// ─── Recording store ─────────────────────────────────────────────────────────
+
+/// Long-term store that records when each task and `log` event was saved.
+#[derive(Default)]
+struct RecordingLongTermStore {
+    task_saves: Mutex<Vec<(TaskStatus, Instant)>>,
+    log_saves: Mutex<Vec<Instant>>,
+}
+
+#[async_trait]
+impl LongTermStore for RecordingLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.task_saves
+            .lock()
+            .unwrap()
+            .push((task.status, Instant::now()));
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if event.r#type == "log" {
+            self.log_saves.lock().unwrap().push(Instant::now());
+        }
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Default)]
+struct DropRecorder {
+    reasons: Mutex<Vec<String>>,
+}
+
+impl TaskcastHooks for DropRecorder {
+    fn on_event_dropped(&self, _event: &TaskEvent, reason: &str) {
+        self.reasons.lock().unwrap().push(reason.to_string());
+    }
+}
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+async fn running_task(
+    config: WriteShapingConfig,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+) -> (Arc<TaskEngine>, Arc<RecordingLongTermStore>) {
+    let store = Arc::new(RecordingLongTermStore::default());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: Some(Arc::clone(&store) as Arc<dyn LongTermStore>),
+        hooks,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_write_shaping(config);
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    // Let the status event's write finish, so the tests start from an idle
+    // shaper with one token spent.
+    assert!(engine.background().drain(Duration::from_secs(1)).await);
+    (Arc::new(engine), store)
+}
+
+async fn publish_logs(engine: &TaskEngine, count: usize) {
+    for n in 0..count {
+        engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: json!({ "n": n }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn a_burst_is_written_at_the_configured_rate() {
+    let (engine, store) = running_task(
+        WriteShapingConfig {
+            rows_per_sec: 50.0,
+            burst: 5,
+            ..Default::default()
+        },
+        None,
+    )
+    .await;
+
+    publish_logs(&engine, 30).await;
+    assert!(engine.background().drain(Duration::from_secs(5)).await);
+
+    let saves = store.log_saves.lock().unwrap().clone();
+    assert_eq!(saves.len(), 30);
+    // The status event spent one token, so the burst covers four logs and
+    // the remaining 26 follow at 50 per second.
+    let spread = saves[29].duration_since(saves[0]);
+    assert!(
+        spread >= Duration::from_millis(450) && spread < Duration::from_millis(1_000),
+        "30 writes took {spread:?}"
+    );
+    let stats = engine.write_shaping_stats().unwrap();
+    assert_eq!(stats.queue_depth, 0);
+    assert_eq!(stats.shed, 0);
+}
+
+#[tokio::test]
+async fn task_saves_do_not_wait_behind_queued_events() {
+    let (engine, store) = running_task(
+        WriteShapingConfig {
+            rows_per_sec: 10.0,
+            burst: 1,
+            ..Default::default()
+        },
+        None,
+    )
+    .await;
+    publish_logs(&engine, 20).await;
+    assert!(engine.write_shaping_stats().unwrap().queue_depth > 10);
+
+    let started = Instant::now();
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    let saved_at = store
+        .task_saves
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(status, _)| *status == TaskStatus::Completed)
+        .map(|(_, at)| *at)
+        .expect("completed task not saved");
+    assert!(saved_at.duration_since(started) < Duration::from_millis(100));
+    assert!(store.log_saves.lock().unwrap().len() < 5);
+}
+
+#[tokio::test]
+async fn a_full_queue_drops_writes_and_reports_them() {
+    let hooks = Arc::new(DropRecorder::default());
+    let (engine, store) = running_task(
+        WriteShapingConfig {
+            rows_per_sec: 1.0,
+            burst: 1,
+            queue_capacity: 5,
+            overflow: WriteOverflow::Drop,
+        },
+        Some(Arc::clone(&hooks) as Arc<dyn TaskcastHooks>),
+    )
+    .await;
+
+    let started = Instant::now();
+    publish_logs(&engine, 20).await;
+    assert!(started.elapsed() < Duration::from_millis(500));
+
+    let stats = engine.write_shaping_stats().unwrap();
+    assert_eq!(stats.queue_depth, 5);
+    assert_eq!(stats.shed, 15);
+    let reasons = hooks.reasons.lock().unwrap().clone();
+    assert_eq!(reasons.len(), 15);
+    assert!(reasons.iter().all(|r| r == "long-term write queue full"));
+    assert!(store.log_saves.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn a_full_queue_with_backpressure_slows_publishers() {
+    let (engine, store) = running_task(
+        WriteShapingConfig {
+            rows_per_sec: 40.0,
+            burst: 1,
+            queue_capacity: 2,
+            overflow: WriteOverflow::Backpressure,
+        },
+        None,
+    )
+    .await;
+
+    let started = Instant::now();
+    publish_logs(&engine, 12).await;
+    // Each publish past the queue's capacity waited for a write to start.
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    assert!(engine.background().drain(Duration::from_secs(5)).await);
+    assert_eq!(store.log_saves.lock().unwrap().len(), 12);
+    assert_eq!(engine.write_shaping_stats().unwrap().shed, 0);
+}
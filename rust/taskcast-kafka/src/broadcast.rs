@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaResult;
+use rdkafka::message::Message;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use tokio::sync::RwLock;
+
+use taskcast_core::channels::channel_matches;
+use taskcast_core::series::{decode_broadcast_payload, encode_broadcast_payload};
+use taskcast_core::types::{BroadcastProvider, TaskEvent};
+
+use crate::DEFAULT_TOPIC;
+
+type Handler = Arc<dyn Fn(TaskEvent) + Send + Sync>;
+type Handlers = Arc<RwLock<HashMap<String, Vec<Handler>>>>;
+
+/// How long `publish` waits for the broker to acknowledge an event.
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kafka-backed broadcast provider.
+///
+/// Events are produced to a single topic keyed by task id, so each task's
+/// events stay in order within their partition. A background consumer reads
+/// the topic and fans events out to locally-registered handlers.
+///
+/// Unlike Redis Pub/Sub, consumers sharing a group id split the topic's
+/// partitions between them rather than each receiving every event. Every
+/// instance serving SSE needs its own group id, which is what the default
+/// (a fresh `taskcast-<ulid>` per provider) gives. The consumer starts at
+/// the latest offset: like Pub/Sub, it only sees events published after it
+/// joined.
+pub struct KafkaBroadcastProvider {
+    producer: FutureProducer,
+    handlers: Handlers,
+    /// Pattern subscriptions, keyed by glob pattern and matched against
+    /// each message's key.
+    pattern_handlers: Handlers,
+    topic: String,
+    group_id: String,
+}
+
+impl KafkaBroadcastProvider {
+    /// Create a new `KafkaBroadcastProvider`.
+    ///
+    /// - `brokers`: comma-separated `host:port` bootstrap servers.
+    /// - `topic`: topic to produce to and consume from (defaults to
+    ///   [`DEFAULT_TOPIC`]).
+    /// - `group_id`: consumer group (defaults to one unique to this
+    ///   provider).
+    ///
+    /// Spawns the consumer task, so it must be called within a Tokio runtime.
+    pub fn new(brokers: &str, topic: Option<&str>, group_id: Option<&str>) -> KafkaResult<Self> {
+        let topic = topic.unwrap_or(DEFAULT_TOPIC).to_string();
+        let group_id = group_id
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("taskcast-{}", ulid::Ulid::new()));
+
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set(
+                "message.timeout.ms",
+                PUBLISH_TIMEOUT.as_millis().to_string(),
+            )
+            .create()?;
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", &group_id)
+            .set("auto.offset.reset", "latest")
+            .set("enable.auto.commit", "false")
+            .create()?;
+        consumer.subscribe(&[&topic])?;
+
+        let handlers: Handlers = Arc::new(RwLock::new(HashMap::new()));
+        let pattern_handlers: Handlers = Arc::new(RwLock::new(HashMap::new()));
+
+        // Spawn background listener that reads the topic and dispatches to
+        // local handlers by message key.
+        let handlers_clone = Arc::clone(&handlers);
+        let pattern_handlers_clone = Arc::clone(&pattern_handlers);
+        let topic_clone = topic.clone();
+        tokio::spawn(async move {
+            loop {
+                let message = match consumer.recv().await {
+                    Ok(m) => m,
+                    Err(e) => {
+                        eprintln!("[taskcast] Kafka consume failed on {topic_clone}: {e}");
+                        continue;
+                    }
+                };
+                let Some(Ok(channel)) = message.key_view::<str>() else {
+                    continue;
+                };
+                let event = match message.payload().and_then(decode_broadcast_payload) {
+                    Some(e) => e,
+                    None => continue,
+                };
+
+                let handlers = handlers_clone.read().await;
+                if let Some(task_handlers) = handlers.get(channel) {
+                    for handler in task_handlers {
+                        handler(event.clone());
+                    }
+                }
+                drop(handlers);
+                let patterns = pattern_handlers_clone.read().await;
+                for (pattern, pattern_handlers) in patterns.iter() {
+                    if channel_matches(pattern, channel) {
+                        for handler in pattern_handlers {
+                            handler(event.clone());
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            producer,
+            handlers,
+            pattern_handlers,
+            topic,
+            group_id,
+        })
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn group_id(&self) -> &str {
+        &self.group_id
+    }
+}
+
+#[async_trait]
+impl BroadcastProvider for KafkaBroadcastProvider {
+    async fn publish(
+        &self,
+        channel: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let payload = encode_broadcast_payload(&event)?;
+        let record = FutureRecord::to(&self.topic).key(channel).payload(&payload);
+        self.producer
+            .send(record, PUBLISH_TIMEOUT)
+            .await
+            .map_err(|(err, _)| err)?;
+        Ok(())
+    }
+
+    async fn subscribe(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        add_handler(&self.handlers, channel, handler).await
+    }
+
+    async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(add_handler(&self.pattern_handlers, pattern, handler).await)
+    }
+}
+
+/// Adds `handler` under `key`, returning the closure that removes it again.
+async fn add_handler(
+    handlers: &Handlers,
+    key: &str,
+    handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+) -> Box<dyn Fn() + Send + Sync> {
+    let handler: Handler = Arc::from(handler);
+    {
+        let mut handlers = handlers.write().await;
+        handlers
+            .entry(key.to_string())
+            .or_default()
+            .push(Arc::clone(&handler));
+    }
+
+    let handlers = Arc::clone(handlers);
+    let key = key.to_string();
+    Box::new(move || {
+        let handlers = Arc::clone(&handlers);
+        let key = key.clone();
+        let handler = Arc::clone(&handler);
+        // The unsubscribe closure is synchronous per the trait, so we
+        // spawn a tokio task to do the async cleanup.
+        tokio::spawn(async move {
+            let mut handlers = handlers.write().await;
+            if let Some(key_handlers) = handlers.get_mut(&key) {
+                key_handlers.retain(|h| !Arc::ptr_eq(h, &handler));
+                if key_handlers.is_empty() {
+                    handlers.remove(&key);
+                }
+            }
+        });
+    })
+}
+
@@ -0,0 +1,12 @@
+pub mod broadcast;
+pub mod sink;
+
+pub use broadcast::KafkaBroadcastProvider;
+pub use sink::KafkaEventSink;
+
+/// Topic the broadcast provider uses unless configured otherwise.
+pub const DEFAULT_TOPIC: &str = "taskcast.events";
+
+/// Topic the event sink writes to unless configured otherwise. It differs
+/// from [`DEFAULT_TOPIC`] so a sink never feeds broadcast subscribers.
+pub const DEFAULT_SINK_TOPIC: &str = "taskcast.events.sink";
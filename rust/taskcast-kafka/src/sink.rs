@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rdkafka::error::KafkaResult;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use taskcast_core::series::encode_broadcast_payload;
+use taskcast_core::types::{EventSink, TaskEvent};
+
+use crate::DEFAULT_SINK_TOPIC;
+
+/// How long the producer keeps retrying an event before giving up on it.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Streams every published event into a Kafka topic, keyed by task id.
+///
+/// `on_event` only queues the event in the producer, so a slow or
+/// unreachable broker does not hold up publishing. Events that still fail
+/// to deliver after [`DELIVERY_TIMEOUT`] are logged and dropped.
+pub struct KafkaEventSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaEventSink {
+    /// Create a new `KafkaEventSink`.
+    ///
+    /// - `brokers`: comma-separated `host:port` bootstrap servers.
+    /// - `topic`: topic to write to (defaults to [`DEFAULT_SINK_TOPIC`]).
+    pub fn new(brokers: &str, topic: Option<&str>) -> KafkaResult<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set(
+                "message.timeout.ms",
+                DELIVERY_TIMEOUT.as_millis().to_string(),
+            )
+            .create()?;
+        Ok(Self {
+            producer,
+            topic: topic.unwrap_or(DEFAULT_SINK_TOPIC).to_string(),
+        })
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+}
+
+#[async_trait]
+impl EventSink for KafkaEventSink {
+    async fn on_event(
+        &self,
+        event: &TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let payload = encode_broadcast_payload(event)?;
+        let record = FutureRecord::to(&self.topic)
+            .key(&event.task_id)
+            .payload(&payload);
+        let delivery = self.producer.send_result(record).map_err(|(err, _)| err)?;
+
+        let topic = self.topic.clone();
+        tokio::spawn(async move {
+            if let Ok(Err((err, _))) = delivery.await {
+                eprintln!("[taskcast] Kafka sink delivery to {topic} failed: {err}");
+            }
+        });
+        Ok(())
+    }
+}
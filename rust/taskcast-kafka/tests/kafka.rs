@@ -0,0 +1,220 @@
+//! Integration tests against a Kafka container. Run with
+//! `cargo test -p taskcast-kafka --features kafka-integration`.
+#![cfg(feature = "kafka-integration")]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::message::Message;
+use rdkafka::ClientConfig;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::kafka::{Kafka, KAFKA_PORT};
+
+use taskcast_core::series::decode_broadcast_payload;
+use taskcast_core::{
+    BroadcastProvider, CreateTaskInput, EventSink, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use taskcast_kafka::{KafkaBroadcastProvider, KafkaEventSink};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+const WAIT: Duration = Duration::from_secs(60);
+
+async fn setup() -> (String, testcontainers::ContainerAsync<Kafka>) {
+    let container = Kafka::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(KAFKA_PORT).await.unwrap();
+    (format!("127.0.0.1:{port}"), container)
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "index": index }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+type Received = Arc<Mutex<Vec<TaskEvent>>>;
+
+async fn collect(provider: &KafkaBroadcastProvider, task_id: &str) -> Received {
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&received);
+    // Dropping the unsubscribe closure leaves the handler registered.
+    let _ = provider
+        .subscribe(
+            task_id,
+            Box::new(move |event| sink.lock().unwrap().push(event)),
+        )
+        .await;
+    received
+}
+
+/// Polls until `received` holds `count` events.
+async fn wait_for(received: &Received, count: usize) {
+    tokio::time::timeout(WAIT, async {
+        while received.lock().unwrap().len() < count {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| {
+        panic!(
+            "expected {count} events, got {:?}",
+            received.lock().unwrap()
+        )
+    });
+}
+
+/// The consumer only sees events published after its partitions are
+/// assigned, which happens some time after it starts. Publishes to a
+/// throwaway task until every provider has seen one.
+async fn wait_until_consuming(providers: &[&KafkaBroadcastProvider]) {
+    let mut warmups = Vec::new();
+    for provider in providers {
+        warmups.push(collect(provider, "warmup").await);
+    }
+    tokio::time::timeout(WAIT, async {
+        for index in 0.. {
+            providers[0]
+                .publish("warmup", make_event("warmup", index))
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            if warmups.iter().all(|w| !w.lock().unwrap().is_empty()) {
+                return;
+            }
+        }
+    })
+    .await
+    .expect("consumers never received the warm-up events");
+}
+
+fn indices(received: &Received) -> Vec<u64> {
+    received.lock().unwrap().iter().map(|e| e.index).collect()
+}
+
+// ─── Broadcast ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn broadcast_keeps_per_task_order_on_every_instance() {
+    let (brokers, _container) = setup().await;
+    let first = KafkaBroadcastProvider::new(&brokers, Some("broadcast-order"), None).unwrap();
+    let second = KafkaBroadcastProvider::new(&brokers, Some("broadcast-order"), None).unwrap();
+    assert_ne!(first.group_id(), second.group_id());
+    wait_until_consuming(&[&first, &second]).await;
+
+    let t1 = collect(&first, "t1").await;
+    let t2 = collect(&first, "t2").await;
+    let t1_elsewhere = collect(&second, "t1").await;
+    for index in 0..10 {
+        first.publish("t1", make_event("t1", index)).await.unwrap();
+        first.publish("t2", make_event("t2", index)).await.unwrap();
+    }
+    wait_for(&t1, 10).await;
+    wait_for(&t2, 10).await;
+    wait_for(&t1_elsewhere, 10).await;
+
+    let expected: Vec<u64> = (0..10).collect();
+    assert_eq!(indices(&t1), expected);
+    assert_eq!(indices(&t2), expected);
+    assert_eq!(indices(&t1_elsewhere), expected);
+    assert!(t2.lock().unwrap().iter().all(|e| e.task_id == "t2"));
+}
+
+#[tokio::test]
+async fn broadcast_carries_accumulated_data() {
+    let (brokers, _container) = setup().await;
+    let provider = KafkaBroadcastProvider::new(&brokers, Some("broadcast-acc"), None).unwrap();
+    wait_until_consuming(&[&provider]).await;
+
+    let received = collect(&provider, "t1").await;
+    let event = TaskEvent {
+        _accumulated_data: Some(serde_json::json!({ "delta": "Hello" })),
+        ..make_event("t1", 0)
+    };
+    provider.publish("t1", event.clone()).await.unwrap();
+    wait_for(&received, 1).await;
+    assert_eq!(received.lock().unwrap()[0], event);
+}
+
+// ─── Sink ────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sink_streams_every_published_event() {
+    let (brokers, _container) = setup().await;
+    let sink = KafkaEventSink::new(&brokers, Some("sink-events")).unwrap();
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: vec![Arc::new(sink) as Arc<dyn EventSink>],
+        coalesce_reads: None,
+    });
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..3 {
+        engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!({ "i": i }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("bootstrap.servers", &brokers)
+        .set("group.id", "sink-reader")
+        .set("auto.offset.reset", "earliest")
+        .create()
+        .unwrap();
+    consumer.subscribe(&["sink-events"]).unwrap();
+    let mut delivered = Vec::new();
+    tokio::time::timeout(WAIT, async {
+        while delivered.len() < 4 {
+            let message = consumer.recv().await.unwrap();
+            assert_eq!(message.key(), Some("t1".as_bytes()));
+            delivered.push(decode_broadcast_payload(message.payload().unwrap()).unwrap());
+        }
+    })
+    .await
+    .expect("sink events never arrived");
+
+    let types: Vec<&str> = delivered.iter().map(|e| e.r#type.as_str()).collect();
+    assert_eq!(types, ["taskcast:status", "log", "log", "log"]);
+    assert_eq!(delivered, engine.get_events("t1", None).await.unwrap());
+}
@@ -0,0 +1,164 @@
+use taskcast_core::{StoreError, StoreErrorKind};
+
+/// Classifies a sqlx error for [`StoreError`], by SQLSTATE for errors the
+/// server returned.
+pub fn sqlx_error_kind(err: &sqlx::Error) -> StoreErrorKind {
+    match err {
+        sqlx::Error::Database(db) => match db.code() {
+            Some(code) => sqlstate_kind(&code),
+            None => StoreErrorKind::Other,
+        },
+        sqlx::Error::PoolTimedOut => StoreErrorKind::Timeout,
+        sqlx::Error::Io(io) if io.kind() == std::io::ErrorKind::TimedOut => StoreErrorKind::Timeout,
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => StoreErrorKind::Unavailable,
+        sqlx::Error::Decode(_) | sqlx::Error::ColumnDecode { .. } => StoreErrorKind::Corrupt,
+        _ => StoreErrorKind::Other,
+    }
+}
+
+fn sqlstate_kind(code: &str) -> StoreErrorKind {
+    match code {
+        // serialization_failure, deadlock_detected, unique_violation
+        "40001" | "40P01" | "23505" => StoreErrorKind::Conflict,
+        // string_data_right_truncation, program_limit_exceeded (54xxx)
+        "22001" => StoreErrorKind::TooLarge,
+        _ if code.starts_with("54") => StoreErrorKind::TooLarge,
+        // query_canceled, raised by statement_timeout
+        "57014" => StoreErrorKind::Timeout,
+        // admin_shutdown, crash_shutdown, cannot_connect_now
+        "57P01" | "57P02" | "57P03" => StoreErrorKind::Unavailable,
+        // connection_exception (08xxx), insufficient_resources (53xxx)
+        _ if code.starts_with("08") || code.starts_with("53") => StoreErrorKind::Unavailable,
+        // data_corrupted, index_corrupted
+        "XX001" | "XX002" => StoreErrorKind::Corrupt,
+        _ => StoreErrorKind::Other,
+    }
+}
+
+/// Wraps a sqlx error so that only its kind and a short detail surface.
+pub fn store_error(err: sqlx::Error) -> StoreError {
+    StoreError::new(sqlx_error_kind(&err), err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::error::Error;
+
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    use super::*;
+
+    /// A server error with a SQLSTATE, as Postgres would report it.
+    #[derive(Debug)]
+    struct ServerError {
+        code: &'static str,
+        message: String,
+    }
+
+    impl std::fmt::Display for ServerError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    impl Error for ServerError {}
+
+    impl DatabaseError for ServerError {
+        fn message(&self) -> &str {
+            &self.message
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn server_error(code: &'static str, message: &str) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(ServerError {
+            code,
+            message: message.to_string(),
+        }))
+    }
+
+    #[test]
+    fn sqlstates_are_classified() {
+        let cases = [
+            ("53300", StoreErrorKind::Unavailable),
+            ("53100", StoreErrorKind::Unavailable),
+            ("08006", StoreErrorKind::Unavailable),
+            ("57P01", StoreErrorKind::Unavailable),
+            ("22001", StoreErrorKind::TooLarge),
+            ("54000", StoreErrorKind::TooLarge),
+            ("40001", StoreErrorKind::Conflict),
+            ("40P01", StoreErrorKind::Conflict),
+            ("57014", StoreErrorKind::Timeout),
+            ("XX001", StoreErrorKind::Corrupt),
+            ("42P01", StoreErrorKind::Other),
+        ];
+        for (code, kind) in cases {
+            assert_eq!(
+                sqlx_error_kind(&server_error(code, "failed")),
+                kind,
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn client_errors_are_classified() {
+        assert_eq!(
+            sqlx_error_kind(&sqlx::Error::PoolTimedOut),
+            StoreErrorKind::Timeout
+        );
+        assert_eq!(
+            sqlx_error_kind(&sqlx::Error::PoolClosed),
+            StoreErrorKind::Unavailable
+        );
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert_eq!(
+            sqlx_error_kind(&sqlx::Error::Io(refused)),
+            StoreErrorKind::Unavailable
+        );
+        assert_eq!(
+            sqlx_error_kind(&sqlx::Error::Decode("bad utf-8".into())),
+            StoreErrorKind::Corrupt
+        );
+        assert_eq!(
+            sqlx_error_kind(&sqlx::Error::RowNotFound),
+            StoreErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn store_error_bounds_the_detail_and_keeps_the_source() {
+        let message = format!(
+            "value too long for type character varying(64)\nCONTEXT: INSERT INTO taskcast_events {}",
+            "(id, task_id) ".repeat(100)
+        );
+        let err = store_error(server_error("22001", &message));
+        assert_eq!(err.kind, StoreErrorKind::TooLarge);
+        assert!(!err.detail.contains('\n'));
+        assert!(err.detail.chars().count() <= taskcast_core::MAX_STORE_ERROR_DETAIL);
+        assert!(err.source().unwrap().to_string().ends_with(&message));
+    }
+}
@@ -1,3 +1,6 @@
+mod error;
+mod recent_writes;
 mod store;
 
-pub use store::PostgresLongTermStore;
+pub use error::{sqlx_error_kind, store_error};
+pub use store::{PostgresLongTermStore, DEFAULT_READ_AFTER_WRITE_WINDOW};
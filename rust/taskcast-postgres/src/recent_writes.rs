@@ -0,0 +1,132 @@
+//! Tasks written recently, for routing their reads to the write pool.
+//!
+//! A replica can lag the primary, so a task read right after it was saved
+//! could come back stale or missing. Reads for a task written within the
+//! window go to the primary instead.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Most tasks tracked at once. When full, the oldest write is forgotten and
+/// its task reads from the replica again.
+const CAPACITY: usize = 10_000;
+
+pub(crate) struct RecentWrites {
+    window: Duration,
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    /// Last write per task, with its sequence number.
+    written_at: HashMap<String, (Instant, u64)>,
+    /// Writes oldest first. A task written again keeps its older entries
+    /// until they reach the front; their sequence numbers no longer match
+    /// `written_at`.
+    order: VecDeque<(String, u64)>,
+    next_seq: u64,
+}
+
+impl RecentWrites {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self::with_capacity(window, CAPACITY)
+    }
+
+    fn with_capacity(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Records a write to `task_id`.
+    pub(crate) fn mark(&self, task_id: &str) {
+        self.mark_at(task_id, Instant::now());
+    }
+
+    /// Whether `task_id` was written within the window.
+    pub(crate) fn contains(&self, task_id: &str) -> bool {
+        self.contains_at(task_id, Instant::now())
+    }
+
+    fn mark_at(&self, task_id: &str, now: Instant) {
+        if self.window.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let seq = entries.next_seq;
+        entries.next_seq += 1;
+        entries.written_at.insert(task_id.to_string(), (now, seq));
+        entries.order.push_back((task_id.to_string(), seq));
+
+        while let Some((id, seq)) = entries.order.front().cloned() {
+            let (superseded, expired) = match entries.written_at.get(&id) {
+                Some((at, latest)) => (*latest != seq, now.duration_since(*at) >= self.window),
+                None => (true, false),
+            };
+            let over_capacity = entries.written_at.len() > self.capacity;
+            if !(superseded || expired || over_capacity) {
+                break;
+            }
+            entries.order.pop_front();
+            if !superseded {
+                entries.written_at.remove(&id);
+            }
+        }
+    }
+
+    fn contains_at(&self, task_id: &str, now: Instant) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .written_at
+            .get(task_id)
+            .is_some_and(|(at, _)| now.duration_since(*at) < self.window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_are_recent_until_the_window_passes() {
+        let recent = RecentWrites::new(Duration::from_secs(5));
+        let start = Instant::now();
+        recent.mark_at("t1", start);
+
+        assert!(recent.contains_at("t1", start + Duration::from_secs(4)));
+        assert!(!recent.contains_at("t1", start + Duration::from_secs(5)));
+        assert!(!recent.contains_at("t2", start));
+
+        // A later write restarts the window.
+        recent.mark_at("t1", start + Duration::from_secs(4));
+        assert!(recent.contains_at("t1", start + Duration::from_secs(8)));
+    }
+
+    #[test]
+    fn oldest_writes_are_forgotten_past_capacity() {
+        let recent = RecentWrites::with_capacity(Duration::from_secs(60), 2);
+        let start = Instant::now();
+        recent.mark_at("t1", start);
+        recent.mark_at("t2", start);
+        recent.mark_at("t1", start);
+        recent.mark_at("t3", start);
+
+        assert!(!recent.contains_at("t2", start));
+        assert!(recent.contains_at("t1", start));
+        assert!(recent.contains_at("t3", start));
+        assert_eq!(recent.entries.lock().unwrap().written_at.len(), 2);
+    }
+
+    #[test]
+    fn zero_window_tracks_nothing() {
+        let recent = RecentWrites::new(Duration::ZERO);
+        recent.mark("t1");
+        assert!(!recent.contains("t1"));
+        assert!(recent.entries.lock().unwrap().order.is_empty());
+    }
+}
@@ -1,30 +1,231 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
-use sqlx::postgres::PgRow;
-use sqlx::{PgPool, Postgres, Row, Transaction};
-
+use sqlx::postgres::{PgArguments, PgRow};
+use sqlx::query::Query;
+use sqlx::{PgExecutor, PgPool, Postgres, Row, Transaction};
+
+use taskcast_core::conventions::{ConventionNotes, EventConvention, EventConventionStore};
+use taskcast_core::integrity::{CorruptRecord, CorruptRecordKind, IntegrityMonitor};
+use taskcast_core::payload_dedup::{blob_ref, blob_ref_hash, DedupedPayload, PayloadDedupConfig};
+use taskcast_core::query_stats::{QueryStats, QueryStatsSnapshot, SlowQueryLogger};
+use taskcast_core::request_deadline;
 use taskcast_core::types::{
-    AssignMode, CleanupConfig, DisconnectPolicy, EventQueryOptions, Level, LongTermStore,
-    SeriesMode, Task, TaskAuthConfig, TaskError, TaskEvent, TaskStatus, WebhookConfig,
+    is_type_pattern, AssignMode, CleanupConfig, DisconnectPolicy, EventQueryOptions,
+    EventTypeCounts, Level, ListTasksQuery, LongTermStore, PoolHealth, SeriesMode, Task,
+    TaskAuthConfig, TaskError, TaskEvent, TaskPage, TaskStatus, WebhookConfig, WebhookGroupPolicy,
     WorkerAuditAction, WorkerAuditEvent,
 };
 
+use crate::error::store_error;
+use crate::recent_writes::RecentWrites;
+
 const TASKS: &str = "taskcast_tasks";
 const EVENTS: &str = "taskcast_events";
 const WORKER_EVENTS: &str = "taskcast_worker_events";
+const BLOBS: &str = "taskcast_blobs";
+const CONVENTIONS: &str = "taskcast_event_conventions";
+
+/// Default for [`PostgresLongTermStore::with_read_after_write_window`].
+pub const DEFAULT_READ_AFTER_WRITE_WINDOW: Duration = Duration::from_secs(5);
+
+/// How long a pool probe may take before the pool is reported unhealthy.
+const POOL_HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// PostgreSQL-backed long-term store for tasks and events.
 ///
 /// Uses `sqlx::PgPool` for connection pooling and implements the
-/// `LongTermStore` trait from `taskcast-core`.
+/// `LongTermStore` trait from `taskcast-core`. Reads can be served from a
+/// replica through a separate read pool; writes always go to the primary.
 pub struct PostgresLongTermStore {
     pool: PgPool,
+    read_pool: Option<PgPool>,
+    recent_writes: RecentWrites,
+    dedup: Option<PayloadDedupConfig>,
+    integrity: IntegrityMonitor,
+    stats: QueryStats,
 }
 
 impl PostgresLongTermStore {
     /// Create a new store with the given connection pool.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            read_pool: None,
+            recent_writes: RecentWrites::new(DEFAULT_READ_AFTER_WRITE_WINDOW),
+            dedup: None,
+            integrity: IntegrityMonitor::default(),
+            stats: QueryStats::new("postgres"),
+        }
+    }
+
+    /// Create a store that writes through `write_pool` and reads through
+    /// `read_pool`. See [`with_read_pool`](Self::with_read_pool).
+    pub fn with_pools(write_pool: PgPool, read_pool: PgPool) -> Self {
+        Self::new(write_pool).with_read_pool(read_pool)
+    }
+
+    /// Serve task and event reads from `pool`, typically a replica of the
+    /// primary the store writes to. Reads of a task written within the
+    /// read-after-write window still go to the primary, so a lagging replica
+    /// cannot hide a write from the engine's read-then-write flows.
+    pub fn with_read_pool(self, pool: PgPool) -> Self {
+        Self {
+            read_pool: Some(pool),
+            ..self
+        }
+    }
+
+    /// How long after a write to a task its reads stay on the primary.
+    /// Should exceed the replica's usual lag. Defaults to
+    /// [`DEFAULT_READ_AFTER_WRITE_WINDOW`]; only matters with a read pool.
+    pub fn with_read_after_write_window(self, window: Duration) -> Self {
+        Self {
+            recent_writes: RecentWrites::new(window),
+            ..self
+        }
+    }
+
+    /// Use `monitor` for rows that fail to decode on read. The default monitor
+    /// surfaces them (see `CorruptRecordPolicy`).
+    pub fn with_integrity(self, monitor: IntegrityMonitor) -> Self {
+        Self {
+            integrity: monitor,
+            ..self
+        }
+    }
+
+    /// Log calls that take at least `threshold` as slow queries. Defaults to
+    /// [`DEFAULT_SLOW_QUERY_THRESHOLD`](taskcast_core::query_stats::DEFAULT_SLOW_QUERY_THRESHOLD).
+    pub fn with_slow_query_threshold(self, threshold: Duration) -> Self {
+        Self {
+            stats: self.stats.with_slow_threshold(threshold),
+            ..self
+        }
+    }
+
+    /// Send slow queries to `logger` instead of stderr.
+    pub fn with_slow_query_logger(self, logger: Arc<dyn SlowQueryLogger>) -> Self {
+        Self {
+            stats: self.stats.with_logger(logger),
+            ..self
+        }
+    }
+
+    /// Create a store that keeps large event payloads once per distinct
+    /// content in `taskcast_blobs` and stores `{"$blobRef": hash}` in the
+    /// event row. Reads resolve references transparently.
+    pub fn with_payload_dedup(pool: PgPool, config: PayloadDedupConfig) -> Self {
+        Self {
+            dedup: Some(config),
+            ..Self::new(pool)
+        }
+    }
+
+    /// Deletes the given events of a task, releasing any blobs they reference
+    /// in the same transaction.
+    pub async fn delete_events(
+        &self,
+        task_id: &str,
+        event_ids: &[String],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        let sql =
+            format!("DELETE FROM {EVENTS} WHERE task_id = $1 AND id = ANY($2) RETURNING data");
+        let rows = sqlx::query(&sql)
+            .bind(task_id)
+            .bind(event_ids)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        release_blobs_pg(&mut tx, blob_hashes(&rows)).await?;
+        tx.commit().await.map_err(store_error)?;
+        self.recent_writes.mark(task_id);
+        Ok(())
+    }
+
+    /// Deletes a task and its events, releasing any blobs they reference in
+    /// the same transaction.
+    pub async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        let sql = format!("DELETE FROM {EVENTS} WHERE task_id = $1 RETURNING data");
+        let rows = sqlx::query(&sql)
+            .bind(task_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        release_blobs_pg(&mut tx, blob_hashes(&rows)).await?;
+        let sql = format!("DELETE FROM {TASKS} WHERE id = $1");
+        sqlx::query(&sql)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        tx.commit().await.map_err(store_error)?;
+        self.recent_writes.mark(task_id);
+        Ok(())
+    }
+
+    /// Overwrites each of the task's stored events with the tombstone of the
+    /// same id, keeping its `idx`, and releases the blobs the replaced events
+    /// referenced. Returns how many events were replaced.
+    pub async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            UPDATE {EVENTS} e
+            SET timestamp = $1,
+                type = $2,
+                level = $3,
+                data = $4,
+                series_id = NULL,
+                series_mode = NULL,
+                series_acc_field = NULL,
+                labels = NULL
+            FROM {EVENTS} old
+            WHERE old.id = e.id AND e.task_id = $5 AND e.id = $6
+            RETURNING old.data
+            "#
+        );
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        let mut rows = Vec::new();
+        for tombstone in tombstones {
+            let replaced = sqlx::query(&sql)
+                .bind(tombstone.timestamp as i64)
+                .bind(&tombstone.r#type)
+                .bind(level_to_string(&tombstone.level)?)
+                .bind(data_json_for_db(&tombstone.data))
+                .bind(task_id)
+                .bind(&tombstone.id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(store_error)?;
+            rows.extend(replaced);
+        }
+        release_blobs_pg(&mut tx, blob_hashes(&rows)).await?;
+        tx.commit().await.map_err(store_error)?;
+        self.recent_writes.mark(task_id);
+        Ok(rows.len() as u64)
+    }
+
+    /// The pool to read `task_id` from: the read pool, unless the task was
+    /// written within the read-after-write window.
+    fn read_pool_for(&self, task_id: &str) -> &PgPool {
+        match self.read_pool {
+            Some(ref read_pool) if !self.recent_writes.contains(task_id) => read_pool,
+            _ => &self.pool,
+        }
     }
 
     /// Run migrations to create/update tables and indexes.
@@ -39,60 +240,96 @@ impl PostgresLongTermStore {
         Ok(())
     }
 
-    /// Convert a database row into a `Task`.
-    fn row_to_task(row: &PgRow) -> Task {
+    /// Convert a database row into a `Task`. A row with an unknown status or
+    /// an undecodable column is reported as a `CorruptRecord` rather than
+    /// coerced.
+    fn row_to_task(row: &PgRow) -> Result<Task, CorruptRecord> {
+        let id: String = row.get("id");
+        Self::decode_task(row)
+            .map_err(|reason| CorruptRecord::task(&id, Self::raw_task(row), reason))
+    }
+
+    fn decode_task(row: &PgRow) -> Result<Task, String> {
         let status_str: String = row.get("status");
-        let status: TaskStatus =
-            serde_json::from_value(JsonValue::String(status_str)).unwrap_or(TaskStatus::Pending);
+        let status: TaskStatus = decode_enum(status_str, "status")?;
 
         let created_at_i64: i64 = row.get("created_at");
         let updated_at_i64: i64 = row.get("updated_at");
         let completed_at_i64: Option<i64> = row.get("completed_at");
         let ttl_i32: Option<i32> = row.get("ttl");
 
-        let params: Option<JsonValue> = row.get("params");
-        let result: Option<JsonValue> = row.get("result");
-        let error: Option<JsonValue> = row.get("error");
-        let metadata: Option<JsonValue> = row.get("metadata");
-        let auth_config: Option<JsonValue> = row.get("auth_config");
-        let webhooks: Option<JsonValue> = row.get("webhooks");
-        let cleanup: Option<JsonValue> = row.get("cleanup");
-
-        let tags: Option<JsonValue> = row.get("tags");
         let assign_mode_str: Option<String> = row.get("assign_mode");
         let cost_i32: Option<i32> = row.get("cost");
         let assigned_worker: Option<String> = row.get("assigned_worker");
         let disconnect_policy_str: Option<String> = row.get("disconnect_policy");
+        let group_policy_str: Option<String> = row.get("group_policy");
 
-        let assign_mode: Option<AssignMode> =
-            assign_mode_str.and_then(|s| serde_json::from_value(JsonValue::String(s)).ok());
-        let disconnect_policy: Option<DisconnectPolicy> =
-            disconnect_policy_str.and_then(|s| serde_json::from_value(JsonValue::String(s)).ok());
-
-        Task {
+        Ok(Task {
             id: row.get("id"),
             r#type: row.get("type"),
             status,
-            params: params.and_then(|v| serde_json::from_value(v).ok()),
-            result: result.and_then(|v| serde_json::from_value(v).ok()),
-            error: error.and_then(|v| serde_json::from_value::<TaskError>(v).ok()),
-            metadata: metadata.and_then(|v| serde_json::from_value(v).ok()),
-            auth_config: auth_config.and_then(|v| serde_json::from_value::<TaskAuthConfig>(v).ok()),
-            webhooks: webhooks.and_then(|v| serde_json::from_value::<Vec<WebhookConfig>>(v).ok()),
-            cleanup: cleanup.and_then(|v| serde_json::from_value::<CleanupConfig>(v).ok()),
+            params: decode_column(row.get("params"), "params")?,
+            result: decode_column(row.get("result"), "result")?,
+            error: decode_column::<TaskError>(row.get("error"), "error")?,
+            metadata: decode_column(row.get("metadata"), "metadata")?,
+            auth_config: decode_column::<TaskAuthConfig>(row.get("auth_config"), "auth_config")?,
+            webhooks: decode_column::<Vec<WebhookConfig>>(row.get("webhooks"), "webhooks")?,
+            cleanup: decode_column::<CleanupConfig>(row.get("cleanup"), "cleanup")?,
             created_at: created_at_i64 as f64,
             updated_at: updated_at_i64 as f64,
             completed_at: completed_at_i64.map(|v| v as f64),
             ttl: ttl_i32.map(|v| v as u64),
-            tags: tags.and_then(|v| serde_json::from_value::<Vec<String>>(v).ok()),
-            assign_mode,
+            tags: decode_column::<Vec<String>>(row.get("tags"), "tags")?,
+            assign_mode: assign_mode_str
+                .map(|s| decode_enum::<AssignMode>(s, "assign_mode"))
+                .transpose()?,
             cost: cost_i32.map(|v| v as u32),
             assigned_worker,
-            disconnect_policy,
+            disconnect_policy: disconnect_policy_str
+                .map(|s| decode_enum::<DisconnectPolicy>(s, "disconnect_policy"))
+                .transpose()?,
             reason: None,
             resume_at: None,
             blocked_request: None,
-        }
+            filters: decode_column(row.get("filters"), "filters")?,
+            retry_policy: decode_column(row.get("retry_policy"), "retry_policy")?,
+            group_policy: group_policy_str
+                .map(|s| decode_enum::<WebhookGroupPolicy>(s, "group_policy"))
+                .transpose()?,
+            forward_to: decode_column(row.get("forward_to"), "forward_to")?,
+            scheduled_for: row.get::<Option<i64>, _>("scheduled_for").map(|v| v as f64),
+            deadline_ms: row.get::<Option<i64>, _>("deadline_ms").map(|v| v as u64),
+            origin: decode_column(row.get("origin"), "origin")?,
+            parent_id: row.get("parent_id"),
+            completion_policy: decode_column(row.get("completion_policy"), "completion_policy")?,
+            version: row.get::<i64, _>("version") as u64,
+        })
+    }
+
+    /// The decoded columns of a task row, as stored.
+    fn raw_task(row: &PgRow) -> JsonValue {
+        serde_json::json!({
+            "id": row.get::<String, _>("id"),
+            "status": row.get::<String, _>("status"),
+            "assignMode": row.get::<Option<String>, _>("assign_mode"),
+            "disconnectPolicy": row.get::<Option<String>, _>("disconnect_policy"),
+            "params": row.get::<Option<JsonValue>, _>("params"),
+            "result": row.get::<Option<JsonValue>, _>("result"),
+            "error": row.get::<Option<JsonValue>, _>("error"),
+            "metadata": row.get::<Option<JsonValue>, _>("metadata"),
+            "authConfig": row.get::<Option<JsonValue>, _>("auth_config"),
+            "webhooks": row.get::<Option<JsonValue>, _>("webhooks"),
+            "cleanup": row.get::<Option<JsonValue>, _>("cleanup"),
+            "tags": row.get::<Option<JsonValue>, _>("tags"),
+            "filters": row.get::<Option<JsonValue>, _>("filters"),
+            "retryPolicy": row.get::<Option<JsonValue>, _>("retry_policy"),
+            "groupPolicy": row.get::<Option<String>, _>("group_policy"),
+            "forwardTo": row.get::<Option<JsonValue>, _>("forward_to"),
+            "scheduledFor": row.get::<Option<i64>, _>("scheduled_for"),
+            "deadlineMs": row.get::<Option<i64>, _>("deadline_ms"),
+            "origin": row.get::<Option<JsonValue>, _>("origin"),
+            "completionPolicy": row.get::<Option<JsonValue>, _>("completion_policy"),
+        })
     }
 
     /// Convert a database row into a `WorkerAuditEvent`.
@@ -113,24 +350,41 @@ impl PostgresLongTermStore {
         }
     }
 
-    /// Convert a database row into a `TaskEvent`.
-    fn row_to_event(row: &PgRow) -> TaskEvent {
+    /// Convert a database row into a `TaskEvent`. A row with an unknown level
+    /// or series mode, a negative index or malformed labels is reported as a
+    /// `CorruptRecord` rather than coerced.
+    fn row_to_event(row: &PgRow) -> Result<TaskEvent, CorruptRecord> {
+        let idx: i32 = row.get("idx");
+        let timestamp_i64: i64 = row.get("timestamp");
+        Self::decode_event(row).map_err(|reason| CorruptRecord {
+            kind: CorruptRecordKind::Event,
+            task_id: row.get("task_id"),
+            id: Some(row.get("id")),
+            position: u64::try_from(idx).ok(),
+            timestamp: Some(timestamp_i64 as f64),
+            raw: Box::new(Self::raw_event(row)),
+            reason,
+        })
+    }
+
+    fn decode_event(row: &PgRow) -> Result<TaskEvent, String> {
         let level_str: String = row.get("level");
-        let level: Level =
-            serde_json::from_value(JsonValue::String(level_str)).unwrap_or(Level::Info);
+        let level: Level = decode_enum(level_str, "level")?;
 
         let idx: i32 = row.get("idx");
+        let index = u64::try_from(idx).map_err(|_| format!("idx: negative index {idx}"))?;
         let timestamp_i64: i64 = row.get("timestamp");
         let data: Option<JsonValue> = row.get("data");
 
         let series_mode_str: Option<String> = row.get("series_mode");
-        let series_mode: Option<SeriesMode> =
-            series_mode_str.and_then(|s| serde_json::from_value(JsonValue::String(s)).ok());
+        let series_mode: Option<SeriesMode> = series_mode_str
+            .map(|s| decode_enum(s, "series_mode"))
+            .transpose()?;
 
-        TaskEvent {
+        Ok(TaskEvent {
             id: row.get("id"),
             task_id: row.get("task_id"),
-            index: idx as u64,
+            index,
             timestamp: timestamp_i64 as f64,
             r#type: row.get("type"),
             level,
@@ -139,14 +393,36 @@ impl PostgresLongTermStore {
             series_mode,
             series_acc_field: row.get("series_acc_field"),
             series_snapshot: None,
+            labels: decode_column(row.get("labels"), "labels")?,
+            replaces_event_id: None,
             _accumulated_data: None,
-        }
+        })
     }
-}
 
-#[async_trait]
-impl LongTermStore for PostgresLongTermStore {
-    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// An event row's columns, as stored.
+    fn raw_event(row: &PgRow) -> JsonValue {
+        serde_json::json!({
+            "id": row.get::<String, _>("id"),
+            "taskId": row.get::<String, _>("task_id"),
+            "idx": row.get::<i32, _>("idx"),
+            "timestamp": row.get::<i64, _>("timestamp"),
+            "type": row.get::<String, _>("type"),
+            "level": row.get::<String, _>("level"),
+            "data": row.get::<Option<JsonValue>, _>("data"),
+            "seriesId": row.get::<Option<String>, _>("series_id"),
+            "seriesMode": row.get::<Option<String>, _>("series_mode"),
+            "seriesAccField": row.get::<Option<String>, _>("series_acc_field"),
+            "labels": row.get::<Option<JsonValue>, _>("labels"),
+        })
+    }
+
+    /// Inserts or replaces `task`. With `expected_version`, an existing row is
+    /// only replaced at that version; returns whether a row was written.
+    async fn upsert_task(
+        &self,
+        task: Task,
+        expected_version: Option<u64>,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let params_json: Option<JsonValue> = task
             .params
             .as_ref()
@@ -192,22 +468,50 @@ impl LongTermStore for PostgresLongTermStore {
                 .unwrap_or_default()
         });
         let cost_i32: Option<i32> = task.cost.map(|c| c as i32);
+        let filters_json: Option<JsonValue> = task
+            .filters
+            .as_ref()
+            .map(|f| serde_json::to_value(f).unwrap_or(JsonValue::Null));
+        let retry_policy_json: Option<JsonValue> = task
+            .retry_policy
+            .as_ref()
+            .map(|p| serde_json::to_value(p).unwrap_or(JsonValue::Null));
+        let forward_to_json: Option<JsonValue> = task
+            .forward_to
+            .as_ref()
+            .map(|f| serde_json::to_value(f).unwrap_or(JsonValue::Null));
+        let origin_json: Option<JsonValue> = task
+            .origin
+            .as_ref()
+            .map(|o| serde_json::to_value(o).unwrap_or(JsonValue::Null));
+        let completion_policy_json: Option<JsonValue> = task
+            .completion_policy
+            .as_ref()
+            .map(|p| serde_json::to_value(p).unwrap_or(JsonValue::Null));
         let disconnect_policy_str: Option<String> = task.disconnect_policy.as_ref().map(|d| {
             serde_json::to_value(d)
                 .ok()
                 .and_then(|v| v.as_str().map(|s| s.to_string()))
                 .unwrap_or_default()
         });
+        let group_policy_str: Option<String> = task.group_policy.as_ref().map(|p| {
+            serde_json::to_value(p)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.to_string()))
+                .unwrap_or_default()
+        });
 
         let sql = format!(
             r#"
             INSERT INTO {TASKS} (
                 id, type, status, params, result, error, metadata,
                 auth_config, webhooks, cleanup, created_at, updated_at, completed_at, ttl,
-                tags, assign_mode, cost, assigned_worker, disconnect_policy
+                tags, assign_mode, cost, assigned_worker, disconnect_policy, filters,
+                retry_policy, group_policy, forward_to, scheduled_for, version,
+                deadline_ms, origin, parent_id, completion_policy
             ) VALUES (
                 $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
-                $15, $16, $17, $18, $19
+                $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29
             )
             ON CONFLICT (id) DO UPDATE SET
                 status = EXCLUDED.status,
@@ -220,14 +524,22 @@ impl LongTermStore for PostgresLongTermStore {
                 assign_mode = EXCLUDED.assign_mode,
                 cost = EXCLUDED.cost,
                 assigned_worker = EXCLUDED.assigned_worker,
-                disconnect_policy = EXCLUDED.disconnect_policy
+                disconnect_policy = EXCLUDED.disconnect_policy,
+                filters = EXCLUDED.filters,
+                retry_policy = EXCLUDED.retry_policy,
+                group_policy = EXCLUDED.group_policy,
+                forward_to = EXCLUDED.forward_to,
+                parent_id = EXCLUDED.parent_id,
+                completion_policy = EXCLUDED.completion_policy,
+                version = EXCLUDED.version
+            WHERE $30::BIGINT IS NULL OR {TASKS}.version = $30
             "#
         );
 
         let status_str = serde_json::to_value(&task.status)
             .map(|v| v.as_str().unwrap_or("pending").to_string())?;
 
-        sqlx::query(&sql)
+        let result = sqlx::query(&sql)
             .bind(&task.id)
             .bind(&task.r#type)
             .bind(&status_str)
@@ -247,36 +559,468 @@ impl LongTermStore for PostgresLongTermStore {
             .bind(cost_i32)
             .bind(&task.assigned_worker)
             .bind(&disconnect_policy_str)
+            .bind(&filters_json)
+            .bind(&retry_policy_json)
+            .bind(&group_policy_str)
+            .bind(&forward_to_json)
+            .bind(task.scheduled_for.map(|v| v as i64))
+            .bind(task.version as i64)
+            .bind(task.deadline_ms.map(|v| v as i64))
+            .bind(&origin_json)
+            .bind(&task.parent_id)
+            .bind(&completion_policy_json)
+            .bind(expected_version.map(|v| v as i64))
             .execute(&self.pool)
-            .await?;
+            .await
+            .map_err(store_error)?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        self.recent_writes.mark(&task.id);
+
+        Ok(true)
+    }
+}
+
+/// Decodes a string column holding a serde enum variant.
+fn decode_enum<T: DeserializeOwned>(value: String, column: &str) -> Result<T, String> {
+    serde_json::from_value(JsonValue::String(value)).map_err(|e| format!("{column}: {e}"))
+}
 
+/// Decodes a nullable JSON column.
+fn decode_column<T: DeserializeOwned>(
+    value: Option<JsonValue>,
+    column: &str,
+) -> Result<Option<T>, String> {
+    value
+        .map(|v| serde_json::from_value(v).map_err(|e| format!("{column}: {e}")))
+        .transpose()
+}
+
+#[async_trait]
+impl LongTermStore for PostgresLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let task_id = task.id.clone();
+        self.timed("save_task", Some(&task_id), self.upsert_task(task, None))
+            .await?;
         Ok(())
     }
 
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let task_id = task.id.clone();
+        self.timed(
+            "save_task_versioned",
+            Some(&task_id),
+            self.upsert_task(task, Some(expected_version)),
+        )
+        .await
+    }
+
     async fn get_task(
         &self,
         task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed("get_task", Some(task_id), self.fetch_task(task_id))
+            .await
+    }
+
+    fn supports_task_listing(&self) -> bool {
+        true
+    }
+
+    async fn list_tasks(
+        &self,
+        query: &ListTasksQuery,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "list_tasks",
+            None,
+            self.fetch_tasks(&TasksQuery::new(query)),
+        )
+        .await
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let task_id = event.task_id.clone();
+        self.timed("save_event", Some(&task_id), self.insert_event(event))
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "replace_last_series_event",
+            Some(task_id),
+            self.replace_series_event(task_id, series_id, event),
+        )
+        .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "accumulate_series",
+            Some(task_id),
+            self.accumulate_series_event(task_id, series_id, event, field),
+        )
+        .await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = EventsQuery::new(opts.as_ref());
+        self.timed(
+            query.method(),
+            Some(task_id),
+            self.fetch_events(task_id, &query),
+        )
+        .await
+    }
+
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "get_event_type_counts",
+            Some(task_id),
+            self.fetch_event_type_counts(task_id),
+        )
+        .await
+    }
+
+    fn supports_series_compaction(&self) -> bool {
+        true
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        Some(&self.integrity)
+    }
+
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        Some(self)
+    }
+
+    async fn pool_health(&self) -> Vec<PoolHealth> {
+        let mut pools = vec![check_pool("write", &self.pool).await];
+        if let Some(ref read_pool) = self.read_pool {
+            pools.push(check_pool("read", read_pool).await);
+        }
+        pools
+    }
+
+    fn query_stats(&self) -> Option<QueryStatsSnapshot> {
+        Some(self.stats.snapshot())
+    }
+
+    async fn explain_get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = EventsQuery::new(opts.as_ref());
+        let sql = format!("EXPLAIN {}", query.sql);
+        let rows = query
+            .bind(&sql, task_id)
+            .fetch_all(self.read_pool_for(task_id))
+            .await
+            .map_err(store_error)?;
+        let lines = rows
+            .iter()
+            .map(|row| row.try_get::<String, _>(0))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(store_error)?;
+        Ok(Some(lines.join("\n")))
+    }
+
+    async fn list_event_task_ids(
+        &self,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "list_event_task_ids",
+            None,
+            self.fetch_event_task_ids(after, limit),
+        )
+        .await
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "delete_task",
+            Some(task_id),
+            PostgresLongTermStore::delete_task(self, task_id),
+        )
+        .await
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "redact_events",
+            Some(task_id),
+            PostgresLongTermStore::redact_events(self, task_id, tombstones),
+        )
+        .await
+    }
+
+    async fn save_worker_event(
+        &self,
+        event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.timed("save_worker_event", None, self.insert_worker_event(event))
+            .await
+    }
+
+    async fn get_worker_events(
+        &self,
+        worker_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "get_worker_events",
+            None,
+            self.fetch_worker_events(worker_id, opts),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl EventConventionStore for PostgresLongTermStore {
+    async fn record_event_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "record_event_conventions",
+            None,
+            self.upsert_conventions(observed),
+        )
+        .await
+    }
+
+    async fn annotate_event_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "annotate_event_convention",
+            None,
+            self.upsert_convention_notes(task_type, event_type, notes),
+        )
+        .await
+    }
+
+    async fn list_event_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        self.timed(
+            "list_event_conventions",
+            None,
+            self.fetch_conventions(task_type),
+        )
+        .await
+    }
+}
+
+// The queries behind the `LongTermStore` methods, which time each call.
+impl PostgresLongTermStore {
+    /// Runs `call`, recording its latency under `method`. Under a request
+    /// deadline the query is dropped once the deadline passes.
+    async fn timed<T: RowCount>(
+        &self,
+        method: &'static str,
+        task_id: Option<&str>,
+        call: impl Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        let started = Instant::now();
+        let result = request_deadline::limit(call).await;
+        let rows = result.as_ref().ok().and_then(RowCount::rows);
+        self.stats.record(method, task_id, rows, started.elapsed());
+        result
+    }
+
+    async fn upsert_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            INSERT INTO {CONVENTIONS} AS c (
+                task_type, event_type, estimated_count, last_seen_at, example, example_at
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (task_type, event_type) DO UPDATE SET
+                estimated_count = c.estimated_count + excluded.estimated_count,
+                last_seen_at = GREATEST(c.last_seen_at, excluded.last_seen_at),
+                example = CASE
+                    WHEN excluded.example_at >= COALESCE(c.example_at, excluded.example_at)
+                    THEN excluded.example ELSE c.example END,
+                example_at = GREATEST(c.example_at, excluded.example_at)
+            "#
+        );
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+        for sample in observed {
+            sqlx::query(&sql)
+                .bind(&sample.task_type)
+                .bind(&sample.event_type)
+                .bind(sample.estimated_count as i64)
+                .bind(sample.last_seen_at)
+                .bind(&sample.example)
+                .bind(sample.example_at)
+                .execute(&mut *tx)
+                .await
+                .map_err(store_error)?;
+        }
+        tx.commit().await.map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn upsert_convention_notes(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            INSERT INTO {CONVENTIONS} (task_type, event_type, description, data_schema)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (task_type, event_type) DO UPDATE SET
+                description = excluded.description,
+                data_schema = excluded.data_schema
+            "#
+        );
+        sqlx::query(&sql)
+            .bind(task_type)
+            .bind(event_type)
+            .bind(&notes.description)
+            .bind(&notes.schema)
+            .execute(&self.pool)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn fetch_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            SELECT * FROM {CONVENTIONS}
+            WHERE $1::TEXT IS NULL OR task_type = $1
+            ORDER BY task_type ASC, event_type ASC
+            "#
+        );
+        let rows = sqlx::query(&sql)
+            .bind(task_type)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+        rows.iter()
+            .map(|row| {
+                Ok(EventConvention {
+                    task_type: row.try_get("task_type")?,
+                    event_type: row.try_get("event_type")?,
+                    estimated_count: row.try_get::<i64, _>("estimated_count")?.max(0) as u64,
+                    last_seen_at: row.try_get("last_seen_at")?,
+                    example: row.try_get("example")?,
+                    example_at: row.try_get("example_at")?,
+                    description: row.try_get("description")?,
+                    schema: row.try_get("data_schema")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|err| store_error(err).into())
+    }
+
+    async fn fetch_task(
+        &self,
+        task_id: &str,
     ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
         let sql = format!("SELECT * FROM {TASKS} WHERE id = $1");
 
         let row = sqlx::query(&sql)
             .bind(task_id)
-            .fetch_optional(&self.pool)
-            .await?;
+            .fetch_optional(self.read_pool_for(task_id))
+            .await
+            .map_err(store_error)?;
+
+        match row {
+            Some(row) => Ok(self.integrity.task(Self::row_to_task(&row))?),
+            None => Ok(None),
+        }
+    }
 
-        Ok(row.as_ref().map(Self::row_to_task))
+    /// Reads from the read pool: a task that ended within the
+    /// read-after-write window may be missing from the page.
+    async fn fetch_tasks(
+        &self,
+        query: &TasksQuery,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = query
+            .bind()
+            .fetch_all(self.read_pool.as_ref().unwrap_or(&self.pool))
+            .await
+            .map_err(store_error)?;
+
+        let mut tasks = Vec::with_capacity(rows.len());
+        for row in &rows {
+            tasks.extend(self.integrity.task(Self::row_to_task(row))?);
+        }
+        Ok(TaskPage::from_sorted(tasks, query.page_size))
     }
 
-    async fn save_event(
+    async fn insert_event(
         &self,
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(blob) = self.dedup.as_ref().and_then(|d| d.dedupe(&event.data)) {
+            // The event row and its blob reference commit together.
+            let mut tx = self.pool.begin().await.map_err(store_error)?;
+            insert_event_pg_tx(&mut tx, &event, Some(blob)).await?;
+            tx.commit().await.map_err(store_error)?;
+            self.recent_writes.mark(&event.task_id);
+            return Ok(());
+        }
+
         let sql = format!(
             r#"
             INSERT INTO {EVENTS} (
-                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+                labels
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
             )
             ON CONFLICT (id) DO NOTHING
             "#
@@ -309,20 +1053,23 @@ impl LongTermStore for PostgresLongTermStore {
             .bind(&event.series_id)
             .bind(&series_mode_str)
             .bind(&event.series_acc_field)
+            .bind(labels_json_for_db(&event.labels))
             .execute(&self.pool)
-            .await?;
+            .await
+            .map_err(store_error)?;
+        self.recent_writes.mark(&event.task_id);
 
         Ok(())
     }
 
-    async fn replace_last_series_event(
+    async fn replace_series_event(
         &self,
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mode = series_mode_to_string(&SeriesMode::Latest).unwrap();
-        let mut tx = self.pool.begin().await?;
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
         let sql = format!(
             r#"
             SELECT * FROM {EVENTS}
@@ -335,32 +1082,39 @@ impl LongTermStore for PostgresLongTermStore {
             .bind(series_id)
             .bind(&mode)
             .fetch_all(&mut *tx)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
-        if let Some(existing) = rows.first().map(Self::row_to_event) {
-            update_stored_series_event_pg(&mut tx, &existing, &event).await?;
+        // A corrupt series event cannot be compacted, so the write fails.
+        if let Some(existing) = rows.first().map(Self::row_to_event).transpose()? {
+            update_stored_series_event_pg(&mut tx, &existing, &event, self.dedup.as_ref()).await?;
             let sql = format!(
                 r#"
                 DELETE FROM {EVENTS}
                 WHERE task_id = $1 AND series_id = $2 AND series_mode = $3 AND id <> $4
+                RETURNING data
                 "#
             );
-            sqlx::query(&sql)
+            let deleted = sqlx::query(&sql)
                 .bind(task_id)
                 .bind(series_id)
                 .bind(&mode)
                 .bind(&existing.id)
-                .execute(&mut *tx)
-                .await?;
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(store_error)?;
+            release_blobs_pg(&mut tx, blob_hashes(&deleted)).await?;
         } else {
-            insert_event_pg_tx(&mut tx, &event).await?;
+            let blob = self.dedup.as_ref().and_then(|d| d.dedupe(&event.data));
+            insert_event_pg_tx(&mut tx, &event, blob).await?;
         }
 
-        tx.commit().await?;
+        tx.commit().await.map_err(store_error)?;
+        self.recent_writes.mark(task_id);
         Ok(())
     }
 
-    async fn accumulate_series(
+    async fn accumulate_series_event(
         &self,
         task_id: &str,
         series_id: &str,
@@ -368,7 +1122,7 @@ impl LongTermStore for PostgresLongTermStore {
         field: &str,
     ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
         let mode = series_mode_to_string(&SeriesMode::Accumulate).unwrap();
-        let mut tx = self.pool.begin().await?;
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
         let sql = format!(
             r#"
             SELECT * FROM {EVENTS}
@@ -381,10 +1135,12 @@ impl LongTermStore for PostgresLongTermStore {
             .bind(series_id)
             .bind(&mode)
             .fetch_all(&mut *tx)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
-        let first = rows.first().map(Self::row_to_event);
-        let previous = rows.last().map(Self::row_to_event);
+        let first = rows.first().map(Self::row_to_event).transpose()?;
+        let mut previous = rows.last().map(Self::row_to_event).transpose()?;
+        resolve_blobs_pg(&mut *tx, previous.as_mut_slice()).await?;
         let accumulated = if let Some(previous) = previous {
             accumulate_task_event(&previous, event, field)
         } else {
@@ -392,107 +1148,113 @@ impl LongTermStore for PostgresLongTermStore {
         };
 
         if let Some(first) = first {
-            update_stored_series_event_pg(&mut tx, &first, &accumulated).await?;
+            update_stored_series_event_pg(&mut tx, &first, &accumulated, self.dedup.as_ref())
+                .await?;
             let sql = format!(
                 r#"
                 DELETE FROM {EVENTS}
                 WHERE task_id = $1 AND series_id = $2 AND series_mode = $3 AND id <> $4
+                RETURNING data
                 "#
             );
-            sqlx::query(&sql)
+            let deleted = sqlx::query(&sql)
                 .bind(task_id)
                 .bind(series_id)
                 .bind(&mode)
                 .bind(&first.id)
-                .execute(&mut *tx)
-                .await?;
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(store_error)?;
+            release_blobs_pg(&mut tx, blob_hashes(&deleted)).await?;
         } else {
-            insert_event_pg_tx(&mut tx, &accumulated).await?;
+            let blob = self
+                .dedup
+                .as_ref()
+                .and_then(|d| d.dedupe(&accumulated.data));
+            insert_event_pg_tx(&mut tx, &accumulated, blob).await?;
         }
 
-        tx.commit().await?;
+        tx.commit().await.map_err(store_error)?;
+        self.recent_writes.mark(task_id);
         Ok(accumulated)
     }
 
-    async fn get_events(
+    async fn fetch_events(
         &self,
         task_id: &str,
-        opts: Option<EventQueryOptions>,
+        query: &EventsQuery,
     ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
-        let since = opts.as_ref().and_then(|o| o.since.as_ref());
-        let limit = opts.as_ref().and_then(|o| o.limit);
-
-        // Use a bind parameter for LIMIT to prevent SQL injection.
-        // When no limit is specified, use a very large value (i.e. effectively unlimited).
-        let limit_val = limit.map(|l| l as i64).unwrap_or(i64::MAX);
-
-        let rows = if let Some(since) = since {
-            if let Some(index) = since.index {
-                let sql = format!(
-                    "SELECT * FROM {EVENTS} WHERE task_id = $1 AND idx > $2 ORDER BY idx ASC LIMIT $3"
-                );
-                sqlx::query(&sql)
-                    .bind(task_id)
-                    .bind(index as i32)
-                    .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
-            } else if let Some(timestamp) = since.timestamp {
-                let sql = format!(
-                    "SELECT * FROM {EVENTS} WHERE task_id = $1 AND timestamp > $2 ORDER BY idx ASC LIMIT $3"
-                );
-                sqlx::query(&sql)
-                    .bind(task_id)
-                    .bind(timestamp as i64)
-                    .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
-            } else if let Some(ref id) = since.id {
-                // Look up the anchor event's idx, then fetch events after it
-                let anchor_sql = format!("SELECT idx FROM {EVENTS} WHERE id = $1");
-                let anchor_row = sqlx::query(&anchor_sql)
-                    .bind(id)
-                    .fetch_optional(&self.pool)
-                    .await?;
-                let anchor_idx: i32 = anchor_row.as_ref().map(|r| r.get("idx")).unwrap_or(-1);
+        // Blob payloads are resolved by a second query; a repeatable-read
+        // transaction has both see the same snapshot.
+        let mut tx = self
+            .read_pool_for(task_id)
+            .begin()
+            .await
+            .map_err(store_error)?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+
+        let rows = query
+            .bind(&query.sql, task_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(store_error)?;
+
+        let mut events: Vec<TaskEvent> = rows
+            .iter()
+            .filter_map(|row| self.integrity.event(Self::row_to_event(row)))
+            .collect();
+        resolve_blobs_pg(&mut *tx, &mut events).await?;
+        tx.commit().await.map_err(store_error)?;
+        Ok(events)
+    }
 
-                let sql = format!(
-                    "SELECT * FROM {EVENTS} WHERE task_id = $1 AND idx > $2 ORDER BY idx ASC LIMIT $3"
-                );
-                sqlx::query(&sql)
-                    .bind(task_id)
-                    .bind(anchor_idx)
-                    .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
-            } else {
-                // since exists but has no usable cursor fields
-                let sql =
-                    format!("SELECT * FROM {EVENTS} WHERE task_id = $1 ORDER BY idx ASC LIMIT $2");
-                sqlx::query(&sql)
-                    .bind(task_id)
-                    .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
+    async fn fetch_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "SELECT type, level, COUNT(*) AS count FROM {EVENTS} WHERE task_id = $1 \
+             GROUP BY type, level"
+        );
+        let rows = sqlx::query(&sql)
+            .bind(task_id)
+            .fetch_all(self.read_pool_for(task_id))
+            .await
+            .map_err(store_error)?;
+
+        let mut counts = EventTypeCounts::default();
+        for row in rows {
+            let count = row.get::<i64, _>("count") as u64;
+            *counts.types.entry(row.get("type")).or_default() += count;
+            // Rows with an unknown level are reported when read as events.
+            if let Ok(level) = decode_enum::<Level>(row.get("level"), "level") {
+                *counts.levels.entry(level).or_default() += count;
             }
-        } else {
-            let sql =
-                format!("SELECT * FROM {EVENTS} WHERE task_id = $1 ORDER BY idx ASC LIMIT $2");
-            sqlx::query(&sql)
-                .bind(task_id)
-                .bind(limit_val)
-                .fetch_all(&self.pool)
-                .await?
-        };
-
-        Ok(rows.iter().map(Self::row_to_event).collect())
+        }
+        Ok(counts)
     }
 
-    fn supports_series_compaction(&self) -> bool {
-        true
+    async fn fetch_event_task_ids(
+        &self,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "SELECT DISTINCT task_id FROM {EVENTS} WHERE task_id > $1 ORDER BY task_id ASC LIMIT $2"
+        );
+        let rows = sqlx::query(&sql)
+            .bind(after.unwrap_or(""))
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(store_error)?;
+        Ok(rows.iter().map(|row| row.get("task_id")).collect())
     }
 
-    async fn save_worker_event(
+    async fn insert_worker_event(
         &self,
         event: WorkerAuditEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -520,12 +1282,13 @@ impl LongTermStore for PostgresLongTermStore {
             .bind(&action_str)
             .bind(&data_json)
             .execute(&self.pool)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
         Ok(())
     }
 
-    async fn get_worker_events(
+    async fn fetch_worker_events(
         &self,
         worker_id: &str,
         opts: Option<EventQueryOptions>,
@@ -533,6 +1296,7 @@ impl LongTermStore for PostgresLongTermStore {
         let since = opts.as_ref().and_then(|o| o.since.as_ref());
         let limit = opts.as_ref().and_then(|o| o.limit);
         let limit_val = limit.map(|l| l as i64).unwrap_or(i64::MAX);
+        let pool = self.read_pool.as_ref().unwrap_or(&self.pool);
 
         let rows = if let Some(since) = since {
             if let Some(timestamp) = since.timestamp {
@@ -543,15 +1307,17 @@ impl LongTermStore for PostgresLongTermStore {
                     .bind(worker_id)
                     .bind(timestamp as i64)
                     .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
+                    .fetch_all(pool)
+                    .await
+                    .map_err(store_error)?
             } else if let Some(ref id) = since.id {
                 // Look up the anchor event's timestamp, then fetch events after it
                 let anchor_sql = format!("SELECT timestamp FROM {WORKER_EVENTS} WHERE id = $1");
                 let anchor_row = sqlx::query(&anchor_sql)
                     .bind(id)
-                    .fetch_optional(&self.pool)
-                    .await?;
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(store_error)?;
                 let anchor_ts: i64 = anchor_row
                     .as_ref()
                     .map(|r| r.get("timestamp"))
@@ -565,8 +1331,9 @@ impl LongTermStore for PostgresLongTermStore {
                     .bind(anchor_ts)
                     .bind(id)
                     .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
+                    .fetch_all(pool)
+                    .await
+                    .map_err(store_error)?
             } else {
                 // since exists but has no usable cursor fields
                 let sql = format!(
@@ -575,8 +1342,9 @@ impl LongTermStore for PostgresLongTermStore {
                 sqlx::query(&sql)
                     .bind(worker_id)
                     .bind(limit_val)
-                    .fetch_all(&self.pool)
-                    .await?
+                    .fetch_all(pool)
+                    .await
+                    .map_err(store_error)?
             }
         } else {
             let sql = format!(
@@ -585,33 +1353,375 @@ impl LongTermStore for PostgresLongTermStore {
             sqlx::query(&sql)
                 .bind(worker_id)
                 .bind(limit_val)
-                .fetch_all(&self.pool)
-                .await?
+                .fetch_all(pool)
+                .await
+                .map_err(store_error)?
         };
 
         Ok(rows.iter().map(Self::row_to_worker_event).collect())
     }
 }
 
+/// Rows a store call returned, for the slow query log. `None` for calls that
+/// do not read rows.
+trait RowCount {
+    fn rows(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<T> RowCount for Vec<T> {
+    fn rows(&self) -> Option<u64> {
+        Some(self.len() as u64)
+    }
+}
+
+impl<T> RowCount for Option<T> {
+    fn rows(&self) -> Option<u64> {
+        Some(self.is_some() as u64)
+    }
+}
+
+impl RowCount for () {}
+impl RowCount for bool {}
+impl RowCount for u64 {}
+impl RowCount for TaskEvent {}
+impl RowCount for EventTypeCounts {}
+
+impl RowCount for TaskPage {
+    fn rows(&self) -> Option<u64> {
+        Some(self.tasks.len() as u64)
+    }
+}
+
+/// How a `list_tasks` query matches the task type.
+enum TypeCondition {
+    /// `*`: any task with a type.
+    Any,
+    /// `prefix.*`, as a `LIKE` pattern.
+    Like(String),
+    Exact(String),
+}
+
+/// The SQL of a `list_tasks` call and the values bound to it. Tasks are
+/// read in `(created_at, id)` order from after the query's start, one row
+/// past the page to tell whether another follows.
+struct TasksQuery {
+    statuses: Option<Vec<String>>,
+    task_type: Option<TypeCondition>,
+    origins: Option<Vec<String>>,
+    after: Option<(i64, String)>,
+    created_before: Option<i64>,
+    page_size: usize,
+    sql: String,
+}
+
+impl TasksQuery {
+    fn new(query: &ListTasksQuery) -> Self {
+        let task_type = query.r#type.as_ref().map(|pattern| {
+            if pattern == "*" {
+                TypeCondition::Any
+            } else if is_type_pattern(pattern) {
+                let prefix = &pattern[..pattern.len() - 1];
+                TypeCondition::Like(format!("{}%", escape_like(prefix)))
+            } else {
+                TypeCondition::Exact(pattern.clone())
+            }
+        });
+        // Timestamps are stored as whole milliseconds.
+        let after = query
+            .start()
+            .map(|cursor| (cursor.created_at.ceil() as i64, cursor.id));
+        let mut tasks_query = Self {
+            statuses: query.status.as_ref().map(|statuses| {
+                statuses
+                    .iter()
+                    .filter_map(|status| {
+                        serde_json::to_value(status)
+                            .ok()
+                            .and_then(|v| v.as_str().map(str::to_string))
+                    })
+                    .collect()
+            }),
+            task_type,
+            origins: query
+                .origin
+                .as_ref()
+                .map(|origins| origins.iter().map(|o| o.as_str().to_string()).collect()),
+            after,
+            created_before: query.created_before.map(|before| before.ceil() as i64),
+            page_size: query.limit.unwrap_or(usize::MAX),
+            sql: String::new(),
+        };
+        tasks_query.sql = tasks_query.build_sql();
+        tasks_query
+    }
+
+    fn build_sql(&self) -> String {
+        let mut conditions = Vec::new();
+        let mut param = 1;
+        let mut next_param = || {
+            let current = param;
+            param += 1;
+            format!("${current}")
+        };
+        if self.statuses.is_some() {
+            conditions.push(format!("status = ANY({})", next_param()));
+        }
+        match self.task_type {
+            Some(TypeCondition::Any) => conditions.push("type IS NOT NULL".to_string()),
+            Some(TypeCondition::Like(_)) => {
+                conditions.push(format!("type LIKE {} ESCAPE '\\'", next_param()))
+            }
+            Some(TypeCondition::Exact(_)) => conditions.push(format!("type = {}", next_param())),
+            None => {}
+        }
+        if self.origins.is_some() {
+            // Rows written before origins were recorded count as user-created.
+            conditions.push(format!(
+                "COALESCE(origin->>'kind', 'user') = ANY({})",
+                next_param()
+            ));
+        }
+        if self.after.is_some() {
+            conditions.push(format!(
+                "(created_at, id COLLATE \"C\") > ({}, {})",
+                next_param(),
+                next_param()
+            ));
+        }
+        if self.created_before.is_some() {
+            conditions.push(format!("created_at < {}", next_param()));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        format!(
+            "SELECT * FROM {TASKS}{where_clause} ORDER BY created_at ASC, id COLLATE \"C\" ASC LIMIT {}",
+            next_param()
+        )
+    }
+
+    fn bind(&self) -> Query<'_, Postgres, PgArguments> {
+        let mut query = sqlx::query(&self.sql);
+        if let Some(ref statuses) = self.statuses {
+            query = query.bind(statuses);
+        }
+        match self.task_type {
+            Some(TypeCondition::Like(ref pattern)) | Some(TypeCondition::Exact(ref pattern)) => {
+                query = query.bind(pattern);
+            }
+            Some(TypeCondition::Any) | None => {}
+        }
+        if let Some(ref origins) = self.origins {
+            query = query.bind(origins);
+        }
+        if let Some((created_at, ref id)) = self.after {
+            query = query.bind(created_at).bind(id);
+        }
+        if let Some(created_before) = self.created_before {
+            query = query.bind(created_before);
+        }
+        let limit = i64::try_from(self.page_size.saturating_add(1)).unwrap_or(i64::MAX);
+        query.bind(limit)
+    }
+}
+
+/// Escapes `LIKE` wildcards in `value`, for use with `ESCAPE '\'`.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Where a `get_events` query starts. `since.id` takes priority over
+/// `since.index`, which takes priority over `since.timestamp`.
+enum EventsCursor {
+    Id(String),
+    Index(i32),
+    Timestamp(i64),
+    Start,
+}
+
+/// The SQL of a `get_events` call and the values bound to it, shared by
+/// the read and its `EXPLAIN`.
+struct EventsQuery {
+    cursor: EventsCursor,
+    limit: i64,
+    label_selector: Option<JsonValue>,
+    until: Option<i64>,
+    before_index: Option<i64>,
+    sql: String,
+}
+
+impl EventsQuery {
+    fn new(opts: Option<&EventQueryOptions>) -> Self {
+        let since = opts.and_then(|o| o.since.as_ref());
+        let cursor = match since {
+            Some(since) => match (&since.id, since.index, since.timestamp) {
+                (Some(id), _, _) => EventsCursor::Id(id.clone()),
+                (None, Some(index), _) => EventsCursor::Index(index as i32),
+                (None, None, Some(timestamp)) => EventsCursor::Timestamp(timestamp as i64),
+                // since exists but has no usable cursor fields
+                (None, None, None) => EventsCursor::Start,
+            },
+            None => EventsCursor::Start,
+        };
+        // Use a bind parameter for LIMIT to prevent SQL injection.
+        // When no limit is specified, use a very large value (i.e. effectively unlimited).
+        let limit = opts
+            .and_then(|o| o.limit)
+            .map(|l| l as i64)
+            .unwrap_or(i64::MAX);
+        // The label selector is pushed down as JSONB containment (`labels @> ...`),
+        // which is served by the GIN index on `labels`.
+        let label_selector = opts
+            .and_then(|o| o.label_selector.as_ref())
+            .map(|selector| serde_json::json!(selector));
+        // Timestamps are stored as whole milliseconds.
+        let until = opts.and_then(|o| o.until).map(|until| until.floor() as i64);
+        let before_index = opts.and_then(|o| o.before_index).map(|index| index as i64);
+
+        let mut query = Self {
+            cursor,
+            limit,
+            label_selector,
+            until,
+            before_index,
+            sql: String::new(),
+        };
+        query.sql = query.build_sql();
+        query
+    }
+
+    fn build_sql(&self) -> String {
+        const ORDER: &str = "ORDER BY idx ASC, timestamp ASC, id COLLATE \"C\" ASC";
+        match self.cursor {
+            // The anchor is resolved within this task only; an unknown id
+            // falls back to the full history (idx > -1). `UNIQUE(task_id, idx)`
+            // rules out ties, so comparing indexes alone resumes right after
+            // the anchor in the canonical order.
+            EventsCursor::Id(_) => format!(
+                "SELECT * FROM {EVENTS} WHERE task_id = $1 AND idx > COALESCE(\
+                 (SELECT idx FROM {EVENTS} WHERE task_id = $1 AND id = $2), -1)\
+                 {} {ORDER} LIMIT $3",
+                self.filter_clause(4)
+            ),
+            EventsCursor::Index(_) => format!(
+                "SELECT * FROM {EVENTS} WHERE task_id = $1 AND idx > $2{} {ORDER} LIMIT $3",
+                self.filter_clause(4)
+            ),
+            EventsCursor::Timestamp(_) => format!(
+                "SELECT * FROM {EVENTS} WHERE task_id = $1 AND timestamp > $2{} {ORDER} LIMIT $3",
+                self.filter_clause(4)
+            ),
+            EventsCursor::Start => format!(
+                "SELECT * FROM {EVENTS} WHERE task_id = $1{} {ORDER} LIMIT $2",
+                self.filter_clause(3)
+            ),
+        }
+    }
+
+    /// Name of the query's histogram: `get_events` by cursor type.
+    fn method(&self) -> &'static str {
+        match self.cursor {
+            EventsCursor::Id(_) => "get_events.since_id",
+            EventsCursor::Index(_) => "get_events.since_index",
+            EventsCursor::Timestamp(_) => "get_events.since_timestamp",
+            EventsCursor::Start => "get_events.full",
+        }
+    }
+
+    /// SQL fragment for an `until` bound, a `before_index` bound and a label
+    /// selector, bound in that order from parameter `$param`, or `""` when
+    /// there is none of them.
+    fn filter_clause(&self, param: usize) -> String {
+        let mut clause = String::new();
+        let mut param = param;
+        if self.until.is_some() {
+            clause.push_str(&format!(" AND timestamp <= ${param}"));
+            param += 1;
+        }
+        if self.before_index.is_some() {
+            clause.push_str(&format!(" AND idx < ${param}"));
+            param += 1;
+        }
+        if self.label_selector.is_some() {
+            clause.push_str(&format!(" AND labels @> ${param}"));
+        }
+        clause
+    }
+
+    /// Binds the query's values to `sql`, which is [`Self::sql`] or a
+    /// statement wrapping it.
+    fn bind<'q>(&'q self, sql: &'q str, task_id: &'q str) -> Query<'q, Postgres, PgArguments> {
+        let query = sqlx::query(sql).bind(task_id);
+        let query = match self.cursor {
+            EventsCursor::Id(ref id) => query.bind(id),
+            EventsCursor::Index(index) => query.bind(index),
+            EventsCursor::Timestamp(timestamp) => query.bind(timestamp),
+            EventsCursor::Start => query,
+        };
+        let query = query.bind(self.limit);
+        let query = match self.until {
+            Some(until) => query.bind(until),
+            None => query,
+        };
+        let query = match self.before_index {
+            Some(index) => query.bind(index),
+            None => query,
+        };
+        match self.label_selector {
+            Some(ref selector) => query.bind(selector),
+            None => query,
+        }
+    }
+}
+
+/// Probes `pool` with `SELECT 1`.
+async fn check_pool(name: &str, pool: &PgPool) -> PoolHealth {
+    let started = Instant::now();
+    let result = tokio::time::timeout(POOL_HEALTH_TIMEOUT, sqlx::query("SELECT 1").execute(pool))
+        .await
+        .map_err(|_| format!("timed out after {}ms", POOL_HEALTH_TIMEOUT.as_millis()))
+        .and_then(|result| result.map_err(|e| e.to_string()));
+    PoolHealth {
+        name: name.to_string(),
+        ok: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: result.err(),
+    }
+}
+
+/// Inserts an event, storing `blob` (if any) as a reference and taking a blob
+/// reference only when the row is actually inserted.
 async fn insert_event_pg_tx(
     tx: &mut Transaction<'_, Postgres>,
     event: &TaskEvent,
+    blob: Option<DedupedPayload>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let sql = format!(
         r#"
         INSERT INTO {EVENTS} (
-            id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+            id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+            labels
         ) VALUES (
-            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11
         )
         ON CONFLICT (id) DO NOTHING
         "#
     );
     let level_str = level_to_string(&event.level)?;
     let series_mode_str = event.series_mode.as_ref().and_then(series_mode_to_string);
-    let data_json = data_json_for_db(&event.data);
+    let data_json = match blob {
+        Some(ref blob) => Some(blob_ref(&blob.hash)),
+        None => data_json_for_db(&event.data),
+    };
 
-    sqlx::query(&sql)
+    let inserted = sqlx::query(&sql)
         .bind(&event.id)
         .bind(&event.task_id)
         .bind(event.index as i32)
@@ -622,16 +1732,28 @@ async fn insert_event_pg_tx(
         .bind(&event.series_id)
         .bind(&series_mode_str)
         .bind(&event.series_acc_field)
+        .bind(labels_json_for_db(&event.labels))
         .execute(&mut **tx)
-        .await?;
+        .await
+        .map_err(store_error)?
+        .rows_affected();
+
+    if let Some(ref blob) = blob {
+        if inserted > 0 {
+            acquire_blob_pg(tx, blob).await?;
+        }
+    }
 
     Ok(())
 }
 
+/// Overwrites a stored series event in place. `existing` is the row as
+/// stored, so a blob it references is released.
 async fn update_stored_series_event_pg(
     tx: &mut Transaction<'_, Postgres>,
     existing: &TaskEvent,
     event: &TaskEvent,
+    dedup: Option<&PayloadDedupConfig>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let sql = format!(
         r#"
@@ -642,13 +1764,21 @@ async fn update_stored_series_event_pg(
             data = $4,
             series_id = $5,
             series_mode = $6,
-            series_acc_field = $7
-        WHERE id = $8
+            series_acc_field = $7,
+            labels = $8
+        WHERE id = $9
         "#
     );
     let level_str = level_to_string(&event.level)?;
     let series_mode_str = event.series_mode.as_ref().and_then(series_mode_to_string);
-    let data_json = data_json_for_db(&event.data);
+    let blob = dedup.and_then(|d| d.dedupe(&event.data));
+    let data_json = match blob {
+        Some(ref blob) => Some(blob_ref(&blob.hash)),
+        None => data_json_for_db(&event.data),
+    };
+    if let Some(ref blob) = blob {
+        acquire_blob_pg(tx, blob).await?;
+    }
 
     sqlx::query(&sql)
         .bind(event.timestamp as i64)
@@ -658,10 +1788,104 @@ async fn update_stored_series_event_pg(
         .bind(&event.series_id)
         .bind(&series_mode_str)
         .bind(&event.series_acc_field)
+        .bind(labels_json_for_db(&event.labels))
         .bind(&existing.id)
         .execute(&mut **tx)
-        .await?;
+        .await
+        .map_err(store_error)?;
+
+    if let Some(hash) = blob_ref_hash(&existing.data) {
+        release_blobs_pg(tx, vec![hash.to_string()]).await?;
+    }
+
+    Ok(())
+}
+
+/// Stores the blob if absent and takes one reference on it.
+async fn acquire_blob_pg(
+    tx: &mut Transaction<'_, Postgres>,
+    blob: &DedupedPayload,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let sql = format!(
+        r#"
+        INSERT INTO {BLOBS} (hash, data, refcount) VALUES ($1, $2, 1)
+        ON CONFLICT (hash) DO UPDATE SET refcount = {BLOBS}.refcount + 1
+        "#
+    );
+    sqlx::query(&sql)
+        .bind(&blob.hash)
+        .bind(&blob.json)
+        .execute(&mut **tx)
+        .await
+        .map_err(store_error)?;
+    Ok(())
+}
+
+/// Drops one reference per hash, deleting blobs that are no longer
+/// referenced. Hashes are processed in sorted order so concurrent releases
+/// lock blob rows in the same order.
+async fn release_blobs_pg(
+    tx: &mut Transaction<'_, Postgres>,
+    mut hashes: Vec<String>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    hashes.sort();
+    let decrement = format!("UPDATE {BLOBS} SET refcount = refcount - 1 WHERE hash = $1");
+    let delete = format!("DELETE FROM {BLOBS} WHERE hash = $1 AND refcount <= 0");
+    for hash in &hashes {
+        sqlx::query(&decrement)
+            .bind(hash)
+            .execute(&mut **tx)
+            .await
+            .map_err(store_error)?;
+        sqlx::query(&delete)
+            .bind(hash)
+            .execute(&mut **tx)
+            .await
+            .map_err(store_error)?;
+    }
+    Ok(())
+}
+
+/// Blob hashes referenced by the `data` column of deleted rows.
+fn blob_hashes(rows: &[PgRow]) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| row.get::<Option<JsonValue>, _>("data"))
+        .filter_map(|data| blob_ref_hash(&data).map(str::to_string))
+        .collect()
+}
+
+/// Replaces blob references with their payloads. A reference with no blob
+/// is left as is: it is an ordinary payload that happens to look like one
+/// (written while deduplication was off).
+async fn resolve_blobs_pg<'e>(
+    executor: impl PgExecutor<'e>,
+    events: &mut [TaskEvent],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let hashes: Vec<String> = events
+        .iter()
+        .filter_map(|e| blob_ref_hash(&e.data).map(str::to_string))
+        .collect();
+    if hashes.is_empty() {
+        return Ok(());
+    }
 
+    let sql = format!("SELECT hash, data FROM {BLOBS} WHERE hash = ANY($1)");
+    let rows = sqlx::query(&sql)
+        .bind(&hashes)
+        .fetch_all(executor)
+        .await
+        .map_err(store_error)?;
+    let mut resolved = HashMap::new();
+    for row in &rows {
+        let hash: String = row.get("hash");
+        let data: String = row.get("data");
+        resolved.insert(hash, serde_json::from_str::<JsonValue>(&data)?);
+    }
+    for event in events.iter_mut() {
+        if let Some(data) = blob_ref_hash(&event.data).and_then(|h| resolved.get(h)) {
+            event.data = data.clone();
+        }
+    }
     Ok(())
 }
 
@@ -711,6 +1935,13 @@ fn data_json_for_db(data: &JsonValue) -> Option<JsonValue> {
     }
 }
 
+fn labels_json_for_db(labels: &Option<HashMap<String, String>>) -> Option<JsonValue> {
+    labels
+        .as_ref()
+        .filter(|labels| !labels.is_empty())
+        .map(|labels| serde_json::json!(labels))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
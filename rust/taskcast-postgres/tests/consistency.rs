@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use taskcast_core::types::{Level, LongTermStore, ShortTermStore, Task, TaskEvent, TaskStatus};
+use taskcast_core::{scan_consistency, MemoryShortTermStore, ScanOptions};
+use taskcast_test_backends::{make_postgres_store, unique_schema};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+struct Stores {
+    short_term: Arc<dyn ShortTermStore>,
+    long_term: Arc<dyn LongTermStore>,
+}
+
+async fn setup() -> Option<Stores> {
+    let store = make_postgres_store(&unique_schema("consistency")).await?;
+    Some(Stores {
+        short_term: Arc::new(MemoryShortTermStore::new()),
+        long_term: Arc::new(store),
+    })
+}
+
+fn make_task(id: &str, status: TaskStatus) -> Task {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "status": status,
+        "createdAt": 1000.0,
+        "updatedAt": 2000.0,
+        "completedAt": 2000.0,
+    }))
+    .unwrap()
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "n": index }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+async fn scan(stores: &Stores, repair: bool) -> taskcast_core::ConsistencyReport {
+    scan_consistency(
+        &stores.short_term,
+        Some(&stores.long_term),
+        ScanOptions {
+            repair,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn orphaned_events_are_found_and_deleted() {
+    let Some(stores) = setup().await else { return };
+    for index in 0..3 {
+        stores
+            .long_term
+            .save_event(make_event("orphan", index))
+            .await
+            .unwrap();
+    }
+    stores
+        .long_term
+        .save_task(make_task("kept", TaskStatus::Completed))
+        .await
+        .unwrap();
+    stores
+        .long_term
+        .save_event(make_event("kept", 0))
+        .await
+        .unwrap();
+
+    let report = scan(&stores, false).await;
+    assert_eq!(report.long_term_tasks_scanned, 2);
+    assert_eq!(report.counts.orphaned_events, 1);
+    assert!(report.skipped.is_empty());
+
+    let report = scan(&stores, true).await;
+    assert_eq!(report.repaired, 1);
+    assert!(stores
+        .long_term
+        .get_events("orphan", None)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        stores
+            .long_term
+            .get_events("kept", None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(scan(&stores, false).await.is_clean());
+}
+
+#[tokio::test]
+async fn terminal_tasks_missing_from_long_term_are_backfilled() {
+    let Some(stores) = setup().await else { return };
+    stores
+        .short_term
+        .save_task(make_task("ended", TaskStatus::Failed))
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let index = stores.short_term.next_index("ended").await.unwrap();
+        stores
+            .short_term
+            .append_event("ended", make_event("ended", index))
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(scan(&stores, false).await.counts.missing_from_long_term, 1);
+    assert_eq!(scan(&stores, true).await.repaired, 1);
+
+    let task = stores.long_term.get_task("ended").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Failed);
+    assert_eq!(
+        stores
+            .long_term
+            .get_events("ended", None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(scan(&stores, false).await.is_clean());
+}
@@ -0,0 +1,239 @@
+use std::sync::{Arc, Mutex};
+
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use taskcast_core::types::{
+    ErrorContext, Level, LongTermStore, Task, TaskEvent, TaskStatus, TaskcastHooks,
+};
+use taskcast_core::{
+    CorruptRecord, CorruptRecordKind, CorruptRecordPolicy, IntegrityMonitor, CORRUPT_EVENT_TYPE,
+};
+use taskcast_postgres::PostgresLongTermStore;
+use taskcast_test_backends::{postgres_url, unique_schema};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct RecordingHooks {
+    errors: Mutex<Vec<(String, ErrorContext)>>,
+}
+
+impl TaskcastHooks for RecordingHooks {
+    fn on_unhandled_error(
+        &self,
+        err: &(dyn std::error::Error + Send + Sync),
+        context: &ErrorContext,
+    ) {
+        self.errors
+            .lock()
+            .unwrap()
+            .push((err.to_string(), context.clone()));
+    }
+}
+
+struct Context {
+    store: PostgresLongTermStore,
+    pool: PgPool,
+    monitor: IntegrityMonitor,
+    hooks: Arc<RecordingHooks>,
+}
+
+async fn setup(policy: CorruptRecordPolicy) -> Option<Context> {
+    let database_url = postgres_url(&unique_schema("corrupt_records")).await?;
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .unwrap();
+    let hooks = Arc::new(RecordingHooks::default());
+    let monitor =
+        IntegrityMonitor::with_hooks(policy, Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+    let store = PostgresLongTermStore::new(pool.clone()).with_integrity(monitor.clone());
+    store.migrate().await.unwrap();
+    Some(Context {
+        store,
+        pool,
+        monitor,
+        hooks,
+    })
+}
+
+fn make_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: None,
+        status: TaskStatus::Pending,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+        ttl: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "i": index }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// Saves a task with three valid events, then corrupts the event at index 1
+/// with `sql`, which is bound to that event's id.
+async fn seed_and_corrupt_event(ctx: &Context, sql: &str) {
+    ctx.store.save_task(make_task("t1")).await.unwrap();
+    for i in 0..3 {
+        ctx.store.save_event(make_event("t1", i)).await.unwrap();
+    }
+    sqlx::query(sql)
+        .bind("evt-t1-1")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+}
+
+async fn corrupt_task(ctx: &Context, sql: &str) {
+    ctx.store.save_task(make_task("t1")).await.unwrap();
+    sqlx::query(sql)
+        .bind("t1")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+}
+
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_returns_invalid_level_as_corrupt_event() {
+    let Some(ctx) = setup(CorruptRecordPolicy::Surface).await else {
+        return;
+    };
+    seed_and_corrupt_event(
+        &ctx,
+        "UPDATE taskcast_events SET level = 'critical' WHERE id = $1",
+    )
+    .await;
+
+    let events = ctx.store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1].id, "evt-t1-1");
+    assert_eq!(events[1].index, 1);
+    assert_eq!(events[1].r#type, CORRUPT_EVENT_TYPE);
+    assert_eq!(events[1].level, Level::Error);
+    assert_eq!(events[1].data["raw"]["level"], "critical");
+    assert_eq!(events[1].data["raw"]["data"], serde_json::json!({ "i": 1 }));
+    assert!(events[1].data["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("level:"));
+    assert_eq!(events[0].level, Level::Info);
+    assert_eq!(events[2].data, serde_json::json!({ "i": 2 }));
+
+    ctx.store.get_events("t1", None).await.unwrap();
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+    assert!(ctx.hooks.errors.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn skip_policy_drops_event_with_malformed_labels() {
+    let Some(ctx) = setup(CorruptRecordPolicy::Skip).await else {
+        return;
+    };
+    seed_and_corrupt_event(
+        &ctx,
+        "UPDATE taskcast_events SET labels = '[1, 2]'::jsonb WHERE id = $1",
+    )
+    .await;
+
+    let events = ctx.store.get_events("t1", None).await.unwrap();
+    let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indices, vec![0, 2]);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].0.contains("evt-t1-1"), "{}", errors[0].0);
+    assert_eq!(errors[0].1.operation, "storage.read_event");
+    assert_eq!(errors[0].1.task_id.as_deref(), Some("t1"));
+    drop(errors);
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+// ─── Tasks ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_fails_task_read_with_unknown_status() {
+    let Some(ctx) = setup(CorruptRecordPolicy::Surface).await else {
+        return;
+    };
+    corrupt_task(
+        &ctx,
+        "UPDATE taskcast_tasks SET status = 'exploded' WHERE id = $1",
+    )
+    .await;
+
+    let err = ctx.store.get_task("t1").await.unwrap_err();
+    let record = err.downcast_ref::<CorruptRecord>().expect("corrupt record");
+    assert_eq!(record.kind, CorruptRecordKind::Task);
+    assert_eq!(record.task_id, "t1");
+    assert_eq!(record.raw["status"], "exploded");
+    assert!(record.reason.starts_with("status:"), "{}", record.reason);
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+#[tokio::test]
+async fn skip_policy_hides_task_with_malformed_params() {
+    let Some(ctx) = setup(CorruptRecordPolicy::Skip).await else {
+        return;
+    };
+    corrupt_task(
+        &ctx,
+        "UPDATE taskcast_tasks SET params = '\"not an object\"'::jsonb WHERE id = $1",
+    )
+    .await;
+
+    assert_eq!(ctx.store.get_task("t1").await.unwrap(), None);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].1.operation, "storage.read_task");
+    assert!(errors[0].0.contains("params"), "{}", errors[0].0);
+    drop(errors);
+    assert_eq!(ctx.monitor.total_corrupt_records(), 1);
+}
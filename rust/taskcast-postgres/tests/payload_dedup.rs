@@ -0,0 +1,251 @@
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{PgPool, Row};
+
+use taskcast_core::payload_dedup::{content_hash, PayloadDedupConfig};
+use taskcast_core::types::{Level, LongTermStore, SeriesMode, TaskEvent};
+use taskcast_postgres::PostgresLongTermStore;
+use taskcast_test_backends::{postgres_url, unique_schema};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+const MIN_BYTES: usize = 256;
+
+async fn setup() -> Option<(PostgresLongTermStore, PgPool)> {
+    let database_url = postgres_url(&unique_schema("payload_dedup")).await?;
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .unwrap();
+    let store = PostgresLongTermStore::with_payload_dedup(
+        pool.clone(),
+        PayloadDedupConfig {
+            min_bytes: MIN_BYTES,
+        },
+    );
+    store.migrate().await.unwrap();
+    Some((store, pool))
+}
+
+fn large_payload() -> serde_json::Value {
+    serde_json::json!({
+        "config": { "model": "m-1", "prompt": "p".repeat(MIN_BYTES), "temperature": 0.25 },
+        "attempt": null,
+        "tags": ["retry", "échec", 1, 2.5e10]
+    })
+}
+
+fn make_event(task_id: &str, index: u64, data: serde_json::Value) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "retry".to_string(),
+        level: Level::Info,
+        data,
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn hash_of(data: &serde_json::Value) -> String {
+    content_hash(&serde_json::to_string(data).unwrap())
+}
+
+async fn refcount(pool: &PgPool, hash: &str) -> Option<i32> {
+    sqlx::query("SELECT refcount FROM taskcast_blobs WHERE hash = $1")
+        .bind(hash)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+        .map(|row| row.get("refcount"))
+}
+
+async fn stored_data(pool: &PgPool, event_id: &str) -> Option<serde_json::Value> {
+    sqlx::query("SELECT data FROM taskcast_events WHERE id = $1")
+        .bind(event_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("data")
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn large_payload_is_stored_once_and_referenced() {
+    let Some((store, pool)) = setup().await else {
+        return;
+    };
+    let data = large_payload();
+    let hash = hash_of(&data);
+
+    for i in 0..3 {
+        store
+            .save_event(make_event("t1", i, data.clone()))
+            .await
+            .unwrap();
+    }
+    // Re-saving an existing event id must not take another reference.
+    store
+        .save_event(make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stored_data(&pool, "evt-t1-0").await,
+        Some(serde_json::json!({ "$blobRef": hash }))
+    );
+    assert_eq!(refcount(&pool, &hash).await, Some(3));
+}
+
+#[tokio::test]
+async fn resolved_reads_are_byte_identical() {
+    let Some((store, _pool)) = setup().await else {
+        return;
+    };
+    let data = large_payload();
+
+    store
+        .save_event(make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        serde_json::to_string(&events[0].data).unwrap(),
+        serde_json::to_string(&data).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn refcounts_follow_event_and_task_deletion() {
+    let Some((store, pool)) = setup().await else {
+        return;
+    };
+    let data = large_payload();
+    let hash = hash_of(&data);
+
+    for (task_id, index) in [("t1", 0), ("t1", 1), ("t2", 0)] {
+        store
+            .save_event(make_event(task_id, index, data.clone()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(refcount(&pool, &hash).await, Some(3));
+
+    store
+        .delete_events("t1", &["evt-t1-0".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(refcount(&pool, &hash).await, Some(2));
+    let remaining = store.get_events("t1", None).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].data, data);
+
+    store.delete_task("t1").await.unwrap();
+    assert_eq!(refcount(&pool, &hash).await, Some(1));
+    assert!(store.get_events("t1", None).await.unwrap().is_empty());
+
+    store.delete_task("t2").await.unwrap();
+    assert_eq!(refcount(&pool, &hash).await, None);
+}
+
+#[tokio::test]
+async fn series_compaction_releases_replaced_payloads() {
+    let Some((store, pool)) = setup().await else {
+        return;
+    };
+    let first = large_payload();
+    let second = serde_json::json!({ "config": "q".repeat(MIN_BYTES) });
+
+    let mut event = make_event("t1", 0, first.clone());
+    event.series_id = Some("s1".to_string());
+    event.series_mode = Some(SeriesMode::Latest);
+    store
+        .replace_last_series_event("t1", "s1", event.clone())
+        .await
+        .unwrap();
+    store
+        .replace_last_series_event(
+            "t1",
+            "s1",
+            TaskEvent {
+                id: "evt-t1-1".to_string(),
+                index: 1,
+                data: second.clone(),
+                ..event
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(refcount(&pool, &hash_of(&first)).await, None);
+    assert_eq!(refcount(&pool, &hash_of(&second)).await, Some(1));
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].data, second);
+}
+
+#[tokio::test]
+async fn accumulate_series_reads_through_blob_reference() {
+    let Some((store, _pool)) = setup().await else {
+        return;
+    };
+    let prefix = "a".repeat(MIN_BYTES);
+
+    let mut event = make_event("t1", 0, serde_json::json!({ "text": prefix }));
+    event.series_id = Some("s1".to_string());
+    event.series_mode = Some(SeriesMode::Accumulate);
+    store
+        .accumulate_series("t1", "s1", event.clone(), "text")
+        .await
+        .unwrap();
+    let accumulated = store
+        .accumulate_series(
+            "t1",
+            "s1",
+            TaskEvent {
+                id: "evt-t1-1".to_string(),
+                index: 1,
+                data: serde_json::json!({ "text": "b" }),
+                ..event
+            },
+            "text",
+        )
+        .await
+        .unwrap();
+
+    let expected = serde_json::json!({ "text": format!("{prefix}b") });
+    assert_eq!(accumulated.data, expected);
+    assert_eq!(store.get_events("t1", None).await.unwrap()[0].data, expected);
+}
+
+#[tokio::test]
+async fn small_payload_bypasses_dedup() {
+    let Some((store, pool)) = setup().await else {
+        return;
+    };
+    let data = serde_json::json!({ "text": "hi" });
+
+    store
+        .save_event(make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(stored_data(&pool, "evt-t1-0").await, Some(data.clone()));
+    let blobs: i64 = sqlx::query("SELECT COUNT(*) AS n FROM taskcast_blobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("n");
+    assert_eq!(blobs, 0);
+    assert_eq!(store.get_events("t1", None).await.unwrap()[0].data, data);
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use taskcast_core::types::{
+    EventQueryOptions, Level, LongTermStore, SinceCursor, Task, TaskEvent, TaskStatus,
+};
+use taskcast_core::CollectingSlowQueryLogger;
+use taskcast_postgres::PostgresLongTermStore;
+use taskcast_test_backends::{postgres_url, unique_schema};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+async fn setup() -> Option<(PgPool, CollectingSlowQueryLogger, PostgresLongTermStore)> {
+    let database_url = postgres_url(&unique_schema("query_stats")).await?;
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .unwrap();
+    let logger = CollectingSlowQueryLogger::default();
+    let store =
+        PostgresLongTermStore::new(pool.clone()).with_slow_query_logger(Arc::new(logger.clone()));
+    store.migrate().await.unwrap();
+    Some((pool, logger, store))
+}
+
+fn make_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: None,
+        status: TaskStatus::Running,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+        ttl: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "llm.delta".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "text": "hi" }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn since_index(index: u64) -> Option<EventQueryOptions> {
+    Some(EventQueryOptions {
+        since: Some(SinceCursor {
+            id: None,
+            index: Some(index),
+            timestamp: None,
+        }),
+        ..Default::default()
+    })
+}
+
+// ─── Histograms ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn histograms_are_kept_per_method_and_cursor_type() {
+    let Some((_pool, logger, store)) = setup().await else {
+        return;
+    };
+    store.save_task(make_task("t1")).await.unwrap();
+    for index in 0..3 {
+        store.save_event(make_event("t1", index)).await.unwrap();
+    }
+    store.get_task("t1").await.unwrap();
+    store.get_events("t1", None).await.unwrap();
+    store.get_events("t1", since_index(0)).await.unwrap();
+    store.get_events("t1", since_index(1)).await.unwrap();
+
+    let stats = store.query_stats().unwrap();
+    assert_eq!(stats.store, "postgres");
+    assert_eq!(stats.slow_threshold_ms, 250.0);
+    assert_eq!(stats.methods["save_task"].count, 1);
+    assert_eq!(stats.methods["save_event"].count, 3);
+    assert_eq!(stats.methods["get_task"].count, 1);
+    assert_eq!(stats.methods["get_events.full"].count, 1);
+    assert_eq!(stats.methods["get_events.since_index"].count, 2);
+    assert!(!stats.methods.contains_key("get_events.since_id"));
+    let histogram = &stats.methods["save_event"];
+    assert_eq!(histogram.buckets.iter().map(|b| b.count).sum::<u64>(), 3);
+    assert!(logger.queries().is_empty());
+}
+
+// ─── Slow Query Log ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn calls_at_the_threshold_are_logged_without_sql() {
+    let Some((_pool, logger, store)) = setup().await else {
+        return;
+    };
+    let store = store.with_slow_query_threshold(Duration::ZERO);
+    store.save_task(make_task("t1")).await.unwrap();
+    for index in 0..3 {
+        store.save_event(make_event("t1", index)).await.unwrap();
+    }
+    store.get_events("t1", since_index(0)).await.unwrap();
+
+    let queries = logger.queries();
+    assert_eq!(queries.len(), 5);
+    let read = queries.last().unwrap();
+    assert_eq!(read.level, "warn");
+    assert_eq!(read.store, "postgres");
+    assert_eq!(read.method, "get_events.since_index");
+    assert_eq!(read.task_id.as_deref(), Some("t1"));
+    assert_eq!(read.rows, Some(2));
+    let line = serde_json::to_string(read).unwrap();
+    assert!(!line.contains("SELECT"));
+    assert_eq!(store.query_stats().unwrap().slow_queries, 5);
+}
+
+// ─── Explain ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn explain_uses_the_task_index() {
+    let Some((pool, _logger, store)) = setup().await else {
+        return;
+    };
+    store.save_task(make_task("t1")).await.unwrap();
+    store.save_task(make_task("bulk")).await.unwrap();
+    store.save_event(make_event("t1", 0)).await.unwrap();
+    // Enough rows of another task that a sequential scan costs more than the
+    // index.
+    sqlx::query(
+        "INSERT INTO taskcast_events (id, task_id, idx, timestamp, type, level, data) \
+         SELECT 'bulk-' || i, 'bulk', i, 1000 + i, 'llm.delta', 'info', '{}' \
+         FROM generate_series(0, 4999) AS i",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("ANALYZE taskcast_events")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let plan = store
+        .explain_get_events("t1", since_index(0))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(plan.contains("taskcast_events_task_id"), "{plan}");
+    // Explaining does not run the read.
+    assert!(!store
+        .query_stats()
+        .unwrap()
+        .methods
+        .contains_key("get_events.since_index"));
+}
@@ -0,0 +1,198 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use taskcast_core::types::{Level, LongTermStore, Task, TaskEvent, TaskStatus};
+use taskcast_postgres::PostgresLongTermStore;
+use taskcast_test_backends::{postgres_url, unique_schema};
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+const WINDOW: Duration = Duration::from_millis(300);
+
+/// Two pools on the same database, each counting the connections it hands
+/// out.
+struct Pools {
+    write: PgPool,
+    read: PgPool,
+    writes: Arc<AtomicUsize>,
+    reads: Arc<AtomicUsize>,
+}
+
+impl Pools {
+    /// Connections handed out so far, as `(write, read)`.
+    fn acquired(&self) -> (usize, usize) {
+        (
+            self.writes.load(Ordering::SeqCst),
+            self.reads.load(Ordering::SeqCst),
+        )
+    }
+}
+
+async fn counted_pool(database_url: &str, counter: Arc<AtomicUsize>) -> PgPool {
+    let on_connect = Arc::clone(&counter);
+    PgPoolOptions::new()
+        .max_connections(5)
+        .after_connect(move |_, _| {
+            on_connect.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(()) })
+        })
+        .before_acquire(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(true) })
+        })
+        .connect(database_url)
+        .await
+        .unwrap()
+}
+
+async fn setup() -> Option<(PostgresLongTermStore, Pools)> {
+    let database_url = postgres_url(&unique_schema("read_replica")).await?;
+    let writes = Arc::new(AtomicUsize::new(0));
+    let reads = Arc::new(AtomicUsize::new(0));
+    let pools = Pools {
+        write: counted_pool(&database_url, Arc::clone(&writes)).await,
+        read: counted_pool(&database_url, Arc::clone(&reads)).await,
+        writes,
+        reads,
+    };
+    let store = PostgresLongTermStore::with_pools(pools.write.clone(), pools.read.clone())
+        .with_read_after_write_window(WINDOW);
+    store.migrate().await.unwrap();
+    Some((store, pools))
+}
+
+fn make_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: None,
+        status: TaskStatus::Running,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+        ttl: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "llm.delta".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "text": "hi" }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+// ─── Routing ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn reads_use_the_read_pool_outside_the_window() {
+    let Some((store, pools)) = setup().await else {
+        return;
+    };
+    store.save_task(make_task("t1")).await.unwrap();
+    store.save_event(make_event("t1", 0)).await.unwrap();
+    tokio::time::sleep(WINDOW * 2).await;
+
+    let (writes, reads) = pools.acquired();
+    assert_eq!(store.get_task("t1").await.unwrap().unwrap().id, "t1");
+    assert_eq!(store.get_events("t1", None).await.unwrap().len(), 1);
+    assert!(store.get_task("unknown").await.unwrap().is_none());
+    assert_eq!(pools.acquired(), (writes, reads + 3));
+}
+
+#[tokio::test]
+async fn reads_right_after_a_write_use_the_write_pool() {
+    let Some((store, pools)) = setup().await else {
+        return;
+    };
+    store.save_task(make_task("t1")).await.unwrap();
+    store.save_event(make_event("t1", 0)).await.unwrap();
+
+    let (writes, reads) = pools.acquired();
+    assert_eq!(store.get_task("t1").await.unwrap().unwrap().id, "t1");
+    assert_eq!(store.get_events("t1", None).await.unwrap().len(), 1);
+    assert_eq!(pools.acquired(), (writes + 2, reads));
+
+    // Other tasks are unaffected.
+    store.get_task("t2").await.unwrap();
+    assert_eq!(pools.acquired(), (writes + 2, reads + 1));
+
+    tokio::time::sleep(WINDOW * 2).await;
+    store.get_task("t1").await.unwrap();
+    assert_eq!(pools.acquired(), (writes + 2, reads + 2));
+}
+
+#[tokio::test]
+async fn write_failures_do_not_fall_back_to_the_read_pool() {
+    let Some((store, pools)) = setup().await else {
+        return;
+    };
+    pools.write.close().await;
+
+    let (_, reads) = pools.acquired();
+    assert!(store.save_task(make_task("t1")).await.is_err());
+    assert!(store.save_event(make_event("t1", 0)).await.is_err());
+    assert_eq!(pools.acquired().1, reads);
+
+    // The read pool never saw the task.
+    assert!(store.get_task("t1").await.unwrap().is_none());
+}
+
+// ─── Health ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn pool_health_probes_each_pool() {
+    let Some((store, pools)) = setup().await else {
+        return;
+    };
+
+    let health = store.pool_health().await;
+    assert_eq!(health.len(), 2);
+    assert_eq!((health[0].name.as_str(), health[0].ok), ("write", true));
+    assert_eq!((health[1].name.as_str(), health[1].ok), ("read", true));
+
+    pools.read.close().await;
+    let health = store.pool_health().await;
+    assert!(health[0].ok);
+    assert!(!health[1].ok);
+    assert!(health[1].error.is_some());
+}
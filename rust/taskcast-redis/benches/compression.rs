@@ -0,0 +1,181 @@
+//! Cost and gain of compressing stored events, on a synthetic workload of
+//! LLM transcripts and stack traces.
+//!
+//! Without Redis only the codec is measured. With `TASKCAST_REDIS_URL` set,
+//! event appends and history reads are timed against that server for each
+//! algorithm, and the memory the event list takes (`MEMORY USAGE`) and the
+//! server's `used_memory` growth are printed per algorithm. Keys are written
+//! under a fresh prefix and deleted afterwards.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use redis::AsyncCommands;
+use serde_json::{json, Value};
+use taskcast_core::compression::{CompressionAlgorithm, ValueCompressionConfig};
+use taskcast_core::types::{Level, ShortTermStore, TaskEvent};
+use taskcast_redis::codec::{compress_value, decode_event, encode_value, VALUE_FORMAT_VERSION};
+use taskcast_redis::RedisShortTermStore;
+
+const EVENTS: u64 = 500;
+
+const ALGORITHMS: [CompressionAlgorithm; 3] = [
+    CompressionAlgorithm::None,
+    CompressionAlgorithm::Zstd,
+    CompressionAlgorithm::Lz4,
+];
+
+fn config(algorithm: CompressionAlgorithm) -> ValueCompressionConfig {
+    ValueCompressionConfig {
+        algorithm,
+        ..Default::default()
+    }
+}
+
+/// Alternates transcript turns and stack traces of a few KB each.
+fn payload(index: u64) -> Value {
+    if index.is_multiple_of(2) {
+        json!({
+            "role": "assistant",
+            "content": (0..40)
+                .map(|line| format!("Step {line}: checking the deployment of service-{index} in region eu-west-1; all replicas healthy.\n"))
+                .collect::<String>(),
+            "usage": { "promptTokens": 1200 + index, "completionTokens": 800 },
+        })
+    } else {
+        json!({
+            "error": "TimeoutError: upstream did not answer within 30000 ms",
+            "stack": (0..30)
+                .map(|frame| format!("    at handler_{frame} (/srv/app/src/handlers/worker.rs:{})\n", 100 + frame))
+                .collect::<String>(),
+        })
+    }
+}
+
+fn event(index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{index}"),
+        task_id: "bench".to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "llm.output".to_string(),
+        level: Level::Info,
+        data: payload(index),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn codec(c: &mut Criterion) {
+    let encoded = encode_value(&event(0), VALUE_FORMAT_VERSION).unwrap();
+    let mut group = c.benchmark_group("compression_codec");
+    for algorithm in ALGORITHMS {
+        let config = config(algorithm);
+        let stored = compress_value(encoded.clone(), &config);
+        println!(
+            "{algorithm:?}: {} bytes stored for {} encoded",
+            stored.len(),
+            encoded.len()
+        );
+        group.bench_with_input(
+            BenchmarkId::new("compress", format!("{algorithm:?}")),
+            &config,
+            |b, config| b.iter(|| compress_value(encoded.clone(), config)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("decode", format!("{algorithm:?}")),
+            &stored,
+            |b, stored| b.iter(|| decode_event("bench", 0, stored).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+async fn used_memory(conn: &mut redis::aio::MultiplexedConnection) -> u64 {
+    let info: String = redis::cmd("INFO")
+        .arg("memory")
+        .query_async(conn)
+        .await
+        .unwrap();
+    info.lines()
+        .find_map(|line| line.strip_prefix("used_memory:"))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn store(c: &mut Criterion) {
+    let Ok(url) = std::env::var("TASKCAST_REDIS_URL") else {
+        println!("TASKCAST_REDIS_URL is unset; skipping the store benchmarks");
+        return;
+    };
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let mut conn = runtime.block_on(async {
+        let client = redis::Client::open(url).unwrap();
+        client.get_multiplexed_async_connection().await.unwrap()
+    });
+    let run = ulid::Ulid::new().to_string().to_lowercase();
+
+    let mut group = c.benchmark_group("compression_store");
+    for algorithm in ALGORITHMS {
+        let prefix = format!("bench-compression-{run}-{algorithm:?}").to_lowercase();
+        let store = RedisShortTermStore::new(conn.clone(), Some(&prefix))
+            .with_compression(config(algorithm));
+
+        let before = runtime.block_on(used_memory(&mut conn));
+        runtime.block_on(async {
+            for index in 0..EVENTS {
+                store.append_event("bench", event(index)).await.unwrap();
+            }
+        });
+        let after = runtime.block_on(used_memory(&mut conn));
+        let list_bytes: Option<u64> = runtime
+            .block_on(
+                redis::cmd("MEMORY")
+                    .arg("USAGE")
+                    .arg(format!("{prefix}:events:bench"))
+                    .arg("SAMPLES")
+                    .arg(0)
+                    .query_async(&mut conn),
+            )
+            .unwrap();
+        println!(
+            "{algorithm:?}: {EVENTS} events take {} bytes (MEMORY USAGE), used_memory grew {} bytes",
+            list_bytes.unwrap_or(0),
+            after.saturating_sub(before)
+        );
+
+        let mut next = EVENTS;
+        group.bench_function(
+            BenchmarkId::new("append_event", format!("{algorithm:?}")),
+            |b| {
+                b.iter(|| {
+                    next += 1;
+                    runtime
+                        .block_on(store.append_event("bench", event(next)))
+                        .unwrap()
+                })
+            },
+        );
+        group.bench_function(
+            BenchmarkId::new("get_events", format!("{algorithm:?}")),
+            |b| b.iter(|| runtime.block_on(store.get_events("bench", None)).unwrap()),
+        );
+
+        runtime
+            .block_on(conn.del::<_, ()>(&[
+                format!("{prefix}:events:bench"),
+                format!("{prefix}:typeCounts:bench"),
+            ]))
+            .unwrap();
+    }
+    group.finish();
+}
+
+criterion_group!(benches, codec, store);
+criterion_main!(benches);
@@ -3,12 +3,16 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures_util::StreamExt;
-use redis::aio::MultiplexedConnection;
+use redis::aio::{MultiplexedConnection, PubSubSink};
 use tokio::sync::RwLock;
 
+use taskcast_core::series::{decode_broadcast_payload, encode_broadcast_payload};
 use taskcast_core::types::{BroadcastProvider, TaskEvent};
 
+use crate::error::store_error;
+
 type Handler = Arc<dyn Fn(TaskEvent) + Send + Sync>;
+type Handlers = Arc<RwLock<HashMap<String, Vec<Handler>>>>;
 
 /// Redis-backed broadcast provider.
 ///
@@ -17,7 +21,11 @@ type Handler = Arc<dyn Fn(TaskEvent) + Send + Sync>;
 /// locally-registered handlers.
 pub struct RedisBroadcastProvider {
     pub_conn: MultiplexedConnection,
-    handlers: Arc<RwLock<HashMap<String, Vec<Handler>>>>,
+    sub_sink: PubSubSink,
+    handlers: Handlers,
+    /// Pattern subscriptions, keyed by the pattern without the channel
+    /// prefix. Each has its own `PSUBSCRIBE` while it has handlers.
+    pattern_handlers: Handlers,
     channel_prefix: String,
 }
 
@@ -29,14 +37,15 @@ impl RedisBroadcastProvider {
     /// - `prefix`: key/channel prefix (defaults to `"taskcast"`).
     pub fn new(
         pub_conn: MultiplexedConnection,
-        mut sub_conn: redis::aio::PubSub,
+        sub_conn: redis::aio::PubSub,
         prefix: Option<&str>,
     ) -> Self {
         let resolved_prefix = prefix.unwrap_or("taskcast");
         let channel_prefix = format!("{resolved_prefix}:task:");
 
-        let handlers: Arc<RwLock<HashMap<String, Vec<Handler>>>> =
-            Arc::new(RwLock::new(HashMap::new()));
+        let handlers: Handlers = Arc::new(RwLock::new(HashMap::new()));
+        let pattern_handlers: Handlers = Arc::new(RwLock::new(HashMap::new()));
+        let (sub_sink, mut stream) = sub_conn.split();
 
         // Spawn background listener that reads from the PubSub connection
         // and dispatches to local handlers.
@@ -44,16 +53,16 @@ impl RedisBroadcastProvider {
         // We use PSUBSCRIBE with a wildcard pattern so a single subscription
         // covers all task channels without needing per-task SUBSCRIBE calls.
         let handlers_clone = Arc::clone(&handlers);
+        let pattern_handlers_clone = Arc::clone(&pattern_handlers);
         let prefix_clone = channel_prefix.clone();
+        let mut sink = sub_sink.clone();
         tokio::spawn(async move {
             let pattern = format!("{prefix_clone}*");
-            if let Err(e) = sub_conn.psubscribe(&pattern).await {
+            if let Err(e) = sink.psubscribe(&pattern).await {
                 eprintln!("[taskcast] Redis PSUBSCRIBE failed for pattern {pattern}: {e}");
                 return;
             }
 
-            let mut stream = sub_conn.on_message();
-
             while let Some(msg) = stream.next().await {
                 let channel: String = match msg.get_channel() {
                     Ok(c) => c,
@@ -63,30 +72,47 @@ impl RedisBroadcastProvider {
                     Ok(p) => p,
                     Err(_) => continue,
                 };
+                // Redis sends one message per matching pattern, so the
+                // pattern tells which handlers this copy is for.
+                let matched: String = match msg.get_pattern() {
+                    Ok(p) => p,
+                    Err(_) => continue,
+                };
 
                 let task_id = if channel.starts_with(&prefix_clone) {
                     &channel[prefix_clone.len()..]
                 } else {
                     &channel
                 };
+                let matched = matched.strip_prefix(&prefix_clone).unwrap_or(&matched);
 
-                let event: TaskEvent = match serde_json::from_str(&payload) {
-                    Ok(e) => e,
-                    Err(_) => continue,
+                let event = match decode_broadcast_payload(payload.as_bytes()) {
+                    Some(e) => e,
+                    None => continue,
                 };
 
-                let handlers = handlers_clone.read().await;
-                if let Some(task_handlers) = handlers.get(task_id) {
-                    for handler in task_handlers {
-                        handler(event.clone());
+                // Release the lock before running handlers so a handler that
+                // subscribes or unsubscribes cannot deadlock the listener.
+                let mut task_handlers = Vec::new();
+                if matched == "*" {
+                    if let Some(exact) = handlers_clone.read().await.get(task_id) {
+                        task_handlers.extend(exact.iter().cloned());
                     }
                 }
+                if let Some(patterned) = pattern_handlers_clone.read().await.get(matched) {
+                    task_handlers.extend(patterned.iter().cloned());
+                }
+                for handler in &task_handlers {
+                    handler(event.clone());
+                }
             }
         });
 
         Self {
             pub_conn,
+            sub_sink,
             handlers,
+            pattern_handlers,
             channel_prefix,
         }
     }
@@ -105,13 +131,14 @@ impl BroadcastProvider for RedisBroadcastProvider {
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let full_channel = format!("{}{}", self.channel_prefix, channel);
-        let payload = serde_json::to_string(&event)?;
+        let payload = encode_broadcast_payload(&event)?;
         let mut conn = self.pub_conn.clone();
         redis::cmd("PUBLISH")
             .arg(&full_channel)
             .arg(&payload)
             .query_async::<i64>(&mut conn)
-            .await?;
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -151,6 +178,61 @@ impl BroadcastProvider for RedisBroadcastProvider {
             });
         })
     }
+
+    async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
+        let handler: Handler = Arc::from(handler);
+        {
+            // Holding the lock across PSUBSCRIBE orders it against the
+            // PUNSUBSCRIBE of a concurrent last unsubscribe.
+            let mut pattern_handlers = self.pattern_handlers.write().await;
+            // `*` is already covered by the listener's own subscription.
+            if !pattern_handlers.contains_key(pattern) && pattern != "*" {
+                self.sub_sink
+                    .clone()
+                    .psubscribe(format!("{}{}", self.channel_prefix, pattern))
+                    .await
+                    .map_err(store_error)?;
+            }
+            pattern_handlers
+                .entry(pattern.to_string())
+                .or_default()
+                .push(Arc::clone(&handler));
+        }
+
+        let pattern_handlers = Arc::clone(&self.pattern_handlers);
+        let sink = self.sub_sink.clone();
+        let pattern = pattern.to_string();
+        let full_pattern = format!("{}{}", self.channel_prefix, pattern);
+        let handler_addr = Arc::as_ptr(&handler) as *const () as usize;
+
+        Ok(Box::new(move || {
+            let pattern_handlers = Arc::clone(&pattern_handlers);
+            let mut sink = sink.clone();
+            let pattern = pattern.clone();
+            let full_pattern = full_pattern.clone();
+            tokio::spawn(async move {
+                let mut pattern_handlers = pattern_handlers.write().await;
+                let Some(handlers) = pattern_handlers.get_mut(&pattern) else {
+                    return;
+                };
+                handlers.retain(|h| (Arc::as_ptr(h) as *const () as usize) != handler_addr);
+                if handlers.is_empty() {
+                    pattern_handlers.remove(&pattern);
+                    if pattern != "*" {
+                        if let Err(e) = sink.punsubscribe(&full_pattern).await {
+                            eprintln!(
+                                "[taskcast] Redis PUNSUBSCRIBE failed for pattern {full_pattern}: {e}"
+                            );
+                        }
+                    }
+                }
+            });
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,246 @@
+//! Versioned encoding of the tasks and events the Redis store keeps.
+//!
+//! Values are written as an envelope `{"v": <version>, "d": <payload>}`.
+//! Version 1 is the format from before envelopes: the bare JSON of the
+//! value. A read upgrades the payload to the current version through one
+//! migration per version step, then decodes it, so an instance always reads
+//! what an older one wrote.
+//!
+//! A value from a newer version is decoded as the current one, relying on
+//! unknown fields being ignored. A format change that readers of the
+//! previous version cannot decode that way must ship in two releases:
+//! readers first, then writers.
+//!
+//! Changing how `Task` or `TaskEvent` serialize changes the format: bump
+//! [`VALUE_FORMAT_VERSION`], add the migration from the previous version and
+//! add fixtures for the new version under `tests/fixtures/value_format`.
+//!
+//! An encoded value may then be compressed (see [`compress_value`]). A
+//! compressed value starts with a byte no JSON text starts with, naming its
+//! algorithm, so compressed and plain values are read alike and
+//! compression can be turned on or off on a store that holds either.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use taskcast_core::compression::{CompressionAlgorithm, ValueCompressionConfig};
+use taskcast_core::integrity::{CorruptRecord, CorruptRecordKind};
+use taskcast_core::types::{Task, TaskEvent};
+
+/// Version of the format values are written in by default.
+pub const VALUE_FORMAT_VERSION: u64 = 2;
+
+/// Oldest version values can be written in, for rolling upgrades from a
+/// release that reads only that version.
+pub const MIN_VALUE_FORMAT_VERSION: u64 = 1;
+
+/// First byte of a zstd-compressed value, followed by a zstd frame.
+pub const ZSTD_PREFIX: u8 = 0x01;
+
+/// First byte of an lz4-compressed value, followed by the uncompressed
+/// length (4 bytes, little endian) and an lz4 block.
+pub const LZ4_PREFIX: u8 = 0x02;
+
+/// zstd level values are compressed at: fast, and most of the gain on JSON.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Encodes `value` in format `version`, which must be between
+/// [`MIN_VALUE_FORMAT_VERSION`] and [`VALUE_FORMAT_VERSION`].
+pub fn encode_value<T: Serialize>(value: &T, version: u64) -> Result<String, serde_json::Error> {
+    if version <= 1 {
+        return serde_json::to_string(value);
+    }
+    serde_json::to_string(&Envelope {
+        v: version,
+        d: value,
+    })
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    v: u64,
+    d: &'a T,
+}
+
+/// The bytes to store for an `encoded` value: compressed under `config`
+/// when it is long enough and compressing shrinks it, otherwise `encoded`
+/// itself.
+pub fn compress_value(encoded: String, config: &ValueCompressionConfig) -> Vec<u8> {
+    if !config.applies_to(encoded.len()) {
+        return encoded.into_bytes();
+    }
+    let compressed = match config.algorithm {
+        CompressionAlgorithm::None => None,
+        CompressionAlgorithm::Zstd => zstd::bulk::compress(encoded.as_bytes(), ZSTD_LEVEL)
+            .ok()
+            .map(|frame| [&[ZSTD_PREFIX][..], &frame].concat()),
+        CompressionAlgorithm::Lz4 => Some(
+            [
+                &[LZ4_PREFIX][..],
+                &lz4_flex::compress_prepend_size(encoded.as_bytes()),
+            ]
+            .concat(),
+        ),
+    };
+    match compressed {
+        Some(compressed) if compressed.len() < encoded.len() => compressed,
+        _ => encoded.into_bytes(),
+    }
+}
+
+/// The encoded value a stored one holds, decompressing it if it was
+/// compressed.
+pub fn decompress_value(raw: &[u8]) -> Result<Cow<'_, str>, String> {
+    let bytes = match raw.split_first() {
+        Some((&ZSTD_PREFIX, frame)) => Cow::Owned(
+            zstd::stream::decode_all(frame).map_err(|err| format!("invalid zstd value: {err}"))?,
+        ),
+        Some((&LZ4_PREFIX, block)) => Cow::Owned(
+            lz4_flex::decompress_size_prepended(block)
+                .map_err(|err| format!("invalid lz4 value: {err}"))?,
+        ),
+        _ => Cow::Borrowed(raw),
+    };
+    match bytes {
+        Cow::Borrowed(bytes) => std::str::from_utf8(bytes)
+            .map(Cow::Borrowed)
+            .map_err(|err| err.to_string()),
+        Cow::Owned(bytes) => String::from_utf8(bytes)
+            .map(Cow::Owned)
+            .map_err(|err| err.to_string()),
+    }
+}
+
+/// A stored value as text for a corrupt record: decompressed where
+/// possible.
+fn raw_text(raw: &[u8]) -> Value {
+    Value::String(match decompress_value(raw) {
+        Ok(text) => text.into_owned(),
+        Err(_) => String::from_utf8_lossy(raw).into_owned(),
+    })
+}
+
+/// The payload of a stored value, upgraded to the current version, with the
+/// version it was stored in.
+pub fn upgrade_value(raw: &str) -> Result<(u64, Value), String> {
+    let value: Value = serde_json::from_str(raw).map_err(|err| err.to_string())?;
+    let (version, mut payload) = split_envelope(value)?;
+    let mut at = version;
+    while at < VALUE_FORMAT_VERSION {
+        payload = migrate(at, payload)?;
+        at += 1;
+    }
+    Ok((version, payload))
+}
+
+/// Splits an envelope into its version and payload. Anything else that is
+/// an object is a version 1 value.
+fn split_envelope(value: Value) -> Result<(u64, Value), String> {
+    let Value::Object(mut object) = value else {
+        return Err("stored value is not a JSON object".to_string());
+    };
+    if !(object.contains_key("v") && object.contains_key("d")) {
+        return Ok((1, Value::Object(object)));
+    }
+    let version = object
+        .get("v")
+        .and_then(Value::as_u64)
+        .filter(|version| *version >= 2)
+        .ok_or_else(|| format!("invalid value format version: {}", object["v"]))?;
+    Ok((version, object.remove("d").unwrap_or(Value::Null)))
+}
+
+/// Migrates a payload from `version` to the next version.
+fn migrate(version: u64, payload: Value) -> Result<Value, String> {
+    match version {
+        1 => Ok(migrate_v1(payload)),
+        _ => Err(format!("no migration from value format version {version}")),
+    }
+}
+
+/// Version 2 introduced the envelope; the payload is unchanged.
+fn migrate_v1(payload: Value) -> Value {
+    payload
+}
+
+/// Decodes a stored task, compressed or not.
+pub fn decode_task(task_id: &str, raw: impl AsRef<[u8]>) -> Result<Task, CorruptRecord> {
+    let raw = raw.as_ref();
+    decompress_value(raw)
+        .and_then(|json| upgrade_value(&json))
+        .and_then(|(_, payload)| Task::deserialize(&payload).map_err(|err| err.to_string()))
+        .map_err(|reason| CorruptRecord::task(task_id, raw_text(raw), reason))
+}
+
+/// Decodes a stored event, compressed or not. On failure, the id, index and
+/// timestamp are recovered from the payload where possible, falling back to
+/// `position` (the record's offset in storage) for the index.
+pub fn decode_event(
+    task_id: &str,
+    position: u64,
+    raw: impl AsRef<[u8]>,
+) -> Result<TaskEvent, CorruptRecord> {
+    let raw = raw.as_ref();
+    let (payload, decoded) = match decompress_value(raw).and_then(|json| upgrade_value(&json)) {
+        Ok((_, payload)) => {
+            let decoded = TaskEvent::deserialize(&payload).map_err(|err| err.to_string());
+            (Some(payload), decoded)
+        }
+        Err(reason) => (None, Err(reason)),
+    };
+    decoded.map_err(|reason| {
+        let field = |name: &str| payload.as_ref().and_then(|payload| payload.get(name));
+        CorruptRecord {
+            kind: CorruptRecordKind::Event,
+            task_id: task_id.to_string(),
+            id: field("id").and_then(Value::as_str).map(str::to_string),
+            position: Some(field("index").and_then(Value::as_u64).unwrap_or(position)),
+            timestamp: field("timestamp").and_then(Value::as_f64),
+            raw: Box::new(raw_text(raw)),
+            reason,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bare_objects_are_version_1() {
+        let (version, payload) = upgrade_value(r#"{"id":"t1","v":1}"#).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(payload, json!({ "id": "t1", "v": 1 }));
+    }
+
+    #[test]
+    fn envelopes_are_unwrapped() {
+        let (version, payload) = upgrade_value(r#"{"v":2,"d":{"id":"t1"}}"#).unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(payload, json!({ "id": "t1" }));
+    }
+
+    #[test]
+    fn version_1_is_written_bare() {
+        let value = json!({ "id": "t1" });
+        assert_eq!(encode_value(&value, 1).unwrap(), r#"{"id":"t1"}"#);
+        assert_eq!(
+            encode_value(&value, VALUE_FORMAT_VERSION).unwrap(),
+            format!(r#"{{"v":{VALUE_FORMAT_VERSION},"d":{{"id":"t1"}}}}"#)
+        );
+    }
+
+    #[test]
+    fn malformed_values_are_rejected() {
+        for raw in [
+            "[1]",
+            "not json",
+            r#"{"v":"2","d":{}}"#,
+            r#"{"v":1,"d":{}}"#,
+        ] {
+            assert!(upgrade_value(raw).is_err(), "{raw}");
+        }
+    }
+}
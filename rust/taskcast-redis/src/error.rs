@@ -0,0 +1,97 @@
+use redis::{ErrorKind, RedisError};
+use taskcast_core::{StoreError, StoreErrorKind};
+
+/// Classifies a Redis error for [`StoreError`].
+pub fn redis_error_kind(err: &RedisError) -> StoreErrorKind {
+    if err.is_timeout() {
+        return StoreErrorKind::Timeout;
+    }
+    match err.kind() {
+        ErrorKind::IoError
+        | ErrorKind::AuthenticationFailed
+        | ErrorKind::BusyLoadingError
+        | ErrorKind::TryAgain
+        | ErrorKind::ClusterDown
+        | ErrorKind::MasterDown
+        | ErrorKind::ReadOnly
+        | ErrorKind::ClusterConnectionNotFound
+        | ErrorKind::MasterNameNotFoundBySentinel
+        | ErrorKind::NoValidReplicasFoundBySentinel
+        | ErrorKind::EmptySentinelList => StoreErrorKind::Unavailable,
+        ErrorKind::TypeError | ErrorKind::ParseError => StoreErrorKind::Corrupt,
+        // Server errors without a dedicated kind keep their code here.
+        ErrorKind::ExtensionError => match err.code() {
+            Some("OOM" | "BUSY" | "MISCONF" | "NOREPLICAS") => StoreErrorKind::Unavailable,
+            Some("WRONGTYPE") => StoreErrorKind::Corrupt,
+            _ => StoreErrorKind::Other,
+        },
+        ErrorKind::ResponseError
+            if err
+                .detail()
+                .is_some_and(|detail| detail.contains("exceeds maximum allowed size")) =>
+        {
+            StoreErrorKind::TooLarge
+        }
+        _ => StoreErrorKind::Other,
+    }
+}
+
+/// Wraps a Redis error so that only its kind and a short detail surface.
+pub fn store_error(err: RedisError) -> StoreError {
+    StoreError::new(redis_error_kind(&err), err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+
+    use super::*;
+
+    /// Parses an error reply as the server would send it.
+    fn server_error(code: &str, detail: &str) -> RedisError {
+        let reply = format!("-{code} {detail}\r\n");
+        redis::parse_redis_value(reply.as_bytes())
+            .unwrap()
+            .extract_error()
+            .unwrap_err()
+    }
+
+    #[test]
+    fn connection_failures_are_unavailable_or_timeouts() {
+        let refused = RedisError::from(io::Error::from(io::ErrorKind::ConnectionRefused));
+        assert_eq!(redis_error_kind(&refused), StoreErrorKind::Unavailable);
+        let timed_out = RedisError::from(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(redis_error_kind(&timed_out), StoreErrorKind::Timeout);
+        let loading = RedisError::from((ErrorKind::BusyLoadingError, "loading"));
+        assert_eq!(redis_error_kind(&loading), StoreErrorKind::Unavailable);
+    }
+
+    #[test]
+    fn server_codes_are_classified() {
+        let oom = server_error("OOM", "command not allowed when used memory > 'maxmemory'.");
+        assert_eq!(redis_error_kind(&oom), StoreErrorKind::Unavailable);
+        let wrong_type = server_error(
+            "WRONGTYPE",
+            "Operation against a key holding the wrong kind of value",
+        );
+        assert_eq!(redis_error_kind(&wrong_type), StoreErrorKind::Corrupt);
+        let too_large = RedisError::from((
+            ErrorKind::ResponseError,
+            "An error was signalled by the server",
+            "string exceeds maximum allowed size (proto-max-bulk-len)".to_string(),
+        ));
+        assert_eq!(redis_error_kind(&too_large), StoreErrorKind::TooLarge);
+        let other = RedisError::from((ErrorKind::ResponseError, "syntax error"));
+        assert_eq!(redis_error_kind(&other), StoreErrorKind::Other);
+    }
+
+    #[test]
+    fn store_error_keeps_the_full_driver_error_as_source() {
+        let detail = format!("taskcast:task:secret-id {}", "z".repeat(400));
+        let err = store_error(server_error("OOM", &detail));
+        assert_eq!(err.kind, StoreErrorKind::Unavailable);
+        assert!(err.detail.chars().count() <= taskcast_core::MAX_STORE_ERROR_DETAIL);
+        assert!(err.source().unwrap().to_string().contains(&detail));
+    }
+}
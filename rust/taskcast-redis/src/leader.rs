@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use taskcast_core::{LeaderLease, DEFAULT_LEADER_KEY};
+
+use crate::error::store_error;
+
+/// A [`LeaderLease`] kept in one Redis key, `{prefix}:{key}`, holding the
+/// holder's name and expiring with the lease.
+pub struct RedisLeaderLease {
+    conn: MultiplexedConnection,
+    key: String,
+}
+
+impl RedisLeaderLease {
+    /// - `prefix`: key prefix (defaults to `"taskcast"`).
+    /// - `key`: lease name under the prefix (defaults to `"leader"`).
+    pub fn new(conn: MultiplexedConnection, prefix: Option<&str>, key: Option<&str>) -> Self {
+        Self {
+            conn,
+            key: format!(
+                "{}:{}",
+                prefix.unwrap_or("taskcast"),
+                key.unwrap_or(DEFAULT_LEADER_KEY)
+            ),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+#[async_trait]
+impl LeaderLease for RedisLeaderLease {
+    async fn acquire(
+        &self,
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Renew our own lease, or take a free one; never touch another
+        // holder's.
+        let lua = r#"
+            local current = redis.call('GET', KEYS[1])
+            if current == ARGV[1] then
+              redis.call('PEXPIRE', KEYS[1], ARGV[2])
+              return 1
+            end
+            if current then
+              return 0
+            end
+            redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+            return 1
+        "#;
+        let mut conn = self.conn.clone();
+        let held: i64 = redis::Script::new(lua)
+            .key(&self.key)
+            .arg(holder)
+            .arg(ttl.as_millis().max(1) as u64)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(held == 1)
+    }
+
+    async fn release(&self, holder: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+              return redis.call('DEL', KEYS[1])
+            end
+            return 0
+        "#;
+        let mut conn = self.conn.clone();
+        redis::Script::new(lua)
+            .key(&self.key)
+            .arg(holder)
+            .invoke_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 pub mod broadcast;
+pub mod codec;
+mod error;
+pub mod leader;
 pub mod short_term;
 
 pub use broadcast::RedisBroadcastProvider;
+pub use error::{redis_error_kind, store_error};
+pub use leader::RedisLeaderLease;
 pub use short_term::RedisShortTermStore;
 
 use redis::aio::MultiplexedConnection;
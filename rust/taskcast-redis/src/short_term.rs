@@ -1,12 +1,34 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use redis::aio::MultiplexedConnection;
 use redis::AsyncCommands;
+use serde::Serialize;
 
+use taskcast_core::compression::{
+    CompressionAlgorithm, CompressionCounters, CompressionStats, ValueCompressionConfig,
+};
+use taskcast_core::conventions::{ConventionNotes, EventConvention, EventConventionStore};
+use taskcast_core::filter::apply_event_query;
+use taskcast_core::integrity::IntegrityMonitor;
+use taskcast_core::payload_dedup::{blob_ref, blob_ref_hash, DedupedPayload, PayloadDedupConfig};
 use taskcast_core::types::{
-    EventQueryOptions, ShortTermStore, Task, TaskEvent, TaskFilter, Worker, WorkerAssignment,
+    task_order, EventQueryOptions, EventTypeCounts, FilteredIndexClaim, FilteredIndexMark, Level,
+    NewTaskGroupOutcome, NewTaskOutcome, OutcomeQuery, RetrySchedule, SeriesSummary, ShortTermStore,
+    Task, TaskCursor, TaskEvent, TaskFilter, TaskOutcome, TaskPage, Worker, WorkerAssignment,
     WorkerFilter,
 };
 
+use crate::codec::{
+    compress_value, decode_event, decode_task, encode_value, MIN_VALUE_FORMAT_VERSION,
+    VALUE_FORMAT_VERSION,
+};
+use crate::error::store_error;
+
+/// How long a creation reservation may be held before Redis expires it, so a
+/// creator that dies mid-write cannot block the id forever.
+const CREATE_RESERVATION_TTL_MS: u64 = 5_000;
+
 /// Helper to generate Redis key names for a given prefix.
 struct Keys {
     prefix: String,
@@ -24,6 +46,11 @@ impl Keys {
         format!("{}:task:{}", self.prefix, id)
     }
 
+    /// `{prefix}:reserve:{id}` -- short-lived creation reservation (SET NX PX).
+    fn reservation(&self, id: &str) -> String {
+        format!("{}:reserve:{}", self.prefix, id)
+    }
+
     /// `{prefix}:events:{id}` -- a Redis list of event JSONs.
     fn events(&self, id: &str) -> String {
         format!("{}:events:{}", self.prefix, id)
@@ -34,6 +61,12 @@ impl Keys {
         format!("{}:idx:{}", self.prefix, id)
     }
 
+    /// `{prefix}:typeCounts:{id}` -- HASH of `type:{type}` and
+    /// `level:{level}` to the number of stored events (HINCRBY).
+    fn type_counts(&self, id: &str) -> String {
+        format!("{}:typeCounts:{}", self.prefix, id)
+    }
+
     /// `{prefix}:series:{taskId}:{seriesId}` -- latest event in a series.
     fn series_latest(&self, task_id: &str, series_id: &str) -> String {
         format!("{}:series:{}:{}", self.prefix, task_id, series_id)
@@ -68,6 +101,154 @@ impl Keys {
     fn worker_assignments(&self, worker_id: &str) -> String {
         format!("{}:workerAssignments:{}", self.prefix, worker_id)
     }
+
+    /// `{prefix}:blob:{hash}` -- HASH of a deduplicated payload (`data`) and
+    /// the number of stored events referencing it (`refs`).
+    fn blob(&self, hash: &str) -> String {
+        format!("{}:blob:{}", self.prefix, hash)
+    }
+
+    /// `{prefix}:retries` -- ZSET of task IDs with a pending retry, scored by due time.
+    fn retries(&self) -> String {
+        format!("{}:retries", self.prefix)
+    }
+
+    /// `{prefix}:retry:{taskId}` -- stores the RetrySchedule JSON.
+    fn retry(&self, task_id: &str) -> String {
+        format!("{}:retry:{}", self.prefix, task_id)
+    }
+
+    /// `{prefix}:retryClaim:{taskId}` -- claim on a due retry (SET NX PX).
+    fn retry_claim(&self, task_id: &str) -> String {
+        format!("{}:retryClaim:{}", self.prefix, task_id)
+    }
+
+    /// `{prefix}:activations` -- ZSET of scheduled task IDs, scored by activation time.
+    fn activations(&self) -> String {
+        format!("{}:activations", self.prefix)
+    }
+
+    /// `{prefix}:activationClaim:{taskId}` -- claim on a due activation (SET NX PX).
+    fn activation_claim(&self, task_id: &str) -> String {
+        format!("{}:activationClaim:{}", self.prefix, task_id)
+    }
+
+    /// `{prefix}:webhookDeliveries:{taskId}` -- HASH of `{webhook}:{fingerprint}`
+    /// to the last delivery time, for suppression windows.
+    fn webhook_deliveries(&self, task_id: &str) -> String {
+        format!("{}:webhookDeliveries:{}", self.prefix, task_id)
+    }
+
+    /// `{prefix}:filteredIndices:{taskId}` -- HASH of consumer to its JSON
+    /// filtered index mark.
+    fn filtered_indices(&self, task_id: &str) -> String {
+        format!("{}:filteredIndices:{}", self.prefix, task_id)
+    }
+
+    /// `{prefix}:deadlineWarning:{taskId}:{warning}` -- claim on a deadline
+    /// warning (SET NX PX).
+    fn deadline_warning(&self, task_id: &str, warning: &str) -> String {
+        format!("{}:deadlineWarning:{}:{}", self.prefix, task_id, warning)
+    }
+
+    /// `{prefix}:outcomes` -- ZSET of TaskOutcome JSONs, scored by completion time.
+    fn outcomes(&self) -> String {
+        format!("{}:outcomes", self.prefix)
+    }
+
+    /// `{prefix}:conventionTaskTypes` -- SET of task types with conventions.
+    fn convention_task_types(&self) -> String {
+        format!("{}:conventionTaskTypes", self.prefix)
+    }
+
+    /// `{prefix}:conventions:{taskType}` -- HASH of `{field}:{eventType}`,
+    /// one field per convention attribute.
+    fn conventions(&self, task_type: &str) -> String {
+        format!("{}:conventions:{}", self.prefix, task_type)
+    }
+}
+
+/// Outcomes read per round trip while filtering the outcomes index.
+const OUTCOME_SCAN_BATCH: isize = 500;
+
+/// Task ids asked for per `SSCAN` while paging through tasks.
+const TASK_SCAN_BATCH: usize = 500;
+
+/// Applies `field, delta` pairs to a type counts hash, dropping fields that
+/// reach zero.
+const ADJUST_TYPE_COUNTS: &str = r#"
+    for i = 1, #ARGV, 2 do
+        if redis.call('HINCRBY', KEYS[1], ARGV[i], ARGV[i + 1]) <= 0 then
+            redis.call('HDEL', KEYS[1], ARGV[i])
+        end
+    end
+"#;
+
+fn type_field(event: &TaskEvent) -> String {
+    format!("type:{}", event.r#type)
+}
+
+fn level_field(event: &TaskEvent) -> String {
+    let level = serde_json::to_value(&event.level).unwrap_or_default();
+    format!("level:{}", level.as_str().unwrap_or_default())
+}
+
+/// Whether `t` passes every condition of `filter` but its `limit`.
+fn task_matches(t: &Task, filter: &TaskFilter) -> bool {
+    if let Some(ref statuses) = filter.status {
+        if !statuses.contains(&t.status) {
+            return false;
+        }
+    }
+    if let Some(ref types) = filter.types {
+        match &t.r#type {
+            Some(task_type) if types.contains(task_type) => {}
+            _ => return false,
+        }
+    }
+    if let Some(ref tag_matcher) = filter.tags {
+        let task_tags = t.tags.as_deref().unwrap_or(&[]);
+        // all: every tag in the filter must be present
+        if let Some(ref all) = tag_matcher.all {
+            if !all.iter().all(|tag| task_tags.contains(tag)) {
+                return false;
+            }
+        }
+        // any: at least one tag must be present
+        if let Some(ref any) = tag_matcher.any {
+            if !any.iter().any(|tag| task_tags.contains(tag)) {
+                return false;
+            }
+        }
+        // none: no tag in the filter should be present
+        if let Some(ref none) = tag_matcher.none {
+            if none.iter().any(|tag| task_tags.contains(tag)) {
+                return false;
+            }
+        }
+    }
+    if let Some(ref assign_modes) = filter.assign_mode {
+        match &t.assign_mode {
+            Some(mode) if assign_modes.contains(mode) => {}
+            _ => return false,
+        }
+    }
+    if let Some(ref exclude_ids) = filter.exclude_task_ids {
+        if exclude_ids.contains(&t.id) {
+            return false;
+        }
+    }
+    if let Some(created_before) = filter.created_before {
+        if t.created_at >= created_before {
+            return false;
+        }
+    }
+    if let Some(ref origins) = filter.origin {
+        if !origins.contains(&t.origin_kind()) {
+            return false;
+        }
+    }
+    true
 }
 
 /// Redis-backed short-term store.
@@ -77,6 +258,11 @@ impl Keys {
 pub struct RedisShortTermStore {
     conn: MultiplexedConnection,
     keys: Keys,
+    dedup: Option<PayloadDedupConfig>,
+    integrity: IntegrityMonitor,
+    value_format: u64,
+    compression: ValueCompressionConfig,
+    compression_counters: CompressionCounters,
 }
 
 impl RedisShortTermStore {
@@ -89,39 +275,515 @@ impl RedisShortTermStore {
         Self {
             conn,
             keys: Keys::new(resolved_prefix),
+            dedup: None,
+            integrity: IntegrityMonitor::default(),
+            value_format: VALUE_FORMAT_VERSION,
+            compression: ValueCompressionConfig::default(),
+            compression_counters: CompressionCounters::default(),
         }
     }
 
+    /// Create a store that keeps large event payloads once per distinct
+    /// content under `{prefix}:blob:{hash}` and stores `{"$blobRef": hash}`
+    /// in the event list. Reads resolve references transparently.
+    pub fn with_payload_dedup(
+        conn: MultiplexedConnection,
+        prefix: Option<&str>,
+        config: PayloadDedupConfig,
+    ) -> Self {
+        Self {
+            dedup: Some(config),
+            ..Self::new(conn, prefix)
+        }
+    }
+
+    /// Use `monitor` for entries that fail to decode on read. The default
+    /// monitor surfaces them (see `CorruptRecordPolicy`).
+    pub fn with_integrity(self, monitor: IntegrityMonitor) -> Self {
+        Self {
+            integrity: monitor,
+            ..self
+        }
+    }
+
+    /// Write tasks and events in value format `version` (see
+    /// [`codec`](crate::codec)) instead of the latest. Values in any format
+    /// are read either way; writing the previous format lets instances of an
+    /// older release keep reading the store during a rolling upgrade.
+    ///
+    /// # Panics
+    ///
+    /// If `version` is outside
+    /// [`MIN_VALUE_FORMAT_VERSION`]`..=`[`VALUE_FORMAT_VERSION`].
+    pub fn with_value_format_version(self, version: u64) -> Self {
+        assert!(
+            (MIN_VALUE_FORMAT_VERSION..=VALUE_FORMAT_VERSION).contains(&version),
+            "unsupported value format version {version}"
+        );
+        Self {
+            value_format: version,
+            ..self
+        }
+    }
+
+    /// Compress the events in each task's event list under `config` (see
+    /// [`codec::compress_value`](crate::codec::compress_value)). Tasks and
+    /// the latest value of each series stay uncompressed: scripts running
+    /// inside Redis read them. Compressed events are read whatever the
+    /// setting, so compression can be turned off again at any time.
+    pub fn with_compression(self, config: ValueCompressionConfig) -> Self {
+        Self {
+            compression: config,
+            ..self
+        }
+    }
+
+    /// Encodes a task or event in the configured value format.
+    fn encode<T: Serialize>(&self, value: &T) -> Result<String, serde_json::Error> {
+        encode_value(value, self.value_format)
+    }
+
+    /// Encodes an event for the event list, compressed as configured.
+    fn pack_event(&self, event: &TaskEvent) -> Result<Vec<u8>, serde_json::Error> {
+        let encoded = self.encode(event)?;
+        if self.compression.algorithm == CompressionAlgorithm::None {
+            return Ok(encoded.into_bytes());
+        }
+        let raw_len = encoded.len();
+        let packed = compress_value(encoded, &self.compression);
+        self.compression_counters
+            .record(raw_len, packed.len(), packed.len() < raw_len);
+        Ok(packed)
+    }
+
     /// Returns a reference to the key helper for testing or introspection.
     pub fn key_prefix(&self) -> &str {
         &self.keys.prefix
     }
+
+    /// Writes the task key only if it is absent. Returns `false` if another
+    /// creator's task is already stored.
+    async fn write_new_task(
+        &self,
+        task: &Task,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let json = self.encode(task)?;
+        let mut conn = self.conn.clone();
+        let written: Option<String> = redis::cmd("SET")
+            .arg(self.keys.task(&task.id))
+            .arg(&json)
+            .arg("NX")
+            .query_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        if written.is_none() {
+            return Ok(false);
+        }
+        conn.sadd::<_, _, ()>(self.keys.tasks_set(), &task.id)
+            .await
+            .map_err(store_error)?;
+        Ok(true)
+    }
+
+    /// Deletes the reservation only if it still holds our token, so an expired
+    /// reservation re-acquired by another creator is left alone.
+    async fn release_reservation(
+        &self,
+        key: &str,
+        token: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+              return redis.call('DEL', KEYS[1])
+            end
+            return 0
+        "#;
+        let mut conn = self.conn.clone();
+        redis::Script::new(lua)
+            .key(key)
+            .arg(token)
+            .invoke_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// Splits off the payload to deduplicate, returning the event as it
+    /// should be stored.
+    fn dedupe_event(&self, event: TaskEvent) -> (TaskEvent, Option<DedupedPayload>) {
+        match self.dedup.as_ref().and_then(|d| d.dedupe(&event.data)) {
+            Some(blob) => (
+                TaskEvent {
+                    data: blob_ref(&blob.hash),
+                    ..event
+                },
+                Some(blob),
+            ),
+            None => (event, None),
+        }
+    }
+
+    /// Stores the blob if absent and takes one reference on it. Must run
+    /// before the referencing event is written, so a crash in between leaves
+    /// an orphaned blob rather than a dangling reference.
+    async fn acquire_blob(
+        &self,
+        blob: &DedupedPayload,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            redis.call('HSETNX', KEYS[1], 'data', ARGV[1])
+            return redis.call('HINCRBY', KEYS[1], 'refs', 1)
+        "#;
+        let mut conn = self.conn.clone();
+        redis::Script::new(lua)
+            .key(self.keys.blob(&blob.hash))
+            .arg(&blob.json)
+            .invoke_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// Drops one reference per hash, deleting a blob when its count reaches
+    /// zero. Must run after the referencing events are gone.
+    async fn release_blobs(
+        &self,
+        hashes: &[String],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            local refs = redis.call('HINCRBY', KEYS[1], 'refs', -1)
+            if refs <= 0 then
+              redis.call('DEL', KEYS[1])
+            end
+            return refs
+        "#;
+        let script = redis::Script::new(lua);
+        let mut conn = self.conn.clone();
+        for hash in hashes {
+            script
+                .key(self.keys.blob(hash))
+                .invoke_async::<()>(&mut conn)
+                .await
+                .map_err(store_error)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces blob references with their payloads. A reference with no
+    /// blob is left as is: it is an ordinary payload that happens to look
+    /// like one (written while deduplication was off).
+    async fn resolve_blobs(
+        &self,
+        events: &mut [TaskEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut hashes: Vec<String> = events
+            .iter()
+            .filter_map(|e| blob_ref_hash(&e.data).map(str::to_string))
+            .collect();
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        hashes.sort();
+        hashes.dedup();
+
+        let mut pipe = redis::pipe();
+        for hash in &hashes {
+            pipe.hget(self.keys.blob(hash), "data");
+        }
+        let mut conn = self.conn.clone();
+        let payloads: Vec<Option<String>> =
+            pipe.query_async(&mut conn).await.map_err(store_error)?;
+
+        let mut resolved: HashMap<&str, serde_json::Value> = HashMap::new();
+        for (hash, payload) in hashes.iter().zip(payloads) {
+            if let Some(json) = payload {
+                resolved.insert(hash.as_str(), serde_json::from_str(&json)?);
+            }
+        }
+        for event in events.iter_mut() {
+            if let Some(data) = blob_ref_hash(&event.data).and_then(|h| resolved.get(h)) {
+                event.data = data.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves a task's type counts from `removed` to `added` events. Changes
+    /// that cancel out, such as a replacement of the same type, cost nothing.
+    async fn adjust_type_counts(
+        &self,
+        task_id: &str,
+        removed: &[&TaskEvent],
+        added: &[&TaskEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut deltas: HashMap<String, i64> = HashMap::new();
+        for (events, delta) in [(removed, -1), (added, 1)] {
+            for event in events {
+                *deltas.entry(type_field(event)).or_default() += delta;
+                *deltas.entry(level_field(event)).or_default() += delta;
+            }
+        }
+        deltas.retain(|_, delta| *delta != 0);
+        if deltas.is_empty() {
+            return Ok(());
+        }
+
+        let script = redis::Script::new(ADJUST_TYPE_COUNTS);
+        let mut invocation = script.prepare_invoke();
+        invocation.key(self.keys.type_counts(task_id));
+        for (field, delta) in &deltas {
+            invocation.arg(field).arg(delta);
+        }
+        let mut conn = self.conn.clone();
+        invocation
+            .invoke_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// Deletes the given events from a task's event list, releasing any blobs
+    /// they reference.
+    pub async fn delete_events(
+        &self,
+        task_id: &str,
+        event_ids: &[String],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let key = self.keys.events(task_id);
+        let mut conn = self.conn.clone();
+        let raw: Vec<Vec<u8>> = conn.lrange(&key, 0, -1).await.map_err(store_error)?;
+
+        let mut released = Vec::new();
+        let mut deleted = Vec::new();
+        for (position, item) in raw.into_iter().enumerate() {
+            let Ok(event) = decode_event(task_id, position as u64, &item) else {
+                continue;
+            };
+            if !event_ids.contains(&event.id) {
+                continue;
+            }
+            let removed: i64 = conn.lrem(&key, 1, &item).await.map_err(store_error)?;
+            if removed > 0 {
+                if let Some(hash) = blob_ref_hash(&event.data) {
+                    released.push(hash.to_string());
+                }
+                deleted.push(event);
+            }
+        }
+        let removed: Vec<&TaskEvent> = deleted.iter().collect();
+        self.adjust_type_counts(task_id, &removed, &[]).await?;
+        self.release_blobs(&released).await?;
+        self.drop_series_state(task_id, &deleted).await
+    }
+
+    /// Drops the series state of every series one of `events` belonged to.
+    async fn drop_series_state(
+        &self,
+        task_id: &str,
+        events: &[TaskEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        for series_id in events.iter().filter_map(|event| event.series_id.as_ref()) {
+            redis::pipe()
+                .atomic()
+                .del(self.keys.series_latest(task_id, series_id))
+                .srem(self.keys.series_ids(task_id), series_id)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(store_error)?;
+        }
+        Ok(())
+    }
+
+    /// Deletes a task with its events and series state, releasing any blobs
+    /// its events reference.
+    pub async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let raw: Vec<Vec<u8>> = conn
+            .lrange(self.keys.events(task_id), 0, -1)
+            .await
+            .map_err(store_error)?;
+        let released: Vec<String> = raw
+            .iter()
+            .enumerate()
+            .filter_map(|(position, item)| decode_event(task_id, position as u64, item).ok())
+            .filter_map(|event| blob_ref_hash(&event.data).map(str::to_string))
+            .collect();
+
+        let series_ids_key = self.keys.series_ids(task_id);
+        let series_ids: Vec<String> = conn.smembers(&series_ids_key).await.map_err(store_error)?;
+        let mut keys = vec![
+            self.keys.task(task_id),
+            self.keys.events(task_id),
+            self.keys.idx(task_id),
+            self.keys.type_counts(task_id),
+            series_ids_key,
+        ];
+        keys.extend(
+            series_ids
+                .iter()
+                .map(|sid| self.keys.series_latest(task_id, sid)),
+        );
+        conn.del::<_, ()>(&keys).await.map_err(store_error)?;
+        conn.srem::<_, _, ()>(self.keys.tasks_set(), task_id)
+            .await
+            .map_err(store_error)?;
+
+        self.release_blobs(&released).await
+    }
 }
 
 #[async_trait]
 impl ShortTermStore for RedisShortTermStore {
-    async fn save_task(
-        &self,
-        task: Task,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.task(&task.id);
         let tasks_set_key = self.keys.tasks_set();
-        let json = serde_json::to_string(&task)?;
+        let json = self.encode(&task)?;
         let mut conn = self.conn.clone();
-        conn.set::<_, _, ()>(&key, &json).await?;
-        conn.sadd::<_, _, ()>(&tasks_set_key, &task.id).await?;
+        conn.set::<_, _, ()>(&key, &json)
+            .await
+            .map_err(store_error)?;
+        conn.sadd::<_, _, ()>(&tasks_set_key, &task.id)
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
+    /// Compare-and-set in Lua: the stored task's `version` is checked and the
+    /// new JSON written in one step, so no other save can land in between.
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            local taskJson = redis.call('GET', KEYS[1])
+            if taskJson then
+              local task = cjson.decode(taskJson)
+              if task.v ~= nil and type(task.d) == 'table' then task = task.d end
+              local stored = task.version or 0
+              if stored ~= tonumber(ARGV[2]) then return 0 end
+            end
+            redis.call('SET', KEYS[1], ARGV[1])
+            redis.call('SADD', KEYS[2], ARGV[3])
+            return 1
+        "#;
+        let json = self.encode(&task)?;
+        let mut conn = self.conn.clone();
+        let written: i32 = redis::Script::new(lua)
+            .key(self.keys.task(&task.id))
+            .key(self.keys.tasks_set())
+            .arg(&json)
+            .arg(expected_version)
+            .arg(&task.id)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(written == 1)
+    }
+
+    /// Reservation protocol: `SET reserve:{id} NX PX` claims the id, the task
+    /// is written with `SET NX`, then the reservation is released. A creator
+    /// that loses the reservation reports the winner's task, which may still
+    /// be `None` while the winner's write is in flight.
+    async fn save_new_task(
+        &self,
+        task: Task,
+    ) -> Result<NewTaskOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let reservation_key = self.keys.reservation(&task.id);
+        let token = ulid::Ulid::new().to_string();
+        let mut conn = self.conn.clone();
+        let reserved: Option<String> = redis::cmd("SET")
+            .arg(&reservation_key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(CREATE_RESERVATION_TTL_MS)
+            .query_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        if reserved.is_none() {
+            return Ok(NewTaskOutcome::AlreadyExists(
+                self.get_task(&task.id).await?.map(Box::new),
+            ));
+        }
+
+        let written = self.write_new_task(&task).await;
+        self.release_reservation(&reservation_key, &token).await?;
+        if written? {
+            Ok(NewTaskOutcome::Created)
+        } else {
+            Ok(NewTaskOutcome::AlreadyExists(
+                self.get_task(&task.id).await?.map(Box::new),
+            ))
+        }
+    }
+
+    /// One script checks that no task key or creation reservation of the
+    /// group exists and writes every task, so the group lands whole or not
+    /// at all and a concurrent creation of any of its ids sees none of it.
+    async fn save_new_task_group(
+        &self,
+        tasks: Vec<Task>,
+    ) -> Result<NewTaskGroupOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let lua = r#"
+            local n = #ARGV / 2
+            for i = 1, n do
+              if redis.call('EXISTS', KEYS[i], KEYS[n + i]) > 0 then
+                return ARGV[n + i]
+              end
+            end
+            -- SADD is the only write that can fail, on a mistyped key, so it
+            -- goes first: a script error leaves no task written.
+            for i = 1, n do
+              redis.call('SADD', KEYS[2 * n + 1], ARGV[n + i])
+            end
+            for i = 1, n do
+              redis.call('SET', KEYS[i], ARGV[i])
+            end
+            return false
+        "#;
+        let script = redis::Script::new(lua);
+        let mut invocation = script.prepare_invoke();
+        for task in &tasks {
+            invocation.key(self.keys.task(&task.id));
+        }
+        for task in &tasks {
+            invocation.key(self.keys.reservation(&task.id));
+        }
+        invocation.key(self.keys.tasks_set());
+        for task in &tasks {
+            invocation.arg(self.encode(task)?);
+        }
+        for task in &tasks {
+            invocation.arg(&task.id);
+        }
+        let mut conn = self.conn.clone();
+        let taken: Option<String> = invocation
+            .invoke_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        match taken {
+            None => Ok(NewTaskGroupOutcome::Created),
+            Some(task_id) => Ok(NewTaskGroupOutcome::AlreadyExists {
+                existing: self.get_task(&task_id).await?.map(Box::new),
+                task_id,
+            }),
+        }
+    }
+
     async fn get_task(
         &self,
         task_id: &str,
     ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.task(task_id);
         let mut conn = self.conn.clone();
-        let result: Option<String> = conn.get(&key).await?;
+        let result: Option<String> = conn.get(&key).await.map_err(store_error)?;
         match result {
-            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Some(json) => Ok(self.integrity.task(decode_task(task_id, &json))?),
             None => Ok(None),
         }
     }
@@ -132,9 +794,45 @@ impl ShortTermStore for RedisShortTermStore {
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.events(task_id);
-        let json = serde_json::to_string(&event)?;
+        let counts_key = self.keys.type_counts(task_id);
+        let (type_field, level_field) = (type_field(&event), level_field(&event));
+        let (event, blob) = self.dedupe_event(event);
+        let json = self.pack_event(&event)?;
         let mut conn = self.conn.clone();
-        conn.rpush::<_, _, ()>(&key, &json).await?;
+        match blob {
+            Some(blob) => {
+                // Blob reference, event and type counts are written atomically.
+                let lua = r#"
+                    redis.call('HSETNX', KEYS[1], 'data', ARGV[1])
+                    redis.call('HINCRBY', KEYS[1], 'refs', 1)
+                    redis.call('HINCRBY', KEYS[3], ARGV[3], 1)
+                    redis.call('HINCRBY', KEYS[3], ARGV[4], 1)
+                    return redis.call('RPUSH', KEYS[2], ARGV[2])
+                "#;
+                redis::Script::new(lua)
+                    .key(self.keys.blob(&blob.hash))
+                    .key(&key)
+                    .key(&counts_key)
+                    .arg(&blob.json)
+                    .arg(&json)
+                    .arg(&type_field)
+                    .arg(&level_field)
+                    .invoke_async::<()>(&mut conn)
+                    .await
+                    .map_err(store_error)?;
+            }
+            None => redis::pipe()
+                .atomic()
+                .rpush(&key, &json)
+                .ignore()
+                .hincr(&counts_key, &type_field, 1)
+                .ignore()
+                .hincr(&counts_key, &level_field, 1)
+                .ignore()
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(store_error)?,
+        }
         Ok(())
     }
 
@@ -145,36 +843,23 @@ impl ShortTermStore for RedisShortTermStore {
     ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.events(task_id);
         let mut conn = self.conn.clone();
-        let raw: Vec<String> = conn.lrange(&key, 0, -1).await?;
+        let raw: Vec<Vec<u8>> = conn.lrange(&key, 0, -1).await.map_err(store_error)?;
 
         let all: Vec<TaskEvent> = raw
-            .into_iter()
-            .filter_map(|s| serde_json::from_str(&s).ok())
+            .iter()
+            .enumerate()
+            .filter_map(|(position, s)| {
+                self.integrity
+                    .event(decode_event(task_id, position as u64, s))
+            })
             .collect();
 
-        let mut result = all;
-
-        if let Some(ref opts) = opts {
-            if let Some(ref since) = opts.since {
-                if let Some(ref id) = since.id {
-                    let idx = result.iter().position(|e| &e.id == id);
-                    result = match idx {
-                        Some(i) => result[i + 1..].to_vec(),
-                        None => result,
-                    };
-                } else if let Some(index) = since.index {
-                    result.retain(|e| e.index > index);
-                } else if let Some(timestamp) = since.timestamp {
-                    result.retain(|e| e.timestamp > timestamp);
-                }
-            }
-
-            if let Some(limit) = opts.limit {
-                result.truncate(limit as usize);
-            }
-        }
-
-        Ok(result)
+        // Series replacements rewrite list entries in place, so the list is
+        // not trusted to be in index order; `apply_event_query` sorts it into
+        // the canonical order before slicing, as the memory store does.
+        let mut events = apply_event_query(all, opts.as_ref());
+        self.resolve_blobs(&mut events).await?;
+        Ok(events)
     }
 
     async fn set_ttl(
@@ -187,24 +872,42 @@ impl ShortTermStore for RedisShortTermStore {
 
         // Expire task key
         conn.expire::<_, ()>(&self.keys.task(task_id), ttl_secs)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
         // Expire events list
         conn.expire::<_, ()>(&self.keys.events(task_id), ttl_secs)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
         // Expire index counter
         conn.expire::<_, ()>(&self.keys.idx(task_id), ttl_secs)
-            .await?;
+            .await
+            .map_err(store_error)?;
+
+        // Expire type counts
+        conn.expire::<_, ()>(&self.keys.type_counts(task_id), ttl_secs)
+            .await
+            .map_err(store_error)?;
 
         // Expire series IDs set and each series latest key
         let series_ids_key = self.keys.series_ids(task_id);
         let series_ids: Vec<String> = conn.smembers(&series_ids_key).await.unwrap_or_default();
         for sid in &series_ids {
             conn.expire::<_, ()>(&self.keys.series_latest(task_id, sid), ttl_secs)
-                .await?;
+                .await
+                .map_err(store_error)?;
         }
-        conn.expire::<_, ()>(&series_ids_key, ttl_secs).await?;
+        conn.expire::<_, ()>(&series_ids_key, ttl_secs)
+            .await
+            .map_err(store_error)?;
+
+        conn.expire::<_, ()>(&self.keys.webhook_deliveries(task_id), ttl_secs)
+            .await
+            .map_err(store_error)?;
+        conn.expire::<_, ()>(&self.keys.filtered_indices(task_id), ttl_secs)
+            .await
+            .map_err(store_error)?;
 
         Ok(())
     }
@@ -216,13 +919,56 @@ impl ShortTermStore for RedisShortTermStore {
     ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.series_latest(task_id, series_id);
         let mut conn = self.conn.clone();
-        let result: Option<String> = conn.get(&key).await?;
+        let result: Option<String> = conn.get(&key).await.map_err(store_error)?;
         match result {
-            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            Some(json) => Ok(Some(decode_event(task_id, 0, &json)?)),
             None => Ok(None),
         }
     }
 
+    async fn list_series(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<SeriesSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let mut series_ids: Vec<String> = conn
+            .smembers(self.keys.series_ids(task_id))
+            .await
+            .map_err(store_error)?;
+        if series_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        series_ids.sort();
+        let keys: Vec<String> = series_ids
+            .iter()
+            .map(|series_id| self.keys.series_latest(task_id, series_id))
+            .collect();
+        let values: Vec<Option<String>> = conn.mget(&keys).await.map_err(store_error)?;
+        let mut series = Vec::with_capacity(series_ids.len());
+        for (series_id, value) in series_ids.iter().zip(values) {
+            // An id whose latest key is gone (expired or deleted) has no state.
+            if let Some(json) = value {
+                series.push(SeriesSummary::of(
+                    series_id,
+                    &decode_event(task_id, 0, &json)?,
+                ));
+            }
+        }
+        Ok(series)
+    }
+
+    async fn series_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let count: u64 = conn
+            .scard(self.keys.series_ids(task_id))
+            .await
+            .map_err(store_error)?;
+        Ok(count)
+    }
+
     async fn set_series_latest(
         &self,
         task_id: &str,
@@ -230,12 +976,15 @@ impl ShortTermStore for RedisShortTermStore {
         event: TaskEvent,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.series_latest(task_id, series_id);
-        let json = serde_json::to_string(&event)?;
+        let json = self.encode(&event)?;
         let mut conn = self.conn.clone();
-        conn.set::<_, _, ()>(&key, &json).await?;
+        conn.set::<_, _, ()>(&key, &json)
+            .await
+            .map_err(store_error)?;
         // Track series ID
         conn.sadd::<_, _, ()>(&self.keys.series_ids(task_id), series_id)
-            .await?;
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -249,12 +998,16 @@ impl ShortTermStore for RedisShortTermStore {
         // Atomic read-modify-write via Lua script (matches TS implementation)
         let lua = r#"
             local prevJson = redis.call('GET', KEYS[1])
-            local newEvent = cjson.decode(ARGV[1])
+            -- Values may be wrapped in a format envelope; the event is its payload.
+            local newValue = cjson.decode(ARGV[1])
+            local newEvent = newValue
+            if newValue.v ~= nil and type(newValue.d) == 'table' then newEvent = newValue.d end
             local field = ARGV[2]
             local seriesId = ARGV[3]
 
             if prevJson then
               local prev = cjson.decode(prevJson)
+              if prev.v ~= nil and type(prev.d) == 'table' then prev = prev.d end
               local prevData = prev.data
               local newData = newEvent.data
 
@@ -265,7 +1018,7 @@ impl ShortTermStore for RedisShortTermStore {
               end
             end
 
-            local result = cjson.encode(newEvent)
+            local result = cjson.encode(newValue)
             redis.call('SET', KEYS[1], result)
             redis.call('SADD', KEYS[2], seriesId)
             return result
@@ -273,7 +1026,7 @@ impl ShortTermStore for RedisShortTermStore {
 
         let series_latest_key = self.keys.series_latest(task_id, series_id);
         let series_ids_key = self.keys.series_ids(task_id);
-        let event_json = serde_json::to_string(&event)?;
+        let event_json = self.encode(&event)?;
 
         let script = redis::Script::new(lua);
         let mut conn = self.conn.clone();
@@ -284,9 +1037,10 @@ impl ShortTermStore for RedisShortTermStore {
             .arg(field)
             .arg(series_id)
             .invoke_async(&mut conn)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
-        let accumulated: TaskEvent = serde_json::from_str(&result_json)?;
+        let accumulated = decode_event(task_id, event.index, &result_json)?;
         Ok(accumulated)
     }
 
@@ -295,43 +1049,57 @@ impl ShortTermStore for RedisShortTermStore {
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         let series_key = self.keys.series_latest(task_id, series_id);
         let events_key = self.keys.events(task_id);
         let mut conn = self.conn.clone();
 
         // Get the previous series latest
-        let prev_json: Option<String> = conn.get(&series_key).await?;
+        let prev_json: Option<String> = conn.get(&series_key).await.map_err(store_error)?;
 
-        if let Some(prev_json) = prev_json {
-            let prev: TaskEvent = serde_json::from_str(&prev_json)?;
+        let replaced = if let Some(prev_json) = prev_json {
+            let prev = decode_event(task_id, 0, &prev_json)?;
 
             // Find and replace the event in the list
-            let raw: Vec<String> = conn.lrange(&events_key, 0, -1).await?;
-            let new_event_json = serde_json::to_string(&event)?;
+            let raw: Vec<Vec<u8>> = conn.lrange(&events_key, 0, -1).await.map_err(store_error)?;
+            let (stored, blob) = self.dedupe_event(event.clone());
+            let new_event_json = self.pack_event(&stored)?;
 
             // Search from the end (rposition equivalent)
             for (i, item) in raw.iter().enumerate().rev() {
-                if let Ok(e) = serde_json::from_str::<TaskEvent>(item) {
+                if let Ok(e) = decode_event(task_id, i as u64, item) {
                     if e.id == prev.id {
+                        if let Some(ref blob) = blob {
+                            self.acquire_blob(blob).await?;
+                        }
                         conn.lset::<_, _, ()>(&events_key, i as isize, &new_event_json)
-                            .await?;
+                            .await
+                            .map_err(store_error)?;
+                        self.adjust_type_counts(task_id, &[&e], &[&event]).await?;
+                        if let Some(hash) = blob_ref_hash(&e.data) {
+                            self.release_blobs(&[hash.to_string()]).await?;
+                        }
                         break;
                     }
                 }
             }
+            Some(prev.id)
         } else {
             // No previous -- just append
             self.append_event(task_id, event.clone()).await?;
-        }
+            None
+        };
 
         // Update series latest
-        let json = serde_json::to_string(&event)?;
-        conn.set::<_, _, ()>(&series_key, &json).await?;
+        let json = self.encode(&event)?;
+        conn.set::<_, _, ()>(&series_key, &json)
+            .await
+            .map_err(store_error)?;
         conn.sadd::<_, _, ()>(&self.keys.series_ids(task_id), series_id)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
-        Ok(())
+        Ok(replaced)
     }
 
     async fn next_index(
@@ -342,10 +1110,63 @@ impl ShortTermStore for RedisShortTermStore {
         let mut conn = self.conn.clone();
         // INCR is atomic -- safe across multiple instances sharing the same Redis.
         // Returns 1-based, so subtract 1 to get 0-based index.
-        let val: i64 = conn.incr(&key, 1).await?;
+        let val: i64 = conn.incr(&key, 1).await.map_err(store_error)?;
         Ok((val - 1) as u64)
     }
 
+    fn compression_stats(&self) -> Option<CompressionStats> {
+        (self.compression.algorithm != CompressionAlgorithm::None)
+            .then(|| self.compression_counters.stats(self.compression.algorithm))
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        Some(&self.integrity)
+    }
+
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        Some(self)
+    }
+
+    async fn event_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        // The counter holds the number of indices handed out so far.
+        let val: Option<i64> = conn
+            .get(self.keys.idx(task_id))
+            .await
+            .map_err(store_error)?;
+        Ok(val.unwrap_or(0) as u64)
+    }
+
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let fields: HashMap<String, i64> = conn
+            .hgetall(self.keys.type_counts(task_id))
+            .await
+            .map_err(store_error)?;
+
+        let mut counts = EventTypeCounts::default();
+        for (field, count) in fields {
+            let count = match u64::try_from(count) {
+                Ok(count) if count > 0 => count,
+                _ => continue,
+            };
+            if let Some(r#type) = field.strip_prefix("type:") {
+                counts.types.insert(r#type.to_string(), count);
+            } else if let Some(level) = field.strip_prefix("level:") {
+                if let Ok(level) = serde_json::from_value::<Level>(level.into()) {
+                    counts.levels.insert(level, count);
+                }
+            }
+        }
+        Ok(counts)
+    }
+
     // ─── Task query ──────────────────────────────────────────────────────
 
     async fn list_tasks(
@@ -355,73 +1176,42 @@ impl ShortTermStore for RedisShortTermStore {
         let tasks_set_key = self.keys.tasks_set();
         let mut conn = self.conn.clone();
 
-        let task_ids: Vec<String> = conn.smembers(&tasks_set_key).await?;
+        let task_ids: Vec<String> = conn.smembers(&tasks_set_key).await.map_err(store_error)?;
         if task_ids.is_empty() {
             return Ok(Vec::new());
         }
 
         // Build task keys for MGET
         let task_keys: Vec<String> = task_ids.iter().map(|id| self.keys.task(id)).collect();
-        let raw: Vec<Option<String>> = conn.mget(&task_keys).await?;
+        let raw: Vec<Option<String>> = conn.mget(&task_keys).await.map_err(store_error)?;
 
         // Collect stale IDs (task expired but ID still in SET) for passive cleanup
         let stale_ids: Vec<&str> = raw
             .iter()
             .enumerate()
-            .filter_map(|(i, opt)| if opt.is_none() { Some(task_ids[i].as_str()) } else { None })
+            .filter_map(|(i, opt)| {
+                if opt.is_none() {
+                    Some(task_ids[i].as_str())
+                } else {
+                    None
+                }
+            })
             .collect();
         if !stale_ids.is_empty() {
-            conn.srem::<_, _, ()>(&tasks_set_key, &stale_ids).await?;
+            conn.srem::<_, _, ()>(&tasks_set_key, &stale_ids)
+                .await
+                .map_err(store_error)?;
         }
 
-        let mut tasks: Vec<Task> = raw
-            .into_iter()
-            .filter_map(|opt| opt.and_then(|s| serde_json::from_str(&s).ok()))
-            .collect();
-
-        // Apply filters in Rust
-        if let Some(ref statuses) = filter.status {
-            tasks.retain(|t| statuses.contains(&t.status));
-        }
-        if let Some(ref types) = filter.types {
-            tasks.retain(|t| match &t.r#type {
-                Some(task_type) => types.contains(task_type),
-                None => false,
-            });
-        }
-        if let Some(ref tag_matcher) = filter.tags {
-            tasks.retain(|t| {
-                let task_tags = t.tags.as_deref().unwrap_or(&[]);
-                // all: every tag in the filter must be present
-                if let Some(ref all) = tag_matcher.all {
-                    if !all.iter().all(|tag| task_tags.contains(tag)) {
-                        return false;
-                    }
-                }
-                // any: at least one tag must be present
-                if let Some(ref any) = tag_matcher.any {
-                    if !any.iter().any(|tag| task_tags.contains(tag)) {
-                        return false;
-                    }
-                }
-                // none: no tag in the filter should be present
-                if let Some(ref none) = tag_matcher.none {
-                    if none.iter().any(|tag| task_tags.contains(tag)) {
-                        return false;
-                    }
-                }
-                true
-            });
-        }
-        if let Some(ref assign_modes) = filter.assign_mode {
-            tasks.retain(|t| match &t.assign_mode {
-                Some(mode) => assign_modes.contains(mode),
-                None => false,
-            });
-        }
-        if let Some(ref exclude_ids) = filter.exclude_task_ids {
-            tasks.retain(|t| !exclude_ids.contains(&t.id));
+        let mut tasks: Vec<Task> = Vec::new();
+        for (task_id, json) in task_ids.iter().zip(raw) {
+            let Some(json) = json else { continue };
+            if let Some(task) = self.integrity.task(decode_task(task_id, &json))? {
+                tasks.push(task);
+            }
         }
+
+        tasks.retain(|t| task_matches(t, &filter));
         if let Some(limit) = filter.limit {
             tasks.truncate(limit as usize);
         }
@@ -429,6 +1219,58 @@ impl ShortTermStore for RedisShortTermStore {
         Ok(tasks)
     }
 
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        let tasks_set_key = self.keys.tasks_set();
+        let mut conn = self.conn.clone();
+        let by_position = |a: &Task, b: &Task| task_order(a.created_at, &a.id, b.created_at, &b.id);
+
+        // The set has no order, so every id is scanned, a batch at a time,
+        // keeping only the page and the task after it. SSCAN may repeat an
+        // id; repeats sort next to each other and are dropped.
+        let mut page: Vec<Task> = Vec::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, task_ids): (u64, Vec<String>) = redis::cmd("SSCAN")
+                .arg(&tasks_set_key)
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(TASK_SCAN_BATCH)
+                .query_async(&mut conn)
+                .await
+                .map_err(store_error)?;
+            if !task_ids.is_empty() {
+                let task_keys: Vec<String> = task_ids.iter().map(|id| self.keys.task(id)).collect();
+                let raw: Vec<Option<String>> = conn.mget(&task_keys).await.map_err(store_error)?;
+                for (task_id, json) in task_ids.iter().zip(raw) {
+                    let Some(json) = json else { continue };
+                    let Some(task) = self.integrity.task(decode_task(task_id, &json))? else {
+                        continue;
+                    };
+                    if after.as_ref().is_none_or(|c| c.precedes(&task)) && task_matches(&task, &filter) {
+                        page.push(task);
+                    }
+                }
+                if page.len() > limit + 1 {
+                    page.sort_by(by_position);
+                    page.dedup_by(|a, b| a.id == b.id);
+                    page.truncate(limit + 1);
+                }
+            }
+            cursor = next;
+            if cursor == 0 {
+                break;
+            }
+        }
+        page.sort_by(by_position);
+        page.dedup_by(|a, b| a.id == b.id);
+        Ok(TaskPage::from_sorted(page, limit))
+    }
+
     // ─── Worker state ────────────────────────────────────────────────────
 
     async fn save_worker(
@@ -439,8 +1281,12 @@ impl ShortTermStore for RedisShortTermStore {
         let workers_set_key = self.keys.workers_set();
         let json = serde_json::to_string(&worker)?;
         let mut conn = self.conn.clone();
-        conn.set::<_, _, ()>(&key, &json).await?;
-        conn.sadd::<_, _, ()>(&workers_set_key, &worker.id).await?;
+        conn.set::<_, _, ()>(&key, &json)
+            .await
+            .map_err(store_error)?;
+        conn.sadd::<_, _, ()>(&workers_set_key, &worker.id)
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -450,7 +1296,7 @@ impl ShortTermStore for RedisShortTermStore {
     ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.worker(worker_id);
         let mut conn = self.conn.clone();
-        let result: Option<String> = conn.get(&key).await?;
+        let result: Option<String> = conn.get(&key).await.map_err(store_error)?;
         match result {
             Some(json) => Ok(Some(serde_json::from_str(&json)?)),
             None => Ok(None),
@@ -464,13 +1310,13 @@ impl ShortTermStore for RedisShortTermStore {
         let workers_set_key = self.keys.workers_set();
         let mut conn = self.conn.clone();
 
-        let worker_ids: Vec<String> = conn.smembers(&workers_set_key).await?;
+        let worker_ids: Vec<String> = conn.smembers(&workers_set_key).await.map_err(store_error)?;
         if worker_ids.is_empty() {
             return Ok(Vec::new());
         }
 
         let worker_keys: Vec<String> = worker_ids.iter().map(|id| self.keys.worker(id)).collect();
-        let raw: Vec<Option<String>> = conn.mget(&worker_keys).await?;
+        let raw: Vec<Option<String>> = conn.mget(&worker_keys).await.map_err(store_error)?;
 
         let mut workers: Vec<Worker> = raw
             .into_iter()
@@ -497,8 +1343,10 @@ impl ShortTermStore for RedisShortTermStore {
         let key = self.keys.worker(worker_id);
         let workers_set_key = self.keys.workers_set();
         let mut conn = self.conn.clone();
-        conn.del::<_, ()>(&key).await?;
-        conn.srem::<_, _, ()>(&workers_set_key, worker_id).await?;
+        conn.del::<_, ()>(&key).await.map_err(store_error)?;
+        conn.srem::<_, _, ()>(&workers_set_key, worker_id)
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -516,7 +1364,9 @@ impl ShortTermStore for RedisShortTermStore {
         let lua = r#"
             local taskJson = redis.call('GET', KEYS[1])
             if not taskJson then return 0 end
-            local task = cjson.decode(taskJson)
+            local value = cjson.decode(taskJson)
+            local task = value
+            if value.v ~= nil and type(value.d) == 'table' then task = value.d end
             if task.status ~= 'pending' and task.status ~= 'assigned' then return 0 end
 
             local workerJson = redis.call('GET', KEYS[2])
@@ -532,7 +1382,8 @@ impl ShortTermStore for RedisShortTermStore {
             task.assignedWorker = ARGV[2]
             task.cost = cost
             task.updatedAt = tonumber(ARGV[3])
-            redis.call('SET', KEYS[1], cjson.encode(task))
+            task.version = (task.version or 0) + 1
+            redis.call('SET', KEYS[1], cjson.encode(value))
 
             return 1
         "#;
@@ -551,7 +1402,8 @@ impl ShortTermStore for RedisShortTermStore {
             .arg(worker_id)
             .arg(timestamp_ms)
             .invoke_async(&mut conn)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
         Ok(result == 1)
     }
@@ -566,9 +1418,12 @@ impl ShortTermStore for RedisShortTermStore {
         let worker_assignments_key = self.keys.worker_assignments(&assignment.worker_id);
         let json = serde_json::to_string(&assignment)?;
         let mut conn = self.conn.clone();
-        conn.set::<_, _, ()>(&assignment_key, &json).await?;
+        conn.set::<_, _, ()>(&assignment_key, &json)
+            .await
+            .map_err(store_error)?;
         conn.sadd::<_, _, ()>(&worker_assignments_key, &assignment.task_id)
-            .await?;
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -580,15 +1435,18 @@ impl ShortTermStore for RedisShortTermStore {
         let mut conn = self.conn.clone();
 
         // First, get the assignment to find the worker ID
-        let result: Option<String> = conn.get(&assignment_key).await?;
+        let result: Option<String> = conn.get(&assignment_key).await.map_err(store_error)?;
         if let Some(json) = result {
             let assignment: WorkerAssignment = serde_json::from_str(&json)?;
             let worker_assignments_key = self.keys.worker_assignments(&assignment.worker_id);
             conn.srem::<_, _, ()>(&worker_assignments_key, task_id)
-                .await?;
+                .await
+                .map_err(store_error)?;
         }
 
-        conn.del::<_, ()>(&assignment_key).await?;
+        conn.del::<_, ()>(&assignment_key)
+            .await
+            .map_err(store_error)?;
         Ok(())
     }
 
@@ -599,14 +1457,17 @@ impl ShortTermStore for RedisShortTermStore {
         let worker_assignments_key = self.keys.worker_assignments(worker_id);
         let mut conn = self.conn.clone();
 
-        let task_ids: Vec<String> = conn.smembers(&worker_assignments_key).await?;
+        let task_ids: Vec<String> = conn
+            .smembers(&worker_assignments_key)
+            .await
+            .map_err(store_error)?;
         if task_ids.is_empty() {
             return Ok(Vec::new());
         }
 
         let assignment_keys: Vec<String> =
             task_ids.iter().map(|id| self.keys.assignment(id)).collect();
-        let raw: Vec<Option<String>> = conn.mget(&assignment_keys).await?;
+        let raw: Vec<Option<String>> = conn.mget(&assignment_keys).await.map_err(store_error)?;
 
         let assignments: Vec<WorkerAssignment> = raw
             .into_iter()
@@ -622,12 +1483,565 @@ impl ShortTermStore for RedisShortTermStore {
     ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
         let key = self.keys.assignment(task_id);
         let mut conn = self.conn.clone();
-        let result: Option<String> = conn.get(&key).await?;
+        let result: Option<String> = conn.get(&key).await.map_err(store_error)?;
         match result {
             Some(json) => Ok(Some(serde_json::from_str(&json)?)),
             None => Ok(None),
         }
     }
+
+    async fn save_retry_schedule(
+        &self,
+        schedule: RetrySchedule,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let json = serde_json::to_string(&schedule)?;
+        let mut conn = self.conn.clone();
+        redis::pipe()
+            .atomic()
+            .set(self.keys.retry(&schedule.task_id), &json)
+            .zadd(self.keys.retries(), &schedule.task_id, schedule.due_at)
+            .del(self.keys.retry_claim(&schedule.task_id))
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn list_due_retries(
+        &self,
+        now: f64,
+    ) -> Result<Vec<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let task_ids: Vec<String> = conn
+            .zrangebyscore(self.keys.retries(), "-inf", now)
+            .await
+            .map_err(store_error)?;
+        if task_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let retry_keys: Vec<String> = task_ids.iter().map(|id| self.keys.retry(id)).collect();
+        let raw: Vec<Option<String>> = conn.mget(&retry_keys).await.map_err(store_error)?;
+
+        Ok(raw
+            .into_iter()
+            .filter_map(|opt| opt.and_then(|s| serde_json::from_str(&s).ok()))
+            .collect())
+    }
+
+    async fn get_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let result: Option<String> = conn
+            .get(self.keys.retry(task_id))
+            .await
+            .map_err(store_error)?;
+        match result {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn claim_retry(
+        &self,
+        task_id: &str,
+        _now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let exists: bool = conn
+            .exists(self.keys.retry(task_id))
+            .await
+            .map_err(store_error)?;
+        if !exists {
+            return Ok(false);
+        }
+        // The lease runs on the Redis clock, so instances with skewed
+        // clocks still agree on when it expires.
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(self.keys.retry_claim(task_id))
+            .arg(ulid::Ulid::new().to_string())
+            .arg("NX")
+            .arg("PX")
+            .arg(lease_ms.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(claimed.is_some())
+    }
+
+    async fn delete_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        redis::pipe()
+            .atomic()
+            .del(self.keys.retry(task_id))
+            .zrem(self.keys.retries(), task_id)
+            .del(self.keys.retry_claim(task_id))
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn save_activation(
+        &self,
+        task_id: &str,
+        at: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        redis::pipe()
+            .atomic()
+            .zadd(self.keys.activations(), task_id, at)
+            .del(self.keys.activation_claim(task_id))
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn list_due_activations(
+        &self,
+        now: f64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let task_ids: Vec<String> = conn
+            .zrangebyscore(self.keys.activations(), "-inf", now)
+            .await
+            .map_err(store_error)?;
+        Ok(task_ids)
+    }
+
+    async fn claim_activation(
+        &self,
+        task_id: &str,
+        _now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let score: Option<f64> = conn
+            .zscore(self.keys.activations(), task_id)
+            .await
+            .map_err(store_error)?;
+        if score.is_none() {
+            return Ok(false);
+        }
+        // As with retries, the lease runs on the Redis clock.
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(self.keys.activation_claim(task_id))
+            .arg(ulid::Ulid::new().to_string())
+            .arg("NX")
+            .arg("PX")
+            .arg(lease_ms.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(claimed.is_some())
+    }
+
+    async fn delete_activation(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        redis::pipe()
+            .atomic()
+            .zrem(self.keys.activations(), task_id)
+            .del(self.keys.activation_claim(task_id))
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn claim_filtered_index(
+        &self,
+        task_id: &str,
+        consumer: &str,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> Result<Option<FilteredIndexClaim>, Box<dyn std::error::Error + Send + Sync>> {
+        // Mirrors `FilteredIndexMark::claim` in one script so concurrent
+        // deliveries never share an index. The hash inherits the task's TTL.
+        let lua = r#"
+            local epoch, nxt, through = 0, 0, -1
+            local raw = redis.call('HGET', KEYS[1], ARGV[1])
+            if raw then
+              local mark = cjson.decode(raw)
+              if mark.filterHash == ARGV[2] then
+                epoch, nxt = mark.epoch, mark.next
+                if mark.through ~= cjson.null then through = mark.through end
+              else
+                epoch = mark.epoch + 1
+              end
+            end
+            through = math.max(through, tonumber(ARGV[3]))
+            redis.call('HSET', KEYS[1], ARGV[1], cjson.encode({
+              filterHash = ARGV[2], epoch = epoch, next = nxt + 1, through = through
+            }))
+            local ttl = redis.call('PTTL', KEYS[2])
+            if ttl > 0 then
+              redis.call('PEXPIRE', KEYS[1], ttl)
+            end
+            return {nxt, epoch}
+        "#;
+        let mut conn = self.conn.clone();
+        let (filtered_index, epoch): (u64, u64) = redis::Script::new(lua)
+            .key(self.keys.filtered_indices(task_id))
+            .key(self.keys.task(task_id))
+            .arg(consumer)
+            .arg(filter_hash)
+            .arg(raw_index)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(Some(FilteredIndexClaim {
+            filtered_index,
+            epoch,
+        }))
+    }
+
+    async fn get_filtered_index_mark(
+        &self,
+        task_id: &str,
+        consumer: &str,
+    ) -> Result<Option<FilteredIndexMark>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let raw: Option<String> = conn
+            .hget(self.keys.filtered_indices(task_id), consumer)
+            .await
+            .map_err(store_error)?;
+        raw.map(|raw| serde_json::from_str(&raw).map_err(Into::into))
+            .transpose()
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        fingerprint: &str,
+        now: f64,
+        window_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Check-and-record in one script so concurrent publishers of the
+        // same event deliver it once. The hash inherits the task's TTL.
+        let lua = r#"
+            local last = redis.call('HGET', KEYS[1], ARGV[1])
+            if last and tonumber(ARGV[2]) - tonumber(last) < tonumber(ARGV[3]) then
+              return 0
+            end
+            redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+            local ttl = redis.call('PTTL', KEYS[2])
+            if ttl > 0 then
+              redis.call('PEXPIRE', KEYS[1], ttl)
+            end
+            return 1
+        "#;
+        let mut conn = self.conn.clone();
+        let claimed: i64 = redis::Script::new(lua)
+            .key(self.keys.webhook_deliveries(task_id))
+            .key(self.keys.task(task_id))
+            .arg(format!("{webhook}:{fingerprint}"))
+            .arg(now)
+            .arg(window_ms)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(claimed == 1)
+    }
+
+    async fn claim_deadline_warning(
+        &self,
+        task_id: &str,
+        warning: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(self.keys.deadline_warning(task_id, warning))
+            .arg("1")
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(claimed.is_some())
+    }
+
+    async fn record_outcome(
+        &self,
+        outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let json = serde_json::to_string(&outcome)?;
+        let mut conn = self.conn.clone();
+        conn.zadd::<_, _, _, ()>(self.keys.outcomes(), json, outcome.completed_at)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn list_outcomes(
+        &self,
+        query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+        // Members with equal scores sort by their JSON, which starts with
+        // the task id, so the set's order is the index's order. Filters other
+        // than time are applied here, a batch at a time.
+        let min = query
+            .since
+            .into_iter()
+            .chain(query.after.as_ref().map(|after| after.completed_at))
+            .fold(f64::NEG_INFINITY, f64::max);
+        let mut conn = self.conn.clone();
+        let mut matched = Vec::new();
+        let mut offset = 0;
+        while matched.len() < query.limit {
+            let batch: Vec<String> = conn
+                .zrangebyscore_limit(
+                    self.keys.outcomes(),
+                    min,
+                    "+inf",
+                    offset,
+                    OUTCOME_SCAN_BATCH,
+                )
+                .await
+                .map_err(store_error)?;
+            for json in &batch {
+                let outcome: TaskOutcome = serde_json::from_str(json)?;
+                if query.matches(&outcome) && matched.len() < query.limit {
+                    matched.push(outcome);
+                }
+            }
+            if (batch.len() as isize) < OUTCOME_SCAN_BATCH {
+                break;
+            }
+            offset += OUTCOME_SCAN_BATCH;
+        }
+        Ok(matched)
+    }
+
+    async fn trim_outcomes(
+        &self,
+        before: f64,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let removed: u64 = conn
+            .zrembyscore(self.keys.outcomes(), "-inf", format!("({before}"))
+            .await
+            .map_err(store_error)?;
+        Ok(removed)
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.remove_assignment(task_id).await?;
+        self.delete_retry_schedule(task_id).await?;
+        self.delete_activation(task_id).await?;
+
+        let mut conn = self.conn.clone();
+        let series_ids_key = self.keys.series_ids(task_id);
+        let series_ids: Vec<String> = conn.smembers(&series_ids_key).await.map_err(store_error)?;
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .del(self.keys.task(task_id))
+            .del(self.keys.events(task_id))
+            .del(self.keys.idx(task_id))
+            .del(self.keys.type_counts(task_id))
+            .del(&series_ids_key)
+            .del(self.keys.webhook_deliveries(task_id))
+            .del(self.keys.filtered_indices(task_id))
+            .srem(self.keys.tasks_set(), task_id);
+        for sid in &series_ids {
+            pipe.del(self.keys.series_latest(task_id, sid));
+        }
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let events_key = self.keys.events(task_id);
+        let mut conn = self.conn.clone();
+        let raw: Vec<Vec<u8>> = conn.lrange(&events_key, 0, -1).await.map_err(store_error)?;
+
+        let mut replaced = Vec::new();
+        let mut added = Vec::new();
+        for (i, item) in raw.iter().enumerate() {
+            let Ok(event) = decode_event(task_id, i as u64, item) else {
+                continue;
+            };
+            let Some(tombstone) = tombstones.iter().find(|t| t.id == event.id) else {
+                continue;
+            };
+            conn.lset::<_, _, ()>(&events_key, i as isize, self.pack_event(tombstone)?)
+                .await
+                .map_err(store_error)?;
+            replaced.push(event);
+            added.push(tombstone);
+        }
+
+        let removed: Vec<&TaskEvent> = replaced.iter().collect();
+        self.adjust_type_counts(task_id, &removed, &added).await?;
+        let released: Vec<String> = replaced
+            .iter()
+            .filter_map(|event| blob_ref_hash(&event.data).map(str::to_string))
+            .collect();
+        self.release_blobs(&released).await?;
+        self.drop_series_state(task_id, &replaced).await?;
+        Ok(replaced.len() as u64)
+    }
+}
+
+#[async_trait]
+impl EventConventionStore for RedisShortTermStore {
+    async fn record_event_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Counts add up; the last sighting and example are only replaced by
+        // newer ones, so instances can flush in any order.
+        let lua = r#"
+            redis.call('SADD', KEYS[1], ARGV[1])
+            local e = ARGV[2]
+            redis.call('HINCRBY', KEYS[2], 'count:' .. e, ARGV[3])
+            if ARGV[4] ~= '' then
+              local seen = tonumber(redis.call('HGET', KEYS[2], 'seen:' .. e))
+              if not seen or tonumber(ARGV[4]) >= seen then
+                redis.call('HSET', KEYS[2], 'seen:' .. e, ARGV[4])
+              end
+            end
+            if ARGV[6] ~= '' then
+              local at = tonumber(redis.call('HGET', KEYS[2], 'exampleAt:' .. e))
+              if not at or tonumber(ARGV[6]) >= at then
+                redis.call('HSET', KEYS[2], 'exampleAt:' .. e, ARGV[6], 'example:' .. e, ARGV[5])
+              end
+            end
+            return 1
+        "#;
+        let script = redis::Script::new(lua);
+        let mut conn = self.conn.clone();
+        for sample in observed {
+            let time = |at: Option<f64>| at.map(|at| at.to_string()).unwrap_or_default();
+            let example = match (&sample.example, sample.example_at) {
+                (Some(example), Some(_)) => serde_json::to_string(example)?,
+                _ => String::new(),
+            };
+            let example_at = if example.is_empty() {
+                String::new()
+            } else {
+                time(sample.example_at)
+            };
+            script
+                .key(self.keys.convention_task_types())
+                .key(self.keys.conventions(&sample.task_type))
+                .arg(&sample.task_type)
+                .arg(&sample.event_type)
+                .arg(sample.estimated_count)
+                .arg(time(sample.last_seen_at))
+                .arg(example)
+                .arg(example_at)
+                .invoke_async::<i64>(&mut conn)
+                .await
+                .map_err(store_error)?;
+        }
+        Ok(())
+    }
+
+    async fn annotate_event_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let key = self.keys.conventions(task_type);
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .sadd(self.keys.convention_task_types(), task_type)
+            .ignore()
+            .hset_nx(&key, format!("count:{event_type}"), 0)
+            .ignore();
+        match notes.description {
+            Some(ref description) => {
+                pipe.hset(&key, format!("description:{event_type}"), description)
+            }
+            None => pipe.hdel(&key, format!("description:{event_type}")),
+        }
+        .ignore();
+        match notes.schema {
+            Some(ref schema) => pipe.hset(
+                &key,
+                format!("schema:{event_type}"),
+                serde_json::to_string(schema)?,
+            ),
+            None => pipe.hdel(&key, format!("schema:{event_type}")),
+        }
+        .ignore();
+        let mut conn = self.conn.clone();
+        pipe.query_async::<()>(&mut conn)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn list_event_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut conn = self.conn.clone();
+        let task_types: Vec<String> = match task_type {
+            Some(task_type) => vec![task_type.to_string()],
+            None => conn
+                .smembers(self.keys.convention_task_types())
+                .await
+                .map_err(store_error)?,
+        };
+        let mut conventions = std::collections::BTreeMap::new();
+        for task_type in task_types {
+            let fields: HashMap<String, String> = conn
+                .hgetall(self.keys.conventions(&task_type))
+                .await
+                .map_err(store_error)?;
+            for (field, value) in fields {
+                let Some((attribute, event_type)) = field.split_once(':') else {
+                    continue;
+                };
+                let convention = conventions
+                    .entry((task_type.clone(), event_type.to_string()))
+                    .or_insert_with(|| EventConvention {
+                        task_type: task_type.clone(),
+                        event_type: event_type.to_string(),
+                        estimated_count: 0,
+                        last_seen_at: None,
+                        example: None,
+                        example_at: None,
+                        description: None,
+                        schema: None,
+                    });
+                match attribute {
+                    "count" => convention.estimated_count = value.parse().unwrap_or(0),
+                    "seen" => convention.last_seen_at = value.parse().ok(),
+                    "example" => convention.example = Some(serde_json::from_str(&value)?),
+                    "exampleAt" => convention.example_at = value.parse().ok(),
+                    "description" => convention.description = Some(value),
+                    "schema" => convention.schema = Some(serde_json::from_str(&value)?),
+                    _ => {}
+                }
+            }
+        }
+        Ok(conventions.into_values().collect())
+    }
 }
 
 #[cfg(test)]
@@ -640,10 +2054,8 @@ mod tests {
         assert_eq!(keys.task("t1"), "taskcast:task:t1");
         assert_eq!(keys.events("t1"), "taskcast:events:t1");
         assert_eq!(keys.idx("t1"), "taskcast:idx:t1");
-        assert_eq!(
-            keys.series_latest("t1", "s1"),
-            "taskcast:series:t1:s1"
-        );
+        assert_eq!(keys.type_counts("t1"), "taskcast:typeCounts:t1");
+        assert_eq!(keys.series_latest("t1", "s1"), "taskcast:series:t1:s1");
         assert_eq!(keys.series_ids("t1"), "taskcast:seriesIds:t1");
     }
 
@@ -678,12 +2090,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn key_generation_conventions() {
+        let keys = Keys::new("taskcast");
+        assert_eq!(keys.convention_task_types(), "taskcast:conventionTaskTypes");
+        assert_eq!(
+            keys.conventions("llm:chat"),
+            "taskcast:conventions:llm:chat"
+        );
+    }
+
     #[test]
     fn key_generation_tasks_set() {
         let keys = Keys::new("taskcast");
         assert_eq!(keys.tasks_set(), "taskcast:tasks");
     }
 
+    #[test]
+    fn key_generation_blob() {
+        let keys = Keys::new("myapp");
+        assert_eq!(keys.blob("abc123"), "myapp:blob:abc123");
+    }
+
     #[test]
     fn key_generation_worker() {
         let keys = Keys::new("taskcast");
@@ -723,4 +2151,27 @@ mod tests {
         );
         assert_eq!(keys.tasks_set(), "myapp:tasks");
     }
+
+    #[test]
+    fn key_generation_retries() {
+        let keys = Keys::new("taskcast");
+        assert_eq!(keys.retries(), "taskcast:retries");
+        assert_eq!(keys.retry("t1"), "taskcast:retry:t1");
+        assert_eq!(keys.retry_claim("t1"), "taskcast:retryClaim:t1");
+        assert_eq!(
+            keys.webhook_deliveries("t1"),
+            "taskcast:webhookDeliveries:t1"
+        );
+        assert_eq!(keys.filtered_indices("t1"), "taskcast:filteredIndices:t1");
+    }
+
+    #[test]
+    fn key_generation_activations() {
+        let keys = Keys::new("taskcast");
+        assert_eq!(keys.activations(), "taskcast:activations");
+        assert_eq!(
+            keys.activation_claim("t1"),
+            "taskcast:activationClaim:t1"
+        );
+    }
 }
@@ -0,0 +1,243 @@
+//! Pattern subscriptions on the Redis broadcast provider (via testcontainers).
+//!
+//! Redis matches `PSUBSCRIBE` patterns itself while the memory provider
+//! matches them locally; these tests check the two agree, and that engines
+//! sharing Redis see each other's events through pattern subscriptions.
+//!
+//! Run with: `cargo test -p taskcast-redis --test broadcast_patterns`
+//! Set `TASKCAST_TEST_SKIP_DOCKER=1` to skip them without Docker.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use taskcast_core::{
+    channel_matches, BroadcastChannels, BroadcastProvider, CreateTaskInput, Level,
+    MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput, TaskEngine,
+    TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use taskcast_test_backends::{make_redis_broadcast, unique_prefix};
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+const CHANNELS: &[&str] = &[
+    "task.t1",
+    "task.t2",
+    "task.T1",
+    "task.a1b",
+    "task.*",
+    "task.[x]",
+    "type.llm",
+    "type.log",
+    "legacy-id",
+];
+
+const PATTERNS: &[&str] = &[
+    "*",
+    "task.*",
+    "task.t?",
+    "t*",
+    "task.[ab]*",
+    "task.[^t]*",
+    "task.t[0-9]",
+    "type.l[a-m]*",
+    r"task.\*",
+    r"task.\[x\]",
+    "legacy-*",
+];
+
+const SENTINEL: &str = "sentinel";
+
+fn make_test_event(id: &str) -> TaskEvent {
+    TaskEvent {
+        id: id.to_string(),
+        task_id: id.to_string(),
+        index: 0,
+        timestamp: 0.0,
+        r#type: "test".to_string(),
+        level: Level::Info,
+        data: serde_json::json!(null),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// Subscribes every pattern in [`PATTERNS`], publishes one event to each of
+/// [`CHANNELS`] (its id is the channel name) and returns the channels each
+/// pattern received.
+async fn received_by_pattern(provider: &dyn BroadcastProvider) -> BTreeMap<String, Vec<String>> {
+    let received: Arc<Mutex<BTreeMap<String, Vec<String>>>> = Arc::default();
+    let mut unsubs = Vec::new();
+    for pattern in PATTERNS {
+        let received = Arc::clone(&received);
+        let key = pattern.to_string();
+        received.lock().unwrap().insert(key.clone(), Vec::new());
+        let unsub = provider
+            .subscribe_pattern(
+                pattern,
+                Box::new(move |event| {
+                    received
+                        .lock()
+                        .unwrap()
+                        .get_mut(&key)
+                        .unwrap()
+                        .push(event.id);
+                }),
+            )
+            .await
+            .unwrap();
+        unsubs.push(unsub);
+    }
+    // Messages reach a subscriber in publish order, so once the sentinel
+    // arrives everything published before it has.
+    let done = Arc::new(tokio::sync::Notify::new());
+    let notify = Arc::clone(&done);
+    let sentinel_unsub = provider
+        .subscribe(SENTINEL, Box::new(move |_| notify.notify_one()))
+        .await;
+
+    for channel in CHANNELS {
+        provider
+            .publish(channel, make_test_event(channel))
+            .await
+            .unwrap();
+    }
+    provider
+        .publish(SENTINEL, make_test_event(SENTINEL))
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), done.notified())
+        .await
+        .expect("sentinel was not delivered");
+
+    for unsub in unsubs {
+        unsub();
+    }
+    sentinel_unsub();
+    let mut received = received.lock().unwrap().clone();
+    for channels in received.values_mut() {
+        channels.retain(|channel| channel != SENTINEL);
+        channels.sort();
+    }
+    received
+}
+
+fn make_engine(broadcast: Arc<dyn BroadcastProvider>, channels: BroadcastChannels) -> TaskEngine {
+    TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast,
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_broadcast_channels(channels)
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn redis_and_memory_patterns_match_the_same_channels() {
+    let Some(redis) = make_redis_broadcast(&unique_prefix("patterns")).await else {
+        return;
+    };
+
+    let expected: BTreeMap<String, Vec<String>> = PATTERNS
+        .iter()
+        .map(|pattern| {
+            let mut channels: Vec<String> = CHANNELS
+                .iter()
+                .filter(|channel| channel_matches(pattern, channel))
+                .map(|channel| channel.to_string())
+                .collect();
+            channels.sort();
+            (pattern.to_string(), channels)
+        })
+        .collect();
+
+    let memory = MemoryBroadcastProvider::new();
+    assert_eq!(received_by_pattern(&memory).await, expected);
+    assert_eq!(received_by_pattern(&redis).await, expected);
+}
+
+#[tokio::test]
+async fn pattern_subscriber_receives_other_instances_tasks_once() {
+    let prefix = unique_prefix("patterns-engine");
+    let (Some(broadcast_a), Some(broadcast_b)) = (
+        make_redis_broadcast(&prefix).await,
+        make_redis_broadcast(&prefix).await,
+    ) else {
+        return;
+    };
+    let channels = BroadcastChannels {
+        type_channels: true,
+        legacy_channels: true,
+    };
+    let publisher = make_engine(Arc::new(broadcast_a), channels);
+    let subscriber = make_engine(Arc::new(broadcast_b), channels);
+    // Allow the listener's own PSUBSCRIBE to complete.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // `t*` matches both the `task.` and `type.` channel of every event.
+    let received: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+    let sink = Arc::clone(&received);
+    let _unsub = subscriber
+        .subscribe_pattern(
+            "t*",
+            Box::new(move |event| {
+                sink.lock().unwrap().push((event.task_id, event.r#type));
+            }),
+        )
+        .await
+        .unwrap();
+
+    for id in ["t1", "t2"] {
+        publisher
+            .create_task(CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        publisher
+            .transition_task(id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+        publisher
+            .publish_event(
+                id,
+                PublishEventInput {
+                    r#type: "llm.delta".to_string(),
+                    level: Level::Info,
+                    data: serde_json::json!(null),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let received = received.lock().unwrap();
+    let expected: Vec<(String, String)> = ["t1", "t2"]
+        .iter()
+        .flat_map(|id| {
+            [
+                (id.to_string(), "taskcast:status".to_string()),
+                (id.to_string(), "llm.delta".to_string()),
+            ]
+        })
+        .collect();
+    assert_eq!(*received, expected);
+}
@@ -0,0 +1,216 @@
+//! Tests for compression of stored events in `RedisShortTermStore`.
+//!
+//! The codec tests run anywhere. The store tests share one Redis container
+//! and keep apart through key prefixes; set `TASKCAST_TEST_SKIP_DOCKER=1` to
+//! skip them without Docker.
+//!
+//! Run with: `cargo test -p taskcast-redis --test compression`
+
+use redis::AsyncCommands;
+use serde_json::{json, Value};
+use taskcast_core::compression::{CompressionAlgorithm, ValueCompressionConfig};
+use taskcast_core::types::{Level, ShortTermStore, TaskEvent};
+use taskcast_core::CorruptRecordKind;
+use taskcast_redis::codec::{
+    compress_value, decode_event, decompress_value, encode_value, LZ4_PREFIX, VALUE_FORMAT_VERSION,
+    ZSTD_PREFIX,
+};
+use taskcast_redis::RedisShortTermStore;
+use taskcast_test_backends::{redis_url, unique_prefix};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+const MIN_BYTES: usize = 256;
+
+fn config(algorithm: CompressionAlgorithm) -> ValueCompressionConfig {
+    ValueCompressionConfig {
+        algorithm,
+        min_bytes: MIN_BYTES,
+    }
+}
+
+fn make_event(task_id: &str, index: u64, data: Value) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "llm.transcript".to_string(),
+        level: Level::Info,
+        data,
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// A transcript heavy in multi-byte characters, long enough to compress.
+fn unicode_transcript() -> Value {
+    json!({
+        "messages": (0..20).map(|i| json!({
+            "role": if i % 2 == 0 { "user" } else { "assistant" },
+            "content": format!("第{i}轮：你好，世界 🌍🚀 — Grüße, naïve café, Ωμέγα, \u{200d}👩‍💻 {}", "ß".repeat(i)),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn encoded(event: &TaskEvent) -> String {
+    encode_value(event, VALUE_FORMAT_VERSION).unwrap()
+}
+
+async fn connect() -> Option<redis::aio::MultiplexedConnection> {
+    let client = redis::Client::open(redis_url().await?).unwrap();
+    Some(client.get_multiplexed_async_connection().await.unwrap())
+}
+
+// ── Codec ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn values_below_the_threshold_are_stored_plain() {
+    let small = encoded(&make_event("t1", 0, json!({ "ok": true })));
+    assert!(small.len() < MIN_BYTES);
+    for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+        assert_eq!(
+            compress_value(small.clone(), &config(algorithm)),
+            small.as_bytes()
+        );
+    }
+}
+
+#[test]
+fn values_that_do_not_shrink_are_stored_plain() {
+    // Past a 1-byte threshold, but shorter than any compressed form.
+    let value = r#"{"v":2,"d":{}}"#.to_string();
+    for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+        let config = ValueCompressionConfig {
+            algorithm,
+            min_bytes: 1,
+        };
+        let stored = compress_value(value.clone(), &config);
+        assert_eq!(stored, value.as_bytes());
+        assert_eq!(decompress_value(&stored).unwrap(), value);
+    }
+}
+
+#[test]
+fn unicode_payloads_round_trip_through_each_algorithm() {
+    let event = make_event("t1", 3, unicode_transcript());
+    let value = encoded(&event);
+    for (algorithm, prefix) in [
+        (CompressionAlgorithm::Zstd, ZSTD_PREFIX),
+        (CompressionAlgorithm::Lz4, LZ4_PREFIX),
+    ] {
+        let stored = compress_value(value.clone(), &config(algorithm));
+        assert_eq!(stored[0], prefix);
+        assert!(stored.len() * 2 < value.len(), "{algorithm:?}");
+        assert_eq!(decode_event("t1", 0, &stored).unwrap(), event);
+    }
+}
+
+#[test]
+fn plain_and_compressed_values_decode_alike() {
+    let event = make_event("t1", 1, unicode_transcript());
+    let plain = encoded(&event);
+    let zstd = compress_value(plain.clone(), &config(CompressionAlgorithm::Zstd));
+    let lz4 = compress_value(plain.clone(), &config(CompressionAlgorithm::Lz4));
+    for stored in [plain.as_bytes(), &zstd, &lz4] {
+        assert_eq!(decode_event("t1", 0, stored).unwrap(), event);
+    }
+}
+
+#[test]
+fn damaged_compressed_values_are_corrupt_records() {
+    let value = encoded(&make_event("t1", 0, unicode_transcript()));
+    let mut stored = compress_value(value, &config(CompressionAlgorithm::Zstd));
+    stored.truncate(stored.len() / 2);
+    let err = decode_event("t1", 7, &stored).unwrap_err();
+    assert_eq!(err.kind, CorruptRecordKind::Event);
+    assert_eq!(err.position, Some(7));
+    assert!(err.reason.contains("zstd"), "{}", err.reason);
+}
+
+// ── Store ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn compression_can_be_turned_on_and_off_over_existing_events() {
+    let Some(conn) = connect().await else { return };
+    let prefix = unique_prefix("compression");
+    let plain = RedisShortTermStore::new(conn.clone(), Some(&prefix));
+    let zstd = RedisShortTermStore::new(conn.clone(), Some(&prefix))
+        .with_compression(config(CompressionAlgorithm::Zstd));
+    let lz4 = RedisShortTermStore::new(conn.clone(), Some(&prefix))
+        .with_compression(config(CompressionAlgorithm::Lz4));
+
+    let events: Vec<TaskEvent> = (0..4)
+        .map(|i| {
+            let data = if i == 3 {
+                json!({ "small": "é" })
+            } else {
+                unicode_transcript()
+            };
+            make_event("t1", i, data)
+        })
+        .collect();
+    plain.append_event("t1", events[0].clone()).await.unwrap();
+    zstd.append_event("t1", events[1].clone()).await.unwrap();
+    lz4.append_event("t1", events[2].clone()).await.unwrap();
+    zstd.append_event("t1", events[3].clone()).await.unwrap();
+
+    let mut conn = conn;
+    let raw: Vec<Vec<u8>> = conn
+        .lrange(format!("{prefix}:events:t1"), 0, -1)
+        .await
+        .unwrap();
+    assert_eq!(
+        raw.iter().map(|value| value[0]).collect::<Vec<_>>(),
+        vec![b'{', ZSTD_PREFIX, LZ4_PREFIX, b'{']
+    );
+    for store in [&plain, &zstd, &lz4] {
+        assert_eq!(store.get_events("t1", None).await.unwrap(), events);
+    }
+
+    assert_eq!(plain.compression_stats(), None);
+    let stats = zstd.compression_stats().unwrap();
+    assert_eq!(stats.algorithm, CompressionAlgorithm::Zstd);
+    assert_eq!((stats.values_written, stats.values_compressed), (2, 1));
+    assert!(stats.ratio > 2.0, "{stats:?}");
+}
+
+#[tokio::test]
+async fn compressed_events_can_be_deleted_and_redacted() {
+    let Some(conn) = connect().await else { return };
+    let prefix = unique_prefix("compression-delete");
+    let store = RedisShortTermStore::new(conn, Some(&prefix))
+        .with_compression(config(CompressionAlgorithm::Lz4));
+    for i in 0..3 {
+        store
+            .append_event("t1", make_event("t1", i, unicode_transcript()))
+            .await
+            .unwrap();
+    }
+
+    store
+        .delete_events("t1", &["evt-t1-1".to_string()])
+        .await
+        .unwrap();
+    let tombstone = make_event("t1", 2, json!({ "redacted": true }));
+    assert_eq!(
+        store
+            .redact_events("t1", std::slice::from_ref(&tombstone))
+            .await
+            .unwrap(),
+        1
+    );
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(
+        events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+        vec!["evt-t1-0", "evt-t1-2"]
+    );
+    assert_eq!(events[0].data, unicode_transcript());
+    assert_eq!(events[1], tombstone);
+}
@@ -4,15 +4,18 @@
 //! engine instances share the same Redis backend. They require Docker.
 //!
 //! Run with: `cargo test -p taskcast-redis --test concurrent`
-//! Skip if Docker unavailable: tests will fail with connection errors.
+//! The tests share one Redis container and keep apart through key prefixes;
+//! set `TASKCAST_TEST_SKIP_DOCKER=1` to skip them without Docker.
 
+use std::future::IntoFuture;
 use std::sync::Arc;
 
 use taskcast_core::{
     BroadcastProvider, CreateTaskInput, Level, MemoryBroadcastProvider, PublishEventInput,
     TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
 };
-use taskcast_redis::{RedisBroadcastProvider, RedisShortTermStore};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+use taskcast_test_backends::{make_redis_broadcast, make_redis_store, unique_prefix};
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
@@ -30,57 +33,38 @@ fn make_test_event(task_id: &str, event_type: &str) -> TaskEvent {
         series_mode: None,
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
 
-/// Open a fresh `RedisBroadcastProvider` against `redis_url`.
-async fn make_redis_broadcast(redis_url: &str) -> RedisBroadcastProvider {
-    let client = redis::Client::open(redis_url).unwrap();
-    let pub_conn = client.get_multiplexed_async_connection().await.unwrap();
-    let sub_conn = client.get_async_pubsub().await.unwrap();
-    RedisBroadcastProvider::new(pub_conn, sub_conn, Some("test"))
-}
-
-/// Create a Redis-backed engine using the given connection URL.
-async fn make_redis_engine(redis_url: &str) -> TaskEngine {
-    let client = redis::Client::open(redis_url).unwrap();
-    let conn = client.get_multiplexed_async_connection().await.unwrap();
-    let store = RedisShortTermStore::new(conn, Some("test"));
-    TaskEngine::new(TaskEngineOptions {
+/// Create a Redis-backed engine whose keys start with `prefix`.
+async fn make_redis_engine(prefix: &str) -> Option<TaskEngine> {
+    let store = make_redis_store(prefix).await?;
+    Some(TaskEngine::new(TaskEngineOptions {
         short_term_store: Arc::new(store),
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
-    })
-}
-
-/// Flush the test Redis instance.
-async fn flush_redis(redis_url: &str) {
-    let client = redis::Client::open(redis_url).unwrap();
-    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
-    redis::cmd("FLUSHALL")
-        .query_async::<()>(&mut conn)
-        .await
-        .unwrap();
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
 }
 
 #[tokio::test]
 async fn two_engine_instances_produce_no_duplicate_event_indices() {
     // This is the Rust equivalent of the TS regression test that found 37/60 index
     // collisions before nextIndex was moved to RedisShortTermStore with INCR.
-    let container = testcontainers::runners::AsyncRunner::start(
-        testcontainers_modules::redis::Redis::default(),
-    )
-    .await
-    .unwrap();
-    let port = container.get_host_port_ipv4(6379).await.unwrap();
-    let redis_url = format!("redis://127.0.0.1:{port}");
-
-    flush_redis(&redis_url).await;
-
-    let engine1 = Arc::new(make_redis_engine(&redis_url).await);
-    let engine2 = Arc::new(make_redis_engine(&redis_url).await);
+    let prefix = unique_prefix("concurrent");
+    let (Some(engine1), Some(engine2)) = (
+        make_redis_engine(&prefix).await,
+        make_redis_engine(&prefix).await,
+    ) else {
+        return;
+    };
+    let (engine1, engine2) = (Arc::new(engine1), Arc::new(engine2));
 
     // Create task via engine1
     let task = engine1
@@ -114,6 +98,9 @@ async fn two_engine_instances_produce_no_duplicate_event_indices() {
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -134,6 +121,9 @@ async fn two_engine_instances_produce_no_duplicate_event_indices() {
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -160,17 +150,11 @@ async fn two_engine_instances_produce_no_duplicate_event_indices() {
 
 #[tokio::test]
 async fn concurrent_publish_to_redis_maintains_monotonic_index() {
-    let container = testcontainers::runners::AsyncRunner::start(
-        testcontainers_modules::redis::Redis::default(),
-    )
-    .await
-    .unwrap();
-    let port = container.get_host_port_ipv4(6379).await.unwrap();
-    let redis_url = format!("redis://127.0.0.1:{port}");
-
-    flush_redis(&redis_url).await;
-
-    let engine = Arc::new(make_redis_engine(&redis_url).await);
+    let prefix = unique_prefix("concurrent");
+    let Some(engine) = make_redis_engine(&prefix).await else {
+        return;
+    };
+    let engine = Arc::new(engine);
     let task = engine
         .create_task(CreateTaskInput::default())
         .await
@@ -196,6 +180,9 @@ async fn concurrent_publish_to_redis_maintains_monotonic_index() {
                         series_id: None,
                         series_mode: None,
                         series_acc_field: None,
+                        labels: None,
+                        broadcast_debounce_ms: None,
+                        series_end: false,
                     },
                 )
                 .await
@@ -226,17 +213,11 @@ async fn concurrent_publish_to_redis_maintains_monotonic_index() {
 
 #[tokio::test]
 async fn redis_store_100_concurrent_tasks_all_get_unique_ids() {
-    let container = testcontainers::runners::AsyncRunner::start(
-        testcontainers_modules::redis::Redis::default(),
-    )
-    .await
-    .unwrap();
-    let port = container.get_host_port_ipv4(6379).await.unwrap();
-    let redis_url = format!("redis://127.0.0.1:{port}");
-
-    flush_redis(&redis_url).await;
-
-    let engine = Arc::new(make_redis_engine(&redis_url).await);
+    let prefix = unique_prefix("concurrent");
+    let Some(engine) = make_redis_engine(&prefix).await else {
+        return;
+    };
+    let engine = Arc::new(engine);
     let count = 100;
 
     let mut handles = Vec::new();
@@ -257,6 +238,56 @@ async fn redis_store_100_concurrent_tasks_all_get_unique_ids() {
     assert_eq!(ids.len(), count, "all task IDs must be unique");
 }
 
+#[tokio::test]
+async fn two_instances_racing_same_task_id_have_exactly_one_winner() {
+    // Before the reservation protocol, every racer passed the get_task check
+    // and then overwrote the task, so all 20 got 201 and the last write won.
+    let prefix = unique_prefix("concurrent");
+    let (Some(engine1), Some(engine2)) = (
+        make_redis_engine(&prefix).await,
+        make_redis_engine(&prefix).await,
+    ) else {
+        return;
+    };
+    let servers = [engine1, engine2].map(|engine| {
+        let (app, _) = create_app(
+            Arc::new(engine),
+            AuthMode::None,
+            None,
+            None,
+            CorsConfig::default(),
+        );
+        axum_test::TestServer::new(app)
+    });
+
+    let requests = (0..20).map(|i| {
+        servers[i % 2]
+            .post("/tasks")
+            .json(&serde_json::json!({ "id": "task-x", "params": { "creator": i } }))
+            .into_future()
+    });
+    let responses = futures::future::join_all(requests).await;
+
+    let winners: Vec<_> = responses
+        .iter()
+        .filter(|r| r.status_code() == 201)
+        .map(|r| r.json::<serde_json::Value>())
+        .collect();
+    let conflicts = responses
+        .iter()
+        .filter(|r| r.status_code() == 409)
+        .inspect(|r| assert_eq!(r.json::<serde_json::Value>()["code"], "TASK_ALREADY_EXISTS"))
+        .count();
+    assert_eq!(winners.len(), 1, "exactly one creator must win");
+    assert_eq!(conflicts, 19, "every other creator must get 409");
+
+    let stored = servers[0]
+        .get("/tasks/task-x")
+        .await
+        .json::<serde_json::Value>();
+    assert_eq!(stored["params"], winners[0]["params"]);
+}
+
 // ── RedisBroadcastProvider regression tests ───────────────────────────────────
 //
 // Regression for: subscribe() only registered a local handler without issuing
@@ -268,16 +299,13 @@ async fn redis_store_100_concurrent_tasks_all_get_unique_ids() {
 async fn cross_instance_broadcast_delivers_to_subscriber_on_other_instance() {
     // Instance A publishes; instance B subscribes. Before the fix, B's handler
     // was never called because the background task had no Redis subscriptions.
-    let container = testcontainers::runners::AsyncRunner::start(
-        testcontainers_modules::redis::Redis::default(),
-    )
-    .await
-    .unwrap();
-    let port = container.get_host_port_ipv4(6379).await.unwrap();
-    let redis_url = format!("redis://127.0.0.1:{port}");
-
-    let provider_a = make_redis_broadcast(&redis_url).await;
-    let provider_b = make_redis_broadcast(&redis_url).await;
+    let prefix = unique_prefix("concurrent");
+    let (Some(provider_a), Some(provider_b)) = (
+        make_redis_broadcast(&prefix).await,
+        make_redis_broadcast(&prefix).await,
+    ) else {
+        return;
+    };
 
     // Allow PSUBSCRIBE (spawned in tokio::spawn inside new()) to complete
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
@@ -316,16 +344,13 @@ async fn cross_instance_broadcast_delivers_to_subscriber_on_other_instance() {
 async fn cross_instance_broadcast_wildcard_covers_multiple_task_channels() {
     // PSUBSCRIBE uses a wildcard so all task IDs are covered by a single
     // Redis subscription — no per-task SUBSCRIBE call needed.
-    let container = testcontainers::runners::AsyncRunner::start(
-        testcontainers_modules::redis::Redis::default(),
-    )
-    .await
-    .unwrap();
-    let port = container.get_host_port_ipv4(6379).await.unwrap();
-    let redis_url = format!("redis://127.0.0.1:{port}");
-
-    let provider_a = make_redis_broadcast(&redis_url).await;
-    let provider_b = make_redis_broadcast(&redis_url).await;
+    let prefix = unique_prefix("concurrent");
+    let (Some(provider_a), Some(provider_b)) = (
+        make_redis_broadcast(&prefix).await,
+        make_redis_broadcast(&prefix).await,
+    ) else {
+        return;
+    };
 
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
@@ -0,0 +1,222 @@
+//! Integration tests for corrupt-record handling in `RedisShortTermStore`.
+//!
+//! Run with: `cargo test -p taskcast-redis --test corrupt_records`
+
+use std::sync::{Arc, Mutex};
+
+use redis::AsyncCommands;
+use taskcast_core::types::{
+    ErrorContext, Level, ShortTermStore, Task, TaskEvent, TaskFilter, TaskStatus, TaskcastHooks,
+};
+use taskcast_core::{
+    CorruptRecord, CorruptRecordKind, CorruptRecordPolicy, IntegrityMonitor, CORRUPT_EVENT_TYPE,
+};
+use taskcast_redis::RedisShortTermStore;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct RecordingHooks {
+    errors: Mutex<Vec<(String, ErrorContext)>>,
+}
+
+impl TaskcastHooks for RecordingHooks {
+    fn on_unhandled_error(
+        &self,
+        err: &(dyn std::error::Error + Send + Sync),
+        context: &ErrorContext,
+    ) {
+        self.errors
+            .lock()
+            .unwrap()
+            .push((err.to_string(), context.clone()));
+    }
+}
+
+struct Context {
+    store: RedisShortTermStore,
+    conn: redis::aio::MultiplexedConnection,
+    monitor: IntegrityMonitor,
+    hooks: Arc<RecordingHooks>,
+    _container: testcontainers::ContainerAsync<Redis>,
+}
+
+async fn setup(policy: CorruptRecordPolicy) -> Context {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+    let client = redis::Client::open(format!("redis://127.0.0.1:{port}")).unwrap();
+    let conn = client.get_multiplexed_async_connection().await.unwrap();
+    let hooks = Arc::new(RecordingHooks::default());
+    let monitor =
+        IntegrityMonitor::with_hooks(policy, Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+    let store =
+        RedisShortTermStore::new(conn.clone(), Some("test")).with_integrity(monitor.clone());
+    Context {
+        store,
+        conn,
+        monitor,
+        hooks,
+        _container: container,
+    }
+}
+
+fn make_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: None,
+        status: TaskStatus::Pending,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+        ttl: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "i": index }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// Appends event 0, pushes `raw` straight onto the list, then appends event 2.
+async fn seed_with_raw_event(ctx: &mut Context, raw: &str) {
+    ctx.store
+        .append_event("t1", make_event("t1", 0))
+        .await
+        .unwrap();
+    ctx.conn
+        .rpush::<_, _, ()>("test:events:t1", raw)
+        .await
+        .unwrap();
+    ctx.store
+        .append_event("t1", make_event("t1", 2))
+        .await
+        .unwrap();
+}
+
+// ── Events ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_returns_unparseable_entry_as_corrupt_event() {
+    let mut ctx = setup(CorruptRecordPolicy::Surface).await;
+    seed_with_raw_event(&mut ctx, "{\"id\":\"evt-t1-1\",\"index\":1,").await;
+
+    let events = ctx.store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 3);
+    // Truncated JSON: nothing is recoverable, so the list offset stands in.
+    assert_eq!(events[1].id, "corrupt:t1:1");
+    assert_eq!(events[1].index, 1);
+    assert_eq!(events[1].r#type, CORRUPT_EVENT_TYPE);
+    assert_eq!(events[1].data["raw"], "{\"id\":\"evt-t1-1\",\"index\":1,");
+    assert!(events[1].data["error"].is_string());
+    assert_eq!(events[2].id, "evt-t1-2");
+
+    ctx.store.get_events("t1", None).await.unwrap();
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+    assert!(ctx.hooks.errors.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn skip_policy_drops_invalid_level_and_reports_it() {
+    let mut ctx = setup(CorruptRecordPolicy::Skip).await;
+    let mut bad = serde_json::to_value(make_event("t1", 1)).unwrap();
+    bad["level"] = serde_json::json!("critical");
+    seed_with_raw_event(&mut ctx, &bad.to_string()).await;
+
+    let events = ctx.store.get_events("t1", None).await.unwrap();
+    let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indices, vec![0, 2]);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].0.contains("evt-t1-1"), "{}", errors[0].0);
+    assert_eq!(errors[0].1.operation, "storage.read_event");
+    assert_eq!(errors[0].1.task_id.as_deref(), Some("t1"));
+    drop(errors);
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+// ── Tasks ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_fails_task_read_with_unknown_status() {
+    let mut ctx = setup(CorruptRecordPolicy::Surface).await;
+    ctx.store.save_task(make_task("t1")).await.unwrap();
+    let mut bad = serde_json::to_value(make_task("t1")).unwrap();
+    bad["status"] = serde_json::json!("exploded");
+    ctx.conn
+        .set::<_, _, ()>("test:task:t1", bad.to_string())
+        .await
+        .unwrap();
+
+    let err = ctx.store.get_task("t1").await.unwrap_err();
+    let record = err.downcast_ref::<CorruptRecord>().expect("corrupt record");
+    assert_eq!(record.kind, CorruptRecordKind::Task);
+    assert_eq!(*record.raw, serde_json::json!(bad.to_string()));
+    assert!(ctx.store.list_tasks(TaskFilter::default()).await.is_err());
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+#[tokio::test]
+async fn skip_policy_hides_corrupt_task_from_reads_and_listings() {
+    let mut ctx = setup(CorruptRecordPolicy::Skip).await;
+    ctx.store.save_task(make_task("t1")).await.unwrap();
+    ctx.store.save_task(make_task("t2")).await.unwrap();
+    ctx.conn
+        .set::<_, _, ()>("test:task:t1", "not json")
+        .await
+        .unwrap();
+
+    assert_eq!(ctx.store.get_task("t1").await.unwrap(), None);
+    let listed = ctx.store.list_tasks(TaskFilter::default()).await.unwrap();
+    let ids: Vec<&str> = listed.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["t2"]);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|(_, context)| context.operation == "storage.read_task"));
+    drop(errors);
+    assert_eq!(ctx.monitor.total_corrupt_records(), 1);
+}
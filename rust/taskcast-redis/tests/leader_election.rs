@@ -0,0 +1,203 @@
+//! Warm standby failover between two servers sharing real Redis (via
+//! testcontainers). Requires Docker.
+//!
+//! Run with: `cargo test -p taskcast-redis --test leader_election`
+//! Set `TASKCAST_TEST_SKIP_DOCKER=1` to skip without Docker.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    LeaderElection, LeaderElectionOptions, LeaderLease, RoleSwitch, ServerRole, TaskEngine,
+    TaskEngineOptions,
+};
+use taskcast_redis::RedisLeaderLease;
+use taskcast_server::{
+    create_app_with_options, AppOptions, AuthMode, LogLevel, StderrHttpFailureLogger,
+};
+use taskcast_test_backends::{make_redis_broadcast, make_redis_store, redis_url, unique_prefix};
+
+const TTL: Duration = Duration::from_millis(2_000);
+const RENEW_INTERVAL: Duration = Duration::from_millis(400);
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// The Redis lease behind a link that hangs every call while `cut` is set,
+/// as a partition or a stalled process would.
+struct Partitioned {
+    lease: RedisLeaderLease,
+    cut: AtomicBool,
+}
+
+#[async_trait]
+impl LeaderLease for Partitioned {
+    async fn acquire(
+        &self,
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        if self.cut.load(Ordering::SeqCst) {
+            std::future::pending::<()>().await;
+        }
+        self.lease.acquire(holder, ttl).await
+    }
+
+    async fn release(&self, holder: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.lease.release(holder).await
+    }
+}
+
+struct Instance {
+    server: TestServer,
+    role: RoleSwitch,
+    link: Arc<Partitioned>,
+    election: LeaderElection,
+}
+
+/// A server on the shared Redis under `prefix`, campaigning as `holder`.
+async fn start_instance(prefix: &str, holder: &str) -> Option<Instance> {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(make_redis_store(prefix).await?),
+        broadcast: Arc::new(make_redis_broadcast(prefix).await?),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let client = redis::Client::open(redis_url().await?).unwrap();
+    let conn = client.get_multiplexed_async_connection().await.unwrap();
+    let link = Arc::new(Partitioned {
+        lease: RedisLeaderLease::new(conn, Some(prefix), None),
+        cut: AtomicBool::new(false),
+    });
+
+    let role = RoleSwitch::new(ServerRole::Active, engine.runners().clone());
+    let election = LeaderElection::start(
+        role.clone(),
+        LeaderElectionOptions {
+            lease: link.clone(),
+            holder: holder.to_string(),
+            ttl: TTL,
+            renew_interval: RENEW_INTERVAL,
+        },
+    );
+    let (app, _) = create_app_with_options(
+        engine,
+        AuthMode::None,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            role: Some(role.clone()),
+            ..Default::default()
+        },
+    );
+    Some(Instance {
+        server: TestServer::new(app),
+        role,
+        link,
+        election,
+    })
+}
+
+/// Polls until `check` holds, for at most `within`.
+async fn eventually(within: Duration, check: impl Fn() -> bool) -> bool {
+    let deadline = Instant::now() + within;
+    while Instant::now() < deadline {
+        if check() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    check()
+}
+
+async fn create_task(server: &TestServer, id: &str) -> axum_test::TestResponse {
+    server.post("/tasks").json(&json!({ "id": id })).await
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn standby_takes_over_when_the_leader_stops_renewing() {
+    let prefix = unique_prefix("leader");
+    let Some(a) = start_instance(&prefix, "a").await else {
+        return;
+    };
+    assert!(eventually(TTL, || a.role.is_active()).await);
+    let b = start_instance(&prefix, "b").await.unwrap();
+    tokio::time::sleep(RENEW_INTERVAL * 2).await;
+    assert!(!b.role.is_active());
+
+    // The standby refuses writes but reads what the leader wrote.
+    create_task(&a.server, "t1")
+        .await
+        .assert_status(StatusCode::CREATED);
+    let res = create_task(&b.server, "t2").await;
+    res.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.json::<Value>()["code"], "READ_ONLY");
+    b.server.get("/tasks/t1").await.assert_status_ok();
+    let health: Value = b.server.get("/health/detail").await.json();
+    assert_eq!(health["role"], "standby");
+
+    // Kill the leader's renewals.
+    a.link.cut.store(true, Ordering::SeqCst);
+    let cut_at = Instant::now();
+    let mut a_demoted_after = None;
+    while !b.role.is_active() {
+        assert!(
+            !(a.role.is_active() && b.role.is_active()),
+            "both instances active"
+        );
+        if a_demoted_after.is_none() && !a.role.is_active() {
+            a_demoted_after = Some(cut_at.elapsed());
+        }
+        assert!(
+            cut_at.elapsed() < TTL + RENEW_INTERVAL * 2,
+            "standby not promoted within the lease window"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!a.role.is_active());
+    assert!(a_demoted_after.unwrap_or(TTL) <= TTL);
+
+    // Writes move to the new leader.
+    create_task(&b.server, "t3")
+        .await
+        .assert_status(StatusCode::CREATED);
+    let res = create_task(&a.server, "t4").await;
+    res.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+
+    // The old leader recovers as a standby instead of writing alongside.
+    a.link.cut.store(false, Ordering::SeqCst);
+    tokio::time::sleep(RENEW_INTERVAL * 4).await;
+    assert!(!a.role.is_active());
+    assert!(b.role.is_active());
+    create_task(&a.server, "t5")
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    create_task(&b.server, "t5")
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    // Stepping down hands the lease over without waiting for it to lapse.
+    b.election.stop().await;
+    assert!(eventually(RENEW_INTERVAL * 2, || a.role.is_active()).await);
+    a.election.stop().await;
+}
+
+#[tokio::test]
+async fn manual_promotion_is_refused_while_elected() {
+    let prefix = unique_prefix("leader-manual");
+    let Some(a) = start_instance(&prefix, "a").await else {
+        return;
+    };
+    let res = a.server.post("/admin/promote").await;
+    res.assert_status(StatusCode::CONFLICT);
+    assert_eq!(res.json::<Value>()["code"], "ROLE_ELECTED");
+    a.election.stop().await;
+}
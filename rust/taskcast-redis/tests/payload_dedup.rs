@@ -0,0 +1,230 @@
+//! Integration tests for payload deduplication in `RedisShortTermStore`.
+//!
+//! Run with: `cargo test -p taskcast-redis --test payload_dedup`
+
+use redis::AsyncCommands;
+use taskcast_core::payload_dedup::{content_hash, PayloadDedupConfig};
+use taskcast_core::types::{Level, SeriesMode, ShortTermStore, TaskEvent};
+use taskcast_redis::RedisShortTermStore;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+const MIN_BYTES: usize = 256;
+
+async fn start_redis() -> (testcontainers::ContainerAsync<Redis>, String) {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+    let url = format!("redis://127.0.0.1:{port}");
+    (container, url)
+}
+
+async fn connect(redis_url: &str) -> redis::aio::MultiplexedConnection {
+    let client = redis::Client::open(redis_url).unwrap();
+    client.get_multiplexed_async_connection().await.unwrap()
+}
+
+async fn make_store(redis_url: &str) -> RedisShortTermStore {
+    RedisShortTermStore::with_payload_dedup(
+        connect(redis_url).await,
+        Some("test"),
+        PayloadDedupConfig {
+            min_bytes: MIN_BYTES,
+        },
+    )
+}
+
+fn large_payload() -> serde_json::Value {
+    serde_json::json!({
+        "config": { "model": "m-1", "prompt": "p".repeat(MIN_BYTES), "temperature": 0.25 },
+        "attempt": null,
+        "tags": ["retry", "échec", 1, 2.5e10]
+    })
+}
+
+fn make_event(task_id: &str, index: u64, data: serde_json::Value) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "retry".to_string(),
+        level: Level::Info,
+        data,
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn blob_key(data: &serde_json::Value) -> String {
+    let hash = content_hash(&serde_json::to_string(data).unwrap());
+    format!("test:blob:{hash}")
+}
+
+async fn raw_events(conn: &mut redis::aio::MultiplexedConnection, task_id: &str) -> Vec<String> {
+    conn.lrange(format!("test:events:{task_id}"), 0, -1)
+        .await
+        .unwrap()
+}
+
+async fn blob_refs(conn: &mut redis::aio::MultiplexedConnection, key: &str) -> Option<i64> {
+    conn.hget(key, "refs").await.unwrap()
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn large_payload_is_stored_once_and_referenced() {
+    let (_container, redis_url) = start_redis().await;
+    let store = make_store(&redis_url).await;
+    let mut conn = connect(&redis_url).await;
+    let data = large_payload();
+
+    for i in 0..3 {
+        store
+            .append_event("t1", make_event("t1", i, data.clone()))
+            .await
+            .unwrap();
+    }
+
+    let key = blob_key(&data);
+    let hash = key.trim_start_matches("test:blob:");
+    for raw in raw_events(&mut conn, "t1").await {
+        let stored: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(stored["data"], serde_json::json!({ "$blobRef": hash }));
+    }
+    assert_eq!(blob_refs(&mut conn, &key).await, Some(3));
+}
+
+#[tokio::test]
+async fn resolved_reads_are_byte_identical() {
+    let (_container, redis_url) = start_redis().await;
+    let store = make_store(&redis_url).await;
+    let data = large_payload();
+
+    store
+        .append_event("t1", make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        serde_json::to_string(&events[0].data).unwrap(),
+        serde_json::to_string(&data).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn refcounts_follow_event_and_task_deletion() {
+    let (_container, redis_url) = start_redis().await;
+    let store = make_store(&redis_url).await;
+    let mut conn = connect(&redis_url).await;
+    let data = large_payload();
+    let key = blob_key(&data);
+
+    for (task_id, index) in [("t1", 0), ("t1", 1), ("t2", 0)] {
+        store
+            .append_event(task_id, make_event(task_id, index, data.clone()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(blob_refs(&mut conn, &key).await, Some(3));
+
+    store
+        .delete_events("t1", &["evt-t1-0".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(blob_refs(&mut conn, &key).await, Some(2));
+    let remaining = store.get_events("t1", None).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].data, data);
+
+    store.delete_task("t1").await.unwrap();
+    assert_eq!(blob_refs(&mut conn, &key).await, Some(1));
+    assert!(raw_events(&mut conn, "t1").await.is_empty());
+
+    store.delete_task("t2").await.unwrap();
+    let exists: bool = conn.exists(&key).await.unwrap();
+    assert!(!exists, "blob must be deleted when its last reference goes");
+}
+
+#[tokio::test]
+async fn series_replacement_moves_reference_to_new_payload() {
+    let (_container, redis_url) = start_redis().await;
+    let store = make_store(&redis_url).await;
+    let mut conn = connect(&redis_url).await;
+    let first = large_payload();
+    let second = serde_json::json!({ "config": "q".repeat(MIN_BYTES) });
+
+    let mut event = make_event("t1", 0, first.clone());
+    event.series_id = Some("s1".to_string());
+    event.series_mode = Some(SeriesMode::Latest);
+    store
+        .replace_last_series_event("t1", "s1", event.clone())
+        .await
+        .unwrap();
+
+    let replacement = TaskEvent {
+        id: "evt-t1-1".to_string(),
+        index: 1,
+        data: second.clone(),
+        ..event
+    };
+    store
+        .replace_last_series_event("t1", "s1", replacement)
+        .await
+        .unwrap();
+
+    let first_key = blob_key(&first);
+    let exists: bool = conn.exists(&first_key).await.unwrap();
+    assert!(!exists, "replaced payload's blob must be released");
+    assert_eq!(blob_refs(&mut conn, &blob_key(&second)).await, Some(1));
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].data, second);
+}
+
+#[tokio::test]
+async fn small_payload_bypasses_dedup() {
+    let (_container, redis_url) = start_redis().await;
+    let store = make_store(&redis_url).await;
+    let mut conn = connect(&redis_url).await;
+    let data = serde_json::json!({ "text": "hi" });
+
+    store
+        .append_event("t1", make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    let raw = raw_events(&mut conn, "t1").await;
+    let stored: serde_json::Value = serde_json::from_str(&raw[0]).unwrap();
+    assert_eq!(stored["data"], data);
+    let blobs: Vec<String> = conn.keys("test:blob:*").await.unwrap();
+    assert!(blobs.is_empty());
+    assert_eq!(store.get_events("t1", None).await.unwrap()[0].data, data);
+}
+
+#[tokio::test]
+async fn store_without_dedup_keeps_payloads_inline() {
+    let (_container, redis_url) = start_redis().await;
+    let store = RedisShortTermStore::new(connect(&redis_url).await, Some("test"));
+    let mut conn = connect(&redis_url).await;
+    let data = large_payload();
+
+    store
+        .append_event("t1", make_event("t1", 0, data.clone()))
+        .await
+        .unwrap();
+
+    let raw = raw_events(&mut conn, "t1").await;
+    let stored: serde_json::Value = serde_json::from_str(&raw[0]).unwrap();
+    assert_eq!(stored["data"], data);
+}
@@ -5,12 +5,16 @@
 //!
 //! Run with: `cargo test -p taskcast-redis --test short_term_tests`
 
+use std::collections::HashMap;
+
 use taskcast_core::types::{
-    AssignMode, ConnectionMode, EventQueryOptions, Level, SeriesMode, ShortTermStore, SinceCursor,
-    Task, TaskError, TaskFilter, TaskStatus, Worker, WorkerAssignment, WorkerAssignmentStatus,
+    AssignMode, ConnectionMode, EventQueryOptions, FilteredIndexClaim, FilteredIndexMark, Level, NewTaskOutcome, OutcomeCursor,
+    OutcomeQuery, RetrySchedule,
+    SeriesMode, ShortTermStore, SinceCursor, Task, TaskError, TaskFilter, TaskOutcome, TaskStatus, Worker, WorkerAssignment, WorkerAssignmentStatus,
     WorkerFilter, WorkerMatchRule, WorkerStatus,
 };
-use taskcast_core::TaskEvent;
+use taskcast_core::series::process_series;
+use taskcast_core::{MemoryShortTermStore, TaskEvent};
 use taskcast_redis::RedisShortTermStore;
 use testcontainers::runners::AsyncRunner;
 use testcontainers_modules::redis::Redis;
@@ -45,6 +49,16 @@ fn make_task(id: &str) -> Task {
         updated_at: 1000.0,
         completed_at: None,
         ttl: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     }
 }
 
@@ -61,6 +75,8 @@ fn make_event(task_id: &str, index: u64) -> TaskEvent {
         series_mode: None,
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
@@ -143,6 +159,90 @@ async fn upsert_task_on_conflict() {
     assert_eq!(retrieved.updated_at, 2000.0);
 }
 
+#[tokio::test]
+async fn save_task_versioned_rejects_a_stale_version() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    let mut task = make_task("task-cas");
+    task.version = 1;
+    assert!(store.save_task_versioned(task.clone(), 0).await.unwrap());
+
+    task.version = 2;
+    task.status = TaskStatus::Running;
+    assert!(
+        !store.save_task_versioned(task.clone(), 0).await.unwrap(),
+        "a write expecting an old version must be rejected"
+    );
+    let retrieved = store.get_task("task-cas").await.unwrap().unwrap();
+    assert_eq!(retrieved.version, 1);
+    assert_eq!(retrieved.status, TaskStatus::Pending);
+
+    assert!(store.save_task_versioned(task.clone(), 1).await.unwrap());
+    let retrieved = store.get_task("task-cas").await.unwrap().unwrap();
+    assert_eq!(retrieved.version, 2);
+    let ids: Vec<String> = store
+        .list_tasks(TaskFilter::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    assert_eq!(ids, vec!["task-cas".to_string()]);
+}
+
+#[tokio::test]
+async fn claim_deadline_warning_succeeds_once() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    assert!(store.claim_deadline_warning("t1", "atFraction:0.8", 60_000).await.unwrap());
+    assert!(!store.claim_deadline_warning("t1", "atFraction:0.8", 60_000).await.unwrap());
+    assert!(store.claim_deadline_warning("t1", "beforeMs:1000", 60_000).await.unwrap());
+    assert!(store.claim_deadline_warning("t2", "atFraction:0.8", 60_000).await.unwrap());
+}
+
+#[tokio::test]
+async fn save_new_task_never_overwrites_an_existing_task() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    let first = make_task("task-new");
+    let outcome = store.save_new_task(first.clone()).await.unwrap();
+    assert_eq!(outcome, NewTaskOutcome::Created);
+
+    let mut second = make_task("task-new");
+    second.status = TaskStatus::Running;
+    let outcome = store.save_new_task(second).await.unwrap();
+    assert_eq!(outcome, NewTaskOutcome::AlreadyExists(Some(Box::new(first.clone()))));
+
+    let stored = store.get_task("task-new").await.unwrap().unwrap();
+    assert_eq!(stored, first, "the losing creator must not overwrite the task");
+    let listed = store.list_tasks(TaskFilter::default()).await.unwrap();
+    assert_eq!(listed.len(), 1);
+}
+
+#[tokio::test]
+async fn save_new_task_releases_its_reservation() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    store.save_new_task(make_task("task-res")).await.unwrap();
+
+    let client = redis::Client::open(redis_url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let reservation: Option<String> = redis::cmd("GET")
+        .arg(format!("{}:reserve:task-res", store.key_prefix()))
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(reservation.is_none(), "reservation must be cleared after the write");
+}
+
 #[tokio::test]
 async fn preserve_optional_fields_on_round_trip() {
     let (_container, redis_url) = start_redis().await;
@@ -192,6 +292,16 @@ async fn preserve_optional_fields_on_round_trip() {
         updated_at: 2000.0,
         completed_at: Some(3000.0),
         ttl: Some(60),
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     };
 
     store.save_task(task.clone()).await.unwrap();
@@ -235,6 +345,16 @@ async fn handle_task_with_no_optional_fields() {
         updated_at: 500.0,
         completed_at: None,
         ttl: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     };
 
     store.save_task(task.clone()).await.unwrap();
@@ -267,6 +387,20 @@ async fn generate_monotonic_indices_starting_from_0() {
     assert_eq!(idx2, 2, "third index must be 2");
 }
 
+#[tokio::test]
+async fn event_count_reads_counter_without_advancing_it() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    assert_eq!(store.event_count("task-count").await.unwrap(), 0);
+    store.next_index("task-count").await.unwrap();
+    store.next_index("task-count").await.unwrap();
+
+    assert_eq!(store.event_count("task-count").await.unwrap(), 2);
+    assert_eq!(store.next_index("task-count").await.unwrap(), 2);
+}
+
 #[tokio::test]
 async fn maintain_separate_counters_per_task() {
     let (_container, redis_url) = start_redis().await;
@@ -310,6 +444,50 @@ async fn append_and_retrieve_events_in_order() {
     assert_eq!(events[2].index, 2);
 }
 
+#[tokio::test]
+async fn get_events_sorts_ties_and_out_of_order_appends() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    let at = |id: &str, index: u64, timestamp: f64| TaskEvent {
+        id: id.to_string(),
+        timestamp,
+        ..make_event("task-ord", index)
+    };
+    for event in [
+        at("e4", 2, 1000.0),
+        at("e3", 1, 3000.0),
+        at("e2b", 1, 2000.0),
+        at("e2a", 1, 2000.0),
+        at("e1", 0, 4000.0),
+    ] {
+        store.append_event("task-ord", event).await.unwrap();
+    }
+
+    let events = store.get_events("task-ord", None).await.unwrap();
+    let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["e1", "e2a", "e2b", "e3", "e4"]);
+
+    // since.index is strictly greater: every event tied at index 1 is excluded.
+    let events = store
+        .get_events(
+            "task-ord",
+            Some(EventQueryOptions {
+                since: Some(SinceCursor {
+                    id: None,
+                    index: Some(1),
+                    timestamp: None,
+                }),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let ids: Vec<&str> = events.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["e4"]);
+}
+
 #[tokio::test]
 async fn return_empty_vec_when_no_events_exist() {
     let (_container, redis_url) = start_redis().await;
@@ -346,6 +524,9 @@ async fn filter_events_by_since_index() {
                     timestamp: None,
                 }),
                 limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -381,6 +562,9 @@ async fn filter_events_by_since_timestamp() {
                     timestamp: Some(1200.0),
                 }),
                 limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -415,6 +599,9 @@ async fn filter_events_by_since_id() {
                     timestamp: None,
                 }),
                 limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -449,6 +636,9 @@ async fn return_all_events_when_since_id_not_found() {
                     timestamp: None,
                 }),
                 limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -461,6 +651,140 @@ async fn return_all_events_when_since_id_not_found() {
     );
 }
 
+#[tokio::test]
+async fn since_id_takes_priority_over_index() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for i in 0..5 {
+        store
+            .append_event("task-prio", make_event("task-prio", i))
+            .await
+            .unwrap();
+    }
+
+    let events = store
+        .get_events(
+            "task-prio",
+            Some(EventQueryOptions {
+                since: Some(SinceCursor {
+                    id: Some("evt-task-prio-3".to_string()),
+                    index: Some(0),
+                    timestamp: Some(0.0),
+                }),
+                limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1, "since.id must win over index and timestamp");
+    assert_eq!(events[0].index, 4);
+}
+
+#[tokio::test]
+async fn since_id_from_another_task_returns_all_events() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for i in 0..3 {
+        store
+            .append_event("task-a", make_event("task-a", i))
+            .await
+            .unwrap();
+        store
+            .append_event("task-b", make_event("task-b", i))
+            .await
+            .unwrap();
+    }
+
+    let events = store
+        .get_events(
+            "task-a",
+            Some(EventQueryOptions {
+                since: Some(SinceCursor {
+                    id: Some("evt-task-b-1".to_string()),
+                    index: None,
+                    timestamp: None,
+                }),
+                limit: Some(2),
+                label_selector: None,
+                until: None,
+                before_index: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 2, "foreign anchor is unknown; limit still applies");
+    assert_eq!(events[0].id, "evt-task-a-0");
+}
+
+#[tokio::test]
+async fn return_empty_vec_for_unknown_task_with_cursor() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    let events = store
+        .get_events(
+            "no-such-task",
+            Some(EventQueryOptions {
+                since: Some(SinceCursor {
+                    id: Some("whatever".to_string()),
+                    index: None,
+                    timestamp: None,
+                }),
+                limit: Some(10),
+                label_selector: None,
+                until: None,
+                before_index: None,
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn round_trip_and_filter_by_event_labels() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for i in 0..4 {
+        let mut event = make_event("task-1", i);
+        let region = if i % 2 == 0 { "eu" } else { "us" };
+        event.labels = Some(HashMap::from([("region".to_string(), region.to_string())]));
+        store.append_event("task-1", event).await.unwrap();
+    }
+
+    let all = store.get_events("task-1", None).await.unwrap();
+    assert_eq!(all[1].labels.as_ref().unwrap()["region"], "us");
+
+    let events = store
+        .get_events(
+            "task-1",
+            Some(EventQueryOptions {
+                since: None,
+                limit: None,
+                label_selector: Some(HashMap::from([("region".to_string(), "eu".to_string())])),
+                until: None,
+                before_index: None,
+            }),
+        )
+        .await
+        .unwrap();
+    let indexes: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indexes, vec![0, 2]);
+}
+
 #[tokio::test]
 async fn respect_limit_parameter() {
     let (_container, redis_url) = start_redis().await;
@@ -480,6 +804,9 @@ async fn respect_limit_parameter() {
             Some(EventQueryOptions {
                 since: None,
                 limit: Some(2),
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -514,6 +841,9 @@ async fn apply_limit_after_since_filter() {
                     timestamp: None,
                 }),
                 limit: Some(2),
+                label_selector: None,
+                until: None,
+                before_index: None,
             }),
         )
         .await
@@ -524,6 +854,90 @@ async fn apply_limit_after_since_filter() {
     assert_eq!(events[1].index, 7);
 }
 
+#[tokio::test]
+async fn until_keeps_events_at_or_before_the_bound() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for i in 0..5 {
+        store
+            .append_event("task-until", make_event("task-until", i))
+            .await
+            .unwrap();
+    }
+
+    // Event i is timestamped 1000 + 100 * i.
+    let query = |since: Option<u64>, limit: Option<u64>, until: f64| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit,
+        label_selector: None,
+        until: Some(until),
+        before_index: None,
+    };
+    let indexes = |events: Vec<TaskEvent>| events.iter().map(|e| e.index).collect::<Vec<u64>>();
+
+    let events = store
+        .get_events("task-until", Some(query(None, None, 1200.0)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![0, 1, 2]);
+
+    // `until` applies before the limit, alongside the cursor
+    let events = store
+        .get_events("task-until", Some(query(Some(0), Some(1), 1250.5)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![1]);
+
+    let events = store
+        .get_events("task-until", Some(query(None, Some(5), 999.0)))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn before_index_bounds_paged_reads() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    for i in 0..6 {
+        store
+            .append_event("task-before", make_event("task-before", i))
+            .await
+            .unwrap();
+    }
+
+    // A scan that ended at index 4, read two events at a time.
+    let page = |since: Option<u64>| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit: Some(2),
+        before_index: Some(4),
+        ..Default::default()
+    };
+    let mut indexes: Vec<u64> = Vec::new();
+    loop {
+        let events = store
+            .get_events("task-before", Some(page(indexes.last().copied())))
+            .await
+            .unwrap();
+        if events.is_empty() {
+            break;
+        }
+        indexes.extend(events.iter().map(|e| e.index));
+    }
+    assert_eq!(indexes, vec![0, 1, 2, 3]);
+}
+
 // ── Series Tests ────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -624,10 +1038,11 @@ async fn replace_last_series_event_in_event_list() {
     replacement.series_mode = Some(SeriesMode::Latest);
     replacement.data = serde_json::json!({"text": "replaced"});
 
-    store
+    let replaced = store
         .replace_last_series_event("task-rse", "progress", replacement.clone())
         .await
         .unwrap();
+    assert_eq!(replaced.as_deref(), Some(e1.id.as_str()));
 
     // Check that the event list has the replacement in place of e1
     let events = store.get_events("task-rse", None).await.unwrap();
@@ -687,6 +1102,61 @@ async fn append_when_no_previous_series_event() {
     assert_eq!(latest.id, series_event.id);
 }
 
+#[tokio::test]
+async fn list_series_orders_by_series_id_and_counts() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for (index, sid) in [(0, "status"), (1, "answer"), (2, "status")] {
+        let mut event = make_event("task-ls", index);
+        event.series_id = Some(sid.to_string());
+        event.series_mode = Some(SeriesMode::Latest);
+        store
+            .replace_last_series_event("task-ls", sid, event)
+            .await
+            .unwrap();
+    }
+
+    let series = store.list_series("task-ls").await.unwrap();
+    let ids: Vec<&str> = series.iter().map(|s| s.series_id.as_str()).collect();
+    assert_eq!(ids, ["answer", "status"]);
+    assert_eq!(series[1].latest_index, 2);
+    assert_eq!(store.series_count("task-ls").await.unwrap(), 2);
+    assert_eq!(store.series_count("task-none").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn delete_events_drops_the_series_state_of_deleted_events() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for (index, sid) in [(0, "a"), (1, "b")] {
+        let mut event = make_event("task-ds", index);
+        event.series_id = Some(sid.to_string());
+        event.series_mode = Some(SeriesMode::Latest);
+        store
+            .replace_last_series_event("task-ds", sid, event)
+            .await
+            .unwrap();
+    }
+
+    store
+        .delete_events("task-ds", &["evt-task-ds-0".to_string()])
+        .await
+        .unwrap();
+    assert!(store
+        .get_series_latest("task-ds", "a")
+        .await
+        .unwrap()
+        .is_none());
+    let series = store.list_series("task-ds").await.unwrap();
+    assert_eq!(series.len(), 1);
+    assert_eq!(series[0].series_id, "b");
+    assert_eq!(store.series_count("task-ds").await.unwrap(), 1);
+}
+
 // ── TTL Tests ───────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -1207,6 +1677,60 @@ async fn list_tasks_with_exclude_task_ids() {
     assert_eq!(tasks[0].id, "t-ex-2");
 }
 
+#[tokio::test]
+async fn list_tasks_with_created_before() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for (id, created_at) in [("t-cb-1", 1000.0), ("t-cb-2", 2000.0)] {
+        let mut task = make_task(id);
+        task.created_at = created_at;
+        store.save_task(task).await.unwrap();
+    }
+
+    let tasks = store
+        .list_tasks(TaskFilter {
+            created_before: Some(2000.0),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, "t-cb-1");
+}
+
+#[tokio::test]
+async fn list_tasks_page_walks_every_task_in_creation_order() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    // More tasks than one SSCAN batch, saved newest first.
+    for i in (0..1200).rev() {
+        let mut task = make_task(&format!("t-page-{i:04}"));
+        task.created_at = 1000.0 + (i / 2) as f64;
+        store.save_task(task).await.unwrap();
+    }
+
+    let mut ids = Vec::new();
+    let mut after = None;
+    loop {
+        let page = store
+            .list_tasks_page(TaskFilter::default(), after, 250)
+            .await
+            .unwrap();
+        assert!(page.tasks.len() <= 250);
+        ids.extend(page.tasks.into_iter().map(|t| t.id));
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = (0..1200).map(|i| format!("t-page-{i:04}")).collect();
+    assert_eq!(ids, expected);
+}
+
 // ── accumulate_series Tests ─────────────────────────────────────────────────
 
 fn make_accumulate_event(task_id: &str, index: u64, field: &str, value: serde_json::Value) -> TaskEvent {
@@ -1222,6 +1746,8 @@ fn make_accumulate_event(task_id: &str, index: u64, field: &str, value: serde_js
         series_mode: Some(SeriesMode::Accumulate),
         series_acc_field: Some(field.to_string()),
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
@@ -1337,3 +1863,460 @@ async fn accumulate_series_updates_series_latest() {
     assert_eq!(latest.data, serde_json::json!({"delta": "firstsecond"}));
     assert_eq!(latest.id, e1.id);
 }
+
+// ── JSON Patch series ───────────────────────────────────────────────────────
+
+fn make_patch_event(task_id: &str, index: u64, patch: serde_json::Value) -> TaskEvent {
+    TaskEvent {
+        series_mode: Some(SeriesMode::JsonPatch),
+        series_acc_field: None,
+        r#type: "crawl.frontier".to_string(),
+        data: patch,
+        ..make_accumulate_event(task_id, index, "delta", serde_json::Value::Null)
+    }
+}
+
+#[tokio::test]
+async fn json_patch_series_document_matches_memory_store() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let redis = make_store(&redis_url).await;
+    let memory = MemoryShortTermStore::new();
+
+    let patches = [
+        serde_json::json!([{ "op": "add", "path": "/pending", "value": ["a", "b"] }]),
+        serde_json::json!([
+            { "op": "add", "path": "/pending/-", "value": "c" },
+            { "op": "add", "path": "/visited", "value": 0 },
+        ]),
+        serde_json::json!([
+            { "op": "remove", "path": "/pending/0" },
+            { "op": "replace", "path": "/visited", "value": 1 },
+        ]),
+    ];
+    for (index, patch) in patches.into_iter().enumerate() {
+        let event = make_patch_event("task-jp", index as u64, patch);
+        let from_redis = process_series(event.clone(), &redis).await.unwrap();
+        let from_memory = process_series(event, &memory).await.unwrap();
+        assert_eq!(from_redis.event, from_memory.event);
+    }
+
+    let redis_latest = redis.get_series_latest("task-jp", "s").await.unwrap().unwrap();
+    let memory_latest = memory.get_series_latest("task-jp", "s").await.unwrap().unwrap();
+    assert_eq!(
+        redis_latest.data,
+        serde_json::json!({ "pending": ["b", "c"], "visited": 1 })
+    );
+    assert_eq!(redis_latest, memory_latest);
+}
+
+// ── Retry schedules ─────────────────────────────────────────────────────────
+
+fn make_retry(task_id: &str, due_at: f64) -> RetrySchedule {
+    RetrySchedule {
+        task_id: task_id.to_string(),
+        successor_id: format!("{}-retry", task_id),
+        attempt: 2,
+        due_at,
+    }
+}
+
+#[tokio::test]
+async fn list_due_retries_in_due_order() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    store.save_retry_schedule(make_retry("b", 2000.0)).await.unwrap();
+    store.save_retry_schedule(make_retry("a", 1000.0)).await.unwrap();
+    store.save_retry_schedule(make_retry("c", 5000.0)).await.unwrap();
+
+    let due = store.list_due_retries(2000.0).await.unwrap();
+    let ids: Vec<&str> = due.iter().map(|s| s.task_id.as_str()).collect();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(
+        store.get_retry_schedule("c").await.unwrap(),
+        Some(make_retry("c", 5000.0))
+    );
+}
+
+#[tokio::test]
+async fn claim_retry_is_exclusive_until_deleted() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let other = make_store(&redis_url).await;
+
+    store.save_retry_schedule(make_retry("t1", 1000.0)).await.unwrap();
+    assert!(store.claim_retry("t1", 1000.0, 30_000).await.unwrap());
+    assert!(!other.claim_retry("t1", 1000.0, 30_000).await.unwrap());
+    assert!(!other.claim_retry("missing", 1000.0, 30_000).await.unwrap());
+
+    store.delete_retry_schedule("t1").await.unwrap();
+    assert!(store.get_retry_schedule("t1").await.unwrap().is_none());
+    assert!(store.list_due_retries(f64::MAX).await.unwrap().is_empty());
+
+    // A new schedule for the same task can be claimed again.
+    store.save_retry_schedule(make_retry("t1", 1000.0)).await.unwrap();
+    assert!(other.claim_retry("t1", 1000.0, 30_000).await.unwrap());
+}
+
+#[tokio::test]
+async fn activations_are_listed_in_time_order_and_claimed_once() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let other = make_store(&redis_url).await;
+
+    store.save_activation("b", 2000.0).await.unwrap();
+    store.save_activation("a", 1000.0).await.unwrap();
+    store.save_activation("c", 5000.0).await.unwrap();
+    assert_eq!(store.list_due_activations(2000.0).await.unwrap(), ["a", "b"]);
+
+    assert!(store.claim_activation("a", 2000.0, 30_000).await.unwrap());
+    assert!(!other.claim_activation("a", 2000.0, 30_000).await.unwrap());
+    assert!(!other.claim_activation("missing", 2000.0, 30_000).await.unwrap());
+
+    store.delete_activation("a").await.unwrap();
+    assert_eq!(store.list_due_activations(2000.0).await.unwrap(), ["b"]);
+    assert!(!other.claim_activation("a", 2000.0, 30_000).await.unwrap());
+}
+
+#[tokio::test]
+async fn claim_webhook_delivery_suppresses_within_the_window_and_inherits_ttl() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let other = make_store(&redis_url).await;
+    store.save_task(make_task("t1")).await.unwrap();
+    store.set_ttl("t1", 60).await.unwrap();
+
+    let fingerprint = "llm.error:error";
+    assert!(store
+        .claim_webhook_delivery("t1", 0, fingerprint, 0.0, 1000)
+        .await
+        .unwrap());
+    assert!(!other
+        .claim_webhook_delivery("t1", 0, fingerprint, 999.0, 1000)
+        .await
+        .unwrap());
+    assert!(other
+        .claim_webhook_delivery("t1", 1, fingerprint, 999.0, 1000)
+        .await
+        .unwrap());
+    assert!(other
+        .claim_webhook_delivery("t1", 0, fingerprint, 1000.0, 1000)
+        .await
+        .unwrap());
+
+    let client = redis::Client::open(redis_url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let ttl: i64 = redis::cmd("TTL")
+        .arg(format!("{}:webhookDeliveries:t1", store.key_prefix()))
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(ttl > 0 && ttl <= 60, "ttl = {ttl}");
+}
+
+#[tokio::test]
+async fn claim_filtered_index_numbers_per_consumer_and_filter() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let other = make_store(&redis_url).await;
+    store.save_task(make_task("t1")).await.unwrap();
+    store.set_ttl("t1", 60).await.unwrap();
+    let at = |filtered_index, epoch| Some(FilteredIndexClaim { filtered_index, epoch });
+
+    assert_eq!(store.claim_filtered_index("t1", "a", "h1", 3).await.unwrap(), at(0, 0));
+    assert_eq!(other.claim_filtered_index("t1", "a", "h1", 5).await.unwrap(), at(1, 0));
+    assert_eq!(other.claim_filtered_index("t1", "b", "h1", 5).await.unwrap(), at(0, 0));
+    // A new filter restarts the numbering in the next epoch.
+    assert_eq!(store.claim_filtered_index("t1", "a", "h2", 6).await.unwrap(), at(0, 1));
+    assert_eq!(
+        other.get_filtered_index_mark("t1", "a").await.unwrap(),
+        Some(FilteredIndexMark {
+            filter_hash: "h2".to_string(),
+            epoch: 1,
+            next: 1,
+            through: Some(6),
+        })
+    );
+
+    let client = redis::Client::open(redis_url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let ttl: i64 = redis::cmd("TTL")
+        .arg(format!("{}:filteredIndices:t1", store.key_prefix()))
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(ttl > 0 && ttl <= 60, "ttl = {ttl}");
+}
+
+#[tokio::test]
+async fn redact_events_overwrites_events_and_drops_their_series() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let mut series_event = make_event("t1", 1);
+    series_event.series_id = Some("s1".to_string());
+    series_event.series_mode = Some(SeriesMode::Latest);
+    for event in [make_event("t1", 0), series_event.clone(), make_event("t1", 2)] {
+        store.append_event("t1", event).await.unwrap();
+    }
+    store
+        .set_series_latest("t1", "s1", series_event.clone())
+        .await
+        .unwrap();
+
+    let tombstone = taskcast_core::tombstone(&series_event, Some("erasure request"));
+    assert_eq!(
+        store
+            .redact_events("t1", std::slice::from_ref(&tombstone))
+            .await
+            .unwrap(),
+        1
+    );
+
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1], tombstone);
+    assert!(store.get_series_latest("t1", "s1").await.unwrap().is_none());
+    let counts = store.get_event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types.get("llm.delta"), Some(&2));
+    assert_eq!(counts.types.get(taskcast_core::REDACTED_EVENT_TYPE), Some(&1));
+}
+
+// ── Outcomes Index Tests ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn outcomes_are_listed_in_order_filtered_and_trimmed() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let outcome = |task_id: &str, r#type: Option<&str>, status: TaskStatus, at: f64| TaskOutcome {
+        task_id: task_id.to_string(),
+        r#type: r#type.map(str::to_string),
+        status,
+        completed_at: at,
+        error_code: None,
+        origin: None,
+    };
+    for recorded in [
+        outcome("d", None, TaskStatus::Timeout, 3000.0),
+        outcome("c", Some("import.json"), TaskStatus::Completed, 2000.0),
+        outcome("b", Some("export.csv"), TaskStatus::Failed, 2000.0),
+        outcome("a", Some("import.csv"), TaskStatus::Failed, 1000.0),
+    ] {
+        store.record_outcome(recorded).await.unwrap();
+    }
+    let ids = |outcomes: Vec<TaskOutcome>| -> Vec<String> {
+        outcomes.into_iter().map(|o| o.task_id).collect()
+    };
+
+    let all = OutcomeQuery {
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(store.list_outcomes(&all).await.unwrap()),
+        ["a", "b", "c", "d"]
+    );
+
+    let failed_imports = OutcomeQuery {
+        statuses: Some(vec![TaskStatus::Failed]),
+        types: Some(vec!["import.*".to_string()]),
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(store.list_outcomes(&failed_imports).await.unwrap()),
+        ["a"]
+    );
+
+    let since = OutcomeQuery {
+        since: Some(2000.0),
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(store.list_outcomes(&since).await.unwrap()),
+        ["b", "c", "d"]
+    );
+
+    let next_page = OutcomeQuery {
+        after: Some(OutcomeCursor {
+            completed_at: 2000.0,
+            task_id: "b".to_string(),
+        }),
+        limit: 1,
+        ..Default::default()
+    };
+    assert_eq!(ids(store.list_outcomes(&next_page).await.unwrap()), ["c"]);
+
+    assert_eq!(store.trim_outcomes(2000.0).await.unwrap(), 1);
+    assert_eq!(
+        ids(store.list_outcomes(&all).await.unwrap()),
+        ["b", "c", "d"]
+    );
+}
+
+// ── Event Type Counts ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn event_type_counts_track_appends_and_deletions() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+
+    for index in 0..3 {
+        store.append_event("t1", make_event("t1", index)).await.unwrap();
+    }
+    let mut error = make_event("t1", 3);
+    error.r#type = "tool.error".to_string();
+    error.level = Level::Error;
+    store.append_event("t1", error).await.unwrap();
+
+    let counts = store.get_event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types["llm.delta"], 3);
+    assert_eq!(counts.types["tool.error"], 1);
+    assert_eq!(counts.levels[&Level::Info], 3);
+    assert_eq!(counts.levels[&Level::Error], 1);
+
+    store
+        .delete_events("t1", &["evt-t1-0".to_string(), "evt-t1-3".to_string()])
+        .await
+        .unwrap();
+    let counts = store.get_event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types.len(), 1);
+    assert_eq!(counts.types["llm.delta"], 2);
+    assert!(!counts.levels.contains_key(&Level::Error));
+
+    ShortTermStore::delete_task(&store, "t1").await.unwrap();
+    assert!(store.get_event_type_counts("t1").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn event_type_counts_follow_latest_series_replacements() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let phase = |index: u64, r#type: &str| TaskEvent {
+        r#type: r#type.to_string(),
+        series_id: Some("phase".to_string()),
+        series_mode: Some(SeriesMode::Latest),
+        ..make_event("t1", index)
+    };
+
+    store
+        .replace_last_series_event("t1", "phase", phase(0, "phase.plan"))
+        .await
+        .unwrap();
+    store
+        .replace_last_series_event("t1", "phase", phase(1, "phase.plan"))
+        .await
+        .unwrap();
+    let counts = store.get_event_type_counts("t1").await.unwrap();
+    assert_eq!(counts.types["phase.plan"], 1);
+
+    store
+        .replace_last_series_event("t1", "phase", phase(2, "phase.execute"))
+        .await
+        .unwrap();
+    let counts = store.get_event_type_counts("t1").await.unwrap();
+    assert!(!counts.types.contains_key("phase.plan"));
+    assert_eq!(counts.types["phase.execute"], 1);
+    assert_eq!(counts.levels[&Level::Info], 1);
+}
+
+// ── Value Format ────────────────────────────────────────────────────────────
+
+async fn raw_conn(redis_url: &str) -> redis::aio::MultiplexedConnection {
+    let client = redis::Client::open(redis_url).unwrap();
+    client.get_multiplexed_async_connection().await.unwrap()
+}
+
+/// The JSON stored under `key`, as written.
+async fn raw_value(conn: &mut redis::aio::MultiplexedConnection, key: &str) -> serde_json::Value {
+    let raw: String = redis::cmd("GET").arg(key).query_async(conn).await.unwrap();
+    serde_json::from_str(&raw).unwrap()
+}
+
+#[tokio::test]
+async fn values_written_before_envelopes_are_read_and_updated() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let store = make_store(&redis_url).await;
+    let mut conn = raw_conn(&redis_url).await;
+    let task = make_task("t1");
+    redis::cmd("SET")
+        .arg("test:task:t1")
+        .arg(serde_json::to_string(&task).unwrap())
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+    redis::cmd("SADD")
+        .arg("test:tasks")
+        .arg("t1")
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+    redis::cmd("RPUSH")
+        .arg("test:events:t1")
+        .arg(serde_json::to_string(&make_event("t1", 0)).unwrap())
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+    store.append_event("t1", make_event("t1", 1)).await.unwrap();
+
+    assert_eq!(store.get_task("t1").await.unwrap(), Some(task.clone()));
+    let events = store.get_events("t1", None).await.unwrap();
+    assert_eq!(events, vec![make_event("t1", 0), make_event("t1", 1)]);
+
+    // The compare-and-set and claim scripts read the bare task too.
+    let saved = Task {
+        version: 1,
+        ..task.clone()
+    };
+    assert!(store.save_task_versioned(saved, 0).await.unwrap());
+    store.save_worker(make_worker("w1")).await.unwrap();
+    assert!(store.claim_task("t1", "w1", 1).await.unwrap());
+    let claimed = store.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(claimed.status, TaskStatus::Assigned);
+    assert_eq!(claimed.version, 2);
+}
+
+#[tokio::test]
+async fn store_writes_the_configured_value_format() {
+    let (_container, redis_url) = start_redis().await;
+    flush_redis(&redis_url).await;
+    let mut conn = raw_conn(&redis_url).await;
+
+    let latest = make_store(&redis_url).await;
+    latest.save_task(make_task("t1")).await.unwrap();
+    let value = raw_value(&mut conn, "test:task:t1").await;
+    assert_eq!(value["v"], serde_json::json!(2));
+    assert_eq!(value["d"]["id"], "t1");
+
+    let previous = make_store(&redis_url).await.with_value_format_version(1);
+    previous.save_task(make_task("t1")).await.unwrap();
+    let value = raw_value(&mut conn, "test:task:t1").await;
+    assert_eq!(value["id"], "t1");
+    assert!(value.get("v").is_none());
+
+    // Accumulated series values keep the writer's format.
+    let first = make_accumulate_event("t1", 0, "delta", serde_json::json!("a"));
+    latest
+        .accumulate_series("t1", "s", first, "delta")
+        .await
+        .unwrap();
+    let second = make_accumulate_event("t1", 1, "delta", serde_json::json!("b"));
+    let result = latest
+        .accumulate_series("t1", "s", second, "delta")
+        .await
+        .unwrap();
+    assert_eq!(result.data, serde_json::json!({"delta": "ab"}));
+    let stored = raw_value(&mut conn, "test:series:t1:s").await;
+    assert_eq!(stored["d"]["data"]["delta"], "ab");
+}
@@ -0,0 +1,182 @@
+//! All-or-nothing task groups on `RedisShortTermStore`, through the engine.
+//!
+//! Run with: `cargo test -p taskcast-redis --test task_groups`
+//! Set `TASKCAST_TEST_SKIP_DOCKER=1` to skip them without Docker.
+
+use std::sync::Arc;
+
+use redis::AsyncCommands;
+use taskcast_core::{
+    CompletionMode, CompletionPolicy, CreateTaskGroupInput, CreateTaskInput, EngineError,
+    MemoryBroadcastProvider, TaskEngine, TaskEngineOptions, TaskFilter,
+};
+use taskcast_test_backends::{make_redis_store, redis_url, unique_prefix};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+struct Context {
+    engine: Arc<TaskEngine>,
+    conn: redis::aio::MultiplexedConnection,
+    prefix: String,
+}
+
+async fn setup(label: &str) -> Option<Context> {
+    let prefix = unique_prefix(label);
+    let store = make_redis_store(&prefix).await?;
+    let client = redis::Client::open(redis_url().await?).unwrap();
+    let conn = client.get_multiplexed_async_connection().await.unwrap();
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(store),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    Some(Context {
+        engine,
+        conn,
+        prefix,
+    })
+}
+
+fn task(id: &str) -> CreateTaskInput {
+    CreateTaskInput {
+        id: Some(id.to_string()),
+        ..Default::default()
+    }
+}
+
+/// `deploy` with children `build`, `test` and `ship`.
+fn deploy_group() -> CreateTaskGroupInput {
+    CreateTaskGroupInput {
+        parent: CreateTaskInput {
+            completion_policy: Some(CompletionPolicy {
+                mode: CompletionMode::AllChildren,
+                child_ids: Vec::new(),
+            }),
+            ..task("deploy")
+        },
+        children: vec![task("build"), task("test"), task("ship")],
+        link_children: true,
+    }
+}
+
+/// Ids with a task key under the test's prefix.
+async fn task_keys(ctx: &mut Context) -> Vec<String> {
+    let pattern = format!("{}:task:*", ctx.prefix);
+    let mut keys: Vec<String> = ctx.conn.keys(&pattern).await.unwrap();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| key.rsplit(':').next().unwrap().to_string())
+        .collect()
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn group_is_written_whole_with_children_linked() {
+    let Some(mut ctx) = setup("group-created").await else {
+        return;
+    };
+
+    let group = ctx.engine.create_task_group(deploy_group()).await.unwrap();
+
+    assert_eq!(
+        group.parent.completion_policy.unwrap().child_ids,
+        vec!["build", "test", "ship"]
+    );
+    for id in ["build", "test", "ship"] {
+        let child = ctx.engine.get_task(id).await.unwrap().unwrap();
+        assert_eq!(child.parent_id.as_deref(), Some("deploy"));
+    }
+    assert_eq!(
+        task_keys(&mut ctx).await,
+        vec!["build", "deploy", "ship", "test"]
+    );
+    let listed = ctx
+        .engine
+        .short_term_store()
+        .list_tasks(TaskFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 4);
+}
+
+#[tokio::test]
+async fn failed_write_leaves_no_task_behind() {
+    let Some(mut ctx) = setup("group-failed").await else {
+        return;
+    };
+    // A tasks set of the wrong type fails the group's first write.
+    let tasks_set = format!("{}:tasks", ctx.prefix);
+    ctx.conn
+        .set::<_, _, ()>(&tasks_set, "not a set")
+        .await
+        .unwrap();
+
+    let err = ctx
+        .engine
+        .create_task_group(deploy_group())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, EngineError::Store(_)), "{err}");
+    assert!(task_keys(&mut ctx).await.is_empty());
+}
+
+#[tokio::test]
+async fn reserved_child_id_creates_nothing() {
+    let Some(mut ctx) = setup("group-reserved").await else {
+        return;
+    };
+    // Another instance is midway through creating `test`, the second child.
+    let reservation = format!("{}:reserve:test", ctx.prefix);
+    ctx.conn
+        .set::<_, _, ()>(&reservation, "other-creator")
+        .await
+        .unwrap();
+
+    let err = ctx
+        .engine
+        .create_task_group(deploy_group())
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, EngineError::TaskAlreadyExists { ref task_id, .. } if task_id == "test"),
+        "{err}"
+    );
+    assert!(task_keys(&mut ctx).await.is_empty());
+}
+
+#[tokio::test]
+async fn concurrent_identical_groups_do_not_interleave() {
+    let Some(ctx) = setup("group-concurrent").await else {
+        return;
+    };
+
+    let attempts = (0..8).map(|attempt| {
+        let engine = Arc::clone(&ctx.engine);
+        tokio::spawn(async move {
+            let mut input = deploy_group();
+            for member in std::iter::once(&mut input.parent).chain(&mut input.children) {
+                member.metadata = Some([("attempt".to_string(), attempt.into())].into());
+            }
+            engine.create_task_group(input).await
+        })
+    });
+    let results = futures::future::join_all(attempts).await;
+
+    let created: Vec<_> = results
+        .into_iter()
+        .filter_map(|result| result.unwrap().ok())
+        .collect();
+    assert_eq!(created.len(), 1);
+    let winner = &created[0].parent.metadata.as_ref().unwrap()["attempt"];
+    for id in ["deploy", "build", "test", "ship"] {
+        let task = ctx.engine.get_task(id).await.unwrap().unwrap();
+        assert_eq!(&task.metadata.unwrap()["attempt"], winner, "{id}");
+    }
+}
@@ -0,0 +1,122 @@
+//! Compatibility tests for the value format of stored tasks and events.
+//!
+//! `tests/fixtures/value_format/v{N}` holds a task and an event as version N
+//! wrote them. Every version must decode to what the current one does, and
+//! the current fixtures must match what is written today, so a change to how
+//! tasks or events serialize fails here until the format version is bumped.
+//!
+//! Run with: `cargo test -p taskcast-redis --test value_format`
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+use taskcast_core::CorruptRecordKind;
+use taskcast_redis::codec::{
+    decode_event, decode_task, encode_value, MIN_VALUE_FORMAT_VERSION, VALUE_FORMAT_VERSION,
+};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn fixture(version: u64, name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/value_format")
+        .join(format!("v{version}"))
+        .join(format!("{name}.json"));
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("missing fixture {}: {err}", path.display()))
+}
+
+fn json(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap()
+}
+
+// ── Fixtures ────────────────────────────────────────────────────────────────
+
+#[test]
+fn every_version_has_fixtures() {
+    for version in MIN_VALUE_FORMAT_VERSION..=VALUE_FORMAT_VERSION {
+        fixture(version, "task");
+        fixture(version, "event");
+    }
+}
+
+#[test]
+fn tasks_from_every_version_decode_alike() {
+    let current = decode_task("t1", fixture(VALUE_FORMAT_VERSION, "task")).unwrap();
+    for version in MIN_VALUE_FORMAT_VERSION..VALUE_FORMAT_VERSION {
+        let task = decode_task("t1", fixture(version, "task")).unwrap();
+        assert_eq!(task, current, "v{version}");
+    }
+}
+
+#[test]
+fn events_from_every_version_decode_alike() {
+    let current = decode_event("t1", 0, fixture(VALUE_FORMAT_VERSION, "event")).unwrap();
+    for version in MIN_VALUE_FORMAT_VERSION..VALUE_FORMAT_VERSION {
+        let event = decode_event("t1", 0, fixture(version, "event")).unwrap();
+        assert_eq!(event, current, "v{version}");
+    }
+}
+
+#[test]
+fn current_fixtures_match_what_is_written() {
+    let raw = fixture(VALUE_FORMAT_VERSION, "task");
+    let task = decode_task("t1", &raw).unwrap();
+    assert_eq!(
+        json(&encode_value(&task, VALUE_FORMAT_VERSION).unwrap()),
+        json(&raw)
+    );
+
+    let raw = fixture(VALUE_FORMAT_VERSION, "event");
+    let event = decode_event("t1", 0, &raw).unwrap();
+    assert_eq!(
+        json(&encode_value(&event, VALUE_FORMAT_VERSION).unwrap()),
+        json(&raw)
+    );
+}
+
+#[test]
+fn older_versions_are_written_as_their_fixtures() {
+    let task = decode_task("t1", fixture(VALUE_FORMAT_VERSION, "task")).unwrap();
+    let event = decode_event("t1", 0, fixture(VALUE_FORMAT_VERSION, "event")).unwrap();
+    for version in MIN_VALUE_FORMAT_VERSION..VALUE_FORMAT_VERSION {
+        assert_eq!(
+            json(&encode_value(&task, version).unwrap()),
+            json(&fixture(version, "task")),
+            "v{version}"
+        );
+        assert_eq!(
+            json(&encode_value(&event, version).unwrap()),
+            json(&fixture(version, "event")),
+            "v{version}"
+        );
+    }
+}
+
+// ── Newer And Corrupt Values ────────────────────────────────────────────────
+
+#[test]
+fn newer_versions_decode_ignoring_unknown_fields() {
+    let mut value = json(&fixture(VALUE_FORMAT_VERSION, "task"));
+    value["v"] = Value::from(VALUE_FORMAT_VERSION + 1);
+    value["d"]["priority"] = Value::from(5);
+    let task = decode_task("t1", value.to_string()).unwrap();
+    assert_eq!(
+        task,
+        decode_task("t1", fixture(VALUE_FORMAT_VERSION, "task")).unwrap()
+    );
+}
+
+#[test]
+fn invalid_envelopes_are_corrupt_records() {
+    let err = decode_task("t1", r#"{"v":"two","d":{}}"#).unwrap_err();
+    assert_eq!(err.kind, CorruptRecordKind::Task);
+    assert_eq!(err.task_id, "t1");
+
+    let raw = r#"{"v":2,"d":{"id":"evt-1","index":4,"level":"loud"}}"#;
+    let err = decode_event("t1", 9, raw).unwrap_err();
+    assert_eq!(err.kind, CorruptRecordKind::Event);
+    assert_eq!(err.id.as_deref(), Some("evt-1"));
+    assert_eq!(err.position, Some(4));
+    assert_eq!(*err.raw, Value::String(raw.to_string()));
+}
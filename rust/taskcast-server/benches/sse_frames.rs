@@ -0,0 +1,88 @@
+//! Cost of building one live event's SSE payloads for every connection on a
+//! task, serializing each connection's envelope versus sharing the task's
+//! frame cache. Bytes serialized per event are printed for each subscriber
+//! count.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::json;
+use taskcast_core::{Level, SSEEnvelope};
+use taskcast_server::frame_cache::{event_payload, FrameCache};
+
+const SUBSCRIBERS: [u64; 3] = [1, 100, 1000];
+
+fn envelope(filtered_index: u64) -> SSEEnvelope {
+    SSEEnvelope {
+        filtered_index,
+        raw_index: 42,
+        event_id: "01JBENCH0000000000000000042".to_string(),
+        task_id: "bench".to_string(),
+        r#type: "llm.delta".to_string(),
+        timestamp: 1700000000000.0,
+        level: Level::Info,
+        data: json!({
+            "role": "assistant",
+            "delta": "Checking the deployment of service-42 in region eu-west-1; all replicas healthy. ".repeat(4),
+            "usage": { "promptTokens": 1242, "completionTokens": 800 },
+        }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        filter_epoch: None,
+    }
+}
+
+fn sse_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sse_frames");
+    for subscribers in SUBSCRIBERS {
+        let per_connection: usize = (0..subscribers)
+            .map(|n| {
+                serde_json::to_string(&event_payload(envelope(n), true))
+                    .unwrap()
+                    .len()
+            })
+            .sum();
+        let cache = Arc::new(FrameCache::new());
+        let leases: Vec<_> = (0..subscribers)
+            .map(|_| cache.open("bench", true, None))
+            .collect();
+        for (n, lease) in leases.iter().enumerate() {
+            lease.payload(envelope(n as u64));
+        }
+        println!(
+            "{subscribers} subscribers: {per_connection} bytes serialized per event per connection, {} shared",
+            cache.stats().serialized_bytes
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("per_connection", subscribers),
+            &subscribers,
+            |b, &subscribers| {
+                b.iter(|| {
+                    for n in 0..subscribers {
+                        serde_json::to_string(&event_payload(envelope(n), true)).unwrap();
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("shared", subscribers),
+            &leases,
+            |b, leases| {
+                b.iter(|| {
+                    for (n, lease) in leases.iter().enumerate() {
+                        lease.payload(envelope(n as u64));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sse_frames);
+criterion_main!(benches);
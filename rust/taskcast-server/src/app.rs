@@ -1,29 +1,60 @@
 use std::sync::Arc;
 use std::time::Instant;
 
-use axum::extract::State as AxumState;
+use axum::extract::{Request, State as AxumState};
+use axum::http::Method;
 use axum::middleware;
-use axum::response::IntoResponse;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, patch, post};
 use axum::{Extension, Router};
 use taskcast_core::config::TaskcastConfig;
 use taskcast_core::heartbeat_monitor::{HeartbeatMonitor, HeartbeatMonitorOptions};
+use taskcast_core::outcomes::{
+    OutcomeTrimmer, OutcomeTrimmerOptions, DEFAULT_OUTCOME_RETENTION_MS,
+};
+use taskcast_core::activation::{SchedulerRunner, SchedulerRunnerOptions};
+use taskcast_core::cleanup::{AbandonedPendingSweeper, AbandonedPendingSweeperOptions};
+use taskcast_core::retry::{RetryRunner, RetryRunnerOptions};
 use taskcast_core::scheduler::{TaskScheduler, TaskSchedulerOptions};
 use taskcast_core::state_machine::is_terminal;
 use taskcast_core::worker_manager::{DispatchResult, WorkerManager};
 use taskcast_core::{
-    AssignMode, ConnectionMode, DisconnectPolicy, ShortTermStore, Task, TaskEngine, TaskStatus,
-    WorkerStatus,
+    AssignMode, ConnectionMode, DisconnectPolicy, ReplayBudget, RoleSwitch,
+    ShortTermStore, StorageManager, Task, TaskEngine, TaskStatus, WorkerStatus,
 };
 use tower_http::cors::{Any, CorsLayer};
 use utoipa::OpenApi;
 use utoipa_scalar::{Scalar, Servable};
 
-use crate::auth::{auth_middleware, AuthMode};
+use crate::auth::{
+    auth_middleware, query_token_middleware, scope_rules_middleware, AuthMode, ScopeRules,
+};
+use crate::bulk::BulkLimits;
+use crate::debug_bundle::{DebugBundleOptions, DEBUG_BUNDLE_PATH};
+use crate::error::ErrorMessageProvider;
+use crate::event_links::{event_link_middleware, EventLinks};
+use crate::http_tap::{HttpTap, HTTP_TAP_PATH};
+use crate::export::ExportLimits;
+use crate::frame_cache::FrameCache;
+use crate::ingest::IngestLimits;
 use crate::openapi::ApiDoc;
-use crate::routes::sse::create_subscriber_counts;
+use crate::request_deadline::{self, RequestDeadlines};
+use crate::routes::replication::ReplicatedWrites;
+use crate::routes::sse::{create_subscriber_counts, SseHeartbeat};
+use crate::routes::templates::templates_router;
 use crate::routes::worker_ws::{task_to_summary, WorkerCommand, WsRegistry};
-use crate::routes::{admin, sse, tasks};
+use crate::routes::{admin, conventions, outcomes, replication, series, sse, tasks, view};
+use crate::runtime_info::{RuntimeInfo, RUNTIME_INFO_PATH};
+use crate::runtime_metrics::{RuntimeSampler, RUNTIME_METRICS_PATH};
+use crate::standby::{read_only_response, standby_gate};
+use crate::strict::BodyStrictness;
+use crate::templates::TemplateRegistry;
+use crate::versioning::{
+    api_version_middleware, default_api_version_middleware, legacy_path_middleware, ApiVersion,
+    ApiVersions, API_V1,
+};
+use crate::webhook::{DeliveryLimits, SyncWebhooks, WebhookDelivery, WebhookDispatcher};
 
 /// Shared application state available to all handlers.
 #[derive(Clone)]
@@ -32,6 +63,8 @@ pub struct AppState {
     pub auth_mode: Arc<AuthMode>,
     pub start_time: Instant,
     pub config: Option<Arc<TaskcastConfig>>,
+    /// Optional hook for localizing or rewriting error messages by code.
+    pub error_messages: Option<Arc<dyn ErrorMessageProvider>>,
 }
 
 /// Create the Axum router with all taskcast routes mounted.
@@ -57,84 +90,282 @@ pub fn create_app(
     config: Option<TaskcastConfig>,
     cors_config: CorsConfig,
 ) -> (Router, Option<WsRegistry>) {
-    create_app_with_failure_logger(
+    create_app_with_options(
         engine,
         auth_mode,
-        worker_manager,
-        config,
-        cors_config,
-        Arc::new(crate::http_failure::StderrHttpFailureLogger::new(
-            crate::http_failure::LogLevel::Info,
-        )),
+        AppOptions {
+            worker_manager,
+            config,
+            cors_config,
+            ..Default::default()
+        },
     )
 }
 
-pub fn create_app_with_failure_logger(
+/// Everything [`create_app_with_options`] can be given besides the engine
+/// and auth mode. Unset fields take the defaults described on each, so
+/// callers name only what they supply:
+///
+/// ```ignore
+/// let (app, _) = create_app_with_options(engine, AuthMode::None, AppOptions {
+///     storage: Some(storage),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Default)]
+pub struct AppOptions {
+    /// Mounts the worker routes and returns a [`WsRegistry`].
+    pub worker_manager: Option<Arc<WorkerManager>>,
+    pub config: Option<TaskcastConfig>,
+    pub cors_config: CorsConfig,
+    /// Where failed requests are logged. Defaults to stderr at `info`.
+    pub failure_logger: Option<Arc<dyn crate::http_failure::HttpFailureLogger>>,
+    /// Routes merged into the app beside the taskcast ones.
+    pub additional_routes: Router,
+    /// Rewrites error messages by code.
+    pub error_messages: Option<Arc<dyn ErrorMessageProvider>>,
+    /// Delivers webhooks and backs `/admin/webhooks/circuits` and
+    /// `/admin/webhooks/concurrency`. Without one, a delivery is built from
+    /// the `webhook` section of `config`.
+    pub webhook_delivery: Option<Arc<WebhookDelivery>>,
+    /// Mounts `GET /admin/storage` and `POST /tasks/{task_id}/archive`.
+    pub storage: Option<Arc<StorageManager>>,
+    /// Records exchanges and mounts `GET /admin/http-tap` to read them.
+    pub http_tap: Option<Arc<HttpTap>>,
+    /// The adapter description served by `GET /admin/info`. Without one,
+    /// the adapters are described from `config`.
+    pub runtime_info: Option<Arc<RuntimeInfo>>,
+    /// The templates `POST /tasks` and `/templates` use. Without a
+    /// registry, one is built from the `templates` section of `config`.
+    pub templates: Option<Arc<TemplateRegistry>>,
+    /// The sampler `GET /admin/runtime` reads. Without one, each request
+    /// samples the runtime on the spot.
+    pub runtime_sampler: Option<Arc<RuntimeSampler>>,
+    /// The API versions to mount. Without them, only v1 is mounted, with
+    /// its legacy aliases deprecated as the `api` section of `config` says.
+    pub api_versions: Option<ApiVersions>,
+    /// The role switch writes and background runners follow, for an
+    /// instance whose role changes at runtime (see
+    /// [`LeaderElection`](taskcast_core::LeaderElection)). Without one, the
+    /// instance starts as `server.role` in `config` says and changes only
+    /// through `POST /admin/promote`.
+    pub role: Option<RoleSwitch>,
+}
+
+/// Like [`create_app`], with every optional part of the app given through
+/// [`AppOptions`].
+pub fn create_app_with_options(
     engine: Arc<TaskEngine>,
     auth_mode: AuthMode,
-    worker_manager: Option<Arc<WorkerManager>>,
-    config: Option<TaskcastConfig>,
-    cors_config: CorsConfig,
-    failure_logger: Arc<dyn crate::http_failure::HttpFailureLogger>,
+    options: AppOptions,
 ) -> (Router, Option<WsRegistry>) {
-    create_app_with_failure_logger_and_routes(
-        engine,
-        auth_mode,
+    let AppOptions {
         worker_manager,
         config,
         cors_config,
         failure_logger,
-        Router::new(),
-    )
-}
-
-pub fn create_app_with_failure_logger_and_routes(
-    engine: Arc<TaskEngine>,
-    auth_mode: AuthMode,
-    worker_manager: Option<Arc<WorkerManager>>,
-    config: Option<TaskcastConfig>,
-    cors_config: CorsConfig,
-    failure_logger: Arc<dyn crate::http_failure::HttpFailureLogger>,
-    additional_routes: Router,
-) -> (Router, Option<WsRegistry>) {
+        additional_routes,
+        error_messages,
+        webhook_delivery,
+        storage,
+        http_tap,
+        runtime_info,
+        templates,
+        runtime_sampler,
+        api_versions,
+        role,
+    } = options;
+    let failure_logger = failure_logger.unwrap_or_else(|| {
+        Arc::new(crate::http_failure::StderrHttpFailureLogger::new(
+            crate::http_failure::LogLevel::Info,
+        ))
+    });
+    let role = role.unwrap_or_else(|| {
+        let configured = config
+            .as_ref()
+            .and_then(|c| c.server.as_ref())
+            .and_then(|server| server.role)
+            .unwrap_or_default();
+        RoleSwitch::new(configured, engine.runners().clone())
+    });
+    let api_versions = api_versions
+        .unwrap_or_else(|| ApiVersions::from_config(config.as_ref().and_then(|c| c.api.as_ref())));
+    let templates =
+        templates.unwrap_or_else(|| Arc::new(TemplateRegistry::from_config(config.as_ref())));
+    let event_links = Arc::new(EventLinks::from_config(config.as_ref()));
+    let sync_webhooks = Arc::new(SyncWebhooks::new(
+        WebhookDispatcher::new(
+            webhook_delivery.clone().unwrap_or_else(|| {
+                let webhook_config = config.as_ref().and_then(|c| c.webhook.as_ref());
+                let delivery = WebhookDelivery::new()
+                    .with_payload_limits(
+                        webhook_config.and_then(|w| w.max_payload_bytes),
+                        Arc::clone(&event_links),
+                    )
+                    .with_limits(DeliveryLimits::from_config(webhook_config));
+                Arc::new(match engine.hooks() {
+                    Some(hooks) => delivery.with_hooks(Arc::clone(hooks)),
+                    None => delivery,
+                })
+            }),
+            Arc::clone(engine.short_term_store()),
+        ),
+        config.as_ref().and_then(|c| c.webhook.as_ref()),
+    ));
+    let runtime_info =
+        runtime_info.unwrap_or_else(|| Arc::new(RuntimeInfo::from_config(config.as_ref())));
+    let runtime_sampler =
+        runtime_sampler.unwrap_or_else(|| Arc::new(RuntimeSampler::new(Arc::clone(&engine))));
     let auth_mode = Arc::new(auth_mode);
     let subscriber_counts = create_subscriber_counts();
+    let wait_limits = wait_limits(config.as_ref());
+    let bulk_limits =
+        BulkLimits::from_config(config.as_ref().and_then(|c| c.bulk_operations.as_ref()));
+    let task_group_limits =
+        tasks::TaskGroupLimits::from_config(config.as_ref().and_then(|c| c.task_groups.as_ref()));
+    let replay_budget = ReplayBudget::from_config(config.as_ref().and_then(|c| c.sse.as_ref()));
+    let heartbeat = SseHeartbeat::from_config(config.as_ref().and_then(|c| c.sse.as_ref()));
+    let ingest_limits = IngestLimits::from_config(config.as_ref().and_then(|c| c.ingest.as_ref()));
+    let export_limits = ExportLimits::from_config(config.as_ref().and_then(|c| c.export.as_ref()));
+    let deadline_aware = middleware::from_fn_with_state(
+        RequestDeadlines::from_config(
+            config.as_ref().and_then(|c| c.http.as_ref()),
+            Arc::clone(engine.request_counters()),
+        ),
+        request_deadline::deadline_aware,
+    );
+    let ui_enabled = config
+        .as_ref()
+        .and_then(|c| c.ui.as_ref())
+        .and_then(|ui| ui.enabled)
+        .unwrap_or(false);
+    let replicated_writes = ReplicatedWrites {
+        engine: Arc::clone(&engine),
+        allowed: config
+            .as_ref()
+            .and_then(|c| c.replication.as_ref())
+            .and_then(|r| r.allow_replicated_writes)
+            .unwrap_or(false),
+    };
+    let task_validation = Arc::new(tasks::task_validation_options(
+        config.as_ref().and_then(|c| c.http.as_ref()),
+        &auth_mode,
+    ));
+    let body_strictness =
+        BodyStrictness::from_config(config.as_ref().and_then(|c| c.http.as_ref()));
+    let scope_rules = ScopeRules::from_config(config.as_ref().and_then(|c| c.auth.as_ref()));
+    let debug_bundle_options = DebugBundleOptions::from_config(
+        config
+            .as_ref()
+            .and_then(|c| c.debug.as_ref())
+            .and_then(|d| d.bundle.as_ref()),
+    );
 
     let app_state = AppState {
         engine: Arc::clone(&engine),
         auth_mode: Arc::clone(&auth_mode),
         start_time: Instant::now(),
         config: config.as_ref().map(|c| Arc::new(c.clone())),
+        error_messages,
+    };
+
+    let archive_route = match storage {
+        Some(ref storage) => get(tasks::export_task_archive)
+            .post(tasks::save_task_archive)
+            .layer(Extension(Arc::clone(storage))),
+        None => get(tasks::export_task_archive),
     };
 
+    let task_group_route = post(tasks::create_task_group)
+        .layer((
+            Extension(task_group_limits),
+            Extension(Arc::clone(&templates)),
+            Extension(Arc::clone(&sync_webhooks)),
+            Extension(Arc::clone(&task_validation)),
+            Extension(body_strictness),
+        ))
+        .with_state(Arc::clone(&engine));
+
     let task_routes = Router::new()
-        .route("/", get(tasks::list_tasks).post(tasks::create_task))
+        .route(
+            "/",
+            post(tasks::create_task)
+                .layer(middleware::from_fn_with_state(
+                    replicated_writes.clone(),
+                    replication::replicated_create,
+                ))
+                .merge(get(tasks::list_tasks).layer(deadline_aware.clone())),
+        )
+        .route(
+            "/export",
+            get(tasks::export_tasks).layer(deadline_aware.clone()),
+        )
         .route("/import", post(tasks::import_task_archive))
-        .route("/{task_id}/archive", get(tasks::export_task_archive))
+        .route("/{task_id}/archive", archive_route)
+        .route("/{task_id}/integrity", get(tasks::get_task_integrity))
+        .route("/{task_id}/attempts", get(tasks::get_task_attempts))
         .route("/{task_id}", get(tasks::get_task))
+        .route("/{task_id}/wait", get(tasks::wait_for_task))
+        .route("/{task_id}/transitions", get(tasks::get_task_transitions))
         .route("/{task_id}/status", patch(tasks::transition_task))
+        .route("/{task_id}/cancel", post(tasks::cancel_task))
         .route("/{task_id}/resolve", post(tasks::resolve_task))
         .route("/{task_id}/request", get(tasks::get_blocked_request))
         .route(
             "/{task_id}/events",
-            post(tasks::publish_events).get(sse::sse_events),
+            post(tasks::publish_events)
+                .layer(middleware::from_fn_with_state(
+                    replicated_writes,
+                    replication::replicated_events,
+                ))
+                .get(sse::sse_events)
+                .delete(tasks::delete_events),
         )
-        .route("/{task_id}/events/history", get(tasks::get_event_history))
+        .route(
+            "/{task_id}/events/stream",
+            post(tasks::publish_events_stream),
+        )
+        .route(
+            "/{task_id}/events/history",
+            get(tasks::get_event_history).layer(deadline_aware.clone()),
+        )
+        .route(
+            "/{task_id}/events/stats",
+            get(tasks::get_event_stats).layer(deadline_aware),
+        )
+        .route("/{task_id}/series", get(series::list_series))
+        .route("/{task_id}/events/{event_id}", get(tasks::get_task_event))
         .layer(Extension(subscriber_counts))
+        .layer(Extension(Arc::new(FrameCache::new())))
+        .layer(Extension(replay_budget))
+        .layer(Extension(heartbeat))
+        .layer(Extension(wait_limits))
+        .layer(Extension(Arc::clone(&templates)))
+        .layer(Extension(sync_webhooks))
+        .layer(Extension(task_validation))
+        .layer(Extension(ingest_limits))
+        .layer(Extension(export_limits))
+        .layer(Extension(body_strictness))
         .with_state(Arc::clone(&engine));
 
     let events_route = Router::new()
         .route("/events", get(sse::global_sse_events))
+        .layer(Extension(heartbeat))
         .with_state(Arc::clone(&engine));
 
     // OpenAPI spec and Scalar UI are public so linked docs work in JWT/custom auth modes.
     let openapi_spec = ApiDoc::openapi();
 
     // Public routes bypass auth (health, API root, and docs endpoints)
-    let public_routes = Router::new()
+    let mut public_routes = Router::new()
         .route("/", get(root))
         .route("/health", get(health))
-        .route("/health/detail", get(health_detail).with_state(app_state))
+        .route(
+            "/health/detail",
+            get(health_detail)
+                .layer(Extension(role.clone()))
+                .with_state(app_state.clone()),
+        )
         .route(
             "/openapi.json",
             get({
@@ -143,11 +374,127 @@ pub fn create_app_with_failure_logger_and_routes(
             }),
         )
         .merge(Scalar::with_url("/docs", openapi_spec));
+    // The viewer pages carry no task data; the streams they open are
+    // authenticated as usual.
+    if ui_enabled {
+        public_routes = public_routes
+            .route("/view", get(view::view_index))
+            .route("/tasks/{task_id}/view", get(view::view_task));
+    }
 
     // Authenticated routes (tasks, events, workers, etc.)
     let mut authenticated_routes = Router::new()
         .nest("/tasks", task_routes)
-        .merge(events_route);
+        .route("/task-groups", task_group_route)
+        .merge(events_route)
+        .route(
+            "/outcomes",
+            get(outcomes::list_outcomes).with_state(Arc::clone(&engine)),
+        )
+        .nest("/templates", templates_router().with_state(templates))
+        .nest(
+            "/conventions",
+            conventions::conventions_router().with_state(Arc::clone(&engine)),
+        )
+        .route(
+            RUNTIME_INFO_PATH,
+            get(admin::get_runtime_info)
+                .layer(Extension(Arc::clone(&runtime_info)))
+                .with_state(app_state.clone()),
+        )
+        .route(
+            RUNTIME_METRICS_PATH,
+            get(admin::get_runtime_metrics).with_state(Arc::clone(&runtime_sampler)),
+        )
+        .route(
+            DEBUG_BUNDLE_PATH,
+            get(admin::get_debug_bundle).with_state(Arc::new(admin::DebugBundleState {
+                app: app_state.clone(),
+                runtime_info,
+                http_tap: http_tap.clone(),
+                runtime_sampler,
+                options: Arc::new(debug_bundle_options),
+            })),
+        )
+        .route(
+            "/admin/tasks/bulk",
+            post(admin::bulk_tasks)
+                .layer((Extension(bulk_limits), Extension(body_strictness)))
+                .with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/consistency",
+            post(admin::scan_stores).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/flags",
+            get(admin::list_flags).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/runners",
+            get(admin::list_runners).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/runners/{name}/run-now",
+            post(admin::run_runner_now).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/runners/{name}/pause",
+            post(admin::pause_runner).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/runners/{name}/resume",
+            post(admin::resume_runner).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/store/migration",
+            get(admin::get_store_migration)
+                .put(admin::set_store_migration)
+                .with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/store/migration/backfill",
+            post(admin::backfill_store).with_state(app_state.clone()),
+        )
+        .route(
+            "/admin/store/explain/{task_id}",
+            get(admin::explain_get_events).with_state(app_state.clone()),
+        );
+
+    if let Some(delivery) = webhook_delivery {
+        authenticated_routes = authenticated_routes.merge(
+            Router::new()
+                .route(
+                    "/admin/webhooks/circuits",
+                    get(admin::list_webhook_circuits),
+                )
+                .route(
+                    "/admin/webhooks/circuits/{host}/reset",
+                    post(admin::reset_webhook_circuit),
+                )
+                .route(
+                    "/admin/webhooks/concurrency",
+                    get(admin::get_webhook_concurrency),
+                )
+                .with_state(delivery),
+        );
+    }
+
+    if let Some(storage) = storage {
+        authenticated_routes = authenticated_routes.merge(
+            Router::new()
+                .route("/admin/storage", get(admin::get_storage_usage))
+                .with_state(storage),
+        );
+    }
+
+    if let Some(ref tap) = http_tap {
+        authenticated_routes = authenticated_routes.merge(
+            Router::new()
+                .route(HTTP_TAP_PATH, get(admin::list_http_tap))
+                .with_state(Arc::clone(tap)),
+        );
+    }
 
     // Conditionally mount worker routes if a WorkerManager is provided
     let mut ws_registry_out: Option<WsRegistry> = None;
@@ -158,11 +505,12 @@ pub fn create_app_with_failure_logger_and_routes(
         // Auto-release worker capacity on terminal transitions
         {
             let wm_clone = Arc::clone(&manager);
+            let background = engine.background().clone();
             engine.add_transition_listener(Box::new(move |task, _from, to| {
                 if is_terminal(to) {
                     let wm = Arc::clone(&wm_clone);
                     let task_id = task.id.clone();
-                    tokio::spawn(async move {
+                    background.spawn("worker.auto_release", Some(task_id.clone()), async move {
                         auto_release_worker(&wm, &task_id).await;
                     });
                 }
@@ -173,6 +521,7 @@ pub fn create_app_with_failure_logger_and_routes(
         {
             let wm_clone = Arc::clone(&manager);
             let registry_clone = ws_registry.clone();
+            let background = engine.background().clone();
             engine.add_transition_listener(Box::new(move |task, _from, to| {
                 if *to != TaskStatus::Pending {
                     return;
@@ -186,17 +535,25 @@ pub fn create_app_with_failure_logger_and_routes(
                         let wm = Arc::clone(&wm_clone);
                         let registry = registry_clone.clone();
                         let task_clone = task.clone();
-                        tokio::spawn(async move {
-                            dispatch_ws_offer(&wm, &registry, &task_clone).await;
-                        });
+                        background.spawn(
+                            "worker.ws_offer",
+                            Some(task_clone.id.clone()),
+                            async move {
+                                dispatch_ws_offer(&wm, &registry, &task_clone).await;
+                            },
+                        );
                     }
                     AssignMode::WsRace => {
                         let wm = Arc::clone(&wm_clone);
                         let registry = registry_clone.clone();
                         let task_clone = task.clone();
-                        tokio::spawn(async move {
-                            dispatch_ws_race(&wm, &registry, &task_clone).await;
-                        });
+                        background.spawn(
+                            "worker.ws_race",
+                            Some(task_clone.id.clone()),
+                            async move {
+                                dispatch_ws_race(&wm, &registry, &task_clone).await;
+                            },
+                        );
                     }
                     _ => {}
                 }
@@ -218,15 +575,37 @@ pub fn create_app_with_failure_logger_and_routes(
         ws_registry_out = Some(ws_registry);
     }
 
+    // Every write route is in by now. Promotion is the one write a standby
+    // takes.
+    let authenticated_routes = authenticated_routes
+        .layer(middleware::from_fn_with_state(role.clone(), standby_gate))
+        .route(
+            "/admin/promote",
+            post(admin::promote_instance).with_state(role),
+        );
+
     // Auth middleware is applied only to authenticated routes, so health
     // and docs endpoints (public_routes) bypass auth — matching the TS implementation.
-    let authenticated_with_auth = authenticated_routes.layer(middleware::from_fn_with_state(
-        Arc::clone(&auth_mode),
-        auth_middleware,
+    let mut authenticated_with_auth = authenticated_routes
+        .layer(middleware::from_fn_with_state(
+            scope_rules,
+            scope_rules_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&auth_mode),
+            auth_middleware,
+        ));
+    if ui_enabled {
+        authenticated_with_auth =
+            authenticated_with_auth.layer(middleware::from_fn(query_token_middleware));
+    }
+    let authenticated_with_auth = authenticated_with_auth.layer(middleware::from_fn_with_state(
+        event_links,
+        event_link_middleware,
     ));
 
-    // Merge public (no auth) + authenticated (with auth)
-    let mut app = public_routes.merge(authenticated_with_auth);
+    // Query type filters are normalized like published types.
+    let mut api = authenticated_with_auth.layer(Extension(engine.event_types()));
 
     // Admin route is merged AFTER the auth layer so it bypasses JWT/custom auth.
     // It authenticates via admin token independently.
@@ -238,17 +617,199 @@ pub fn create_app_with_failure_logger_and_routes(
         let admin_routes = Router::new()
             .route("/admin/token", post(admin::admin_token))
             .with_state(admin_state);
-        app = app.merge(admin_routes);
+        api = api.merge(admin_routes);
+    }
+
+    // The API is mounted once per version, and again at its unprefixed
+    // paths as deprecated v1 aliases. Public routes stay unversioned.
+    let mut app = public_routes;
+    let mut legacy_version = ApiVersion::v1();
+    for version in api_versions.versions {
+        if version.name == API_V1 {
+            legacy_version = version.clone();
+        }
+        app = app.nest(
+            &format!("/{}", version.name),
+            api.clone().layer(middleware::from_fn_with_state(
+                version,
+                api_version_middleware,
+            )),
+        );
+    }
+    let app = app.merge(
+        api.layer(middleware::from_fn_with_state(
+            legacy_version,
+            api_version_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(api_versions.legacy),
+            legacy_path_middleware,
+        )),
+    );
+
+    let app = with_cors(app, cors_config);
+
+    // Merge caller-owned routes before applying the outer observer so every
+    // final 5xx response is logged exactly once.
+    let app = app.merge(additional_routes);
+    let app = app.layer(middleware::from_fn_with_state(
+        app_state,
+        crate::error::error_response_middleware,
+    ));
+    let app = app.layer(middleware::from_fn(default_api_version_middleware));
+    // The tap sits outside the error middleware so it records the response
+    // clients actually received.
+    let app = match http_tap {
+        Some(tap) => app.layer(middleware::from_fn_with_state(
+            tap,
+            crate::http_tap::http_tap_middleware,
+        )),
+        None => app,
+    };
+    let app = app.layer(middleware::from_fn_with_state(
+        failure_logger,
+        crate::http_failure::http_failure_logger_middleware,
+    ));
+
+    (app, ws_registry_out)
+}
+
+/// Create a router serving only reads: tasks, events, their SSE streams,
+/// outcomes and health. Nothing on it can change a task. Methods other than
+/// `GET`, `HEAD` and `OPTIONS` get `405` and unknown paths `404`, both
+/// before authentication, so it can face the public on its own port while
+/// the writable app from [`create_app`] stays internal. Both may share one
+/// engine.
+///
+/// Exports, archives, admin and worker routes are left out, as are signed
+/// event links.
+pub fn create_readonly_app(
+    engine: Arc<TaskEngine>,
+    auth_mode: AuthMode,
+    config: Option<&TaskcastConfig>,
+    cors_config: CorsConfig,
+) -> Router {
+    let api_versions = ApiVersions::from_config(config.and_then(|c| c.api.as_ref()));
+    let auth_mode = Arc::new(auth_mode);
+    let replay_budget = ReplayBudget::from_config(config.and_then(|c| c.sse.as_ref()));
+    let heartbeat = SseHeartbeat::from_config(config.and_then(|c| c.sse.as_ref()));
+    let deadline_aware = middleware::from_fn_with_state(
+        RequestDeadlines::from_config(
+            config.and_then(|c| c.http.as_ref()),
+            Arc::clone(engine.request_counters()),
+        ),
+        request_deadline::deadline_aware,
+    );
+
+    let task_routes = Router::new()
+        .route("/", get(tasks::list_tasks).layer(deadline_aware.clone()))
+        .route("/{task_id}", get(tasks::get_task))
+        .route("/{task_id}/wait", get(tasks::wait_for_task))
+        .route("/{task_id}/transitions", get(tasks::get_task_transitions))
+        .route("/{task_id}/attempts", get(tasks::get_task_attempts))
+        .route("/{task_id}/events", get(sse::sse_events))
+        .route(
+            "/{task_id}/events/history",
+            get(tasks::get_event_history).layer(deadline_aware.clone()),
+        )
+        .route(
+            "/{task_id}/events/stats",
+            get(tasks::get_event_stats).layer(deadline_aware),
+        )
+        .route("/{task_id}/series", get(series::list_series))
+        .route("/{task_id}/events/{event_id}", get(tasks::get_task_event))
+        .layer(Extension(create_subscriber_counts()))
+        .layer(Extension(Arc::new(FrameCache::new())))
+        .layer(Extension(replay_budget))
+        .layer(Extension(heartbeat))
+        .layer(Extension(wait_limits(config)))
+        .with_state(Arc::clone(&engine));
+
+    // Auth is a route layer, so unknown paths are refused before it runs.
+    let api = Router::new()
+        .nest("/tasks", task_routes)
+        .route(
+            "/events",
+            get(sse::global_sse_events).layer(Extension(heartbeat)),
+        )
+        .route("/outcomes", get(outcomes::list_outcomes))
+        .with_state(Arc::clone(&engine))
+        .route_layer(middleware::from_fn_with_state(
+            ScopeRules::from_config(config.and_then(|c| c.auth.as_ref())),
+            scope_rules_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&auth_mode),
+            auth_middleware,
+        ))
+        .layer(Extension(engine.event_types()));
+
+    let mut app = Router::new()
+        .route("/", get(root))
+        .route("/health", get(health));
+    let mut legacy_version = ApiVersion::v1();
+    for version in api_versions.versions {
+        if version.name == API_V1 {
+            legacy_version = version.clone();
+        }
+        app = app.nest(
+            &format!("/{}", version.name),
+            api.clone().layer(middleware::from_fn_with_state(
+                version,
+                api_version_middleware,
+            )),
+        );
     }
+    let app = app.merge(api.layer(middleware::from_fn_with_state(
+        legacy_version,
+        api_version_middleware,
+    )));
 
-    // Apply CORS layer
-    let app = match cors_config {
+    // Outside CORS, so preflights still succeed, and inside the error
+    // middleware, so refusals get a request id.
+    let app = with_cors(app, cors_config).layer(middleware::from_fn(reads_only));
+    let app_state = AppState {
+        engine,
+        auth_mode,
+        start_time: Instant::now(),
+        config: config.map(|c| Arc::new(c.clone())),
+        error_messages: None,
+    };
+    app.layer(middleware::from_fn_with_state(
+        app_state,
+        crate::error::error_response_middleware,
+    ))
+    .layer(middleware::from_fn(default_api_version_middleware))
+}
+
+/// Refuses every method that could change something, whatever the path.
+async fn reads_only(request: Request, next: Next) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    read_only_response()
+}
+
+fn wait_limits(config: Option<&TaskcastConfig>) -> tasks::WaitLimits {
+    tasks::WaitLimits {
+        max_timeout_ms: config
+            .and_then(|c| c.long_poll.as_ref())
+            .and_then(|lp| lp.max_timeout_ms)
+            .unwrap_or(tasks::DEFAULT_MAX_WAIT_TIMEOUT_MS),
+    }
+}
+
+/// Applies the CORS layer. Response headers are exposed so browser
+/// publishers can read the event index/count headers.
+fn with_cors(app: Router, cors_config: CorsConfig) -> Router {
+    match cors_config {
         CorsConfig::Disabled => app,
         CorsConfig::AllowAll => app.layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods(Any)
-                .allow_headers(Any),
+                .allow_headers(Any)
+                .expose_headers(Any),
         ),
         CorsConfig::AllowOrigins(origins) => {
             let origins: Vec<_> = origins.iter().filter_map(|o| o.parse().ok()).collect();
@@ -256,31 +817,21 @@ pub fn create_app_with_failure_logger_and_routes(
                 CorsLayer::new()
                     .allow_origin(origins)
                     .allow_methods(Any)
-                    .allow_headers(Any),
+                    .allow_headers(Any)
+                    .expose_headers(Any),
             )
         }
-    };
-
-    // Merge caller-owned routes before applying the outer observer so every
-    // final 5xx response is logged exactly once.
-    let app = app.merge(additional_routes);
-    let app = app.layer(middleware::from_fn_with_state(
-        failure_logger,
-        crate::http_failure::http_failure_logger_middleware,
-    ));
-
-    (app, ws_registry_out)
+    }
 }
 
 const SERVER_NAME: &str = "taskcast";
-const API_VERSION: &str = "v1";
 const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 async fn root() -> impl IntoResponse {
     axum::Json(serde_json::json!({
         "name": SERVER_NAME,
         "version": SERVER_VERSION,
-        "apiVersion": API_VERSION,
+        "apiVersion": API_V1,
         "links": {
             "health": "/health",
             "healthDetail": "/health/detail",
@@ -295,17 +846,16 @@ async fn health() -> impl IntoResponse {
         "ok": true,
         "name": SERVER_NAME,
         "version": SERVER_VERSION,
-        "apiVersion": API_VERSION
+        "apiVersion": API_V1
     }))
 }
 
-async fn health_detail(AxumState(state): AxumState<AppState>) -> impl IntoResponse {
+async fn health_detail(
+    AxumState(state): AxumState<AppState>,
+    Extension(role): Extension<RoleSwitch>,
+) -> impl IntoResponse {
     let uptime = state.start_time.elapsed().as_secs();
-    let auth_mode_str = match state.auth_mode.as_ref() {
-        AuthMode::None => "none",
-        AuthMode::Jwt(_) => "jwt",
-        AuthMode::JwtWithTrustedServices { .. } => "jwt",
-    };
+    let auth_mode_str = state.auth_mode.name();
 
     let mut adapters = serde_json::json!({
         "broadcast": { "provider": "memory", "status": "ok" },
@@ -329,14 +879,38 @@ async fn health_detail(AxumState(state): AxumState<AppState>) -> impl IntoRespon
         }
     }
 
+    // Each long-term pool is probed separately, so a lost replica shows up
+    // even while the primary answers.
+    let pools = state.engine.long_term_pool_health().await;
+    if !pools.is_empty() {
+        let status = if pools.iter().all(|pool| pool.ok) {
+            "ok"
+        } else {
+            "degraded"
+        };
+        adapters["longTermStore"]["status"] = serde_json::json!(status);
+        adapters["longTermStore"]["pools"] = serde_json::json!(pools);
+    }
+
+    let background = state.engine.background();
+
     axum::Json(serde_json::json!({
         "ok": true,
         "name": SERVER_NAME,
         "version": SERVER_VERSION,
-        "apiVersion": API_VERSION,
+        "apiVersion": API_V1,
         "uptime": uptime,
         "auth": { "mode": auth_mode_str },
-        "adapters": adapters
+        "role": role.role(),
+        "adapters": adapters,
+        "backgroundTasks": {
+            "inFlight": background.in_flight(),
+            "byName": background.in_flight_by_name()
+        },
+        "integrity": {
+            "corruptRecords": state.engine.total_corrupt_records()
+        },
+        "readRouting": state.engine.read_router().stats()
     }))
 }
 
@@ -398,6 +972,10 @@ pub async fn dispatch_ws_race(wm: &WorkerManager, registry: &WsRegistry, task: &
 pub struct BackgroundServices {
     pub scheduler: Option<TaskScheduler>,
     pub heartbeat_monitor: Option<HeartbeatMonitor>,
+    pub retry_runner: Option<RetryRunner>,
+    pub scheduler_runner: Option<SchedulerRunner>,
+    pub outcome_trimmer: Option<OutcomeTrimmer>,
+    pub abandoned_pending_sweeper: Option<AbandonedPendingSweeper>,
 }
 
 impl BackgroundServices {
@@ -409,10 +987,24 @@ impl BackgroundServices {
         if let Some(ref mut h) = self.heartbeat_monitor {
             h.stop();
         }
+        if let Some(ref mut r) = self.retry_runner {
+            r.stop();
+        }
+        if let Some(ref mut r) = self.scheduler_runner {
+            r.stop();
+        }
+        if let Some(ref mut t) = self.outcome_trimmer {
+            t.stop();
+        }
+        if let Some(ref mut s) = self.abandoned_pending_sweeper {
+            s.stop();
+        }
     }
 }
 
-/// Create and start background services (scheduler + heartbeat monitor).
+/// Create and start background services (scheduler, retry runner, scheduler
+/// runner, outcome trimmer, abandoned task sweeper and heartbeat monitor).
+/// The sweeper only acts on tasks with their own `cleanup.abandonedPending`.
 ///
 /// The caller owns the returned `BackgroundServices` and should call `.stop()`
 /// on shutdown.
@@ -430,6 +1022,39 @@ pub fn start_background_services(
     });
     scheduler.start();
 
+    let mut retry_runner = RetryRunner::new(RetryRunnerOptions {
+        engine: Arc::clone(&engine),
+        short_term_store: Arc::clone(&store),
+        check_interval_ms: 1_000,
+        claim_lease_ms: 30_000,
+    });
+    retry_runner.start();
+
+    let mut scheduler_runner = SchedulerRunner::new(SchedulerRunnerOptions {
+        engine: Arc::clone(&engine),
+        short_term_store: Arc::clone(&store),
+        check_interval_ms: 1_000,
+        claim_lease_ms: 30_000,
+    });
+    scheduler_runner.start();
+
+    let mut outcome_trimmer = OutcomeTrimmer::new(OutcomeTrimmerOptions {
+        engine: Arc::clone(&engine),
+        short_term_store: Arc::clone(&store),
+        check_interval_ms: 60_000,
+        retention_ms: DEFAULT_OUTCOME_RETENTION_MS,
+    });
+    outcome_trimmer.start();
+
+    let mut abandoned_pending_sweeper =
+        AbandonedPendingSweeper::new(AbandonedPendingSweeperOptions {
+            engine: Arc::clone(&engine),
+            short_term_store: Arc::clone(&store),
+            check_interval_ms: 60_000,
+            config: None,
+        });
+    abandoned_pending_sweeper.start();
+
     let heartbeat_monitor = worker_manager.map(|wm| {
         let mut monitor = HeartbeatMonitor::new(HeartbeatMonitorOptions {
             worker_manager: wm,
@@ -447,5 +1072,9 @@ pub fn start_background_services(
     BackgroundServices {
         scheduler: Some(scheduler),
         heartbeat_monitor,
+        retry_runner: Some(retry_runner),
+        scheduler_runner: Some(scheduler_runner),
+        outcome_trimmer: Some(outcome_trimmer),
+        abandoned_pending_sweeper: Some(abandoned_pending_sweeper),
     }
 }
@@ -0,0 +1,290 @@
+//! Acting on every task a selector matches, for `POST /admin/tasks/bulk`.
+//!
+//! Selection reuses the store's task listing and narrows it in memory, so
+//! a bulk call sees the same tasks `GET /tasks` would. Each task is acted on
+//! independently: one that cannot be changed is reported in the results and
+//! does not stop the others.
+
+use std::collections::HashMap;
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use taskcast_core::config::BulkOperationsConfig;
+use taskcast_core::filter::matches_type;
+use taskcast_core::{
+    CleanupConfig, CleanupRule, EngineError, Task, TaskEngine, TaskError, TaskFilter, TaskStatus,
+    TransitionPayload,
+};
+
+use crate::timestamps;
+
+pub const DEFAULT_BULK_MAX_TASKS: usize = 1000;
+pub const DEFAULT_BULK_CONCURRENCY: usize = 16;
+
+/// Which tasks a bulk operation applies to. Every set criterion must match.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkSelector {
+    pub status: Option<Vec<TaskStatus>>,
+    /// Task type patterns, with the same wildcards as event type filters.
+    pub types: Option<Vec<String>>,
+    /// Only tasks created strictly before this time (ms since epoch, or
+    /// RFC 3339).
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_before: Option<f64>,
+    /// Only tasks created strictly after this time (ms since epoch, or
+    /// RFC 3339).
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_after: Option<f64>,
+    /// Metadata entries the task must have, with equal values.
+    pub metadata: Option<HashMap<String, Value>>,
+}
+
+impl BulkSelector {
+    pub fn matches(&self, task: &Task) -> bool {
+        if let Some(ref statuses) = self.status {
+            if !statuses.contains(&task.status) {
+                return false;
+            }
+        }
+        if self.types.is_some() {
+            match &task.r#type {
+                Some(t) if matches_type(t, self.types.as_deref()) => {}
+                _ => return false,
+            }
+        }
+        if self
+            .created_before
+            .is_some_and(|before| task.created_at >= before)
+        {
+            return false;
+        }
+        if self
+            .created_after
+            .is_some_and(|after| task.created_at <= after)
+        {
+            return false;
+        }
+        if let Some(ref wanted) = self.metadata {
+            let metadata = task.metadata.as_ref();
+            if !wanted
+                .iter()
+                .all(|(key, value)| metadata.and_then(|m| m.get(key)) == Some(value))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// What to do to each selected task.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkAction {
+    Transition {
+        to: TaskStatus,
+        error: Option<TaskError>,
+    },
+    /// Merges these entries into each task's metadata, replacing any with
+    /// the same key.
+    AddMetadata(HashMap<String, Value>),
+    /// Appends the rule to each task's cleanup rules.
+    ApplyCleanupRule { rule: CleanupRule },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTaskResult {
+    pub task_id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Limits applied to every bulk call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkLimits {
+    pub max_tasks: usize,
+    pub concurrency: usize,
+}
+
+impl Default for BulkLimits {
+    fn default() -> Self {
+        Self {
+            max_tasks: DEFAULT_BULK_MAX_TASKS,
+            concurrency: DEFAULT_BULK_CONCURRENCY,
+        }
+    }
+}
+
+impl BulkLimits {
+    pub fn from_config(config: Option<&BulkOperationsConfig>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_tasks: config
+                .and_then(|c| c.max_tasks)
+                .unwrap_or(defaults.max_tasks),
+            concurrency: config
+                .and_then(|c| c.concurrency)
+                .unwrap_or(defaults.concurrency)
+                .max(1),
+        }
+    }
+}
+
+/// The tasks `selector` matches, oldest first.
+pub async fn select_tasks(
+    engine: &TaskEngine,
+    selector: &BulkSelector,
+) -> Result<Vec<Task>, EngineError> {
+    let filter = TaskFilter {
+        status: selector.status.clone(),
+        ..Default::default()
+    };
+    let mut tasks: Vec<Task> = engine
+        .list_tasks(filter)
+        .await?
+        .into_iter()
+        .filter(|task| selector.matches(task))
+        .collect();
+    tasks.sort_by(|a, b| {
+        a.created_at
+            .total_cmp(&b.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(tasks)
+}
+
+/// Applies `action` to each of `task_ids`, at most `concurrency` at a time.
+/// Results are in the order of `task_ids`.
+pub async fn execute(
+    engine: &TaskEngine,
+    task_ids: Vec<String>,
+    action: &BulkAction,
+    concurrency: usize,
+) -> Vec<BulkTaskResult> {
+    stream::iter(task_ids)
+        .map(|task_id| async move {
+            let outcome = apply(engine, &task_id, action).await;
+            BulkTaskResult {
+                task_id,
+                ok: outcome.is_ok(),
+                error: outcome.err().map(|err| err.to_string()),
+            }
+        })
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+async fn apply(engine: &TaskEngine, task_id: &str, action: &BulkAction) -> Result<(), EngineError> {
+    match action {
+        BulkAction::Transition { to, error } => {
+            let payload = TransitionPayload {
+                error: error.clone(),
+                ..Default::default()
+            };
+            engine
+                .transition_task(task_id, to.clone(), Some(payload))
+                .await?;
+        }
+        BulkAction::AddMetadata(entries) => {
+            engine
+                .update_task(task_id, |task| {
+                    task.metadata
+                        .get_or_insert_with(HashMap::new)
+                        .extend(entries.clone());
+                })
+                .await?;
+        }
+        BulkAction::ApplyCleanupRule { rule } => {
+            engine
+                .update_task(task_id, |task| {
+                    task.cleanup
+                        .get_or_insert_with(|| CleanupConfig {
+                            rules: Vec::new(),
+                            abandoned_pending: None,
+                        })
+                        .rules
+                        .push(rule.clone());
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn task(value: Value) -> Task {
+        let mut base = json!({
+            "id": "t1",
+            "status": "pending",
+            "createdAt": 1000.0,
+            "updatedAt": 1000.0,
+        });
+        base.as_object_mut()
+            .unwrap()
+            .extend(value.as_object().unwrap().clone());
+        serde_json::from_value(base).unwrap()
+    }
+
+    fn selector(value: Value) -> BulkSelector {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn empty_selector_matches_every_task() {
+        assert!(selector(json!({})).matches(&task(json!({}))));
+    }
+
+    #[test]
+    fn type_patterns_use_event_type_wildcards() {
+        let imports = selector(json!({ "types": ["import.*"] }));
+        assert!(imports.matches(&task(json!({ "type": "import.csv" }))));
+        assert!(!imports.matches(&task(json!({ "type": "export.csv" }))));
+        assert!(!imports.matches(&task(json!({}))));
+    }
+
+    #[test]
+    fn created_bounds_are_exclusive() {
+        let window = selector(json!({ "createdAfter": 1000.0, "createdBefore": 2000.0 }));
+        assert!(!window.matches(&task(json!({ "createdAt": 1000.0 }))));
+        assert!(window.matches(&task(json!({ "createdAt": 1500.0 }))));
+        assert!(!window.matches(&task(json!({ "createdAt": 2000.0 }))));
+    }
+
+    #[test]
+    fn metadata_entries_must_be_equal() {
+        let tenant = selector(json!({ "status": ["pending"], "metadata": { "tenant": "acme" } }));
+        assert!(tenant.matches(&task(json!({ "metadata": { "tenant": "acme", "x": 1 } }))));
+        assert!(!tenant.matches(&task(json!({ "metadata": { "tenant": "other" } }))));
+        assert!(!tenant.matches(&task(json!({}))));
+        assert!(!tenant.matches(&task(json!({
+            "status": "running",
+            "metadata": { "tenant": "acme" },
+        }))));
+    }
+
+    #[test]
+    fn limits_fall_back_to_defaults() {
+        assert_eq!(BulkLimits::from_config(None), BulkLimits::default());
+        let config = BulkOperationsConfig {
+            max_tasks: Some(5),
+            concurrency: Some(0),
+        };
+        assert_eq!(
+            BulkLimits::from_config(Some(&config)),
+            BulkLimits {
+                max_tasks: 5,
+                concurrency: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! Debug bundles: what a support ticket about one task needs, in one tar.gz.
+//!
+//! [`DebugBundle::collect`] gathers the node's runtime info, the task and its
+//! events as each store has them, the task's integrity and consistency
+//! report, the HTTP tap entries that touch it, a metrics snapshot and the
+//! tail of the log file. A part whose subsystem is absent or cannot be read
+//! is listed in the manifest with the reason instead of failing the bundle.
+//!
+//! The configuration is echoed through [`TaskcastConfig::redacted`] and task
+//! webhook secrets are masked. Every file is then scrubbed of the secret
+//! values of the configuration and the task, and of the values of
+//! environment variables whose names match the redaction patterns.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use taskcast_core::config::{glob_matches, redact_webhook_secrets, DebugBundleConfig, REDACTED};
+use taskcast_core::{scan_task_consistency, EngineError, ScanOptions, Task, TaskEngine, TaskEvent};
+
+use crate::auth::AuthMode;
+use crate::http_tap::{HttpTap, HttpTapQuery};
+use crate::runtime_info::RuntimeInfo;
+use crate::runtime_metrics::RuntimeSampler;
+
+/// Path of the endpoint that builds a bundle on the node.
+pub const DEBUG_BUNDLE_PATH: &str = "/admin/tasks/{task_id}/debug-bundle";
+
+/// Log lines included when the configuration does not say.
+pub const DEFAULT_LOG_LINES: usize = 500;
+
+/// Environment variable name patterns whose values are always scrubbed.
+pub const DEFAULT_REDACT_ENV: [&str; 6] = [
+    "*SECRET*",
+    "*TOKEN*",
+    "*PASSWORD*",
+    "*KEY*",
+    "*CREDENTIAL*",
+    "*DSN*",
+];
+
+/// Shorter secrets are not scrubbed: they would match all over the bundle.
+const MIN_SECRET_LEN: usize = 4;
+
+/// Environment variables matching a pattern also hold settings such as
+/// `MAX_TOKENS=32000`; only values at least this long and not purely
+/// numeric are taken for secrets.
+const MIN_ENV_SECRET_LEN: usize = 8;
+
+/// Bumped when the layout of the bundle changes.
+const BUNDLE_FORMAT: u32 = 1;
+
+// ─── Options ────────────────────────────────────────────────────────────────
+
+/// The `debug.bundle` section of the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugBundleOptions {
+    pub log_file: Option<PathBuf>,
+    pub log_lines: usize,
+    /// Environment variable name patterns, the defaults included.
+    pub redact_env: Vec<String>,
+}
+
+impl Default for DebugBundleOptions {
+    fn default() -> Self {
+        Self::from_config(None)
+    }
+}
+
+impl DebugBundleOptions {
+    pub fn from_config(config: Option<&DebugBundleConfig>) -> Self {
+        let mut redact_env: Vec<String> =
+            DEFAULT_REDACT_ENV.iter().map(|p| p.to_string()).collect();
+        redact_env.extend(
+            config
+                .and_then(|c| c.redact_env.clone())
+                .unwrap_or_default(),
+        );
+        Self {
+            log_file: config.and_then(|c| c.log_file.as_ref()).map(PathBuf::from),
+            log_lines: config
+                .and_then(|c| c.log_lines)
+                .unwrap_or(DEFAULT_LOG_LINES),
+            redact_env,
+        }
+    }
+}
+
+/// Where a bundle's parts come from. Subsystems the caller does not have,
+/// such as the tap and sampler of a CLI without a running node, are `None`.
+pub struct DebugBundleSources<'a> {
+    pub engine: &'a TaskEngine,
+    pub auth_mode: &'a AuthMode,
+    pub uptime: Duration,
+    pub runtime_info: &'a RuntimeInfo,
+    pub http_tap: Option<&'a HttpTap>,
+    pub runtime_sampler: Option<&'a RuntimeSampler>,
+    pub options: &'a DebugBundleOptions,
+}
+
+// ─── Bundle ─────────────────────────────────────────────────────────────────
+
+/// The redacted files of a bundle, `manifest.json` first.
+#[derive(Debug, Clone)]
+pub struct DebugBundle {
+    files: Vec<(String, String)>,
+}
+
+impl DebugBundle {
+    /// Collects a bundle for `task_id`. Fails only when neither store has
+    /// the task.
+    pub async fn collect(
+        sources: &DebugBundleSources<'_>,
+        task_id: &str,
+    ) -> Result<Self, EngineError> {
+        let engine = sources.engine;
+        let mut collector = Collector::default();
+
+        let short_term_task = engine.short_term_store().get_task(task_id).await;
+        let long_term_task = match engine.long_term_store() {
+            Some(store) => Some(store.get_task(task_id).await),
+            None => None,
+        };
+        let in_short_term = matches!(short_term_task, Ok(Some(_)));
+        let in_long_term = matches!(long_term_task, Some(Ok(Some(_))));
+        if !in_short_term && !in_long_term {
+            if let Err(e) = short_term_task {
+                return Err(EngineError::Store(e));
+            }
+            return Err(EngineError::TaskNotFound(task_id.to_string()));
+        }
+
+        let mut secrets: Vec<String> = sources
+            .runtime_info
+            .config
+            .as_ref()
+            .map(|config| config.secret_values())
+            .unwrap_or_default();
+        let masked_env = env_secrets(&sources.options.redact_env, &mut secrets);
+
+        collector.add(
+            "runtime.json",
+            "Version, adapters, runners and the redacted configuration",
+            &sources
+                .runtime_info
+                .report(engine, sources.auth_mode, sources.uptime),
+        );
+
+        // ── Task and events, per store ──
+        let short_term_events = engine.short_term_store().get_events(task_id, None).await;
+        let short_term = StoreCopy::read(short_term_task, short_term_events, &mut secrets);
+        collector.add_store_copy("task/short-term.json", "short-term", &short_term);
+        let long_term = match (engine.long_term_store(), long_term_task) {
+            (Some(store), Some(task)) => {
+                let events = store.get_events(task_id, None).await;
+                let copy = StoreCopy::read(task, events, &mut secrets);
+                collector.add_store_copy("task/long-term.json", "long-term", &copy);
+                Some(copy)
+            }
+            _ => {
+                collector.absent("task/long-term.json", "No long-term store is configured");
+                None
+            }
+        };
+        let divergence = long_term
+            .as_ref()
+            .and_then(|long_term| divergence(&short_term, long_term));
+        match divergence {
+            Some(note) => collector.add(
+                "task/divergence.json",
+                "How the short-term and long-term copies differ",
+                &note,
+            ),
+            None if long_term.is_some() => {
+                collector.absent("task/divergence.json", "The stores agree")
+            }
+            None => collector.absent("task/divergence.json", "Only one store to compare"),
+        }
+
+        // ── Integrity ──
+        let consistency = scan_task_consistency(
+            engine.short_term_store(),
+            engine.long_term_store(),
+            task_id,
+            ScanOptions {
+                repair: false,
+                ..Default::default()
+            },
+        )
+        .await;
+        let consistency = match consistency {
+            Ok(report) => serde_json::to_value(report).unwrap_or_default(),
+            Err(e) => json!({ "error": e.to_string() }),
+        };
+        collector.add(
+            "integrity.json",
+            "Corrupt records read for the task and a consistency scan of it",
+            &json!({
+                "taskId": task_id,
+                "corruptRecords": engine.corrupt_records(task_id),
+                "consistency": consistency,
+            }),
+        );
+
+        // ── Optional subsystems ──
+        collector.absent(
+            "webhook-dlq.json",
+            "This build has no webhook dead-letter queue",
+        );
+        match sources.http_tap {
+            Some(tap) if tap.is_enabled() => {
+                let needle = format!("/{task_id}");
+                let entries: Vec<_> = tap
+                    .entries(&HttpTapQuery::default())
+                    .into_iter()
+                    .filter(|entry| {
+                        entry.path.ends_with(&needle) || entry.path.contains(&format!("{needle}/"))
+                    })
+                    .collect();
+                collector.add(
+                    "http-tap.json",
+                    "Recorded HTTP exchanges for the task, newest first",
+                    &json!({ "entries": entries }),
+                );
+            }
+            Some(_) => collector.absent("http-tap.json", "The HTTP tap is disabled"),
+            None => collector.absent("http-tap.json", "The HTTP tap is not available"),
+        }
+        match sources.runtime_sampler {
+            Some(sampler) => collector.add(
+                "metrics.json",
+                "Executor and engine metrics",
+                &serde_json::to_value(sampler.snapshot()).unwrap_or_default(),
+            ),
+            None => collector.absent("metrics.json", "No running node to sample"),
+        }
+        match sources.options.log_file {
+            Some(ref path) => match tail_lines(path.clone(), sources.options.log_lines).await {
+                Ok(lines) => {
+                    collector.add_text("logs.txt", "The last lines of the log file", lines)
+                }
+                Err(e) => collector.absent(
+                    "logs.txt",
+                    &format!("Could not read {}: {e}", path.display()),
+                ),
+            },
+            None => collector.absent("logs.txt", "No log file is configured"),
+        }
+
+        Ok(collector.finish(
+            task_id,
+            engine.clock().now_ms(),
+            &secrets,
+            &sources.options.redact_env,
+            masked_env,
+        ))
+    }
+
+    /// File names, `manifest.json` first.
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn file(&self, name: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|(file, _)| file == name)
+            .map(|(_, contents)| contents.as_str())
+    }
+
+    pub fn manifest(&self) -> Value {
+        self.file("manifest.json")
+            .and_then(|manifest| serde_json::from_str(manifest).ok())
+            .unwrap_or_default()
+    }
+
+    /// The bundle as a gzip-compressed tarball.
+    pub fn to_tar_gz(&self) -> std::io::Result<Vec<u8>> {
+        let mtime = self.manifest()["createdAt"].as_f64().unwrap_or(0.0) / 1000.0;
+        let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, contents) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime as u64);
+            archive.append_data(&mut header, name, contents.as_bytes())?;
+        }
+        let mut encoder = archive.into_inner()?;
+        encoder.flush()?;
+        encoder.finish()
+    }
+}
+
+// ─── Collection ─────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Collector {
+    files: Vec<(String, String)>,
+    contents: Vec<Value>,
+    absent: Vec<Value>,
+}
+
+impl Collector {
+    fn add(&mut self, name: &str, description: &str, value: &Value) {
+        let text = serde_json::to_string_pretty(value).unwrap_or_default();
+        self.add_text(name, description, text);
+    }
+
+    fn add_text(&mut self, name: &str, description: &str, text: String) {
+        self.contents
+            .push(json!({ "name": name, "description": description }));
+        self.files.push((name.to_string(), text));
+    }
+
+    fn absent(&mut self, name: &str, reason: &str) {
+        self.absent.push(json!({ "name": name, "reason": reason }));
+    }
+
+    fn add_store_copy(&mut self, name: &str, store: &str, copy: &StoreCopy) {
+        self.add(
+            name,
+            &format!("The task and its events as the {store} store has them"),
+            &copy.to_json(),
+        );
+    }
+
+    fn finish(
+        self,
+        task_id: &str,
+        now: f64,
+        secrets: &[String],
+        env_patterns: &[String],
+        masked_env: Vec<String>,
+    ) -> DebugBundle {
+        let scrubber = Scrubber::new(secrets);
+        let mut replacements = BTreeMap::new();
+        let mut files = Vec::with_capacity(self.files.len() + 1);
+        for (name, text) in self.files {
+            let (text, count) = scrubber.scrub(&text);
+            if count > 0 {
+                replacements.insert(name.clone(), count);
+            }
+            files.push((name, text));
+        }
+
+        let manifest = json!({
+            "format": BUNDLE_FORMAT,
+            "taskId": task_id,
+            "createdAt": now,
+            "taskcastVersion": env!("CARGO_PKG_VERSION"),
+            "contents": self.contents,
+            "absent": self.absent,
+            "redaction": {
+                "placeholder": REDACTED,
+                "rules": [
+                    "Configuration echoed in redacted form: tokens, secrets, key hashes and URL credentials",
+                    "Webhook secrets of the task",
+                    "Configuration and webhook secret values scrubbed from every file",
+                    "Values of environment variables whose names match envPatterns scrubbed from every file",
+                ],
+                "envPatterns": env_patterns,
+                "maskedEnvVars": masked_env,
+                "replacements": replacements,
+            },
+        });
+        files.insert(
+            0,
+            (
+                "manifest.json".to_string(),
+                serde_json::to_string_pretty(&manifest).unwrap_or_default(),
+            ),
+        );
+        DebugBundle { files }
+    }
+}
+
+/// The task and events one store returned, or why it could not.
+struct StoreCopy {
+    task: Result<Option<Value>, String>,
+    events: Result<Vec<Value>, String>,
+}
+
+type StoreRead<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+impl StoreCopy {
+    fn read(
+        task: StoreRead<Option<Task>>,
+        events: StoreRead<Vec<TaskEvent>>,
+        secrets: &mut Vec<String>,
+    ) -> Self {
+        let task = task.map_err(|e| e.to_string()).map(|task| {
+            task.map(|mut task| {
+                for webhook in task.webhooks.iter().flatten() {
+                    secrets.extend(webhook.secret.clone());
+                }
+                redact_webhook_secrets(task.webhooks.iter_mut().flatten());
+                serde_json::to_value(task).unwrap_or_default()
+            })
+        });
+        let events = events.map_err(|e| e.to_string()).map(|events| {
+            events
+                .into_iter()
+                .map(|event| serde_json::to_value(event).unwrap_or_default())
+                .collect()
+        });
+        Self { task, events }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = json!({});
+        match self.task {
+            Ok(ref task) => value["task"] = json!(task),
+            Err(ref e) => value["taskError"] = json!(e),
+        }
+        match self.events {
+            Ok(ref events) => value["events"] = json!(events),
+            Err(ref e) => value["eventsError"] = json!(e),
+        }
+        value
+    }
+}
+
+/// How the long-term copy differs from the short-term one, or `None` when
+/// they agree.
+fn divergence(short_term: &StoreCopy, long_term: &StoreCopy) -> Option<Value> {
+    let mut notes = serde_json::Map::new();
+
+    match (&short_term.task, &long_term.task) {
+        (Ok(Some(short)), Ok(Some(long))) if short != long => {
+            let mut fields: Vec<&String> = short
+                .as_object()
+                .into_iter()
+                .chain(long.as_object())
+                .flat_map(|object| object.keys())
+                .filter(|key| short.get(*key) != long.get(*key))
+                .collect();
+            fields.sort();
+            fields.dedup();
+            notes.insert("taskFieldsDiffer".to_string(), json!(fields));
+        }
+        (Ok(Some(_)), Ok(None)) => {
+            notes.insert(
+                "task".to_string(),
+                json!("missing from the long-term store"),
+            );
+        }
+        (Ok(None), Ok(Some(_))) => {
+            notes.insert(
+                "task".to_string(),
+                json!("missing from the short-term store"),
+            );
+        }
+        _ => {}
+    }
+
+    if let (Ok(short), Ok(long)) = (&short_term.events, &long_term.events) {
+        let ids = |events: &[Value]| -> Vec<String> {
+            events
+                .iter()
+                .filter_map(|event| event["id"].as_str().map(str::to_string))
+                .collect()
+        };
+        let (short_ids, long_ids) = (ids(short), ids(long));
+        let short_set: HashSet<&String> = short_ids.iter().collect();
+        let long_set: HashSet<&String> = long_ids.iter().collect();
+        let only_short: Vec<&String> = short_ids
+            .iter()
+            .filter(|id| !long_set.contains(id))
+            .collect();
+        let only_long: Vec<&String> = long_ids
+            .iter()
+            .filter(|id| !short_set.contains(id))
+            .collect();
+        if !only_short.is_empty() || !only_long.is_empty() {
+            notes.insert(
+                "events".to_string(),
+                json!({
+                    "shortTermCount": short_ids.len(),
+                    "longTermCount": long_ids.len(),
+                    "onlyInShortTerm": only_short,
+                    "onlyInLongTerm": only_long,
+                }),
+            );
+        }
+    }
+
+    (!notes.is_empty()).then_some(Value::Object(notes))
+}
+
+// ─── Redaction ──────────────────────────────────────────────────────────────
+
+/// Adds the values of environment variables matching `patterns` to
+/// `secrets` and returns the names of those variables.
+fn env_secrets(patterns: &[String], secrets: &mut Vec<String>) -> Vec<String> {
+    let mut names = Vec::new();
+    for (name, value) in std::env::vars() {
+        if value.len() >= MIN_ENV_SECRET_LEN
+            && !value.chars().all(|c| c.is_ascii_digit())
+            && patterns.iter().any(|pattern| glob_matches(pattern, &name))
+        {
+            secrets.push(value);
+            names.push(name);
+        }
+    }
+    names.sort();
+    names
+}
+
+/// Replaces known secret values, raw or JSON-escaped, with [`REDACTED`].
+struct Scrubber {
+    needles: Vec<String>,
+}
+
+impl Scrubber {
+    fn new(secrets: &[String]) -> Self {
+        let mut needles: Vec<String> = secrets
+            .iter()
+            .filter(|secret| secret.len() >= MIN_SECRET_LEN && secret.as_str() != REDACTED)
+            .flat_map(|secret| {
+                let escaped = serde_json::to_string(secret).unwrap_or_default();
+                let escaped = escaped[1..escaped.len() - 1].to_string();
+                [secret.clone(), escaped]
+            })
+            .collect();
+        // Longest first, so a secret containing another is replaced whole.
+        needles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        needles.dedup();
+        Self { needles }
+    }
+
+    fn scrub(&self, text: &str) -> (String, usize) {
+        let mut text = text.to_string();
+        let mut count = 0;
+        for needle in &self.needles {
+            let found = text.matches(needle.as_str()).count();
+            if found > 0 {
+                count += found;
+                text = text.replace(needle.as_str(), REDACTED);
+            }
+        }
+        (text, count)
+    }
+}
+
+// ─── Logs ───────────────────────────────────────────────────────────────────
+
+/// The last `lines` lines of the file, read without holding more than that
+/// in memory.
+async fn tail_lines(path: PathBuf, lines: usize) -> std::io::Result<String> {
+    tokio::task::spawn_blocking(move || {
+        let mut reader = BufReader::new(std::fs::File::open(path)?);
+        let mut tail: VecDeque<String> = VecDeque::with_capacity(lines.min(4096));
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            if lines > 0 {
+                if tail.len() == lines {
+                    tail.pop_front();
+                }
+                tail.push_back(String::from_utf8_lossy(&line).into_owned());
+            }
+            line.clear();
+        }
+        Ok(tail.into_iter().collect())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_names() {
+        assert!(glob_matches("*SECRET*", "TASKCAST_JWT_SECRET"));
+        assert!(glob_matches("*secret*", "jwt_secret_file"));
+        assert!(glob_matches("STRIPE_*", "STRIPE_API"));
+        assert!(glob_matches("*_KEY", "SERVICE_KEY"));
+        assert!(glob_matches("EXACT", "exact"));
+        assert!(!glob_matches("STRIPE_*", "MY_STRIPE_API"));
+        assert!(!glob_matches("*_KEY", "KEYRING"));
+        assert!(!glob_matches("A*B*B", "AB"));
+    }
+
+    #[test]
+    fn scrubber_replaces_raw_and_escaped_values() {
+        let scrubber = Scrubber::new(&["pa\"ss-word".to_string(), "abc".to_string()]);
+        let (text, count) = scrubber.scrub(r#"raw pa"ss-word, json "pa\"ss-word", abc"#);
+        assert_eq!(count, 2);
+        assert_eq!(text, r#"raw [REDACTED], json "[REDACTED]", abc"#);
+    }
+}
@@ -1,9 +1,23 @@
-use axum::http::StatusCode;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header::CONTENT_LENGTH;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
-use taskcast_core::EngineError;
+use serde_json::{json, Value};
+use taskcast_core::{
+    store_error_detail, CorruptRecord, DeadlineExceeded, EngineError, FilterPresetError,
+    PermissionScope, RunnerError, StorageError, StoreError, StoreErrorKind, Violation,
+};
 
+use crate::app::AppState;
 use crate::http_failure::{HttpFailureDetail, HttpFailureKind};
+use crate::versioning::ApiVersion;
+
+/// Header used to propagate (or assign) a per-request correlation id.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LEN: usize = 128;
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -13,87 +27,467 @@ pub enum AppError {
     #[error("{0}")]
     BadRequest(String),
 
+    /// The request body failed validation; every violation is listed.
+    #[error("{}", join_messages(.0))]
+    Validation(Vec<Violation>),
+
+    /// A query parameter is malformed or not accepted by the route.
+    #[error("Invalid query parameter \"{param}\": {reason}")]
+    InvalidQuery { param: String, reason: String },
+
     #[error("Task not found")]
     NotFound(String),
 
     #[error("Forbidden")]
     Forbidden,
 
+    /// The caller is authenticated but lacks the scope required by the route.
+    #[error("Forbidden")]
+    MissingScope(PermissionScope),
+
+    /// The task does not meet the subscription's `taskMatch`.
+    #[error("Task does not match the subscription's taskMatch")]
+    TaskMismatch(String),
+
     #[error("Missing Bearer token")]
     MissingToken,
 
     #[error("Invalid or expired token")]
     InvalidToken,
 
+    #[error("Invalid service key")]
+    InvalidServiceKey,
+
+    #[error("Invalid API key")]
+    InvalidApiKey,
+
+    #[error("Invalid admin token")]
+    InvalidAdminToken,
+
     #[error("{0}")]
     NotImplemented(String),
+
+    /// A method that could change something, sent to the read-only API.
+    #[error("This API is read-only")]
+    ReadOnly,
+
+    /// A write was refused because the storage directory is at its byte cap.
+    #[error("Storage directory {directory} is full")]
+    InsufficientStorage {
+        directory: String,
+        used_bytes: u64,
+        max_bytes: u64,
+    },
+
+    /// A run-now request for a background runner that has stopped.
+    #[error("Runner {0} is not running")]
+    RunnerStopped(String),
+
+    /// A manual promotion on an instance whose role leader election decides.
+    #[error("This instance's role is decided by leader election")]
+    RoleElected,
+
+    #[error("{0}")]
+    Internal(String),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message, detail) = match &self {
+/// Structured error body returned by every failing HTTP route.
+///
+/// Serialized as `{"code", "message", "details"?, "requestId"?}`. The request id
+/// is filled in by [`error_response_middleware`], which also gives the
+/// [`ErrorMessageProvider`] a chance to rewrite `message`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorPayload {
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl ErrorPayload {
+    pub fn to_json(&self, request_id: Option<&str>) -> Value {
+        let mut body = json!({ "code": self.code, "message": self.message });
+        if let Some(ref details) = self.details {
+            body["details"] = details.clone();
+        }
+        if let Some(request_id) = request_id {
+            body["requestId"] = json!(request_id);
+        }
+        body
+    }
+}
+
+/// Hook for localizing or rewriting error messages by code.
+///
+/// Only the `message` field can be changed; `code`, `details` and `requestId`
+/// are always produced by the server. Returning `None` keeps the default text.
+pub trait ErrorMessageProvider: Send + Sync {
+    fn message(&self, error: &ErrorPayload, request_headers: &HeaderMap) -> Option<String>;
+}
+
+fn join_messages(violations: &[Violation]) -> String {
+    let messages: Vec<_> = violations.iter().map(|v| v.message.as_str()).collect();
+    messages.join("; ")
+}
+
+fn status_name(status: &taskcast_core::TaskStatus) -> Value {
+    serde_json::to_value(status).unwrap_or(Value::Null)
+}
+
+fn corrupt_record(error: &EngineError) -> Option<&CorruptRecord> {
+    match error {
+        EngineError::Store(source) => source.downcast_ref::<CorruptRecord>(),
+        _ => None,
+    }
+}
+
+/// The request deadline a read ran out of, if that is why it failed.
+fn deadline_exceeded(error: &EngineError) -> Option<&DeadlineExceeded> {
+    match error {
+        EngineError::Store(source) => source.downcast_ref::<DeadlineExceeded>(),
+        _ => None,
+    }
+}
+
+/// The adapter's classification of a store failure, if it made one.
+fn store_error_kind(error: &EngineError) -> Option<StoreErrorKind> {
+    match error {
+        EngineError::Store(source) => source.downcast_ref::<StoreError>().map(|e| e.kind),
+        _ => None,
+    }
+}
+
+/// The store failure for logs: one bounded line, then the full error chain.
+fn store_failure(source: &(dyn std::error::Error + 'static)) -> (String, String) {
+    let mut chain = source.to_string();
+    let mut next = source.source();
+    while let Some(error) = next {
+        chain.push_str(": ");
+        chain.push_str(&error.to_string());
+        next = error.source();
+    }
+    (store_error_detail(&source.to_string()), chain)
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
             AppError::Engine(e) => match e {
-                EngineError::TaskNotFound(msg) => (StatusCode::NOT_FOUND, msg.clone(), None),
-                EngineError::TaskConflict(msg) => (
-                    StatusCode::CONFLICT,
-                    format!("Task already exists: {msg}"),
-                    None,
-                ),
-                EngineError::InvalidTransition { from, to } => (
-                    StatusCode::CONFLICT,
-                    format!("Invalid transition: {from:?} \u{2192} {to:?}"),
-                    None,
-                ),
-                EngineError::TaskTerminal(status) => (
-                    StatusCode::CONFLICT,
-                    format!("Cannot publish to task in terminal status: {status:?}"),
-                    None,
-                ),
-                EngineError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg.clone(), None),
-                EngineError::Archive(error) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    error.to_string(),
-                    Some(HttpFailureDetail::new(
-                        HttpFailureKind::Archive,
-                        error.to_string(),
-                    )),
-                ),
-                EngineError::Store(error) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    error.to_string(),
-                    Some(HttpFailureDetail::new(
-                        HttpFailureKind::Store,
-                        error.to_string(),
-                    )),
-                ),
+                EngineError::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                EngineError::TaskConflict(_)
+                | EngineError::ConcurrentModification(_)
+                | EngineError::TaskAlreadyExists { .. }
+                | EngineError::InvalidTransition { .. }
+                | EngineError::TaskTerminal(_) => StatusCode::CONFLICT,
+                EngineError::InvalidInput(_) | EngineError::FilterPreset(_) => {
+                    StatusCode::BAD_REQUEST
+                }
+                EngineError::InvalidSeriesPatch(_) | EngineError::SeriesLimitExceeded { .. } => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                EngineError::Store(_) if deadline_exceeded(e).is_some() => {
+                    StatusCode::GATEWAY_TIMEOUT
+                }
+                EngineError::Store(_) => match store_error_kind(e) {
+                    Some(StoreErrorKind::Unavailable | StoreErrorKind::Timeout) => {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    }
+                    Some(StoreErrorKind::Conflict) => StatusCode::CONFLICT,
+                    Some(StoreErrorKind::TooLarge) => StatusCode::PAYLOAD_TOO_LARGE,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                },
+                EngineError::Archive(_) | EngineError::PartialTaskGroup(_) => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
             },
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone(), None),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone(), None),
-            AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string(), None),
-            AppError::MissingToken => (
-                StatusCode::UNAUTHORIZED,
-                "Missing Bearer token".to_string(),
-                None,
-            ),
-            AppError::InvalidToken => (
-                StatusCode::UNAUTHORIZED,
-                "Invalid or expired token".to_string(),
-                None,
-            ),
-            AppError::NotImplemented(msg) => (
-                StatusCode::NOT_IMPLEMENTED,
-                msg.clone(),
-                Some(HttpFailureDetail::new(
-                    HttpFailureKind::Internal,
-                    msg.clone(),
-                )),
+            AppError::BadRequest(_) | AppError::Validation(_) | AppError::InvalidQuery { .. } => {
+                StatusCode::BAD_REQUEST
+            }
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Forbidden | AppError::MissingScope(_) | AppError::TaskMismatch(_) => {
+                StatusCode::FORBIDDEN
+            }
+            AppError::MissingToken
+            | AppError::InvalidToken
+            | AppError::InvalidServiceKey
+            | AppError::InvalidApiKey
+            | AppError::InvalidAdminToken => StatusCode::UNAUTHORIZED,
+            AppError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            AppError::ReadOnly => StatusCode::METHOD_NOT_ALLOWED,
+            AppError::InsufficientStorage { .. } => StatusCode::INSUFFICIENT_STORAGE,
+            AppError::RunnerStopped(_) | AppError::RoleElected => StatusCode::CONFLICT,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable, machine-readable error code (e.g. `INVALID_TRANSITION`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Engine(e) => match e {
+                EngineError::TaskNotFound(_) => "TASK_NOT_FOUND",
+                EngineError::TaskConflict(_) => "TASK_CONFLICT",
+                EngineError::ConcurrentModification(_) => "CONCURRENT_MODIFICATION",
+                EngineError::TaskAlreadyExists { .. } => "TASK_ALREADY_EXISTS",
+                EngineError::InvalidTransition { .. } => "INVALID_TRANSITION",
+                EngineError::TaskTerminal(_) => "TASK_TERMINAL",
+                EngineError::InvalidInput(_) => "INVALID_INPUT",
+                EngineError::InvalidSeriesPatch(_) => "INVALID_SERIES_PATCH",
+                EngineError::SeriesLimitExceeded { .. } => "SERIES_LIMIT_EXCEEDED",
+                EngineError::FilterPreset(FilterPresetError::UnknownPreset(_)) => {
+                    "UNKNOWN_FILTER_PRESET"
+                }
+                EngineError::FilterPreset(
+                    FilterPresetError::LabelConflict { .. }
+                    | FilterPresetError::TaskMatchConflict { .. },
+                ) => "FILTER_PRESET_CONFLICT",
+                EngineError::Archive(_) => "ARCHIVE_ERROR",
+                EngineError::PartialTaskGroup(_) => "PARTIAL_TASK_GROUP",
+                EngineError::Store(_) if corrupt_record(e).is_some() => "CORRUPT_RECORD",
+                EngineError::Store(_) if deadline_exceeded(e).is_some() => "DEADLINE_EXCEEDED",
+                EngineError::Store(_) => match store_error_kind(e) {
+                    Some(StoreErrorKind::Unavailable) => "STORE_UNAVAILABLE",
+                    Some(StoreErrorKind::Timeout) => "STORE_TIMEOUT",
+                    Some(StoreErrorKind::Conflict) => "STORE_CONFLICT",
+                    Some(StoreErrorKind::TooLarge) => "STORE_TOO_LARGE",
+                    Some(StoreErrorKind::Corrupt) => "STORE_CORRUPT",
+                    Some(StoreErrorKind::Other) | None => "STORE_ERROR",
+                },
+            },
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Validation(_) => "INVALID_INPUT",
+            AppError::InvalidQuery { .. } => "INVALID_QUERY",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Forbidden | AppError::MissingScope(_) => "FORBIDDEN",
+            AppError::TaskMismatch(_) => "TASK_MISMATCH",
+            AppError::MissingToken => "MISSING_TOKEN",
+            AppError::InvalidToken => "INVALID_TOKEN",
+            AppError::InvalidServiceKey => "INVALID_SERVICE_KEY",
+            AppError::InvalidApiKey => "INVALID_API_KEY",
+            AppError::InvalidAdminToken => "INVALID_ADMIN_TOKEN",
+            AppError::NotImplemented(_) => "NOT_IMPLEMENTED",
+            AppError::ReadOnly => "READ_ONLY",
+            AppError::InsufficientStorage { .. } => "INSUFFICIENT_STORAGE",
+            AppError::RunnerStopped(_) => "RUNNER_NOT_RUNNING",
+            AppError::RoleElected => "ROLE_ELECTED",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Structured data describing the error, if the variant carries any.
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            AppError::Engine(e) => match e {
+                EngineError::TaskNotFound(task_id)
+                | EngineError::TaskConflict(task_id)
+                | EngineError::ConcurrentModification(task_id) => {
+                    Some(json!({ "taskId": task_id }))
+                }
+                EngineError::TaskAlreadyExists { task_id, existing } => {
+                    let mut details = json!({ "taskId": task_id });
+                    if let Some(task) = existing {
+                        details["existingTask"] = json!(task);
+                    }
+                    Some(details)
+                }
+                EngineError::InvalidTransition { from, to } => {
+                    Some(json!({ "from": status_name(from), "to": status_name(to) }))
+                }
+                EngineError::TaskTerminal(status) => Some(json!({ "status": status_name(status) })),
+                EngineError::InvalidInput(msg) => Some(json!({ "errors": [msg] })),
+                EngineError::FilterPreset(FilterPresetError::UnknownPreset(preset)) => {
+                    Some(json!({ "preset": preset }))
+                }
+                EngineError::FilterPreset(
+                    FilterPresetError::LabelConflict { preset, key }
+                    | FilterPresetError::TaskMatchConflict { preset, key },
+                ) => Some(json!({ "preset": preset, "key": key })),
+                EngineError::Store(_) if deadline_exceeded(e).is_some() => {
+                    deadline_exceeded(e).map(|exceeded| json!({ "budgetMs": exceeded.budget_ms }))
+                }
+                EngineError::Store(_) => corrupt_record(e).map(|record| {
+                    json!({
+                        "taskId": record.task_id,
+                        "kind": record.kind,
+                        "id": record.id,
+                        "position": record.position,
+                    })
+                }),
+                EngineError::InvalidSeriesPatch(msg) => Some(json!({ "errors": [msg] })),
+                EngineError::SeriesLimitExceeded { task_id, limit } => {
+                    Some(json!({ "taskId": task_id, "limit": limit }))
+                }
+                EngineError::Archive(_) => None,
+                EngineError::PartialTaskGroup(partial) => {
+                    Some(json!({ "taskIds": partial.task_ids }))
+                }
+            },
+            AppError::BadRequest(msg) => Some(json!({ "errors": [msg] })),
+            AppError::Validation(violations) => {
+                let messages: Vec<_> = violations.iter().map(|v| &v.message).collect();
+                Some(json!({ "errors": messages, "violations": violations }))
+            }
+            AppError::InvalidQuery { param, reason } => {
+                Some(json!({ "param": param, "reason": reason }))
+            }
+            AppError::MissingScope(scope) => Some(json!({ "requiredScope": scope })),
+            AppError::TaskMismatch(task_id) => Some(json!({ "taskId": task_id })),
+            AppError::InsufficientStorage {
+                directory,
+                used_bytes,
+                max_bytes,
+            } => Some(json!({
+                "directory": directory,
+                "usedBytes": used_bytes,
+                "maxBytes": max_bytes,
+            })),
+            AppError::RunnerStopped(runner) => Some(json!({ "runner": runner })),
+            AppError::NotFound(_)
+            | AppError::Forbidden
+            | AppError::MissingToken
+            | AppError::InvalidToken
+            | AppError::InvalidServiceKey
+            | AppError::InvalidApiKey
+            | AppError::InvalidAdminToken
+            | AppError::NotImplemented(_)
+            | AppError::ReadOnly
+            | AppError::RoleElected
+            | AppError::Internal(_) => None,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            AppError::Engine(EngineError::TaskNotFound(_)) => "Task not found".to_string(),
+            // Driver messages can quote SQL, key names or server locale text,
+            // so they stay in logs.
+            AppError::Engine(e @ EngineError::Store(_)) if corrupt_record(e).is_none() => {
+                match store_error_kind(e) {
+                    Some(StoreErrorKind::Unavailable) => "Storage is unavailable",
+                    Some(StoreErrorKind::Timeout) => "Storage timed out",
+                    Some(StoreErrorKind::Conflict) => "Storage write conflicted with another",
+                    Some(StoreErrorKind::TooLarge) => "Value is too large for storage",
+                    Some(StoreErrorKind::Corrupt) => "Stored data could not be read",
+                    Some(StoreErrorKind::Other) | None => "Storage error",
+                }
+                .to_string()
+            }
+            AppError::NotFound(msg) => msg.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn payload(&self) -> ErrorPayload {
+        ErrorPayload {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+        }
+    }
+
+    fn failure_detail(&self) -> Option<HttpFailureDetail> {
+        match self {
+            AppError::Engine(EngineError::Archive(error)) => Some(HttpFailureDetail::new(
+                HttpFailureKind::Archive,
+                error.to_string(),
+            )),
+            AppError::Engine(EngineError::Store(error)) => {
+                let (detail, chain) = store_failure(error.as_ref());
+                Some(HttpFailureDetail::new(HttpFailureKind::Store, detail).with_source(chain))
+            }
+            AppError::NotImplemented(msg) | AppError::Internal(msg) => Some(
+                HttpFailureDetail::new(HttpFailureKind::Internal, msg.clone()),
             ),
-        };
+            AppError::InsufficientStorage { .. } => Some(HttpFailureDetail::new(
+                HttpFailureKind::Internal,
+                self.to_string(),
+            )),
+            _ => None,
+        }
+    }
+}
 
-        let mut response = (status, axum::Json(json!({ "error": message }))).into_response();
-        if let Some(detail) = detail {
+impl From<RunnerError> for AppError {
+    fn from(error: RunnerError) -> Self {
+        match error {
+            RunnerError::NotFound(name) => AppError::NotFound(format!("No runner named {name}")),
+            RunnerError::NotRunning(name) => AppError::RunnerStopped(name),
+        }
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::Full {
+                directory,
+                used_bytes,
+                max_bytes,
+            } => AppError::InsufficientStorage {
+                directory,
+                used_bytes,
+                max_bytes,
+            },
+            StorageError::InvalidFileName(_) => AppError::BadRequest(error.to_string()),
+            StorageError::UnknownDirectory(_) | StorageError::Io(_) => {
+                AppError::Internal(error.to_string())
+            }
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let payload = self.payload();
+        let mut response = (self.status(), axum::Json(payload.to_json(None))).into_response();
+        if let Some(detail) = self.failure_detail() {
             response.extensions_mut().insert(detail);
         }
+        response.extensions_mut().insert(payload);
         response
     }
 }
+
+fn request_id_from(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?.trim();
+    (!value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN).then(|| value.to_string())
+}
+
+/// Assigns a request id to every response and finalizes [`AppError`] bodies.
+///
+/// A caller-supplied `x-request-id` is echoed back; otherwise a ULID is
+/// generated. Error responses get `requestId` added and their message passed
+/// through the configured [`ErrorMessageProvider`], then are rendered by the
+/// presenter of the API version that served them.
+pub async fn error_response_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let request_id =
+        request_id_from(request.headers()).unwrap_or_else(|| ulid::Ulid::new().to_string());
+    let request_headers = state
+        .error_messages
+        .as_ref()
+        .map(|_| request.headers().clone());
+
+    let mut response = next.run(request).await;
+
+    if let Some(mut payload) = response.extensions().get::<ErrorPayload>().cloned() {
+        if let (Some(provider), Some(headers)) = (&state.error_messages, &request_headers) {
+            if let Some(message) = provider.message(&payload, headers) {
+                payload.message = message;
+            }
+        }
+        let mut body = payload.to_json(Some(&request_id));
+        if let Some(version) = response.extensions().get::<ApiVersion>() {
+            body = version.presenter.error(body);
+        }
+        *response.body_mut() = Body::from(serde_json::to_vec(&body).unwrap());
+        response.headers_mut().remove(CONTENT_LENGTH);
+        response.extensions_mut().insert(payload);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
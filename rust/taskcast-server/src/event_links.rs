@@ -0,0 +1,285 @@
+//! Signed links to a single event.
+//!
+//! A webhook delivery too large for its receiver is sent with the event's
+//! `data` replaced by a `fetchUrl` pointing at
+//! `GET /v1/tasks/{taskId}/events/{eventId}`. The link carries an expiry and
+//! an HMAC over the task id, event id and expiry, so a receiver holding no
+//! credentials can fetch that one event until the link expires.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{Method, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use taskcast_core::config::TaskcastConfig;
+use taskcast_core::PermissionScope;
+
+use crate::auth::{AuthContext, TaskIdAccess};
+use crate::error::AppError;
+use crate::routes::view::encode_path_segment;
+use crate::versioning::API_V1;
+
+/// How long a link stays valid unless `webhook.fetchLinkTtlMs` says
+/// otherwise.
+pub const DEFAULT_FETCH_LINK_TTL_MS: u64 = 86_400_000;
+
+/// Builds and checks signed event links.
+pub struct EventLinks {
+    base_url: Option<String>,
+    key: Vec<u8>,
+    ttl: Duration,
+}
+
+impl EventLinks {
+    /// Links under `base_url`, signed with `secret`. Without a secret a
+    /// random key is generated, so links only work on this process.
+    pub fn new(base_url: Option<&str>, secret: Option<&str>, ttl: Duration) -> Self {
+        let key = match secret {
+            Some(secret) => secret.as_bytes().to_vec(),
+            None => format!("{}{}", ulid::Ulid::new(), ulid::Ulid::new()).into_bytes(),
+        };
+        Self {
+            base_url: base_url.map(|url| url.trim_end_matches('/').to_string()),
+            key,
+            ttl,
+        }
+    }
+
+    /// Links as `serverBaseUrl` and the `webhook` section of `config` say.
+    pub fn from_config(config: Option<&TaskcastConfig>) -> Self {
+        let webhook = config.and_then(|c| c.webhook.as_ref());
+        Self::new(
+            config.and_then(|c| c.server_base_url.as_deref()),
+            webhook.and_then(|w| w.fetch_link_secret.as_deref()),
+            Duration::from_millis(
+                webhook
+                    .and_then(|w| w.fetch_link_ttl_ms)
+                    .unwrap_or(DEFAULT_FETCH_LINK_TTL_MS),
+            ),
+        )
+    }
+
+    /// A link to the event valid from now, or `None` without a base URL to
+    /// build it on.
+    pub fn fetch_url(&self, task_id: &str, event_id: &str) -> Option<String> {
+        self.fetch_url_at(task_id, event_id, SystemTime::now())
+    }
+
+    /// Like [`fetch_url`](Self::fetch_url), for a link issued at
+    /// `issued_at`.
+    pub fn fetch_url_at(
+        &self,
+        task_id: &str,
+        event_id: &str,
+        issued_at: SystemTime,
+    ) -> Option<String> {
+        let base_url = self.base_url.as_deref()?;
+        let expires = unix_secs(issued_at + self.ttl);
+        Some(format!(
+            "{base_url}/{API_V1}/tasks/{}/events/{}?expires={expires}&signature={}",
+            encode_path_segment(task_id),
+            encode_path_segment(event_id),
+            self.sign(task_id, event_id, expires),
+        ))
+    }
+
+    /// Whether `signature` was issued by these links for the event and
+    /// `expires` has not passed.
+    pub fn verify(&self, task_id: &str, event_id: &str, expires: u64, signature: &str) -> bool {
+        if expires < unix_secs(SystemTime::now()) {
+            return false;
+        }
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        self.mac(task_id, event_id, expires)
+            .verify_slice(&signature)
+            .is_ok()
+    }
+
+    fn sign(&self, task_id: &str, event_id: &str, expires: u64) -> String {
+        hex::encode(self.mac(task_id, event_id, expires).finalize().into_bytes())
+    }
+
+    fn mac(&self, task_id: &str, event_id: &str, expires: u64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("{task_id}\n{event_id}\n{expires}").as_bytes());
+        mac
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Marks a request authenticated by a signed event link, so
+/// [`auth_middleware`](crate::auth::auth_middleware) lets it through with the
+/// [`AuthContext`] set here.
+#[derive(Clone)]
+pub(crate) struct SignedEventLink;
+
+/// Authenticates `GET /tasks/{taskId}/events/{eventId}` requests carrying a
+/// `signature`, ahead of [`auth_middleware`](crate::auth::auth_middleware).
+/// A valid link may read that task's history and nothing else; an invalid or
+/// expired one is rejected. Requests without a signature are left alone.
+pub async fn event_link_middleware(
+    State(links): State<Arc<EventLinks>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some((task_id, event_id)) = event_path(req.method(), req.uri().path()) else {
+        return next.run(req).await;
+    };
+    let Ok(Query(pairs)) = Query::<Vec<(String, String)>>::try_from_uri(req.uri()) else {
+        return next.run(req).await;
+    };
+    let param = |name: &str| {
+        pairs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let Some(signature) = param("signature") else {
+        return next.run(req).await;
+    };
+    let expires = param("expires").and_then(|value| value.parse::<u64>().ok());
+    if !expires.is_some_and(|expires| links.verify(&task_id, &event_id, expires, signature)) {
+        return AppError::InvalidToken.into_response();
+    }
+    req.extensions_mut().insert(AuthContext {
+        sub: None,
+        jti: None,
+        worker_id: None,
+        task_ids: TaskIdAccess::List(vec![task_id]),
+        scope: vec![PermissionScope::EventHistory],
+    });
+    req.extensions_mut().insert(SignedEventLink);
+    next.run(req).await
+}
+
+/// The decoded task and event ids of a `GET /tasks/{taskId}/events/{eventId}`
+/// path.
+fn event_path(method: &Method, path: &str) -> Option<(String, String)> {
+    if method != Method::GET {
+        return None;
+    }
+    let mut segments = path.strip_prefix("/tasks/")?.split('/');
+    let task_id = segments.next()?;
+    if segments.next()? != "events" {
+        return None;
+    }
+    let event_id = segments.next()?;
+    if segments.next().is_some() || task_id.is_empty() || event_id.is_empty() {
+        return None;
+    }
+    Some((
+        decode_path_segment(task_id)?,
+        decode_path_segment(event_id)?,
+    ))
+}
+
+/// Reverses percent-encoding, or `None` if `segment` is not valid UTF-8
+/// once decoded.
+fn decode_path_segment(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| segment.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links() -> EventLinks {
+        EventLinks::new(
+            Some("https://taskcast.example.com/"),
+            Some("link-secret"),
+            Duration::from_secs(60),
+        )
+    }
+
+    fn query_param<'a>(url: &'a str, name: &str) -> &'a str {
+        let query = url.split_once('?').unwrap().1;
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(&format!("{name}=")))
+            .unwrap()
+    }
+
+    #[test]
+    fn fetch_url_is_signed_for_the_event() {
+        let links = links();
+        let url = links.fetch_url("task:1", "01EVENT").unwrap();
+        assert!(url.starts_with("https://taskcast.example.com/v1/tasks/task%3A1/events/01EVENT?"));
+        let expires: u64 = query_param(&url, "expires").parse().unwrap();
+        let signature = query_param(&url, "signature");
+        assert!(links.verify("task:1", "01EVENT", expires, signature));
+        assert!(!links.verify("task:1", "01OTHER", expires, signature));
+        assert!(!links.verify("task:2", "01EVENT", expires, signature));
+        assert!(!links.verify("task:1", "01EVENT", expires + 60, signature));
+    }
+
+    #[test]
+    fn expired_links_do_not_verify() {
+        let links = links();
+        let issued_at = SystemTime::now() - Duration::from_secs(120);
+        let url = links.fetch_url_at("t1", "e1", issued_at).unwrap();
+        let expires: u64 = query_param(&url, "expires").parse().unwrap();
+        assert!(!links.verify("t1", "e1", expires, query_param(&url, "signature")));
+    }
+
+    #[test]
+    fn links_signed_with_another_key_do_not_verify() {
+        let url = links().fetch_url("t1", "e1").unwrap();
+        let other = EventLinks::new(
+            Some("https://taskcast.example.com"),
+            None,
+            Duration::from_secs(60),
+        );
+        let expires: u64 = query_param(&url, "expires").parse().unwrap();
+        assert!(!other.verify("t1", "e1", expires, query_param(&url, "signature")));
+    }
+
+    #[test]
+    fn no_fetch_url_without_a_base_url() {
+        let links = EventLinks::new(None, Some("link-secret"), Duration::from_secs(60));
+        assert_eq!(links.fetch_url("t1", "e1"), None);
+    }
+
+    #[test]
+    fn event_path_matches_single_event_reads() {
+        assert_eq!(
+            event_path(&Method::GET, "/tasks/task%3A1/events/e1"),
+            Some(("task:1".to_string(), "e1".to_string()))
+        );
+        assert_eq!(event_path(&Method::POST, "/tasks/t1/events/e1"), None);
+        assert_eq!(event_path(&Method::GET, "/tasks/t1/events"), None);
+        assert_eq!(event_path(&Method::GET, "/tasks/t1/events/e1/more"), None);
+        assert_eq!(event_path(&Method::GET, "/tasks/t1/status/e1"), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Streaming task export, for `GET /tasks/export`.
+//!
+//! Matching tasks are read from the store a page at a time, in
+//! `(createdAt, id)` order, and written as one NDJSON line each, so memory
+//! stays bounded however many tasks match. Tasks the caller's token may not
+//! access are dropped page by page. The last line is a summary,
+//! `{"kind":"summary","count":n,"truncated":bool}`; a store failure ends the
+//! stream with a `{"kind":"error","error":{..}}` line instead.
+//!
+//! Under a request deadline the deadline is checked before each page, and
+//! the first chunk is read before the response starts, so an export that
+//! runs out of time on it is answered `504`. Once the response has started,
+//! running out of time ends the stream with an error line. A body dropped
+//! before it ends, by a client that went away, reads no more pages.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use serde_json::{json, Value};
+use taskcast_core::config::ExportConfig;
+use taskcast_core::{
+    DeadlineExceeded, EngineError, InFlightRequest, RequestDeadline, Task, TaskCursor, TaskEngine,
+    TaskFilter, TaskPage,
+};
+
+use crate::auth::TaskIdAccess;
+use crate::error::AppError;
+use crate::versioning::Presenter;
+
+pub const DEFAULT_EXPORT_MAX_ROWS: u64 = 100_000;
+pub const DEFAULT_EXPORT_CHUNK_SIZE: usize = 500;
+
+/// Limits applied to every export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportLimits {
+    pub max_rows: u64,
+    pub chunk_size: usize,
+}
+
+impl Default for ExportLimits {
+    fn default() -> Self {
+        Self {
+            max_rows: DEFAULT_EXPORT_MAX_ROWS,
+            chunk_size: DEFAULT_EXPORT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl ExportLimits {
+    pub fn from_config(config: Option<&ExportConfig>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_rows: config
+                .and_then(|c| c.max_rows)
+                .unwrap_or(defaults.max_rows),
+            chunk_size: config
+                .and_then(|c| c.chunk_size)
+                .unwrap_or(defaults.chunk_size)
+                .max(1),
+        }
+    }
+}
+
+/// Streams the tasks matching `filter` that come after `after` and that
+/// `task_ids` grants access to, reading the first chunk before returning.
+/// Fails only when `deadline` passes during that first chunk.
+#[allow(clippy::too_many_arguments)]
+pub async fn export_ndjson(
+    engine: Arc<TaskEngine>,
+    presenter: Arc<dyn Presenter>,
+    filter: TaskFilter,
+    after: Option<TaskCursor>,
+    task_ids: TaskIdAccess,
+    limits: ExportLimits,
+    deadline: Option<RequestDeadline>,
+) -> Result<Body, AppError> {
+    let mut export = Export {
+        engine,
+        presenter,
+        filter,
+        task_ids,
+        limits,
+        position: after,
+        count: 0,
+        done: false,
+        deadline,
+        exceeded: None,
+        in_flight: None,
+    };
+    let first = export.next_chunk().await;
+    if let Some(exceeded) = export.exceeded.take() {
+        return Err(AppError::Engine(EngineError::Store(Box::new(exceeded))));
+    }
+    // Until here the request itself was in flight.
+    if !export.done {
+        export.in_flight = Some(export.engine.request_counters().begin());
+    }
+    let rest = stream::unfold(export, |mut export| async move {
+        let chunk = export.next_chunk().await?;
+        if export.exceeded.take().is_some() {
+            export.engine.request_counters().record_deadline_exceeded();
+        }
+        Some((chunk, export))
+    });
+    let lines = stream::iter(first).chain(rest);
+    Ok(Body::from_stream(
+        lines.map(Ok::<_, std::convert::Infallible>),
+    ))
+}
+
+struct Export {
+    engine: Arc<TaskEngine>,
+    presenter: Arc<dyn Presenter>,
+    filter: TaskFilter,
+    task_ids: TaskIdAccess,
+    limits: ExportLimits,
+    /// Where the next page starts.
+    position: Option<TaskCursor>,
+    /// Tasks written so far.
+    count: u64,
+    done: bool,
+    deadline: Option<RequestDeadline>,
+    /// Set when the deadline ended the export.
+    exceeded: Option<DeadlineExceeded>,
+    /// Dropped unfinished, with the export, when the client goes away.
+    in_flight: Option<InFlightRequest>,
+}
+
+impl Export {
+    /// The lines for the next page with a task the caller may see, ending
+    /// with the summary or error line once the export is over. `None` after
+    /// that.
+    async fn next_chunk(&mut self) -> Option<Bytes> {
+        if self.done {
+            return None;
+        }
+        let mut out = Vec::new();
+        while out.is_empty() {
+            let page = match self.next_page().await {
+                Ok(page) => page,
+                Err(e) => {
+                    push_line(&mut out, &error_line(&AppError::Engine(e)));
+                    self.end();
+                    break;
+                }
+            };
+            for task in &page.tasks {
+                if !allows(&self.task_ids, task) {
+                    continue;
+                }
+                if self.count == self.limits.max_rows {
+                    self.finish(&mut out, true);
+                    return Some(Bytes::from(out));
+                }
+                push_line(&mut out, &self.presenter.task(task));
+                self.count += 1;
+            }
+            match page.next {
+                Some(next) => self.position = Some(next),
+                None => self.finish(&mut out, false),
+            }
+        }
+        Some(Bytes::from(out))
+    }
+
+    /// The page at the current position, unless the deadline passes first.
+    async fn next_page(&mut self) -> Result<TaskPage, EngineError> {
+        let list = self.engine.list_tasks_page(
+            self.filter.clone(),
+            self.position.clone(),
+            self.limits.chunk_size,
+        );
+        let Some(deadline) = self.deadline else {
+            return list.await;
+        };
+        let page = match deadline.check() {
+            Ok(()) => deadline.scope(deadline.run(list)).await,
+            Err(exceeded) => Err(exceeded),
+        };
+        page.unwrap_or_else(|exceeded| {
+            self.exceeded = Some(exceeded);
+            Err(EngineError::Store(Box::new(exceeded)))
+        })
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>, truncated: bool) {
+        push_line(
+            out,
+            &json!({ "kind": "summary", "count": self.count, "truncated": truncated }),
+        );
+        self.end();
+    }
+
+    fn end(&mut self) {
+        self.done = true;
+        if let Some(in_flight) = self.in_flight.take() {
+            in_flight.finish();
+        }
+    }
+}
+
+/// Whether `task_ids` grants access to `task`.
+fn allows(task_ids: &TaskIdAccess, task: &Task) -> bool {
+    match task_ids {
+        TaskIdAccess::All => true,
+        TaskIdAccess::List(ids) => ids.contains(&task.id),
+    }
+}
+
+fn error_line(error: &AppError) -> Value {
+    let payload = error.payload();
+    let mut body = json!({ "code": payload.code, "message": payload.message });
+    if let Some(details) = payload.details {
+        body["details"] = details;
+    }
+    json!({ "kind": "error", "error": body })
+}
+
+fn push_line(out: &mut Vec<u8>, value: &Value) {
+    serde_json::to_writer(&mut *out, value).unwrap();
+    out.push(b'\n');
+}
@@ -0,0 +1,457 @@
+//! Event frame payloads shared between the SSE connections of a task.
+//!
+//! Every connection tailing a task is handed the same live events. With the
+//! same `wrap` and `seriesFormat` their envelopes differ only in
+//! `filteredIndex`, so the payload is serialized once per event and class,
+//! with a placeholder where the index goes, and each connection splices its
+//! own index into a copy. The result is byte for byte what serializing the
+//! connection's own envelope gives.
+//!
+//! A payload is dropped once every connection open on the task when it was
+//! cached has taken it, and when the task's last connection closes. Since a
+//! connection whose filter excludes an event never takes it, each task also
+//! keeps at most [`MAX_FRAMES_PER_TASK`] payloads, dropping the oldest.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use taskcast_core::{SSEEnvelope, SeriesFormat, TaskEvent};
+
+/// Payloads each task keeps at most.
+pub const MAX_FRAMES_PER_TASK: usize = 256;
+
+/// Stands in for `filteredIndex` in a cached payload. As wide as any index,
+/// and an index a connection never reaches.
+const PLACEHOLDER: u64 = u64::MAX;
+
+/// The JSON payload of an event frame for `envelope`: the envelope itself
+/// when `wrap`, otherwise the bare event.
+pub fn event_payload(envelope: SSEEnvelope, wrap: bool) -> serde_json::Value {
+    if wrap {
+        serde_json::to_value(envelope).unwrap()
+    } else {
+        serde_json::to_value(TaskEvent::from(envelope)).unwrap()
+    }
+}
+
+/// A serialized payload, split where `filteredIndex` goes. Without a
+/// suffix the payload carries no index and is `prefix` alone.
+#[derive(Debug)]
+struct Spliced {
+    prefix: String,
+    suffix: Option<String>,
+}
+
+impl Spliced {
+    fn of(envelope: SSEEnvelope, wrap: bool) -> Option<Self> {
+        if !wrap {
+            let text = serde_json::to_string(&event_payload(envelope, false)).unwrap();
+            return Some(Self {
+                prefix: text,
+                suffix: None,
+            });
+        }
+        let envelope = SSEEnvelope {
+            filtered_index: PLACEHOLDER,
+            ..envelope
+        };
+        let text = serde_json::to_string(&event_payload(envelope, true)).unwrap();
+        // The key can only appear once outside of string values; a second
+        // match means event data nests one, so the payload is not shared.
+        let marker = format!("\"filteredIndex\":{PLACEHOLDER}");
+        let mut matches = text.match_indices(&marker);
+        let (at, _) = matches.next()?;
+        if matches.next().is_some() {
+            return None;
+        }
+        let split = at + marker.len() - PLACEHOLDER.to_string().len();
+        Some(Self {
+            prefix: text[..split].to_string(),
+            suffix: Some(text[at + marker.len()..].to_string()),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.prefix.len() + self.suffix.as_ref().map_or(0, String::len)
+    }
+
+    fn with_index(&self, filtered_index: u64) -> String {
+        match &self.suffix {
+            None => self.prefix.clone(),
+            Some(suffix) => format!("{}{filtered_index}{suffix}", self.prefix),
+        }
+    }
+}
+
+/// What an event's payload depends on besides the event: every connection
+/// with the same class gets the same payload, up to `filteredIndex`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FrameClass {
+    wrap: bool,
+    series_format: Option<SeriesFormat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FrameKey {
+    event_id: String,
+    class: FrameClass,
+}
+
+struct Cached {
+    payload: Arc<Spliced>,
+    /// Connections that still have to take it.
+    remaining: usize,
+}
+
+#[derive(Default)]
+struct TaskFrames {
+    connections: usize,
+    frames: HashMap<FrameKey, Cached>,
+    /// Cached keys, oldest first. May name keys already dropped.
+    order: VecDeque<FrameKey>,
+}
+
+impl TaskFrames {
+    fn insert(&mut self, key: FrameKey, cached: Cached) {
+        while self.frames.len() >= MAX_FRAMES_PER_TASK {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.frames.remove(&oldest);
+        }
+        self.order.push_back(key.clone());
+        self.frames.insert(key, cached);
+    }
+
+    fn take(&mut self, key: &FrameKey) -> Option<Arc<Spliced>> {
+        let cached = self.frames.get_mut(key)?;
+        let payload = Arc::clone(&cached.payload);
+        cached.remaining = cached.remaining.saturating_sub(1);
+        if cached.remaining == 0 {
+            self.frames.remove(key);
+            if self.frames.is_empty() {
+                self.order.clear();
+            }
+        }
+        Some(payload)
+    }
+}
+
+/// How much serializing the cache has done and saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameCacheStats {
+    /// Payloads serialized, shared or not.
+    pub serializations: u64,
+    /// Bytes those serializations produced.
+    pub serialized_bytes: u64,
+    /// Payloads handed out from the cache instead of serialized again.
+    pub hits: u64,
+}
+
+/// Payloads of live event frames, per task. One per server, shared by the
+/// SSE handler through an extension.
+#[derive(Default)]
+pub struct FrameCache {
+    tasks: Mutex<HashMap<String, Arc<Mutex<TaskFrames>>>>,
+    serializations: AtomicU64,
+    serialized_bytes: AtomicU64,
+    hits: AtomicU64,
+}
+
+impl FrameCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a connection on `task_id` until the returned lease is dropped.
+    /// The connection's payloads are wrapped envelopes when `wrap`, with
+    /// series data in `series_format`'s view.
+    pub fn open(
+        self: &Arc<Self>,
+        task_id: &str,
+        wrap: bool,
+        series_format: Option<&SeriesFormat>,
+    ) -> FrameLease {
+        let frames = {
+            let mut tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(tasks.entry(task_id.to_string()).or_default())
+        };
+        frames
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .connections += 1;
+        FrameLease {
+            cache: Arc::clone(self),
+            task_id: task_id.to_string(),
+            class: FrameClass {
+                wrap,
+                series_format: series_format.cloned(),
+            },
+            frames,
+        }
+    }
+
+    pub fn stats(&self) -> FrameCacheStats {
+        FrameCacheStats {
+            serializations: self.serializations.load(Ordering::Relaxed),
+            serialized_bytes: self.serialized_bytes.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Payloads cached for `task_id`.
+    pub fn cached(&self, task_id: &str) -> usize {
+        let tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+        tasks.get(task_id).map_or(0, |frames| {
+            frames
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .frames
+                .len()
+        })
+    }
+
+    fn serialized(&self, bytes: usize) {
+        self.serializations.fetch_add(1, Ordering::Relaxed);
+        self.serialized_bytes
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// One SSE connection's use of the cache for its task.
+pub struct FrameLease {
+    cache: Arc<FrameCache>,
+    task_id: String,
+    class: FrameClass,
+    frames: Arc<Mutex<TaskFrames>>,
+}
+
+impl FrameLease {
+    /// The serialized payload of a live event frame for `envelope`, as
+    /// [`event_payload`] gives it.
+    pub fn payload(&self, envelope: SSEEnvelope) -> String {
+        let wrap = self.class.wrap;
+        let key = FrameKey {
+            event_id: envelope.event_id.clone(),
+            class: self.class.clone(),
+        };
+        let filtered_index = envelope.filtered_index;
+        // Serialized under the lock, so connections racing for a new event
+        // wait for the one serialization instead of each making their own.
+        let mut frames = self.frames.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(payload) = frames.take(&key) {
+            self.cache.hits.fetch_add(1, Ordering::Relaxed);
+            return payload.with_index(filtered_index);
+        }
+        // Nobody to share with: skip the splitting.
+        let spliced = match frames.connections {
+            0 | 1 => None,
+            _ => Spliced::of(envelope.clone(), wrap),
+        };
+        let Some(spliced) = spliced else {
+            drop(frames);
+            let text = serde_json::to_string(&event_payload(envelope, wrap)).unwrap();
+            self.cache.serialized(text.len());
+            return text;
+        };
+        self.cache.serialized(spliced.len());
+        let text = spliced.with_index(filtered_index);
+        let remaining = frames.connections.saturating_sub(1);
+        if remaining > 0 {
+            frames.insert(
+                key,
+                Cached {
+                    payload: Arc::new(spliced),
+                    remaining,
+                },
+            );
+        }
+        text
+    }
+}
+
+impl Drop for FrameLease {
+    fn drop(&mut self) {
+        let mut tasks = self
+            .cache
+            .tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut frames = self.frames.lock().unwrap_or_else(PoisonError::into_inner);
+        frames.connections -= 1;
+        if frames.connections == 0 {
+            drop(frames);
+            tasks.remove(&self.task_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use taskcast_core::{Level, SeriesMode};
+
+    use super::*;
+
+    fn envelope(filtered_index: u64) -> SSEEnvelope {
+        SSEEnvelope {
+            filtered_index,
+            raw_index: 4,
+            event_id: "e4".to_string(),
+            task_id: "t1".to_string(),
+            r#type: "llm.delta".to_string(),
+            timestamp: 1700000000123.5,
+            level: Level::Info,
+            data: json!({ "text": "hi \"there\"\n", "n": [1, 2.5, null] }),
+            series_id: None,
+            series_mode: None,
+            series_acc_field: None,
+            series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
+            filter_epoch: None,
+        }
+    }
+
+    fn variants() -> Vec<SSEEnvelope> {
+        let base = envelope(0);
+        vec![
+            base.clone(),
+            SSEEnvelope {
+                series_id: Some("s1".to_string()),
+                series_mode: Some(SeriesMode::Accumulate),
+                series_acc_field: Some("text".to_string()),
+                ..base.clone()
+            },
+            SSEEnvelope {
+                series_id: Some("s2".to_string()),
+                series_mode: Some(SeriesMode::Latest),
+                replaces_event_id: Some("e3".to_string()),
+                ..base.clone()
+            },
+            SSEEnvelope {
+                series_snapshot: Some(true),
+                labels: Some(HashMap::from([
+                    ("team".to_string(), "a".to_string()),
+                    ("filteredIndex".to_string(), "7".to_string()),
+                ])),
+                ..base.clone()
+            },
+            SSEEnvelope {
+                level: Level::Error,
+                data: json!("filteredIndex"),
+                ..base.clone()
+            },
+            SSEEnvelope {
+                data: json!({ "filteredIndex": 3, "nested": { "filteredIndex": u64::MAX } }),
+                ..base
+            },
+        ]
+    }
+
+    fn expected(envelope: &SSEEnvelope, wrap: bool) -> String {
+        serde_json::to_string(&event_payload(envelope.clone(), wrap)).unwrap()
+    }
+
+    #[test]
+    fn shared_payloads_match_each_connections_own_serialization() {
+        let cache = Arc::new(FrameCache::new());
+        for wrap in [true, false] {
+            for format in [None, Some(SeriesFormat::Accumulated)] {
+                let leases: Vec<_> = (0..3)
+                    .map(|_| cache.open("t1", wrap, format.as_ref()))
+                    .collect();
+                for variant in variants() {
+                    for (n, lease) in leases.iter().enumerate() {
+                        let filtered_index = [0, 9, u64::MAX - 1][n];
+                        let own = SSEEnvelope {
+                            filtered_index,
+                            ..variant.clone()
+                        };
+                        assert_eq!(
+                            lease.payload(own.clone()),
+                            expected(&own, wrap),
+                            "wrap={wrap} index={filtered_index} {variant:?}"
+                        );
+                    }
+                }
+            }
+        }
+        assert!(cache.stats().hits > 0);
+    }
+
+    #[test]
+    fn one_serialization_serves_every_connection() {
+        let cache = Arc::new(FrameCache::new());
+        let leases: Vec<_> = (0..100).map(|_| cache.open("t1", true, None)).collect();
+        for (n, lease) in leases.iter().enumerate() {
+            lease.payload(envelope(n as u64));
+        }
+        let stats = cache.stats();
+        assert_eq!(stats.serializations, 1);
+        assert_eq!(stats.hits, 99);
+        // Every connection took it, so it is gone.
+        assert_eq!(cache.cached("t1"), 0);
+    }
+
+    #[test]
+    fn classes_are_cached_apart() {
+        let cache = Arc::new(FrameCache::new());
+        let leases = [
+            cache.open("t1", true, Some(&SeriesFormat::Accumulated)),
+            cache.open("t1", true, Some(&SeriesFormat::Accumulated)),
+            cache.open("t1", true, None),
+            cache.open("t1", true, None),
+            cache.open("t1", false, None),
+            cache.open("t1", false, None),
+        ];
+        for lease in &leases {
+            lease.payload(envelope(0));
+        }
+        assert_eq!(cache.stats().serializations, 3);
+        assert_eq!(cache.stats().hits, 3);
+    }
+
+    #[test]
+    fn nested_index_keys_fall_back_to_serializing_per_connection() {
+        let cache = Arc::new(FrameCache::new());
+        let leases: Vec<_> = (0..2).map(|_| cache.open("t1", true, None)).collect();
+        let nested = variants().pop().unwrap();
+        for lease in &leases {
+            lease.payload(nested.clone());
+        }
+        assert_eq!(cache.stats().serializations, 2);
+        assert_eq!(cache.stats().hits, 0);
+    }
+
+    #[test]
+    fn payloads_are_bounded_per_task_and_dropped_with_the_last_connection() {
+        let cache = Arc::new(FrameCache::new());
+        let first = cache.open("t1", true, None);
+        let second = cache.open("t1", true, None);
+        for n in 0..(MAX_FRAMES_PER_TASK as u64 + 10) {
+            let envelope = SSEEnvelope {
+                event_id: format!("e{n}"),
+                ..envelope(n)
+            };
+            first.payload(envelope);
+        }
+        assert_eq!(cache.cached("t1"), MAX_FRAMES_PER_TASK);
+        drop(first);
+        assert_eq!(cache.cached("t1"), MAX_FRAMES_PER_TASK);
+        drop(second);
+        assert_eq!(cache.cached("t1"), 0);
+        assert!(cache.tasks.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_lone_connection_caches_nothing() {
+        let cache = Arc::new(FrameCache::new());
+        let lease = cache.open("t1", true, None);
+        lease.payload(envelope(0));
+        assert_eq!(cache.cached("t1"), 0);
+    }
+}
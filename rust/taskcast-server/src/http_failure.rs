@@ -44,6 +44,10 @@ impl LogLevel {
     pub fn allows_error(self) -> bool {
         self.priority() <= Self::Error.priority()
     }
+
+    pub fn allows_debug(self) -> bool {
+        self.priority() <= Self::Debug.priority()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
@@ -58,6 +62,8 @@ pub enum HttpFailureKind {
 pub(crate) struct HttpFailureDetail {
     pub(crate) error_kind: HttpFailureKind,
     pub(crate) error: String,
+    /// The full error chain, when `error` is an abbreviation of it.
+    pub(crate) source: Option<String>,
 }
 
 impl HttpFailureDetail {
@@ -65,6 +71,14 @@ impl HttpFailureDetail {
         Self {
             error_kind,
             error: error.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_source(self, source: impl Into<String>) -> Self {
+        Self {
+            source: Some(source.into()),
+            ..self
         }
     }
 }
@@ -82,6 +96,10 @@ pub struct HttpFailureLog {
     pub error_kind: Option<HttpFailureKind>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Full error chain behind `error`. Only written by loggers at debug
+    /// level, since driver errors can be long and quote queries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 pub trait HttpFailureLogger: Send + Sync + 'static {
@@ -101,9 +119,13 @@ impl StderrHttpFailureLogger {
 impl HttpFailureLogger for StderrHttpFailureLogger {
     fn log(&self, record: &HttpFailureLog) {
         if self.level.allows_error() {
+            let mut record = record.clone();
+            if !self.level.allows_debug() {
+                record.source = None;
+            }
             eprintln!(
                 "{}",
-                serde_json::to_string(record)
+                serde_json::to_string(&record)
                     .expect("HttpFailureLog contains only serializable fields")
             );
         }
@@ -157,6 +179,9 @@ pub async fn http_failure_logger_middleware(
             status: response.status().as_u16(),
             error_kind: detail.map(|value| value.error_kind),
             error: detail.and_then(|value| sanitize_error_message(&value.error)),
+            source: detail
+                .and_then(|value| value.source.as_deref())
+                .and_then(sanitize_error_message),
         };
         logger.log(&record);
     }
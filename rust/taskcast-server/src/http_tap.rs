@@ -0,0 +1,470 @@
+//! Debug tap that records matching HTTP exchanges for `GET /admin/http-tap`.
+//!
+//! Bodies are redacted at the configured JSON paths and truncated before an
+//! entry is stored, and entries live only in a bounded in-memory ring
+//! buffer. Streaming (SSE) responses are recorded by their headers alone.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderMap, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use taskcast_core::config::HttpTapConfig;
+
+use crate::ingest::NDJSON_CONTENT_TYPE;
+use crate::timestamps;
+use crate::versioning::unversioned_path;
+
+/// Path of the endpoint that reads the tap; never recorded itself.
+pub const HTTP_TAP_PATH: &str = "/admin/http-tap";
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Request bodies whose `Content-Length` exceeds this are not buffered for
+/// the tap.
+const MAX_CAPTURE_BYTES: usize = 1024 * 1024;
+
+const DEFAULT_MAX_BODY_BYTES: usize = 4096;
+const DEFAULT_CAPACITY: usize = 500;
+
+const SENSITIVE_HEADERS: [&str; 5] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-taskcast-service-key",
+];
+
+// ─── Entries ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpTapEntry {
+    pub id: u64,
+    /// Epoch milliseconds at which the request arrived.
+    pub timestamp: f64,
+    pub method: String,
+    pub path: String,
+    /// The configured pattern that matched, if routes are configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route: Option<String>,
+    pub status: u16,
+    pub duration_ms: f64,
+    pub request_headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub request_body_truncated: bool,
+    pub response_headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    pub response_body_truncated: bool,
+    /// The response was a stream (SSE); only its headers were recorded.
+    pub streaming: bool,
+}
+
+/// Filters for [`HttpTap::entries`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpTapQuery {
+    /// Matched route pattern, e.g. `POST /tasks/*/events`.
+    pub route: Option<String>,
+    /// Exact status (`404`) or class (`4xx`).
+    pub status: Option<String>,
+    /// Entries at or after this time, in epoch milliseconds or RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub since: Option<f64>,
+    /// Entries at or before this time, in epoch milliseconds or RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub until: Option<f64>,
+    pub limit: Option<usize>,
+}
+
+// ─── Route Patterns ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct RoutePattern {
+    source: String,
+    method: Option<Method>,
+    segments: Vec<String>,
+}
+
+impl RoutePattern {
+    fn parse(source: &str) -> Self {
+        let source = source.trim();
+        let (method, path) = match source.split_once(' ') {
+            Some((method, path)) => (Method::from_bytes(method.as_bytes()).ok(), path.trim()),
+            None => (None, source),
+        };
+        Self {
+            source: source.to_string(),
+            method,
+            segments: split_path(path).map(str::to_string).collect(),
+        }
+    }
+
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if self.method.as_ref().is_some_and(|m| m != method) {
+            return false;
+        }
+        let mut segments = split_path(path);
+        for pattern in &self.segments {
+            match segments.next() {
+                Some(segment) if pattern == "*" || pattern == segment => {}
+                _ => return false,
+            }
+        }
+        segments.next().is_none()
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+// ─── Tap ────────────────────────────────────────────────────────────────────
+
+/// Bounded in-memory record of HTTP exchanges on the configured routes.
+pub struct HttpTap {
+    enabled: AtomicBool,
+    routes: Vec<RoutePattern>,
+    max_body_bytes: usize,
+    redact_paths: Vec<Vec<String>>,
+    capacity: usize,
+    next_id: AtomicU64,
+    entries: Mutex<VecDeque<HttpTapEntry>>,
+}
+
+impl HttpTap {
+    pub fn new(config: &HttpTapConfig) -> Self {
+        Self {
+            enabled: AtomicBool::new(config.enabled.unwrap_or(false)),
+            routes: config
+                .routes
+                .iter()
+                .flatten()
+                .map(|route| RoutePattern::parse(route))
+                .collect(),
+            max_body_bytes: config.max_body_bytes.unwrap_or(DEFAULT_MAX_BODY_BYTES),
+            redact_paths: config
+                .redact_paths
+                .iter()
+                .flatten()
+                .map(|path| path.split('.').map(str::to_string).collect())
+                .collect(),
+            capacity: config.capacity.unwrap_or(DEFAULT_CAPACITY).max(1),
+            next_id: AtomicU64::new(1),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Recorded entries matching `query`, newest first.
+    pub fn entries(&self, query: &HttpTapQuery) -> Vec<HttpTapEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| {
+                query
+                    .route
+                    .as_deref()
+                    .is_none_or(|route| entry.route.as_deref() == Some(route))
+                    && query
+                        .status
+                        .as_deref()
+                        .is_none_or(|status| status_matches(status, entry.status))
+                    && query.since.is_none_or(|since| entry.timestamp >= since)
+                    && query.until.is_none_or(|until| entry.timestamp <= until)
+            })
+            .take(query.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// The pattern `method path` is recorded under, or `None` if it is not
+    /// tapped. With no routes configured every path is tapped. Patterns
+    /// match the path with its API version prefix removed.
+    fn route_for(&self, method: &Method, path: &str) -> Option<Option<String>> {
+        let path = unversioned_path(path);
+        if path == HTTP_TAP_PATH {
+            return None;
+        }
+        if self.routes.is_empty() {
+            return Some(None);
+        }
+        self.routes
+            .iter()
+            .find(|route| route.matches(method, path))
+            .map(|route| Some(route.source.clone()))
+    }
+
+    /// Redacts and truncates `bytes` for storage.
+    fn capture_body(&self, bytes: &[u8]) -> (Option<String>, bool) {
+        if bytes.is_empty() {
+            return (None, false);
+        }
+        let text = match serde_json::from_slice::<Value>(bytes) {
+            Ok(mut value) => {
+                for path in &self.redact_paths {
+                    redact(&mut value, path);
+                }
+                value.to_string()
+            }
+            // Non-JSON bodies cannot be redacted, so they are not kept.
+            Err(_) => format!("<{} bytes, not JSON>", bytes.len()),
+        };
+        let (text, truncated) = truncate(text, self.max_body_bytes);
+        (Some(text), truncated)
+    }
+
+    fn push(&self, mut entry: HttpTapEntry) {
+        entry.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+}
+
+fn status_matches(filter: &str, status: u16) -> bool {
+    match filter.strip_suffix("xx") {
+        Some(class) => class
+            .parse::<u16>()
+            .is_ok_and(|class| status / 100 == class),
+        None => filter.parse::<u16>().is_ok_and(|code| code == status),
+    }
+}
+
+/// Replaces the value at `path`. A non-numeric segment applied to an array
+/// is applied to each of its elements, so `data.apiKey` also covers a batch
+/// of events.
+fn redact(value: &mut Value, path: &[String]) {
+    let Some((head, rest)) = path.split_first() else {
+        return;
+    };
+    match value {
+        Value::Object(map) => {
+            if let Some(child) = map.get_mut(head) {
+                if rest.is_empty() {
+                    *child = Value::String(REDACTED.to_string());
+                } else {
+                    redact(child, rest);
+                }
+            }
+        }
+        Value::Array(items) => match head.parse::<usize>() {
+            Ok(index) => {
+                if let Some(child) = items.get_mut(index) {
+                    if rest.is_empty() {
+                        *child = Value::String(REDACTED.to_string());
+                    } else {
+                        redact(child, rest);
+                    }
+                }
+            }
+            Err(_) => {
+                for item in items {
+                    redact(item, path);
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+fn truncate(mut text: String, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text, false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    (text, true)
+}
+
+fn header_map(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if SENSITIVE_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+/// SSE and NDJSON bodies are streamed, so they are passed through instead
+/// of buffered.
+fn is_streaming(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value.starts_with("text/event-stream") || value.starts_with(NDJSON_CONTENT_TYPE)
+        })
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
+
+// ─── Middleware ─────────────────────────────────────────────────────────────
+
+/// Records requests on the tap's routes into the [`HttpTap`].
+///
+/// Requests are passed through untouched when the tap is disabled or the
+/// route is not tapped; bodies are only buffered after both checks.
+pub async fn http_tap_middleware(
+    State(tap): State<Arc<HttpTap>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !tap.is_enabled() {
+        return next.run(request).await;
+    }
+    let Some(route) = tap.route_for(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let timestamp = now_ms();
+    let started = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let request_headers = header_map(request.headers());
+
+    let body_too_large = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok())
+        .is_some_and(|len| len > MAX_CAPTURE_BYTES);
+    let should_capture_body = matches!(method, Method::POST | Method::PATCH | Method::PUT)
+        && !body_too_large
+        && !is_streaming(request.headers());
+    let (request_bytes, request) = if should_capture_body {
+        let (parts, body) = request.into_parts();
+        match axum::body::to_bytes(body, MAX_CAPTURE_BYTES).await {
+            Ok(bytes) => (bytes.clone(), Request::from_parts(parts, Body::from(bytes))),
+            Err(_) => (Bytes::new(), Request::from_parts(parts, Body::empty())),
+        }
+    } else {
+        (Bytes::new(), request)
+    };
+    let (request_body, request_body_truncated) = tap.capture_body(&request_bytes);
+
+    let response = next.run(request).await;
+    let status = response.status().as_u16();
+    let response_headers = header_map(response.headers());
+
+    let streaming = is_streaming(response.headers());
+    let (response_body, response_body_truncated, response) = if streaming {
+        (None, false, response)
+    } else {
+        let (parts, body) = response.into_parts();
+        let bytes = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .unwrap_or_default();
+        let (captured, truncated) = tap.capture_body(&bytes);
+        (
+            captured,
+            truncated,
+            Response::from_parts(parts, Body::from(bytes)),
+        )
+    };
+
+    tap.push(HttpTapEntry {
+        id: 0,
+        timestamp,
+        method: method.to_string(),
+        path,
+        route,
+        status,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        request_headers,
+        request_body,
+        request_body_truncated,
+        response_headers,
+        response_body,
+        response_body_truncated,
+        streaming,
+    });
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn route_patterns_match_method_and_segments() {
+        let pattern = RoutePattern::parse("POST /tasks/*/events");
+        assert!(pattern.matches(&Method::POST, "/tasks/t1/events"));
+        assert!(!pattern.matches(&Method::GET, "/tasks/t1/events"));
+        assert!(!pattern.matches(&Method::POST, "/tasks/t1/events/history"));
+        assert!(!pattern.matches(&Method::POST, "/tasks/events"));
+
+        let any_method = RoutePattern::parse("/tasks/*");
+        assert!(any_method.matches(&Method::GET, "/tasks/t1"));
+        assert!(any_method.matches(&Method::DELETE, "/tasks/t1/"));
+    }
+
+    #[test]
+    fn redact_replaces_nested_paths_and_array_elements() {
+        let mut value = json!([
+            { "type": "a", "data": { "apiKey": "k1", "keep": 1 } },
+            { "type": "b", "data": { "other": true } },
+        ]);
+        redact(&mut value, &["data".to_string(), "apiKey".to_string()]);
+        assert_eq!(value[0]["data"]["apiKey"], REDACTED);
+        assert_eq!(value[0]["data"]["keep"], 1);
+        assert!(value[1]["data"].get("apiKey").is_none());
+
+        let mut value = json!({ "items": ["a", "b"] });
+        redact(&mut value, &["items".to_string(), "1".to_string()]);
+        assert_eq!(value, json!({ "items": ["a", REDACTED] }));
+    }
+
+    #[test]
+    fn truncate_respects_char_boundaries() {
+        assert_eq!(truncate("héllo".to_string(), 2), ("h".to_string(), true));
+        assert_eq!(truncate("abc".to_string(), 3), ("abc".to_string(), false));
+    }
+
+    #[test]
+    fn status_filter_accepts_codes_and_classes() {
+        assert!(status_matches("404", 404));
+        assert!(status_matches("4xx", 422));
+        assert!(!status_matches("4xx", 500));
+        assert!(!status_matches("abc", 200));
+    }
+}
@@ -0,0 +1,340 @@
+//! Incremental NDJSON publishing, for `POST /tasks/:taskId/events/stream`.
+//!
+//! The body is read a line at a time and every `chunk_size` events are
+//! stored, then acknowledged with one `{"id", "index"}` line each. The
+//! response is produced as the client reads it, so a client that stops
+//! reading also stops the server reading its body. A bad line, a limit or a
+//! failed publish ends the response with an error line; chunks acknowledged
+//! before it stay stored.
+
+use std::sync::Arc;
+
+use axum::body::{Body, BodyDataStream};
+use bytes::{Bytes, BytesMut};
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use taskcast_core::config::IngestConfig;
+use taskcast_core::{EngineError, PublishEventInput, TaskEngine, TaskEvent};
+
+use crate::error::AppError;
+use crate::routes::tasks::PublishEventBody;
+use crate::strict::BodyStrictness;
+use crate::webhook::SyncWebhooks;
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+pub const DEFAULT_INGEST_CHUNK_SIZE: usize = 500;
+pub const DEFAULT_INGEST_MAX_LINE_BYTES: usize = 1024 * 1024;
+pub const DEFAULT_INGEST_MAX_EVENTS: usize = 1_000_000;
+
+/// Limits applied to every streamed publish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngestLimits {
+    pub chunk_size: usize,
+    pub max_line_bytes: usize,
+    pub max_events: usize,
+    pub max_request_bytes: Option<u64>,
+}
+
+impl Default for IngestLimits {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_INGEST_CHUNK_SIZE,
+            max_line_bytes: DEFAULT_INGEST_MAX_LINE_BYTES,
+            max_events: DEFAULT_INGEST_MAX_EVENTS,
+            max_request_bytes: None,
+        }
+    }
+}
+
+impl IngestLimits {
+    pub fn from_config(config: Option<&IngestConfig>) -> Self {
+        let defaults = Self::default();
+        Self {
+            chunk_size: config
+                .and_then(|c| c.chunk_size)
+                .unwrap_or(defaults.chunk_size)
+                .max(1),
+            max_line_bytes: config
+                .and_then(|c| c.max_line_bytes)
+                .unwrap_or(defaults.max_line_bytes),
+            max_events: config
+                .and_then(|c| c.max_events)
+                .unwrap_or(defaults.max_events),
+            max_request_bytes: config.and_then(|c| c.max_request_bytes),
+        }
+    }
+}
+
+/// What ended a stream early, and the 1-based line it happened on.
+struct IngestError {
+    line: usize,
+    error: AppError,
+}
+
+impl IngestError {
+    fn new(line: usize, error: AppError) -> Self {
+        Self { line, error }
+    }
+
+    fn to_line(&self) -> Value {
+        let payload = self.error.payload();
+        let mut error = json!({ "code": payload.code, "message": payload.message });
+        if let Some(details) = payload.details {
+            error["details"] = details;
+        }
+        json!({ "line": self.line, "error": error })
+    }
+}
+
+/// Reads `body` as NDJSON and publishes its events to `task_id`, returning
+/// the acknowledgement stream to send back.
+pub fn ingest_ndjson(
+    engine: Arc<TaskEngine>,
+    sync_webhooks: Arc<SyncWebhooks>,
+    task_id: String,
+    limits: IngestLimits,
+    strictness: BodyStrictness,
+    body: Body,
+) -> Body {
+    let ingest = Ingest {
+        engine,
+        sync_webhooks,
+        task_id,
+        limits,
+        strictness,
+        body: body.into_data_stream(),
+        buffer: BytesMut::new(),
+        line: 0,
+        events: 0,
+        request_bytes: 0,
+        body_done: false,
+        done: false,
+    };
+    let acks = stream::unfold(ingest, |mut ingest| async move {
+        let chunk = ingest.next_chunk().await?;
+        Some((Ok::<_, std::convert::Infallible>(chunk), ingest))
+    });
+    Body::from_stream(acks)
+}
+
+struct Ingest {
+    engine: Arc<TaskEngine>,
+    sync_webhooks: Arc<SyncWebhooks>,
+    task_id: String,
+    limits: IngestLimits,
+    strictness: BodyStrictness,
+    body: BodyDataStream,
+    /// Bytes read past the last complete line.
+    buffer: BytesMut,
+    /// Lines consumed so far, blank ones included.
+    line: usize,
+    events: usize,
+    request_bytes: u64,
+    body_done: bool,
+    done: bool,
+}
+
+impl Ingest {
+    /// Stores the next chunk and returns its acknowledgements, followed by
+    /// an error line if the stream ends there. `None` once it has ended.
+    async fn next_chunk(&mut self) -> Option<Bytes> {
+        if self.done {
+            return None;
+        }
+        let mut pending = Vec::with_capacity(self.limits.chunk_size);
+        let mut outcome = Ok(());
+        while pending.len() < self.limits.chunk_size {
+            match self.next_event().await {
+                Ok(Some(event)) => pending.push(event),
+                Ok(None) => {
+                    self.done = true;
+                    break;
+                }
+                Err(error) => {
+                    outcome = Err(error);
+                    break;
+                }
+            }
+        }
+        // Lines before a bad one are stored, so the client can resume
+        // right after the last acknowledgement.
+        let (events, committed) =
+            commit(&self.engine, &self.sync_webhooks, &self.task_id, pending).await;
+        let mut out = Vec::new();
+        for (event, results) in events {
+            let mut ack = json!({ "id": event.id, "index": event.index });
+            if let Some(results) = results {
+                ack["webhookResults"] = results;
+            }
+            push_line(&mut out, &ack);
+        }
+        if let Err(error) = committed.and(outcome) {
+            push_line(&mut out, &error.to_line());
+            self.done = true;
+        }
+        if out.is_empty() {
+            return None;
+        }
+        Some(Bytes::from(out))
+    }
+
+    /// Parses the next non-blank line, with its line number.
+    async fn next_event(&mut self) -> Result<Option<(usize, PublishEventInput)>, IngestError> {
+        loop {
+            let Some(bytes) = self.next_line().await? else {
+                return Ok(None);
+            };
+            if bytes.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let invalid = |e: serde_json::Error| {
+                IngestError::new(
+                    self.line,
+                    AppError::BadRequest(format!("Invalid event on line {}: {e}", self.line)),
+                )
+            };
+            let body: PublishEventBody = if self.strictness.strict {
+                let value: Value = serde_json::from_slice(&bytes).map_err(invalid)?;
+                let violations = self.strictness.check::<PublishEventBody>(&value, "");
+                if !violations.is_empty() {
+                    return Err(IngestError::new(
+                        self.line,
+                        AppError::Validation(violations),
+                    ));
+                }
+                serde_json::from_value(value).map_err(invalid)?
+            } else {
+                serde_json::from_slice(&bytes).map_err(invalid)?
+            };
+            self.events += 1;
+            if self.events > self.limits.max_events {
+                return Err(IngestError::new(
+                    self.line,
+                    AppError::BadRequest(format!(
+                        "A stream may publish at most {} events",
+                        self.limits.max_events
+                    )),
+                ));
+            }
+            let input = PublishEventInput {
+                r#type: body.r#type,
+                level: body.level,
+                data: body.data,
+                series_id: body.series_id,
+                series_mode: body.series_mode,
+                series_acc_field: body.series_acc_field,
+                labels: body.labels,
+                broadcast_debounce_ms: body.broadcast_debounce_ms,
+                series_end: body.series_end.unwrap_or(false),
+            };
+            return Ok(Some((self.line, input)));
+        }
+    }
+
+    /// The next line without its terminator, reading more of the body as
+    /// needed. A last line without a newline counts.
+    async fn next_line(&mut self) -> Result<Option<Bytes>, IngestError> {
+        let mut scanned = 0;
+        loop {
+            if let Some(pos) = self.buffer[scanned..].iter().position(|&b| b == b'\n') {
+                let mut line = self.buffer.split_to(scanned + pos + 1);
+                line.truncate(line.len() - 1);
+                if line.last() == Some(&b'\r') {
+                    line.truncate(line.len() - 1);
+                }
+                self.line += 1;
+                self.check_line_length(self.line, line.len())?;
+                return Ok(Some(line.freeze()));
+            }
+            scanned = self.buffer.len();
+            self.check_line_length(self.line + 1, self.buffer.len())?;
+            if self.body_done {
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                self.line += 1;
+                return Ok(Some(self.buffer.split().freeze()));
+            }
+            match self.body.next().await {
+                Some(Ok(frame)) => {
+                    self.request_bytes += frame.len() as u64;
+                    if let Some(max) = self
+                        .limits
+                        .max_request_bytes
+                        .filter(|&max| self.request_bytes > max)
+                    {
+                        return Err(IngestError::new(
+                            self.line + 1,
+                            AppError::BadRequest(format!("A stream may send at most {max} bytes")),
+                        ));
+                    }
+                    self.buffer.extend_from_slice(&frame);
+                }
+                Some(Err(e)) => {
+                    return Err(IngestError::new(
+                        self.line + 1,
+                        AppError::BadRequest(format!("Failed to read request body: {e}")),
+                    ))
+                }
+                None => self.body_done = true,
+            }
+        }
+    }
+
+    fn check_line_length(&self, line: usize, len: usize) -> Result<(), IngestError> {
+        if len <= self.limits.max_line_bytes {
+            return Ok(());
+        }
+        Err(IngestError::new(
+            line,
+            AppError::BadRequest(format!(
+                "Line {line} is longer than {} bytes",
+                self.limits.max_line_bytes
+            )),
+        ))
+    }
+}
+
+/// Publishes `pending` in order, stopping at the first failure. Returns
+/// the stored events with their sync webhook results.
+async fn commit(
+    engine: &TaskEngine,
+    sync_webhooks: &SyncWebhooks,
+    task_id: &str,
+    pending: Vec<(usize, PublishEventInput)>,
+) -> (Vec<(TaskEvent, Option<Value>)>, Result<(), IngestError>) {
+    let mut events = Vec::with_capacity(pending.len());
+    let mut outcome = Ok(());
+    for (line, input) in pending {
+        match engine.publish_event(task_id, input).await {
+            Ok(event) => events.push(event),
+            Err(e) => {
+                let error = match &e {
+                    EngineError::TaskTerminal(_) => AppError::BadRequest(e.to_string()),
+                    _ => AppError::Engine(e),
+                };
+                outcome = Err(IngestError::new(line, error));
+                break;
+            }
+        }
+    }
+    let task = if events.is_empty() {
+        None
+    } else {
+        engine.get_task(task_id).await.ok().flatten()
+    };
+    let results = match task {
+        Some(task) => sync_webhooks.deliver(&task, &events).await,
+        None => None,
+    };
+    let results = match results {
+        Some(results) => results.into_iter().map(|r| Some(json!(r))).collect(),
+        None => vec![None; events.len()],
+    };
+    (events.into_iter().zip(results).collect(), outcome)
+}
+
+fn push_line(out: &mut Vec<u8>, value: &Value) {
+    serde_json::to_writer(&mut *out, value).unwrap();
+    out.push(b'\n');
+}
@@ -1,24 +1,76 @@
 pub mod app;
 pub mod auth;
+pub mod bulk;
+pub mod debug_bundle;
 pub mod error;
+pub mod event_links;
+pub mod export;
+pub mod frame_cache;
 pub mod http_failure;
+pub mod http_tap;
+pub mod ingest;
 pub mod openapi;
+pub mod query;
+pub mod replication;
+pub mod request_deadline;
 pub mod routes;
+pub mod runtime_info;
+pub mod runtime_metrics;
+pub mod standby;
+pub mod strict;
+pub mod templates;
+pub mod timestamps;
 pub mod verbose;
+pub mod versioning;
 pub mod webhook;
 
 pub use app::{
-    auto_release_worker, create_app, create_app_with_failure_logger,
-    create_app_with_failure_logger_and_routes, dispatch_ws_offer, dispatch_ws_race,
-    start_background_services, AppState, BackgroundServices, CorsConfig,
+    auto_release_worker, create_app, create_app_with_options, create_readonly_app,
+    dispatch_ws_offer, dispatch_ws_race, start_background_services, AppOptions, AppState,
+    BackgroundServices, CorsConfig,
 };
-pub use auth::{check_scope, AuthContext, AuthMode, JwtConfig, TaskIdAccess, TrustedServiceConfig};
-pub use error::AppError;
+pub use auth::{
+    check_scope, hash_api_key, ApiKeyEntry, AuthContext, AuthMode, JwtConfig, TaskIdAccess,
+    TrustedServiceConfig,
+};
+pub use debug_bundle::{
+    DebugBundle, DebugBundleOptions, DebugBundleSources, DEBUG_BUNDLE_PATH, DEFAULT_LOG_LINES,
+    DEFAULT_REDACT_ENV,
+};
+pub use error::{
+    error_response_middleware, AppError, ErrorMessageProvider, ErrorPayload, REQUEST_ID_HEADER,
+};
+pub use event_links::{event_link_middleware, EventLinks, DEFAULT_FETCH_LINK_TTL_MS};
 pub use http_failure::{
     http_failure_logger_middleware, sanitize_error_message, CollectingHttpFailureLogger,
     HttpFailureKind, HttpFailureLog, HttpFailureLogger, LogLevel, StderrHttpFailureLogger,
 };
+pub use http_tap::{http_tap_middleware, HttpTap, HttpTapEntry, HttpTapQuery};
+pub use query::{QueryOptions, SortOrder};
+pub use replication::{
+    ReplicatedWrite, ReplicationError, ReplicationQueue, ReplicationSink, ReplicationSinkOptions,
+    REPLICATED_HEADER,
+};
+pub use request_deadline::{deadline_aware, RequestDeadlines, REQUEST_TIMEOUT_HEADER};
 pub use routes::worker_ws::{ClientMessage, ServerMessage, TaskSummary, WorkerCommand, WsRegistry};
 pub use routes::workers::workers_router;
+pub use runtime_info::{AdapterDescription, RuntimeInfo, GIT_HASH, RUNTIME_INFO_PATH};
+pub use runtime_metrics::{
+    EngineCounters, ExecutorSnapshot, RuntimeSampler, RuntimeSnapshot, WorkerSnapshot,
+    DEFAULT_SAMPLE_INTERVAL, RUNTIME_METRICS_PATH,
+};
+pub use standby::standby_gate;
+pub use templates::{apply_template, TemplateRegistry};
+pub use timestamps::{
+    TimestampCheck, TimestampFormat, TimestampWarnings, TIMESTAMP_FORMAT_HEADER, WARNING_HEADER,
+};
 pub use verbose::{verbose_logger_middleware, CollectingLogger, StderrLogger, VerboseLogger};
-pub use webhook::{WebhookDelivery, WebhookError};
+pub use versioning::{
+    ApiVersion, ApiVersions, LegacyPaths, Presenter, V1Presenter, API_V1, API_VERSION_HEADER,
+};
+pub use webhook::{
+    is_sync, select_webhooks, CircuitBreakerConfig, CircuitState, CircuitStatus,
+    DeliveryConcurrency, DeliveryLimits, DispatchOutcome, SyncWebhookResult, SyncWebhookStatus,
+    SyncWebhooks, WebhookBackfillRun, WebhookDelivery, WebhookDispatch, WebhookDispatcher,
+    WebhookError, BACKFILL_HEADER,
+};
@@ -1,7 +1,7 @@
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 
-use crate::routes::{sse, tasks, workers};
+use crate::routes::{conventions, outcomes, series, sse, tasks, templates, workers};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -10,16 +10,38 @@ use crate::routes::{sse, tasks, workers};
         version = "0.3.0",
         description = "Unified long-lifecycle task tracking service for LLM streaming, agents, and async workloads."
     ),
+    servers((url = "/v1", description = "API v1. Unprefixed paths are deprecated aliases.")),
     paths(
         tasks::list_tasks,
+        tasks::export_tasks,
         tasks::create_task,
+        tasks::create_task_group,
         tasks::export_task_archive,
+        tasks::save_task_archive,
+        tasks::get_task_integrity,
+        tasks::get_task_attempts,
         tasks::import_task_archive,
         tasks::get_task,
+        tasks::wait_for_task,
+        tasks::get_task_transitions,
         tasks::transition_task,
+        tasks::cancel_task,
         tasks::publish_events,
+        tasks::publish_events_stream,
         tasks::get_event_history,
+        tasks::get_event_stats,
+        series::list_series,
+        tasks::get_task_event,
+        tasks::delete_events,
+        outcomes::list_outcomes,
+        conventions::list_conventions,
+        conventions::get_task_type_conventions,
+        conventions::put_convention,
         sse::sse_events,
+        templates::list_templates,
+        templates::create_template,
+        templates::get_template,
+        templates::delete_template,
         workers::list_workers,
         workers::pull_task,
         workers::get_worker,
@@ -31,6 +53,7 @@ use crate::routes::{sse, tasks, workers};
         taskcast_core::Task,
         taskcast_core::TaskStatus,
         taskcast_core::TaskError,
+        taskcast_core::TaskTransitions,
         taskcast_core::TaskEvent,
         taskcast_core::TaskArchive,
         taskcast_core::TaskArchiveEvent,
@@ -43,11 +66,22 @@ use crate::routes::{sse, tasks, workers};
         taskcast_core::DisconnectPolicy,
         taskcast_core::SSEEnvelope,
         tasks::CreateTaskBody,
+        tasks::CreateTaskGroupBody,
+        tasks::TaskGroupResponse,
         tasks::TransitionBody,
+        tasks::CancelTaskBody,
         tasks::TaskErrorBody,
         tasks::PublishEventBody,
         tasks::ImportTaskArchiveBody,
         tasks::ImportTaskArchiveResponse,
+        taskcast_core::TaskOutcome,
+        taskcast_core::SeriesSummary,
+        outcomes::OutcomesPage,
+        series::SeriesPage,
+        taskcast_core::EventConvention,
+        taskcast_core::ConventionNotes,
+        conventions::ConventionCatalog,
+        templates::NamedTemplate,
         workers::DeclineBody,
         workers::WorkerStatusUpdateBody,
         workers::WorkerStatusUpdateValue,
@@ -57,6 +91,7 @@ use crate::routes::{sse, tasks, workers};
         (name = "Tasks", description = "Task lifecycle management"),
         (name = "Events", description = "Task event publishing and streaming"),
         (name = "Workers", description = "Worker management and task assignment"),
+        (name = "Templates", description = "Named defaults for task creation"),
     )
 )]
 pub struct ApiDoc;
@@ -0,0 +1,554 @@
+//! Shared parsing for the filtering, pagination and sorting query parameters.
+//!
+//! [`QueryOptions`] is an axum extractor, so routes (including embedders'
+//! custom ones) get the same names, formats and error messages for the whole
+//! family. Parameters a route cannot honor are refused with
+//! [`QueryOptions::reject`] rather than ignored.
+
+use std::collections::HashMap;
+
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use axum::http::Uri;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{ObjectBuilder, Required, Type};
+
+use taskcast_core::{parse_label_selector, EventTypeRules, Level, SinceCursor, SubscribeFilter};
+
+use crate::error::AppError;
+use crate::timestamps::parse_timestamp;
+
+/// Every level, from least to most severe.
+const LEVELS: [Level; 4] = [Level::Debug, Level::Info, Level::Warn, Level::Error];
+
+/// Name and description of each parameter [`QueryOptions`] parses.
+const PARAMS: &[(&str, &str)] = &[
+    ("since.id", "Only events after the event with this ID."),
+    ("since.index", "Only events after this index."),
+    (
+        "since.timestamp",
+        "Only events after this time, in epoch milliseconds or RFC 3339.",
+    ),
+    ("cursor", "Opaque pagination cursor from a previous response."),
+    ("limit", "Maximum number of items to return."),
+    ("order", "`asc` or `desc`."),
+    ("tail", "Return only the last N items."),
+    (
+        "types",
+        "Comma-separated event type patterns, e.g. `llm.*,progress`.",
+    ),
+    ("excludeTypes", "Comma-separated event type patterns to leave out."),
+    (
+        "levels",
+        "Comma-separated levels: `debug`, `info`, `warn`, `error`.",
+    ),
+    ("minLevel", "Only events at this level or above."),
+    ("includeStatus", "`true` or `false`."),
+    ("wrap", "`true` or `false`."),
+    ("fields", "Comma-separated fields to include in each item."),
+    (
+        "labels",
+        "Label selector, e.g. `region:eu,worker:w-42` (all pairs must match).",
+    ),
+    (
+        "preset",
+        "Name of one of the task's filter presets; the other filter parameters narrow it further.",
+    ),
+];
+
+/// Direction of `order`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Validated filtering, pagination and sorting query parameters.
+///
+/// Malformed values are rejected with a `400 INVALID_QUERY` naming the
+/// parameter and the expected format. Unknown parameters are left for the
+/// route's own query type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryOptions {
+    /// Built from `since.id`, `since.index` and `since.timestamp`.
+    pub since: Option<SinceCursor>,
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+    pub order: Option<SortOrder>,
+    pub tail: Option<u64>,
+    pub types: Option<Vec<String>>,
+    pub exclude_types: Option<Vec<String>>,
+    pub levels: Option<Vec<Level>>,
+    pub min_level: Option<Level>,
+    pub include_status: Option<bool>,
+    pub wrap: Option<bool>,
+    pub fields: Option<Vec<String>>,
+    pub labels: Option<HashMap<String, String>>,
+    pub preset: Option<String>,
+}
+
+impl QueryOptions {
+    /// Parses the query string of `uri`.
+    pub fn from_uri(uri: &Uri) -> Result<Self, AppError> {
+        let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(uri)
+            .map_err(|e| AppError::BadRequest(e.body_text()))?;
+
+        let mut seen: Vec<&str> = Vec::new();
+        let mut options = QueryOptions::default();
+        let mut since = SinceCursor {
+            id: None,
+            index: None,
+            timestamp: None,
+        };
+        for (name, value) in &pairs {
+            let Some(&(param, _)) = PARAMS.iter().find(|(param, _)| param == name) else {
+                continue;
+            };
+            if seen.contains(&param) {
+                return Err(invalid(param, "expected at most one value"));
+            }
+            seen.push(param);
+
+            match param {
+                "since.id" => since.id = Some(non_empty(param, value)?),
+                "since.index" => since.index = Some(integer(param, value)?),
+                "since.timestamp" => {
+                    since.timestamp = Some(parse_timestamp(value).ok_or_else(|| {
+                        invalid_value(
+                            param,
+                            value,
+                            "a number of milliseconds or an RFC 3339 timestamp",
+                        )
+                    })?)
+                }
+                "cursor" => options.cursor = Some(non_empty(param, value)?),
+                "limit" => options.limit = Some(integer(param, value)?),
+                "order" => {
+                    options.order = Some(match value.as_str() {
+                        "asc" => SortOrder::Asc,
+                        "desc" => SortOrder::Desc,
+                        _ => return Err(invalid_value(param, value, "`asc` or `desc`")),
+                    })
+                }
+                "tail" => options.tail = Some(integer(param, value)?),
+                "types" => options.types = Some(list(param, value)?),
+                "excludeTypes" => options.exclude_types = Some(list(param, value)?),
+                "levels" => {
+                    options.levels = Some(
+                        list(param, value)?
+                            .iter()
+                            .map(|level| parse_level(param, level))
+                            .collect::<Result<_, _>>()?,
+                    )
+                }
+                "minLevel" => options.min_level = Some(parse_level(param, value)?),
+                "includeStatus" => options.include_status = Some(boolean(param, value)?),
+                "wrap" => options.wrap = Some(boolean(param, value)?),
+                "fields" => options.fields = Some(list(param, value)?),
+                "labels" => {
+                    options.labels = Some(
+                        parse_label_selector(value).map_err(|e| AppError::InvalidQuery {
+                            param: param.to_string(),
+                            reason: format!("expected `key:value` pairs separated by commas ({e})"),
+                        })?,
+                    )
+                }
+                "preset" => options.preset = Some(non_empty(param, value)?),
+                _ => unreachable!("every name in PARAMS is parsed"),
+            }
+        }
+
+        if since.id.is_some() || since.index.is_some() || since.timestamp.is_some() {
+            options.since = Some(since);
+        }
+        Ok(options)
+    }
+
+    /// Whether the query carried `param`.
+    pub fn is_set(&self, param: &str) -> bool {
+        let since = self.since.as_ref();
+        match param {
+            "since.id" => since.is_some_and(|s| s.id.is_some()),
+            "since.index" => since.is_some_and(|s| s.index.is_some()),
+            "since.timestamp" => since.is_some_and(|s| s.timestamp.is_some()),
+            "cursor" => self.cursor.is_some(),
+            "limit" => self.limit.is_some(),
+            "order" => self.order.is_some(),
+            "tail" => self.tail.is_some(),
+            "types" => self.types.is_some(),
+            "excludeTypes" => self.exclude_types.is_some(),
+            "levels" => self.levels.is_some(),
+            "minLevel" => self.min_level.is_some(),
+            "includeStatus" => self.include_status.is_some(),
+            "wrap" => self.wrap.is_some(),
+            "fields" => self.fields.is_some(),
+            "labels" => self.labels.is_some(),
+            "preset" => self.preset.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Refuses the parameters in `params` that the query carried, for routes
+    /// that cannot honor them.
+    pub fn reject(&self, params: &[&str]) -> Result<(), AppError> {
+        match params.iter().find(|param| self.is_set(param)) {
+            Some(param) => Err(invalid(param, "not supported by this endpoint")),
+            None => Ok(()),
+        }
+    }
+
+    /// The accepted levels: `levels` narrowed to `minLevel` and above.
+    pub fn effective_levels(&self) -> Option<Vec<Level>> {
+        let Some(ref min_level) = self.min_level else {
+            return self.levels.clone();
+        };
+        let rank = |level: &Level| LEVELS.iter().position(|l| l == level);
+        let at_least = |level: &Level| rank(level) >= rank(min_level);
+        Some(match self.levels {
+            Some(ref levels) => levels.iter().filter(|l| at_least(l)).cloned().collect(),
+            None => LEVELS.iter().filter(|l| at_least(l)).cloned().collect(),
+        })
+    }
+
+    /// Normalizes `types` and `excludeTypes` the way published event types
+    /// are, so the patterns match the stored types.
+    pub fn normalize_types(&mut self, rules: &EventTypeRules) {
+        for patterns in [&mut self.types, &mut self.exclude_types].into_iter().flatten() {
+            rules.normalize_patterns(patterns);
+        }
+    }
+
+    /// The subscribe filter these options describe. Series options are left
+    /// unset.
+    pub fn subscribe_filter(&self) -> SubscribeFilter {
+        SubscribeFilter {
+            types: self.types.clone(),
+            levels: self.effective_levels(),
+            include_status: self.include_status,
+            wrap: self.wrap,
+            since: self.since.clone(),
+            label_selector: self.labels.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for QueryOptions
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut options = QueryOptions::from_uri(&parts.uri)?;
+        if let Some(rules) = parts.extensions.get::<EventTypeRules>() {
+            options.normalize_types(rules);
+        }
+        Ok(options)
+    }
+}
+
+impl utoipa::IntoParams for QueryOptions {
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        PARAMS
+            .iter()
+            .map(|(name, description)| {
+                ParameterBuilder::new()
+                    .name(*name)
+                    .parameter_in(parameter_in_provider().unwrap_or(ParameterIn::Query))
+                    .required(Required::False)
+                    .description(Some(*description))
+                    .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+                    .build()
+            })
+            .collect()
+    }
+}
+
+// ─── Value Parsers ───────────────────────────────────────────────────────────
+
+fn invalid(param: &str, reason: &str) -> AppError {
+    AppError::InvalidQuery {
+        param: param.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn invalid_value(param: &str, value: &str, expected: &str) -> AppError {
+    AppError::InvalidQuery {
+        param: param.to_string(),
+        reason: format!("expected {expected}, got \"{value}\""),
+    }
+}
+
+fn non_empty(param: &str, value: &str) -> Result<String, AppError> {
+    if value.is_empty() {
+        return Err(invalid(param, "expected a non-empty value"));
+    }
+    Ok(value.to_string())
+}
+
+fn integer(param: &str, value: &str) -> Result<u64, AppError> {
+    value
+        .parse()
+        .map_err(|_| invalid_value(param, value, "a non-negative integer"))
+}
+
+fn boolean(param: &str, value: &str) -> Result<bool, AppError> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(invalid_value(param, value, "`true` or `false`")),
+    }
+}
+
+fn list(param: &str, value: &str) -> Result<Vec<String>, AppError> {
+    let items: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect();
+    if items.is_empty() {
+        return Err(invalid(param, "expected a comma-separated list"));
+    }
+    Ok(items)
+}
+
+fn parse_level(param: &str, value: &str) -> Result<Level, AppError> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| invalid_value(param, value, "one of `debug`, `info`, `warn`, `error`"))
+}
+
+// ─── Unit Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    fn parse(query: &str) -> Result<QueryOptions, AppError> {
+        QueryOptions::from_uri(&format!("/x?{query}").parse().unwrap())
+    }
+
+    fn details(query: &str) -> serde_json::Value {
+        let err = parse(query).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.code(), "INVALID_QUERY");
+        err.details().unwrap()
+    }
+
+    #[test]
+    fn empty_query_parses_to_defaults() {
+        assert_eq!(parse("").unwrap(), QueryOptions::default());
+        assert_eq!(
+            QueryOptions::from_uri(&"/x".parse().unwrap()).unwrap(),
+            QueryOptions::default()
+        );
+    }
+
+    #[test]
+    fn unknown_parameters_are_left_alone() {
+        let options = parse("seriesFormat=bogus&checksum=yes&limit=2").unwrap();
+        assert_eq!(options.limit, Some(2));
+    }
+
+    // ── since ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn since_parameters_build_one_cursor() {
+        let options = parse("since.id=evt_abc&since.index=5&since.timestamp=999.5").unwrap();
+        assert_eq!(
+            options.since,
+            Some(SinceCursor {
+                id: Some("evt_abc".to_string()),
+                index: Some(5),
+                timestamp: Some(999.5),
+            })
+        );
+    }
+
+    #[test]
+    fn since_timestamp_accepts_rfc3339() {
+        let millis = parse("since.timestamp=1767225600123").unwrap().since;
+        let rfc3339 = parse("since.timestamp=2026-01-01T00:00:00.123Z")
+            .unwrap()
+            .since;
+        assert_eq!(millis.unwrap().timestamp, Some(1767225600123.0));
+        assert_eq!(rfc3339.unwrap().timestamp, Some(1767225600123.0));
+        // `+` must be percent-encoded in a query string.
+        let offset = parse("since.timestamp=2026-01-01T01:00:00.123%2B01:00")
+            .unwrap()
+            .since;
+        assert_eq!(offset.unwrap().timestamp, Some(1767225600123.0));
+    }
+
+    #[test]
+    fn since_index_alone_sets_only_the_index() {
+        let since = parse("since.index=42").unwrap().since.unwrap();
+        assert_eq!(since.index, Some(42));
+        assert!(since.id.is_none());
+        assert!(since.timestamp.is_none());
+    }
+
+    #[test]
+    fn malformed_since_values_are_rejected() {
+        let d = details("since.index=abc");
+        assert_eq!(d["param"], "since.index");
+        assert_eq!(d["reason"], "expected a non-negative integer, got \"abc\"");
+        assert_eq!(details("since.index=-1")["param"], "since.index");
+        assert_eq!(details("since.timestamp=soon")["param"], "since.timestamp");
+        assert_eq!(details("since.timestamp=NaN")["param"], "since.timestamp");
+        assert_eq!(details("since.id=")["param"], "since.id");
+    }
+
+    // ── pagination & sorting ────────────────────────────────────────────────
+
+    #[test]
+    fn pagination_parameters_parse() {
+        let options = parse("cursor=abc&limit=10&order=desc&tail=3").unwrap();
+        assert_eq!(options.cursor.as_deref(), Some("abc"));
+        assert_eq!(options.limit, Some(10));
+        assert_eq!(options.order, Some(SortOrder::Desc));
+        assert_eq!(options.tail, Some(3));
+        assert_eq!(parse("order=asc").unwrap().order, Some(SortOrder::Asc));
+    }
+
+    #[test]
+    fn malformed_pagination_values_are_rejected() {
+        assert_eq!(details("limit=ten")["param"], "limit");
+        assert_eq!(details("limit=1.5")["param"], "limit");
+        assert_eq!(details("tail=-3")["param"], "tail");
+        assert_eq!(details("cursor=")["param"], "cursor");
+        let d = details("order=up");
+        assert_eq!(d["param"], "order");
+        assert_eq!(d["reason"], "expected `asc` or `desc`, got \"up\"");
+    }
+
+    // ── types & levels ──────────────────────────────────────────────────────
+
+    #[test]
+    fn type_lists_are_split_and_trimmed() {
+        let options = parse("types=llm.chunk,%20progress,&excludeTypes=log").unwrap();
+        assert_eq!(
+            options.types,
+            Some(vec!["llm.chunk".to_string(), "progress".to_string()])
+        );
+        assert_eq!(options.exclude_types, Some(vec!["log".to_string()]));
+        assert_eq!(details("types=,")["param"], "types");
+        assert_eq!(details("excludeTypes=")["param"], "excludeTypes");
+    }
+
+    #[test]
+    fn levels_parse_and_reject_unknown_names() {
+        assert_eq!(
+            parse("levels=info,warn").unwrap().levels,
+            Some(vec![Level::Info, Level::Warn])
+        );
+        let d = details("levels=info,loud");
+        assert_eq!(d["param"], "levels");
+        assert_eq!(
+            d["reason"],
+            "expected one of `debug`, `info`, `warn`, `error`, got \"loud\""
+        );
+        assert_eq!(details("minLevel=verbose")["param"], "minLevel");
+    }
+
+    #[test]
+    fn min_level_narrows_levels() {
+        let options = parse("minLevel=warn").unwrap();
+        assert_eq!(options.min_level, Some(Level::Warn));
+        assert_eq!(
+            options.effective_levels(),
+            Some(vec![Level::Warn, Level::Error])
+        );
+
+        let options = parse("levels=debug,info,error&minLevel=info").unwrap();
+        assert_eq!(
+            options.effective_levels(),
+            Some(vec![Level::Info, Level::Error])
+        );
+        assert_eq!(parse("").unwrap().effective_levels(), None);
+    }
+
+    // ── flags, fields, labels, preset ───────────────────────────────────────
+
+    #[test]
+    fn boolean_flags_accept_only_true_or_false() {
+        let options = parse("includeStatus=false&wrap=true").unwrap();
+        assert_eq!(options.include_status, Some(false));
+        assert_eq!(options.wrap, Some(true));
+        let d = details("wrap=yes");
+        assert_eq!(d["param"], "wrap");
+        assert_eq!(d["reason"], "expected `true` or `false`, got \"yes\"");
+        assert_eq!(details("includeStatus=1")["param"], "includeStatus");
+    }
+
+    #[test]
+    fn fields_labels_and_preset_parse() {
+        let options = parse("fields=id,type&labels=region:eu,worker:w-1&preset=errors").unwrap();
+        assert_eq!(
+            options.fields,
+            Some(vec!["id".to_string(), "type".to_string()])
+        );
+        assert_eq!(
+            options.labels,
+            Some(HashMap::from([
+                ("region".to_string(), "eu".to_string()),
+                ("worker".to_string(), "w-1".to_string()),
+            ]))
+        );
+        assert_eq!(options.preset.as_deref(), Some("errors"));
+    }
+
+    #[test]
+    fn malformed_labels_and_preset_are_rejected() {
+        let d = details("labels=region");
+        assert_eq!(d["param"], "labels");
+        assert!(d["reason"].as_str().unwrap().contains("key:value"));
+        assert_eq!(details("preset=")["param"], "preset");
+        assert_eq!(details("fields=")["param"], "fields");
+    }
+
+    #[test]
+    fn repeated_parameters_are_rejected() {
+        let d = details("limit=1&limit=2");
+        assert_eq!(d, json!({ "param": "limit", "reason": "expected at most one value" }));
+    }
+
+    // ── filters & rejection ─────────────────────────────────────────────────
+
+    #[test]
+    fn subscribe_filter_carries_the_filter_parameters() {
+        let options =
+            parse("types=a.*&minLevel=error&includeStatus=false&wrap=false&since.index=3&labels=k:v")
+                .unwrap();
+        let filter = options.subscribe_filter();
+        assert_eq!(filter.types, Some(vec!["a.*".to_string()]));
+        assert_eq!(filter.levels, Some(vec![Level::Error]));
+        assert_eq!(filter.include_status, Some(false));
+        assert_eq!(filter.wrap, Some(false));
+        assert_eq!(filter.since.unwrap().index, Some(3));
+        assert_eq!(
+            filter.label_selector,
+            Some(HashMap::from([("k".to_string(), "v".to_string())]))
+        );
+        assert_eq!(filter.series_format, None);
+    }
+
+    #[test]
+    fn reject_refuses_only_parameters_that_were_given() {
+        let options = parse("limit=1&order=desc").unwrap();
+        assert!(options.reject(&["cursor", "fields"]).is_ok());
+        let err = options.reject(&["cursor", "order"]).unwrap_err();
+        assert_eq!(
+            err.details(),
+            Some(json!({ "param": "order", "reason": "not supported by this endpoint" }))
+        );
+        assert!(parse("since.index=1")
+            .unwrap()
+            .reject(&["since.index"])
+            .is_err());
+    }
+}
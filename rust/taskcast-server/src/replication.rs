@@ -0,0 +1,546 @@
+//! Replicating a deployment's writes to a secondary taskcast server.
+//!
+//! [`ReplicationSink`] is an [`EventSink`]: it queues every task the engine
+//! saves and every event it publishes in a [`ReplicationQueue`] on disk, and
+//! a worker on the engine's [`BackgroundTasks`] ships the queue, oldest
+//! first, to the remote's
+//! `POST /tasks` and `POST /tasks/:taskId/events` with the
+//! [`REPLICATED_HEADER`]. Consecutive events of one task go in one request.
+//!
+//! A write leaves the queue once the remote acknowledges it. While the
+//! remote is unreachable the queue grows, up to its bound, and is retried
+//! with backoff; it survives a restart. The remote applies writes
+//! idempotently, so a batch resent after a lost acknowledgement is harmless.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use taskcast_core::config::ReplicationSinkConfig;
+use taskcast_core::{
+    compute_backoff, BackgroundTasks, BackoffStrategy, EventSink, RetryConfig, RetryJitter, Task,
+    TaskEvent,
+};
+use tokio::sync::Notify;
+use tokio::task::AbortHandle;
+
+/// Marks a request as a replicated write, applied as the origin saved it.
+pub const REPLICATED_HEADER: &str = "X-Taskcast-Replicated";
+
+pub const DEFAULT_REPLICATION_MAX_QUEUED: usize = 100_000;
+pub const DEFAULT_REPLICATION_BATCH_SIZE: usize = 100;
+pub const DEFAULT_REPLICATION_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest wait between retries while the remote keeps failing.
+const MAX_RETRY_INTERVAL_MS: u64 = 60_000;
+
+// ─── Error ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplicationError {
+    #[error("Replication queue is full ({max_queued} writes)")]
+    QueueFull { max_queued: usize },
+
+    #[error("Replication queue: {0}")]
+    Io(#[from] io::Error),
+}
+
+// ─── Queue ──────────────────────────────────────────────────────────────────
+
+/// One write to replicate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReplicatedWrite {
+    Task { task: Box<Task> },
+    Event { event: Box<TaskEvent> },
+}
+
+struct QueueState {
+    writes: VecDeque<ReplicatedWrite>,
+    file: File,
+}
+
+/// Writes awaiting acknowledgement, kept in a JSON-lines file so they
+/// survive a restart.
+///
+/// Each push appends a line; each acknowledgement rewrites the file with
+/// what remains. A line left unreadable by a crash mid-write is skipped on
+/// open.
+pub struct ReplicationQueue {
+    path: PathBuf,
+    max_queued: usize,
+    state: Mutex<QueueState>,
+}
+
+impl ReplicationQueue {
+    /// Opens the queue at `path`, creating it if missing, with the writes a
+    /// previous run left in it.
+    pub fn open(path: impl AsRef<Path>, max_queued: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writes = VecDeque::new();
+        if path.exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                if let Ok(write) = serde_json::from_str(&line?) {
+                    writes.push_back(write);
+                }
+            }
+        }
+        let file = rewrite(&path, &writes)?;
+        Ok(Self {
+            path,
+            max_queued,
+            state: Mutex::new(QueueState { writes, file }),
+        })
+    }
+
+    pub fn push(&self, write: ReplicatedWrite) -> Result<(), ReplicationError> {
+        let mut state = self.state.lock().unwrap();
+        if state.writes.len() >= self.max_queued {
+            return Err(ReplicationError::QueueFull {
+                max_queued: self.max_queued,
+            });
+        }
+        let mut line = serde_json::to_vec(&write).map_err(io::Error::from)?;
+        line.push(b'\n');
+        state.file.write_all(&line)?;
+        state.writes.push_back(write);
+        Ok(())
+    }
+
+    /// Up to `max` writes from the front of the queue, left in place.
+    pub fn peek(&self, max: usize) -> Vec<ReplicatedWrite> {
+        let state = self.state.lock().unwrap();
+        state.writes.iter().take(max).cloned().collect()
+    }
+
+    /// Removes the `count` writes at the front of the queue.
+    pub fn ack(&self, count: usize) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let count = count.min(state.writes.len());
+        state.writes.drain(..count);
+        state.file = rewrite(&self.path, &state.writes)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().writes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Replaces the file at `path` with `writes`, returning it open for append.
+fn rewrite(path: &Path, writes: &VecDeque<ReplicatedWrite>) -> io::Result<File> {
+    let tmp = path.with_extension("tmp");
+    let mut contents = Vec::new();
+    for write in writes {
+        serde_json::to_writer(&mut contents, write)?;
+        contents.push(b'\n');
+    }
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    OpenOptions::new().append(true).open(path)
+}
+
+// ─── Sink ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct ReplicationSinkOptions {
+    /// Base URL of the remote server.
+    pub url: String,
+    /// Bearer token with the `task:replicate` scope.
+    pub token: Option<String>,
+    pub queue_path: PathBuf,
+    pub max_queued: usize,
+    /// Most events sent in one request.
+    pub batch_size: usize,
+    /// Wait before the first retry; doubles up to a minute.
+    pub retry_interval: Duration,
+}
+
+impl ReplicationSinkOptions {
+    pub fn from_config(config: &ReplicationSinkConfig) -> Self {
+        Self {
+            url: config.url.clone(),
+            token: config.token.clone(),
+            queue_path: PathBuf::from(&config.queue_path),
+            max_queued: config.max_queued.unwrap_or(DEFAULT_REPLICATION_MAX_QUEUED),
+            batch_size: config
+                .batch_size
+                .unwrap_or(DEFAULT_REPLICATION_BATCH_SIZE)
+                .max(1),
+            retry_interval: config
+                .retry_interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_REPLICATION_RETRY_INTERVAL),
+        }
+    }
+}
+
+/// Ships task saves and events to a remote taskcast server.
+///
+/// A write that does not fit in the queue is refused, which the engine
+/// reports through `on_event_dropped` or `on_unhandled_error`.
+///
+/// The sink is [opened](Self::open) before the engine it is passed to, and
+/// [started](Self::start) on that engine's [`BackgroundTasks`] once it
+/// exists; writes made in between wait in the queue.
+pub struct ReplicationSink {
+    queue: Arc<ReplicationQueue>,
+    wake: Arc<Notify>,
+    closing: Arc<AtomicBool>,
+    /// Taken by [`start`](Self::start).
+    shipper: Mutex<Option<Shipper>>,
+    worker: Mutex<Option<AbortHandle>>,
+}
+
+impl ReplicationSink {
+    /// Opens the queue, with the writes a previous run left in it.
+    pub fn open(options: ReplicationSinkOptions) -> io::Result<Self> {
+        let queue = Arc::new(ReplicationQueue::open(
+            &options.queue_path,
+            options.max_queued,
+        )?);
+        Ok(Self {
+            queue,
+            wake: Arc::new(Notify::new()),
+            closing: Arc::new(AtomicBool::new(false)),
+            shipper: Mutex::new(Some(Shipper {
+                client: reqwest::Client::new(),
+                options,
+            })),
+            worker: Mutex::new(None),
+        })
+    }
+
+    /// Starts shipping the queue on `background` as `replication.ship`. A
+    /// second call does nothing. Must be called within a Tokio runtime.
+    pub fn start(&self, background: &BackgroundTasks) {
+        let Some(shipper) = self.shipper.lock().unwrap().take() else {
+            return;
+        };
+        let handle = background.spawn(
+            "replication.ship",
+            None,
+            ship(
+                Arc::clone(&self.queue),
+                Arc::clone(&self.wake),
+                Arc::clone(&self.closing),
+                shipper,
+            ),
+        );
+        *self.worker.lock().unwrap() = Some(handle);
+    }
+
+    /// Lets the worker finish once the queue is empty, so that draining the
+    /// background tasks waits for the queue to be shipped. Writes still
+    /// queued when the drain gives up stay on disk for the next run.
+    pub fn close(&self) {
+        self.closing.store(true, Ordering::SeqCst);
+        self.wake.notify_one();
+    }
+
+    /// Writes not yet acknowledged by the remote.
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn push(&self, write: ReplicatedWrite) -> Result<(), ReplicationError> {
+        self.queue.push(write)?;
+        self.wake.notify_one();
+        Ok(())
+    }
+}
+
+impl Drop for ReplicationSink {
+    fn drop(&mut self) {
+        if let Some(handle) = self.worker.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for ReplicationSink {
+    async fn on_event(
+        &self,
+        event: &TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let event = TaskEvent {
+            replaces_event_id: None,
+            ..event.clone()
+        };
+        Ok(self.push(ReplicatedWrite::Event {
+            event: Box::new(event),
+        })?)
+    }
+
+    async fn on_task_saved(
+        &self,
+        task: &Task,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.push(ReplicatedWrite::Task {
+            task: Box::new(task.clone()),
+        })?)
+    }
+}
+
+// ─── Shipping ───────────────────────────────────────────────────────────────
+
+/// Why a batch was not applied.
+enum ShipError {
+    /// Worth retrying: the remote was unreachable, overloaded or refused
+    /// the credentials.
+    Retry(String),
+    /// The remote rejected the writes themselves; resending cannot help.
+    Rejected(String),
+}
+
+struct Shipper {
+    client: reqwest::Client,
+    options: ReplicationSinkOptions,
+}
+
+impl Shipper {
+    async fn send(&self, writes: &[ReplicatedWrite]) -> Result<(), ShipError> {
+        let (segments, body) = match writes {
+            [ReplicatedWrite::Task { task }] => (vec!["tasks"], serde_json::to_value(task)),
+            [ReplicatedWrite::Event { event }, ..] => {
+                let events: Vec<&TaskEvent> = writes
+                    .iter()
+                    .filter_map(|write| match write {
+                        ReplicatedWrite::Event { event } => Some(event.as_ref()),
+                        ReplicatedWrite::Task { .. } => None,
+                    })
+                    .collect();
+                (
+                    vec!["tasks", event.task_id.as_str(), "events"],
+                    serde_json::to_value(events),
+                )
+            }
+            _ => return Ok(()),
+        };
+        let body = body.map_err(|e| ShipError::Rejected(e.to_string()))?;
+
+        let mut url = reqwest::Url::parse(&self.options.url)
+            .map_err(|e| ShipError::Retry(format!("invalid url: {e}")))?;
+        url.path_segments_mut()
+            .map_err(|_| ShipError::Retry("invalid url".to_string()))?
+            .pop_if_empty()
+            .extend(segments);
+        let mut request = self
+            .client
+            .post(url)
+            .header(REPLICATED_HEADER, "true")
+            .json(&body);
+        if let Some(ref token) = self.options.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ShipError::Retry(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let message = format!("{status}: {}", response.text().await.unwrap_or_default());
+        if status.is_server_error()
+            || matches!(
+                status,
+                StatusCode::UNAUTHORIZED
+                    | StatusCode::FORBIDDEN
+                    | StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::TOO_MANY_REQUESTS
+            )
+        {
+            Err(ShipError::Retry(message))
+        } else {
+            Err(ShipError::Rejected(message))
+        }
+    }
+}
+
+/// Number of writes at the front of `writes` sent in one request: a task
+/// alone, or a run of one task's events.
+fn batch_len(writes: &[ReplicatedWrite]) -> usize {
+    match writes.first() {
+        Some(ReplicatedWrite::Event { event }) => writes
+            .iter()
+            .take_while(|write| {
+                matches!(write, ReplicatedWrite::Event { event: next } if next.task_id == event.task_id)
+            })
+            .count(),
+        Some(ReplicatedWrite::Task { .. }) => 1,
+        None => 0,
+    }
+}
+
+/// Ships the queue until it is empty after [`ReplicationSink::close`].
+async fn ship(
+    queue: Arc<ReplicationQueue>,
+    wake: Arc<Notify>,
+    closing: Arc<AtomicBool>,
+    shipper: Shipper,
+) {
+    let url = shipper.options.url.clone();
+    let retry = RetryConfig {
+        retries: u32::MAX,
+        backoff: BackoffStrategy::Exponential,
+        initial_delay_ms: shipper.options.retry_interval.as_millis() as u64,
+        max_delay_ms: MAX_RETRY_INTERVAL_MS,
+        timeout_ms: 0,
+        jitter: RetryJitter::None,
+        max_elapsed_ms: None,
+    };
+    let mut rng = fastrand::Rng::new();
+    let mut attempt = 0;
+    let mut failing = false;
+    loop {
+        let writes = queue.peek(shipper.options.batch_size);
+        if writes.is_empty() {
+            if closing.load(Ordering::SeqCst) {
+                return;
+            }
+            wake.notified().await;
+            continue;
+        }
+        let count = batch_len(&writes);
+        match shipper.send(&writes[..count]).await {
+            Err(ShipError::Retry(message)) => {
+                if !failing {
+                    eprintln!("[taskcast] Replication to {url} failed, queueing writes: {message}");
+                    failing = true;
+                }
+                attempt += 1;
+                let delay = compute_backoff(&retry, attempt, &mut rng);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                continue;
+            }
+            Err(ShipError::Rejected(message)) => {
+                eprintln!("[taskcast] Replication to {url} rejected {count} writes: {message}");
+            }
+            Ok(()) => {
+                if failing {
+                    eprintln!(
+                        "[taskcast] Replication to {url} resumed, {} writes queued",
+                        queue.len()
+                    );
+                    failing = false;
+                }
+            }
+        }
+        attempt = 0;
+        if let Err(err) = queue.ack(count) {
+            eprintln!("[taskcast] Replication queue could not be rewritten: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskcast_core::Level;
+
+    fn task(id: &str) -> ReplicatedWrite {
+        let task = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "status": "pending",
+            "createdAt": 0.0,
+            "updatedAt": 0.0,
+        }))
+        .unwrap();
+        ReplicatedWrite::Task {
+            task: Box::new(task),
+        }
+    }
+
+    fn event(task_id: &str, index: u64) -> ReplicatedWrite {
+        ReplicatedWrite::Event {
+            event: Box::new(TaskEvent {
+                id: format!("{task_id}-{index}"),
+                task_id: task_id.to_string(),
+                index,
+                timestamp: 0.0,
+                r#type: "log".to_string(),
+                level: Level::Info,
+                data: serde_json::json!({}),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                series_snapshot: None,
+                labels: None,
+                replaces_event_id: None,
+                _accumulated_data: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn queue_survives_reopening() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.jsonl");
+        let queue = ReplicationQueue::open(&path, 10).unwrap();
+        queue.push(task("t1")).unwrap();
+        queue.push(event("t1", 0)).unwrap();
+        queue.push(event("t1", 1)).unwrap();
+        queue.ack(2).unwrap();
+        queue.push(event("t1", 2)).unwrap();
+        drop(queue);
+
+        let queue = ReplicationQueue::open(&path, 10).unwrap();
+        assert_eq!(queue.peek(10), vec![event("t1", 1), event("t1", 2)]);
+    }
+
+    #[test]
+    fn queue_skips_a_torn_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.jsonl");
+        let queue = ReplicationQueue::open(&path, 10).unwrap();
+        queue.push(event("t1", 0)).unwrap();
+        drop(queue);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"kind\":\"ev").unwrap();
+
+        let queue = ReplicationQueue::open(&path, 10).unwrap();
+        assert_eq!(queue.peek(10), vec![event("t1", 0)]);
+        queue.push(event("t1", 1)).unwrap();
+        drop(queue);
+        assert_eq!(ReplicationQueue::open(&path, 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn queue_refuses_writes_past_its_bound() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ReplicationQueue::open(dir.path().join("queue.jsonl"), 1).unwrap();
+        queue.push(event("t1", 0)).unwrap();
+        assert!(matches!(
+            queue.push(event("t1", 1)),
+            Err(ReplicationError::QueueFull { max_queued: 1 })
+        ));
+    }
+
+    #[test]
+    fn batches_group_one_tasks_events() {
+        assert_eq!(batch_len(&[task("t1"), event("t1", 0)]), 1);
+        assert_eq!(
+            batch_len(&[event("t1", 0), event("t1", 1), event("t2", 0)]),
+            2
+        );
+        assert_eq!(batch_len(&[event("t1", 0), task("t1")]), 1);
+        assert_eq!(batch_len(&[]), 0);
+    }
+}
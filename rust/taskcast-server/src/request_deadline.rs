@@ -0,0 +1,105 @@
+//! Deadlines for the expensive reads: task listing, export, event history
+//! and event stats.
+//!
+//! A request may carry `X-Request-Timeout-Ms`, capped by
+//! `http.maxRequestTimeoutMs`, which also applies to requests without one.
+//! The handler runs inside the deadline's scope, so long-term store queries
+//! stop with it, and is dropped if it is still running when the deadline
+//! passes; the request is then answered `504 DEADLINE_EXCEEDED`.
+//!
+//! A handler dropped before it answered, which happens when the client
+//! disconnects, counts as cancelled by the client. Both counts are served by
+//! `GET /admin/runtime`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use taskcast_core::config::HttpConfig;
+use taskcast_core::{EngineError, RequestCounters, RequestDeadline};
+
+use crate::error::{AppError, ErrorPayload};
+
+/// Header carrying the client's own timeout, in milliseconds.
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-ms";
+
+/// How long deadline-aware requests may run, passed to [`deadline_aware`]
+/// as its state.
+#[derive(Debug, Clone)]
+pub struct RequestDeadlines {
+    pub max: Option<Duration>,
+    pub counters: Arc<RequestCounters>,
+}
+
+impl RequestDeadlines {
+    pub fn from_config(config: Option<&HttpConfig>, counters: Arc<RequestCounters>) -> Self {
+        Self {
+            max: config
+                .and_then(|c| c.max_request_timeout_ms)
+                .map(Duration::from_millis),
+            counters,
+        }
+    }
+
+    /// The deadline of a request with `headers`, starting now: the shorter
+    /// of its `X-Request-Timeout-Ms` and the configured maximum.
+    pub fn deadline(&self, headers: &HeaderMap) -> Result<Option<RequestDeadline>, AppError> {
+        let requested = match headers.get(REQUEST_TIMEOUT_HEADER) {
+            Some(value) => Some(
+                value
+                    .to_str()
+                    .ok()
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+                    .filter(|ms| *ms > 0)
+                    .map(Duration::from_millis)
+                    .ok_or_else(|| {
+                        AppError::BadRequest(
+                            "Invalid X-Request-Timeout-Ms header: expected a positive number of milliseconds"
+                                .to_string(),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+        let budget = match (requested, self.max) {
+            (Some(requested), Some(max)) => Some(requested.min(max)),
+            (budget, None) | (None, budget) => budget,
+        };
+        Ok(budget.map(RequestDeadline::after))
+    }
+}
+
+/// Runs the handler under the request's deadline, answering `504` if it is
+/// still running when the deadline passes.
+pub async fn deadline_aware(
+    State(deadlines): State<RequestDeadlines>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let deadline = match deadlines.deadline(request.headers()) {
+        Ok(deadline) => deadline,
+        Err(e) => return e.into_response(),
+    };
+    let in_flight = deadlines.counters.begin();
+    let response = match deadline {
+        Some(deadline) => match deadline.scope(deadline.run(next.run(request))).await {
+            Ok(response) => response,
+            Err(exceeded) => {
+                AppError::Engine(EngineError::Store(Box::new(exceeded))).into_response()
+            }
+        },
+        None => next.run(request).await,
+    };
+    in_flight.finish();
+    let exceeded = response
+        .extensions()
+        .get::<ErrorPayload>()
+        .is_some_and(|payload| payload.code == "DEADLINE_EXCEEDED");
+    if exceeded {
+        deadlines.counters.record_deadline_exceeded();
+    }
+    response
+}
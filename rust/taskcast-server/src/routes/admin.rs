@@ -1,15 +1,30 @@
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use axum::extract::State;
-use axum::http::StatusCode;
+use axum::extract::{Path, Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
 use axum::response::IntoResponse;
+use axum::Extension;
 use jsonwebtoken::{encode, EncodingKey, Header};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use taskcast_core::config::TaskcastConfig;
+use taskcast_core::{
+    scan_consistency, EngineError, EventQueryOptions, MigratingShortTermStore, MigrationMode,
+    OrphanPolicy, PermissionScope, RoleSwitch, ScanOptions, StorageManager,
+};
 
-use crate::auth::AuthMode;
+use crate::app::AppState;
+use crate::auth::{check_scope, AuthContext, AuthMode};
+use crate::bulk::{execute, select_tasks, BulkAction, BulkLimits, BulkSelector};
+use crate::debug_bundle::{DebugBundle, DebugBundleOptions, DebugBundleSources};
+use crate::error::AppError;
+use crate::http_tap::{HttpTap, HttpTapQuery};
+use crate::query::QueryOptions;
+use crate::runtime_info::RuntimeInfo;
+use crate::runtime_metrics::RuntimeSampler;
+use crate::timestamps::{self, TimestampCheck};
+use crate::webhook::WebhookDelivery;
 
 // ─── Admin State ────────────────────────────────────────────────────────────
 
@@ -63,33 +78,21 @@ pub async fn admin_token(
 
     // 1. If adminApi is not enabled, this endpoint does not exist
     if config.admin_api != Some(true) {
-        return (
-            StatusCode::NOT_FOUND,
-            axum::Json(json!({ "error": "Not found" })),
-        )
-            .into_response();
+        return AppError::NotFound("Not found".to_string()).into_response();
     }
 
     // 2. Parse and validate request body
     let req: AdminTokenRequest = match serde_json::from_slice(&body) {
         Ok(r) => r,
         Err(_) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                axum::Json(json!({ "error": "Invalid request body" })),
-            )
-                .into_response();
+            return AppError::BadRequest("Invalid request body".to_string()).into_response();
         }
     };
 
     let admin_token = match &req.admin_token {
         Some(t) if !t.is_empty() => t.as_str(),
         _ => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                axum::Json(json!({ "error": "Invalid admin token" })),
-            )
-                .into_response();
+            return AppError::InvalidAdminToken.into_response();
         }
     };
 
@@ -97,20 +100,12 @@ pub async fn admin_token(
     let expected_token = match &config.admin_token {
         Some(t) => t.as_str(),
         None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                axum::Json(json!({ "error": "Invalid admin token" })),
-            )
-                .into_response();
+            return AppError::InvalidAdminToken.into_response();
         }
     };
 
     if admin_token != expected_token {
-        return (
-            StatusCode::UNAUTHORIZED,
-            axum::Json(json!({ "error": "Invalid admin token" })),
-        )
-            .into_response();
+        return AppError::InvalidAdminToken.into_response();
     }
 
     // 4. Parse optional fields
@@ -130,7 +125,7 @@ pub async fn admin_token(
     let jwt_config = match state.auth_mode.as_ref() {
         AuthMode::Jwt(jwt_config) => Some(jwt_config),
         AuthMode::JwtWithTrustedServices { jwt, .. } => Some(jwt),
-        AuthMode::None => None,
+        AuthMode::None | AuthMode::ApiKeys(_) => None,
     };
     if let Some(jwt_config) = jwt_config {
         if let Some(ref secret) = jwt_config.secret {
@@ -142,7 +137,7 @@ pub async fn admin_token(
                 iat: now,
             };
 
-            let header = Header::new(jwt_config.algorithm);
+            let header = Header::new(jwt_config.jsonwebtoken_algorithm());
             let key = EncodingKey::from_secret(secret.as_bytes());
 
             match encode(&header, &claims, &key) {
@@ -150,11 +145,7 @@ pub async fn admin_token(
                     return axum::Json(AdminTokenResponse { token, expires_at }).into_response();
                 }
                 Err(_) => {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        axum::Json(json!({ "error": "Failed to sign JWT" })),
-                    )
-                        .into_response();
+                    return AppError::Internal("Failed to sign JWT".to_string()).into_response();
                 }
             }
         }
@@ -167,3 +158,504 @@ pub async fn admin_token(
     })
     .into_response()
 }
+
+// ─── Webhook Circuits ───────────────────────────────────────────────────────
+
+/// GET /admin/webhooks/circuits — list open and half-open webhook circuits.
+///
+/// Mounted behind the normal auth middleware; requires the `*` scope.
+pub async fn list_webhook_circuits(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::All, None) {
+        return Err(AppError::MissingScope(PermissionScope::All));
+    }
+    Ok(axum::Json(json!({ "circuits": delivery.open_circuits() })))
+}
+
+/// POST /admin/webhooks/circuits/{host}/reset — close a tripped circuit.
+pub async fn reset_webhook_circuit(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(host): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::All, None) {
+        return Err(AppError::MissingScope(PermissionScope::All));
+    }
+    if !delivery.reset_circuit(&host) {
+        return Err(AppError::NotFound(format!("No open circuit for {host}")));
+    }
+    Ok(axum::Json(json!({ "host": host, "reset": true })))
+}
+
+/// GET /admin/webhooks/concurrency — permit use of background deliveries.
+pub async fn get_webhook_concurrency(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::All, None) {
+        return Err(AppError::MissingScope(PermissionScope::All));
+    }
+    Ok(axum::Json(delivery.concurrency()))
+}
+
+// ─── Storage ────────────────────────────────────────────────────────────────
+
+/// GET /admin/storage — usage and caps of the node's storage directories.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn get_storage_usage(
+    State(storage): State<Arc<StorageManager>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let usage = tokio::task::spawn_blocking(move || storage.usage())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+    Ok(axum::Json(json!({ "directories": usage })))
+}
+
+// ─── Runtime Info ───────────────────────────────────────────────────────────
+
+/// GET /admin/info — version, adapters, runners and the redacted config.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`, which
+/// every request has when auth is off.
+pub async fn get_runtime_info(
+    State(state): State<AppState>,
+    Extension(info): Extension<Arc<RuntimeInfo>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(info.report(
+        &state.engine,
+        &state.auth_mode,
+        state.start_time.elapsed(),
+    )))
+}
+
+// ─── Flags ──────────────────────────────────────────────────────────────────
+
+/// GET /admin/flags — every configured feature flag with its rule and how
+/// often it was evaluated, and found on, since the rule was loaded.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn list_flags(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(
+        json!({ "flags": state.engine.flags().statuses() }),
+    ))
+}
+
+// ─── Runners ────────────────────────────────────────────────────────────────
+
+/// GET /admin/runners — schedule, last pass and failure streak of every
+/// background runner on this node.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn list_runners(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(
+        json!({ "runners": state.engine.runners().statuses() }),
+    ))
+}
+
+/// POST /admin/runners/{name}/run-now — run a pass now, even if the runner
+/// is paused, and return its status once the pass is done.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn run_runner_now(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(state.engine.runners().run_now(&name).await?))
+}
+
+/// POST /admin/runners/{name}/pause — skip the runner's scheduled passes.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn pause_runner(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(state.engine.runners().pause(&name)?))
+}
+
+/// POST /admin/runners/{name}/resume — undo a pause.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn resume_runner(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(state.engine.runners().resume(&name)?))
+}
+
+// ─── Role ───────────────────────────────────────────────────────────────────
+
+/// POST /admin/promote — make this standby instance active: writes are taken
+/// and background runners start at once. `promoted` is false if it was
+/// already active. Refused while leader election decides the role.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn promote_instance(
+    State(role): State<RoleSwitch>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    if role.is_elected() {
+        return Err(AppError::RoleElected);
+    }
+    let promoted = role.promote();
+    Ok(axum::Json(
+        json!({ "role": role.role(), "promoted": promoted }),
+    ))
+}
+
+// ─── Runtime Metrics ────────────────────────────────────────────────────────
+
+/// GET /admin/runtime — executor and engine metrics, for telling apart
+/// executor starvation, store latency and a stuck dispatch loop.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn get_runtime_metrics(
+    State(sampler): State<Arc<RuntimeSampler>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(sampler.snapshot()))
+}
+
+// ─── Bulk Operations ────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRequest {
+    #[serde(default)]
+    selector: BulkSelector,
+    action: BulkAction,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// POST /admin/tasks/bulk — apply one action to every task a selector
+/// matches, or with `dryRun` just list them.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn bulk_tasks(
+    State(state): State<AppState>,
+    Extension(limits): Extension<BulkLimits>,
+    Extension(auth): Extension<AuthContext>,
+    timestamp_check: TimestampCheck,
+    axum::Json(body): axum::Json<BulkRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let warnings = timestamp_check.body(&[
+        ("/selector/createdBefore", body.selector.created_before),
+        ("/selector/createdAfter", body.selector.created_after),
+    ])?;
+    let tasks = select_tasks(&state.engine, &body.selector).await?;
+    if tasks.len() > limits.max_tasks {
+        return Err(AppError::BadRequest(format!(
+            "Selector matches {} tasks, more than the limit of {} per bulk operation",
+            tasks.len(),
+            limits.max_tasks
+        )));
+    }
+    let task_ids: Vec<String> = tasks.into_iter().map(|task| task.id).collect();
+    if body.dry_run {
+        return Ok((
+            warnings,
+            axum::Json(json!({
+                "dryRun": true,
+                "matched": task_ids.len(),
+                "taskIds": task_ids,
+            })),
+        ));
+    }
+
+    let results = execute(&state.engine, task_ids, &body.action, limits.concurrency).await;
+    let succeeded = results.iter().filter(|result| result.ok).count();
+    Ok((
+        warnings,
+        axum::Json(json!({
+            "dryRun": false,
+            "matched": results.len(),
+            "succeeded": succeeded,
+            "failed": results.len() - succeeded,
+            "results": results,
+        })),
+    ))
+}
+
+// ─── Consistency ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyRequest {
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    orphan_policy: OrphanPolicy,
+    persistence_lag_ms: Option<u64>,
+    batch_size: Option<u64>,
+}
+
+/// POST /admin/consistency — scan the short- and long-term stores for
+/// drift between them and, unless `dryRun`, repair what it finds.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn scan_stores(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    axum::Json(body): axum::Json<ConsistencyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let defaults = ScanOptions::default();
+    let options = ScanOptions {
+        repair: !body.dry_run,
+        orphan_policy: body.orphan_policy,
+        persistence_lag_ms: body.persistence_lag_ms.unwrap_or(defaults.persistence_lag_ms),
+        batch_size: body.batch_size.unwrap_or(defaults.batch_size),
+        ..defaults
+    };
+    let report = scan_consistency(
+        state.engine.short_term_store(),
+        state.engine.long_term_store(),
+        options,
+    )
+    .await
+    .map_err(EngineError::Store)?;
+    let mut response = serde_json::to_value(report).unwrap_or_default();
+    response["dryRun"] = json!(body.dry_run);
+    Ok(axum::Json(response))
+}
+
+// ─── Store Migration ────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreMigrationRequest {
+    mode: MigrationMode,
+}
+
+fn store_migration(state: &AppState) -> Result<&MigratingShortTermStore, AppError> {
+    state
+        .engine
+        .short_term_store()
+        .migration()
+        .ok_or_else(|| AppError::NotFound("The short-term store is not migrating".to_string()))
+}
+
+fn migration_status(migration: &MigratingShortTermStore) -> serde_json::Value {
+    json!({
+        "mode": migration.mode(),
+        "lazyBackfill": migration.lazy_backfill(),
+        "migratedTasks": migration.migrated_tasks(),
+        "backfill": migration.backfill_progress(),
+    })
+}
+
+/// GET /admin/store/migration — mode of a short-term store migration and
+/// progress of its latest backfill.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn get_store_migration(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(migration_status(store_migration(&state)?)))
+}
+
+/// PUT /admin/store/migration — switch between `dualWrite` and `cutover`.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn set_store_migration(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    axum::Json(body): axum::Json<StoreMigrationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let migration = store_migration(&state)?;
+    migration.set_mode(body.mode);
+    Ok(axum::Json(migration_status(migration)))
+}
+
+/// POST /admin/store/migration/backfill — copy every task of the old store
+/// into the new one and return the run's counts once done.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn backfill_store(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let progress = store_migration(&state)?
+        .backfill_all(|_| {})
+        .await
+        .map_err(EngineError::Store)?;
+    Ok(axum::Json(progress))
+}
+
+// ─── Query Plans ────────────────────────────────────────────────────────────
+
+/// Parameters the long-term store's event read cannot take.
+const EXPLAIN_UNSUPPORTED: &[&str] = &[
+    "cursor",
+    "order",
+    "tail",
+    "types",
+    "excludeTypes",
+    "levels",
+    "minLevel",
+    "includeStatus",
+    "wrap",
+    "fields",
+    "preset",
+];
+
+#[derive(Debug, Deserialize)]
+pub struct ExplainQuery {
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    until: Option<f64>,
+}
+
+/// GET /admin/store/explain/{task_id} — the long-term store's plan for the
+/// event read that `since.*`, `limit`, `labels` and `until` describe,
+/// without running it. 404 when the store cannot explain its reads.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn explain_get_events(
+    State(state): State<AppState>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+    options: QueryOptions,
+    Query(query): Query<ExplainQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    options.reject(EXPLAIN_UNSUPPORTED)?;
+    let not_explained = || AppError::NotFound("Long-term store cannot explain queries".to_string());
+    let store = state.engine.long_term_store().ok_or_else(not_explained)?;
+    let opts = EventQueryOptions {
+        since: options.since,
+        limit: options.limit,
+        label_selector: options.labels,
+        until: query.until,
+        before_index: None,
+    };
+    let plan = store
+        .explain_get_events(&task_id, Some(opts))
+        .await
+        .map_err(EngineError::Store)?
+        .ok_or_else(not_explained)?;
+    Ok(axum::Json(json!({ "taskId": task_id, "plan": plan })))
+}
+
+// ─── HTTP Tap ───────────────────────────────────────────────────────────────
+
+/// GET /admin/http-tap — recorded HTTP exchanges, newest first.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn list_http_tap(
+    State(tap): State<Arc<HttpTap>>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<HttpTapQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(axum::Json(json!({ "entries": tap.entries(&query) })))
+}
+
+// ─── Debug Bundle ───────────────────────────────────────────────────────────
+
+/// What the debug bundle endpoint collects from.
+#[derive(Clone)]
+pub struct DebugBundleState {
+    pub app: AppState,
+    pub runtime_info: Arc<RuntimeInfo>,
+    pub http_tap: Option<Arc<HttpTap>>,
+    pub runtime_sampler: Arc<RuntimeSampler>,
+    pub options: Arc<DebugBundleOptions>,
+}
+
+/// GET /admin/tasks/{task_id}/debug-bundle — a redacted tar.gz of what a
+/// support ticket about the task needs.
+///
+/// Mounted behind the normal auth middleware; requires `task:manage`.
+pub async fn get_debug_bundle(
+    State(state): State<Arc<DebugBundleState>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let sources = DebugBundleSources {
+        engine: &state.app.engine,
+        auth_mode: &state.app.auth_mode,
+        uptime: state.app.start_time.elapsed(),
+        runtime_info: &state.runtime_info,
+        http_tap: state.http_tap.as_deref(),
+        runtime_sampler: Some(&state.runtime_sampler),
+        options: &state.options,
+    };
+    let bundle = DebugBundle::collect(&sources, &task_id).await?;
+    let archive = bundle
+        .to_tar_gz()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok((
+        [
+            (CONTENT_TYPE, "application/gzip"),
+            (
+                CONTENT_DISPOSITION,
+                "attachment; filename=\"taskcast-debug-bundle.tgz\"",
+            ),
+        ],
+        archive,
+    ))
+}
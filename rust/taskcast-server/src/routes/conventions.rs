@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::routing::{get, put};
+use axum::Extension;
+use serde::Serialize;
+use taskcast_core::{ConventionNotes, EngineError, EventConvention, PermissionScope, TaskEngine};
+
+use crate::auth::{check_scope, AuthContext};
+use crate::error::AppError;
+
+// ─── Response ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConventionCatalog {
+    /// Ordered by task type, then event type.
+    pub conventions: Vec<EventConvention>,
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/conventions",
+    tag = "Events",
+    summary = "List discovered event conventions",
+    description = "The event types each task type publishes, sampled from published events, with an estimated count, a recent redacted example and any curated description and JSON Schema.",
+    security(("Bearer" = [])),
+    responses(
+        (status = 200, description = "The catalog", body = ConventionCatalog),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Discovery is off"),
+    )
+)]
+pub async fn list_conventions(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, None) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+    let conventions = engine
+        .list_conventions(None)
+        .await
+        .map_err(EngineError::Store)?
+        .ok_or_else(discovery_off)?;
+    Ok(axum::Json(ConventionCatalog { conventions }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/conventions/{task_type}",
+    tag = "Events",
+    summary = "List a task type's event conventions",
+    security(("Bearer" = [])),
+    params(("task_type" = String, Path, description = "Task type")),
+    responses(
+        (status = 200, description = "The task type's catalog", body = ConventionCatalog),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Discovery is off, or nothing is known of the task type"),
+    )
+)]
+pub async fn get_task_type_conventions(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_type): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, None) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+    let conventions = engine
+        .list_conventions(Some(&task_type))
+        .await
+        .map_err(EngineError::Store)?
+        .ok_or_else(discovery_off)?;
+    if conventions.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "No event conventions known for task type {task_type}"
+        )));
+    }
+    Ok(axum::Json(ConventionCatalog { conventions }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/conventions/{task_type}/{event_type}",
+    tag = "Events",
+    summary = "Describe an event type",
+    description = "Sets the description and JSON Schema of an event type of a task type, replacing both. The event type need not have been sampled yet. Schemas are documentation: published events are not validated against them.",
+    security(("Bearer" = [])),
+    params(
+        ("task_type" = String, Path, description = "Task type"),
+        ("event_type" = String, Path, description = "Event type"),
+    ),
+    request_body = ConventionNotes,
+    responses(
+        (status = 200, description = "The event type's convention", body = EventConvention),
+        (status = 400, description = "Invalid event type or schema"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Discovery is off"),
+    )
+)]
+pub async fn put_convention(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path((task_type, event_type)): Path<(String, String)>,
+    axum::Json(notes): axum::Json<ConventionNotes>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    let rules = engine.event_types();
+    rules
+        .check(&event_type, "/eventType")
+        .map_err(|violation| AppError::BadRequest(violation.message))?;
+    if notes
+        .schema
+        .as_ref()
+        .is_some_and(|schema| !schema.is_object() && !schema.is_boolean())
+    {
+        return Err(AppError::BadRequest(
+            "schema must be a JSON Schema: an object or a boolean".to_string(),
+        ));
+    }
+    let convention = engine
+        .annotate_convention(&task_type, &rules.normalize(&event_type), &notes)
+        .await
+        .map_err(EngineError::Store)?
+        .ok_or_else(discovery_off)?;
+    Ok(axum::Json(convention))
+}
+
+pub fn conventions_router() -> axum::Router<Arc<TaskEngine>> {
+    axum::Router::new()
+        .route("/", get(list_conventions))
+        .route("/{task_type}", get(get_task_type_conventions))
+        .route("/{task_type}/{event_type}", put(put_convention))
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+fn discovery_off() -> AppError {
+    AppError::NotFound("Event type discovery is off; set discovery.enabled".to_string())
+}
@@ -1,5 +1,11 @@
 pub mod admin;
+pub mod conventions;
+pub mod outcomes;
+pub mod replication;
+pub mod series;
 pub mod sse;
 pub mod tasks;
+pub mod templates;
+pub mod view;
 pub mod worker_ws;
 pub mod workers;
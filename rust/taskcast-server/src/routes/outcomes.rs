@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::response::IntoResponse;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+use taskcast_core::{
+    OutcomeCursor, OutcomeQuery, PermissionScope, TaskEngine, TaskOutcome, TaskStatus,
+};
+
+use crate::auth::{check_scope, AuthContext};
+use crate::error::AppError;
+
+/// Page size when `limit` is omitted.
+const DEFAULT_LIMIT: usize = 100;
+/// Largest accepted `limit`.
+const MAX_LIMIT: usize = 1000;
+
+// ─── Query / Response ───────────────────────────────────────────────────────
+
+/// Query parameters for `GET /outcomes`.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct OutcomesQuery {
+    /// Comma-separated terminal statuses, e.g. `failed,timeout`.
+    pub status: Option<String>,
+    /// Comma-separated task type patterns, e.g. `import.*`.
+    pub r#type: Option<String>,
+    /// Only tasks that ended at or after this time, in epoch milliseconds.
+    pub since: Option<String>,
+    /// Page size, 1 to 1000. Default: 100.
+    pub limit: Option<String>,
+    /// `nextCursor` from a previous response.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutcomesPage {
+    /// Oldest first.
+    pub outcomes: Vec<TaskOutcome>,
+    /// Pass as `cursor` for the next page; `null` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/outcomes",
+    tag = "Tasks",
+    summary = "List task outcomes",
+    description = "Compact records of tasks that reached a terminal status within the retention window, oldest first.",
+    security(("Bearer" = [])),
+    params(OutcomesQuery),
+    responses(
+        (status = 200, description = "One page of outcomes", body = OutcomesPage),
+        (status = 400, description = "Invalid query parameter"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn list_outcomes(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Query(query): Query<OutcomesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, None) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    let limit = match query.limit.as_deref() {
+        None => DEFAULT_LIMIT,
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+            .ok_or_else(|| {
+                invalid(
+                    "limit",
+                    format!("expected 1 to {MAX_LIMIT}, got \"{value}\""),
+                )
+            })?,
+    };
+    let statuses = query
+        .status
+        .as_deref()
+        .map(|value| {
+            list(value)
+                .map(|status| {
+                    serde_json::from_value::<TaskStatus>(serde_json::Value::String(
+                        status.to_string(),
+                    ))
+                    .map_err(|_| invalid("status", format!("unknown status \"{status}\"")))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    let types = query
+        .r#type
+        .as_deref()
+        .map(|value| list(value).map(str::to_string).collect::<Vec<_>>());
+    let since = query
+        .since
+        .as_deref()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|since| since.is_finite())
+                .ok_or_else(|| {
+                    invalid(
+                        "since",
+                        format!("expected a number of milliseconds, got \"{value}\""),
+                    )
+                })
+        })
+        .transpose()?;
+    let after = query
+        .cursor
+        .as_deref()
+        .map(|value| {
+            value
+                .parse::<OutcomeCursor>()
+                .map_err(|reason| invalid("cursor", reason))
+        })
+        .transpose()?;
+
+    // One extra row tells whether another page follows.
+    let mut outcomes = engine
+        .list_outcomes(&OutcomeQuery {
+            statuses,
+            types,
+            since,
+            after,
+            limit: limit + 1,
+        })
+        .await?;
+    let next_cursor = if outcomes.len() > limit {
+        outcomes.truncate(limit);
+        outcomes
+            .last()
+            .map(|last| OutcomeCursor::of(last).to_string())
+    } else {
+        None
+    };
+
+    Ok(axum::Json(OutcomesPage {
+        outcomes,
+        next_cursor,
+    }))
+}
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn invalid(param: &str, reason: impl Into<String>) -> AppError {
+    AppError::InvalidQuery {
+        param: param.to_string(),
+        reason: reason.into(),
+    }
+}
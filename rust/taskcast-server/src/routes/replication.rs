@@ -0,0 +1,132 @@
+//! Receiving writes replicated from another deployment.
+//!
+//! A [`ReplicationSink`](crate::replication::ReplicationSink) on the primary
+//! sends its writes to the ordinary `POST /tasks` and
+//! `POST /tasks/:taskId/events` endpoints, marked with the
+//! [`REPLICATED_HEADER`]. The middleware here takes those requests over:
+//! tasks and events are applied as the primary saved them, ids and indices
+//! included, and never replicated onwards. Other requests pass through.
+
+use std::sync::Arc;
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Path, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use taskcast_core::{PermissionScope, ReplicatedTaskOutcome, Task, TaskEngine, TaskEvent};
+
+use crate::auth::{check_scope, AuthContext};
+use crate::error::AppError;
+use crate::replication::REPLICATED_HEADER;
+
+/// Largest replicated request body accepted, in bytes.
+const MAX_REPLICATED_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+/// State of the replicated-write middleware.
+#[derive(Clone)]
+pub struct ReplicatedWrites {
+    pub engine: Arc<TaskEngine>,
+    /// `replication.allowReplicatedWrites`.
+    pub allowed: bool,
+}
+
+impl ReplicatedWrites {
+    fn authorize(&self, auth: &AuthContext, task_id: Option<&str>) -> Result<(), AppError> {
+        if !self.allowed {
+            return Err(AppError::Forbidden);
+        }
+        if !check_scope(auth, PermissionScope::TaskReplicate, task_id) {
+            return Err(AppError::MissingScope(PermissionScope::TaskReplicate));
+        }
+        Ok(())
+    }
+}
+
+fn is_replicated(headers: &HeaderMap) -> bool {
+    headers
+        .get(REPLICATED_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+async fn read_body<T: DeserializeOwned>(body: Body) -> Result<T, AppError> {
+    let bytes = to_bytes(body, MAX_REPLICATED_BODY_BYTES)
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| AppError::BadRequest(e.to_string()))
+}
+
+/// `POST /tasks` with the replicated header: the body is a whole task,
+/// created if it is not here yet (`201`) and otherwise kept or replaced by
+/// `updatedAt` (`200`). Responds with the task as stored.
+pub async fn replicated_create(
+    State(writes): State<ReplicatedWrites>,
+    Extension(auth): Extension<AuthContext>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !is_replicated(request.headers()) {
+        return Ok(next.run(request).await);
+    }
+    writes.authorize(&auth, None)?;
+    let task: Task = read_body(request.into_body()).await?;
+    let task_id = task.id.clone();
+    let outcome = writes.engine.apply_replicated_task(task).await?;
+    let status = match outcome {
+        ReplicatedTaskOutcome::Created => StatusCode::CREATED,
+        ReplicatedTaskOutcome::Updated | ReplicatedTaskOutcome::Unchanged => StatusCode::OK,
+    };
+    let task = writes
+        .engine
+        .get_task(&task_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(task_id))?;
+    Ok((status, axum::Json(task)).into_response())
+}
+
+/// `POST /tasks/:taskId/events` with the replicated header: the body is
+/// one event or an array of events, applied in order with their ids,
+/// indices and timestamps. Events the task already holds are skipped.
+/// Responds `201 { "applied", "skipped" }`.
+pub async fn replicated_events(
+    State(writes): State<ReplicatedWrites>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !is_replicated(request.headers()) {
+        return Ok(next.run(request).await);
+    }
+    writes.authorize(&auth, Some(&task_id))?;
+    let body: serde_json::Value = read_body(request.into_body()).await?;
+    let events: Vec<TaskEvent> = match body {
+        serde_json::Value::Array(_) => serde_json::from_value(body),
+        single => serde_json::from_value(single).map(|event| vec![event]),
+    }
+    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    if let Some(event) = events.iter().find(|event| event.task_id != task_id) {
+        return Err(AppError::BadRequest(format!(
+            "Replicated event {} belongs to task {}, not {task_id}",
+            event.id, event.task_id
+        )));
+    }
+
+    let mut applied = 0;
+    let mut skipped = 0;
+    for event in events {
+        match writes.engine.apply_replicated_event(event).await? {
+            Some(_) => applied += 1,
+            None => skipped += 1,
+        }
+    }
+    Ok((
+        StatusCode::CREATED,
+        axum::Json(json!({ "applied": applied, "skipped": skipped })),
+    )
+        .into_response())
+}
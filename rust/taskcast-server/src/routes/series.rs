@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+use taskcast_core::{EngineError, PermissionScope, SeriesSummary, TaskEngine};
+
+use crate::auth::{check_scope, AuthContext};
+use crate::error::AppError;
+
+/// Page size when `limit` is omitted.
+const DEFAULT_LIMIT: usize = 100;
+/// Largest accepted `limit`.
+const MAX_LIMIT: usize = 1000;
+
+// ─── Query / Response ───────────────────────────────────────────────────────
+
+/// Query parameters for `GET /tasks/{task_id}/series`.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SeriesQuery {
+    /// Page size, 1 to 1000. Default: 100.
+    pub limit: Option<String>,
+    /// `nextCursor` from a previous response.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesPage {
+    pub task_id: String,
+    /// Ordered by series id.
+    pub series: Vec<SeriesSummary>,
+    /// Pass as `cursor` for the next page; `null` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/series",
+    tag = "Events",
+    summary = "List a task's series",
+    description = "The `accumulate`, `latest` and `json-patch` series the task keeps state for, with each one's mode and latest event index and timestamp, ordered by series id. `keep-all` series keep no state and are not listed.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID"), SeriesQuery),
+    responses(
+        (status = 200, description = "One page of series", body = SeriesPage),
+        (status = 400, description = "Invalid query parameter"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn list_series(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+    Query(query): Query<SeriesQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    let limit = match query.limit.as_deref() {
+        None => DEFAULT_LIMIT,
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+            .ok_or_else(|| AppError::InvalidQuery {
+                param: "limit".to_string(),
+                reason: format!("expected 1 to {MAX_LIMIT}, got \"{value}\""),
+            })?,
+    };
+
+    engine
+        .get_task(&task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
+    let mut series = engine.list_series(&task_id).await?;
+    if let Some(ref after) = query.cursor {
+        series.retain(|summary| summary.series_id > *after);
+    }
+    let next_cursor = if series.len() > limit {
+        series.truncate(limit);
+        series.last().map(|last| last.series_id.clone())
+    } else {
+        None
+    };
+
+    Ok(axum::Json(SeriesPage {
+        task_id,
+        series,
+        next_cursor,
+    }))
+}
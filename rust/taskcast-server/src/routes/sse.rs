@@ -1,22 +1,30 @@
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::extract::{Path, Query, State};
-use axum::response::sse::{Event, Sse};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::Extension;
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
 use serde::Deserialize;
 use tokio::sync::Mutex;
 use tokio_stream::wrappers::ReceiverStream;
 
+use taskcast_core::config::SseConfig;
 use taskcast_core::{
-    apply_filtered_index, matches_filter, matches_type, CreationListener, EventQueryOptions, Level,
-    SSEEnvelope, SeriesFormat, SinceCursor, SubscribeFilter, TaskEngine, TaskEvent, TaskStatus,
+    filter_hash, matches_labels, matches_task, matches_type, parse_label_selector, resolve_filter,
+    to_envelope, BackgroundTasks, CreationListener, EngineError, EventTypeCounts,
+    HistoryChecksumBuilder, ReplayBudget, ReplayOptions, SSEEnvelope, SeriesFormat, ShortTermStore,
+    StreamItem, SubscribeFilter, TaskEngine, TaskMatch, TaskStatus,
 };
 
 use crate::auth::{check_scope, AuthContext};
 use crate::error::AppError;
+use crate::frame_cache::{event_payload, FrameCache, FrameLease};
+use crate::query::QueryOptions;
+use crate::timestamps::{TimestampCheck, TimestampWarnings};
 
 // ─── Subscriber Tracking ─────────────────────────────────────────────────────
 
@@ -47,96 +55,379 @@ async fn decrement_subscriber_count(counts: &SubscriberCounts, task_id: &str) {
     }
 }
 
+/// Counts a held connection (e.g. a long-poll waiter) as a subscriber of a
+/// task until dropped, including when the request future is cancelled.
+pub(crate) struct SubscriberGuard {
+    counts: SubscriberCounts,
+    task_id: String,
+    background: BackgroundTasks,
+}
+
+impl SubscriberGuard {
+    pub(crate) async fn acquire(
+        counts: &SubscriberCounts,
+        task_id: &str,
+        background: &BackgroundTasks,
+    ) -> Self {
+        increment_subscriber_count(counts, task_id).await;
+        Self {
+            counts: counts.clone(),
+            task_id: task_id.to_string(),
+            background: background.clone(),
+        }
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        let counts = self.counts.clone();
+        let task_id = std::mem::take(&mut self.task_id);
+        self.background.spawn(
+            "sse.release_subscriber",
+            Some(task_id.clone()),
+            async move {
+                decrement_subscriber_count(&counts, &task_id).await;
+            },
+        );
+    }
+}
+
+// ─── Heartbeat ──────────────────────────────────────────────────────────────
+
+/// Heartbeat interval when `sse.heartbeatIntervalMs` is unset.
+pub const DEFAULT_HEARTBEAT_INTERVAL_MS: u64 = 15_000;
+
+/// How long an SSE stream may stay quiet before a `: keepalive` comment is
+/// sent, so proxies that drop idle connections leave it open. Comments are
+/// not events, so they never count towards `filteredIndex`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SseHeartbeat {
+    /// `None` sends no heartbeat.
+    pub interval: Option<Duration>,
+}
+
+impl SseHeartbeat {
+    /// The heartbeat set by the `sse` config section; `0` turns it off.
+    pub fn from_config(config: Option<&SseConfig>) -> Self {
+        let ms = config
+            .and_then(|c| c.heartbeat_interval_ms)
+            .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL_MS);
+        Self {
+            interval: (ms > 0).then(|| Duration::from_millis(ms)),
+        }
+    }
+
+    /// The SSE response for `stream`. Heartbeats end with the stream, so a
+    /// task stream sends none after its done frame.
+    fn respond<S>(self, stream: S) -> Response
+    where
+        S: Stream<Item = Result<Event, Infallible>> + Send + 'static,
+    {
+        match self.interval {
+            Some(interval) => Sse::new(stream)
+                .keep_alive(KeepAlive::new().interval(interval).text("keepalive"))
+                .into_response(),
+            None => Sse::new(stream).into_response(),
+        }
+    }
+}
+
 // ─── Query Parameters ───────────────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, utoipa::IntoParams)]
+/// Series and checksum options of the SSE endpoint. The filter parameters
+/// are parsed by [`QueryOptions`].
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
 pub struct SseQuery {
-    pub types: Option<String>,
-    pub levels: Option<String>,
-    #[serde(rename = "includeStatus")]
-    pub include_status: Option<String>,
-    pub wrap: Option<String>,
-    #[serde(rename = "seriesFormat")]
+    /// `delta`, `accumulated` or `both`. Also accepted as `seriesView`.
+    #[serde(rename = "seriesFormat", alias = "seriesView")]
     pub series_format: Option<String>,
-    #[serde(rename = "since.id")]
-    pub since_id: Option<String>,
-    #[serde(rename = "since.index")]
-    pub since_index: Option<String>,
-    #[serde(rename = "since.timestamp")]
-    pub since_timestamp: Option<String>,
-    pub limit: Option<String>,
+    /// Replay each `json-patch` series as one snapshot event carrying its
+    /// current document.
+    #[serde(rename = "materializeSeries")]
+    pub materialize_series: Option<String>,
+    /// Add a `checksum` of every event sent on the connection to the
+    /// `taskcast.done` frame.
+    pub checksum: Option<String>,
+    /// Replay the whole history, ignoring the server's replay budget.
+    #[serde(rename = "fullReplay")]
+    pub full_replay: Option<String>,
+    /// Names the subscriber, so its `filteredIndex` numbering is kept in the
+    /// store and a reconnect resumes after the last event numbered.
+    #[serde(rename = "consumerId")]
+    pub consumer_id: Option<String>,
+    /// Comma-separated task type patterns the task must match.
+    #[serde(rename = "taskTypes")]
+    pub task_types: Option<String>,
+    /// Comma-separated statuses the task must be in one of.
+    #[serde(rename = "taskStatus")]
+    pub task_status: Option<String>,
+    /// `key:value` pairs the task's metadata must hold, as string values.
+    #[serde(rename = "taskMetadata")]
+    pub task_metadata: Option<String>,
 }
 
-// ─── Filter Parsing ─────────────────────────────────────────────────────────
+/// Pointer sent in `taskcast.truncated` frames.
+const TRUNCATED_HINT: &str = "use /events/history with pagination";
 
-fn parse_filter(query: &SseQuery) -> SubscribeFilter {
-    let types = query
-        .types
-        .as_ref()
-        .map(|t| t.split(',').filter(|s| !s.is_empty()).map(String::from).collect());
+/// Frames buffered between a task's event stream and the response. Kept
+/// small: a status event can only overtake data still in the stream, not
+/// frames already buffered here.
+const SSE_FRAME_BUFFER: usize = 16;
 
-    let levels = query.levels.as_ref().map(|l| {
-        l.split(',')
-            .filter(|s| !s.is_empty())
-            .filter_map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())).ok())
-            .collect::<Vec<Level>>()
-    });
+/// Parameters the SSE endpoint does not support.
+const SSE_UNSUPPORTED: &[&str] = &["cursor", "order", "tail", "excludeTypes", "fields"];
 
-    let include_status = query.include_status.as_ref().map(|v| v != "false");
-    let wrap = query.wrap.as_ref().map(|v| v != "false");
+// ─── Filter Parsing ─────────────────────────────────────────────────────────
 
+/// The subscribe filter for `options` with the series options of `query`.
+/// Unknown `seriesFormat` values are ignored.
+pub(crate) fn parse_filter(options: &QueryOptions, query: &SseQuery) -> SubscribeFilter {
     let series_format = query.series_format.as_ref().and_then(|s| match s.as_str() {
         "delta" => Some(SeriesFormat::Delta),
         "accumulated" => Some(SeriesFormat::Accumulated),
+        "both" => Some(SeriesFormat::Both),
         _ => None,
     });
+    let materialize_series = query.materialize_series.as_ref().map(|v| v == "true");
 
-    let since = if query.since_id.is_some()
-        || query.since_index.is_some()
-        || query.since_timestamp.is_some()
-    {
-        Some(SinceCursor {
-            id: query.since_id.clone(),
-            index: query.since_index.as_ref().and_then(|s| s.parse().ok()),
-            timestamp: query.since_timestamp.as_ref().and_then(|s| s.parse().ok()),
-        })
-    } else {
-        None
+    SubscribeFilter {
+        series_format,
+        materialize_series,
+        ..options.subscribe_filter()
+    }
+}
+
+/// The task conditions of `query`, or `None` when it sets none.
+fn parse_task_match(query: &SseQuery) -> Result<Option<TaskMatch>, AppError> {
+    let invalid = |param: &str, reason: String| AppError::InvalidQuery {
+        param: param.to_string(),
+        reason,
+    };
+    let list = |value: &str| -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect()
     };
 
-    SubscribeFilter {
+    let types = query.task_types.as_deref().map(list);
+    let status = match query.task_status.as_deref() {
+        Some(value) => Some(
+            list(value)
+                .into_iter()
+                .map(|status| {
+                    serde_json::from_value::<TaskStatus>(serde_json::Value::String(status.clone()))
+                        .map_err(|_| {
+                            invalid("taskStatus", format!("unknown task status \"{status}\""))
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let metadata = match query.task_metadata.as_deref() {
+        Some(value) => Some(
+            parse_label_selector(value)
+                .map_err(|e| {
+                    invalid(
+                        "taskMetadata",
+                        format!("expected `key:value` pairs separated by commas ({e})"),
+                    )
+                })?
+                .into_iter()
+                .map(|(key, value)| (key, serde_json::Value::String(value)))
+                .collect(),
+        ),
+        None => None,
+    };
+
+    if types.is_none() && status.is_none() && metadata.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(TaskMatch {
         types,
-        levels,
-        include_status,
-        wrap,
-        since,
-        series_format,
+        metadata,
+        status,
+    }))
+}
+
+/// Refuses a subscription whose `taskMatch` the task does not meet as it is
+/// now. Checked once, on connect.
+async fn check_task_match(
+    engine: &TaskEngine,
+    task_id: &str,
+    filter: &SubscribeFilter,
+) -> Result<(), AppError> {
+    let Some(ref task_match) = filter.task_match else {
+        return Ok(());
+    };
+    let task = engine
+        .get_task(task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+    if !matches_task(&task, task_match) {
+        return Err(AppError::TaskMismatch(task_id.to_string()));
     }
+    Ok(())
+}
+
+/// Narrows the task's `preset`, if one is named, with `filter`.
+pub(crate) async fn resolve_query_filter(
+    engine: &TaskEngine,
+    task_id: &str,
+    preset: Option<&str>,
+    filter: SubscribeFilter,
+) -> Result<SubscribeFilter, AppError> {
+    let Some(preset) = preset else {
+        return Ok(filter);
+    };
+    let task = engine
+        .get_task(task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+    Ok(resolve_filter(task.filters.as_ref(), Some(preset), filter).map_err(EngineError::from)?)
 }
 
-// ─── Envelope Conversion ────────────────────────────────────────────────────
-
-fn to_envelope(event: &TaskEvent, filtered_index: u64) -> SSEEnvelope {
-    SSEEnvelope {
-        filtered_index,
-        raw_index: event.index,
-        event_id: event.id.clone(),
-        task_id: event.task_id.clone(),
-        r#type: event.r#type.clone(),
-        timestamp: event.timestamp,
-        level: event.level.clone(),
-        data: event.data.clone(),
-        series_id: event.series_id.clone(),
-        series_mode: event.series_mode.clone(),
-        series_acc_field: event.series_acc_field.clone(),
-        series_snapshot: event.series_snapshot,
+// ─── Named Consumers ────────────────────────────────────────────────────────
+
+/// A `consumerId` subscription's persisted `filteredIndex` numbering.
+struct NamedConsumer {
+    store: Arc<dyn ShortTermStore>,
+    task_id: String,
+    consumer: String,
+    filter_hash: String,
+    /// Events up to this raw index were numbered on an earlier connection
+    /// under the same filter.
+    numbered_through: Option<u64>,
+}
+
+impl NamedConsumer {
+    async fn open(
+        engine: &TaskEngine,
+        task_id: &str,
+        consumer_id: &str,
+        filter: &SubscribeFilter,
+    ) -> Result<Self, AppError> {
+        let store = Arc::clone(engine.short_term_store());
+        let consumer = format!("sse:{consumer_id}");
+        let filter_hash = filter_hash(filter);
+        let mark = store
+            .get_filtered_index_mark(task_id, &consumer)
+            .await
+            .map_err(EngineError::from)?;
+        let numbered_through = mark
+            .filter(|mark| mark.filter_hash == filter_hash)
+            .and_then(|mark| mark.through);
+        Ok(Self {
+            store,
+            task_id: task_id.to_string(),
+            consumer,
+            filter_hash,
+            numbered_through,
+        })
+    }
+
+    /// Gives `item` its place in the numbering, or `None` when an earlier
+    /// connection already sent it. Stores without persisted numbering leave
+    /// the per-connection index in place.
+    async fn number(&self, item: StreamItem) -> Result<Option<StreamItem>, EngineError> {
+        let StreamItem::Event(mut envelope) = item else {
+            return Ok(Some(item));
+        };
+        if self
+            .numbered_through
+            .is_some_and(|through| envelope.raw_index <= through)
+        {
+            return Ok(None);
+        }
+        let claim = self
+            .store
+            .claim_filtered_index(
+                &self.task_id,
+                &self.consumer,
+                &self.filter_hash,
+                envelope.raw_index,
+            )
+            .await?;
+        if let Some(claim) = claim {
+            envelope.filtered_index = claim.filtered_index;
+            envelope.filter_epoch = Some(claim.epoch);
+        }
+        Ok(Some(StreamItem::Event(envelope)))
     }
 }
 
-// ─── Terminal Status Check ──────────────────────────────────────────────────
+// ─── Stream Item Framing ────────────────────────────────────────────────────
+
+/// The first frame of a task stream: the distinct event types the task had
+/// when the client connected, so filter UIs can be built before any replay.
+/// Types the subscription's `types` and `includeStatus` rule out are left out.
+fn init_frame(task_id: &str, counts: &EventTypeCounts, filter: &SubscribeFilter) -> Event {
+    let include_status = filter.include_status.unwrap_or(true);
+    let types: Vec<&String> = counts
+        .types
+        .keys()
+        .filter(|r#type| include_status || r#type.as_str() != "taskcast:status")
+        .filter(|r#type| matches_type(r#type, filter.types.as_deref()))
+        .collect();
+    Event::default()
+        .event("taskcast.init")
+        .data(serde_json::json!({ "taskId": task_id, "types": types }).to_string())
+}
+
+/// Frames a stream item. With `checksum`, every event frame's payload is added
+/// to it and the done frame carries the result.
+fn stream_item_to_sse(
+    item: StreamItem,
+    wrap: bool,
+    checksum: &mut Option<HistoryChecksumBuilder>,
+) -> Event {
+    match item {
+        StreamItem::Truncated(truncation) => {
+            let mut data = serde_json::to_value(truncation).unwrap();
+            data["hint"] = TRUNCATED_HINT.into();
+            Event::default()
+                .event("taskcast.truncated")
+                .data(serde_json::to_string(&data).unwrap())
+        }
+        StreamItem::Event(envelope) => {
+            let id = envelope.event_id.clone();
+            let raw_index = envelope.raw_index;
+            let payload = event_payload(envelope, wrap);
+            if let Some(checksum) = checksum {
+                checksum.push_value(raw_index, &payload);
+            }
+            Event::default()
+                .event("taskcast.event")
+                .data(serde_json::to_string(&payload).unwrap())
+                .id(id)
+        }
+        StreamItem::Done(status) => {
+            let mut data = serde_json::json!({ "reason": status });
+            if let Some(checksum) = checksum.take() {
+                data["checksum"] = serde_json::to_value(checksum.finish()).unwrap();
+            }
+            Event::default()
+                .event("taskcast.done")
+                .data(serde_json::to_string(&data).unwrap())
+        }
+        // Failures after the stream opened can't change the HTTP status, so
+        // they are reported in-band using the same code/message/details shape.
+        StreamItem::Error(e) => Event::default()
+            .event("taskcast.error")
+            .data(serde_json::to_string(&AppError::from(e).payload().to_json(None)).unwrap()),
+    }
+}
 
-fn is_terminal_status(status: &TaskStatus) -> bool {
-    taskcast_core::state_machine::is_terminal(status)
+/// Frames a live event with its payload from the task's frame cache.
+fn live_event_frame(frames: &FrameLease, envelope: SSEEnvelope) -> Event {
+    let id = envelope.event_id.clone();
+    Event::default()
+        .event("taskcast.event")
+        .data(frames.payload(envelope))
+        .id(id)
 }
 
 // ─── SSE Handler ────────────────────────────────────────────────────────────
@@ -146,220 +437,161 @@ fn is_terminal_status(status: &TaskStatus) -> bool {
     path = "/tasks/{task_id}/events",
     tag = "Events",
     summary = "Subscribe to task events via SSE",
-    description = "Server-Sent Events stream. Replays history then streams live events.",
+    description = "Server-Sent Events stream. Opens with a `taskcast.init` frame listing the event types the task has, then replays history then streams live events; live status changes are sent ahead of data events still queued for a slow client. A history over the server's replay budget is cut to its most recent events, after a `taskcast.truncated` frame.",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID"), SseQuery),
+    params(("task_id" = String, Path, description = "Task ID"), QueryOptions, SseQuery),
     responses(
         (status = 200, description = "SSE event stream (text/event-stream)"),
+        (status = 400, description = "Invalid query parameter"),
         (status = 404, description = "Task not found"),
-        (status = 403, description = "Forbidden"),
+        (status = 403, description = "Forbidden, or the task does not match taskTypes, taskStatus or taskMetadata"),
     )
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn sse_events(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
     Extension(subscriber_counts): Extension<SubscriberCounts>,
+    Extension(budget): Extension<ReplayBudget>,
+    Extension(heartbeat): Extension<SseHeartbeat>,
+    Extension(frame_cache): Extension<Arc<FrameCache>>,
+    timestamp_check: TimestampCheck,
     Path(task_id): Path<String>,
+    options: QueryOptions,
     Query(query): Query<SseQuery>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+) -> Result<(TimestampWarnings, Response), AppError> {
     if !check_scope(
         &auth,
         taskcast_core::PermissionScope::EventSubscribe,
         Some(&task_id),
     ) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::EventSubscribe,
+        ));
     }
 
-    let task = engine
-        .get_task(&task_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Task not found".to_string()))?;
-
-    let filter = parse_filter(&query);
+    options.reject(SSE_UNSUPPORTED)?;
+    let warnings = timestamp_check.query(&[(
+        "since.timestamp",
+        options.since.as_ref().and_then(|s| s.timestamp),
+    )])?;
+    let filter = SubscribeFilter {
+        task_match: parse_task_match(&query)?,
+        ..parse_filter(&options, &query)
+    };
+    let filter = resolve_query_filter(&engine, &task_id, options.preset.as_deref(), filter).await?;
+    check_task_match(&engine, &task_id, &filter).await?;
     let wrap = filter.wrap.unwrap_or(true);
+    let mut checksum =
+        (query.checksum.as_deref() == Some("true")).then(HistoryChecksumBuilder::new);
 
-    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Event, Infallible>>(256);
-
-    let task_status = task.status.clone();
-    let task_id_clone = task_id.clone();
-    let sub_counts = subscriber_counts.clone();
-
-    let series_format = filter
-        .series_format
-        .clone()
-        .unwrap_or(SeriesFormat::Delta);
-
-    tokio::spawn(async move {
-        increment_subscriber_count(&sub_counts, &task_id_clone).await;
-
-        let series_format_for_send = series_format.clone();
+    let budget = if query.full_replay.as_deref() == Some("true") {
+        ReplayBudget::unlimited()
+    } else {
+        budget
+    };
 
-        // Helper closures
-        let send_event = move |tx: &tokio::sync::mpsc::Sender<Result<Event, Infallible>>,
-                          event: &TaskEvent,
-                          filtered_index: u64,
-                          wrap: bool| {
-            let mut event_to_send = event.clone();
+    let consumer = match query.consumer_id.as_deref() {
+        Some(consumer_id) => {
+            Some(NamedConsumer::open(&engine, &task_id, consumer_id, &filter).await?)
+        }
+        None => None,
+    };
+    // Live event payloads are shared with the task's other connections,
+    // unless the connection adds to a checksum or numbers its own events.
+    let frames = (checksum.is_none() && consumer.is_none())
+        .then(|| frame_cache.open(&task_id, wrap, filter.series_format.as_ref()));
+    let init_filter = filter.clone();
+    let items = engine
+        .subscribe_stream_with_replay(
+            &task_id,
+            filter,
+            ReplayOptions {
+                history_limit: options.limit,
+                budget,
+                ..Default::default()
+            },
+        )
+        .await?;
+    // The checksum is verified against history, and a named consumer's
+    // numbering follows raw indices, so frames go out in store order rather
+    // than with status events first.
+    let mut items = if checksum.is_some() || consumer.is_some() {
+        items.in_store_order()
+    } else {
+        items
+    };
 
-            // For accumulated format, swap data with accumulated data if present
-            if series_format_for_send == SeriesFormat::Accumulated {
-                if let Some(ref acc_data) = event._accumulated_data {
-                    event_to_send.data = acc_data.clone();
-                }
-            }
-            // Strip transient field before sending
-            event_to_send._accumulated_data = None;
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Event, Infallible>>(SSE_FRAME_BUFFER);
+    let sub_counts = subscriber_counts.clone();
 
-            let payload: serde_json::Value = if wrap {
-                serde_json::to_value(to_envelope(&event_to_send, filtered_index)).unwrap()
-            } else {
-                serde_json::to_value(&event_to_send).unwrap()
-            };
-            let sse_event = Event::default()
-                .event("taskcast.event")
-                .data(serde_json::to_string(&payload).unwrap())
-                .id(event.id.clone());
-            let _ = tx.try_send(Ok(sse_event));
-        };
+    let background = engine.background().clone();
+    background.spawn("sse.forward", Some(task_id.clone()), async move {
+        increment_subscriber_count(&sub_counts, &task_id).await;
 
-        let send_done =
-            |tx: &tokio::sync::mpsc::Sender<Result<Event, Infallible>>, reason: &str| {
-                let data = serde_json::json!({ "reason": reason });
-                let sse_event = Event::default()
-                    .event("taskcast.done")
-                    .data(serde_json::to_string(&data).unwrap());
-                let _ = tx.try_send(Ok(sse_event));
+        // Without counts the frame is left out; a failing store surfaces
+        // through the replay's error frame instead.
+        if let Ok(counts) = engine.event_type_counts(&task_id).await {
+            let _ = tx.send(Ok(init_frame(&task_id, &counts, &init_filter))).await;
+        }
+        // Forward until the stream ends (done/error) or the client disconnects
+        // (tx.closed() resolves when rx is dropped). Dropping `items` unsubscribes.
+        loop {
+            let item = tokio::select! {
+                item = items.next() => item,
+                _ = tx.closed() => None,
             };
-
-        // Build storage-level query options (since cursor + limit)
-        let limit = query.limit.as_ref().and_then(|s| s.parse::<u64>().ok());
-        let since = filter.since.as_ref().and_then(|s| {
-            if s.id.is_some() || s.timestamp.is_some() {
-                Some(SinceCursor {
-                    id: s.id.clone(),
-                    index: None,
-                    timestamp: s.timestamp,
-                })
-            } else {
-                None
-            }
-        });
-        let history_opts = if since.is_some() || limit.is_some() {
-            Some(EventQueryOptions { since, limit })
-        } else {
-            None
-        };
-
-        // Replay history
-        let history = match engine.get_events(&task_id_clone, history_opts).await {
-            Ok(events) => events,
-            Err(_) => {
-                decrement_subscriber_count(&sub_counts, &task_id_clone).await;
-                return;
-            }
-        };
-
-        let has_since_cursor = filter.since.is_some();
-
-        // Build replay events with late-join snapshot collapse
-        let replay_events = if !has_since_cursor {
-            let engine_ref = Arc::clone(&engine);
-            taskcast_core::series::collapse_accumulate_series(
-                &history,
-                |tid: &str, sid: &str| {
-                    let eng = Arc::clone(&engine_ref);
-                    let tid = tid.to_string();
-                    let sid = sid.to_string();
-                    async move {
-                        eng.get_series_latest(&tid, &sid).await
-                            .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            let Some(item) = item else { break };
+            let item = match &consumer {
+                Some(consumer) => match consumer.number(item).await {
+                    Ok(Some(item)) => item,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        let error = StreamItem::Error(err);
+                        let _ = tx
+                            .send(Ok(stream_item_to_sse(error, wrap, &mut checksum)))
+                            .await;
+                        break;
                     }
                 },
-            ).await.unwrap_or(history)
-        } else {
-            history
-        };
-
-        let filtered = apply_filtered_index(&replay_events, &filter);
-        for fe in &filtered {
-            send_event(&tx, &fe.event, fe.filtered_index, wrap);
-        }
-
-        // If already terminal, send done and return
-        if is_terminal_status(&task_status) {
-            let status_str =
-                serde_json::to_value(&task_status).unwrap_or(serde_json::Value::Null);
-            send_done(&tx, status_str.as_str().unwrap_or("completed"));
-            decrement_subscriber_count(&sub_counts, &task_id_clone).await;
-            return;
-        }
-
-        // Subscribe to live events
-        let next_filtered_index = if let Some(last) = filtered.last() {
-            last.filtered_index + 1
-        } else {
-            0
-        };
-
-        let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
-        let done_tx = Arc::new(tokio::sync::Mutex::new(Some(done_tx)));
-
-        let filter_for_sub = filter.clone();
-        let tx_for_sub = tx.clone();
-        let done_tx_for_sub = Arc::clone(&done_tx);
-
-        // We need to use a shared mutable counter for the subscription callback
-        let next_idx = Arc::new(std::sync::atomic::AtomicU64::new(next_filtered_index));
-
-        let unsub = engine
-            .subscribe(
-                &task_id_clone,
-                Box::new(move |event| {
-                    if !matches_filter(&event, &filter_for_sub) {
-                        return;
-                    }
-                    let idx = next_idx.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-                    send_event(&tx_for_sub, &event, idx, wrap);
-
-                    if event.r#type == "taskcast:status" {
-                        if let Some(status) = event.data.get("status").and_then(|s| s.as_str()) {
-                            if matches!(
-                                status,
-                                "completed" | "failed" | "timeout" | "cancelled"
-                            ) {
-                                send_done(&tx_for_sub, status);
-                                if let Ok(mut guard) = done_tx_for_sub.try_lock() {
-                                    if let Some(sender) = guard.take() {
-                                        let _ = sender.send(());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }),
-            )
-            .await;
-
-        // Wait for terminal event OR client disconnect (tx.closed() resolves when rx is dropped)
-        tokio::select! {
-            _ = done_rx => {}
-            _ = tx.closed() => {}
+                None => item,
+            };
+            let frame = match (&frames, item) {
+                (Some(frames), StreamItem::Event(envelope)) if items.last_item_live() => {
+                    live_event_frame(frames, envelope)
+                }
+                (_, item) => stream_item_to_sse(item, wrap, &mut checksum),
+            };
+            if tx.send(Ok(frame)).await.is_err() {
+                break;
+            }
         }
-        unsub();
-        decrement_subscriber_count(&sub_counts, &task_id_clone).await;
+        drop(items);
+        drop(frames);
+        decrement_subscriber_count(&sub_counts, &task_id).await;
     });
 
     let stream = ReceiverStream::new(rx);
-    Ok(Sse::new(stream))
+    Ok((warnings, heartbeat.respond(stream)))
 }
 
 // ─── Global SSE Query Parameters ────────────────────────────────────────────
 
-#[derive(Debug, Deserialize, utoipa::IntoParams)]
-pub struct GlobalSseQuery {
-    pub types: Option<String>,
-    pub levels: Option<String>,
-}
+/// Parameters the global SSE endpoint does not support.
+const GLOBAL_SSE_UNSUPPORTED: &[&str] = &[
+    "since.id",
+    "since.index",
+    "since.timestamp",
+    "cursor",
+    "limit",
+    "order",
+    "tail",
+    "excludeTypes",
+    "includeStatus",
+    "wrap",
+    "fields",
+    "preset",
+];
 
 // ─── Global SSE Handler ─────────────────────────────────────────────────────
 
@@ -370,9 +602,10 @@ pub struct GlobalSseQuery {
     summary = "Subscribe to events from all tasks via SSE",
     description = "Global SSE stream. Streams events from all tasks created after the connection is established. Runs indefinitely until client disconnects.",
     security(("Bearer" = [])),
-    params(GlobalSseQuery),
+    params(QueryOptions),
     responses(
         (status = 200, description = "SSE event stream (text/event-stream)"),
+        (status = 400, description = "Invalid query parameter"),
         (status = 403, description = "Forbidden"),
         (status = 501, description = "Global SSE not supported with this broadcast provider"),
     )
@@ -380,26 +613,19 @@ pub struct GlobalSseQuery {
 pub async fn global_sse_events(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
-    Query(query): Query<GlobalSseQuery>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
-    if !check_scope(
-        &auth,
-        taskcast_core::PermissionScope::EventSubscribe,
-        None,
-    ) {
-        return Err(AppError::Forbidden);
+    Extension(heartbeat): Extension<SseHeartbeat>,
+    options: QueryOptions,
+) -> Result<Response, AppError> {
+    if !check_scope(&auth, taskcast_core::PermissionScope::EventSubscribe, None) {
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::EventSubscribe,
+        ));
     }
 
-    let types: Option<Vec<String>> = query
-        .types
-        .as_ref()
-        .map(|t| t.split(',').filter(|s| !s.is_empty()).map(String::from).collect());
-    let levels: Option<Vec<Level>> = query.levels.as_ref().map(|l| {
-        l.split(',')
-            .filter(|s| !s.is_empty())
-            .filter_map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())).ok())
-            .collect()
-    });
+    options.reject(GLOBAL_SSE_UNSUPPORTED)?;
+    let types = options.types.clone();
+    let levels = options.effective_levels();
+    let label_selector = options.labels.clone();
 
     // Probe whether the broadcast provider supports subscribe_sync.
     // If it doesn't, return 501 immediately instead of panicking later
@@ -426,6 +652,7 @@ pub async fn global_sse_events(
     let tx_for_listener = tx.clone();
     let types_for_listener = types;
     let levels_for_listener = levels;
+    let labels_for_listener = label_selector;
     let engine_for_listener = Arc::clone(&engine);
     let unsubs_for_listener = Arc::clone(&unsubscribes);
 
@@ -439,6 +666,7 @@ pub async fn global_sse_events(
         let tx_for_sub = tx_for_listener.clone();
         let types_for_sub = types_for_listener.clone();
         let levels_for_sub = levels_for_listener.clone();
+        let labels_for_sub = labels_for_listener.clone();
 
         let unsub = match engine_for_listener.subscribe_sync(
             &task.id,
@@ -457,6 +685,13 @@ pub async fn global_sse_events(
                     }
                 }
 
+                // Apply label selector
+                if let Some(ref selector) = labels_for_sub {
+                    if !matches_labels(event.labels.as_ref(), selector) {
+                        return;
+                    }
+                }
+
                 let envelope = to_envelope(&event, 0);
                 let payload = serde_json::to_value(envelope).unwrap();
                 let sse_event = Event::default()
@@ -476,7 +711,8 @@ pub async fn global_sse_events(
     engine.add_creation_listener(creation_listener.clone());
 
     // Spawn a cleanup task that waits for client disconnect
-    tokio::spawn(async move {
+    let background = engine.background().clone();
+    background.spawn("sse.global_cleanup", None, async move {
         // Wait for client disconnect (rx is dropped when the SSE stream ends)
         tx.closed().await;
 
@@ -492,7 +728,7 @@ pub async fn global_sse_events(
     });
 
     let stream = ReceiverStream::new(rx);
-    Ok(Sse::new(stream))
+    Ok(heartbeat.respond(stream))
 }
 
 // ─── Unit Tests ──────────────────────────────────────────────────────────────
@@ -501,251 +737,82 @@ pub async fn global_sse_events(
 mod tests {
     use super::*;
     use serde_json::json;
-    use taskcast_core::{Level, SeriesFormat, SeriesMode};
-
-    // ── parse_filter: seriesFormat ──────────────────────────────────────────
-
-    #[test]
-    fn parse_filter_series_format_delta() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: Some("delta".to_string()),
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.series_format, Some(SeriesFormat::Delta));
-    }
+    use taskcast_core::{Level, SeriesFormat, SeriesMode, TaskEvent};
 
-    #[test]
-    fn parse_filter_series_format_accumulated() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: Some("accumulated".to_string()),
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.series_format, Some(SeriesFormat::Accumulated));
-    }
-
-    #[test]
-    fn parse_filter_series_format_invalid_returns_none() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: Some("bogus".to_string()),
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.series_format, None);
-    }
-
-    #[test]
-    fn parse_filter_series_format_none() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.series_format, None);
-    }
-
-    // ── to_envelope ─────────────────────────────────────────────────────────
-
-    // ── parse_filter: since cursor ──────────────────────────────────────
+    // ── SseHeartbeat ────────────────────────────────────────────────────────
 
     #[test]
-    fn parse_filter_since_id_constructs_cursor() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: Some("evt_123".to_string()),
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
+    fn heartbeat_defaults_to_fifteen_seconds_and_zero_turns_it_off() {
+        assert_eq!(
+            SseHeartbeat::from_config(None).interval,
+            Some(Duration::from_millis(DEFAULT_HEARTBEAT_INTERVAL_MS))
+        );
+        let off = SseConfig {
+            heartbeat_interval_ms: Some(0),
+            ..Default::default()
         };
-        let filter = parse_filter(&query);
-        let since = filter.since.unwrap();
-        assert_eq!(since.id, Some("evt_123".to_string()));
-        assert!(since.index.is_none());
-        assert!(since.timestamp.is_none());
+        assert_eq!(SseHeartbeat::from_config(Some(&off)).interval, None);
     }
 
-    #[test]
-    fn parse_filter_since_timestamp_constructs_cursor() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: Some("1700000000000".to_string()),
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        let since = filter.since.unwrap();
-        assert!(since.id.is_none());
-        assert_eq!(since.timestamp, Some(1700000000000.0));
-    }
+    // ── parse_filter: seriesFormat ──────────────────────────────────────────
 
-    #[test]
-    fn parse_filter_since_index_only_constructs_cursor_with_index() {
+    fn series_filter(series_format: Option<&str>) -> SubscribeFilter {
         let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: Some("42".to_string()),
-            since_timestamp: None,
-            limit: None,
+            series_format: series_format.map(String::from),
+            ..Default::default()
         };
-        let filter = parse_filter(&query);
-        let since = filter.since.unwrap();
-        assert!(since.id.is_none());
-        assert_eq!(since.index, Some(42));
-        assert!(since.timestamp.is_none());
+        parse_filter(&QueryOptions::default(), &query)
     }
 
     #[test]
-    fn parse_filter_no_since_returns_none() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert!(filter.since.is_none());
+    fn parse_filter_series_format_delta() {
+        assert_eq!(
+            series_filter(Some("delta")).series_format,
+            Some(SeriesFormat::Delta)
+        );
     }
 
     #[test]
-    fn parse_filter_since_id_and_timestamp_combined() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: Some("evt_abc".to_string()),
-            since_index: Some("5".to_string()),
-            since_timestamp: Some("999".to_string()),
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        let since = filter.since.unwrap();
-        assert_eq!(since.id, Some("evt_abc".to_string()));
-        assert_eq!(since.index, Some(5));
-        assert_eq!(since.timestamp, Some(999.0));
+    fn parse_filter_series_format_accumulated() {
+        assert_eq!(
+            series_filter(Some("accumulated")).series_format,
+            Some(SeriesFormat::Accumulated)
+        );
     }
 
-    // ── parse_filter: types & levels ────────────────────────────────────
-
     #[test]
-    fn parse_filter_types_parsed_from_csv() {
-        let query = SseQuery {
-            types: Some("llm.chunk,progress".to_string()),
-            levels: None,
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
+    fn parse_filter_series_format_both() {
         assert_eq!(
-            filter.types,
-            Some(vec!["llm.chunk".to_string(), "progress".to_string()])
+            series_filter(Some("both")).series_format,
+            Some(SeriesFormat::Both)
         );
     }
 
     #[test]
-    fn parse_filter_levels_parsed_from_csv() {
-        let query = SseQuery {
-            types: None,
-            levels: Some("info,warn".to_string()),
-            include_status: None,
-            wrap: None,
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.levels, Some(vec![Level::Info, Level::Warn]));
+    fn parse_filter_series_format_invalid_returns_none() {
+        assert_eq!(series_filter(Some("bogus")).series_format, None);
     }
 
     #[test]
-    fn parse_filter_include_status_and_wrap() {
-        let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: Some("false".to_string()),
-            wrap: Some("false".to_string()),
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
-        };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.include_status, Some(false));
-        assert_eq!(filter.wrap, Some(false));
+    fn parse_filter_series_format_none() {
+        assert_eq!(series_filter(None).series_format, None);
     }
 
     #[test]
-    fn parse_filter_include_status_true_when_not_false() {
+    fn parse_filter_keeps_the_query_options() {
+        let options = QueryOptions {
+            types: Some(vec!["llm.*".to_string()]),
+            wrap: Some(false),
+            ..Default::default()
+        };
         let query = SseQuery {
-            types: None,
-            levels: None,
-            include_status: Some("true".to_string()),
-            wrap: Some("yes".to_string()),
-            series_format: None,
-            since_id: None,
-            since_index: None,
-            since_timestamp: None,
-            limit: None,
+            materialize_series: Some("true".to_string()),
+            ..Default::default()
         };
-        let filter = parse_filter(&query);
-        assert_eq!(filter.include_status, Some(true));
-        assert_eq!(filter.wrap, Some(true));
+        let filter = parse_filter(&options, &query);
+        assert_eq!(filter.types, Some(vec!["llm.*".to_string()]));
+        assert_eq!(filter.wrap, Some(false));
+        assert_eq!(filter.materialize_series, Some(true));
     }
 
     // ── to_envelope ─────────────────────────────────────────────────────────
@@ -764,6 +831,8 @@ mod tests {
             series_mode: Some(SeriesMode::Accumulate),
             series_acc_field: Some("text".to_string()),
             series_snapshot: Some(true),
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let envelope = to_envelope(&event, 3);
@@ -791,6 +860,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let envelope = to_envelope(&event, 0);
@@ -813,6 +884,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let envelope = to_envelope(&event, 10);
@@ -840,6 +913,8 @@ mod tests {
             series_mode: Some(SeriesMode::Accumulate),
             series_acc_field: None,
             series_snapshot: Some(true),
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let envelope = to_envelope(&event, 0);
@@ -861,6 +936,8 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         };
         let envelope = to_envelope(&event, 0);
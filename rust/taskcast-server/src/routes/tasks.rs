@@ -1,23 +1,40 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::extract::rejection::JsonRejection;
+use axum::body::Body;
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
 use axum::response::IntoResponse;
 use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use taskcast_core::{
-    AssignMode, BlockedRequest, CleanupConfig, CreateTaskInput, DisconnectPolicy, EngineError,
-    EventQueryOptions, Level, PermissionScope, PublishEventInput, SeriesMode, SinceCursor,
-    TaskArchive, TaskArchiveImportOptions, TaskAuthConfig, TaskEngine, TaskError, TaskFilter,
-    TaskStatus, TransitionPayload, WebhookConfig,
+    envelopes_in_history, history_checksum, matches_filter, resolve_filter,
+    AssignMode, BlockedRequest, CleanupConfig, CreateTaskGroupInput, CreateTaskInput, DeleteEventsInput, DisconnectPolicy, EngineError,
+    CompletionPolicy, EventQueryOptions, ForwardRule, Level, PermissionScope, PublishEventInput, is_terminal, RetryPolicy,
+    SeriesFormat, SeriesMode, SinceCursor, StorageManager, SubscribeFilter, TaskArchive, TaskArchiveImportOptions,
+    TaskAuthConfig,
+    RequestDeadline, TaskCursor, TaskEngine, TaskError, TaskFilter, TaskOrigin, TaskOriginKind, TaskStatus, TaskValidationOptions, TransitionPayload,
+    Violation, WebhookConfig, WebhookGroupPolicy, validate_create_task_input, validate_ttl,
 };
+use taskcast_core::config::{HttpConfig, TaskGroupsConfig};
 
-use crate::auth::{check_scope, AuthContext};
+use crate::auth::{check_scope, AuthContext, AuthMode};
 use crate::error::AppError;
-use crate::routes::sse::{get_subscriber_count, SubscriberCounts};
+use crate::export::{export_ndjson, ExportLimits};
+use crate::ingest::{ingest_ndjson, IngestLimits, NDJSON_CONTENT_TYPE};
+use crate::query::QueryOptions;
+use crate::routes::sse::{
+    get_subscriber_count, parse_filter, resolve_query_filter, SseQuery, SubscriberCounts,
+    SubscriberGuard,
+};
+use crate::strict::{BodyStrictness, StrictJson};
+use crate::templates::{apply_template, TemplateRegistry};
+use crate::timestamps::{self, TimestampCheck, TimestampWarnings};
+use crate::versioning::ApiVersion;
+use crate::webhook::{is_sync, SyncWebhookResult, SyncWebhooks};
 
 // ─── Request Bodies ──────────────────────────────────────────────────────────
 
@@ -36,6 +53,43 @@ pub struct CreateTaskBody {
     pub assign_mode: Option<AssignMode>,
     pub cost: Option<u32>,
     pub disconnect_policy: Option<DisconnectPolicy>,
+    pub filters: Option<HashMap<String, SubscribeFilter>>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub group_policy: Option<WebhookGroupPolicy>,
+    pub forward_to: Option<ForwardRule>,
+    /// Epoch milliseconds or an RFC 3339 string: when the task becomes
+    /// `pending`; until then it is `scheduled`.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub scheduled_for: Option<f64>,
+    /// Milliseconds the task is allotted, for deadline warnings. Unlike
+    /// `ttl` it never times the task out.
+    pub deadline_ms: Option<u64>,
+    /// Creates the task as a child of this task, which must not have ended.
+    pub parent_id: Option<String>,
+    /// Ends the task automatically when its children end.
+    pub completion_policy: Option<CompletionPolicy>,
+    /// Name of a registered template to merge under this body.
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTaskGroupBody {
+    pub parent: CreateTaskBody,
+    pub children: Vec<CreateTaskBody>,
+    /// Makes every child a child of the parent. Children the parent's
+    /// `completionPolicy.childIds` lists are linked either way.
+    #[serde(default)]
+    pub link_children: bool,
+}
+
+/// The tasks `POST /task-groups` created.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TaskGroupResponse {
+    #[schema(value_type = taskcast_core::Task)]
+    pub parent: serde_json::Value,
+    #[schema(value_type = Vec<taskcast_core::Task>)]
+    pub children: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
@@ -48,6 +102,15 @@ pub struct TransitionBody {
     pub ttl: Option<u64>,
     pub resume_after_ms: Option<f64>,
     pub blocked_request: Option<BlockedRequest>,
+    /// Replaces the task's filter presets; `{}` removes them.
+    pub filters: Option<HashMap<String, SubscribeFilter>>,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTaskBody {
+    /// Why the task was cancelled; becomes its error message.
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema)]
@@ -67,6 +130,12 @@ pub struct PublishEventBody {
     pub series_id: Option<String>,
     pub series_mode: Option<SeriesMode>,
     pub series_acc_field: Option<String>,
+    pub labels: Option<HashMap<String, String>>,
+    /// Hold the live broadcast for up to this many milliseconds so later
+    /// events of the same `accumulate` or `latest` series can replace it.
+    pub broadcast_debounce_ms: Option<u64>,
+    /// Last event of its series: broadcast anything held for it now.
+    pub series_end: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -92,25 +161,241 @@ pub struct ImportTaskArchiveResponse {
     pub overwritten: bool,
 }
 
+/// Series and checksum options of the history endpoint. The filter and
+/// pagination parameters are parsed by [`QueryOptions`].
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct HistoryQuery {
-    #[serde(rename = "since.index")]
-    pub since_index: Option<u64>,
-    #[serde(rename = "since.timestamp")]
-    pub since_timestamp: Option<f64>,
-    #[serde(rename = "since.id")]
-    pub since_id: Option<String>,
-    pub limit: Option<u64>,
-    #[serde(rename = "seriesFormat")]
+    #[serde(rename = "seriesFormat", alias = "seriesView")]
+    pub series_format: Option<String>,
+    /// Return each `json-patch` series as one snapshot event carrying its
+    /// current document, followed by any newer patches.
+    #[serde(rename = "materializeSeries")]
+    pub materialize_series: Option<bool>,
+    /// Return a checksum of the returned events in the
+    /// `X-Taskcast-Checksum` header.
+    pub checksum: Option<bool>,
+    /// Return only events with a timestamp at or before this one, in epoch
+    /// milliseconds or RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub until: Option<f64>,
+}
+
+/// Parameters the history endpoint does not support.
+const HISTORY_UNSUPPORTED: &[&str] = &["cursor", "order", "tail", "excludeTypes", "wrap", "fields"];
+
+/// Query parameters for `GET /tasks/{task_id}/archive`.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ArchiveQuery {
+    /// Add a `checksum` object covering the archive's events.
+    pub checksum: Option<bool>,
+}
+
+/// Query parameters for `POST /tasks/{task_id}/events`. With
+/// `includeEnvelope=true`, the filter parameters of [`QueryOptions`] mirror
+/// the SSE endpoint's.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct PublishQuery {
+    /// Return each event as the `SSEEnvelope` a subscriber with the given
+    /// filter receives. Costs one history read.
+    #[serde(rename = "includeEnvelope")]
+    pub include_envelope: Option<String>,
+    #[serde(rename = "seriesFormat", alias = "seriesView")]
     pub series_format: Option<String>,
 }
 
+/// Parameters `POST /tasks/{task_id}/events` does not support.
+const PUBLISH_UNSUPPORTED: &[&str] = &[
+    "since.id",
+    "since.index",
+    "since.timestamp",
+    "cursor",
+    "limit",
+    "order",
+    "tail",
+    "excludeTypes",
+    "wrap",
+    "fields",
+];
+
+/// Raw index of the (last) published event.
+pub const EVENT_INDEX_HEADER: &str = "X-Taskcast-Event-Index";
+/// Number of events published to the task, read after the append.
+pub const EVENT_COUNT_HEADER: &str = "X-Taskcast-Event-Count";
+/// `HistoryChecksum` of a returned history, as JSON.
+pub const CHECKSUM_HEADER: &str = "X-Taskcast-Checksum";
+
+/// The task version an `If-Match` header asserts, if any. The version may
+/// be bare or quoted like an ETag; `*` asserts nothing.
+fn if_match_version(headers: &HeaderMap) -> Result<Option<u64>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().unwrap_or_default().trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    value.trim_matches('"').parse().map(Some).map_err(|_| {
+        AppError::BadRequest(format!(
+            "Invalid If-Match header: {value:?}. Expected a task version."
+        ))
+    })
+}
+
+// ─── Wait Query ──────────────────────────────────────────────────────────────
+
+/// Default `timeoutMs` for `GET /tasks/{task_id}/wait`.
+pub const DEFAULT_WAIT_TIMEOUT_MS: u64 = 30_000;
+/// Default server-side cap on `timeoutMs`, overridable via `longPoll.maxTimeoutMs`.
+pub const DEFAULT_MAX_WAIT_TIMEOUT_MS: u64 = 120_000;
+
+/// Query parameters for `GET /tasks/{task_id}`.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct GetTaskQuery {
+    /// Return the task as it was at this moment, in epoch milliseconds or
+    /// RFC 3339. Requires the `event:history` scope.
+    #[serde(rename = "asOf", default, deserialize_with = "timestamps::deserialize")]
+    pub as_of: Option<f64>,
+}
+
+/// Query parameters for `PATCH /tasks/{task_id}/status`.
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct TransitionQuery {
+    /// Validate the transition and return the task it would produce, or the
+    /// error it would fail with, without saving or publishing anything.
+    #[serde(rename = "dryRun")]
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct WaitQuery {
+    /// How long to hold the request before answering with the current task.
+    #[serde(rename = "timeoutMs")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Server-side limits for the wait endpoint, passed via Axum Extension.
+#[derive(Debug, Clone, Copy)]
+pub struct WaitLimits {
+    pub max_timeout_ms: u64,
+}
+
+/// Default for `taskGroups.maxChildren`.
+pub const DEFAULT_TASK_GROUP_MAX_CHILDREN: usize = 100;
+
+/// Server-side limits for `POST /task-groups`, passed via Axum Extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskGroupLimits {
+    pub max_children: usize,
+}
+
+impl TaskGroupLimits {
+    pub fn from_config(config: Option<&TaskGroupsConfig>) -> Self {
+        Self {
+            max_children: config
+                .and_then(|c| c.max_children)
+                .unwrap_or(DEFAULT_TASK_GROUP_MAX_CHILDREN),
+        }
+    }
+}
+
+/// The checks `POST /tasks` and `PATCH /tasks/:taskId/status` apply, from
+/// the `http` config section. Without auth, the local-development setup,
+/// `http://` webhooks are accepted unless the section says otherwise.
+pub fn task_validation_options(
+    http: Option<&HttpConfig>,
+    auth_mode: &AuthMode,
+) -> TaskValidationOptions {
+    let defaults = TaskValidationOptions {
+        allow_insecure_webhooks: http
+            .and_then(|h| h.allow_insecure_webhooks)
+            .unwrap_or(matches!(auth_mode, AuthMode::None)),
+        ..Default::default()
+    };
+    // `parse_config` rejects a pattern that does not compile.
+    match http.and_then(|h| h.task_id_pattern.as_deref()) {
+        Some(pattern) => defaults.clone().with_id_pattern(pattern).unwrap_or(defaults),
+        None => defaults,
+    }
+}
+
 // ─── List Query ──────────────────────────────────────────────────────────────
 
+/// Largest accepted `limit` on `GET /tasks`.
+const MAX_LIST_LIMIT: usize = 1000;
+
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
 pub struct ListTasksQuery {
     pub status: Option<String>,
+    /// A task type or type pattern, e.g. `crawl.*`.
     pub r#type: Option<String>,
+    /// Comma-separated origin kinds, e.g. `retry,schedule`.
+    pub origin: Option<String>,
+    /// Only tasks created at or after this time, in epoch milliseconds or
+    /// RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_after: Option<f64>,
+    /// Only tasks created before this time, in epoch milliseconds or
+    /// RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_before: Option<f64>,
+    /// Page size, 1 to 1000. Without it, every match is returned.
+    pub limit: Option<String>,
+    /// `nextCursor` from a previous response.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTasksQuery {
+    pub status: Option<String>,
+    pub r#type: Option<String>,
+    /// Comma-separated origin kinds, e.g. `retry,schedule`.
+    pub origin: Option<String>,
+    /// Only tasks created at or after this time, in epoch milliseconds or
+    /// RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_after: Option<f64>,
+    /// Only tasks created before this time, in epoch milliseconds or
+    /// RFC 3339.
+    #[serde(default, deserialize_with = "timestamps::deserialize")]
+    pub created_before: Option<f64>,
+    /// Output format; only `ndjson` is supported.
+    pub format: Option<String>,
+}
+
+/// The list filter for comma-separated `status` and `origin` and a single
+/// `type`. Unknown statuses and origin kinds are ignored.
+fn task_filter(status: Option<&str>, task_type: Option<&str>, origin: Option<&str>) -> TaskFilter {
+    let mut filter = TaskFilter::default();
+
+    if let Some(status_str) = status {
+        let statuses: Vec<TaskStatus> = status_str
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())).ok())
+            .collect();
+        if !statuses.is_empty() {
+            filter.status = Some(statuses);
+        }
+    }
+
+    if let Some(type_str) = task_type {
+        filter.types = Some(vec![type_str.to_string()]);
+    }
+
+    if let Some(origin_str) = origin {
+        let kinds: Vec<TaskOriginKind> = origin_str
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())).ok())
+            .collect();
+        if !kinds.is_empty() {
+            filter.origin = Some(kinds);
+        }
+    }
+
+    filter
 }
 
 // ─── Handlers ────────────────────────────────────────────────────────────────
@@ -120,46 +405,75 @@ pub struct ListTasksQuery {
     path = "/tasks",
     tag = "Tasks",
     summary = "List tasks",
-    description = "List tasks with optional status and type filters.",
+    description = "List tasks with optional status, type, origin and creation time filters, oldest first. With `limit`, one page at a time: pass `nextCursor` back as `cursor` for the next one.",
     security(("Bearer" = [])),
     params(ListTasksQuery),
     responses(
         (status = 200, description = "Task list"),
-        (status = 403, description = "Forbidden"),
+        (status = 400, description = "Invalid query parameter"),
+        (status = 403, description = "Missing the `task:read` scope"),
+        (status = 504, description = "The request deadline passed"),
     )
 )]
 pub async fn list_tasks(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
     Extension(subscriber_counts): Extension<SubscriberCounts>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
     Query(query): Query<ListTasksQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    if !check_scope(&auth, taskcast_core::PermissionScope::EventSubscribe, None) {
-        return Err(AppError::Forbidden);
-    }
-
-    let mut filter = TaskFilter::default();
-
-    if let Some(ref status_str) = query.status {
-        let statuses: Vec<TaskStatus> = status_str
-            .split(',')
-            .filter(|s| !s.is_empty())
-            .filter_map(|s| serde_json::from_value(serde_json::Value::String(s.to_string())).ok())
-            .collect();
-        if !statuses.is_empty() {
-            filter.status = Some(statuses);
-        }
-    }
-
-    if let Some(ref type_str) = query.r#type {
-        filter.types = Some(vec![type_str.clone()]);
+    if !check_scope(&auth, PermissionScope::TaskRead, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskRead));
     }
+    let warnings = timestamp_check.query(&[
+        ("createdAfter", query.created_after),
+        ("createdBefore", query.created_before),
+    ])?;
+    let limit = query
+        .limit
+        .as_deref()
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|limit| (1..=MAX_LIST_LIMIT).contains(limit))
+                .ok_or_else(|| AppError::InvalidQuery {
+                    param: "limit".to_string(),
+                    reason: format!("expected 1 to {MAX_LIST_LIMIT}, got \"{value}\""),
+                })
+        })
+        .transpose()?;
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|value| {
+            value
+                .parse::<TaskCursor>()
+                .map_err(|reason| AppError::InvalidQuery {
+                    param: "cursor".to_string(),
+                    reason,
+                })
+        })
+        .transpose()?;
 
-    let tasks = engine.list_tasks(filter).await?;
-    let mut enriched = Vec::with_capacity(tasks.len());
-    for task in &tasks {
+    let TaskFilter { status, origin, .. } =
+        task_filter(query.status.as_deref(), None, query.origin.as_deref());
+    let page = engine
+        .query_tasks(&taskcast_core::ListTasksQuery {
+            status,
+            r#type: query.r#type,
+            origin,
+            created_after: query.created_after,
+            created_before: query.created_before,
+            cursor,
+            limit,
+        })
+        .await?;
+    let mut enriched = Vec::with_capacity(page.tasks.len());
+    for task in &page.tasks {
         let subscriber_count = get_subscriber_count(&subscriber_counts, &task.id).await;
-        let mut task_json = serde_json::to_value(task).unwrap();
+        let mut task_json = api.presenter.task(task);
         if let Some(obj) = task_json.as_object_mut() {
             obj.insert("hot".to_string(), json!(subscriber_count > 0));
             obj.insert("subscriberCount".to_string(), json!(subscriber_count));
@@ -167,7 +481,76 @@ pub async fn list_tasks(
         enriched.push(task_json);
     }
 
-    Ok(axum::Json(json!({ "tasks": enriched })))
+    let next_cursor = page.next.map(|next| next.to_string());
+    Ok((
+        warnings,
+        axum::Json(api.presenter.task_list(enriched, next_cursor)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/export",
+    tag = "Tasks",
+    summary = "Export tasks as NDJSON",
+    description = "Streams every task matching the list filters as one NDJSON line, oldest first, reading the store a chunk at a time. Tasks the token may not access are left out. The last line is {kind: \"summary\", count, truncated}; truncated is true when the configured row cap stopped the export early. A store failure ends the stream with a {kind: \"error\", error} line instead.",
+    security(("Bearer" = [])),
+    params(ExportTasksQuery),
+    responses(
+        (status = 200, description = "NDJSON tasks followed by a summary line", content_type = "application/x-ndjson"),
+        (status = 400, description = "Unsupported format"),
+        (status = 403, description = "Missing the `task:read` scope"),
+        (status = 504, description = "The request deadline passed before the first chunk was read"),
+    )
+)]
+pub async fn export_tasks(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(limits): Extension<ExportLimits>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
+    Query(query): Query<ExportTasksQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskRead, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskRead));
+    }
+    let warnings = timestamp_check.query(&[
+        ("createdAfter", query.created_after),
+        ("createdBefore", query.created_before),
+    ])?;
+    if let Some(format) = query.format.as_deref().filter(|f| *f != "ndjson") {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported export format: {format}"
+        )));
+    }
+
+    let mut filter = task_filter(
+        query.status.as_deref(),
+        query.r#type.as_deref(),
+        query.origin.as_deref(),
+    );
+    filter.created_before = query.created_before;
+    // Every id sorts after the empty one, so this starts at `createdAfter`.
+    let after = query.created_after.map(|created_at| TaskCursor {
+        created_at,
+        id: String::new(),
+    });
+
+    let lines = export_ndjson(
+        engine,
+        api.presenter,
+        filter,
+        after,
+        auth.task_ids,
+        limits,
+        RequestDeadline::current(),
+    )
+    .await?;
+    Ok((
+        warnings,
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        lines,
+    ))
 }
 
 #[utoipa::path(
@@ -179,18 +562,71 @@ pub async fn list_tasks(
     request_body = CreateTaskBody,
     responses(
         (status = 201, description = "Task created", body = taskcast_core::Task),
-        (status = 400, description = "Validation error"),
+        (status = 400, description = "Validation error or unknown template"),
         (status = 403, description = "Forbidden"),
+        (status = 409, description = "A task with this id already exists"),
     )
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_task(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
-    axum::Json(body): axum::Json<CreateTaskBody>,
+    Extension(templates): Extension<Arc<TemplateRegistry>>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    Extension(validation): Extension<Arc<TaskValidationOptions>>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
+    StrictJson(body): StrictJson<CreateTaskBody>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, taskcast_core::PermissionScope::TaskCreate, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::TaskCreate,
+        ));
+    }
+
+    // Linking a child changes its parent, so the token must reach the parent.
+    if let Some(ref parent_id) = body.parent_id {
+        if !check_scope(&auth, PermissionScope::TaskCreate, Some(parent_id)) {
+            return Err(AppError::MissingScope(PermissionScope::TaskCreate));
+        }
+    }
+    let (input, warnings) = create_task_input(
+        body,
+        "",
+        &auth,
+        &templates,
+        &sync_webhooks,
+        &validation,
+        &timestamp_check,
+    )?;
+
+    let task = engine.create_task(input).await?;
+    Ok((
+        StatusCode::CREATED,
+        warnings,
+        axum::Json(api.presenter.task(&task)),
+    ))
+}
+
+/// The engine input for one create body: its template applied, then
+/// checked. Violations and warnings point below `pointer`.
+fn create_task_input(
+    mut body: CreateTaskBody,
+    pointer: &str,
+    auth: &AuthContext,
+    templates: &TemplateRegistry,
+    sync_webhooks: &SyncWebhooks,
+    validation: &TaskValidationOptions,
+    timestamp_check: &TimestampCheck,
+) -> Result<(CreateTaskInput, TimestampWarnings), AppError> {
+    if let Some(name) = body.template.take() {
+        let template = templates
+            .get(&name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown template: {name}")))?;
+        apply_template(&mut body, template);
     }
+    let warnings =
+        timestamp_check.body(&[(&format!("{pointer}/scheduledFor"), body.scheduled_for)])?;
 
     let input = CreateTaskInput {
         id: body.id,
@@ -205,10 +641,118 @@ pub async fn create_task(
         assign_mode: body.assign_mode,
         cost: body.cost,
         disconnect_policy: body.disconnect_policy,
+        filters: body.filters,
+        retry_policy: body.retry_policy,
+        group_policy: body.group_policy,
+        forward_to: body.forward_to,
+        scheduled_for: body.scheduled_for,
+        deadline_ms: body.deadline_ms,
+        origin: Some(TaskOrigin {
+            kind: TaskOriginKind::User,
+            source_task_id: None,
+            detail: auth.sub.clone(),
+        }),
+        parent_id: body.parent_id,
+        completion_policy: body.completion_policy,
     };
+    validate_create_task_input(&input, validation).map_err(|violations| {
+        AppError::Validation(
+            violations
+                .into_iter()
+                .map(|v| Violation::new(format!("{pointer}{}", v.pointer), v.message))
+                .collect(),
+        )
+    })?;
+    if let Some(ref webhooks) = input.webhooks {
+        sync_webhooks
+            .check_limit(webhooks)
+            .map_err(AppError::BadRequest)?;
+    }
+    Ok((input, warnings))
+}
 
-    let task = engine.create_task(input).await?;
-    Ok((StatusCode::CREATED, axum::Json(task)))
+#[utoipa::path(
+    post,
+    path = "/task-groups",
+    tag = "Tasks",
+    summary = "Create a task group",
+    description = "Create a parent task and its children, all or none. Fails with `500 PARTIAL_TASK_GROUP` only when a failed write could not be undone; its `taskIds` may exist.",
+    security(("Bearer" = [])),
+    request_body = CreateTaskGroupBody,
+    responses(
+        (status = 201, description = "Task group created", body = TaskGroupResponse),
+        (status = 400, description = "Validation error, unknown template or too many children"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "A task with one of these ids already exists"),
+        (status = 500, description = "Creation failed and some tasks may remain"),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_task_group(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(templates): Extension<Arc<TemplateRegistry>>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    Extension(validation): Extension<Arc<TaskValidationOptions>>,
+    Extension(limits): Extension<TaskGroupLimits>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
+    StrictJson(body): StrictJson<CreateTaskGroupBody>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskCreate, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskCreate));
+    }
+    if body.children.len() > limits.max_children {
+        return Err(AppError::BadRequest(format!(
+            "Task group has {} children, more than the limit of {}",
+            body.children.len(),
+            limits.max_children
+        )));
+    }
+
+    let (parent, mut warnings) = create_task_input(
+        body.parent,
+        "/parent",
+        &auth,
+        &templates,
+        &sync_webhooks,
+        &validation,
+        &timestamp_check,
+    )?;
+    let mut children = Vec::with_capacity(body.children.len());
+    for (index, child) in body.children.into_iter().enumerate() {
+        let (child, child_warnings) = create_task_input(
+            child,
+            &format!("/children/{index}"),
+            &auth,
+            &templates,
+            &sync_webhooks,
+            &validation,
+            &timestamp_check,
+        )?;
+        warnings.0.extend(child_warnings.0);
+        children.push(child);
+    }
+
+    let group = engine
+        .create_task_group(CreateTaskGroupInput {
+            parent,
+            children,
+            link_children: body.link_children,
+        })
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        warnings,
+        axum::Json(TaskGroupResponse {
+            parent: api.presenter.task(&group.parent),
+            children: group
+                .children
+                .iter()
+                .map(|task| api.presenter.task(task))
+                .collect(),
+        }),
+    ))
 }
 
 #[utoipa::path(
@@ -218,7 +762,7 @@ pub async fn create_task(
     summary = "Export task archive",
     description = "Export a portable single-task archive with task metadata and raw event history.",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID")),
+    params(("task_id" = String, Path, description = "Task ID"), ArchiveQuery),
     responses(
         (status = 200, description = "Task archive", body = taskcast_core::TaskArchive),
         (status = 404, description = "Task not found"),
@@ -229,20 +773,123 @@ pub async fn export_task_archive(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
     Path(task_id): Path<String>,
+    Query(query): Query<ArchiveQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
     }
 
-    let archive = engine
-        .export_task_archive(&task_id)
+    let archive = engine.export_task_archive(&task_id).await?;
+
+    let mut body = serde_json::to_value(&archive).unwrap();
+    if query.checksum == Some(true) {
+        body["checksum"] = serde_json::to_value(history_checksum(&archive.events)).unwrap();
+    }
+    Ok(axum::Json(body))
+}
+
+/// Storage directory that saved task archives are written to.
+pub const EXPORTS_DIRECTORY: &str = "exports";
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/archive",
+    tag = "Tasks",
+    summary = "Save task archive",
+    description = "Write the task's archive to the server's exports directory. Only available when disk storage is configured.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    responses(
+        (status = 201, description = "Archive saved"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+        (status = 507, description = "Exports directory is at its size cap"),
+    )
+)]
+pub async fn save_task_archive(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(storage): Extension<Arc<StorageManager>>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    let archive = engine.export_task_archive(&task_id).await?;
+    let contents = serde_json::to_vec(&archive).unwrap();
+    let bytes = contents.len();
+    let file = format!("{task_id}.{}.json", ulid::Ulid::new());
+    let file_name = file.clone();
+    tokio::task::spawn_blocking(move || storage.write(EXPORTS_DIRECTORY, &file_name, &contents))
         .await
-        .map_err(|e| match &e {
-            EngineError::TaskNotFound(_) => AppError::NotFound(e.to_string()),
-            _ => AppError::Engine(e),
-        })?;
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+
+    Ok((
+        StatusCode::CREATED,
+        axum::Json(json!({ "file": file, "bytes": bytes })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/integrity",
+    tag = "Tasks",
+    summary = "Get task integrity",
+    description = "Number of distinct corrupt task or event records the stores have encountered for this task since the server started.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Corrupt record count"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn get_task_integrity(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
 
-    Ok(axum::Json(archive))
+    let corrupt_records = engine.corrupt_records(&task_id);
+
+    Ok(axum::Json(json!({
+        "taskId": task_id,
+        "corruptRecords": corrupt_records,
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/attempts",
+    tag = "Tasks",
+    summary = "Get task attempts",
+    description = "The retry chain the task belongs to, oldest attempt first, and the retry waiting to run, if any.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Attempts and pending retry"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn get_task_attempts(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    let attempts = engine
+        .task_attempts(&task_id)
+        .await?
+        .ok_or(EngineError::TaskNotFound(task_id))?;
+
+    Ok(axum::Json(attempts))
 }
 
 #[utoipa::path(
@@ -266,7 +913,7 @@ pub async fn import_task_archive(
     body: Result<Json<ImportTaskArchiveBody>, JsonRejection>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::TaskManage, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
     }
 
     let Json(body) = body.map_err(|rejection| AppError::BadRequest(rejection.to_string()))?;
@@ -294,70 +941,179 @@ pub async fn import_task_archive(
     path = "/tasks/{task_id}",
     tag = "Tasks",
     summary = "Get task by ID",
+    description = "With `asOf`, the task's status, result and error as they were at that \
+        moment, rebuilt from its status events. Other fields are current values.",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID")),
+    params(("task_id" = String, Path, description = "Task ID"), GetTaskQuery),
     responses(
         (status = 200, description = "Task details", body = taskcast_core::Task),
+        (status = 400, description = "`asOf` is before the task was created or in the future"),
         (status = 404, description = "Task not found"),
-        (status = 403, description = "Forbidden"),
+        (status = 403, description = "Missing the `task:read` scope"),
     )
 )]
 pub async fn get_task(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
     Extension(subscriber_counts): Extension<SubscriberCounts>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
     Path(task_id): Path<String>,
+    Query(query): Query<GetTaskQuery>,
 ) -> Result<impl IntoResponse, AppError> {
-    if !check_scope(
-        &auth,
-        taskcast_core::PermissionScope::EventSubscribe,
-        Some(&task_id),
-    ) {
-        return Err(AppError::Forbidden);
+    let warnings = timestamp_check.query(&[("asOf", query.as_of)])?;
+    if let Some(as_of) = query.as_of {
+        if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+            return Err(AppError::MissingScope(PermissionScope::EventHistory));
+        }
+        let task = engine
+            .get_task_as_of(&task_id, as_of)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
+        let mut task_json = api.presenter.task(&task);
+        if let Some(obj) = task_json.as_object_mut() {
+            obj.insert("asOf".to_string(), json!(as_of));
+        }
+        return Ok((warnings, axum::Json(task_json)));
+    }
+
+    if !check_scope(&auth, PermissionScope::TaskRead, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::TaskRead));
     }
 
     let task = engine
         .get_task(&task_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Task not found".to_string()))?;
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
 
     let subscriber_count = get_subscriber_count(&subscriber_counts, &task_id).await;
-    let mut task_json = serde_json::to_value(&task).unwrap();
+    let mut task_json = api.presenter.task(&task);
     if let Some(obj) = task_json.as_object_mut() {
         obj.insert("hot".to_string(), json!(subscriber_count > 0));
         obj.insert("subscriberCount".to_string(), json!(subscriber_count));
     }
 
+    Ok((warnings, axum::Json(task_json)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/wait",
+    tag = "Tasks",
+    summary = "Wait for task completion",
+    description = "Long-poll until the task reaches a terminal status or `timeoutMs` elapses. \
+        Always answers 200 with the task plus `completed`, which is false on timeout.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID"), WaitQuery),
+    responses(
+        (status = 200, description = "Task with a `completed` flag", body = taskcast_core::Task),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Missing the `task:read` scope"),
+    )
+)]
+pub async fn wait_for_task(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(subscriber_counts): Extension<SubscriberCounts>,
+    Extension(limits): Extension<WaitLimits>,
+    api: ApiVersion,
+    Path(task_id): Path<String>,
+    Query(query): Query<WaitQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskRead, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::TaskRead));
+    }
+
+    let timeout_ms = query
+        .timeout_ms
+        .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+        .min(limits.max_timeout_ms);
+
+    let task = {
+        let _held =
+            SubscriberGuard::acquire(&subscriber_counts, &task_id, engine.background()).await;
+        engine
+            .wait_for_terminal(&task_id, Duration::from_millis(timeout_ms))
+            .await?
+    };
+
+    let mut task_json = api.presenter.task(&task);
+    if let Some(obj) = task_json.as_object_mut() {
+        obj.insert("completed".to_string(), json!(is_terminal(&task.status)));
+    }
     Ok(axum::Json(task_json))
 }
 
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/transitions",
+    tag = "Tasks",
+    summary = "Get allowed transitions",
+    description = "The statuses the task can move to from its current one, and whether it still accepts events.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Allowed transitions", body = taskcast_core::TaskTransitions),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Missing the `task:read` or `task:transition` scope"),
+    )
+)]
+pub async fn get_task_transitions(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskRead, Some(&task_id))
+        && !check_scope(&auth, PermissionScope::TaskTransition, Some(&task_id))
+    {
+        return Err(AppError::MissingScope(PermissionScope::TaskRead));
+    }
+
+    let transitions = engine
+        .task_transitions(&task_id)
+        .await?
+        .ok_or(EngineError::TaskNotFound(task_id))?;
+
+    Ok(axum::Json(transitions))
+}
+
 #[utoipa::path(
     patch,
     path = "/tasks/{task_id}/status",
     tag = "Tasks",
     summary = "Transition task status",
+    description = "With `dryRun=true`, answers as the real call would, with the would-be task or \
+        the same error, but saves, publishes and delivers nothing.",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID")),
+    params(("task_id" = String, Path, description = "Task ID"), TransitionQuery),
     request_body = TransitionBody,
     responses(
         (status = 200, description = "Updated task", body = taskcast_core::Task),
+        (status = 207, description = "Updated task; a sync webhook was not delivered"),
         (status = 400, description = "Invalid transition"),
         (status = 404, description = "Task not found"),
-        (status = 403, description = "Forbidden"),
+        (status = 403, description = "Missing the `task:transition` scope"),
+        (status = 409, description = "Task is not at the `If-Match` version"),
     )
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn transition_task(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    api: ApiVersion,
     Path(task_id): Path<String>,
-    axum::Json(body): axum::Json<TransitionBody>,
+    Query(query): Query<TransitionQuery>,
+    headers: HeaderMap,
+    StrictJson(body): StrictJson<TransitionBody>,
 ) -> Result<impl IntoResponse, AppError> {
-    if !check_scope(
-        &auth,
-        taskcast_core::PermissionScope::TaskManage,
-        Some(&task_id),
-    ) {
-        return Err(AppError::Forbidden);
+    if !check_scope(&auth, PermissionScope::TaskTransition, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::TaskTransition));
+    }
+    let expected_version = if_match_version(&headers)?;
+    // The TTL is the one field shared with `POST /tasks` a transition can change.
+    if let Some(ttl) = body.ttl {
+        validate_ttl(ttl, "/ttl").map_err(|v| AppError::Validation(vec![v]))?;
     }
 
     let payload = if body.result.is_some()
@@ -366,6 +1122,7 @@ pub async fn transition_task(
         || body.ttl.is_some()
         || body.resume_after_ms.is_some()
         || body.blocked_request.is_some()
+        || body.filters.is_some()
     {
         let error = body.error.map(|e| TaskError {
             code: e.code,
@@ -379,22 +1136,153 @@ pub async fn transition_task(
             ttl: body.ttl,
             resume_after_ms: body.resume_after_ms,
             blocked_request: body.blocked_request,
+            filters: body.filters,
         })
     } else {
         None
     };
 
+    if query.dry_run == Some(true) {
+        let task = engine
+            .preview_transition(&task_id, body.status, payload, expected_version)
+            .await
+            .map_err(transition_error)?;
+        return Ok((StatusCode::OK, axum::Json(api.presenter.task(&task))));
+    }
+
+    // Events the transition emits are indexed from here on.
+    let next_index = engine.event_count(&task_id).await?;
     let task = engine
-        .transition_task(&task_id, body.status, payload)
+        .transition_task_versioned(&task_id, body.status, payload, expected_version)
         .await
-        .map_err(|e| match &e {
-            EngineError::TaskNotFound(_) => AppError::NotFound(e.to_string()),
-            EngineError::InvalidTransition { .. } => AppError::Engine(e),
-            EngineError::TaskTerminal(_) => AppError::BadRequest(e.to_string()),
-            _ => AppError::Engine(e),
-        })?;
+        .map_err(transition_error)?;
+
+    let mut task_json = api.presenter.task(&task);
+    let status = deliver_transition_webhooks(
+        &engine,
+        &sync_webhooks,
+        &task,
+        next_index,
+        &mut task_json,
+    )
+    .await?;
 
-    Ok(axum::Json(task))
+    Ok((status, axum::Json(task_json)))
+}
+
+fn transition_error(e: EngineError) -> AppError {
+    match &e {
+        EngineError::InvalidTransition { .. } => AppError::Engine(e),
+        EngineError::TaskTerminal(_) => AppError::BadRequest(e.to_string()),
+        _ => AppError::Engine(e),
+    }
+}
+
+/// Delivers the `taskcast:` events a transition emitted from `next_index`
+/// on to `task`'s sync webhooks, adding their results to `task_json`.
+/// `200`, or `207` when one was not delivered.
+async fn deliver_transition_webhooks(
+    engine: &TaskEngine,
+    sync_webhooks: &SyncWebhooks,
+    task: &taskcast_core::Task,
+    next_index: u64,
+    task_json: &mut serde_json::Value,
+) -> Result<StatusCode, AppError> {
+    if !task.webhooks.iter().flatten().any(is_sync) {
+        return Ok(StatusCode::OK);
+    }
+    let since = next_index.checked_sub(1).map(|index| SinceCursor {
+        id: None,
+        index: Some(index),
+        timestamp: None,
+    });
+    let emitted: Vec<_> = engine
+        .get_events(
+            &task.id,
+            Some(EventQueryOptions {
+                since,
+                limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
+            }),
+        )
+        .await?
+        .into_iter()
+        .filter(|event| event.r#type.starts_with("taskcast:"))
+        .collect();
+    let Some(results) = sync_webhooks.deliver(task, &emitted).await else {
+        return Ok(StatusCode::OK);
+    };
+    let results: Vec<_> = results.into_iter().flatten().collect();
+    task_json["webhookResults"] = json!(results);
+    Ok(sync_status(&results, StatusCode::OK))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/cancel",
+    tag = "Tasks",
+    summary = "Cancel a task",
+    description = "Cancels a pending or running task. The reason, if any, becomes the task's \
+        error message under code `CANCELLED` and is carried by its `taskcast:status` event. \
+        The body may be omitted. With `If-Match`, the task must be at that version.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    request_body = CancelTaskBody,
+    responses(
+        (status = 200, description = "Cancelled task", body = taskcast_core::Task),
+        (status = 207, description = "Cancelled task; a sync webhook was not delivered"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Missing the `task:transition` scope"),
+        (status = 409, description = "Task has finished, is not pending or running, or is not at the `If-Match` version"),
+    )
+)]
+pub async fn cancel_task(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    api: ApiVersion,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+    body: Option<StrictJson<CancelTaskBody>>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::TaskTransition, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::TaskTransition));
+    }
+    let expected_version = if_match_version(&headers)?;
+    let body = body.map(|StrictJson(body)| body).unwrap_or_default();
+
+    // Events the cancellation emits are indexed from here on.
+    let next_index = engine.event_count(&task_id).await?;
+    let task = engine
+        .cancel_task_versioned(&task_id, body.reason, expected_version)
+        .await?;
+
+    let mut task_json = api.presenter.task(&task);
+    let status = deliver_transition_webhooks(
+        &engine,
+        &sync_webhooks,
+        &task,
+        next_index,
+        &mut task_json,
+    )
+    .await?;
+
+    Ok((status, axum::Json(task_json)))
+}
+
+/// `success`, or `207 Multi-Status` when a sync webhook was not delivered.
+/// The events stay published either way.
+fn sync_status<'a>(
+    results: impl IntoIterator<Item = &'a SyncWebhookResult>,
+    success: StatusCode,
+) -> StatusCode {
+    if results.into_iter().all(SyncWebhookResult::succeeded) {
+        success
+    } else {
+        StatusCode::MULTI_STATUS
+    }
 }
 
 #[utoipa::path(
@@ -402,20 +1290,27 @@ pub async fn transition_task(
     path = "/tasks/{task_id}/events",
     tag = "Events",
     summary = "Publish events to a task",
-    description = "Supports single event or batch (array) publishing.",
+    description = "Supports single event or batch (array) publishing. X-Taskcast-Event-Index carries the raw index of the last published event and X-Taskcast-Event-Count the task's event count after the append. With includeEnvelope=true, events are returned as SSE envelopes for the given filter.",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID")),
+    params(("task_id" = String, Path, description = "Task ID"), PublishQuery, QueryOptions),
     responses(
         (status = 201, description = "Events published"),
-        (status = 400, description = "Validation error"),
+        (status = 207, description = "Events published; a sync webhook was not delivered"),
+        (status = 400, description = "Validation error or invalid query parameter"),
         (status = 404, description = "Task not found"),
         (status = 403, description = "Forbidden"),
     )
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn publish_events(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    Extension(strictness): Extension<BodyStrictness>,
+    api: ApiVersion,
     Path(task_id): Path<String>,
+    options: QueryOptions,
+    Query(query): Query<PublishQuery>,
     axum::Json(body): axum::Json<serde_json::Value>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(
@@ -423,10 +1318,39 @@ pub async fn publish_events(
         taskcast_core::PermissionScope::EventPublish,
         Some(&task_id),
     ) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::EventPublish,
+        ));
     }
 
+    // Resolved up front so a bad filter is rejected before anything is appended.
+    let envelope_filter = if query.include_envelope.as_deref() == Some("true") {
+        options.reject(PUBLISH_UNSUPPORTED)?;
+        let filter_query = SseQuery {
+            series_format: query.series_format,
+            ..Default::default()
+        };
+        let filter = parse_filter(&options, &filter_query);
+        Some(resolve_query_filter(&engine, &task_id, options.preset.as_deref(), filter).await?)
+    } else {
+        None
+    };
+
+    let strictness = strictness
+        .for_task_id(&engine, &task_id, auth.sub.as_deref())
+        .await?;
     let is_batch = body.is_array();
+    let violations = match body.as_array() {
+        Some(items) => items
+            .iter()
+            .enumerate()
+            .flat_map(|(i, item)| strictness.check::<PublishEventBody>(item, &format!("/{i}")))
+            .collect(),
+        None => strictness.check::<PublishEventBody>(&body, ""),
+    };
+    if !violations.is_empty() {
+        return Err(AppError::Validation(violations));
+    }
 
     let inputs: Vec<PublishEventBody> = if is_batch {
         serde_json::from_value(body).map_err(|e| AppError::BadRequest(e.to_string()))?
@@ -435,6 +1359,23 @@ pub async fn publish_events(
             serde_json::from_value(body).map_err(|e| AppError::BadRequest(e.to_string()))?;
         vec![single]
     };
+    // Checked for the whole batch before any event is appended.
+    let event_types = engine.event_types();
+    let violations: Vec<_> = inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, input)| {
+            let pointer = if is_batch {
+                format!("/{i}/type")
+            } else {
+                "/type".to_string()
+            };
+            event_types.check(&input.r#type, &pointer).err()
+        })
+        .collect();
+    if !violations.is_empty() {
+        return Err(AppError::Validation(violations));
+    }
 
     let mut events = Vec::new();
     for input in inputs {
@@ -445,25 +1386,127 @@ pub async fn publish_events(
             series_id: input.series_id,
             series_mode: input.series_mode,
             series_acc_field: input.series_acc_field,
+            labels: input.labels,
+            broadcast_debounce_ms: input.broadcast_debounce_ms,
+            series_end: input.series_end.unwrap_or(false),
         };
         let event = engine
             .publish_event(&task_id, event_input)
             .await
             .map_err(|e| match &e {
-                EngineError::TaskNotFound(_) => AppError::NotFound(e.to_string()),
                 EngineError::TaskTerminal(_) => AppError::BadRequest(e.to_string()),
                 _ => AppError::Engine(e),
             })?;
-        events.push(serde_json::to_value(&event).unwrap());
+        events.push(event);
+    }
+
+    // Delivered after the events are stored, so a failed delivery does not
+    // undo the publish.
+    let webhook_results = match engine.get_task(&task_id).await? {
+        Some(task) => sync_webhooks.deliver(&task, &events).await,
+        None => None,
+    };
+
+    let last_index = events.last().map_or(0, |event| event.index);
+    let event_count = engine.event_count(&task_id).await?;
+    let headers = [
+        (EVENT_INDEX_HEADER, last_index.to_string()),
+        (EVENT_COUNT_HEADER, event_count.to_string()),
+    ];
+
+    let mut entries: Vec<serde_json::Value> = if let Some(filter) = envelope_filter {
+        let history = engine.get_events(&task_id, None).await?;
+        envelopes_in_history(&history, &events, &filter)
+            .into_iter()
+            .zip(&events)
+            .map(|(envelope, event)| match envelope {
+                Some(envelope) => serde_json::to_value(envelope).unwrap(),
+                None => json!({ "rawIndex": event.index, "filteredIndex": null }),
+            })
+            .collect()
+    } else {
+        events
+            .iter()
+            .map(|event| api.presenter.event(event))
+            .collect()
+    };
+
+    let mut status = StatusCode::CREATED;
+    if let Some(webhook_results) = webhook_results {
+        status = sync_status(webhook_results.iter().flatten(), StatusCode::CREATED);
+        for (entry, results) in entries.iter_mut().zip(webhook_results) {
+            entry["webhookResults"] = json!(results);
+        }
     }
 
     let body = if is_batch {
-        json!(events)
+        json!(entries)
     } else {
-        events.into_iter().next().unwrap()
+        entries.into_iter().next().unwrap()
     };
 
-    Ok((StatusCode::CREATED, axum::Json(body)))
+    Ok((status, headers, axum::Json(body)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tasks/{task_id}/events/stream",
+    tag = "Events",
+    summary = "Publish events from an NDJSON stream",
+    description = "Reads one event per line of an application/x-ndjson body and stores them in chunks. Each stored event is acknowledged with an {id, index} line once its chunk is stored. A malformed line, an exceeded limit or a failed publish ends the response with a {line, error} line; chunks acknowledged before it stay stored.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    request_body(content = String, content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "NDJSON acknowledgements, ending with an error line if the stream stopped early", content_type = "application/x-ndjson"),
+        (status = 400, description = "Wrong content type or task is terminal"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn publish_events_stream(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Extension(sync_webhooks): Extension<Arc<SyncWebhooks>>,
+    Extension(limits): Extension<IngestLimits>,
+    Extension(strictness): Extension<BodyStrictness>,
+    Path(task_id): Path<String>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventPublish, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventPublish));
+    }
+    let is_ndjson = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(NDJSON_CONTENT_TYPE));
+    if !is_ndjson {
+        return Err(AppError::BadRequest(format!(
+            "Content-Type must be {NDJSON_CONTENT_TYPE}"
+        )));
+    }
+    // Checked up front so these fail with a status rather than an error line.
+    let task = engine
+        .get_task(&task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
+    if is_terminal(&task.status) {
+        return Err(AppError::BadRequest(
+            EngineError::TaskTerminal(task.status).to_string(),
+        ));
+    }
+
+    let strictness = strictness.for_task(
+        engine.flags(),
+        &task_id,
+        task.r#type.as_deref(),
+        auth.sub.as_deref(),
+    );
+
+    let acks = ingest_ndjson(engine, sync_webhooks, task_id, limits, strictness, body);
+    Ok(([(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)], acks))
 }
 
 #[utoipa::path(
@@ -472,17 +1515,22 @@ pub async fn publish_events(
     tag = "Events",
     summary = "Query event history",
     security(("Bearer" = [])),
-    params(("task_id" = String, Path, description = "Task ID"), HistoryQuery),
+    params(("task_id" = String, Path, description = "Task ID"), QueryOptions, HistoryQuery),
     responses(
         (status = 200, description = "Event list", body = Vec<taskcast_core::TaskEvent>),
+        (status = 400, description = "Invalid query parameter"),
         (status = 404, description = "Task not found"),
         (status = 403, description = "Forbidden"),
+        (status = 504, description = "The request deadline passed"),
     )
 )]
 pub async fn get_event_history(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
+    api: ApiVersion,
+    timestamp_check: TimestampCheck,
     Path(task_id): Path<String>,
+    options: QueryOptions,
     Query(query): Query<HistoryQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(
@@ -490,41 +1538,78 @@ pub async fn get_event_history(
         taskcast_core::PermissionScope::EventHistory,
         Some(&task_id),
     ) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::EventHistory,
+        ));
     }
 
     // Check task exists
-    let _task = engine
+    let task = engine
         .get_task(&task_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Task not found".to_string()))?;
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
 
-    let since = if query.since_id.is_some()
-        || query.since_index.is_some()
-        || query.since_timestamp.is_some()
-    {
-        Some(SinceCursor {
-            id: query.since_id,
-            index: query.since_index,
-            timestamp: query.since_timestamp,
-        })
-    } else {
-        None
+    options.reject(HISTORY_UNSUPPORTED)?;
+    let since_timestamp = options.since.as_ref().and_then(|s| s.timestamp);
+    let warnings =
+        timestamp_check.query(&[("since.timestamp", since_timestamp), ("until", query.until)])?;
+    let since = options.since.clone();
+    let filter = resolve_filter(
+        task.filters.as_ref(),
+        options.preset.as_deref(),
+        SubscribeFilter {
+            since: None,
+            materialize_series: query.materialize_series,
+            ..options.subscribe_filter()
+        },
+    )
+    .map_err(EngineError::from)?;
+    let from_start = since.is_none();
+    // The store only understands cursors and label selectors, so the other
+    // criteria (and therefore the limit) are applied here.
+    let post_filter = options.preset.is_some()
+        || filter.types.is_some()
+        || filter.levels.is_some()
+        || filter.include_status.is_some();
+    let limit = if post_filter { None } else { options.limit };
+
+    let accumulated = match query.series_format.as_deref() {
+        Some(series_format) => series_format == "accumulated",
+        None => filter.series_format == Some(SeriesFormat::Accumulated),
     };
+    // Both views read each series' current state, which may postdate `until`.
+    if query.until.is_some() && (accumulated || filter.materialize_series == Some(true)) {
+        return Err(AppError::InvalidQuery {
+            param: "until".to_string(),
+            reason: "cannot be combined with accumulated or materialized series".to_string(),
+        });
+    }
 
-    let opts = if since.is_some() || query.limit.is_some() {
+    let opts = if since.is_some()
+        || limit.is_some()
+        || filter.label_selector.is_some()
+        || query.until.is_some()
+    {
         Some(EventQueryOptions {
             since,
-            limit: query.limit,
+            limit,
+            label_selector: filter.label_selector.clone(),
+            until: query.until,
+            before_index: None,
         })
     } else {
         None
     };
 
     let mut events = engine.get_events(&task_id, opts).await?;
+    if post_filter {
+        events.retain(|event| matches_filter(event, &filter));
+        if let Some(limit) = options.limit {
+            events.truncate(limit as usize);
+        }
+    }
 
-    let series_format = query.series_format.as_deref().unwrap_or("delta");
-    if series_format == "accumulated" {
+    if accumulated {
         let engine_ref = Arc::clone(&engine);
         events =
             taskcast_core::series::collapse_accumulate_series(&events, |tid: &str, sid: &str| {
@@ -540,8 +1625,154 @@ pub async fn get_event_history(
             .await
             .unwrap_or(events);
     }
+    if filter.materialize_series == Some(true) {
+        let engine_ref = Arc::clone(&engine);
+        events = taskcast_core::series::collapse_json_patch_series(
+            &events,
+            from_start,
+            |tid: &str, sid: &str| {
+                let eng = Arc::clone(&engine_ref);
+                let tid = tid.to_string();
+                let sid = sid.to_string();
+                async move {
+                    eng.get_series_latest(&tid, &sid)
+                        .await
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                }
+            },
+        )
+        .await
+        .unwrap_or(events);
+    }
+
+    let mut headers = HeaderMap::new();
+    if query.checksum == Some(true) {
+        let checksum = serde_json::to_string(&history_checksum(&events)).unwrap();
+        headers.insert(CHECKSUM_HEADER, HeaderValue::from_str(&checksum).unwrap());
+    }
 
-    Ok(axum::Json(events))
+    let events = events
+        .iter()
+        .map(|event| api.presenter.event(event))
+        .collect();
+    Ok((
+        headers,
+        warnings,
+        axum::Json(api.presenter.event_list(events)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/events/stats",
+    tag = "Events",
+    summary = "Get event stats",
+    description = "How many of the task's stored events have each type and each level. Latest-mode series count only their current event. `scanEnd` is the index of the first event the counts leave out, or null when the short-term store has handed out no indices for the task.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    responses(
+        (status = 200, description = "Event counts by type and level"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+        (status = 504, description = "The request deadline passed"),
+    )
+)]
+pub async fn get_event_stats(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    engine
+        .get_task(&task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
+    let (scan, counts) = engine.scanned_event_type_counts(&task_id).await?;
+
+    Ok(axum::Json(json!({
+        "taskId": task_id,
+        "total": counts.total(),
+        "types": counts.types,
+        "levels": counts.levels,
+        "scanEnd": scan.end_index,
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tasks/{task_id}/events/{event_id}",
+    tag = "Events",
+    summary = "Get an event",
+    description = "One stored event by id. Also accepts the signed `expires` and `signature` parameters of a trimmed webhook delivery's `fetchUrl` in place of credentials.",
+    security(("Bearer" = [])),
+    params(
+        ("task_id" = String, Path, description = "Task ID"),
+        ("event_id" = String, Path, description = "Event ID"),
+    ),
+    responses(
+        (status = 200, description = "The event", body = taskcast_core::TaskEvent),
+        (status = 401, description = "Missing credentials, or an invalid or expired signed link"),
+        (status = 404, description = "Task or event not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn get_task_event(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    api: ApiVersion,
+    Path((task_id, event_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    if !check_scope(&auth, PermissionScope::EventHistory, Some(&task_id)) {
+        return Err(AppError::MissingScope(PermissionScope::EventHistory));
+    }
+
+    engine
+        .get_task(&task_id)
+        .await?
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
+    let event = engine
+        .get_event(&task_id, &event_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Event {event_id} not found")))?;
+    Ok(axum::Json(api.presenter.event(&event)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tasks/{task_id}/events",
+    tag = "Events",
+    summary = "Delete events",
+    description = "Erases the events selected by id or by filter from the short-term and long-term stores. \
+        Each is replaced by a `taskcast:redacted` tombstone at the same index, then a `taskcast:redacted` \
+        event listing the erased ids is published. Events the server publishes itself are never erased. \
+        Needs both `task:manage` and `event:delete`.",
+    security(("Bearer" = [])),
+    params(("task_id" = String, Path, description = "Task ID")),
+    request_body = taskcast_core::DeleteEventsInput,
+    responses(
+        (status = 200, description = "Erased event ids and counts per store", body = taskcast_core::DeletedEvents),
+        (status = 400, description = "Neither or both of `eventIds` and `filter` given"),
+        (status = 404, description = "Task not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn delete_events(
+    State(engine): State<Arc<TaskEngine>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(task_id): Path<String>,
+    StrictJson(body): StrictJson<DeleteEventsInput>,
+) -> Result<impl IntoResponse, AppError> {
+    for scope in [PermissionScope::TaskManage, PermissionScope::EventDelete] {
+        if !check_scope(&auth, scope.clone(), Some(&task_id)) {
+            return Err(AppError::MissingScope(scope));
+        }
+    }
+
+    let deleted = engine.delete_events(&task_id, body).await?;
+    Ok(axum::Json(deleted))
 }
 
 // ─── Resolve / Request Handlers ─────────────────────────────────────────────
@@ -555,7 +1786,9 @@ pub struct ResolveBody {
 pub async fn resolve_task(
     State(engine): State<Arc<TaskEngine>>,
     Extension(auth): Extension<AuthContext>,
+    api: ApiVersion,
     Path(task_id): Path<String>,
+    headers: HeaderMap,
     axum::Json(body): axum::Json<ResolveBody>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(
@@ -563,13 +1796,16 @@ pub async fn resolve_task(
         taskcast_core::PermissionScope::TaskResolve,
         Some(&task_id),
     ) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::TaskResolve,
+        ));
     }
+    let expected_version = if_match_version(&headers)?;
 
     let task = engine
         .get_task(&task_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Task not found".to_string()))?;
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
 
     if task.status != TaskStatus::Blocked {
         return Err(AppError::BadRequest("Task is not blocked".to_string()));
@@ -586,18 +1822,22 @@ pub async fn resolve_task(
     };
 
     let updated = engine
-        .transition_task(
+        .transition_task_versioned(
             &task_id,
             TaskStatus::Running,
             Some(TransitionPayload {
                 result,
                 ..Default::default()
             }),
+            expected_version,
         )
         .await
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        .map_err(|e| match e {
+            EngineError::ConcurrentModification(_) => AppError::Engine(e),
+            e => AppError::BadRequest(e.to_string()),
+        })?;
 
-    Ok(axum::Json(updated))
+    Ok(axum::Json(api.presenter.task(&updated)))
 }
 
 pub async fn get_blocked_request(
@@ -610,13 +1850,15 @@ pub async fn get_blocked_request(
         taskcast_core::PermissionScope::TaskResolve,
         Some(&task_id),
     ) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(
+            taskcast_core::PermissionScope::TaskResolve,
+        ));
     }
 
     let task = engine
         .get_task(&task_id)
         .await?
-        .ok_or_else(|| AppError::NotFound("Task not found".to_string()))?;
+        .ok_or_else(|| EngineError::TaskNotFound(task_id.clone()))?;
 
     if task.status != TaskStatus::Blocked {
         return Err(AppError::NotFound("No blocked request".to_string()));
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use taskcast_core::config::TaskTemplate;
+use taskcast_core::PermissionScope;
+
+use crate::auth::{check_scope, AuthContext};
+use crate::error::AppError;
+use crate::templates::TemplateRegistry;
+
+// ─── Request/Response Bodies ────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedTemplate {
+    pub name: String,
+    #[serde(flatten)]
+    pub template: TaskTemplate,
+}
+
+// ─── Handlers ───────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/templates",
+    tag = "Templates",
+    summary = "List task templates",
+    security(("Bearer" = [])),
+    responses(
+        (status = 200, description = "Template list"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn list_templates(
+    State(registry): State<Arc<TemplateRegistry>>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<impl IntoResponse, AppError> {
+    require_manage(&auth)?;
+    let templates: Vec<NamedTemplate> = registry
+        .list()
+        .into_iter()
+        .map(|(name, template)| NamedTemplate { name, template })
+        .collect();
+    Ok(axum::Json(json!({ "templates": templates })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/templates",
+    tag = "Templates",
+    summary = "Register a task template",
+    description = "Registers a template under `name`, replacing any template of that name. Templates registered here are not persisted across restarts.",
+    security(("Bearer" = [])),
+    request_body = NamedTemplate,
+    responses(
+        (status = 201, description = "Template registered", body = NamedTemplate),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn create_template(
+    State(registry): State<Arc<TemplateRegistry>>,
+    Extension(auth): Extension<AuthContext>,
+    axum::Json(body): axum::Json<NamedTemplate>,
+) -> Result<impl IntoResponse, AppError> {
+    require_manage(&auth)?;
+    if body.name.is_empty() {
+        return Err(AppError::BadRequest(
+            "Template name must not be empty".to_string(),
+        ));
+    }
+    registry.insert(&body.name, body.template.clone());
+    Ok((StatusCode::CREATED, axum::Json(body)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/templates/{name}",
+    tag = "Templates",
+    summary = "Get a task template",
+    security(("Bearer" = [])),
+    params(("name" = String, Path, description = "Template name")),
+    responses(
+        (status = 200, description = "Template", body = NamedTemplate),
+        (status = 404, description = "Not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn get_template(
+    State(registry): State<Arc<TemplateRegistry>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    require_manage(&auth)?;
+    let template = registry
+        .get(&name)
+        .ok_or_else(|| AppError::NotFound(format!("Template {name} not found")))?;
+    Ok(axum::Json(NamedTemplate { name, template }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/templates/{name}",
+    tag = "Templates",
+    summary = "Delete a task template",
+    security(("Bearer" = [])),
+    params(("name" = String, Path, description = "Template name")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Not found"),
+        (status = 403, description = "Forbidden"),
+    )
+)]
+pub async fn delete_template(
+    State(registry): State<Arc<TemplateRegistry>>,
+    Extension(auth): Extension<AuthContext>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    require_manage(&auth)?;
+    if registry.remove(&name).is_none() {
+        return Err(AppError::NotFound(format!("Template {name} not found")));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn templates_router() -> axum::Router<Arc<TemplateRegistry>> {
+    axum::Router::new()
+        .route("/", get(list_templates).post(create_template))
+        .route("/{name}", get(get_template).delete(delete_template))
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+fn require_manage(auth: &AuthContext) -> Result<(), AppError> {
+    if !check_scope(auth, PermissionScope::TaskManage, None) {
+        return Err(AppError::MissingScope(PermissionScope::TaskManage));
+    }
+    Ok(())
+}
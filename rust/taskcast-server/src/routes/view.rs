@@ -0,0 +1,92 @@
+//! The bundled task viewer: a self-contained HTML page that follows a task's
+//! SSE stream, for watching tasks during development. Served only when
+//! `ui.enabled` is set.
+//!
+//! The pages carry no task data, so they are served without auth. The task
+//! page reads the task and its stream with the `token` query parameter it was
+//! opened with: as a bearer header for the task read, and in the query
+//! string for the stream, since `EventSource` cannot set headers.
+
+use axum::extract::Path;
+use axum::http::{header, HeaderValue, Uri};
+use axum::response::{Html, IntoResponse, Response};
+use serde_json::json;
+
+use crate::auth::query_token;
+use crate::versioning::API_V1;
+
+const TASK_PAGE: &str = include_str!("view/task.html");
+const INDEX_PAGE: &str = include_str!("view/index.html");
+
+/// `GET /view`: asks for a task id and opens its viewer.
+pub async fn view_index() -> Response {
+    page(INDEX_PAGE, None)
+}
+
+/// `GET /tasks/{task_id}/view`: follows the task's SSE stream. The query
+/// string, `token` included, is passed through to the stream.
+pub async fn view_task(Path(task_id): Path<String>, uri: Uri) -> Response {
+    let task_url = format!("/{API_V1}/tasks/{}", encode_path_segment(&task_id));
+    let events_url = match uri.query() {
+        Some(query) if !query.is_empty() => format!("{task_url}/events?{query}"),
+        _ => format!("{task_url}/events"),
+    };
+    let config = json!({
+        "taskId": task_id,
+        "taskUrl": task_url,
+        "eventsUrl": events_url,
+        "token": query_token(&uri),
+    });
+    page(TASK_PAGE, Some(&script_json(&config)))
+}
+
+/// `template` with a fresh CSP nonce and, for the task page, its config.
+fn page(template: &str, config: Option<&str>) -> Response {
+    let nonce = ulid::Ulid::new().to_string();
+    let mut body = template.replace("__NONCE__", &nonce);
+    if let Some(config) = config {
+        body = body.replace("__CONFIG__", config);
+    }
+    let csp = format!(
+        "default-src 'none'; script-src 'nonce-{nonce}'; style-src 'nonce-{nonce}'; \
+         connect-src 'self'; base-uri 'none'; form-action 'self'; frame-ancestors 'none'"
+    );
+    let mut response = Html(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_str(&csp).expect("nonce is alphanumeric"),
+    );
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("no-referrer"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    response
+}
+
+/// `value` as JSON safe to embed in a `<script>` element.
+fn script_json(value: &serde_json::Value) -> String {
+    value
+        .to_string()
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters.
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
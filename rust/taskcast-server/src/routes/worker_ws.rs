@@ -164,7 +164,7 @@ pub async fn ws_handler(
     Extension(auth): Extension<AuthContext>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerConnect, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerConnect));
     }
 
     Ok(ws.on_upgrade(move |socket| handle_socket(socket, manager, registry, auth)))
@@ -500,10 +500,7 @@ async fn handle_socket(
 
 // ─── Helpers ────────────────────────────────────────────────────────────────
 
-async fn send_message(
-    socket: &mut WebSocket,
-    msg: &ServerMessage,
-) -> Result<(), axum::Error> {
+async fn send_message(socket: &mut WebSocket, msg: &ServerMessage) -> Result<(), axum::Error> {
     let text = serde_json::to_string(msg).unwrap();
     socket.send(Message::Text(text.into())).await
 }
@@ -7,7 +7,9 @@ use axum::routing::{get, patch, post};
 use axum::Extension;
 use serde::Deserialize;
 use serde_json::json;
-use taskcast_core::worker_manager::{DeclineOptions, WorkerManager, WorkerUpdate, WorkerUpdateStatus};
+use taskcast_core::worker_manager::{
+    DeclineOptions, WorkerManager, WorkerUpdate, WorkerUpdateStatus,
+};
 use taskcast_core::PermissionScope;
 
 use crate::auth::{check_scope, AuthContext};
@@ -70,7 +72,7 @@ pub async fn list_workers(
     Extension(auth): Extension<AuthContext>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerManage, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerManage));
     }
 
     let workers = manager.list_workers(None).await.map_err(manager_error)?;
@@ -96,7 +98,7 @@ pub async fn pull_task(
     Query(query): Query<PullQuery>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerConnect, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerConnect));
     }
 
     let worker_id = &query.worker_id;
@@ -121,10 +123,7 @@ pub async fn pull_task(
     }
 
     // Heartbeat
-    manager
-        .heartbeat(worker_id)
-        .await
-        .map_err(manager_error)?;
+    manager.heartbeat(worker_id).await.map_err(manager_error)?;
 
     // Wait for task with configurable timeout (default 30s)
     let timeout_ms = query.timeout.unwrap_or(30_000);
@@ -158,7 +157,7 @@ pub async fn get_worker(
     Path(worker_id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerManage, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerManage));
     }
 
     let worker = manager
@@ -189,7 +188,7 @@ pub async fn delete_worker(
     Path(worker_id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerManage, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerManage));
     }
 
     let worker = manager
@@ -231,7 +230,7 @@ pub async fn update_worker_status(
     axum::Json(body): axum::Json<WorkerStatusUpdateBody>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerManage, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerManage));
     }
 
     let update_status = match body.status {
@@ -273,7 +272,7 @@ pub async fn decline_task(
     axum::Json(body): axum::Json<DeclineBody>,
 ) -> Result<impl IntoResponse, AppError> {
     if !check_scope(&auth, PermissionScope::WorkerConnect, None) {
-        return Err(AppError::Forbidden);
+        return Err(AppError::MissingScope(PermissionScope::WorkerConnect));
     }
 
     // Enforce auth.worker_id matches requested workerId
@@ -0,0 +1,351 @@
+//! Executor and engine metrics for `GET /admin/runtime`, for telling apart
+//! executor starvation, store latency and a stuck dispatch loop when streams
+//! stall.
+//!
+//! [`RuntimeSampler`] reads tokio's runtime metrics and the engine's own
+//! counters. Started, it samples every interval and the endpoint serves the
+//! latest sample; otherwise each request takes one. Busy ratios cover the
+//! time since the previous sample. Local queue depths and forced yields are
+//! only available in builds with `--cfg tokio_unstable`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use taskcast_core::{
+    CompressionStats, NegativeCacheStats, QueryStatsSnapshot, ReadRoutingStats,
+    RequestCancellationStats, TaskEngine, WriteShapingStats,
+};
+use tokio::runtime::{Handle, RuntimeMetrics};
+use tokio::task::AbortHandle;
+
+/// Path of the runtime metrics endpoint.
+pub const RUNTIME_METRICS_PATH: &str = "/admin/runtime";
+
+/// Default interval of a started [`RuntimeSampler`].
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// ─── Snapshot ───────────────────────────────────────────────────────────────
+
+/// One sample of the executor and the engine.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeSnapshot {
+    /// Unix epoch milliseconds.
+    pub sampled_at: f64,
+    /// Time covered by the busy ratios: since the previous sample, or since
+    /// the sampler was created for the first one.
+    pub window_ms: f64,
+    /// `None` when sampled outside a tokio runtime.
+    pub executor: Option<ExecutorSnapshot>,
+    pub engine: EngineCounters,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorSnapshot {
+    pub workers: usize,
+    pub alive_tasks: usize,
+    /// Tasks waiting in the shared injection queue.
+    pub global_queue_depth: usize,
+    /// Times the coop budget forced a task to yield, since startup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_forced_yields: Option<u64>,
+    pub worker_stats: Vec<WorkerSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerSnapshot {
+    /// Time spent polling tasks since startup.
+    pub busy_ms: f64,
+    /// Share of the window spent polling tasks, 0 to 1.
+    pub busy_ratio: f64,
+    /// Times the worker parked since startup.
+    pub park_count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_queue_depth: Option<usize>,
+}
+
+/// The engine's own in-flight work.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineCounters {
+    /// Background operations (persistence, dispatch, stream forwarding).
+    pub background_in_flight: usize,
+    pub background_by_name: HashMap<String, usize>,
+    /// Store latency as seen by read routing.
+    pub read_routing: ReadRoutingStats,
+    /// Lookups of missing tasks answered without a store read. Absent while
+    /// the negative cache is off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_cache: Option<NegativeCacheStats>,
+    /// Long-term write queue depth, rate and shed count. Absent while write
+    /// shaping is off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_term_writes: Option<WriteShapingStats>,
+    /// Per-method latency histograms and slow query count of the long-term
+    /// store. Absent for stores that do not time their queries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_term_queries: Option<QueryStatsSnapshot>,
+    /// What compressing stored values saves the short-term store. Absent
+    /// while compression is off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_term_compression: Option<CompressionStats>,
+    /// Deadline-aware reads that ended early, by their client going away
+    /// or by their deadline passing.
+    pub requests: RequestCancellationStats,
+}
+
+// ─── Sampler ────────────────────────────────────────────────────────────────
+
+struct SamplerState {
+    taken_at: Instant,
+    busy: Vec<Duration>,
+    latest: Option<RuntimeSnapshot>,
+    handle: Option<AbortHandle>,
+}
+
+/// Samples the executor and the engine, on demand or periodically.
+pub struct RuntimeSampler {
+    engine: Arc<TaskEngine>,
+    state: Mutex<SamplerState>,
+}
+
+impl RuntimeSampler {
+    pub fn new(engine: Arc<TaskEngine>) -> Self {
+        Self {
+            engine,
+            state: Mutex::new(SamplerState {
+                taken_at: Instant::now(),
+                busy: Vec::new(),
+                latest: None,
+                handle: None,
+            }),
+        }
+    }
+
+    /// Samples every `interval` until [`stop`](Self::stop) is called.
+    pub fn start(self: &Arc<Self>, interval: Duration) {
+        let sampler = Arc::clone(self);
+        let handle = self
+            .engine
+            .background()
+            .spawn("runtime.sampler", None, async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    sampler.sample();
+                }
+            });
+        if let Some(previous) = self.state.lock().unwrap().handle.replace(handle) {
+            previous.abort();
+        }
+    }
+
+    pub fn stop(&self) {
+        if let Some(handle) = self.state.lock().unwrap().handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// The latest periodic sample, or a new one when not started.
+    pub fn snapshot(&self) -> RuntimeSnapshot {
+        {
+            let state = self.state.lock().unwrap();
+            if state.handle.is_some() {
+                if let Some(ref latest) = state.latest {
+                    return latest.clone();
+                }
+            }
+        }
+        self.sample()
+    }
+
+    /// Takes a sample now and keeps it as the latest.
+    pub fn sample(&self) -> RuntimeSnapshot {
+        let metrics = Handle::try_current().ok().map(|handle| handle.metrics());
+        let background = self.engine.background();
+        let engine = EngineCounters {
+            background_in_flight: background.in_flight(),
+            background_by_name: background.in_flight_by_name(),
+            read_routing: self.engine.read_router().stats(),
+            negative_cache: self.engine.negative_cache_stats(),
+            long_term_writes: self.engine.write_shaping_stats(),
+            long_term_queries: self.engine.long_term_query_stats(),
+            short_term_compression: self.engine.short_term_compression_stats(),
+            requests: self.engine.request_counters().stats(),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let window = now.duration_since(state.taken_at);
+        let executor = metrics.map(|metrics| {
+            let busy: Vec<Duration> = (0..metrics.num_workers())
+                .map(|worker| metrics.worker_total_busy_duration(worker))
+                .collect();
+            let snapshot = executor_snapshot(&metrics, &busy, &state.busy, window);
+            state.busy = busy;
+            snapshot
+        });
+        state.taken_at = now;
+
+        let snapshot = RuntimeSnapshot {
+            sampled_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system time before UNIX epoch")
+                .as_millis() as f64,
+            window_ms: window.as_secs_f64() * 1000.0,
+            executor,
+            engine,
+        };
+        state.latest = Some(snapshot.clone());
+        snapshot
+    }
+}
+
+impl Drop for RuntimeSampler {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn executor_snapshot(
+    metrics: &RuntimeMetrics,
+    busy: &[Duration],
+    previous_busy: &[Duration],
+    window: Duration,
+) -> ExecutorSnapshot {
+    let worker_stats = busy
+        .iter()
+        .enumerate()
+        .map(|(worker, total)| {
+            let before = previous_busy.get(worker).copied().unwrap_or_default();
+            let ratio = if window.is_zero() {
+                0.0
+            } else {
+                (total.saturating_sub(before).as_secs_f64() / window.as_secs_f64()).min(1.0)
+            };
+            WorkerSnapshot {
+                busy_ms: total.as_secs_f64() * 1000.0,
+                busy_ratio: ratio,
+                park_count: metrics.worker_park_count(worker),
+                local_queue_depth: local_queue_depth(metrics, worker),
+            }
+        })
+        .collect();
+
+    ExecutorSnapshot {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        budget_forced_yields: budget_forced_yields(metrics),
+        worker_stats,
+    }
+}
+
+#[cfg(tokio_unstable)]
+fn local_queue_depth(metrics: &RuntimeMetrics, worker: usize) -> Option<usize> {
+    Some(metrics.worker_local_queue_depth(worker))
+}
+
+#[cfg(not(tokio_unstable))]
+fn local_queue_depth(_metrics: &RuntimeMetrics, _worker: usize) -> Option<usize> {
+    None
+}
+
+#[cfg(tokio_unstable)]
+fn budget_forced_yields(metrics: &RuntimeMetrics) -> Option<u64> {
+    Some(metrics.budget_forced_yield_count())
+}
+
+#[cfg(not(tokio_unstable))]
+fn budget_forced_yields(_metrics: &RuntimeMetrics) -> Option<u64> {
+    None
+}
+
+// ─── Unit Tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngineOptions};
+
+    fn make_engine() -> Arc<TaskEngine> {
+        Arc::new(TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        }))
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn sampler_sees_synthetic_load() {
+        let sampler = RuntimeSampler::new(make_engine());
+        sampler.sample();
+
+        let (release, parked) = tokio::sync::oneshot::channel::<()>();
+        let waiter = tokio::spawn(async move {
+            let _ = parked.await;
+        });
+        let load: Vec<_> = (0..4)
+            .map(|_| {
+                tokio::spawn(async {
+                    let until = Instant::now() + Duration::from_millis(50);
+                    while Instant::now() < until {
+                        std::hint::spin_loop();
+                    }
+                })
+            })
+            .collect();
+        for task in load {
+            task.await.unwrap();
+        }
+        // Workers publish their busy time when they park.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let snapshot = sampler.sample();
+        release.send(()).unwrap();
+        waiter.await.unwrap();
+
+        let executor = snapshot.executor.unwrap();
+        assert_eq!(executor.workers, 2);
+        assert!(executor.alive_tasks >= 1);
+        assert!(snapshot.window_ms >= 50.0);
+        assert!(executor.worker_stats.iter().any(|w| w.busy_ms > 0.0));
+        assert!(executor.worker_stats.iter().any(|w| w.busy_ratio > 0.0));
+        assert!(executor
+            .worker_stats
+            .iter()
+            .all(|w| (0.0..=1.0).contains(&w.busy_ratio)));
+    }
+
+    #[tokio::test]
+    async fn started_sampler_serves_its_latest_sample() {
+        let engine = make_engine();
+        let sampler = Arc::new(RuntimeSampler::new(Arc::clone(&engine)));
+        sampler.start(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let first = sampler.snapshot();
+        assert_eq!(first, sampler.snapshot());
+        assert_eq!(first.engine.background_by_name["runtime.sampler"], 1);
+
+        sampler.stop();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(engine.background().in_flight(), 0);
+        assert_ne!(sampler.snapshot().sampled_at, 0.0);
+    }
+
+    #[test]
+    fn sample_outside_a_runtime_has_no_executor() {
+        let sampler = RuntimeSampler::new(make_engine());
+        let snapshot = sampler.sample();
+        assert!(snapshot.executor.is_none());
+        assert_eq!(snapshot.engine.background_in_flight, 0);
+    }
+}
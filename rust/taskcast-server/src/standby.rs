@@ -0,0 +1,123 @@
+//! Serving as the standby of an active/standby pair.
+//!
+//! [`standby_gate`] refuses writes while the instance is standby, the way the
+//! read-only API does, and cuts off writes in progress when it is demoted.
+//! Reads and SSE streams are untouched, so clients can stay connected to a
+//! standby across its promotion.
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use taskcast_core::{RoleSwitch, ServerRole};
+
+use crate::error::AppError;
+
+/// `405` `READ_ONLY`, naming the methods that are still allowed.
+pub(crate) fn read_only_response() -> Response {
+    let mut response = AppError::ReadOnly.into_response();
+    response.headers_mut().insert(
+        header::ALLOW,
+        HeaderValue::from_static("GET, HEAD, OPTIONS"),
+    );
+    response
+}
+
+/// Lets writes through only while `role` is active. A write still running
+/// when the instance is demoted is dropped and answered with `READ_ONLY`.
+/// WebSocket upgrades count as writes, since worker connections claim and
+/// update tasks.
+pub async fn standby_gate(
+    State(role): State<RoleSwitch>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let safe = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if safe && !request.headers().contains_key(header::UPGRADE) {
+        return next.run(request).await;
+    }
+    let mut changes = role.subscribe();
+    if *changes.borrow_and_update() != ServerRole::Active {
+        return read_only_response();
+    }
+    tokio::select! {
+        response = next.run(request) => response,
+        _ = changes.wait_for(|role| *role != ServerRole::Active) => read_only_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{middleware, Router};
+    use taskcast_core::{BackgroundTasks, RunnerSupervisor, SystemClock};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn gated(role: &RoleSwitch) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                post(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "written"
+                })
+                .get(|| async { "read" }),
+            )
+            .layer(middleware::from_fn_with_state(role.clone(), standby_gate))
+    }
+
+    fn request(method: Method) -> Request {
+        Request::builder()
+            .method(method)
+            .uri("/slow")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn runners() -> RunnerSupervisor {
+        RunnerSupervisor::new(
+            BackgroundTasks::new(None),
+            None,
+            std::sync::Arc::new(SystemClock),
+        )
+    }
+
+    #[tokio::test]
+    async fn standby_refuses_writes_but_serves_reads() {
+        let role = RoleSwitch::new(ServerRole::Standby, runners());
+        let app = gated(&role);
+
+        let res = app.clone().oneshot(request(Method::POST)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[header::ALLOW], "GET, HEAD, OPTIONS");
+
+        let res = app.oneshot(request(Method::GET)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn demotion_cuts_off_writes_in_progress() {
+        let role = RoleSwitch::new(ServerRole::Active, runners());
+        let write = tokio::spawn(gated(&role).oneshot(request(Method::POST)));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!write.is_finished());
+
+        role.demote();
+        let res = write.await.unwrap().unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "READ_ONLY");
+    }
+}
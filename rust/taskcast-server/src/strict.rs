@@ -0,0 +1,404 @@
+//! Strict request bodies, enabled by `http.strictBodies`.
+//!
+//! serde skips fields a body type does not declare, so a misspelled
+//! `seriesMode` or `webhooks` silently does nothing. In strict mode a body
+//! is first checked against the OpenAPI schema of its type, which carries
+//! the same camelCase names serde accepts, and every key the schema does not
+//! know is rejected with its JSON pointer and the closest known field.
+//!
+//! Bodies of events published to a task can instead be made strict per
+//! task with the `strictBodies` feature flag.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use axum::body::{Body, Bytes};
+use axum::extract::{FromRequest, Request};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use taskcast_core::config::HttpConfig;
+use taskcast_core::{EngineError, FlagContext, FlagEvaluator, TaskEngine, Violation};
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+
+/// Feature flag that, when configured, decides whether events published to
+/// a task are checked strictly, in place of `http.strictBodies`.
+pub const STRICT_BODIES_FLAG: &str = "strictBodies";
+
+/// Serde aliases, which the schema does not list: `(field, alias)`.
+const ALIASES: &[(&str, &str)] = &[("seriesFormat", "seriesView")];
+
+/// Whether request bodies may carry fields their type does not declare,
+/// passed via Axum Extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BodyStrictness {
+    pub strict: bool,
+}
+
+impl BodyStrictness {
+    pub fn from_config(config: Option<&HttpConfig>) -> Self {
+        Self {
+            strict: config.and_then(|c| c.strict_bodies).unwrap_or(false),
+        }
+    }
+
+    /// The strictness of events published to a task, decided by the
+    /// `strictBodies` flag when one is configured.
+    pub fn for_task(
+        self,
+        flags: &FlagEvaluator,
+        task_id: &str,
+        task_type: Option<&str>,
+        sub: Option<&str>,
+    ) -> Self {
+        let ctx = FlagContext {
+            task_id,
+            task_type,
+            sub,
+        };
+        Self {
+            strict: flags.is_enabled(STRICT_BODIES_FLAG, &ctx, self.strict),
+        }
+    }
+
+    /// [`for_task`](Self::for_task) for a task not read yet. The task is
+    /// read only when the flag targets task types.
+    pub async fn for_task_id(
+        self,
+        engine: &TaskEngine,
+        task_id: &str,
+        sub: Option<&str>,
+    ) -> Result<Self, EngineError> {
+        let flags = engine.flags();
+        if !flags.is_configured(STRICT_BODIES_FLAG) {
+            return Ok(self);
+        }
+        let task = if flags.targets_task_types(STRICT_BODIES_FLAG) {
+            engine.get_task(task_id).await?
+        } else {
+            None
+        };
+        let task_type = task.as_ref().and_then(|task| task.r#type.as_deref());
+        Ok(self.for_task(flags, task_id, task_type, sub))
+    }
+
+    /// The fields of `body` that `T` does not declare, each as a violation
+    /// under `pointer`. Always empty when not strict.
+    pub fn check<T: ToSchema + 'static>(&self, body: &Value, pointer: &str) -> Vec<Violation> {
+        if !self.strict {
+            return Vec::new();
+        }
+        let schema = BodySchema::of::<T>();
+        let mut violations = Vec::new();
+        schema.check(&schema.root, body, pointer, &mut violations);
+        violations
+    }
+}
+
+/// A JSON body extractor that, in strict mode, rejects unknown fields with
+/// `400 INVALID_INPUT`. Otherwise it behaves exactly like [`Json`].
+pub struct StrictJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for StrictJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + ToSchema + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let strictness = req
+            .extensions()
+            .get::<BodyStrictness>()
+            .copied()
+            .unwrap_or_default();
+        if !strictness.strict {
+            let Json(body) = Json::<T>::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Ok(Self(body));
+        }
+
+        // Parsed twice, once untyped to find unknown keys, so that any other
+        // rejection is the one `Json` would give.
+        let (parts, body) = req.into_parts();
+        let bytes = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let Json(value) = Json::<Value>::from_request(
+            Request::from_parts(parts, Body::from(bytes.clone())),
+            state,
+        )
+        .await
+        .map_err(IntoResponse::into_response)?;
+        let violations = strictness.check::<T>(&value, "");
+        if !violations.is_empty() {
+            return Err(AppError::Validation(violations).into_response());
+        }
+        let Json(body) = Json::<T>::from_bytes(&bytes).map_err(IntoResponse::into_response)?;
+        Ok(Self(body))
+    }
+}
+
+/// `Option<StrictJson<T>>` is `None` for a request without a body type
+/// and without a body. A body sent without a type is refused as a required
+/// `StrictJson<T>` would refuse it, rather than dropped.
+impl<S, T> axum::extract::OptionalFromRequest<S> for StrictJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + ToSchema + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request(mut req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        if !req.headers().contains_key(axum::http::header::CONTENT_TYPE) {
+            let (parts, body) = req.into_parts();
+            let bytes = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            if bytes.is_empty() {
+                return Ok(None);
+            }
+            req = Request::from_parts(parts, Body::from(bytes));
+        }
+        <Self as FromRequest<S>>::from_request(req, state)
+            .await
+            .map(Some)
+    }
+}
+
+/// The OpenAPI schema of a body type and every schema it references, as
+/// JSON.
+struct BodySchema {
+    root: Value,
+    components: HashMap<String, Value>,
+}
+
+impl BodySchema {
+    /// Built once per type.
+    fn of<T: ToSchema + 'static>() -> Arc<Self> {
+        static CACHE: OnceLock<Mutex<HashMap<TypeId, Arc<BodySchema>>>> = OnceLock::new();
+        let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+        Arc::clone(cache.entry(TypeId::of::<T>()).or_insert_with(|| {
+            let mut components = Vec::new();
+            T::schemas(&mut components);
+            Arc::new(Self {
+                root: serde_json::to_value(T::schema()).unwrap_or_default(),
+                components: components
+                    .into_iter()
+                    .map(|(name, schema)| (name, serde_json::to_value(schema).unwrap_or_default()))
+                    .collect(),
+            })
+        }))
+    }
+
+    fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        match schema["$ref"].as_str() {
+            Some(location) => location
+                .rsplit('/')
+                .next()
+                .and_then(|name| self.components.get(name))
+                .unwrap_or(&Value::Null),
+            None => schema,
+        }
+    }
+
+    fn check(&self, schema: &Value, value: &Value, pointer: &str, out: &mut Vec<Violation>) {
+        let schema = self.resolve(schema);
+        if let Some(variants) = schema["oneOf"].as_array().or(schema["anyOf"].as_array()) {
+            self.check_variants(variants, value, pointer, out);
+            return;
+        }
+        match value {
+            Value::Object(object) => {
+                let properties = schema["properties"].as_object();
+                let additional = &schema["additionalProperties"];
+                for (key, field) in object {
+                    let field_pointer = format!("{pointer}/{}", escape_pointer(key));
+                    match properties.and_then(|p| p.get(known_name(key, p))) {
+                        Some(field_schema) => self.check(field_schema, field, &field_pointer, out),
+                        None if additional.is_object() => {
+                            self.check(additional, field, &field_pointer, out)
+                        }
+                        None => match properties {
+                            Some(properties) if additional != &Value::Bool(true) => {
+                                out.push(unknown_field(key, properties, field_pointer))
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            Value::Array(items) if schema["items"].is_object() => {
+                for (i, item) in items.iter().enumerate() {
+                    self.check(&schema["items"], item, &format!("{pointer}/{i}"), out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A value matching any variant is fine; otherwise the variant it comes
+    /// closest to explains what is wrong.
+    fn check_variants(
+        &self,
+        variants: &[Value],
+        value: &Value,
+        pointer: &str,
+        out: &mut Vec<Violation>,
+    ) {
+        let mut closest: Option<Vec<Violation>> = None;
+        for variant in variants {
+            let variant = self.resolve(variant);
+            if !accepts_kind(variant, value) {
+                continue;
+            }
+            let mut found = Vec::new();
+            self.check(variant, value, pointer, &mut found);
+            if found.is_empty() {
+                return;
+            }
+            if closest.as_ref().is_none_or(|c| found.len() < c.len()) {
+                closest = Some(found);
+            }
+        }
+        out.extend(closest.into_iter().flatten());
+    }
+}
+
+/// Whether `schema` can describe `value` at all, judging by its `type`.
+fn accepts_kind(schema: &Value, value: &Value) -> bool {
+    let kind = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    let matches = |t: &Value| t == kind || (kind == "number" && t == "integer");
+    match &schema["type"] {
+        Value::Null => true,
+        Value::Array(types) => types.iter().any(matches),
+        t => matches(t),
+    }
+}
+
+/// `key`, or the field it is a serde alias of.
+fn known_name<'a>(key: &'a str, properties: &serde_json::Map<String, Value>) -> &'a str {
+    ALIASES
+        .iter()
+        .find(|(field, alias)| *alias == key && properties.contains_key(*field))
+        .map_or(key, |(field, _)| field)
+}
+
+fn unknown_field(
+    key: &str,
+    properties: &serde_json::Map<String, Value>,
+    pointer: String,
+) -> Violation {
+    let message = match suggest(key, properties.keys().map(String::as_str)) {
+        Some(known) => format!("Unknown field \"{key}\"; did you mean \"{known}\"?"),
+        None => format!("Unknown field \"{key}\""),
+    };
+    Violation::new(pointer, message)
+}
+
+/// The known field closest to `key` by case-insensitive edit distance, if
+/// it is within a third of the longer name.
+fn suggest<'a>(key: &str, known: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let lower = key.to_lowercase();
+    known
+        .map(|field| (strsim::levenshtein(&lower, &field.to_lowercase()), field))
+        .filter(|(distance, field)| *distance <= key.len().max(field.len()) / 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, field)| field)
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::routes::tasks::{CreateTaskBody, PublishEventBody};
+
+    const STRICT: BodyStrictness = BodyStrictness { strict: true };
+
+    fn messages(violations: &[Violation]) -> Vec<(&str, &str)> {
+        violations
+            .iter()
+            .map(|v| (v.pointer.as_str(), v.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn suggests_the_closest_field() {
+        let fields = ["seriesId", "seriesMode", "type", "labels"];
+        assert_eq!(suggest("serieId", fields.into_iter()), Some("seriesId"));
+        assert_eq!(
+            suggest("seriesmode", fields.into_iter()),
+            Some("seriesMode")
+        );
+        assert_eq!(suggest("priority", fields.into_iter()), None);
+    }
+
+    #[test]
+    fn nested_unknown_fields_are_found_through_refs_maps_and_arrays() {
+        let body = json!({
+            "webooks": [],
+            "webhooks": [{ "url": "https://hooks.example", "retry": { "retires": 3 } }],
+            "filters": { "errors": { "level": ["error"] } },
+            "cleanup": { "rules": [{ "trigger": { "afterMS": 1000 }, "target": "all" }] },
+            "params": { "anything": { "goes": true } },
+        });
+        let violations = STRICT.check::<CreateTaskBody>(&body, "");
+        assert_eq!(
+            messages(&violations),
+            [
+                (
+                    "/cleanup/rules/0/trigger/afterMS",
+                    "Unknown field \"afterMS\"; did you mean \"afterMs\"?"
+                ),
+                (
+                    "/filters/errors/level",
+                    "Unknown field \"level\"; did you mean \"levels\"?"
+                ),
+                (
+                    "/webhooks/0/retry/retires",
+                    "Unknown field \"retires\"; did you mean \"retries\"?"
+                ),
+                (
+                    "/webooks",
+                    "Unknown field \"webooks\"; did you mean \"webhooks\"?"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn aliases_and_valid_bodies_pass() {
+        let body = json!({
+            "id": "t1",
+            "filters": { "deltas": { "seriesView": "delta", "types": ["llm.*"] } },
+        });
+        assert!(STRICT.check::<CreateTaskBody>(&body, "").is_empty());
+        let event = json!({ "type": "log", "level": "info", "data": { "x": 1 } });
+        assert!(STRICT.check::<PublishEventBody>(&event, "").is_empty());
+    }
+
+    #[test]
+    fn lenient_mode_reports_nothing() {
+        let body = json!({ "serieId": "s1" });
+        assert!(BodyStrictness::default()
+            .check::<PublishEventBody>(&body, "")
+            .is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Named task templates that `POST /tasks` bodies can start from.
+//!
+//! Templates come from the `templates` config section and from the
+//! `/templates` API. They are resolved in the route handler, so the engine
+//! only ever sees the merged [`taskcast_core::CreateTaskInput`].
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use serde_json::Value;
+use taskcast_core::config::{TaskTemplate, TaskcastConfig};
+
+use crate::routes::tasks::CreateTaskBody;
+
+/// The server's registered templates.
+///
+/// Templates registered through the API are kept in memory; only those in
+/// the config file survive a restart.
+#[derive(Debug, Default)]
+pub struct TemplateRegistry {
+    templates: RwLock<HashMap<String, TaskTemplate>>,
+}
+
+impl TemplateRegistry {
+    pub fn new(templates: HashMap<String, TaskTemplate>) -> Self {
+        Self {
+            templates: RwLock::new(templates),
+        }
+    }
+
+    pub fn from_config(config: Option<&TaskcastConfig>) -> Self {
+        Self::new(config.and_then(|c| c.templates.clone()).unwrap_or_default())
+    }
+
+    pub fn get(&self, name: &str) -> Option<TaskTemplate> {
+        self.templates.read().unwrap().get(name).cloned()
+    }
+
+    /// All templates, sorted by name.
+    pub fn list(&self) -> Vec<(String, TaskTemplate)> {
+        let mut templates: Vec<_> = self
+            .templates
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, template)| (name.clone(), template.clone()))
+            .collect();
+        templates.sort_by(|a, b| a.0.cmp(&b.0));
+        templates
+    }
+
+    /// Registers `template` under `name`, returning the one it replaced.
+    pub fn insert(&self, name: &str, template: TaskTemplate) -> Option<TaskTemplate> {
+        self.templates
+            .write()
+            .unwrap()
+            .insert(name.to_string(), template)
+    }
+
+    pub fn remove(&self, name: &str) -> Option<TaskTemplate> {
+        self.templates.write().unwrap().remove(name)
+    }
+}
+
+/// Merges `template` under `body`.
+///
+/// Fields set in the body win. Webhooks are concatenated, the template's
+/// first, and a template webhook is dropped when the body has one with the
+/// same URL. Metadata objects are merged recursively.
+pub fn apply_template(body: &mut CreateTaskBody, template: TaskTemplate) {
+    if body.r#type.is_none() {
+        body.r#type = template.r#type;
+    }
+    if body.ttl.is_none() {
+        body.ttl = template.ttl;
+    }
+    if body.cleanup.is_none() {
+        body.cleanup = template.cleanup;
+    }
+    if body.auth_config.is_none() {
+        body.auth_config = template.auth_config;
+    }
+
+    if let Some(template_webhooks) = template.webhooks {
+        let own = body.webhooks.take().unwrap_or_default();
+        let mut webhooks: Vec<_> = template_webhooks
+            .into_iter()
+            .filter(|w| !own.iter().any(|o| o.url == w.url))
+            .collect();
+        webhooks.extend(own);
+        body.webhooks = Some(webhooks);
+    }
+
+    if let Some(template_metadata) = template.metadata {
+        let mut metadata = template_metadata;
+        for (key, value) in body.metadata.take().unwrap_or_default() {
+            match metadata.get_mut(&key) {
+                Some(base) => merge_value(base, value),
+                None => {
+                    metadata.insert(key, value);
+                }
+            }
+        }
+        body.metadata = Some(metadata);
+    }
+}
+
+fn merge_value(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use taskcast_core::{CleanupConfig, CleanupRule, CleanupTarget, CleanupTrigger};
+    use taskcast_core::{TaskAuthConfig, WebhookConfig};
+
+    use super::*;
+
+    fn body(value: Value) -> CreateTaskBody {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn template(value: Value) -> TaskTemplate {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn cleanup(after_ms: u64) -> CleanupConfig {
+        CleanupConfig {
+            rules: vec![CleanupRule {
+                name: None,
+                r#match: None,
+                trigger: CleanupTrigger {
+                    after_ms: Some(after_ms),
+                },
+                target: CleanupTarget::All,
+                event_filter: None,
+            }],
+            abandoned_pending: None,
+        }
+    }
+
+    fn urls(webhooks: &[WebhookConfig]) -> Vec<&str> {
+        webhooks.iter().map(|w| w.url.as_str()).collect()
+    }
+
+    #[test]
+    fn template_fills_fields_the_body_leaves_unset() {
+        let mut merged = body(json!({ "template": "report" }));
+        apply_template(
+            &mut merged,
+            template(json!({
+                "type": "report.generate",
+                "ttl": 3600,
+                "cleanup": { "rules": [{ "trigger": { "afterMs": 60000 }, "target": "all" }] },
+                "authConfig": { "rules": [] },
+            })),
+        );
+        assert_eq!(merged.r#type.as_deref(), Some("report.generate"));
+        assert_eq!(merged.ttl, Some(3600));
+        assert_eq!(merged.cleanup, Some(cleanup(60000)));
+        assert_eq!(merged.auth_config, Some(TaskAuthConfig { rules: vec![] }));
+    }
+
+    #[test]
+    fn body_values_win_over_the_template() {
+        let mut merged = body(json!({
+            "type": "report.quick",
+            "ttl": 60,
+            "cleanup": { "rules": [{ "trigger": { "afterMs": 1000 }, "target": "all" }] },
+        }));
+        apply_template(
+            &mut merged,
+            template(json!({
+                "type": "report.generate",
+                "ttl": 3600,
+                "cleanup": { "rules": [{ "trigger": { "afterMs": 60000 }, "target": "all" }] },
+            })),
+        );
+        assert_eq!(merged.r#type.as_deref(), Some("report.quick"));
+        assert_eq!(merged.ttl, Some(60));
+        assert_eq!(merged.cleanup, Some(cleanup(1000)));
+    }
+
+    #[test]
+    fn webhooks_concatenate_and_dedupe_by_url() {
+        let mut merged = body(json!({
+            "webhooks": [
+                { "url": "https://b.example/hook", "secret": "body" },
+                { "url": "https://c.example/hook" },
+            ],
+        }));
+        apply_template(
+            &mut merged,
+            template(json!({
+                "webhooks": [
+                    { "url": "https://a.example/hook" },
+                    { "url": "https://b.example/hook", "secret": "template" },
+                ],
+            })),
+        );
+        let webhooks = merged.webhooks.unwrap();
+        assert_eq!(
+            urls(&webhooks),
+            [
+                "https://a.example/hook",
+                "https://b.example/hook",
+                "https://c.example/hook"
+            ]
+        );
+        assert_eq!(webhooks[1].secret.as_deref(), Some("body"));
+    }
+
+    #[test]
+    fn template_webhooks_apply_when_the_body_has_none() {
+        let mut merged = body(json!({}));
+        apply_template(
+            &mut merged,
+            template(json!({ "webhooks": [{ "url": "https://a.example/hook" }] })),
+        );
+        assert_eq!(urls(&merged.webhooks.unwrap()), ["https://a.example/hook"]);
+    }
+
+    #[test]
+    fn metadata_deep_merges() {
+        let mut merged = body(json!({
+            "metadata": {
+                "owner": { "user": "u-42" },
+                "limits": { "pages": 10 },
+                "tags": ["urgent"],
+            },
+        }));
+        apply_template(
+            &mut merged,
+            template(json!({
+                "metadata": {
+                    "owner": { "team": "reporting" },
+                    "limits": { "pages": 100, "bytes": 1024 },
+                    "tags": ["scheduled"],
+                    "source": "template",
+                },
+            })),
+        );
+        assert_eq!(
+            json!(merged.metadata.unwrap()),
+            json!({
+                "owner": { "team": "reporting", "user": "u-42" },
+                "limits": { "pages": 10, "bytes": 1024 },
+                "tags": ["urgent"],
+                "source": "template",
+            })
+        );
+    }
+
+    #[test]
+    fn registry_lists_templates_by_name() {
+        let registry = TemplateRegistry::default();
+        registry.insert("b", TaskTemplate::default());
+        registry.insert("a", TaskTemplate::default());
+        let names: Vec<_> = registry.list().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(registry.remove("a").is_some());
+        assert!(registry.get("a").is_none());
+    }
+}
@@ -0,0 +1,437 @@
+//! Timestamps at the HTTP boundary.
+//!
+//! Tasks and events store times as epoch milliseconds, and responses render
+//! them that way by default. Request fields that take a time also accept an
+//! RFC 3339 string, parsed to the same milliseconds by [`deserialize`] or
+//! [`parse_timestamp`]. A request can ask for RFC 3339 in the response with
+//! `?timestampFormat=rfc3339` or the [`TIMESTAMP_FORMAT_HEADER`]; the
+//! presenter then renders task and event times as strings.
+//!
+//! A time before 2001-09-09 reads as fewer than 10^12 milliseconds, which is
+//! almost always seconds sent by mistake. [`TimestampCheck`] warns about
+//! such values in [`WARNING_HEADER`], or rejects them in strict mode
+//! (`http.strictBodies`).
+
+use std::convert::Infallible;
+use std::fmt;
+
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use axum::http::HeaderValue;
+use axum::response::{IntoResponseParts, ResponseParts};
+use chrono::{DateTime, SecondsFormat};
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use taskcast_core::{Task, TaskEvent, Violation};
+
+use crate::error::AppError;
+use crate::strict::BodyStrictness;
+use crate::versioning::Presenter;
+
+/// Request header selecting how response timestamps are rendered, like the
+/// `timestampFormat` query parameter. The parameter wins when both are set.
+pub const TIMESTAMP_FORMAT_HEADER: &str = "X-Taskcast-Timestamp-Format";
+
+/// Response header carrying one warning about the request per value.
+pub const WARNING_HEADER: &str = "X-Taskcast-Warning";
+
+/// Epoch milliseconds below which a timestamp most likely is in seconds:
+/// 2001-09-09T01:46:40Z.
+pub const SECONDS_SCALE_BELOW_MS: f64 = 1e12;
+
+// ─── Parsing ────────────────────────────────────────────────────────────────
+
+/// Epoch milliseconds from a number or an RFC 3339 string. Non-finite
+/// numbers and unparseable strings give `None`.
+pub fn parse_timestamp(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Ok(millis) = value.parse::<f64>() {
+        return millis.is_finite().then_some(millis);
+    }
+    let parsed = DateTime::parse_from_rfc3339(value).ok()?;
+    Some(parsed.timestamp_micros() as f64 / 1000.0)
+}
+
+/// `millis` as an RFC 3339 UTC string with as many fractional digits as it
+/// needs, down to microseconds. `None` outside chrono's range.
+pub fn format_rfc3339(millis: f64) -> Option<String> {
+    if !millis.is_finite() {
+        return None;
+    }
+    let micros = (millis * 1000.0).round() as i64;
+    DateTime::from_timestamp_micros(micros).map(|t| t.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+/// Deserializes an optional timestamp given as epoch milliseconds or an
+/// RFC 3339 string, from JSON bodies and query strings alike. Use with
+/// `#[serde(default, deserialize_with = "timestamps::deserialize")]`.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<TimestampValue>::deserialize(deserializer)?.map(|t| t.0))
+}
+
+struct TimestampValue(f64);
+
+impl<'de> Deserialize<'de> for TimestampValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(TimestampVisitor)
+    }
+}
+
+struct TimestampVisitor;
+
+impl Visitor<'_> for TimestampVisitor {
+    type Value = TimestampValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("epoch milliseconds or an RFC 3339 timestamp")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        if value.is_finite() {
+            Ok(TimestampValue(value))
+        } else {
+            Err(E::invalid_value(de::Unexpected::Float(value), &self))
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(TimestampValue(value as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(TimestampValue(value as f64))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        parse_timestamp(value)
+            .map(TimestampValue)
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+// ─── Seconds-Scale Check ────────────────────────────────────────────────────
+
+/// Whether `millis` looks like a time in seconds: positive but before
+/// [`SECONDS_SCALE_BELOW_MS`]. Zero, as in `since.timestamp=0`, does not.
+pub fn is_seconds_scale(millis: f64) -> bool {
+    millis > 0.0 && millis < SECONDS_SCALE_BELOW_MS
+}
+
+fn seconds_scale_reason(millis: f64) -> String {
+    format!(
+        "{millis} is before 2001-09-09 in epoch milliseconds and looks like seconds; \
+         send milliseconds or an RFC 3339 string"
+    )
+}
+
+/// Checks request timestamps for seconds sent as milliseconds. Extracted
+/// from the request, it is strict when `http.strictBodies` is on.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimestampCheck {
+    strict: bool,
+}
+
+impl TimestampCheck {
+    pub fn new(strict: bool) -> Self {
+        Self { strict }
+    }
+
+    /// Checks query parameters by name. Strict mode rejects the first
+    /// seconds-scale one with `400 INVALID_QUERY`.
+    pub fn query(&self, params: &[(&str, Option<f64>)]) -> Result<TimestampWarnings, AppError> {
+        let mut warnings = TimestampWarnings::default();
+        for (param, millis) in params {
+            let Some(millis) = millis.filter(|m| is_seconds_scale(*m)) else {
+                continue;
+            };
+            let reason = seconds_scale_reason(millis);
+            if self.strict {
+                return Err(AppError::InvalidQuery {
+                    param: param.to_string(),
+                    reason,
+                });
+            }
+            warnings.0.push(format!("{param}: {reason}"));
+        }
+        Ok(warnings)
+    }
+
+    /// Checks body fields by JSON pointer. Strict mode rejects every
+    /// seconds-scale one with `400 INVALID_INPUT`.
+    pub fn body(&self, fields: &[(&str, Option<f64>)]) -> Result<TimestampWarnings, AppError> {
+        let violations: Vec<Violation> = fields
+            .iter()
+            .filter_map(|(pointer, millis)| {
+                let millis = millis.filter(|m| is_seconds_scale(*m))?;
+                Some(Violation::new(*pointer, seconds_scale_reason(millis)))
+            })
+            .collect();
+        if self.strict && !violations.is_empty() {
+            return Err(AppError::Validation(violations));
+        }
+        Ok(TimestampWarnings(
+            violations
+                .into_iter()
+                .map(|v| format!("{}: {}", v.pointer, v.message))
+                .collect(),
+        ))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for TimestampCheck {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let strictness = parts
+            .extensions
+            .get::<BodyStrictness>()
+            .copied()
+            .unwrap_or_default();
+        Ok(Self::new(strictness.strict))
+    }
+}
+
+/// Warnings about a request's timestamps, each sent as a
+/// [`WARNING_HEADER`] on the response.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimestampWarnings(pub Vec<String>);
+
+impl IntoResponseParts for TimestampWarnings {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        for warning in self.0 {
+            if let Ok(value) = HeaderValue::from_str(&warning) {
+                res.headers_mut().append(WARNING_HEADER, value);
+            }
+        }
+        Ok(res)
+    }
+}
+
+// ─── Output Format ──────────────────────────────────────────────────────────
+
+/// How a response renders task and event times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Epoch milliseconds, as stored.
+    #[default]
+    EpochMillis,
+    /// RFC 3339 UTC strings.
+    Rfc3339,
+}
+
+impl TimestampFormat {
+    /// The format `parts` asks for with `timestampFormat` or
+    /// [`TIMESTAMP_FORMAT_HEADER`]: `epochMillis` or `rfc3339`.
+    pub fn from_parts(parts: &Parts) -> Result<Self, AppError> {
+        let from_query = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(pairs)| {
+                pairs
+                    .into_iter()
+                    .find(|(name, _)| name == "timestampFormat")
+                    .map(|(_, value)| ("timestampFormat", value))
+            });
+        let from_header = || {
+            parts.headers.get(TIMESTAMP_FORMAT_HEADER).map(|value| {
+                (
+                    TIMESTAMP_FORMAT_HEADER,
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+        };
+        let Some((param, value)) = from_query.or_else(from_header) else {
+            return Ok(Self::default());
+        };
+        match value.trim() {
+            "epochMillis" => Ok(Self::EpochMillis),
+            "rfc3339" => Ok(Self::Rfc3339),
+            other => Err(AppError::InvalidQuery {
+                param: param.to_string(),
+                reason: format!("expected `epochMillis` or `rfc3339`, got {other:?}"),
+            }),
+        }
+    }
+}
+
+/// Task fields holding epoch milliseconds.
+const TASK_TIME_FIELDS: &[&str] = &[
+    "createdAt",
+    "updatedAt",
+    "completedAt",
+    "resumeAt",
+    "scheduledFor",
+];
+
+/// Event fields holding epoch milliseconds.
+const EVENT_TIME_FIELDS: &[&str] = &["timestamp"];
+
+/// Wraps a version's presenter to render times as RFC 3339 strings. Lists
+/// and errors are left to the inner presenter.
+pub struct Rfc3339Presenter<P: ?Sized>(pub std::sync::Arc<P>);
+
+impl<P: Presenter + ?Sized> Presenter for Rfc3339Presenter<P> {
+    fn task(&self, task: &Task) -> Value {
+        rfc3339_fields(self.0.task(task), TASK_TIME_FIELDS)
+    }
+
+    fn event(&self, event: &TaskEvent) -> Value {
+        rfc3339_fields(self.0.event(event), EVENT_TIME_FIELDS)
+    }
+
+    fn task_list(&self, tasks: Vec<Value>, next_cursor: Option<String>) -> Value {
+        self.0.task_list(tasks, next_cursor)
+    }
+
+    fn event_list(&self, events: Vec<Value>) -> Value {
+        self.0.event_list(events)
+    }
+
+    fn error(&self, body: Value) -> Value {
+        self.0.error(body)
+    }
+}
+
+fn rfc3339_fields(mut value: Value, fields: &[&str]) -> Value {
+    if let Some(object) = value.as_object_mut() {
+        for field in fields {
+            let formatted = object
+                .get(*field)
+                .and_then(Value::as_f64)
+                .and_then(format_rfc3339);
+            if let Some(formatted) = formatted {
+                object.insert(field.to_string(), Value::String(formatted));
+            }
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_and_rfc3339_parse_to_the_same_millis() {
+        assert_eq!(parse_timestamp("1767225600123"), Some(1767225600123.0));
+        assert_eq!(
+            parse_timestamp("2026-01-01T00:00:00.123Z"),
+            Some(1767225600123.0)
+        );
+        assert_eq!(
+            parse_timestamp("2026-01-01T01:00:00.123+01:00"),
+            Some(1767225600123.0)
+        );
+        assert_eq!(parse_timestamp("999.5"), Some(999.5));
+        assert_eq!(parse_timestamp("soon"), None);
+        assert_eq!(parse_timestamp("NaN"), None);
+        assert_eq!(parse_timestamp("2026-01-01"), None);
+    }
+
+    #[test]
+    fn formatting_round_trips() {
+        for millis in [1767225600000.0, 1767225600123.0, 1767225600123.456, 0.0] {
+            let formatted = format_rfc3339(millis).unwrap();
+            assert_eq!(parse_timestamp(&formatted), Some(millis), "{formatted}");
+        }
+        assert_eq!(
+            format_rfc3339(1767225600123.0).as_deref(),
+            Some("2026-01-01T00:00:00.123Z")
+        );
+        assert_eq!(
+            format_rfc3339(1767225600000.0).as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(format_rfc3339(f64::NAN), None);
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Body {
+        #[serde(default, deserialize_with = "deserialize")]
+        scheduled_for: Option<f64>,
+    }
+
+    #[test]
+    fn deserializes_json_numbers_and_strings() {
+        let parse = |body: Value| serde_json::from_value::<Body>(body).map(|b| b.scheduled_for);
+        let expected = Some(1767225600123.0);
+        assert_eq!(
+            parse(serde_json::json!({ "scheduledFor": 1767225600123u64 })).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "scheduledFor": 1767225600123.0 })).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "scheduledFor": "2026-01-01T00:00:00.123Z" })).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse(serde_json::json!({ "scheduledFor": null })).unwrap(),
+            None
+        );
+        assert_eq!(parse(serde_json::json!({})).unwrap(), None);
+        assert!(parse(serde_json::json!({ "scheduledFor": "tomorrow" })).is_err());
+        assert!(parse(serde_json::json!({ "scheduledFor": true })).is_err());
+    }
+
+    #[test]
+    fn seconds_scale_values_warn_or_fail_in_strict_mode() {
+        assert!(is_seconds_scale(1767225600.0));
+        assert!(!is_seconds_scale(1767225600000.0));
+        assert!(!is_seconds_scale(0.0));
+
+        let lenient = TimestampCheck::new(false);
+        let warnings = lenient
+            .query(&[
+                ("asOf", Some(1767225600.0)),
+                ("until", Some(1767225600000.0)),
+            ])
+            .unwrap();
+        assert_eq!(warnings.0.len(), 1);
+        assert!(warnings.0[0].starts_with("asOf: 1767225600 is before 2001-09-09"));
+        assert_eq!(
+            lenient.body(&[("/scheduledFor", None)]).unwrap(),
+            TimestampWarnings::default()
+        );
+
+        let strict = TimestampCheck::new(true);
+        assert!(matches!(
+            strict.query(&[("asOf", Some(1767225600.0))]),
+            Err(AppError::InvalidQuery { param, .. }) if param == "asOf"
+        ));
+        assert!(matches!(
+            strict.body(&[("/scheduledFor", Some(1767225600.0))]),
+            Err(AppError::Validation(violations)) if violations[0].pointer == "/scheduledFor"
+        ));
+        assert!(strict
+            .query(&[("asOf", Some(1767225600000.0))])
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn rfc3339_presenter_rewrites_time_fields_only() {
+        let value = serde_json::json!({
+            "createdAt": 1767225600123.0,
+            "completedAt": null,
+            "ttl": 60,
+        });
+        assert_eq!(
+            rfc3339_fields(value, TASK_TIME_FIELDS),
+            serde_json::json!({
+                "createdAt": "2026-01-01T00:00:00.123Z",
+                "completedAt": null,
+                "ttl": 60,
+            })
+        );
+    }
+}
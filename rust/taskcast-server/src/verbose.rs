@@ -67,14 +67,14 @@ pub async fn verbose_logger_middleware(
     // Capture body for POST/PATCH/PUT unless Content-Length explicitly exceeds the limit.
     // When Content-Length is missing (e.g. chunked transfer), we still attempt capture
     // with to_bytes' built-in limit to avoid breaking body logging for normal requests.
-    let should_capture_body = matches!(method.as_str(), "POST" | "PATCH" | "PUT") && !body_too_large;
+    let should_capture_body =
+        matches!(method.as_str(), "POST" | "PATCH" | "PUT") && !body_too_large;
 
     let (request_body, req) = if should_capture_body {
         let (parts, body) = req.into_parts();
         match axum::body::to_bytes(body, MAX_BODY_SIZE).await {
             Ok(bytes) => {
-                let parsed: Option<serde_json::Value> =
-                    serde_json::from_slice(&bytes).ok();
+                let parsed: Option<serde_json::Value> = serde_json::from_slice(&bytes).ok();
                 let req = Request::from_parts(parts, axum::body::Body::from(bytes));
                 (parsed, req)
             }
@@ -103,8 +103,13 @@ pub async fn verbose_logger_middleware(
         status.to_string()
     };
 
-    let context =
-        extract_context(&method, &path, status, request_body.as_ref(), body_too_large);
+    let context = extract_context(
+        &method,
+        &path,
+        status,
+        request_body.as_ref(),
+        body_too_large,
+    );
 
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S");
 
@@ -0,0 +1,310 @@
+//! API versions and the presenters that render their response bodies.
+//!
+//! Every API route is served under `/v1`, and at its unprefixed path as a
+//! deprecated alias that answers with `Deprecation` (and, when configured,
+//! `Sunset`) headers. Each response carries the version it was served by in
+//! [`API_VERSION_HEADER`]; routes outside the API (health, docs) report v1.
+//!
+//! The API router is built once and mounted per version with that version's
+//! [`Presenter`]. Handlers render tasks, events and lists through it, and
+//! [`error_response_middleware`](crate::error::error_response_middleware)
+//! renders error bodies through it, so a later version can change response
+//! shapes without forking handlers. The v1 shapes are frozen; rendering
+//! timestamps as RFC 3339 is opt-in per request (see
+//! [`timestamps`](crate::timestamps)).
+
+use std::sync::Arc;
+
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{NaiveDate, TimeZone, Utc};
+use serde_json::{json, Value};
+use taskcast_core::config::ApiConfig;
+use taskcast_core::{Task, TaskEvent};
+
+use crate::error::AppError;
+use crate::timestamps::{Rfc3339Presenter, TimestampFormat};
+
+/// Response header naming the API version that served the request.
+pub const API_VERSION_HEADER: &str = "x-taskcast-api-version";
+
+/// Name of the first API version, which the unprefixed paths alias.
+pub const API_V1: &str = "v1";
+
+// ─── Presenter ──────────────────────────────────────────────────────────────
+
+/// Renders task, event, list and error bodies for one API version. The
+/// default methods render the v1 shapes, so a presenter only overrides what
+/// its version changes.
+pub trait Presenter: Send + Sync {
+    fn task(&self, task: &Task) -> Value {
+        serde_json::to_value(task).unwrap()
+    }
+
+    fn event(&self, event: &TaskEvent) -> Value {
+        serde_json::to_value(event).unwrap()
+    }
+
+    /// The body of `GET /tasks`, from tasks already rendered by
+    /// [`task`](Self::task) and the cursor of the next page, if any.
+    fn task_list(&self, tasks: Vec<Value>, next_cursor: Option<String>) -> Value {
+        json!({ "tasks": tasks, "nextCursor": next_cursor })
+    }
+
+    /// The body of `GET /tasks/{id}/events/history`, from events already
+    /// rendered by [`event`](Self::event).
+    fn event_list(&self, events: Vec<Value>) -> Value {
+        Value::Array(events)
+    }
+
+    /// An error body as built by
+    /// [`ErrorPayload::to_json`](crate::error::ErrorPayload::to_json).
+    fn error(&self, body: Value) -> Value {
+        body
+    }
+}
+
+/// The v1 shapes: tasks and events as serialized by `taskcast-core`, with
+/// millisecond timestamps as floats.
+pub struct V1Presenter;
+
+impl Presenter for V1Presenter {}
+
+// ─── Versions ───────────────────────────────────────────────────────────────
+
+/// An API version: the path prefix it is mounted under and its presenter.
+/// Handlers take it as an extractor.
+#[derive(Clone)]
+pub struct ApiVersion {
+    pub name: &'static str,
+    pub presenter: Arc<dyn Presenter>,
+}
+
+impl ApiVersion {
+    pub fn new(name: &'static str, presenter: Arc<dyn Presenter>) -> Self {
+        Self { name, presenter }
+    }
+
+    pub fn v1() -> Self {
+        Self::new(API_V1, Arc::new(V1Presenter))
+    }
+}
+
+/// The version the request was routed through, or v1 for handlers mounted
+/// outside a versioned router. When the request asks for RFC 3339
+/// timestamps, its presenter renders them so.
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let version = parts
+            .extensions
+            .get::<ApiVersion>()
+            .cloned()
+            .unwrap_or_else(ApiVersion::v1);
+        Ok(match TimestampFormat::from_parts(parts)? {
+            TimestampFormat::EpochMillis => version,
+            TimestampFormat::Rfc3339 => Self {
+                presenter: Arc::new(Rfc3339Presenter(version.presenter)),
+                ..version
+            },
+        })
+    }
+}
+
+/// The API versions an app mounts, and how the unprefixed v1 aliases are
+/// deprecated.
+#[derive(Clone)]
+pub struct ApiVersions {
+    /// Each is mounted at `/{name}`. v1 is always mounted.
+    pub versions: Vec<ApiVersion>,
+    pub legacy: LegacyPaths,
+}
+
+impl Default for ApiVersions {
+    fn default() -> Self {
+        Self {
+            versions: vec![ApiVersion::v1()],
+            legacy: LegacyPaths::default(),
+        }
+    }
+}
+
+impl ApiVersions {
+    pub fn from_config(config: Option<&ApiConfig>) -> Self {
+        Self {
+            legacy: LegacyPaths::from_config(config),
+            ..Self::default()
+        }
+    }
+
+    /// Mounts `version` as well, replacing one of the same name.
+    pub fn with_version(mut self, version: ApiVersion) -> Self {
+        self.versions.retain(|v| v.name != version.name);
+        self.versions.push(version);
+        self
+    }
+}
+
+/// When the unprefixed paths were deprecated and when they will be removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LegacyPaths {
+    /// Sent as `Deprecation: @{seconds}`; `Deprecation: true` when unset.
+    pub deprecated_at: Option<NaiveDate>,
+    /// Sent as `Sunset` when set.
+    pub sunset_at: Option<NaiveDate>,
+}
+
+impl LegacyPaths {
+    /// Dates are `YYYY-MM-DD`, already checked by config validation.
+    pub fn from_config(config: Option<&ApiConfig>) -> Self {
+        let date = |value: Option<&String>| {
+            value.and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        };
+        Self {
+            deprecated_at: date(config.and_then(|c| c.legacy_deprecated_at.as_ref())),
+            sunset_at: date(config.and_then(|c| c.legacy_sunset_at.as_ref())),
+        }
+    }
+
+    fn deprecation_header(&self) -> String {
+        match self.deprecated_at {
+            Some(date) => format!("@{}", midnight_utc(date).timestamp()),
+            None => "true".to_string(),
+        }
+    }
+
+    fn sunset_header(&self) -> Option<String> {
+        self.sunset_at.map(|date| {
+            midnight_utc(date)
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string()
+        })
+    }
+}
+
+fn midnight_utc(date: NaiveDate) -> chrono::DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// `path` without a leading `/v{n}` version segment.
+pub(crate) fn unversioned_path(path: &str) -> &str {
+    let Some(rest) = path.strip_prefix("/v") else {
+        return path;
+    };
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let tail = &rest[digits..];
+    if digits > 0 && (tail.is_empty() || tail.starts_with('/')) {
+        tail
+    } else {
+        path
+    }
+}
+
+// ─── Middleware ─────────────────────────────────────────────────────────────
+
+/// Makes `version` available to handlers and the error middleware, and names
+/// it in the response's [`API_VERSION_HEADER`].
+pub async fn api_version_middleware(
+    State(version): State<ApiVersion>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    request.extensions_mut().insert(version.clone());
+    let mut response = next.run(request).await;
+    response.headers_mut().insert(
+        HeaderName::from_static(API_VERSION_HEADER),
+        HeaderValue::from_static(version.name),
+    );
+    response.extensions_mut().insert(version);
+    response
+}
+
+/// Marks a response from an unprefixed path as deprecated and links the
+/// `/v1` path that replaces it.
+pub async fn legacy_path_middleware(
+    State(legacy): State<Arc<LegacyPaths>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let successor = format!(
+        "</{API_V1}{}>; rel=\"successor-version\"",
+        request.uri().path()
+    );
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&legacy.deprecation_header()) {
+        headers.insert("deprecation", value);
+    }
+    if let Some(value) = legacy
+        .sunset_header()
+        .and_then(|sunset| HeaderValue::from_str(&sunset).ok())
+    {
+        headers.insert("sunset", value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&successor) {
+        headers.append("link", value);
+    }
+    response
+}
+
+/// Names v1 on responses no API version served, such as health checks.
+pub async fn default_api_version_middleware(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .entry(HeaderName::from_static(API_VERSION_HEADER))
+        .or_insert(HeaderValue::from_static(API_V1));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_headers_use_structured_and_http_dates() {
+        let legacy = LegacyPaths {
+            deprecated_at: NaiveDate::from_ymd_opt(2026, 1, 1),
+            sunset_at: NaiveDate::from_ymd_opt(2027, 6, 30),
+        };
+        assert_eq!(legacy.deprecation_header(), "@1767225600");
+        assert_eq!(
+            legacy.sunset_header().as_deref(),
+            Some("Wed, 30 Jun 2027 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn legacy_headers_without_dates() {
+        let legacy = LegacyPaths::default();
+        assert_eq!(legacy.deprecation_header(), "true");
+        assert_eq!(legacy.sunset_header(), None);
+    }
+
+    #[test]
+    fn version_prefixes_are_stripped() {
+        assert_eq!(unversioned_path("/v1/tasks/t1"), "/tasks/t1");
+        assert_eq!(unversioned_path("/v12/admin"), "/admin");
+        assert_eq!(unversioned_path("/tasks/v1"), "/tasks/v1");
+        assert_eq!(unversioned_path("/view"), "/view");
+    }
+
+    #[test]
+    fn configured_dates_are_parsed() {
+        let config = ApiConfig {
+            legacy_deprecated_at: Some("2026-01-01".to_string()),
+            legacy_sunset_at: Some("2027-06-30".to_string()),
+        };
+        assert_eq!(
+            LegacyPaths::from_config(Some(&config)),
+            LegacyPaths {
+                deprecated_at: NaiveDate::from_ymd_opt(2026, 1, 1),
+                sunset_at: NaiveDate::from_ymd_opt(2027, 6, 30),
+            }
+        );
+    }
+}
@@ -1,15 +1,44 @@
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use hmac::{Hmac, Mac};
+use serde::Serialize;
 use sha2::Sha256;
-use taskcast_core::{matches_filter, BackoffStrategy, RetryConfig, TaskEvent, WebhookConfig};
+use taskcast_core::config::WebhookGlobalConfig;
+use taskcast_core::{
+    compute_backoff, filter_hash, is_control_event, matches_filter, matches_filter_for_task,
+    matches_task, resolve_filter, series_view_data, BackoffStrategy, Clock, EngineError,
+    EventQueryOptions, FilterPresetError, FilteredIndexClaim, LifecycleListener, RetryConfig, RetryJitter, ShortTermStore, SubscribeFilter,
+    SystemClock, Task, TaskEngine, TaskEvent, TaskcastHooks, WebhookBackfill, WebhookConfig, WebhookGroupPolicy,
+    WebhookMode,
+};
+use tokio::sync::{
+    Mutex as AsyncMutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore, SemaphorePermit,
+};
+
+use crate::event_links::EventLinks;
 
 // ─── Error ──────────────────────────────────────────────────────────────────
 
 #[derive(Debug, thiserror::Error)]
 pub enum WebhookError {
+    /// `code` is the machine-readable error code from the receiver's last
+    /// response body (`{"code": ...}`), when it sent one.
     #[error("Webhook delivery failed after {attempts} attempts: {message}")]
-    DeliveryFailed { attempts: u32, message: String },
+    DeliveryFailed {
+        attempts: u32,
+        message: String,
+        code: Option<String>,
+    },
+
+    /// The target's circuit is open; no request was sent.
+    #[error("circuit-open: deliveries to {host} are suspended")]
+    CircuitOpen { host: String },
+
+    /// `filterPreset` does not resolve against the presets passed in.
+    #[error("{0}")]
+    FilterPreset(#[from] FilterPresetError),
 }
 
 // ─── Default Retry Config ───────────────────────────────────────────────────
@@ -21,6 +50,8 @@ fn default_retry() -> RetryConfig {
         initial_delay_ms: 1000,
         max_delay_ms: 30000,
         timeout_ms: 5000,
+        jitter: RetryJitter::None,
+        max_elapsed_ms: None,
     }
 }
 
@@ -31,16 +62,466 @@ fn merge_retry(config_retry: Option<&RetryConfig>) -> RetryConfig {
     }
 }
 
+// ─── Circuit Breaker ────────────────────────────────────────────────────────
+
+/// Per-target circuit breaker settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed deliveries (after retries) that open the circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit fails fast before a half-open probe.
+    pub cooldown_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    Open,
+    /// A single probe delivery is in flight; its outcome closes or reopens
+    /// the circuit.
+    HalfOpen,
+}
+
+/// Snapshot of a tripped circuit, as listed by `GET /admin/webhooks/circuits`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitStatus {
+    pub host: String,
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Unix milliseconds when the circuit last opened (or the probe started).
+    pub opened_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    /// Set while the circuit is open or half-open.
+    opened: Option<Opened>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Opened {
+    at: Instant,
+    at_ms: u64,
+    probing: bool,
+}
+
+impl Opened {
+    fn now(probing: bool) -> Self {
+        Self {
+            at: Instant::now(),
+            at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            probing,
+        }
+    }
+}
+
+/// Whether a delivery may proceed, and if so whether it is the half-open probe.
+enum Admission {
+    Closed,
+    Probe,
+    Rejected,
+}
+
+/// Circuits are keyed by `host[:port]`, falling back to the full URL when it
+/// does not parse.
+fn circuit_key(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+// ─── Concurrency ────────────────────────────────────────────────────────────
+
+pub const DEFAULT_MAX_CONCURRENT_DELIVERIES: usize = 64;
+pub const DEFAULT_MAX_CONCURRENT_PER_TASK: usize = 8;
+pub const DEFAULT_SATURATION_ALERT_MS: u64 = 30_000;
+
+/// Caps on background delivery requests in flight. A permit is held for a
+/// single attempt, so a retry waiting out its backoff holds none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryLimits {
+    /// Requests in flight across all tasks.
+    pub max_concurrent: usize,
+    /// Requests in flight for any one task, so a noisy task leaves permits
+    /// for the others.
+    pub max_per_task: usize,
+    /// How long every permit may stay taken before
+    /// `on_webhook_saturated` fires.
+    pub saturation_alert: Duration,
+}
+
+impl Default for DeliveryLimits {
+    fn default() -> Self {
+        Self::from_config(None)
+    }
+}
+
+impl DeliveryLimits {
+    pub fn from_config(config: Option<&WebhookGlobalConfig>) -> Self {
+        Self {
+            max_concurrent: config
+                .and_then(|c| c.max_concurrent_deliveries)
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_DELIVERIES),
+            max_per_task: config
+                .and_then(|c| c.max_concurrent_per_task)
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_PER_TASK),
+            saturation_alert: Duration::from_millis(
+                config
+                    .and_then(|c| c.saturation_alert_ms)
+                    .unwrap_or(DEFAULT_SATURATION_ALERT_MS),
+            ),
+        }
+    }
+}
+
+/// Permit use, as listed by `GET /admin/webhooks/concurrency`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryConcurrency {
+    pub max_concurrent: usize,
+    pub max_per_task: usize,
+    pub in_use: usize,
+    /// Deliveries waiting for a global or per-task permit.
+    pub queued: usize,
+    /// Permits handed out since startup.
+    pub acquired: u64,
+    /// Time acquisitions spent waiting, in total and at most.
+    pub wait_ms_total: f64,
+    pub wait_ms_max: f64,
+    /// Unix milliseconds since every permit has been taken.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturated_since: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    queued: usize,
+    acquired: u64,
+    wait_total: Duration,
+    wait_max: Duration,
+    /// Set while no permit is free.
+    saturated: Option<Saturation>,
+}
+
+/// A task's semaphore and the deliveries holding or waiting for it.
+#[derive(Debug)]
+struct TaskPermits {
+    semaphore: Arc<Semaphore>,
+    users: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Saturation {
+    at: Instant,
+    at_ms: u64,
+    reported: bool,
+}
+
+/// Permits of one delivery attempt, returned when it is dropped. Dropped
+/// while still waiting, it gives up its place in the queue.
+struct DeliveryPermit<'a> {
+    delivery: &'a WebhookDelivery,
+    task_id: &'a str,
+    task: Option<OwnedSemaphorePermit>,
+    global: Option<SemaphorePermit<'a>>,
+    queued: bool,
+}
+
+impl Drop for DeliveryPermit<'_> {
+    fn drop(&mut self) {
+        if self.queued {
+            self.delivery.limiter.lock().unwrap().queued -= 1;
+        }
+        drop(self.task.take());
+        let mut per_task = self.delivery.per_task.lock().unwrap();
+        if let Some(permits) = per_task.get_mut(self.task_id) {
+            permits.users -= 1;
+            if permits.users == 0 {
+                per_task.remove(self.task_id);
+            }
+        }
+        drop(per_task);
+        let Some(global) = self.global.take() else {
+            return;
+        };
+        // A released permit goes straight to the next waiter, so one stays
+        // free only once nothing is queued.
+        drop(global);
+        if self.delivery.global.available_permits() > 0 {
+            self.delivery.limiter.lock().unwrap().saturated = None;
+        } else {
+            self.delivery.check_saturation();
+        }
+    }
+}
+
 // ─── WebhookDelivery ────────────────────────────────────────────────────────
 
+/// Delivers webhook events with retries and a per-target circuit breaker.
+///
+/// Breaker state is held in memory, so each server process trips and
+/// recovers its circuits independently.
+///
+/// An event delivery whose body is larger than the webhook's
+/// `maxPayloadBytes` (or the default set with
+/// [`with_payload_limits`](Self::with_payload_limits)) is sent with `data`
+/// replaced by `{"$truncated": true, "bytes": n, "fetchUrl": ...}`, where
+/// `n` is the size of the serialized `data` and `fetchUrl` is a signed link
+/// to the full event. `fetchUrl` is left out when there is no
+/// [`EventLinks`] base URL to build it on. Snapshots are never trimmed.
+///
+/// Each attempt at a background delivery first takes a permit under its
+/// [`DeliveryLimits`]; `sync` webhooks, already capped per task and bounded
+/// by their timeout, do not queue behind them.
 pub struct WebhookDelivery {
     client: reqwest::Client,
+    breaker: CircuitBreakerConfig,
+    circuits: Mutex<HashMap<String, Circuit>>,
+    limits: DeliveryLimits,
+    global: Semaphore,
+    per_task: Mutex<HashMap<String, TaskPermits>>,
+    limiter: Mutex<LimiterState>,
+    default_max_payload_bytes: Option<usize>,
+    event_links: Option<Arc<EventLinks>>,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+    /// Jitter for retry delays.
+    rng: Mutex<fastrand::Rng>,
 }
 
 impl WebhookDelivery {
     pub fn new() -> Self {
+        Self::with_circuit_breaker(CircuitBreakerConfig::default())
+    }
+
+    pub fn with_circuit_breaker(breaker: CircuitBreakerConfig) -> Self {
+        let limits = DeliveryLimits::default();
         Self {
             client: reqwest::Client::new(),
+            breaker,
+            circuits: Mutex::new(HashMap::new()),
+            global: Semaphore::new(limits.max_concurrent),
+            limits,
+            per_task: Mutex::new(HashMap::new()),
+            limiter: Mutex::new(LimiterState::default()),
+            default_max_payload_bytes: None,
+            event_links: None,
+            hooks: None,
+            rng: Mutex::new(fastrand::Rng::new()),
+        }
+    }
+
+    /// Reports every delivery attempt to `hooks.on_webhook_attempt`.
+    pub fn with_hooks(mut self, hooks: Arc<dyn TaskcastHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Trims deliveries over `default_max_bytes` for webhooks without their
+    /// own `maxPayloadBytes`, pointing receivers at `links` for the full
+    /// event.
+    pub fn with_payload_limits(
+        mut self,
+        default_max_bytes: Option<usize>,
+        links: Arc<EventLinks>,
+    ) -> Self {
+        self.default_max_payload_bytes = default_max_bytes;
+        self.event_links = Some(links);
+        self
+    }
+
+    /// Caps background deliveries at `limits` instead of the defaults.
+    /// Limits of zero are raised to one.
+    pub fn with_limits(mut self, mut limits: DeliveryLimits) -> Self {
+        limits.max_concurrent = limits.max_concurrent.max(1);
+        limits.max_per_task = limits.max_per_task.max(1);
+        self.global = Semaphore::new(limits.max_concurrent);
+        self.limits = limits;
+        self
+    }
+
+    /// Current permit use of background deliveries.
+    pub fn concurrency(&self) -> DeliveryConcurrency {
+        let state = self.limiter.lock().unwrap();
+        DeliveryConcurrency {
+            max_concurrent: self.limits.max_concurrent,
+            max_per_task: self.limits.max_per_task,
+            in_use: self.limits.max_concurrent - self.global.available_permits(),
+            queued: state.queued,
+            acquired: state.acquired,
+            wait_ms_total: state.wait_total.as_secs_f64() * 1000.0,
+            wait_ms_max: state.wait_max.as_secs_f64() * 1000.0,
+            saturated_since: state.saturated.map(|saturation| saturation.at_ms),
+        }
+    }
+
+    /// Waits for a permit of `task_id`'s own and then a global one. Queuing
+    /// on the task's permits first keeps one task's backlog from filling
+    /// the global queue ahead of other tasks.
+    async fn acquire<'a>(&'a self, task_id: &'a str) -> DeliveryPermit<'a> {
+        let started = Instant::now();
+        let semaphore = {
+            let mut per_task = self.per_task.lock().unwrap();
+            let permits = per_task
+                .entry(task_id.to_string())
+                .or_insert_with(|| TaskPermits {
+                    semaphore: Arc::new(Semaphore::new(self.limits.max_per_task)),
+                    users: 0,
+                });
+            permits.users += 1;
+            Arc::clone(&permits.semaphore)
+        };
+        self.limiter.lock().unwrap().queued += 1;
+        let mut permit = DeliveryPermit {
+            delivery: self,
+            task_id,
+            task: None,
+            global: None,
+            queued: true,
+        };
+        self.check_saturation();
+
+        permit.task = Some(
+            semaphore
+                .acquire_owned()
+                .await
+                .expect("delivery semaphores are never closed"),
+        );
+        permit.global = Some(
+            self.global
+                .acquire()
+                .await
+                .expect("delivery semaphores are never closed"),
+        );
+        permit.queued = false;
+
+        let waited = started.elapsed();
+        let mut state = self.limiter.lock().unwrap();
+        state.queued -= 1;
+        state.acquired += 1;
+        state.wait_total += waited;
+        state.wait_max = state.wait_max.max(waited);
+        if self.global.available_permits() == 0 && state.saturated.is_none() {
+            state.saturated = Some(Saturation {
+                at: Instant::now(),
+                at_ms: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64,
+                reported: false,
+            });
+        }
+        drop(state);
+        permit
+    }
+
+    /// Reports, once, a saturation lasting past the alert threshold. Checked
+    /// as deliveries queue up and finish.
+    fn check_saturation(&self) {
+        let (queued, saturated_for) = {
+            let mut state = self.limiter.lock().unwrap();
+            let queued = state.queued;
+            match state.saturated {
+                Some(ref mut saturation)
+                    if !saturation.reported
+                        && saturation.at.elapsed() >= self.limits.saturation_alert =>
+                {
+                    saturation.reported = true;
+                    (queued, saturation.at.elapsed())
+                }
+                _ => return,
+            }
+        };
+        let in_use = self.limits.max_concurrent - self.global.available_permits();
+        match self.hooks {
+            Some(ref hooks) => hooks.on_webhook_saturated(in_use, queued, saturated_for),
+            None => eprintln!(
+                "[taskcast] All {in_use} webhook delivery permits taken for {} ms with {queued} deliveries queued; raise webhook.maxConcurrentDeliveries or check for slow receivers",
+                saturated_for.as_millis()
+            ),
+        }
+    }
+
+    /// Circuits that are currently open or half-open, sorted by host.
+    pub fn open_circuits(&self) -> Vec<CircuitStatus> {
+        let circuits = self.circuits.lock().unwrap();
+        let mut open: Vec<CircuitStatus> = circuits
+            .iter()
+            .filter_map(|(host, circuit)| {
+                let opened = circuit.opened?;
+                Some(CircuitStatus {
+                    host: host.clone(),
+                    state: if opened.probing {
+                        CircuitState::HalfOpen
+                    } else {
+                        CircuitState::Open
+                    },
+                    consecutive_failures: circuit.consecutive_failures,
+                    opened_at: opened.at_ms,
+                })
+            })
+            .collect();
+        open.sort_by(|a, b| a.host.cmp(&b.host));
+        open
+    }
+
+    /// Closes the circuit for `host`. Returns `false` if it was not tripped.
+    pub fn reset_circuit(&self, host: &str) -> bool {
+        let mut circuits = self.circuits.lock().unwrap();
+        circuits
+            .remove(host)
+            .is_some_and(|circuit| circuit.opened.is_some())
+    }
+
+    fn admit(&self, host: &str) -> Admission {
+        let mut circuits = self.circuits.lock().unwrap();
+        let Some(circuit) = circuits.get_mut(host) else {
+            return Admission::Closed;
+        };
+        match circuit.opened {
+            None => Admission::Closed,
+            Some(opened)
+                if opened.at.elapsed() < Duration::from_millis(self.breaker.cooldown_ms) =>
+            {
+                Admission::Rejected
+            }
+            // Restarting the clock means a probe that never reports back
+            // (e.g. its task was dropped) is retried after another cooldown.
+            Some(_) => {
+                circuit.opened = Some(Opened::now(true));
+                Admission::Probe
+            }
+        }
+    }
+
+    fn record_success(&self, host: &str) {
+        self.circuits.lock().unwrap().remove(host);
+    }
+
+    fn record_failure(&self, host: &str, probe: bool) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(host.to_string()).or_default();
+        circuit.consecutive_failures += 1;
+        if probe || circuit.consecutive_failures >= self.breaker.failure_threshold {
+            circuit.opened = Some(Opened::now(false));
         }
     }
 
@@ -49,15 +530,127 @@ impl WebhookDelivery {
         event: &TaskEvent,
         config: &WebhookConfig,
     ) -> Result<(), WebhookError> {
-        // Check filter
-        if let Some(ref filter) = config.filter {
-            if !matches_filter(event, filter) {
-                return Ok(());
-            }
+        self.send_with_presets(event, config, None).await
+    }
+
+    /// Like [`send`](Self::send), resolving `config.filter_preset` against
+    /// the owning task's `filters`. Without the task at hand, `task_match`
+    /// conditions are not checked.
+    pub async fn send_with_presets(
+        &self,
+        event: &TaskEvent,
+        config: &WebhookConfig,
+        presets: Option<&HashMap<String, SubscribeFilter>>,
+    ) -> Result<(), WebhookError> {
+        let filter = webhook_filter(presets, config)?;
+        if filter.as_ref().is_some_and(|f| !matches_filter(event, f)) {
+            return Ok(());
         }
+        let payload = webhook_payload(event, filter.as_ref());
+        self.deliver(&payload, config, None).await
+    }
 
+    /// Delivers `event` to `config.url` without consulting its filter.
+    async fn deliver(
+        &self,
+        event: &TaskEvent,
+        config: &WebhookConfig,
+        position: Option<FilteredIndexClaim>,
+    ) -> Result<(), WebhookError> {
         let retry = merge_retry(config.retry.as_ref());
-        let body = serde_json::to_string(event).unwrap();
+        self.deliver_with_retry(event, config, retry, false, position)
+            .await
+    }
+
+    /// `backfill` marks a replayed event with `X-Taskcast-Backfill: true`.
+    async fn deliver_with_retry(
+        &self,
+        event: &TaskEvent,
+        config: &WebhookConfig,
+        retry: RetryConfig,
+        backfill: bool,
+        position: Option<FilteredIndexClaim>,
+    ) -> Result<(), WebhookError> {
+        let body = self.event_body(event, config, position);
+        self.post_with_retry(body, &event.r#type, &event.task_id, config, retry, backfill)
+            .await
+    }
+
+    /// `event` serialized for `config` with its `position` in the webhook's
+    /// persisted numbering, trimmed if it is over the webhook's payload
+    /// limit.
+    fn event_body(
+        &self,
+        event: &TaskEvent,
+        config: &WebhookConfig,
+        position: Option<FilteredIndexClaim>,
+    ) -> String {
+        let body = positioned_body(event, position);
+        let limit = config.max_payload_bytes.or(self.default_max_payload_bytes);
+        if limit.is_none_or(|limit| body.len() <= limit) {
+            return body;
+        }
+        let mut truncated = serde_json::json!({
+            "$truncated": true,
+            "bytes": serde_json::to_string(&event.data).unwrap().len(),
+        });
+        if let Some(url) = self
+            .event_links
+            .as_ref()
+            .and_then(|links| links.fetch_url(&event.task_id, &event.id))
+        {
+            truncated["fetchUrl"] = serde_json::Value::String(url);
+        }
+        positioned_body(
+            &TaskEvent {
+                data: truncated,
+                ..event.clone()
+            },
+            position,
+        )
+    }
+
+    /// Posts `task`'s `task.snapshot` to `config.url`.
+    async fn deliver_snapshot(
+        &self,
+        task: &Task,
+        last_event_index: Option<u64>,
+        config: &WebhookConfig,
+        retry: RetryConfig,
+    ) -> Result<(), WebhookError> {
+        let snapshot = TaskSnapshot {
+            kind: SNAPSHOT_KIND,
+            task,
+            last_event_index,
+        };
+        let body = serde_json::to_string(&snapshot).unwrap();
+        self.post_with_retry(body, SNAPSHOT_KIND, &task.id, config, retry, false)
+            .await
+    }
+
+    /// Posts `body` with `kind` as its `X-Taskcast-Event` header, counting
+    /// each attempt against `task_id`'s permits.
+    async fn post_with_retry(
+        &self,
+        body: String,
+        kind: &str,
+        task_id: &str,
+        config: &WebhookConfig,
+        mut retry: RetryConfig,
+        backfill: bool,
+    ) -> Result<(), WebhookError> {
+        let host = circuit_key(&config.url);
+        let probe = match self.admit(&host) {
+            Admission::Closed => false,
+            Admission::Probe => true,
+            Admission::Rejected => return Err(WebhookError::CircuitOpen { host }),
+        };
+
+        // A half-open probe gets a single attempt so a still-dead target
+        // costs one timeout, not a full retry budget.
+        if probe {
+            retry.retries = 0;
+        }
         let timestamp = format!(
             "{}",
             std::time::SystemTime::now()
@@ -68,18 +661,47 @@ impl WebhookDelivery {
         let signature = config.secret.as_ref().map(|s| Self::sign(&body, s));
 
         let mut last_error: Option<String> = None;
+        let mut last_code: Option<String> = None;
+        let mut attempts = 0;
+        let started = Instant::now();
 
         for attempt in 0..=retry.retries {
-            if attempt > 0 {
-                let delay = Self::backoff_ms(&retry, attempt);
-                tokio::time::sleep(Duration::from_millis(delay)).await;
+            let delay = if attempt == 0 {
+                Duration::ZERO
+            } else {
+                let delay = Duration::from_millis(compute_backoff(
+                    &retry,
+                    attempt,
+                    &mut self.rng.lock().unwrap(),
+                ));
+                let out_of_time = retry.max_elapsed_ms.is_some_and(|max| {
+                    started.elapsed() + delay > Duration::from_millis(max)
+                });
+                if out_of_time {
+                    break;
+                }
+                delay
+            };
+            if let Some(ref hooks) = self.hooks {
+                hooks.on_webhook_attempt(config, kind, attempt + 1, delay, last_error.as_deref());
             }
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            // Held until the response is read, and released before the
+            // next backoff.
+            let _permit = if is_sync(config) {
+                None
+            } else {
+                Some(self.acquire(task_id).await)
+            };
+            attempts += 1;
 
             let mut req = self
                 .client
                 .post(&config.url)
                 .header("Content-Type", "application/json")
-                .header("X-Taskcast-Event", &event.r#type)
+                .header("X-Taskcast-Event", kind)
                 .header("X-Taskcast-Timestamp", &timestamp)
                 .timeout(Duration::from_millis(retry.timeout_ms))
                 .body(body.clone());
@@ -87,24 +709,43 @@ impl WebhookDelivery {
             if let Some(ref sig) = signature {
                 req = req.header("X-Taskcast-Signature", sig);
             }
+            if backfill {
+                req = req.header(BACKFILL_HEADER, "true");
+            }
 
             match req.send().await {
-                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) if res.status().is_success() => {
+                    self.record_success(&host);
+                    return Ok(());
+                }
                 Ok(res) => {
-                    last_error = Some(format!("HTTP {}", res.status().as_u16()));
+                    let status = res.status().as_u16();
+                    last_code = Self::response_error_code(res).await;
+                    last_error = Some(match last_code {
+                        Some(ref code) => format!("HTTP {status} ({code})"),
+                        None => format!("HTTP {status}"),
+                    });
                 }
                 Err(err) => {
+                    last_code = None;
                     last_error = Some(err.to_string());
                 }
             }
         }
 
+        self.record_failure(&host, probe);
         Err(WebhookError::DeliveryFailed {
-            attempts: retry.retries + 1,
+            attempts,
             message: last_error.unwrap_or_else(|| "Unknown error".to_string()),
+            code: last_code,
         })
     }
 
+    async fn response_error_code(res: reqwest::Response) -> Option<String> {
+        let body = res.json::<serde_json::Value>().await.ok()?;
+        body.get("code")?.as_str().map(str::to_string)
+    }
+
     fn sign(body: &str, secret: &str) -> String {
         let mut mac =
             Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
@@ -112,15 +753,6 @@ impl WebhookDelivery {
         format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
     }
 
-    fn backoff_ms(retry: &RetryConfig, attempt: u32) -> u64 {
-        match retry.backoff {
-            BackoffStrategy::Fixed => retry.initial_delay_ms,
-            BackoffStrategy::Linear => retry.initial_delay_ms * attempt as u64,
-            BackoffStrategy::Exponential => {
-                (retry.initial_delay_ms * 2u64.pow(attempt - 1)).min(retry.max_delay_ms)
-            }
-        }
-    }
 }
 
 impl Default for WebhookDelivery {
@@ -129,13 +761,762 @@ impl Default for WebhookDelivery {
     }
 }
 
+/// `event` serialized with `filteredIndex` and `filterEpoch` from `position`,
+/// when it has one.
+fn positioned_body(event: &TaskEvent, position: Option<FilteredIndexClaim>) -> String {
+    let Some(position) = position else {
+        return serde_json::to_string(event).unwrap();
+    };
+    let mut body = serde_json::to_value(event).unwrap();
+    body["filteredIndex"] = position.filtered_index.into();
+    body["filterEpoch"] = position.epoch.into();
+    body.to_string()
+}
+
+/// Store key of the webhook's persisted filtered numbering; a webhook is
+/// identified by its task and url.
+fn filtered_index_consumer(config: &WebhookConfig) -> String {
+    format!("webhook:{}", config.url)
+}
+
+/// The webhook's effective filter, or `None` when it has neither a filter
+/// nor a preset.
+fn webhook_filter(
+    presets: Option<&HashMap<String, SubscribeFilter>>,
+    config: &WebhookConfig,
+) -> Result<Option<SubscribeFilter>, FilterPresetError> {
+    if config.filter.is_none() && config.filter_preset.is_none() {
+        return Ok(None);
+    }
+    resolve_filter(
+        presets,
+        config.filter_preset.as_deref(),
+        config.filter.clone().unwrap_or_default(),
+    )
+    .map(Some)
+}
+
+/// Whether `task`, as it is now, meets the webhook's own `task_match`.
+fn webhook_admits_task(config: &WebhookConfig, task: &Task) -> bool {
+    config
+        .task_match
+        .as_ref()
+        .is_none_or(|task_match| matches_task(task, task_match))
+}
+
+fn webhook_matches(
+    task: &Task,
+    config: &WebhookConfig,
+    event: &TaskEvent,
+) -> Result<bool, FilterPresetError> {
+    // Settled without resolving the filter. With a preset, resolving it
+    // still reports a preset that does not exist.
+    if is_control_event(event)
+        && config.filter_preset.is_none()
+        && config
+            .filter
+            .as_ref()
+            .is_some_and(|filter| filter.include_status == Some(false))
+    {
+        return Ok(false);
+    }
+    let filter = webhook_filter(task.filters.as_ref(), config)?;
+    Ok(webhook_admits_task(config, task)
+        && filter.is_none_or(|filter| matches_filter_for_task(event, &filter, Some(task))))
+}
+
+/// `event` as posted to a webhook, with `accumulate` series data in the
+/// filter's `seriesFormat`.
+fn webhook_payload(event: &TaskEvent, filter: Option<&SubscribeFilter>) -> TaskEvent {
+    let format = filter.and_then(|f| f.series_format.as_ref());
+    TaskEvent {
+        data: series_view_data(event, format),
+        ..event.clone()
+    }
+}
+
+// ─── Dispatcher ─────────────────────────────────────────────────────────────
+
+/// Type of the event emitted on every status transition.
+const LIFECYCLE_EVENT_TYPE: &str = "taskcast:status";
+
+/// What happened when an event was offered to one of a task's webhooks.
+#[derive(Debug)]
+pub enum DispatchOutcome {
+    Delivered,
+    /// An event with the same type and level went to this webhook within
+    /// its `suppression_window_ms`.
+    Suppressed,
+    Failed(WebhookError),
+}
+
+#[derive(Debug)]
+pub struct WebhookDispatch {
+    /// Position of the webhook in the task's `webhooks`.
+    pub webhook: usize,
+    pub url: String,
+    pub outcome: DispatchOutcome,
+}
+
+/// Positions of the task's webhooks that should receive `event`: those
+/// whose filter matches, except that under
+/// [`WebhookGroupPolicy::FirstMatch`] only the first matching webhook of
+/// each group, in declaration order, is kept.
+pub fn select_webhooks(task: &Task, event: &TaskEvent) -> Result<Vec<usize>, FilterPresetError> {
+    let policy = task.group_policy.unwrap_or_default();
+    let mut matched_groups: HashSet<&str> = HashSet::new();
+    let mut selected = Vec::new();
+    for (index, webhook) in task.webhooks.iter().flatten().enumerate() {
+        let group = webhook.group.as_deref();
+        if policy == WebhookGroupPolicy::FirstMatch
+            && group.is_some_and(|g| matched_groups.contains(g))
+        {
+            continue;
+        }
+        if !webhook_matches(task, webhook, event)? {
+            continue;
+        }
+        if let Some(group) = group {
+            matched_groups.insert(group);
+        }
+        selected.push(index);
+    }
+    Ok(selected)
+}
+
+/// Fans a task's events out to its webhooks, applying group policies and
+/// suppression windows before handing each delivery to [`WebhookDelivery`].
+///
+/// Suppression state lives in the short-term store, so instances sharing a
+/// store share windows. Status-change events are never suppressed: they
+/// all share one type and level, and dropping one would hide the outcome.
+pub struct WebhookDispatcher {
+    delivery: Arc<WebhookDelivery>,
+    store: Arc<dyn ShortTermStore>,
+    clock: Arc<dyn Clock>,
+    /// Initial deliveries by task id and webhook position, until live
+    /// dispatch has moved past the history they replayed or the task is
+    /// removed.
+    backfills: Arc<Mutex<HashMap<(String, usize), BackfillGate>>>,
+    /// The engine lifecycle listener dropping backfills of removed tasks,
+    /// registered by the first `add_webhook`.
+    lifecycle: Mutex<Option<(Weak<TaskEngine>, LifecycleListener)>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(delivery: Arc<WebhookDelivery>, store: Arc<dyn ShortTermStore>) -> Self {
+        Self {
+            delivery,
+            store,
+            clock: Arc::new(SystemClock),
+            backfills: Arc::default(),
+            lifecycle: Mutex::new(None),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Offers `event` to `task`'s webhooks, delivering to the selected ones
+    /// concurrently. Returns one entry per selected webhook, leaving out
+    /// those whose backfill already replayed `event`. `sync` webhooks are
+    /// left to [`dispatch_sync`](Self::dispatch_sync).
+    pub async fn dispatch(
+        &self,
+        task: &Task,
+        event: &TaskEvent,
+    ) -> Result<Vec<WebhookDispatch>, WebhookError> {
+        let webhooks = task.webhooks.as_deref().unwrap_or_default();
+        let selected = select_webhooks(task, event)?;
+        let deliveries = selected
+            .into_iter()
+            .filter(|&index| !is_sync(&webhooks[index]))
+            .map(|index| {
+                let config = &webhooks[index];
+                async move {
+                    // Held through the delivery so events queued behind a
+                    // backfill go out in the order they arrived.
+                    let _queued = match self.wait_for_backfill(&task.id, index, event.index).await {
+                        Some(state) if state.replayed(event.index) => return None,
+                        state => state,
+                    };
+                    let outcome = if self.suppressed(task, index, config, event).await {
+                        DispatchOutcome::Suppressed
+                    } else {
+                        // Selection already resolved this filter.
+                        let filter = webhook_filter(task.filters.as_ref(), config).ok().flatten();
+                        let payload = webhook_payload(event, filter.as_ref());
+                        let position = self
+                            .position(&task.id, config, filter.as_ref(), event)
+                            .await;
+                        match self.delivery.deliver(&payload, config, position).await {
+                            Ok(()) => DispatchOutcome::Delivered,
+                            Err(err) => DispatchOutcome::Failed(err),
+                        }
+                    };
+                    Some(WebhookDispatch {
+                        webhook: index,
+                        url: config.url.clone(),
+                        outcome,
+                    })
+                }
+            });
+        Ok(futures::future::join_all(deliveries)
+            .await
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Appends `config` to the task's webhooks. When it asks for an
+    /// `initialDelivery` snapshot or a `backfill`, also returns the run
+    /// delivering them, which the caller runs or spawns; until it has run or
+    /// been dropped, [`dispatch`](Self::dispatch) holds back live events for
+    /// the webhook.
+    pub async fn add_webhook(
+        &self,
+        engine: &Arc<TaskEngine>,
+        task_id: &str,
+        mut config: WebhookConfig,
+    ) -> Result<(Task, Option<WebhookBackfillRun>), EngineError> {
+        if let Some(ref mut filter) = config.filter {
+            engine.event_types().normalize_filter(filter);
+        }
+        let task = engine
+            .get_task(task_id)
+            .await?
+            .ok_or_else(|| EngineError::TaskNotFound(task_id.to_string()))?;
+        webhook_filter(task.filters.as_ref(), &config)?;
+        let snapshot = wants_snapshot(&config);
+        let backfill = config.backfill.clone();
+        if snapshot || backfill.is_some() {
+            self.watch_lifecycle(engine);
+        }
+
+        let mut registered = None;
+        let result = engine
+            .update_task(task_id, |task| {
+                // A retried update starts over from a fresh read.
+                if let Some((index, _)) = registered.take() {
+                    self.backfills
+                        .lock()
+                        .unwrap()
+                        .remove(&(task_id.to_string(), index));
+                }
+                let webhooks = task.webhooks.get_or_insert_with(Vec::new);
+                webhooks.push(config.clone());
+                // Registered before the task is saved, so no live dispatch
+                // can see the webhook without also seeing the gate.
+                if snapshot || backfill.is_some() {
+                    let index = webhooks.len() - 1;
+                    registered = Some((index, self.hold_live_events(task_id, index)));
+                }
+            })
+            .await;
+        let task = match result {
+            Ok(task) => task,
+            Err(err) => {
+                if let Some((index, _)) = registered {
+                    self.backfills
+                        .lock()
+                        .unwrap()
+                        .remove(&(task_id.to_string(), index));
+                }
+                return Err(err);
+            }
+        };
+
+        let run = registered.map(|(webhook, state)| WebhookBackfillRun {
+            delivery: Arc::clone(&self.delivery),
+            engine: Arc::clone(engine),
+            task: task.clone(),
+            webhook,
+            snapshot,
+            since: backfill,
+            state,
+        });
+        Ok((task, run))
+    }
+
+    /// Snapshot runs for the `initialDelivery` webhooks of a just-created
+    /// `task`, one per webhook, which the caller runs or spawns. Call it
+    /// before publishing to the task: live events for those webhooks are
+    /// held back from then until their run has finished or been dropped.
+    /// `backfill` is ignored here, as a new task has no history.
+    pub fn task_created(&self, engine: &Arc<TaskEngine>, task: &Task) -> Vec<WebhookBackfillRun> {
+        let runs: Vec<WebhookBackfillRun> = task
+            .webhooks
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, config)| wants_snapshot(config))
+            .map(|(webhook, _)| WebhookBackfillRun {
+                delivery: Arc::clone(&self.delivery),
+                engine: Arc::clone(engine),
+                task: task.clone(),
+                webhook,
+                snapshot: true,
+                since: None,
+                state: self.hold_live_events(&task.id, webhook),
+            })
+            .collect();
+        if !runs.is_empty() {
+            self.watch_lifecycle(engine);
+        }
+        runs
+    }
+
+    /// Registers a gate holding back live events for the webhook, returning
+    /// its state locked until the initial deliveries are done.
+    fn hold_live_events(&self, task_id: &str, webhook: usize) -> OwnedMutexGuard<BackfillState> {
+        let gate = Arc::new(AsyncMutex::new(BackfillState::Running));
+        let state = Arc::clone(&gate)
+            .try_lock_owned()
+            .expect("a new lock is free");
+        self.backfills
+            .lock()
+            .unwrap()
+            .insert((task_id.to_string(), webhook), gate);
+        state
+    }
+
+    /// Number of backfills live dispatch still has to get past, for leak
+    /// checks.
+    pub fn pending_backfills(&self) -> usize {
+        self.backfills.lock().unwrap().len()
+    }
+
+    fn watch_lifecycle(&self, engine: &Arc<TaskEngine>) {
+        let mut lifecycle = self.lifecycle.lock().unwrap();
+        if lifecycle.is_some() {
+            return;
+        }
+        let backfills = Arc::downgrade(&self.backfills);
+        let listener: LifecycleListener = Arc::new(move |task_id, event| {
+            // A terminal task still gets its final status event dispatched,
+            // which may have to wait for the backfill.
+            if !event.is_removal() {
+                return;
+            }
+            if let Some(backfills) = backfills.upgrade() {
+                backfills
+                    .lock()
+                    .unwrap()
+                    .retain(|(id, _), _| id != task_id);
+            }
+        });
+        engine.lifecycle().subscribe(Arc::clone(&listener));
+        *lifecycle = Some((Arc::downgrade(engine), listener));
+    }
+
+    /// Waits out a backfill of the webhook, if one is registered, and
+    /// returns its state. The backfill is forgotten once live dispatch
+    /// reaches an event it did not replay.
+    async fn wait_for_backfill(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        event_index: u64,
+    ) -> Option<OwnedMutexGuard<BackfillState>> {
+        let key = (task_id.to_string(), webhook);
+        let gate = self.backfills.lock().unwrap().get(&key).cloned()?;
+        let state = Arc::clone(&gate).lock_owned().await;
+        if !state.replayed(event_index) {
+            let mut backfills = self.backfills.lock().unwrap();
+            if backfills
+                .get(&key)
+                .is_some_and(|current| Arc::ptr_eq(current, &gate))
+            {
+                backfills.remove(&key);
+            }
+        }
+        Some(state)
+    }
+
+    /// Delivers `event` to `task`'s selected `sync` webhooks concurrently,
+    /// making one attempt each and waiting at most `timeout` for it.
+    pub async fn dispatch_sync(
+        &self,
+        task: &Task,
+        event: &TaskEvent,
+        timeout: Duration,
+    ) -> Result<Vec<SyncWebhookResult>, WebhookError> {
+        let webhooks = task.webhooks.as_deref().unwrap_or_default();
+        let selected = select_webhooks(task, event)?;
+        let deliveries = selected
+            .into_iter()
+            .filter(|&index| is_sync(&webhooks[index]))
+            .map(|index| {
+                let config = &webhooks[index];
+                async move {
+                    let started = Instant::now();
+                    let (status, error) = if self.suppressed(task, index, config, event).await {
+                        (SyncWebhookStatus::Suppressed, None)
+                    } else {
+                        let filter = webhook_filter(task.filters.as_ref(), config).ok().flatten();
+                        let payload = webhook_payload(event, filter.as_ref());
+                        let position = self
+                            .position(&task.id, config, filter.as_ref(), event)
+                            .await;
+                        let retry = RetryConfig {
+                            retries: 0,
+                            ..merge_retry(config.retry.as_ref())
+                        };
+                        let attempt = self.delivery.deliver_with_retry(
+                            &payload, config, retry, false, position,
+                        );
+                        match tokio::time::timeout(timeout, attempt).await {
+                            Ok(Ok(())) => (SyncWebhookStatus::Delivered, None),
+                            Ok(Err(err)) => (SyncWebhookStatus::Failed, Some(err.to_string())),
+                            Err(_) => {
+                                // The abandoned attempt never reports back, so
+                                // count it against the circuit here.
+                                self.delivery
+                                    .record_failure(&circuit_key(&config.url), false);
+                                (
+                                    SyncWebhookStatus::Timeout,
+                                    Some(format!("No response within {} ms", timeout.as_millis())),
+                                )
+                            }
+                        }
+                    };
+                    SyncWebhookResult {
+                        url: config.url.clone(),
+                        status,
+                        latency_ms: started.elapsed().as_millis() as u64,
+                        error,
+                    }
+                }
+            });
+        Ok(futures::future::join_all(deliveries).await)
+    }
+
+    async fn suppressed(
+        &self,
+        task: &Task,
+        index: usize,
+        config: &WebhookConfig,
+        event: &TaskEvent,
+    ) -> bool {
+        let Some(window_ms) = config.suppression_window_ms else {
+            return false;
+        };
+        if event.r#type == LIFECYCLE_EVENT_TYPE {
+            return false;
+        }
+        let level = serde_json::to_value(&event.level).unwrap_or_default();
+        let fingerprint = format!("{}:{}", event.r#type, level.as_str().unwrap_or_default());
+        // A store failure delivers rather than drops: a duplicate is cheaper
+        // than a lost alert.
+        !self
+            .store
+            .claim_webhook_delivery(
+                &task.id,
+                index,
+                &fingerprint,
+                self.clock.now_ms(),
+                window_ms,
+            )
+            .await
+            .unwrap_or(true)
+    }
+
+    async fn position(
+        &self,
+        task_id: &str,
+        config: &WebhookConfig,
+        filter: Option<&SubscribeFilter>,
+        event: &TaskEvent,
+    ) -> Option<FilteredIndexClaim> {
+        claim_position(self.store.as_ref(), task_id, config, filter, event).await
+    }
+}
+
+/// Numbers `event` in the webhook's persisted filtered numbering. A store
+/// failure delivers the event without a position.
+async fn claim_position(
+    store: &dyn ShortTermStore,
+    task_id: &str,
+    config: &WebhookConfig,
+    filter: Option<&SubscribeFilter>,
+    event: &TaskEvent,
+) -> Option<FilteredIndexClaim> {
+    let hash = filter_hash(filter.unwrap_or(&SubscribeFilter::default()));
+    store
+        .claim_filtered_index(task_id, &filtered_index_consumer(config), &hash, event.index)
+        .await
+        .ok()
+        .flatten()
+}
+
+impl Drop for WebhookDispatcher {
+    fn drop(&mut self) {
+        if let Some((engine, listener)) = self.lifecycle.get_mut().unwrap().take() {
+            if let Some(engine) = engine.upgrade() {
+                engine.lifecycle().unsubscribe(&listener);
+            }
+        }
+    }
+}
+
+// ─── Backfill ───────────────────────────────────────────────────────────────
+
+/// Set to `true` on deliveries replayed by a backfill.
+pub const BACKFILL_HEADER: &str = "X-Taskcast-Backfill";
+
+/// `kind` of the initial snapshot, also sent as its `X-Taskcast-Event`.
+const SNAPSHOT_KIND: &str = "task.snapshot";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskSnapshot<'a> {
+    kind: &'static str,
+    task: &'a Task,
+    last_event_index: Option<u64>,
+}
+
+fn wants_snapshot(config: &WebhookConfig) -> bool {
+    config.initial_delivery == Some(true)
+}
+
+type BackfillGate = Arc<AsyncMutex<BackfillState>>;
+
+enum BackfillState {
+    Running,
+    /// History up to and including `through` was read for the replay.
+    Done { through: Option<u64> },
+}
+
+impl BackfillState {
+    fn replayed(&self, index: u64) -> bool {
+        matches!(self, Self::Done { through: Some(through) } if index <= *through)
+    }
+}
+
+/// Initial deliveries of a newly added webhook, from
+/// [`WebhookDispatcher::add_webhook`] or
+/// [`WebhookDispatcher::task_created`]: its `task.snapshot`, then its
+/// `backfill`.
+///
+/// The snapshot posts `{"kind": "task.snapshot", "task": ..., "lastEventIndex": ...}`
+/// with the current task and the index of the last event published before
+/// it was read, or null before the first. Replayed history follows one event
+/// at a time in index order, with the backfill header. Both are signed and
+/// retried like live deliveries; group policies and suppression windows
+/// apply to live events only.
+pub struct WebhookBackfillRun {
+    delivery: Arc<WebhookDelivery>,
+    engine: Arc<TaskEngine>,
+    task: Task,
+    webhook: usize,
+    snapshot: bool,
+    since: Option<WebhookBackfill>,
+    state: OwnedMutexGuard<BackfillState>,
+}
+
+impl WebhookBackfillRun {
+    /// Position of the webhook in the task's `webhooks`.
+    pub fn webhook(&self) -> usize {
+        self.webhook
+    }
+
+    /// Delivers the snapshot and history and releases the live events held
+    /// back meanwhile. Returns one entry for the snapshot, if any, followed
+    /// by one per replayed event.
+    pub async fn run(mut self) -> Result<Vec<WebhookDispatch>, EngineError> {
+        let config = &self.task.webhooks.as_deref().unwrap_or_default()[self.webhook];
+        let retry = merge_retry(config.retry.as_ref());
+        let mut dispatches = Vec::new();
+
+        if self.snapshot {
+            // Counted first, so the task read next reflects at least the
+            // events up to `lastEventIndex`.
+            let last_event_index = self.engine.event_count(&self.task.id).await?.checked_sub(1);
+            let task = self
+                .engine
+                .get_task(&self.task.id)
+                .await?
+                .ok_or_else(|| EngineError::TaskNotFound(self.task.id.clone()))?;
+            let outcome = match self
+                .delivery
+                .deliver_snapshot(&task, last_event_index, config, retry.clone())
+                .await
+            {
+                Ok(()) => DispatchOutcome::Delivered,
+                Err(err) => DispatchOutcome::Failed(err),
+            };
+            dispatches.push(WebhookDispatch {
+                webhook: self.webhook,
+                url: config.url.clone(),
+                outcome,
+            });
+        }
+
+        let Some(ref since) = self.since else {
+            *self.state = BackfillState::Done { through: None };
+            return Ok(dispatches);
+        };
+        let opts = match since {
+            WebhookBackfill::All => None,
+            WebhookBackfill::Since(cursor) => Some(EventQueryOptions {
+                since: Some(cursor.clone()),
+                limit: None,
+                label_selector: None,
+                until: None,
+                before_index: None,
+            }),
+        };
+        let events = self.engine.get_events(&self.task.id, opts).await?;
+        let filter = webhook_filter(self.task.filters.as_ref(), config)?;
+        let store = self.engine.short_term_store();
+        for event in &events {
+            if !webhook_admits_task(config, &self.task)
+                || filter
+                    .as_ref()
+                    .is_some_and(|f| !matches_filter_for_task(event, f, Some(&self.task)))
+            {
+                continue;
+            }
+            let payload = webhook_payload(event, filter.as_ref());
+            let position =
+                claim_position(store.as_ref(), &self.task.id, config, filter.as_ref(), event).await;
+            let outcome = match self
+                .delivery
+                .deliver_with_retry(&payload, config, retry.clone(), true, position)
+                .await
+            {
+                Ok(()) => DispatchOutcome::Delivered,
+                Err(err) => DispatchOutcome::Failed(err),
+            };
+            dispatches.push(WebhookDispatch {
+                webhook: self.webhook,
+                url: config.url.clone(),
+                outcome,
+            });
+        }
+        *self.state = BackfillState::Done {
+            through: events.iter().map(|event| event.index).max(),
+        };
+        Ok(dispatches)
+    }
+}
+
+impl Drop for WebhookBackfillRun {
+    fn drop(&mut self) {
+        // Dropped without running, or failed: stop holding back live events.
+        if matches!(*self.state, BackfillState::Running) {
+            *self.state = BackfillState::Done { through: None };
+        }
+    }
+}
+
+// ─── Sync Webhooks ──────────────────────────────────────────────────────────
+
+pub const DEFAULT_SYNC_TIMEOUT_MS: u64 = 2000;
+pub const DEFAULT_MAX_SYNC_WEBHOOKS: usize = 2;
+
+pub fn is_sync(config: &WebhookConfig) -> bool {
+    config.mode == Some(WebhookMode::Sync)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncWebhookStatus {
+    Delivered,
+    Suppressed,
+    Failed,
+    Timeout,
+}
+
+/// Outcome of an inline delivery, as listed in `webhookResults`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWebhookResult {
+    pub url: String,
+    pub status: SyncWebhookStatus,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SyncWebhookResult {
+    pub fn succeeded(&self) -> bool {
+        matches!(
+            self.status,
+            SyncWebhookStatus::Delivered | SyncWebhookStatus::Suppressed
+        )
+    }
+}
+
+/// Inline delivery of `sync` webhooks for the routes that publish events.
+pub struct SyncWebhooks {
+    dispatcher: WebhookDispatcher,
+    timeout: Duration,
+    max_per_task: usize,
+}
+
+impl SyncWebhooks {
+    pub fn new(dispatcher: WebhookDispatcher, config: Option<&WebhookGlobalConfig>) -> Self {
+        Self {
+            dispatcher,
+            timeout: Duration::from_millis(
+                config
+                    .and_then(|c| c.sync_timeout_ms)
+                    .unwrap_or(DEFAULT_SYNC_TIMEOUT_MS),
+            ),
+            max_per_task: config
+                .and_then(|c| c.max_sync_webhooks)
+                .unwrap_or(DEFAULT_MAX_SYNC_WEBHOOKS),
+        }
+    }
+
+    /// Rejects a webhook list with more `sync` entries than a task may have.
+    pub fn check_limit(&self, webhooks: &[WebhookConfig]) -> Result<(), String> {
+        let count = webhooks.iter().filter(|w| is_sync(w)).count();
+        if count > self.max_per_task {
+            return Err(format!(
+                "A task may have at most {} sync webhooks, got {count}",
+                self.max_per_task
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delivers `events` to `task`'s `sync` webhooks in order, returning the
+    /// results for each event. `None` when the task has no `sync` webhooks.
+    pub async fn deliver(
+        &self,
+        task: &Task,
+        events: &[TaskEvent],
+    ) -> Option<Vec<Vec<SyncWebhookResult>>> {
+        if !task.webhooks.iter().flatten().any(is_sync) {
+            return None;
+        }
+        let mut results = Vec::with_capacity(events.len());
+        for event in events {
+            // Presets were validated when the task was written; an event
+            // that no longer resolves is simply not delivered.
+            let delivered = self
+                .dispatcher
+                .dispatch_sync(task, event, self.timeout)
+                .await
+                .unwrap_or_default();
+            results.push(delivered);
+        }
+        Some(results)
+    }
+}
+
 // ─── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     use std::sync::Arc;
-    use taskcast_core::{Level, SubscribeFilter};
+    use taskcast_core::{Level, SeriesFormat, SeriesMode, SubscribeFilter};
 
     #[test]
     fn sign_produces_correct_hmac_sha256() {
@@ -165,6 +1546,11 @@ mod tests {
         assert_eq!(sig1, sig2);
     }
 
+    /// The un-jittered delay before retry `attempt`.
+    fn backoff_ms(retry: &RetryConfig, attempt: u32) -> u64 {
+        compute_backoff(retry, attempt, &mut fastrand::Rng::new())
+    }
+
     #[test]
     fn backoff_fixed_returns_initial_delay() {
         let retry = RetryConfig {
@@ -173,10 +1559,12 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 1), 1000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 2), 1000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 3), 1000);
+        assert_eq!(backoff_ms(&retry, 1), 1000);
+        assert_eq!(backoff_ms(&retry, 2), 1000);
+        assert_eq!(backoff_ms(&retry, 3), 1000);
     }
 
     #[test]
@@ -187,10 +1575,12 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 1), 1000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 2), 2000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 3), 3000);
+        assert_eq!(backoff_ms(&retry, 1), 1000);
+        assert_eq!(backoff_ms(&retry, 2), 2000);
+        assert_eq!(backoff_ms(&retry, 3), 3000);
     }
 
     #[test]
@@ -201,12 +1591,14 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 1), 1000); // 1000 * 2^0
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 2), 2000); // 1000 * 2^1
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 3), 4000); // 1000 * 2^2
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 4), 8000); // 1000 * 2^3
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 5), 16000); // 1000 * 2^4
+        assert_eq!(backoff_ms(&retry, 1), 1000); // 1000 * 2^0
+        assert_eq!(backoff_ms(&retry, 2), 2000); // 1000 * 2^1
+        assert_eq!(backoff_ms(&retry, 3), 4000); // 1000 * 2^2
+        assert_eq!(backoff_ms(&retry, 4), 8000); // 1000 * 2^3
+        assert_eq!(backoff_ms(&retry, 5), 16000); // 1000 * 2^4
     }
 
     #[test]
@@ -217,12 +1609,14 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 5000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 1), 1000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 2), 2000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 3), 4000);
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 4), 5000); // capped at max_delay_ms
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 5), 5000); // still capped
+        assert_eq!(backoff_ms(&retry, 1), 1000);
+        assert_eq!(backoff_ms(&retry, 2), 2000);
+        assert_eq!(backoff_ms(&retry, 3), 4000);
+        assert_eq!(backoff_ms(&retry, 4), 5000); // capped at max_delay_ms
+        assert_eq!(backoff_ms(&retry, 5), 5000); // still capped
     }
 
     #[test]
@@ -248,33 +1642,208 @@ mod tests {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }
     }
 
     #[tokio::test]
-    async fn send_skips_when_filter_does_not_match() {
+    async fn send_skips_when_filter_does_not_match() {
+        let delivery = WebhookDelivery::new();
+        let event = make_test_event();
+        let config = WebhookConfig {
+            url: "http://localhost:9999/hook".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter: Some(SubscribeFilter {
+                types: Some(vec!["log".to_string()]), // does NOT match "progress"
+                levels: None,
+                include_status: None,
+                wrap: None,
+                since: None,
+                series_format: None,
+                materialize_series: None,
+                label_selector: None,
+                task_match: None,
+            }),
+            secret: None,
+            wrap: None,
+            retry: None,
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        };
+        // Should return Ok(()) without attempting to send because filter doesn't match
+        let result = delivery.send(&event, &config).await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn payload_data_follows_the_series_format() {
+        let event = TaskEvent {
+            r#type: "llm.delta".to_string(),
+            data: serde_json::json!({ "delta": "lo" }),
+            series_id: Some("s1".to_string()),
+            series_mode: Some(SeriesMode::Accumulate),
+            _accumulated_data: Some(serde_json::json!({ "delta": "Hello" })),
+            ..make_test_event()
+        };
+        let filter = |format| SubscribeFilter {
+            series_format: Some(format),
+            ..Default::default()
+        };
+
+        assert_eq!(webhook_payload(&event, None).data, event.data);
+        assert_eq!(
+            webhook_payload(&event, Some(&filter(SeriesFormat::Accumulated))).data,
+            serde_json::json!({ "delta": "Hello" })
+        );
+        assert_eq!(
+            webhook_payload(&event, Some(&filter(SeriesFormat::Both))).data,
+            serde_json::json!({ "delta": { "delta": "lo" }, "accumulated": { "delta": "Hello" } })
+        );
+        // Events outside an accumulate series are posted as published.
+        let plain = make_test_event();
+        assert_eq!(
+            webhook_payload(&plain, Some(&filter(SeriesFormat::Both))).data,
+            plain.data
+        );
+    }
+
+    #[tokio::test]
+    async fn send_skips_when_label_selector_does_not_match() {
         let delivery = WebhookDelivery::new();
-        let event = make_test_event();
+        let event = TaskEvent {
+            labels: Some(HashMap::from([("region".to_string(), "us".to_string())])),
+            ..make_test_event()
+        };
         let config = WebhookConfig {
             url: "http://localhost:9999/hook".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: Some(SubscribeFilter {
-                types: Some(vec!["log".to_string()]), // does NOT match "progress"
+                types: None,
                 levels: None,
                 include_status: None,
                 wrap: None,
                 since: None,
                 series_format: None,
+                materialize_series: None,
+                label_selector: Some(HashMap::from([("region".to_string(), "eu".to_string())])),
+                task_match: None,
             }),
             secret: None,
             wrap: None,
             retry: None,
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
-        // Should return Ok(()) without attempting to send because filter doesn't match
+        // Should return Ok(()) without attempting to send because the selector doesn't match
         let result = delivery.send(&event, &config).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn send_with_presets_resolves_filter_preset() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let call_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let call_count_clone = call_count.clone();
+        let mock_app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move || {
+                let count = call_count_clone.clone();
+                async move {
+                    count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    axum::http::StatusCode::OK
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, mock_app).await.unwrap();
+        });
+
+        let presets = HashMap::from([
+            (
+                "progressOnly".to_string(),
+                SubscribeFilter {
+                    types: Some(vec!["progress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "logsOnly".to_string(),
+                SubscribeFilter {
+                    types: Some(vec!["log".to_string()]),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let config = |preset: &str, filter: Option<SubscribeFilter>| WebhookConfig {
+            url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter,
+            secret: None,
+            wrap: None,
+            retry: None,
+            filter_preset: Some(preset.to_string()),
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        };
+        let delivery = WebhookDelivery::new();
+        let event = make_test_event();
+
+        delivery
+            .send_with_presets(&event, &config("logsOnly", None), Some(&presets))
+            .await
+            .unwrap();
+        assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // The inline filter narrows the preset to errors, which excludes this event.
+        let errors = SubscribeFilter {
+            levels: Some(vec![Level::Error]),
+            ..Default::default()
+        };
+        delivery
+            .send_with_presets(
+                &event,
+                &config("progressOnly", Some(errors)),
+                Some(&presets),
+            )
+            .await
+            .unwrap();
+        assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        delivery
+            .send_with_presets(&event, &config("progressOnly", None), Some(&presets))
+            .await
+            .unwrap();
+        assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let err = delivery
+            .send(&event, &config("progressOnly", None))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            WebhookError::FilterPreset(FilterPresetError::UnknownPreset(_))
+        ));
+        assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn send_fails_after_retries_on_server_error() {
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -301,6 +1870,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -310,17 +1882,241 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&event, &config).await;
         assert!(result.is_err());
         let err = result.unwrap_err();
-        let WebhookError::DeliveryFailed { attempts, .. } = err;
+        let WebhookError::DeliveryFailed { attempts, code, .. } = err else {
+            panic!("expected DeliveryFailed, got {err:?}");
+        };
         assert_eq!(attempts, 3); // 1 initial + 2 retries
+        assert_eq!(code, None);
         assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 3);
     }
 
+    /// `(kind, attempt, delay, error)` as passed to `on_webhook_attempt`.
+    type Attempt = (String, u32, Duration, Option<String>);
+
+    /// Records every `on_webhook_attempt`.
+    #[derive(Default)]
+    struct AttemptLog(Mutex<Vec<Attempt>>);
+
+    impl TaskcastHooks for AttemptLog {
+        fn on_webhook_attempt(
+            &self,
+            _config: &WebhookConfig,
+            kind: &str,
+            attempt: u32,
+            delay: Duration,
+            error: Option<&str>,
+        ) {
+            self.0.lock().unwrap().push((
+                kind.to_string(),
+                attempt,
+                delay,
+                error.map(str::to_string),
+            ));
+        }
+    }
+
+    /// A receiver at `/hook` that always answers 500, and its request count.
+    async fn failing_receiver() -> (String, Arc<std::sync::atomic::AtomicU32>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counted = Arc::clone(&calls);
+        let mock_app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move || {
+                let counted = Arc::clone(&counted);
+                async move {
+                    counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, mock_app).await.unwrap();
+        });
+        (format!("http://{addr}/hook"), calls)
+    }
+
+    fn retrying_webhook(url: String, retry: RetryConfig) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter: None,
+            secret: None,
+            wrap: None,
+            retry: Some(retry),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn every_attempt_is_reported_to_hooks() {
+        let (url, _) = failing_receiver().await;
+        let log = Arc::new(AttemptLog::default());
+        let delivery = WebhookDelivery::new().with_hooks(log.clone());
+        let config = retrying_webhook(
+            url,
+            RetryConfig {
+                retries: 2,
+                backoff: BackoffStrategy::Linear,
+                initial_delay_ms: 5,
+                max_delay_ms: 100,
+                timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
+            },
+        );
+
+        let result = delivery.send(&make_test_event(), &config).await;
+        assert!(matches!(
+            result,
+            Err(WebhookError::DeliveryFailed { attempts: 3, .. })
+        ));
+        let attempts = log.0.lock().unwrap().clone();
+        let failed = Some("HTTP 500".to_string());
+        assert_eq!(
+            attempts,
+            vec![
+                ("progress".to_string(), 1, Duration::ZERO, None),
+                ("progress".to_string(), 2, Duration::from_millis(5), failed.clone()),
+                ("progress".to_string(), 3, Duration::from_millis(10), failed),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn jittered_retries_stay_under_the_computed_delay() {
+        let (url, calls) = failing_receiver().await;
+        let log = Arc::new(AttemptLog::default());
+        let delivery = WebhookDelivery::new().with_hooks(log.clone());
+        let config = retrying_webhook(
+            url,
+            RetryConfig {
+                retries: 4,
+                backoff: BackoffStrategy::Exponential,
+                initial_delay_ms: 4,
+                max_delay_ms: 20,
+                timeout_ms: 5000,
+                jitter: RetryJitter::Full,
+                max_elapsed_ms: None,
+            },
+        );
+
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 5);
+        let delays: Vec<Duration> = log.0.lock().unwrap().iter().map(|a| a.2).collect();
+        for (delay, bound) in delays.iter().zip([0, 4, 8, 16, 20]) {
+            assert!(*delay <= Duration::from_millis(bound), "{delay:?} > {bound}ms");
+        }
+    }
+
+    #[tokio::test]
+    async fn max_elapsed_cuts_retries_short() {
+        let (url, calls) = failing_receiver().await;
+        let log = Arc::new(AttemptLog::default());
+        let delivery = WebhookDelivery::new().with_hooks(log.clone());
+        let config = retrying_webhook(
+            url,
+            RetryConfig {
+                retries: 10,
+                backoff: BackoffStrategy::Fixed,
+                initial_delay_ms: 40,
+                max_delay_ms: 40,
+                timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: Some(100),
+            },
+        );
+
+        let started = Instant::now();
+        let result = delivery.send(&make_test_event(), &config).await;
+        let Err(WebhookError::DeliveryFailed { attempts, .. }) = result else {
+            panic!("expected DeliveryFailed, got {result:?}");
+        };
+        // Retries at ~40ms and ~80ms fit; one at ~120ms would not.
+        assert!((2..=3).contains(&attempts), "{attempts} attempts");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), attempts);
+        assert_eq!(log.0.lock().unwrap().len() as u32, attempts);
+        assert!(started.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn send_failure_includes_receiver_error_code() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mock_app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(|| async {
+                (
+                    axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                    axum::Json(serde_json::json!({
+                        "code": "STORE_ERROR",
+                        "message": "store unavailable"
+                    })),
+                )
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, mock_app).await.unwrap();
+        });
+
+        let delivery = WebhookDelivery::new();
+        let event = make_test_event();
+        let config = WebhookConfig {
+            url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter: None,
+            secret: None,
+            wrap: None,
+            retry: Some(RetryConfig {
+                retries: 0,
+                backoff: BackoffStrategy::Fixed,
+                initial_delay_ms: 1,
+                max_delay_ms: 1,
+                timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
+            }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        };
+
+        let err = delivery.send(&event, &config).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Webhook delivery failed after 1 attempts: HTTP 503 (STORE_ERROR)"
+        );
+        let WebhookError::DeliveryFailed { code, .. } = err else {
+            panic!("expected DeliveryFailed, got {err:?}");
+        };
+        assert_eq!(code.as_deref(), Some("STORE_ERROR"));
+    }
+
     #[tokio::test]
     async fn send_succeeds_after_transient_failure() {
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -351,6 +2147,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -360,7 +2159,14 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&event, &config).await;
@@ -395,6 +2201,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -404,7 +2213,14 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 50, // Very short timeout
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&event, &config).await;
@@ -419,6 +2235,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: "http://nonexistent.invalid:9999/hook".to_string(),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -428,7 +2247,14 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 1000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&event, &config).await;
@@ -463,6 +2289,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None, // No secret
             wrap: None,
@@ -472,7 +2301,14 @@ mod tests {
                 initial_delay_ms: 0,
                 max_delay_ms: 0,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         delivery.send(&event, &config).await.unwrap();
@@ -509,6 +2345,9 @@ mod tests {
         let event = make_test_event();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -518,7 +2357,14 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&event, &config).await;
@@ -551,10 +2397,12 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
         // 2^30 * 1000 would overflow u64 at very high attempts, but .min(max_delay_ms)
         // should clamp. With attempt=30, 2^29 * 1000 = huge, clamped to 30000.
-        let result = WebhookDelivery::backoff_ms(&retry, 30);
+        let result = backoff_ms(&retry, 30);
         assert_eq!(result, 30000);
     }
 
@@ -566,8 +2414,10 @@ mod tests {
             initial_delay_ms: 1000,
             max_delay_ms: 30000,
             timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
         };
-        assert_eq!(WebhookDelivery::backoff_ms(&retry, 50), 50000);
+        assert_eq!(backoff_ms(&retry, 50), 50000);
     }
 
     #[tokio::test]
@@ -595,6 +2445,9 @@ mod tests {
         let delivery = WebhookDelivery::new();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -604,12 +2457,21 @@ mod tests {
                 initial_delay_ms: 0,
                 max_delay_ms: 0,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&make_test_event(), &config).await;
         assert!(result.is_err());
-        let WebhookError::DeliveryFailed { attempts, .. } = result.unwrap_err();
+        let WebhookError::DeliveryFailed { attempts, .. } = result.unwrap_err() else {
+            panic!("expected DeliveryFailed");
+        };
         assert_eq!(attempts, 1);
         assert_eq!(call_count.load(std::sync::atomic::Ordering::SeqCst), 1);
     }
@@ -624,15 +2486,17 @@ mod tests {
 
         let mock_app = axum::Router::new().route(
             "/hook",
-            axum::routing::post(move |headers: axum::http::HeaderMap, _body: axum::body::Bytes| {
-                let sig = cs.clone();
-                async move {
-                    if let Some(val) = headers.get("x-taskcast-signature") {
-                        *sig.lock().await = val.to_str().unwrap().to_string();
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap, _body: axum::body::Bytes| {
+                    let sig = cs.clone();
+                    async move {
+                        if let Some(val) = headers.get("x-taskcast-signature") {
+                            *sig.lock().await = val.to_str().unwrap().to_string();
+                        }
+                        axum::http::StatusCode::OK
                     }
-                    axum::http::StatusCode::OK
-                }
-            }),
+                },
+            ),
         );
         tokio::spawn(async move {
             axum::serve(listener, mock_app).await.unwrap();
@@ -641,6 +2505,9 @@ mod tests {
         let delivery = WebhookDelivery::new();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: Some("test-secret".to_string()),
             wrap: None,
@@ -650,12 +2517,22 @@ mod tests {
                 initial_delay_ms: 0,
                 max_delay_ms: 0,
                 timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         delivery.send(&make_test_event(), &config).await.unwrap();
         let sig = captured_sig.lock().await;
-        assert!(sig.starts_with("sha256="), "expected sha256 signature, got: {sig}");
+        assert!(
+            sig.starts_with("sha256="),
+            "expected sha256 signature, got: {sig}"
+        );
     }
 
     #[tokio::test]
@@ -668,6 +2545,9 @@ mod tests {
         let delivery = WebhookDelivery::new();
         let config = WebhookConfig {
             url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
             filter: None,
             secret: None,
             wrap: None,
@@ -677,12 +2557,462 @@ mod tests {
                 initial_delay_ms: 1,
                 max_delay_ms: 1,
                 timeout_ms: 1000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
             }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
         };
 
         let result = delivery.send(&make_test_event(), &config).await;
         assert!(result.is_err());
-        let WebhookError::DeliveryFailed { attempts, .. } = result.unwrap_err();
+        let WebhookError::DeliveryFailed { attempts, .. } = result.unwrap_err() else {
+            panic!("expected DeliveryFailed");
+        };
         assert_eq!(attempts, 2); // 1 initial + 1 retry
     }
+
+    // ─── Circuit breaker ────────────────────────────────────────────────────
+
+    /// Mock receiver that counts requests and answers 200 while `healthy`.
+    async fn spawn_toggle_receiver(
+        healthy: Arc<std::sync::atomic::AtomicBool>,
+    ) -> (std::net::SocketAddr, Arc<std::sync::atomic::AtomicU32>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let calls_clone = calls.clone();
+        let mock_app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move || {
+                let calls = calls_clone.clone();
+                let healthy = healthy.clone();
+                async move {
+                    calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    if healthy.load(std::sync::atomic::Ordering::SeqCst) {
+                        axum::http::StatusCode::OK
+                    } else {
+                        axum::http::StatusCode::INTERNAL_SERVER_ERROR
+                    }
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, mock_app).await.unwrap();
+        });
+        (addr, calls)
+    }
+
+    fn breaker_config(addr: std::net::SocketAddr, retries: u32) -> WebhookConfig {
+        WebhookConfig {
+            url: format!("http://{addr}/hook"),
+            group: None,
+            suppression_window_ms: None,
+            mode: None,
+            filter: None,
+            secret: None,
+            wrap: None,
+            retry: Some(RetryConfig {
+                retries,
+                backoff: BackoffStrategy::Fixed,
+                initial_delay_ms: 1,
+                max_delay_ms: 1,
+                timeout_ms: 5000,
+                jitter: RetryJitter::None,
+                max_elapsed_ms: None,
+            }),
+            filter_preset: None,
+            backfill: None,
+            initial_delivery: None,
+            max_payload_bytes: None,
+            task_match: None,
+        }
+    }
+
+    #[test]
+    fn circuit_key_uses_host_and_explicit_port() {
+        assert_eq!(circuit_key("https://example.com/hook"), "example.com");
+        assert_eq!(circuit_key("http://127.0.0.1:8080/a?b=c"), "127.0.0.1:8080");
+        assert_eq!(circuit_key("not a url"), "not a url");
+    }
+
+    #[tokio::test]
+    async fn consecutive_failures_open_the_circuit_and_fail_fast() {
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (addr, calls) = spawn_toggle_receiver(healthy).await;
+        let delivery = WebhookDelivery::with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown_ms: 60_000,
+        });
+        let config = breaker_config(addr, 1);
+
+        for _ in 0..2 {
+            let err = delivery
+                .send(&make_test_event(), &config)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, WebhookError::DeliveryFailed { .. }));
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+        let circuits = delivery.open_circuits();
+        assert_eq!(circuits.len(), 1);
+        assert_eq!(circuits[0].host, addr.to_string());
+        assert_eq!(circuits[0].state, CircuitState::Open);
+        assert_eq!(circuits[0].consecutive_failures, 2);
+
+        let err = delivery
+            .send(&make_test_event(), &config)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("circuit-open: deliveries to {addr} are suspended")
+        );
+        assert_eq!(
+            calls.load(std::sync::atomic::Ordering::SeqCst),
+            4,
+            "an open circuit must not reach the receiver"
+        );
+    }
+
+    #[tokio::test]
+    async fn success_resets_the_failure_count() {
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (addr, _calls) = spawn_toggle_receiver(healthy.clone()).await;
+        let delivery = WebhookDelivery::with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            cooldown_ms: 60_000,
+        });
+        let config = breaker_config(addr, 0);
+
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+        healthy.store(true, std::sync::atomic::Ordering::SeqCst);
+        assert!(delivery.send(&make_test_event(), &config).await.is_ok());
+        healthy.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+
+        assert!(delivery.open_circuits().is_empty());
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_success_closes_the_circuit() {
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (addr, calls) = spawn_toggle_receiver(healthy.clone()).await;
+        let delivery = WebhookDelivery::with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown_ms: 50,
+        });
+        let config = breaker_config(addr, 0);
+
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+        assert_eq!(delivery.open_circuits().len(), 1);
+
+        healthy.store(true, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        delivery.send(&make_test_event(), &config).await.unwrap();
+
+        assert!(delivery.open_circuits().is_empty());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_probe_reopens_the_circuit_after_one_attempt() {
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (addr, calls) = spawn_toggle_receiver(healthy).await;
+        let delivery = WebhookDelivery::with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown_ms: 50,
+        });
+        let config = breaker_config(addr, 3);
+
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        let err = delivery
+            .send(&make_test_event(), &config)
+            .await
+            .unwrap_err();
+        let WebhookError::DeliveryFailed { attempts, .. } = err else {
+            panic!("expected the probe to be delivered");
+        };
+        assert_eq!(attempts, 1, "the probe must not retry");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 5);
+
+        let err = delivery
+            .send(&make_test_event(), &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebhookError::CircuitOpen { .. }));
+        assert_eq!(delivery.open_circuits()[0].state, CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn reset_circuit_closes_an_open_circuit() {
+        let healthy = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (addr, calls) = spawn_toggle_receiver(healthy).await;
+        let delivery = WebhookDelivery::with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown_ms: 60_000,
+        });
+        let config = breaker_config(addr, 0);
+
+        assert!(delivery.send(&make_test_event(), &config).await.is_err());
+        assert!(delivery.reset_circuit(&addr.to_string()));
+        assert!(!delivery.reset_circuit(&addr.to_string()));
+        assert!(delivery.open_circuits().is_empty());
+
+        assert!(matches!(
+            delivery.send(&make_test_event(), &config).await,
+            Err(WebhookError::DeliveryFailed { .. })
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    // ─── Dispatcher ─────────────────────────────────────────────────────────
+
+    struct ManualClock(Mutex<f64>);
+
+    impl ManualClock {
+        fn set(&self, now: f64) {
+            *self.0.lock().unwrap() = now;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now_ms(&self) -> f64 {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    /// Mock receiver that records the path of every delivery.
+    async fn spawn_path_receiver() -> (std::net::SocketAddr, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let paths_clone = paths.clone();
+        let mock_app = axum::Router::new().fallback(move |uri: axum::http::Uri| {
+            let paths = paths_clone.clone();
+            async move {
+                paths.lock().unwrap().push(uri.path().to_string());
+                axum::http::StatusCode::OK
+            }
+        });
+        tokio::spawn(async move {
+            axum::serve(listener, mock_app).await.unwrap();
+        });
+        (addr, paths)
+    }
+
+    fn make_task(policy: Option<&str>, webhooks: serde_json::Value) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": "task_01",
+            "status": "running",
+            "createdAt": 0.0,
+            "updatedAt": 0.0,
+            "webhooks": webhooks,
+            "groupPolicy": policy,
+        }))
+        .unwrap()
+    }
+
+    fn event(r#type: &str, level: Level) -> TaskEvent {
+        TaskEvent {
+            r#type: r#type.to_string(),
+            level,
+            ..make_test_event()
+        }
+    }
+
+    fn make_dispatcher() -> (WebhookDispatcher, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock(Mutex::new(0.0)));
+        let dispatcher = WebhookDispatcher::new(
+            Arc::new(WebhookDelivery::new()),
+            Arc::new(taskcast_core::MemoryShortTermStore::new()),
+        )
+        .with_clock(clock.clone());
+        (dispatcher, clock)
+    }
+
+    fn delivered(dispatches: &[WebhookDispatch]) -> Vec<usize> {
+        dispatches
+            .iter()
+            .filter(|d| matches!(d.outcome, DispatchOutcome::Delivered))
+            .map(|d| d.webhook)
+            .collect()
+    }
+
+    #[test]
+    fn first_match_keeps_the_first_matching_webhook_per_group() {
+        let webhooks = serde_json::json!([
+            { "url": "http://h/pager", "group": "severity", "filter": { "levels": ["error"] } },
+            { "url": "http://h/lake" },
+            { "url": "http://h/chat", "group": "severity", "filter": { "levels": ["warn", "error"] } },
+            { "url": "http://h/fallback", "group": "severity" },
+        ]);
+        let task = make_task(Some("firstMatch"), webhooks.clone());
+
+        let selected = |level| select_webhooks(&task, &event("llm.log", level)).unwrap();
+        assert_eq!(selected(Level::Error), [0, 1]);
+        assert_eq!(selected(Level::Warn), [1, 2]);
+        assert_eq!(selected(Level::Info), [1, 3]);
+
+        // `all` (and an unset policy) keeps every matching webhook.
+        for policy in [Some("all"), None] {
+            let task = make_task(policy, webhooks.clone());
+            let selected = select_webhooks(&task, &event("llm.log", Level::Error)).unwrap();
+            assert_eq!(selected, [0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn status_excluding_webhooks_are_not_selected_for_status_events() {
+        let task = make_task(
+            None,
+            serde_json::json!([
+                { "url": "http://h/quiet", "filter": { "includeStatus": false } },
+                { "url": "http://h/all" },
+            ]),
+        );
+        let status = event(LIFECYCLE_EVENT_TYPE, Level::Info);
+        assert_eq!(select_webhooks(&task, &status).unwrap(), [1]);
+        let progress = event("progress", Level::Info);
+        assert_eq!(select_webhooks(&task, &progress).unwrap(), [0, 1]);
+
+        // A missing preset is still reported.
+        let task = make_task(
+            None,
+            serde_json::json!([
+                { "url": "http://h/quiet", "filterPreset": "nope", "filter": { "includeStatus": false } },
+            ]),
+        );
+        assert!(matches!(
+            select_webhooks(&task, &status),
+            Err(FilterPresetError::UnknownPreset(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn suppression_window_drops_repeats_until_it_expires() {
+        let (addr, paths) = spawn_path_receiver().await;
+        let task = make_task(
+            None,
+            serde_json::json!([
+                { "url": format!("http://{addr}/quiet"), "suppressionWindowMs": 1000 },
+                { "url": format!("http://{addr}/loud") },
+            ]),
+        );
+        let (dispatcher, clock) = make_dispatcher();
+        let error = event("llm.error", Level::Error);
+
+        let first = dispatcher.dispatch(&task, &error).await.unwrap();
+        assert_eq!(delivered(&first), [0, 1]);
+
+        clock.set(999.0);
+        let repeat = dispatcher.dispatch(&task, &error).await.unwrap();
+        assert_eq!(delivered(&repeat), [1]);
+        assert!(matches!(repeat[0].outcome, DispatchOutcome::Suppressed));
+
+        // A different level is a different event.
+        let warn = dispatcher
+            .dispatch(&task, &event("llm.error", Level::Warn))
+            .await
+            .unwrap();
+        assert_eq!(delivered(&warn), [0, 1]);
+
+        clock.set(1000.0);
+        let expired = dispatcher.dispatch(&task, &error).await.unwrap();
+        assert_eq!(delivered(&expired), [0, 1]);
+
+        let quiet = paths
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| *p == "/quiet")
+            .count();
+        assert_eq!(quiet, 3);
+    }
+
+    #[tokio::test]
+    async fn status_events_follow_group_policy_but_are_never_suppressed() {
+        let (addr, paths) = spawn_path_receiver().await;
+        let task = make_task(
+            Some("firstMatch"),
+            serde_json::json!([
+                {
+                    "url": format!("http://{addr}/origin"),
+                    "group": "lifecycle",
+                    "filter": { "types": ["taskcast:status"] },
+                    "suppressionWindowMs": 60000,
+                },
+                { "url": format!("http://{addr}/other"), "group": "lifecycle" },
+            ]),
+        );
+        let (dispatcher, _clock) = make_dispatcher();
+
+        for _ in 0..2 {
+            let status = event(LIFECYCLE_EVENT_TYPE, Level::Info);
+            let dispatches = dispatcher.dispatch(&task, &status).await.unwrap();
+            assert_eq!(delivered(&dispatches), [0]);
+        }
+        let progress = dispatcher
+            .dispatch(&task, &event("progress", Level::Info))
+            .await
+            .unwrap();
+        assert_eq!(delivered(&progress), [1]);
+
+        assert_eq!(*paths.lock().unwrap(), ["/origin", "/origin", "/other"]);
+    }
+
+    #[tokio::test]
+    async fn sync_webhooks_are_only_delivered_by_dispatch_sync() {
+        let (addr, paths) = spawn_path_receiver().await;
+        let task = make_task(
+            None,
+            serde_json::json!([
+                { "url": format!("http://{addr}/inline"), "mode": "sync" },
+                { "url": format!("http://{addr}/background"), "mode": "async" },
+            ]),
+        );
+        let (dispatcher, _clock) = make_dispatcher();
+        let alert = event("fraud.alert", Level::Warn);
+
+        let dispatches = dispatcher.dispatch(&task, &alert).await.unwrap();
+        assert_eq!(delivered(&dispatches), [1]);
+        let results = dispatcher
+            .dispatch_sync(&task, &alert, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, format!("http://{addr}/inline"));
+        assert_eq!(results[0].status, SyncWebhookStatus::Delivered);
+        assert!(results[0].succeeded());
+
+        assert_eq!(*paths.lock().unwrap(), ["/background", "/inline"]);
+    }
+
+    #[tokio::test]
+    async fn abandoned_permit_wait_leaves_nothing_behind() {
+        let delivery = WebhookDelivery::new().with_limits(DeliveryLimits {
+            max_concurrent: 1,
+            max_per_task: 1,
+            saturation_alert: Duration::from_secs(60),
+        });
+        let held = delivery.acquire("task-1").await;
+        assert!(delivery.concurrency().saturated_since.is_some());
+
+        let waiting = tokio::time::timeout(Duration::from_millis(20), delivery.acquire("task-1"));
+        assert!(waiting.await.is_err());
+        assert_eq!(delivery.concurrency().queued, 0);
+
+        drop(held);
+        assert!(delivery.per_task.lock().unwrap().is_empty());
+        let concurrency = delivery.concurrency();
+        assert_eq!(concurrency.in_use, 0);
+        assert_eq!(concurrency.saturated_since, None);
+    }
 }
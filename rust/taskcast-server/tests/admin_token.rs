@@ -14,6 +14,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -28,7 +31,7 @@ fn make_admin_server(
 
 fn jwt_auth_mode() -> AuthMode {
     AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -125,6 +128,9 @@ async fn admin_token_returns_401_when_token_missing_from_body() {
         .json(&json!({}))
         .await;
     resp.assert_status_unauthorized();
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "INVALID_ADMIN_TOKEN");
+    assert_eq!(body["message"], "Invalid admin token");
 }
 
 #[tokio::test]
@@ -0,0 +1,148 @@
+//! Integration tests for `AuthMode::ApiKeys`.
+
+use std::sync::Arc;
+
+use axum_test::http::{HeaderName, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, PermissionScope, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, hash_api_key, ApiKeyEntry, AuthMode, CorsConfig, TaskIdAccess};
+
+const ADMIN_KEY: &str = "tck_admin-key";
+const READER_KEY: &str = "tck_reader-key";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let auth_mode = AuthMode::ApiKeys(vec![
+        ApiKeyEntry {
+            name: "admin".to_string(),
+            key_hash: hash_api_key(ADMIN_KEY),
+            task_ids: TaskIdAccess::All,
+            scope: vec![PermissionScope::All],
+        },
+        ApiKeyEntry {
+            name: "reader".to_string(),
+            key_hash: hash_api_key(READER_KEY),
+            task_ids: TaskIdAccess::List(vec!["readable".to_string()]),
+            scope: vec![PermissionScope::EventHistory],
+        },
+    ]);
+    let (app, _) = create_app(engine, auth_mode, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(key: &str) -> HeaderValue {
+    HeaderValue::from_str(&format!("Bearer {key}")).unwrap()
+}
+
+fn x_api_key() -> HeaderName {
+    HeaderName::from_static("x-api-key")
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn key_is_accepted_via_bearer_or_x_api_key() {
+    let server = make_server();
+    server
+        .post("/tasks")
+        .add_header(axum_test::http::header::AUTHORIZATION, bearer(ADMIN_KEY))
+        .json(&json!({ "id": "readable" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .get("/tasks/readable")
+        .add_header(x_api_key(), HeaderValue::from_static(ADMIN_KEY))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn limited_key_gets_403_outside_its_scope_and_task_ids() {
+    let server = make_server();
+    for id in ["readable", "other"] {
+        server
+            .post("/tasks")
+            .add_header(x_api_key(), HeaderValue::from_static(ADMIN_KEY))
+            .json(&json!({ "id": id }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    server
+        .get("/tasks/readable/events/history")
+        .add_header(x_api_key(), HeaderValue::from_static(READER_KEY))
+        .await
+        .assert_status_ok();
+
+    let resp = server
+        .post("/tasks")
+        .add_header(x_api_key(), HeaderValue::from_static(READER_KEY))
+        .json(&json!({}))
+        .await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["details"]["requiredScope"], "task:create");
+
+    server
+        .get("/tasks/other/events/history")
+        .add_header(x_api_key(), HeaderValue::from_static(READER_KEY))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn unknown_key_is_rejected_with_401() {
+    let server = make_server();
+    let resp = server
+        .get("/tasks")
+        .add_header(axum_test::http::header::AUTHORIZATION, bearer("tck_nope"))
+        .await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "INVALID_API_KEY");
+}
+
+#[tokio::test]
+async fn missing_key_is_rejected_with_401() {
+    let server = make_server();
+    let resp = server.get("/tasks").await;
+    resp.assert_status(StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "MISSING_TOKEN");
+}
+
+#[tokio::test]
+async fn configured_hash_does_not_work_as_a_key() {
+    let server = make_server();
+    let hash = hash_api_key(ADMIN_KEY);
+    server
+        .get("/tasks")
+        .add_header(x_api_key(), HeaderValue::from_str(&hash).unwrap())
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get("/tasks")
+        .add_header(axum_test::http::header::AUTHORIZATION, bearer(&hash))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn health_detail_reports_api_keys_mode() {
+    let server = make_server();
+    let body: serde_json::Value = server.get("/health/detail").await.json();
+    assert_eq!(body["auth"]["mode"], "apiKeys");
+}
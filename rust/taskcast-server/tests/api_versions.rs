@@ -0,0 +1,260 @@
+//! `/v1` routes, deprecated unprefixed aliases, the version header and the
+//! presenter seam a later version renders through.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::{TestResponse, TestServer};
+use serde_json::{json, Value};
+use taskcast_core::config::{ApiConfig, TaskcastConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{
+    create_app, create_app_with_options, ApiVersion, ApiVersions, AppOptions, AuthMode, CorsConfig,
+    LogLevel, Presenter, StderrHttpFailureLogger, API_VERSION_HEADER,
+};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(config: Option<TaskcastConfig>) -> TestServer {
+    let (app, _) = create_app(
+        make_engine(),
+        AuthMode::None,
+        None,
+        config,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+/// A v2 that renders timestamps as integers, envelopes lists and nests
+/// error bodies.
+struct ToyV2Presenter;
+
+impl ToyV2Presenter {
+    fn integer_timestamps(mut value: Value, fields: &[&str]) -> Value {
+        for field in fields {
+            if let Some(ms) = value.get(*field).and_then(Value::as_f64) {
+                value[*field] = json!(ms.round() as u64);
+            }
+        }
+        value
+    }
+}
+
+impl Presenter for ToyV2Presenter {
+    fn task(&self, task: &taskcast_core::Task) -> Value {
+        Self::integer_timestamps(
+            serde_json::to_value(task).unwrap(),
+            &["createdAt", "updatedAt", "completedAt"],
+        )
+    }
+
+    fn event(&self, event: &taskcast_core::TaskEvent) -> Value {
+        Self::integer_timestamps(serde_json::to_value(event).unwrap(), &["timestamp"])
+    }
+
+    fn task_list(&self, tasks: Vec<Value>, next_cursor: Option<String>) -> Value {
+        json!({ "items": tasks, "nextCursor": next_cursor })
+    }
+
+    fn event_list(&self, events: Vec<Value>) -> Value {
+        json!({ "items": events })
+    }
+
+    fn error(&self, body: Value) -> Value {
+        json!({ "error": body })
+    }
+}
+
+fn make_server_with_v2() -> TestServer {
+    let (app, _) = create_app_with_options(
+        make_engine(),
+        AuthMode::None,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            api_versions: Some(
+                ApiVersions::default()
+                    .with_version(ApiVersion::new("v2", Arc::new(ToyV2Presenter))),
+            ),
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+async fn task_with_event(server: &TestServer, prefix: &str) {
+    server
+        .post(&format!("{prefix}/tasks"))
+        .json(&json!({ "id": "t1" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("{prefix}/tasks/t1/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    server
+        .post(&format!("{prefix}/tasks/t1/events"))
+        .json(&json!({ "type": "log", "level": "info", "data": { "n": 1 } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+fn header(res: &TestResponse, name: &str) -> Option<String> {
+    res.maybe_header(name)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+// ─── Prefixed and Legacy Paths ───────────────────────────────────────────────
+
+#[tokio::test]
+async fn prefixed_and_legacy_paths_serve_identical_bodies() {
+    let server = make_server(None);
+    task_with_event(&server, "/v1").await;
+
+    for path in ["/tasks", "/tasks/t1", "/tasks/t1/events/history"] {
+        let v1 = server.get(&format!("/v1{path}")).await;
+        let legacy = server.get(path).await;
+        v1.assert_status_ok();
+        legacy.assert_status_ok();
+        assert_eq!(v1.json::<Value>(), legacy.json::<Value>(), "{path}");
+    }
+
+    // Errors match too, given the same request id.
+    let v1 = server
+        .get("/v1/tasks/missing")
+        .add_header("x-request-id", "req-1")
+        .await;
+    let legacy = server
+        .get("/tasks/missing")
+        .add_header("x-request-id", "req-1")
+        .await;
+    v1.assert_status(StatusCode::NOT_FOUND);
+    legacy.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(v1.json::<Value>(), legacy.json::<Value>());
+}
+
+#[tokio::test]
+async fn v1_keeps_float_timestamps() {
+    let server = make_server(None);
+    task_with_event(&server, "/v1").await;
+
+    let task: Value = server.get("/v1/tasks/t1").await.json();
+    assert!(task["createdAt"].is_f64(), "{task}");
+    let history: Value = server.get("/v1/tasks/t1/events/history").await.json();
+    assert!(history[0]["timestamp"].is_f64(), "{history}");
+}
+
+// ─── Headers ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn only_legacy_paths_are_deprecated() {
+    let server = make_server(Some(TaskcastConfig {
+        api: Some(ApiConfig {
+            legacy_deprecated_at: Some("2026-01-01".to_string()),
+            legacy_sunset_at: Some("2027-06-30".to_string()),
+        }),
+        ..Default::default()
+    }));
+
+    let legacy = server.get("/tasks").await;
+    assert_eq!(
+        header(&legacy, "deprecation"),
+        Some("@1767225600".to_string())
+    );
+    assert_eq!(
+        header(&legacy, "sunset"),
+        Some("Wed, 30 Jun 2027 00:00:00 GMT".to_string())
+    );
+    assert_eq!(
+        header(&legacy, "link"),
+        Some("</v1/tasks>; rel=\"successor-version\"".to_string())
+    );
+
+    let v1 = server.get("/v1/tasks").await;
+    assert_eq!(header(&v1, "deprecation"), None);
+    assert_eq!(header(&v1, "sunset"), None);
+    assert_eq!(header(&v1, "link"), None);
+
+    let health = server.get("/health").await;
+    assert_eq!(header(&health, "deprecation"), None);
+}
+
+#[tokio::test]
+async fn legacy_paths_without_dates_are_still_deprecated() {
+    let server = make_server(None);
+
+    let legacy = server.get("/tasks/missing").await;
+    legacy.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(header(&legacy, "deprecation"), Some("true".to_string()));
+    assert_eq!(header(&legacy, "sunset"), None);
+}
+
+#[tokio::test]
+async fn every_response_names_its_api_version() {
+    let server = make_server(None);
+
+    for path in ["/v1/tasks", "/tasks", "/v1/tasks/missing", "/health", "/"] {
+        let res = server.get(path).await;
+        assert_eq!(
+            header(&res, API_VERSION_HEADER),
+            Some("v1".to_string()),
+            "{path}"
+        );
+    }
+    let unknown = server.get("/no/such/route").await;
+    unknown.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(header(&unknown, API_VERSION_HEADER), Some("v1".to_string()));
+}
+
+// ─── Presenter Seam ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn a_v2_presenter_reshapes_bodies_without_touching_v1() {
+    let server = make_server_with_v2();
+    task_with_event(&server, "/v2").await;
+
+    let v2 = server.get("/v2/tasks/t1").await;
+    assert_eq!(header(&v2, API_VERSION_HEADER), Some("v2".to_string()));
+    assert_eq!(header(&v2, "deprecation"), None);
+    let task: Value = v2.json();
+    assert!(task["createdAt"].is_u64(), "{task}");
+    assert_eq!(task["hot"], json!(false));
+
+    let v1: Value = server.get("/v1/tasks/t1").await.json();
+    assert!(v1["createdAt"].is_f64(), "{v1}");
+    assert_eq!(
+        v1["createdAt"].as_f64().unwrap().round(),
+        task["createdAt"].as_f64().unwrap()
+    );
+
+    let list: Value = server.get("/v2/tasks").await.json();
+    assert_eq!(list["items"][0]["id"], "t1");
+    let history: Value = server.get("/v2/tasks/t1/events/history").await.json();
+    assert!(history["items"][0]["timestamp"].is_u64(), "{history}");
+    let published: Value = server
+        .post("/v2/tasks/t1/events")
+        .json(&json!({ "type": "log", "level": "info", "data": null }))
+        .await
+        .json();
+    assert!(published["timestamp"].is_u64(), "{published}");
+
+    let missing = server.get("/v2/tasks/missing").await;
+    missing.assert_status(StatusCode::NOT_FOUND);
+    let body: Value = missing.json();
+    assert_eq!(body["error"]["code"], "TASK_NOT_FOUND");
+    let legacy: Value = server.get("/tasks/missing").await.json();
+    assert_eq!(legacy["code"], "TASK_NOT_FOUND");
+}
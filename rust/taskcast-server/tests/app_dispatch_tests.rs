@@ -17,6 +17,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -20,6 +20,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -38,7 +41,7 @@ fn make_no_auth_server() -> (Arc<TaskEngine>, TestServer) {
 fn make_jwt_server() -> (Arc<TaskEngine>, TestServer) {
     let engine = make_engine();
     let auth = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -54,6 +57,9 @@ fn make_corrupt_history_server() -> TestServer {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: Some(Arc::new(AccumulatedOnlyLongTermStore)),
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
     TestServer::new(app)
@@ -116,6 +122,9 @@ async fn publish_event(
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -359,6 +368,16 @@ impl LongTermStore for AccumulatedOnlyLongTermStore {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         }))
     }
 
@@ -389,6 +408,8 @@ impl LongTermStore for AccumulatedOnlyLongTermStore {
             series_mode: Some(SeriesMode::Accumulate),
             series_acc_field: Some("delta".to_string()),
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }])
     }
@@ -14,6 +14,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -127,7 +130,8 @@ async fn resolve_returns_400_for_non_blocked_task() {
 
     response.assert_status(axum_test::http::StatusCode::BAD_REQUEST);
     let body: serde_json::Value = response.json();
-    assert!(body["error"].as_str().unwrap().contains("not blocked"));
+    assert!(body["message"].as_str().unwrap().contains("not blocked"));
+    assert_eq!(body["details"]["errors"][0], body["message"]);
 }
 
 #[tokio::test]
@@ -0,0 +1,88 @@
+//! Integration tests for `broadcastDebounceMs` and `seriesEnd` on
+//! `POST /tasks/{id}/events`.
+
+use std::sync::{Arc, Mutex};
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    BroadcastProvider, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+#[tokio::test]
+async fn publish_body_debounces_series_broadcasts() {
+    let broadcast = Arc::new(MemoryBroadcastProvider::new());
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let server = TestServer::new(app);
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t1" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    let live = Arc::new(Mutex::new(Vec::<TaskEvent>::new()));
+    let sink = Arc::clone(&live);
+    let _unsubscribe = broadcast
+        .subscribe(
+            "task.t1",
+            Box::new(move |event| sink.lock().unwrap().push(event)),
+        )
+        .await;
+
+    let token = |delta: &str, series_end: bool| {
+        json!({
+            "type": "llm.delta",
+            "level": "info",
+            "data": { "delta": delta },
+            "seriesId": "answer",
+            "seriesMode": "accumulate",
+            "broadcastDebounceMs": 60_000,
+            "seriesEnd": series_end,
+        })
+    };
+    server
+        .post("/tasks/t1/events")
+        .json(&json!([token("Hel", false), token("lo", false)]))
+        .await
+        .assert_status(StatusCode::CREATED);
+    assert!(live.lock().unwrap().is_empty());
+
+    server
+        .post("/tasks/t1/events")
+        .json(&token("!", true))
+        .await
+        .assert_status(StatusCode::CREATED);
+    let live = live.lock().unwrap().clone();
+    assert_eq!(live.len(), 1);
+    assert_eq!(live[0].index, 3);
+    assert_eq!(live[0].data, json!({ "delta": "Hello!" }));
+    assert_eq!(live[0].series_snapshot, Some(true));
+
+    let history = engine.get_events("t1", None).await.unwrap();
+    assert_eq!(
+        history.iter().filter(|e| e.r#type == "llm.delta").count(),
+        3
+    );
+}
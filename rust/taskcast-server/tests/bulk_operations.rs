@@ -0,0 +1,346 @@
+//! Integration tests for `POST /admin/tasks/bulk`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{BulkOperationsConfig, JwtAlgorithm, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+    TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "bulk-operations-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, config: Option<TaskcastConfig>) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        AuthMode::None,
+        None,
+        config,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+async fn create(engine: &TaskEngine, id: &str, r#type: &str, metadata: Value) -> f64 {
+    let metadata: Option<HashMap<String, Value>> = serde_json::from_value(metadata).unwrap();
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            r#type: Some(r#type.to_string()),
+            metadata,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .created_at
+}
+
+async fn status(engine: &TaskEngine, id: &str) -> TaskStatus {
+    engine.get_task(id).await.unwrap().unwrap().status
+}
+
+fn ids(values: &Value) -> Vec<&str> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap_or_else(|| v["taskId"].as_str().unwrap()))
+        .collect()
+}
+
+// ─── Selection ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn selector_combines_status_type_time_and_metadata() {
+    let engine = make_engine();
+    create(
+        &engine,
+        "old-import",
+        "import.csv",
+        json!({ "tenant": "acme" }),
+    )
+    .await;
+    create(
+        &engine,
+        "other-tenant",
+        "import.csv",
+        json!({ "tenant": "globex" }),
+    )
+    .await;
+    create(&engine, "export", "export.csv", json!({ "tenant": "acme" })).await;
+    create(
+        &engine,
+        "running",
+        "import.json",
+        json!({ "tenant": "acme" }),
+    )
+    .await;
+    engine
+        .transition_task("running", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    let cutoff = create(
+        &engine,
+        "new-import",
+        "import.csv",
+        json!({ "tenant": "acme" }),
+    )
+    .await;
+    let server = make_server(&engine, None);
+
+    let body: Value = server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": {
+                "status": ["pending"],
+                "types": ["import.*"],
+                "createdBefore": cutoff,
+                "metadata": { "tenant": "acme" },
+            },
+            "action": { "transition": { "to": "cancelled" } },
+            "dryRun": true,
+        }))
+        .await
+        .json();
+    assert_eq!(body["dryRun"], true);
+    assert_eq!(body["matched"], 1);
+    assert_eq!(ids(&body["taskIds"]), ["old-import"]);
+}
+
+#[tokio::test]
+async fn dry_run_lists_exactly_the_tasks_execution_changes() {
+    let engine = make_engine();
+    for id in ["a", "b", "c"] {
+        create(&engine, id, "import.csv", json!(null)).await;
+    }
+    create(&engine, "d", "export.csv", json!(null)).await;
+    let server = make_server(&engine, None);
+    let request = |dry_run: bool| {
+        json!({
+            "selector": { "types": ["import.*"] },
+            "action": { "transition": { "to": "cancelled" } },
+            "dryRun": dry_run,
+        })
+    };
+
+    let dry: Value = server
+        .post("/admin/tasks/bulk")
+        .json(&request(true))
+        .await
+        .json();
+    for id in ["a", "b", "c", "d"] {
+        assert_eq!(status(&engine, id).await, TaskStatus::Pending);
+    }
+
+    let executed: Value = server
+        .post("/admin/tasks/bulk")
+        .json(&request(false))
+        .await
+        .json();
+    assert_eq!(executed["dryRun"], false);
+    assert_eq!(executed["matched"], dry["matched"]);
+    assert_eq!(ids(&executed["results"]), ids(&dry["taskIds"]));
+    for id in ["a", "b", "c"] {
+        assert_eq!(status(&engine, id).await, TaskStatus::Cancelled);
+    }
+    assert_eq!(status(&engine, "d").await, TaskStatus::Pending);
+}
+
+// ─── Execution ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn invalid_transitions_are_reported_per_task() {
+    let engine = make_engine();
+    create(&engine, "done", "import.csv", json!(null)).await;
+    create(&engine, "running", "import.csv", json!(null)).await;
+    for id in ["running", "done"] {
+        engine
+            .transition_task(id, TaskStatus::Running, None)
+            .await
+            .unwrap();
+    }
+    engine
+        .transition_task("done", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    let server = make_server(&engine, None);
+
+    let res = server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": { "types": ["import.*"] },
+            "action": {
+                "transition": { "to": "failed", "error": { "message": "Aborted by operator" } },
+            },
+        }))
+        .await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["matched"], 2);
+    assert_eq!(body["succeeded"], 1);
+    assert_eq!(body["failed"], 1);
+    assert_eq!(
+        body["results"],
+        json!([
+            {
+                "taskId": "done",
+                "ok": false,
+                "error": "Invalid transition: Completed \u{2192} Failed",
+            },
+            { "taskId": "running", "ok": true },
+        ])
+    );
+
+    let failed = engine.get_task("running").await.unwrap().unwrap();
+    assert_eq!(failed.status, TaskStatus::Failed);
+    assert_eq!(failed.error.unwrap().message, "Aborted by operator");
+    assert_eq!(status(&engine, "done").await, TaskStatus::Completed);
+}
+
+#[tokio::test]
+async fn add_metadata_and_apply_cleanup_rule_update_tasks() {
+    let engine = make_engine();
+    create(
+        &engine,
+        "a",
+        "import.csv",
+        json!({ "tenant": "acme", "note": "x" }),
+    )
+    .await;
+    let server = make_server(&engine, None);
+
+    server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": {},
+            "action": { "addMetadata": { "note": "incident-42", "reviewed": true } },
+        }))
+        .await
+        .assert_status_ok();
+    server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": {},
+            "action": {
+                "applyCleanupRule": {
+                    "rule": { "trigger": { "afterMs": 60000 }, "target": "all" },
+                },
+            },
+        }))
+        .await
+        .assert_status_ok();
+
+    let task = engine.get_task("a").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Pending);
+    assert_eq!(
+        json!(task.metadata),
+        json!({ "tenant": "acme", "note": "incident-42", "reviewed": true })
+    );
+    let rules = task.cleanup.unwrap().rules;
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].trigger.after_ms, Some(60000));
+}
+
+// ─── Limits and Auth ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn selections_over_the_cap_are_rejected() {
+    let engine = make_engine();
+    for id in ["a", "b", "c"] {
+        create(&engine, id, "import.csv", json!(null)).await;
+    }
+    let config = TaskcastConfig {
+        bulk_operations: Some(BulkOperationsConfig {
+            max_tasks: Some(2),
+            concurrency: None,
+        }),
+        ..Default::default()
+    };
+    let server = make_server(&engine, Some(config));
+
+    for dry_run in [true, false] {
+        let res = server
+            .post("/admin/tasks/bulk")
+            .json(&json!({
+                "selector": {},
+                "action": { "transition": { "to": "cancelled" } },
+                "dryRun": dry_run,
+            }))
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.json::<Value>()["message"],
+            "Selector matches 3 tasks, more than the limit of 2 per bulk operation"
+        );
+    }
+    for id in ["a", "b", "c"] {
+        assert_eq!(status(&engine, id).await, TaskStatus::Pending);
+    }
+
+    server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": { "createdBefore": 0 },
+            "action": { "transition": { "to": "cancelled" } },
+        }))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn bulk_operations_require_task_manage() {
+    let engine = make_engine();
+    let auth_mode = AuthMode::Jwt(JwtConfig {
+        algorithm: JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    });
+    let (app, _) = create_app(engine, auth_mode, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+    let token = |scope: &str| {
+        let token = encode(
+            &Header::default(),
+            &json!({ "sub": "bulk-test", "scope": [scope], "taskIds": "*", "exp": 9999999999u64 }),
+            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+        )
+        .unwrap();
+        HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+    };
+    let body = json!({ "selector": {}, "action": { "addMetadata": {} }, "dryRun": true });
+
+    server
+        .post("/admin/tasks/bulk")
+        .add_header(header::AUTHORIZATION, token("event:subscribe"))
+        .json(&body)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/admin/tasks/bulk")
+        .add_header(header::AUTHORIZATION, token("task:manage"))
+        .json(&body)
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,197 @@
+//! Integration tests for `POST /tasks/{id}/cancel`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+    TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "cancel-task-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth: AuthMode) -> TestServer {
+    let (app, _) = create_app(Arc::clone(engine), auth, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn jwt_auth() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "orchestrator", "scope": scope, "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn running_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn cancel_records_the_reason() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server
+        .post("/tasks/t1/cancel")
+        .json(&json!({ "reason": "user aborted" }))
+        .await;
+
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["status"], "cancelled");
+    assert_eq!(
+        body["error"],
+        json!({ "code": "CANCELLED", "message": "user aborted" })
+    );
+    let history: Vec<Value> = server
+        .get("/tasks/t1/events/history?types=taskcast:status")
+        .await
+        .json();
+    assert_eq!(history.last().unwrap()["data"]["reason"], "user aborted");
+}
+
+#[tokio::test]
+async fn cancel_without_a_body() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server.post("/tasks/t1/cancel").await;
+
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["error"]["message"], "Task cancelled");
+}
+
+#[tokio::test]
+async fn finished_task_conflicts() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server.post("/tasks/t1/cancel").json(&json!({})).await;
+
+    res.assert_status(StatusCode::CONFLICT);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "TASK_TERMINAL");
+}
+
+#[tokio::test]
+async fn missing_task_is_not_found() {
+    let server = make_server(&make_engine(), AuthMode::None);
+
+    server
+        .post("/tasks/nope/cancel")
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn cancel_requires_task_transition() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    running_task(&engine, "t2").await;
+    let server = make_server(&engine, jwt_auth());
+
+    server
+        .post("/tasks/t1/cancel")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/tasks/t1/cancel")
+        .add_header(header::AUTHORIZATION, bearer(&["task:transition"]))
+        .await
+        .assert_status_ok();
+    // `task:manage` implies `task:transition`.
+    server
+        .post("/tasks/t2/cancel")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn cancel_checks_if_match() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server
+        .post("/tasks/t1/cancel")
+        .add_header(header::IF_MATCH, HeaderValue::from_static("1"))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    assert_eq!(res.json::<Value>()["code"], "CONCURRENT_MODIFICATION");
+
+    let res = server
+        .post("/tasks/t1/cancel")
+        .add_header(header::IF_MATCH, HeaderValue::from_static("2"))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<Value>()["status"], "cancelled");
+}
+
+#[tokio::test]
+async fn body_without_a_content_type_is_refused() {
+    let engine = make_engine();
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None);
+
+    server
+        .post("/tasks/t1/cancel")
+        .bytes(r#"{"reason":"user aborted"}"#.into())
+        .await
+        .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let task = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Running);
+}
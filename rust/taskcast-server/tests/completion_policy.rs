@@ -0,0 +1,90 @@
+//! Integration tests for `parentId` and `completionPolicy` on task creation.
+
+use std::sync::Arc;
+
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+fn make_server() -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+async fn transition(server: &TestServer, task_id: &str, body: Value) {
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&body)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn children_fail_their_parent_over_http() {
+    let server = make_server();
+    let parent: Value = server
+        .post("/tasks")
+        .json(&json!({ "id": "deploy", "completionPolicy": { "mode": "allChildren" } }))
+        .await
+        .json();
+    assert_eq!(parent["completionPolicy"], json!({ "mode": "allChildren" }));
+    for id in ["build", "ship"] {
+        let child: Value = server
+            .post("/tasks")
+            .json(&json!({ "id": id, "parentId": "deploy" }))
+            .await
+            .json();
+        assert_eq!(child["parentId"], "deploy");
+        transition(&server, id, json!({ "status": "running" })).await;
+    }
+
+    transition(&server, "build", json!({ "status": "completed" })).await;
+    transition(
+        &server,
+        "ship",
+        json!({ "status": "failed", "error": { "message": "no capacity" } }),
+    )
+    .await;
+
+    let parent: Value = server.get("/tasks/deploy").await.json();
+    assert_eq!(parent["status"], "failed");
+    assert_eq!(parent["error"]["code"], "CHILD_FAILED");
+    assert_eq!(
+        parent["error"]["details"]["failedChildIds"],
+        json!(["ship"])
+    );
+    assert_eq!(
+        parent["completionPolicy"]["childIds"],
+        json!(["build", "ship"])
+    );
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "late", "parentId": "deploy" }))
+        .await;
+    res.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn invalid_policies_are_bad_requests() {
+    let server = make_server();
+    for body in [
+        json!({ "id": "p", "completionPolicy": { "mode": "allChildren", "childIds": ["p"] } }),
+        json!({ "id": "p", "completionPolicy": { "mode": "sometimes" } }),
+        json!({ "id": "p", "parentId": "missing" }),
+    ] {
+        let status = server.post("/tasks").json(&body).await.status_code();
+        assert!(status.is_client_error(), "{body}: {status}");
+    }
+    server.get("/tasks/p").await.assert_status_not_found();
+}
@@ -0,0 +1,164 @@
+//! Integration tests for `POST /admin/consistency`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::JwtAlgorithm;
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskEvent,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "consistency-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+/// A task whose stored history holds an index its counter never handed out.
+async fn stale_counter_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let event = TaskEvent {
+        id: format!("{id}-restored"),
+        task_id: id.to_string(),
+        index: 7,
+        timestamp: 1000.0,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!({}),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    };
+    engine
+        .short_term_store()
+        .append_event(id, event)
+        .await
+        .unwrap();
+}
+
+// ─── Scan and Repair ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn dry_run_reports_and_a_repair_run_fixes() {
+    let engine = make_engine();
+    stale_counter_task(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    let body: Value = server
+        .post("/admin/consistency")
+        .json(&json!({ "dryRun": true }))
+        .await
+        .json();
+    assert_eq!(body["dryRun"], true);
+    assert_eq!(body["repair"], false);
+    assert_eq!(body["shortTermTasksScanned"], 1);
+    assert_eq!(body["counts"]["staleIndexCounters"], 1);
+    assert_eq!(
+        body["findings"][0],
+        json!({ "kind": "staleIndexCounter", "taskId": "t1", "nextIndex": 0, "maxIndex": 7 })
+    );
+    assert_eq!(
+        body["skipped"],
+        json!(["orphanedEvents, missingFromLongTerm: no long-term store"])
+    );
+
+    let body: Value = server
+        .post("/admin/consistency")
+        .json(&json!({}))
+        .await
+        .json();
+    assert_eq!(body["dryRun"], false);
+    assert_eq!(body["repaired"], 1);
+    assert_eq!(body["findings"][0]["repaired"], true);
+
+    let body: Value = server
+        .post("/admin/consistency")
+        .json(&json!({ "dryRun": true }))
+        .await
+        .json();
+    assert_eq!(body["counts"]["staleIndexCounters"], 0);
+    assert_eq!(body["findings"], json!([]));
+}
+
+#[tokio::test]
+async fn unknown_orphan_policies_are_rejected() {
+    let engine = make_engine();
+    make_server(&engine)
+        .post("/admin/consistency")
+        .json(&json!({ "orphanPolicy": "archive" }))
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// ─── Auth ────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn consistency_scans_require_task_manage() {
+    let auth_mode = AuthMode::Jwt(JwtConfig {
+        algorithm: JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    });
+    let (app, _) = create_app(make_engine(), auth_mode, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+    let token = |scope: &str| {
+        let token = encode(
+            &Header::default(),
+            &json!({ "sub": "consistency-test", "scope": [scope], "taskIds": "*", "exp": 9999999999u64 }),
+            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+        )
+        .unwrap();
+        HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+    };
+
+    server
+        .post("/admin/consistency")
+        .add_header(header::AUTHORIZATION, token("task:create"))
+        .json(&json!({ "dryRun": true }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/admin/consistency")
+        .add_header(header::AUTHORIZATION, token("task:manage"))
+        .json(&json!({ "dryRun": true }))
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,219 @@
+//! Integration tests for the event convention catalog at `/conventions`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    DiscoveryOptions, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "conventions-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(discovery: bool) -> Arc<TaskEngine> {
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    if !discovery {
+        return Arc::new(engine);
+    }
+    Arc::new(engine.with_discovery(DiscoveryOptions {
+        sample_rate: 1.0,
+        ..Default::default()
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth_mode: AuthMode) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        auth_mode,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn jwt_mode() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "conventions-test", "scope": scope, "taskIds": "*", "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn publish_on_typed_task(
+    server: &TestServer,
+    task_id: &str,
+    task_type: &str,
+    events: &[&str],
+) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id, "type": task_type }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    for event_type in events {
+        server
+            .post(&format!("/tasks/{task_id}/events"))
+            .json(&json!({ "type": event_type, "level": "info", "data": { "password": "hunter2", "n": 1 } }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+}
+
+// ─── Catalog ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn catalog_lists_sampled_event_types() {
+    let engine = make_engine(true);
+    let server = make_server(&engine, AuthMode::None);
+    publish_on_typed_task(
+        &server,
+        "chat-1",
+        "llm.chat",
+        &["llm.delta", "llm.delta", "llm.done"],
+    )
+    .await;
+    publish_on_typed_task(&server, "import-1", "import.csv", &["rows"]).await;
+
+    let catalog: Value = server.get("/conventions").await.json();
+    let conventions = catalog["conventions"].as_array().unwrap();
+    assert_eq!(conventions.len(), 3);
+    assert_eq!(conventions[0]["taskType"], "import.csv");
+    assert_eq!(conventions[1]["eventType"], "llm.delta");
+    assert_eq!(conventions[1]["estimatedCount"], 2);
+    assert_eq!(
+        conventions[1]["example"],
+        json!({ "password": "[REDACTED]", "n": 1 })
+    );
+
+    let catalog: Value = server.get("/v1/conventions/llm.chat").await.json();
+    let event_types: Vec<&str> = catalog["conventions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["eventType"].as_str().unwrap())
+        .collect();
+    assert_eq!(event_types, vec!["llm.delta", "llm.done"]);
+
+    server
+        .get("/conventions/unknown")
+        .await
+        .assert_status_not_found();
+}
+
+#[tokio::test]
+async fn catalog_is_not_found_while_discovery_is_off() {
+    let engine = make_engine(false);
+    let server = make_server(&engine, AuthMode::None);
+    publish_on_typed_task(&server, "chat-1", "llm.chat", &["llm.delta"]).await;
+
+    server.get("/conventions").await.assert_status_not_found();
+    server
+        .put("/conventions/llm.chat/llm.delta")
+        .json(&json!({ "description": "Text" }))
+        .await
+        .assert_status_not_found();
+}
+
+// ─── Curation ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn curated_notes_round_trip() {
+    let engine = make_engine(true);
+    let server = make_server(&engine, AuthMode::None);
+    let notes = json!({
+        "description": "Incremental completion text",
+        "schema": { "type": "object", "required": ["text"] }
+    });
+
+    let res = server
+        .put("/conventions/llm.chat/llm.delta")
+        .json(&notes)
+        .await;
+    res.assert_status_ok();
+    let convention: Value = res.json();
+    assert_eq!(convention["estimatedCount"], 0);
+    assert_eq!(convention["description"], notes["description"]);
+
+    publish_on_typed_task(&server, "chat-1", "llm.chat", &["llm.delta"]).await;
+    let catalog: Value = server.get("/conventions/llm.chat").await.json();
+    let convention = &catalog["conventions"][0];
+    assert_eq!(convention["estimatedCount"], 1);
+    assert_eq!(convention["schema"], notes["schema"]);
+}
+
+#[tokio::test]
+async fn curation_rejects_non_schemas() {
+    let engine = make_engine(true);
+    let server = make_server(&engine, AuthMode::None);
+    server
+        .put("/conventions/llm.chat/llm.delta")
+        .json(&json!({ "schema": "object" }))
+        .await
+        .assert_status_bad_request();
+    server
+        .put("/conventions/llm.chat/llm.delta")
+        .json(&json!({ "schema": true }))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn reading_needs_event_history_and_curating_needs_task_manage() {
+    let engine = make_engine(true);
+    let server = make_server(&engine, jwt_mode());
+
+    server
+        .get("/conventions")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .get("/conventions")
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .await
+        .assert_status_ok();
+
+    server
+        .put("/conventions/llm.chat/llm.delta")
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .json(&json!({ "description": "Text" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .put("/conventions/llm.chat/llm.delta")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .json(&json!({ "description": "Text" }))
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,497 @@
+//! Integration tests for debug bundles and
+//! `GET /admin/tasks/{task_id}/debug-bundle`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use flate2::read::GzDecoder;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{parse_config, ConfigFormat, HttpTapConfig};
+use taskcast_core::{
+    CreateTaskInput, EventQueryOptions, Level, LongTermStore, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, Task, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskStatus, WorkerAuditEvent,
+};
+use taskcast_server::{
+    create_app_with_options, AppOptions, AuthMode, DebugBundle, DebugBundleOptions,
+    DebugBundleSources, HttpTap, JwtConfig, LogLevel, RuntimeInfo, StderrHttpFailureLogger,
+};
+
+const JWT_SECRET: &str = "debug-bundle-planted-jwt-secret-long-enough";
+const ADMIN_TOKEN: &str = "debug-bundle-planted-admin-token";
+const WEBHOOK_SECRET: &str = "debug-bundle-planted-webhook-secret";
+const REDIS_PASSWORD: &str = "debug-bundle-planted-redis-password";
+const ENV_NAME: &str = "TASKCAST_BUNDLE_TEST_SECRET";
+const ENV_VALUE: &str = "debug-bundle-planted-env-value";
+
+// ─── Long-term store ─────────────────────────────────────────────────────────
+
+/// Long-term store keeping tasks and events in maps.
+#[derive(Default)]
+struct MapLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    events: Mutex<BTreeMap<String, Vec<TaskEvent>>>,
+}
+
+#[async_trait]
+impl LongTermStore for MapLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.events
+            .lock()
+            .unwrap()
+            .entry(event.task_id.clone())
+            .or_default()
+            .push(event);
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .get(task_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(long_term: Option<Arc<MapLongTermStore>>) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: long_term.map(|store| store as Arc<dyn LongTermStore>),
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn config(log_file: &std::path::Path) -> taskcast_core::config::TaskcastConfig {
+    let yaml = format!(
+        r#"
+adminApi: true
+adminToken: {ADMIN_TOKEN}
+auth:
+  mode: jwt
+  jwt:
+    algorithm: HS256
+    secret: {JWT_SECRET}
+adapters:
+  broadcast:
+    provider: redis
+    url: redis://:{REDIS_PASSWORD}@cache:6379
+debug:
+  bundle:
+    logFile: {}
+    logLines: 2
+    redactEnv: ["TASKCAST_BUNDLE_*"]
+"#,
+        log_file.display()
+    );
+    parse_config(&yaml, ConfigFormat::Yaml).unwrap()
+}
+
+fn jwt_mode() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "debug-bundle-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn fixture_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            webhooks: Some(vec![serde_json::from_value(json!({
+                "url": "https://hooks.example.com/done",
+                "secret": WEBHOOK_SECRET,
+            }))
+            .unwrap()]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine
+        .publish_event(
+            id,
+            PublishEventInput {
+                r#type: "log".to_string(),
+                level: Level::Info,
+                data: json!({ "message": "step one" }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+    // Long-term event writes run in the background.
+    assert!(engine.background().drain(Duration::from_secs(5)).await);
+}
+
+/// Unpacks a tar.gz into `(name, contents)` pairs.
+fn unpack(archive: &[u8]) -> BTreeMap<String, String> {
+    let mut tar = tar::Archive::new(GzDecoder::new(archive));
+    tar.entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            (name, contents)
+        })
+        .collect()
+}
+
+fn absent_names(manifest: &Value) -> Vec<&str> {
+    manifest["absent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["name"].as_str().unwrap())
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn endpoint_bundles_the_expected_files_without_planted_secrets() {
+    std::env::set_var(ENV_NAME, ENV_VALUE);
+    let dir = tempfile::tempdir().unwrap();
+    let log_file = dir.path().join("taskcast.log");
+    std::fs::write(
+        &log_file,
+        format!(
+            "{{\"msg\":\"boot\"}}\n{{\"msg\":\"env {ENV_VALUE}\"}}\n{{\"msg\":\"signed with {WEBHOOK_SECRET}\"}}\n"
+        ),
+    )
+    .unwrap();
+
+    let long_term = Arc::new(MapLongTermStore::default());
+    let engine = make_engine(Some(Arc::clone(&long_term)));
+    fixture_task(&engine, "bundle-task").await;
+    let tap = Arc::new(HttpTap::new(&HttpTapConfig {
+        enabled: Some(true),
+        ..Default::default()
+    }));
+    let config = config(&log_file);
+    let (app, _) = create_app_with_options(
+        Arc::clone(&engine),
+        jwt_mode(),
+        AppOptions {
+            config: Some(config.clone()),
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            http_tap: Some(Arc::clone(&tap)),
+            runtime_info: Some(Arc::new(RuntimeInfo::from_config(Some(&config)))),
+            ..Default::default()
+        },
+    );
+    let server = TestServer::new(app);
+
+    server
+        .get("/tasks/bundle-task")
+        .add_header(header::AUTHORIZATION, bearer(&["*"]))
+        .await
+        .assert_status_ok();
+    let res = server
+        .get("/admin/tasks/bundle-task/debug-bundle")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.header(header::CONTENT_TYPE), "application/gzip");
+
+    let files = unpack(res.as_bytes());
+    let names: Vec<&str> = files.keys().map(String::as_str).collect();
+    assert_eq!(
+        names,
+        [
+            "http-tap.json",
+            "integrity.json",
+            "logs.txt",
+            "manifest.json",
+            "metrics.json",
+            "runtime.json",
+            "task/long-term.json",
+            "task/short-term.json",
+        ]
+    );
+
+    let short_term: Value = serde_json::from_str(&files["task/short-term.json"]).unwrap();
+    assert_eq!(short_term["task"]["status"], "running");
+    assert_eq!(short_term["task"]["webhooks"][0]["secret"], "[REDACTED]");
+    assert_eq!(short_term["events"].as_array().unwrap().len(), 2);
+    let long_term: Value = serde_json::from_str(&files["task/long-term.json"]).unwrap();
+    assert_eq!(long_term["events"], short_term["events"]);
+    let tap: Value = serde_json::from_str(&files["http-tap.json"]).unwrap();
+    assert_eq!(tap["entries"][0]["path"], "/tasks/bundle-task");
+    let runtime: Value = serde_json::from_str(&files["runtime.json"]).unwrap();
+    assert_eq!(runtime["config"]["adminToken"], "[REDACTED]");
+    assert_eq!(files["logs.txt"].lines().count(), 2);
+
+    let manifest: Value = serde_json::from_str(&files["manifest.json"]).unwrap();
+    assert_eq!(manifest["taskId"], "bundle-task");
+    assert_eq!(
+        absent_names(&manifest),
+        ["task/divergence.json", "webhook-dlq.json"]
+    );
+    assert!(manifest["redaction"]["maskedEnvVars"]
+        .as_array()
+        .unwrap()
+        .contains(&json!(ENV_NAME)));
+    assert_eq!(manifest["redaction"]["replacements"]["logs.txt"], 2);
+
+    let everything: String = files.values().map(String::as_str).collect();
+    for secret in [
+        JWT_SECRET,
+        ADMIN_TOKEN,
+        WEBHOOK_SECRET,
+        REDIS_PASSWORD,
+        ENV_VALUE,
+    ] {
+        assert!(
+            !everything.contains(secret),
+            "{secret} leaked into the bundle"
+        );
+    }
+}
+
+#[tokio::test]
+async fn bundle_notes_divergence_between_stores() {
+    let long_term = Arc::new(MapLongTermStore::default());
+    let engine = make_engine(Some(Arc::clone(&long_term)));
+    fixture_task(&engine, "diverged").await;
+    let mut stale = engine.get_task("diverged").await.unwrap().unwrap();
+    stale.status = TaskStatus::Pending;
+    long_term.save_task(stale).await.unwrap();
+    let lost = long_term
+        .events
+        .lock()
+        .unwrap()
+        .get_mut("diverged")
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let options = DebugBundleOptions::default();
+    let info = RuntimeInfo::default();
+    let bundle = DebugBundle::collect(
+        &DebugBundleSources {
+            engine: &engine,
+            auth_mode: &AuthMode::None,
+            uptime: Duration::ZERO,
+            runtime_info: &info,
+            http_tap: None,
+            runtime_sampler: None,
+            options: &options,
+        },
+        "diverged",
+    )
+    .await
+    .unwrap();
+
+    let long_term: Value =
+        serde_json::from_str(bundle.file("task/long-term.json").unwrap()).unwrap();
+    assert_eq!(long_term["task"]["status"], "pending");
+    assert_eq!(long_term["events"].as_array().unwrap().len(), 1);
+    let divergence: Value =
+        serde_json::from_str(bundle.file("task/divergence.json").unwrap()).unwrap();
+    assert!(divergence["taskFieldsDiffer"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("status")));
+    assert_eq!(divergence["events"]["shortTermCount"], 2);
+    assert_eq!(divergence["events"]["longTermCount"], 1);
+    assert_eq!(divergence["events"]["onlyInShortTerm"], json!([lost.id]));
+}
+
+#[tokio::test]
+async fn bundle_without_optional_subsystems_is_partial() {
+    let engine = make_engine(None);
+    fixture_task(&engine, "bare").await;
+    let options = DebugBundleOptions::default();
+    let info = RuntimeInfo::default();
+    let bundle = DebugBundle::collect(
+        &DebugBundleSources {
+            engine: &engine,
+            auth_mode: &AuthMode::None,
+            uptime: Duration::ZERO,
+            runtime_info: &info,
+            http_tap: None,
+            runtime_sampler: None,
+            options: &options,
+        },
+        "bare",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        bundle.file_names().collect::<Vec<_>>(),
+        [
+            "manifest.json",
+            "runtime.json",
+            "task/short-term.json",
+            "integrity.json",
+        ]
+    );
+    let manifest = bundle.manifest();
+    assert_eq!(
+        absent_names(&manifest),
+        [
+            "task/long-term.json",
+            "task/divergence.json",
+            "webhook-dlq.json",
+            "http-tap.json",
+            "metrics.json",
+            "logs.txt",
+        ]
+    );
+    let integrity: Value = serde_json::from_str(bundle.file("integrity.json").unwrap()).unwrap();
+    assert_eq!(integrity["corruptRecords"], 0);
+    assert_eq!(integrity["consistency"]["repair"], false);
+
+    let files = unpack(&bundle.to_tar_gz().unwrap());
+    assert_eq!(files.len(), 4);
+}
+
+#[tokio::test]
+async fn bundle_with_unreadable_log_file_records_why() {
+    let engine = make_engine(None);
+    fixture_task(&engine, "no-log").await;
+    let options = DebugBundleOptions {
+        log_file: Some("/nonexistent/taskcast.log".into()),
+        ..Default::default()
+    };
+    let info = RuntimeInfo::default();
+    let bundle = DebugBundle::collect(
+        &DebugBundleSources {
+            engine: &engine,
+            auth_mode: &AuthMode::None,
+            uptime: Duration::ZERO,
+            runtime_info: &info,
+            http_tap: None,
+            runtime_sampler: None,
+            options: &options,
+        },
+        "no-log",
+    )
+    .await
+    .unwrap();
+
+    assert!(bundle.file("logs.txt").is_none());
+    let manifest = bundle.manifest();
+    let logs = manifest["absent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == "logs.txt")
+        .unwrap();
+    assert!(logs["reason"]
+        .as_str()
+        .unwrap()
+        .starts_with("Could not read /nonexistent/taskcast.log"));
+}
+
+#[tokio::test]
+async fn endpoint_requires_task_manage_and_an_existing_task() {
+    let dir = tempfile::tempdir().unwrap();
+    let engine = make_engine(None);
+    fixture_task(&engine, "guarded").await;
+    let config = config(&dir.path().join("taskcast.log"));
+    let (app, _) = create_app_with_options(
+        Arc::clone(&engine),
+        jwt_mode(),
+        AppOptions {
+            config: Some(config),
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            ..Default::default()
+        },
+    );
+    let server = TestServer::new(app);
+
+    server
+        .get("/admin/tasks/guarded/debug-bundle")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .get("/admin/tasks/missing/debug-bundle")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .get("/v1/admin/tasks/guarded/debug-bundle")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,441 @@
+//! Structured error body tests: `code`/`message`/`details`, request ids,
+//! the `ErrorMessageProvider` hook, and in-band SSE error events.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use axum_test::http::HeaderValue;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    CorruptRecord, CorruptRecordPolicy, EventQueryOptions, IntegrityMonitor,
+    MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore, Task, TaskEngine,
+    TaskEngineOptions, TaskEvent, TaskFilter, Worker, WorkerAssignment, WorkerFilter,
+};
+use taskcast_server::{
+    create_app, create_app_with_options, AppOptions, AuthMode, CorsConfig, ErrorMessageProvider,
+    ErrorPayload, JwtConfig, StderrHttpFailureLogger, LogLevel, REQUEST_ID_HEADER,
+};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+/// Delegates to MemoryShortTermStore but can be flipped to fail `get_events`,
+/// so an SSE stream can open successfully and then fail during history replay,
+/// or to read tasks back as corrupt records.
+struct FailingEventsStore {
+    inner: MemoryShortTermStore,
+    fail_get_events: AtomicBool,
+    corrupt_tasks: AtomicBool,
+    integrity: IntegrityMonitor,
+}
+
+impl FailingEventsStore {
+    fn new() -> Self {
+        Self {
+            inner: MemoryShortTermStore::new(),
+            fail_get_events: AtomicBool::new(false),
+            corrupt_tasks: AtomicBool::new(false),
+            integrity: IntegrityMonitor::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for FailingEventsStore {
+    async fn save_task(
+        &self,
+        task: Task,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.corrupt_tasks.load(Ordering::SeqCst) {
+            let raw = json!({ "id": task_id, "status": "exploded" });
+            let record = CorruptRecord::task(task_id, raw, "status: unknown variant `exploded`");
+            return Ok(self.integrity.task(Err(record))?);
+        }
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.fail_get_events.load(Ordering::SeqCst) {
+            return Err("injected get_events failure".into());
+        }
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_series_latest(task_id, series_id, event).await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        Some(&self.integrity)
+    }
+}
+
+fn make_engine_with_store(store: Arc<dyn ShortTermStore>) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server() -> TestServer {
+    let engine = make_engine_with_store(Arc::new(MemoryShortTermStore::new()));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+/// Translates a couple of codes to German when the client asks for it.
+struct GermanMessages;
+
+impl ErrorMessageProvider for GermanMessages {
+    fn message(&self, error: &ErrorPayload, request_headers: &HeaderMap) -> Option<String> {
+        let wants_german = request_headers
+            .get("accept-language")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("de"));
+        if !wants_german {
+            return None;
+        }
+        match error.code {
+            "TASK_NOT_FOUND" => Some(format!(
+                "Aufgabe nicht gefunden: {}",
+                error.details.as_ref()?["taskId"].as_str()?
+            )),
+            "MISSING_TOKEN" => Some("Bearer-Token fehlt".to_string()),
+            _ => None,
+        }
+    }
+}
+
+fn make_localized_server(auth_mode: AuthMode) -> TestServer {
+    let engine = make_engine_with_store(Arc::new(MemoryShortTermStore::new()));
+    let (app, _) = create_app_with_options(
+        engine,
+        auth_mode,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            error_messages: Some(Arc::new(GermanMessages)),
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+// ─── Request ids ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn error_body_includes_generated_request_id_matching_header() {
+    let server = make_server();
+
+    let response = server.get("/tasks/missing").await;
+
+    response.assert_status_not_found();
+    let body: serde_json::Value = response.json();
+    let request_id = body["requestId"].as_str().unwrap();
+    assert_eq!(request_id.len(), 26, "expected a ULID, got {request_id}");
+    assert_eq!(response.header(REQUEST_ID_HEADER), request_id);
+    assert_eq!(
+        body,
+        json!({
+            "code": "TASK_NOT_FOUND",
+            "message": "Task not found",
+            "details": { "taskId": "missing" },
+            "requestId": request_id,
+        })
+    );
+}
+
+#[tokio::test]
+async fn caller_supplied_request_id_is_echoed() {
+    let server = make_server();
+
+    let response = server
+        .patch("/tasks/missing/status")
+        .add_header(REQUEST_ID_HEADER, HeaderValue::from_static("req-abc-123"))
+        .json(&json!({ "status": "running" }))
+        .await;
+
+    response.assert_status_not_found();
+    assert_eq!(response.header(REQUEST_ID_HEADER), "req-abc-123");
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["requestId"], "req-abc-123");
+}
+
+#[tokio::test]
+async fn successful_responses_carry_request_id_header_only() {
+    let server = make_server();
+
+    let response = server.post("/tasks").json(&json!({ "id": "ok-task" })).await;
+
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+    assert!(response.maybe_header(REQUEST_ID_HEADER).is_some());
+    let body: serde_json::Value = response.json();
+    assert!(body.get("requestId").is_none());
+}
+
+#[tokio::test]
+async fn validation_errors_list_the_failure_in_details() {
+    let server = make_server();
+
+    let response = server.post("/tasks").json(&json!({ "ttl": 0 })).await;
+
+    response.assert_status_bad_request();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(body["details"]["errors"], json!([body["message"]]));
+}
+
+// ─── ErrorMessageProvider ───────────────────────────────────────────────────
+
+#[tokio::test]
+async fn message_provider_rewrites_message_but_keeps_structure() {
+    let server = make_localized_server(AuthMode::None);
+
+    let response = server
+        .get("/tasks/missing")
+        .add_header("accept-language", HeaderValue::from_static("de-DE"))
+        .await;
+
+    response.assert_status_not_found();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["code"], "TASK_NOT_FOUND");
+    assert_eq!(body["message"], "Aufgabe nicht gefunden: missing");
+    assert_eq!(body["details"], json!({ "taskId": "missing" }));
+    assert!(body["requestId"].is_string());
+}
+
+#[tokio::test]
+async fn message_provider_returning_none_keeps_default_message() {
+    let server = make_localized_server(AuthMode::None);
+
+    let response = server.get("/tasks/missing").await;
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["message"], "Task not found");
+}
+
+#[tokio::test]
+async fn message_provider_applies_to_auth_middleware_errors() {
+    let server = make_localized_server(AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some("test-secret-key-for-jwt-signing-needs-to-be-long-enough".to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    }));
+
+    let response = server
+        .post("/tasks")
+        .add_header("accept-language", HeaderValue::from_static("de"))
+        .json(&json!({}))
+        .await;
+
+    response.assert_status_unauthorized();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["code"], "MISSING_TOKEN");
+    assert_eq!(body["message"], "Bearer-Token fehlt");
+}
+
+// ─── SSE ────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_history_failure_emits_error_event_with_code() {
+    let store = Arc::new(FailingEventsStore::new());
+    let engine = make_engine_with_store(store.clone());
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+
+    server.post("/tasks").json(&json!({ "id": "sse-fail" })).await;
+    store.fail_get_events.store(true, Ordering::SeqCst);
+
+    let response = server.get("/tasks/sse-fail/events").await;
+
+    response.assert_status_ok();
+    let text = response.text();
+    assert!(text.contains("event: taskcast.error"), "got:\n{text}");
+    assert!(text.contains(r#""code":"STORE_ERROR""#), "got:\n{text}");
+    assert!(!text.contains("event: taskcast.done"), "got:\n{text}");
+}
+
+// ─── Corrupt records ────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn corrupt_task_is_reported_with_code_and_counted() {
+    let store = Arc::new(FailingEventsStore::new());
+    assert_eq!(store.integrity.policy(), CorruptRecordPolicy::Surface);
+    let engine = make_engine_with_store(store.clone());
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+
+    server.post("/tasks").json(&json!({ "id": "bad-task" })).await;
+    store.corrupt_tasks.store(true, Ordering::SeqCst);
+
+    let response = server.get("/tasks/bad-task").await;
+
+    response.assert_status(axum_test::http::StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["code"], "CORRUPT_RECORD");
+    assert_eq!(
+        body["details"],
+        json!({ "taskId": "bad-task", "kind": "task", "id": "bad-task", "position": null })
+    );
+
+    let integrity: serde_json::Value = server.get("/tasks/bad-task/integrity").await.json();
+    assert_eq!(
+        integrity,
+        json!({ "taskId": "bad-task", "corruptRecords": 1 })
+    );
+    let other: serde_json::Value = server.get("/tasks/other/integrity").await.json();
+    assert_eq!(other["corruptRecords"], 0);
+
+    let health: serde_json::Value = server.get("/health/detail").await.json();
+    assert_eq!(health["integrity"]["corruptRecords"], 1);
+}
@@ -0,0 +1,267 @@
+//! Integration tests for the `diff` attached to `taskcast:status` and
+//! `taskcast:updated` events.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, EventDiffs, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TASK_UPDATED_EVENT,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(diffs: EventDiffs) -> Arc<TaskEngine> {
+    Arc::new(
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_event_diffs(diffs),
+    )
+}
+
+fn make_server(engine: &Arc<TaskEngine>) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+async fn create(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            metadata: Some(HashMap::from([("tenant".to_string(), json!("acme"))])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+async fn transition(server: &TestServer, id: &str, body: Value) {
+    server
+        .patch(&format!("/tasks/{id}/status"))
+        .json(&body)
+        .await
+        .assert_status_ok();
+}
+
+async fn history(server: &TestServer, id: &str, r#type: &str) -> Vec<Value> {
+    let res = server
+        .get(&format!("/tasks/{id}/events/history?types={type}"))
+        .await;
+    res.assert_status_ok();
+    let events: Vec<Value> = res.json();
+    events.into_iter().map(|e| e["data"].clone()).collect()
+}
+
+/// `op path` of each operation in `diff`, sorted.
+fn ops(diff: &Value) -> Vec<String> {
+    let mut ops: Vec<String> = diff
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|op| {
+            format!(
+                "{} {}",
+                op["op"].as_str().unwrap(),
+                op["path"].as_str().unwrap()
+            )
+        })
+        .collect();
+    ops.sort();
+    ops
+}
+
+fn apply(document: &Value, diff: &Value) -> Value {
+    let patch: json_patch::Patch = serde_json::from_value(diff.clone()).unwrap();
+    let mut document = document.clone();
+    json_patch::patch(&mut document, &patch).unwrap();
+    document
+}
+
+// ─── Status Events ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn status_events_diff_added_changed_and_removed_result_keys() {
+    let engine = make_engine(EventDiffs::default());
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    transition(
+        &server,
+        "t1",
+        json!({ "status": "running", "result": { "rows": 10, "file": "a.csv" } }),
+    )
+    .await;
+    transition(
+        &server,
+        "t1",
+        json!({ "status": "completed", "result": { "rows": 12, "errors": 1 } }),
+    )
+    .await;
+
+    let events = history(&server, "t1", "taskcast:status").await;
+    assert_eq!(events.len(), 2);
+    let (running, completed) = (&events[0], &events[1]);
+    assert_eq!(
+        apply(&json!({ "result": null }), &running["diff"]),
+        json!({ "result": { "rows": 10, "file": "a.csv" } })
+    );
+    assert_eq!(
+        ops(&completed["diff"]),
+        [
+            "add /result/errors",
+            "remove /result/file",
+            "replace /result/rows"
+        ]
+    );
+    assert_eq!(
+        apply(&json!({ "result": running["result"] }), &completed["diff"]),
+        json!({ "result": completed["result"] })
+    );
+}
+
+#[tokio::test]
+async fn status_event_fields_are_unchanged() {
+    let engine = make_engine(EventDiffs::default());
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    transition(&server, "t1", json!({ "status": "running" })).await;
+    transition(
+        &server,
+        "t1",
+        json!({ "status": "failed", "error": { "message": "disk full" } }),
+    )
+    .await;
+
+    let events = history(&server, "t1", "taskcast:status").await;
+    // Neither transition changed the result, so neither carries a diff.
+    assert_eq!(
+        events[0],
+        json!({ "status": "running", "result": null, "error": null })
+    );
+    assert_eq!(events[1]["status"], "failed");
+    assert_eq!(events[1]["result"], Value::Null);
+    assert_eq!(events[1]["error"]["message"], "disk full");
+    assert!(events[1].get("diff").is_none());
+}
+
+#[tokio::test]
+async fn diffs_over_the_size_cap_are_omitted() {
+    let engine = make_engine(EventDiffs {
+        enabled: true,
+        max_bytes: 256,
+    });
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    transition(&server, "t1", json!({ "status": "running" })).await;
+    transition(
+        &server,
+        "t1",
+        json!({ "status": "completed", "result": { "text": "x".repeat(1024) } }),
+    )
+    .await;
+
+    let events = history(&server, "t1", "taskcast:status").await;
+    assert_eq!(
+        events[1]["diff"],
+        json!({ "diffOmitted": true, "reason": "too-large" })
+    );
+    // The full result is still carried.
+    assert_eq!(events[1]["result"]["text"].as_str().unwrap().len(), 1024);
+}
+
+#[tokio::test]
+async fn disabled_diffs_leave_status_events_as_before() {
+    let engine = make_engine(EventDiffs {
+        enabled: false,
+        ..Default::default()
+    });
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    transition(&server, "t1", json!({ "status": "running" })).await;
+    transition(
+        &server,
+        "t1",
+        json!({ "status": "completed", "result": { "rows": 1 } }),
+    )
+    .await;
+
+    let events = history(&server, "t1", "taskcast:status").await;
+    assert_eq!(
+        events[1],
+        json!({ "status": "completed", "result": { "rows": 1 }, "error": null })
+    );
+}
+
+// ─── Update Events ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn metadata_updates_publish_an_updated_event_with_a_diff() {
+    let engine = make_engine(EventDiffs::default());
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": {},
+            "action": { "addMetadata": { "tenant": "globex", "reviewed": true } },
+        }))
+        .await
+        .assert_status_ok();
+
+    let events = history(&server, "t1", TASK_UPDATED_EVENT).await;
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(
+        event["metadata"],
+        json!({ "tenant": "globex", "reviewed": true })
+    );
+    assert_eq!(
+        ops(&event["diff"]),
+        ["add /metadata/reviewed", "replace /metadata/tenant"]
+    );
+    assert_eq!(
+        apply(
+            &json!({ "result": null, "metadata": { "tenant": "acme" } }),
+            &event["diff"]
+        ),
+        json!({ "result": event["result"], "metadata": event["metadata"] })
+    );
+}
+
+#[tokio::test]
+async fn updates_that_change_nothing_publish_no_event() {
+    let engine = make_engine(EventDiffs::default());
+    create(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": {},
+            "action": { "addMetadata": { "tenant": "acme" } },
+        }))
+        .await
+        .assert_status_ok();
+
+    assert!(history(&server, "t1", TASK_UPDATED_EVENT).await.is_empty());
+}
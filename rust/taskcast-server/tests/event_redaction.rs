@@ -0,0 +1,381 @@
+//! Integration tests for `DELETE /tasks/:id/events`: erased events are
+//! replaced by tombstones at their indices in every store, and the erasure
+//! is announced to live subscribers.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    EventQueryOptions, LongTermStore, MemoryBroadcastProvider, MemoryShortTermStore, Task,
+    TaskEngine, TaskEngineOptions, TaskEvent, WorkerAuditEvent, REDACTED_EVENT_TYPE,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "redaction-test-secret-key-that-is-long-enough-for-hs256";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// Long-term store keeping everything in memory.
+#[derive(Default)]
+struct RecordingLongTermStore {
+    tasks: Mutex<HashMap<String, Task>>,
+    events: Mutex<Vec<TaskEvent>>,
+}
+
+#[async_trait]
+impl LongTermStore for RecordingLongTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tasks.lock().unwrap().get(task_id).cloned())
+    }
+
+    async fn save_event(
+        &self,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut events: Vec<TaskEvent> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.task_id == task_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.index);
+        Ok(events)
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut events = self.events.lock().unwrap();
+        let mut replaced = 0;
+        for tombstone in tombstones {
+            if let Some(event) = events
+                .iter_mut()
+                .find(|e| e.task_id == task_id && e.id == tombstone.id)
+            {
+                *event = tombstone.clone();
+                replaced += 1;
+            }
+        }
+        Ok(replaced)
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+fn make_engine(long_term_store: Option<Arc<dyn LongTermStore>>) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: Arc<TaskEngine>, auth_mode: AuthMode) -> TestServer {
+    let (app, _) = create_app(engine, auth_mode, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "redaction-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// Creates a running task and publishes one event per type, returning the
+/// published events' ids. The task's status event takes index 0.
+async fn seed(server: &TestServer, task_id: &str, types: &[&str]) -> Vec<String> {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    let mut ids = Vec::new();
+    for r#type in types {
+        let event: Value = server
+            .post(&format!("/tasks/{task_id}/events"))
+            .json(&json!({
+                "type": r#type,
+                "level": "info",
+                "data": { "email": "someone@example.com" },
+            }))
+            .await
+            .json();
+        ids.push(event["id"].as_str().unwrap().to_string());
+    }
+    ids
+}
+
+async fn history(server: &TestServer, task_id: &str, query: &str) -> Vec<Value> {
+    server
+        .get(&format!("/tasks/{task_id}/events/history?{query}"))
+        .await
+        .json::<Value>()
+        .as_array()
+        .unwrap()
+        .clone()
+}
+
+fn types_of(events: &[Value]) -> Vec<&str> {
+    events.iter().map(|e| e["type"].as_str().unwrap()).collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn deleting_by_id_leaves_tombstones_at_the_same_indices() {
+    let server = make_server(make_engine(None), AuthMode::None);
+    let ids = seed(&server, "t1", &["user.profile", "log", "user.profile"]).await;
+
+    let res = server
+        .delete("/tasks/t1/events")
+        .json(&json!({ "eventIds": [ids[0], ids[2]], "reason": "erasure request" }))
+        .await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["eventIds"], json!([ids[0], ids[2]]));
+    assert_eq!(body["shortTerm"], 2);
+    assert_eq!(body["longTerm"], Value::Null);
+    assert_eq!(body["notificationIndex"], 4);
+
+    let events = history(&server, "t1", "").await;
+    assert_eq!(
+        types_of(&events),
+        vec![
+            "taskcast:status",
+            REDACTED_EVENT_TYPE,
+            "log",
+            REDACTED_EVENT_TYPE,
+            REDACTED_EVENT_TYPE
+        ]
+    );
+    assert_eq!(
+        events[1],
+        json!({
+            "id": ids[0],
+            "taskId": "t1",
+            "index": 1,
+            "timestamp": events[1]["timestamp"],
+            "type": REDACTED_EVENT_TYPE,
+            "level": "info",
+            "data": { "reason": "erasure request" },
+        })
+    );
+    assert_eq!(events[4]["data"]["eventIds"], json!([ids[0], ids[2]]));
+}
+
+#[tokio::test]
+async fn deleting_by_filter_cleans_both_stores() {
+    let long_term_store = Arc::new(RecordingLongTermStore::default());
+    let engine = make_engine(Some(Arc::clone(&long_term_store) as Arc<dyn LongTermStore>));
+    let server = make_server(Arc::clone(&engine), AuthMode::None);
+    let ids = seed(&server, "t1", &["user.profile", "log", "user.email"]).await;
+    // Long-term writes land in the background.
+    for _ in 0..100 {
+        if long_term_store.events.lock().unwrap().len() == 4 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let body: Value = server
+        .delete("/tasks/t1/events")
+        .json(&json!({ "filter": { "types": ["user.*"] } }))
+        .await
+        .json();
+    assert_eq!(body["eventIds"], json!([ids[0], ids[2]]));
+    assert_eq!(body["shortTerm"], 2);
+    assert_eq!(body["longTerm"], 2);
+
+    let short_term = engine.get_events("t1", None).await.unwrap();
+    let long_term = long_term_store.get_events("t1", None).await.unwrap();
+    for events in [&short_term[..4], &long_term[..4]] {
+        let types: Vec<&str> = events.iter().map(|e| e.r#type.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "taskcast:status",
+                REDACTED_EVENT_TYPE,
+                "log",
+                REDACTED_EVENT_TYPE
+            ]
+        );
+        assert_eq!(events[1].data, json!({ "reason": null }));
+        assert_eq!(events[3].id, ids[2]);
+    }
+}
+
+#[tokio::test]
+async fn deletion_is_announced_to_live_subscribers() {
+    let engine = make_engine(None);
+    let server = make_server(Arc::clone(&engine), AuthMode::None);
+    let ids = seed(&server, "t1", &["user.profile"]).await;
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&received);
+    let _unsubscribe = engine
+        .subscribe(
+            "t1",
+            Box::new(move |event| recorded.lock().unwrap().push(event)),
+        )
+        .await;
+
+    server
+        .delete("/tasks/t1/events")
+        .json(&json!({ "eventIds": [ids[0]], "reason": "erasure request" }))
+        .await
+        .assert_status_ok();
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].r#type, REDACTED_EVENT_TYPE);
+    assert_eq!(
+        received[0].data,
+        json!({ "eventIds": [ids[0]], "reason": "erasure request" })
+    );
+}
+
+#[tokio::test]
+async fn cursors_page_across_the_erased_range() {
+    let server = make_server(make_engine(None), AuthMode::None);
+    let ids = seed(&server, "t1", &["a", "b", "c", "d"]).await;
+    server
+        .delete("/tasks/t1/events")
+        .json(&json!({ "eventIds": [ids[1], ids[2]] }))
+        .await
+        .assert_status_ok();
+
+    let page = history(&server, "t1", "since.index=1&limit=2").await;
+    let indices: Vec<u64> = page.iter().map(|e| e["index"].as_u64().unwrap()).collect();
+    assert_eq!(indices, vec![2, 3]);
+    assert_eq!(page[0]["id"], json!(ids[1]));
+    let page = history(&server, "t1", "since.index=3&limit=2").await;
+    assert_eq!(types_of(&page), vec!["d", REDACTED_EVENT_TYPE]);
+    let page = history(&server, "t1", &format!("since.id={}", ids[2])).await;
+    assert_eq!(types_of(&page), vec!["d", REDACTED_EVENT_TYPE]);
+}
+
+#[tokio::test]
+async fn nothing_is_announced_when_nothing_matches() {
+    let server = make_server(make_engine(None), AuthMode::None);
+    seed(&server, "t1", &["log"]).await;
+
+    let body: Value = server
+        .delete("/tasks/t1/events")
+        .json(&json!({ "filter": { "types": ["user.*", "taskcast:status"] } }))
+        .await
+        .json();
+    assert_eq!(body["eventIds"], json!([]));
+    assert_eq!(body["notificationIndex"], Value::Null);
+    assert_eq!(history(&server, "t1", "").await.len(), 2);
+}
+
+#[tokio::test]
+async fn invalid_selections_and_missing_tasks_are_refused() {
+    let server = make_server(make_engine(None), AuthMode::None);
+    seed(&server, "t1", &["log"]).await;
+
+    for body in [
+        json!({}),
+        json!({ "eventIds": [] }),
+        json!({ "eventIds": ["e1"], "filter": {} }),
+    ] {
+        let res = server.delete("/tasks/t1/events").json(&body).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+    }
+    server
+        .delete("/tasks/missing/events")
+        .json(&json!({ "eventIds": ["e1"] }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn deleting_needs_task_manage_and_event_delete() {
+    let engine = make_engine(None);
+    seed(
+        &make_server(Arc::clone(&engine), AuthMode::None),
+        "t1",
+        &["log"],
+    )
+    .await;
+    let server = make_server(
+        engine,
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+    );
+    let body = json!({ "filter": { "types": ["log"] } });
+
+    for (scope, status) in [
+        (&["task:manage"][..], StatusCode::FORBIDDEN),
+        (&["event:delete"][..], StatusCode::FORBIDDEN),
+        (&["task:manage", "event:delete"][..], StatusCode::OK),
+    ] {
+        server
+            .delete("/tasks/t1/events")
+            .add_header(header::AUTHORIZATION, bearer(scope))
+            .json(&body)
+            .await
+            .assert_status(status);
+    }
+}
@@ -0,0 +1,172 @@
+//! Tests for `GET /tasks/{id}/events/stats` and the `taskcast.init` SSE frame.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    SeriesMode, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn input(r#type: &str, level: Level, series_id: Option<&str>) -> PublishEventInput {
+    PublishEventInput {
+        r#type: r#type.to_string(),
+        level,
+        data: json!({}),
+        series_id: series_id.map(str::to_string),
+        series_mode: series_id.map(|_| SeriesMode::Latest),
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+/// A completed task with two deltas, a warning and a replaced phase event.
+async fn completed_task(engine: &TaskEngine) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for event in [
+        input("llm.delta", Level::Info, None),
+        input("llm.delta", Level::Info, None),
+        input("tool.retry", Level::Warn, None),
+        input("phase.plan", Level::Info, Some("phase")),
+        input("phase.execute", Level::Info, Some("phase")),
+    ] {
+        engine.publish_event("t1", event).await.unwrap();
+    }
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+// ─── Stats Endpoint ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn stats_count_events_by_type_and_level() {
+    let engine = make_engine();
+    completed_task(&engine).await;
+
+    let body: Value = make_server(&engine)
+        .get("/tasks/t1/events/stats")
+        .await
+        .json();
+    assert_eq!(body["taskId"], "t1");
+    assert_eq!(
+        body["types"],
+        json!({
+            "llm.delta": 2,
+            "phase.execute": 1,
+            "taskcast:status": 2,
+            "tool.retry": 1,
+        })
+    );
+    assert_eq!(body["levels"], json!({ "info": 5, "warn": 1 }));
+    assert_eq!(body["total"], 6);
+    assert_eq!(body["scanEnd"], 7);
+}
+
+#[tokio::test]
+async fn stats_for_a_missing_task_are_not_found() {
+    make_server(&make_engine())
+        .get("/tasks/missing/events/stats")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+// ─── SSE Init Frame ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_streams_open_with_the_task_event_types() {
+    let engine = make_engine();
+    completed_task(&engine).await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let body = reqwest::get(format!("http://{addr}/tasks/t1/events"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    let mut lines = body.lines().filter(|line| !line.is_empty());
+    assert_eq!(lines.next(), Some("event: taskcast.init"));
+    let data: Value =
+        serde_json::from_str(lines.next().unwrap().strip_prefix("data: ").unwrap()).unwrap();
+    assert_eq!(
+        data,
+        json!({
+            "taskId": "t1",
+            "types": ["llm.delta", "phase.execute", "taskcast:status", "tool.retry"],
+        })
+    );
+    assert!(body.contains("event: taskcast.done"));
+}
+
+#[tokio::test]
+async fn sse_init_types_follow_the_subscription_type_filter() {
+    let engine = make_engine();
+    completed_task(&engine).await;
+
+    let body = make_server(&engine)
+        .get("/tasks/t1/events")
+        .add_query_param("types", "llm.*,tool.*")
+        .await
+        .text();
+    let init = body
+        .lines()
+        .skip_while(|line| *line != "event: taskcast.init")
+        .nth(1)
+        .and_then(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let data: Value = serde_json::from_str(init).unwrap();
+    assert_eq!(data["types"], json!(["llm.delta", "tool.retry"]));
+}
@@ -0,0 +1,164 @@
+//! Integration tests for published event type checks and type normalization.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, EventTypeRules, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskStatus, TypeNormalization,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(rules: EventTypeRules) -> Arc<TaskEngine> {
+    Arc::new(
+        TaskEngine::new(TaskEngineOptions {
+            short_term_store: Arc::new(MemoryShortTermStore::new()),
+            broadcast: Arc::new(MemoryBroadcastProvider::new()),
+            long_term_store: None,
+            hooks: None,
+            label_limits: None,
+            sinks: Vec::new(),
+            coalesce_reads: None,
+        })
+        .with_event_types(rules),
+    )
+}
+
+fn make_server(engine: &Arc<TaskEngine>) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+async fn running_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+fn event(r#type: &str) -> Value {
+    json!({ "type": r#type, "level": "info", "data": null })
+}
+
+async fn history_types(server: &TestServer, query: &str) -> Vec<String> {
+    let res = server.get(&format!("/tasks/t1/events/history?{query}")).await;
+    res.assert_status_ok();
+    res.json::<Vec<Value>>()
+        .iter()
+        .map(|e| e["type"].as_str().unwrap().to_string())
+        .filter(|t| !t.starts_with("taskcast:"))
+        .collect()
+}
+
+fn violations(body: &Value) -> Vec<(&str, &str)> {
+    body["details"]["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["pointer"].as_str().unwrap(),
+                v["message"].as_str().unwrap(),
+            )
+        })
+        .collect()
+}
+
+// ─── Publish checks ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn malformed_types_are_rejected_with_a_pointer() {
+    let engine = make_engine(EventTypeRules::default());
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    let res = server
+        .post("/tasks/t1/events")
+        .json(&event(&"x".repeat(2048)))
+        .await;
+    res.assert_status_bad_request();
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    let found = violations(&body);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0, "/type");
+    assert!(found[0].1.contains("must be 1-256"), "{}", found[0].1);
+
+    let res = server
+        .post("/tasks/t1/events")
+        .json(&json!([event("ok"), event("bad type"), event("a,b")]))
+        .await;
+    res.assert_status_bad_request();
+    let body: Value = res.json();
+    let found = violations(&body);
+    assert_eq!(
+        found.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+        ["/1/type", "/2/type"]
+    );
+    assert!(found[1].1.contains("commas are not allowed"), "{}", found[1].1);
+
+    // Nothing in the rejected batch was appended.
+    assert!(history_types(&server, "").await.is_empty());
+}
+
+// ─── Normalization ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn types_match_exactly_by_default() {
+    let engine = make_engine(EventTypeRules::default());
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    server
+        .post("/tasks/t1/events")
+        .json(&json!([event("Progress"), event("progress")]))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    assert_eq!(history_types(&server, "types=Progress").await, ["Progress"]);
+    assert_eq!(history_types(&server, "types=progress").await, ["progress"]);
+}
+
+#[tokio::test]
+async fn lowercase_mode_normalizes_published_types_and_query_filters() {
+    let engine = make_engine(EventTypeRules {
+        normalization: TypeNormalization::Lowercase,
+        ..Default::default()
+    });
+    running_task(&engine, "t1").await;
+    let server = make_server(&engine);
+
+    server
+        .post("/tasks/t1/events")
+        .json(&json!([event("Progress"), event("PROGRESS"), event("LLM.Delta")]))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    assert_eq!(
+        history_types(&server, "").await,
+        ["progress", "progress", "llm.delta"]
+    );
+    assert_eq!(
+        history_types(&server, "types=Progress").await,
+        ["progress", "progress"]
+    );
+    assert_eq!(history_types(&server, "types=llm.*").await, ["llm.delta"]);
+    assert_eq!(history_types(&server, "types=LLM.*").await, ["llm.delta"]);
+}
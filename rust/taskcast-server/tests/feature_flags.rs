@@ -0,0 +1,165 @@
+//! Integration tests for feature flags: the `includeDiffs` and
+//! `strictBodies` switches follow their flag for the tasks it targets, and
+//! `GET /admin/flags` reports each flag's evaluations.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::config::FlagRule;
+use taskcast_core::{
+    EventDiffs, FlagEvaluator, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// A server whose only flag is `name`, on for `llm.*` tasks. Diffs are off
+/// and bodies lenient unless the flag says otherwise.
+fn make_server(name: &str) -> TestServer {
+    let rule = FlagRule {
+        enabled: true,
+        task_types: Some(vec!["llm.*".to_string()]),
+        ..Default::default()
+    };
+    let flags = FlagEvaluator::from_config(Some(&HashMap::from([(name.to_string(), rule)])));
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_event_diffs(EventDiffs {
+        enabled: false,
+        ..Default::default()
+    })
+    .with_flags(Arc::new(flags));
+    let (app, _) = create_app(
+        Arc::new(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+async fn create_running(server: &TestServer, id: &str, task_type: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": id, "type": task_type }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("/tasks/{id}/status"))
+        .json(&json!({ "status": "running", "result": { "rows": 1 } }))
+        .await
+        .assert_status_ok();
+}
+
+async fn status_events(server: &TestServer, id: &str) -> Vec<Value> {
+    let res = server
+        .get(&format!("/tasks/{id}/events/history?types=taskcast:status"))
+        .await;
+    res.assert_status_ok();
+    let events: Vec<Value> = res.json();
+    events.into_iter().map(|e| e["data"].clone()).collect()
+}
+
+fn misspelled_event() -> Value {
+    json!({ "type": "log", "level": "info", "data": {}, "serieId": "s1" })
+}
+
+// ─── includeDiffs ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn include_diffs_flag_attaches_diffs_only_for_targeted_tasks() {
+    let server = make_server("includeDiffs");
+    create_running(&server, "chat", "llm.chat").await;
+    create_running(&server, "export", "export").await;
+
+    let targeted = status_events(&server, "chat").await;
+    assert_eq!(
+        targeted[0]["diff"],
+        json!([{ "op": "replace", "path": "/result", "value": { "rows": 1 } }])
+    );
+    let untargeted = status_events(&server, "export").await;
+    assert!(untargeted[0].get("diff").is_none(), "{}", untargeted[0]);
+}
+
+// ─── strictBodies ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn strict_bodies_flag_rejects_unknown_fields_only_for_targeted_tasks() {
+    let server = make_server("strictBodies");
+    create_running(&server, "chat", "llm.chat").await;
+    create_running(&server, "export", "export").await;
+
+    let res = server
+        .post("/tasks/chat/events")
+        .json(&misspelled_event())
+        .await;
+    res.assert_status_bad_request();
+    let body: Value = res.json();
+    assert_eq!(body["details"]["violations"][0]["pointer"], "/serieId");
+
+    server
+        .post("/tasks/export/events")
+        .json(&misspelled_event())
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn strict_bodies_flag_applies_to_ndjson_streams() {
+    let server = make_server("strictBodies");
+    create_running(&server, "chat", "llm.chat").await;
+    create_running(&server, "export", "export").await;
+    let line = misspelled_event().to_string();
+
+    for (id, rejected) in [("chat", true), ("export", false)] {
+        let res = server
+            .post(&format!("/tasks/{id}/events/stream"))
+            .content_type("application/x-ndjson")
+            .bytes(Bytes::from(line.clone()))
+            .await;
+        let out: Value = serde_json::from_str(res.text().lines().next().unwrap()).unwrap();
+        assert_eq!(
+            out["error"]["code"] == "INVALID_INPUT",
+            rejected,
+            "{id}: {out}"
+        );
+    }
+}
+
+// ─── GET /admin/flags ────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn admin_flags_lists_rules_and_evaluation_counts() {
+    let server = make_server("includeDiffs");
+    create_running(&server, "chat", "llm.chat").await;
+    create_running(&server, "export", "export").await;
+
+    let res = server.get("/admin/flags").await;
+
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(
+        body,
+        json!({
+            "flags": [{
+                "name": "includeDiffs",
+                "rule": { "enabled": true, "taskTypes": ["llm.*"] },
+                "evaluations": 2,
+                "enabled": 1,
+            }]
+        })
+    );
+}
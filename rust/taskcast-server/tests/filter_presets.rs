@@ -0,0 +1,258 @@
+//! Integration tests for named filter presets on tasks: resolution on SSE and
+//! history, inline narrowing, and rejection of unknown preset names.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput, TaskEngine,
+    TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> (Arc<TaskEngine>, TestServer) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    (engine, TestServer::new(app))
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, event_type: &str, level: Level) {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: event_type.to_string(),
+                level,
+                data: json!(null),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+/// Creates a completed task with presets over HTTP so SSE replays and closes.
+async fn create_completed_task(engine: &TaskEngine, server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({
+            "id": task_id,
+            "filters": {
+                "errorsOnly": { "levels": ["error"], "includeStatus": false },
+                "llm": { "types": ["llm.*"], "includeStatus": false }
+            }
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    publish(engine, task_id, "llm.delta", Level::Info).await;
+    publish(engine, task_id, "llm.delta", Level::Error).await;
+    publish(engine, task_id, "tool.call", Level::Error).await;
+    engine
+        .transition_task(task_id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+/// Returns `(type, level)` for every `taskcast.event` frame in an SSE body.
+fn sse_events(body: &str) -> Vec<(String, String)> {
+    let mut results = Vec::new();
+    let mut current_event = String::new();
+    for line in body.lines() {
+        if let Some(ev) = line.strip_prefix("event: ") {
+            current_event = ev.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            if current_event == "taskcast.event" {
+                let event: serde_json::Value = serde_json::from_str(data).unwrap();
+                results.push((
+                    event["type"].as_str().unwrap().to_string(),
+                    event["level"].as_str().unwrap().to_string(),
+                ));
+            }
+        }
+    }
+    results
+}
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(t, l)| (t.to_string(), l.to_string()))
+        .collect()
+}
+
+// ─── SSE ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_resolves_preset_by_name() {
+    let (engine, server) = make_server();
+    create_completed_task(&engine, &server, "preset-sse").await;
+
+    let resp = server
+        .get("/tasks/preset-sse/events")
+        .add_query_param("preset", "errorsOnly")
+        .await;
+
+    resp.assert_status_ok();
+    assert_eq!(
+        sse_events(&resp.text()),
+        pairs(&[("llm.delta", "error"), ("tool.call", "error")])
+    );
+    assert!(resp.text().contains("event: taskcast.done"));
+}
+
+#[tokio::test]
+async fn sse_inline_parameters_narrow_preset() {
+    let (engine, server) = make_server();
+    create_completed_task(&engine, &server, "preset-narrow").await;
+
+    let resp = server
+        .get("/tasks/preset-narrow/events")
+        .add_query_param("preset", "llm")
+        .add_query_param("levels", "error")
+        .await;
+    assert_eq!(sse_events(&resp.text()), pairs(&[("llm.delta", "error")]));
+
+    // `tool.*` is outside the preset's `llm.*`, so it cannot widen it.
+    let resp = server
+        .get("/tasks/preset-narrow/events")
+        .add_query_param("preset", "llm")
+        .add_query_param("types", "tool.*")
+        .await;
+    assert_eq!(sse_events(&resp.text()), vec![]);
+
+    // Inline includeStatus=true cannot re-enable what the preset excluded.
+    let resp = server
+        .get("/tasks/preset-narrow/events")
+        .add_query_param("preset", "errorsOnly")
+        .add_query_param("includeStatus", "true")
+        .await;
+    assert!(!resp.text().contains("taskcast:status"));
+}
+
+#[tokio::test]
+async fn sse_unknown_preset_returns_400() {
+    let (engine, server) = make_server();
+    create_completed_task(&engine, &server, "preset-unknown").await;
+
+    let resp = server
+        .get("/tasks/preset-unknown/events")
+        .add_query_param("preset", "warnings")
+        .await;
+
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "UNKNOWN_FILTER_PRESET");
+    assert_eq!(body["details"]["preset"], "warnings");
+}
+
+// ─── History ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn history_applies_preset_before_limit() {
+    let (engine, server) = make_server();
+    create_completed_task(&engine, &server, "preset-history").await;
+
+    let resp = server
+        .get("/tasks/preset-history/events/history")
+        .add_query_param("preset", "errorsOnly")
+        .await;
+    let events: Vec<serde_json::Value> = resp.json();
+    let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+    assert_eq!(types, vec!["llm.delta", "tool.call"]);
+    assert!(events.iter().all(|e| e["level"] == "error"));
+
+    let resp = server
+        .get("/tasks/preset-history/events/history")
+        .add_query_param("preset", "errorsOnly")
+        .add_query_param("limit", 1)
+        .await;
+    let events: Vec<serde_json::Value> = resp.json();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["type"], "llm.delta");
+    assert_eq!(events[0]["level"], "error");
+
+    server
+        .get("/tasks/preset-history/events/history")
+        .add_query_param("preset", "warnings")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+// ─── Task create / update ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn create_task_rejects_webhook_with_unknown_preset() {
+    let (_engine, server) = make_server();
+
+    let resp = server
+        .post("/tasks")
+        .json(&json!({
+            "id": "preset-webhook",
+            "filters": { "errorsOnly": { "levels": ["error"] } },
+            "webhooks": [{ "url": "https://example.com/hook", "filterPreset": "warnings" }]
+        }))
+        .await;
+
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "UNKNOWN_FILTER_PRESET");
+    assert_eq!(body["details"]["preset"], "warnings");
+    server
+        .get("/tasks/preset-webhook")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn transition_replaces_presets_and_task_omits_filters_when_unused() {
+    let (_engine, server) = make_server();
+
+    let created: serde_json::Value = server
+        .post("/tasks")
+        .json(&json!({ "id": "preset-update" }))
+        .await
+        .json();
+    assert!(created.get("filters").is_none());
+
+    let resp = server
+        .patch("/tasks/preset-update/status")
+        .json(&json!({
+            "status": "running",
+            "filters": { "errorsOnly": { "levels": ["error"] } }
+        }))
+        .await;
+    resp.assert_status_ok();
+
+    let task: serde_json::Value = server.get("/tasks/preset-update").await.json();
+    assert_eq!(
+        task["filters"],
+        json!({ "errorsOnly": { "levels": ["error"] } })
+    );
+}
@@ -0,0 +1,336 @@
+//! Integration tests for persisted `filteredIndex` numbering: webhooks and
+//! SSE subscriptions with a `consumerId` keep numbering across restarts and
+//! reconnects, and restart it in a new `filterEpoch` when their filter
+//! changes.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use taskcast_core::config::{SseConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    SubscribeFilter, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus, WebhookConfig,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, WebhookDelivery, WebhookDispatcher};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_dispatcher(engine: &TaskEngine) -> WebhookDispatcher {
+    WebhookDispatcher::new(
+        Arc::new(WebhookDelivery::new()),
+        Arc::clone(engine.short_term_store()),
+    )
+}
+
+type Bodies = Arc<Mutex<Vec<Value>>>;
+
+/// Receiver that records the body of every delivery.
+async fn spawn_receiver() -> (SocketAddr, Bodies) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let bodies: Bodies = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&bodies);
+    let app = axum::Router::new().fallback(move |body: String| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            recorded
+                .lock()
+                .unwrap()
+                .push(serde_json::from_str(&body).unwrap());
+            axum::http::StatusCode::OK
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, bodies)
+}
+
+/// Serves `engine`, replaying at most `max_replay_events` history events to
+/// each SSE connection.
+async fn serve_app(engine: Arc<TaskEngine>, max_replay_events: u64) -> SocketAddr {
+    let config = TaskcastConfig {
+        sse: Some(SseConfig {
+            max_replay_events: Some(max_replay_events),
+            max_replay_bytes: None,
+            heartbeat_interval_ms: None,
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    addr
+}
+
+async fn create_running_task(engine: &TaskEngine) -> String {
+    let task = engine
+        .create_task(CreateTaskInput::default())
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    task.id
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, r#type: &str) -> TaskEvent {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: r#type.to_string(),
+                level: Level::Info,
+                data: json!({}),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+fn webhook(addr: SocketAddr, types: &[&str]) -> WebhookConfig {
+    serde_json::from_value(json!({
+        "url": format!("http://{addr}/hook"),
+        "filter": { "types": types },
+    }))
+    .unwrap()
+}
+
+/// `(raw index, filteredIndex, filterEpoch)` of each recorded body.
+fn positions(bodies: &[Value]) -> Vec<(u64, u64, Option<u64>)> {
+    bodies
+        .iter()
+        .map(|body| {
+            (
+                body["index"].as_u64().unwrap(),
+                body["filteredIndex"].as_u64().unwrap(),
+                body["filterEpoch"].as_u64(),
+            )
+        })
+        .collect()
+}
+
+/// Reads `count` event envelopes from the task's SSE stream, then
+/// disconnects.
+async fn read_events(addr: SocketAddr, task_id: &str, query: &str, count: usize) -> Vec<Value> {
+    let mut response = reqwest::get(format!("http://{addr}/tasks/{task_id}/events?{query}"))
+        .await
+        .unwrap();
+    let mut collected = String::new();
+    let mut events = Vec::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(3);
+    while events.len() < count && tokio::time::Instant::now() < deadline {
+        match tokio::time::timeout(Duration::from_millis(200), response.chunk()).await {
+            Ok(Ok(Some(chunk))) => collected.push_str(&String::from_utf8_lossy(&chunk)),
+            Ok(_) => break,
+            Err(_) => continue,
+        }
+        events = event_frames(&collected);
+    }
+    assert_eq!(events.len(), count, "stream:\n{collected}");
+    events
+}
+
+/// Waits until the server has dropped every subscription to the task.
+async fn wait_for_disconnect(addr: SocketAddr, task_id: &str) {
+    for _ in 0..100 {
+        let task: Value = reqwest::get(format!("http://{addr}/tasks/{task_id}"))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        if task["subscriberCount"] == 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("subscriptions to {task_id} were not dropped");
+}
+
+fn event_frames(body: &str) -> Vec<Value> {
+    let mut frames = Vec::new();
+    let mut event = "";
+    for line in body.lines() {
+        if let Some(name) = line.strip_prefix("event: ") {
+            event = name;
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            if event == "taskcast.event" {
+                frames.push(serde_json::from_str(data).unwrap());
+            }
+        }
+    }
+    frames
+}
+
+/// `(rawIndex, filteredIndex, filterEpoch)` of each envelope.
+fn envelope_positions(envelopes: &[Value]) -> Vec<(u64, u64, Option<u64>)> {
+    envelopes
+        .iter()
+        .map(|envelope| {
+            (
+                envelope["rawIndex"].as_u64().unwrap(),
+                envelope["filteredIndex"].as_u64().unwrap(),
+                envelope["filterEpoch"].as_u64(),
+            )
+        })
+        .collect()
+}
+
+// ─── Webhooks ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn webhook_numbering_continues_across_a_dispatcher_restart() {
+    let engine = make_engine();
+    let (addr, bodies) = spawn_receiver().await;
+    let task_id = create_running_task(&engine).await;
+    let dispatcher = make_dispatcher(&engine);
+    let (task, _) = dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, &["llm.*"]))
+        .await
+        .unwrap();
+
+    for r#type in ["llm.chunk", "log", "llm.chunk"] {
+        let event = publish(&engine, &task_id, r#type).await;
+        dispatcher.dispatch(&task, &event).await.unwrap();
+    }
+    drop(dispatcher);
+
+    // A new dispatcher over the same store, as after a server restart.
+    let restarted = make_dispatcher(&engine);
+    let event = publish(&engine, &task_id, "llm.done").await;
+    restarted.dispatch(&task, &event).await.unwrap();
+
+    assert_eq!(
+        positions(&bodies.lock().unwrap()),
+        vec![(1, 0, Some(0)), (3, 1, Some(0)), (4, 2, Some(0))]
+    );
+}
+
+#[tokio::test]
+async fn webhook_filter_change_restarts_numbering_in_a_new_epoch() {
+    let engine = make_engine();
+    let (addr, bodies) = spawn_receiver().await;
+    let task_id = create_running_task(&engine).await;
+    let dispatcher = make_dispatcher(&engine);
+    let (mut task, _) = dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, &["llm.*"]))
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let event = publish(&engine, &task_id, "llm.chunk").await;
+        dispatcher.dispatch(&task, &event).await.unwrap();
+    }
+
+    task.webhooks.as_mut().unwrap()[0].filter = Some(SubscribeFilter {
+        types: Some(vec!["llm.*".to_string(), "log".to_string()]),
+        ..Default::default()
+    });
+    for r#type in ["log", "llm.chunk"] {
+        let event = publish(&engine, &task_id, r#type).await;
+        dispatcher.dispatch(&task, &event).await.unwrap();
+    }
+
+    assert_eq!(
+        positions(&bodies.lock().unwrap()),
+        vec![
+            (1, 0, Some(0)),
+            (2, 1, Some(0)),
+            (3, 0, Some(1)),
+            (4, 1, Some(1))
+        ]
+    );
+}
+
+// ─── SSE ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn anonymous_sse_connections_number_per_connection() {
+    let engine = make_engine();
+    let task_id = create_running_task(&engine).await;
+    for _ in 0..2 {
+        publish(&engine, &task_id, "llm.chunk").await;
+    }
+    let addr = serve_app(Arc::clone(&engine), 100).await;
+
+    for _ in 0..2 {
+        let events = read_events(addr, &task_id, "types=llm.*", 2).await;
+        assert_eq!(
+            envelope_positions(&events),
+            vec![(1, 0, None), (2, 1, None)]
+        );
+    }
+}
+
+#[tokio::test]
+async fn named_sse_consumer_resumes_its_numbering_on_reconnect() {
+    let engine = make_engine();
+    let task_id = create_running_task(&engine).await;
+    for r#type in ["llm.chunk", "log", "llm.chunk"] {
+        publish(&engine, &task_id, r#type).await;
+    }
+    // History over one event is cut to its last event.
+    let addr = serve_app(Arc::clone(&engine), 1).await;
+    let query = "types=llm.*&consumerId=ui";
+
+    let first = read_events(addr, &task_id, &format!("{query}&fullReplay=true"), 2).await;
+    assert_eq!(
+        envelope_positions(&first),
+        vec![(1, 0, Some(0)), (3, 1, Some(0))]
+    );
+
+    wait_for_disconnect(addr, &task_id).await;
+
+    publish(&engine, &task_id, "llm.done").await;
+    // Only what this consumer was not sent is replayed, even from a
+    // history cut short.
+    let resumed = read_events(addr, &task_id, query, 1).await;
+    assert_eq!(envelope_positions(&resumed), vec![(4, 2, Some(0))]);
+}
+
+#[tokio::test]
+async fn named_sse_consumer_filter_change_restarts_in_a_new_epoch() {
+    let engine = make_engine();
+    let task_id = create_running_task(&engine).await;
+    for r#type in ["llm.chunk", "log"] {
+        publish(&engine, &task_id, r#type).await;
+    }
+    let addr = serve_app(Arc::clone(&engine), 100).await;
+
+    let first = read_events(addr, &task_id, "types=llm.*&consumerId=ui", 1).await;
+    assert_eq!(envelope_positions(&first), vec![(1, 0, Some(0))]);
+    wait_for_disconnect(addr, &task_id).await;
+
+    let changed = read_events(addr, &task_id, "types=log&consumerId=ui", 1).await;
+    assert_eq!(envelope_positions(&changed), vec![(2, 0, Some(1))]);
+}
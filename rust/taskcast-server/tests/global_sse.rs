@@ -22,6 +22,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -68,6 +71,9 @@ async fn publish_event(
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -79,7 +85,7 @@ const JWT_SECRET: &str = "test-secret-key-for-jwt-signing-needs-to-be-long-enoug
 fn make_jwt_engine_and_app() -> (Arc<TaskEngine>, axum::Router) {
     let engine = make_engine();
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -676,6 +682,9 @@ async fn global_sse_returns_501_when_subscribe_sync_unsupported() {
         broadcast: Arc::new(NoSyncBroadcastProvider),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let app = make_app(Arc::clone(&engine));
     let addr = serve_app(app).await;
@@ -10,6 +10,9 @@ fn make_server() -> TestServer {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
     TestServer::new(app)
@@ -78,15 +81,38 @@ async fn health_detail_reports_memory_adapters_by_default() {
     assert_eq!(body["adapters"]["shortTermStore"]["status"], "ok");
 }
 
+#[tokio::test]
+async fn health_detail_reports_idle_background_tasks() {
+    let server = make_server();
+    let res = server.get("/health/detail").await;
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["backgroundTasks"]["inFlight"], 0);
+    assert_eq!(body["backgroundTasks"]["byName"], serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn health_detail_reports_read_routing() {
+    let server = make_server();
+    let res = server.get("/health/detail").await;
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["readRouting"]["preference"], "short");
+    assert_eq!(body["readRouting"]["latencyThresholdMs"], 50.0);
+    assert_eq!(body["readRouting"]["shortTermDegraded"], false);
+    assert_eq!(body["readRouting"]["longTermReads"], 0);
+}
+
 fn make_jwt_server() -> TestServer {
     let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
         short_term_store: Arc::new(MemoryShortTermStore::new()),
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let auth = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some("test-secret-key-for-jwt-signing".to_string()),
         public_key: None,
         issuer: None,
@@ -150,6 +176,9 @@ fn make_server_with_config(config: TaskcastConfig) -> TestServer {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (app, _) = create_app(
         engine,
@@ -168,6 +197,15 @@ async fn health_detail_with_config_broadcast_override() {
             broadcast: Some(AdapterEntry {
                 provider: "redis".to_string(),
                 url: Some("redis://localhost:6379".to_string()),
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
             short_term_store: None,
             long_term_store: None,
@@ -193,6 +231,15 @@ async fn health_detail_with_config_short_term_store_override() {
             short_term_store: Some(AdapterEntry {
                 provider: "redis".to_string(),
                 url: None,
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
             long_term_store: None,
         }),
@@ -216,6 +263,15 @@ async fn health_detail_with_config_long_term_store() {
             long_term_store: Some(AdapterEntry {
                 provider: "postgres".to_string(),
                 url: Some("postgresql://localhost/taskcast".to_string()),
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
         }),
         ..Default::default()
@@ -235,14 +291,41 @@ async fn health_detail_with_all_adapters_configured() {
             broadcast: Some(AdapterEntry {
                 provider: "redis".to_string(),
                 url: Some("redis://localhost:6379".to_string()),
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
             short_term_store: Some(AdapterEntry {
                 provider: "redis".to_string(),
                 url: Some("redis://localhost:6379".to_string()),
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
             long_term_store: Some(AdapterEntry {
                 provider: "postgres".to_string(),
                 url: Some("postgresql://localhost/taskcast".to_string()),
+                read_url: None,
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
             }),
         }),
         ..Default::default()
@@ -292,3 +375,126 @@ async fn health_detail_with_config_empty_adapters() {
     assert_eq!(body["adapters"]["shortTermStore"]["provider"], "memory");
     assert!(body["adapters"]["longTermStore"].is_null());
 }
+
+// ─── health_detail with long-term pool health ───────────────────────────────
+
+use taskcast_core::{
+    EventQueryOptions, LongTermStore, PoolHealth, Task, TaskEvent, WorkerAuditEvent,
+};
+
+/// Long-term store whose read pool is down.
+struct ReplicaDownLongTermStore;
+
+#[async_trait::async_trait]
+impl LongTermStore for ReplicaDownLongTermStore {
+    async fn save_task(&self, _task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    async fn save_event(
+        &self,
+        _event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    async fn pool_health(&self) -> Vec<PoolHealth> {
+        vec![
+            PoolHealth {
+                name: "write".to_string(),
+                ok: true,
+                latency_ms: 1,
+                error: None,
+            },
+            PoolHealth {
+                name: "read".to_string(),
+                ok: false,
+                latency_ms: 2000,
+                error: Some("timed out after 2000ms".to_string()),
+            },
+        ]
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn health_detail_reports_each_long_term_pool() {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: Some(Arc::new(ReplicaDownLongTermStore)),
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        adapters: Some(AdaptersConfig {
+            broadcast: None,
+            short_term_store: None,
+            long_term_store: Some(AdapterEntry {
+                provider: "postgres".to_string(),
+                url: Some("postgresql://primary/taskcast".to_string()),
+                read_url: Some("postgresql://replica/taskcast".to_string()),
+                read_after_write_ms: None,
+                topic: None,
+                group_id: None,
+                type_channels: None,
+                legacy_channels: None,
+                persist_path: None,
+                persist_interval_ms: None,
+                slow_query_ms: None,
+            }),
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    let server = TestServer::new(app);
+
+    let res = server.get("/health/detail").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    let long_term = &body["adapters"]["longTermStore"];
+    assert_eq!(long_term["provider"], "postgres");
+    assert_eq!(long_term["status"], "degraded");
+    assert_eq!(long_term["pools"][0]["name"], "write");
+    assert_eq!(long_term["pools"][0]["ok"], true);
+    assert_eq!(long_term["pools"][1]["name"], "read");
+    assert_eq!(long_term["pools"][1]["ok"], false);
+    assert_eq!(long_term["pools"][1]["error"], "timed out after 2000ms");
+}
@@ -0,0 +1,233 @@
+//! Integration tests for `checksum=true` on history, archive export and SSE.
+
+use std::sync::Arc;
+
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    verify_history_checksum, HistoryChecksum, HistoryChecksumBuilder, MemoryBroadcastProvider,
+    MemoryShortTermStore, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> (Arc<TaskEngine>, TestServer) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    (engine, TestServer::new(app))
+}
+
+/// Creates a completed task with a few events, some labelled `region:eu`.
+async fn completed_task(engine: &TaskEngine, server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id }))
+        .await
+        .assert_status(axum_test::http::StatusCode::CREATED);
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..4 {
+        let region = if i % 2 == 0 { "eu" } else { "us" };
+        server
+            .post(&format!("/tasks/{task_id}/events"))
+            .json(&json!({
+                "type": "log",
+                "level": "info",
+                "data": { "text": format!("line {i}"), "n": i, "nested": { "b": 1, "a": [i] } },
+                "labels": { "region": region, "host": "h1" }
+            }))
+            .await
+            .assert_status(axum_test::http::StatusCode::CREATED);
+    }
+    engine
+        .transition_task(task_id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+fn checksum_header(resp: &axum_test::TestResponse) -> HistoryChecksum {
+    serde_json::from_str(resp.header("x-taskcast-checksum").to_str().unwrap()).unwrap()
+}
+
+fn sse_frames(body: &str) -> Vec<(String, serde_json::Value)> {
+    let mut frames = Vec::new();
+    let mut current_event = String::new();
+    for line in body.lines() {
+        if let Some(ev) = line.strip_prefix("event: ") {
+            current_event = ev.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            frames.push((current_event.clone(), serde_json::from_str(data).unwrap()));
+        }
+    }
+    frames
+}
+
+// ─── History ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn history_checksum_verifies_returned_events() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-history").await;
+
+    let resp = server
+        .get("/tasks/ck-history/events/history")
+        .add_query_param("checksum", "true")
+        .await;
+    resp.assert_status_ok();
+    let checksum = checksum_header(&resp);
+    let events: Vec<TaskEvent> = resp.json();
+
+    assert_eq!(checksum.version, "taskcast.history.v1");
+    assert_eq!(checksum.count, events.len() as u64);
+    assert_eq!(checksum.first_index, Some(0));
+    assert_eq!(checksum.last_index, Some(events.last().unwrap().index));
+    assert!(verify_history_checksum(&events, &checksum));
+}
+
+#[tokio::test]
+async fn flipping_one_byte_fails_verification() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-tamper").await;
+
+    let resp = server
+        .get("/tasks/ck-tamper/events/history")
+        .add_query_param("checksum", "true")
+        .await;
+    let checksum = checksum_header(&resp);
+    let body = resp.text();
+
+    let tampered = body.replacen("line 2", "line 3", 1);
+    assert_ne!(tampered, body);
+    let events: Vec<TaskEvent> = serde_json::from_str(&tampered).unwrap();
+    assert!(!verify_history_checksum(&events, &checksum));
+}
+
+#[tokio::test]
+async fn filtered_history_hashes_only_what_was_returned() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-filtered").await;
+
+    let full = server
+        .get("/tasks/ck-filtered/events/history")
+        .add_query_param("checksum", "true")
+        .await;
+    let filtered = server
+        .get("/tasks/ck-filtered/events/history")
+        .add_query_param("labels", "region:eu")
+        .add_query_param("checksum", "true")
+        .await;
+
+    let checksum = checksum_header(&filtered);
+    let events: Vec<TaskEvent> = filtered.json();
+    assert_eq!(events.len(), 2);
+    assert_eq!(checksum.count, 2);
+    assert_eq!(checksum.first_index, Some(events[0].index));
+    assert_eq!(checksum.last_index, Some(events[1].index));
+    assert!(verify_history_checksum(&events, &checksum));
+    assert_ne!(checksum.sha256, checksum_header(&full).sha256);
+}
+
+#[tokio::test]
+async fn history_without_checksum_has_no_header() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-none").await;
+
+    let resp = server.get("/tasks/ck-none/events/history").await;
+    assert!(resp.maybe_header("x-taskcast-checksum").is_none());
+}
+
+// ─── Archive ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn archive_checksum_covers_archive_events() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-archive").await;
+
+    let archive: serde_json::Value = server
+        .get("/tasks/ck-archive/archive")
+        .add_query_param("checksum", "true")
+        .await
+        .json();
+    let checksum: HistoryChecksum = serde_json::from_value(archive["checksum"].clone()).unwrap();
+    let events: Vec<TaskEvent> = serde_json::from_value(archive["events"].clone()).unwrap();
+    assert!(verify_history_checksum(&events, &checksum));
+
+    let plain: serde_json::Value = server.get("/tasks/ck-archive/archive").await.json();
+    assert!(plain.get("checksum").is_none());
+}
+
+// ─── SSE ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_done_frame_carries_checksum_of_replayed_events() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-sse").await;
+
+    let resp = server
+        .get("/tasks/ck-sse/events")
+        .add_query_param("wrap", "false")
+        .add_query_param("checksum", "true")
+        .await;
+    let frames = sse_frames(&resp.text());
+    let (last_event, done) = frames.last().unwrap();
+    assert_eq!(last_event, "taskcast.done");
+    let checksum: HistoryChecksum = serde_json::from_value(done["checksum"].clone()).unwrap();
+
+    let events: Vec<TaskEvent> = frames
+        .iter()
+        .filter(|(event, _)| event == "taskcast.event")
+        .map(|(_, data)| serde_json::from_value(data.clone()).unwrap())
+        .collect();
+    assert!(!events.is_empty());
+    assert!(verify_history_checksum(&events, &checksum));
+}
+
+#[tokio::test]
+async fn sse_checksum_covers_wrapped_envelopes() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-sse-wrapped").await;
+
+    let resp = server
+        .get("/tasks/ck-sse-wrapped/events")
+        .add_query_param("labels", "region:us")
+        .add_query_param("includeStatus", "false")
+        .add_query_param("checksum", "true")
+        .await;
+    let frames = sse_frames(&resp.text());
+    let checksum: HistoryChecksum =
+        serde_json::from_value(frames.last().unwrap().1["checksum"].clone()).unwrap();
+
+    let mut builder = HistoryChecksumBuilder::new();
+    for (_, data) in frames.iter().filter(|(event, _)| event == "taskcast.event") {
+        builder.push_value(data["rawIndex"].as_u64().unwrap(), data);
+    }
+    assert_eq!(builder.finish(), checksum);
+    assert_eq!(checksum.count, 2);
+}
+
+#[tokio::test]
+async fn sse_done_frame_omits_checksum_unless_requested() {
+    let (engine, server) = make_server();
+    completed_task(&engine, &server, "ck-sse-plain").await;
+
+    let resp = server.get("/tasks/ck-sse-plain/events").await;
+    let frames = sse_frames(&resp.text());
+    assert!(frames.last().unwrap().1.get("checksum").is_none());
+}
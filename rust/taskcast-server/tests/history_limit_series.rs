@@ -16,6 +16,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -57,6 +60,9 @@ async fn limit_caps_returned_events() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -92,6 +98,9 @@ async fn returns_all_events_when_limit_not_specified() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -123,6 +132,9 @@ async fn series_format_accumulated_collapses_series() {
                     series_id: Some("tokens".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -139,6 +151,9 @@ async fn series_format_accumulated_collapses_series() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -179,6 +194,9 @@ async fn series_format_delta_returns_raw_deltas() {
                     series_id: Some("tokens".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -218,6 +236,9 @@ async fn limit_with_series_format_accumulated() {
                     series_id: Some("tokens".to_string()),
                     series_mode: Some(SeriesMode::Accumulate),
                     series_acc_field: Some("delta".to_string()),
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -235,6 +256,9 @@ async fn limit_with_series_format_accumulated() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -269,6 +293,9 @@ async fn invalid_series_format_treated_as_delta() {
                 series_id: Some("s1".to_string()),
                 series_mode: Some(SeriesMode::Accumulate),
                 series_acc_field: Some("delta".to_string()),
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -305,6 +332,9 @@ async fn limit_with_since_cursor() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -320,6 +350,9 @@ async fn limit_with_since_cursor() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -335,6 +368,9 @@ async fn limit_with_since_cursor() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -0,0 +1,264 @@
+//! Integration tests for the HTTP debug tap and `GET /admin/http-tap`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::config::HttpTapConfig;
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{
+    create_app_with_options, AppOptions, AuthMode, HttpTap, HttpTapQuery, JwtConfig, LogLevel,
+    StderrHttpFailureLogger,
+};
+
+const JWT_SECRET: &str = "http-tap-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_tap(routes: &[&str], max_body_bytes: Option<usize>) -> Arc<HttpTap> {
+    Arc::new(HttpTap::new(&HttpTapConfig {
+        enabled: Some(true),
+        routes: Some(routes.iter().map(|r| r.to_string()).collect()),
+        max_body_bytes,
+        redact_paths: Some(vec![
+            "data.apiKey".to_string(),
+            "params.auth.password".to_string(),
+        ]),
+        capacity: None,
+    }))
+}
+
+fn make_server(auth_mode: AuthMode, tap: Arc<HttpTap>) -> TestServer {
+    let (app, _) = create_app_with_options(
+        make_engine(),
+        auth_mode,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            http_tap: Some(tap),
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "http-tap-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn create_task(server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+// ─── Capture ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn records_matched_routes_only() {
+    let tap = make_tap(&["POST /tasks/*/events"], None);
+    let server = make_server(AuthMode::None, Arc::clone(&tap));
+    create_task(&server, "t1").await;
+    server
+        .patch("/tasks/t1/status")
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+
+    server
+        .post("/tasks/t1/events")
+        .json(&json!({ "type": "llm.delta", "level": "info", "data": { "text": "hi" } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server.get("/tasks/t1").await.assert_status_ok();
+
+    let entries = tap.entries(&HttpTapQuery::default());
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.method, "POST");
+    assert_eq!(entry.path, "/tasks/t1/events");
+    assert_eq!(entry.route.as_deref(), Some("POST /tasks/*/events"));
+    assert_eq!(entry.status, 201);
+    assert!(!entry.streaming);
+    let request: serde_json::Value =
+        serde_json::from_str(entry.request_body.as_deref().unwrap()).unwrap();
+    assert_eq!(request["data"]["text"], "hi");
+    let response: serde_json::Value =
+        serde_json::from_str(entry.response_body.as_deref().unwrap()).unwrap();
+    assert_eq!(response["type"], "llm.delta");
+}
+
+#[tokio::test]
+async fn redacts_nested_paths_and_sensitive_headers() {
+    let tap = make_tap(&["POST /tasks"], None);
+    let server = make_server(AuthMode::None, Arc::clone(&tap));
+
+    server
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, HeaderValue::from_static("Bearer x"))
+        .json(&json!({
+            "id": "t1",
+            "params": { "auth": { "user": "u", "password": "hunter2" } }
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let entry = &tap.entries(&HttpTapQuery::default())[0];
+    let request = entry.request_body.as_deref().unwrap();
+    assert!(!request.contains("hunter2"), "{request}");
+    let request: serde_json::Value = serde_json::from_str(request).unwrap();
+    assert_eq!(request["params"]["auth"]["password"], "[REDACTED]");
+    assert_eq!(request["params"]["auth"]["user"], "u");
+    // The created task echoes its params back, and is redacted the same way.
+    let response = entry.response_body.as_deref().unwrap();
+    assert!(!response.contains("hunter2"), "{response}");
+    assert_eq!(entry.request_headers["authorization"], "[REDACTED]");
+}
+
+#[tokio::test]
+async fn truncates_bodies_at_max_body_bytes() {
+    let tap = make_tap(&["POST /tasks"], Some(16));
+    let server = make_server(AuthMode::None, Arc::clone(&tap));
+
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t1", "params": { "prompt": "x".repeat(100) } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let entry = &tap.entries(&HttpTapQuery::default())[0];
+    assert_eq!(entry.request_body.as_deref().unwrap().len(), 16);
+    assert!(entry.request_body_truncated);
+    assert_eq!(entry.response_body.as_deref().unwrap().len(), 16);
+    assert!(entry.response_body_truncated);
+}
+
+#[tokio::test]
+async fn disabled_tap_records_nothing() {
+    let tap = make_tap(&[], None);
+    tap.set_enabled(false);
+    let server = make_server(AuthMode::None, Arc::clone(&tap));
+
+    create_task(&server, "t1").await;
+    server.get("/tasks/t1").await.assert_status_ok();
+
+    assert!(tap.entries(&HttpTapQuery::default()).is_empty());
+}
+
+#[tokio::test]
+async fn sse_responses_are_not_body_buffered() {
+    let tap = make_tap(&["GET /tasks/*/events"], None);
+    let server = make_server(AuthMode::None, Arc::clone(&tap));
+    create_task(&server, "t1").await;
+    for status in ["running", "completed"] {
+        server
+            .patch("/tasks/t1/status")
+            .json(&json!({ "status": status }))
+            .await
+            .assert_status_ok();
+    }
+
+    let res = server.get("/tasks/t1/events").await;
+    res.assert_status_ok();
+    assert!(res.text().contains("taskcast.done"));
+
+    let entry = &tap.entries(&HttpTapQuery::default())[0];
+    assert!(entry.streaming);
+    assert_eq!(entry.response_body, None);
+    assert!(entry.response_headers["content-type"].starts_with("text/event-stream"));
+}
+
+// ─── Admin Endpoint ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn admin_endpoint_filters_and_requires_task_manage() {
+    let tap = make_tap(&[], None);
+    let server = make_server(
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+        Arc::clone(&tap),
+    );
+    let token = bearer(&["*"]);
+
+    server
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .json(&json!({ "id": "t1" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .get("/tasks/missing")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let res = server
+        .get("/admin/http-tap")
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get("/admin/http-tap")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await;
+    res.assert_status_ok();
+    // Newest first; the forbidden read of the tap itself is not recorded.
+    let entries = res.json::<serde_json::Value>()["entries"].clone();
+    let paths: Vec<&str> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["/tasks/missing", "/tasks"]);
+
+    let res = server
+        .get("/admin/http-tap")
+        .add_query_param("status", "4xx")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await;
+    let entries = res.json::<serde_json::Value>()["entries"].clone();
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["status"], 404);
+    assert_eq!(entries[0]["requestHeaders"]["authorization"], "[REDACTED]");
+
+    let since = entries[0]["timestamp"].as_f64().unwrap() + 60_000.0;
+    let res = server
+        .get("/admin/http-tap")
+        .add_query_param("since", since)
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await;
+    assert_eq!(res.json::<serde_json::Value>()["entries"], json!([]));
+}
@@ -0,0 +1,159 @@
+//! Integration tests for `If-Match` task versions on mutating task routes.
+
+use std::sync::Arc;
+
+use axum_test::http::header::IF_MATCH;
+use axum_test::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+async fn create_task(server: &TestServer) -> Value {
+    let res = server.post("/tasks").json(&json!({})).await;
+    res.assert_status(StatusCode::CREATED);
+    res.json()
+}
+
+fn if_match(value: &str) -> HeaderValue {
+    HeaderValue::from_str(value).unwrap()
+}
+
+// ─── Transitions ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn transition_with_a_stale_if_match_is_a_conflict() {
+    let server = make_server();
+    let task = create_task(&server).await;
+    let task_id = task["id"].as_str().unwrap();
+    assert_eq!(task["version"], 1);
+
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(IF_MATCH, if_match("2"))
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "CONCURRENT_MODIFICATION");
+    assert_eq!(body["details"]["taskId"], task_id);
+
+    let task: Value = server.get(&format!("/tasks/{task_id}")).await.json();
+    assert_eq!(task["status"], "pending");
+    assert_eq!(task["version"], 1);
+}
+
+#[tokio::test]
+async fn transition_at_the_if_match_version_succeeds() {
+    let server = make_server();
+    let task = create_task(&server).await;
+    let task_id = task["id"].as_str().unwrap();
+
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(IF_MATCH, if_match("1"))
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<Value>()["version"], 2);
+
+    // Quoted like an ETag, and `*` for any version.
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(IF_MATCH, if_match("\"2\""))
+        .json(&json!({ "status": "paused" }))
+        .await;
+    res.assert_status_ok();
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(IF_MATCH, if_match("*"))
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<Value>()["version"], 4);
+}
+
+#[tokio::test]
+async fn dry_run_checks_if_match_too() {
+    let server = make_server();
+    let task = create_task(&server).await;
+    let task_id = task["id"].as_str().unwrap();
+
+    server
+        .patch(&format!("/tasks/{task_id}/status?dryRun=true"))
+        .add_header(IF_MATCH, if_match("5"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status(StatusCode::CONFLICT);
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status?dryRun=true"))
+        .add_header(IF_MATCH, if_match("1"))
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<Value>()["version"], 2);
+}
+
+#[tokio::test]
+async fn malformed_if_match_is_a_bad_request() {
+    let server = make_server();
+    let task = create_task(&server).await;
+    let task_id = task["id"].as_str().unwrap();
+
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(IF_MATCH, if_match("W/\"abc\""))
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+// ─── Resolve ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn resolve_with_a_stale_if_match_is_a_conflict() {
+    let server = make_server();
+    let task = create_task(&server).await;
+    let task_id = task["id"].as_str().unwrap();
+    for status in ["running", "blocked"] {
+        server
+            .patch(&format!("/tasks/{task_id}/status"))
+            .json(&json!({ "status": status }))
+            .await
+            .assert_status_ok();
+    }
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/resolve"))
+        .add_header(IF_MATCH, if_match("1"))
+        .json(&json!({ "data": { "approved": true } }))
+        .await;
+    res.assert_status(StatusCode::CONFLICT);
+    assert_eq!(res.json::<Value>()["code"], "CONCURRENT_MODIFICATION");
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/resolve"))
+        .add_header(IF_MATCH, if_match("3"))
+        .json(&json!({ "data": { "approved": true } }))
+        .await;
+    res.assert_status_ok();
+    let task: Value = res.json();
+    assert_eq!(task["status"], "running");
+    assert_eq!(task["version"], 4);
+}
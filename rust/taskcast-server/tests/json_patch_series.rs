@@ -0,0 +1,237 @@
+//! Integration tests for `json-patch` series: publishing patches, the 422 for
+//! patches that do not apply, and `materializeSeries` replay.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_app() -> axum::Router {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    app
+}
+
+async fn create_running_task(server: &TestServer) -> String {
+    let res = server.post("/tasks").json(&json!({})).await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    task_id
+}
+
+fn patch_event(patch: Value) -> Value {
+    json!({
+        "type": "crawl.frontier",
+        "level": "info",
+        "data": patch,
+        "seriesId": "frontier",
+        "seriesMode": "json-patch",
+    })
+}
+
+/// Publishes a frontier that ends up as `{ "pending": ["b.com", "c.com"], "visited": 1 }`.
+async fn publish_frontier(server: &TestServer, task_id: &str) {
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&json!([
+            patch_event(json!([{ "op": "add", "path": "/pending", "value": ["a.com"] }])),
+            { "type": "log", "level": "info", "data": { "msg": "crawling" } },
+            patch_event(json!([
+                { "op": "add", "path": "/pending/-", "value": "b.com" },
+                { "op": "add", "path": "/visited", "value": 0 },
+            ])),
+            patch_event(json!([
+                { "op": "remove", "path": "/pending/0" },
+                { "op": "add", "path": "/pending/-", "value": "c.com" },
+                { "op": "replace", "path": "/visited", "value": 1 },
+            ])),
+        ]))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+fn types(events: &Value) -> Vec<&str> {
+    events
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["type"].as_str().unwrap())
+        .collect()
+}
+
+// ─── Publish ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn events_keep_their_patches() {
+    let server = TestServer::new(make_app());
+    let task_id = create_running_task(&server).await;
+    publish_frontier(&server, &task_id).await;
+
+    let history: Value = server
+        .get(&format!("/tasks/{task_id}/events/history"))
+        .await
+        .json();
+    let frontier: Vec<&Value> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["seriesId"] == "frontier")
+        .collect();
+    assert_eq!(frontier.len(), 3);
+    assert_eq!(frontier[0]["seriesMode"], "json-patch");
+    assert_eq!(
+        frontier[0]["data"],
+        json!([{ "op": "add", "path": "/pending", "value": ["a.com"] }])
+    );
+    assert!(frontier.iter().all(|e| e.get("seriesSnapshot").is_none()));
+}
+
+#[tokio::test]
+async fn patch_that_does_not_apply_is_rejected() {
+    let server = TestServer::new(make_app());
+    let task_id = create_running_task(&server).await;
+    publish_frontier(&server, &task_id).await;
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&patch_event(
+            json!([{ "op": "remove", "path": "/missing" }]),
+        ))
+        .await;
+    res.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(res.json::<Value>()["code"], "INVALID_SERIES_PATCH");
+
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&patch_event(json!({ "visited": 2 })))
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Nothing was recorded and the document is unchanged.
+    let history: Value = server
+        .get(&format!("/tasks/{task_id}/events/history"))
+        .await
+        .json();
+    assert_eq!(history.as_array().unwrap().last().unwrap()["index"], 4);
+    let materialized: Value = server
+        .get(&format!(
+            "/tasks/{task_id}/events/history?materializeSeries=true"
+        ))
+        .await
+        .json();
+    assert_eq!(
+        materialized[1]["data"],
+        json!({ "pending": ["b.com", "c.com"], "visited": 1 })
+    );
+}
+
+// ─── Materialized Replay ─────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn history_can_start_series_from_the_current_document() {
+    let server = TestServer::new(make_app());
+    let task_id = create_running_task(&server).await;
+    publish_frontier(&server, &task_id).await;
+
+    let history: Value = server
+        .get(&format!(
+            "/tasks/{task_id}/events/history?materializeSeries=true"
+        ))
+        .await
+        .json();
+    assert_eq!(
+        types(&history),
+        ["taskcast:status", "crawl.frontier", "log"]
+    );
+    let snapshot = &history[1];
+    assert_eq!(snapshot["seriesSnapshot"], true);
+    assert_eq!(snapshot["index"], 4);
+    assert_eq!(
+        snapshot["data"],
+        json!({ "pending": ["b.com", "c.com"], "visited": 1 })
+    );
+
+    // Later patches follow the snapshot.
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&patch_event(json!([
+            { "op": "replace", "path": "/visited", "value": 2 },
+        ])))
+        .await
+        .assert_status(StatusCode::CREATED);
+    let since: Value = server
+        .get(&format!(
+            "/tasks/{task_id}/events/history?materializeSeries=true&since.index=3"
+        ))
+        .await
+        .json();
+    assert_eq!(since[0]["seriesSnapshot"], true);
+    assert_eq!(since[0]["data"]["visited"], 2);
+    assert_eq!(since.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn late_sse_subscriber_receives_the_current_document() {
+    let server = TestServer::new(make_app());
+    let task_id = create_running_task(&server).await;
+    publish_frontier(&server, &task_id).await;
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&patch_event(json!([
+            { "op": "add", "path": "/done", "value": true },
+        ])))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "completed" }))
+        .await
+        .assert_status_ok();
+
+    let body = server
+        .get(&format!(
+            "/tasks/{task_id}/events?materializeSeries=true&wrap=false&includeStatus=false"
+        ))
+        .await
+        .text();
+    let events: Vec<Value> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).unwrap())
+        .filter(|event: &Value| event.get("type").is_some())
+        .collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["seriesSnapshot"], true);
+    assert_eq!(events[1]["type"], "log");
+    assert_eq!(
+        events[0]["data"],
+        json!({ "pending": ["b.com", "c.com"], "visited": 1, "done": true })
+    );
+
+    // Without the option, every patch is replayed.
+    let body = server
+        .get(&format!(
+            "/tasks/{task_id}/events?wrap=false&includeStatus=false"
+        ))
+        .await
+        .text();
+    assert_eq!(body.matches("\"seriesMode\":\"json-patch\"").count(), 4);
+}
@@ -16,9 +16,12 @@ fn make_jwt_server() -> TestServer {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -107,7 +110,8 @@ async fn no_authorization_header_returns_401_with_message() {
         .await;
     response.assert_status(axum_test::http::StatusCode::UNAUTHORIZED);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Missing Bearer token");
+    assert_eq!(body["code"], "MISSING_TOKEN");
+    assert_eq!(body["message"], "Missing Bearer token");
 }
 
 #[tokio::test]
@@ -123,5 +127,6 @@ async fn garbled_token_returns_401_with_message() {
         .await;
     response.assert_status(axum_test::http::StatusCode::UNAUTHORIZED);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Invalid or expired token");
+    assert_eq!(body["code"], "INVALID_TOKEN");
+    assert_eq!(body["message"], "Invalid or expired token");
 }
\ No newline at end of file
@@ -0,0 +1,472 @@
+//! Integration tests for the outcomes index: terminal transitions recorded
+//! by the engine, `GET /outcomes` filtering and pagination, and retention
+//! trimming.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, ErrorContext, EventQueryOptions, MemoryBroadcastProvider,
+    MemoryShortTermStore, OutcomeQuery, OutcomeTrimmer, OutcomeTrimmerOptions, ShortTermStore,
+    Task, TaskEngine, TaskEngineOptions, TaskError, TaskEvent, TaskFilter, TaskOutcome, TaskStatus,
+    TaskcastHooks, TransitionPayload, Worker, WorkerAssignment, WorkerFilter,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "outcomes-test-secret-key-needs-to-be-long-enough";
+const HOUR_MS: f64 = 60.0 * 60.0 * 1000.0;
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(
+    store: Arc<dyn ShortTermStore>,
+    hooks: Option<Arc<dyn TaskcastHooks>>,
+) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth: AuthMode) -> TestServer {
+    let (app, _) = create_app(Arc::clone(engine), auth, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "results-poller", "scope": scope, "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// Creates a running task `id` of `type` and ends it with `status`.
+async fn run_to(engine: &TaskEngine, id: &str, r#type: &str, status: TaskStatus) -> Task {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            r#type: Some(r#type.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let error = (status == TaskStatus::Failed).then(|| TaskError {
+        code: Some("BAD_ROW".to_string()),
+        message: "row 7".to_string(),
+        details: None,
+    });
+    let task = engine
+        .transition_task(
+            id,
+            status,
+            Some(TransitionPayload {
+                error,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    // Keeps consecutive tasks on distinct completedAt timestamps.
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    task
+}
+
+async fn get_page(server: &TestServer, url: &str) -> Value {
+    let res = server.get(url).await;
+    res.assert_status_ok();
+    res.json()
+}
+
+fn task_ids(page: &Value) -> Vec<&str> {
+    page["outcomes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|outcome| outcome["taskId"].as_str().unwrap())
+        .collect()
+}
+
+/// Delegates to MemoryShortTermStore, except that writing an outcome fails.
+struct FailingOutcomesStore {
+    inner: MemoryShortTermStore,
+}
+
+#[async_trait]
+impl ShortTermStore for FailingOutcomesStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+
+    async fn record_outcome(
+        &self,
+        _outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err("injected record_outcome failure".into())
+    }
+}
+
+/// Records the operation of every unhandled error.
+#[derive(Default)]
+struct ErrorLog(Mutex<Vec<ErrorContext>>);
+
+impl TaskcastHooks for ErrorLog {
+    fn on_unhandled_error(
+        &self,
+        _err: &(dyn std::error::Error + Send + Sync),
+        context: &ErrorContext,
+    ) {
+        self.0.lock().unwrap().push(context.clone());
+    }
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn only_terminal_transitions_are_recorded() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("open".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("open", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine
+        .transition_task("open", TaskStatus::Paused, None)
+        .await
+        .unwrap();
+    assert!(engine
+        .list_outcomes(&OutcomeQuery {
+            limit: 10,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .is_empty());
+
+    let failed = run_to(&engine, "t1", "import.csv", TaskStatus::Failed).await;
+    let server = make_server(&engine, AuthMode::None);
+    let page = get_page(&server, "/outcomes").await;
+    assert_eq!(
+        page,
+        json!({
+            "outcomes": [{
+                "taskId": "t1",
+                "type": "import.csv",
+                "status": "failed",
+                "completedAt": failed.completed_at.unwrap(),
+                "errorCode": "BAD_ROW",
+                "origin": "user",
+            }],
+            "nextCursor": null,
+        })
+    );
+}
+
+#[tokio::test]
+async fn outcomes_are_filtered_by_status_type_and_since() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+    run_to(&engine, "a", "import.csv", TaskStatus::Failed).await;
+    run_to(&engine, "b", "export.csv", TaskStatus::Failed).await;
+    let c = run_to(&engine, "c", "import.json", TaskStatus::Completed).await;
+    run_to(&engine, "d", "import.json", TaskStatus::Timeout).await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let page = get_page(&server, "/outcomes?status=failed&type=import.*").await;
+    assert_eq!(task_ids(&page), ["a"]);
+    let page = get_page(&server, "/outcomes?status=failed,timeout").await;
+    assert_eq!(task_ids(&page), ["a", "b", "d"]);
+    let page = get_page(&server, "/outcomes?type=import.*,export.*").await;
+    assert_eq!(task_ids(&page), ["a", "b", "c", "d"]);
+    let since = c.completed_at.unwrap();
+    let page = get_page(&server, &format!("/outcomes?since={since}")).await;
+    assert_eq!(task_ids(&page), ["c", "d"]);
+}
+
+#[tokio::test]
+async fn cursor_walks_every_page_once() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+    for id in ["a", "b", "c", "d", "e"] {
+        run_to(&engine, id, "report", TaskStatus::Completed).await;
+    }
+    let server = make_server(&engine, AuthMode::None);
+
+    let mut seen = Vec::new();
+    let mut url = "/outcomes?limit=2".to_string();
+    loop {
+        let page = get_page(&server, &url).await;
+        seen.extend(task_ids(&page).iter().map(|id| id.to_string()));
+        match page["nextCursor"].as_str() {
+            Some(cursor) => url = format!("/outcomes?limit=2&cursor={cursor}"),
+            None => break,
+        }
+    }
+    assert_eq!(seen, ["a", "b", "c", "d", "e"]);
+
+    for url in [
+        "/outcomes?limit=0",
+        "/outcomes?limit=1001",
+        "/outcomes?cursor=nonsense",
+        "/outcomes?status=exploded",
+        "/outcomes?since=yesterday",
+    ] {
+        let res = server.get(url).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+        let body: Value = res.json();
+        assert_eq!(body["code"], "INVALID_QUERY", "{url}");
+    }
+}
+
+#[tokio::test]
+async fn trimming_drops_outcomes_past_retention() {
+    let store: Arc<dyn ShortTermStore> = Arc::new(MemoryShortTermStore::new());
+    let engine = make_engine(Arc::clone(&store), None);
+    run_to(&engine, "fresh", "report", TaskStatus::Completed).await;
+    store
+        .record_outcome(TaskOutcome {
+            task_id: "stale".to_string(),
+            r#type: Some("report".to_string()),
+            status: TaskStatus::Completed,
+            completed_at: engine.clock().now_ms() - 3.0 * HOUR_MS,
+            error_code: None,
+            origin: None,
+        })
+        .await
+        .unwrap();
+
+    let trimmer = OutcomeTrimmer::new(OutcomeTrimmerOptions {
+        engine: Arc::clone(&engine),
+        short_term_store: Arc::clone(&store),
+        check_interval_ms: 60_000,
+        retention_ms: HOUR_MS as u64,
+    });
+    assert_eq!(trimmer.tick().await.unwrap(), 1);
+
+    let server = make_server(&engine, AuthMode::None);
+    let page = get_page(&server, "/outcomes").await;
+    assert_eq!(task_ids(&page), ["fresh"]);
+}
+
+#[tokio::test]
+async fn failed_index_write_does_not_fail_the_transition() {
+    let hooks = Arc::new(ErrorLog::default());
+    let engine = make_engine(
+        Arc::new(FailingOutcomesStore {
+            inner: MemoryShortTermStore::new(),
+        }),
+        Some(hooks.clone()),
+    );
+
+    let task = run_to(&engine, "t1", "report", TaskStatus::Completed).await;
+    assert_eq!(task.status, TaskStatus::Completed);
+    let stored = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(stored.status, TaskStatus::Completed);
+
+    let errors = hooks.0.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].operation, "outcomes.record");
+    assert_eq!(errors[0].task_id.as_deref(), Some("t1"));
+}
+
+#[tokio::test]
+async fn outcomes_require_the_history_scope() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()), None);
+    run_to(&engine, "t1", "report", TaskStatus::Completed).await;
+    let server = make_server(
+        &engine,
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+    );
+
+    server
+        .get("/outcomes")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get("/outcomes")
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .await;
+    res.assert_status_ok();
+    let page: Value = res.json();
+    assert_eq!(task_ids(&page), ["t1"]);
+}
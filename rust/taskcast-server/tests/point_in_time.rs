@@ -0,0 +1,249 @@
+//! Integration tests for point-in-time reads: `GET /tasks/{id}?asOf=` and
+//! `GET /tasks/{id}/events/history?until=`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskError, TaskEvent, TaskStatus, TransitionPayload,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "point-in-time-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth: AuthMode) -> TestServer {
+    let (app, _) = create_app(Arc::clone(engine), auth, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "incident-review", "scope": scope, "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// Keeps consecutive steps on distinct millisecond timestamps.
+async fn tick() {
+    tokio::time::sleep(Duration::from_millis(5)).await;
+}
+
+/// Creates `t1` and takes it through running (with a partial result), a log
+/// event and failed. Returns the stored events.
+async fn failed_run(engine: &TaskEngine) -> Vec<TaskEvent> {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            metadata: Some(HashMap::from([("owner".to_string(), json!("ops"))])),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    tick().await;
+    engine
+        .transition_task(
+            "t1",
+            TaskStatus::Running,
+            Some(TransitionPayload {
+                result: Some(HashMap::from([("rows".to_string(), json!(10))])),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    tick().await;
+    engine
+        .publish_event(
+            "t1",
+            PublishEventInput {
+                r#type: "log".to_string(),
+                level: Level::Warn,
+                data: json!({ "message": "upstream slow" }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+    tick().await;
+    engine
+        .transition_task(
+            "t1",
+            TaskStatus::Failed,
+            Some(TransitionPayload {
+                error: Some(TaskError {
+                    code: Some("TIMEOUT".to_string()),
+                    message: "upstream timed out".to_string(),
+                    details: None,
+                }),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    engine.get_events("t1", None).await.unwrap()
+}
+
+async fn task_as_of(server: &TestServer, as_of: f64) -> Value {
+    let res = server.get(&format!("/tasks/t1?asOf={as_of}")).await;
+    res.assert_status_ok();
+    res.json()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn task_is_reconstructed_at_each_cut_point() {
+    let engine = make_engine();
+    let events = failed_run(&engine).await;
+    let server = make_server(&engine, AuthMode::None);
+    let created_at = engine.get_task("t1").await.unwrap().unwrap().created_at;
+    let (running, log, failed) = (&events[0], &events[1], &events[2]);
+
+    let task = task_as_of(&server, created_at).await;
+    assert_eq!(task["status"], "pending");
+    assert!(task.get("result").is_none());
+    assert_eq!(task["asOf"], created_at);
+    // Not event-sourced: the current value.
+    assert_eq!(task["metadata"]["owner"], "ops");
+
+    let task = task_as_of(&server, running.timestamp).await;
+    assert_eq!(task["status"], "running");
+    assert_eq!(task["result"], json!({ "rows": 10 }));
+    assert!(task.get("error").is_none());
+    assert!(task.get("completedAt").is_none());
+
+    let task = task_as_of(&server, log.timestamp).await;
+    assert_eq!(task["status"], "running");
+
+    let task = task_as_of(&server, failed.timestamp).await;
+    assert_eq!(task["status"], "failed");
+    assert_eq!(task["result"], json!({ "rows": 10 }));
+    assert_eq!(task["error"]["code"], "TIMEOUT");
+    assert_eq!(task["completedAt"], failed.timestamp);
+}
+
+#[tokio::test]
+async fn history_is_cut_at_until() {
+    let engine = make_engine();
+    let events = failed_run(&engine).await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server
+        .get(&format!(
+            "/tasks/t1/events/history?until={}",
+            events[1].timestamp
+        ))
+        .await;
+    res.assert_status_ok();
+    let body: Vec<Value> = res.json();
+    let types: Vec<&str> = body.iter().map(|e| e["type"].as_str().unwrap()).collect();
+    assert_eq!(types, ["taskcast:status", "log"]);
+
+    // Combines with the other history filters.
+    let res = server
+        .get(&format!(
+            "/tasks/t1/events/history?until={}&types=taskcast:status",
+            events[2].timestamp
+        ))
+        .await;
+    let body: Vec<Value> = res.json();
+    assert_eq!(body.len(), 2);
+
+    let res = server
+        .get(&format!(
+            "/tasks/t1/events/history?until={}&seriesFormat=accumulated",
+            events[2].timestamp
+        ))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_QUERY");
+    assert_eq!(body["details"]["param"], "until");
+}
+
+#[tokio::test]
+async fn as_of_outside_the_task_lifetime_is_rejected() {
+    let engine = make_engine();
+    failed_run(&engine).await;
+    let server = make_server(&engine, AuthMode::None);
+    let created_at = engine.get_task("t1").await.unwrap().unwrap().created_at;
+
+    for as_of in [created_at - 1.0, 4_102_444_800_000.0] {
+        let res = server.get(&format!("/tasks/t1?asOf={as_of}")).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+        let body: Value = res.json();
+        assert_eq!(body["code"], "INVALID_INPUT");
+    }
+
+    server
+        .get(&format!("/tasks/missing?asOf={created_at}"))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn as_of_requires_the_history_scope() {
+    let engine = make_engine();
+    failed_run(&engine).await;
+    let server = make_server(
+        &engine,
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+    );
+    let created_at = engine.get_task("t1").await.unwrap().unwrap().created_at;
+    let url = format!("/tasks/t1?asOf={created_at}");
+
+    let res = server
+        .get(&url)
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(&url)
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .await;
+    res.assert_status_ok();
+    let task: Value = res.json();
+    assert_eq!(task["status"], "pending");
+
+    // The current task still needs only the subscribe scope.
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status_ok();
+}
@@ -0,0 +1,219 @@
+//! Integration tests for the publish response: event index/count headers and
+//! the optional inline SSE envelope.
+
+use std::future::IntoFuture;
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::{TestResponse, TestServer};
+use serde_json::json;
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> (Arc<TaskEngine>, TestServer) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    (engine, TestServer::new(app))
+}
+
+async fn running_task(engine: &TaskEngine, server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+fn header_u64(resp: &TestResponse, name: &str) -> u64 {
+    resp.header(name).to_str().unwrap().parse().unwrap()
+}
+
+/// Returns the data of every `taskcast.event` frame in an SSE body.
+fn sse_payloads(body: &str) -> Vec<serde_json::Value> {
+    let mut results = Vec::new();
+    let mut current_event = String::new();
+    for line in body.lines() {
+        if let Some(ev) = line.strip_prefix("event: ") {
+            current_event = ev.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            if current_event == "taskcast.event" {
+                results.push(serde_json::from_str(data).unwrap());
+            }
+        }
+    }
+    results
+}
+
+// ─── Headers ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn single_publish_reports_index_and_count() {
+    let (engine, server) = make_server();
+    running_task(&engine, &server, "pub-headers").await;
+
+    let resp = server
+        .post("/tasks/pub-headers/events")
+        .json(&json!({ "type": "log", "level": "info", "data": null }))
+        .await;
+
+    resp.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = resp.json();
+    // Index 0 is the running status event.
+    assert_eq!(body["index"], 1);
+    assert_eq!(header_u64(&resp, "x-taskcast-event-index"), 1);
+    assert_eq!(header_u64(&resp, "x-taskcast-event-count"), 2);
+}
+
+#[tokio::test]
+async fn headers_are_consistent_under_interleaved_publishes() {
+    let (engine, server) = make_server();
+    running_task(&engine, &server, "pub-interleaved").await;
+
+    let requests = (0..20).map(|i| {
+        server
+            .post("/tasks/pub-interleaved/events")
+            .json(&json!({ "type": "log", "level": "info", "data": { "i": i } }))
+            .into_future()
+    });
+    let responses = futures::future::join_all(requests).await;
+
+    let mut indices = Vec::new();
+    for resp in &responses {
+        resp.assert_status(StatusCode::CREATED);
+        let body: serde_json::Value = resp.json();
+        let index = header_u64(resp, "x-taskcast-event-index");
+        assert_eq!(body["index"], index);
+        // Other publishes may land between the append and the count read,
+        // but never fewer events than this one's position.
+        let count = header_u64(resp, "x-taskcast-event-count");
+        assert!(count > index && count <= 21, "index {index}, count {count}");
+        indices.push(index);
+    }
+    indices.sort_unstable();
+    assert_eq!(indices, (1..=20).collect::<Vec<u64>>());
+
+    let resp = server
+        .post("/tasks/pub-interleaved/events")
+        .json(&json!({ "type": "log", "level": "info", "data": null }))
+        .await;
+    assert_eq!(header_u64(&resp, "x-taskcast-event-index"), 21);
+    assert_eq!(header_u64(&resp, "x-taskcast-event-count"), 22);
+}
+
+// ─── Envelope ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn envelope_matches_what_sse_delivers() {
+    let (engine, server) = make_server();
+    running_task(&engine, &server, "pub-envelope").await;
+
+    let mut envelopes = Vec::new();
+    for event_type in ["llm.delta", "tool.call", "llm.delta"] {
+        let resp = server
+            .post("/tasks/pub-envelope/events")
+            .add_query_param("includeEnvelope", "true")
+            .add_query_param("types", "llm.*")
+            .json(&json!({ "type": event_type, "level": "info", "data": { "t": event_type } }))
+            .await;
+        resp.assert_status(StatusCode::CREATED);
+        envelopes.push(resp.json::<serde_json::Value>());
+    }
+    assert_eq!(
+        envelopes[1],
+        json!({ "rawIndex": 2, "filteredIndex": null })
+    );
+    assert_eq!(envelopes[0]["filteredIndex"], 0);
+    assert_eq!(envelopes[2]["filteredIndex"], 1);
+
+    engine
+        .transition_task("pub-envelope", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    let resp = server
+        .get("/tasks/pub-envelope/events")
+        .add_query_param("types", "llm.*")
+        .await;
+    assert_eq!(
+        sse_payloads(&resp.text()),
+        vec![envelopes[0].clone(), envelopes[2].clone()]
+    );
+}
+
+#[tokio::test]
+async fn batch_entries_keep_order_and_header_reflects_last() {
+    let (engine, server) = make_server();
+    running_task(&engine, &server, "pub-batch").await;
+
+    let resp = server
+        .post("/tasks/pub-batch/events")
+        .add_query_param("includeEnvelope", "true")
+        .add_query_param("levels", "warn,error")
+        .add_query_param("includeStatus", "false")
+        .json(&json!([
+            { "type": "a", "level": "warn", "data": null },
+            { "type": "b", "level": "info", "data": null },
+            { "type": "c", "level": "error", "data": null }
+        ]))
+        .await;
+
+    resp.assert_status(StatusCode::CREATED);
+    let entries: Vec<serde_json::Value> = resp.json();
+    let raw: Vec<_> = entries.iter().map(|e| e["rawIndex"].clone()).collect();
+    assert_eq!(raw, vec![json!(1), json!(2), json!(3)]);
+    let filtered: Vec<_> = entries.iter().map(|e| e["filteredIndex"].clone()).collect();
+    assert_eq!(filtered, vec![json!(0), json!(null), json!(1)]);
+    assert_eq!(entries[2]["type"], "c");
+    assert_eq!(header_u64(&resp, "x-taskcast-event-index"), 3);
+    assert_eq!(header_u64(&resp, "x-taskcast-event-count"), 4);
+
+    // Without includeEnvelope the batch returns raw events, still in order.
+    let resp = server
+        .post("/tasks/pub-batch/events")
+        .json(&json!([
+            { "type": "d", "level": "info", "data": null },
+            { "type": "e", "level": "info", "data": null }
+        ]))
+        .await;
+    let events: Vec<serde_json::Value> = resp.json();
+    let indices: Vec<_> = events.iter().map(|e| e["index"].clone()).collect();
+    assert_eq!(indices, vec![json!(4), json!(5)]);
+    assert_eq!(header_u64(&resp, "x-taskcast-event-index"), 5);
+}
+
+#[tokio::test]
+async fn invalid_envelope_filter_is_rejected_before_publishing() {
+    let (engine, server) = make_server();
+    running_task(&engine, &server, "pub-bad-filter").await;
+
+    let resp = server
+        .post("/tasks/pub-bad-filter/events")
+        .add_query_param("includeEnvelope", "true")
+        .add_query_param("labels", "no-colon")
+        .json(&json!({ "type": "log", "level": "info", "data": null }))
+        .await;
+
+    resp.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(engine.event_count("pub-bad-filter").await.unwrap(), 1);
+}
@@ -0,0 +1,321 @@
+//! Integration tests for `POST /tasks/:taskId/events/stream`: NDJSON bodies
+//! are stored chunk by chunk and acknowledged as each chunk is stored.
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::{Body, Bytes};
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use futures::{stream, StreamExt};
+use http::Request;
+use serde_json::{json, Value};
+use taskcast_core::config::{IngestConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, EventSink, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+use tower::ServiceExt;
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+const NDJSON: &str = "application/x-ndjson";
+
+/// Records, for every stored event, how many body lines the client had sent
+/// that were not yet stored.
+#[derive(Default)]
+struct LagSink {
+    sent: Arc<AtomicUsize>,
+    stored: AtomicUsize,
+    max_lag: AtomicUsize,
+}
+
+#[async_trait]
+impl EventSink for LagSink {
+    async fn on_event(
+        &self,
+        event: &TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if event.r#type == "log" {
+            let stored = self.stored.fetch_add(1, Ordering::SeqCst) + 1;
+            let lag = self.sent.load(Ordering::SeqCst).saturating_sub(stored);
+            self.max_lag.fetch_max(lag, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+}
+
+async fn make_engine(sink: Option<Arc<LagSink>>) -> Arc<TaskEngine> {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: sink
+            .into_iter()
+            .map(|sink| sink as Arc<dyn EventSink>)
+            .collect(),
+        coalesce_reads: None,
+    }));
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine
+}
+
+fn config(ingest: IngestConfig) -> Option<TaskcastConfig> {
+    Some(TaskcastConfig {
+        ingest: Some(ingest),
+        ..Default::default()
+    })
+}
+
+fn make_server(engine: Arc<TaskEngine>, config: Option<TaskcastConfig>) -> TestServer {
+    let (app, _) = create_app(engine, AuthMode::None, None, config, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn log_line(n: usize) -> String {
+    json!({ "type": "log", "level": "info", "data": { "n": n } }).to_string()
+}
+
+fn parse_lines(text: &str) -> Vec<Value> {
+    text.lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+async fn stored_logs(engine: &TaskEngine) -> Vec<TaskEvent> {
+    engine
+        .get_events("t1", None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|event| event.r#type == "log")
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn large_ingest_is_stored_in_chunks_as_the_body_arrives() {
+    const LINES: usize = 50_000;
+    const LINES_PER_FRAME: usize = 100;
+    const CHUNK_SIZE: usize = 500;
+
+    let sink = Arc::new(LagSink::default());
+    let sent = Arc::clone(&sink.sent);
+    let engine = make_engine(Some(Arc::clone(&sink))).await;
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        config(IngestConfig {
+            chunk_size: Some(CHUNK_SIZE),
+            ..Default::default()
+        }),
+        CorsConfig::default(),
+    );
+
+    // Frames are produced only when the server asks for them.
+    let frames = stream::iter(0..LINES / LINES_PER_FRAME).map(move |frame| {
+        let mut text = String::new();
+        for n in frame * LINES_PER_FRAME..(frame + 1) * LINES_PER_FRAME {
+            text.push_str(&log_line(n));
+            text.push('\n');
+        }
+        sent.fetch_add(LINES_PER_FRAME, Ordering::SeqCst);
+        Ok::<_, Infallible>(Bytes::from(text))
+    });
+    let request = Request::post("/tasks/t1/events/stream")
+        .header("content-type", NDJSON)
+        .body(Body::from_stream(frames))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut body = response.into_body().into_data_stream();
+    let mut ack_frames = 0;
+    let mut acks = Vec::new();
+    while let Some(frame) = body.next().await {
+        ack_frames += 1;
+        acks.extend(parse_lines(std::str::from_utf8(&frame.unwrap()).unwrap()));
+    }
+
+    assert_eq!(ack_frames, LINES / CHUNK_SIZE);
+    assert_eq!(acks.len(), LINES);
+    // The server never holds more than a chunk and the frame that filled it.
+    let max_lag = sink.max_lag.load(Ordering::SeqCst);
+    assert!(
+        max_lag <= CHUNK_SIZE + LINES_PER_FRAME,
+        "{max_lag} lines were read ahead of the store"
+    );
+
+    let stored = stored_logs(&engine).await;
+    assert_eq!(stored.len(), LINES);
+    for (ack, event) in acks.iter().zip(&stored) {
+        assert_eq!(ack["id"], event.id.as_str());
+        assert_eq!(ack["index"], event.index);
+    }
+}
+
+#[tokio::test]
+async fn malformed_line_stops_the_stream_after_storing_earlier_lines() {
+    let engine = make_engine(None).await;
+    let server = make_server(
+        Arc::clone(&engine),
+        config(IngestConfig {
+            chunk_size: Some(3),
+            ..Default::default()
+        }),
+    );
+    // Line 3 is blank and line 8 is malformed.
+    let lines = [
+        log_line(0),
+        log_line(1),
+        String::new(),
+        log_line(2),
+        log_line(3),
+        log_line(4),
+        log_line(5),
+        "{\"type\": \"log\", ".to_string(),
+        log_line(6),
+    ];
+
+    let res = server
+        .post("/tasks/t1/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(lines.join("\n")))
+        .await;
+
+    res.assert_status_ok();
+    assert_eq!(res.header("content-type"), NDJSON);
+    let out = parse_lines(&res.text());
+    let (error, acks) = out.split_last().unwrap();
+    assert_eq!(acks.len(), 6);
+    assert_eq!(error["line"], 8);
+    assert_eq!(error["error"]["code"], "BAD_REQUEST");
+    assert!(error["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("line 8"));
+
+    let stored = stored_logs(&engine).await;
+    assert_eq!(stored.len(), 6);
+    for (ack, event) in acks.iter().zip(&stored) {
+        assert_eq!(ack["id"], event.id.as_str());
+        assert_eq!(ack["index"], event.index);
+    }
+}
+
+#[tokio::test]
+async fn limits_apply_per_line_and_per_request() {
+    let engine = make_engine(None).await;
+    let server = make_server(
+        Arc::clone(&engine),
+        config(IngestConfig {
+            chunk_size: Some(10),
+            max_line_bytes: Some(200),
+            max_events: Some(3),
+            max_request_bytes: None,
+        }),
+    );
+
+    let long = json!({ "type": "log", "level": "info", "data": "x".repeat(300) }).to_string();
+    let res = server
+        .post("/tasks/t1/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(format!("{}\n{long}\n", log_line(0))))
+        .await;
+    let out = parse_lines(&res.text());
+    assert_eq!(out.len(), 2);
+    assert_eq!(out[1]["line"], 2);
+    assert!(out[1]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("longer than 200 bytes"));
+
+    let body: Vec<_> = (0..5).map(log_line).collect();
+    let res = server
+        .post("/tasks/t1/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(body.join("\n")))
+        .await;
+    let out = parse_lines(&res.text());
+    assert_eq!(out.len(), 4);
+    assert_eq!(out[3]["line"], 4);
+
+    assert_eq!(stored_logs(&engine).await.len(), 4);
+}
+
+#[tokio::test]
+async fn request_byte_limit_ends_the_stream() {
+    let engine = make_engine(None).await;
+    let line = log_line(0);
+    let server = make_server(
+        Arc::clone(&engine),
+        config(IngestConfig {
+            chunk_size: Some(1),
+            max_request_bytes: Some(line.len() as u64 * 2),
+            ..Default::default()
+        }),
+    );
+
+    let body: Vec<_> = (0..3).map(log_line).collect();
+    let res = server
+        .post("/tasks/t1/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(body.join("\n")))
+        .await;
+
+    let out = parse_lines(&res.text());
+    let error = out.last().unwrap();
+    assert_eq!(error["line"], 1);
+    assert!(error["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("at most"));
+    assert!(stored_logs(&engine).await.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_other_content_types_and_finished_tasks() {
+    let engine = make_engine(None).await;
+    let server = make_server(Arc::clone(&engine), None);
+
+    server
+        .post("/tasks/t1/events/stream")
+        .content_type("application/json")
+        .bytes(Bytes::from(log_line(0)))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .post("/tasks/missing/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(log_line(0)))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    server
+        .post("/tasks/t1/events/stream")
+        .content_type(NDJSON)
+        .bytes(Bytes::from(log_line(0)))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,175 @@
+//! Integration tests for the shared `QueryOptions` extractor: the SSE and
+//! history routes parse the same parameters the same way, and embedders'
+//! routes can reuse it.
+
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::Json;
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AppError, AuthMode, CorsConfig, QueryOptions};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_app() -> axum::Router {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    app
+}
+
+async fn create_task_with_events(server: &TestServer) -> String {
+    let res = server.post("/tasks").json(&json!({})).await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&json!([
+            { "type": "llm.chunk", "level": "debug", "data": {} },
+            { "type": "llm.chunk", "level": "warn", "data": {} },
+            { "type": "progress", "level": "error", "data": {} },
+        ]))
+        .await
+        .assert_status(StatusCode::CREATED);
+    task_id
+}
+
+/// The error body without the per-request `requestId`.
+fn error_body(res: &axum_test::TestResponse) -> Value {
+    let mut body: Value = res.json();
+    body.as_object_mut().unwrap().remove("requestId");
+    body
+}
+
+// ─── Route Parity ────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_and_history_reject_malformed_parameters_alike() {
+    let server = TestServer::new(make_app());
+    let task_id = create_task_with_events(&server).await;
+
+    for query in [
+        "since.index=abc",
+        "since.timestamp=later",
+        "limit=-1",
+        "levels=info,loud",
+        "minLevel=verbose",
+        "includeStatus=yes",
+        "labels=region",
+        "types=",
+        "limit=1&limit=2",
+    ] {
+        let sse = server
+            .get(&format!("/tasks/{task_id}/events?{query}"))
+            .await;
+        let history = server
+            .get(&format!("/tasks/{task_id}/events/history?{query}"))
+            .await;
+        sse.assert_status(StatusCode::BAD_REQUEST);
+        history.assert_status(StatusCode::BAD_REQUEST);
+        let body = error_body(&sse);
+        assert_eq!(body["code"], "INVALID_QUERY", "{query}");
+        assert_eq!(body, error_body(&history), "{query}");
+    }
+}
+
+#[tokio::test]
+async fn history_applies_type_and_level_filters() {
+    let server = TestServer::new(make_app());
+    let task_id = create_task_with_events(&server).await;
+
+    let history: Value = server
+        .get(&format!(
+            "/tasks/{task_id}/events/history?types=llm.*&minLevel=warn&limit=5"
+        ))
+        .await
+        .json();
+    let events = history.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["type"], "llm.chunk");
+    assert_eq!(events[0]["level"], "warn");
+
+    let history: Value = server
+        .get(&format!(
+            "/tasks/{task_id}/events/history?includeStatus=false&limit=2"
+        ))
+        .await
+        .json();
+    let types: Vec<&str> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["llm.chunk", "llm.chunk"]);
+}
+
+#[tokio::test]
+async fn unsupported_parameters_are_refused() {
+    let server = TestServer::new(make_app());
+    let task_id = create_task_with_events(&server).await;
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events/history?order=desc"))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.json::<Value>()["details"],
+        json!({ "param": "order", "reason": "not supported by this endpoint" })
+    );
+
+    server
+        .get(&format!("/tasks/{task_id}/events/history?wrap=false"))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .get(&format!("/tasks/{task_id}/events?fields=id"))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    server
+        .get("/events?since.index=1")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+// ─── Embedder Routes ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn custom_routes_can_extract_query_options() {
+    async fn handler(options: QueryOptions) -> Result<Json<Value>, AppError> {
+        options.reject(&["cursor"])?;
+        Ok(Json(json!({
+            "limit": options.limit,
+            "types": options.types,
+        })))
+    }
+    let server = TestServer::new(axum::Router::new().route("/custom", get(handler)));
+
+    let body: Value = server.get("/custom?limit=3&types=a,b").await.json();
+    assert_eq!(body, json!({ "limit": 3, "types": ["a", "b"] }));
+
+    let res = server.get("/custom?limit=three").await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_QUERY");
+    assert_eq!(body["details"]["param"], "limit");
+    server
+        .get("/custom?cursor=abc")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
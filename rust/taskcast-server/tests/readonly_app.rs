@@ -0,0 +1,248 @@
+//! Integration tests for the read-only router from `create_readonly_app`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+    TaskStatus,
+};
+use taskcast_server::{create_app, create_readonly_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "readonly-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn jwt_mode() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer() -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "readonly-test",
+            "scope": ["*"],
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn create(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+}
+
+// ─── Mutations ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn mutations_are_refused_before_auth() {
+    let engine = make_engine();
+    create(&engine, "t1").await;
+    let server = TestServer::new(create_readonly_app(
+        Arc::clone(&engine),
+        jwt_mode(),
+        None,
+        CorsConfig::default(),
+    ));
+
+    for authorized in [false, true] {
+        let auth = |req: axum_test::TestRequest| match authorized {
+            true => req.add_header(header::AUTHORIZATION, bearer()),
+            false => req,
+        };
+        let res = auth(server.post("/tasks").json(&json!({}))).await;
+        res.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.header(header::ALLOW), "GET, HEAD, OPTIONS");
+        assert_eq!(res.json::<serde_json::Value>()["code"], "READ_ONLY");
+        auth(server.post("/v1/tasks").json(&json!({})))
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        auth(server.delete("/tasks/t1"))
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        auth(
+            server
+                .post("/tasks/t1/events")
+                .json(&json!({ "type": "log", "level": "info", "data": null })),
+        )
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        auth(
+            server
+                .patch("/tasks/t1/status")
+                .json(&json!({ "status": "running" })),
+        )
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        auth(server.post("/admin/tasks/bulk").json(&json!({})))
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        auth(server.get("/tasks/t1/archive"))
+            .await
+            .assert_status_not_found();
+        auth(server.get("/workers/ws"))
+            .await
+            .assert_status_not_found();
+    }
+
+    let task = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Pending);
+    assert_eq!(engine.event_count("t1").await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn reads_still_need_credentials() {
+    let engine = make_engine();
+    create(&engine, "t1").await;
+    let server = TestServer::new(create_readonly_app(
+        Arc::clone(&engine),
+        jwt_mode(),
+        None,
+        CorsConfig::default(),
+    ));
+
+    server
+        .get("/tasks/t1")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    let res = server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, bearer())
+        .await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>()["id"], "t1");
+    server.get("/health").await.assert_status_ok();
+}
+
+// ─── Both apps ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn readonly_and_writable_apps_share_one_engine() {
+    let engine = make_engine();
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let writable = TestServer::new(app);
+    let readonly = TestServer::new(create_readonly_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        CorsConfig::default(),
+    ));
+
+    writable
+        .post("/tasks")
+        .json(&json!({ "id": "shared" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    readonly
+        .post("/tasks")
+        .json(&json!({ "id": "refused" }))
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    writable
+        .patch("/tasks/shared/status")
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    writable
+        .post("/tasks/shared/events")
+        .json(&json!({ "type": "progress", "level": "info", "data": { "percent": 10 } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let task: serde_json::Value = readonly.get("/tasks/shared").await.json();
+    assert_eq!(task["status"], "running");
+    let history: Vec<serde_json::Value> = readonly
+        .get("/tasks/shared/events/history?types=progress")
+        .await
+        .json();
+    assert_eq!(history.len(), 1);
+    let stats: serde_json::Value = readonly.get("/tasks/shared/events/stats").await.json();
+    assert_eq!(stats["types"]["progress"], 1);
+    let listed: serde_json::Value = readonly.get("/tasks").await.json();
+    assert!(listed.to_string().contains("shared"));
+    readonly.get("/tasks/refused").await.assert_status_not_found();
+}
+
+// ─── SSE ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_streams_live_events_through_the_readonly_app() {
+    let engine = make_engine();
+    create(&engine, "live").await;
+    engine
+        .transition_task("live", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let app = create_readonly_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        CorsConfig::default(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let writer = Arc::clone(&engine);
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        writer
+            .transition_task("live", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+    });
+
+    let response = reqwest::Client::new()
+        .get(format!("http://{addr}/tasks/live/events"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body = tokio::time::timeout(Duration::from_secs(5), response.text())
+        .await
+        .expect("stream should end with the task")
+        .unwrap();
+    assert!(body.contains("\"completed\""), "{body}");
+    assert!(body.contains("event: taskcast.done"), "{body}");
+}
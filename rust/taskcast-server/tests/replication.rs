@@ -0,0 +1,408 @@
+//! Integration tests for replicating one deployment's writes to another:
+//! `ReplicationSink` on the primary and `X-Taskcast-Replicated` writes on
+//! the secondary, over real HTTP.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{ReplicationConfig, TaskcastConfig};
+use taskcast_core::{
+    BackgroundTasks, CreateTaskInput, EventSink, Level, MemoryBroadcastProvider,
+    MemoryShortTermStore, PublishEventInput, SeriesMode, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskOrigin, TaskOriginKind, TaskStatus, TransitionPayload,
+};
+use taskcast_server::{
+    create_app, AuthMode, CorsConfig, JwtConfig, ReplicationSink, ReplicationSinkOptions,
+    REPLICATED_HEADER,
+};
+
+const JWT_SECRET: &str = "replication-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn replication_config(allowed: bool) -> TaskcastConfig {
+    TaskcastConfig {
+        replication: Some(ReplicationConfig {
+            allow_replicated_writes: Some(allowed),
+        }),
+        ..Default::default()
+    }
+}
+
+fn make_engine(sinks: Vec<Arc<dyn EventSink>>) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks,
+        coalesce_reads: None,
+    }))
+}
+
+/// Opens a sink; it ships nothing until started.
+fn make_sink(target: SocketAddr, queue_dir: &Path) -> Arc<ReplicationSink> {
+    Arc::new(
+        ReplicationSink::open(ReplicationSinkOptions {
+            url: format!("http://{target}"),
+            token: None,
+            queue_path: queue_dir.join("replication.jsonl"),
+            max_queued: 1000,
+            batch_size: 10,
+            retry_interval: Duration::from_millis(20),
+        })
+        .unwrap(),
+    )
+}
+
+/// An engine replicating through `sink`, which is started on its
+/// background tasks.
+fn make_primary(sink: &Arc<ReplicationSink>) -> Arc<TaskEngine> {
+    let engine = make_engine(vec![Arc::clone(sink) as Arc<dyn EventSink>]);
+    sink.start(engine.background());
+    engine
+}
+
+/// Serves a node accepting replicated writes on `listener`.
+fn serve(engine: Arc<TaskEngine>, listener: tokio::net::TcpListener) {
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(replication_config(true)),
+        CorsConfig::default(),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+}
+
+async fn bind() -> (tokio::net::TcpListener, SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    (listener, addr)
+}
+
+async fn wait_until_drained(sink: &ReplicationSink) {
+    for _ in 0..500 {
+        if sink.queued() == 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("replication queue still holds {} writes", sink.queued());
+}
+
+fn log(message: &str) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!({ "message": message }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+fn delta(text: &str) -> PublishEventInput {
+    PublishEventInput {
+        r#type: "llm.delta".to_string(),
+        data: json!({ "delta": text }),
+        series_id: Some("answer".to_string()),
+        series_mode: Some(SeriesMode::Accumulate),
+        ..log("")
+    }
+}
+
+/// Creates `task_id`, runs it through a few events and completes it.
+async fn run_task(engine: &TaskEngine, task_id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            r#type: Some("report".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    engine.publish_event(task_id, log("started")).await.unwrap();
+    for text in ["Hel", "lo"] {
+        engine.publish_event(task_id, delta(text)).await.unwrap();
+    }
+    engine
+        .transition_task(
+            task_id,
+            TaskStatus::Completed,
+            Some(TransitionPayload {
+                result: Some([("answer".to_string(), json!("Hello"))].into()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+}
+
+fn ids_and_indices(events: &[TaskEvent]) -> Vec<(String, u64)> {
+    events
+        .iter()
+        .map(|event| (event.id.clone(), event.index))
+        .collect()
+}
+
+async fn assert_replicated(primary: &TaskEngine, secondary: &TaskEngine, task_id: &str) {
+    let mut task = secondary.get_task(task_id).await.unwrap().unwrap();
+    let original = primary.get_task(task_id).await.unwrap().unwrap();
+    // The copy records where it came from, and the kind it had there.
+    assert_eq!(
+        task.origin,
+        Some(TaskOrigin::new(TaskOriginKind::Replication).with_detail("user"))
+    );
+    task.origin = original.origin.clone();
+    assert_eq!(task, original);
+    assert_eq!(task.status, TaskStatus::Completed);
+
+    let events = secondary.get_events(task_id, None).await.unwrap();
+    assert_eq!(
+        ids_and_indices(&events),
+        ids_and_indices(&primary.get_events(task_id, None).await.unwrap())
+    );
+    let total = secondary
+        .get_series_latest(task_id, "answer")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(total.data, json!({ "delta": "Hello" }));
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn lifecycle_on_the_primary_appears_on_the_secondary() {
+    let dir = tempfile::tempdir().unwrap();
+    let (listener, addr) = bind().await;
+    let secondary = make_engine(Vec::new());
+    serve(Arc::clone(&secondary), listener);
+    let sink = make_sink(addr, dir.path());
+    let primary = make_primary(&sink);
+
+    run_task(&primary, "t1").await;
+    wait_until_drained(&sink).await;
+
+    assert_replicated(&primary, &secondary, "t1").await;
+}
+
+#[tokio::test]
+async fn writes_queue_during_an_outage_and_catch_up() {
+    let dir = tempfile::tempdir().unwrap();
+    // Reserve a port, then leave it closed until the secondary comes up.
+    let (listener, addr) = bind().await;
+    drop(listener);
+    let sink = make_sink(addr, dir.path());
+    let primary = make_primary(&sink);
+
+    run_task(&primary, "t1").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let queued = sink.queued();
+    assert!(queued > 0);
+
+    // The queue is on disk: a restarted sink picks it up.
+    drop(primary);
+    drop(sink);
+    let sink = make_sink(addr, dir.path());
+    assert_eq!(sink.queued(), queued);
+    sink.start(&BackgroundTasks::new(None));
+
+    let secondary = make_engine(Vec::new());
+    serve(
+        Arc::clone(&secondary),
+        tokio::net::TcpListener::bind(addr).await.unwrap(),
+    );
+    wait_until_drained(&sink).await;
+
+    let events = secondary.get_events("t1", None).await.unwrap();
+    assert_eq!(events.last().unwrap().r#type, "taskcast:status");
+    let task = secondary.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+}
+
+#[tokio::test]
+async fn closing_lets_the_drain_wait_for_the_queue() {
+    let dir = tempfile::tempdir().unwrap();
+    let (listener, addr) = bind().await;
+    let sink = make_sink(addr, dir.path());
+    let primary = make_primary(&sink);
+
+    // Written while the secondary is still down.
+    run_task(&primary, "t1").await;
+    assert!(sink.queued() > 0);
+    assert!(!primary.background().drain(Duration::from_millis(50)).await);
+
+    let secondary = make_engine(Vec::new());
+    serve(Arc::clone(&secondary), listener);
+    sink.close();
+    assert!(primary.background().drain(Duration::from_secs(5)).await);
+    assert_eq!(sink.queued(), 0);
+
+    assert_replicated(&primary, &secondary, "t1").await;
+}
+
+#[tokio::test]
+async fn replicated_writes_are_not_replicated_back() {
+    let dir_a = tempfile::tempdir().unwrap();
+    let dir_b = tempfile::tempdir().unwrap();
+    let (listener_a, addr_a) = bind().await;
+    let (listener_b, addr_b) = bind().await;
+    let sink_a = make_sink(addr_b, dir_a.path());
+    let sink_b = make_sink(addr_a, dir_b.path());
+    let a = make_primary(&sink_a);
+    let b = make_primary(&sink_b);
+    serve(Arc::clone(&a), listener_a);
+    serve(Arc::clone(&b), listener_b);
+
+    run_task(&a, "from-a").await;
+    run_task(&b, "from-b").await;
+    wait_until_drained(&sink_a).await;
+    wait_until_drained(&sink_b).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(sink_a.queued(), 0);
+    assert_eq!(sink_b.queued(), 0);
+
+    assert_replicated(&a, &b, "from-a").await;
+    assert_replicated(&b, &a, "from-b").await;
+}
+
+#[tokio::test]
+async fn redelivered_writes_are_applied_once() {
+    let engine = make_engine(Vec::new());
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        Some(replication_config(true)),
+        CorsConfig::default(),
+    );
+    let server = TestServer::new(app);
+    let task = json!({
+        "id": "t1",
+        "status": "running",
+        "createdAt": 1000.0,
+        "updatedAt": 2000.0,
+    });
+    let events = json!([
+        { "id": "e0", "taskId": "t1", "index": 0, "timestamp": 1500.0, "type": "log", "level": "info", "data": {} },
+        { "id": "e2", "taskId": "t1", "index": 2, "timestamp": 1600.0, "type": "log", "level": "info", "data": {} },
+    ]);
+
+    let created = server
+        .post("/tasks")
+        .add_header(REPLICATED_HEADER, "true")
+        .json(&task)
+        .await;
+    created.assert_status(StatusCode::CREATED);
+    assert_eq!(created.json::<Value>()["status"], "running");
+    server
+        .post("/tasks")
+        .add_header(REPLICATED_HEADER, "true")
+        .json(&json!({
+            "id": "t1",
+            "status": "pending",
+            "createdAt": 1000.0,
+            "updatedAt": 1500.0,
+        }))
+        .await
+        .assert_status(StatusCode::OK);
+
+    for (applied, skipped) in [(2, 0), (0, 2)] {
+        let response = server
+            .post("/tasks/t1/events")
+            .add_header(REPLICATED_HEADER, "true")
+            .json(&events)
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        assert_eq!(
+            response.json::<Value>(),
+            json!({ "applied": applied, "skipped": skipped })
+        );
+    }
+
+    let task = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Running);
+    assert_eq!(task.origin_kind(), TaskOriginKind::Replication);
+    let history = engine.get_events("t1", None).await.unwrap();
+    assert_eq!(
+        ids_and_indices(&history),
+        vec![("e0".to_string(), 0), ("e2".to_string(), 2)]
+    );
+    // Local publishes continue after the replicated indices.
+    let event = engine.publish_event("t1", log("local")).await.unwrap();
+    assert_eq!(event.index, 3);
+}
+
+#[tokio::test]
+async fn replicated_writes_need_the_flag_and_the_scope() {
+    let task = json!({
+        "id": "t1",
+        "status": "pending",
+        "createdAt": 1000.0,
+        "updatedAt": 1000.0,
+    });
+    let server = |allowed: bool| {
+        let auth = AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        });
+        let (app, _) = create_app(
+            make_engine(Vec::new()),
+            auth,
+            None,
+            Some(replication_config(allowed)),
+            CorsConfig::default(),
+        );
+        TestServer::new(app)
+    };
+    let bearer = |scope: &str| {
+        let token = encode(
+            &Header::default(),
+            &json!({ "scope": [scope], "taskIds": "*", "exp": 9999999999u64 }),
+            &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+        )
+        .unwrap();
+        HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+    };
+
+    for (allowed, scope, status) in [
+        (false, "task:replicate", StatusCode::FORBIDDEN),
+        (true, "task:create", StatusCode::FORBIDDEN),
+        (true, "task:replicate", StatusCode::CREATED),
+    ] {
+        server(allowed)
+            .post("/tasks")
+            .add_header(header::AUTHORIZATION, bearer(scope))
+            .add_header(REPLICATED_HEADER, "true")
+            .json(&task)
+            .await
+            .assert_status(status);
+    }
+
+    // Without the header the body is an ordinary create request.
+    server(false)
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, bearer("task:create"))
+        .json(&json!({ "id": "t2" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
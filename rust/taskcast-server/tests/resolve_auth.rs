@@ -18,9 +18,12 @@ fn make_jwt_server() -> (Arc<TaskEngine>, TestServer) {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -104,7 +107,9 @@ async fn resolve_task_returns_403_without_task_resolve_scope() {
 
     resp.assert_status(axum_test::http::StatusCode::FORBIDDEN);
     let body: serde_json::Value = resp.json();
-    assert_eq!(body["error"], "Forbidden");
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert_eq!(body["message"], "Forbidden");
+    assert_eq!(body["details"]["requiredScope"], "task:resolve");
 }
 
 // ─── GET /tasks/:id/request — 403 without task:resolve scope ──────────────
@@ -128,5 +133,7 @@ async fn get_blocked_request_returns_403_without_task_resolve_scope() {
 
     resp.assert_status(axum_test::http::StatusCode::FORBIDDEN);
     let body: serde_json::Value = resp.json();
-    assert_eq!(body["error"], "Forbidden");
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert_eq!(body["message"], "Forbidden");
+    assert_eq!(body["details"]["requiredScope"], "task:resolve");
 }
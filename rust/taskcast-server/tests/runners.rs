@@ -0,0 +1,208 @@
+//! Integration tests for `GET /admin/runners` and the runner controls.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    runner_fn, MemoryBroadcastProvider, MemoryShortTermStore, RunnerTickReport, TaskEngine,
+    TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "runners-test-secret-key-that-is-long-enough-for-hs256";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: Arc<TaskEngine>, auth_mode: AuthMode) -> TestServer {
+    let (app, _) = create_app(engine, auth_mode, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "runners-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// Starts a runner that counts its passes and fails every one of them.
+fn start_failing_runner(engine: &TaskEngine) -> Arc<AtomicU64> {
+    let passes = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&passes);
+    let runner = runner_fn("test.failing", Duration::from_secs(3600), move || {
+        let counter = Arc::clone(&counter);
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err::<RunnerTickReport, _>("upstream refused the sweep".into())
+        }
+    });
+    engine.runners().start(Arc::new(runner));
+    passes
+}
+
+async fn runner_status(server: &TestServer, name: &str) -> Value {
+    let body: Value = server.get("/admin/runners").await.json();
+    body["runners"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|runner| runner["name"] == name)
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn runners_lists_a_failing_runner_with_its_error() {
+    let engine = make_engine();
+    let passes = start_failing_runner(&engine);
+    let server = make_server(engine, AuthMode::None);
+
+    for _ in 0..100 {
+        if runner_status(&server, "test.failing").await["runs"] == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let status = runner_status(&server, "test.failing").await;
+    assert_eq!(passes.load(Ordering::SeqCst), 1);
+    assert_eq!(status["intervalMs"], 3_600_000);
+    assert_eq!(status["running"], true);
+    assert_eq!(status["paused"], false);
+    assert_eq!(status["lastOutcome"], "failed");
+    assert_eq!(status["lastError"], "upstream refused the sweep");
+    assert_eq!(status["consecutiveFailures"], 1);
+    assert!(status["lastRunAt"].as_f64().is_some());
+}
+
+#[tokio::test]
+async fn run_now_runs_a_pass_and_returns_its_status() {
+    let engine = make_engine();
+    let passes = start_failing_runner(&engine);
+    let server = make_server(engine, AuthMode::None);
+
+    let res = server.post("/admin/runners/test.failing/run-now").await;
+    res.assert_status_ok();
+    let status: Value = res.json();
+    // The first scheduled pass may or may not have run before the request.
+    let runs = status["runs"].as_u64().unwrap();
+    assert!(runs >= 1);
+    assert_eq!(passes.load(Ordering::SeqCst), runs);
+    assert_eq!(status["consecutiveFailures"], runs);
+    assert_eq!(status["lastOutcome"], "failed");
+}
+
+#[tokio::test]
+async fn pause_and_resume_flip_the_paused_flag() {
+    let engine = make_engine();
+    start_failing_runner(&engine);
+    let server = make_server(engine, AuthMode::None);
+
+    let paused: Value = server
+        .post("/admin/runners/test.failing/pause")
+        .await
+        .json();
+    assert_eq!(paused["paused"], true);
+    assert_eq!(runner_status(&server, "test.failing").await["paused"], true);
+
+    // A paused runner still runs on demand.
+    server
+        .post("/admin/runners/test.failing/run-now")
+        .await
+        .assert_status_ok();
+
+    let resumed: Value = server
+        .post("/admin/runners/test.failing/resume")
+        .await
+        .json();
+    assert_eq!(resumed["paused"], false);
+}
+
+#[tokio::test]
+async fn unknown_and_stopped_runners_are_refused() {
+    let engine = make_engine();
+    let handle = engine.runners().start(Arc::new(runner_fn(
+        "test.stopped",
+        Duration::from_secs(3600),
+        || async { Ok(RunnerTickReport::default()) },
+    )));
+    handle.abort();
+    let server = make_server(Arc::clone(&engine), AuthMode::None);
+
+    let res = server.post("/admin/runners/test.missing/pause").await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(res.json::<Value>()["code"], "NOT_FOUND");
+
+    for _ in 0..100 {
+        if runner_status(&server, "test.stopped").await["running"] == false {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let res = server.post("/admin/runners/test.stopped/run-now").await;
+    res.assert_status(StatusCode::CONFLICT);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "RUNNER_NOT_RUNNING");
+    assert_eq!(body["details"]["runner"], "test.stopped");
+}
+
+#[tokio::test]
+async fn runners_require_task_manage_scope() {
+    let server = make_server(
+        make_engine(),
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+    );
+
+    server
+        .get("/admin/runners")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get("/admin/runners")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/admin/runners/test.failing/pause")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let body: Value = server
+        .get("/admin/runners")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .json();
+    assert_eq!(body["runners"], json!([]));
+}
@@ -0,0 +1,125 @@
+//! Integration tests for `GET /admin/runtime`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{
+    create_app, create_app_with_options, AppOptions, AuthMode, CorsConfig, JwtConfig, LogLevel,
+    RuntimeSampler, StderrHttpFailureLogger,
+};
+
+const JWT_SECRET: &str = "runtime-metrics-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(
+    engine: Arc<TaskEngine>,
+    auth_mode: AuthMode,
+    sampler: Option<Arc<RuntimeSampler>>,
+) -> TestServer {
+    let (app, _) = create_app_with_options(
+        engine,
+        auth_mode,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            runtime_sampler: sampler,
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "runtime-metrics-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn runtime_samples_on_request_by_default() {
+    let (app, _) = create_app(make_engine(), AuthMode::None, None, None, CorsConfig::default());
+    let server = TestServer::new(app);
+
+    let body: Value = server.get("/admin/runtime").await.json();
+    let executor = &body["executor"];
+    assert_eq!(executor["workers"], 2);
+    assert!(executor["aliveTasks"].as_u64().is_some());
+    assert!(executor["globalQueueDepth"].as_u64().is_some());
+    assert_eq!(executor["workerStats"].as_array().unwrap().len(), 2);
+    assert!(executor["workerStats"][0]["busyRatio"].as_f64().is_some());
+    assert_eq!(body["engine"]["backgroundInFlight"], 0);
+    assert_eq!(body["engine"]["readRouting"]["preference"], "short");
+}
+
+#[tokio::test]
+async fn runtime_serves_the_started_samplers_latest_sample() {
+    let engine = make_engine();
+    let sampler = Arc::new(RuntimeSampler::new(Arc::clone(&engine)));
+    sampler.start(Duration::from_secs(60));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let server = make_server(engine, AuthMode::None, Some(Arc::clone(&sampler)));
+
+    let first: Value = server.get("/admin/runtime").await.json();
+    let second: Value = server.get("/admin/runtime").await.json();
+    assert_eq!(first, second);
+    assert_eq!(first["engine"]["backgroundByName"]["runtime.sampler"], 1);
+    sampler.stop();
+}
+
+#[tokio::test]
+async fn runtime_requires_task_manage_scope() {
+    let server = make_server(
+        make_engine(),
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+        None,
+    );
+
+    server
+        .get("/admin/runtime")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get("/admin/runtime")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .get("/admin/runtime")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+}
@@ -19,6 +19,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -38,7 +41,7 @@ const JWT_SECRET: &str = "test-secret-key-for-jwt-signing-needs-to-be-long-enoug
 fn make_jwt_server() -> (Arc<TaskEngine>, TestServer) {
     let engine = make_engine();
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -150,7 +153,9 @@ async fn get_task_returns_404_for_missing() {
     let response = server.get("/tasks/nonexistent").await;
     response.assert_status(axum_test::http::StatusCode::NOT_FOUND);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Task not found");
+    assert_eq!(body["code"], "TASK_NOT_FOUND");
+    assert_eq!(body["message"], "Task not found");
+    assert_eq!(body["details"]["taskId"], "nonexistent");
 }
 
 // ─── PATCH /tasks/:taskId/status ─────────────────────────────────────────────
@@ -194,7 +199,9 @@ async fn patch_task_status_returns_409_for_invalid_transition() {
 
     response.assert_status(axum_test::http::StatusCode::CONFLICT);
     let body: serde_json::Value = response.json();
-    assert!(body["error"].as_str().unwrap().contains("Invalid transition"));
+    assert_eq!(body["code"], "INVALID_TRANSITION");
+    assert!(body["message"].as_str().unwrap().contains("Invalid transition"));
+    assert_eq!(body["details"], json!({ "from": "pending", "to": "completed" }));
 }
 
 #[tokio::test]
@@ -448,7 +455,8 @@ async fn jwt_mode_returns_401_without_token() {
 
     response.assert_status(axum_test::http::StatusCode::UNAUTHORIZED);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Missing Bearer token");
+    assert_eq!(body["code"], "MISSING_TOKEN");
+    assert_eq!(body["message"], "Missing Bearer token");
 }
 
 #[tokio::test]
@@ -466,7 +474,8 @@ async fn jwt_mode_returns_401_with_invalid_token() {
 
     response.assert_status(axum_test::http::StatusCode::UNAUTHORIZED);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Invalid or expired token");
+    assert_eq!(body["code"], "INVALID_TOKEN");
+    assert_eq!(body["message"], "Invalid or expired token");
 }
 
 #[tokio::test]
@@ -512,7 +521,9 @@ async fn jwt_mode_returns_403_for_insufficient_scope() {
 
     response.assert_status(axum_test::http::StatusCode::FORBIDDEN);
     let body: serde_json::Value = response.json();
-    assert_eq!(body["error"], "Forbidden");
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert_eq!(body["message"], "Forbidden");
+    assert_eq!(body["details"]["requiredScope"], "task:create");
 }
 
 #[tokio::test]
@@ -959,6 +970,83 @@ fn app_error_engine_store_error_returns_500() {
     );
 }
 
+#[test]
+fn app_error_codes_are_stable_per_variant() {
+    let cases = [
+        (AppError::BadRequest("x".to_string()), "BAD_REQUEST"),
+        (
+            AppError::InvalidQuery {
+                param: "limit".to_string(),
+                reason: "x".to_string(),
+            },
+            "INVALID_QUERY",
+        ),
+        (AppError::NotFound("x".to_string()), "NOT_FOUND"),
+        (AppError::Forbidden, "FORBIDDEN"),
+        (AppError::MissingScope(taskcast_core::PermissionScope::TaskCreate), "FORBIDDEN"),
+        (AppError::MissingToken, "MISSING_TOKEN"),
+        (AppError::InvalidToken, "INVALID_TOKEN"),
+        (AppError::InvalidServiceKey, "INVALID_SERVICE_KEY"),
+        (AppError::InvalidApiKey, "INVALID_API_KEY"),
+        (AppError::InvalidAdminToken, "INVALID_ADMIN_TOKEN"),
+        (AppError::NotImplemented("x".to_string()), "NOT_IMPLEMENTED"),
+        (AppError::Internal("x".to_string()), "INTERNAL_ERROR"),
+        (
+            AppError::InsufficientStorage {
+                directory: "spool".to_string(),
+                used_bytes: 1,
+                max_bytes: 1,
+            },
+            "INSUFFICIENT_STORAGE",
+        ),
+        (AppError::Engine(EngineError::TaskNotFound("t1".to_string())), "TASK_NOT_FOUND"),
+        (AppError::Engine(EngineError::TaskConflict("t1".to_string())), "TASK_CONFLICT"),
+        (
+            AppError::Engine(EngineError::TaskAlreadyExists {
+                task_id: "t1".to_string(),
+                existing: None,
+            }),
+            "TASK_ALREADY_EXISTS",
+        ),
+        (AppError::Engine(EngineError::TaskTerminal(TaskStatus::Failed)), "TASK_TERMINAL"),
+        (AppError::Engine(EngineError::InvalidInput("x".to_string())), "INVALID_INPUT"),
+    ];
+    for (error, code) in cases {
+        assert_eq!(error.code(), code, "{error:?}");
+    }
+}
+
+#[test]
+fn app_error_details_carry_structured_data() {
+    assert_eq!(
+        AppError::Engine(EngineError::InvalidTransition {
+            from: TaskStatus::Pending,
+            to: TaskStatus::Completed,
+        })
+        .details(),
+        Some(json!({ "from": "pending", "to": "completed" }))
+    );
+    assert_eq!(
+        AppError::Engine(EngineError::TaskConflict("t1".to_string())).details(),
+        Some(json!({ "taskId": "t1" }))
+    );
+    assert_eq!(
+        AppError::Engine(EngineError::TaskTerminal(TaskStatus::Failed)).details(),
+        Some(json!({ "status": "failed" }))
+    );
+    assert_eq!(
+        AppError::MissingScope(taskcast_core::PermissionScope::EventPublish).details(),
+        Some(json!({ "requiredScope": "event:publish" }))
+    );
+    assert_eq!(
+        AppError::BadRequest("bad cursor".to_string()).details(),
+        Some(json!({ "errors": ["bad cursor"] }))
+    );
+    let store_err: Box<dyn std::error::Error + Send + Sync> = "db error".into();
+    assert_eq!(AppError::Engine(EngineError::Store(store_err)).details(), None);
+    assert_eq!(AppError::Forbidden.details(), None);
+}
+
 // ─── Webhook Delivery Tests ──────────────────────────────────────────────────
 
 #[tokio::test]
@@ -997,10 +1085,15 @@ async fn webhook_delivery_sends_to_mock_server() {
 
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     };
     let config = taskcast_core::WebhookConfig {
         url: format!("http://{addr}/hook"),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
         filter: None,
         secret: Some("test-secret".to_string()),
         wrap: None,
@@ -1010,7 +1103,14 @@ async fn webhook_delivery_sends_to_mock_server() {
             initial_delay_ms: 100,
             max_delay_ms: 100,
             timeout_ms: 5000,
+            jitter: taskcast_core::RetryJitter::None,
+            max_elapsed_ms: None,
         }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
     };
 
     let result = delivery.send(&event, &config).await;
@@ -1055,10 +1155,15 @@ async fn webhook_delivery_retries_on_failure() {
 
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     };
     let config = taskcast_core::WebhookConfig {
         url: format!("http://{addr}/hook"),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
         filter: None,
         secret: None,
         wrap: None,
@@ -1068,7 +1173,14 @@ async fn webhook_delivery_retries_on_failure() {
             initial_delay_ms: 10, // fast retries for test
             max_delay_ms: 10,
             timeout_ms: 5000,
+            jitter: taskcast_core::RetryJitter::None,
+            max_elapsed_ms: None,
         }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
     };
 
     let result = delivery.send(&event, &config).await;
@@ -1119,10 +1231,15 @@ async fn webhook_delivery_succeeds_on_retry() {
 
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     };
     let config = taskcast_core::WebhookConfig {
         url: format!("http://{addr}/hook"),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
         filter: None,
         secret: None,
         wrap: None,
@@ -1132,7 +1249,14 @@ async fn webhook_delivery_succeeds_on_retry() {
             initial_delay_ms: 10,
             max_delay_ms: 10,
             timeout_ms: 5000,
+            jitter: taskcast_core::RetryJitter::None,
+            max_elapsed_ms: None,
         }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
     };
 
     let result = delivery.send(&event, &config).await;
@@ -1145,7 +1269,7 @@ async fn webhook_delivery_succeeds_on_retry() {
 fn make_jwt_server_with_issuer(issuer: &str) -> (Arc<TaskEngine>, TestServer) {
     let engine = make_engine();
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: Some(issuer.to_string()),
@@ -1208,7 +1332,7 @@ async fn jwt_with_issuer_rejects_wrong_issuer() {
 fn make_jwt_server_with_audience(audience: &str) -> (Arc<TaskEngine>, TestServer) {
     let engine = make_engine();
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -1272,7 +1396,7 @@ async fn jwt_with_audience_rejects_wrong_audience() {
 async fn jwt_no_key_returns_401() {
     let engine = make_engine();
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: None,
         public_key: None,
         issuer: None,
@@ -1429,7 +1553,7 @@ async fn transition_task_returns_409_for_terminal_task() {
 
     response.assert_status(axum_test::http::StatusCode::CONFLICT);
     let body: serde_json::Value = response.json();
-    let error_msg = body["error"].as_str().unwrap();
+    let error_msg = body["message"].as_str().unwrap();
     assert!(
         error_msg.contains("terminal") || error_msg.contains("Terminal") || error_msg.contains("Invalid"),
         "Error message should indicate terminal state, got: {error_msg}"
@@ -1517,7 +1641,8 @@ async fn publish_events_returns_400_for_terminal_task() {
 
     response.assert_status(axum_test::http::StatusCode::BAD_REQUEST);
     let body: serde_json::Value = response.json();
-    let error_msg = body["error"].as_str().unwrap();
+    assert_eq!(body["code"], "BAD_REQUEST");
+    let error_msg = body["message"].as_str().unwrap();
     assert!(
         error_msg.contains("terminal") || error_msg.contains("Terminal"),
         "Error message should indicate terminal state, got: {error_msg}"
@@ -1607,14 +1732,24 @@ async fn webhook_uses_default_retry_when_none_provided() {
 
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     };
     let config = taskcast_core::WebhookConfig {
         url: format!("http://{addr}/hook"),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
         filter: None,
         secret: None,
         wrap: None,
-        retry: None, // No custom retry — should use default_retry()
+        retry: None, // No custom retry — should use default_retry(),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
     };
 
     let result = delivery.send(&event, &config).await;
@@ -1640,11 +1775,16 @@ async fn webhook_network_error_captures_error_string() {
 
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     };
     // Unreachable address — should trigger a network error (not an HTTP status error)
     let config = taskcast_core::WebhookConfig {
         url: "http://127.0.0.1:1/hook".to_string(),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
         filter: None,
         secret: None,
         wrap: None,
@@ -1654,7 +1794,14 @@ async fn webhook_network_error_captures_error_string() {
             initial_delay_ms: 10,
             max_delay_ms: 10,
             timeout_ms: 2000,
+            jitter: taskcast_core::RetryJitter::None,
+            max_elapsed_ms: None,
         }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
     };
 
     let result = delivery.send(&event, &config).await;
@@ -1763,6 +1910,9 @@ async fn sse_live_streaming_receives_events_and_done() {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1779,6 +1929,9 @@ async fn sse_live_streaming_receives_events_and_done() {
                     series_mode: None,
 
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1839,6 +1992,9 @@ fn make_worker_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestServer) {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -1867,6 +2023,9 @@ fn make_worker_ws_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestServer)
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -2367,6 +2526,15 @@ async fn ws_register_then_claim_task() {
             assign_mode: Some(taskcast_core::AssignMode::WsOffer),
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -2434,6 +2602,15 @@ async fn ws_claim_message_returns_claimed_response() {
             assign_mode: None,
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -2493,6 +2670,15 @@ async fn ws_claim_already_claimed_task_returns_success_false() {
             assign_mode: None,
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -2590,6 +2776,15 @@ async fn ws_decline_after_register() {
             assign_mode: None,
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -2648,6 +2843,15 @@ async fn ws_decline_with_blacklist_flag() {
             assign_mode: None,
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -2821,6 +3025,15 @@ async fn ws_accept_pending_task_returns_assigned() {
             assign_mode: None,
             cost: None,
             disconnect_policy: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
         })
         .await
         .unwrap();
@@ -3022,6 +3235,9 @@ fn make_jwt_worker_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestServer)
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -3032,7 +3248,7 @@ fn make_jwt_worker_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestServer)
         defaults: None,
     }));
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -3622,6 +3838,37 @@ async fn list_tasks_filter_no_match_returns_empty() {
     assert_eq!(body["tasks"].as_array().unwrap().len(), 0);
 }
 
+#[tokio::test]
+async fn scheduled_task_is_listed_by_status_and_can_be_cancelled() {
+    let (_engine, server) = make_no_auth_server();
+
+    let response = server
+        .post("/tasks")
+        .json(&json!({ "id": "later", "scheduledFor": 4_102_444_800_000.0_f64 }))
+        .await;
+    response.assert_status(axum_test::http::StatusCode::CREATED);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["status"], "scheduled");
+    assert_eq!(body["scheduledFor"], 4_102_444_800_000.0_f64);
+
+    let body: serde_json::Value = server.get("/tasks?status=scheduled").await.json();
+    let tasks = body["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["id"], "later");
+
+    server
+        .patch("/tasks/later/status")
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status(axum_test::http::StatusCode::CONFLICT);
+    let response = server
+        .patch("/tasks/later/status")
+        .json(&json!({ "status": "cancelled" }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<serde_json::Value>()["status"], "cancelled");
+}
+
 // ─── Sequential double-complete (HTTP layer) ───────────────────────────────
 
 #[tokio::test]
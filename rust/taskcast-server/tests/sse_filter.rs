@@ -7,6 +7,7 @@
 //! - Lines 266, 268: Terminal status detection in the subscription callback
 //!   triggering the done signal and closing the stream
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde_json::json;
@@ -24,6 +25,9 @@ fn make_sse_app() -> (Arc<TaskEngine>, axum::Router) {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (router, _) = create_app(
         Arc::clone(&engine),
@@ -103,6 +107,9 @@ async fn sse_filter_excludes_non_matching_events() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -121,6 +128,9 @@ async fn sse_filter_excludes_non_matching_events() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -340,6 +350,9 @@ async fn sse_limit_caps_history_replay() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -449,6 +462,9 @@ async fn sse_since_id_constructs_cursor_and_filters_history() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -528,3 +544,121 @@ async fn sse_since_id_constructs_cursor_and_filters_history() {
         "should have taskcast.done event. Got:\n{body}"
     );
 }
+
+// =============================================================================
+// 5. SSE label selector filters history and live events
+// =============================================================================
+
+async fn publish_labeled(engine: &TaskEngine, task_id: &str, msg: &str, region: &str) {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: "log".to_string(),
+                level: Level::Info,
+                data: json!({ "msg": msg }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: Some(HashMap::from([
+                    ("region".to_string(), region.to_string()),
+                    ("worker".to_string(), "w-42".to_string()),
+                ])),
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn sse_label_selector_filters_history_and_live_events() {
+    let (engine, app) = make_sse_app();
+    let addr = serve_app(app).await;
+    let client = reqwest::Client::new();
+
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("labels-sse".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("labels-sse", TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    // History: one matching, one not
+    publish_labeled(&engine, "labels-sse", "history-eu", "eu").await;
+    publish_labeled(&engine, "labels-sse", "history-us", "us").await;
+
+    let engine_clone = Arc::clone(&engine);
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        publish_labeled(&engine_clone, "labels-sse", "live-us", "us").await;
+        publish_labeled(&engine_clone, "labels-sse", "live-eu", "eu").await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        engine_clone
+            .transition_task("labels-sse", TaskStatus::Completed, None)
+            .await
+            .unwrap();
+    });
+
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client
+            .get(format!(
+                "http://{addr}/tasks/labels-sse/events?labels=region:eu,worker:w-42"
+            ))
+            .header("Accept", "text/event-stream")
+            .send(),
+    )
+    .await
+    .expect("SSE connect timed out")
+    .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let body = tokio::time::timeout(std::time::Duration::from_secs(5), response.text())
+        .await
+        .expect("SSE stream timed out")
+        .unwrap();
+
+    let messages: Vec<String> = parse_sse_events(&body)
+        .into_iter()
+        .filter(|(name, data)| name == "taskcast.event" && data["type"] == "log")
+        .map(|(_, data)| {
+            assert_eq!(data["labels"]["region"], "eu");
+            data["data"]["msg"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(messages, vec!["history-eu", "live-eu"]);
+
+    // Status events bypass the selector, so the stream still closes
+    assert!(
+        body.contains("taskcast.done"),
+        "should have taskcast.done event. Got:\n{body}"
+    );
+}
+
+#[tokio::test]
+async fn sse_malformed_label_selector_returns_400() {
+    let (engine, app) = make_sse_app();
+    let addr = serve_app(app).await;
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("labels-bad".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let response = reqwest::Client::new()
+        .get(format!("http://{addr}/tasks/labels-bad/events?labels=region"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
@@ -0,0 +1,153 @@
+//! Integration tests for `sse.heartbeatIntervalMs`: keepalive comments on
+//! quiet SSE streams.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use taskcast_core::config::{SseConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+const HEARTBEAT: &str = ": keepalive\n\n";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// Serves an engine holding one running task, `t1`.
+async fn serve(heartbeat_interval_ms: u64) -> (Arc<TaskEngine>, std::net::SocketAddr) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let config = TaskcastConfig {
+        sse: Some(SseConfig {
+            heartbeat_interval_ms: Some(heartbeat_interval_ms),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (engine, addr)
+}
+
+async fn log(engine: &TaskEngine, n: u64) {
+    engine
+        .publish_event(
+            "t1",
+            PublishEventInput {
+                r#type: "log".to_string(),
+                level: Level::Info,
+                data: json!({ "n": n }),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+/// The next chunk of the stream, or `None` once it has ended.
+async fn next_chunk(response: &mut reqwest::Response, within: Duration) -> Option<String> {
+    tokio::time::timeout(within, response.chunk())
+        .await
+        .expect("no frame in time")
+        .unwrap()
+        .map(|chunk| String::from_utf8_lossy(&chunk).into_owned())
+}
+
+/// Reads chunks until one contains `marker`, returning everything read.
+async fn read_until(response: &mut reqwest::Response, marker: &str, within: Duration) -> String {
+    let deadline = tokio::time::Instant::now() + within;
+    let mut text = String::new();
+    while !text.contains(marker) {
+        let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+        match next_chunk(response, left).await {
+            Some(chunk) => text.push_str(&chunk),
+            None => panic!("stream ended before {marker:?}: {text:?}"),
+        }
+    }
+    text
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn quiet_stream_gets_a_keepalive_within_twice_the_interval() {
+    let (_engine, addr) = serve(200).await;
+    let mut response = reqwest::get(format!("http://{addr}/tasks/t1/events"))
+        .await
+        .unwrap();
+    read_until(&mut response, "taskcast.init", Duration::from_secs(5)).await;
+
+    read_until(&mut response, HEARTBEAT, Duration::from_millis(400)).await;
+}
+
+#[tokio::test]
+async fn keepalives_leave_indices_alone_and_stop_after_done() {
+    let (engine, addr) = serve(100).await;
+    let mut response = reqwest::get(format!("http://{addr}/tasks/t1/events"))
+        .await
+        .unwrap();
+    let mut text = read_until(&mut response, HEARTBEAT, Duration::from_secs(5)).await;
+
+    log(&engine, 0).await;
+    text.push_str(&read_until(&mut response, "\"n\":0", Duration::from_secs(5)).await);
+    text.push_str(&read_until(&mut response, HEARTBEAT, Duration::from_secs(5)).await);
+    log(&engine, 1).await;
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    while let Some(chunk) = next_chunk(&mut response, Duration::from_secs(5)).await {
+        text.push_str(&chunk);
+    }
+
+    // Heartbeats between events leave no gaps in the numbering. Status
+    // events may arrive ahead of data events, so order doesn't matter.
+    let mut indices: Vec<u64> = text
+        .split("\n\n")
+        .filter(|frame| frame.contains("event: taskcast.event"))
+        .filter_map(|frame| frame.lines().find_map(|l| l.strip_prefix("data: ")))
+        .map(|data| serde_json::from_str::<serde_json::Value>(data).unwrap())
+        .map(|envelope| envelope["filteredIndex"].as_u64().unwrap())
+        .collect();
+    indices.sort_unstable();
+    assert!(indices.len() >= 3, "{text:?}");
+    assert!(indices.windows(2).all(|w| w[1] == w[0] + 1), "{indices:?}");
+    let done = text.rfind("event: taskcast.done").expect("no done frame");
+    assert!(!text[done..].contains(HEARTBEAT));
+}
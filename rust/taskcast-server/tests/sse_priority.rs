@@ -0,0 +1,145 @@
+//! Integration tests for priority delivery on the task SSE endpoint: a
+//! status change is not held behind data a slow client has yet to read.
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use futures::StreamExt;
+use http::Request;
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+use tower::ServiceExt;
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+const LOGS: usize = 10_000;
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+/// Opens `uri` on a running task and reads the replayed `running` status, so
+/// the replay is over. Then floods the task with log events and completes it
+/// before reading on, and returns the (event type, data) frames in the order
+/// they arrive.
+async fn flooded_frames(uri: &str) -> Vec<(String, serde_json::Value)> {
+    let engine = make_engine();
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task("t1", TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let response = app
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let mut body = response.into_body().into_data_stream();
+    let mut text = String::new();
+    let first = body.next().await.unwrap().unwrap();
+    text.push_str(std::str::from_utf8(&first).unwrap());
+
+    for i in 0..LOGS {
+        engine
+            .publish_event(
+                "t1",
+                PublishEventInput {
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: json!({ "n": i }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    engine
+        .transition_task("t1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    while let Some(chunk) = body.next().await {
+        text.push_str(std::str::from_utf8(&chunk.unwrap()).unwrap());
+    }
+    let mut frames = Vec::new();
+    let mut event = String::new();
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix("event: ") {
+            event = name.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            frames.push((event.clone(), serde_json::from_str(data).unwrap()));
+        }
+    }
+    frames
+}
+
+/// Position of the frame carrying the task's completion.
+fn completion_position(frames: &[(String, serde_json::Value)]) -> usize {
+    frames
+        .iter()
+        .position(|(_, data)| {
+            data["type"] == "taskcast:status" && data["data"]["status"] == "completed"
+        })
+        .expect("no completion frame")
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn completion_overtakes_a_data_flood() {
+    let frames = flooded_frames("/tasks/t1/events").await;
+
+    let position = completion_position(&frames);
+    assert!(position < 32, "completion arrived as frame {position}");
+    assert_eq!(frames[position].1["filteredIndex"], LOGS + 1);
+
+    // Data keeps store order around it, and the done frame still closes.
+    let logs: Vec<u64> = frames
+        .iter()
+        .filter(|(_, data)| data["type"] == "log")
+        .map(|(_, data)| data["rawIndex"].as_u64().unwrap())
+        .collect();
+    assert_eq!(logs, (1..=LOGS as u64).collect::<Vec<_>>());
+    assert_eq!(frames.last().unwrap().0, "taskcast.done");
+}
+
+#[tokio::test]
+async fn checksummed_streams_keep_store_order() {
+    let frames = flooded_frames("/tasks/t1/events?checksum=true").await;
+
+    // Frame 0 is `taskcast.init`; the events follow in store order.
+    assert_eq!(frames[0].0, "taskcast.init");
+    assert_eq!(completion_position(&frames), LOGS + 2);
+    let done = &frames.last().unwrap().1;
+    assert_eq!(done["checksum"]["count"], LOGS + 2);
+    assert_eq!(done["checksum"]["lastIndex"], LOGS + 1);
+}
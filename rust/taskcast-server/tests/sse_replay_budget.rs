@@ -0,0 +1,216 @@
+//! Integration tests for the SSE replay budget: `sse.maxReplayEvents`,
+//! `sse.maxReplayBytes` and the `fullReplay` override.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use taskcast_core::config::{SseConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+async fn serve(sse: Option<SseConfig>) -> (Arc<TaskEngine>, std::net::SocketAddr) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        sse,
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (engine, addr)
+}
+
+/// A completed task with `logs` log events between its status events.
+async fn completed_task(engine: &TaskEngine, task_id: &str, logs: usize) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(task_id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(task_id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    for i in 0..logs {
+        engine
+            .publish_event(
+                task_id,
+                PublishEventInput {
+                    r#type: "log".to_string(),
+                    level: Level::Info,
+                    data: json!({ "n": i }),
+                    series_id: None,
+                    series_mode: None,
+                    series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
+                },
+            )
+            .await
+            .unwrap();
+    }
+    engine
+        .transition_task(task_id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+}
+
+/// The (event type, data) frames of a stream that closes by itself, after
+/// its `taskcast.init` frame.
+async fn frames(url: String) -> Vec<(String, serde_json::Value)> {
+    let body = tokio::time::timeout(Duration::from_secs(10), async {
+        reqwest::get(url).await.unwrap().text().await.unwrap()
+    })
+    .await
+    .expect("stream did not close");
+    let mut frames = Vec::new();
+    let mut event = String::new();
+    for line in body.lines() {
+        if let Some(name) = line.strip_prefix("event: ") {
+            event = name.to_string();
+        } else if let Some(data) = line.strip_prefix("data: ") {
+            frames.push((event.clone(), serde_json::from_str(data).unwrap()));
+        }
+    }
+    assert_eq!(frames.remove(0).0, "taskcast.init");
+    frames
+}
+
+fn event_indices(frames: &[(String, serde_json::Value)]) -> Vec<u64> {
+    frames
+        .iter()
+        .filter(|(event, _)| event == "taskcast.event")
+        .map(|(_, data)| data["rawIndex"].as_u64().unwrap())
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn over_budget_history_is_truncated_to_its_tail() {
+    let (engine, addr) = serve(Some(SseConfig {
+        max_replay_events: Some(10),
+        max_replay_bytes: None,
+        heartbeat_interval_ms: None,
+    }))
+    .await;
+    completed_task(&engine, "t1", 2_500).await;
+
+    let frames = frames(format!("http://{addr}/tasks/t1/events?types=log")).await;
+
+    assert_eq!(frames[0].0, "taskcast.truncated");
+    assert_eq!(
+        frames[0].1,
+        json!({
+            "omittedCount": 2_490,
+            "earliestSentIndex": 2_491,
+            "hint": "use /events/history with pagination",
+        })
+    );
+    assert_eq!(event_indices(&frames), (2_491..=2_500).collect::<Vec<_>>());
+    let filtered: Vec<u64> = frames[1..11]
+        .iter()
+        .map(|(_, data)| data["filteredIndex"].as_u64().unwrap())
+        .collect();
+    assert_eq!(filtered, (2_490..2_500).collect::<Vec<_>>());
+    assert_eq!(frames.last().unwrap().0, "taskcast.done");
+}
+
+#[tokio::test]
+async fn byte_budget_truncates_too() {
+    let (engine, addr) = serve(Some(SseConfig {
+        max_replay_events: None,
+        max_replay_bytes: Some(1_000),
+        heartbeat_interval_ms: None,
+    }))
+    .await;
+    completed_task(&engine, "t1", 100).await;
+
+    let frames = frames(format!("http://{addr}/tasks/t1/events")).await;
+
+    assert_eq!(frames[0].0, "taskcast.truncated");
+    let sent = event_indices(&frames);
+    assert!(!sent.is_empty() && sent.len() < 20, "sent {}", sent.len());
+    assert_eq!(sent.last(), Some(&101));
+    assert_eq!(frames[0].1["earliestSentIndex"], sent[0]);
+    assert_eq!(
+        frames[0].1["omittedCount"].as_u64().unwrap() + sent.len() as u64,
+        102
+    );
+}
+
+#[tokio::test]
+async fn default_budget_replays_ten_thousand_events() {
+    let (engine, addr) = serve(None).await;
+    completed_task(&engine, "t1", 10_000).await;
+
+    let frames = frames(format!("http://{addr}/tasks/t1/events")).await;
+
+    assert_eq!(frames[0].1["omittedCount"], 2);
+    assert_eq!(event_indices(&frames).len(), 10_000);
+}
+
+#[tokio::test]
+async fn full_replay_delivers_every_event() {
+    let (engine, addr) = serve(Some(SseConfig {
+        max_replay_events: Some(10),
+        max_replay_bytes: None,
+        heartbeat_interval_ms: None,
+    }))
+    .await;
+    completed_task(&engine, "t1", 2_500).await;
+
+    let frames = frames(format!("http://{addr}/tasks/t1/events?fullReplay=true")).await;
+
+    assert!(frames
+        .iter()
+        .all(|(event, _)| event != "taskcast.truncated"));
+    assert_eq!(event_indices(&frames), (0..=2_501).collect::<Vec<_>>());
+    assert_eq!(frames.last().unwrap().0, "taskcast.done");
+}
+
+#[tokio::test]
+async fn history_within_budget_has_no_truncated_frame() {
+    let (engine, addr) = serve(None).await;
+    completed_task(&engine, "t1", 3).await;
+
+    let frames = frames(format!("http://{addr}/tasks/t1/events?wrap=false")).await;
+
+    assert!(frames
+        .iter()
+        .all(|(event, _)| event != "taskcast.truncated"));
+    assert_eq!(
+        frames
+            .iter()
+            .filter(|(event, _)| event == "taskcast.event")
+            .map(|(_, data)| data["index"].as_u64().unwrap())
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2, 3, 4]
+    );
+}
@@ -22,6 +22,9 @@ fn make_app() -> (Arc<TaskEngine>, axum::Router) {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (router, _ws_registry) = create_app(
         Arc::clone(&engine),
@@ -73,6 +76,9 @@ async fn publish_accumulate_event(
                 series_id: Some(series_id.to_string()),
                 series_mode: Some(taskcast_core::SeriesMode::Accumulate),
                 series_acc_field: Some("text".to_string()),
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -264,6 +270,9 @@ async fn mixed_events_only_accumulate_collapsed() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -284,6 +293,9 @@ async fn mixed_events_only_accumulate_collapsed() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1361,6 +1373,9 @@ async fn multiple_series_with_non_series_events_preserved() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -1802,6 +1817,9 @@ async fn non_series_events_unaffected_by_series_format() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1821,6 +1839,9 @@ async fn non_series_events_unaffected_by_series_format() {
                     series_id: None,
                     series_mode: None,
                     series_acc_field: None,
+                    labels: None,
+                    broadcast_debounce_ms: None,
+                    series_end: false,
                 },
             )
             .await
@@ -1920,3 +1941,87 @@ async fn non_series_events_unaffected_by_series_format() {
         "Status event data should be unchanged"
     );
 }
+
+// =============================================================================
+// 22. Live latest-mode rewrite is marked with replacesEventId, wrapped or not
+// =============================================================================
+
+async fn publish_latest_event(engine: &TaskEngine, task_id: &str, v: u64) -> String {
+    engine
+        .publish_event(
+            task_id,
+            taskcast_core::PublishEventInput {
+                r#type: "progress".to_string(),
+                level: Level::Info,
+                data: json!({ "v": v }),
+                series_id: Some("p".to_string()),
+                series_mode: Some(taskcast_core::SeriesMode::Latest),
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .expect("publish_event failed")
+        .id
+}
+
+#[tokio::test]
+async fn live_latest_rewrite_carries_replaces_event_id() {
+    for wrap in [true, false] {
+        let (engine, app) = make_app();
+        let addr = serve_app(app).await;
+        let task_id = format!("sf-latest-22-{wrap}");
+        create_running_task(&engine, &task_id).await;
+
+        let engine_clone = Arc::clone(&engine);
+        let publisher_task_id = task_id.clone();
+        let publisher = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            let first = publish_latest_event(&engine_clone, &publisher_task_id, 1).await;
+            publish_latest_event(&engine_clone, &publisher_task_id, 2).await;
+            engine_clone
+                .transition_task(&publisher_task_id, TaskStatus::Completed, None)
+                .await
+                .unwrap();
+            first
+        });
+
+        let text = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            reqwest::get(format!("http://{addr}/tasks/{task_id}/events?wrap={wrap}"))
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap()
+        })
+        .await
+        .expect("SSE stream timed out");
+        let first_id = publisher.await.unwrap();
+
+        let updates: Vec<_> = parse_sse_events(&text)
+            .into_iter()
+            .filter(|(t, v)| t == "taskcast.event" && v["type"] == "progress")
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(updates.len(), 2, "wrap={wrap}: {text}");
+        assert!(updates[0].get("replacesEventId").is_none(), "wrap={wrap}");
+        assert_eq!(updates[1]["replacesEventId"], first_id.as_str(), "wrap={wrap}");
+        assert_eq!(updates[1]["data"]["v"], 2, "wrap={wrap}");
+
+        // A late joiner replays history, where the rewrite is just the event.
+        let replay = reqwest::get(format!("http://{addr}/tasks/{task_id}/events?wrap={wrap}"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        let replayed: Vec<_> = parse_sse_events(&replay)
+            .into_iter()
+            .filter(|(t, v)| t == "taskcast.event" && v["type"] == "progress")
+            .collect();
+        assert_eq!(replayed.len(), 1, "wrap={wrap}");
+        assert!(replayed[0].1.get("replacesEventId").is_none(), "wrap={wrap}");
+    }
+}
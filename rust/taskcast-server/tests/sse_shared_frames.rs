@@ -0,0 +1,190 @@
+//! Integration tests for live event payloads shared between the SSE
+//! connections of a task: every connection must receive what it would have
+//! serialized on its own.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    SeriesMode, TaskEngine, TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+async fn serve() -> (Arc<TaskEngine>, std::net::SocketAddr) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (engine, addr)
+}
+
+/// An open SSE connection and the text it has received so far.
+struct Connection {
+    response: reqwest::Response,
+    text: String,
+}
+
+impl Connection {
+    /// Connects and waits for the init frame, so the connection is
+    /// subscribed before anything is published.
+    async fn open(addr: std::net::SocketAddr, task_id: &str, query: &str) -> Self {
+        let response = reqwest::get(format!("http://{addr}/tasks/{task_id}/events?{query}"))
+            .await
+            .unwrap();
+        let mut connection = Self {
+            response,
+            text: String::new(),
+        };
+        connection.read_until("taskcast.init").await;
+        connection
+    }
+
+    async fn read_until(&mut self, marker: &str) {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !self.text.contains(marker) {
+            let chunk = tokio::time::timeout_at(deadline, self.response.chunk())
+                .await
+                .unwrap_or_else(|_| panic!("no {marker} frame in {:?}", self.text))
+                .unwrap()
+                .unwrap_or_else(|| panic!("stream ended before {marker}"));
+            self.text.push_str(&String::from_utf8_lossy(&chunk));
+        }
+    }
+
+    /// Reads to the done frame and returns each event frame's data, by id.
+    async fn events(mut self) -> BTreeMap<String, String> {
+        self.read_until("taskcast.done").await;
+        let mut events = BTreeMap::new();
+        for frame in self.text.split("\n\n") {
+            let mut data = None;
+            let mut id = None;
+            let mut is_event = false;
+            for line in frame.lines() {
+                if line == "event: taskcast.event" {
+                    is_event = true;
+                } else if let Some(value) = line.strip_prefix("data: ") {
+                    data = Some(value.to_string());
+                } else if let Some(value) = line.strip_prefix("id: ") {
+                    id = Some(value.to_string());
+                }
+            }
+            if is_event {
+                events.insert(id.unwrap(), data.unwrap());
+            }
+        }
+        events
+    }
+}
+
+fn input(r#type: &str, data: serde_json::Value) -> PublishEventInput {
+    PublishEventInput {
+        r#type: r#type.to_string(),
+        level: Level::Info,
+        data,
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        labels: None,
+        broadcast_debounce_ms: None,
+        series_end: false,
+    }
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+/// For each filter, two connections share payloads and a checksum
+/// connection serializes its own; all three must receive the same bytes.
+#[tokio::test]
+async fn shared_payloads_match_per_connection_serialization() {
+    let (engine, addr) = serve().await;
+    let task = engine
+        .create_task(CreateTaskInput::default())
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+
+    let queries = [
+        "",
+        "types=log",
+        "levels=warn",
+        "wrap=false",
+        "seriesFormat=accumulated",
+        "seriesFormat=delta&types=llm.*",
+        "includeStatus=false&wrap=false",
+    ];
+    let mut connections = Vec::new();
+    for query in queries {
+        let shared = [
+            Connection::open(addr, &task.id, query).await,
+            Connection::open(addr, &task.id, query).await,
+        ];
+        let own = Connection::open(addr, &task.id, &format!("{query}&checksum=true")).await;
+        connections.push((query, shared, own));
+    }
+
+    for n in 0..4 {
+        engine
+            .publish_event(&task.id, input("log", json!({ "line": n })))
+            .await
+            .unwrap();
+        engine
+            .publish_event(
+                &task.id,
+                PublishEventInput {
+                    level: Level::Warn,
+                    ..input("alert", json!({ "filteredIndex": n }))
+                },
+            )
+            .await
+            .unwrap();
+        engine
+            .publish_event(
+                &task.id,
+                PublishEventInput {
+                    series_id: Some("out".to_string()),
+                    series_mode: Some(SeriesMode::Accumulate),
+                    series_acc_field: Some("text".to_string()),
+                    ..input("llm.delta", json!({ "text": format!("part {n} ") }))
+                },
+            )
+            .await
+            .unwrap();
+    }
+    engine
+        .transition_task(&task.id, TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    for (query, shared, own) in connections {
+        let expected = own.events().await;
+        assert!(!expected.is_empty(), "{query:?} received no events");
+        for connection in shared {
+            assert_eq!(connection.events().await, expected, "query {query:?}");
+        }
+    }
+}
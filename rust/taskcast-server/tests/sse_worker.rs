@@ -28,6 +28,9 @@ fn make_sse_worker_app() -> (Arc<TaskEngine>, Arc<WorkerManager>, axum::Router)
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -101,6 +104,9 @@ async fn publish_event(engine: &TaskEngine, task_id: &str, event_type: &str, dat
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -0,0 +1,158 @@
+//! Integration tests for standby mode: `server.role: standby` and
+//! `POST /admin/promote`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{ServerConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, ServerRole, TaskEngine,
+    TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "standby-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn standby_config() -> TaskcastConfig {
+    TaskcastConfig {
+        server: Some(ServerConfig {
+            role: Some(ServerRole::Standby),
+            leader_election: None,
+        }),
+        ..Default::default()
+    }
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth_mode: AuthMode) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        auth_mode,
+        None,
+        Some(standby_config()),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "standby-test", "scope": scope, "taskIds": "*", "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+// ─── Standby ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn standby_refuses_writes_and_serves_reads() {
+    let engine = make_engine();
+    let server = make_server(&engine, AuthMode::None);
+    engine
+        .create_task(CreateTaskInput {
+            id: Some("t1".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let res = server.post("/tasks").json(&json!({ "id": "t2" })).await;
+    res.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.header(header::ALLOW), "GET, HEAD, OPTIONS");
+    assert_eq!(res.json::<Value>()["code"], "READ_ONLY");
+    server
+        .patch("/tasks/t1/status")
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    server
+        .post("/v1/tasks/t1/events")
+        .json(&json!({ "type": "log", "level": "info", "data": {} }))
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+
+    server.get("/tasks/t1").await.assert_status_ok();
+    server
+        .get("/tasks/t1/events/history")
+        .await
+        .assert_status_ok();
+    let health: Value = server.get("/health/detail").await.json();
+    assert_eq!(health["role"], "standby");
+    assert!(engine.runners().is_held());
+}
+
+#[tokio::test]
+async fn promotion_enables_writes_and_runners() {
+    let engine = make_engine();
+    let server = make_server(&engine, AuthMode::None);
+
+    let res = server.post("/admin/promote").await;
+    res.assert_status_ok();
+    assert_eq!(
+        res.json::<Value>(),
+        json!({ "role": "active", "promoted": true })
+    );
+    assert!(!engine.runners().is_held());
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t1" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    let health: Value = server.get("/health/detail").await.json();
+    assert_eq!(health["role"], "active");
+
+    // Promoting an active instance changes nothing.
+    let res: Value = server.post("/v1/admin/promote").await.json();
+    assert_eq!(res["promoted"], false);
+}
+
+#[tokio::test]
+async fn promotion_requires_task_manage() {
+    let engine = make_engine();
+    let server = make_server(
+        &engine,
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+    );
+
+    let res = server
+        .post("/admin/promote")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"]))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    server
+        .post("/admin/promote")
+        .await
+        .assert_status_unauthorized();
+
+    server
+        .post("/admin/promote")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+    assert!(!engine.runners().is_held());
+}
@@ -0,0 +1,221 @@
+//! Integration tests for `GET /admin/storage` and `POST /tasks/{id}/archive`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::{
+    DirectoryLimits, MemoryBroadcastProvider, MemoryShortTermStore, StorageManager,
+    StorageManagerOptions, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{
+    create_app, create_app_with_options, AppOptions, AuthMode, CorsConfig, JwtConfig, LogLevel,
+    StderrHttpFailureLogger,
+};
+
+const JWT_SECRET: &str = "storage-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_storage(root: &std::path::Path, exports_max_bytes: Option<u64>) -> Arc<StorageManager> {
+    let storage = StorageManager::new(StorageManagerOptions::default());
+    storage
+        .register(
+            "spool",
+            root.join("spool"),
+            DirectoryLimits {
+                max_bytes: Some(1000),
+                max_age: Some(Duration::from_secs(3600)),
+            },
+        )
+        .unwrap();
+    storage
+        .register(
+            "exports",
+            root.join("exports"),
+            DirectoryLimits {
+                max_bytes: exports_max_bytes,
+                max_age: None,
+            },
+        )
+        .unwrap();
+    Arc::new(storage)
+}
+
+fn make_server(auth_mode: AuthMode, storage: Arc<StorageManager>) -> TestServer {
+    let (app, _) = create_app_with_options(
+        make_engine(),
+        auth_mode,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            storage: Some(storage),
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+fn make_token(scope: &[&str]) -> String {
+    encode(
+        &Header::default(),
+        &json!({
+            "sub": "storage-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    HeaderValue::from_str(&format!("Bearer {}", make_token(scope))).unwrap()
+}
+
+async fn create_task(server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": task_id, "params": { "prompt": "hi" } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn storage_usage_reports_each_directory() {
+    let tmp = tempfile::tempdir().unwrap();
+    let storage = make_storage(tmp.path(), None);
+    std::fs::write(tmp.path().join("spool").join("a"), [0u8; 250]).unwrap();
+    let server = make_server(AuthMode::None, storage);
+
+    let res = server.get("/admin/storage").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    let directories = body["directories"].as_array().unwrap();
+    assert_eq!(directories.len(), 2);
+
+    let exports = &directories[0];
+    assert_eq!(exports["name"], "exports");
+    assert_eq!(exports["bytes"], 0);
+    assert_eq!(exports["files"], 0);
+    assert!(exports["maxBytes"].is_null());
+    assert!(exports["utilization"].is_null());
+
+    let spool = &directories[1];
+    assert_eq!(spool["name"], "spool");
+    assert_eq!(spool["bytes"], 250);
+    assert_eq!(spool["files"], 1);
+    assert_eq!(spool["maxBytes"], 1000);
+    assert_eq!(spool["maxAgeMs"], 3_600_000);
+    assert_eq!(spool["utilization"], 0.25);
+    assert_eq!(spool["oldestModifiedAt"], spool["newestModifiedAt"]);
+}
+
+#[tokio::test]
+async fn storage_usage_requires_task_manage_scope() {
+    let tmp = tempfile::tempdir().unwrap();
+    let server = make_server(
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+        make_storage(tmp.path(), None),
+    );
+
+    let res = server
+        .get("/admin/storage")
+        .add_header(header::AUTHORIZATION, bearer(&["event:history"]))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        res.json::<serde_json::Value>()["details"]["requiredScope"],
+        "task:manage"
+    );
+
+    server
+        .get("/admin/storage")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn save_archive_writes_to_exports() {
+    let tmp = tempfile::tempdir().unwrap();
+    let server = make_server(AuthMode::None, make_storage(tmp.path(), None));
+    create_task(&server, "t1").await;
+
+    let res = server.post("/tasks/t1/archive").await;
+    res.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = res.json();
+    let file = body["file"].as_str().unwrap();
+    assert!(file.starts_with("t1.") && file.ends_with(".json"), "{file}");
+
+    let saved = std::fs::read(tmp.path().join("exports").join(file)).unwrap();
+    assert_eq!(body["bytes"], saved.len());
+    let archive: serde_json::Value = serde_json::from_slice(&saved).unwrap();
+    assert_eq!(archive["task"]["id"], "t1");
+}
+
+#[tokio::test]
+async fn save_archive_returns_507_when_exports_is_full() {
+    let tmp = tempfile::tempdir().unwrap();
+    let server = make_server(AuthMode::None, make_storage(tmp.path(), Some(10)));
+    create_task(&server, "t1").await;
+
+    let res = server.post("/tasks/t1/archive").await;
+    res.assert_status(StatusCode::INSUFFICIENT_STORAGE);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "INSUFFICIENT_STORAGE");
+    assert_eq!(body["details"]["directory"], "exports");
+    assert_eq!(body["details"]["usedBytes"], 0);
+    assert_eq!(body["details"]["maxBytes"], 10);
+    assert_eq!(
+        std::fs::read_dir(tmp.path().join("exports"))
+            .unwrap()
+            .count(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn storage_routes_absent_without_storage_manager() {
+    let (app, _) = create_app(
+        make_engine(),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let server = TestServer::new(app);
+    create_task(&server, "t1").await;
+
+    server
+        .get("/admin/storage")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .post("/tasks/t1/archive")
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+}
@@ -0,0 +1,158 @@
+//! Integration tests for `GET /admin/store/explain/{task_id}` and the
+//! long-term store's query stats in `GET /admin/runtime`.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::Value;
+use taskcast_core::{
+    EventQueryOptions, LongTermStore, MemoryBroadcastProvider, MemoryShortTermStore, QueryStats,
+    QueryStatsSnapshot, Task, TaskEngine, TaskEngineOptions, TaskEvent, WorkerAuditEvent,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// Long-term store that explains its reads with the options it was given.
+#[derive(Default)]
+struct ExplainingLongTermStore {
+    explained: Mutex<Vec<(String, Option<EventQueryOptions>)>>,
+}
+
+#[async_trait::async_trait]
+impl LongTermStore for ExplainingLongTermStore {
+    async fn save_task(&self, _task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_task(
+        &self,
+        _task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    async fn save_event(
+        &self,
+        _event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_events(
+        &self,
+        _task_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_worker_event(
+        &self,
+        _event: WorkerAuditEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn get_worker_events(
+        &self,
+        _worker_id: &str,
+        _opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<WorkerAuditEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    fn query_stats(&self) -> Option<QueryStatsSnapshot> {
+        let stats = QueryStats::new("test");
+        stats.record("get_task", Some("t1"), Some(1), Duration::from_millis(3));
+        Some(stats.snapshot())
+    }
+
+    async fn explain_get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.explained
+            .lock()
+            .unwrap()
+            .push((task_id.to_string(), opts));
+        Ok(Some(
+            "Index Scan using taskcast_events_task_id_idx".to_string(),
+        ))
+    }
+}
+
+fn make_server(long_term_store: Option<Arc<dyn LongTermStore>>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+// ─── Explain ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn explain_passes_the_read_options_to_the_store() {
+    let store = Arc::new(ExplainingLongTermStore::default());
+    let server = make_server(Some(store.clone()));
+
+    let res = server
+        .get("/admin/store/explain/t1?since.index=4&limit=10&labels=region:eu&until=5000")
+        .await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["taskId"], "t1");
+    assert!(body["plan"]
+        .as_str()
+        .unwrap()
+        .contains("taskcast_events_task_id_idx"));
+
+    let explained = store.explained.lock().unwrap();
+    let (task_id, opts) = &explained[0];
+    assert_eq!(task_id, "t1");
+    let opts = opts.as_ref().unwrap();
+    assert_eq!(opts.since.as_ref().unwrap().index, Some(4));
+    assert_eq!(opts.limit, Some(10));
+    assert_eq!(opts.label_selector.as_ref().unwrap()["region"], "eu");
+    assert_eq!(opts.until, Some(5000.0));
+}
+
+#[tokio::test]
+async fn explain_refuses_parameters_the_store_cannot_take() {
+    let server = make_server(Some(Arc::new(ExplainingLongTermStore::default())));
+    let res = server.get("/admin/store/explain/t1?types=llm.*").await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn explain_is_not_found_without_a_long_term_store() {
+    let server = make_server(None);
+    let res = server.get("/admin/store/explain/t1").await;
+    res.assert_status(StatusCode::NOT_FOUND);
+}
+
+// ─── Runtime Metrics ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn runtime_metrics_include_long_term_query_stats() {
+    let server = make_server(Some(Arc::new(ExplainingLongTermStore::default())));
+    let body: Value = server.get("/admin/runtime").await.json();
+    let queries = &body["engine"]["longTermQueries"];
+    assert_eq!(queries["store"], "test");
+    assert_eq!(queries["slowThresholdMs"], 250.0);
+    assert_eq!(queries["methods"]["get_task"]["count"], 1);
+
+    let server = make_server(None);
+    let body: Value = server.get("/admin/runtime").await.json();
+    assert!(body["engine"].get("longTermQueries").is_none());
+}
@@ -0,0 +1,116 @@
+//! Integration tests for the `/admin/store/migration` endpoints.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, MigratingShortTermStore,
+    ShortTermStore, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(store: Arc<dyn ShortTermStore>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+/// An old store holding `count` tasks.
+async fn seeded_old_store(count: usize) -> Arc<MemoryShortTermStore> {
+    let old = Arc::new(MemoryShortTermStore::new());
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: old.clone(),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    });
+    for i in 0..count {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(format!("t{i}")),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    old
+}
+
+// ─── Endpoints ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn backfill_then_cutover_through_the_admin_api() {
+    let old = seeded_old_store(3).await;
+    let new = Arc::new(MemoryShortTermStore::new());
+    let server = make_server(Arc::new(MigratingShortTermStore::new(
+        old.clone(),
+        new.clone(),
+    )));
+
+    let res = server.get("/admin/store/migration").await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["mode"], "dualWrite");
+    assert_eq!(body["lazyBackfill"], false);
+    assert_eq!(body["backfill"], Value::Null);
+
+    let res = server.post("/admin/store/migration/backfill").await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["scanned"], 3);
+    assert_eq!(body["copied"], 3);
+    assert_eq!(body["done"], true);
+    assert!(new.get_task("t2").await.unwrap().is_some());
+
+    let res = server
+        .put("/admin/store/migration")
+        .json(&json!({ "mode": "cutover" }))
+        .await;
+    res.assert_status_ok();
+    let body: Value = res.json();
+    assert_eq!(body["mode"], "cutover");
+    assert_eq!(body["migratedTasks"], 3);
+    assert_eq!(body["backfill"]["copied"], 3);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "after-cutover" }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert!(new.get_task("after-cutover").await.unwrap().is_some());
+    assert!(old.get_task("after-cutover").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn endpoints_are_not_found_without_a_migration() {
+    let server = make_server(Arc::new(MemoryShortTermStore::new()));
+
+    server
+        .get("/admin/store/migration")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .put("/admin/store/migration")
+        .json(&json!({ "mode": "cutover" }))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .post("/admin/store/migration/backfill")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
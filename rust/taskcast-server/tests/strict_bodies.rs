@@ -0,0 +1,262 @@
+//! Integration tests for `http.strictBodies`: unknown fields in request
+//! bodies are rejected with a pointer and a suggestion, or ignored when the
+//! flag is off.
+
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::config::{HttpConfig, TaskcastConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(strict: bool) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        http: Some(HttpConfig {
+            strict_bodies: Some(strict),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn violations(body: &Value) -> Vec<(String, String)> {
+    body["details"]["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            (
+                v["pointer"].as_str().unwrap().to_string(),
+                v["message"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+async fn create_running(server: &TestServer, id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({ "id": id }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch(&format!("/tasks/{id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn misspelled_fields_are_rejected_with_suggestions() {
+    let server = make_server(true);
+    create_running(&server, "t1").await;
+
+    let res = server
+        .post("/tasks/t1/events")
+        .json(&json!({
+            "type": "llm.delta",
+            "level": "info",
+            "data": { "text": "hi" },
+            "serieId": "reply",
+            "seriesmode": "accumulate",
+        }))
+        .await;
+
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(
+        violations(&body),
+        [
+            (
+                "/serieId".to_string(),
+                "Unknown field \"serieId\"; did you mean \"seriesId\"?".to_string()
+            ),
+            (
+                "/seriesmode".to_string(),
+                "Unknown field \"seriesmode\"; did you mean \"seriesMode\"?".to_string()
+            ),
+        ]
+    );
+    assert_eq!(body["details"]["errors"].as_array().unwrap().len(), 2);
+
+    let res = server
+        .patch("/tasks/t1/status")
+        .json(&json!({ "status": "completed", "reslt": {} }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(violations(&res.json())[0].0, "/reslt");
+}
+
+#[tokio::test]
+async fn nested_unknown_fields_are_reported_with_their_pointer() {
+    let server = make_server(true);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "webooks": [],
+            "webhooks": [{ "url": "https://hooks.example/a", "secrte": "s" }],
+            "cleanup": { "rules": [{ "trigger": { "afterMs": 1000 }, "target": "all", "nmae": "x" }] },
+        }))
+        .await;
+
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let pointers: Vec<_> = violations(&res.json())
+        .into_iter()
+        .map(|(pointer, _)| pointer)
+        .collect();
+    assert_eq!(
+        pointers,
+        ["/cleanup/rules/0/nmae", "/webhooks/0/secrte", "/webooks"]
+    );
+
+    create_running(&server, "t1").await;
+    let res = server
+        .post("/tasks/t1/events")
+        .json(&json!([
+            { "type": "log", "level": "info", "data": null },
+            { "type": "log", "level": "info", "data": null, "lables": {} },
+        ]))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(violations(&res.json())[0].0, "/1/lables");
+}
+
+#[tokio::test]
+async fn streamed_lines_are_checked_too() {
+    let server = make_server(true);
+    create_running(&server, "t1").await;
+
+    let lines = [
+        json!({ "type": "log", "level": "info", "data": 1 }).to_string(),
+        json!({ "type": "log", "levle": "info", "level": "info", "data": 2 }).to_string(),
+    ];
+    let res = server
+        .post("/tasks/t1/events/stream")
+        .content_type("application/x-ndjson")
+        .bytes(Bytes::from(lines.join("\n")))
+        .await;
+
+    let out: Vec<Value> = res
+        .text()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(out.len(), 2);
+    assert_eq!(out[1]["line"], 2);
+    assert_eq!(out[1]["error"]["code"], "INVALID_INPUT");
+    assert_eq!(
+        out[1]["error"]["details"]["violations"][0]["pointer"],
+        "/levle"
+    );
+}
+
+#[tokio::test]
+async fn unknown_fields_are_ignored_when_strict_mode_is_off() {
+    let server = make_server(false);
+
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t1", "webooks": [{ "url": "https://hooks.example/a" }] }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    server
+        .patch("/tasks/t1/status")
+        .json(&json!({ "status": "running", "reslt": {} }))
+        .await
+        .assert_status_ok();
+    server
+        .post("/tasks/t1/events")
+        .json(&json!({ "type": "log", "level": "info", "data": null, "serieId": "s" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn valid_bodies_pass_in_both_modes() {
+    for strict in [false, true] {
+        let server = make_server(strict);
+        let res = server
+            .post("/tasks")
+            .json(&json!({
+                "id": "t1",
+                "type": "report",
+                "params": { "free": { "form": true } },
+                "webhooks": [{
+                    "url": "https://hooks.example/a",
+                    "filter": { "types": ["llm.*"], "seriesView": "delta" },
+                    "retry": { "retries": 3, "backoff": "fixed", "initialDelayMs": 100, "maxDelayMs": 100, "timeoutMs": 1000 },
+                }],
+                "filters": { "errors": { "levels": ["error"], "includeStatus": false } },
+                "forwardTo": { "targetTaskId": "parent", "transform": { "prefixType": "child" } },
+            }))
+            .await;
+        res.assert_status(StatusCode::CREATED);
+
+        server
+            .patch("/tasks/t1/status")
+            .json(&json!({ "status": "running" }))
+            .await
+            .assert_status_ok();
+        server
+            .post("/tasks/t1/events")
+            .json(&json!({
+                "type": "llm.delta",
+                "level": "info",
+                "data": { "text": "hi" },
+                "seriesId": "reply",
+                "seriesMode": "accumulate",
+                "labels": { "model": "m1" },
+            }))
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+}
+
+#[tokio::test]
+async fn malformed_json_is_rejected_as_before() {
+    for strict in [false, true] {
+        let server = make_server(strict);
+        let res = server
+            .post("/tasks")
+            .content_type("application/json")
+            .bytes(Bytes::from_static(b"{\"id\": "))
+            .await;
+        assert_eq!(
+            res.status_code(),
+            StatusCode::BAD_REQUEST,
+            "strict={strict}"
+        );
+        let res = server.post("/tasks").json(&json!({ "ttl": "soon" })).await;
+        assert_eq!(
+            res.status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "strict={strict}"
+        );
+    }
+}
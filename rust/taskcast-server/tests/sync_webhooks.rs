@@ -0,0 +1,327 @@
+//! Integration tests for inline (`mode: "sync"`) webhook delivery.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::config::{TaskcastConfig, WebhookGlobalConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(webhook: Option<WebhookGlobalConfig>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        webhook,
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn sync_timeout(ms: u64) -> Option<WebhookGlobalConfig> {
+    Some(WebhookGlobalConfig {
+        default_retry: None,
+        sync_timeout_ms: Some(ms),
+        max_sync_webhooks: None,
+        max_payload_bytes: None,
+        fetch_link_secret: None,
+        fetch_link_ttl_ms: None,
+        max_concurrent_deliveries: None,
+        max_concurrent_per_task: None,
+        saturation_alert_ms: None,
+    })
+}
+
+type Hits = Arc<Mutex<Vec<String>>>;
+
+/// Receiver that records every delivery's path. `/ok` and `/async` answer
+/// 200, `/fail` 500, and `/slow` waits five seconds first.
+async fn spawn_receiver() -> (SocketAddr, Hits) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits: Hits = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&hits);
+    let app = axum::Router::new().fallback(move |uri: axum::http::Uri| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            recorded.lock().unwrap().push(uri.path().to_string());
+            match uri.path() {
+                "/fail" => StatusCode::INTERNAL_SERVER_ERROR,
+                "/slow" => {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    StatusCode::OK
+                }
+                _ => StatusCode::OK,
+            }
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, hits)
+}
+
+async fn create_running_task(server: &TestServer, webhooks: serde_json::Value) -> String {
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "webhooks": webhooks }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    task_id
+}
+
+fn log_event() -> serde_json::Value {
+    json!({ "type": "fraud.alert", "level": "warn", "data": { "score": 0.97 } })
+}
+
+// ─── Publish ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn publish_response_lists_sync_webhook_results() {
+    let (addr, hits) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id = create_running_task(
+        &server,
+        json!([
+            { "url": format!("http://{addr}/ok"), "mode": "sync", "filter": { "types": ["fraud.*"] } },
+            { "url": format!("http://{addr}/async") },
+        ]),
+    )
+    .await;
+    hits.lock().unwrap().clear();
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&log_event())
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["type"], "fraud.alert");
+    let results = body["webhookResults"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["url"], format!("http://{addr}/ok"));
+    assert_eq!(results[0]["status"], "delivered");
+    assert!(results[0]["latencyMs"].is_u64());
+    assert!(results[0].get("error").is_none());
+
+    // The async webhook is not delivered inline.
+    assert_eq!(*hits.lock().unwrap(), ["/ok"]);
+}
+
+#[tokio::test]
+async fn batch_publish_lists_results_per_event() {
+    let (addr, _hits) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id = create_running_task(
+        &server,
+        json!([{ "url": format!("http://{addr}/ok"), "mode": "sync", "filter": { "levels": ["error"] } }]),
+    )
+    .await;
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&json!([
+            { "type": "log", "level": "info", "data": null },
+            { "type": "log", "level": "error", "data": null },
+        ]))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body[0]["webhookResults"], json!([]));
+    assert_eq!(body[1]["webhookResults"][0]["status"], "delivered");
+}
+
+#[tokio::test]
+async fn publish_without_sync_webhooks_is_unchanged() {
+    let (addr, hits) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id =
+        create_running_task(&server, json!([{ "url": format!("http://{addr}/async") }])).await;
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&log_event())
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert!(res
+        .json::<serde_json::Value>()
+        .get("webhookResults")
+        .is_none());
+    assert!(hits.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn slow_sync_target_is_cut_off_at_the_timeout() {
+    let (addr, _hits) = spawn_receiver().await;
+    let server = make_server(sync_timeout(200));
+    let task_id = create_running_task(
+        &server,
+        json!([
+            { "url": format!("http://{addr}/slow"), "mode": "sync", "filter": { "types": ["fraud.*"] } },
+            { "url": format!("http://{addr}/ok"), "mode": "sync", "filter": { "types": ["fraud.*"] } },
+        ]),
+    )
+    .await;
+
+    let started = Instant::now();
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&log_event())
+        .await;
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    res.assert_status(StatusCode::MULTI_STATUS);
+    let results = res.json::<serde_json::Value>()["webhookResults"].clone();
+    assert_eq!(results[0]["status"], "timeout");
+    assert_eq!(results[0]["error"], "No response within 200 ms");
+    assert!(results[0]["latencyMs"].as_u64().unwrap() >= 200);
+    assert_eq!(results[1]["status"], "delivered");
+
+    // The event stays published.
+    let history: serde_json::Value = server
+        .get(&format!("/tasks/{task_id}/events/history"))
+        .await
+        .json();
+    assert_eq!(
+        history.as_array().unwrap().last().unwrap()["type"],
+        "fraud.alert"
+    );
+}
+
+#[tokio::test]
+async fn failed_sync_delivery_is_not_retried() {
+    let (addr, hits) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id = create_running_task(
+        &server,
+        json!([{
+            "url": format!("http://{addr}/fail"),
+            "mode": "sync",
+            "filter": { "types": ["fraud.*"] },
+            "retry": { "retries": 3, "backoff": "fixed", "initialDelayMs": 10, "maxDelayMs": 10, "timeoutMs": 1000 },
+        }]),
+    )
+    .await;
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&log_event())
+        .await;
+    res.assert_status(StatusCode::MULTI_STATUS);
+    let result = &res.json::<serde_json::Value>()["webhookResults"][0];
+    assert_eq!(result["status"], "failed");
+    assert_eq!(
+        result["error"],
+        "Webhook delivery failed after 1 attempts: HTTP 500"
+    );
+    assert_eq!(*hits.lock().unwrap(), ["/fail"]);
+}
+
+// ─── Transition ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn transition_delivers_status_events_inline() {
+    let (addr, hits) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id = create_running_task(
+        &server,
+        json!([{
+            "url": format!("http://{addr}/ok"),
+            "mode": "sync",
+            "filter": { "types": ["taskcast:status"] },
+        }]),
+    )
+    .await;
+    // Created and started.
+    assert_eq!(hits.lock().unwrap().len(), 1);
+
+    let res = server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "completed" }))
+        .await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["webhookResults"].as_array().unwrap().len(), 1);
+    assert_eq!(body["webhookResults"][0]["status"], "delivered");
+    assert_eq!(hits.lock().unwrap().len(), 2);
+}
+
+// ─── Limits ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sync_webhook_cap_is_enforced_at_creation() {
+    let server = make_server(None);
+    let sync = |n: usize| {
+        json!((0..n)
+            .map(|i| json!({ "url": format!("http://h/{i}"), "mode": "sync" }))
+            .collect::<Vec<_>>())
+    };
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "webhooks": sync(3) }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.json::<serde_json::Value>()["message"],
+        "A task may have at most 2 sync webhooks, got 3"
+    );
+
+    // Async webhooks do not count towards the cap.
+    let mut webhooks = sync(2);
+    webhooks
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "url": "http://h/async", "mode": "async" }));
+    server
+        .post("/tasks")
+        .json(&json!({ "webhooks": webhooks }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let strict = make_server(Some(WebhookGlobalConfig {
+        default_retry: None,
+        sync_timeout_ms: None,
+        max_sync_webhooks: Some(1),
+        max_payload_bytes: None,
+        fetch_link_secret: None,
+        fetch_link_ttl_ms: None,
+        max_concurrent_deliveries: None,
+        max_concurrent_per_task: None,
+        saturation_alert_ms: None,
+    }));
+    strict
+        .post("/tasks")
+        .json(&json!({ "webhooks": sync(2) }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,147 @@
+//! Integration tests for `retryPolicy` on task creation and `GET /tasks/{id}/attempts`.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, RetryRunner, RetryRunnerOptions, ShortTermStore,
+    TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> (Arc<TaskEngine>, RetryRunner, TestServer) {
+    let store: Arc<dyn ShortTermStore> = Arc::new(MemoryShortTermStore::new());
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::clone(&store),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let runner = RetryRunner::new(RetryRunnerOptions {
+        engine: Arc::clone(&engine),
+        short_term_store: store,
+        check_interval_ms: 60_000,
+        claim_lease_ms: 30_000,
+    });
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    (engine, runner, TestServer::new(app))
+}
+
+async fn create_and_fail(server: &TestServer, task_id: &str) {
+    server
+        .post("/tasks")
+        .json(&json!({
+            "id": task_id,
+            "params": { "prompt": "hi" },
+            "retryPolicy": {
+                "maxAttempts": 2,
+                "backoff": "fixed",
+                "initialDelayMs": 0,
+                "maxDelayMs": 0
+            }
+        }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    for status in ["running", "failed"] {
+        server
+            .patch(&format!("/tasks/{task_id}/status"))
+            .json(&json!({ "status": status }))
+            .await
+            .assert_status_ok();
+    }
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn create_task_returns_retry_policy() {
+    let (_engine, _runner, server) = make_server();
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "retryPolicy": {
+                "maxAttempts": 3,
+                "backoff": "exponential",
+                "initialDelayMs": 1000,
+                "maxDelayMs": 60000
+            }
+        }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["retryPolicy"]["maxAttempts"], 3);
+    assert_eq!(body["retryPolicy"]["retryOn"], json!(["failed", "timeout"]));
+}
+
+#[tokio::test]
+async fn create_task_rejects_zero_max_attempts() {
+    let (_engine, _runner, server) = make_server();
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "retryPolicy": {
+                "maxAttempts": 0,
+                "backoff": "fixed",
+                "initialDelayMs": 0,
+                "maxDelayMs": 0
+            }
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn attempts_show_the_pending_retry_then_the_successor() {
+    let (_engine, runner, server) = make_server();
+    create_and_fail(&server, "t1").await;
+
+    let res = server.get("/tasks/t1/attempts").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["attempts"].as_array().unwrap().len(), 1);
+    assert_eq!(body["attempts"][0]["taskId"], "t1");
+    assert_eq!(body["attempts"][0]["attempt"], 1);
+    assert_eq!(body["attempts"][0]["status"], "failed");
+    assert_eq!(body["scheduled"]["attempt"], 2);
+    let successor_id = body["scheduled"]["successorId"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    assert_eq!(runner.tick().await.unwrap(), 1);
+
+    let res = server.get(&format!("/tasks/{successor_id}/attempts")).await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["attempts"][0]["taskId"], "t1");
+    assert_eq!(body["attempts"][1]["taskId"], successor_id.as_str());
+    assert_eq!(body["attempts"][1]["attempt"], 2);
+    assert_eq!(body["attempts"][1]["status"], "pending");
+    assert!(body["scheduled"].is_null());
+
+    let successor: serde_json::Value = server.get(&format!("/tasks/{successor_id}")).await.json();
+    assert_eq!(successor["metadata"]["retryOf"], "t1");
+    assert_eq!(successor["params"]["prompt"], "hi");
+}
+
+#[tokio::test]
+async fn attempts_for_missing_task_is_404() {
+    let (_engine, _runner, server) = make_server();
+    server
+        .get("/tasks/missing/attempts")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
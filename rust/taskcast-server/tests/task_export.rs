@@ -0,0 +1,600 @@
+//! Integration tests for `GET /tasks/export`: every matching task streams as
+//! one NDJSON line, read from the store a chunk at a time, followed by a
+//! summary line.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::Router;
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use futures::StreamExt;
+use http::Request;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{ExportConfig, HttpConfig, TaskcastConfig};
+use taskcast_core::{
+    EventQueryOptions, MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore, Task,
+    TaskCursor, TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter, TaskPage, TaskStatus,
+    Worker, WorkerAssignment, WorkerFilter,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig, REQUEST_TIMEOUT_HEADER};
+use tower::ServiceExt;
+
+const JWT_SECRET: &str = "task-export-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// Delegates to MemoryShortTermStore, counting list calls and the largest
+/// page it handed out, taking `page_delay` over each page.
+#[derive(Default)]
+struct CountingStore {
+    inner: MemoryShortTermStore,
+    list_calls: AtomicUsize,
+    page_calls: AtomicUsize,
+    largest_page: AtomicUsize,
+    page_delay: Duration,
+}
+
+impl CountingStore {
+    fn slow(page_delay: Duration) -> Self {
+        Self {
+            page_delay,
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for CountingStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.list_calls.fetch_add(1, Ordering::SeqCst);
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        self.page_calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(self.page_delay).await;
+        let page = self.inner.list_tasks_page(filter, after, limit).await?;
+        self.largest_page
+            .fetch_max(page.tasks.len(), Ordering::SeqCst);
+        Ok(page)
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+fn make_task(id: &str, r#type: &str, status: TaskStatus, created_at: f64) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: Some(r#type.to_string()),
+        status,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        created_at,
+        updated_at: created_at,
+        completed_at: None,
+        ttl: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+/// Saves `count` tasks `task-0000`.. in reverse, so the store's insertion
+/// order is not the export order. Every fourth is `completed`, the rest
+/// `failed`; pairs share a creation time.
+async fn seed(store: &CountingStore, count: usize) {
+    for i in (0..count).rev() {
+        let status = if i % 4 == 0 {
+            TaskStatus::Completed
+        } else {
+            TaskStatus::Failed
+        };
+        let r#type = if i % 2 == 0 { "llm" } else { "render" };
+        let task = make_task(&format!("task-{i:04}"), r#type, status, 1000.0 + (i / 2) as f64);
+        store.save_task(task).await.unwrap();
+    }
+}
+
+fn make_server(store: Arc<CountingStore>, auth: AuthMode, export: ExportConfig) -> TestServer {
+    let config = TaskcastConfig {
+        export: Some(export),
+        ..Default::default()
+    };
+    TestServer::new(make_app(store, auth, config).0)
+}
+
+fn make_app(
+    store: Arc<CountingStore>,
+    auth: AuthMode,
+    config: TaskcastConfig,
+) -> (Router, Arc<TaskEngine>) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        auth,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    (app, engine)
+}
+
+fn one_task_per_chunk(max_request_timeout_ms: Option<u64>) -> TaskcastConfig {
+    TaskcastConfig {
+        export: Some(ExportConfig {
+            max_rows: None,
+            chunk_size: Some(1),
+        }),
+        http: Some(HttpConfig {
+            max_request_timeout_ms,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn jwt_auth() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer(scope: &[&str], task_ids: Value) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "exporter", "scope": scope, "taskIds": task_ids, "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// The exported task ids and the summary line.
+fn parse_export(body: &str) -> (Vec<String>, Value) {
+    let mut lines: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let summary = lines.pop().unwrap();
+    let ids = lines
+        .iter()
+        .map(|task| task["id"].as_str().unwrap().to_string())
+        .collect();
+    (ids, summary)
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn export_streams_every_task_a_chunk_at_a_time() {
+    let store = Arc::new(CountingStore::default());
+    seed(&store, 3000).await;
+    let server = make_server(
+        Arc::clone(&store),
+        AuthMode::None,
+        ExportConfig {
+            max_rows: None,
+            chunk_size: Some(250),
+        },
+    );
+
+    let res = server.get("/tasks/export?format=ndjson").await;
+    res.assert_status(StatusCode::OK);
+    assert_eq!(
+        res.headers()[header::CONTENT_TYPE],
+        "application/x-ndjson"
+    );
+    let (ids, summary) = parse_export(&res.text());
+
+    let expected: Vec<String> = (0..3000).map(|i| format!("task-{i:04}")).collect();
+    assert_eq!(ids, expected);
+    assert_eq!(summary, json!({ "kind": "summary", "count": 3000, "truncated": false }));
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 12);
+    assert_eq!(store.largest_page.load(Ordering::SeqCst), 250);
+    assert_eq!(store.list_calls.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn export_applies_status_type_and_creation_filters() {
+    let store = Arc::new(CountingStore::default());
+    seed(&store, 400).await;
+    let server = make_server(Arc::clone(&store), AuthMode::None, ExportConfig::default());
+
+    // Tasks 100..=299 were created at 1050..1150.
+    let res = server
+        .get("/tasks/export?status=failed&type=llm&createdAfter=1050&createdBefore=1150")
+        .await;
+    res.assert_status(StatusCode::OK);
+    let (ids, summary) = parse_export(&res.text());
+
+    let expected: Vec<String> = (100..300)
+        .filter(|i| i % 4 == 2)
+        .map(|i| format!("task-{i:04}"))
+        .collect();
+    assert_eq!(ids, expected);
+    assert_eq!(summary["count"], 50);
+    assert_eq!(summary["truncated"], false);
+}
+
+#[tokio::test]
+async fn export_leaves_out_tasks_the_token_cannot_access() {
+    let store = Arc::new(CountingStore::default());
+    seed(&store, 2000).await;
+    let server = make_server(
+        Arc::clone(&store),
+        jwt_auth(),
+        ExportConfig {
+            max_rows: None,
+            chunk_size: Some(100),
+        },
+    );
+
+    let res = server
+        .get("/tasks/export")
+        .add_header(
+            header::AUTHORIZATION,
+            bearer(
+                &["event:subscribe"],
+                json!(["task-1999", "task-0005", "task-0950", "missing"]),
+            ),
+        )
+        .await;
+    res.assert_status(StatusCode::OK);
+    let (ids, summary) = parse_export(&res.text());
+
+    assert_eq!(ids, vec!["task-0005", "task-0950", "task-1999"]);
+    assert_eq!(summary, json!({ "kind": "summary", "count": 3, "truncated": false }));
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 20);
+}
+
+#[tokio::test]
+async fn export_stops_at_the_row_cap() {
+    let store = Arc::new(CountingStore::default());
+    seed(&store, 1000).await;
+    let server = make_server(
+        Arc::clone(&store),
+        AuthMode::None,
+        ExportConfig {
+            max_rows: Some(300),
+            chunk_size: Some(128),
+        },
+    );
+
+    let res = server.get("/tasks/export").await;
+    res.assert_status(StatusCode::OK);
+    let (ids, summary) = parse_export(&res.text());
+
+    let expected: Vec<String> = (0..300).map(|i| format!("task-{i:04}")).collect();
+    assert_eq!(ids, expected);
+    assert_eq!(summary, json!({ "kind": "summary", "count": 300, "truncated": true }));
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn export_at_exactly_the_cap_is_not_truncated() {
+    let store = Arc::new(CountingStore::default());
+    seed(&store, 300).await;
+    let server = make_server(
+        Arc::clone(&store),
+        AuthMode::None,
+        ExportConfig {
+            max_rows: Some(300),
+            chunk_size: Some(100),
+        },
+    );
+
+    let (ids, summary) = parse_export(&server.get("/tasks/export").await.text());
+    assert_eq!(ids.len(), 300);
+    assert_eq!(summary["truncated"], false);
+}
+
+#[tokio::test]
+async fn export_rejects_other_formats_and_missing_scope() {
+    let store = Arc::new(CountingStore::default());
+    let server = make_server(Arc::clone(&store), jwt_auth(), ExportConfig::default());
+
+    let res = server
+        .get("/tasks/export?format=csv")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"], json!("*")))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    let res = server
+        .get("/tasks/export")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"], json!("*")))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 0);
+}
+
+// ─── Deadlines ───────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn export_past_its_deadline_is_a_504_and_reads_no_more_pages() {
+    let store = Arc::new(CountingStore::slow(Duration::from_millis(50)));
+    seed(&store, 20).await;
+    let (app, engine) = make_app(Arc::clone(&store), jwt_auth(), one_task_per_chunk(None));
+    let server = TestServer::new(app);
+
+    // The only task the token may see is the last one, 20 pages in.
+    let res = server
+        .get("/tasks/export")
+        .add_header(
+            header::AUTHORIZATION,
+            bearer(&["event:subscribe"], json!(["task-0019"])),
+        )
+        .add_header(REQUEST_TIMEOUT_HEADER, HeaderValue::from_static("120"))
+        .await;
+    res.assert_status(StatusCode::GATEWAY_TIMEOUT);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "DEADLINE_EXCEEDED");
+    assert_eq!(body["details"], json!({ "budgetMs": 120 }));
+
+    let read = store.page_calls.load(Ordering::SeqCst);
+    assert!(read <= 3, "read {read} pages");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), read);
+    let stats = engine.request_counters().stats();
+    assert_eq!(stats.deadline_exceeded, 1);
+    assert_eq!(stats.cancelled_by_client, 0);
+}
+
+#[tokio::test]
+async fn the_configured_maximum_caps_and_defaults_the_deadline() {
+    let store = Arc::new(CountingStore::slow(Duration::from_millis(50)));
+    seed(&store, 20).await;
+    let (app, _) = make_app(
+        Arc::clone(&store),
+        jwt_auth(),
+        one_task_per_chunk(Some(100)),
+    );
+    let server = TestServer::new(app);
+    let token = bearer(&["event:subscribe"], json!(["task-0019"]));
+
+    for timeout in [Some("60000"), None] {
+        let mut req = server
+            .get("/tasks/export")
+            .add_header(header::AUTHORIZATION, token.clone());
+        if let Some(timeout) = timeout {
+            req = req.add_header(REQUEST_TIMEOUT_HEADER, HeaderValue::from_static(timeout));
+        }
+        let res = req.await;
+        res.assert_status(StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(res.json::<Value>()["details"]["budgetMs"], 100);
+    }
+
+    let res = server
+        .get("/tasks/export")
+        .add_header(header::AUTHORIZATION, token)
+        .add_header(REQUEST_TIMEOUT_HEADER, HeaderValue::from_static("soon"))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn a_dropped_export_body_reads_no_more_pages() {
+    let store = Arc::new(CountingStore::slow(Duration::from_millis(10)));
+    seed(&store, 10).await;
+    let (app, _) = make_app(Arc::clone(&store), AuthMode::None, one_task_per_chunk(None));
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/tasks/export").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+    body.next().await.unwrap().unwrap();
+    body.next().await.unwrap().unwrap();
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 2);
+
+    // The client goes away.
+    drop(body);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(store.page_calls.load(Ordering::SeqCst), 2);
+
+    let response = app
+        .oneshot(Request::get("/admin/runtime").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let metrics: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        metrics["engine"]["requests"],
+        json!({ "cancelledByClient": 1, "deadlineExceeded": 0 })
+    );
+}
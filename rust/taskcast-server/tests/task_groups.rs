@@ -0,0 +1,261 @@
+//! Integration tests for `POST /task-groups`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::config::{TaskGroupsConfig, TaskcastConfig};
+use taskcast_core::{
+    EventQueryOptions, MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore, Task,
+    TaskEngine, TaskEngineOptions, TaskEvent, TaskFilter, Worker, WorkerAssignment, WorkerFilter,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Helpers ────────────────────────────────────────────────────────────────
+
+type StoreResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Memory store, staging groups one task at a time, that cannot write
+/// `ship` and cannot delete anything, so a group holding `ship` fails and
+/// cannot be undone.
+#[derive(Default)]
+struct StuckStore {
+    inner: MemoryShortTermStore,
+}
+
+#[async_trait]
+impl ShortTermStore for StuckStore {
+    async fn save_task(&self, task: Task) -> StoreResult<()> {
+        if task.id == "ship" {
+            return Err("injected save_task failure".into());
+        }
+        self.inner.save_task(task).await
+    }
+
+    async fn delete_task(&self, _task_id: &str) -> StoreResult<()> {
+        Err("injected delete_task failure".into())
+    }
+
+    async fn get_task(&self, task_id: &str) -> StoreResult<Option<Task>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(&self, task_id: &str, event: TaskEvent) -> StoreResult<()> {
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> StoreResult<Vec<TaskEvent>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(&self, task_id: &str, ttl_seconds: u64) -> StoreResult<()> {
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> StoreResult<Option<TaskEvent>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> StoreResult<()> {
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> StoreResult<TaskEvent> {
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> StoreResult<Option<String>> {
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(&self, task_id: &str) -> StoreResult<u64> {
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(&self, filter: TaskFilter) -> StoreResult<Vec<Task>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(&self, worker: Worker) -> StoreResult<()> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(&self, worker_id: &str) -> StoreResult<Option<Worker>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(&self, filter: Option<WorkerFilter>) -> StoreResult<Vec<Worker>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(&self, worker_id: &str) -> StoreResult<()> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(&self, task_id: &str, worker_id: &str, cost: u32) -> StoreResult<bool> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(&self, assignment: WorkerAssignment) -> StoreResult<()> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(&self, task_id: &str) -> StoreResult<()> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(&self, worker_id: &str) -> StoreResult<Vec<WorkerAssignment>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(&self, task_id: &str) -> StoreResult<Option<WorkerAssignment>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+fn make_server(store: Arc<dyn ShortTermStore>, config: Option<TaskcastConfig>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, config, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn deploy_group() -> Value {
+    json!({
+        "parent": { "id": "deploy", "completionPolicy": { "mode": "allChildren" } },
+        "children": [{ "id": "build" }, { "id": "test" }, { "id": "ship" }],
+        "linkChildren": true,
+    })
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn group_is_created_with_children_linked() {
+    let server = make_server(Arc::new(MemoryShortTermStore::new()), None);
+
+    let res = server.post("/task-groups").json(&deploy_group()).await;
+
+    res.assert_status(StatusCode::CREATED);
+    let body: Value = res.json();
+    assert_eq!(body["parent"]["id"], "deploy");
+    assert_eq!(
+        body["parent"]["completionPolicy"]["childIds"],
+        json!(["build", "test", "ship"])
+    );
+    let children = body["children"].as_array().unwrap();
+    assert_eq!(children.len(), 3);
+    assert!(children.iter().all(|child| child["parentId"] == "deploy"));
+
+    let child: Value = server.get("/tasks/ship").await.json();
+    assert_eq!(child["parentId"], "deploy");
+}
+
+#[tokio::test]
+async fn group_over_the_configured_cap_is_rejected() {
+    let config = TaskcastConfig {
+        task_groups: Some(TaskGroupsConfig {
+            max_children: Some(2),
+        }),
+        ..Default::default()
+    };
+    let server = make_server(Arc::new(MemoryShortTermStore::new()), Some(config));
+
+    let res = server.post("/task-groups").json(&deploy_group()).await;
+
+    res.assert_status_bad_request();
+    let body: Value = res.json();
+    assert!(
+        body["message"].as_str().unwrap().contains("limit of 2"),
+        "{body}"
+    );
+    server.get("/tasks/deploy").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn violations_point_into_the_member_that_failed() {
+    let server = make_server(Arc::new(MemoryShortTermStore::new()), None);
+    let mut group = deploy_group();
+    group["children"][1]["ttl"] = json!(0);
+
+    let res = server.post("/task-groups").json(&group).await;
+
+    res.assert_status_bad_request();
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(
+        body["details"]["violations"][0]["pointer"],
+        "/children/1/ttl"
+    );
+    server.get("/tasks/deploy").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn taken_id_conflicts_and_creates_nothing() {
+    let server = make_server(Arc::new(MemoryShortTermStore::new()), None);
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "test" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let res = server.post("/task-groups").json(&deploy_group()).await;
+
+    res.assert_status(StatusCode::CONFLICT);
+    server.get("/tasks/deploy").await.assert_status_not_found();
+    server.get("/tasks/build").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn failed_compensation_lists_the_tasks_that_may_exist() {
+    let server = make_server(Arc::new(StuckStore::default()), None);
+
+    let res = server.post("/task-groups").json(&deploy_group()).await;
+
+    res.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "PARTIAL_TASK_GROUP");
+    assert_eq!(
+        body["details"]["taskIds"],
+        json!(["deploy", "build", "test"])
+    );
+}
@@ -0,0 +1,127 @@
+//! Integration tests for `GET /tasks` filtering and pagination.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, ShortTermStore, Task, TaskEngine,
+    TaskEngineOptions, TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn task(id: &str, task_type: &str, status: TaskStatus, created_at: f64) -> Task {
+    serde_json::from_value(json!({
+        "id": id,
+        "type": task_type,
+        "status": status,
+        "createdAt": created_at,
+        "updatedAt": created_at,
+    }))
+    .unwrap()
+}
+
+/// A server over a memory store holding `tasks`.
+async fn make_server(tasks: Vec<Task>) -> TestServer {
+    let store = Arc::new(MemoryShortTermStore::new());
+    for task in tasks {
+        store.save_task(task).await.unwrap();
+    }
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(engine, AuthMode::None, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn crawls() -> Vec<Task> {
+    vec![
+        task("c1", "crawl.page", TaskStatus::Running, 1000.0),
+        task("e1", "export", TaskStatus::Running, 1500.0),
+        task("c2", "crawl.site", TaskStatus::Running, 2000.0),
+        task("c3", "crawl.page", TaskStatus::Pending, 2500.0),
+        task("c4", "crawl.page", TaskStatus::Running, 3000.0),
+        task("c5", "crawl.page", TaskStatus::Running, 3000.0),
+    ]
+}
+
+fn ids(body: &Value) -> Vec<&str> {
+    body["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["id"].as_str().unwrap())
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn pages_through_filtered_tasks_with_the_cursor() {
+    let server = make_server(crawls()).await;
+
+    let mut url = "/tasks?status=running&type=crawl.*&limit=2".to_string();
+    let mut pages = Vec::new();
+    loop {
+        let res = server.get(&url).await;
+        res.assert_status_ok();
+        let body: Value = res.json();
+        pages.push(ids(&body).join(","));
+        match body["nextCursor"].as_str() {
+            Some(cursor) => {
+                url = format!("/tasks?status=running&type=crawl.*&limit=2&cursor={cursor}")
+            }
+            None => break,
+        }
+    }
+
+    assert_eq!(pages, ["c1,c2", "c4,c5"]);
+}
+
+#[tokio::test]
+async fn without_a_limit_every_match_is_listed_in_creation_order() {
+    let server = make_server(crawls()).await;
+
+    let body: Value = server.get("/tasks").await.json();
+
+    assert_eq!(ids(&body), ["c1", "e1", "c2", "c3", "c4", "c5"]);
+    assert_eq!(body["nextCursor"], Value::Null);
+}
+
+#[tokio::test]
+async fn filters_by_creation_time() {
+    let server = make_server(crawls()).await;
+
+    let body: Value = server
+        .get("/tasks?createdAfter=1500&createdBefore=3000")
+        .await
+        .json();
+
+    assert_eq!(ids(&body), ["e1", "c2", "c3"]);
+}
+
+#[tokio::test]
+async fn rejects_a_bad_limit_or_cursor() {
+    let server = make_server(crawls()).await;
+
+    for url in [
+        "/tasks?limit=0",
+        "/tasks?limit=1001",
+        "/tasks?limit=ten",
+        "/tasks?cursor=nope",
+    ] {
+        let res = server.get(url).await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+        let body: Value = res.json();
+        assert_eq!(body["code"], "INVALID_QUERY", "{url}");
+    }
+}
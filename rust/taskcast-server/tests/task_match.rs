@@ -0,0 +1,299 @@
+//! Integration tests for `taskMatch`: webhook and SSE conditions on the task
+//! an event belongs to rather than on the event itself.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server() -> (Arc<TaskEngine>, TestServer) {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app(
+        Arc::clone(&engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    (engine, TestServer::new(app))
+}
+
+type Hits = Arc<Mutex<Vec<serde_json::Value>>>;
+
+/// Receiver that records the body of every delivery and answers 200.
+async fn spawn_receiver() -> (SocketAddr, Hits) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits: Hits = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&hits);
+    let app = axum::Router::new().fallback(move |body: String| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            recorded
+                .lock()
+                .unwrap()
+                .push(serde_json::from_str(&body).unwrap());
+            StatusCode::OK
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, hits)
+}
+
+async fn create_running_task(server: &TestServer, body: serde_json::Value) -> String {
+    let res = server.post("/tasks").json(&body).await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<serde_json::Value>()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    task_id
+}
+
+/// A `sync` webhook to `addr`, so deliveries finish before publish returns.
+fn eu_webhook(addr: SocketAddr, filter: Option<serde_json::Value>) -> serde_json::Value {
+    let mut webhook = json!({
+        "url": format!("http://{addr}/hook"),
+        "mode": "sync",
+        "taskMatch": { "metadata": { "region": "eu" } },
+    });
+    if let Some(filter) = filter {
+        webhook["filter"] = filter;
+    }
+    webhook
+}
+
+/// Publishes an event through the API, returning its index.
+async fn publish(server: &TestServer, task_id: &str, event_type: &str) -> u64 {
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&json!({ "type": event_type, "level": "info", "data": null }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    res.json::<serde_json::Value>()["index"].as_u64().unwrap()
+}
+
+fn delivered_types(hits: &Hits) -> Vec<String> {
+    hits.lock()
+        .unwrap()
+        .iter()
+        .map(|body| body["type"].as_str().unwrap().to_string())
+        .collect()
+}
+
+// ─── Webhooks ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn webhook_fires_only_for_tasks_matching_its_task_match() {
+    let (addr, hits) = spawn_receiver().await;
+    let (_engine, server) = make_server();
+    let eu = create_running_task(
+        &server,
+        json!({ "metadata": { "region": "eu" }, "webhooks": [eu_webhook(addr, None)] }),
+    )
+    .await;
+    let us = create_running_task(
+        &server,
+        json!({ "metadata": { "region": "us" }, "webhooks": [eu_webhook(addr, None)] }),
+    )
+    .await;
+    hits.lock().unwrap().clear();
+
+    publish(&server, &us, "log").await;
+    publish(&server, &eu, "log").await;
+
+    let bodies = hits.lock().unwrap().clone();
+    assert_eq!(bodies.len(), 1);
+    assert_eq!(bodies[0]["taskId"], eu);
+}
+
+#[tokio::test]
+async fn metadata_change_starts_and_stops_deliveries() {
+    let (addr, hits) = spawn_receiver().await;
+    let (engine, server) = make_server();
+    let task_id = create_running_task(
+        &server,
+        json!({
+            "metadata": { "region": "us" },
+            "webhooks": [eu_webhook(addr, Some(json!({ "types": ["step"] })))],
+        }),
+    )
+    .await;
+
+    let set_region = |region: &'static str| {
+        let engine = Arc::clone(&engine);
+        let task_id = task_id.clone();
+        async move {
+            engine
+                .update_task(&task_id, |task| {
+                    task.metadata
+                        .get_or_insert_with(Default::default)
+                        .insert("region".to_string(), json!(region));
+                })
+                .await
+                .unwrap();
+        }
+    };
+
+    publish(&server, &task_id, "step").await;
+    set_region("eu").await;
+    let matched = publish(&server, &task_id, "step").await;
+    set_region("us").await;
+    publish(&server, &task_id, "step").await;
+
+    let bodies = hits.lock().unwrap().clone();
+    assert_eq!(bodies.len(), 1);
+    assert_eq!(bodies[0]["index"], matched);
+}
+
+#[tokio::test]
+async fn webhook_needs_both_event_and_task_conditions() {
+    let (addr, hits) = spawn_receiver().await;
+    let (_engine, server) = make_server();
+    let filter = json!({
+        "types": ["llm.*"],
+        "taskMatch": { "types": ["chat.*"], "status": ["running"] },
+    });
+    let chat = create_running_task(
+        &server,
+        json!({
+            "type": "chat.reply",
+            "metadata": { "region": "eu" },
+            "webhooks": [eu_webhook(addr, Some(filter.clone()))],
+        }),
+    )
+    .await;
+    let batch = create_running_task(
+        &server,
+        json!({
+            "type": "batch.import",
+            "metadata": { "region": "eu" },
+            "webhooks": [eu_webhook(addr, Some(filter))],
+        }),
+    )
+    .await;
+    hits.lock().unwrap().clear();
+
+    publish(&server, &chat, "log").await;
+    publish(&server, &chat, "llm.delta").await;
+    publish(&server, &batch, "llm.delta").await;
+
+    assert_eq!(delivered_types(&hits), ["llm.delta"]);
+    assert_eq!(hits.lock().unwrap()[0]["taskId"], chat);
+}
+
+// ─── SSE ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn sse_rejects_a_task_that_does_not_match_on_connect() {
+    let (_engine, server) = make_server();
+    let task_id = create_running_task(
+        &server,
+        json!({ "type": "chat.reply", "metadata": { "region": "eu" } }),
+    )
+    .await;
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("taskMetadata", "region:us")
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "TASK_MISMATCH");
+    assert_eq!(body["details"]["taskId"], task_id);
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("taskTypes", "batch.*")
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("taskStatus", "runing")
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.json::<serde_json::Value>()["details"]["param"],
+        "taskStatus"
+    );
+}
+
+#[tokio::test]
+async fn sse_streams_a_task_that_matches() {
+    let (_engine, server) = make_server();
+    let task_id = create_running_task(
+        &server,
+        json!({ "type": "chat.reply", "metadata": { "region": "eu" } }),
+    )
+    .await;
+    publish(&server, &task_id, "log").await;
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "completed" }))
+        .await
+        .assert_status_ok();
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("taskMetadata", "region:eu")
+        .add_query_param("taskTypes", "chat.*")
+        .add_query_param("taskStatus", "completed")
+        .await;
+    res.assert_status_ok();
+    let body = res.text();
+    assert!(body.contains("\"type\":\"log\""));
+    assert!(body.contains("event: taskcast.done"));
+}
+
+#[tokio::test]
+async fn sse_checks_a_preset_task_match() {
+    let (_engine, server) = make_server();
+    let task_id = create_running_task(
+        &server,
+        json!({
+            "metadata": { "region": "us" },
+            "filters": { "euOnly": { "taskMatch": { "metadata": { "region": "eu" } } } },
+        }),
+    )
+    .await;
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("preset", "euOnly")
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(res.json::<serde_json::Value>()["code"], "TASK_MISMATCH");
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events"))
+        .add_query_param("preset", "euOnly")
+        .add_query_param("taskMetadata", "region:us")
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.json::<serde_json::Value>()["code"],
+        "FILTER_PRESET_CONFLICT"
+    );
+}
@@ -0,0 +1,128 @@
+//! Integration tests for task origins over HTTP: the origin stamped on
+//! `POST /tasks` and the `origin` list filter.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine,
+    TaskEngineOptions, TaskOrigin, TaskOriginKind,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "task-origin-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: Arc<TaskEngine>, auth: AuthMode) -> TestServer {
+    let (app, _) = create_app(engine, auth, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn list_ids(body: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = body["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    ids
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn created_tasks_record_the_token_subject() {
+    let auth = AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    });
+    let server = make_server(make_engine(), auth);
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "alice", "scope": ["*"], "taskIds": "*", "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+
+    let res = server
+        .post("/tasks")
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        )
+        .json(&json!({ "id": "t1" }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert_eq!(
+        res.json::<Value>()["origin"],
+        json!({ "kind": "user", "detail": "alice" })
+    );
+}
+
+#[tokio::test]
+async fn origin_cannot_be_set_in_the_body() {
+    let server = make_server(make_engine(), AuthMode::None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "t1", "origin": { "kind": "system" } }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert_eq!(res.json::<Value>()["origin"], json!({ "kind": "user" }));
+}
+
+#[tokio::test]
+async fn list_filters_by_origin() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine), AuthMode::None);
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "user" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    for (task_id, kind) in [
+        ("retry", TaskOriginKind::Retry),
+        ("system", TaskOriginKind::System),
+    ] {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(task_id.to_string()),
+                origin: Some(TaskOrigin::new(kind)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+
+    for (query, expected) in [
+        ("origin=retry", vec!["retry"]),
+        ("origin=user", vec!["user"]),
+        ("origin=retry,system", vec!["retry", "system"]),
+        // Unknown kinds are ignored, leaving the list unfiltered.
+        ("origin=bogus", vec!["retry", "system", "user"]),
+    ] {
+        let res = server.get(&format!("/tasks?{query}")).await;
+        res.assert_status_ok();
+        assert_eq!(list_ids(&res.json::<Value>()), expected, "{query}");
+    }
+}
@@ -0,0 +1,219 @@
+//! Integration tests for the `task:transition` and `task:read` scopes and
+//! `auth.strictScopes`.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{AuthConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "task-scopes-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+async fn make_server(strict_scopes: bool) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    for id in ["t1", "t2"] {
+        engine
+            .create_task(CreateTaskInput {
+                id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+    }
+    let config = TaskcastConfig {
+        auth: Some(AuthConfig {
+            mode: taskcast_core::config::AuthMode::Jwt,
+            jwt: None,
+            api_keys: None,
+            strict_scopes: Some(strict_scopes),
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::Jwt(JwtConfig {
+            algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+            secret: Some(JWT_SECRET.to_string()),
+            public_key: None,
+            issuer: None,
+            audience: None,
+        }),
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "scopes-test", "scope": scope, "taskIds": "*", "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+async fn transition(server: &TestServer, token: &HeaderValue, task_id: &str, status: &str) -> u16 {
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(header::AUTHORIZATION, token.clone())
+        .json(&json!({ "status": status }))
+        .await
+        .status_code()
+        .as_u16()
+}
+
+// ─── Narrow scopes ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn transition_scope_changes_status_and_nothing_else() {
+    let server = make_server(false).await;
+    let token = bearer(&["task:transition"]);
+
+    assert_eq!(transition(&server, &token, "t1", "running").await, 200);
+    assert_eq!(transition(&server, &token, "t1", "completed").await, 200);
+    // Cancelling is a transition too.
+    assert_eq!(transition(&server, &token, "t2", "cancelled").await, 200);
+
+    let res = server
+        .delete("/tasks/t1/events")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .json(&json!({ "eventIds": [] }))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        res.json::<Value>()["details"]["requiredScope"],
+        "task:manage"
+    );
+    server
+        .post("/templates")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .json(&json!({ "name": "report", "webhooks": [{ "url": "https://example.com" }] }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    let res = server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(res.json::<Value>()["details"]["requiredScope"], "task:read");
+    server
+        .get("/tasks/t1/transitions")
+        .add_header(header::AUTHORIZATION, token)
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn read_scope_reads_tasks_but_cannot_transition_them() {
+    let server = make_server(false).await;
+    let token = bearer(&["task:read"]);
+
+    for path in ["/tasks/t1", "/tasks", "/tasks/t1/transitions"] {
+        server
+            .get(path)
+            .add_header(header::AUTHORIZATION, token.clone())
+            .await
+            .assert_status_ok();
+    }
+    let res = server
+        .patch("/tasks/t1/status")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .json(&json!({ "status": "running" }))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        res.json::<Value>()["details"]["requiredScope"],
+        "task:transition"
+    );
+    server
+        .get("/tasks/t1/events/history")
+        .add_header(header::AUTHORIZATION, token)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn manage_scope_still_does_everything() {
+    let server = make_server(false).await;
+    let token = bearer(&["task:manage"]);
+
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .await
+        .assert_status_ok();
+    server
+        .get("/tasks")
+        .add_header(header::AUTHORIZATION, token.clone())
+        .await
+        .assert_status_ok();
+    assert_eq!(transition(&server, &token, "t1", "running").await, 200);
+    assert_eq!(transition(&server, &token, "t2", "cancelled").await, 200);
+    server
+        .post("/templates")
+        .add_header(header::AUTHORIZATION, token)
+        .json(&json!({ "name": "report" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn subscribe_scope_still_reads_tasks() {
+    let server = make_server(false).await;
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status_ok();
+}
+
+// ─── Strict scopes ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn strict_scopes_require_the_narrow_scopes() {
+    let server = make_server(true).await;
+
+    let manage = bearer(&["task:manage"]);
+    assert_eq!(transition(&server, &manage, "t1", "running").await, 403);
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, manage)
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let narrow = bearer(&["task:transition", "task:read"]);
+    assert_eq!(transition(&server, &narrow, "t1", "running").await, 200);
+    server
+        .get("/tasks/t1")
+        .add_header(header::AUTHORIZATION, narrow)
+        .await
+        .assert_status_ok();
+    assert_eq!(
+        transition(&server, &bearer(&["*"]), "t1", "completed").await,
+        200
+    );
+}
@@ -0,0 +1,144 @@
+//! Integration tests for `GET /tasks/{task_id}/series` and the 422 for
+//! events that would start a series past the task's cap.
+
+use std::sync::Arc;
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, SeriesLimits, SeriesOverLimit, TaskEngine,
+    TaskEngineOptions,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(max_per_task: usize) -> TestServer {
+    let engine = TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    })
+    .with_series_limits(SeriesLimits {
+        max_per_task,
+        over_limit: SeriesOverLimit::Reject,
+    });
+    let (app, _) = create_app(
+        Arc::new(engine),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+async fn create_running_task(server: &TestServer) -> String {
+    let res = server.post("/tasks").json(&json!({})).await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    task_id
+}
+
+fn series_event(series_id: &str, mode: &str) -> Value {
+    json!({
+        "type": "agent.progress",
+        "level": "info",
+        "data": { "delta": "x" },
+        "seriesId": series_id,
+        "seriesMode": mode,
+    })
+}
+
+fn series_ids(page: &Value) -> Vec<&str> {
+    page["series"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["seriesId"].as_str().unwrap())
+        .collect()
+}
+
+// ─── Listing ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn lists_stateful_series_in_pages() {
+    let server = make_server(10);
+    let task_id = create_running_task(&server).await;
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&json!([
+            series_event("c", "latest"),
+            series_event("a", "accumulate"),
+            series_event("log", "keep-all"),
+            series_event("b", "latest"),
+        ]))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let page: Value = server
+        .get(&format!("/tasks/{task_id}/series?limit=2"))
+        .await
+        .json();
+    assert_eq!(page["taskId"], task_id.as_str());
+    assert_eq!(series_ids(&page), ["a", "b"]);
+    assert_eq!(page["series"][0]["mode"], "accumulate");
+    assert!(page["series"][0]["latestIndex"].is_u64());
+    assert!(page["series"][0]["latestTimestamp"].is_number());
+    assert_eq!(page["nextCursor"], "b");
+
+    let page: Value = server
+        .get(&format!("/tasks/{task_id}/series?limit=2&cursor=b"))
+        .await
+        .json();
+    assert_eq!(series_ids(&page), ["c"]);
+    assert!(page["nextCursor"].is_null());
+}
+
+#[tokio::test]
+async fn listing_checks_the_task_and_the_limit() {
+    let server = make_server(10);
+    server
+        .get("/tasks/missing/series")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let task_id = create_running_task(&server).await;
+    let res = server
+        .get(&format!("/tasks/{task_id}/series?limit=0"))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(res.json::<Value>()["code"], "INVALID_QUERY");
+}
+
+// ─── Cap ─────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn a_series_past_the_cap_is_rejected() {
+    let server = make_server(1);
+    let task_id = create_running_task(&server).await;
+    server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&series_event("a", "latest"))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .json(&series_event("b", "latest"))
+        .await;
+    res.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "SERIES_LIMIT_EXCEEDED");
+    assert_eq!(body["details"], json!({ "taskId": task_id, "limit": 1 }));
+}
@@ -0,0 +1,320 @@
+//! Integration tests for the validation `POST /tasks` and
+//! `PATCH /tasks/:taskId/status` apply to request bodies.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::config::{HttpConfig, TaskcastConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "validation-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(auth_mode: AuthMode, http: Option<HttpConfig>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        http,
+        ..Default::default()
+    };
+    let (app, _) = create_app(engine, auth_mode, None, Some(config), CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn jwt_mode() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer() -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "validation-test",
+            "scope": ["*"],
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+fn pointers(body: &serde_json::Value) -> Vec<&str> {
+    body["details"]["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["pointer"].as_str().unwrap())
+        .collect()
+}
+
+// ─── POST /tasks ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn every_violation_is_reported_with_a_pointer() {
+    let server = make_server(AuthMode::None, None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "id": "a/b",
+            "ttl": 0,
+            "webhooks": [
+                { "url": "https://hooks.example/ok" },
+                { "url": "notaurl" },
+            ],
+            "cleanup": {
+                "rules": [{ "trigger": { "afterMs": 0 }, "target": "all" }],
+            },
+            "authConfig": {
+                "rules": [{ "match": { "scope": [] }, "require": { "sub": ["u1"] } }],
+            },
+        }))
+        .await;
+
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(
+        pointers(&body),
+        [
+            "/id",
+            "/ttl",
+            "/webhooks/1/url",
+            "/cleanup/rules/0/trigger/afterMs",
+            "/authConfig/rules/0/match/scope",
+        ]
+    );
+    assert_eq!(body["details"]["errors"].as_array().unwrap().len(), 5);
+    server.get("/tasks/a%2Fb").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn retry_settings_are_checked() {
+    let server = make_server(AuthMode::None, None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "webhooks": [{
+                "url": "https://hooks.example/a",
+                "retry": { "retries": 50, "backoff": "fixed", "initialDelayMs": 5000, "maxDelayMs": 100, "timeoutMs": 1000 },
+            }],
+            "retryPolicy": { "maxAttempts": 3, "backoff": "fixed", "initialDelayMs": 5000, "maxDelayMs": 100 },
+        }))
+        .await;
+
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        pointers(&res.json()),
+        [
+            "/webhooks/0/retry/retries",
+            "/webhooks/0/retry/initialDelayMs",
+            "/retryPolicy/initialDelayMs",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn forward_rules_are_checked() {
+    let server = make_server(AuthMode::None, None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "id": "child",
+            "forwardTo": { "targetTaskId": "child", "transform": { "prefixType": "" } },
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(
+        pointers(&res.json()),
+        ["/forwardTo/targetTaskId", "/forwardTo/transform/prefixType"]
+    );
+
+    let forward_to = json!({
+        "targetTaskId": "parent",
+        "filter": { "types": ["progress"] },
+        "transform": { "prefixType": "child", "level": "debug" },
+    });
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "child", "forwardTo": forward_to }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert_eq!(res.json::<serde_json::Value>()["forwardTo"], forward_to);
+
+    // A rule closing a cycle only shows once the other task is known.
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "parent", "forwardTo": { "targetTaskId": "child" } }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(res.json::<serde_json::Value>()["code"], "INVALID_INPUT");
+}
+
+#[tokio::test]
+async fn template_values_are_validated_too() {
+    let server = make_server(AuthMode::None, None);
+    server
+        .post("/templates")
+        .json(&json!({ "name": "bad", "cleanup": { "rules": [{ "trigger": { "afterMs": 10 }, "target": "all" }] } }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "template": "bad" }))
+        .await;
+
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(pointers(&res.json()), ["/cleanup/rules/0/trigger/afterMs"]);
+}
+
+#[tokio::test]
+async fn a_maximal_valid_payload_is_stored_as_sent() {
+    let server = make_server(jwt_mode(), None);
+    let webhooks = json!([{
+        "url": "https://hooks.example/taskcast",
+        "retry": { "retries": 10, "backoff": "exponential", "initialDelayMs": 1000, "maxDelayMs": 60000, "timeoutMs": 5000 },
+    }]);
+    let cleanup = json!({
+        "rules": [{ "trigger": { "afterMs": 1000 }, "target": "all" }],
+    });
+    let auth_config = json!({
+        "rules": [{ "match": { "scope": ["event:subscribe"] }, "require": { "sub": ["u1"] } }],
+    });
+    let retry_policy = json!({
+        "maxAttempts": 100, "backoff": "fixed", "initialDelayMs": 500, "maxDelayMs": 500,
+    });
+
+    let res = server
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, bearer())
+        .json(&json!({
+            "id": "report:2026-10.v1_a-b",
+            "type": "report.generate",
+            "ttl": 31536000,
+            "webhooks": webhooks,
+            "cleanup": cleanup,
+            "authConfig": auth_config,
+            "retryPolicy": retry_policy,
+        }))
+        .await;
+
+    res.assert_status(StatusCode::CREATED);
+    let task: serde_json::Value = res.json();
+    assert_eq!(task["id"], "report:2026-10.v1_a-b");
+    assert_eq!(task["ttl"], 31536000);
+    assert_eq!(task["webhooks"][0]["url"], webhooks[0]["url"]);
+    assert_eq!(task["webhooks"][0]["retry"], webhooks[0]["retry"]);
+    assert_eq!(task["cleanup"], cleanup);
+    assert_eq!(task["authConfig"], auth_config);
+    assert_eq!(task["retryPolicy"]["maxAttempts"], 100);
+}
+
+#[tokio::test]
+async fn http_webhooks_need_auth_off_or_the_config_flag() {
+    let body = json!({ "webhooks": [{ "url": "http://localhost:8080/hook" }] });
+
+    let dev = make_server(AuthMode::None, None);
+    dev.post("/tasks")
+        .json(&body)
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let secured = make_server(jwt_mode(), None);
+    let res = secured
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, bearer())
+        .json(&body)
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(pointers(&res.json()), ["/webhooks/0/url"]);
+
+    let allowed = make_server(
+        jwt_mode(),
+        Some(HttpConfig {
+            allow_insecure_webhooks: Some(true),
+            task_id_pattern: None,
+            strict_bodies: None,
+            max_request_timeout_ms: None,
+        }),
+    );
+    allowed
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, bearer())
+        .json(&body)
+        .await
+        .assert_status(StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn task_id_pattern_is_configurable() {
+    let server = make_server(
+        AuthMode::None,
+        Some(HttpConfig {
+            allow_insecure_webhooks: None,
+            task_id_pattern: Some("^job-[0-9]+$".to_string()),
+            strict_bodies: None,
+            max_request_timeout_ms: None,
+        }),
+    );
+
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "job-7" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    let res = server.post("/tasks").json(&json!({ "id": "t1" })).await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(pointers(&res.json()), ["/id"]);
+}
+
+// ─── PATCH /tasks/:taskId/status ─────────────────────────────────────────────
+
+#[tokio::test]
+async fn transition_ttl_is_checked() {
+    let server = make_server(AuthMode::None, None);
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t1" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    for ttl in [0u64, 31536001] {
+        let res = server
+            .patch("/tasks/t1/status")
+            .json(&json!({ "status": "running", "ttl": ttl }))
+            .await;
+        res.assert_status(StatusCode::BAD_REQUEST);
+        assert_eq!(pointers(&res.json()), ["/ttl"]);
+    }
+    let task: serde_json::Value = server.get("/tasks/t1").await.json();
+    assert_eq!(task["status"], "pending");
+
+    server
+        .patch("/tasks/t1/status")
+        .json(&json!({ "status": "running", "ttl": 60 }))
+        .await
+        .assert_status_ok();
+}
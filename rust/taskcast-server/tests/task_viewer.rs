@@ -0,0 +1,236 @@
+//! Integration tests for the bundled task viewer: `GET /view`,
+//! `GET /tasks/{id}/view` and `?token=` auth on task SSE streams.
+
+use std::sync::Arc;
+
+use axum_test::http::{header, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::config::{TaskcastConfig, UiConfig};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+    TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "task-viewer-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn ui_config(enabled: bool) -> TaskcastConfig {
+    TaskcastConfig {
+        ui: Some(UiConfig {
+            enabled: Some(enabled),
+        }),
+        ..Default::default()
+    }
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth: AuthMode, config: TaskcastConfig) -> TestServer {
+    let (app, _) = create_app(
+        Arc::clone(engine),
+        auth,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn jwt_auth() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn token() -> String {
+    encode(
+        &Header::default(),
+        &json!({ "sub": "viewer", "scope": ["event:subscribe"], "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn completed_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    for status in [TaskStatus::Running, TaskStatus::Completed] {
+        engine.transition_task(id, status, None).await.unwrap();
+    }
+}
+
+/// The config object the task page embeds.
+fn page_config(page: &str) -> Value {
+    let start = r#"<script type="application/json" id="taskcast-config">"#;
+    let (_, rest) = page.split_once(start).expect("config script");
+    let (config, _) = rest.split_once("</script>").unwrap();
+    serde_json::from_str(config).unwrap()
+}
+
+// ─── Pages ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn viewer_is_off_by_default() {
+    let engine = make_engine();
+    completed_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None, TaskcastConfig::default());
+
+    server.get("/view").await.assert_status_not_found();
+    server.get("/tasks/t1/view").await.assert_status_not_found();
+
+    let server = make_server(&engine, AuthMode::None, ui_config(false));
+    server.get("/view").await.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn serves_the_index_page() {
+    let engine = make_engine();
+    let server = make_server(&engine, AuthMode::None, ui_config(true));
+
+    let res = server.get("/view").await;
+    res.assert_status_ok();
+    assert!(res
+        .header(header::CONTENT_TYPE)
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+    assert!(res.text().contains(r#"<input id="task-id""#));
+}
+
+#[tokio::test]
+async fn task_page_is_served_with_a_nonce_csp() {
+    let engine = make_engine();
+    completed_task(&engine, "t1").await;
+    let server = make_server(&engine, AuthMode::None, ui_config(true));
+
+    let res = server.get("/tasks/t1/view").await;
+    res.assert_status_ok();
+    let csp = res
+        .header(header::CONTENT_SECURITY_POLICY)
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(csp.starts_with("default-src 'none'"));
+    assert!(csp.contains("connect-src 'self'"));
+    let nonce = csp
+        .split("'nonce-")
+        .nth(1)
+        .and_then(|rest| rest.split('\'').next())
+        .unwrap();
+    let page = res.text();
+    assert!(page.contains(&format!(r#"<script nonce="{nonce}">"#)));
+    assert!(!page.contains("__NONCE__"));
+    assert_eq!(res.header(header::REFERRER_POLICY), "no-referrer");
+    assert_eq!(res.header(header::CACHE_CONTROL), "no-store");
+
+    // A fresh nonce per response.
+    let again = server.get("/tasks/t1/view").await;
+    assert_ne!(again.header(header::CONTENT_SECURITY_POLICY), csp.as_str());
+}
+
+#[tokio::test]
+async fn query_and_token_are_passed_to_the_embedded_stream() {
+    let engine = make_engine();
+    let server = make_server(&engine, AuthMode::None, ui_config(true));
+
+    let page = server
+        .get("/tasks/t1/view?types=llm.*&wrap=false&token=abc.def")
+        .await
+        .text();
+    assert_eq!(
+        page_config(&page),
+        json!({
+            "taskId": "t1",
+            "taskUrl": "/v1/tasks/t1",
+            "eventsUrl": "/v1/tasks/t1/events?types=llm.*&wrap=false&token=abc.def",
+            "token": "abc.def",
+        })
+    );
+
+    let page = server.get("/tasks/t1/view").await.text();
+    assert_eq!(page_config(&page)["eventsUrl"], "/v1/tasks/t1/events");
+    assert_eq!(page_config(&page)["token"], Value::Null);
+}
+
+#[tokio::test]
+async fn task_ids_and_query_cannot_break_out_of_the_page() {
+    let engine = make_engine();
+    let server = make_server(&engine, AuthMode::None, ui_config(true));
+
+    let page = server
+        .get("/tasks/%3C%2Fscript%3E%20a/view?note=%3C/script%3E")
+        .await
+        .text();
+    let config = page_config(&page);
+    assert_eq!(config["taskId"], "</script> a");
+    assert_eq!(config["taskUrl"], "/v1/tasks/%3C%2Fscript%3E%20a");
+    assert_eq!(page.matches("</script>").count(), 2);
+}
+
+// ─── Query Token Auth ────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn query_token_authenticates_task_streams_when_enabled() {
+    let engine = make_engine();
+    completed_task(&engine, "t1").await;
+    let server = make_server(&engine, jwt_auth(), ui_config(true));
+
+    // The page itself needs no auth.
+    server.get("/tasks/t1/view").await.assert_status_ok();
+
+    let res = server
+        .get(&format!("/tasks/t1/events?token={}", token()))
+        .await;
+    res.assert_status_ok();
+    assert!(res.text().contains("event: taskcast.done"));
+    // The viewer opens the versioned stream.
+    server
+        .get(&format!("/v1/tasks/t1/events?token={}", token()))
+        .await
+        .assert_status_ok();
+
+    server
+        .get("/tasks/t1/events")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    // Only streams accept the query token.
+    server
+        .get(&format!("/tasks/t1?token={}", token()))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn query_token_is_ignored_when_the_viewer_is_off() {
+    let engine = make_engine();
+    completed_task(&engine, "t1").await;
+    let server = make_server(&engine, jwt_auth(), TaskcastConfig::default());
+
+    server
+        .get(&format!("/tasks/t1/events?token={}", token()))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
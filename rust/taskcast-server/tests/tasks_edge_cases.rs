@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use axum_test::http::StatusCode;
 use axum_test::TestServer;
 use serde_json::json;
 use taskcast_core::{
@@ -14,6 +15,9 @@ fn make_engine() -> Arc<TaskEngine> {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }))
 }
 
@@ -331,6 +335,27 @@ async fn create_task_duplicate_id_returns_conflict() {
     assert_eq!(status, 409, "expected 409 Conflict, got {status}");
 }
 
+#[tokio::test]
+async fn create_task_duplicate_id_reports_existing_task() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine));
+    server
+        .post("/tasks")
+        .json(&json!({"id": "dup-2", "params": {"owner": "first"}}))
+        .await
+        .assert_status(StatusCode::CREATED);
+
+    let resp = server
+        .post("/tasks")
+        .json(&json!({"id": "dup-2", "params": {"owner": "second"}}))
+        .await;
+    resp.assert_status(StatusCode::CONFLICT);
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "TASK_ALREADY_EXISTS");
+    assert_eq!(body["details"]["taskId"], "dup-2");
+    assert_eq!(body["details"]["existingTask"]["params"]["owner"], "first");
+}
+
 // ─── GET /tasks — type filter ─────────────────────────────────────────────
 
 #[tokio::test]
@@ -381,9 +406,85 @@ async fn create_task_with_zero_ttl_returns_400() {
         .await;
     resp.assert_status_bad_request();
     let body: serde_json::Value = resp.json();
-    let error_msg = body["error"].as_str().unwrap();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    let error_msg = body["message"].as_str().unwrap();
     assert!(
         error_msg.contains("TTL"),
         "expected error message to mention TTL, got: {error_msg}"
     );
 }
+
+// ─── Event labels ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn publish_event_with_too_many_labels_returns_400() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine));
+    create_running_task(&engine, "labels-limit").await;
+
+    let labels: serde_json::Map<String, serde_json::Value> =
+        (0..17).map(|i| (format!("k{i}"), json!("v"))).collect();
+    let resp = server
+        .post("/tasks/labels-limit/events")
+        .json(&json!({"type": "log", "level": "info", "data": null, "labels": labels}))
+        .await;
+    resp.assert_status_bad_request();
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    let error_msg = body["message"].as_str().unwrap();
+    assert!(
+        error_msg.contains("labels"),
+        "expected error message to mention labels, got: {error_msg}"
+    );
+}
+
+#[tokio::test]
+async fn publish_event_with_overlong_label_value_returns_400() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine));
+    create_running_task(&engine, "labels-long").await;
+
+    let resp = server
+        .post("/tasks/labels-long/events")
+        .json(&json!({
+            "type": "log",
+            "level": "info",
+            "data": null,
+            "labels": {"region": "x".repeat(257)}
+        }))
+        .await;
+    resp.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn event_history_filters_by_label_selector() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine));
+    create_running_task(&engine, "labels-hist").await;
+
+    let resp = server
+        .post("/tasks/labels-hist/events")
+        .json(&json!([
+            {"type": "log", "level": "info", "data": 1, "labels": {"region": "eu", "worker": "w-42"}},
+            {"type": "log", "level": "info", "data": 2, "labels": {"region": "us", "worker": "w-42"}},
+            {"type": "log", "level": "info", "data": 3, "labels": {"region": "eu", "worker": "w-7"}},
+            {"type": "log", "level": "info", "data": 4}
+        ]))
+        .await;
+    resp.assert_status(axum_test::http::StatusCode::CREATED);
+
+    let resp = server
+        .get("/tasks/labels-hist/events/history?labels=region:eu,worker:w-42")
+        .await;
+    resp.assert_status_ok();
+    let body: serde_json::Value = resp.json();
+    let events = body.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["data"], 1);
+    assert_eq!(events[0]["labels"]["worker"], "w-42");
+
+    let resp = server
+        .get("/tasks/labels-hist/events/history?labels=region")
+        .await;
+    resp.assert_status_bad_request();
+}
@@ -0,0 +1,241 @@
+//! Integration tests for `/templates` and `POST /tasks` with a `template`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::config::{TaskTemplate, TaskcastConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "templates-test-secret-key-that-is-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn report_template() -> TaskTemplate {
+    serde_json::from_value(json!({
+        "type": "report.generate",
+        "ttl": 3600,
+        "webhooks": [{ "url": "https://notify.example/hooks" }],
+        "cleanup": {
+            "rules": [{
+                "match": { "status": ["completed"] },
+                "trigger": { "afterMs": 60000 },
+                "target": "all",
+            }],
+        },
+        "metadata": { "owner": { "team": "reporting" } },
+    }))
+    .unwrap()
+}
+
+fn make_server(auth_mode: AuthMode) -> TestServer {
+    let config = TaskcastConfig {
+        templates: Some(HashMap::from([("report".to_string(), report_template())])),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        make_engine(),
+        auth_mode,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({
+            "sub": "templates-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+// ─── POST /tasks ─────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn created_task_carries_the_template() {
+    let server = make_server(AuthMode::None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({
+            "template": "report",
+            "webhooks": [{ "url": "https://audit.example/hooks" }],
+            "metadata": { "owner": { "user": "u-42" } },
+        }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let task: serde_json::Value = res.json();
+    assert_eq!(task["type"], "report.generate");
+    assert_eq!(task["ttl"], 3600);
+    assert_eq!(
+        task["webhooks"],
+        json!([
+            { "url": "https://notify.example/hooks" },
+            { "url": "https://audit.example/hooks" },
+        ])
+    );
+    assert_eq!(
+        task["metadata"],
+        json!({ "owner": { "team": "reporting", "user": "u-42" } })
+    );
+    assert!(task.get("template").is_none());
+
+    let stored: serde_json::Value = server
+        .get(&format!("/tasks/{}", task["id"].as_str().unwrap()))
+        .await
+        .json();
+    assert_eq!(
+        stored["cleanup"],
+        json!({
+            "rules": [{
+                "match": { "status": ["completed"] },
+                "trigger": { "afterMs": 60000 },
+                "target": "all",
+            }],
+        })
+    );
+}
+
+#[tokio::test]
+async fn request_values_override_the_template() {
+    let server = make_server(AuthMode::None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "template": "report", "type": "report.quick", "ttl": 60 }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let task: serde_json::Value = res.json();
+    assert_eq!(task["type"], "report.quick");
+    assert_eq!(task["ttl"], 60);
+}
+
+#[tokio::test]
+async fn unknown_template_is_rejected() {
+    let server = make_server(AuthMode::None);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "template": "missing" }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["code"], "BAD_REQUEST");
+    assert_eq!(body["message"], "Unknown template: missing");
+}
+
+// ─── /templates ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn template_crud_round_trip() {
+    let server = make_server(AuthMode::None);
+
+    let res = server.get("/templates").await;
+    res.assert_status_ok();
+    let names: Vec<String> = res.json::<serde_json::Value>()["templates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["report"]);
+
+    let template = json!({ "name": "export", "type": "export.csv", "ttl": 120 });
+    let res = server.post("/templates").json(&template).await;
+    res.assert_status(StatusCode::CREATED);
+    assert_eq!(res.json::<serde_json::Value>(), template);
+
+    let res = server.get("/templates/export").await;
+    res.assert_status_ok();
+    assert_eq!(res.json::<serde_json::Value>(), template);
+
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "template": "export" }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    assert_eq!(res.json::<serde_json::Value>()["type"], "export.csv");
+
+    server
+        .delete("/templates/export")
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    server
+        .get("/templates/export")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .delete("/templates/export")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+    server
+        .post("/tasks")
+        .json(&json!({ "template": "export" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn templates_require_task_manage_scope() {
+    let server = make_server(AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    }));
+
+    let res = server
+        .get("/templates")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"]))
+        .await;
+    res.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        res.json::<serde_json::Value>()["details"]["requiredScope"],
+        "task:manage"
+    );
+    server
+        .post("/templates")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"]))
+        .json(&json!({ "name": "export" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    server
+        .get("/templates")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .await
+        .assert_status_ok();
+    // Creating from a template only needs task:create.
+    server
+        .post("/tasks")
+        .add_header(header::AUTHORIZATION, bearer(&["task:create"]))
+        .json(&json!({ "template": "report" }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
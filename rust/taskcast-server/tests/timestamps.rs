@@ -0,0 +1,322 @@
+//! Integration tests for RFC 3339 timestamps at the HTTP boundary: request
+//! fields accept them beside epoch milliseconds, `timestampFormat=rfc3339`
+//! renders response times as strings, and seconds-scale numbers are warned
+//! about, or rejected under `http.strictBodies`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum_test::http::StatusCode;
+use axum_test::{TestResponse, TestServer};
+use serde_json::{json, Value};
+use taskcast_core::config::{HttpConfig, TaskcastConfig};
+use taskcast_core::{MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions};
+use taskcast_server::timestamps::format_rfc3339;
+use taskcast_server::{create_app, AuthMode, CorsConfig, TIMESTAMP_FORMAT_HEADER, WARNING_HEADER};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(strict: bool) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let config = TaskcastConfig {
+        http: Some(HttpConfig {
+            strict_bodies: Some(strict),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(
+        engine,
+        AuthMode::None,
+        None,
+        Some(config),
+        CorsConfig::default(),
+    );
+    TestServer::new(app)
+}
+
+/// Creates a running task `id` with one `log` event and returns it.
+async fn running_task(server: &TestServer, id: &str) -> Value {
+    let task: Value = server
+        .post("/tasks")
+        .json(&json!({ "id": id }))
+        .await
+        .json();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    server
+        .patch(&format!("/tasks/{id}/status"))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    server
+        .post(&format!("/tasks/{id}/events"))
+        .json(&json!({ "type": "log", "level": "info", "data": {} }))
+        .await
+        .assert_status(StatusCode::CREATED);
+    task
+}
+
+/// A whole millisecond at or after `millis`, so it survives RFC 3339.
+fn whole_ms_after(millis: f64) -> f64 {
+    millis.floor() + 1.0
+}
+
+fn rfc3339(millis: f64) -> String {
+    format_rfc3339(millis).unwrap()
+}
+
+fn warnings(res: &TestResponse) -> Vec<String> {
+    res.headers()
+        .get_all(WARNING_HEADER)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect()
+}
+
+/// A fixed time after 2001, in both forms.
+const MILLIS: f64 = 1767225600123.0;
+const RFC3339: &str = "2026-01-01T00:00:00.123Z";
+
+// ─── Accepting Both Forms ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn scheduled_for_accepts_rfc3339() {
+    let server = make_server(false);
+    let by_millis: Value = server
+        .post("/tasks")
+        .json(&json!({ "id": "ms", "scheduledFor": MILLIS }))
+        .await
+        .json();
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "rfc", "scheduledFor": RFC3339 }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let by_rfc3339: Value = res.json();
+
+    assert_eq!(by_millis["scheduledFor"], json!(MILLIS));
+    assert_eq!(by_rfc3339["scheduledFor"], by_millis["scheduledFor"]);
+    assert_eq!(by_rfc3339["status"], "scheduled");
+    assert!(warnings(&res).is_empty());
+}
+
+#[tokio::test]
+async fn unparseable_timestamps_are_rejected() {
+    let server = make_server(false);
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "scheduledFor": "next tuesday" }))
+        .await;
+    assert!(res.status_code().is_client_error());
+
+    let res = server
+        .get("/tasks/t1/events/history?since.timestamp=yesterday")
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["details"]["param"], "since.timestamp");
+}
+
+#[tokio::test]
+async fn as_of_accepts_rfc3339() {
+    let server = make_server(false);
+    let task = running_task(&server, "t1").await;
+    let as_of = whole_ms_after(task["createdAt"].as_f64().unwrap());
+
+    let by_millis: Value = server.get(&format!("/tasks/t1?asOf={as_of}")).await.json();
+    let res = server
+        .get("/tasks/t1")
+        .add_query_param("asOf", rfc3339(as_of))
+        .await;
+    res.assert_status_ok();
+
+    assert_eq!(by_millis["status"], "pending");
+    assert_eq!(res.json::<Value>(), by_millis);
+}
+
+#[tokio::test]
+async fn history_since_and_until_accept_rfc3339() {
+    let server = make_server(false);
+    let task = running_task(&server, "t1").await;
+    let cut = whole_ms_after(task["createdAt"].as_f64().unwrap());
+
+    for param in ["since.timestamp", "until"] {
+        let by_millis: Value = server
+            .get(&format!("/tasks/t1/events/history?{param}={cut}"))
+            .await
+            .json();
+        let by_rfc3339: Value = server
+            .get("/tasks/t1/events/history")
+            .add_query_param(param, rfc3339(cut))
+            .await
+            .json();
+        assert_eq!(by_rfc3339, by_millis, "{param}");
+    }
+}
+
+#[tokio::test]
+async fn export_and_bulk_created_bounds_accept_rfc3339() {
+    let server = make_server(false);
+    running_task(&server, "t1").await;
+    let after = rfc3339(1577836800000.0);
+
+    let by_millis = server
+        .get("/tasks/export?createdAfter=1577836800000")
+        .await
+        .text();
+    let by_rfc3339 = server
+        .get("/tasks/export")
+        .add_query_param("createdAfter", &after)
+        .await
+        .text();
+    assert_eq!(by_rfc3339, by_millis);
+    assert!(by_millis.contains("\"t1\""));
+
+    let dry_run = |selector: Value| {
+        let server = &server;
+        async move {
+            let res = server
+                .post("/admin/tasks/bulk")
+                .json(&json!({ "selector": selector, "action": { "transition": { "to": "cancelled" } }, "dryRun": true }))
+                .await;
+            res.assert_status_ok();
+            res.json::<Value>()
+        }
+    };
+    let by_millis = dry_run(json!({ "createdAfter": 1577836800000u64 })).await;
+    let by_rfc3339 = dry_run(json!({ "createdAfter": after })).await;
+    assert_eq!(by_millis["taskIds"], json!(["t1"]));
+    assert_eq!(by_rfc3339, by_millis);
+}
+
+// ─── Output Format ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn default_output_keeps_epoch_milliseconds() {
+    let server = make_server(false);
+    running_task(&server, "t1").await;
+
+    let task: Value = server.get("/tasks/t1").await.json();
+    assert!(task["createdAt"].is_number());
+    assert!(task["updatedAt"].is_number());
+    let events: Value = server.get("/tasks/t1/events/history").await.json();
+    assert!(events[0]["timestamp"].is_number());
+}
+
+#[tokio::test]
+async fn rfc3339_output_round_trips() {
+    let server = make_server(false);
+    running_task(&server, "t1").await;
+    let task: Value = server.get("/tasks/t1").await.json();
+    let events: Value = server.get("/tasks/t1/events/history").await.json();
+
+    let formatted: Value = server.get("/tasks/t1?timestampFormat=rfc3339").await.json();
+    for field in ["createdAt", "updatedAt"] {
+        let text = formatted[field].as_str().unwrap();
+        assert!(text.ends_with('Z'), "{text}");
+        let millis = taskcast_server::timestamps::parse_timestamp(text).unwrap();
+        assert!(
+            (millis - task[field].as_f64().unwrap()).abs() < 0.001,
+            "{field}"
+        );
+    }
+    assert_eq!(formatted["id"], "t1");
+    assert_eq!(formatted["subscriberCount"], task["subscriberCount"]);
+
+    let formatted: Value = server
+        .get("/tasks/t1/events/history")
+        .add_header(TIMESTAMP_FORMAT_HEADER, "rfc3339")
+        .await
+        .json();
+    let text = formatted[0]["timestamp"].as_str().unwrap();
+    let millis = taskcast_server::timestamps::parse_timestamp(text).unwrap();
+    assert!((millis - events[0]["timestamp"].as_f64().unwrap()).abs() < 0.001);
+}
+
+#[tokio::test]
+async fn unknown_timestamp_format_is_rejected() {
+    let server = make_server(false);
+    running_task(&server, "t1").await;
+
+    let res = server.get("/tasks/t1?timestampFormat=iso").await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_QUERY");
+    assert_eq!(body["details"]["param"], "timestampFormat");
+}
+
+// ─── Seconds-Scale Values ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn seconds_scale_values_are_warned_about() {
+    let server = make_server(false);
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "t1", "scheduledFor": 1767225600 }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let sent = warnings(&res);
+    assert_eq!(sent.len(), 1);
+    assert!(
+        sent[0].starts_with("/scheduledFor: 1767225600 is before 2001-09-09"),
+        "{}",
+        sent[0]
+    );
+
+    let res = server
+        .get("/tasks/t1/events/history?since.timestamp=1767225600&until=1767225600")
+        .await;
+    res.assert_status_ok();
+    assert_eq!(warnings(&res).len(), 2);
+
+    // Zero means "from the start", not seconds.
+    let res = server
+        .get("/tasks/t1/events/history?since.timestamp=0")
+        .await;
+    assert!(warnings(&res).is_empty());
+}
+
+#[tokio::test]
+async fn seconds_scale_values_are_rejected_in_strict_mode() {
+    let server = make_server(true);
+    let res = server
+        .post("/tasks")
+        .json(&json!({ "id": "t1", "scheduledFor": 1767225600 }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_INPUT");
+    assert_eq!(body["details"]["violations"][0]["pointer"], "/scheduledFor");
+
+    running_task(&server, "t2").await;
+    let res = server.get("/tasks/t2?asOf=1767225600").await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = res.json();
+    assert_eq!(body["code"], "INVALID_QUERY");
+    assert_eq!(body["details"]["param"], "asOf");
+
+    let res = server
+        .post("/admin/tasks/bulk")
+        .json(&json!({
+            "selector": { "createdBefore": 1767225600 },
+            "action": { "transition": { "to": "cancelled" } },
+            "dryRun": true,
+        }))
+        .await;
+    res.assert_status(StatusCode::BAD_REQUEST);
+
+    // RFC 3339 and millisecond values pass.
+    server
+        .post("/tasks")
+        .json(&json!({ "id": "t3", "scheduledFor": RFC3339 }))
+        .await
+        .assert_status(StatusCode::CREATED);
+}
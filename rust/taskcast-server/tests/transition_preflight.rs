@@ -0,0 +1,429 @@
+//! Integration tests for transition pre-flight: `GET /tasks/{id}/transitions`
+//! and `PATCH /tasks/{id}/status?dryRun=true`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use taskcast_core::{
+    allowed_transitions, is_terminal, CreateTaskInput, EventQueryOptions, MemoryBroadcastProvider,
+    MemoryShortTermStore, ShortTermStore, Task, TaskEngine, TaskEngineOptions, TaskEvent,
+    TaskFilter, TaskStatus, Worker, WorkerAssignment, WorkerFilter,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig, JwtConfig};
+
+const JWT_SECRET: &str = "transition-preflight-test-secret-key-needs-to-be-long-enough";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine(store: Arc<dyn ShortTermStore>) -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: store,
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: &Arc<TaskEngine>, auth: AuthMode) -> TestServer {
+    let (app, _) = create_app(Arc::clone(engine), auth, None, None, CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn jwt_auth() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+fn bearer(scope: &[&str]) -> HeaderValue {
+    let token = encode(
+        &Header::default(),
+        &json!({ "sub": "orchestrator", "scope": scope, "exp": 9999999999u64 }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    HeaderValue::from_str(&format!("Bearer {token}")).unwrap()
+}
+
+/// Creates `id` and moves it through `path`.
+async fn task_at(engine: &TaskEngine, id: &str, path: &[TaskStatus]) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    for status in path {
+        engine
+            .transition_task(id, status.clone(), None)
+            .await
+            .unwrap();
+    }
+}
+
+/// Status and body of a response, without the per-request id.
+fn outcome(res: &axum_test::TestResponse) -> (StatusCode, Value) {
+    let text = res.text();
+    let mut body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+    if let Some(body) = body.as_object_mut() {
+        body.remove("requestId");
+    }
+    (res.status_code(), body)
+}
+
+/// Delegates to MemoryShortTermStore, counting the calls that write.
+struct CountingStore {
+    inner: MemoryShortTermStore,
+    writes: AtomicUsize,
+}
+
+impl CountingStore {
+    fn new() -> Self {
+        Self {
+            inner: MemoryShortTermStore::new(),
+            writes: AtomicUsize::new(0),
+        }
+    }
+
+    fn writes(&self) -> usize {
+        self.writes.load(Ordering::SeqCst)
+    }
+
+    fn write(&self) {
+        self.writes.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for CountingStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner.save_task(task).await
+    }
+
+    async fn get_task(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn clear_ttl(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner.clear_ttl(task_id).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn next_index(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.write();
+        self.inner.next_index(task_id).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn save_worker(
+        &self,
+        worker: Worker,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.get_task_assignment(task_id).await
+    }
+}
+
+// ─── Allowed Transitions ─────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn lists_allowed_transitions_for_each_status() {
+    use TaskStatus::*;
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    let paths: [(TaskStatus, &[TaskStatus]); 9] = [
+        (Pending, &[]),
+        (Assigned, &[Assigned]),
+        (Running, &[Running]),
+        (Paused, &[Paused]),
+        (Blocked, &[Running, Blocked]),
+        (Completed, &[Running, Completed]),
+        (Failed, &[Running, Failed]),
+        (Timeout, &[Running, Timeout]),
+        (Cancelled, &[Cancelled]),
+    ];
+    for (i, (_, path)) in paths.iter().enumerate() {
+        task_at(&engine, &format!("t{i}"), path).await;
+    }
+    let server = make_server(&engine, AuthMode::None);
+
+    for (i, (status, _)) in paths.iter().enumerate() {
+        let res = server.get(&format!("/tasks/t{i}/transitions")).await;
+        res.assert_status_ok();
+        assert_eq!(
+            res.json::<Value>(),
+            json!({
+                "taskId": format!("t{i}"),
+                "status": status,
+                "transitions": allowed_transitions(status),
+                "canPublish": !is_terminal(status),
+            }),
+            "{status:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn transitions_of_a_missing_task_is_not_found() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    let server = make_server(&engine, AuthMode::None);
+
+    server
+        .get("/tasks/ghost/transitions")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn transitions_need_subscribe_or_manage_scope() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    task_at(&engine, "t1", &[]).await;
+    let server = make_server(&engine, jwt_auth());
+
+    for scope in ["event:subscribe", "task:manage"] {
+        server
+            .get("/tasks/t1/transitions")
+            .add_header(header::AUTHORIZATION, bearer(&[scope]))
+            .await
+            .assert_status_ok();
+    }
+    server
+        .get("/tasks/t1/transitions")
+        .add_header(header::AUTHORIZATION, bearer(&["event:publish"]))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
+
+// ─── Dry Run ─────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn dry_run_returns_the_would_be_task_and_leaves_no_trace() {
+    let store = Arc::new(CountingStore::new());
+    let engine = make_engine(Arc::clone(&store) as Arc<dyn ShortTermStore>);
+    task_at(&engine, "t1", &[TaskStatus::Running]).await;
+    let server = make_server(&engine, AuthMode::None);
+    let writes = store.writes();
+
+    let res = server
+        .patch("/tasks/t1/status?dryRun=true")
+        .json(&json!({ "status": "completed", "result": { "rows": 3 }, "ttl": 600 }))
+        .await;
+    res.assert_status_ok();
+    let task: Value = res.json();
+    assert_eq!(task["status"], "completed");
+    assert_eq!(task["result"], json!({ "rows": 3 }));
+    assert_eq!(task["ttl"], 600);
+    assert!(task["completedAt"].is_number());
+
+    assert_eq!(store.writes(), writes);
+    let stored = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(stored.status, TaskStatus::Running);
+    assert_eq!(stored.result, None);
+    let history: Vec<Value> = server.get("/tasks/t1/events/history").await.json();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["data"]["status"], "running");
+}
+
+#[tokio::test]
+async fn dry_run_errors_match_the_real_call() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    task_at(&engine, "t1", &[]).await;
+    let server = make_server(&engine, AuthMode::None);
+
+    let cases = [
+        ("ghost", json!({ "status": "running" })),
+        ("t1", json!({ "status": "completed" })),
+        ("t1", json!({ "status": "running", "ttl": 0 })),
+        ("t1", json!({ "status": "sleeping" })),
+    ];
+    for (id, body) in cases {
+        let dry_run = server
+            .patch(&format!("/tasks/{id}/status?dryRun=true"))
+            .json(&body)
+            .await;
+        let real = server
+            .patch(&format!("/tasks/{id}/status"))
+            .json(&body)
+            .await;
+        assert!(real.status_code().is_client_error(), "{body}");
+        assert_eq!(outcome(&dry_run), outcome(&real), "{body}");
+    }
+}
+
+#[tokio::test]
+async fn dry_run_needs_the_same_scope_as_the_real_call() {
+    let engine = make_engine(Arc::new(MemoryShortTermStore::new()));
+    task_at(&engine, "t1", &[]).await;
+    let server = make_server(&engine, jwt_auth());
+
+    server
+        .patch("/tasks/t1/status?dryRun=true")
+        .add_header(header::AUTHORIZATION, bearer(&["event:subscribe"]))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+    server
+        .patch("/tasks/t1/status?dryRun=true")
+        .add_header(header::AUTHORIZATION, bearer(&["task:manage"]))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    let task = engine.get_task("t1").await.unwrap().unwrap();
+    assert_eq!(task.status, TaskStatus::Pending);
+}
@@ -17,6 +17,9 @@ fn make_verbose_server() -> (Arc<TaskEngine>, TestServer, CollectingLogger) {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let (app, _) = create_app(Arc::clone(&engine), AuthMode::None, None, None, CorsConfig::default());
 
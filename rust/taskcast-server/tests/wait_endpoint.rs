@@ -0,0 +1,189 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum_test::http::StatusCode;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::config::{LongPollConfig, TaskcastConfig};
+use taskcast_core::{
+    CreateTaskInput, MemoryBroadcastProvider, MemoryShortTermStore, TaskEngine, TaskEngineOptions,
+    TaskStatus,
+};
+use taskcast_server::{create_app, AuthMode, CorsConfig};
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(engine: Arc<TaskEngine>, config: Option<TaskcastConfig>) -> TestServer {
+    let (app, _) = create_app(engine, AuthMode::None, None, config, CorsConfig::default());
+    TestServer::new(app)
+}
+
+async fn create_running_task(engine: &TaskEngine, id: &str) {
+    engine
+        .create_task(CreateTaskInput {
+            id: Some(id.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    engine
+        .transition_task(id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn wait_returns_terminal_task_immediately() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine), None);
+    create_running_task(&engine, "done-1").await;
+    engine
+        .transition_task("done-1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    let resp = server.get("/tasks/done-1/wait").await;
+
+    resp.assert_status_ok();
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["id"], "done-1");
+    assert_eq!(body["status"], "completed");
+    assert_eq!(body["completed"], true);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn wait_returns_promptly_when_task_completes() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine), None);
+    create_running_task(&engine, "live-1").await;
+
+    let completer = {
+        let engine = Arc::clone(&engine);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            engine
+                .transition_task("live-1", TaskStatus::Failed, None)
+                .await
+                .unwrap();
+        })
+    };
+
+    let started = Instant::now();
+    let resp = server
+        .get("/tasks/live-1/wait")
+        .add_query_param("timeoutMs", 10_000)
+        .await;
+    completer.await.unwrap();
+
+    resp.assert_status_ok();
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["status"], "failed");
+    assert_eq!(body["completed"], true);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn wait_times_out_with_current_task() {
+    let engine = make_engine();
+    let server = make_server(Arc::clone(&engine), None);
+    create_running_task(&engine, "slow-1").await;
+
+    let resp = server
+        .get("/tasks/slow-1/wait")
+        .add_query_param("timeoutMs", 50)
+        .await;
+
+    resp.assert_status_ok();
+    let body: serde_json::Value = resp.json();
+    assert_eq!(body["status"], "running");
+    assert_eq!(body["completed"], false);
+}
+
+#[tokio::test]
+async fn wait_timeout_is_capped_by_config() {
+    let engine = make_engine();
+    let config = TaskcastConfig {
+        long_poll: Some(LongPollConfig {
+            max_timeout_ms: Some(50),
+        }),
+        ..Default::default()
+    };
+    let server = make_server(Arc::clone(&engine), Some(config));
+    create_running_task(&engine, "capped-1").await;
+
+    let started = Instant::now();
+    let resp = server
+        .get("/tasks/capped-1/wait")
+        .add_query_param("timeoutMs", 60_000)
+        .await;
+
+    resp.assert_status_ok();
+    assert_eq!(resp.json::<serde_json::Value>()["completed"], false);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn wait_counts_as_subscriber_while_held() {
+    let engine = make_engine();
+    let server = Arc::new(make_server(Arc::clone(&engine), None));
+    create_running_task(&engine, "held-1").await;
+
+    let waiter = {
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            server
+                .get("/tasks/held-1/wait")
+                .add_query_param("timeoutMs", 10_000)
+                .await
+        })
+    };
+
+    let mut subscriber_count = 0;
+    for _ in 0..100 {
+        let task: serde_json::Value = server.get("/tasks/held-1").await.json();
+        subscriber_count = task["subscriberCount"].as_u64().unwrap();
+        if subscriber_count == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(subscriber_count, 1);
+
+    engine
+        .transition_task("held-1", TaskStatus::Completed, None)
+        .await
+        .unwrap();
+    waiter.await.unwrap().assert_status_ok();
+
+    let mut task: serde_json::Value = json!(null);
+    for _ in 0..100 {
+        task = server.get("/tasks/held-1").await.json();
+        if task["subscriberCount"] == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(task["subscriberCount"], 0);
+}
+
+#[tokio::test]
+async fn wait_missing_task_returns_404() {
+    let server = make_server(make_engine(), None);
+
+    let resp = server.get("/tasks/nope/wait").await;
+
+    resp.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<serde_json::Value>()["code"], "TASK_NOT_FOUND");
+}
@@ -0,0 +1,287 @@
+//! Integration tests for webhook `backfill`: a webhook added to a running
+//! task receives matching history, then live events.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::http::HeaderMap;
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskStatus, WebhookConfig,
+};
+use taskcast_server::{DispatchOutcome, WebhookDelivery, WebhookDispatcher, BACKFILL_HEADER};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_dispatcher(engine: &TaskEngine) -> WebhookDispatcher {
+    WebhookDispatcher::new(
+        Arc::new(WebhookDelivery::new()),
+        Arc::clone(engine.short_term_store()),
+    )
+}
+
+/// `(event type, event index, backfill header)` of one delivery.
+type Delivery = (String, u64, Option<String>);
+type Deliveries = Arc<Mutex<Vec<Delivery>>>;
+
+/// Receiver that records every delivery, taking `delay` to answer each.
+async fn spawn_receiver(delay: Duration) -> (SocketAddr, Deliveries) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let deliveries: Deliveries = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&deliveries);
+    let app = axum::Router::new().fallback(move |headers: HeaderMap, body: String| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            tokio::time::sleep(delay).await;
+            let event: Value = serde_json::from_str(&body).unwrap();
+            recorded.lock().unwrap().push((
+                event["type"].as_str().unwrap().to_string(),
+                event["index"].as_u64().unwrap(),
+                headers
+                    .get(BACKFILL_HEADER)
+                    .map(|value| value.to_str().unwrap().to_string()),
+            ));
+            axum::http::StatusCode::OK
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, deliveries)
+}
+
+async fn create_running_task(engine: &TaskEngine) -> String {
+    let task = engine
+        .create_task(CreateTaskInput::default())
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    task.id
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, r#type: &str) -> taskcast_core::TaskEvent {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: r#type.to_string(),
+                level: Level::Info,
+                data: json!({}),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+fn webhook(addr: SocketAddr, extra: Value) -> WebhookConfig {
+    let mut config = json!({ "url": format!("http://{addr}/hook") });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(config).unwrap()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn backfill_all_delivers_history_before_live_events() {
+    let engine = make_engine();
+    let dispatcher = Arc::new(make_dispatcher(&engine));
+    let (addr, deliveries) = spawn_receiver(Duration::from_millis(20)).await;
+    let task_id = create_running_task(&engine).await;
+    let mut history = Vec::new();
+    for r#type in ["llm.chunk", "log", "llm.chunk", "llm.done"] {
+        history.push(publish(&engine, &task_id, r#type).await);
+    }
+
+    let config = webhook(
+        addr,
+        json!({ "filter": { "types": ["llm.*"] }, "backfill": "all" }),
+    );
+    let (task, run) = dispatcher
+        .add_webhook(&engine, &task_id, config)
+        .await
+        .unwrap();
+    let run = run.expect("backfill requested");
+    assert_eq!(run.webhook(), 0);
+    let backfill = tokio::spawn(run.run());
+    while deliveries.lock().unwrap().is_empty() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    // A history event reaching live dispatch late is not sent twice.
+    assert!(dispatcher.dispatch(&task, &history[0]).await.unwrap().is_empty());
+    // Published after the replay read history: held back until it ends.
+    let live = publish(&engine, &task_id, "llm.chunk").await;
+    let dispatched = dispatcher.dispatch(&task, &live).await.unwrap();
+    assert!(matches!(dispatched[0].outcome, DispatchOutcome::Delivered));
+
+    let replayed = backfill.await.unwrap().unwrap();
+    assert_eq!(replayed.len(), 3);
+    let later = publish(&engine, &task_id, "llm.done").await;
+    dispatcher.dispatch(&task, &later).await.unwrap();
+
+    let backfilled = Some("true".to_string());
+    assert_eq!(
+        *deliveries.lock().unwrap(),
+        vec![
+            ("llm.chunk".to_string(), history[0].index, backfilled.clone()),
+            ("llm.chunk".to_string(), history[2].index, backfilled.clone()),
+            ("llm.done".to_string(), history[3].index, backfilled),
+            ("llm.chunk".to_string(), live.index, None),
+            ("llm.done".to_string(), later.index, None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn backfill_since_cursor_skips_earlier_history() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, deliveries) = spawn_receiver(Duration::ZERO).await;
+    let task_id = create_running_task(&engine).await;
+    let first = publish(&engine, &task_id, "progress").await;
+    let second = publish(&engine, &task_id, "progress").await;
+
+    let config = webhook(
+        addr,
+        json!({
+            "filter": { "types": ["progress"] },
+            "backfill": { "index": first.index },
+        }),
+    );
+    let (_, run) = dispatcher
+        .add_webhook(&engine, &task_id, config)
+        .await
+        .unwrap();
+    run.unwrap().run().await.unwrap();
+
+    let indices: Vec<u64> = deliveries.lock().unwrap().iter().map(|d| d.1).collect();
+    assert_eq!(indices, [second.index]);
+}
+
+#[tokio::test]
+async fn webhook_without_backfill_only_receives_live_events() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, deliveries) = spawn_receiver(Duration::ZERO).await;
+    let task_id = create_running_task(&engine).await;
+    publish(&engine, &task_id, "progress").await;
+
+    let (task, run) = dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, json!({})))
+        .await
+        .unwrap();
+    assert!(run.is_none());
+    assert_eq!(task.webhooks.as_ref().unwrap().len(), 1);
+    assert_eq!(
+        engine.get_task(&task_id).await.unwrap().unwrap().webhooks,
+        task.webhooks
+    );
+
+    let live = publish(&engine, &task_id, "progress").await;
+    dispatcher.dispatch(&task, &live).await.unwrap();
+    assert_eq!(
+        *deliveries.lock().unwrap(),
+        vec![("progress".to_string(), live.index, None)]
+    );
+}
+
+#[tokio::test]
+async fn dropped_backfill_releases_live_events() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, deliveries) = spawn_receiver(Duration::ZERO).await;
+    let task_id = create_running_task(&engine).await;
+    publish(&engine, &task_id, "progress").await;
+
+    let (task, run) = dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, json!({ "backfill": "all" })))
+        .await
+        .unwrap();
+    drop(run);
+
+    let live = publish(&engine, &task_id, "progress").await;
+    let dispatched = tokio::time::timeout(
+        Duration::from_secs(5),
+        dispatcher.dispatch(&task, &live),
+    )
+    .await
+    .expect("live dispatch is not held back")
+    .unwrap();
+    assert_eq!(dispatched.len(), 1);
+    assert_eq!(deliveries.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn add_webhook_rejects_unknown_task_and_preset() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let task_id = create_running_task(&engine).await;
+    let addr: SocketAddr = "127.0.0.1:9".parse().unwrap();
+
+    assert!(dispatcher
+        .add_webhook(&engine, "missing", webhook(addr, json!({})))
+        .await
+        .is_err());
+    assert!(dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, json!({ "filterPreset": "nope" })))
+        .await
+        .is_err());
+    assert!(engine
+        .get_task(&task_id)
+        .await
+        .unwrap()
+        .unwrap()
+        .webhooks
+        .is_none());
+}
+
+#[tokio::test]
+async fn deleting_the_task_forgets_its_backfills() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, _) = spawn_receiver(Duration::ZERO).await;
+    let task_id = create_running_task(&engine).await;
+    publish(&engine, &task_id, "progress").await;
+
+    let (_, run) = dispatcher
+        .add_webhook(&engine, &task_id, webhook(addr, json!({ "backfill": "all" })))
+        .await
+        .unwrap();
+    run.unwrap().run().await.unwrap();
+    // No live event has moved past the replay yet.
+    assert_eq!(dispatcher.pending_backfills(), 1);
+
+    assert!(engine.delete_task(&task_id).await.unwrap());
+    assert_eq!(dispatcher.pending_backfills(), 0);
+
+    let listeners = engine.lifecycle().listener_count();
+    drop(dispatcher);
+    assert_eq!(engine.lifecycle().listener_count(), listeners - 1);
+}
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use axum_test::http::{header, HeaderValue, StatusCode};
+use axum_test::TestServer;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use taskcast_core::{
+    BackoffStrategy, Level, MemoryBroadcastProvider, MemoryShortTermStore, RetryConfig, RetryJitter,
+    TaskEngine, TaskEngineOptions, TaskEvent, WebhookConfig,
+};
+use taskcast_server::{
+    create_app, create_app_with_options, AppOptions, AuthMode, CircuitBreakerConfig, CorsConfig,
+    JwtConfig, LogLevel, StderrHttpFailureLogger, WebhookDelivery,
+};
+
+const JWT_SECRET: &str = "webhook-circuit-test-secret-key-needs-to-be-long-enough";
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_server(auth_mode: AuthMode, delivery: Arc<WebhookDelivery>) -> TestServer {
+    let (app, _) = create_app_with_options(
+        make_engine(),
+        auth_mode,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            webhook_delivery: Some(delivery),
+            ..Default::default()
+        },
+    );
+    TestServer::new(app)
+}
+
+fn make_delivery() -> Arc<WebhookDelivery> {
+    Arc::new(WebhookDelivery::with_circuit_breaker(
+        CircuitBreakerConfig {
+            failure_threshold: 1,
+            cooldown_ms: 60_000,
+        },
+    ))
+}
+
+fn make_event() -> TaskEvent {
+    TaskEvent {
+        id: "evt-1".to_string(),
+        task_id: "task-1".to_string(),
+        index: 0,
+        timestamp: 0.0,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!(null),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// Trips the circuit for a target that refuses connections; returns its host key.
+async fn trip_circuit(delivery: &WebhookDelivery) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let config = WebhookConfig {
+        url: format!("http://{addr}/hook"),
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
+        filter: None,
+        secret: None,
+        wrap: None,
+        retry: Some(RetryConfig {
+            retries: 0,
+            backoff: BackoffStrategy::Fixed,
+            initial_delay_ms: 0,
+            max_delay_ms: 0,
+            timeout_ms: 1000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
+        }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
+    };
+    assert!(delivery.send(&make_event(), &config).await.is_err());
+    addr.to_string()
+}
+
+fn make_token(scope: &[&str]) -> String {
+    encode(
+        &Header::default(),
+        &json!({
+            "sub": "webhook-circuit-test",
+            "scope": scope,
+            "taskIds": "*",
+            "exp": 9999999999u64
+        }),
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+fn jwt_auth_mode() -> AuthMode {
+    AuthMode::Jwt(JwtConfig {
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
+        secret: Some(JWT_SECRET.to_string()),
+        public_key: None,
+        issuer: None,
+        audience: None,
+    })
+}
+
+#[tokio::test]
+async fn list_circuits_is_empty_when_nothing_tripped() {
+    let server = make_server(AuthMode::None, make_delivery());
+
+    let resp = server.get("/admin/webhooks/circuits").await;
+    resp.assert_status_ok();
+    resp.assert_json(&json!({ "circuits": [] }));
+}
+
+#[tokio::test]
+async fn list_circuits_reports_open_circuit() {
+    let delivery = make_delivery();
+    let host = trip_circuit(&delivery).await;
+    let server = make_server(AuthMode::None, delivery);
+
+    let body: serde_json::Value = server.get("/admin/webhooks/circuits").await.json();
+    let circuits = body["circuits"].as_array().unwrap();
+    assert_eq!(circuits.len(), 1);
+    assert_eq!(circuits[0]["host"], host);
+    assert_eq!(circuits[0]["state"], "open");
+    assert_eq!(circuits[0]["consecutiveFailures"], 1);
+    assert!(circuits[0]["openedAt"].as_u64().unwrap() > 0);
+}
+
+#[tokio::test]
+async fn reset_closes_circuit_and_unknown_host_is_404() {
+    let delivery = make_delivery();
+    let host = trip_circuit(&delivery).await;
+    let server = make_server(AuthMode::None, Arc::clone(&delivery));
+
+    let resp = server
+        .post(&format!("/admin/webhooks/circuits/{host}/reset"))
+        .await;
+    resp.assert_status_ok();
+    resp.assert_json(&json!({ "host": host, "reset": true }));
+    assert!(delivery.open_circuits().is_empty());
+
+    server
+        .get("/admin/webhooks/circuits")
+        .await
+        .assert_json(&json!({ "circuits": [] }));
+
+    let resp = server
+        .post(&format!("/admin/webhooks/circuits/{host}/reset"))
+        .await;
+    resp.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(resp.json::<serde_json::Value>()["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn circuit_endpoints_require_wildcard_scope() {
+    let server = make_server(jwt_auth_mode(), make_delivery());
+
+    let resp = server
+        .get("/admin/webhooks/circuits")
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", make_token(&["task:manage"]))).unwrap(),
+        )
+        .await;
+    resp.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(
+        resp.json::<serde_json::Value>()["details"]["requiredScope"],
+        "*"
+    );
+
+    let resp = server
+        .get("/admin/webhooks/circuits")
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", make_token(&["*"]))).unwrap(),
+        )
+        .await;
+    resp.assert_status_ok();
+}
+
+#[tokio::test]
+async fn circuit_endpoints_absent_without_webhook_delivery() {
+    let (app, _) = create_app(
+        make_engine(),
+        AuthMode::None,
+        None,
+        None,
+        CorsConfig::default(),
+    );
+    let server = TestServer::new(app);
+
+    server
+        .get("/admin/webhooks/circuits")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,320 @@
+//! Integration tests for the delivery permits of background webhooks: the
+//! global and per-task caps, permits released during retry backoff, and
+//! saturation reporting.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Router;
+use axum_test::TestServer;
+use serde_json::json;
+use taskcast_core::{
+    BackoffStrategy, Level, MemoryBroadcastProvider, MemoryShortTermStore, RetryConfig,
+    RetryJitter, TaskEngine, TaskEngineOptions, TaskEvent, TaskcastHooks, WebhookConfig,
+};
+use taskcast_server::{
+    create_app_with_options, AppOptions, AuthMode, DeliveryLimits, LogLevel,
+    StderrHttpFailureLogger, WebhookDelivery,
+};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+/// Mock receiver counting the requests it is serving, overall and per task.
+#[derive(Default)]
+struct Receiver {
+    delay_ms: AtomicU64,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+    received: AtomicUsize,
+    /// Per task: requests in flight and the most seen at once.
+    per_task: Mutex<HashMap<String, (usize, usize)>>,
+}
+
+impl Receiver {
+    fn max_for(&self, task_id: &str) -> usize {
+        self.per_task.lock().unwrap()[task_id].1
+    }
+}
+
+async fn receive(State(receiver): State<Arc<Receiver>>, body: String) -> StatusCode {
+    let event: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let task_id = event["taskId"].as_str().unwrap().to_string();
+    let now = receiver.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    receiver.max_in_flight.fetch_max(now, Ordering::SeqCst);
+    {
+        let mut per_task = receiver.per_task.lock().unwrap();
+        let (current, max) = per_task.entry(task_id.clone()).or_default();
+        *current += 1;
+        *max = (*max).max(*current);
+    }
+
+    let delay = receiver.delay_ms.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(delay)).await;
+
+    receiver
+        .per_task
+        .lock()
+        .unwrap()
+        .get_mut(&task_id)
+        .unwrap()
+        .0 -= 1;
+    receiver.in_flight.fetch_sub(1, Ordering::SeqCst);
+    receiver.received.fetch_add(1, Ordering::SeqCst);
+    StatusCode::OK
+}
+
+/// Serves `/hook` (after `delay_ms`) and `/fail` (an immediate 500).
+async fn spawn_receiver(delay_ms: u64) -> (SocketAddr, Arc<Receiver>) {
+    let receiver = Arc::new(Receiver {
+        delay_ms: AtomicU64::new(delay_ms),
+        ..Default::default()
+    });
+    let app = Router::new()
+        .route("/hook", post(receive))
+        .route(
+            "/fail",
+            post(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+        )
+        .with_state(Arc::clone(&receiver));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, receiver)
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("{task_id}-evt-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 0.0,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: json!(null),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+fn make_webhook(url: String, retries: u32, delay_ms: u64) -> WebhookConfig {
+    WebhookConfig {
+        url,
+        group: None,
+        suppression_window_ms: None,
+        mode: None,
+        filter: None,
+        secret: None,
+        wrap: None,
+        retry: Some(RetryConfig {
+            retries,
+            backoff: BackoffStrategy::Fixed,
+            initial_delay_ms: delay_ms,
+            max_delay_ms: delay_ms,
+            timeout_ms: 5000,
+            jitter: RetryJitter::None,
+            max_elapsed_ms: None,
+        }),
+        filter_preset: None,
+        backfill: None,
+        initial_delivery: None,
+        max_payload_bytes: None,
+        task_match: None,
+    }
+}
+
+fn limited(max_concurrent: usize, max_per_task: usize) -> WebhookDelivery {
+    WebhookDelivery::new().with_limits(DeliveryLimits {
+        max_concurrent,
+        max_per_task,
+        saturation_alert: Duration::from_secs(60),
+    })
+}
+
+/// Sends one event per entry of `task_ids` concurrently, returning once all
+/// deliveries have finished.
+async fn send_all(delivery: &Arc<WebhookDelivery>, webhook: &WebhookConfig, task_ids: &[&str]) {
+    let sends: Vec<_> = task_ids
+        .iter()
+        .enumerate()
+        .map(|(index, task_id)| {
+            let delivery = Arc::clone(delivery);
+            let webhook = webhook.clone();
+            let event = make_event(task_id, index as u64);
+            tokio::spawn(async move { delivery.send(&event, &webhook).await })
+        })
+        .collect();
+    for send in sends {
+        send.await.unwrap().unwrap();
+    }
+}
+
+#[derive(Default)]
+struct SaturationLog {
+    reports: Mutex<Vec<(usize, usize)>>,
+}
+
+impl TaskcastHooks for SaturationLog {
+    fn on_webhook_saturated(&self, in_use: usize, queued: usize, _saturated_for: Duration) {
+        self.reports.lock().unwrap().push((in_use, queued));
+    }
+}
+
+// ─── Caps ────────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn global_cap_is_never_exceeded() {
+    let (addr, receiver) = spawn_receiver(30).await;
+    let delivery = Arc::new(limited(4, 100));
+    let webhook = make_webhook(format!("http://{addr}/hook"), 0, 0);
+    let task_ids: Vec<String> = (0..24).map(|i| format!("task-{}", i % 6)).collect();
+    let task_ids: Vec<&str> = task_ids.iter().map(String::as_str).collect();
+
+    send_all(&delivery, &webhook, &task_ids).await;
+
+    assert_eq!(receiver.received.load(Ordering::SeqCst), 24);
+    assert_eq!(receiver.max_in_flight.load(Ordering::SeqCst), 4);
+    let concurrency = delivery.concurrency();
+    assert_eq!(concurrency.acquired, 24);
+    assert_eq!(concurrency.in_use, 0);
+    assert_eq!(concurrency.queued, 0);
+    assert!(concurrency.wait_ms_max > 0.0);
+    assert_eq!(concurrency.saturated_since, None);
+}
+
+#[tokio::test]
+async fn per_task_cap_leaves_room_for_other_tasks() {
+    let (addr, receiver) = spawn_receiver(30).await;
+    let delivery = Arc::new(limited(10, 2));
+    let webhook = make_webhook(format!("http://{addr}/hook"), 0, 0);
+    let mut task_ids = vec!["noisy"; 12];
+    task_ids.extend(["quiet-1", "quiet-2"]);
+
+    send_all(&delivery, &webhook, &task_ids).await;
+
+    assert_eq!(receiver.received.load(Ordering::SeqCst), 14);
+    assert_eq!(receiver.max_for("noisy"), 2);
+    assert!(receiver.max_in_flight.load(Ordering::SeqCst) > 2);
+}
+
+#[tokio::test]
+async fn retry_backoff_releases_its_permit() {
+    let (addr, receiver) = spawn_receiver(0).await;
+    let delivery = Arc::new(limited(1, 1));
+    let failing = make_webhook(format!("http://{addr}/fail"), 1, 500);
+    let healthy = make_webhook(format!("http://{addr}/hook"), 0, 0);
+
+    let retrying = {
+        let delivery = Arc::clone(&delivery);
+        let event = make_event("task-a", 0);
+        tokio::spawn(async move { delivery.send(&event, &failing).await })
+    };
+    // Let the first attempt fail and the retry start its backoff.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(delivery.concurrency().in_use, 0);
+
+    let started = Instant::now();
+    send_all(&delivery, &healthy, &["task-a", "task-b"]).await;
+    assert!(started.elapsed() < Duration::from_millis(300));
+    assert_eq!(receiver.received.load(Ordering::SeqCst), 2);
+    assert!(!retrying.is_finished());
+
+    assert!(retrying.await.unwrap().is_err());
+    assert_eq!(delivery.concurrency().acquired, 4);
+}
+
+// ─── Saturation ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn saturation_is_reported_and_clears_when_the_receiver_speeds_up() {
+    let (addr, receiver) = spawn_receiver(100).await;
+    let log = Arc::new(SaturationLog::default());
+    let delivery = Arc::new(WebhookDelivery::new().with_hooks(log.clone()).with_limits(
+        DeliveryLimits {
+            max_concurrent: 2,
+            max_per_task: 100,
+            saturation_alert: Duration::from_millis(50),
+        },
+    ));
+    let webhook = make_webhook(format!("http://{addr}/hook"), 0, 0);
+
+    let slow = {
+        let delivery = Arc::clone(&delivery);
+        let webhook = webhook.clone();
+        tokio::spawn(async move { send_all(&delivery, &webhook, &["task-a"; 6]).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let saturated = delivery.concurrency();
+    assert_eq!(saturated.in_use, 2);
+    assert_eq!(saturated.queued, 4);
+    assert!(saturated.saturated_since.is_some());
+    slow.await.unwrap();
+    let reports = log.reports.lock().unwrap().clone();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].0, 2);
+
+    receiver.delay_ms.store(0, Ordering::SeqCst);
+    let started = Instant::now();
+    send_all(&delivery, &webhook, &["task-a"; 20]).await;
+    assert!(started.elapsed() < Duration::from_millis(600));
+    assert_eq!(receiver.received.load(Ordering::SeqCst), 26);
+    let recovered = delivery.concurrency();
+    assert_eq!(recovered.in_use, 0);
+    assert_eq!(recovered.queued, 0);
+    assert_eq!(recovered.saturated_since, None);
+}
+
+// ─── Admin Endpoint ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn concurrency_endpoint_lists_permit_use() {
+    let (addr, _receiver) = spawn_receiver(0).await;
+    let delivery = Arc::new(limited(3, 2));
+    send_all(
+        &delivery,
+        &make_webhook(format!("http://{addr}/hook"), 0, 0),
+        &["task-a"],
+    )
+    .await;
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let (app, _) = create_app_with_options(
+        engine,
+        AuthMode::None,
+        AppOptions {
+            failure_logger: Some(Arc::new(StderrHttpFailureLogger::new(LogLevel::Error))),
+            webhook_delivery: Some(delivery),
+            ..Default::default()
+        },
+    );
+    let server = TestServer::new(app);
+
+    let res = server.get("/admin/webhooks/concurrency").await;
+    res.assert_status_ok();
+    let body: serde_json::Value = res.json();
+    assert_eq!(body["maxConcurrent"], 3);
+    assert_eq!(body["maxPerTask"], 2);
+    assert_eq!(body["inUse"], 0);
+    assert_eq!(body["queued"], 0);
+    assert_eq!(body["acquired"], 1);
+    assert!(body.get("saturatedSince").is_none());
+}
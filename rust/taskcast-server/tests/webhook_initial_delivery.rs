@@ -0,0 +1,338 @@
+//! Integration tests for webhook `initialDelivery`: a `task.snapshot` goes
+//! out when the webhook becomes active, ahead of backfilled and live events.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::http::{HeaderMap, StatusCode};
+use serde_json::{json, Value};
+use taskcast_core::{
+    CreateTaskInput, Level, MemoryBroadcastProvider, MemoryShortTermStore, PublishEventInput,
+    TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus, WebhookConfig,
+};
+use taskcast_server::{DispatchOutcome, WebhookDelivery, WebhookDispatcher, BACKFILL_HEADER};
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_engine() -> Arc<TaskEngine> {
+    Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }))
+}
+
+fn make_dispatcher(engine: &TaskEngine) -> WebhookDispatcher {
+    WebhookDispatcher::new(
+        Arc::new(WebhookDelivery::new()),
+        Arc::clone(engine.short_term_store()),
+    )
+}
+
+/// One request as received: its `X-Taskcast-Event` header, body and
+/// headers, and the status it was answered with.
+#[derive(Debug, Clone)]
+struct Received {
+    event: String,
+    body: Value,
+    headers: HeaderMap,
+    status: StatusCode,
+}
+
+type Requests = Arc<Mutex<Vec<Received>>>;
+
+/// Receiver that records every request, answering the first
+/// `failed_snapshots` snapshot attempts with a 503.
+async fn spawn_receiver(failed_snapshots: usize) -> (SocketAddr, Requests) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests: Requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&requests);
+    let app = axum::Router::new().fallback(move |headers: HeaderMap, body: String| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            let event = headers["x-taskcast-event"].to_str().unwrap().to_string();
+            let mut recorded = recorded.lock().unwrap();
+            let snapshots = recorded
+                .iter()
+                .filter(|r| r.event == "task.snapshot")
+                .count();
+            let status = if event == "task.snapshot" && snapshots < failed_snapshots {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            };
+            recorded.push(Received {
+                event,
+                body: serde_json::from_str(&body).unwrap(),
+                headers,
+                status,
+            });
+            status
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, requests)
+}
+
+async fn publish(engine: &TaskEngine, task_id: &str, r#type: &str) -> TaskEvent {
+    engine
+        .publish_event(
+            task_id,
+            PublishEventInput {
+                r#type: r#type.to_string(),
+                level: Level::Info,
+                data: json!({}),
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        )
+        .await
+        .unwrap()
+}
+
+fn webhook(addr: SocketAddr, extra: Value) -> WebhookConfig {
+    let mut config = json!({ "url": format!("http://{addr}/hook") });
+    config
+        .as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(config).unwrap()
+}
+
+/// `(X-Taskcast-Event, event index)` of each successful request; the
+/// snapshot's index is its `lastEventIndex`.
+fn delivered(requests: &Requests) -> Vec<(String, Option<u64>)> {
+    requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| r.status == StatusCode::OK)
+        .map(|r| {
+            let index = if r.event == "task.snapshot" {
+                r.body["lastEventIndex"].as_u64()
+            } else {
+                r.body["index"].as_u64()
+            };
+            (r.event.clone(), index)
+        })
+        .collect()
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn snapshot_of_a_new_task_arrives_before_its_events() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, requests) = spawn_receiver(0).await;
+    let task = engine
+        .create_task(CreateTaskInput {
+            r#type: Some("report".to_string()),
+            params: Some([("month".to_string(), json!("2026-09"))].into()),
+            webhooks: Some(vec![
+                webhook(addr, json!({ "initialDelivery": true, "secret": "s3cret" })),
+                webhook(
+                    addr,
+                    json!({ "initialDelivery": false, "filter": { "types": ["log"] } }),
+                ),
+            ]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let runs = dispatcher.task_created(&engine, &task);
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].webhook(), 0);
+    let snapshot = tokio::spawn(runs.into_iter().next().unwrap().run());
+    while requests.lock().unwrap().is_empty() {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let running = engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let status = engine.get_events(&task.id, None).await.unwrap().remove(0);
+    dispatcher.dispatch(&running, &status).await.unwrap();
+    let dispatched = snapshot.await.unwrap().unwrap();
+    assert_eq!(dispatched.len(), 1);
+    assert!(matches!(dispatched[0].outcome, DispatchOutcome::Delivered));
+
+    let requests = requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 2);
+    let first = &requests[0];
+    assert_eq!(first.event, "task.snapshot");
+    assert_eq!(first.body["kind"], "task.snapshot");
+    assert_eq!(first.body["task"]["id"], task.id.as_str());
+    assert_eq!(first.body["task"]["type"], "report");
+    assert_eq!(first.body["task"]["params"], json!({ "month": "2026-09" }));
+    assert_eq!(first.body["task"]["status"], "pending");
+    assert_eq!(first.body["lastEventIndex"], Value::Null);
+    assert!(first.headers.contains_key("x-taskcast-signature"));
+    assert!(!first.headers.contains_key(BACKFILL_HEADER));
+    assert_eq!(requests[1].event, "taskcast:status");
+    assert_eq!(requests[1].body["index"], status.index);
+}
+
+#[tokio::test]
+async fn snapshot_then_backfill_then_live_events() {
+    let engine = make_engine();
+    let dispatcher = Arc::new(make_dispatcher(&engine));
+    let (addr, requests) = spawn_receiver(0).await;
+    let task = engine
+        .create_task(CreateTaskInput::default())
+        .await
+        .unwrap();
+    engine
+        .transition_task(&task.id, TaskStatus::Running, None)
+        .await
+        .unwrap();
+    let first = publish(&engine, &task.id, "progress").await;
+    let second = publish(&engine, &task.id, "progress").await;
+
+    let config = webhook(
+        addr,
+        json!({
+            "initialDelivery": true,
+            "filter": { "types": ["progress"] },
+            "backfill": "all",
+        }),
+    );
+    let (task, run) = dispatcher
+        .add_webhook(&engine, &task.id, config)
+        .await
+        .unwrap();
+    let run = tokio::spawn(run.expect("initial deliveries requested").run());
+    // Once replay has read history, a new event is held back until it ends.
+    while requests.lock().unwrap().len() < 2 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    let live = publish(&engine, &task.id, "progress").await;
+    let dispatched = dispatcher.dispatch(&task, &live).await.unwrap();
+    assert!(matches!(dispatched[0].outcome, DispatchOutcome::Delivered));
+    assert_eq!(run.await.unwrap().unwrap().len(), 3);
+
+    let snapshot = requests.lock().unwrap()[0].body.clone();
+    assert_eq!(snapshot["task"]["status"], "running");
+    let through = snapshot["lastEventIndex"].as_u64().unwrap();
+    assert!(through >= second.index);
+    let delivered = delivered(&requests);
+    assert_eq!(delivered[0].0, "task.snapshot");
+    assert_eq!(
+        delivered[1..],
+        [
+            ("progress".to_string(), Some(first.index)),
+            ("progress".to_string(), Some(second.index)),
+            ("progress".to_string(), Some(live.index)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn snapshot_retries_hold_back_live_events() {
+    let engine = make_engine();
+    let dispatcher = Arc::new(make_dispatcher(&engine));
+    let (addr, requests) = spawn_receiver(2).await;
+    let config = webhook(
+        addr,
+        json!({
+            "initialDelivery": true,
+            "retry": {
+                "retries": 3,
+                "backoff": "fixed",
+                "initialDelayMs": 50,
+                "maxDelayMs": 50,
+                "timeoutMs": 1000,
+            },
+        }),
+    );
+    let task = engine
+        .create_task(CreateTaskInput {
+            webhooks: Some(vec![config]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let run = dispatcher.task_created(&engine, &task).pop().unwrap();
+    let snapshot = tokio::spawn(run.run());
+
+    let live = publish(&engine, &task.id, "log").await;
+    let live_dispatch = {
+        let dispatcher = Arc::clone(&dispatcher);
+        let task = task.clone();
+        tokio::spawn(async move { dispatcher.dispatch(&task, &live).await.unwrap() })
+    };
+    let dispatched = snapshot.await.unwrap().unwrap();
+    assert!(matches!(dispatched[0].outcome, DispatchOutcome::Delivered));
+    live_dispatch.await.unwrap();
+
+    let events: Vec<(String, StatusCode)> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|r| (r.event.clone(), r.status))
+        .collect();
+    let snapshot = "task.snapshot".to_string();
+    assert_eq!(
+        events,
+        [
+            (snapshot.clone(), StatusCode::SERVICE_UNAVAILABLE),
+            (snapshot.clone(), StatusCode::SERVICE_UNAVAILABLE),
+            (snapshot, StatusCode::OK),
+            ("log".to_string(), StatusCode::OK),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn without_initial_delivery_nothing_changes() {
+    let engine = make_engine();
+    let dispatcher = make_dispatcher(&engine);
+    let (addr, requests) = spawn_receiver(0).await;
+    let task = engine
+        .create_task(CreateTaskInput {
+            webhooks: Some(vec![webhook(addr, json!({}))]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(dispatcher.task_created(&engine, &task).is_empty());
+
+    let (task, run) = dispatcher
+        .add_webhook(
+            &engine,
+            &task.id,
+            webhook(addr, json!({ "initialDelivery": false })),
+        )
+        .await
+        .unwrap();
+    assert!(run.is_none());
+    assert_eq!(dispatcher.pending_backfills(), 0);
+    assert_eq!(
+        task.webhooks.as_ref().unwrap()[1].initial_delivery,
+        Some(false)
+    );
+
+    let live = publish(&engine, &task.id, "log").await;
+    dispatcher.dispatch(&task, &live).await.unwrap();
+    assert_eq!(
+        delivered(&requests),
+        [
+            ("log".to_string(), Some(live.index)),
+            ("log".to_string(), Some(live.index)),
+        ]
+    );
+}
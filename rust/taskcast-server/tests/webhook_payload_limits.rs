@@ -0,0 +1,302 @@
+//! Integration tests for webhook payload limits, trimmed deliveries and the
+//! single-event route their `fetchUrl` points at.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use axum_test::http::header::AUTHORIZATION;
+use axum_test::http::{HeaderValue, StatusCode};
+use axum_test::TestServer;
+use serde_json::{json, Value};
+use taskcast_core::config::{HttpConfig, TaskcastConfig, WebhookGlobalConfig};
+use taskcast_core::{
+    MemoryBroadcastProvider, MemoryShortTermStore, PermissionScope, TaskEngine, TaskEngineOptions,
+};
+use taskcast_server::{
+    create_app, hash_api_key, ApiKeyEntry, AuthMode, CorsConfig, EventLinks, TaskIdAccess,
+};
+
+const BASE_URL: &str = "http://taskcast.test";
+const LINK_SECRET: &str = "link-secret";
+const ADMIN_KEY: &str = "tck_admin-key";
+const SUBSCRIBER_KEY: &str = "tck_subscriber-key";
+
+// ─── Test Helpers ────────────────────────────────────────────────────────────
+
+fn make_server(max_payload_bytes: Option<usize>) -> TestServer {
+    let engine = Arc::new(TaskEngine::new(TaskEngineOptions {
+        short_term_store: Arc::new(MemoryShortTermStore::new()),
+        broadcast: Arc::new(MemoryBroadcastProvider::new()),
+        long_term_store: None,
+        hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
+    }));
+    let auth_mode = AuthMode::ApiKeys(vec![
+        ApiKeyEntry {
+            name: "admin".to_string(),
+            key_hash: hash_api_key(ADMIN_KEY),
+            task_ids: TaskIdAccess::All,
+            scope: vec![PermissionScope::All],
+        },
+        ApiKeyEntry {
+            name: "subscriber".to_string(),
+            key_hash: hash_api_key(SUBSCRIBER_KEY),
+            task_ids: TaskIdAccess::All,
+            scope: vec![PermissionScope::EventSubscribe],
+        },
+    ]);
+    let config = TaskcastConfig {
+        server_base_url: Some(BASE_URL.to_string()),
+        webhook: Some(WebhookGlobalConfig {
+            default_retry: None,
+            sync_timeout_ms: None,
+            max_sync_webhooks: None,
+            max_payload_bytes,
+            fetch_link_secret: Some(LINK_SECRET.to_string()),
+            fetch_link_ttl_ms: None,
+            max_concurrent_deliveries: None,
+            max_concurrent_per_task: None,
+            saturation_alert_ms: None,
+        }),
+        // The receivers below listen on plain HTTP.
+        http: Some(HttpConfig {
+            allow_insecure_webhooks: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let (app, _) = create_app(engine, auth_mode, None, Some(config), CorsConfig::default());
+    TestServer::new(app)
+}
+
+fn bearer(key: &str) -> HeaderValue {
+    HeaderValue::from_str(&format!("Bearer {key}")).unwrap()
+}
+
+type Deliveries = Arc<Mutex<Vec<(String, Value)>>>;
+
+/// Receiver that records the path and JSON body of every delivery.
+async fn spawn_receiver() -> (SocketAddr, Deliveries) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let deliveries: Deliveries = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&deliveries);
+    let app = axum::Router::new().fallback(move |uri: axum::http::Uri, body: String| {
+        let recorded = Arc::clone(&recorded);
+        async move {
+            let body = serde_json::from_str(&body).unwrap_or(Value::Null);
+            recorded
+                .lock()
+                .unwrap()
+                .push((uri.path().to_string(), body));
+            StatusCode::OK
+        }
+    });
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, deliveries)
+}
+
+async fn create_running_task(server: &TestServer, webhooks: Value) -> String {
+    let res = server
+        .post("/tasks")
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .json(&json!({ "webhooks": webhooks }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    let task_id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+    server
+        .patch(&format!("/tasks/{task_id}/status"))
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .json(&json!({ "status": "running" }))
+        .await
+        .assert_status_ok();
+    task_id
+}
+
+/// Publishes an event whose `data` serializes to a little over `size` bytes.
+async fn publish(server: &TestServer, task_id: &str, size: usize) -> Value {
+    let res = server
+        .post(&format!("/tasks/{task_id}/events"))
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .json(&json!({ "type": "report.chunk", "level": "info", "data": { "blob": "x".repeat(size) } }))
+        .await;
+    res.assert_status(StatusCode::CREATED);
+    res.json()
+}
+
+fn delivered_to(deliveries: &Deliveries, path: &str) -> Value {
+    deliveries
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|(p, _)| p == path)
+        .map(|(_, body)| body.clone())
+        .unwrap_or_else(|| panic!("nothing delivered to {path}"))
+}
+
+/// The path and query of a `fetchUrl`, to request from the test server.
+fn local_path(fetch_url: &str) -> &str {
+    fetch_url.strip_prefix(BASE_URL).unwrap()
+}
+
+// ─── Trimming ────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn oversized_delivery_is_trimmed_with_a_working_fetch_url() {
+    let (addr, deliveries) = spawn_receiver().await;
+    let server = make_server(None);
+    let task_id = create_running_task(
+        &server,
+        json!([
+            { "url": format!("http://{addr}/limited"), "mode": "sync", "maxPayloadBytes": 1024 },
+            { "url": format!("http://{addr}/unlimited"), "mode": "sync" },
+        ]),
+    )
+    .await;
+
+    let published = publish(&server, &task_id, 4096).await;
+    let full_data = json!({ "blob": "x".repeat(4096) });
+
+    // Limits are per webhook: only the limited receiver gets the trimmed body.
+    assert_eq!(delivered_to(&deliveries, "/unlimited")["data"], full_data);
+    let trimmed = delivered_to(&deliveries, "/limited");
+    assert_eq!(trimmed["id"], published["id"]);
+    assert_eq!(trimmed["type"], "report.chunk");
+    assert_eq!(trimmed["data"]["$truncated"], true);
+    assert_eq!(
+        trimmed["data"]["bytes"],
+        serde_json::to_string(&full_data).unwrap().len()
+    );
+    let fetch_url = trimmed["data"]["fetchUrl"].as_str().unwrap();
+    let event_id = published["id"].as_str().unwrap();
+    assert!(fetch_url.starts_with(&format!("{BASE_URL}/v1/tasks/{task_id}/events/{event_id}?")));
+
+    // The link works without credentials and returns the whole event.
+    let res = server.get(local_path(fetch_url)).await;
+    res.assert_status_ok();
+    let event: Value = res.json();
+    assert_eq!(event["id"], published["id"]);
+    assert_eq!(event["data"], full_data);
+}
+
+#[tokio::test]
+async fn under_limit_payloads_are_untouched_and_the_global_default_applies() {
+    let (addr, deliveries) = spawn_receiver().await;
+    let server = make_server(Some(1024));
+    let task_id = create_running_task(
+        &server,
+        json!([{ "url": format!("http://{addr}/hook"), "mode": "sync" }]),
+    )
+    .await;
+
+    publish(&server, &task_id, 16).await;
+    assert_eq!(
+        delivered_to(&deliveries, "/hook")["data"],
+        json!({ "blob": "x".repeat(16) })
+    );
+
+    publish(&server, &task_id, 4096).await;
+    assert_eq!(
+        delivered_to(&deliveries, "/hook")["data"]["$truncated"],
+        true
+    );
+}
+
+// ─── Single-event route ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn single_event_route_enforces_auth_and_404s() {
+    let server = make_server(None);
+    let task_id = create_running_task(&server, json!([])).await;
+    let published = publish(&server, &task_id, 16).await;
+    let path = format!(
+        "/tasks/{task_id}/events/{}",
+        published["id"].as_str().unwrap()
+    );
+
+    let res = server
+        .get(&format!("/v1{path}"))
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .await;
+    res.assert_status_ok();
+    assert_eq!(
+        res.json::<Value>()["data"],
+        json!({ "blob": "x".repeat(16) })
+    );
+
+    server
+        .get(&path)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .get(&path)
+        .add_header(AUTHORIZATION, bearer(SUBSCRIBER_KEY))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let res = server
+        .get(&format!("/tasks/{task_id}/events/01UNKNOWNEVENT"))
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .await;
+    res.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(res.json::<Value>()["code"], "NOT_FOUND");
+    server
+        .get("/tasks/missing/events/01UNKNOWNEVENT")
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    // The static sibling routes still resolve.
+    server
+        .get(&format!("/tasks/{task_id}/events/history"))
+        .add_header(AUTHORIZATION, bearer(ADMIN_KEY))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn signed_links_expire_and_are_bound_to_their_event() {
+    let server = make_server(None);
+    let task_id = create_running_task(&server, json!([])).await;
+    let first = publish(&server, &task_id, 16).await;
+    let second = publish(&server, &task_id, 16).await;
+    let first_id = first["id"].as_str().unwrap();
+    let second_id = second["id"].as_str().unwrap();
+    let links = EventLinks::new(Some(BASE_URL), Some(LINK_SECRET), Duration::from_secs(60));
+
+    let fresh = links.fetch_url(&task_id, first_id).unwrap();
+    server.get(local_path(&fresh)).await.assert_status_ok();
+
+    let expired = links
+        .fetch_url_at(
+            &task_id,
+            first_id,
+            SystemTime::now() - Duration::from_secs(120),
+        )
+        .unwrap();
+    let res = server.get(local_path(&expired)).await;
+    res.assert_status(StatusCode::UNAUTHORIZED);
+    assert_eq!(res.json::<Value>()["code"], "INVALID_TOKEN");
+
+    // A link for one event does not open another.
+    let swapped = fresh.replace(first_id, second_id);
+    server
+        .get(local_path(&swapped))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // Nor does one signed with a different key.
+    let forged = EventLinks::new(Some(BASE_URL), Some("guess"), Duration::from_secs(60))
+        .fetch_url(&task_id, first_id)
+        .unwrap();
+    server
+        .get(local_path(&forged))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
@@ -21,6 +21,9 @@ fn make_no_auth_server_with_workers() -> (Arc<TaskEngine>, Arc<WorkerManager>, T
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -49,6 +52,9 @@ fn make_jwt_server_with_workers() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestS
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -59,7 +65,7 @@ fn make_jwt_server_with_workers() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestS
         defaults: None,
     }));
     let auth_mode = AuthMode::Jwt(JwtConfig {
-        algorithm: jsonwebtoken::Algorithm::HS256,
+        algorithm: taskcast_core::config::JwtAlgorithm::HS256,
         secret: Some(JWT_SECRET.to_string()),
         public_key: None,
         issuer: None,
@@ -124,7 +124,7 @@ impl ShortTermStore for FailingShortTermStore {
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         self.inner
             .replace_last_series_event(task_id, series_id, event)
             .await
@@ -229,6 +229,9 @@ fn make_ws_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, TestServer) {
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
@@ -258,6 +261,9 @@ fn make_failing_ws_server() -> (Arc<TaskEngine>, Arc<WorkerManager>, Arc<Failing
         broadcast: Arc::clone(&broadcast) as Arc<dyn BroadcastProvider>,
         long_term_store: None,
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     }));
     let manager = Arc::new(WorkerManager::new(WorkerManagerOptions {
         engine: Arc::clone(&engine),
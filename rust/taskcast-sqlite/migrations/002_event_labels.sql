@@ -0,0 +1 @@
+ALTER TABLE taskcast_events ADD COLUMN labels TEXT
@@ -0,0 +1 @@
+ALTER TABLE taskcast_tasks ADD COLUMN filters TEXT
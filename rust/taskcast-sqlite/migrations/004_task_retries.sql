@@ -0,0 +1,11 @@
+ALTER TABLE taskcast_tasks ADD COLUMN retry_policy TEXT;
+
+CREATE TABLE IF NOT EXISTS taskcast_retry_schedules (
+  task_id TEXT PRIMARY KEY,
+  successor_id TEXT NOT NULL,
+  attempt INTEGER NOT NULL,
+  due_at REAL NOT NULL,
+  claimed_until REAL
+);
+
+CREATE INDEX IF NOT EXISTS idx_retry_schedules_due ON taskcast_retry_schedules(due_at)
@@ -0,0 +1,9 @@
+ALTER TABLE taskcast_tasks ADD COLUMN group_policy TEXT;
+
+CREATE TABLE IF NOT EXISTS taskcast_webhook_suppressions (
+  task_id TEXT NOT NULL,
+  webhook INTEGER NOT NULL,
+  fingerprint TEXT NOT NULL,
+  delivered_at REAL NOT NULL,
+  PRIMARY KEY (task_id, webhook, fingerprint)
+)
\ No newline at end of file
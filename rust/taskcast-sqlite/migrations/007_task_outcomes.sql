@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS taskcast_outcomes (
+  task_id TEXT NOT NULL,
+  type TEXT,
+  status TEXT NOT NULL,
+  completed_at REAL NOT NULL,
+  error_code TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_outcomes_completed ON taskcast_outcomes(completed_at, task_id)
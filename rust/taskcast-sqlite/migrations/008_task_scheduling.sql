@@ -0,0 +1,9 @@
+ALTER TABLE taskcast_tasks ADD COLUMN scheduled_for INTEGER;
+
+CREATE TABLE IF NOT EXISTS taskcast_task_activations (
+  task_id TEXT PRIMARY KEY,
+  activate_at REAL NOT NULL,
+  claimed_until REAL
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_activations_due ON taskcast_task_activations(activate_at)
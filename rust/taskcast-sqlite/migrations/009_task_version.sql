@@ -0,0 +1 @@
+ALTER TABLE taskcast_tasks ADD COLUMN version INTEGER NOT NULL DEFAULT 0
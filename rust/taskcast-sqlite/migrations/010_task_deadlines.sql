@@ -0,0 +1,7 @@
+ALTER TABLE taskcast_tasks ADD COLUMN deadline_ms INTEGER;
+
+CREATE TABLE IF NOT EXISTS taskcast_deadline_warnings (
+  task_id TEXT NOT NULL,
+  warning TEXT NOT NULL,
+  PRIMARY KEY (task_id, warning)
+)
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_tasks_created ON taskcast_tasks(created_at, id)
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS taskcast_filtered_indices (
+  task_id TEXT NOT NULL,
+  consumer TEXT NOT NULL,
+  filter_hash TEXT NOT NULL,
+  epoch INTEGER NOT NULL,
+  next INTEGER NOT NULL,
+  through INTEGER,
+  PRIMARY KEY (task_id, consumer)
+)
@@ -0,0 +1,3 @@
+ALTER TABLE taskcast_tasks ADD COLUMN origin TEXT;
+
+ALTER TABLE taskcast_outcomes ADD COLUMN origin TEXT
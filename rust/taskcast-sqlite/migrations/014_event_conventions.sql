@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS taskcast_event_conventions (
+  task_type TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  estimated_count INTEGER NOT NULL DEFAULT 0,
+  last_seen_at REAL,
+  example TEXT,
+  example_at REAL,
+  description TEXT,
+  data_schema TEXT,
+  PRIMARY KEY (task_type, event_type)
+)
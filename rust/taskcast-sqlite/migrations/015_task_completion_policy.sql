@@ -0,0 +1,3 @@
+ALTER TABLE taskcast_tasks ADD COLUMN parent_id TEXT;
+
+ALTER TABLE taskcast_tasks ADD COLUMN completion_policy TEXT
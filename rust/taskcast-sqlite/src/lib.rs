@@ -40,13 +40,38 @@ pub async fn create_sqlite_adapters(
 }
 
 async fn run_migrations(pool: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
-    let migration_sql = include_str!("../migrations/001_initial.sql");
+    let migrations = [
+        include_str!("../migrations/001_initial.sql"),
+        include_str!("../migrations/002_event_labels.sql"),
+        include_str!("../migrations/003_task_filters.sql"),
+        include_str!("../migrations/004_task_retries.sql"),
+        include_str!("../migrations/005_webhook_groups.sql"),
+        include_str!("../migrations/006_task_forwarding.sql"),
+        include_str!("../migrations/007_task_outcomes.sql"),
+        include_str!("../migrations/008_task_scheduling.sql"),
+        include_str!("../migrations/009_task_version.sql"),
+        include_str!("../migrations/010_task_deadlines.sql"),
+        include_str!("../migrations/011_task_created_index.sql"),
+        include_str!("../migrations/012_filtered_indices.sql"),
+        include_str!("../migrations/013_task_origin.sql"),
+        include_str!("../migrations/014_event_conventions.sql"),
+        include_str!("../migrations/015_task_completion_policy.sql"),
+    ];
 
     // Split on semicolons and execute each statement individually
-    for statement in migration_sql.split(';') {
-        let trimmed = statement.trim();
-        if !trimmed.is_empty() {
-            sqlx::query(trimmed).execute(pool).await?;
+    for migration_sql in migrations {
+        for statement in migration_sql.split(';') {
+            let trimmed = statement.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            // SQLite has no `ADD COLUMN IF NOT EXISTS`; re-running an applied
+            // ALTER fails with "duplicate column name", which is safe to skip.
+            if let Err(err) = sqlx::query(trimmed).execute(pool).await {
+                if !err.to_string().contains("duplicate column name") {
+                    return Err(err.into());
+                }
+            }
         }
     }
 
@@ -2,6 +2,9 @@ use async_trait::async_trait;
 use sqlx::{Row, Sqlite, SqlitePool, Transaction};
 use std::collections::BTreeSet;
 
+use taskcast_core::conventions::{ConventionNotes, EventConvention, EventConventionStore};
+use taskcast_core::filter::matches_labels;
+use taskcast_core::integrity::IntegrityMonitor;
 use taskcast_core::types::{
     EventQueryOptions, LongTermStore, SeriesMode, Task, TaskArchiveImportOptions,
     TaskArchiveRestoreData, TaskEvent, WorkerAuditEvent,
@@ -9,13 +12,15 @@ use taskcast_core::types::{
 
 use crate::row_helpers::{
     assign_mode_to_string, audit_action_to_string, disconnect_policy_to_string,
-    json_value_to_string, level_to_string, row_to_event, row_to_task, row_to_worker_audit_event,
-    series_mode_to_string, status_to_string, to_json_string,
+    group_policy_to_string, json_value_to_string, labels_to_string, level_to_string,
+    redact_event_row, row_to_event, row_to_task, row_to_worker_audit_event, series_mode_to_string,
+    status_to_string, to_json_string,
 };
 
 pub struct SqliteLongTermStore {
     pool: SqlitePool,
     shares_task_archive_restore_storage: bool,
+    integrity: IntegrityMonitor,
 }
 
 async fn insert_event_in_sqlite_tx(
@@ -29,9 +34,10 @@ async fn insert_event_in_sqlite_tx(
     sqlx::query(
         r#"
         INSERT INTO taskcast_events (
-            id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+            id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+            labels
         ) VALUES (
-            ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+            ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
         )
         ON CONFLICT (id) DO NOTHING
         "#,
@@ -46,6 +52,7 @@ async fn insert_event_in_sqlite_tx(
     .bind(&event.series_id)
     .bind(&series_mode_str)
     .bind(&event.series_acc_field)
+    .bind(labels_to_string(&event.labels))
     .execute(&mut **tx)
     .await?;
 
@@ -85,13 +92,23 @@ impl SqliteLongTermStore {
         Self {
             pool,
             shares_task_archive_restore_storage: false,
+            integrity: IntegrityMonitor::default(),
         }
     }
 
     pub fn new_shared_archive_restore_storage(pool: SqlitePool) -> Self {
         Self {
-            pool,
             shares_task_archive_restore_storage: true,
+            ..Self::new(pool)
+        }
+    }
+
+    /// Use `monitor` for rows that fail to decode on read. The default monitor
+    /// surfaces them (see `CorruptRecordPolicy`).
+    pub fn with_integrity(self, monitor: IntegrityMonitor) -> Self {
+        Self {
+            integrity: monitor,
+            ..self
         }
     }
 
@@ -148,6 +165,13 @@ impl LongTermStore for SqliteLongTermStore {
         let webhooks_json = to_json_string(&task.webhooks);
         let cleanup_json = to_json_string(&task.cleanup);
         let tags_json = to_json_string(&task.tags);
+        let filters_json = to_json_string(&task.filters);
+        let retry_policy_json = to_json_string(&task.retry_policy);
+        let forward_to_json = to_json_string(&task.forward_to);
+        let origin_json = to_json_string(&task.origin);
+        let completion_policy_json = to_json_string(&task.completion_policy);
+        let group_policy_str: Option<String> =
+            task.group_policy.as_ref().map(group_policy_to_string);
         let assign_mode_str: Option<String> = task.assign_mode.as_ref().map(assign_mode_to_string);
         let cost = task.cost.map(|v| v as i32);
         let disconnect_policy_str: Option<String> = task
@@ -165,10 +189,12 @@ impl LongTermStore for SqliteLongTermStore {
             INSERT INTO taskcast_tasks (
                 id, type, status, params, result, error, metadata,
                 auth_config, webhooks, cleanup, created_at, updated_at, completed_at, ttl,
-                tags, assign_mode, cost, assigned_worker, disconnect_policy
+                tags, assign_mode, cost, assigned_worker, disconnect_policy, filters,
+                retry_policy, group_policy, forward_to, scheduled_for, version,
+                deadline_ms, origin, parent_id, completion_policy
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
-                ?15, ?16, ?17, ?18, ?19
+                ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29
             )
             ON CONFLICT (id) DO UPDATE SET
                 status = excluded.status,
@@ -178,7 +204,14 @@ impl LongTermStore for SqliteLongTermStore {
                 updated_at = excluded.updated_at,
                 completed_at = excluded.completed_at,
                 cost = excluded.cost,
-                assigned_worker = excluded.assigned_worker
+                assigned_worker = excluded.assigned_worker,
+                filters = excluded.filters,
+                retry_policy = excluded.retry_policy,
+                group_policy = excluded.group_policy,
+                forward_to = excluded.forward_to,
+                parent_id = excluded.parent_id,
+                completion_policy = excluded.completion_policy,
+                version = excluded.version
             "#,
         )
         .bind(&task.id)
@@ -200,6 +233,16 @@ impl LongTermStore for SqliteLongTermStore {
         .bind(cost)
         .bind(&task.assigned_worker)
         .bind(&disconnect_policy_str)
+        .bind(&filters_json)
+        .bind(&retry_policy_json)
+        .bind(&group_policy_str)
+        .bind(&forward_to_json)
+        .bind(task.scheduled_for.map(|v| v as i64))
+        .bind(task.version as i64)
+        .bind(task.deadline_ms.map(|v| v as i64))
+        .bind(&origin_json)
+        .bind(&task.parent_id)
+        .bind(&completion_policy_json)
         .execute(&self.pool)
         .await?;
 
@@ -215,7 +258,10 @@ impl LongTermStore for SqliteLongTermStore {
             .fetch_optional(&self.pool)
             .await?;
 
-        Ok(row.as_ref().map(row_to_task))
+        match row {
+            Some(row) => Ok(self.integrity.task(row_to_task(&row))?),
+            None => Ok(None),
+        }
     }
 
     async fn save_event(
@@ -232,9 +278,10 @@ impl LongTermStore for SqliteLongTermStore {
         sqlx::query(
             r#"
             INSERT INTO taskcast_events (
-                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+                labels
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
             )
             ON CONFLICT (id) DO NOTHING
             "#,
@@ -249,6 +296,7 @@ impl LongTermStore for SqliteLongTermStore {
         .bind(&event.series_id)
         .bind(&series_mode_str)
         .bind(&event.series_acc_field)
+        .bind(labels_to_string(&event.labels))
         .execute(&self.pool)
         .await?;
 
@@ -276,7 +324,8 @@ impl LongTermStore for SqliteLongTermStore {
         .fetch_all(&mut *tx)
         .await?;
 
-        if let Some(existing) = rows.first().map(row_to_event) {
+        // A corrupt series event cannot be compacted, so the write fails.
+        if let Some(existing) = rows.first().map(row_to_event).transpose()? {
             let level_str = level_to_string(&event.level);
             let series_mode_str = event.series_mode.as_ref().and_then(series_mode_to_string);
             let data_str = json_value_to_string(&event.data);
@@ -289,8 +338,9 @@ impl LongTermStore for SqliteLongTermStore {
                     data = ?4,
                     series_id = ?5,
                     series_mode = ?6,
-                    series_acc_field = ?7
-                WHERE id = ?8
+                    series_acc_field = ?7,
+                    labels = ?8
+                WHERE id = ?9
                 "#,
             )
             .bind(event.timestamp as i64)
@@ -300,6 +350,7 @@ impl LongTermStore for SqliteLongTermStore {
             .bind(&event.series_id)
             .bind(&series_mode_str)
             .bind(&event.series_acc_field)
+            .bind(labels_to_string(&event.labels))
             .bind(&existing.id)
             .execute(&mut *tx)
             .await?;
@@ -345,8 +396,8 @@ impl LongTermStore for SqliteLongTermStore {
         .fetch_all(&mut *tx)
         .await?;
 
-        let first = rows.first().map(row_to_event);
-        let previous = rows.last().map(row_to_event);
+        let first = rows.first().map(row_to_event).transpose()?;
+        let previous = rows.last().map(row_to_event).transpose()?;
         let accumulated = if let Some(previous) = previous {
             accumulate_task_event(&previous, event, field)
         } else {
@@ -369,8 +420,9 @@ impl LongTermStore for SqliteLongTermStore {
                     data = ?4,
                     series_id = ?5,
                     series_mode = ?6,
-                    series_acc_field = ?7
-                WHERE id = ?8
+                    series_acc_field = ?7,
+                    labels = ?8
+                WHERE id = ?9
                 "#,
             )
             .bind(accumulated.timestamp as i64)
@@ -380,6 +432,7 @@ impl LongTermStore for SqliteLongTermStore {
             .bind(&accumulated.series_id)
             .bind(&series_mode_str)
             .bind(&accumulated.series_acc_field)
+            .bind(labels_to_string(&accumulated.labels))
             .bind(&first.id)
             .execute(&mut *tx)
             .await?;
@@ -410,28 +463,51 @@ impl LongTermStore for SqliteLongTermStore {
     ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
         let since = opts.as_ref().and_then(|o| o.since.as_ref());
         let limit = opts.as_ref().and_then(|o| o.limit);
-
-        let limit_val = limit.map(|l| l as i64).unwrap_or(i64::MAX);
+        let label_selector = opts.as_ref().and_then(|o| o.label_selector.as_ref());
+        // Timestamps are stored as whole milliseconds; a NULL bound keeps every
+        // event.
+        let until = opts
+            .as_ref()
+            .and_then(|o| o.until)
+            .map(|until| until.floor() as i64);
+        let before_index = opts
+            .as_ref()
+            .and_then(|o| o.before_index)
+            .map(|index| index as i64);
+
+        // When no limit is specified, use a very large value (effectively unlimited).
+        // Label selectors are evaluated after the query, so the limit is applied there.
+        let limit_val = match (limit, label_selector) {
+            (Some(l), None) => l as i64,
+            _ => i64::MAX,
+        };
 
         let rows = if let Some(since) = since {
             if let Some(ref id) = since.id {
                 // since.id takes priority: look up anchor's idx, then fetch events after it.
-                // COALESCE ensures that if the anchor is not found, we return all events (idx > -1).
+                // The anchor is resolved within this task only; COALESCE ensures that if
+                // it is not found, we return all events (idx > -1).
+                // `UNIQUE(task_id, idx)` rules out ties, so comparing indexes alone
+                // resumes right after the anchor in the canonical order.
                 sqlx::query(
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1
                       AND idx > COALESCE(
-                          (SELECT idx FROM taskcast_events WHERE id = ?2),
+                          (SELECT idx FROM taskcast_events WHERE task_id = ?1 AND id = ?2),
                           -1
                       )
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(id)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else if let Some(index) = since.index {
@@ -439,13 +515,17 @@ impl LongTermStore for SqliteLongTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1 AND idx > ?2
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(index as i32)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else if let Some(timestamp) = since.timestamp {
@@ -453,13 +533,17 @@ impl LongTermStore for SqliteLongTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1 AND timestamp > ?2
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(timestamp as i64)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else {
@@ -467,12 +551,16 @@ impl LongTermStore for SqliteLongTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1
-                    ORDER BY idx ASC
+                      AND (?3 IS NULL OR timestamp <= ?3)
+                      AND (?4 IS NULL OR idx < ?4)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?2
                     "#,
                 )
                 .bind(task_id)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             }
@@ -481,17 +569,31 @@ impl LongTermStore for SqliteLongTermStore {
                 r#"
                 SELECT * FROM taskcast_events
                 WHERE task_id = ?1
-                ORDER BY idx ASC
+                  AND (?3 IS NULL OR timestamp <= ?3)
+                  AND (?4 IS NULL OR idx < ?4)
+                ORDER BY idx ASC, timestamp ASC, id ASC
                 LIMIT ?2
                 "#,
             )
             .bind(task_id)
             .bind(limit_val)
+            .bind(until)
+            .bind(before_index)
             .fetch_all(&self.pool)
             .await?
         };
 
-        Ok(rows.iter().map(row_to_event).collect())
+        let mut events: Vec<TaskEvent> = rows
+            .iter()
+            .filter_map(|row| self.integrity.event(row_to_event(row)))
+            .collect();
+        if let Some(selector) = label_selector {
+            events.retain(|e| matches_labels(e.labels.as_ref(), selector));
+            if let Some(limit) = limit {
+                events.truncate(limit as usize);
+            }
+        }
+        Ok(events)
     }
 
     fn supports_task_archive_restore(&self) -> bool {
@@ -502,6 +604,14 @@ impl LongTermStore for SqliteLongTermStore {
         true
     }
 
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        Some(&self.integrity)
+    }
+
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        Some(self)
+    }
+
     fn shares_task_archive_restore_storage(&self) -> bool {
         self.shares_task_archive_restore_storage
     }
@@ -543,6 +653,13 @@ impl LongTermStore for SqliteLongTermStore {
         let webhooks_json = to_json_string(&task.webhooks);
         let cleanup_json = to_json_string(&task.cleanup);
         let tags_json = to_json_string(&task.tags);
+        let filters_json = to_json_string(&task.filters);
+        let retry_policy_json = to_json_string(&task.retry_policy);
+        let forward_to_json = to_json_string(&task.forward_to);
+        let origin_json = to_json_string(&task.origin);
+        let completion_policy_json = to_json_string(&task.completion_policy);
+        let group_policy_str: Option<String> =
+            task.group_policy.as_ref().map(group_policy_to_string);
         let assign_mode_str: Option<String> = task.assign_mode.as_ref().map(assign_mode_to_string);
         let cost = task.cost.map(|value| value as i32);
         let disconnect_policy_str: Option<String> = task
@@ -555,10 +672,12 @@ impl LongTermStore for SqliteLongTermStore {
             INSERT INTO taskcast_tasks (
                 id, type, status, params, result, error, metadata,
                 auth_config, webhooks, cleanup, created_at, updated_at, completed_at, ttl,
-                tags, assign_mode, cost, assigned_worker, disconnect_policy
+                tags, assign_mode, cost, assigned_worker, disconnect_policy, filters,
+                retry_policy, group_policy, forward_to, scheduled_for, version,
+                deadline_ms, origin, parent_id, completion_policy
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
-                ?15, ?16, ?17, ?18, ?19
+                ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29
             )
             "#,
         )
@@ -581,6 +700,16 @@ impl LongTermStore for SqliteLongTermStore {
         .bind(cost)
         .bind(&task.assigned_worker)
         .bind(&disconnect_policy_str)
+        .bind(&filters_json)
+        .bind(&retry_policy_json)
+        .bind(&group_policy_str)
+        .bind(&forward_to_json)
+        .bind(task.scheduled_for.map(|value| value as i64))
+        .bind(task.version as i64)
+        .bind(task.deadline_ms.map(|v| v as i64))
+        .bind(&origin_json)
+        .bind(&task.parent_id)
+        .bind(&completion_policy_json)
         .execute(&mut *tx)
         .await?;
 
@@ -593,9 +722,10 @@ impl LongTermStore for SqliteLongTermStore {
             sqlx::query(
                 r#"
                 INSERT INTO taskcast_events (
-                    id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+                    id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+                    labels
                 ) VALUES (
-                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
                 )
                 "#,
             )
@@ -609,6 +739,7 @@ impl LongTermStore for SqliteLongTermStore {
             .bind(&event.series_id)
             .bind(&series_mode_str)
             .bind(&event.series_acc_field)
+            .bind(labels_to_string(&event.labels))
             .execute(&mut *tx)
             .await?;
         }
@@ -617,6 +748,52 @@ impl LongTermStore for SqliteLongTermStore {
         Ok(overwritten)
     }
 
+    async fn list_event_task_ids(
+        &self,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT task_id FROM taskcast_events WHERE task_id > ?1 ORDER BY task_id ASC LIMIT ?2",
+        )
+        .bind(after.unwrap_or(""))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(|row| row.get("task_id")).collect())
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM taskcast_events WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM taskcast_tasks WHERE id = ?1")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let mut replaced = 0;
+        for tombstone in tombstones {
+            replaced += redact_event_row(&mut tx, task_id, tombstone).await?;
+        }
+        tx.commit().await?;
+        Ok(replaced)
+    }
+
     async fn save_worker_event(
         &self,
         event: WorkerAuditEvent,
@@ -723,3 +900,105 @@ impl LongTermStore for SqliteLongTermStore {
         Ok(rows.iter().map(row_to_worker_audit_event).collect())
     }
 }
+
+#[async_trait]
+impl EventConventionStore for SqliteLongTermStore {
+    async fn record_event_conventions(
+        &self,
+        observed: &[EventConvention],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        for sample in observed {
+            sqlx::query(
+                r#"
+                INSERT INTO taskcast_event_conventions (
+                    task_type, event_type, estimated_count, last_seen_at, example, example_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT (task_type, event_type) DO UPDATE SET
+                    estimated_count = estimated_count + excluded.estimated_count,
+                    last_seen_at = CASE
+                        WHEN excluded.last_seen_at >= COALESCE(last_seen_at, excluded.last_seen_at)
+                        THEN excluded.last_seen_at ELSE last_seen_at END,
+                    example = CASE
+                        WHEN excluded.example_at >= COALESCE(example_at, excluded.example_at)
+                        THEN excluded.example ELSE example END,
+                    example_at = CASE
+                        WHEN excluded.example_at >= COALESCE(example_at, excluded.example_at)
+                        THEN excluded.example_at ELSE example_at END
+                "#,
+            )
+            .bind(&sample.task_type)
+            .bind(&sample.event_type)
+            .bind(sample.estimated_count as i64)
+            .bind(sample.last_seen_at)
+            .bind(sample.example.as_ref().and_then(json_value_to_string))
+            .bind(sample.example_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn annotate_event_convention(
+        &self,
+        task_type: &str,
+        event_type: &str,
+        notes: &ConventionNotes,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO taskcast_event_conventions (task_type, event_type, description, data_schema)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (task_type, event_type) DO UPDATE SET
+                description = excluded.description,
+                data_schema = excluded.data_schema
+            "#,
+        )
+        .bind(task_type)
+        .bind(event_type)
+        .bind(&notes.description)
+        .bind(notes.schema.as_ref().and_then(json_value_to_string))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_event_conventions(
+        &self,
+        task_type: Option<&str>,
+    ) -> Result<Vec<EventConvention>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM taskcast_event_conventions
+            WHERE ?1 IS NULL OR task_type = ?1
+            ORDER BY task_type ASC, event_type ASC
+            "#,
+        )
+        .bind(task_type)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(row_to_convention).collect()
+    }
+}
+
+fn row_to_convention(
+    row: &sqlx::sqlite::SqliteRow,
+) -> Result<EventConvention, Box<dyn std::error::Error + Send + Sync>> {
+    let json = |column: &str| -> Result<Option<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(row
+            .try_get::<Option<String>, _>(column)?
+            .map(|text| serde_json::from_str(&text))
+            .transpose()?)
+    };
+    Ok(EventConvention {
+        task_type: row.try_get("task_type")?,
+        event_type: row.try_get("event_type")?,
+        estimated_count: row.try_get::<i64, _>("estimated_count")?.max(0) as u64,
+        last_seen_at: row.try_get("last_seen_at")?,
+        example: json("example")?,
+        example_at: row.try_get("example_at")?,
+        description: row.try_get("description")?,
+        schema: json("data_schema")?,
+    })
+}
@@ -1,94 +1,150 @@
-use serde_json::Value as JsonValue;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value as JsonValue};
 use sqlx::sqlite::SqliteRow;
-use sqlx::Row;
+use sqlx::{Row, Sqlite, Transaction};
 use std::collections::HashMap;
 
+use taskcast_core::integrity::{CorruptRecord, CorruptRecordKind};
 use taskcast_core::types::{
     AssignMode, CleanupConfig, ConnectionMode, DisconnectPolicy, Level, SeriesMode, Task,
     TaskAuthConfig, TaskError, TaskEvent, TaskStatus, WebhookConfig, Worker, WorkerAssignment,
-    WorkerAssignmentStatus, WorkerAuditAction, WorkerAuditEvent, WorkerMatchRule, WorkerStatus,
+    WebhookGroupPolicy, WorkerAssignmentStatus, WorkerAuditAction, WorkerAuditEvent, WorkerMatchRule,
+    WorkerStatus,
 };
 
-/// Convert a SQLite row from the tasks table into a `Task`.
-pub fn row_to_task(row: &SqliteRow) -> Task {
+/// Convert a SQLite row from the tasks table into a `Task`. A row with an
+/// unknown status or an undecodable column is reported as a `CorruptRecord`
+/// rather than coerced.
+pub fn row_to_task(row: &SqliteRow) -> Result<Task, CorruptRecord> {
+    let id: String = row.get("id");
+    decode_task(row).map_err(|reason| CorruptRecord::task(&id, raw_task(row), reason))
+}
+
+fn decode_task(row: &SqliteRow) -> Result<Task, String> {
     let status_str: String = row.get("status");
-    let status: TaskStatus =
-        serde_json::from_value(JsonValue::String(status_str)).unwrap_or(TaskStatus::Pending);
+    let status: TaskStatus = decode_enum(status_str, "status")?;
 
     let created_at_i64: i64 = row.get("created_at");
     let updated_at_i64: i64 = row.get("updated_at");
     let completed_at_i64: Option<i64> = row.get("completed_at");
     let ttl_i32: Option<i32> = row.get("ttl");
 
-    let params_str: Option<String> = row.get("params");
-    let result_str: Option<String> = row.get("result");
-    let error_str: Option<String> = row.get("error");
-    let metadata_str: Option<String> = row.get("metadata");
-    let auth_config_str: Option<String> = row.get("auth_config");
-    let webhooks_str: Option<String> = row.get("webhooks");
-    let cleanup_str: Option<String> = row.get("cleanup");
-
-    let tags_str: Option<String> = row.get("tags");
     let assign_mode_str: Option<String> = row.get("assign_mode");
     let cost: Option<i32> = row.get("cost");
     let assigned_worker: Option<String> = row.get("assigned_worker");
     let disconnect_policy_str: Option<String> = row.get("disconnect_policy");
+    let group_policy_str: Option<String> = row.get("group_policy");
 
-    Task {
+    Ok(Task {
         id: row.get("id"),
         r#type: row.get("type"),
         status,
-        params: params_str
-            .and_then(|s| serde_json::from_str::<HashMap<String, JsonValue>>(&s).ok()),
-        result: result_str
-            .and_then(|s| serde_json::from_str::<HashMap<String, JsonValue>>(&s).ok()),
-        error: error_str.and_then(|s| serde_json::from_str::<TaskError>(&s).ok()),
-        metadata: metadata_str
-            .and_then(|s| serde_json::from_str::<HashMap<String, JsonValue>>(&s).ok()),
-        auth_config: auth_config_str
-            .and_then(|s| serde_json::from_str::<TaskAuthConfig>(&s).ok()),
-        webhooks: webhooks_str
-            .and_then(|s| serde_json::from_str::<Vec<WebhookConfig>>(&s).ok()),
-        cleanup: cleanup_str.and_then(|s| serde_json::from_str::<CleanupConfig>(&s).ok()),
+        params: decode_text::<HashMap<String, JsonValue>>(row.get("params"), "params")?,
+        result: decode_text::<HashMap<String, JsonValue>>(row.get("result"), "result")?,
+        error: decode_text::<TaskError>(row.get("error"), "error")?,
+        metadata: decode_text::<HashMap<String, JsonValue>>(row.get("metadata"), "metadata")?,
+        auth_config: decode_text::<TaskAuthConfig>(row.get("auth_config"), "auth_config")?,
+        webhooks: decode_text::<Vec<WebhookConfig>>(row.get("webhooks"), "webhooks")?,
+        cleanup: decode_text::<CleanupConfig>(row.get("cleanup"), "cleanup")?,
         created_at: created_at_i64 as f64,
         updated_at: updated_at_i64 as f64,
         completed_at: completed_at_i64.map(|v| v as f64),
         ttl: ttl_i32.map(|v| v as u64),
-        tags: tags_str.and_then(|s| serde_json::from_str::<Vec<String>>(&s).ok()),
+        tags: decode_text::<Vec<String>>(row.get("tags"), "tags")?,
         assign_mode: assign_mode_str
-            .and_then(|s| serde_json::from_value(JsonValue::String(s)).ok()),
+            .map(|s| decode_enum::<AssignMode>(s, "assign_mode"))
+            .transpose()?,
         cost: cost.map(|v| v as u32),
         assigned_worker,
         disconnect_policy: disconnect_policy_str
-            .and_then(|s| serde_json::from_value(JsonValue::String(s)).ok()),
+            .map(|s| decode_enum::<DisconnectPolicy>(s, "disconnect_policy"))
+            .transpose()?,
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: decode_text(row.get("filters"), "filters")?,
+        retry_policy: decode_text(row.get("retry_policy"), "retry_policy")?,
+        group_policy: group_policy_str
+            .map(|s| decode_enum::<WebhookGroupPolicy>(s, "group_policy"))
+            .transpose()?,
+        forward_to: decode_text(row.get("forward_to"), "forward_to")?,
+        scheduled_for: row
+            .get::<Option<i64>, _>("scheduled_for")
+            .map(|v| v as f64),
+        deadline_ms: row
+            .get::<Option<i64>, _>("deadline_ms")
+            .map(|v| v as u64),
+        origin: decode_text(row.get("origin"), "origin")?,
+        parent_id: row.get("parent_id"),
+        completion_policy: decode_text(row.get("completion_policy"), "completion_policy")?,
+        version: row.get::<i64, _>("version") as u64,
+    })
+}
+
+/// The decoded columns of a task row, as stored.
+fn raw_task(row: &SqliteRow) -> JsonValue {
+    let mut raw = serde_json::Map::new();
+    for column in [
+        "id",
+        "status",
+        "assign_mode",
+        "disconnect_policy",
+        "params",
+        "result",
+        "error",
+        "metadata",
+        "auth_config",
+        "webhooks",
+        "cleanup",
+        "tags",
+        "filters",
+        "retry_policy",
+        "group_policy",
+        "forward_to",
+        "origin",
+        "completion_policy",
+    ] {
+        raw.insert(column.to_string(), json!(row.get::<Option<String>, _>(column)));
     }
+    JsonValue::Object(raw)
+}
+
+/// Convert a SQLite row from the events table into a `TaskEvent`. A row with
+/// an unknown level or series mode, a negative index, or undecodable data or
+/// labels is reported as a `CorruptRecord` rather than coerced.
+pub fn row_to_event(row: &SqliteRow) -> Result<TaskEvent, CorruptRecord> {
+    let idx: i32 = row.get("idx");
+    let timestamp_i64: i64 = row.get("timestamp");
+    decode_event(row).map_err(|reason| CorruptRecord {
+        kind: CorruptRecordKind::Event,
+        task_id: row.get("task_id"),
+        id: Some(row.get("id")),
+        position: u64::try_from(idx).ok(),
+        timestamp: Some(timestamp_i64 as f64),
+        raw: Box::new(raw_event(row)),
+        reason,
+    })
 }
 
-/// Convert a SQLite row from the events table into a `TaskEvent`.
-pub fn row_to_event(row: &SqliteRow) -> TaskEvent {
+fn decode_event(row: &SqliteRow) -> Result<TaskEvent, String> {
     let level_str: String = row.get("level");
-    let level: Level =
-        serde_json::from_value(JsonValue::String(level_str)).unwrap_or(Level::Info);
+    let level: Level = decode_enum(level_str, "level")?;
 
     let idx: i32 = row.get("idx");
+    let index = u64::try_from(idx).map_err(|_| format!("idx: negative index {idx}"))?;
     let timestamp_i64: i64 = row.get("timestamp");
-    let data_str: Option<String> = row.get("data");
 
     let series_mode_str: Option<String> = row.get("series_mode");
-    let series_mode: Option<SeriesMode> =
-        series_mode_str.and_then(|s| serde_json::from_value(JsonValue::String(s)).ok());
+    let series_mode: Option<SeriesMode> = series_mode_str
+        .map(|s| decode_enum(s, "series_mode"))
+        .transpose()?;
 
-    let data: JsonValue = data_str
-        .and_then(|s| serde_json::from_str(&s).ok())
-        .unwrap_or(JsonValue::Null);
+    let data: JsonValue = decode_text(row.get("data"), "data")?.unwrap_or(JsonValue::Null);
 
-    TaskEvent {
+    Ok(TaskEvent {
         id: row.get("id"),
         task_id: row.get("task_id"),
-        index: idx as u64,
+        index,
         timestamp: timestamp_i64 as f64,
         r#type: row.get("type"),
         level,
@@ -97,8 +153,82 @@ pub fn row_to_event(row: &SqliteRow) -> TaskEvent {
         series_mode,
         series_acc_field: row.get("series_acc_field"),
         series_snapshot: None,
+        labels: decode_text(row.get("labels"), "labels")?,
+        replaces_event_id: None,
         _accumulated_data: None,
-    }
+    })
+}
+
+/// An event row's columns, as stored.
+fn raw_event(row: &SqliteRow) -> JsonValue {
+    json!({
+        "id": row.get::<String, _>("id"),
+        "taskId": row.get::<String, _>("task_id"),
+        "idx": row.get::<i32, _>("idx"),
+        "timestamp": row.get::<i64, _>("timestamp"),
+        "type": row.get::<String, _>("type"),
+        "level": row.get::<String, _>("level"),
+        "data": row.get::<Option<String>, _>("data"),
+        "seriesId": row.get::<Option<String>, _>("series_id"),
+        "seriesMode": row.get::<Option<String>, _>("series_mode"),
+        "seriesAccField": row.get::<Option<String>, _>("series_acc_field"),
+        "labels": row.get::<Option<String>, _>("labels"),
+    })
+}
+
+/// Decodes a string column holding a serde enum variant.
+fn decode_enum<T: DeserializeOwned>(value: String, column: &str) -> Result<T, String> {
+    serde_json::from_value(JsonValue::String(value)).map_err(|e| format!("{column}: {e}"))
+}
+
+/// Decodes a nullable column holding JSON text.
+fn decode_text<T: DeserializeOwned>(
+    value: Option<String>,
+    column: &str,
+) -> Result<Option<T>, String> {
+    value
+        .map(|s| serde_json::from_str(&s).map_err(|e| format!("{column}: {e}")))
+        .transpose()
+}
+
+/// Serialize event labels to a JSON string for DB storage; empty label sets are stored as NULL.
+pub fn labels_to_string(labels: &Option<HashMap<String, String>>) -> Option<String> {
+    labels
+        .as_ref()
+        .filter(|labels| !labels.is_empty())
+        .and_then(|labels| serde_json::to_string(labels).ok())
+}
+
+/// Overwrites the task's event row with `tombstone`'s id in place, keeping
+/// its `idx`. Returns how many rows were rewritten.
+pub async fn redact_event_row(
+    tx: &mut Transaction<'_, Sqlite>,
+    task_id: &str,
+    tombstone: &TaskEvent,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE taskcast_events
+        SET timestamp = ?1,
+            type = ?2,
+            level = ?3,
+            data = ?4,
+            series_id = NULL,
+            series_mode = NULL,
+            series_acc_field = NULL,
+            labels = NULL
+        WHERE task_id = ?5 AND id = ?6
+        "#,
+    )
+    .bind(tombstone.timestamp as i64)
+    .bind(&tombstone.r#type)
+    .bind(level_to_string(&tombstone.level))
+    .bind(json_value_to_string(&tombstone.data))
+    .bind(task_id)
+    .bind(&tombstone.id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(result.rows_affected())
 }
 
 /// Serialize a `TaskStatus` to its string representation for DB storage.
@@ -156,6 +286,14 @@ pub fn disconnect_policy_to_string(policy: &DisconnectPolicy) -> String {
         .unwrap_or_else(|| "fail".to_string())
 }
 
+/// Serialize a `WebhookGroupPolicy` to its string representation for DB storage.
+pub fn group_policy_to_string(policy: &WebhookGroupPolicy) -> String {
+    serde_json::to_value(policy)
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+        .unwrap_or_else(|| "all".to_string())
+}
+
 /// Serialize a `WorkerStatus` to its string representation for DB storage.
 pub fn worker_status_to_string(status: &WorkerStatus) -> String {
     serde_json::to_value(status)
@@ -2,25 +2,43 @@ use async_trait::async_trait;
 use sqlx::{Row, SqlitePool};
 use std::collections::BTreeSet;
 
+use taskcast_core::filter::matches_labels;
+use taskcast_core::integrity::IntegrityMonitor;
 use taskcast_core::types::{
-    EventQueryOptions, ShortTermStore, Task, TaskArchiveImportOptions, TaskArchiveRestoreData,
-    TaskEvent, TaskFilter, TaskStatus, Worker, WorkerAssignment, WorkerFilter,
+    EventQueryOptions, EventTypeCounts, FilteredIndexClaim, FilteredIndexMark, Level, OutcomeQuery, RetrySchedule, SeriesSummary, ShortTermStore, Task, TaskArchiveImportOptions, TaskArchiveRestoreData,
+    TaskCursor, TaskEvent, TaskFilter, TaskOutcome, TaskPage, TaskStatus, Worker, WorkerAssignment, WorkerFilter,
 };
 
 use crate::row_helpers::{
     assign_mode_to_string, assignment_status_to_string, connection_mode_to_string,
-    disconnect_policy_to_string, json_value_to_string, level_to_string, row_to_event, row_to_task,
-    row_to_worker, row_to_worker_assignment, series_mode_to_string, status_to_string, to_json_string,
-    worker_status_to_string,
+    disconnect_policy_to_string, group_policy_to_string, json_value_to_string, labels_to_string,
+    level_to_string, redact_event_row, row_to_event, row_to_task, row_to_worker, row_to_worker_assignment,
+    series_mode_to_string, status_to_string, to_json_string, worker_status_to_string,
 };
 
+/// Outcomes read per query while filtering the outcomes index.
+const OUTCOME_SCAN_BATCH: i64 = 500;
+
 pub struct SqliteShortTermStore {
     pool: SqlitePool,
+    integrity: IntegrityMonitor,
 }
 
 impl SqliteShortTermStore {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            integrity: IntegrityMonitor::default(),
+        }
+    }
+
+    /// Use `monitor` for rows that fail to decode on read. The default monitor
+    /// surfaces them (see `CorruptRecordPolicy`).
+    pub fn with_integrity(self, monitor: IntegrityMonitor) -> Self {
+        Self {
+            integrity: monitor,
+            ..self
+        }
     }
 
     async fn validate_archive_restore(
@@ -62,14 +80,14 @@ impl SqliteShortTermStore {
 
         Ok(existing.is_some())
     }
-}
 
-#[async_trait]
-impl ShortTermStore for SqliteShortTermStore {
-    async fn save_task(
+    /// Inserts or replaces `task`. With `expected_version`, an existing row is
+    /// only replaced at that version; returns whether a row was written.
+    async fn upsert_task(
         &self,
         task: Task,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        expected_version: Option<u64>,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let status_str = status_to_string(&task.status);
         let params_json = to_json_string(&task.params);
         let result_json = to_json_string(&task.result);
@@ -79,6 +97,13 @@ impl ShortTermStore for SqliteShortTermStore {
         let webhooks_json = to_json_string(&task.webhooks);
         let cleanup_json = to_json_string(&task.cleanup);
         let tags_json = to_json_string(&task.tags);
+        let filters_json = to_json_string(&task.filters);
+        let retry_policy_json = to_json_string(&task.retry_policy);
+        let forward_to_json = to_json_string(&task.forward_to);
+        let origin_json = to_json_string(&task.origin);
+        let completion_policy_json = to_json_string(&task.completion_policy);
+        let group_policy_str: Option<String> =
+            task.group_policy.as_ref().map(group_policy_to_string);
         let assign_mode_str: Option<String> = task.assign_mode.as_ref().map(assign_mode_to_string);
         let cost = task.cost.map(|v| v as i32);
         let disconnect_policy_str: Option<String> = task
@@ -91,15 +116,17 @@ impl ShortTermStore for SqliteShortTermStore {
         let completed_at = task.completed_at.map(|v| v as i64);
         let ttl = task.ttl.map(|v| v as i32);
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             INSERT INTO taskcast_tasks (
                 id, type, status, params, result, error, metadata,
                 auth_config, webhooks, cleanup, created_at, updated_at, completed_at, ttl,
-                tags, assign_mode, cost, assigned_worker, disconnect_policy
+                tags, assign_mode, cost, assigned_worker, disconnect_policy, filters,
+                retry_policy, group_policy, forward_to, scheduled_for, version,
+                deadline_ms, origin, parent_id, completion_policy
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
-                ?15, ?16, ?17, ?18, ?19
+                ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29
             )
             ON CONFLICT (id) DO UPDATE SET
                 status = excluded.status,
@@ -109,7 +136,15 @@ impl ShortTermStore for SqliteShortTermStore {
                 updated_at = excluded.updated_at,
                 completed_at = excluded.completed_at,
                 cost = excluded.cost,
-                assigned_worker = excluded.assigned_worker
+                assigned_worker = excluded.assigned_worker,
+                filters = excluded.filters,
+                retry_policy = excluded.retry_policy,
+                group_policy = excluded.group_policy,
+                forward_to = excluded.forward_to,
+                parent_id = excluded.parent_id,
+                completion_policy = excluded.completion_policy,
+                version = excluded.version
+            WHERE ?30 IS NULL OR taskcast_tasks.version = ?30
             "#,
         )
         .bind(&task.id)
@@ -131,12 +166,42 @@ impl ShortTermStore for SqliteShortTermStore {
         .bind(cost)
         .bind(&task.assigned_worker)
         .bind(&disconnect_policy_str)
+        .bind(&filters_json)
+        .bind(&retry_policy_json)
+        .bind(&group_policy_str)
+        .bind(&forward_to_json)
+        .bind(task.scheduled_for.map(|v| v as i64))
+        .bind(task.version as i64)
+        .bind(task.deadline_ms.map(|v| v as i64))
+        .bind(&origin_json)
+        .bind(&task.parent_id)
+        .bind(&completion_policy_json)
+        .bind(expected_version.map(|v| v as i64))
         .execute(&self.pool)
         .await?;
 
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for SqliteShortTermStore {
+    async fn save_task(
+        &self,
+        task: Task,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.upsert_task(task, None).await?;
         Ok(())
     }
 
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        self.upsert_task(task, Some(expected_version)).await
+    }
+
     async fn get_task(
         &self,
         task_id: &str,
@@ -146,7 +211,10 @@ impl ShortTermStore for SqliteShortTermStore {
             .fetch_optional(&self.pool)
             .await?;
 
-        Ok(row.as_ref().map(row_to_task))
+        match row {
+            Some(row) => Ok(self.integrity.task(row_to_task(&row))?),
+            None => Ok(None),
+        }
     }
 
     async fn next_index(
@@ -169,6 +237,48 @@ impl ShortTermStore for SqliteShortTermStore {
         Ok(counter as u64)
     }
 
+    async fn event_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        // The counter holds the last index handed out; no row means none yet.
+        let row = sqlx::query("SELECT counter FROM taskcast_index_counters WHERE task_id = ?1")
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map_or(0, |row| row.get::<i32, _>("counter") as u64 + 1))
+    }
+
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT type, level, COUNT(*) AS count FROM taskcast_events \
+             WHERE task_id = ?1 GROUP BY type, level",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut counts = EventTypeCounts::default();
+        for row in rows {
+            let count = row.get::<i64, _>("count") as u64;
+            *counts.types.entry(row.get("type")).or_default() += count;
+            // Rows with an unknown level are reported when read as events.
+            let level = serde_json::Value::String(row.get("level"));
+            if let Ok(level) = serde_json::from_value::<Level>(level) {
+                *counts.levels.entry(level).or_default() += count;
+            }
+        }
+        Ok(counts)
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        Some(&self.integrity)
+    }
+
     fn supports_task_archive_restore(&self) -> bool {
         true
     }
@@ -214,6 +324,13 @@ impl ShortTermStore for SqliteShortTermStore {
         let webhooks_json = to_json_string(&task.webhooks);
         let cleanup_json = to_json_string(&task.cleanup);
         let tags_json = to_json_string(&task.tags);
+        let filters_json = to_json_string(&task.filters);
+        let retry_policy_json = to_json_string(&task.retry_policy);
+        let forward_to_json = to_json_string(&task.forward_to);
+        let origin_json = to_json_string(&task.origin);
+        let completion_policy_json = to_json_string(&task.completion_policy);
+        let group_policy_str: Option<String> =
+            task.group_policy.as_ref().map(group_policy_to_string);
         let assign_mode_str: Option<String> = task.assign_mode.as_ref().map(assign_mode_to_string);
         let cost = task.cost.map(|value| value as i32);
         let disconnect_policy_str: Option<String> = task
@@ -226,10 +343,12 @@ impl ShortTermStore for SqliteShortTermStore {
             INSERT INTO taskcast_tasks (
                 id, type, status, params, result, error, metadata,
                 auth_config, webhooks, cleanup, created_at, updated_at, completed_at, ttl,
-                tags, assign_mode, cost, assigned_worker, disconnect_policy
+                tags, assign_mode, cost, assigned_worker, disconnect_policy, filters,
+                retry_policy, group_policy, forward_to, scheduled_for, version,
+                deadline_ms, origin, parent_id, completion_policy
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
-                ?15, ?16, ?17, ?18, ?19
+                ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29
             )
             "#,
         )
@@ -252,6 +371,16 @@ impl ShortTermStore for SqliteShortTermStore {
         .bind(cost)
         .bind(&task.assigned_worker)
         .bind(&disconnect_policy_str)
+        .bind(&filters_json)
+        .bind(&retry_policy_json)
+        .bind(&group_policy_str)
+        .bind(&forward_to_json)
+        .bind(task.scheduled_for.map(|value| value as i64))
+        .bind(task.version as i64)
+        .bind(task.deadline_ms.map(|v| v as i64))
+        .bind(&origin_json)
+        .bind(&task.parent_id)
+        .bind(&completion_policy_json)
         .execute(&mut *tx)
         .await?;
 
@@ -264,9 +393,10 @@ impl ShortTermStore for SqliteShortTermStore {
             sqlx::query(
                 r#"
                 INSERT INTO taskcast_events (
-                    id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+                    id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+                    labels
                 ) VALUES (
-                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
                 )
                 "#,
             )
@@ -280,6 +410,7 @@ impl ShortTermStore for SqliteShortTermStore {
             .bind(&event.series_id)
             .bind(&series_mode_str)
             .bind(&event.series_acc_field)
+            .bind(labels_to_string(&event.labels))
             .execute(&mut *tx)
             .await?;
         }
@@ -331,9 +462,10 @@ impl ShortTermStore for SqliteShortTermStore {
         sqlx::query(
             r#"
             INSERT INTO taskcast_events (
-                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field
+                id, task_id, idx, timestamp, type, level, data, series_id, series_mode, series_acc_field,
+                labels
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11
             )
             "#,
         )
@@ -347,6 +479,7 @@ impl ShortTermStore for SqliteShortTermStore {
         .bind(&event.series_id)
         .bind(&series_mode_str)
         .bind(&event.series_acc_field)
+        .bind(labels_to_string(&event.labels))
         .execute(&self.pool)
         .await?;
 
@@ -360,29 +493,51 @@ impl ShortTermStore for SqliteShortTermStore {
     ) -> Result<Vec<TaskEvent>, Box<dyn std::error::Error + Send + Sync>> {
         let since = opts.as_ref().and_then(|o| o.since.as_ref());
         let limit = opts.as_ref().and_then(|o| o.limit);
+        let label_selector = opts.as_ref().and_then(|o| o.label_selector.as_ref());
+        // Timestamps are stored as whole milliseconds; a NULL bound keeps every
+        // event.
+        let until = opts
+            .as_ref()
+            .and_then(|o| o.until)
+            .map(|until| until.floor() as i64);
+        let before_index = opts
+            .as_ref()
+            .and_then(|o| o.before_index)
+            .map(|index| index as i64);
 
         // When no limit is specified, use a very large value (effectively unlimited).
-        let limit_val = limit.map(|l| l as i64).unwrap_or(i64::MAX);
+        // Label selectors are evaluated after the query, so the limit is applied there.
+        let limit_val = match (limit, label_selector) {
+            (Some(l), None) => l as i64,
+            _ => i64::MAX,
+        };
 
         let rows = if let Some(since) = since {
             if let Some(ref id) = since.id {
                 // since.id takes priority: look up anchor's idx, then fetch events after it.
-                // COALESCE ensures that if the anchor is not found, we return all events (idx > -1).
+                // The anchor is resolved within this task only; COALESCE ensures that if
+                // it is not found, we return all events (idx > -1).
+                // `UNIQUE(task_id, idx)` rules out ties, so comparing indexes alone
+                // resumes right after the anchor in the canonical order.
                 sqlx::query(
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1
                       AND idx > COALESCE(
-                          (SELECT idx FROM taskcast_events WHERE id = ?2),
+                          (SELECT idx FROM taskcast_events WHERE task_id = ?1 AND id = ?2),
                           -1
                       )
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(id)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else if let Some(index) = since.index {
@@ -390,13 +545,17 @@ impl ShortTermStore for SqliteShortTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1 AND idx > ?2
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(index as i32)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else if let Some(timestamp) = since.timestamp {
@@ -404,13 +563,17 @@ impl ShortTermStore for SqliteShortTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1 AND timestamp > ?2
-                    ORDER BY idx ASC
+                      AND (?4 IS NULL OR timestamp <= ?4)
+                      AND (?5 IS NULL OR idx < ?5)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?3
                     "#,
                 )
                 .bind(task_id)
                 .bind(timestamp as i64)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             } else {
@@ -419,12 +582,16 @@ impl ShortTermStore for SqliteShortTermStore {
                     r#"
                     SELECT * FROM taskcast_events
                     WHERE task_id = ?1
-                    ORDER BY idx ASC
+                      AND (?3 IS NULL OR timestamp <= ?3)
+                      AND (?4 IS NULL OR idx < ?4)
+                    ORDER BY idx ASC, timestamp ASC, id ASC
                     LIMIT ?2
                     "#,
                 )
                 .bind(task_id)
                 .bind(limit_val)
+                .bind(until)
+                .bind(before_index)
                 .fetch_all(&self.pool)
                 .await?
             }
@@ -433,17 +600,31 @@ impl ShortTermStore for SqliteShortTermStore {
                 r#"
                 SELECT * FROM taskcast_events
                 WHERE task_id = ?1
-                ORDER BY idx ASC
+                  AND (?3 IS NULL OR timestamp <= ?3)
+                  AND (?4 IS NULL OR idx < ?4)
+                ORDER BY idx ASC, timestamp ASC, id ASC
                 LIMIT ?2
                 "#,
             )
             .bind(task_id)
             .bind(limit_val)
+            .bind(until)
+            .bind(before_index)
             .fetch_all(&self.pool)
             .await?
         };
 
-        Ok(rows.iter().map(row_to_event).collect())
+        let mut events: Vec<TaskEvent> = rows
+            .iter()
+            .filter_map(|row| self.integrity.event(row_to_event(row)))
+            .collect();
+        if let Some(selector) = label_selector {
+            events.retain(|e| matches_labels(e.labels.as_ref(), selector));
+            if let Some(limit) = limit {
+                events.truncate(limit as usize);
+            }
+        }
+        Ok(events)
     }
 
     async fn set_ttl(
@@ -504,6 +685,40 @@ impl ShortTermStore for SqliteShortTermStore {
         Ok(())
     }
 
+    async fn list_series(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<SeriesSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT series_id, event_json FROM taskcast_series_latest WHERE task_id = ?1 \
+             ORDER BY series_id",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut series = Vec::with_capacity(rows.len());
+        for row in rows {
+            let series_id: String = row.get("series_id");
+            let json_str: String = row.get("event_json");
+            let latest: TaskEvent = serde_json::from_str(&json_str)?;
+            series.push(SeriesSummary::of(&series_id, &latest));
+        }
+        Ok(series)
+    }
+
+    async fn series_count(
+        &self,
+        task_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let row =
+            sqlx::query("SELECT COUNT(*) AS count FROM taskcast_series_latest WHERE task_id = ?1")
+                .bind(task_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(row.get::<i64, _>("count") as u64)
+    }
+
     async fn accumulate_series(
         &self,
         task_id: &str,
@@ -586,11 +801,11 @@ impl ShortTermStore for SqliteShortTermStore {
         task_id: &str,
         series_id: &str,
         event: TaskEvent,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         // Get the previous series latest event
         let prev = self.get_series_latest(task_id, series_id).await?;
 
-        if let Some(prev) = prev {
+        let replaced = if let Some(prev) = prev {
             // Update only content fields of the previous event in the events table,
             // preserving the original event's id and idx to maintain position ordering.
             let level_str = level_to_string(&event.level);
@@ -617,61 +832,24 @@ impl ShortTermStore for SqliteShortTermStore {
             .bind(&prev.id)
             .execute(&self.pool)
             .await?;
+            Some(prev.id)
         } else {
             // No previous series event — append as a new event
             self.append_event(task_id, event.clone()).await?;
-        }
+            None
+        };
 
         // Always update the series latest entry
         self.set_series_latest(task_id, series_id, event).await?;
 
-        Ok(())
+        Ok(replaced)
     }
 
     async fn list_tasks(
         &self,
         filter: TaskFilter,
     ) -> Result<Vec<Task>, Box<dyn std::error::Error + Send + Sync>> {
-        // Build query dynamically based on filter.
-        // SQLite doesn't support array parameters, so we build WHERE clauses with
-        // comma-separated IN lists and apply tag matching in Rust post-fetch.
-        let mut conditions: Vec<String> = Vec::new();
-
-        if let Some(ref statuses) = filter.status {
-            if !statuses.is_empty() {
-                let placeholders: Vec<String> = statuses
-                    .iter()
-                    .map(|s| format!("'{}'", status_to_string(s)))
-                    .collect();
-                conditions.push(format!("status IN ({})", placeholders.join(",")));
-            }
-        }
-
-        if let Some(ref types) = filter.types {
-            if !types.is_empty() {
-                let placeholders: Vec<String> =
-                    types.iter().map(|t| format!("'{}'", t.replace('\'', "''"))).collect();
-                conditions.push(format!("type IN ({})", placeholders.join(",")));
-            }
-        }
-
-        if let Some(ref modes) = filter.assign_mode {
-            if !modes.is_empty() {
-                let placeholders: Vec<String> = modes
-                    .iter()
-                    .map(|m| format!("'{}'", assign_mode_to_string(m)))
-                    .collect();
-                conditions.push(format!("assign_mode IN ({})", placeholders.join(",")));
-            }
-        }
-
-        if let Some(ref exclude) = filter.exclude_task_ids {
-            if !exclude.is_empty() {
-                let placeholders: Vec<String> =
-                    exclude.iter().map(|id| format!("'{}'", id.replace('\'', "''"))).collect();
-                conditions.push(format!("id NOT IN ({})", placeholders.join(",")));
-            }
-        }
+        let conditions = task_filter_conditions(&filter);
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -688,7 +866,12 @@ impl ShortTermStore for SqliteShortTermStore {
             .fetch_all(&self.pool)
             .await?;
 
-        let mut tasks: Vec<Task> = rows.iter().map(row_to_task).collect();
+        let mut tasks: Vec<Task> = Vec::new();
+        for row in &rows {
+            if let Some(task) = self.integrity.task(row_to_task(row))? {
+                tasks.push(task);
+            }
+        }
 
         // Apply tag filtering in Rust since it requires JSON parsing
         if let Some(ref tag_matcher) = filter.tags {
@@ -705,6 +888,61 @@ impl ShortTermStore for SqliteShortTermStore {
         Ok(tasks)
     }
 
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn std::error::Error + Send + Sync>> {
+        let conditions = task_filter_conditions(&filter);
+        let batch = limit + 1;
+
+        // Tags are matched after the query, so batches are read until the
+        // page and the task after it are found or the rows run out.
+        let mut page: Vec<Task> = Vec::new();
+        let mut position = after;
+        loop {
+            let mut batch_conditions = conditions.clone();
+            if position.is_some() {
+                batch_conditions.push("(created_at > ? OR (created_at = ? AND id > ?))".to_string());
+            }
+            let where_clause = if batch_conditions.is_empty() {
+                String::new()
+            } else {
+                format!(" WHERE {}", batch_conditions.join(" AND "))
+            };
+            let query_str = format!(
+                "SELECT * FROM taskcast_tasks{where_clause} ORDER BY created_at, id LIMIT {batch}"
+            );
+            let mut query = sqlx::query(&query_str);
+            if let Some(ref position) = position {
+                let created_at = position.created_at as i64;
+                query = query.bind(created_at).bind(created_at).bind(&position.id);
+            }
+            let rows = query.fetch_all(&self.pool).await?;
+
+            for row in &rows {
+                if let Some(task) = self.integrity.task(row_to_task(row))? {
+                    if filter.tags.as_ref().is_none_or(|tag_matcher| {
+                        taskcast_core::worker_matching::matches_tag(task.tags.as_deref(), tag_matcher)
+                    }) {
+                        page.push(task);
+                    }
+                }
+            }
+            let Some(last) = rows.last() else { break };
+            if page.len() >= batch || rows.len() < batch {
+                break;
+            }
+            // Past every row read, including any that were skipped.
+            position = Some(TaskCursor {
+                created_at: last.get::<i64, _>("created_at") as f64,
+                id: last.get("id"),
+            });
+        }
+        Ok(TaskPage::from_sorted(page, limit))
+    }
+
     async fn save_worker(
         &self,
         worker: Worker,
@@ -898,7 +1136,8 @@ impl ShortTermStore for SqliteShortTermStore {
                 status = ?1,
                 assigned_worker = ?2,
                 cost = ?3,
-                updated_at = ?4
+                updated_at = ?4,
+                version = version + 1
             WHERE id = ?5
             "#,
         )
@@ -981,4 +1220,479 @@ impl ShortTermStore for SqliteShortTermStore {
 
         Ok(row.as_ref().map(row_to_worker_assignment))
     }
+
+    async fn save_retry_schedule(
+        &self,
+        schedule: RetrySchedule,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO taskcast_retry_schedules (task_id, successor_id, attempt, due_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (task_id) DO UPDATE SET
+                successor_id = excluded.successor_id,
+                attempt = excluded.attempt,
+                due_at = excluded.due_at,
+                claimed_until = NULL
+            "#,
+        )
+        .bind(&schedule.task_id)
+        .bind(&schedule.successor_id)
+        .bind(schedule.attempt as i64)
+        .bind(schedule.due_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_due_retries(
+        &self,
+        now: f64,
+    ) -> Result<Vec<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT * FROM taskcast_retry_schedules WHERE due_at <= ?1 ORDER BY due_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(row_to_retry_schedule).collect())
+    }
+
+    async fn get_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<RetrySchedule>, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query("SELECT * FROM taskcast_retry_schedules WHERE task_id = ?1")
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(row_to_retry_schedule))
+    }
+
+    async fn claim_retry(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // The conditional UPDATE is atomic, so of several instances sharing
+        // the database only one sees a changed row.
+        let result = sqlx::query(
+            r#"
+            UPDATE taskcast_retry_schedules SET claimed_until = ?2
+            WHERE task_id = ?1 AND (claimed_until IS NULL OR claimed_until <= ?3)
+            "#,
+        )
+        .bind(task_id)
+        .bind(now + lease_ms as f64)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM taskcast_retry_schedules WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn save_activation(
+        &self,
+        task_id: &str,
+        at: f64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO taskcast_task_activations (task_id, activate_at)
+            VALUES (?1, ?2)
+            ON CONFLICT (task_id) DO UPDATE SET
+                activate_at = excluded.activate_at,
+                claimed_until = NULL
+            "#,
+        )
+        .bind(task_id)
+        .bind(at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_due_activations(
+        &self,
+        now: f64,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            "SELECT task_id FROM taskcast_task_activations WHERE activate_at <= ?1 ORDER BY activate_at",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("task_id")).collect())
+    }
+
+    async fn claim_activation(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Atomic for the same reason as `claim_retry`.
+        let result = sqlx::query(
+            r#"
+            UPDATE taskcast_task_activations SET claimed_until = ?2
+            WHERE task_id = ?1 AND (claimed_until IS NULL OR claimed_until <= ?3)
+            "#,
+        )
+        .bind(task_id)
+        .bind(now + lease_ms as f64)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_activation(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM taskcast_task_activations WHERE task_id = ?1")
+            .bind(task_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        fingerprint: &str,
+        now: f64,
+        window_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // The upsert only touches the row when the window has passed, so of
+        // several instances delivering the same event only one sees a change.
+        let result = sqlx::query(
+            r#"
+            INSERT INTO taskcast_webhook_suppressions (task_id, webhook, fingerprint, delivered_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (task_id, webhook, fingerprint) DO UPDATE SET delivered_at = excluded.delivered_at
+            WHERE taskcast_webhook_suppressions.delivered_at <= ?5
+            "#,
+        )
+        .bind(task_id)
+        .bind(webhook as i64)
+        .bind(fingerprint)
+        .bind(now)
+        .bind(now - window_ms as f64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn claim_filtered_index(
+        &self,
+        task_id: &str,
+        consumer: &str,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> Result<Option<FilteredIndexClaim>, Box<dyn std::error::Error + Send + Sync>> {
+        // One upsert, so concurrent claims never hand out the same index.
+        // SET expressions read the row as it was before the update.
+        let row = sqlx::query(
+            r#"
+            INSERT INTO taskcast_filtered_indices (task_id, consumer, filter_hash, epoch, next, through)
+            VALUES (?1, ?2, ?3, 0, 1, ?4)
+            ON CONFLICT (task_id, consumer) DO UPDATE SET
+              epoch = CASE WHEN filter_hash = excluded.filter_hash THEN epoch ELSE epoch + 1 END,
+              next = CASE WHEN filter_hash = excluded.filter_hash THEN next + 1 ELSE 1 END,
+              through = CASE WHEN filter_hash = excluded.filter_hash
+                THEN max(coalesce(through, excluded.through), excluded.through)
+                ELSE excluded.through END,
+              filter_hash = excluded.filter_hash
+            RETURNING epoch, next
+            "#,
+        )
+        .bind(task_id)
+        .bind(consumer)
+        .bind(filter_hash)
+        .bind(raw_index as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Some(FilteredIndexClaim {
+            filtered_index: row.get::<i64, _>("next") as u64 - 1,
+            epoch: row.get::<i64, _>("epoch") as u64,
+        }))
+    }
+
+    async fn get_filtered_index_mark(
+        &self,
+        task_id: &str,
+        consumer: &str,
+    ) -> Result<Option<FilteredIndexMark>, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(
+            "SELECT filter_hash, epoch, next, through FROM taskcast_filtered_indices WHERE task_id = ?1 AND consumer = ?2",
+        )
+        .bind(task_id)
+        .bind(consumer)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| FilteredIndexMark {
+            filter_hash: row.get("filter_hash"),
+            epoch: row.get::<i64, _>("epoch") as u64,
+            next: row.get::<i64, _>("next") as u64,
+            through: row.get::<Option<i64>, _>("through").map(|through| through as u64),
+        }))
+    }
+
+    async fn claim_deadline_warning(
+        &self,
+        task_id: &str,
+        warning: &str,
+        _ttl_ms: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // Claims are kept until the task is deleted.
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO taskcast_deadline_warnings (task_id, warning) VALUES (?1, ?2)",
+        )
+        .bind(task_id)
+        .bind(warning)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn record_outcome(
+        &self,
+        outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO taskcast_outcomes (task_id, type, status, completed_at, error_code, origin)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&outcome.task_id)
+        .bind(&outcome.r#type)
+        .bind(status_to_string(&outcome.status))
+        .bind(outcome.completed_at)
+        .bind(&outcome.error_code)
+        .bind(outcome.origin.map(|kind| kind.as_str()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_outcomes(
+        &self,
+        query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, Box<dyn std::error::Error + Send + Sync>> {
+        // Time bounds are answered from the index; statuses and type
+        // patterns are applied here, a batch at a time.
+        let after = query.after.as_ref();
+        let mut matched = Vec::new();
+        let mut offset = 0;
+        while matched.len() < query.limit {
+            let rows = sqlx::query(
+                r#"
+                SELECT * FROM taskcast_outcomes
+                WHERE (?1 IS NULL OR completed_at >= ?1)
+                  AND (?2 IS NULL OR completed_at > ?2 OR (completed_at = ?2 AND task_id > ?3))
+                ORDER BY completed_at, task_id
+                LIMIT ?4 OFFSET ?5
+                "#,
+            )
+            .bind(query.since)
+            .bind(after.map(|cursor| cursor.completed_at))
+            .bind(after.map(|cursor| cursor.task_id.as_str()))
+            .bind(OUTCOME_SCAN_BATCH)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in &rows {
+                let outcome = row_to_outcome(row)?;
+                if query.matches(&outcome) && matched.len() < query.limit {
+                    matched.push(outcome);
+                }
+            }
+            if (rows.len() as i64) < OUTCOME_SCAN_BATCH {
+                break;
+            }
+            offset += OUTCOME_SCAN_BATCH;
+        }
+        Ok(matched)
+    }
+
+    async fn trim_outcomes(
+        &self,
+        before: f64,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("DELETE FROM taskcast_outcomes WHERE completed_at < ?1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_task(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        for sql in [
+            "DELETE FROM taskcast_events WHERE task_id = ?1",
+            "DELETE FROM taskcast_series_latest WHERE task_id = ?1",
+            "DELETE FROM taskcast_index_counters WHERE task_id = ?1",
+            "DELETE FROM taskcast_worker_assignments WHERE task_id = ?1",
+            "DELETE FROM taskcast_retry_schedules WHERE task_id = ?1",
+            "DELETE FROM taskcast_task_activations WHERE task_id = ?1",
+            "DELETE FROM taskcast_webhook_suppressions WHERE task_id = ?1",
+            "DELETE FROM taskcast_deadline_warnings WHERE task_id = ?1",
+            "DELETE FROM taskcast_filtered_indices WHERE task_id = ?1",
+            "DELETE FROM taskcast_tasks WHERE id = ?1",
+        ] {
+            sqlx::query(sql).bind(task_id).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let mut replaced = 0;
+        for tombstone in tombstones {
+            sqlx::query(
+                r#"
+                DELETE FROM taskcast_series_latest
+                WHERE task_id = ?1 AND series_id IN (
+                    SELECT series_id FROM taskcast_events WHERE task_id = ?1 AND id = ?2
+                )
+                "#,
+            )
+            .bind(task_id)
+            .bind(&tombstone.id)
+            .execute(&mut *tx)
+            .await?;
+            replaced += redact_event_row(&mut tx, task_id, tombstone).await?;
+        }
+        tx.commit().await?;
+        Ok(replaced)
+    }
+}
+
+/// WHERE conditions for every part of `filter` but its tags and `limit`.
+///
+/// SQLite doesn't support array parameters, so the conditions use
+/// comma-separated IN lists; callers match tags in Rust after the query.
+fn task_filter_conditions(filter: &TaskFilter) -> Vec<String> {
+    let mut conditions: Vec<String> = Vec::new();
+
+    if let Some(ref statuses) = filter.status {
+        if !statuses.is_empty() {
+            let placeholders: Vec<String> = statuses
+                .iter()
+                .map(|s| format!("'{}'", status_to_string(s)))
+                .collect();
+            conditions.push(format!("status IN ({})", placeholders.join(",")));
+        }
+    }
+
+    if let Some(ref types) = filter.types {
+        if !types.is_empty() {
+            let placeholders: Vec<String> =
+                types.iter().map(|t| format!("'{}'", t.replace('\'', "''"))).collect();
+            conditions.push(format!("type IN ({})", placeholders.join(",")));
+        }
+    }
+
+    if let Some(ref modes) = filter.assign_mode {
+        if !modes.is_empty() {
+            let placeholders: Vec<String> = modes
+                .iter()
+                .map(|m| format!("'{}'", assign_mode_to_string(m)))
+                .collect();
+            conditions.push(format!("assign_mode IN ({})", placeholders.join(",")));
+        }
+    }
+
+    if let Some(ref exclude) = filter.exclude_task_ids {
+        if !exclude.is_empty() {
+            let placeholders: Vec<String> =
+                exclude.iter().map(|id| format!("'{}'", id.replace('\'', "''"))).collect();
+            conditions.push(format!("id NOT IN ({})", placeholders.join(",")));
+        }
+    }
+
+    if let Some(ref origins) = filter.origin {
+        if !origins.is_empty() {
+            let placeholders: Vec<String> = origins
+                .iter()
+                .map(|o| format!("'{}'", o.as_str()))
+                .collect();
+            // Rows written before origins were recorded count as user-created.
+            conditions.push(format!(
+                "COALESCE(json_extract(origin, '$.kind'), 'user') IN ({})",
+                placeholders.join(",")
+            ));
+        }
+    }
+
+    if let Some(created_before) = filter.created_before {
+        conditions.push(format!("created_at < {}", created_before.ceil() as i64));
+    }
+
+    conditions
+}
+
+fn row_to_outcome(
+    row: &sqlx::sqlite::SqliteRow,
+) -> Result<TaskOutcome, Box<dyn std::error::Error + Send + Sync>> {
+    let status: String = row.get("status");
+    Ok(TaskOutcome {
+        task_id: row.get("task_id"),
+        r#type: row.get("type"),
+        status: serde_json::from_value(serde_json::Value::String(status))?,
+        completed_at: row.get("completed_at"),
+        error_code: row.get("error_code"),
+        origin: row
+            .get::<Option<String>, _>("origin")
+            .map(|kind| serde_json::from_value(serde_json::Value::String(kind)))
+            .transpose()?,
+    })
+}
+
+fn row_to_retry_schedule(row: &sqlx::sqlite::SqliteRow) -> RetrySchedule {
+    let attempt: i64 = row.get("attempt");
+    RetrySchedule {
+        task_id: row.get("task_id"),
+        successor_id: row.get("successor_id"),
+        attempt: attempt as u32,
+        due_at: row.get("due_at"),
+    }
 }
@@ -0,0 +1,258 @@
+use std::sync::{Arc, Mutex};
+
+use sqlx::SqlitePool;
+use taskcast_core::types::{
+    ErrorContext, Level, LongTermStore, ShortTermStore, Task, TaskEvent, TaskStatus, TaskcastHooks,
+};
+use taskcast_core::{
+    CorruptRecord, CorruptRecordKind, CorruptRecordPolicy, IntegrityMonitor, CORRUPT_EVENT_TYPE,
+};
+use taskcast_sqlite::{SqliteLongTermStore, SqliteShortTermStore};
+use tempfile::TempDir;
+
+// ─── Helpers ─────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct RecordingHooks {
+    errors: Mutex<Vec<(String, ErrorContext)>>,
+}
+
+impl TaskcastHooks for RecordingHooks {
+    fn on_unhandled_error(
+        &self,
+        err: &(dyn std::error::Error + Send + Sync),
+        context: &ErrorContext,
+    ) {
+        self.errors
+            .lock()
+            .unwrap()
+            .push((err.to_string(), context.clone()));
+    }
+}
+
+struct Context {
+    short: SqliteShortTermStore,
+    long: SqliteLongTermStore,
+    pool: SqlitePool,
+    monitor: IntegrityMonitor,
+    hooks: Arc<RecordingHooks>,
+    _dir: TempDir,
+}
+
+async fn setup(policy: CorruptRecordPolicy) -> Context {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("test.db");
+    let db_path = db_path.to_str().unwrap();
+    let adapters = taskcast_sqlite::create_sqlite_adapters(db_path)
+        .await
+        .unwrap();
+    let pool = SqlitePool::connect(&format!("sqlite:{db_path}"))
+        .await
+        .unwrap();
+    let hooks = Arc::new(RecordingHooks::default());
+    let monitor =
+        IntegrityMonitor::with_hooks(policy, Some(hooks.clone() as Arc<dyn TaskcastHooks>));
+
+    Context {
+        short: adapters.short_term_store.with_integrity(monitor.clone()),
+        long: adapters.long_term_store.with_integrity(monitor.clone()),
+        pool,
+        monitor,
+        hooks,
+        _dir: dir,
+    }
+}
+
+fn make_task(id: &str) -> Task {
+    Task {
+        id: id.to_string(),
+        r#type: None,
+        status: TaskStatus::Pending,
+        params: None,
+        result: None,
+        error: None,
+        metadata: None,
+        auth_config: None,
+        webhooks: None,
+        cleanup: None,
+        created_at: 1000.0,
+        updated_at: 1000.0,
+        completed_at: None,
+        ttl: None,
+        tags: None,
+        assign_mode: None,
+        cost: None,
+        assigned_worker: None,
+        disconnect_policy: None,
+        reason: None,
+        resume_at: None,
+        blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
+    }
+}
+
+fn make_event(task_id: &str, index: u64) -> TaskEvent {
+    TaskEvent {
+        id: format!("evt-{task_id}-{index}"),
+        task_id: task_id.to_string(),
+        index,
+        timestamp: 1000.0 + index as f64,
+        r#type: "log".to_string(),
+        level: Level::Info,
+        data: serde_json::json!({ "i": index }),
+        series_id: None,
+        series_mode: None,
+        series_acc_field: None,
+        series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
+        _accumulated_data: None,
+    }
+}
+
+/// Saves a task with three valid events, then corrupts the event at index 1
+/// with `sql`, which is bound to that event's id.
+async fn seed_and_corrupt_event(ctx: &Context, sql: &str) {
+    ctx.long.save_task(make_task("t1")).await.unwrap();
+    for i in 0..3 {
+        ctx.long.save_event(make_event("t1", i)).await.unwrap();
+    }
+    sqlx::query(sql)
+        .bind("evt-t1-1")
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+}
+
+async fn set_task_status(ctx: &Context, task_id: &str, status: &str) {
+    sqlx::query("UPDATE taskcast_tasks SET status = ?1 WHERE id = ?2")
+        .bind(status)
+        .bind(task_id)
+        .execute(&ctx.pool)
+        .await
+        .unwrap();
+}
+
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_returns_invalid_level_as_corrupt_event() {
+    let ctx = setup(CorruptRecordPolicy::Surface).await;
+    seed_and_corrupt_event(
+        &ctx,
+        "UPDATE taskcast_events SET level = 'critical' WHERE id = ?1",
+    )
+    .await;
+
+    for events in [
+        ctx.long.get_events("t1", None).await.unwrap(),
+        ctx.short.get_events("t1", None).await.unwrap(),
+    ] {
+        assert_eq!(events.len(), 3);
+        let corrupt = &events[1];
+        assert_eq!(corrupt.id, "evt-t1-1");
+        assert_eq!(corrupt.index, 1);
+        assert_eq!(corrupt.r#type, CORRUPT_EVENT_TYPE);
+        assert_eq!(corrupt.data["raw"]["level"], "critical");
+        assert!(corrupt.data["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("level:"));
+        assert_eq!(events[2].data, serde_json::json!({ "i": 2 }));
+    }
+
+    // Both reads saw the same record.
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+    assert!(ctx.hooks.errors.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn surface_policy_reports_corrupt_data_instead_of_null() {
+    let ctx = setup(CorruptRecordPolicy::Surface).await;
+    seed_and_corrupt_event(
+        &ctx,
+        "UPDATE taskcast_events SET data = '{\"text\":' WHERE id = ?1",
+    )
+    .await;
+
+    let events = ctx.long.get_events("t1", None).await.unwrap();
+    assert_eq!(events[1].r#type, CORRUPT_EVENT_TYPE);
+    assert_eq!(events[1].data["raw"]["data"], "{\"text\":");
+    assert!(events[1].data["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("data:"));
+}
+
+#[tokio::test]
+async fn skip_policy_drops_corrupt_event_and_reports_it() {
+    let ctx = setup(CorruptRecordPolicy::Skip).await;
+    seed_and_corrupt_event(
+        &ctx,
+        "UPDATE taskcast_events SET series_mode = 'sometimes' WHERE id = ?1",
+    )
+    .await;
+
+    let events = ctx.short.get_events("t1", None).await.unwrap();
+    let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indices, vec![0, 2]);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].0.contains("evt-t1-1"), "{}", errors[0].0);
+    assert_eq!(errors[0].1.operation, "storage.read_event");
+    assert_eq!(errors[0].1.task_id.as_deref(), Some("t1"));
+    drop(errors);
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+// ─── Tasks ───────────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn surface_policy_fails_task_read_with_unknown_status() {
+    let ctx = setup(CorruptRecordPolicy::Surface).await;
+    ctx.long.save_task(make_task("t1")).await.unwrap();
+    set_task_status(&ctx, "t1", "exploded").await;
+
+    for err in [
+        ctx.long.get_task("t1").await.unwrap_err(),
+        ctx.short.get_task("t1").await.unwrap_err(),
+    ] {
+        let record = err.downcast_ref::<CorruptRecord>().expect("corrupt record");
+        assert_eq!(record.kind, CorruptRecordKind::Task);
+        assert_eq!(record.task_id, "t1");
+        assert_eq!(record.raw["status"], "exploded");
+    }
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+}
+
+#[tokio::test]
+async fn skip_policy_hides_corrupt_task_from_reads_and_listings() {
+    let ctx = setup(CorruptRecordPolicy::Skip).await;
+    ctx.short.save_task(make_task("t1")).await.unwrap();
+    ctx.short.save_task(make_task("t2")).await.unwrap();
+    set_task_status(&ctx, "t1", "exploded").await;
+
+    assert_eq!(ctx.long.get_task("t1").await.unwrap(), None);
+    let listed = ctx.short.list_tasks(Default::default()).await.unwrap();
+    let ids: Vec<&str> = listed.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["t2"]);
+
+    let errors = ctx.hooks.errors.lock().unwrap();
+    assert!(errors
+        .iter()
+        .all(|(_, context)| context.operation == "storage.read_task"));
+    assert!(!errors.is_empty());
+    drop(errors);
+    assert_eq!(ctx.monitor.corrupt_records("t1"), 1);
+    assert_eq!(ctx.monitor.total_corrupt_records(), 1);
+}
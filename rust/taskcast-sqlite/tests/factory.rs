@@ -40,6 +40,16 @@ async fn returns_working_adapters() {
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     };
 
     adapters
@@ -81,6 +91,9 @@ async fn imports_archive_through_paired_sqlite_adapters() {
         broadcast: Arc::new(MemoryBroadcastProvider::new()),
         long_term_store: Some(long.clone()),
         hooks: None,
+        label_limits: None,
+        sinks: Vec::new(),
+        coalesce_reads: None,
     });
     let archive = TaskArchive {
         schema: "taskcast.taskArchive".to_string(),
@@ -109,6 +122,16 @@ async fn imports_archive_through_paired_sqlite_adapters() {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         },
         events: vec![TaskEvent {
             id: "archive-event-0".to_string(),
@@ -122,6 +145,8 @@ async fn imports_archive_through_paired_sqlite_adapters() {
             series_mode: None,
             series_acc_field: None,
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }],
     };
@@ -137,6 +162,9 @@ async fn imports_archive_through_paired_sqlite_adapters() {
                 series_id: None,
                 series_mode: None,
                 series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
             },
         )
         .await
@@ -204,6 +232,16 @@ fn rich_archive(task_id: &str, event_id: &str) -> TaskArchive {
             reason: None,
             resume_at: None,
             blocked_request: None,
+            filters: None,
+            retry_policy: None,
+            group_policy: None,
+            forward_to: None,
+            scheduled_for: None,
+            deadline_ms: None,
+            origin: None,
+            parent_id: None,
+            completion_policy: None,
+            version: 0,
         },
         events: vec![TaskEvent {
             id: event_id.to_string(),
@@ -217,6 +255,8 @@ fn rich_archive(task_id: &str, event_id: &str) -> TaskArchive {
             series_mode: Some(SeriesMode::Accumulate),
             series_acc_field: Some("delta".to_string()),
             series_snapshot: None,
+            labels: None,
+            replaces_event_id: None,
             _accumulated_data: None,
         }],
     }
@@ -392,3 +432,18 @@ async fn creates_database_file() {
 
     assert!(db_path.exists());
 }
+
+#[tokio::test]
+async fn reopening_an_existing_database_reruns_migrations() {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("reopen.db");
+
+    let first = create_sqlite_adapters(db_path.to_str().unwrap())
+        .await
+        .unwrap();
+    drop(first);
+
+    let _second = create_sqlite_adapters(db_path.to_str().unwrap())
+        .await
+        .unwrap();
+}
@@ -1,4 +1,6 @@
-use taskcast_core::types::{Level, Task, TaskEvent, TaskStatus, WorkerAuditAction, WorkerAuditEvent};
+use taskcast_core::types::{
+    Level, Task, TaskEvent, TaskStatus, WorkerAuditAction, WorkerAuditEvent,
+};
 use taskcast_sqlite::{SqliteLongTermStore, SqliteShortTermStore};
 use tempfile::TempDir;
 
@@ -50,6 +52,16 @@ pub fn make_task(id: &str) -> Task {
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     }
 }
 
@@ -66,6 +78,8 @@ pub fn make_event(task_id: &str, index: u64) -> TaskEvent {
         series_mode: None,
         series_acc_field: None,
         series_snapshot: None,
+        labels: None,
+        replaces_event_id: None,
         _accumulated_data: None,
     }
 }
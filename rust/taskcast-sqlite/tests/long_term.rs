@@ -1,8 +1,11 @@
 mod helpers;
 
 use helpers::{make_event, make_task, make_worker_event, setup};
+use serde_json::json;
+use taskcast_core::{ConventionNotes, EventConvention};
 use taskcast_core::types::{
-    EventQueryOptions, LongTermStore, SeriesMode, SinceCursor, TaskStatus, WorkerAuditAction,
+    EventQueryOptions, LongTermStore, SeriesMode, SinceCursor, TaskEvent, TaskStatus,
+    WorkerAuditAction,
 };
 
 // ─── save_task / get_task ─────────────────────────────────────────────────
@@ -93,6 +96,16 @@ async fn handle_task_with_no_optional_fields() {
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     };
     ctx.long.save_task(task.clone()).await.unwrap();
     let retrieved = ctx.long.get_task("minimal").await.unwrap().unwrap();
@@ -118,6 +131,35 @@ async fn save_and_retrieve_events() {
     assert_eq!(events, vec![e0, e1, e2]);
 }
 
+#[tokio::test]
+async fn return_events_in_index_order_whatever_the_insertion_order() {
+    let ctx = setup().await;
+    ctx.long.save_task(make_task("task-1")).await.unwrap();
+
+    let e0 = make_event("task-1", 0);
+    let e1 = make_event("task-1", 1);
+    let e2 = make_event("task-1", 2);
+
+    ctx.long.save_event(e2.clone()).await.unwrap();
+    ctx.long.save_event(e0.clone()).await.unwrap();
+    ctx.long.save_event(e1.clone()).await.unwrap();
+
+    let events = ctx.long.get_events("task-1", None).await.unwrap();
+    assert_eq!(events, vec![e0, e1.clone(), e2.clone()]);
+
+    // since.index is exclusive.
+    let opts = EventQueryOptions {
+        since: Some(SinceCursor {
+            id: None,
+            index: Some(0),
+            timestamp: None,
+        }),
+        ..Default::default()
+    };
+    let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
+    assert_eq!(events, vec![e1, e2]);
+}
+
 #[tokio::test]
 async fn compact_latest_series_events_in_long_term_storage() {
     let ctx = setup().await;
@@ -224,6 +266,9 @@ async fn filter_events_by_since_index() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -246,6 +291,9 @@ async fn filter_events_by_since_timestamp() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -268,6 +316,9 @@ async fn filter_events_by_since_id() {
             id: Some("evt-task-1-2".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -290,9 +341,38 @@ async fn return_all_events_when_since_id_not_found() {
             id: Some("nonexistent-id".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
+    };
+    let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
+    assert_eq!(events.len(), 3);
+}
+
+#[tokio::test]
+async fn since_id_from_another_task_returns_all_events() {
+    let ctx = setup().await;
+    ctx.long.save_task(make_task("task-1")).await.unwrap();
+    ctx.long.save_task(make_task("task-2")).await.unwrap();
+    for i in 0..3 {
+        ctx.long.save_event(make_event("task-1", i)).await.unwrap();
+        ctx.long.save_event(make_event("task-2", i)).await.unwrap();
+    }
+
+    let opts = EventQueryOptions {
+        since: Some(SinceCursor {
+            index: None,
+            timestamp: None,
+            id: Some("evt-task-2-1".to_string()),
+        }),
+        limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
+    assert_eq!(events[0].id, "evt-task-1-0");
 }
 
 #[tokio::test]
@@ -306,6 +386,9 @@ async fn respect_limit_parameter() {
     let opts = EventQueryOptions {
         since: None,
         limit: Some(3),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -328,6 +411,9 @@ async fn apply_limit_after_since_filter() {
             id: None,
         }),
         limit: Some(2),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -335,6 +421,81 @@ async fn apply_limit_after_since_filter() {
     assert_eq!(events[1].index, 7);
 }
 
+#[tokio::test]
+async fn until_keeps_events_at_or_before_the_bound() {
+    let ctx = setup().await;
+    ctx.long.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..5 {
+        ctx.long.save_event(make_event("task-1", i)).await.unwrap();
+    }
+
+    // Event i is timestamped 1000 + 100 * i.
+    let query = |since: Option<u64>, limit: Option<u64>, until: f64| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit,
+        label_selector: None,
+        until: Some(until),
+        before_index: None,
+    };
+    let indexes = |events: Vec<TaskEvent>| events.iter().map(|e| e.index).collect::<Vec<u64>>();
+
+    let events = ctx.long
+        .get_events("task-1", Some(query(None, None, 1200.0)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![0, 1, 2]);
+
+    // `until` applies before the limit, alongside the cursor
+    let events = ctx.long
+        .get_events("task-1", Some(query(Some(0), Some(1), 1250.5)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![1]);
+
+    let events = ctx.long
+        .get_events("task-1", Some(query(None, Some(5), 999.0)))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn before_index_bounds_paged_reads() {
+    let ctx = setup().await;
+    ctx.long.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..6 {
+        ctx.long.save_event(make_event("task-1", i)).await.unwrap();
+    }
+
+    // A scan that ended at index 4, read two events at a time.
+    let page = |since: Option<u64>| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit: Some(2),
+        before_index: Some(4),
+        ..Default::default()
+    };
+    let mut indexes: Vec<u64> = Vec::new();
+    loop {
+        let events = ctx.long
+            .get_events("task-1", Some(page(indexes.last().copied())))
+            .await
+            .unwrap();
+        if events.is_empty() {
+            break;
+        }
+        indexes.extend(events.iter().map(|e| e.index));
+    }
+    assert_eq!(indexes, vec![0, 1, 2, 3]);
+}
+
 #[tokio::test]
 async fn save_event_on_conflict_do_nothing() {
     let ctx = setup().await;
@@ -362,6 +523,35 @@ async fn preserve_series_fields_on_events() {
     assert_eq!(events[0], event);
 }
 
+#[tokio::test]
+async fn redact_events_overwrites_events_in_place() {
+    let ctx = setup().await;
+    ctx.long.save_task(make_task("task-1")).await.unwrap();
+    for index in 0..3 {
+        ctx.long.save_event(make_event("task-1", index)).await.unwrap();
+    }
+
+    let tombstone = taskcast_core::tombstone(&make_event("task-1", 1), None);
+    assert_eq!(
+        ctx.long
+            .redact_events("task-1", std::slice::from_ref(&tombstone))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        ctx.long
+            .redact_events("task-2", std::slice::from_ref(&tombstone))
+            .await
+            .unwrap(),
+        0
+    );
+
+    let events = ctx.long.get_events("task-1", None).await.unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1], tombstone);
+}
+
 // ─── save_worker_event / get_worker_events ──────────────────────────────────
 
 #[tokio::test]
@@ -423,6 +613,9 @@ async fn filter_worker_events_by_since_timestamp() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -448,6 +641,9 @@ async fn filter_worker_events_by_since_id() {
             id: Some("wevt-w1-2".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -472,6 +668,9 @@ async fn return_all_worker_events_when_since_id_not_found() {
             id: Some("nonexistent-id".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -490,6 +689,9 @@ async fn respect_limit_on_worker_events() {
     let opts = EventQueryOptions {
         since: None,
         limit: Some(3),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -515,6 +717,9 @@ async fn apply_limit_after_since_filter_on_worker_events() {
             id: None,
         }),
         limit: Some(2),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -640,6 +845,9 @@ async fn worker_events_since_with_empty_cursor_returns_all() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -664,8 +872,77 @@ async fn worker_events_since_id_takes_priority_over_timestamp() {
             id: Some("wevt-w1-3".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.long.get_worker_events("w1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 1);
     assert_eq!(events[0].id, "wevt-w1-4");
 }
+
+// ─── event conventions ────────────────────────────────────────────────────
+
+fn sample(
+    event_type: &str,
+    count: u64,
+    at: f64,
+    example: Option<serde_json::Value>,
+) -> EventConvention {
+    EventConvention {
+        task_type: "llm.chat".to_string(),
+        event_type: event_type.to_string(),
+        estimated_count: count,
+        last_seen_at: Some(at),
+        example_at: example.as_ref().map(|_| at),
+        example,
+        description: None,
+        schema: None,
+    }
+}
+
+#[tokio::test]
+async fn event_conventions_add_up_and_keep_notes() {
+    let ctx = setup().await;
+    let store = ctx.long.event_conventions().unwrap();
+    let notes = ConventionNotes {
+        description: Some("Incremental completion text".to_string()),
+        schema: Some(json!({ "type": "object" })),
+    };
+    store
+        .annotate_event_convention("llm.chat", "llm.delta", &notes)
+        .await
+        .unwrap();
+    store
+        .record_event_conventions(&[
+            sample("llm.delta", 20, 1000.0, Some(json!({ "text": "a" }))),
+            sample("llm.done", 20, 1000.0, None),
+        ])
+        .await
+        .unwrap();
+    store
+        .record_event_conventions(&[sample("llm.delta", 20, 2000.0, None)])
+        .await
+        .unwrap();
+
+    let conventions = store
+        .list_event_conventions(Some("llm.chat"))
+        .await
+        .unwrap();
+    assert_eq!(conventions.len(), 2);
+    let delta = &conventions[0];
+    assert_eq!(delta.event_type, "llm.delta");
+    assert_eq!(delta.estimated_count, 40);
+    assert_eq!(delta.last_seen_at, Some(2000.0));
+    // A sample without an example keeps the earlier one.
+    assert_eq!(delta.example, Some(json!({ "text": "a" })));
+    assert_eq!(delta.example_at, Some(1000.0));
+    assert_eq!(delta.description, notes.description);
+    assert_eq!(delta.schema, notes.schema);
+    assert!(store
+        .list_event_conventions(Some("other"))
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(store.list_event_conventions(None).await.unwrap().len(), 2);
+}
@@ -1,9 +1,13 @@
 mod helpers;
 
+use std::collections::HashMap;
+
 use helpers::{make_event, make_task, setup};
 use taskcast_core::types::{
-    AssignMode, ConnectionMode, DisconnectPolicy, EventQueryOptions, SeriesMode, ShortTermStore,
-    SinceCursor, TagMatcher, TaskFilter, TaskStatus, Worker, WorkerAssignment,
+    AssignMode, BackoffStrategy, CompletionMode, CompletionPolicy, ConnectionMode, DisconnectPolicy,
+    EventQueryOptions,
+    FilteredIndexClaim, FilteredIndexMark, Level,
+    OutcomeCursor, OutcomeQuery, RetryOn, RetryPolicy, RetrySchedule, SeriesMode, ShortTermStore, SinceCursor, SubscribeFilter, TagMatcher, TaskEvent, TaskFilter, TaskOrigin, TaskOriginKind, TaskOutcome, TaskStatus, Worker, WorkerAssignment,
     WorkerAssignmentStatus, WorkerFilter, WorkerMatchRule, WorkerStatus,
 };
 
@@ -95,6 +99,16 @@ async fn handle_task_with_no_optional_fields() {
         reason: None,
         resume_at: None,
         blocked_request: None,
+        filters: None,
+        retry_policy: None,
+        group_policy: None,
+        forward_to: None,
+        scheduled_for: None,
+        deadline_ms: None,
+        origin: None,
+        parent_id: None,
+        completion_policy: None,
+        version: 0,
     };
     ctx.short.save_task(task.clone()).await.unwrap();
     let retrieved = ctx.short.get_task("minimal").await.unwrap().unwrap();
@@ -108,6 +122,366 @@ async fn handle_task_with_no_optional_fields() {
     assert!(retrieved.ttl.is_none());
 }
 
+#[tokio::test]
+async fn round_trip_and_update_filter_presets() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.filters = Some(HashMap::from([(
+        "errorsOnly".to_string(),
+        SubscribeFilter {
+            levels: Some(vec![Level::Error]),
+            ..Default::default()
+        },
+    )]));
+    ctx.short.save_task(task.clone()).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved, task);
+
+    task.filters = None;
+    ctx.short.save_task(task.clone()).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved.filters, None);
+}
+
+#[tokio::test]
+async fn round_trip_retry_policy() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.retry_policy = Some(RetryPolicy {
+        max_attempts: 3,
+        backoff: BackoffStrategy::Exponential,
+        initial_delay_ms: 1000,
+        max_delay_ms: 30000,
+        retry_on: vec![RetryOn::Failed],
+    });
+    ctx.short.save_task(task.clone()).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved, task);
+}
+
+#[tokio::test]
+async fn origin_round_trips_and_is_kept_on_update() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.origin = Some(TaskOrigin::new(TaskOriginKind::Retry).with_source_task_id("task-0"));
+    ctx.short.save_task(task.clone()).await.unwrap();
+    assert_eq!(
+        ctx.short.get_task("task-1").await.unwrap(),
+        Some(task.clone())
+    );
+
+    let mut updated = task.clone();
+    updated.status = TaskStatus::Running;
+    updated.origin = Some(TaskOrigin::new(TaskOriginKind::User));
+    ctx.short.save_task(updated).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved.status, TaskStatus::Running);
+    assert_eq!(retrieved.origin, task.origin);
+}
+
+#[tokio::test]
+async fn completion_policy_and_parent_round_trip_and_update() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.parent_id = Some("task-0".to_string());
+    task.completion_policy = Some(CompletionPolicy {
+        mode: CompletionMode::AllChildren,
+        child_ids: vec!["task-2".to_string()],
+    });
+    ctx.short.save_task(task.clone()).await.unwrap();
+    assert_eq!(
+        ctx.short.get_task("task-1").await.unwrap(),
+        Some(task.clone())
+    );
+
+    let mut updated = task.clone();
+    updated
+        .completion_policy
+        .as_mut()
+        .unwrap()
+        .child_ids
+        .push("task-3".to_string());
+    ctx.short.save_task(updated.clone()).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved.completion_policy, updated.completion_policy);
+}
+
+// ─── retry schedules ────────────────────────────────────────────────────
+
+fn make_retry(task_id: &str, due_at: f64) -> RetrySchedule {
+    RetrySchedule {
+        task_id: task_id.to_string(),
+        successor_id: format!("{task_id}-retry"),
+        attempt: 2,
+        due_at,
+    }
+}
+
+#[tokio::test]
+async fn list_due_retries_in_due_order() {
+    let ctx = setup().await;
+    ctx.short.save_retry_schedule(make_retry("b", 2000.0)).await.unwrap();
+    ctx.short.save_retry_schedule(make_retry("a", 1000.0)).await.unwrap();
+    ctx.short.save_retry_schedule(make_retry("c", 5000.0)).await.unwrap();
+
+    let due = ctx.short.list_due_retries(2000.0).await.unwrap();
+    let ids: Vec<&str> = due.iter().map(|s| s.task_id.as_str()).collect();
+    assert_eq!(ids, ["a", "b"]);
+    assert_eq!(
+        ctx.short.get_retry_schedule("c").await.unwrap(),
+        Some(make_retry("c", 5000.0))
+    );
+}
+
+#[tokio::test]
+async fn claim_retry_is_exclusive_until_the_lease_expires() {
+    let ctx = setup().await;
+    ctx.short.save_retry_schedule(make_retry("t1", 1000.0)).await.unwrap();
+
+    assert!(ctx.short.claim_retry("t1", 1000.0, 500).await.unwrap());
+    assert!(!ctx.short.claim_retry("t1", 1400.0, 500).await.unwrap());
+    assert!(ctx.short.claim_retry("t1", 1500.0, 500).await.unwrap());
+    assert!(!ctx.short.claim_retry("missing", 1000.0, 500).await.unwrap());
+
+    ctx.short.delete_retry_schedule("t1").await.unwrap();
+    assert!(ctx.short.get_retry_schedule("t1").await.unwrap().is_none());
+    assert!(ctx.short.list_due_retries(f64::MAX).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn round_trip_scheduled_for() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.status = TaskStatus::Scheduled;
+    task.scheduled_for = Some(1_700_000_060_000.0);
+    ctx.short.save_task(task.clone()).await.unwrap();
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved, task);
+}
+
+#[tokio::test]
+async fn save_task_versioned_rejects_a_stale_version() {
+    let ctx = setup().await;
+    let mut task = make_task("task-1");
+    task.version = 1;
+    assert!(ctx.short.save_task_versioned(task.clone(), 0).await.unwrap());
+
+    task.version = 2;
+    task.status = TaskStatus::Running;
+    assert!(!ctx.short.save_task_versioned(task.clone(), 0).await.unwrap());
+    let retrieved = ctx.short.get_task("task-1").await.unwrap().unwrap();
+    assert_eq!(retrieved.version, 1);
+    assert_eq!(retrieved.status, TaskStatus::Pending);
+
+    assert!(ctx.short.save_task_versioned(task.clone(), 1).await.unwrap());
+    assert_eq!(ctx.short.get_task("task-1").await.unwrap().unwrap(), task);
+}
+
+// ─── scheduled activations ──────────────────────────────────────────────
+
+#[tokio::test]
+async fn list_due_activations_in_time_order() {
+    let ctx = setup().await;
+    ctx.short.save_activation("b", 2000.0).await.unwrap();
+    ctx.short.save_activation("a", 1000.0).await.unwrap();
+    ctx.short.save_activation("c", 5000.0).await.unwrap();
+
+    assert_eq!(
+        ctx.short.list_due_activations(2000.0).await.unwrap(),
+        ["a", "b"]
+    );
+}
+
+#[tokio::test]
+async fn claim_activation_is_exclusive_until_the_lease_expires() {
+    let ctx = setup().await;
+    ctx.short.save_activation("t1", 1000.0).await.unwrap();
+
+    assert!(ctx.short.claim_activation("t1", 1000.0, 500).await.unwrap());
+    assert!(!ctx.short.claim_activation("t1", 1400.0, 500).await.unwrap());
+    assert!(ctx.short.claim_activation("t1", 1500.0, 500).await.unwrap());
+    assert!(!ctx.short.claim_activation("missing", 1000.0, 500).await.unwrap());
+
+    ctx.short.delete_activation("t1").await.unwrap();
+    assert!(ctx.short.list_due_activations(f64::MAX).await.unwrap().is_empty());
+}
+
+// ─── webhook suppression ────────────────────────────────────────────────
+
+#[tokio::test]
+async fn claim_webhook_delivery_suppresses_within_the_window() {
+    let ctx = setup().await;
+    let claim = |webhook, fingerprint, now| {
+        ctx.short
+            .claim_webhook_delivery("t1", webhook, fingerprint, now, 1000)
+    };
+
+    assert!(claim(0, "llm.error:error", 0.0).await.unwrap());
+    assert!(!claim(0, "llm.error:error", 999.0).await.unwrap());
+    // Other webhooks and fingerprints keep their own windows.
+    assert!(claim(1, "llm.error:error", 999.0).await.unwrap());
+    assert!(claim(0, "llm.error:warn", 999.0).await.unwrap());
+    assert!(claim(0, "llm.error:error", 1000.0).await.unwrap());
+    assert!(!claim(0, "llm.error:error", 1500.0).await.unwrap());
+}
+
+// ─── filtered indices ────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn claim_filtered_index_numbers_per_consumer_and_filter() {
+    let ctx = setup().await;
+    let claim = |consumer, hash, raw| ctx.short.claim_filtered_index("t1", consumer, hash, raw);
+    let at = |filtered_index, epoch| Some(FilteredIndexClaim { filtered_index, epoch });
+
+    assert_eq!(claim("a", "h1", 3).await.unwrap(), at(0, 0));
+    assert_eq!(claim("a", "h1", 5).await.unwrap(), at(1, 0));
+    assert_eq!(claim("b", "h1", 5).await.unwrap(), at(0, 0));
+    // A new filter restarts the numbering in the next epoch.
+    assert_eq!(claim("a", "h2", 6).await.unwrap(), at(0, 1));
+    assert_eq!(
+        ctx.short.get_filtered_index_mark("t1", "a").await.unwrap(),
+        Some(FilteredIndexMark {
+            filter_hash: "h2".to_string(),
+            epoch: 1,
+            next: 1,
+            through: Some(6),
+        })
+    );
+    assert_eq!(ctx.short.get_filtered_index_mark("t2", "a").await.unwrap(), None);
+}
+
+// ─── deadline warnings ───────────────────────────────────────────────────
+
+#[tokio::test]
+async fn claim_deadline_warning_succeeds_once() {
+    let ctx = setup().await;
+    let claim = |task_id, warning| ctx.short.claim_deadline_warning(task_id, warning, 1000);
+
+    assert!(claim("t1", "atFraction:0.8").await.unwrap());
+    assert!(!claim("t1", "atFraction:0.8").await.unwrap());
+    assert!(claim("t1", "beforeMs:1000").await.unwrap());
+    assert!(claim("t2", "atFraction:0.8").await.unwrap());
+}
+
+// ─── delete_task ─────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn delete_task_removes_the_task_and_its_state() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    ctx.short.save_task(make_task("task-2")).await.unwrap();
+    for task_id in ["task-1", "task-2"] {
+        ctx.short.next_index(task_id).await.unwrap();
+        ctx.short
+            .append_event(task_id, make_event(task_id, 0))
+            .await
+            .unwrap();
+    }
+    ctx.short
+        .set_series_latest("task-1", "s1", make_event("task-1", 0))
+        .await
+        .unwrap();
+    ctx.short.save_retry_schedule(make_retry("task-1", 1000.0)).await.unwrap();
+    ctx.short
+        .claim_webhook_delivery("task-1", 0, "log:info", 0.0, 1000)
+        .await
+        .unwrap();
+    ctx.short
+        .claim_deadline_warning("task-1", "beforeMs:1000", 1000)
+        .await
+        .unwrap();
+    ctx.short
+        .claim_filtered_index("task-1", "sse:reader", "h", 0)
+        .await
+        .unwrap();
+
+    ctx.short.delete_task("task-1").await.unwrap();
+    ctx.short.delete_task("missing").await.unwrap();
+
+    assert!(ctx.short.get_task("task-1").await.unwrap().is_none());
+    assert!(ctx.short.get_events("task-1", None).await.unwrap().is_empty());
+    assert!(ctx.short.get_series_latest("task-1", "s1").await.unwrap().is_none());
+    assert!(ctx.short.get_retry_schedule("task-1").await.unwrap().is_none());
+    assert_eq!(ctx.short.event_count("task-1").await.unwrap(), 0);
+    assert!(ctx
+        .short
+        .claim_webhook_delivery("task-1", 0, "log:info", 1.0, 1000)
+        .await
+        .unwrap());
+    assert!(ctx
+        .short
+        .claim_deadline_warning("task-1", "beforeMs:1000", 1000)
+        .await
+        .unwrap());
+    assert_eq!(
+        ctx.short
+            .get_filtered_index_mark("task-1", "sse:reader")
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(ctx.short.get_task("task-2").await.unwrap().is_some());
+    assert_eq!(ctx.short.get_events("task-2", None).await.unwrap().len(), 1);
+}
+
+// ─── redact_events ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn redact_events_overwrites_events_in_place() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    let mut series_event = make_event("task-1", 1);
+    series_event.series_id = Some("s1".to_string());
+    series_event.series_mode = Some(SeriesMode::Latest);
+    for event in [make_event("task-1", 0), series_event.clone(), make_event("task-1", 2)] {
+        ctx.short.append_event("task-1", event).await.unwrap();
+    }
+    ctx.short
+        .set_series_latest("task-1", "s1", series_event.clone())
+        .await
+        .unwrap();
+
+    let tombstones: Vec<TaskEvent> = [&series_event, &make_event("task-1", 9)]
+        .into_iter()
+        .map(|event| taskcast_core::tombstone(event, Some("erasure request")))
+        .collect();
+    assert_eq!(ctx.short.redact_events("task-1", &tombstones).await.unwrap(), 1);
+
+    let events = ctx.short.get_events("task-1", None).await.unwrap();
+    let indices: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+    assert_eq!(events[1], tombstones[0]);
+    assert_eq!(events[2], make_event("task-1", 2));
+    assert!(ctx.short.get_series_latest("task-1", "s1").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn list_series_orders_by_series_id_and_counts() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for (index, sid, mode) in [
+        (0, "status", SeriesMode::Latest),
+        (1, "answer", SeriesMode::Accumulate),
+        (2, "status", SeriesMode::Latest),
+    ] {
+        let mut event = make_event("task-1", index);
+        event.series_id = Some(sid.to_string());
+        event.series_mode = Some(mode);
+        ctx.short
+            .set_series_latest("task-1", sid, event)
+            .await
+            .unwrap();
+    }
+
+    let series = ctx.short.list_series("task-1").await.unwrap();
+    let ids: Vec<&str> = series.iter().map(|s| s.series_id.as_str()).collect();
+    assert_eq!(ids, vec!["answer", "status"]);
+    assert_eq!(series[1].mode, Some(SeriesMode::Latest));
+    assert_eq!(series[1].latest_index, 2);
+    assert_eq!(ctx.short.series_count("task-1").await.unwrap(), 2);
+    assert_eq!(ctx.short.series_count("task-2").await.unwrap(), 0);
+}
+
 // ─── next_index ──────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -139,6 +513,63 @@ async fn maintain_separate_counters_per_task() {
     assert_eq!(a1, 1);
 }
 
+#[tokio::test]
+async fn event_count_reads_counter_without_advancing_it() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+
+    assert_eq!(ctx.short.event_count("task-1").await.unwrap(), 0);
+    ctx.short.next_index("task-1").await.unwrap();
+    ctx.short.next_index("task-1").await.unwrap();
+
+    assert_eq!(ctx.short.event_count("task-1").await.unwrap(), 2);
+    assert_eq!(ctx.short.event_count("task-1").await.unwrap(), 2);
+    assert_eq!(ctx.short.next_index("task-1").await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn event_type_counts_follow_appends_replacements_and_deletion() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for index in 0..2 {
+        ctx.short
+            .append_event("task-1", make_event("task-1", index))
+            .await
+            .unwrap();
+    }
+    let mut phase = make_event("task-1", 2);
+    phase.r#type = "phase.plan".to_string();
+    ctx.short
+        .replace_last_series_event("task-1", "phase", phase)
+        .await
+        .unwrap();
+
+    let counts = ctx.short.get_event_type_counts("task-1").await.unwrap();
+    assert_eq!(counts.types["llm.delta"], 2);
+    assert_eq!(counts.types["phase.plan"], 1);
+    assert_eq!(counts.levels[&Level::Info], 3);
+
+    let mut next_phase = make_event("task-1", 3);
+    next_phase.r#type = "phase.execute".to_string();
+    next_phase.level = Level::Warn;
+    ctx.short
+        .replace_last_series_event("task-1", "phase", next_phase)
+        .await
+        .unwrap();
+    let counts = ctx.short.get_event_type_counts("task-1").await.unwrap();
+    assert!(!counts.types.contains_key("phase.plan"));
+    assert_eq!(counts.types["phase.execute"], 1);
+    assert_eq!(counts.levels[&Level::Warn], 1);
+
+    ctx.short.delete_task("task-1").await.unwrap();
+    assert!(ctx
+        .short
+        .get_event_type_counts("task-1")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
 // ─── append_event / get_events ────────────────────────────────────────────
 
 #[tokio::test]
@@ -184,6 +615,9 @@ async fn filter_events_by_since_index() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -210,6 +644,9 @@ async fn filter_events_by_since_timestamp() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -235,6 +672,9 @@ async fn filter_events_by_since_id() {
             id: Some("evt-task-1-2".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -260,9 +700,98 @@ async fn return_all_events_when_since_id_not_found() {
             id: Some("nonexistent-id".to_string()),
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
+    };
+    let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
+    assert_eq!(events.len(), 3);
+}
+
+#[tokio::test]
+async fn since_id_takes_priority_over_index() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..5 {
+        ctx.short
+            .append_event("task-1", make_event("task-1", i))
+            .await
+            .unwrap();
+    }
+
+    let opts = EventQueryOptions {
+        since: Some(SinceCursor {
+            index: Some(0),
+            timestamp: Some(0.0),
+            id: Some("evt-task-1-3".to_string()),
+        }),
+        limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
+    };
+    let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].index, 4);
+}
+
+#[tokio::test]
+async fn since_id_from_another_task_returns_all_events() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    ctx.short.save_task(make_task("task-2")).await.unwrap();
+    for i in 0..3 {
+        ctx.short
+            .append_event("task-1", make_event("task-1", i))
+            .await
+            .unwrap();
+        ctx.short
+            .append_event("task-2", make_event("task-2", i))
+            .await
+            .unwrap();
+    }
+
+    let opts = EventQueryOptions {
+        since: Some(SinceCursor {
+            index: None,
+            timestamp: None,
+            id: Some("evt-task-2-1".to_string()),
+        }),
+        limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
+    assert_eq!(events[0].id, "evt-task-1-0");
+}
+
+#[tokio::test]
+async fn round_trip_and_filter_by_event_labels() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..5 {
+        let mut event = make_event("task-1", i);
+        let region = if i % 2 == 0 { "eu" } else { "us" };
+        event.labels = Some(HashMap::from([("region".to_string(), region.to_string())]));
+        ctx.short.append_event("task-1", event).await.unwrap();
+    }
+
+    let all = ctx.short.get_events("task-1", None).await.unwrap();
+    assert_eq!(all[1].labels.as_ref().unwrap()["region"], "us");
+
+    // The selector is applied before the limit
+    let opts = EventQueryOptions {
+        since: None,
+        limit: Some(2),
+        label_selector: Some(HashMap::from([("region".to_string(), "eu".to_string())])),
+        until: None,
+        before_index: None,
+    };
+    let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
+    let indexes: Vec<u64> = events.iter().map(|e| e.index).collect();
+    assert_eq!(indexes, vec![0, 2]);
 }
 
 #[tokio::test]
@@ -279,6 +808,9 @@ async fn respect_limit_parameter() {
     let opts = EventQueryOptions {
         since: None,
         limit: Some(3),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -304,6 +836,9 @@ async fn apply_limit_after_since_filter() {
             id: None,
         }),
         limit: Some(2),
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 2);
@@ -311,6 +846,87 @@ async fn apply_limit_after_since_filter() {
     assert_eq!(events[1].index, 7);
 }
 
+#[tokio::test]
+async fn until_keeps_events_at_or_before_the_bound() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..5 {
+        ctx.short
+            .append_event("task-1", make_event("task-1", i))
+            .await
+            .unwrap();
+    }
+
+    // Event i is timestamped 1000 + 100 * i.
+    let query = |since: Option<u64>, limit: Option<u64>, until: f64| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit,
+        label_selector: None,
+        until: Some(until),
+        before_index: None,
+    };
+    let indexes = |events: Vec<TaskEvent>| events.iter().map(|e| e.index).collect::<Vec<u64>>();
+
+    let events = ctx.short
+        .get_events("task-1", Some(query(None, None, 1200.0)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![0, 1, 2]);
+
+    // `until` applies before the limit, alongside the cursor
+    let events = ctx.short
+        .get_events("task-1", Some(query(Some(0), Some(1), 1250.5)))
+        .await
+        .unwrap();
+    assert_eq!(indexes(events), vec![1]);
+
+    let events = ctx.short
+        .get_events("task-1", Some(query(None, Some(5), 999.0)))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn before_index_bounds_paged_reads() {
+    let ctx = setup().await;
+    ctx.short.save_task(make_task("task-1")).await.unwrap();
+    for i in 0..6 {
+        ctx.short
+            .append_event("task-1", make_event("task-1", i))
+            .await
+            .unwrap();
+    }
+
+    // A scan that ended at index 4, read two events at a time.
+    let page = |since: Option<u64>| EventQueryOptions {
+        since: since.map(|index| SinceCursor {
+            index: Some(index),
+            timestamp: None,
+            id: None,
+        }),
+        limit: Some(2),
+        before_index: Some(4),
+        ..Default::default()
+    };
+    let mut indexes: Vec<u64> = Vec::new();
+    loop {
+        let events = ctx.short
+            .get_events("task-1", Some(page(indexes.last().copied())))
+            .await
+            .unwrap();
+        if events.is_empty() {
+            break;
+        }
+        indexes.extend(events.iter().map(|e| e.index));
+    }
+    assert_eq!(indexes, vec![0, 1, 2, 3]);
+}
+
 // ─── series ─────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -382,10 +998,12 @@ async fn replace_last_series_event_in_event_list() {
 
     let mut replacement = make_event("task-1", 1);
     replacement.data = serde_json::json!({"text": "replaced"});
-    ctx.short
+    let replaced = ctx
+        .short
         .replace_last_series_event("task-1", "series-a", replacement.clone())
         .await
         .unwrap();
+    assert_eq!(replaced, Some(e0.id.clone()));
 
     let events = ctx.short.get_events("task-1", None).await.unwrap();
     assert_eq!(events.len(), 1);
@@ -653,6 +1271,9 @@ async fn since_with_no_cursor_fields_returns_all() {
             id: None,
         }),
         limit: None,
+        label_selector: None,
+        until: None,
+        before_index: None,
     };
     let events = ctx.short.get_events("task-1", Some(opts)).await.unwrap();
     assert_eq!(events.len(), 3);
@@ -817,6 +1438,59 @@ async fn list_tasks_excludes_task_ids() {
     assert_eq!(tasks[0].id, "task-2");
 }
 
+#[tokio::test]
+async fn list_tasks_filters_by_created_before() {
+    let ctx = setup().await;
+    for (id, created_at) in [("task-1", 1000.0), ("task-2", 2000.0), ("task-3", 3000.0)] {
+        let mut task = make_task(id);
+        task.created_at = created_at;
+        ctx.short.save_task(task).await.unwrap();
+    }
+
+    let filter = TaskFilter {
+        created_before: Some(2000.0),
+        ..Default::default()
+    };
+    let tasks = ctx.short.list_tasks(filter).await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, "task-1");
+}
+
+#[tokio::test]
+async fn list_tasks_filters_by_origin() {
+    let ctx = setup().await;
+    for (id, origin) in [
+        ("task-1", None),
+        ("task-2", Some(TaskOriginKind::User)),
+        ("task-3", Some(TaskOriginKind::Retry)),
+        ("task-4", Some(TaskOriginKind::System)),
+    ] {
+        let mut task = make_task(id);
+        task.origin = origin.map(TaskOrigin::new);
+        ctx.short.save_task(task).await.unwrap();
+    }
+    let list = |kinds: Vec<TaskOriginKind>| {
+        ctx.short.list_tasks(TaskFilter {
+            origin: Some(kinds),
+            ..Default::default()
+        })
+    };
+
+    let mut ids: Vec<String> = list(vec![TaskOriginKind::User])
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    ids.sort();
+    // A task saved without an origin counts as user-created.
+    assert_eq!(ids, ["task-1", "task-2"]);
+
+    let tasks = list(vec![TaskOriginKind::Retry]).await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].id, "task-3");
+}
+
 #[tokio::test]
 async fn list_tasks_respects_limit() {
     let ctx = setup().await;
@@ -1407,6 +2081,88 @@ async fn task_with_zero_cost_round_trips() {
     assert_eq!(retrieved.cost, Some(0));
 }
 
+// ─── list_tasks_page ─────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn list_tasks_page_walks_tasks_in_creation_order() {
+    let ctx = setup().await;
+
+    // Saved newest first; pairs share a creation time and sort by id.
+    for i in (0..25).rev() {
+        let mut task = make_task(&format!("task-{i:02}"));
+        task.created_at = 1000.0 + (i / 2) as f64;
+        task.status = if i % 5 == 0 {
+            TaskStatus::Completed
+        } else {
+            TaskStatus::Failed
+        };
+        ctx.short.save_task(task).await.unwrap();
+    }
+    let filter = TaskFilter {
+        status: Some(vec![TaskStatus::Failed]),
+        ..Default::default()
+    };
+
+    let mut ids = Vec::new();
+    let mut after = None;
+    loop {
+        let page = ctx
+            .short
+            .list_tasks_page(filter.clone(), after, 4)
+            .await
+            .unwrap();
+        assert!(page.tasks.len() <= 4);
+        ids.extend(page.tasks.into_iter().map(|t| t.id));
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = (0..25)
+        .filter(|i| i % 5 != 0)
+        .map(|i| format!("task-{i:02}"))
+        .collect();
+    assert_eq!(ids, expected);
+}
+
+#[tokio::test]
+async fn list_tasks_page_fills_pages_across_tag_mismatches() {
+    let ctx = setup().await;
+
+    for i in 0..12 {
+        let mut task = make_task(&format!("task-{i:02}"));
+        task.created_at = 1000.0 + i as f64;
+        if i % 3 == 0 {
+            task.tags = Some(vec!["gpu".to_string()]);
+        }
+        ctx.short.save_task(task).await.unwrap();
+    }
+    let filter = TaskFilter {
+        tags: Some(TagMatcher {
+            all: Some(vec!["gpu".to_string()]),
+            any: None,
+            none: None,
+        }),
+        ..Default::default()
+    };
+
+    let page = ctx
+        .short
+        .list_tasks_page(filter.clone(), None, 2)
+        .await
+        .unwrap();
+    let ids: Vec<&str> = page.tasks.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["task-00", "task-03"]);
+    let page = ctx
+        .short
+        .list_tasks_page(filter, page.next, 2)
+        .await
+        .unwrap();
+    let ids: Vec<&str> = page.tasks.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["task-06", "task-09"]);
+    assert_eq!(page.next, None);
+}
+
 // ─── list_tasks tag matching ─────────────────────────────────────────────
 
 #[tokio::test]
@@ -1505,3 +2261,97 @@ async fn list_tasks_filters_by_tags_none() {
     assert!(ids.contains(&"task-n2"));
     assert!(ids.contains(&"task-n3"));
 }
+
+// ─── Outcomes index ───────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn outcome_origin_round_trips() {
+    let ctx = setup().await;
+    let outcome = TaskOutcome {
+        task_id: "a".to_string(),
+        r#type: None,
+        status: TaskStatus::Completed,
+        completed_at: 1000.0,
+        error_code: None,
+        origin: Some(TaskOriginKind::Retry),
+    };
+    ctx.short.record_outcome(outcome.clone()).await.unwrap();
+    let all = OutcomeQuery {
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(ctx.short.list_outcomes(&all).await.unwrap(), [outcome]);
+}
+
+#[tokio::test]
+async fn outcomes_are_listed_in_order_filtered_and_trimmed() {
+    let ctx = setup().await;
+    let outcome = |task_id: &str, r#type: Option<&str>, status: TaskStatus, at: f64| TaskOutcome {
+        task_id: task_id.to_string(),
+        r#type: r#type.map(str::to_string),
+        status,
+        completed_at: at,
+        error_code: None,
+        origin: None,
+    };
+    for recorded in [
+        outcome("d", None, TaskStatus::Timeout, 3000.0),
+        outcome("c", Some("import.json"), TaskStatus::Completed, 2000.0),
+        outcome("b", Some("export.csv"), TaskStatus::Failed, 2000.0),
+        outcome("a", Some("import.csv"), TaskStatus::Failed, 1000.0),
+    ] {
+        ctx.short.record_outcome(recorded).await.unwrap();
+    }
+    let ids = |outcomes: Vec<TaskOutcome>| -> Vec<String> {
+        outcomes.into_iter().map(|o| o.task_id).collect()
+    };
+
+    let all = OutcomeQuery {
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(ctx.short.list_outcomes(&all).await.unwrap()),
+        ["a", "b", "c", "d"]
+    );
+
+    let failed_imports = OutcomeQuery {
+        statuses: Some(vec![TaskStatus::Failed]),
+        types: Some(vec!["import.*".to_string()]),
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(ctx.short.list_outcomes(&failed_imports).await.unwrap()),
+        ["a"]
+    );
+
+    let since = OutcomeQuery {
+        since: Some(2000.0),
+        limit: 10,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(ctx.short.list_outcomes(&since).await.unwrap()),
+        ["b", "c", "d"]
+    );
+
+    let next_page = OutcomeQuery {
+        after: Some(OutcomeCursor {
+            completed_at: 2000.0,
+            task_id: "b".to_string(),
+        }),
+        limit: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        ids(ctx.short.list_outcomes(&next_page).await.unwrap()),
+        ["c"]
+    );
+
+    assert_eq!(ctx.short.trim_outcomes(2000.0).await.unwrap(), 1);
+    assert_eq!(
+        ids(ctx.short.list_outcomes(&all).await.unwrap()),
+        ["b", "c", "d"]
+    );
+}
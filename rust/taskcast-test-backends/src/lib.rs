@@ -0,0 +1,159 @@
+//! Shared Redis and Postgres fixtures for the backend integration suites.
+//!
+//! Each backend runs in a single container per test process, started on first
+//! use and removed when the process exits. Tests isolate themselves inside the
+//! shared container instead of flushing it: Redis suites namespace their keys
+//! with [`unique_prefix`], Postgres suites their tables with
+//! [`unique_schema`]. Suites therefore run under the default parallel test
+//! harness without interfering with each other.
+//!
+//! Set `TASKCAST_TEST_SKIP_DOCKER=1` on machines without Docker. Every
+//! fixture then returns `None`, and a test that bails out on `None` passes as
+//! skipped instead of failing to start its container.
+//!
+//! [`sim`] runs the engine itself under scripted scenarios, on in-memory
+//! stores and virtual time; it needs no Docker.
+
+// The container plumbing is only used by the backend modules, which a
+// featureless build (e.g. `cargo build --workspace`) leaves out.
+#![cfg_attr(not(any(feature = "redis", feature = "postgres")), allow(dead_code))]
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "redis")]
+mod redis;
+pub mod sim;
+
+#[cfg(feature = "postgres")]
+pub use self::postgres::{make_postgres_store, postgres_url};
+#[cfg(feature = "redis")]
+pub use self::redis::{make_redis_broadcast, make_redis_store, redis_url};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, Once, OnceLock, PoisonError};
+
+use testcontainers::{ContainerAsync, Image};
+use tokio::runtime::Runtime;
+
+/// Environment variable that turns backend tests into no-ops.
+pub const SKIP_DOCKER_ENV: &str = "TASKCAST_TEST_SKIP_DOCKER";
+
+/// Postgres truncates identifiers beyond 63 bytes; `test_` and the ULID
+/// suffix take 32 of them.
+const MAX_SCHEMA_LABEL: usize = 31;
+
+type Removal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Container removals still to run at process exit.
+static REMOVALS: Mutex<Vec<Removal>> = Mutex::new(Vec::new());
+
+/// Whether backend tests are skipped: `TASKCAST_TEST_SKIP_DOCKER` is set to
+/// anything other than an empty string, `0` or `false`.
+pub fn docker_skipped() -> bool {
+    std::env::var(SKIP_DOCKER_ENV)
+        .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
+/// A Redis key prefix no other test uses, e.g. `test-concurrent-01j…`.
+pub fn unique_prefix(label: &str) -> String {
+    format!("test-{label}-{}", ulid::Ulid::new()).to_lowercase()
+}
+
+/// A Postgres schema name no other test uses, e.g. `test_store_01j…`.
+/// Characters of `label` that need quoting become `_`.
+pub fn unique_schema(label: &str) -> String {
+    let label: String = label
+        .chars()
+        .take(MAX_SCHEMA_LABEL)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("test_{label}_{}", ulid::Ulid::new()).to_lowercase()
+}
+
+/// Like [`docker_skipped`], noting the skip on the test's stderr.
+fn skip(backend: &str) -> bool {
+    let skipped = docker_skipped();
+    if skipped {
+        eprintln!("skipping {backend} test: {SKIP_DOCKER_ENV} is set");
+    }
+    skipped
+}
+
+/// Unwraps the outcome of starting a shared container, pointing at the
+/// escape hatch when Docker is the problem.
+fn expect_started<'a>(backend: &str, started: &'a Result<String, String>) -> &'a str {
+    match started {
+        Ok(url) => url,
+        Err(err) => panic!(
+            "failed to start the shared {backend} container: {err} \
+             (set {SKIP_DOCKER_ENV}=1 to skip backend tests without Docker)"
+        ),
+    }
+}
+
+/// The runtime shared containers are started on and removed from. Each test
+/// has its own runtime, which ends with the test; the containers' Docker
+/// client must outlive all of them.
+fn fixture_runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("taskcast-test-backends")
+            .enable_all()
+            .build()
+            .expect("failed to build the test fixture runtime")
+    })
+}
+
+/// Runs `start` on the fixture runtime and returns its output.
+async fn on_fixture_runtime<F>(start: F) -> Result<String, String>
+where
+    F: Future<Output = Result<String, String>> + Send + 'static,
+{
+    fixture_runtime()
+        .spawn(start)
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+/// Keeps `container` running for the rest of the process and removes it at
+/// exit. Must be called on the fixture runtime.
+fn remove_at_exit<I: Image + 'static>(container: ContainerAsync<I>) {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        // SAFETY: `remove_containers` is a plain `extern "C"` function that
+        // does not unwind.
+        unsafe {
+            libc::atexit(remove_containers);
+        }
+    });
+    REMOVALS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::pin(async move {
+            let _ = container.rm().await;
+        }));
+}
+
+extern "C" fn remove_containers() {
+    let removals = std::mem::take(&mut *REMOVALS.lock().unwrap_or_else(PoisonError::into_inner));
+    // A fresh thread, since the exiting thread may itself be a runtime
+    // worker, where `block_on` would panic.
+    let _ = std::thread::spawn(move || {
+        fixture_runtime().block_on(async move {
+            for removal in removals {
+                removal.await;
+            }
+        })
+    })
+    .join();
+}
@@ -0,0 +1,55 @@
+use taskcast_redis::{RedisBroadcastProvider, RedisShortTermStore};
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::{Redis, REDIS_PORT};
+use tokio::sync::OnceCell;
+
+use crate::{expect_started, on_fixture_runtime, remove_at_exit, skip};
+
+static REDIS: OnceCell<Result<String, String>> = OnceCell::const_new();
+
+/// URL of the shared Redis container, started on first use. `None` when
+/// backend tests are skipped.
+pub async fn redis_url() -> Option<&'static str> {
+    if skip("Redis") {
+        return None;
+    }
+    let started = REDIS
+        .get_or_init(|| {
+            on_fixture_runtime(async {
+                let container = Redis::default()
+                    .start()
+                    .await
+                    .map_err(|err| err.to_string())?;
+                let port = container
+                    .get_host_port_ipv4(REDIS_PORT)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                remove_at_exit(container);
+                Ok(format!("redis://127.0.0.1:{port}"))
+            })
+        })
+        .await;
+    Some(expect_started("Redis", started))
+}
+
+/// A short-term store on the shared Redis whose keys all start with
+/// `prefix`. Use [`unique_prefix`](crate::unique_prefix) to keep it apart
+/// from other tests.
+pub async fn make_redis_store(prefix: &str) -> Option<RedisShortTermStore> {
+    let client = redis::Client::open(redis_url().await?).unwrap();
+    let conn = client.get_multiplexed_async_connection().await.unwrap();
+    Some(RedisShortTermStore::new(conn, Some(prefix)))
+}
+
+/// A broadcast provider on the shared Redis publishing and subscribing under
+/// `prefix`.
+pub async fn make_redis_broadcast(prefix: &str) -> Option<RedisBroadcastProvider> {
+    let client = redis::Client::open(redis_url().await?).unwrap();
+    let pub_conn = client.get_multiplexed_async_connection().await.unwrap();
+    let sub_conn = client.get_async_pubsub().await.unwrap();
+    Some(RedisBroadcastProvider::new(
+        pub_conn,
+        sub_conn,
+        Some(prefix),
+    ))
+}
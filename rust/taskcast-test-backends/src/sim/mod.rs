@@ -0,0 +1,36 @@
+//! Deterministic simulation of the engine under a scripted scenario.
+//!
+//! A [`Scenario`] is a list of timed [`Step`]s: engine operations,
+//! subscribers connecting and disconnecting, store fault windows and clock
+//! jumps. [`Scenario::run`] executes them against one or more engines that
+//! share in-memory stores wrapped in a fault gate, on virtual time, and
+//! returns a [`Trace`] of what every virtual subscriber and webhook
+//! received. Assertions are made on the trace:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use serde_json::json;
+//! use taskcast_core::TaskStatus;
+//! use taskcast_test_backends::sim::{Scenario, Step};
+//!
+//! let trace = Scenario::new()
+//!     .at(0, Step::create("t1"))
+//!     .at(0, Step::transition("t1", TaskStatus::Running))
+//!     .at(10, Step::store_latency(Duration::from_millis(50), Duration::from_millis(100)))
+//!     .at(20, Step::connect("client", "t1"))
+//!     .at(30, Step::publish("t1", "log", json!({ "line": 1 })))
+//!     .at(200, Step::transition("t1", TaskStatus::Completed))
+//!     .run();
+//! trace.assert_no_gaps("client");
+//! trace.assert_done("client", TaskStatus::Completed);
+//! ```
+//!
+//! Nothing touches the network, so scenarios run without Docker.
+
+mod scenario;
+mod store;
+mod trace;
+
+pub use scenario::{Scenario, Step};
+pub use store::{Fault, FaultKind, Faults, SimBroadcast, SimShortTermStore};
+pub use trace::{Delivery, Frame, Received, StepOutcome, Trace};
@@ -0,0 +1,675 @@
+//! Scenarios: timed steps run against engines sharing scripted stores.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+use taskcast_core::{
+    matches_filter, Clock, CreateTaskInput, Level, PublishEventInput, SeriesMode, ShortTermStore,
+    SinceCursor, StreamItem, SubscribeFilter, TaskEngine, TaskEngineOptions, TaskEvent, TaskStatus,
+};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+
+use super::store::{Fault, FaultKind, Faults, SimBroadcast, SimShortTermStore};
+use super::trace::{Delivery, Frame, Received, StepOutcome, Trace};
+
+/// Wall-clock time the engines' clock reads when a scenario starts.
+const EPOCH_MS: f64 = 1_700_000_000_000.0;
+
+/// How long subscribers may take to finish once every step has run, before
+/// they are cut off. Virtual time, so waiting it out costs nothing.
+const DEFAULT_SETTLE: Duration = Duration::from_secs(60);
+
+/// One thing a scenario does at its time.
+pub enum Step {
+    CreateTask {
+        task_id: String,
+    },
+    Publish {
+        task_id: String,
+        event: PublishEventInput,
+    },
+    Transition {
+        task_id: String,
+        status: TaskStatus,
+    },
+    /// Connects virtual subscriber `subscriber`, replacing its connection
+    /// if it has one. With `resume` it passes the id of the last event it
+    /// received as `since.id`, as an SSE client reconnecting with
+    /// `Last-Event-ID` does.
+    Connect {
+        subscriber: String,
+        task_id: String,
+        filter: SubscribeFilter,
+        resume: bool,
+    },
+    Disconnect {
+        subscriber: String,
+    },
+    /// Opens a fault window on the shared stores for `window`.
+    Fault {
+        kind: FaultKind,
+        ops: Option<Vec<String>>,
+        window: Duration,
+    },
+    /// Moves the engines' clock forward, leaving the schedule alone: time
+    /// the engines see jumps, as after a pause or a clock step.
+    AdvanceClock(Duration),
+}
+
+impl Step {
+    pub fn create(task_id: &str) -> Self {
+        Step::CreateTask {
+            task_id: task_id.to_string(),
+        }
+    }
+
+    /// Publishes an info event of type `r#type` carrying `data`.
+    pub fn publish(task_id: &str, r#type: &str, data: Value) -> Self {
+        Step::Publish {
+            task_id: task_id.to_string(),
+            event: PublishEventInput {
+                r#type: r#type.to_string(),
+                level: Level::Info,
+                data,
+                series_id: None,
+                series_mode: None,
+                series_acc_field: None,
+                labels: None,
+                broadcast_debounce_ms: None,
+                series_end: false,
+            },
+        }
+    }
+
+    /// Publishes an event of series `series_id` in `mode`.
+    pub fn publish_series(
+        task_id: &str,
+        r#type: &str,
+        data: Value,
+        series_id: &str,
+        mode: SeriesMode,
+    ) -> Self {
+        let mut step = Step::publish(task_id, r#type, data);
+        if let Step::Publish { event, .. } = &mut step {
+            event.series_id = Some(series_id.to_string());
+            event.series_mode = Some(mode);
+        }
+        step
+    }
+
+    pub fn transition(task_id: &str, status: TaskStatus) -> Self {
+        Step::Transition {
+            task_id: task_id.to_string(),
+            status,
+        }
+    }
+
+    /// Connects `subscriber` to every event of the task.
+    pub fn connect(subscriber: &str, task_id: &str) -> Self {
+        Step::connect_with(subscriber, task_id, SubscribeFilter::default())
+    }
+
+    pub fn connect_with(subscriber: &str, task_id: &str, filter: SubscribeFilter) -> Self {
+        Step::Connect {
+            subscriber: subscriber.to_string(),
+            task_id: task_id.to_string(),
+            filter,
+            resume: false,
+        }
+    }
+
+    /// Reconnects `subscriber` with its filter, resuming after the last
+    /// event it received.
+    pub fn reconnect(subscriber: &str, task_id: &str) -> Self {
+        Step::Connect {
+            subscriber: subscriber.to_string(),
+            task_id: task_id.to_string(),
+            filter: SubscribeFilter::default(),
+            resume: true,
+        }
+    }
+
+    pub fn disconnect(subscriber: &str) -> Self {
+        Step::Disconnect {
+            subscriber: subscriber.to_string(),
+        }
+    }
+
+    /// Delays every store and broadcast call by `latency` for `window`.
+    pub fn store_latency(latency: Duration, window: Duration) -> Self {
+        Step::Fault {
+            kind: FaultKind::Latency(latency),
+            ops: None,
+            window,
+        }
+    }
+
+    /// Fails every store and broadcast call for `window`.
+    pub fn store_errors(window: Duration) -> Self {
+        Step::Fault {
+            kind: FaultKind::Error,
+            ops: None,
+            window,
+        }
+    }
+
+    /// Narrows a fault step to the named operations (see [`Fault`]).
+    ///
+    /// # Panics
+    ///
+    /// If the step is not a fault step.
+    pub fn only(mut self, names: &[&str]) -> Self {
+        match &mut self {
+            Step::Fault { ops, .. } => {
+                *ops = Some(names.iter().map(|name| name.to_string()).collect());
+            }
+            other => panic!("only() narrows fault steps, not {}", other.describe()),
+        }
+        self
+    }
+
+    /// How the step appears in [`StepOutcome::step`].
+    fn describe(&self) -> String {
+        match self {
+            Step::CreateTask { task_id } => format!("create {task_id}"),
+            Step::Publish { task_id, event } => format!("publish {task_id} {}", event.r#type),
+            Step::Transition { task_id, status } => {
+                format!("transition {task_id} {}", status_name(status))
+            }
+            Step::Connect { subscriber, .. } => format!("connect {subscriber}"),
+            Step::Disconnect { subscriber } => format!("disconnect {subscriber}"),
+            Step::Fault { kind, .. } => format!("fault {kind:?}"),
+            Step::AdvanceClock(by) => format!("advance clock {by:?}"),
+        }
+    }
+}
+
+fn status_name(status: &TaskStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{status:?}"))
+}
+
+struct Timed {
+    at_ms: u64,
+    instance: usize,
+    step: Step,
+}
+
+/// A sequence of timed steps, run against `instances` engines that share a
+/// scripted short-term store and broadcast provider, as instances behind
+/// one Redis do.
+///
+/// [`run`](Self::run) executes it on a single-threaded runtime whose clock
+/// only moves when every task is waiting on it, so a scenario runs the same
+/// way every time and takes no real time waiting. The engines read that
+/// clock too.
+///
+/// Engine operations (create, publish, transition) of one instance run one
+/// at a time in step order, like a single producer's requests; those of
+/// different instances run concurrently. Subscribers and fault windows
+/// act at their step's time.
+pub struct Scenario {
+    instances: usize,
+    webhooks: Vec<(String, SubscribeFilter)>,
+    steps: Vec<Timed>,
+    settle: Duration,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self {
+            instances: 1,
+            webhooks: Vec::new(),
+            steps: Vec::new(),
+            settle: DEFAULT_SETTLE,
+        }
+    }
+
+    /// Runs `count` engine instances instead of one.
+    pub fn instances(mut self, count: usize) -> Self {
+        assert!(count > 0, "a scenario needs an instance");
+        self.instances = count;
+        self
+    }
+
+    /// Adds virtual webhook `name`: every instance hands it the events it
+    /// publishes that `filter` matches, as the webhook dispatcher is handed
+    /// them. No request is made.
+    pub fn webhook(mut self, name: &str, filter: SubscribeFilter) -> Self {
+        self.webhooks.push((name.to_string(), filter));
+        self
+    }
+
+    /// How long subscribers may take to finish after the last step.
+    pub fn settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// Runs `step` on instance 0, `at_ms` milliseconds into the scenario.
+    pub fn at(self, at_ms: u64, step: Step) -> Self {
+        self.at_on(at_ms, 0, step)
+    }
+
+    /// Runs `step` on `instance`, `at_ms` milliseconds into the scenario.
+    /// Steps at the same time run in the order they were added.
+    pub fn at_on(mut self, at_ms: u64, instance: usize, step: Step) -> Self {
+        assert!(
+            instance < self.instances,
+            "instance {instance} of {}; call instances() first",
+            self.instances
+        );
+        self.steps.push(Timed {
+            at_ms,
+            instance,
+            step,
+        });
+        self
+    }
+
+    /// Runs the scenario to completion and returns what it recorded.
+    pub fn run(self) -> Trace {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .expect("failed to build the scenario runtime")
+            .block_on(Runner::new(self).run())
+    }
+}
+
+/// The engines' clock: [`EPOCH_MS`] plus virtual time since the start,
+/// plus any [`Step::AdvanceClock`].
+struct SimClock {
+    started: Instant,
+    advanced: Mutex<Duration>,
+}
+
+impl Clock for SimClock {
+    fn now_ms(&self) -> f64 {
+        let advanced = *self.advanced.lock().unwrap_or_else(PoisonError::into_inner);
+        EPOCH_MS + (self.started.elapsed() + advanced).as_millis() as f64
+    }
+}
+
+/// The trace being recorded, shared by the runner, subscribers and
+/// webhooks.
+#[derive(Clone)]
+struct Recorder {
+    started: Instant,
+    trace: Arc<Mutex<Trace>>,
+}
+
+impl Recorder {
+    fn now_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut Trace) -> T) -> T {
+        f(&mut self.trace.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn frame(&self, subscriber: &str, connection: u32, item: Received) {
+        let frame = Frame {
+            at_ms: self.now_ms(),
+            connection,
+            item,
+        };
+        self.with(|trace| {
+            trace
+                .frames
+                .entry(subscriber.to_string())
+                .or_default()
+                .push(frame)
+        });
+    }
+}
+
+/// Hands a virtual webhook the events its filter matches.
+struct WebhookSink {
+    name: String,
+    filter: SubscribeFilter,
+    instance: usize,
+    recorder: Recorder,
+}
+
+#[async_trait]
+impl taskcast_core::EventSink for WebhookSink {
+    async fn on_event(&self, event: &TaskEvent) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if matches_filter(event, &self.filter) {
+            let delivery = Delivery {
+                at_ms: self.recorder.now_ms(),
+                instance: self.instance,
+                event: event.clone(),
+            };
+            self.recorder.with(|trace| {
+                trace
+                    .deliveries
+                    .entry(self.name.clone())
+                    .or_default()
+                    .push(delivery)
+            });
+        }
+        Ok(())
+    }
+}
+
+struct Subscriber {
+    filter: SubscribeFilter,
+    connections: u32,
+    current: Option<JoinHandle<()>>,
+}
+
+struct Runner {
+    scenario: Scenario,
+    started: Instant,
+    faults: Arc<Faults>,
+    store: Arc<SimShortTermStore>,
+    clock: Arc<SimClock>,
+    recorder: Recorder,
+    engines: Vec<Arc<TaskEngine>>,
+    subscribers: HashMap<String, Subscriber>,
+    tasks: Vec<String>,
+}
+
+impl Runner {
+    fn new(scenario: Scenario) -> Self {
+        let started = Instant::now();
+        let faults = Arc::new(Faults::default());
+        let store = Arc::new(SimShortTermStore::new(Arc::clone(&faults)));
+        let broadcast = Arc::new(SimBroadcast::new(Arc::clone(&faults)));
+        let clock = Arc::new(SimClock {
+            started,
+            advanced: Mutex::new(Duration::ZERO),
+        });
+        let recorder = Recorder {
+            started,
+            trace: Arc::default(),
+        };
+        recorder.with(|trace| {
+            for (name, filter) in &scenario.webhooks {
+                trace.filters.insert(name.clone(), (None, filter.clone()));
+            }
+        });
+        let engines = (0..scenario.instances)
+            .map(|instance| {
+                let sinks = scenario
+                    .webhooks
+                    .iter()
+                    .map(|(name, filter)| {
+                        Arc::new(WebhookSink {
+                            name: name.clone(),
+                            filter: filter.clone(),
+                            instance,
+                            recorder: recorder.clone(),
+                        }) as Arc<dyn taskcast_core::EventSink>
+                    })
+                    .collect();
+                let engine = TaskEngine::new(TaskEngineOptions {
+                    short_term_store: Arc::clone(&store) as Arc<dyn ShortTermStore>,
+                    broadcast: Arc::clone(&broadcast) as _,
+                    long_term_store: None,
+                    hooks: None,
+                    label_limits: None,
+                    sinks,
+                    coalesce_reads: None,
+                });
+                Arc::new(engine.with_clock(Arc::clone(&clock) as Arc<dyn Clock>))
+            })
+            .collect();
+        Self {
+            scenario,
+            started,
+            faults,
+            store,
+            clock,
+            recorder,
+            engines,
+            subscribers: HashMap::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    async fn run(mut self) -> Trace {
+        let mut steps = std::mem::take(&mut self.scenario.steps);
+        steps.sort_by_key(|timed| timed.at_ms);
+
+        let mut lanes = Vec::new();
+        let mut workers = Vec::new();
+        for engine in &self.engines {
+            let (lane, queue) = mpsc::unbounded_channel();
+            lanes.push(lane);
+            workers.push(tokio::spawn(operate(
+                Arc::clone(engine),
+                queue,
+                self.recorder.clone(),
+            )));
+        }
+
+        for Timed {
+            at_ms,
+            instance,
+            step,
+        } in steps
+        {
+            tokio::time::sleep_until(self.started + Duration::from_millis(at_ms)).await;
+            match step {
+                Step::CreateTask { ref task_id } => {
+                    self.tasks.push(task_id.clone());
+                    let _ = lanes[instance].send((at_ms, instance, step));
+                }
+                Step::Publish { .. } | Step::Transition { .. } => {
+                    let _ = lanes[instance].send((at_ms, instance, step));
+                }
+                Step::Connect {
+                    subscriber,
+                    task_id,
+                    filter,
+                    resume,
+                } => self.connect(instance, subscriber, task_id, filter, resume),
+                Step::Disconnect { subscriber } => self.disconnect(&subscriber),
+                Step::Fault { kind, ops, window } => {
+                    let now = Instant::now();
+                    self.faults.open(Fault {
+                        kind,
+                        ops,
+                        from: now,
+                        until: now + window,
+                    });
+                }
+                Step::AdvanceClock(by) => {
+                    *self
+                        .clock
+                        .advanced
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner) += by;
+                }
+            }
+        }
+
+        drop(lanes);
+        for worker in workers {
+            worker.await.expect("scenario operations panicked");
+        }
+        let deadline = Instant::now() + self.scenario.settle;
+        for (name, subscriber) in self.subscribers.drain() {
+            let Some(mut current) = subscriber.current else {
+                continue;
+            };
+            if tokio::time::timeout_at(deadline, &mut current)
+                .await
+                .is_err()
+            {
+                current.abort();
+                eprintln!("subscriber {name} had not finished when the scenario settled");
+            }
+        }
+
+        let mut histories = BTreeMap::new();
+        for task_id in &self.tasks {
+            let events = self
+                .store
+                .inner()
+                .get_events(task_id, None)
+                .await
+                .unwrap_or_default();
+            histories.insert(task_id.clone(), events);
+        }
+        drop(self.engines);
+        let mut trace = std::mem::take(
+            &mut *self
+                .recorder
+                .trace
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        trace.histories = histories;
+        trace
+    }
+
+    fn connect(
+        &mut self,
+        instance: usize,
+        name: String,
+        task_id: String,
+        filter: SubscribeFilter,
+        resume: bool,
+    ) {
+        if self.subscribers.contains_key(&name) {
+            self.disconnect(&name);
+        }
+        let connection = match self.subscribers.get_mut(&name) {
+            Some(subscriber) => {
+                subscriber.connections += 1;
+                subscriber.connections - 1
+            }
+            None => {
+                self.subscribers.insert(
+                    name.clone(),
+                    Subscriber {
+                        filter: filter.clone(),
+                        connections: 1,
+                        current: None,
+                    },
+                );
+                0
+            }
+        };
+        let subscriber = self.subscribers.get_mut(&name).expect("inserted above");
+        let mut filter = if resume {
+            subscriber.filter.clone()
+        } else {
+            subscriber.filter = filter.clone();
+            filter
+        };
+        if resume {
+            let last = self.recorder.with(|trace| {
+                trace.frames.get(&name).and_then(|frames| {
+                    frames.iter().rev().find_map(|frame| match &frame.item {
+                        Received::Event(envelope) => Some(envelope.event_id.clone()),
+                        _ => None,
+                    })
+                })
+            });
+            filter.since = last.map(|id| SinceCursor {
+                id: Some(id),
+                index: None,
+                timestamp: None,
+            });
+        }
+        self.recorder.with(|trace| {
+            trace.filters.insert(
+                name.clone(),
+                (Some(task_id.clone()), subscriber.filter.clone()),
+            );
+        });
+
+        let engine = Arc::clone(&self.engines[instance]);
+        let recorder = self.recorder.clone();
+        subscriber.current = Some(tokio::spawn(async move {
+            let mut stream = match engine.subscribe_stream(&task_id, filter).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    recorder.frame(&name, connection, Received::Error(err.to_string()));
+                    return;
+                }
+            };
+            while let Some(item) = stream.next().await {
+                let (item, last) = match item {
+                    StreamItem::Event(envelope) => (Received::Event(envelope), false),
+                    StreamItem::Truncated(_) => (Received::Truncated, false),
+                    StreamItem::Done(status) => (Received::Done(status), true),
+                    StreamItem::Error(err) => (Received::Error(err.to_string()), true),
+                };
+                recorder.frame(&name, connection, item);
+                if last {
+                    break;
+                }
+            }
+        }));
+    }
+
+    fn disconnect(&mut self, name: &str) {
+        let subscriber = self
+            .subscribers
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("disconnecting unknown subscriber {name:?}"));
+        if let Some(current) = subscriber.current.take() {
+            if !current.is_finished() {
+                current.abort();
+                self.recorder
+                    .frame(name, subscriber.connections - 1, Received::Disconnected);
+            }
+        }
+    }
+}
+
+/// Runs one instance's engine operations in the order they arrive.
+async fn operate(
+    engine: Arc<TaskEngine>,
+    mut queue: mpsc::UnboundedReceiver<(u64, usize, Step)>,
+    recorder: Recorder,
+) {
+    while let Some((at_ms, instance, step)) = queue.recv().await {
+        let description = step.describe();
+        let result = match step {
+            Step::CreateTask { task_id } => engine
+                .create_task(CreateTaskInput {
+                    id: Some(task_id),
+                    ..Default::default()
+                })
+                .await
+                .map(drop),
+            Step::Publish { task_id, event } => {
+                engine.publish_event(&task_id, event).await.map(drop)
+            }
+            Step::Transition { task_id, status } => engine
+                .transition_task(&task_id, status, None)
+                .await
+                .map(drop),
+            other => unreachable!("{} is not an engine operation", other.describe()),
+        };
+        let outcome = StepOutcome {
+            at_ms,
+            finished_ms: recorder.now_ms(),
+            instance,
+            step: description,
+            result: result.map_err(|err| err.to_string()),
+        };
+        recorder.with(|trace| trace.outcomes.push(outcome));
+    }
+}
@@ -0,0 +1,624 @@
+//! Stores whose behavior a scenario scripts: each call first passes a
+//! fault gate, which delays or fails it while a fault window the scenario
+//! opened covers it.
+
+use std::error::Error;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+use taskcast_core::{
+    BroadcastProvider, CompressionStats, EventConventionStore, EventQueryOptions, EventTypeCounts,
+    FilteredIndexClaim, FilteredIndexMark, IntegrityMonitor, MemoryBroadcastProvider,
+    MemoryShortTermStore, MigratingShortTermStore, NewTaskGroupOutcome, NewTaskOutcome,
+    OutcomeQuery, RetrySchedule, ScanToken, SeriesSummary, ShortTermStore, StoreError,
+    StoreErrorKind, Task, TaskArchiveImportOptions, TaskArchiveRestoreData, TaskCursor, TaskEvent,
+    TaskFilter, TaskOutcome, TaskPage, TaskStatus, Worker, WorkerAssignment, WorkerFilter,
+};
+use tokio::time::{Duration, Instant};
+
+/// What an open fault window does to the calls it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaultKind {
+    /// Each call is delayed this long before it runs.
+    Latency(Duration),
+    /// Each call fails with an unavailable [`StoreError`] without running.
+    Error,
+}
+
+/// A fault window: `kind` applies to calls made from `from` until `until`,
+/// on the operations named in `ops` (all of them when `None`).
+///
+/// Operations are named after the trait methods: `get_events`,
+/// `append_event`, … for the short-term store, `publish` and `subscribe`
+/// for the broadcast provider.
+#[derive(Debug, Clone)]
+pub struct Fault {
+    pub kind: FaultKind,
+    pub ops: Option<Vec<String>>,
+    pub from: Instant,
+    pub until: Instant,
+}
+
+impl Fault {
+    fn covers(&self, op: &str, now: Instant) -> bool {
+        self.from <= now
+            && now < self.until
+            && self
+                .ops
+                .as_ref()
+                .is_none_or(|ops| ops.iter().any(|name| name == op))
+    }
+}
+
+/// The fault windows a scenario has opened, shared by its stores.
+#[derive(Debug, Default)]
+pub struct Faults(Mutex<Vec<Fault>>);
+
+impl Faults {
+    pub fn open(&self, fault: Fault) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(fault);
+    }
+
+    /// Delays or fails a call to `op` as the windows open now say: by the
+    /// longest latency among them, then with an error if one injects
+    /// errors.
+    pub async fn gate(&self, op: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let now = Instant::now();
+        let (latency, fail) = {
+            let faults = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            let open = faults.iter().filter(|fault| fault.covers(op, now));
+            open.fold(
+                (Duration::ZERO, false),
+                |(latency, fail), fault| match fault.kind {
+                    FaultKind::Latency(delay) => (latency.max(delay), fail),
+                    FaultKind::Error => (latency, true),
+                },
+            )
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        if fail {
+            return Err(Box::new(StoreError::new(
+                StoreErrorKind::Unavailable,
+                format!("injected fault on {op}"),
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A [`MemoryShortTermStore`] behind the fault gate.
+pub struct SimShortTermStore {
+    inner: MemoryShortTermStore,
+    faults: Arc<Faults>,
+}
+
+impl SimShortTermStore {
+    pub fn new(faults: Arc<Faults>) -> Self {
+        Self {
+            inner: MemoryShortTermStore::new(),
+            faults,
+        }
+    }
+
+    /// The store itself, for reads that must not be faulted, such as the
+    /// final history a trace is checked against.
+    pub fn inner(&self) -> &MemoryShortTermStore {
+        &self.inner
+    }
+
+    async fn gate(&self, op: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.faults.gate(op).await
+    }
+}
+
+#[async_trait]
+impl ShortTermStore for SimShortTermStore {
+    async fn save_task(&self, task: Task) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("save_task").await?;
+        self.inner.save_task(task).await
+    }
+
+    async fn save_new_task(
+        &self,
+        task: Task,
+    ) -> Result<NewTaskOutcome, Box<dyn Error + Send + Sync>> {
+        self.gate("save_new_task").await?;
+        self.inner.save_new_task(task).await
+    }
+
+    async fn save_new_task_group(
+        &self,
+        tasks: Vec<Task>,
+    ) -> Result<NewTaskGroupOutcome, Box<dyn Error + Send + Sync>> {
+        self.gate("save_new_task_group").await?;
+        self.inner.save_new_task_group(tasks).await
+    }
+
+    async fn save_task_versioned(
+        &self,
+        task: Task,
+        expected_version: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("save_task_versioned").await?;
+        self.inner.save_task_versioned(task, expected_version).await
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<Option<Task>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_task").await?;
+        self.inner.get_task(task_id).await
+    }
+
+    async fn append_event(
+        &self,
+        task_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("append_event").await?;
+        self.inner.append_event(task_id, event).await
+    }
+
+    async fn get_events(
+        &self,
+        task_id: &str,
+        opts: Option<EventQueryOptions>,
+    ) -> Result<Vec<TaskEvent>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_events").await?;
+        self.inner.get_events(task_id, opts).await
+    }
+
+    async fn set_ttl(
+        &self,
+        task_id: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("set_ttl").await?;
+        self.inner.set_ttl(task_id, ttl_seconds).await
+    }
+
+    async fn get_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+    ) -> Result<Option<TaskEvent>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_series_latest").await?;
+        self.inner.get_series_latest(task_id, series_id).await
+    }
+
+    async fn set_series_latest(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("set_series_latest").await?;
+        self.inner
+            .set_series_latest(task_id, series_id, event)
+            .await
+    }
+
+    async fn replace_last_series_event(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+    ) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        self.gate("replace_last_series_event").await?;
+        self.inner
+            .replace_last_series_event(task_id, series_id, event)
+            .await
+    }
+
+    async fn accumulate_series(
+        &self,
+        task_id: &str,
+        series_id: &str,
+        event: TaskEvent,
+        field: &str,
+    ) -> Result<TaskEvent, Box<dyn Error + Send + Sync>> {
+        self.gate("accumulate_series").await?;
+        self.inner
+            .accumulate_series(task_id, series_id, event, field)
+            .await
+    }
+
+    async fn list_series(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<SeriesSummary>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_series").await?;
+        self.inner.list_series(task_id).await
+    }
+
+    async fn series_count(&self, task_id: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.gate("series_count").await?;
+        self.inner.series_count(task_id).await
+    }
+
+    async fn next_index(&self, task_id: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.gate("next_index").await?;
+        self.inner.next_index(task_id).await
+    }
+
+    async fn event_count(&self, task_id: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.gate("event_count").await?;
+        self.inner.event_count(task_id).await
+    }
+
+    async fn begin_scan(&self, task_id: &str) -> Result<ScanToken, Box<dyn Error + Send + Sync>> {
+        self.gate("begin_scan").await?;
+        self.inner.begin_scan(task_id).await
+    }
+
+    async fn get_event_type_counts(
+        &self,
+        task_id: &str,
+    ) -> Result<EventTypeCounts, Box<dyn Error + Send + Sync>> {
+        self.gate("get_event_type_counts").await?;
+        self.inner.get_event_type_counts(task_id).await
+    }
+
+    fn integrity(&self) -> Option<&IntegrityMonitor> {
+        self.inner.integrity()
+    }
+
+    fn compression_stats(&self) -> Option<CompressionStats> {
+        self.inner.compression_stats()
+    }
+
+    fn migration(&self) -> Option<&MigratingShortTermStore> {
+        self.inner.migration()
+    }
+
+    fn supports_task_archive_restore(&self) -> bool {
+        self.inner.supports_task_archive_restore()
+    }
+
+    async fn validate_task_archive_restore(
+        &self,
+        data: &TaskArchiveRestoreData,
+        options: Option<TaskArchiveImportOptions>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("validate_task_archive_restore").await?;
+        self.inner
+            .validate_task_archive_restore(data, options)
+            .await
+    }
+
+    async fn restore_task_archive(
+        &self,
+        data: TaskArchiveRestoreData,
+        options: Option<TaskArchiveImportOptions>,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("restore_task_archive").await?;
+        self.inner.restore_task_archive(data, options).await
+    }
+
+    async fn list_tasks(
+        &self,
+        filter: TaskFilter,
+    ) -> Result<Vec<Task>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_tasks").await?;
+        self.inner.list_tasks(filter).await
+    }
+
+    async fn list_tasks_page(
+        &self,
+        filter: TaskFilter,
+        after: Option<TaskCursor>,
+        limit: usize,
+    ) -> Result<TaskPage, Box<dyn Error + Send + Sync>> {
+        self.gate("list_tasks_page").await?;
+        self.inner.list_tasks_page(filter, after, limit).await
+    }
+
+    async fn save_worker(&self, worker: Worker) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("save_worker").await?;
+        self.inner.save_worker(worker).await
+    }
+
+    async fn get_worker(
+        &self,
+        worker_id: &str,
+    ) -> Result<Option<Worker>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_worker").await?;
+        self.inner.get_worker(worker_id).await
+    }
+
+    async fn list_workers(
+        &self,
+        filter: Option<WorkerFilter>,
+    ) -> Result<Vec<Worker>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_workers").await?;
+        self.inner.list_workers(filter).await
+    }
+
+    async fn delete_worker(&self, worker_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("delete_worker").await?;
+        self.inner.delete_worker(worker_id).await
+    }
+
+    async fn claim_task(
+        &self,
+        task_id: &str,
+        worker_id: &str,
+        cost: u32,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_task").await?;
+        self.inner.claim_task(task_id, worker_id, cost).await
+    }
+
+    async fn add_assignment(
+        &self,
+        assignment: WorkerAssignment,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("add_assignment").await?;
+        self.inner.add_assignment(assignment).await
+    }
+
+    async fn remove_assignment(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("remove_assignment").await?;
+        self.inner.remove_assignment(task_id).await
+    }
+
+    async fn get_worker_assignments(
+        &self,
+        worker_id: &str,
+    ) -> Result<Vec<WorkerAssignment>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_worker_assignments").await?;
+        self.inner.get_worker_assignments(worker_id).await
+    }
+
+    async fn get_task_assignment(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<WorkerAssignment>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_task_assignment").await?;
+        self.inner.get_task_assignment(task_id).await
+    }
+
+    async fn clear_ttl(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("clear_ttl").await?;
+        self.inner.clear_ttl(task_id).await
+    }
+
+    async fn list_by_status(
+        &self,
+        statuses: &[TaskStatus],
+    ) -> Result<Vec<Task>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_by_status").await?;
+        self.inner.list_by_status(statuses).await
+    }
+
+    async fn save_retry_schedule(
+        &self,
+        schedule: RetrySchedule,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("save_retry_schedule").await?;
+        self.inner.save_retry_schedule(schedule).await
+    }
+
+    async fn list_due_retries(
+        &self,
+        now: f64,
+    ) -> Result<Vec<RetrySchedule>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_due_retries").await?;
+        self.inner.list_due_retries(now).await
+    }
+
+    async fn get_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<Option<RetrySchedule>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_retry_schedule").await?;
+        self.inner.get_retry_schedule(task_id).await
+    }
+
+    async fn claim_retry(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_retry").await?;
+        self.inner.claim_retry(task_id, now, lease_ms).await
+    }
+
+    async fn delete_retry_schedule(
+        &self,
+        task_id: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("delete_retry_schedule").await?;
+        self.inner.delete_retry_schedule(task_id).await
+    }
+
+    async fn save_activation(
+        &self,
+        task_id: &str,
+        at: f64,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("save_activation").await?;
+        self.inner.save_activation(task_id, at).await
+    }
+
+    async fn list_due_activations(
+        &self,
+        now: f64,
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_due_activations").await?;
+        self.inner.list_due_activations(now).await
+    }
+
+    async fn claim_activation(
+        &self,
+        task_id: &str,
+        now: f64,
+        lease_ms: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_activation").await?;
+        self.inner.claim_activation(task_id, now, lease_ms).await
+    }
+
+    async fn delete_activation(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("delete_activation").await?;
+        self.inner.delete_activation(task_id).await
+    }
+
+    async fn claim_filtered_index(
+        &self,
+        task_id: &str,
+        consumer: &str,
+        filter_hash: &str,
+        raw_index: u64,
+    ) -> Result<Option<FilteredIndexClaim>, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_filtered_index").await?;
+        self.inner
+            .claim_filtered_index(task_id, consumer, filter_hash, raw_index)
+            .await
+    }
+
+    async fn get_filtered_index_mark(
+        &self,
+        task_id: &str,
+        consumer: &str,
+    ) -> Result<Option<FilteredIndexMark>, Box<dyn Error + Send + Sync>> {
+        self.gate("get_filtered_index_mark").await?;
+        self.inner.get_filtered_index_mark(task_id, consumer).await
+    }
+
+    async fn claim_webhook_delivery(
+        &self,
+        task_id: &str,
+        webhook: usize,
+        fingerprint: &str,
+        now: f64,
+        window_ms: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_webhook_delivery").await?;
+        self.inner
+            .claim_webhook_delivery(task_id, webhook, fingerprint, now, window_ms)
+            .await
+    }
+
+    async fn claim_deadline_warning(
+        &self,
+        task_id: &str,
+        warning: &str,
+        ttl_ms: u64,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        self.gate("claim_deadline_warning").await?;
+        self.inner
+            .claim_deadline_warning(task_id, warning, ttl_ms)
+            .await
+    }
+
+    async fn record_outcome(
+        &self,
+        outcome: TaskOutcome,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("record_outcome").await?;
+        self.inner.record_outcome(outcome).await
+    }
+
+    async fn list_outcomes(
+        &self,
+        query: &OutcomeQuery,
+    ) -> Result<Vec<TaskOutcome>, Box<dyn Error + Send + Sync>> {
+        self.gate("list_outcomes").await?;
+        self.inner.list_outcomes(query).await
+    }
+
+    async fn trim_outcomes(&self, before: f64) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.gate("trim_outcomes").await?;
+        self.inner.trim_outcomes(before).await
+    }
+
+    async fn delete_task(&self, task_id: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.gate("delete_task").await?;
+        self.inner.delete_task(task_id).await
+    }
+
+    async fn redact_events(
+        &self,
+        task_id: &str,
+        tombstones: &[TaskEvent],
+    ) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        self.gate("redact_events").await?;
+        self.inner.redact_events(task_id, tombstones).await
+    }
+
+    fn event_conventions(&self) -> Option<&dyn EventConventionStore> {
+        self.inner.event_conventions()
+    }
+}
+
+/// A [`MemoryBroadcastProvider`] behind the fault gate. Subscribing cannot
+/// fail, so `subscribe` is only ever delayed.
+pub struct SimBroadcast {
+    inner: MemoryBroadcastProvider,
+    faults: Arc<Faults>,
+}
+
+impl SimBroadcast {
+    pub fn new(faults: Arc<Faults>) -> Self {
+        Self {
+            inner: MemoryBroadcastProvider::new(),
+            faults,
+        }
+    }
+
+    async fn delay(&self, op: &str) {
+        let _ = self.faults.gate(op).await;
+    }
+}
+
+#[async_trait]
+impl BroadcastProvider for SimBroadcast {
+    async fn publish(
+        &self,
+        channel: &str,
+        event: TaskEvent,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.faults.gate("publish").await?;
+        self.inner.publish(channel, event).await
+    }
+
+    async fn subscribe(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.delay("subscribe").await;
+        self.inner.subscribe(channel, handler).await
+    }
+
+    fn subscribe_sync(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn Error + Send + Sync>> {
+        self.inner.subscribe_sync(channel, handler)
+    }
+
+    async fn subscribe_pattern(
+        &self,
+        pattern: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Result<Box<dyn Fn() + Send + Sync>, Box<dyn Error + Send + Sync>> {
+        self.faults.gate("subscribe").await?;
+        self.inner.subscribe_pattern(pattern, handler).await
+    }
+
+    async fn subscribe_without_status(
+        &self,
+        channel: &str,
+        handler: Box<dyn Fn(TaskEvent) + Send + Sync>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.delay("subscribe").await;
+        self.inner.subscribe_without_status(channel, handler).await
+    }
+}
@@ -0,0 +1,242 @@
+//! What a scenario run recorded, and the assertions scenarios make on it.
+
+use std::collections::{BTreeMap, HashSet};
+
+use taskcast_core::{matches_filter, SSEEnvelope, SubscribeFilter, TaskEvent, TaskStatus};
+
+/// An item a virtual subscriber received, or the end of its connection.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Received {
+    Event(SSEEnvelope),
+    /// The replay budget left older events out.
+    Truncated,
+    Done(TaskStatus),
+    /// Connecting failed, or the stream ended with an error.
+    Error(String),
+    /// The scenario disconnected the subscriber.
+    Disconnected,
+}
+
+/// One item in a subscriber's trace. `connection` counts the subscriber's
+/// connections from 0, so a reconnect starts a new one.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub at_ms: u64,
+    pub connection: u32,
+    pub item: Received,
+}
+
+/// An event a virtual webhook was handed by the instance that published it.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub at_ms: u64,
+    pub instance: usize,
+    pub event: TaskEvent,
+}
+
+/// How an engine operation step ended. `finished_ms` is later than
+/// `at_ms` when store latency or an earlier step on the same instance held
+/// it up.
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    pub at_ms: u64,
+    pub finished_ms: u64,
+    pub instance: usize,
+    pub step: String,
+    pub result: Result<(), String>,
+}
+
+/// Everything a run recorded. Times are virtual milliseconds since the
+/// scenario started.
+#[derive(Debug, Default)]
+pub struct Trace {
+    pub frames: BTreeMap<String, Vec<Frame>>,
+    pub deliveries: BTreeMap<String, Vec<Delivery>>,
+    pub outcomes: Vec<StepOutcome>,
+    /// Each task's stored events once the run settled.
+    pub histories: BTreeMap<String, Vec<TaskEvent>>,
+    /// The task and filter of each subscriber and webhook.
+    pub(crate) filters: BTreeMap<String, (Option<String>, SubscribeFilter)>,
+}
+
+impl Trace {
+    /// The frames `subscriber` received, in arrival order.
+    pub fn frames(&self, subscriber: &str) -> &[Frame] {
+        self.frames
+            .get(subscriber)
+            .unwrap_or_else(|| panic!("no subscriber named {subscriber:?}"))
+    }
+
+    /// The events `subscriber` received over all its connections.
+    pub fn events(&self, subscriber: &str) -> Vec<&SSEEnvelope> {
+        self.frames(subscriber)
+            .iter()
+            .filter_map(|frame| match &frame.item {
+                Received::Event(envelope) => Some(envelope),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The frames of each of `subscriber`'s connections.
+    pub fn connections(&self, subscriber: &str) -> Vec<Vec<&Frame>> {
+        let mut connections: BTreeMap<u32, Vec<&Frame>> = BTreeMap::new();
+        for frame in self.frames(subscriber) {
+            connections.entry(frame.connection).or_default().push(frame);
+        }
+        connections.into_values().collect()
+    }
+
+    pub fn history(&self, task_id: &str) -> &[TaskEvent] {
+        self.histories
+            .get(task_id)
+            .map(Vec::as_slice)
+            .unwrap_or_else(|| panic!("no task {task_id:?} was created"))
+    }
+
+    /// The events `webhook` was handed, in delivery order.
+    pub fn deliveries(&self, webhook: &str) -> &[Delivery] {
+        self.deliveries
+            .get(webhook)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The step that ran as `step` (its description, e.g.
+    /// `publish t1 log`), failing if several did.
+    pub fn outcome(&self, step: &str) -> &StepOutcome {
+        let mut matching = self.outcomes.iter().filter(|o| o.step == step);
+        let outcome = matching
+            .next()
+            .unwrap_or_else(|| panic!("no step {step:?} ran"));
+        assert!(matching.next().is_none(), "several steps ran as {step:?}");
+        outcome
+    }
+
+    pub fn assert_steps_succeeded(&self) {
+        let failed: Vec<_> = self.outcomes.iter().filter(|o| o.result.is_err()).collect();
+        assert!(failed.is_empty(), "steps failed: {failed:#?}");
+    }
+
+    /// Every stored event of the subscriber's task that its filter matches
+    /// reached it on some connection.
+    pub fn assert_no_gaps(&self, subscriber: &str) {
+        let received: HashSet<&str> = self
+            .events(subscriber)
+            .into_iter()
+            .map(|envelope| envelope.event_id.as_str())
+            .collect();
+        let missing: Vec<_> = self
+            .expected(subscriber)
+            .filter(|event| !received.contains(event.id.as_str()))
+            .map(|event| (event.index, event.r#type.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "{subscriber} missed stored events (index, type): {missing:?}"
+        );
+    }
+
+    /// No connection of the subscriber received an event twice.
+    pub fn assert_no_duplicates(&self, subscriber: &str) {
+        for (connection, frames) in self.connections(subscriber).into_iter().enumerate() {
+            let mut seen = HashSet::new();
+            for frame in frames {
+                if let Received::Event(envelope) = &frame.item {
+                    assert!(
+                        seen.insert(envelope.event_id.as_str()),
+                        "{subscriber} connection {connection} received event {} twice",
+                        envelope.raw_index
+                    );
+                }
+            }
+        }
+    }
+
+    /// Each connection received status events in store order, and other
+    /// events in store order: a stream may move status changes ahead of
+    /// queued data, but never reorders within either class.
+    pub fn assert_ordered(&self, subscriber: &str) {
+        for (connection, frames) in self.connections(subscriber).into_iter().enumerate() {
+            let mut last: [Option<u64>; 2] = [None, None];
+            for frame in frames {
+                let Received::Event(envelope) = &frame.item else {
+                    continue;
+                };
+                let class = usize::from(envelope.r#type == "taskcast:status");
+                if let Some(previous) = last[class] {
+                    assert!(
+                        previous < envelope.raw_index,
+                        "{subscriber} connection {connection} received event {} after {previous}",
+                        envelope.raw_index
+                    );
+                }
+                last[class] = Some(envelope.raw_index);
+            }
+        }
+    }
+
+    /// The subscriber's last connection ended with a done frame for
+    /// `status`, and no connection received anything after its done frame.
+    pub fn assert_done(&self, subscriber: &str, status: TaskStatus) {
+        let connections = self.connections(subscriber);
+        for (connection, frames) in connections.iter().enumerate() {
+            if let Some(done) = frames
+                .iter()
+                .position(|frame| matches!(frame.item, Received::Done(_)))
+            {
+                assert_eq!(
+                    done,
+                    frames.len() - 1,
+                    "{subscriber} connection {connection} received frames after done: {:?}",
+                    &frames[done..]
+                );
+            }
+        }
+        let last = connections
+            .last()
+            .and_then(|frames| frames.last())
+            .map(|frame| &frame.item);
+        match last {
+            Some(Received::Done(done)) => assert_eq!(*done, status, "{subscriber} done status"),
+            other => panic!("{subscriber} ended with {other:?} instead of a done frame"),
+        }
+    }
+
+    /// The webhook was handed every stored event its filter matches exactly
+    /// once. Events a `latest` series later replaced were delivered too,
+    /// but are no longer stored.
+    pub fn assert_delivered_once(&self, webhook: &str) {
+        let mut delivered = HashSet::new();
+        for delivery in self.deliveries(webhook) {
+            assert!(
+                delivered.insert(delivery.event.id.as_str()),
+                "{webhook} was handed event {} twice",
+                delivery.event.index
+            );
+        }
+        let missing: Vec<_> = self
+            .expected(webhook)
+            .filter(|event| !delivered.contains(event.id.as_str()))
+            .map(|event| (event.index, event.r#type.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "{webhook} was never handed (index, type): {missing:?}"
+        );
+    }
+
+    /// The stored events the filter of subscriber or webhook `name` matches.
+    fn expected(&self, name: &str) -> impl Iterator<Item = &TaskEvent> {
+        let (task_id, filter) = self
+            .filters
+            .get(name)
+            .unwrap_or_else(|| panic!("no subscriber or webhook named {name:?}"));
+        self.histories
+            .iter()
+            .filter(move |(id, _)| task_id.as_ref().is_none_or(|task_id| task_id == *id))
+            .flat_map(|(_, events)| events)
+            .filter(move |event| matches_filter(event, filter))
+    }
+}
@@ -0,0 +1,168 @@
+//! Regression scenarios run on the deterministic simulation harness.
+//!
+//! Each scenario scripts the timing that once exposed a bug and asserts the
+//! invariant the fix restored on what subscribers and webhooks received.
+
+use std::time::Duration;
+
+use serde_json::json;
+use taskcast_core::{SeriesMode, SubscribeFilter, TaskStatus};
+use taskcast_test_backends::sim::{Received, Scenario, Step};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+/// A subscriber tails live events before it reads history, and events
+/// published while that read is slow arrive on both paths. Each must reach
+/// it once.
+#[test]
+fn connect_during_slow_history_read_has_no_gap_or_duplicate() {
+    let mut scenario = Scenario::new()
+        .at(0, Step::create("t1"))
+        .at(0, Step::transition("t1", TaskStatus::Running))
+        .at(5, Step::publish("t1", "log", json!({ "line": 0 })))
+        .at(
+            10,
+            Step::store_latency(ms(100), ms(300)).only(&["get_events"]),
+        )
+        .at(20, Step::connect("early", "t1"))
+        .at(75, Step::connect("late", "t1"));
+    for line in 1..=12 {
+        scenario = scenario.at(
+            20 + line * 10,
+            Step::publish("t1", "log", json!({ "line": line })),
+        );
+    }
+    let trace = scenario
+        .at(400, Step::transition("t1", TaskStatus::Completed))
+        .run();
+
+    trace.assert_steps_succeeded();
+    for subscriber in ["early", "late"] {
+        trace.assert_no_gaps(subscriber);
+        trace.assert_no_duplicates(subscriber);
+        trace.assert_ordered(subscriber);
+        trace.assert_done(subscriber, TaskStatus::Completed);
+    }
+    // The history read really was held up past the publishes it overlaps.
+    let first = &trace.frames("early")[0];
+    assert!(first.at_ms >= 120, "first frame at {}", first.at_ms);
+}
+
+/// One instance completes a task while another publishes to it. The
+/// terminal status must be the last stored event and the last thing any
+/// subscriber sees, whichever write wins.
+#[test]
+fn terminal_transition_racing_a_publish_on_another_instance_stays_last() {
+    let trace = Scenario::new()
+        .instances(2)
+        .webhook("audit", SubscribeFilter::default())
+        .at(0, Step::create("t1"))
+        .at(0, Step::transition("t1", TaskStatus::Running))
+        .at(10, Step::connect("watcher", "t1"))
+        .at(
+            50,
+            Step::store_latency(ms(20), ms(100)).only(&["append_event"]),
+        )
+        .at_on(60, 0, Step::publish("t1", "log", json!({ "n": 1 })))
+        .at_on(60, 1, Step::transition("t1", TaskStatus::Completed))
+        .at_on(65, 0, Step::publish("t1", "log", json!({ "n": 2 })))
+        .at_on(200, 1, Step::connect("latecomer", "t1"))
+        .run();
+
+    let history = trace.history("t1");
+    let last = history.last().unwrap();
+    assert_eq!(last.r#type, "taskcast:status");
+    assert_eq!(last.data["status"], "completed");
+    // A publish either landed before the terminal status or was refused.
+    let stored = history.iter().filter(|e| e.r#type == "log").count();
+    let accepted = trace
+        .outcomes
+        .iter()
+        .filter(|o| o.step == "publish t1 log" && o.result.is_ok())
+        .count();
+    assert_eq!(stored, accepted);
+
+    for subscriber in ["watcher", "latecomer"] {
+        trace.assert_no_gaps(subscriber);
+        trace.assert_no_duplicates(subscriber);
+        trace.assert_done(subscriber, TaskStatus::Completed);
+    }
+    trace.assert_delivered_once("audit");
+}
+
+/// A client saw a `latest` series value, dropped, and the value was
+/// rewritten before it reconnected with that event's id as its cursor. The
+/// cursor no longer names a stored event; the client must still end up
+/// with the current value and everything after it.
+#[test]
+fn reconnect_with_cursor_after_latest_rewrite_misses_nothing() {
+    let progress = |pct: u64| {
+        Step::publish_series(
+            "t1",
+            "progress",
+            json!({ "pct": pct }),
+            "p",
+            SeriesMode::Latest,
+        )
+    };
+    let trace = Scenario::new()
+        .at(0, Step::create("t1"))
+        .at(0, Step::transition("t1", TaskStatus::Running))
+        .at(10, Step::connect("client", "t1"))
+        .at(20, progress(10))
+        .at(30, Step::disconnect("client"))
+        .at(40, progress(50))
+        .at(50, Step::publish("t1", "log", json!({ "line": 1 })))
+        .at(60, Step::reconnect("client", "t1"))
+        .at(70, progress(90))
+        .at(100, Step::transition("t1", TaskStatus::Completed))
+        .run();
+
+    trace.assert_steps_succeeded();
+    trace.assert_no_gaps("client");
+    trace.assert_done("client", TaskStatus::Completed);
+    let connections = trace.connections("client");
+    assert_eq!(connections.len(), 2);
+    assert!(matches!(
+        connections[0].last().unwrap().item,
+        Received::Disconnected
+    ));
+    let last_progress = trace
+        .events("client")
+        .into_iter()
+        .rfind(|e| e.r#type == "progress")
+        .unwrap();
+    assert_eq!(last_progress.data["pct"], 90);
+}
+
+/// The same scenario records the same trace on every run.
+#[test]
+fn scenarios_replay_identically() {
+    let run = || {
+        let trace = Scenario::new()
+            .instances(2)
+            .at(0, Step::create("t1"))
+            .at(0, Step::transition("t1", TaskStatus::Running))
+            .at(5, Step::store_latency(ms(7), ms(200)))
+            .at_on(10, 1, Step::connect("a", "t1"))
+            .at(12, Step::publish("t1", "log", json!({ "n": 1 })))
+            .at_on(13, 1, Step::publish("t1", "log", json!({ "n": 2 })))
+            .at(30, Step::transition("t1", TaskStatus::Completed))
+            .run();
+        trace
+            .frames("a")
+            .iter()
+            .map(|frame| match &frame.item {
+                Received::Event(e) => format!("{} {} {}", frame.at_ms, e.raw_index, e.r#type),
+                other => format!("{} {other:?}", frame.at_ms),
+            })
+            .collect::<Vec<_>>()
+    };
+    let first = run();
+    assert!(!first.is_empty());
+    for _ in 0..5 {
+        assert_eq!(run(), first);
+    }
+}